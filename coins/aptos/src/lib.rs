@@ -33,6 +33,8 @@ use thiserror::Error;
 
 // Re-export traits
 pub use walletd_traits::WalletError;
+use walletd_core::SecretBytes;
+use walletd_error::WalletdError;
 
 /// Aptos-specific errors
 #[derive(Error, Debug)]
@@ -50,8 +52,13 @@ pub enum AptosError {
     InvalidPrivateKey(String),
 
     /// Invalid address format
-    #[error("Invalid address: {0}")]
-    InvalidAddress(String),
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress {
+        /// The address as given
+        address: String,
+        /// Why it was rejected
+        reason: String,
+    },
 
     /// Signing error
     #[error("Signing failed: {0}")]
@@ -68,7 +75,32 @@ pub enum AptosError {
 
 impl From<AptosError> for WalletError {
     fn from(e: AptosError) -> Self {
-        WalletError::Other(e.to_string())
+        match e {
+            AptosError::InvalidAddress { address, .. } => WalletError::InvalidAddress(address),
+            AptosError::Network(m) => WalletError::NetworkError(m),
+            AptosError::InvalidMnemonic(_)
+            | AptosError::KeyDerivation(_)
+            | AptosError::InvalidPrivateKey(_)
+            | AptosError::SigningError(_) => WalletError::KeyError(e.to_string()),
+            AptosError::Serialization(_) => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<AptosError> for WalletdError {
+    fn from(e: AptosError) -> Self {
+        let error = match e {
+            AptosError::InvalidMnemonic(m) => WalletdError::InvalidMnemonic(m),
+            AptosError::KeyDerivation(m) => WalletdError::KeyDerivationError(m),
+            AptosError::InvalidPrivateKey(m) => WalletdError::InvalidPrivateKey(m),
+            AptosError::InvalidAddress { address, reason } => {
+                WalletdError::InvalidAddress { address, reason }
+            }
+            AptosError::SigningError(m) => WalletdError::SigningError(m),
+            AptosError::Serialization(m) => WalletdError::FormatError(m),
+            AptosError::Network(m) => WalletdError::NetworkError(m),
+        };
+        error.on_chain("aptos")
     }
 }
 
@@ -247,6 +279,10 @@ impl AptosAddress {
 
     /// Creates an address from a hex string
     pub fn from_hex(hex_str: &str) -> Result<Self, AptosError> {
+        let invalid = |reason: String| AptosError::InvalidAddress {
+            address: hex_str.to_string(),
+            reason,
+        };
         let hex_str = hex_str.trim_start_matches("0x");
         
         // Aptos addresses can be shortened (leading zeros omitted)
@@ -254,13 +290,13 @@ impl AptosAddress {
         let padded = format!("{:0>64}", hex_str);
         
         if padded.len() != 64 {
-            return Err(AptosError::InvalidAddress(
+            return Err(invalid(
                 "Address must be at most 64 hex characters".to_string(),
             ));
         }
         
         let bytes = hex::decode(&padded)
-            .map_err(|e| AptosError::InvalidAddress(e.to_string()))?;
+            .map_err(|e| invalid(e.to_string()))?;
         let mut arr = [0u8; 32];
         arr.copy_from_slice(&bytes);
         Ok(Self(arr))
@@ -316,7 +352,7 @@ impl std::str::FromStr for AptosAddress {
 
 /// Aptos wallet
 pub struct AptosWallet {
-    signing_key: SigningKey,
+    secret_key: SecretBytes,
     verifying_key: VerifyingKey,
     address: AptosAddress,
     network: AptosNetwork,
@@ -334,7 +370,7 @@ impl AptosWallet {
         let address = AptosAddress::from_ed25519_pubkey(&verifying_key);
 
         Self {
-            signing_key,
+            secret_key: SecretBytes::from_slice(signing_key.as_bytes()),
             verifying_key,
             address,
             network,
@@ -385,15 +421,12 @@ impl AptosWallet {
             ));
         }
 
-        let mut key_bytes = [0u8; 32];
-        key_bytes.copy_from_slice(bytes);
-
-        let signing_key = SigningKey::from_bytes(&key_bytes);
-        let verifying_key = signing_key.verifying_key();
+        let secret_key = SecretBytes::from_slice(bytes);
+        let verifying_key = Self::signing_key_of(&secret_key).verifying_key();
         let address = AptosAddress::from_ed25519_pubkey(&verifying_key);
 
         Ok(Self {
-            signing_key,
+            secret_key,
             verifying_key,
             address,
             network,
@@ -428,21 +461,28 @@ impl AptosWallet {
         format!("0x{}", hex::encode(self.verifying_key.as_bytes()))
     }
 
+    /// Returns the private key
+    ///
+    /// See [`SecretBytes`](walletd_core::SecretBytes#handle-with-care).
+    pub fn secret_key(&self) -> &SecretBytes {
+        &self.secret_key
+    }
+
     /// Returns the private key bytes
-    /// ⚠️ Handle with care!
+    #[deprecated(note = "use `secret_key`")]
     pub fn private_key(&self) -> &[u8; 32] {
-        self.signing_key.as_bytes()
+        self.key_bytes()
     }
 
     /// Returns the private key as hex
     /// ⚠️ Handle with care!
     pub fn private_key_hex(&self) -> String {
-        format!("0x{}", hex::encode(self.signing_key.as_bytes()))
+        format!("0x{}", hex::encode(self.key_bytes()))
     }
 
     /// Signs arbitrary data
     pub fn sign(&self, data: &[u8]) -> Signature {
-        self.signing_key.sign(data)
+        self.signing_key().sign(data)
     }
 
     /// Signs data and returns the signature as bytes
@@ -453,7 +493,7 @@ impl AptosWallet {
     /// Signs a transaction (raw signing message)
     /// In Aptos, signing_message = prefix || sha3_256(raw_txn) 
     pub fn sign_transaction(&self, signing_message: &[u8]) -> Result<AptosSignature, AptosError> {
-        let signature = self.signing_key.sign(signing_message);
+        let signature = self.signing_key().sign(signing_message);
 
         Ok(AptosSignature {
             public_key: self.verifying_key.as_bytes().to_vec(),
@@ -485,6 +525,19 @@ impl AptosWallet {
     pub fn auth_key_hex(&self) -> String {
         format!("0x{}", hex::encode(self.auth_key()))
     }
+
+    fn key_bytes(&self) -> &[u8; 32] {
+        self.secret_key.as_array().expect("32-byte key")
+    }
+
+    /// Short-lived signing key; zeroized when dropped
+    fn signing_key(&self) -> SigningKey {
+        Self::signing_key_of(&self.secret_key)
+    }
+
+    fn signing_key_of(secret_key: &SecretBytes) -> SigningKey {
+        SigningKey::from_bytes(secret_key.as_array().expect("32-byte key"))
+    }
 }
 
 impl fmt::Debug for AptosWallet {
@@ -723,6 +776,10 @@ mod tests {
         
         let wallet2 = AptosWallet::from_private_key_hex(&private_key, AptosNetwork::Testnet).unwrap();
         assert_eq!(wallet1.address(), wallet2.address());
+        assert_eq!(wallet1.secret_key(), wallet2.secret_key());
+        #[allow(deprecated)]
+        let raw = wallet2.private_key();
+        assert_eq!(wallet2.secret_key().expose_secret(), raw);
     }
 
     #[test]
@@ -9,6 +9,7 @@ use alloy::signers::local::PrivateKeySigner;
 use anyhow::Result;
 use bip39::Mnemonic;
 use std::str::FromStr;
+use walletd_core::SecretBytes;
 
 /// Arbitrum wallet for managing accounts and transactions
 pub struct ArbitrumWallet {
@@ -53,9 +54,9 @@ impl ArbitrumWallet {
         // Ethereum derivation path: m/44'/60'/0'/0/index
         let path = DerivationPath::from_str(&format!("m/44'/60'/0'/0/{}", index))?;
         let child_xprv = XPrv::derive_from_path(seed, &path)?;
-        let private_key_bytes: [u8; 32] = child_xprv.private_key().to_bytes().into();
+        let private_key = SecretBytes::from_slice(&child_xprv.private_key().to_bytes());
 
-        let signer = PrivateKeySigner::from_slice(&private_key_bytes)?;
+        let signer = PrivateKeySigner::from_slice(private_key.expose_secret())?;
 
         Ok(Self {
             signer,
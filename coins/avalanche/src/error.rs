@@ -1,4 +1,5 @@
 use thiserror::Error;
+use walletd_error::WalletdError;
 
 #[derive(Error, Debug)]
 pub enum AvalancheError {
@@ -29,3 +30,26 @@ pub enum AvalancheError {
     #[error("Other error: {0}")]
     Other(#[from] anyhow::Error),
 }
+
+impl From<AvalancheError> for WalletdError {
+    fn from(e: AvalancheError) -> Self {
+        let error = match e {
+            AvalancheError::RpcError(reason) => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason,
+            },
+            AvalancheError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            AvalancheError::WalletError(m) => WalletdError::InvalidState(m),
+            AvalancheError::NetworkError(m) => WalletdError::NetworkError(m),
+            AvalancheError::InvalidAddress(reason) => WalletdError::InvalidAddress {
+                address: String::new(),
+                reason,
+            },
+            AvalancheError::InsufficientBalance => WalletdError::TransactionFailed(e.to_string()),
+            AvalancheError::UnsupportedChain(m) => WalletdError::NotSupported(m),
+            AvalancheError::GasEstimationFailed(m) => WalletdError::TransactionBuildError(m),
+            AvalancheError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("avalanche")
+    }
+}
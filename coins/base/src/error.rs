@@ -1,4 +1,5 @@
 use thiserror::Error;
+use walletd_error::WalletdError;
 
 #[derive(Error, Debug)]
 pub enum BaseError {
@@ -17,3 +18,19 @@ pub enum BaseError {
     #[error("Other error: {0}")]
     Other(#[from] anyhow::Error),
 }
+
+impl From<BaseError> for WalletdError {
+    fn from(e: BaseError) -> Self {
+        let error = match e {
+            BaseError::RpcError(reason) => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason,
+            },
+            BaseError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            BaseError::WalletError(m) => WalletdError::InvalidState(m),
+            BaseError::NetworkError(m) => WalletdError::NetworkError(m),
+            BaseError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("base")
+    }
+}
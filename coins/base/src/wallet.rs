@@ -6,6 +6,7 @@ use alloy::signers::local::PrivateKeySigner;
 use alloy::network::TransactionBuilder;
 use alloy::rpc::types::TransactionRequest;
 use std::str::FromStr;
+use walletd_traits::{keystore, KeyFormat};
 
 pub struct BaseWallet {
     signer: PrivateKeySigner,
@@ -65,6 +66,25 @@ impl BaseWallet {
         format!("0x{}", hex::encode(self.signer.to_bytes()))
     }
 
+    /// Exports the key as a keystore v3 JSON file (geth/MetaMask compatible)
+    pub fn export_encrypted(&self, password: &str) -> Result<String> {
+        Ok(keystore::encrypt_key(
+            KeyFormat::Web3Keystore,
+            self.signer.to_bytes().as_slice(),
+            password,
+            Some(&self.address()),
+        )?)
+    }
+
+    /// Restores a wallet from a keystore v3 JSON file
+    pub fn from_encrypted(json: &str, password: &str, chain_id: u64) -> Result<Self> {
+        let key = keystore::decrypt_key(json, password)?;
+        if key.format != KeyFormat::Web3Keystore {
+            anyhow::bail!("expected a web3 keystore, found {}", key.format);
+        }
+        Self::from_private_key(&hex::encode(key.payload.expose_secret()), chain_id)
+    }
+
     pub async fn get_balance(&self) -> Result<U256> {
         if let Some(rpc_url) = &self.rpc_url {
             let provider = ProviderBuilder::new()
@@ -187,6 +207,15 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_from_encrypted() {
+        let key = hex::decode(TEST_PRIVATE_KEY.strip_prefix("0x").unwrap()).unwrap();
+        let json = keystore::encrypt_key_with(KeyFormat::Web3Keystore, &key, "pw", None, 10).unwrap();
+        let wallet = BaseWallet::from_encrypted(&json, "pw", BASE_MAINNET).unwrap();
+        assert!(wallet.address().eq_ignore_ascii_case("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"));
+        assert!(BaseWallet::from_encrypted(&json, "wrong", BASE_MAINNET).is_err());
+    }
+
     // ============================================================================
     // Provider Connection Tests
     // ============================================================================
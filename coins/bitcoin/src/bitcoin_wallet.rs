@@ -1,5 +1,8 @@
+use crate::consolidation::{
+    self, ConsolidationPlan, ConsolidationPolicy, ScriptKind, Utxo, UtxoChain,
+};
 use crate::Error;
-use bdk::bitcoin::{Address, Txid};
+use bdk::bitcoin::{Address, Script, Transaction, Txid};
 use bdk::blockchain::{Blockchain, GetHeight, WalletSync};
 use bdk::keys::bip39::Mnemonic;
 use bdk::keys::{DerivableKey, ExtendedKey};
@@ -8,10 +11,13 @@ use bdk::wallet::AddressInfo;
 use walletd_hd_key::slip44::Coin;
 pub use bdk::bitcoin::AddressType;
 use bdk::{bitcoin::Network, database::MemoryDatabase, wallet::AddressIndex, Wallet};
-use bdk::{Balance, KeychainKind, SignOptions, SyncOptions};
+use bdk::{Balance, FeeRate, KeychainKind, SignOptions, SyncOptions};
 use std::str::FromStr;
 use walletd_hd_key::HDPurpose;
 
+/// Confirmation target for consolidations, which are never urgent (a day)
+const CONSOLIDATION_TARGET_BLOCKS: usize = 144;
+
 /// Represents a Hierarchical Deterministic (HD) Bitcoin wallet.
 pub struct BitcoinWallet {
     wallet: Option<Wallet<MemoryDatabase>>,
@@ -65,6 +71,116 @@ impl BitcoinWallet {
         Ok(txid)
     }
 
+    /// Builds and signs one transaction with an output per recipient
+    ///
+    /// Amounts are in satoshis. Change goes to a new internal address; the
+    /// transaction signals RBF. Nothing is broadcast.
+    pub fn build_batch(&self, recipients: &[(String, u64)], fee_rate: FeeRate) -> Result<Transaction, Error> {
+        if recipients.is_empty() {
+            return Err(Error::MissingData("no recipients".to_string()));
+        }
+        let wallet = self.wallet.as_ref().ok_or(Error::MissingMasterHDKey)?;
+        let mut tx_builder = wallet.build_tx();
+        for (to, amount) in recipients {
+            let address = Address::from_str(to)
+                .map_err(|e| Error::FromStr(format!("{to}: {e}")))?
+                .require_network(wallet.network())
+                .map_err(|e| Error::FromStr(format!("{to}: {e}")))?;
+            tx_builder.add_recipient(address.script_pubkey(), *amount);
+        }
+        tx_builder.fee_rate(fee_rate).enable_rbf();
+        let (mut psbt, _) = tx_builder.finish()?;
+        if !wallet.sign(&mut psbt, SignOptions::default())? {
+            return Err(Error::MissingData("batch transaction was not fully signed".to_string()));
+        }
+        Ok(psbt.extract_tx())
+    }
+
+    /// Plans sweeping the wallet's small UTXOs into one output of its own
+    ///
+    /// Uses the blockchain's fee estimate for confirmation within a day.
+    /// Returns [Error::ConsolidationSkipped] when fees are above the
+    /// policy's threshold or too few UTXOs are worth sweeping.
+    pub async fn plan_consolidation<B: Blockchain + GetHeight>(
+        &self,
+        blockchain: &B,
+        policy: &ConsolidationPolicy,
+    ) -> Result<ConsolidationPlan, Error> {
+        let wallet = self.wallet.as_ref().ok_or(Error::MissingMasterHDKey)?;
+        let fee_rate = blockchain.estimate_fee(CONSOLIDATION_TARGET_BLOCKS)?;
+        let tip = blockchain.get_height()?;
+        let mut utxos = Vec::new();
+        for local in wallet.list_unspent()? {
+            let Some(kind) = script_kind(&local.txout.script_pubkey) else {
+                continue;
+            };
+            let confirmations = wallet
+                .get_tx(&local.outpoint.txid, false)?
+                .and_then(|details| details.confirmation_time)
+                .map_or(0, |time| tip.saturating_sub(time.height) + 1);
+            utxos.push(Utxo {
+                outpoint: local.outpoint,
+                value: local.txout.value,
+                address: Address::from_script(&local.txout.script_pubkey, wallet.network())
+                    .map(|address| address.to_string())
+                    .unwrap_or_default(),
+                kind,
+                confirmations,
+            });
+        }
+        let destination = wallet.get_internal_address(AddressIndex::Peek(0))?;
+        let kind = script_kind(&destination.script_pubkey()).unwrap_or(ScriptKind::P2wpkh);
+        consolidation::plan(
+            UtxoChain::Bitcoin,
+            &utxos,
+            fee_rate.as_sat_per_vb() as f64,
+            (&destination.to_string(), kind),
+            policy,
+        )
+        .map_err(Error::ConsolidationSkipped)
+    }
+
+    /// Sweeps the wallet's small UTXOs into a new change address and
+    /// broadcasts the transaction
+    ///
+    /// Check [ConsolidationPlan::warnings] with [plan_consolidation](Self::plan_consolidation)
+    /// first: a consolidation publicly links every address it spends from.
+    /// The returned plan carries the fee actually paid.
+    pub async fn consolidate<B: Blockchain + GetHeight>(
+        &self,
+        blockchain: &B,
+        policy: &ConsolidationPolicy,
+    ) -> Result<(ConsolidationPlan, Txid), Error> {
+        let mut plan = self.plan_consolidation(blockchain, policy).await?;
+        let wallet = self.wallet.as_ref().ok_or(Error::MissingMasterHDKey)?;
+        let destination = wallet.get_internal_address(AddressIndex::New)?;
+
+        let mut tx_builder = wallet.build_tx();
+        tx_builder
+            .add_utxos(&plan.outpoints())?
+            .manually_selected_only()
+            .drain_to(destination.script_pubkey())
+            .fee_rate(FeeRate::from_sat_per_vb(plan.fee_rate as f32))
+            .enable_rbf();
+        let (mut psbt, details) = tx_builder.finish()?;
+        if !wallet.sign(&mut psbt, SignOptions::default())? {
+            return Err(Error::MissingData(
+                "consolidation transaction was not fully signed".to_string(),
+            ));
+        }
+        let transaction = psbt.extract_tx();
+        blockchain
+            .broadcast(&transaction)
+            .map_err(|e| Error::BroadcastTransaction(e.to_string()))?;
+
+        if let Some(fee) = details.fee {
+            plan.fee = fee;
+            plan.output_value = plan.total_in.saturating_sub(fee);
+            plan.vbytes = transaction.vsize() as u64;
+        }
+        Ok((plan, transaction.txid()))
+    }
+
     /// Syncs the wallet with the blockchain by adding previously used addresses to the wallet.
     pub async fn sync<B: WalletSync + GetHeight>(&mut self, blockchain: &B) -> Result<(), Error> {
         let _ = self
@@ -139,12 +255,32 @@ impl BitcoinWallet {
         Ok(address)
     }
 
+    /// The underlying BDK wallet
+    pub(crate) fn bdk_wallet(&self) -> Result<&Wallet<MemoryDatabase>, Error> {
+        self.wallet.as_ref().ok_or(Error::MissingMasterHDKey)
+    }
+
     /// Returns the Builder for [BitcoinWallet]
     pub fn builder() -> BitcoinWalletBuilder {
         BitcoinWalletBuilder::new()
     }
 }
 
+/// Script type of a wallet output, if the planner can size it
+fn script_kind(script: &Script) -> Option<ScriptKind> {
+    if script.is_p2pkh() {
+        Some(ScriptKind::P2pkh)
+    } else if script.is_p2sh() {
+        Some(ScriptKind::P2sh)
+    } else if script.is_v0_p2wpkh() {
+        Some(ScriptKind::P2wpkh)
+    } else if script.is_v1_p2tr() {
+        Some(ScriptKind::P2tr)
+    } else {
+        None
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 /// Builder for [BitcoinWallet] that allows for the creation of a [BitcoinWallet] with a custom configuration
 pub struct BitcoinWalletBuilder {
@@ -0,0 +1,512 @@
+//! UTXO consolidation
+//!
+//! Many small UTXOs make every later payment larger and more expensive.
+//! Consolidating sweeps them into a single output of the same wallet,
+//! ideally while fees are low. [`plan`] decides whether that is worthwhile:
+//! it checks the fee rate against a threshold, picks the small confirmed
+//! UTXOs that are still worth more than the fee to spend them, and reports
+//! what the sweep reveals about the wallet.
+//!
+//! The planner only needs the values and script types of the UTXOs, so it
+//! works for Bitcoin, Litecoin, Dogecoin and Bitcoin Cash alike;
+//! [`BitcoinWallet::consolidate`](crate::BitcoinWallet::consolidate) builds
+//! and broadcasts the sweep for Bitcoin.
+
+use bdk::bitcoin::OutPoint;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// Weight of the version, locktime and input/output counts
+const TX_OVERHEAD_WEIGHT: u64 = 40;
+/// Weight of the segwit marker and flag
+const SEGWIT_MARKER_WEIGHT: u64 = 2;
+
+/// A UTXO chain the planner knows the parameters of
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum UtxoChain {
+    /// Bitcoin
+    Bitcoin,
+    /// Litecoin
+    Litecoin,
+    /// Dogecoin
+    Dogecoin,
+    /// Bitcoin Cash
+    BitcoinCash,
+}
+
+impl UtxoChain {
+    /// Smallest output the network relays, in base units
+    ///
+    /// Bitcoin and Litecoin use Bitcoin Core's 3 sat/vB dust rule, Bitcoin
+    /// Cash a flat 546 satoshis and Dogecoin its 0.01 DOGE soft limit.
+    pub fn dust_limit(&self, kind: ScriptKind) -> u64 {
+        match self {
+            UtxoChain::Bitcoin | UtxoChain::Litecoin => {
+                // Core sizes the spend as 67 vB for witness outputs, 148 otherwise
+                let spend = match kind {
+                    ScriptKind::P2pkh | ScriptKind::P2sh => 148,
+                    ScriptKind::P2wpkh | ScriptKind::P2tr => 67,
+                };
+                3 * (kind.output_weight() / 4 + spend)
+            }
+            UtxoChain::BitcoinCash => 546,
+            UtxoChain::Dogecoin => 1_000_000,
+        }
+    }
+
+    /// Whether the chain has outputs of this script type
+    pub fn supports(&self, kind: ScriptKind) -> bool {
+        match self {
+            UtxoChain::Bitcoin => true,
+            UtxoChain::Litecoin => kind != ScriptKind::P2tr,
+            UtxoChain::Dogecoin | UtxoChain::BitcoinCash => {
+                matches!(kind, ScriptKind::P2pkh | ScriptKind::P2sh)
+            }
+        }
+    }
+
+    /// UTXOs below this value are consolidated by default (0.01 BTC, LTC
+    /// or BCH; 100 DOGE)
+    pub fn default_small_value(&self) -> u64 {
+        match self {
+            UtxoChain::Dogecoin => 10_000_000_000,
+            _ => 1_000_000,
+        }
+    }
+}
+
+/// Script type of an output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ScriptKind {
+    /// Legacy pay-to-pubkey-hash
+    P2pkh,
+    /// Pay-to-script-hash; spends are sized as nested P2WPKH
+    P2sh,
+    /// Native segwit v0
+    P2wpkh,
+    /// Taproot key path
+    P2tr,
+}
+
+impl ScriptKind {
+    /// Weight of an input spending this script with a single signature
+    pub fn input_weight(&self) -> u64 {
+        match self {
+            ScriptKind::P2pkh => 592,
+            ScriptKind::P2sh => 364,
+            ScriptKind::P2wpkh => 271,
+            ScriptKind::P2tr => 230,
+        }
+    }
+
+    /// Weight of an output paying this script
+    pub fn output_weight(&self) -> u64 {
+        match self {
+            ScriptKind::P2pkh => 136,
+            ScriptKind::P2sh => 128,
+            ScriptKind::P2wpkh => 124,
+            ScriptKind::P2tr => 172,
+        }
+    }
+
+    fn is_segwit(&self) -> bool {
+        !matches!(self, ScriptKind::P2pkh)
+    }
+}
+
+/// An unspent output the wallet controls
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Utxo {
+    /// Output being spent
+    pub outpoint: OutPoint,
+    /// Value in base units
+    pub value: u64,
+    /// Address holding it
+    pub address: String,
+    /// Script type
+    pub kind: ScriptKind,
+    /// Confirmations (0 while unconfirmed)
+    pub confirmations: u32,
+}
+
+/// When and what to consolidate
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidationPolicy {
+    /// Consolidate only at or below this fee rate (base units per vbyte)
+    pub max_fee_rate: f64,
+    /// Only UTXOs worth less than this are swept
+    pub small_value: u64,
+    /// Fewest UTXOs worth a consolidation
+    pub min_inputs: usize,
+    /// Most UTXOs per consolidation, smallest first
+    pub max_inputs: usize,
+    /// Confirmations a UTXO needs before it is swept
+    pub min_confirmations: u32,
+}
+
+impl ConsolidationPolicy {
+    /// Consolidates `chain`'s small UTXOs while fees are at most
+    /// `max_fee_rate` per vbyte
+    pub fn new(chain: UtxoChain, max_fee_rate: f64) -> Self {
+        Self {
+            max_fee_rate,
+            small_value: chain.default_small_value(),
+            min_inputs: 5,
+            max_inputs: 200,
+            min_confirmations: 1,
+        }
+    }
+
+    /// Sets the value below which UTXOs are swept
+    pub fn small_value(mut self, value: u64) -> Self {
+        self.small_value = value;
+        self
+    }
+
+    /// Sets the fewest UTXOs worth a consolidation
+    pub fn min_inputs(mut self, count: usize) -> Self {
+        self.min_inputs = count;
+        self
+    }
+
+    /// Sets the most UTXOs per consolidation
+    pub fn max_inputs(mut self, count: usize) -> Self {
+        self.max_inputs = count;
+        self
+    }
+
+    /// Sets the confirmations a UTXO needs before it is swept
+    pub fn min_confirmations(mut self, confirmations: u32) -> Self {
+        self.min_confirmations = confirmations;
+        self
+    }
+}
+
+/// Why a consolidation was not planned
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SkipReason {
+    /// Fees are above the policy's threshold
+    FeeRateTooHigh {
+        /// Current rate
+        current: f64,
+        /// Threshold
+        max: f64,
+    },
+    /// Not enough small UTXOs are worth sweeping
+    TooFewUtxos {
+        /// Eligible UTXOs
+        found: usize,
+        /// Policy minimum
+        min: usize,
+    },
+    /// After fees the consolidated output would be dust
+    OutputBelowDust {
+        /// Value left after fees
+        value: u64,
+        /// Dust limit of the destination
+        dust_limit: u64,
+    },
+}
+
+impl fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SkipReason::FeeRateTooHigh { current, max } => {
+                write!(
+                    f,
+                    "fee rate {:.2}/vB is above the {:.2}/vB threshold",
+                    current, max
+                )
+            }
+            SkipReason::TooFewUtxos { found, min } => {
+                write!(f, "{} small UTXOs worth sweeping, need {}", found, min)
+            }
+            SkipReason::OutputBelowDust { value, dust_limit } => write!(
+                f,
+                "output of {} after fees is below the dust limit of {}",
+                value, dust_limit
+            ),
+        }
+    }
+}
+
+/// What a consolidation reveals about the wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PrivacyWarning {
+    /// Spending UTXOs of several addresses together shows they share an
+    /// owner
+    LinksAddresses {
+        /// Distinct input addresses
+        addresses: usize,
+    },
+    /// Inputs of different script types single out the wallet
+    MixedScriptTypes {
+        /// Script types spent
+        kinds: Vec<ScriptKind>,
+    },
+    /// The destination already appears among the inputs
+    AddressReuse {
+        /// Reused address
+        address: String,
+    },
+}
+
+impl fmt::Display for PrivacyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrivacyWarning::LinksAddresses { addresses } => write!(
+                f,
+                "links {} addresses as belonging to the same wallet",
+                addresses
+            ),
+            PrivacyWarning::MixedScriptTypes { kinds } => {
+                write!(f, "spends mixed script types {:?}", kinds)
+            }
+            PrivacyWarning::AddressReuse { address } => {
+                write!(f, "sends back to input address {}", address)
+            }
+        }
+    }
+}
+
+/// A consolidation worth sending
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ConsolidationPlan {
+    /// Chain
+    pub chain: UtxoChain,
+    /// UTXOs to sweep, smallest first
+    pub inputs: Vec<Utxo>,
+    /// Fee rate in base units per vbyte
+    pub fee_rate: f64,
+    /// Estimated size in vbytes
+    pub vbytes: u64,
+    /// Fee in base units
+    pub fee: u64,
+    /// Sum of the inputs
+    pub total_in: u64,
+    /// Value of the consolidated output
+    pub output_value: u64,
+    /// Small UTXOs left alone because spending them costs more than they hold
+    pub uneconomical: usize,
+    /// What the sweep reveals
+    pub warnings: Vec<PrivacyWarning>,
+}
+
+impl ConsolidationPlan {
+    /// Outpoints of the inputs
+    pub fn outpoints(&self) -> Vec<OutPoint> {
+        self.inputs.iter().map(|utxo| utxo.outpoint).collect()
+    }
+}
+
+/// Plans sweeping `utxos` into one `destination` output at `fee_rate`
+///
+/// `destination` is the address of the consolidated output, used to spot
+/// address reuse, and `kind` its script type.
+pub fn plan(
+    chain: UtxoChain,
+    utxos: &[Utxo],
+    fee_rate: f64,
+    destination: (&str, ScriptKind),
+    policy: &ConsolidationPolicy,
+) -> Result<ConsolidationPlan, SkipReason> {
+    if fee_rate > policy.max_fee_rate {
+        return Err(SkipReason::FeeRateTooHigh {
+            current: fee_rate,
+            max: policy.max_fee_rate,
+        });
+    }
+
+    let mut small: Vec<&Utxo> = utxos
+        .iter()
+        .filter(|utxo| {
+            utxo.value < policy.small_value
+                && utxo.confirmations >= policy.min_confirmations
+                && chain.supports(utxo.kind)
+        })
+        .collect();
+    small.sort_by_key(|utxo| utxo.value);
+    let (worth, uneconomical): (Vec<&Utxo>, Vec<&Utxo>) = small
+        .into_iter()
+        .partition(|utxo| utxo.value > fee_for(utxo.kind.input_weight(), fee_rate));
+    let inputs: Vec<Utxo> = worth.into_iter().take(policy.max_inputs).cloned().collect();
+    if inputs.len() < policy.min_inputs.max(2) {
+        return Err(SkipReason::TooFewUtxos {
+            found: inputs.len(),
+            min: policy.min_inputs.max(2),
+        });
+    }
+
+    let (address, kind) = destination;
+    let segwit = inputs.iter().any(|utxo| utxo.kind.is_segwit());
+    let weight = TX_OVERHEAD_WEIGHT
+        + if segwit { SEGWIT_MARKER_WEIGHT } else { 0 }
+        + inputs
+            .iter()
+            .map(|utxo| utxo.kind.input_weight())
+            .sum::<u64>()
+        + kind.output_weight();
+    let vbytes = weight.div_ceil(4);
+    let fee = fee_for(weight, fee_rate);
+    let total_in: u64 = inputs.iter().map(|utxo| utxo.value).sum();
+    let output_value = total_in.saturating_sub(fee);
+    let dust_limit = chain.dust_limit(kind);
+    if output_value < dust_limit {
+        return Err(SkipReason::OutputBelowDust {
+            value: output_value,
+            dust_limit,
+        });
+    }
+
+    Ok(ConsolidationPlan {
+        chain,
+        warnings: privacy_warnings(&inputs, address),
+        inputs,
+        fee_rate,
+        vbytes,
+        fee,
+        total_in,
+        output_value,
+        uneconomical: uneconomical.len(),
+    })
+}
+
+fn fee_for(weight: u64, fee_rate: f64) -> u64 {
+    (weight as f64 / 4.0 * fee_rate).ceil() as u64
+}
+
+fn privacy_warnings(inputs: &[Utxo], destination: &str) -> Vec<PrivacyWarning> {
+    let mut warnings = Vec::new();
+    let addresses: BTreeSet<&str> = inputs.iter().map(|utxo| utxo.address.as_str()).collect();
+    if addresses.len() > 1 {
+        warnings.push(PrivacyWarning::LinksAddresses {
+            addresses: addresses.len(),
+        });
+    }
+    let kinds: BTreeSet<ScriptKind> = inputs.iter().map(|utxo| utxo.kind).collect();
+    if kinds.len() > 1 {
+        warnings.push(PrivacyWarning::MixedScriptTypes {
+            kinds: kinds.into_iter().collect(),
+        });
+    }
+    if addresses.contains(destination) {
+        warnings.push(PrivacyWarning::AddressReuse {
+            address: destination.to_string(),
+        });
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bdk::bitcoin::hashes::Hash;
+    use bdk::bitcoin::Txid;
+
+    fn utxo(n: u8, value: u64, kind: ScriptKind, address: &str) -> Utxo {
+        Utxo {
+            outpoint: OutPoint::new(Txid::from_byte_array([n; 32]), 0),
+            value,
+            address: address.to_string(),
+            kind,
+            confirmations: 3,
+        }
+    }
+
+    #[test]
+    fn test_plans_small_economical_utxos() {
+        let mut utxos: Vec<Utxo> = (0..6)
+            .map(|n| {
+                utxo(
+                    n,
+                    10_000 + n as u64,
+                    ScriptKind::P2wpkh,
+                    &format!("bc1q{}", n % 3),
+                )
+            })
+            .collect();
+        // Dust at 10 sat/vB, a large UTXO and an unconfirmed one
+        utxos.push(utxo(10, 600, ScriptKind::P2wpkh, "bc1q0"));
+        utxos.push(utxo(11, 5_000_000, ScriptKind::P2wpkh, "bc1q0"));
+        utxos.push(Utxo {
+            confirmations: 0,
+            ..utxo(12, 9_000, ScriptKind::P2wpkh, "bc1q0")
+        });
+        utxos.push(utxo(13, 20_000, ScriptKind::P2pkh, "1legacy"));
+
+        let policy = ConsolidationPolicy::new(UtxoChain::Bitcoin, 10.0);
+        let plan = plan(
+            UtxoChain::Bitcoin,
+            &utxos,
+            10.0,
+            ("bc1q0", ScriptKind::P2wpkh),
+            &policy,
+        )
+        .unwrap();
+        assert_eq!(plan.inputs.len(), 7);
+        assert_eq!(plan.inputs[0].value, 10_000);
+        assert_eq!(plan.uneconomical, 1);
+        // 42 + 6 * 271 + 592 + 124 = 2384 WU
+        assert_eq!(plan.vbytes, 596);
+        assert_eq!(plan.fee, 5960);
+        assert_eq!(plan.output_value, plan.total_in - plan.fee);
+        assert_eq!(
+            plan.warnings,
+            vec![
+                PrivacyWarning::LinksAddresses { addresses: 4 },
+                PrivacyWarning::MixedScriptTypes {
+                    kinds: vec![ScriptKind::P2pkh, ScriptKind::P2wpkh],
+                },
+                PrivacyWarning::AddressReuse {
+                    address: "bc1q0".into(),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_skips() {
+        let utxos: Vec<Utxo> = (0..5)
+            .map(|n| utxo(n, 2_000_000, ScriptKind::P2pkh, "D1"))
+            .collect();
+        let policy = ConsolidationPolicy::new(UtxoChain::Dogecoin, 1_000.0);
+        assert!(matches!(
+            plan(
+                UtxoChain::Dogecoin,
+                &utxos,
+                2_000.0,
+                ("D1", ScriptKind::P2pkh),
+                &policy
+            ),
+            Err(SkipReason::FeeRateTooHigh { .. })
+        ));
+        // 40 + 5 * 592 + 136 = 3136 WU at 1,000 koinu/vB
+        let plan = plan(
+            UtxoChain::Dogecoin,
+            &utxos,
+            1_000.0,
+            ("D2", ScriptKind::P2pkh),
+            &policy,
+        )
+        .unwrap();
+        assert_eq!(plan.fee, 784_000);
+        assert!(plan.warnings.is_empty());
+
+        let policy = policy.min_inputs(6);
+        assert_eq!(
+            super::plan(
+                UtxoChain::Dogecoin,
+                &utxos,
+                1_000.0,
+                ("D2", ScriptKind::P2pkh),
+                &policy
+            ),
+            Err(SkipReason::TooFewUtxos { found: 5, min: 6 })
+        );
+        assert_eq!(UtxoChain::Bitcoin.dust_limit(ScriptKind::P2wpkh), 294);
+        assert_eq!(UtxoChain::Bitcoin.dust_limit(ScriptKind::P2pkh), 546);
+        assert_eq!(UtxoChain::Bitcoin.dust_limit(ScriptKind::P2tr), 330);
+        assert!(!UtxoChain::BitcoinCash.supports(ScriptKind::P2wpkh));
+    }
+}
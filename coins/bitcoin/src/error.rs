@@ -54,4 +54,34 @@ pub enum Error {
     /// Error due to overflow
     #[error("Overflow error: {0}")]
     Overflow(String),
+    /// A consolidation was not worth sending
+    #[error("Consolidation skipped: {0}")]
+    ConsolidationSkipped(crate::consolidation::SkipReason),
+    /// A signer refused or returned something unusable
+    #[error("Signer error: {0}")]
+    Signer(String),
+    /// Error from the BDK wallet
+    #[error("Wallet error: {0}")]
+    Bdk(#[from] bdk::Error),
+}
+
+impl From<Error> for walletd_traits::WalletError {
+    fn from(e: Error) -> Self {
+        use walletd_traits::{Amount, WalletError};
+        match e {
+            Error::Bdk(bdk::Error::InsufficientFunds { needed, available }) => {
+                WalletError::InsufficientBalance {
+                    have: Amount::from_smallest_unit(available as u128, 8),
+                    need: Amount::from_smallest_unit(needed as u128, 8),
+                }
+            }
+            Error::FromStr(m) => WalletError::InvalidAddress(m),
+            Error::BroadcastTransaction(m) => WalletError::TransactionFailed(m),
+            Error::MissingMasterHDKey | Error::MissingMnemonicSeed | Error::Signer(_) => {
+                WalletError::KeyError(e.to_string())
+            }
+            Error::CurrentlyNotSupported(m) => WalletError::NotSupported(m),
+            e => WalletError::Other(e.to_string()),
+        }
+    }
 }
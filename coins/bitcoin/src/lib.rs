@@ -7,6 +7,10 @@ pub use bitcoin_wallet::{BitcoinWallet, BitcoinWalletBuilder, AddressType as Bdk
 
 mod error;
 pub use error::Error;
+pub use taproot::TaprootWallet;
+
+mod traits_impl;
+pub use traits_impl::ConnectedBitcoinWallet;
 
 pub mod prelude;
 
@@ -23,11 +27,13 @@ use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+pub mod consolidation;
 pub mod lightning; // Always expose lightning module
 pub mod multi_wallet;
 pub mod security;
 pub mod storage;
 pub mod swaps;
+pub mod taproot;
 pub mod transaction_builder;
 pub mod utxo_manager;
 
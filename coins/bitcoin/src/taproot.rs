@@ -0,0 +1,215 @@
+//! Taproot key-path wallet backed by any BIP-340 [`Signer`]
+//!
+//! The wallet holds no key material. Its address is the P2TR output for the
+//! signer's x-only key, which must already be the tweaked output key, as
+//! [`Secp256k1Taproot`] threshold signers and BIP-86 software keys report.
+//! Spends use the key path with `SIGHASH_DEFAULT`.
+//!
+//! [`Secp256k1Taproot`]: https://docs.rs/walletd-mpc/latest/walletd_mpc/struct.Secp256k1Taproot.html
+
+use crate::Error;
+use bitcoin::key::{TweakedPublicKey, XOnlyPublicKey};
+use bitcoin::sighash::{Prevouts, SighashCache, TapSighashType};
+use bitcoin::{taproot, Address, Network, Transaction, TxOut, Witness};
+use std::fmt;
+use walletd_traits::{SignatureScheme, Signer};
+
+/// A P2TR address spent through the key path by a [`Signer`]
+pub struct TaprootWallet {
+    signer: Box<dyn Signer>,
+    output_key: TweakedPublicKey,
+    network: Network,
+}
+
+impl TaprootWallet {
+    /// Wraps a [`SignatureScheme::Bip340`] signer
+    pub fn from_signer(signer: Box<dyn Signer>, network: Network) -> Result<Self, Error> {
+        if signer.scheme() != SignatureScheme::Bip340 {
+            return Err(Error::CurrentlyNotSupported(format!(
+                "taproot needs a bip340 signer, not {}",
+                signer.scheme()
+            )));
+        }
+        let key = XOnlyPublicKey::from_slice(&signer.public_key())
+            .map_err(|e| Error::Signer(format!("invalid x-only key: {e}")))?;
+        Ok(Self {
+            signer,
+            output_key: TweakedPublicKey::dangerous_assume_tweaked(key),
+            network,
+        })
+    }
+
+    /// The P2TR address
+    pub fn address(&self) -> Address {
+        Address::p2tr_tweaked(self.output_key, self.network)
+    }
+
+    /// The output key the address commits to
+    pub fn output_key(&self) -> XOnlyPublicKey {
+        self.output_key.to_inner()
+    }
+
+    /// Signs every input of `tx` through the key path
+    ///
+    /// `prevouts` are the outputs being spent, one per input in order; each
+    /// must pay this wallet's address.
+    pub async fn sign(&self, tx: &mut Transaction, prevouts: &[TxOut]) -> Result<(), Error> {
+        if prevouts.len() != tx.input.len() {
+            return Err(Error::MissingData(format!(
+                "{} prevouts for {} inputs",
+                prevouts.len(),
+                tx.input.len()
+            )));
+        }
+        let script = self.address().script_pubkey();
+        if let Some(other) = prevouts.iter().find(|p| p.script_pubkey != script) {
+            return Err(Error::ScriptInvalid(format!(
+                "prevout {} is not ours",
+                other.script_pubkey
+            )));
+        }
+
+        let mut witnesses = Vec::with_capacity(tx.input.len());
+        let mut cache = SighashCache::new(&*tx);
+        for index in 0..prevouts.len() {
+            let sighash = cache
+                .taproot_key_spend_signature_hash(
+                    index,
+                    &Prevouts::All(prevouts),
+                    TapSighashType::Default,
+                )
+                .map_err(|e| Error::Signer(e.to_string()))?;
+            let bytes = self
+                .signer
+                .sign_hash(sighash.as_ref())
+                .await
+                .map_err(|e| Error::Signer(e.to_string()))?;
+            let signature = taproot::Signature {
+                sig: bitcoin::secp256k1::schnorr::Signature::from_slice(&bytes)
+                    .map_err(|e| Error::Signer(format!("invalid signature: {e}")))?,
+                hash_ty: TapSighashType::Default,
+            };
+            witnesses.push(Witness::from_slice(&[signature.to_vec()]));
+        }
+        for (input, witness) in tx.input.iter_mut().zip(witnesses) {
+            input.witness = witness;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for TaprootWallet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TaprootWallet")
+            .field("output_key", &self.output_key)
+            .field("network", &self.network)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hashes::Hash;
+    use bitcoin::secp256k1::{schnorr, Message, Secp256k1};
+    use bitcoin::{absolute, transaction, Amount, OutPoint, ScriptBuf, Sequence, TxIn, Txid};
+    use walletd_mpc::{generate_with_dealer, LocalParticipant, Secp256k1Taproot, ThresholdSigner};
+
+    fn threshold_wallet() -> TaprootWallet {
+        let (shares, public) =
+            generate_with_dealer::<Secp256k1Taproot>(3, 2, &mut rand::thread_rng()).unwrap();
+        let participants = shares
+            .into_iter()
+            .take(2)
+            .map(|share| Box::new(LocalParticipant::new(share.try_into().unwrap())) as _)
+            .collect();
+        let signer = ThresholdSigner::new(public, participants).unwrap();
+        TaprootWallet::from_signer(Box::new(signer), Network::Regtest).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_threshold_key_spend_verifies() {
+        let wallet = threshold_wallet();
+        assert!(wallet.address().to_string().starts_with("bcrt1p"));
+
+        let prevouts = vec![
+            TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: wallet.address().script_pubkey(),
+            };
+            2
+        ];
+        let mut tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: (0..2u32)
+                .map(|vout| TxIn {
+                    previous_output: OutPoint::new(Txid::all_zeros(), vout),
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: vec![TxOut {
+                value: Amount::from_sat(99_000),
+                script_pubkey: wallet.address().script_pubkey(),
+            }],
+        };
+        wallet.sign(&mut tx, &prevouts).await.unwrap();
+
+        let secp = Secp256k1::verification_only();
+        let mut cache = SighashCache::new(&tx);
+        for (index, input) in tx.input.iter().enumerate() {
+            // SIGHASH_DEFAULT leaves the 64-byte signature unsuffixed
+            let sig = input.witness.nth(0).unwrap();
+            assert_eq!(sig.len(), 64);
+            let sighash = cache
+                .taproot_key_spend_signature_hash(
+                    index,
+                    &Prevouts::All(&prevouts),
+                    TapSighashType::Default,
+                )
+                .unwrap();
+            let msg = Message::from_digest(sighash.to_byte_array());
+            let sig = schnorr::Signature::from_slice(sig).unwrap();
+            secp.verify_schnorr(&sig, &msg, &wallet.output_key())
+                .unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_wrong_scheme_and_foreign_prevouts() {
+        let (shares, public) =
+            generate_with_dealer::<walletd_mpc::Secp256k1Sha256>(2, 2, &mut rand::thread_rng())
+                .unwrap();
+        let participants = shares
+            .into_iter()
+            .map(|share| Box::new(LocalParticipant::new(share.try_into().unwrap())) as _)
+            .collect();
+        let schnorr = ThresholdSigner::new(public, participants).unwrap();
+        assert!(matches!(
+            TaprootWallet::from_signer(Box::new(schnorr), Network::Regtest),
+            Err(Error::CurrentlyNotSupported(_))
+        ));
+
+        let wallet = threshold_wallet();
+        let mut tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![TxIn::default()],
+            output: vec![],
+        };
+        let foreign = TxOut {
+            value: Amount::from_sat(1_000),
+            script_pubkey: ScriptBuf::new(),
+        };
+        assert!(matches!(
+            wallet.sign(&mut tx, &[foreign]).await,
+            Err(Error::ScriptInvalid(_))
+        ));
+        assert!(matches!(
+            wallet.sign(&mut tx, &[]).await,
+            Err(Error::MissingData(_))
+        ));
+    }
+}
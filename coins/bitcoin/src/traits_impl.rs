@@ -0,0 +1,363 @@
+//! Implementation of walletd-traits for BitcoinWallet
+
+use async_trait::async_trait;
+use bdk::blockchain::Blockchain;
+use std::sync::Mutex;
+use walletd_traits::{
+    Amount, BatchTransferReport, BatchTransferable, Network, TransactionBuilder, Transferable,
+    TxHash, Wallet, WalletError, WalletResult,
+};
+
+use crate::{BitcoinWallet, Error};
+
+/// Confirmation target for payments, in blocks (about an hour)
+const TARGET_BLOCKS: usize = 6;
+
+/// Size of a one-input, two-output P2WPKH transaction, in vbytes
+const SINGLE_TRANSFER_VBYTES: usize = 141;
+
+/// Wrapper that holds a BitcoinWallet with a blockchain backend for trait
+/// implementations
+pub struct ConnectedBitcoinWallet<B> {
+    wallet: Mutex<BitcoinWallet>,
+    blockchain: B,
+    address: String,
+    network: Network,
+}
+
+impl<B: Blockchain> ConnectedBitcoinWallet<B> {
+    /// Creates a new connected wallet, receiving at its next address
+    pub fn new(wallet: BitcoinWallet, blockchain: B, network: Network) -> Result<Self, Error> {
+        let address = wallet.receive_address()?;
+        Ok(Self {
+            wallet: Mutex::new(wallet),
+            blockchain,
+            address,
+            network,
+        })
+    }
+
+    /// The blockchain backend
+    pub fn blockchain(&self) -> &B {
+        &self.blockchain
+    }
+
+    /// Builds, signs and broadcasts one transaction paying every recipient
+    fn send(&self, transfers: &[(String, Amount)]) -> WalletResult<TxHash> {
+        let recipients = transfers
+            .iter()
+            .map(|(to, amount)| {
+                if amount.decimals != 8 {
+                    return Err(WalletError::Other(format!("{} is not in BTC", amount)));
+                }
+                let sats = u64::try_from(amount.smallest_unit())
+                    .map_err(|_| WalletError::Other(format!("{} satoshis overflow", amount)))?;
+                Ok((to.clone(), sats))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+        let fee_rate = self
+            .blockchain
+            .estimate_fee(TARGET_BLOCKS)
+            .map_err(network_error)?;
+        let tx = self
+            .wallet
+            .lock()
+            .map_err(|_| WalletError::Other("wallet lock poisoned".to_string()))?
+            .build_batch(&recipients, fee_rate)?;
+        self.blockchain
+            .broadcast(&tx)
+            .map_err(|e| WalletError::TransactionFailed(e.to_string()))?;
+        Ok(TxHash::new(tx.txid().to_string()))
+    }
+}
+
+fn network_error(e: bdk::Error) -> WalletError {
+    WalletError::NetworkError(e.to_string())
+}
+
+#[async_trait]
+impl<B: Blockchain + Send + Sync> Wallet for ConnectedBitcoinWallet<B> {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        let balance = self
+            .wallet
+            .lock()
+            .map_err(|_| WalletError::Other("wallet lock poisoned".to_string()))?
+            .bdk_wallet()?
+            .get_balance()
+            .map_err(Error::from)?;
+        Ok(Amount::from_smallest_unit(balance.get_total() as u128, 8))
+    }
+
+    fn network(&self) -> &Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "BTC"
+    }
+
+    fn decimals(&self) -> u8 {
+        8
+    }
+}
+
+#[async_trait]
+impl<B: Blockchain + Send + Sync> Transferable for ConnectedBitcoinWallet<B> {
+    type TxParams = ();
+
+    async fn transfer_with(&self, tx: TransactionBuilder<()>) -> WalletResult<TxHash> {
+        if tx.data.is_some() || tx.nonce.is_some() {
+            return Err(WalletError::NotSupported(
+                "custom data and nonce are not supported for bitcoin".to_string(),
+            ));
+        }
+        let to = tx
+            .to
+            .ok_or_else(|| WalletError::InvalidAddress(String::new()))?;
+        let amount = tx
+            .amount
+            .ok_or_else(|| WalletError::Other("missing amount".to_string()))?;
+        self.send(&[(to, amount)])
+    }
+
+    async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+        let fee_rate = self
+            .blockchain
+            .estimate_fee(TARGET_BLOCKS)
+            .map_err(network_error)?;
+        Ok(Amount::from_smallest_unit(
+            fee_rate.fee_vb(SINGLE_TRANSFER_VBYTES) as u128,
+            8,
+        ))
+    }
+}
+
+/// Batches go out as one transaction with an output per recipient, so they
+/// land or fail together
+#[async_trait]
+impl<B: Blockchain + Send + Sync> BatchTransferable for ConnectedBitcoinWallet<B> {
+    fn supports_native_batch(&self) -> bool {
+        true
+    }
+
+    async fn transfer_batch(
+        &self,
+        transfers: &[(String, Amount)],
+    ) -> WalletResult<BatchTransferReport> {
+        let outcome = self.send(transfers);
+        Ok(BatchTransferReport::single_tx(transfers, outcome))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bdk::bitcoin::hashes::Hash;
+    use bdk::bitcoin::secp256k1::{Secp256k1, SecretKey};
+    use bdk::bitcoin::{
+        Address, BlockHash, OutPoint, PublicKey, Script, Transaction, TxIn, TxOut, Txid,
+    };
+    use bdk::blockchain::{Capability, GetBlockHash, GetHeight, GetTx, Progress, WalletSync};
+    use bdk::database::BatchDatabase;
+    use bdk::keys::bip39::Mnemonic;
+    use bdk::{BlockTime, FeeRate, LocalUtxo, TransactionDetails};
+    use std::cell::RefCell;
+    use std::collections::HashSet;
+    use std::str::FromStr;
+
+    /// Test mnemonic (DO NOT USE IN PRODUCTION)
+    const TEST_MNEMONIC: &str =
+        "outer ride neither foil glue number place usage ball shed dry point";
+
+    const AMOUNTS: [u64; 3] = [10_000, 20_000, 30_000];
+
+    /// Regtest P2WPKH addresses of other wallets
+    fn recipients() -> Vec<Address> {
+        let secp = Secp256k1::new();
+        (1..=3u8)
+            .map(|i| {
+                let key = SecretKey::from_slice(&[i; 32]).unwrap();
+                let key = PublicKey::new(key.public_key(&secp));
+                Address::p2wpkh(&key, bdk::bitcoin::Network::Regtest).unwrap()
+            })
+            .collect()
+    }
+
+    /// A chain holding one confirmed funding transaction, which records
+    /// broadcasts instead of relaying them
+    struct MockChain {
+        funding: Transaction,
+        broadcast: Mutex<Vec<Transaction>>,
+    }
+
+    impl MockChain {
+        fn funding(to: &Script, value: u64) -> Self {
+            let funding = Transaction {
+                version: 2,
+                lock_time: bdk::bitcoin::absolute::LockTime::ZERO,
+                // Spends a made-up outpoint so it is not a coinbase
+                input: vec![TxIn {
+                    previous_output: OutPoint::new(Txid::all_zeros(), 0),
+                    ..Default::default()
+                }],
+                output: vec![TxOut {
+                    value,
+                    script_pubkey: to.to_owned(),
+                }],
+            };
+            Self {
+                funding,
+                broadcast: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    impl Blockchain for MockChain {
+        fn get_capabilities(&self) -> HashSet<Capability> {
+            HashSet::new()
+        }
+
+        fn broadcast(&self, tx: &Transaction) -> Result<(), bdk::Error> {
+            self.broadcast.lock().unwrap().push(tx.clone());
+            Ok(())
+        }
+
+        fn estimate_fee(&self, _target: usize) -> Result<FeeRate, bdk::Error> {
+            Ok(FeeRate::from_sat_per_vb(2.0))
+        }
+    }
+
+    impl GetHeight for MockChain {
+        fn get_height(&self) -> Result<u32, bdk::Error> {
+            Ok(100)
+        }
+    }
+
+    impl GetTx for MockChain {
+        fn get_tx(&self, txid: &Txid) -> Result<Option<Transaction>, bdk::Error> {
+            Ok((*txid == self.funding.txid()).then(|| self.funding.clone()))
+        }
+    }
+
+    impl GetBlockHash for MockChain {
+        fn get_block_hash(&self, _height: u64) -> Result<BlockHash, bdk::Error> {
+            Err(bdk::Error::Generic("no blocks".to_string()))
+        }
+    }
+
+    impl WalletSync for MockChain {
+        fn wallet_setup<D: BatchDatabase>(
+            &self,
+            database: &RefCell<D>,
+            _progress_update: Box<dyn Progress>,
+        ) -> Result<(), bdk::Error> {
+            let mut database = database.borrow_mut();
+            let txid = self.funding.txid();
+            for (vout, output) in self.funding.output.iter().enumerate() {
+                if let Some((keychain, _)) =
+                    database.get_path_from_script_pubkey(&output.script_pubkey)?
+                {
+                    database.set_utxo(&LocalUtxo {
+                        outpoint: OutPoint::new(txid, vout as u32),
+                        txout: output.clone(),
+                        keychain,
+                        is_spent: false,
+                    })?;
+                }
+            }
+            database.set_tx(&TransactionDetails {
+                transaction: Some(self.funding.clone()),
+                txid,
+                received: self.funding.output[0].value,
+                sent: 0,
+                fee: Some(0),
+                confirmation_time: Some(BlockTime {
+                    height: 90,
+                    timestamp: 0,
+                }),
+            })?;
+            Ok(())
+        }
+    }
+
+    async fn funded_wallet(sats: u64) -> ConnectedBitcoinWallet<MockChain> {
+        let mut wallet = BitcoinWallet::builder()
+            .mnemonic(Mnemonic::parse(TEST_MNEMONIC).unwrap())
+            .network_type(bdk::bitcoin::Network::Regtest)
+            .build()
+            .unwrap();
+        let address = Address::from_str(&wallet.receive_address().unwrap())
+            .unwrap()
+            .assume_checked();
+        let chain = MockChain::funding(&address.script_pubkey(), sats);
+        wallet.sync(&chain).await.unwrap();
+        ConnectedBitcoinWallet::new(wallet, chain, Network::testnet("bitcoin-regtest")).unwrap()
+    }
+
+    fn btc(sats: u128) -> Amount {
+        Amount::from_smallest_unit(sats, 8)
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_one_multi_output_tx() {
+        let wallet = funded_wallet(100_000).await;
+        assert_eq!(wallet.balance().await.unwrap(), btc(100_000));
+        assert!(wallet.supports_native_batch());
+
+        let recipients = recipients();
+        let transfers: Vec<_> = recipients
+            .iter()
+            .zip(AMOUNTS)
+            .map(|(to, sats)| (to.to_string(), btc(sats as u128)))
+            .collect();
+        let report = wallet.transfer_batch(&transfers).await.unwrap();
+        assert!(report.failed().next().is_none());
+
+        let sent = wallet.blockchain().broadcast.lock().unwrap();
+        assert_eq!(sent.len(), 1);
+        let tx = &sent[0];
+        assert_eq!(report.batch_tx, Some(TxHash::new(tx.txid().to_string())));
+        assert_eq!(tx.input.len(), 1);
+        assert_eq!(
+            tx.input[0].previous_output.txid,
+            wallet.blockchain().funding.txid()
+        );
+        assert!(!tx.input[0].witness.is_empty());
+        // One output per recipient plus change
+        assert_eq!(tx.output.len(), 4);
+        for (to, sats) in recipients.iter().zip(AMOUNTS) {
+            let script = to.script_pubkey();
+            assert!(tx
+                .output
+                .iter()
+                .any(|o| o.script_pubkey == script && o.value == sats));
+        }
+        let fee = 100_000 - tx.output.iter().map(|o| o.value).sum::<u64>();
+        assert!(fee >= 2 * tx.vsize() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_batch_failures_are_reported() {
+        let wallet = funded_wallet(10_000).await;
+        let too_much = [(recipients()[0].to_string(), btc(50_000))];
+        let report = wallet.transfer_batch(&too_much).await.unwrap();
+        assert_eq!(report.batch_tx, None);
+        assert!(report.results[0]
+            .error
+            .as_deref()
+            .unwrap()
+            .starts_with("Insufficient balance"));
+
+        let mainnet = "bc1qw508d6qejxtdg4y5r3zarvary0c5xw7kv8f3t4";
+        assert!(matches!(
+            wallet.transfer(mainnet, btc(1_000)).await,
+            Err(WalletError::InvalidAddress(_))
+        ));
+        assert!(wallet.transfer_batch(&[]).await.unwrap().batch_tx.is_none());
+        assert!(wallet.blockchain().broadcast.lock().unwrap().is_empty());
+    }
+}
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use walletd_error::WalletdError;
 
 #[derive(Error, Debug)]
 pub enum CardanoError {
@@ -32,3 +33,34 @@ pub enum CardanoError {
     #[error("Other error: {0}")]
     Other(#[from] anyhow::Error),
 }
+
+impl From<CardanoError> for WalletdError {
+    fn from(e: CardanoError) -> Self {
+        let error = match e {
+            CardanoError::KeyDerivationError(m) => WalletdError::KeyDerivationError(m),
+            CardanoError::AddressError(reason) | CardanoError::InvalidAddress(reason) => {
+                WalletdError::InvalidAddress {
+                    address: String::new(),
+                    reason,
+                }
+            }
+            CardanoError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            CardanoError::UtxoError(m) => WalletdError::TransactionBuildError(m),
+            CardanoError::InsufficientFunds {
+                required,
+                available,
+            } => WalletdError::InsufficientBalance {
+                have: available.into(),
+                need: required.into(),
+            },
+            CardanoError::NetworkError(m) => WalletdError::NetworkError(m),
+            CardanoError::ApiError(reason) => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason,
+            },
+            CardanoError::SerializationError(m) => WalletdError::FormatError(m),
+            CardanoError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("cardano")
+    }
+}
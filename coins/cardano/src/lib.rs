@@ -89,4 +89,16 @@ mod tests {
         let addr = CardanoAddress::enterprise(&pubkey, MAINNET_NETWORK_ID);
         assert!(addr.is_ok());
     }
+
+    #[test]
+    fn test_error_into_walletd_error() {
+        use walletd_error::{ErrorCode, WalletdError};
+
+        let err: WalletdError = CardanoError::InsufficientFunds { required: 5, available: 2 }.into();
+        assert_eq!(err.chain(), Some("cardano"));
+        assert_eq!(err.code(), ErrorCode::InsufficientBalance);
+
+        let err: WalletdError = CardanoError::NetworkError("timeout".into()).into();
+        assert!(err.is_retryable());
+    }
 }
@@ -3,14 +3,25 @@
 //! Supports Cosmos Hub and other Cosmos SDK chains.
 
 use anyhow::Result;
+use base64::Engine;
 use bech32::{Bech32, Hrp};
 use bip39::Mnemonic;
+use prost::Message;
 use ripemd::Ripemd160;
-use secp256k1::{Secp256k1, SecretKey, PublicKey};
+use secp256k1::PublicKey;
 use sha2::{Sha256, Digest};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
+use walletd_traits::{
+    Amount, BatchTransferReport, CosmosTxParams, Secp256k1Signer, SignatureScheme, Signer,
+    TransactionBuilder, TxHash, WalletError, WalletResult,
+};
+use walletd_error::WalletdError;
+
+mod staking;
+pub mod tx;
+use tx::{AccountInfo, Coin, Fee, TxRaw};
 
 // ============================================================================
 // ERRORS
@@ -32,6 +43,35 @@ pub enum CosmosError {
     Other(#[from] anyhow::Error),
 }
 
+impl From<CosmosError> for WalletdError {
+    fn from(e: CosmosError) -> Self {
+        let error = match e {
+            CosmosError::InvalidAddress(address) => WalletdError::InvalidAddress {
+                address,
+                reason: "not a bech32 address of this chain".to_string(),
+            },
+            CosmosError::KeyError(m) => WalletdError::InvalidPrivateKey(m),
+            CosmosError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            CosmosError::NetworkError(m) => WalletdError::NetworkError(m),
+            CosmosError::ApiError(m) => WalletdError::NetworkError(m),
+            CosmosError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("cosmos")
+    }
+}
+
+impl From<CosmosError> for WalletError {
+    fn from(e: CosmosError) -> Self {
+        match e {
+            CosmosError::InvalidAddress(address) => WalletError::InvalidAddress(address),
+            CosmosError::KeyError(m) => WalletError::KeyError(m),
+            CosmosError::TransactionError(m) => WalletError::TransactionFailed(m),
+            CosmosError::NetworkError(m) | CosmosError::ApiError(m) => WalletError::NetworkError(m),
+            CosmosError::Other(e) => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
 // ============================================================================
 // CONFIG
 // ============================================================================
@@ -50,6 +90,8 @@ pub struct NetworkConfig {
 
 pub const COSMOS_HUB_CHAIN_ID: &str = "cosmoshub-4";
 pub const UATOM_DENOM: &str = "uatom";
+/// Gas price used when a transfer sets no fee, in the fee denom per unit of gas
+pub const DEFAULT_GAS_PRICE: f64 = 0.025;
 
 impl NetworkConfig {
     pub fn cosmos_hub() -> Self {
@@ -95,6 +137,36 @@ impl NetworkConfig {
     pub fn uatom_to_atom(uatom: u64) -> f64 {
         uatom as f64 / 1_000_000.0
     }
+
+    fn network(&self) -> walletd_traits::Network {
+        if self.chain_id.contains("testnet") {
+            walletd_traits::Network::testnet(&self.chain_id)
+        } else {
+            walletd_traits::Network::mainnet(&self.chain_id)
+        }
+    }
+
+    /// Checks that `address` is an account on this chain
+    fn check_address(&self, address: &str) -> std::result::Result<(), CosmosError> {
+        match bech32::decode(address) {
+            Ok((hrp, data)) if hrp.as_str() == self.bech32_prefix && matches!(data.len(), 20 | 32) => Ok(()),
+            _ => Err(CosmosError::InvalidAddress(address.to_string())),
+        }
+    }
+
+    /// Checks that `address` is a validator operator (`{prefix}valoper1...`)
+    /// on this chain
+    fn check_validator_address(&self, address: &str) -> std::result::Result<(), CosmosError> {
+        match bech32::decode(address) {
+            Ok((hrp, data))
+                if hrp.as_str().strip_suffix("valoper") == Some(self.bech32_prefix.as_str())
+                    && data.len() == 20 =>
+            {
+                Ok(())
+            }
+            _ => Err(CosmosError::InvalidAddress(address.to_string())),
+        }
+    }
 }
 
 // ============================================================================
@@ -102,29 +174,20 @@ impl NetworkConfig {
 // ============================================================================
 
 pub struct CosmosWallet {
-    secret_key: SecretKey,
+    signer: Box<dyn Signer>,
     public_key: PublicKey,
     config: NetworkConfig,
     api_endpoint: Option<String>,
+    network: walletd_traits::Network,
 }
 
 impl CosmosWallet {
     pub fn new(config: NetworkConfig) -> Result<Self> {
-        let secp = Secp256k1::new();
-        
         // Generate random 32-byte key
         let mut key_bytes = [0u8; 32];
         rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut key_bytes);
-        
-        let secret_key = SecretKey::from_slice(&key_bytes)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
 
-        Ok(Self {
-            secret_key,
-            public_key,
-            config,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn mainnet() -> Result<Self> {
@@ -143,26 +206,25 @@ impl CosmosWallet {
         let mut key_bytes = [0u8; 32];
         key_bytes.copy_from_slice(&seed[..32]);
 
-        let secp = Secp256k1::new();
-        let secret_key = SecretKey::from_slice(&key_bytes)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
-
-        Ok(Self {
-            secret_key,
-            public_key,
-            config,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn from_private_key(key: &[u8], config: NetworkConfig) -> Result<Self> {
-        let secp = Secp256k1::new();
-        let secret_key = SecretKey::from_slice(key)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        Self::from_signer(Box::new(Secp256k1Signer::from_slice(key)?), config)
+    }
+
+    /// Creates a wallet backed by any secp256k1 signer (hardware, remote, HSM)
+    pub fn from_signer(signer: Box<dyn Signer>, config: NetworkConfig) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            anyhow::bail!("Cosmos requires a secp256k1 signer, got {}", signer.scheme());
+        }
+        let public_key = PublicKey::from_slice(&signer.public_key())
+            .map_err(|e| CosmosError::KeyError(e.to_string()))?;
 
         Ok(Self {
-            secret_key,
+            signer,
             public_key,
+            network: config.network(),
             config,
             api_endpoint: None,
         })
@@ -173,20 +235,20 @@ impl CosmosWallet {
     }
 
     pub fn address(&self) -> String {
-        let pubkey_bytes = self.public_key.serialize();
-        let sha256_hash = Sha256::digest(&pubkey_bytes);
-        let ripemd_hash = Ripemd160::digest(&sha256_hash);
-
-        let hrp = Hrp::parse(&self.config.bech32_prefix).unwrap();
-        bech32::encode::<Bech32>(hrp, &ripemd_hash).unwrap()
+        bech32_address(&self.public_key, &self.config.bech32_prefix)
     }
 
     pub fn public_key(&self) -> String {
         hex::encode(self.public_key.serialize())
     }
 
-    pub fn private_key(&self) -> String {
-        format!("0x{}", hex::encode(self.secret_key.secret_bytes()))
+    /// Fails when the key is held by an external signer
+    pub fn private_key(&self) -> Result<String> {
+        let secret = self
+            .signer
+            .secret_key()
+            .ok_or_else(|| anyhow::anyhow!("private key is held by an external signer"))?;
+        Ok(format!("0x{}", hex::encode(secret)))
     }
 
     pub fn config(&self) -> &NetworkConfig {
@@ -210,12 +272,372 @@ impl CosmosWallet {
         Ok(NetworkConfig::uatom_to_atom(uatom))
     }
 
-    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
-        let secp = Secp256k1::new();
-        let msg_hash = Sha256::digest(message);
-        let msg = secp256k1::Message::from_slice(&msg_hash).unwrap();
-        let sig = secp.sign_ecdsa(&msg, &self.secret_key);
-        sig.serialize_compact().to_vec()
+    pub async fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let msg_hash: [u8; 32] = Sha256::digest(message).into();
+        Ok(self.signer.sign_hash(&msg_hash).await?)
+    }
+
+    /// Fetches the account number and next sequence from the REST API
+    pub async fn account_info(&self) -> std::result::Result<AccountInfo, CosmosError> {
+        #[derive(Deserialize)]
+        struct Response {
+            account: Account,
+        }
+        #[derive(Deserialize)]
+        struct Account {
+            account_number: String,
+            sequence: String,
+        }
+
+        let response: Response = self
+            .query(&format!("/cosmos/auth/v1beta1/accounts/{}", self.address()))
+            .await?;
+        let parse = |field: &str| {
+            field
+                .parse()
+                .map_err(|_| CosmosError::ApiError(format!("bad account field {}", field)))
+        };
+        Ok(AccountInfo {
+            account_number: parse(&response.account.account_number)?,
+            sequence: parse(&response.account.sequence)?,
+        })
+    }
+
+    /// Fee for `gas_limit` at [`DEFAULT_GAS_PRICE`], in the fee denom
+    pub fn default_fee(&self, gas_limit: u64) -> u128 {
+        (gas_limit as f64 * DEFAULT_GAS_PRICE).ceil() as u128
+    }
+
+    /// Signs one transaction with a `MsgSend` per transfer
+    ///
+    /// `params` sets the memo, gas limit and fee; unset, the gas limit is
+    /// [`tx::send_gas`] and the fee is [`default_fee`](Self::default_fee).
+    pub async fn sign_sends(
+        &self,
+        transfers: &[(String, Amount)],
+        params: &CosmosTxParams,
+        account: AccountInfo,
+    ) -> std::result::Result<TxRaw, CosmosError> {
+        if transfers.is_empty() {
+            return Err(CosmosError::TransactionError("no transfers to send".to_string()));
+        }
+        let from = self.address();
+        let mut messages = Vec::with_capacity(transfers.len());
+        for (to, amount) in transfers {
+            self.config.check_address(to)?;
+            let amount = self.check_amount(to, amount)?;
+            messages.push(tx::msg_send(&from, to, amount, &self.config.denom));
+        }
+        self.sign_messages(messages, tx::send_gas(transfers.len()), params, account).await
+    }
+
+    /// Signs one transaction carrying `messages`
+    ///
+    /// `params` sets the memo, gas limit and fee; unset, the gas limit is
+    /// `default_gas` and the fee is [`default_fee`](Self::default_fee).
+    pub async fn sign_messages(
+        &self,
+        messages: Vec<tx::Any>,
+        default_gas: u64,
+        params: &CosmosTxParams,
+        account: AccountInfo,
+    ) -> std::result::Result<TxRaw, CosmosError> {
+        let denom = &self.config.denom;
+        let gas_limit = params.gas_limit.unwrap_or(default_gas);
+        let fee = params
+            .fee
+            .map(|fee| fee.smallest_unit())
+            .unwrap_or_else(|| self.default_fee(gas_limit));
+        let fee = Fee {
+            amount: vec![Coin {
+                denom: denom.clone(),
+                amount: fee.to_string(),
+            }],
+            gas_limit,
+        };
+        let memo = params.memo.as_deref().unwrap_or_default();
+        let doc = tx::sign_doc(messages, memo, &self.public_key.serialize(), fee, self.chain_id(), account);
+
+        let hash: [u8; 32] = Sha256::digest(doc.encode_to_vec()).into();
+        let signature = self
+            .signer
+            .sign_hash(&hash)
+            .await
+            .map_err(|e| CosmosError::KeyError(e.to_string()))?;
+        Ok(TxRaw {
+            body_bytes: doc.body_bytes,
+            auth_info_bytes: doc.auth_info_bytes,
+            signatures: vec![signature],
+        })
+    }
+
+    /// Broadcasts a signed transaction and returns its hash
+    pub async fn broadcast(&self, tx: &TxRaw) -> std::result::Result<String, CosmosError> {
+        #[derive(Deserialize)]
+        struct Response {
+            tx_response: TxResponse,
+        }
+        #[derive(Deserialize)]
+        struct TxResponse {
+            txhash: String,
+            code: u32,
+            #[serde(default)]
+            raw_log: String,
+        }
+
+        let url = format!("{}/cosmos/tx/v1beta1/txs", self.rest_endpoint()?);
+        let body = serde_json::json!({
+            "tx_bytes": base64::engine::general_purpose::STANDARD.encode(tx.encode_to_vec()),
+            "mode": "BROADCAST_MODE_SYNC",
+        });
+        let response: Response = reqwest::Client::new()
+            .post(&url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| CosmosError::NetworkError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| CosmosError::ApiError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| CosmosError::ApiError(e.to_string()))?;
+        if response.tx_response.code != 0 {
+            return Err(CosmosError::TransactionError(response.tx_response.raw_log));
+        }
+        Ok(response.tx_response.txhash)
+    }
+
+    /// Signs and broadcasts one transaction paying every recipient
+    async fn send(
+        &self,
+        transfers: &[(String, Amount)],
+        params: &CosmosTxParams,
+        sequence: Option<u64>,
+    ) -> WalletResult<TxHash> {
+        let mut account = self.account_info().await?;
+        if let Some(sequence) = sequence {
+            account.sequence = sequence;
+        }
+        let tx = self.sign_sends(transfers, params, account).await?;
+        Ok(TxHash::new(self.broadcast(&tx).await?))
+    }
+
+    /// Signs and broadcasts one transaction carrying `messages`
+    async fn submit(&self, messages: Vec<tx::Any>, default_gas: u64) -> WalletResult<TxHash> {
+        let account = self.account_info().await?;
+        let tx = self
+            .sign_messages(messages, default_gas, &CosmosTxParams::default(), account)
+            .await?;
+        Ok(TxHash::new(self.broadcast(&tx).await?))
+    }
+
+    /// Smallest-unit value of `amount`, which must use the fee denom's decimals
+    fn check_amount(&self, to: &str, amount: &Amount) -> std::result::Result<u128, CosmosError> {
+        if amount.decimals != self.config.decimals {
+            return Err(CosmosError::TransactionError(format!(
+                "amount for {} has {} decimals, {} has {}",
+                to, amount.decimals, self.config.denom, self.config.decimals
+            )));
+        }
+        Ok(amount.smallest_unit())
+    }
+
+    /// GETs `path` from the REST API and decodes the JSON response
+    async fn query<T: DeserializeOwned>(&self, path: &str) -> std::result::Result<T, CosmosError> {
+        let url = format!("{}{}", self.rest_endpoint()?, path);
+        reqwest::get(&url)
+            .await
+            .map_err(|e| CosmosError::NetworkError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| CosmosError::ApiError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| CosmosError::ApiError(e.to_string()))
+    }
+
+    fn rest_endpoint(&self) -> std::result::Result<&str, CosmosError> {
+        self.api_endpoint
+            .as_deref()
+            .or_else(|| self.config.rest_endpoints.first().map(String::as_str))
+            .map(|endpoint| endpoint.trim_end_matches('/'))
+            .ok_or_else(|| CosmosError::NetworkError("no REST endpoint configured".to_string()))
+    }
+
+    /// Returns a read-only view of this wallet without the private key
+    pub fn watch_only(&self) -> CosmosWatchOnlyWallet {
+        let mut watch = CosmosWatchOnlyWallet::new(self.address(), Some(self.public_key), self.config.clone());
+        watch.api_endpoint = self.api_endpoint.clone();
+        watch
+    }
+}
+
+/// Bech32 account address of a secp256k1 public key
+fn bech32_address(public_key: &PublicKey, prefix: &str) -> String {
+    let sha256_hash = Sha256::digest(public_key.serialize());
+    let ripemd_hash = Ripemd160::digest(sha256_hash);
+
+    let hrp = Hrp::parse(prefix).unwrap();
+    bech32::encode::<Bech32>(hrp, &ripemd_hash).unwrap()
+}
+
+// ============================================================================
+// WATCH-ONLY WALLET
+// ============================================================================
+
+/// Read-only Cosmos wallet for monitoring an address without its private key
+pub struct CosmosWatchOnlyWallet {
+    address: String,
+    public_key: Option<PublicKey>,
+    config: NetworkConfig,
+    api_endpoint: Option<String>,
+    network: walletd_traits::Network,
+}
+
+impl CosmosWatchOnlyWallet {
+    fn new(address: String, public_key: Option<PublicKey>, config: NetworkConfig) -> Self {
+        Self {
+            address,
+            public_key,
+            network: config.network(),
+            config,
+            api_endpoint: None,
+        }
+    }
+
+    /// Watches a bech32 address with the network's prefix
+    pub fn from_address(address: &str, config: NetworkConfig) -> Result<Self> {
+        let (hrp, data) = bech32::decode(address)
+            .map_err(|_| CosmosError::InvalidAddress(address.to_string()))?;
+        if hrp.as_str() != config.bech32_prefix || data.len() != 20 {
+            return Err(CosmosError::InvalidAddress(address.to_string()).into());
+        }
+        Ok(Self::new(address.to_string(), None, config))
+    }
+
+    /// Watches the address of a compressed or uncompressed secp256k1 public key
+    pub fn from_pubkey(pubkey: &[u8], config: NetworkConfig) -> Result<Self> {
+        let public_key = PublicKey::from_slice(pubkey)
+            .map_err(|e| CosmosError::KeyError(e.to_string()))?;
+        let address = bech32_address(&public_key, &config.bech32_prefix);
+        Ok(Self::new(address, Some(public_key), config))
+    }
+
+    pub fn set_api_endpoint(&mut self, endpoint: &str) {
+        self.api_endpoint = Some(endpoint.to_string());
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Compressed public key as hex, if known
+    pub fn public_key(&self) -> Option<String> {
+        self.public_key.map(|key| hex::encode(key.serialize()))
+    }
+
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
+    pub async fn get_balance(&self) -> Result<u64> {
+        if self.api_endpoint.is_none() {
+            return Ok(0);
+        }
+        // Would query REST API: /cosmos/bank/v1beta1/balances/{address}
+        Ok(0)
+    }
+}
+
+#[async_trait::async_trait]
+impl walletd_traits::Wallet for CosmosWatchOnlyWallet {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn balance(&self) -> walletd_traits::WalletResult<walletd_traits::Amount> {
+        let amount = self
+            .get_balance()
+            .await
+            .map_err(|e| walletd_traits::WalletError::NetworkError(e.to_string()))?;
+        Ok(walletd_traits::Amount::from_smallest_unit(amount as u128, self.config.decimals))
+    }
+
+    fn network(&self) -> &walletd_traits::Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "ATOM"
+    }
+
+    fn decimals(&self) -> u8 {
+        self.config.decimals
+    }
+}
+
+impl walletd_traits::WatchOnly for CosmosWatchOnlyWallet {}
+
+#[async_trait::async_trait]
+impl walletd_traits::Wallet for CosmosWallet {
+    fn address(&self) -> String {
+        CosmosWallet::address(self)
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        let amount = self
+            .get_balance()
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+        Ok(Amount::from_smallest_unit(amount as u128, self.config.decimals))
+    }
+
+    fn network(&self) -> &walletd_traits::Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "ATOM"
+    }
+
+    fn decimals(&self) -> u8 {
+        self.config.decimals
+    }
+}
+
+#[async_trait::async_trait]
+impl walletd_traits::Transferable for CosmosWallet {
+    type TxParams = CosmosTxParams;
+
+    /// Honours the memo, gas limit and fee; the nonce overrides the account
+    /// sequence
+    async fn transfer_with(&self, tx: TransactionBuilder<CosmosTxParams>) -> WalletResult<TxHash> {
+        if tx.data.is_some() {
+            return Err(WalletError::NotSupported(
+                "custom data is not supported; use the memo".to_string(),
+            ));
+        }
+        let to = tx.to.ok_or_else(|| WalletError::InvalidAddress(String::new()))?;
+        let amount = tx.amount.ok_or_else(|| WalletError::Other("missing amount".to_string()))?;
+        self.send(&[(to, amount)], &tx.params, tx.nonce).await
+    }
+
+    async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+        let fee = self.default_fee(tx::send_gas(1));
+        Ok(Amount::from_smallest_unit(fee, self.config.decimals))
+    }
+}
+
+/// Batches go out as one transaction with a `MsgSend` per recipient, so they
+/// land or fail together
+#[async_trait::async_trait]
+impl walletd_traits::BatchTransferable for CosmosWallet {
+    fn supports_native_batch(&self) -> bool {
+        true
+    }
+
+    async fn transfer_batch(&self, transfers: &[(String, Amount)]) -> WalletResult<BatchTransferReport> {
+        let outcome = self.send(transfers, &CosmosTxParams::default(), None).await;
+        Ok(BatchTransferReport::single_tx(transfers, outcome))
     }
 }
 
@@ -229,6 +651,21 @@ mod tests {
 
     const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
 
+    #[test]
+    fn test_walletd_error_conversion() {
+        let err: WalletdError = CosmosWatchOnlyWallet::from_address("osmo1abc", NetworkConfig::cosmos_hub())
+            .err()
+            .unwrap()
+            .downcast::<CosmosError>()
+            .unwrap()
+            .into();
+        assert_eq!(err.chain(), Some("cosmos"));
+        assert!(err.to_string().contains("'osmo1abc'"));
+
+        let err: WalletdError = CosmosError::ApiError("bad account field sequence".into()).into();
+        assert_eq!(err.code(), walletd_error::ErrorCode::NetworkError);
+    }
+
     #[test]
     fn test_new_wallet() {
         let wallet = CosmosWallet::mainnet().unwrap();
@@ -279,18 +716,28 @@ mod tests {
     #[test]
     fn test_private_key_format() {
         let wallet = CosmosWallet::mainnet().unwrap();
-        let pk = wallet.private_key();
+        let pk = wallet.private_key().unwrap();
         assert!(pk.starts_with("0x"));
         assert_eq!(pk.len(), 66);
     }
 
-    #[test]
-    fn test_sign_message() {
+    #[tokio::test]
+    async fn test_sign_message() {
         let wallet = CosmosWallet::mainnet().unwrap();
-        let sig = wallet.sign(b"Hello Cosmos!");
+        let sig = wallet.sign(b"Hello Cosmos!").await.unwrap();
         assert_eq!(sig.len(), 64);
     }
 
+    #[test]
+    fn test_from_signer() {
+        let key = [4u8; 32];
+        let wallet = CosmosWallet::from_signer(Box::new(Secp256k1Signer::from_slice(&key).unwrap()), NetworkConfig::cosmos_hub()).unwrap();
+        assert_eq!(wallet.address(), CosmosWallet::from_private_key(&key, NetworkConfig::cosmos_hub()).unwrap().address());
+
+        let ed = walletd_traits::Ed25519Signer::from_bytes(&key);
+        assert!(CosmosWallet::from_signer(Box::new(ed), NetworkConfig::cosmos_hub()).is_err());
+    }
+
     #[test]
     fn test_config() {
         let config = NetworkConfig::cosmos_hub();
@@ -317,4 +764,105 @@ mod tests {
         let wallet = CosmosWallet::mainnet().unwrap();
         assert_eq!(wallet.chain_id(), "cosmoshub-4");
     }
+
+    #[test]
+    fn test_watch_only_from_pubkey() {
+        let wallet = CosmosWallet::from_mnemonic(TEST_MNEMONIC, NetworkConfig::cosmos_hub()).unwrap();
+        let pubkey = hex::decode(wallet.public_key()).unwrap();
+        let watch = CosmosWatchOnlyWallet::from_pubkey(&pubkey, NetworkConfig::cosmos_hub()).unwrap();
+        assert_eq!(watch.address(), wallet.address());
+        assert_eq!(watch.public_key(), Some(wallet.public_key()));
+        assert_eq!(wallet.watch_only().address(), wallet.address());
+        assert!(CosmosWatchOnlyWallet::from_pubkey(&[2u8; 10], NetworkConfig::cosmos_hub()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_batch_is_one_multi_msg_tx() {
+        use tx::{AuthInfo, MsgSend, SignDoc, TxBody};
+        use walletd_traits::BatchTransferable;
+
+        let wallet = CosmosWallet::from_private_key(&[4u8; 32], NetworkConfig::cosmos_hub()).unwrap();
+        let hrp = Hrp::parse("cosmos").unwrap();
+        let transfers: Vec<(String, Amount)> = (1..=3u8)
+            .map(|i| {
+                let to = bech32::encode::<Bech32>(hrp, &[i; 20]).unwrap();
+                (to, Amount::from_smallest_unit(i as u128 * 1_000, 6))
+            })
+            .collect();
+        let account = AccountInfo {
+            account_number: 42,
+            sequence: 9,
+        };
+        let raw = wallet
+            .sign_sends(&transfers, &CosmosTxParams::default(), account)
+            .await
+            .unwrap();
+        let raw = TxRaw::decode(raw.encode_to_vec().as_slice()).unwrap();
+
+        let body = TxBody::decode(raw.body_bytes.as_slice()).unwrap();
+        assert_eq!(body.messages.len(), 3);
+        for (message, (to, amount)) in body.messages.iter().zip(&transfers) {
+            assert_eq!(message.type_url, tx::MSG_SEND_TYPE_URL);
+            let send = MsgSend::decode(message.value.as_slice()).unwrap();
+            assert_eq!(send.from_address, wallet.address());
+            assert_eq!(&send.to_address, to);
+            assert_eq!(send.amount[0].denom, "uatom");
+            assert_eq!(send.amount[0].amount, amount.smallest_unit().to_string());
+        }
+
+        let fee = AuthInfo::decode(raw.auth_info_bytes.as_slice()).unwrap().fee.unwrap();
+        assert_eq!(fee.gas_limit, 125_000);
+        assert_eq!(fee.amount[0].amount, "3125");
+
+        // The signature covers the SIGN_MODE_DIRECT document
+        let doc = SignDoc {
+            body_bytes: raw.body_bytes.clone(),
+            auth_info_bytes: raw.auth_info_bytes.clone(),
+            chain_id: "cosmoshub-4".to_string(),
+            account_number: 42,
+        };
+        let hash = Sha256::digest(doc.encode_to_vec());
+        let msg = secp256k1::Message::from_slice(&hash).unwrap();
+        let sig = secp256k1::ecdsa::Signature::from_compact(&raw.signatures[0]).unwrap();
+        let secp = secp256k1::Secp256k1::verification_only();
+        assert!(secp.verify_ecdsa(&msg, &sig, &wallet.public_key).is_ok());
+        assert!(wallet.supports_native_batch());
+    }
+
+    #[tokio::test]
+    async fn test_sign_sends_rejects_bad_input() {
+        let wallet = CosmosWallet::from_private_key(&[4u8; 32], NetworkConfig::cosmos_hub()).unwrap();
+        let account = AccountInfo {
+            account_number: 1,
+            sequence: 0,
+        };
+        let osmo = bech32::encode::<Bech32>(Hrp::parse("osmo").unwrap(), &[1; 20]).unwrap();
+        let params = CosmosTxParams::default();
+        assert!(matches!(
+            wallet.sign_sends(&[(osmo, Amount::from_smallest_unit(1, 6))], &params, account).await,
+            Err(CosmosError::InvalidAddress(_))
+        ));
+        let to = wallet.address();
+        assert!(matches!(
+            wallet.sign_sends(&[(to, Amount::from_smallest_unit(1, 18))], &params, account).await,
+            Err(CosmosError::TransactionError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_from_address() {
+        use walletd_traits::Wallet;
+
+        let address = CosmosWallet::mainnet().unwrap().address();
+        let watch = CosmosWatchOnlyWallet::from_address(&address, NetworkConfig::cosmos_hub()).unwrap();
+        assert_eq!(Wallet::address(&watch), address);
+        assert!(watch.public_key().is_none());
+        assert_eq!(watch.balance().await.unwrap().decimals, 6);
+        assert!(!watch.network().is_testnet);
+
+        let mut osmo = NetworkConfig::cosmos_hub();
+        osmo.bech32_prefix = "osmo".to_string();
+        assert!(CosmosWatchOnlyWallet::from_address(&address, osmo).is_err());
+        assert!(CosmosWatchOnlyWallet::from_address("cosmos1invalid", NetworkConfig::cosmos_hub()).is_err());
+    }
 }
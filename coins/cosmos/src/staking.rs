@@ -0,0 +1,488 @@
+//! `Stakeable` for Cosmos SDK chains
+//!
+//! Reads go through the `x/staking` and `x/distribution` REST queries;
+//! writes are `MsgDelegate`, `MsgUndelegate`, `MsgBeginRedelegate` and
+//! `MsgWithdrawDelegatorReward` transactions signed like bank sends.
+
+use serde::Deserialize;
+use walletd_traits::{
+    Amount, StakeInfo, StakeStatus, Stakeable, StakingConfig, TxHash, ValidatorInfo,
+    ValidatorStatus, WalletError, WalletResult,
+};
+
+use crate::{tx, CosmosError, CosmosWallet};
+
+const STAKING: &str = "/cosmos/staking/v1beta1";
+const DISTRIBUTION: &str = "/cosmos/distribution/v1beta1";
+
+#[derive(Deserialize)]
+struct Validator {
+    operator_address: String,
+    #[serde(default)]
+    jailed: bool,
+    status: String,
+    tokens: String,
+    description: Description,
+    commission: Commission,
+}
+
+#[derive(Deserialize)]
+struct Description {
+    #[serde(default)]
+    moniker: String,
+}
+
+#[derive(Deserialize)]
+struct Commission {
+    commission_rates: CommissionRates,
+}
+
+#[derive(Deserialize)]
+struct CommissionRates {
+    rate: String,
+}
+
+#[derive(Deserialize)]
+struct DecCoin {
+    denom: String,
+    amount: String,
+}
+
+#[derive(Deserialize)]
+struct ValidatorReward {
+    validator_address: String,
+    #[serde(default)]
+    reward: Vec<DecCoin>,
+}
+
+#[derive(Deserialize)]
+struct Rewards {
+    #[serde(default)]
+    rewards: Vec<ValidatorReward>,
+    #[serde(default)]
+    total: Vec<DecCoin>,
+}
+
+impl CosmosWallet {
+    /// Amount of `value` smallest units of the fee denom
+    fn amount(&self, value: u128) -> Amount {
+        Amount::from_smallest_unit(value, self.config.decimals)
+    }
+
+    /// Sums the fee-denom entries of `coins`, dropping the fractional part
+    /// the chain keeps for `DecCoin`s
+    fn sum_denom(&self, coins: &[DecCoin]) -> WalletResult<u128> {
+        coins
+            .iter()
+            .filter(|coin| coin.denom == self.config.denom)
+            .map(|coin| parse_int(coin.amount.split('.').next().unwrap_or_default()))
+            .sum()
+    }
+
+    fn validator_info(&self, validator: Validator) -> WalletResult<ValidatorInfo> {
+        let status = match validator.status.as_str() {
+            _ if validator.jailed => ValidatorStatus::Inactive,
+            "BOND_STATUS_BONDED" => ValidatorStatus::Active,
+            "BOND_STATUS_UNBONDING" => ValidatorStatus::Unbonding,
+            _ => ValidatorStatus::Inactive,
+        };
+        let commission = validator
+            .commission
+            .commission_rates
+            .rate
+            .parse()
+            .map_err(|_| WalletError::NetworkError("bad validator commission".to_string()))?;
+        let name = Some(validator.description.moniker).filter(|name| !name.is_empty());
+        Ok(ValidatorInfo {
+            address: validator.operator_address,
+            name,
+            commission,
+            total_stake: self.amount(parse_int(&validator.tokens)?),
+            status,
+            apy: None,
+        })
+    }
+
+    async fn rewards(&self) -> WalletResult<Rewards> {
+        let path = format!("{}/delegators/{}/rewards", DISTRIBUTION, self.address());
+        Ok(self.query(&path).await?)
+    }
+
+    /// Checks a validator address and the amount delegated to it
+    fn staking_amount(&self, validator: &str, amount: &Amount) -> WalletResult<u128> {
+        self.config.check_validator_address(validator)?;
+        let amount = self.check_amount(validator, amount)?;
+        if amount == 0 {
+            return Err(CosmosError::TransactionError("staking amount is zero".to_string()).into());
+        }
+        Ok(amount)
+    }
+}
+
+fn parse_int(value: &str) -> WalletResult<u128> {
+    value
+        .parse()
+        .map_err(|_| WalletError::NetworkError(format!("bad amount {}", value)))
+}
+
+/// Validators are operator addresses (`cosmosvaloper1...`). Unbonding
+/// delegations are reported with [`StakeStatus::Unbonding`] and count towards
+/// neither [`total_staked`](Stakeable::total_staked) nor rewards.
+#[async_trait::async_trait]
+impl Stakeable for CosmosWallet {
+    async fn staking_config(&self) -> WalletResult<StakingConfig> {
+        #[derive(Deserialize)]
+        struct Response {
+            params: Params,
+        }
+        #[derive(Deserialize)]
+        struct Params {
+            unbonding_time: String,
+            max_validators: u32,
+        }
+
+        let response: Response = self.query(&format!("{}/params", STAKING)).await?;
+        let unbonding_period_secs = response
+            .params
+            .unbonding_time
+            .strip_suffix('s')
+            .and_then(|secs| secs.split('.').next()?.parse().ok())
+            .ok_or_else(|| WalletError::NetworkError("bad unbonding time".to_string()))?;
+        Ok(StakingConfig {
+            min_stake: self.amount(1),
+            unbonding_period_secs,
+            max_validators: Some(response.params.max_validators),
+            auto_compound: false,
+        })
+    }
+
+    /// Bonded validators only
+    async fn validators(&self) -> WalletResult<Vec<ValidatorInfo>> {
+        #[derive(Deserialize)]
+        struct Response {
+            validators: Vec<Validator>,
+        }
+
+        let path = format!(
+            "{}/validators?status=BOND_STATUS_BONDED&pagination.limit=500",
+            STAKING
+        );
+        let response: Response = self.query(&path).await?;
+        response
+            .validators
+            .into_iter()
+            .map(|validator| self.validator_info(validator))
+            .collect()
+    }
+
+    async fn validator(&self, address: &str) -> WalletResult<ValidatorInfo> {
+        #[derive(Deserialize)]
+        struct Response {
+            validator: Validator,
+        }
+
+        self.config.check_validator_address(address)?;
+        let response: Response = self
+            .query(&format!("{}/validators/{}", STAKING, address))
+            .await?;
+        self.validator_info(response.validator)
+    }
+
+    async fn current_delegations(&self) -> WalletResult<Vec<StakeInfo>> {
+        #[derive(Deserialize)]
+        struct Delegations {
+            delegation_responses: Vec<DelegationResponse>,
+        }
+        #[derive(Deserialize)]
+        struct DelegationResponse {
+            delegation: Delegation,
+            balance: DecCoin,
+        }
+        #[derive(Deserialize)]
+        struct Delegation {
+            validator_address: String,
+        }
+        #[derive(Deserialize)]
+        struct Unbonding {
+            unbonding_responses: Vec<UnbondingResponse>,
+        }
+        #[derive(Deserialize)]
+        struct UnbondingResponse {
+            validator_address: String,
+            entries: Vec<UnbondingEntry>,
+        }
+        #[derive(Deserialize)]
+        struct UnbondingEntry {
+            balance: String,
+        }
+
+        let address = self.address();
+        let delegations: Delegations = self
+            .query(&format!("{}/delegations/{}", STAKING, address))
+            .await?;
+        let rewards = self.rewards().await?;
+        let unbonding: Unbonding = self
+            .query(&format!(
+                "{}/delegators/{}/unbonding_delegations",
+                STAKING, address
+            ))
+            .await?;
+
+        let mut stakes = Vec::new();
+        for response in delegations.delegation_responses {
+            let validator = response.delegation.validator_address;
+            let reward = match rewards
+                .rewards
+                .iter()
+                .find(|r| r.validator_address == validator)
+            {
+                Some(reward) => self.sum_denom(&reward.reward)?,
+                None => 0,
+            };
+            stakes.push(StakeInfo {
+                amount: self.amount(self.sum_denom(std::slice::from_ref(&response.balance))?),
+                rewards: self.amount(reward),
+                validator,
+                staked_at: None,
+                status: StakeStatus::Active,
+            });
+        }
+        for response in unbonding.unbonding_responses {
+            for entry in response.entries {
+                stakes.push(StakeInfo {
+                    validator: response.validator_address.clone(),
+                    amount: self.amount(parse_int(&entry.balance)?),
+                    rewards: self.amount(0),
+                    staked_at: None,
+                    status: StakeStatus::Unbonding,
+                });
+            }
+        }
+        Ok(stakes)
+    }
+
+    async fn total_staked(&self) -> WalletResult<Amount> {
+        let total = self
+            .current_delegations()
+            .await?
+            .iter()
+            .filter(|stake| stake.status == StakeStatus::Active)
+            .map(|stake| stake.amount.smallest_unit())
+            .sum();
+        Ok(self.amount(total))
+    }
+
+    async fn pending_rewards(&self) -> WalletResult<Amount> {
+        let rewards = self.rewards().await?;
+        Ok(self.amount(self.sum_denom(&rewards.total)?))
+    }
+
+    async fn stake(&self, validator: &str, amount: Amount) -> WalletResult<TxHash> {
+        let amount = self.staking_amount(validator, &amount)?;
+        let msg = tx::msg_delegate(&self.address(), validator, amount, &self.config.denom);
+        self.submit(vec![msg], tx::staking_gas(1)).await
+    }
+
+    async fn unstake(&self, validator: &str, amount: Amount) -> WalletResult<TxHash> {
+        let amount = self.staking_amount(validator, &amount)?;
+        let msg = tx::msg_undelegate(&self.address(), validator, amount, &self.config.denom);
+        self.submit(vec![msg], tx::staking_gas(1)).await
+    }
+
+    /// Withdraws from every validator with outstanding rewards in one
+    /// transaction
+    async fn claim_rewards(&self) -> WalletResult<TxHash> {
+        let address = self.address();
+        let messages: Vec<_> = self
+            .rewards()
+            .await?
+            .rewards
+            .iter()
+            .filter(|reward| !reward.reward.is_empty())
+            .map(|reward| tx::msg_withdraw_reward(&address, &reward.validator_address))
+            .collect();
+        if messages.is_empty() {
+            return Err(CosmosError::TransactionError("no rewards to claim".to_string()).into());
+        }
+        let gas = tx::staking_gas(messages.len());
+        self.submit(messages, gas).await
+    }
+
+    async fn redelegate(
+        &self,
+        from_validator: &str,
+        to_validator: &str,
+        amount: Amount,
+    ) -> WalletResult<TxHash> {
+        self.config.check_validator_address(to_validator)?;
+        let amount = self.staking_amount(from_validator, &amount)?;
+        let msg = tx::msg_begin_redelegate(
+            &self.address(),
+            from_validator,
+            to_validator,
+            amount,
+            &self.config.denom,
+        );
+        self.submit(vec![msg], tx::staking_gas(1)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx::{MsgBeginRedelegate, MsgDelegate, MsgWithdrawDelegatorReward, TxBody, TxRaw};
+    use crate::NetworkConfig;
+    use base64::Engine;
+    use bech32::{Bech32, Hrp};
+    use prost::Message;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn valoper(byte: u8) -> String {
+        bech32::encode::<Bech32>(Hrp::parse("cosmosvaloper").unwrap(), &[byte; 20]).unwrap()
+    }
+
+    async fn wallet_on(server: &MockServer) -> CosmosWallet {
+        let mut wallet =
+            CosmosWallet::from_private_key(&[4u8; 32], NetworkConfig::cosmos_hub()).unwrap();
+        wallet.set_api_endpoint(&server.uri());
+        let rewards = json!({
+            "rewards": [
+                {"validator_address": valoper(1), "reward": [{"denom": "uatom", "amount": "1500.750000000000000000"}]},
+                {"validator_address": valoper(2), "reward": []},
+            ],
+            "total": [{"denom": "uatom", "amount": "1500.750000000000000000"}],
+        });
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "{}/delegators/{}/rewards",
+                DISTRIBUTION,
+                wallet.address()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(rewards))
+            .mount(server)
+            .await;
+        wallet
+    }
+
+    /// Mounts the account query and a broadcast endpoint that accepts
+    /// every transaction
+    async fn mount_broadcast(server: &MockServer, wallet: &CosmosWallet) {
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "/cosmos/auth/v1beta1/accounts/{}",
+                wallet.address()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "account": {"account_number": "7", "sequence": "3"}
+            })))
+            .mount(server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/cosmos/tx/v1beta1/txs"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "tx_response": {"txhash": "ABCD", "code": 0}
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn last_broadcast(requests: &[wiremock::Request]) -> TxBody {
+        let request = requests
+            .iter()
+            .rev()
+            .find(|r| r.method.as_str() == "POST")
+            .unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&request.body).unwrap();
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(body["tx_bytes"].as_str().unwrap())
+            .unwrap();
+        let raw = TxRaw::decode(bytes.as_slice()).unwrap();
+        TxBody::decode(raw.body_bytes.as_slice()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_delegations_and_rewards() {
+        let server = MockServer::start().await;
+        let wallet = wallet_on(&server).await;
+        Mock::given(method("GET"))
+            .and(path(format!("{}/delegations/{}", STAKING, wallet.address())))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "delegation_responses": [
+                    {"delegation": {"validator_address": valoper(1)}, "balance": {"denom": "uatom", "amount": "5000000"}},
+                    {"delegation": {"validator_address": valoper(2)}, "balance": {"denom": "uatom", "amount": "2000000"}},
+                ]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path(format!(
+                "{}/delegators/{}/unbonding_delegations",
+                STAKING,
+                wallet.address()
+            )))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "unbonding_responses": [
+                    {"validator_address": valoper(3), "entries": [{"balance": "700"}]}
+                ]
+            })))
+            .mount(&server)
+            .await;
+
+        let stakes = wallet.current_delegations().await.unwrap();
+        assert_eq!(stakes.len(), 3);
+        assert_eq!(stakes[0].validator, valoper(1));
+        assert_eq!(stakes[0].rewards.smallest_unit(), 1500);
+        assert_eq!(stakes[1].rewards.smallest_unit(), 0);
+        assert_eq!(stakes[2].status, StakeStatus::Unbonding);
+        assert_eq!(
+            wallet.total_staked().await.unwrap().smallest_unit(),
+            7_000_000
+        );
+        assert_eq!(
+            wallet.pending_rewards().await.unwrap().smallest_unit(),
+            1500
+        );
+    }
+
+    #[tokio::test]
+    async fn test_staking_transactions() {
+        let server = MockServer::start().await;
+        let wallet = wallet_on(&server).await;
+        mount_broadcast(&server, &wallet).await;
+        let amount = Amount::from_smallest_unit(1_000_000, 6);
+
+        let hash = wallet.stake(&valoper(1), amount).await.unwrap();
+        assert_eq!(hash.as_str(), "ABCD");
+        let body = last_broadcast(&server.received_requests().await.unwrap());
+        assert_eq!(body.messages[0].type_url, tx::MSG_DELEGATE_TYPE_URL);
+        let delegate = MsgDelegate::decode(body.messages[0].value.as_slice()).unwrap();
+        assert_eq!(delegate.delegator_address, wallet.address());
+        assert_eq!(delegate.validator_address, valoper(1));
+        assert_eq!(delegate.amount.unwrap().amount, "1000000");
+
+        wallet
+            .redelegate(&valoper(1), &valoper(2), amount)
+            .await
+            .unwrap();
+        let body = last_broadcast(&server.received_requests().await.unwrap());
+        let redelegate = MsgBeginRedelegate::decode(body.messages[0].value.as_slice()).unwrap();
+        assert_eq!(redelegate.validator_dst_address, valoper(2));
+
+        // Only validators with outstanding rewards are withdrawn from
+        wallet.claim_rewards().await.unwrap();
+        let body = last_broadcast(&server.received_requests().await.unwrap());
+        assert_eq!(body.messages.len(), 1);
+        assert_eq!(body.messages[0].type_url, tx::MSG_WITHDRAW_REWARD_TYPE_URL);
+        let withdraw =
+            MsgWithdrawDelegatorReward::decode(body.messages[0].value.as_slice()).unwrap();
+        assert_eq!(withdraw.validator_address, valoper(1));
+
+        // Account addresses are not validators
+        assert!(matches!(
+            wallet.stake(&wallet.address(), amount).await,
+            Err(WalletError::InvalidAddress(_))
+        ));
+    }
+}
@@ -0,0 +1,386 @@
+//! Cosmos SDK transactions signed with SIGN_MODE_DIRECT
+//!
+//! Only the protobuf messages for bank sends and staking are defined here.
+//! A transaction may carry several messages, which is how one transaction
+//! pays many recipients or claims rewards from many validators.
+
+use prost::Message;
+
+/// `/cosmos.bank.v1beta1.MsgSend`
+pub const MSG_SEND_TYPE_URL: &str = "/cosmos.bank.v1beta1.MsgSend";
+/// `/cosmos.crypto.secp256k1.PubKey`
+pub const SECP256K1_PUBKEY_TYPE_URL: &str = "/cosmos.crypto.secp256k1.PubKey";
+
+/// `/cosmos.staking.v1beta1.MsgDelegate`
+pub const MSG_DELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgDelegate";
+/// `/cosmos.staking.v1beta1.MsgUndelegate`
+pub const MSG_UNDELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgUndelegate";
+/// `/cosmos.staking.v1beta1.MsgBeginRedelegate`
+pub const MSG_BEGIN_REDELEGATE_TYPE_URL: &str = "/cosmos.staking.v1beta1.MsgBeginRedelegate";
+/// `/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward`
+pub const MSG_WITHDRAW_REWARD_TYPE_URL: &str =
+    "/cosmos.distribution.v1beta1.MsgWithdrawDelegatorReward";
+
+const SIGN_MODE_DIRECT: i32 = 1;
+
+/// Gas of a transaction before its messages
+pub const GAS_PER_TX: u64 = 65_000;
+/// Gas added per `MsgSend`
+pub const GAS_PER_SEND: u64 = 20_000;
+/// Gas added per staking or reward withdrawal message
+pub const GAS_PER_STAKING_MSG: u64 = 200_000;
+
+/// Amount of one denomination
+#[derive(Clone, PartialEq, Message)]
+pub struct Coin {
+    /// Denomination, e.g. `uatom`
+    #[prost(string, tag = "1")]
+    pub denom: String,
+    /// Amount as a decimal string
+    #[prost(string, tag = "2")]
+    pub amount: String,
+}
+
+/// Bank transfer from one account to another
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgSend {
+    /// Sender address
+    #[prost(string, tag = "1")]
+    pub from_address: String,
+    /// Recipient address
+    #[prost(string, tag = "2")]
+    pub to_address: String,
+    /// Coins sent
+    #[prost(message, repeated, tag = "3")]
+    pub amount: Vec<Coin>,
+}
+
+/// Delegation to, or undelegation from, a validator
+///
+/// `MsgDelegate` and `MsgUndelegate` share this layout.
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgDelegate {
+    /// Delegator account address
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    /// Validator operator address
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+    /// Amount delegated or undelegated
+    #[prost(message, optional, tag = "3")]
+    pub amount: Option<Coin>,
+}
+
+/// Moving a delegation between validators without unbonding
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgBeginRedelegate {
+    /// Delegator account address
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    /// Validator the stake leaves
+    #[prost(string, tag = "2")]
+    pub validator_src_address: String,
+    /// Validator the stake moves to
+    #[prost(string, tag = "3")]
+    pub validator_dst_address: String,
+    /// Amount moved
+    #[prost(message, optional, tag = "4")]
+    pub amount: Option<Coin>,
+}
+
+/// Withdrawal of the rewards earned with one validator
+#[derive(Clone, PartialEq, Message)]
+pub struct MsgWithdrawDelegatorReward {
+    /// Delegator account address
+    #[prost(string, tag = "1")]
+    pub delegator_address: String,
+    /// Validator operator address
+    #[prost(string, tag = "2")]
+    pub validator_address: String,
+}
+
+/// `google.protobuf.Any`
+#[derive(Clone, PartialEq, Message)]
+pub struct Any {
+    /// Message type
+    #[prost(string, tag = "1")]
+    pub type_url: String,
+    /// Encoded message
+    #[prost(bytes = "vec", tag = "2")]
+    pub value: Vec<u8>,
+}
+
+/// Messages and memo of a transaction
+#[derive(Clone, PartialEq, Message)]
+pub struct TxBody {
+    /// Messages, executed in order and atomically
+    #[prost(message, repeated, tag = "1")]
+    pub messages: Vec<Any>,
+    /// Memo
+    #[prost(string, tag = "2")]
+    pub memo: String,
+    /// Height after which the transaction is invalid, or 0
+    #[prost(uint64, tag = "3")]
+    pub timeout_height: u64,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct PubKey {
+    #[prost(bytes = "vec", tag = "1")]
+    key: Vec<u8>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct Single {
+    #[prost(int32, tag = "1")]
+    mode: i32,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct ModeInfo {
+    #[prost(message, optional, tag = "1")]
+    single: Option<Single>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+struct SignerInfo {
+    #[prost(message, optional, tag = "1")]
+    public_key: Option<Any>,
+    #[prost(message, optional, tag = "2")]
+    mode_info: Option<ModeInfo>,
+    #[prost(uint64, tag = "3")]
+    sequence: u64,
+}
+
+/// Fee and gas limit of a transaction
+#[derive(Clone, PartialEq, Message)]
+pub struct Fee {
+    /// Fee paid
+    #[prost(message, repeated, tag = "1")]
+    pub amount: Vec<Coin>,
+    /// Gas limit
+    #[prost(uint64, tag = "2")]
+    pub gas_limit: u64,
+}
+
+/// Signer and fee of a transaction
+#[derive(Clone, PartialEq, Message)]
+pub struct AuthInfo {
+    #[prost(message, repeated, tag = "1")]
+    signer_infos: Vec<SignerInfo>,
+    /// Fee and gas limit
+    #[prost(message, optional, tag = "2")]
+    pub fee: Option<Fee>,
+}
+
+/// What a SIGN_MODE_DIRECT signer signs: the SHA-256 of its encoding
+#[derive(Clone, PartialEq, Message)]
+pub struct SignDoc {
+    /// Encoded [`TxBody`]
+    #[prost(bytes = "vec", tag = "1")]
+    pub body_bytes: Vec<u8>,
+    /// Encoded [`AuthInfo`]
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_info_bytes: Vec<u8>,
+    /// Chain the transaction is valid on
+    #[prost(string, tag = "3")]
+    pub chain_id: String,
+    /// On-chain number of the signing account
+    #[prost(uint64, tag = "4")]
+    pub account_number: u64,
+}
+
+/// A signed transaction as broadcast
+#[derive(Clone, PartialEq, Message)]
+pub struct TxRaw {
+    /// Encoded [`TxBody`]
+    #[prost(bytes = "vec", tag = "1")]
+    pub body_bytes: Vec<u8>,
+    /// Encoded [`AuthInfo`]
+    #[prost(bytes = "vec", tag = "2")]
+    pub auth_info_bytes: Vec<u8>,
+    /// One 64-byte `r || s` signature per signer
+    #[prost(bytes = "vec", repeated, tag = "3")]
+    pub signatures: Vec<Vec<u8>>,
+}
+
+/// On-chain state of the signing account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AccountInfo {
+    /// Account number
+    pub account_number: u64,
+    /// Next sequence number
+    pub sequence: u64,
+}
+
+/// A `MsgSend` of `amount` `denom` wrapped in `Any`
+pub fn msg_send(from: &str, to: &str, amount: u128, denom: &str) -> Any {
+    let msg = MsgSend {
+        from_address: from.to_string(),
+        to_address: to.to_string(),
+        amount: vec![coin(amount, denom)],
+    };
+    any(MSG_SEND_TYPE_URL, &msg)
+}
+
+/// A `MsgDelegate` of `amount` `denom` wrapped in `Any`
+pub fn msg_delegate(delegator: &str, validator: &str, amount: u128, denom: &str) -> Any {
+    any(
+        MSG_DELEGATE_TYPE_URL,
+        &delegation(delegator, validator, amount, denom),
+    )
+}
+
+/// A `MsgUndelegate` of `amount` `denom` wrapped in `Any`
+pub fn msg_undelegate(delegator: &str, validator: &str, amount: u128, denom: &str) -> Any {
+    any(
+        MSG_UNDELEGATE_TYPE_URL,
+        &delegation(delegator, validator, amount, denom),
+    )
+}
+
+/// A `MsgBeginRedelegate` of `amount` `denom` wrapped in `Any`
+pub fn msg_begin_redelegate(
+    delegator: &str,
+    from_validator: &str,
+    to_validator: &str,
+    amount: u128,
+    denom: &str,
+) -> Any {
+    let msg = MsgBeginRedelegate {
+        delegator_address: delegator.to_string(),
+        validator_src_address: from_validator.to_string(),
+        validator_dst_address: to_validator.to_string(),
+        amount: Some(coin(amount, denom)),
+    };
+    any(MSG_BEGIN_REDELEGATE_TYPE_URL, &msg)
+}
+
+/// A `MsgWithdrawDelegatorReward` wrapped in `Any`
+pub fn msg_withdraw_reward(delegator: &str, validator: &str) -> Any {
+    let msg = MsgWithdrawDelegatorReward {
+        delegator_address: delegator.to_string(),
+        validator_address: validator.to_string(),
+    };
+    any(MSG_WITHDRAW_REWARD_TYPE_URL, &msg)
+}
+
+fn delegation(delegator: &str, validator: &str, amount: u128, denom: &str) -> MsgDelegate {
+    MsgDelegate {
+        delegator_address: delegator.to_string(),
+        validator_address: validator.to_string(),
+        amount: Some(coin(amount, denom)),
+    }
+}
+
+fn coin(amount: u128, denom: &str) -> Coin {
+    Coin {
+        denom: denom.to_string(),
+        amount: amount.to_string(),
+    }
+}
+
+fn any(type_url: &str, msg: &impl Message) -> Any {
+    Any {
+        type_url: type_url.to_string(),
+        value: msg.encode_to_vec(),
+    }
+}
+
+/// Gas limit for a transaction with `sends` bank sends
+pub fn send_gas(sends: usize) -> u64 {
+    GAS_PER_TX + GAS_PER_SEND * sends as u64
+}
+
+/// Gas limit for a transaction with `messages` staking or reward messages
+pub fn staking_gas(messages: usize) -> u64 {
+    GAS_PER_TX + GAS_PER_STAKING_MSG * messages as u64
+}
+
+/// The document to sign for `messages`, paid by a single secp256k1 signer
+pub fn sign_doc(
+    messages: Vec<Any>,
+    memo: &str,
+    public_key: &[u8],
+    fee: Fee,
+    chain_id: &str,
+    account: AccountInfo,
+) -> SignDoc {
+    let body = TxBody {
+        messages,
+        memo: memo.to_string(),
+        timeout_height: 0,
+    };
+    let auth_info = AuthInfo {
+        signer_infos: vec![SignerInfo {
+            public_key: Some(Any {
+                type_url: SECP256K1_PUBKEY_TYPE_URL.to_string(),
+                value: PubKey {
+                    key: public_key.to_vec(),
+                }
+                .encode_to_vec(),
+            }),
+            mode_info: Some(ModeInfo {
+                single: Some(Single {
+                    mode: SIGN_MODE_DIRECT,
+                }),
+            }),
+            sequence: account.sequence,
+        }],
+        fee: Some(fee),
+    };
+    SignDoc {
+        body_bytes: body.encode_to_vec(),
+        auth_info_bytes: auth_info.encode_to_vec(),
+        chain_id: chain_id.to_string(),
+        account_number: account.account_number,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sign_doc_round_trip() {
+        let messages = vec![
+            msg_send("cosmos1from", "cosmos1a", 10, "uatom"),
+            msg_send("cosmos1from", "cosmos1b", 20, "uatom"),
+        ];
+        let fee = Fee {
+            amount: vec![Coin {
+                denom: "uatom".to_string(),
+                amount: "2625".to_string(),
+            }],
+            gas_limit: send_gas(2),
+        };
+        let account = AccountInfo {
+            account_number: 7,
+            sequence: 3,
+        };
+        let doc = sign_doc(
+            messages,
+            "payroll",
+            &[2u8; 33],
+            fee.clone(),
+            "cosmoshub-4",
+            account,
+        );
+        assert_eq!(
+            SignDoc::decode(doc.encode_to_vec().as_slice()).unwrap(),
+            doc
+        );
+
+        let body = TxBody::decode(doc.body_bytes.as_slice()).unwrap();
+        assert_eq!(body.memo, "payroll");
+        assert_eq!(body.messages.len(), 2);
+        let second = MsgSend::decode(body.messages[1].value.as_slice()).unwrap();
+        assert_eq!(second.to_address, "cosmos1b");
+        assert_eq!(second.amount[0].amount, "20");
+
+        let auth = AuthInfo::decode(doc.auth_info_bytes.as_slice()).unwrap();
+        assert_eq!(auth.fee, Some(fee));
+        assert_eq!(auth.signer_infos[0].sequence, 3);
+        assert_eq!(send_gas(2), 105_000);
+    }
+}
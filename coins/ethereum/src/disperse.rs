@@ -0,0 +1,99 @@
+//! Paying many recipients in one transaction through the Disperse contract
+//!
+//! Disperse (disperse.app) is deployed at [`DISPERSE_ADDRESS`] on the chains
+//! in [`DISPERSE_CHAIN_IDS`]. Elsewhere the address may be an EOA or empty,
+//! and ether sent there is lost.
+
+use alloy::network::TransactionBuilder;
+use alloy::primitives::{address, Address, U256};
+use alloy::rpc::types::TransactionRequest;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+
+sol! {
+    /// The Disperse contract
+    contract Disperse {
+        function disperseEther(address[] recipients, uint256[] values) external payable;
+        function disperseToken(address token, address[] recipients, uint256[] values) external;
+    }
+}
+
+/// Disperse contract address
+pub const DISPERSE_ADDRESS: Address = address!("D152f549545093347A162Dce210e7293f1452150");
+
+/// Chains where Disperse is deployed at [`DISPERSE_ADDRESS`]
+pub const DISPERSE_CHAIN_IDS: &[u64] = &[1, 10, 137, 8453, 42161];
+
+/// Returns true if Disperse is deployed on `chain_id`
+pub fn is_supported_chain(chain_id: u64) -> bool {
+    DISPERSE_CHAIN_IDS.contains(&chain_id)
+}
+
+/// A `disperseEther` call paying every recipient, carrying the total as its
+/// value
+pub fn disperse_ether(recipients: &[(Address, U256)]) -> TransactionRequest {
+    let (addresses, values): (Vec<_>, Vec<_>) = recipients.iter().copied().unzip();
+    let total = values.iter().fold(U256::ZERO, |sum, value| sum + value);
+    let call = Disperse::disperseEtherCall {
+        recipients: addresses,
+        values,
+    };
+    TransactionRequest::default()
+        .with_to(DISPERSE_ADDRESS)
+        .with_value(total)
+        .with_input(call.abi_encode())
+}
+
+/// A `disperseToken` call paying every recipient in the ERC-20 `token`
+///
+/// The sender must first approve the contract for the total.
+pub fn disperse_token(token: Address, recipients: &[(Address, U256)]) -> TransactionRequest {
+    let (addresses, values): (Vec<_>, Vec<_>) = recipients.iter().copied().unzip();
+    let call = Disperse::disperseTokenCall {
+        token,
+        recipients: addresses,
+        values,
+    };
+    TransactionRequest::default()
+        .with_to(DISPERSE_ADDRESS)
+        .with_input(call.abi_encode())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn recipients() -> Vec<(Address, U256)> {
+        vec![
+            (Address::repeat_byte(0x11), U256::from(5)),
+            (Address::repeat_byte(0x22), U256::from(7)),
+        ]
+    }
+
+    #[test]
+    fn test_disperse_ether_calldata() {
+        let tx = disperse_ether(&recipients());
+        assert_eq!(tx.to, Some(DISPERSE_ADDRESS.into()));
+        assert_eq!(tx.value, Some(U256::from(12)));
+
+        let input = tx.input.input().unwrap();
+        assert_eq!(input[..4], [0xe6, 0x3d, 0x38, 0xed]);
+        // selector, two offsets, then each array as length and items
+        assert_eq!(input.len(), 4 + 32 * 8);
+        let call = Disperse::disperseEtherCall::abi_decode(input).unwrap();
+        assert_eq!(call.recipients[1], Address::repeat_byte(0x22));
+        assert_eq!(call.values, [U256::from(5), U256::from(7)]);
+    }
+
+    #[test]
+    fn test_disperse_token_calldata() {
+        let token = Address::repeat_byte(0x33);
+        let tx = disperse_token(token, &recipients());
+        assert_eq!(tx.value, None);
+        let input = tx.input.input().unwrap();
+        assert_eq!(input[..4], [0xc7, 0x3a, 0x2d, 0x60]);
+        let call = Disperse::disperseTokenCall::abi_decode(input).unwrap();
+        assert_eq!(call.token, token);
+        assert_eq!(call.recipients.len(), 2);
+    }
+}
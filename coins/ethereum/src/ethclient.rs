@@ -49,6 +49,17 @@ impl EthClient {
         Ok(EthereumAmount { wei: balance })
     }
 
+    /// Returns the code deployed at an address, empty for an EOA.
+    pub async fn code(rpc_url: &str, address: Address) -> Result<alloy::primitives::Bytes, Error> {
+        let provider = ProviderBuilder::new()
+            .connect_http(rpc_url.parse().map_err(|e| Error::Custom(format!("Invalid URL: {e}")))?);
+        let code = provider
+            .get_code_at(address)
+            .await
+            .map_err(|e| Error::Custom(format!("Failed to get code: {e}")))?;
+        Ok(code)
+    }
+
     /// Gets a transaction given a specific tx hash.
     ///
     /// Returns an error[Error] if the transaction is not found.
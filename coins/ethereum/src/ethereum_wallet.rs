@@ -6,18 +6,18 @@ use crate::Error;
 use crate::EthClient;
 use crate::{EthereumAmount, EthereumFormat};
 
+use alloy::network::TransactionBuilder;
 use alloy::primitives::Address;
 use alloy::providers::{Provider, ProviderBuilder};
-use alloy::network::TransactionBuilder;
 use alloy::rpc::types::TransactionRequest;
 use alloy::signers::local::PrivateKeySigner;
 
-use bdk::bitcoin::secp256k1::ffi::types::AlignedType;
-use bdk::bitcoin::secp256k1::PublicKey;
-use bdk::bitcoin::secp256k1::Secp256k1;
 use bdk::bitcoin::bip32::DerivationPath;
 use bdk::bitcoin::bip32::ExtendedPrivKey;
 use bdk::bitcoin::bip32::ExtendedPubKey;
+use bdk::bitcoin::secp256k1::ffi::types::AlignedType;
+use bdk::bitcoin::secp256k1::PublicKey;
+use bdk::bitcoin::secp256k1::Secp256k1;
 use bdk::keys::bip39::Mnemonic;
 use bdk::keys::{DerivableKey, ExtendedKey};
 use tiny_keccak::{Hasher, Keccak};
@@ -187,8 +187,8 @@ impl EthereumWallet {
 
     /// Returns the balance for this Ethereum Wallet.
     pub async fn balance(&self, rpc_url: &str) -> Result<EthereumAmount, Error> {
-        let address = Address::from_str(&self.public_address())
-            .map_err(|e| Error::FromStr(e.to_string()))?;
+        let address =
+            Address::from_str(&self.public_address()).map_err(|e| Error::FromStr(e.to_string()))?;
         let balance = EthClient::balance(rpc_url, address).await?;
         Ok(balance)
     }
@@ -200,8 +200,55 @@ impl EthereumWallet {
         send_amount: EthereumAmount,
         to_address: &str,
     ) -> Result<String, Error> {
-        let private_key = self.private_key
-            .ok_or(Error::MissingPrivateKey)?;
+        // Parse destination address
+        let to = Address::from_str(to_address).map_err(|e| Error::FromStr(e.to_string()))?;
+
+        // Build transaction request
+        // 21000 = gas limit for basic ETH transfer
+        let tx = TransactionRequest::default()
+            .with_to(to)
+            .with_value(send_amount.wei())
+            .with_gas_limit(21000);
+        self.send_transaction(rpc_url, tx).await
+    }
+
+    /// Pays every recipient in one call to the [Disperse](crate::disperse) contract.
+    ///
+    /// Fails without sending anything unless the chain is in
+    /// [`DISPERSE_CHAIN_IDS`](crate::disperse::DISPERSE_CHAIN_IDS) and the
+    /// node reports code at the contract address.
+    pub async fn disperse(
+        &self,
+        rpc_url: &str,
+        recipients: &[(Address, alloy::primitives::U256)],
+    ) -> Result<String, Error> {
+        let chain_id = self.chain_id();
+        if !crate::disperse::is_supported_chain(chain_id) {
+            return Err(Error::Custom(format!(
+                "Disperse is not deployed on chain {chain_id}"
+            )));
+        }
+        if EthClient::code(rpc_url, crate::disperse::DISPERSE_ADDRESS)
+            .await?
+            .is_empty()
+        {
+            return Err(Error::Custom(format!(
+                "no contract at the Disperse address on chain {chain_id}"
+            )));
+        }
+        self.send_transaction(rpc_url, crate::disperse::disperse_ether(recipients))
+            .await
+    }
+
+    /// Signs and broadcasts a transaction on this wallet's chain, then waits for its receipt.
+    ///
+    /// Fields left unset (nonce, gas, fees) are filled in from the node.
+    pub(crate) async fn send_transaction(
+        &self,
+        rpc_url: &str,
+        tx: TransactionRequest,
+    ) -> Result<String, Error> {
+        let private_key = self.private_key.ok_or(Error::MissingPrivateKey)?;
         let private_key_bytes = private_key.private_key.secret_bytes();
 
         // Create signer from private key bytes
@@ -211,19 +258,13 @@ impl EthereumWallet {
         // Create provider with signer
         let provider = ProviderBuilder::new()
             .wallet(alloy::network::EthereumWallet::from(signer))
-            .connect_http(rpc_url.parse().map_err(|e| Error::Custom(format!("Invalid URL: {e}")))?);
+            .connect_http(
+                rpc_url
+                    .parse()
+                    .map_err(|e| Error::Custom(format!("Invalid URL: {e}")))?,
+            );
 
-        // Parse destination address
-        let to = Address::from_str(to_address)
-            .map_err(|e| Error::FromStr(e.to_string()))?;
-
-        // Build transaction request
-        // 21000 = gas limit for basic ETH transfer
-        let tx = TransactionRequest::default()
-            .with_to(to)
-            .with_value(send_amount.wei())
-            .with_gas_limit(21000)
-            .with_chain_id(self.chain_id);
+        let tx = tx.with_chain_id(self.chain_id);
 
         // Send transaction
         let pending_tx = provider
@@ -340,7 +381,7 @@ mod tests {
     #[test]
     fn test_wallet_chain_id() {
         let mnemonic = Mnemonic::parse(TEST_MNEMONIC).unwrap();
-        
+
         // Default chain ID is 1 (mainnet)
         let wallet = EthereumWallet::builder()
             .mnemonic(mnemonic.clone())
@@ -396,9 +437,8 @@ mod tests {
     #[test]
     fn test_different_mnemonics_different_addresses() {
         let mnemonic1 = Mnemonic::parse(TEST_MNEMONIC).unwrap();
-        let mnemonic2 = Mnemonic::parse(
-            "zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong"
-        ).unwrap();
+        let mnemonic2 =
+            Mnemonic::parse("zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo zoo wrong").unwrap();
 
         let wallet1 = EthereumWallet::builder()
             .mnemonic(mnemonic1)
@@ -0,0 +1,232 @@
+//! Wallet notifications over an `eth_subscribe` WebSocket
+//!
+//! Each `newHeads` notification triggers a look at the new block: value
+//! transfers paying the wallet become [`WalletEvent::IncomingTransaction`],
+//! the wallet's own transactions are reported confirmed or failed from their
+//! receipts, and a balance change at that block becomes
+//! [`WalletEvent::BalanceChanged`].
+
+use async_trait::async_trait;
+use serde_json::Value;
+use std::sync::Arc;
+use walletd_provider::{ProviderError, WsClient};
+use walletd_traits::{
+    Amount, EventStream, TxHash, Wallet, WalletError, WalletEvent, WalletEvents, WalletResult,
+};
+
+use crate::ConnectedEthereumWallet;
+
+const ETH_DECIMALS: u8 = 18;
+
+impl ConnectedEthereumWallet {
+    /// Pushes [`WalletEvents`] notifications over `ws`, a WebSocket
+    /// connection to the same chain
+    pub fn with_websocket(mut self, ws: Arc<WsClient>) -> Self {
+        self.ws = Some(ws);
+        self
+    }
+}
+
+fn network_error(e: ProviderError) -> WalletError {
+    WalletError::NetworkError(e.to_string())
+}
+
+fn hex_u128(value: &Value) -> WalletResult<u128> {
+    value
+        .as_str()
+        .and_then(|hex| u128::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+        .ok_or_else(|| WalletError::NetworkError(format!("bad quantity {}", value)))
+}
+
+fn same_address(value: &Value, address: &str) -> bool {
+    value
+        .as_str()
+        .is_some_and(|value| value.eq_ignore_ascii_case(address))
+}
+
+async fn balance_at(ws: &WsClient, address: &str, block: &str) -> WalletResult<Amount> {
+    let balance: Value = ws
+        .rpc_call("eth_getBalance", [address, block])
+        .await
+        .map_err(network_error)?;
+    Ok(Amount::from_smallest_unit(
+        hex_u128(&balance)?,
+        ETH_DECIMALS,
+    ))
+}
+
+/// Transaction events for `address` in block `number`
+async fn block_events(
+    ws: &WsClient,
+    address: &str,
+    number: &str,
+) -> WalletResult<Vec<WalletEvent>> {
+    let block: Value = ws
+        .rpc_call("eth_getBlockByNumber", (number, true))
+        .await
+        .map_err(network_error)?;
+    let height = u64::try_from(hex_u128(&Value::from(number))?).ok();
+    let mut events = Vec::new();
+    for tx in block["transactions"].as_array().into_iter().flatten() {
+        let hash = TxHash::new(tx["hash"].as_str().unwrap_or_default());
+        if same_address(&tx["from"], address) {
+            let receipt: Value = ws
+                .rpc_call("eth_getTransactionReceipt", [hash.as_str()])
+                .await
+                .map_err(network_error)?;
+            events.push(if receipt["status"] == "0x1" {
+                WalletEvent::TransactionConfirmed {
+                    hash,
+                    block_height: height,
+                }
+            } else {
+                WalletEvent::TransactionFailed {
+                    hash,
+                    reason: Some("reverted".to_string()),
+                }
+            });
+        } else if same_address(&tx["to"], address) {
+            events.push(WalletEvent::IncomingTransaction {
+                hash,
+                from: tx["from"].as_str().map(str::to_string),
+                amount: Amount::from_smallest_unit(hex_u128(&tx["value"])?, ETH_DECIMALS),
+            });
+        }
+    }
+    Ok(events)
+}
+
+/// Needs a WebSocket set with
+/// [`with_websocket`](ConnectedEthereumWallet::with_websocket). Token
+/// transfers and internal transactions are not reported.
+#[async_trait]
+impl WalletEvents for ConnectedEthereumWallet {
+    async fn subscribe(&self) -> WalletResult<EventStream> {
+        let ws = self.ws.clone().ok_or_else(|| {
+            WalletError::NotSupported("no WebSocket connection; see with_websocket".to_string())
+        })?;
+        let address = self.address();
+        let mut heads = ws
+            .subscribe::<_, Value>(["newHeads"])
+            .await
+            .map_err(network_error)?;
+        let mut last = balance_at(&ws, &address, "latest").await?;
+
+        Ok(Box::pin(async_stream::stream! {
+            while let Some(head) = heads.next().await {
+                let number = match head {
+                    Ok(head) => head["number"].as_str().unwrap_or_default().to_string(),
+                    Err(e) => {
+                        yield Err(network_error(e));
+                        continue;
+                    }
+                };
+                match block_events(&ws, &address, &number).await {
+                    Ok(events) => {
+                        for event in events {
+                            yield Ok(event);
+                        }
+                    }
+                    Err(e) => yield Err(e),
+                }
+                match balance_at(&ws, &address, &number).await {
+                    Ok(new) if new != last => {
+                        yield Ok(WalletEvent::BalanceChanged { address: address.clone(), old: last, new });
+                        last = new;
+                    }
+                    Ok(_) => {}
+                    Err(e) => yield Err(e),
+                }
+            }
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures_util::{SinkExt, StreamExt};
+    use serde_json::json;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::tungstenite::Message;
+    use walletd_provider::WsConfig;
+
+    const OTHER: &str = "0x00000000000000000000000000000000000000bb";
+
+    /// Node with one new block at 0x10 holding a 5 wei payment to `me` and a
+    /// reverted transaction from `me`
+    async fn spawn_node(me: String) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                let request: Value = serde_json::from_str(&text).unwrap();
+                let params = &request["params"];
+                let result = match request["method"].as_str().unwrap() {
+                    "eth_subscribe" => json!("0xsub"),
+                    "eth_getBalance" if params[1] == "latest" => json!("0x0"),
+                    "eth_getBalance" => json!("0x5"),
+                    "eth_getBlockByNumber" => json!({"number": "0x10", "transactions": [
+                        {"hash": "0x01", "from": OTHER, "to": me, "value": "0x5"},
+                        {"hash": "0x02", "from": me, "to": OTHER, "value": "0x0"},
+                        {"hash": "0x03", "from": OTHER, "to": OTHER, "value": "0x9"},
+                    ]}),
+                    "eth_getTransactionReceipt" => json!({"status": "0x0"}),
+                    _ => Value::Null,
+                };
+                let reply = json!({"jsonrpc": "2.0", "id": request["id"], "result": result});
+                ws.send(Message::Text(reply.to_string())).await.unwrap();
+                // The block is announced once the balance baseline is read
+                if params[1] == "latest" {
+                    let head = json!({
+                        "jsonrpc": "2.0",
+                        "method": "eth_subscription",
+                        "params": {"subscription": "0xsub", "result": {"number": "0x10"}},
+                    });
+                    ws.send(Message::Text(head.to_string())).await.unwrap();
+                }
+            }
+        });
+        format!("ws://{}", addr)
+    }
+
+    fn wallet() -> ConnectedEthereumWallet {
+        let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        ConnectedEthereumWallet::from_phrase(phrase, 1, "http://127.0.0.1:1").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_block_events() {
+        let wallet = wallet();
+        let url = spawn_node(wallet.address().to_lowercase()).await;
+        let ws = Arc::new(WsClient::connect(WsConfig::new(url)).await.unwrap());
+        let wallet = wallet.with_websocket(ws);
+
+        let events: Vec<_> = wallet.subscribe().await.unwrap().take(3).collect().await;
+        let events: Vec<_> = events.into_iter().map(Result::unwrap).collect();
+        assert_eq!(
+            events[0],
+            WalletEvent::IncomingTransaction {
+                hash: TxHash::new("0x01"),
+                from: Some(OTHER.to_string()),
+                amount: Amount::from_smallest_unit(5, 18),
+            }
+        );
+        assert!(
+            matches!(&events[1], WalletEvent::TransactionFailed { hash, .. } if hash.as_str() == "0x02")
+        );
+        assert!(
+            matches!(&events[2], WalletEvent::BalanceChanged { new, .. } if new.smallest_unit() == 5)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_subscribe_needs_websocket() {
+        assert!(matches!(
+            wallet().subscribe().await,
+            Err(WalletError::NotSupported(_))
+        ));
+    }
+}
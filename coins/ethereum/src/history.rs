@@ -0,0 +1,346 @@
+//! Transaction history from Etherscan-style explorer APIs
+//!
+//! Uses `module=account&action=txlist`. Etherscan's v2 API serves every
+//! chain it indexes from one URL keyed by `chainid`; Blockscout and other
+//! explorers expose the same call, so point [`Etherscan::with_base_url`] at
+//! any of them.
+
+use alloy::primitives::Address;
+use async_trait::async_trait;
+use serde::Deserialize;
+use walletd_traits::{
+    Amount, HistoryPage, HistoryProvider, TransactionRecord, TransactionStatus, TxDirection,
+    TxHash, WalletError, WalletResult,
+};
+
+/// Etherscan's multichain v2 API
+pub const ETHERSCAN_URL: &str = "https://api.etherscan.io/v2/api";
+
+/// Most records Etherscan returns per call
+pub const MAX_RECORDS_PER_PAGE: usize = 10_000;
+
+const ETH_DECIMALS: u8 = 18;
+
+/// Normal (external) transactions of an address from an Etherscan-style API
+///
+/// Token transfers and internal transactions are not included.
+#[derive(Debug, Clone)]
+pub struct Etherscan {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+    chain_id: u64,
+}
+
+impl Etherscan {
+    /// History of `chain_id` from Etherscan
+    pub fn new(api_key: &str, chain_id: u64) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: ETHERSCAN_URL.to_string(),
+            api_key: api_key.to_string(),
+            chain_id,
+        }
+    }
+
+    /// Uses another explorer, e.g. `https://eth.blockscout.com/api`
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Fetches up to `count` transactions in blocks up to `end_block`, newest
+    /// first
+    async fn txlist(
+        &self,
+        address: &str,
+        end_block: Option<u64>,
+        count: usize,
+    ) -> WalletResult<Vec<ExplorerTx>> {
+        #[derive(Deserialize)]
+        struct Response {
+            status: String,
+            message: String,
+            result: serde_json::Value,
+        }
+
+        let mut query = vec![
+            ("chainid", self.chain_id.to_string()),
+            ("module", "account".to_string()),
+            ("action", "txlist".to_string()),
+            ("address", address.to_string()),
+            ("page", "1".to_string()),
+            ("offset", count.to_string()),
+            ("sort", "desc".to_string()),
+            ("apikey", self.api_key.clone()),
+        ];
+        if let Some(end_block) = end_block {
+            query.push(("endblock", end_block.to_string()));
+        }
+        let response: Response = self
+            .client
+            .get(&self.base_url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?
+            .error_for_status()
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?
+            .json()
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+
+        // An address without transactions is reported as a failure
+        if response.status != "1" {
+            return match response.result {
+                serde_json::Value::Array(list) if list.is_empty() => Ok(Vec::new()),
+                result => Err(WalletError::NetworkError(format!(
+                    "{}: {}",
+                    response.message, result
+                ))),
+            };
+        }
+        serde_json::from_value(response.result)
+            .map_err(|e| WalletError::NetworkError(e.to_string()))
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ExplorerTx {
+    block_number: String,
+    time_stamp: String,
+    hash: String,
+    from: String,
+    #[serde(default)]
+    to: String,
+    #[serde(default)]
+    contract_address: String,
+    value: String,
+    gas_price: String,
+    gas_used: String,
+    is_error: String,
+}
+
+impl ExplorerTx {
+    fn block(&self) -> WalletResult<u64> {
+        parse(&self.block_number)
+    }
+
+    fn record(&self, address: &str) -> WalletResult<TransactionRecord> {
+        // Contract creations have no `to`
+        let to = if self.to.is_empty() {
+            &self.contract_address
+        } else {
+            &self.to
+        };
+        let sent = self.from.eq_ignore_ascii_case(address);
+        let (direction, counterparty) = match (sent, to.eq_ignore_ascii_case(address)) {
+            (true, true) => (TxDirection::SelfTransfer, None),
+            (true, false) => (TxDirection::Outgoing, Some(to.clone())),
+            (false, _) => (TxDirection::Incoming, Some(self.from.clone())),
+        };
+        let fee = if sent {
+            let fee = parse::<u128>(&self.gas_used)? * parse::<u128>(&self.gas_price)?;
+            Some(Amount::from_smallest_unit(fee, ETH_DECIMALS))
+        } else {
+            None
+        };
+        let status = if self.is_error == "1" {
+            TransactionStatus::Failed
+        } else {
+            TransactionStatus::Confirmed
+        };
+        Ok(TransactionRecord {
+            hash: TxHash::new(&self.hash),
+            direction,
+            counterparty,
+            amount: Amount::from_smallest_unit(parse(&self.value)?, ETH_DECIMALS),
+            fee,
+            status,
+            timestamp: Some(parse(&self.time_stamp)?),
+            block_height: Some(self.block()?),
+        })
+    }
+}
+
+fn parse<T: std::str::FromStr>(value: &str) -> WalletResult<T> {
+    value
+        .parse()
+        .map_err(|_| WalletError::NetworkError(format!("bad explorer field {}", value)))
+}
+
+/// Cursors are `{block}:{seen}`: the block of the last record returned and
+/// how many of that block's records were returned already. Pages never
+/// overlap, however `limit` changes between calls.
+#[async_trait]
+impl HistoryProvider for Etherscan {
+    async fn transactions(
+        &self,
+        address: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> WalletResult<HistoryPage> {
+        address
+            .parse::<Address>()
+            .map_err(|_| WalletError::InvalidAddress(address.to_string()))?;
+        let (end_block, seen) = match cursor {
+            Some(cursor) => cursor
+                .split_once(':')
+                .and_then(|(block, seen)| Some((Some(block.parse().ok()?), seen.parse().ok()?)))
+                .ok_or_else(|| WalletError::Other(format!("invalid cursor {}", cursor)))?,
+            None => (None, 0usize),
+        };
+        let limit = limit.clamp(1, MAX_RECORDS_PER_PAGE - seen.min(MAX_RECORDS_PER_PAGE - 1));
+
+        let txs = self.txlist(address, end_block, seen + limit).await?;
+        let exhausted = txs.len() < seen + limit;
+        let txs: Vec<_> = txs.into_iter().skip(seen).collect();
+
+        let next_cursor = match txs.last() {
+            Some(last) if !exhausted => {
+                let block = last.block()?;
+                let mut in_block = txs
+                    .iter()
+                    .rev()
+                    .take_while(|tx| tx.block_number == last.block_number)
+                    .count();
+                if Some(block) == end_block && in_block == txs.len() {
+                    in_block += seen;
+                }
+                Some(format!("{}:{}", block, in_block))
+            }
+            _ => None,
+        };
+        let records = txs
+            .iter()
+            .map(|tx| tx.record(address))
+            .collect::<WalletResult<_>>()?;
+        Ok(HistoryPage {
+            records,
+            next_cursor,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use wiremock::matchers::{method, query_param, query_param_is_missing};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ME: &str = "0x00000000000000000000000000000000000000aa";
+    const OTHER: &str = "0x00000000000000000000000000000000000000bb";
+
+    fn tx(hash: u8, block: u64, from: &str, to: &str, is_error: &str) -> Value {
+        json!({
+            "blockNumber": block.to_string(),
+            "timeStamp": "1700000000",
+            "hash": format!("0x{:064x}", hash),
+            "from": from,
+            "to": to,
+            "contractAddress": "",
+            "value": "1000000000000000000",
+            "gas": "21000",
+            "gasPrice": "10000000000",
+            "gasUsed": "21000",
+            "isError": is_error,
+        })
+    }
+
+    fn ok(result: Value) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_json(json!({"status": "1", "message": "OK", "result": result}))
+    }
+
+    #[tokio::test]
+    async fn test_records_and_cursor() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("action", "txlist"))
+            .and(query_param("chainid", "1"))
+            .and(query_param("offset", "2"))
+            .and(query_param_is_missing("endblock"))
+            .respond_with(ok(json!([
+                tx(1, 12, OTHER, ME, "0"),
+                tx(2, 11, ME, OTHER, "1"),
+            ])))
+            .mount(&server)
+            .await;
+        // The second page restarts at block 11 and skips the record seen there
+        Mock::given(method("GET"))
+            .and(query_param("endblock", "11"))
+            .and(query_param("offset", "3"))
+            .respond_with(ok(json!([
+                tx(2, 11, ME, OTHER, "1"),
+                tx(3, 11, ME, ME, "0"),
+            ])))
+            .mount(&server)
+            .await;
+        let explorer = Etherscan::new("key", 1).with_base_url(&server.uri());
+
+        let page = explorer.transactions(ME, None, 2).await.unwrap();
+        assert_eq!(page.records[0].direction, TxDirection::Incoming);
+        assert_eq!(page.records[0].counterparty.as_deref(), Some(OTHER));
+        assert_eq!(page.records[0].fee, None);
+        assert_eq!(page.records[1].direction, TxDirection::Outgoing);
+        assert_eq!(page.records[1].status, TransactionStatus::Failed);
+        assert_eq!(
+            page.records[1].fee.unwrap().smallest_unit(),
+            210_000_000_000_000
+        );
+        assert_eq!(page.records[1].block_height, Some(11));
+        assert_eq!(page.next_cursor.as_deref(), Some("11:1"));
+
+        let page = explorer
+            .transactions(ME, page.next_cursor.as_deref(), 2)
+            .await
+            .unwrap();
+        assert_eq!(page.records.len(), 1);
+        assert_eq!(page.records[0].direction, TxDirection::SelfTransfer);
+        assert_eq!(
+            page.records[0].amount.smallest_unit(),
+            1_000_000_000_000_000_000
+        );
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[tokio::test]
+    async fn test_empty_history_and_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(query_param("apikey", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "0", "message": "No transactions found", "result": []
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(query_param("apikey", "bad"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "status": "0", "message": "NOTOK", "result": "Invalid API Key"
+            })))
+            .mount(&server)
+            .await;
+
+        let explorer = Etherscan::new("key", 1).with_base_url(&server.uri());
+        assert_eq!(
+            explorer.transactions(ME, None, 10).await.unwrap(),
+            HistoryPage::default()
+        );
+        let explorer = Etherscan::new("bad", 1).with_base_url(&server.uri());
+        assert!(matches!(
+            explorer.transactions(ME, None, 10).await,
+            Err(WalletError::NetworkError(_))
+        ));
+        assert!(matches!(
+            explorer.transactions("0x1234", None, 10).await,
+            Err(WalletError::InvalidAddress(_))
+        ));
+        assert!(explorer
+            .transactions(ME, Some("garbage"), 10)
+            .await
+            .is_err());
+    }
+}
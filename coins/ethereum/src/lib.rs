@@ -64,6 +64,7 @@
 
 use core::fmt;
 
+pub mod disperse;
 mod ethclient;
 pub use ethclient::EthClient;
 mod ethereum_amount;
@@ -72,6 +73,9 @@ mod ethereum_wallet;
 pub use ethereum_wallet::{EthereumWallet, EthereumWalletBuilder};
 mod error;
 pub use error::Error;
+mod events;
+mod history;
+pub use history::{Etherscan, ETHERSCAN_URL, MAX_RECORDS_PER_PAGE};
 pub use alloy;
 pub mod prelude;
 
@@ -1,9 +1,16 @@
 //! Implementation of walletd-traits for EthereumWallet
 
+use alloy::network::TransactionBuilder as _;
+use alloy::primitives::{Address, U256};
+use alloy::rpc::types::TransactionRequest;
 use async_trait::async_trait;
-use walletd_traits::{Amount, Network, Transferable, TxHash, Wallet, WalletError, WalletResult};
+use std::str::FromStr;
+use walletd_traits::{
+    Amount, BatchTransferReport, BatchTransferable, EvmTxParams, GasPolicy, Network,
+    TransactionBuilder, TransferResult, Transferable, TxHash, Wallet, WalletError, WalletResult,
+};
 
-use crate::{EthereumWallet, EthClient};
+use crate::{EthClient, EthereumWallet};
 
 impl EthereumWallet {
     /// Creates a Network struct for this wallet
@@ -30,6 +37,8 @@ pub struct ConnectedEthereumWallet {
     pub rpc_url: String,
     /// Cached network info
     network: Network,
+    /// Connection for push notifications
+    pub(crate) ws: Option<std::sync::Arc<walletd_provider::WsClient>>,
 }
 
 impl ConnectedEthereumWallet {
@@ -40,8 +49,42 @@ impl ConnectedEthereumWallet {
             wallet,
             rpc_url: rpc_url.into(),
             network,
+            ws: None,
         }
     }
+
+    /// Creates a connected wallet from a BIP-39 phrase
+    pub fn from_phrase(
+        phrase: &str,
+        chain_id: u64,
+        rpc_url: impl Into<String>,
+    ) -> WalletResult<Self> {
+        let mnemonic = bdk::keys::bip39::Mnemonic::from_str(phrase)
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        let wallet = EthereumWallet::builder()
+            .mnemonic(mnemonic)
+            .chain_id(chain_id)
+            .build()
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        Ok(Self::new(wallet, rpc_url))
+    }
+
+    /// Parses a batch into Disperse recipients
+    fn disperse_recipients(
+        transfers: &[(String, Amount)],
+    ) -> WalletResult<Vec<(alloy::primitives::Address, alloy::primitives::U256)>> {
+        transfers
+            .iter()
+            .map(|(to, amount)| {
+                let address = alloy::primitives::Address::from_str(to)
+                    .map_err(|_| WalletError::InvalidAddress(to.clone()))?;
+                Ok((
+                    address,
+                    alloy::primitives::U256::from(amount.smallest_unit()),
+                ))
+            })
+            .collect()
+    }
 }
 
 #[async_trait]
@@ -51,14 +94,17 @@ impl Wallet for ConnectedEthereumWallet {
     }
 
     async fn balance(&self) -> WalletResult<Amount> {
-        let balance = self.wallet.balance(&self.rpc_url).await
+        let balance = self
+            .wallet
+            .balance(&self.rpc_url)
+            .await
             .map_err(|e| WalletError::NetworkError(e.to_string()))?;
-        
+
         // Convert EthereumAmount to Amount
         // EthereumAmount.wei is alloy U256, we need u128
         let wei_bytes = balance.wei().to_le_bytes::<32>();
         let wei_u128 = u128::from_le_bytes(wei_bytes[0..16].try_into().unwrap());
-        
+
         Ok(Amount::from_smallest_unit(wei_u128, 18))
     }
 
@@ -77,53 +123,161 @@ impl Wallet for ConnectedEthereumWallet {
 
 #[async_trait]
 impl Transferable for ConnectedEthereumWallet {
+    type TxParams = EvmTxParams;
+
+    async fn transfer_with(&self, tx: TransactionBuilder<EvmTxParams>) -> WalletResult<TxHash> {
+        let request = transaction_request(&tx)?;
+        let tx_hash = self
+            .wallet
+            .send_transaction(&self.rpc_url, request)
+            .await
+            .map_err(|e| WalletError::TransactionFailed(e.to_string()))?;
+        Ok(TxHash::new(tx_hash))
+    }
+
     async fn transfer(&self, to: &str, amount: Amount) -> WalletResult<TxHash> {
         // Convert Amount to EthereumAmount
-        let eth_amount = crate::EthereumAmount::from_wei(
-            alloy::primitives::U256::from(amount.smallest_unit())
-        );
-        
-        let tx_hash = self.wallet.transfer(&self.rpc_url, eth_amount, to).await
+        let eth_amount =
+            crate::EthereumAmount::from_wei(alloy::primitives::U256::from(amount.smallest_unit()));
+
+        let tx_hash = self
+            .wallet
+            .transfer(&self.rpc_url, eth_amount, to)
+            .await
             .map_err(|e| WalletError::TransactionFailed(e.to_string()))?;
-        
+
         Ok(TxHash::new(tx_hash))
     }
 
     async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
         // Get current gas price and estimate gas (21000 for simple transfer)
-        let gas_price = EthClient::gas_price(&self.rpc_url).await
+        let gas_price = EthClient::gas_price(&self.rpc_url)
+            .await
             .map_err(|e| WalletError::NetworkError(e.to_string()))?;
-        
+
         // Simple ETH transfer uses 21000 gas
         let gas_limit = 21000u128;
         let gas_price_wei = gas_price.wei();
         let gas_price_bytes = gas_price_wei.to_le_bytes::<32>();
         let gas_price_u128 = u128::from_le_bytes(gas_price_bytes[0..16].try_into().unwrap());
-        
+
         let fee = gas_limit.saturating_mul(gas_price_u128);
         Ok(Amount::from_smallest_unit(fee, 18))
     }
 }
 
+/// Builds the transaction for [`Transferable::transfer_with`]
+///
+/// Nonce, gas limit and fee caps are used as given; unset ones are filled in
+/// from the node, except that a plain value transfer gets 21000 gas. Gas is
+/// always paid natively, so policies that require a sponsor are refused.
+fn transaction_request(tx: &TransactionBuilder<EvmTxParams>) -> WalletResult<TransactionRequest> {
+    let params = &tx.params;
+    if matches!(
+        params.gas_policy,
+        GasPolicy::Sponsored | GasPolicy::Token { .. }
+    ) {
+        return Err(WalletError::NotSupported(
+            "sponsored gas needs a paymaster".to_string(),
+        ));
+    }
+    if params.gas_price.is_some()
+        && (params.max_fee_per_gas.is_some() || params.max_priority_fee_per_gas.is_some())
+    {
+        return Err(WalletError::Other(
+            "set either gas_price or the EIP-1559 fee caps, not both".to_string(),
+        ));
+    }
+    let to = tx
+        .to
+        .as_deref()
+        .ok_or_else(|| WalletError::InvalidAddress(String::new()))?;
+    let to = Address::from_str(to).map_err(|_| WalletError::InvalidAddress(to.to_string()))?;
+    if tx.amount.is_none() && tx.data.is_none() {
+        return Err(WalletError::Other("missing amount".to_string()));
+    }
+
+    let value = tx.amount.map_or(0, |amount| amount.smallest_unit());
+    let mut request = TransactionRequest::default()
+        .with_to(to)
+        .with_value(U256::from(value));
+    if let Some(data) = &tx.data {
+        request = request.with_input(data.clone());
+    }
+    if let Some(nonce) = tx.nonce {
+        request = request.with_nonce(nonce);
+    }
+    match (params.gas_limit, &tx.data) {
+        (Some(gas_limit), _) => request = request.with_gas_limit(gas_limit),
+        (None, None) => request = request.with_gas_limit(21000),
+        (None, Some(_)) => {}
+    }
+    if let Some(gas_price) = params.gas_price {
+        request = request.with_gas_price(gas_price.smallest_unit());
+    }
+    if let Some(max_fee) = params.max_fee_per_gas {
+        request = request.with_max_fee_per_gas(max_fee.smallest_unit());
+    }
+    if let Some(priority_fee) = params.max_priority_fee_per_gas {
+        request = request.with_max_priority_fee_per_gas(priority_fee.smallest_unit());
+    }
+    Ok(request)
+}
+
+/// On chains with Disperse, batches go out as one `disperseEther` call, so
+/// they land or fail together; elsewhere each transfer is sent in turn
+#[async_trait]
+impl BatchTransferable for ConnectedEthereumWallet {
+    fn supports_native_batch(&self) -> bool {
+        crate::disperse::is_supported_chain(self.wallet.chain_id())
+    }
+
+    async fn transfer_batch(
+        &self,
+        transfers: &[(String, Amount)],
+    ) -> WalletResult<BatchTransferReport> {
+        if !self.supports_native_batch() {
+            let mut report = BatchTransferReport::default();
+            for (to, amount) in transfers {
+                let outcome = self.transfer(to, *amount).await;
+                report.results.push(TransferResult {
+                    to: to.clone(),
+                    amount: *amount,
+                    error: outcome.as_ref().err().map(|e| e.to_string()),
+                    tx_hash: outcome.ok(),
+                });
+            }
+            return Ok(report);
+        }
+        let recipients = Self::disperse_recipients(transfers)?;
+        let outcome = self
+            .wallet
+            .disperse(&self.rpc_url, &recipients)
+            .await
+            .map(TxHash::new)
+            .map_err(|e| WalletError::TransactionFailed(e.to_string()));
+        Ok(BatchTransferReport::single_tx(transfers, outcome))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use bdk::keys::bip39::Mnemonic;
-    use std::str::FromStr;
 
     #[test]
     fn test_ethereum_wallet_address() {
         let mnemonic = Mnemonic::from_str(
             "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
         ).unwrap();
-        
+
         let wallet = EthereumWallet::builder()
             .mnemonic(mnemonic)
             .build()
             .unwrap();
-        
+
         let connected = ConnectedEthereumWallet::new(wallet, "https://eth.llamarpc.com");
-        
+
         // Should return a valid Ethereum address
         let address = connected.address();
         assert!(address.starts_with("0x"));
@@ -135,35 +289,145 @@ mod tests {
         let mnemonic = Mnemonic::from_str(
             "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
         ).unwrap();
-        
+
         let wallet = EthereumWallet::builder()
             .mnemonic(mnemonic)
             .chain_id(1)
             .build()
             .unwrap();
-        
+
         let connected = ConnectedEthereumWallet::new(wallet, "https://eth.llamarpc.com");
-        
+
         assert_eq!(connected.currency_symbol(), "ETH");
         assert_eq!(connected.decimals(), 18);
         assert_eq!(connected.network().chain_id, Some(1));
         assert!(!connected.network().is_testnet);
     }
 
+    #[test]
+    fn test_batch_builds_disperse_call() {
+        use alloy::sol_types::SolCall;
+
+        let transfers = vec![
+            (
+                "0x1111111111111111111111111111111111111111".to_string(),
+                Amount::from_smallest_unit(5, 18),
+            ),
+            (
+                "0x2222222222222222222222222222222222222222".to_string(),
+                Amount::from_smallest_unit(7, 18),
+            ),
+        ];
+        let recipients = ConnectedEthereumWallet::disperse_recipients(&transfers).unwrap();
+        let tx = crate::disperse::disperse_ether(&recipients);
+        assert_eq!(tx.to, Some(crate::disperse::DISPERSE_ADDRESS.into()));
+        assert_eq!(tx.value, Some(alloy::primitives::U256::from(12)));
+        let call =
+            crate::disperse::Disperse::disperseEtherCall::abi_decode(tx.input.input().unwrap())
+                .unwrap();
+        assert_eq!(
+            call.recipients[0].to_string(),
+            "0x1111111111111111111111111111111111111111"
+        );
+
+        let bad = vec![("0x1234".to_string(), Amount::from_smallest_unit(1, 18))];
+        assert!(matches!(
+            ConnectedEthereumWallet::disperse_recipients(&bad),
+            Err(WalletError::InvalidAddress(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_batch_on_unknown_chain_is_sequential() {
+        let mnemonic = Mnemonic::from_str(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
+        ).unwrap();
+        let wallet = EthereumWallet::builder()
+            .mnemonic(mnemonic)
+            .chain_id(999_999)
+            .build()
+            .unwrap();
+        let connected = ConnectedEthereumWallet::new(wallet, "http://127.0.0.1:1");
+        assert!(!connected.supports_native_batch());
+
+        let recipients = [(
+            alloy::primitives::Address::repeat_byte(0x11),
+            alloy::primitives::U256::from(5),
+        )];
+        let err = connected
+            .wallet
+            .disperse(&connected.rpc_url, &recipients)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not deployed on chain 999999"));
+
+        let transfers = vec![
+            (
+                "0x1111111111111111111111111111111111111111".to_string(),
+                Amount::from_smallest_unit(5, 18),
+            ),
+            (
+                "0x2222222222222222222222222222222222222222".to_string(),
+                Amount::from_smallest_unit(7, 18),
+            ),
+        ];
+        let report = connected.transfer_batch(&transfers).await.unwrap();
+        assert_eq!(report.batch_tx, None);
+        assert_eq!(report.results.len(), 2);
+        assert_eq!(report.failed().count(), 2);
+    }
+
+    #[test]
+    fn test_transfer_with_applies_params() {
+        let to = "0x3535353535353535353535353535353535353535";
+        let gwei = |n: u128| Amount::from_smallest_unit(n * 1_000_000_000, 18);
+        let tx = TransactionBuilder::new()
+            .to(to)
+            .amount(Amount::from_smallest_unit(5, 18))
+            .data(vec![0xde, 0xad])
+            .nonce(7)
+            .gas_limit(60_000)
+            .eip1559_fees(gwei(30), gwei(2));
+        let request = transaction_request(&tx).unwrap();
+        assert_eq!(request.nonce, Some(7));
+        assert_eq!(request.gas, Some(60_000));
+        assert_eq!(request.max_fee_per_gas, Some(30_000_000_000));
+        assert_eq!(request.max_priority_fee_per_gas, Some(2_000_000_000));
+        assert_eq!(request.value, Some(U256::from(5)));
+        assert_eq!(request.input.input().unwrap().to_vec(), vec![0xde, 0xad]);
+
+        // A plain transfer keeps the 21000 gas limit
+        let plain = TransactionBuilder::new()
+            .to(to)
+            .amount(Amount::from_smallest_unit(1, 18))
+            .gas_price(gwei(20));
+        let request = transaction_request(&plain).unwrap();
+        assert_eq!(request.gas, Some(21000));
+        assert_eq!(request.gas_price, Some(20_000_000_000));
+
+        let both = plain.clone().eip1559_fees(gwei(30), gwei(2));
+        assert!(transaction_request(&both).is_err());
+        let sponsored = plain.gas_policy(GasPolicy::Sponsored);
+        assert!(matches!(
+            transaction_request(&sponsored),
+            Err(WalletError::NotSupported(_))
+        ));
+    }
+
     #[test]
     fn test_sepolia_network() {
         let mnemonic = Mnemonic::from_str(
             "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"
         ).unwrap();
-        
+
         let wallet = EthereumWallet::builder()
             .mnemonic(mnemonic)
             .chain_id(11155111)
             .build()
             .unwrap();
-        
+
         let connected = ConnectedEthereumWallet::new(wallet, "https://sepolia.infura.io");
-        
+
         assert!(connected.network().is_testnet);
         assert_eq!(connected.network().name, "Sepolia");
     }
@@ -4,11 +4,14 @@
 
 use anyhow::Result;
 use bip39::Mnemonic;
-use ed25519_dalek::{SigningKey, VerifyingKey, SECRET_KEY_LENGTH};
+use ed25519_dalek::{VerifyingKey, SECRET_KEY_LENGTH};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
+use walletd_traits::{keystore, Ed25519Signer, KeyFormat, SignatureScheme, Signer};
+use walletd_core::{SecretBytes, Zeroize};
+use walletd_error::WalletdError;
 
 // ============================================================================
 // ERRORS
@@ -32,6 +35,24 @@ pub enum NearError {
     Other(#[from] anyhow::Error),
 }
 
+impl From<NearError> for WalletdError {
+    fn from(e: NearError) -> Self {
+        let error = match e {
+            NearError::InvalidAccountId(address) => WalletdError::InvalidAddress {
+                address,
+                reason: "invalid account ID".to_string(),
+            },
+            NearError::KeyError(m) => WalletdError::InvalidPrivateKey(m),
+            NearError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            NearError::NetworkError(m) => WalletdError::NetworkError(m),
+            NearError::InsufficientBalance => WalletdError::TransactionFailed(e.to_string()),
+            NearError::AccountNotFound(m) => WalletdError::AddressNotFound(m),
+            NearError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("near")
+    }
+}
+
 // ============================================================================
 // CONFIG
 // ============================================================================
@@ -105,7 +126,7 @@ impl KeyType {
 // ============================================================================
 
 pub struct NearWallet {
-    signing_key: SigningKey,
+    signer: Box<dyn Signer>,
     verifying_key: VerifyingKey,
     config: NetworkConfig,
     account_id: Option<String>,
@@ -117,17 +138,8 @@ impl NearWallet {
         let mut csprng = rand::rngs::OsRng;
         let mut secret_bytes = [0u8; SECRET_KEY_LENGTH];
         csprng.fill_bytes(&mut secret_bytes);
-        
-        let signing_key = SigningKey::from_bytes(&secret_bytes);
-        let verifying_key = signing_key.verifying_key();
 
-        Ok(Self {
-            signing_key,
-            verifying_key,
-            config,
-            account_id: None,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&secret_bytes, config)
     }
 
     pub fn mainnet() -> Result<Self> {
@@ -145,25 +157,28 @@ impl NearWallet {
         // Near derivation path: m/44'/397'/0'
         let mut key_bytes = [0u8; 32];
         key_bytes.copy_from_slice(&seed[..32]);
-        
-        let signing_key = SigningKey::from_bytes(&key_bytes);
-        let verifying_key = signing_key.verifying_key();
 
-        Ok(Self {
-            signing_key,
-            verifying_key,
-            config,
-            account_id: None,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn from_private_key(key: &[u8; 32], config: NetworkConfig) -> Result<Self> {
-        let signing_key = SigningKey::from_bytes(key);
-        let verifying_key = signing_key.verifying_key();
+        Self::from_signer(Box::new(Ed25519Signer::from_bytes(key)), config)
+    }
+
+    /// Creates a wallet backed by any Ed25519 signer (hardware, remote, HSM)
+    pub fn from_signer(signer: Box<dyn Signer>, config: NetworkConfig) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            anyhow::bail!("Near requires an ed25519 signer, got {}", signer.scheme());
+        }
+        let public_key: [u8; 32] = signer
+            .public_key()
+            .try_into()
+            .map_err(|_| NearError::KeyError("public key must be 32 bytes".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&public_key)
+            .map_err(|e| NearError::KeyError(e.to_string()))?;
 
         Ok(Self {
-            signing_key,
+            signer,
             verifying_key,
             config,
             account_id: None,
@@ -201,17 +216,56 @@ impl NearWallet {
     }
 
     /// Get private key in Near format
-    pub fn private_key(&self) -> String {
-        let mut full_key = Vec::with_capacity(64);
-        full_key.extend_from_slice(self.signing_key.as_bytes());
+    ///
+    /// Fails when the key is held by an external signer.
+    pub fn private_key(&self) -> Result<String> {
+        let mut full_key = self.secret_key()?.expose_secret().to_vec();
         full_key.extend_from_slice(self.verifying_key.as_bytes());
         let encoded = bs58::encode(&full_key).into_string();
-        format!("ed25519:{}", encoded)
+        full_key.zeroize();
+        Ok(format!("ed25519:{}", encoded))
     }
 
     /// Get private key as hex
-    pub fn private_key_hex(&self) -> String {
-        format!("0x{}", hex::encode(self.signing_key.as_bytes()))
+    pub fn private_key_hex(&self) -> Result<String> {
+        Ok(format!("0x{}", hex::encode(self.secret_key()?.expose_secret())))
+    }
+
+    fn secret_key(&self) -> Result<SecretBytes> {
+        self.signer
+            .secret_key()
+            .map(SecretBytes::from)
+            .ok_or_else(|| anyhow::anyhow!("private key is held by an external signer"))
+    }
+
+    /// Exports NEAR credentials JSON encrypted with `password` (keystore v3)
+    pub fn export_encrypted(&self, password: &str) -> Result<String> {
+        let secret = self.secret_key()?;
+        let secret = secret
+            .as_array()
+            .ok_or_else(|| NearError::KeyError("private key must be 32 bytes".to_string()))?;
+        let credentials = keystore::encode_near_credentials(&self.account_id(), secret)?;
+        Ok(keystore::encrypt_key(
+            KeyFormat::NearCredentials,
+            credentials.as_bytes(),
+            password,
+            None,
+        )?)
+    }
+
+    /// Restores a wallet from [`export_encrypted`](Self::export_encrypted) output
+    pub fn from_encrypted(json: &str, password: &str, config: NetworkConfig) -> Result<Self> {
+        let key = keystore::decrypt_key(json, password)?;
+        if key.format != KeyFormat::NearCredentials {
+            anyhow::bail!("expected NEAR credentials, found {}", key.format);
+        }
+        let credentials = std::str::from_utf8(key.payload.expose_secret())?;
+        let (account_id, secret) = keystore::decode_near_credentials(credentials)?;
+        let mut wallet = Self::from_private_key(&secret, config)?;
+        if account_id != wallet.implicit_account_id() {
+            wallet.set_account_id(&account_id);
+        }
+        Ok(wallet)
     }
 
     pub fn config(&self) -> &NetworkConfig {
@@ -234,10 +288,8 @@ impl NearWallet {
         Ok(NetworkConfig::yocto_to_near(yocto))
     }
 
-    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
-        use ed25519_dalek::Signer;
-        let signature = self.signing_key.sign(message);
-        signature.to_bytes().to_vec()
+    pub async fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.signer.sign_message(message).await?)
     }
 
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
@@ -251,6 +303,13 @@ impl NearWallet {
         self.verifying_key.verify(message, &sig).is_ok()
     }
 
+    /// Returns a read-only view of this wallet without the private key
+    pub fn watch_only(&self) -> NearWatchOnlyWallet {
+        let mut watch = NearWatchOnlyWallet::new(self.account_id(), Some(self.verifying_key), self.config.clone());
+        watch.api_endpoint = self.api_endpoint.clone();
+        watch
+    }
+
     /// Validate a Near account ID
     pub fn validate_account_id(account_id: &str) -> bool {
         // Near account ID rules:
@@ -280,6 +339,117 @@ impl NearWallet {
     }
 }
 
+// ============================================================================
+// WATCH-ONLY WALLET
+// ============================================================================
+
+/// Read-only Near wallet for monitoring an account without its private key
+pub struct NearWatchOnlyWallet {
+    account_id: String,
+    verifying_key: Option<VerifyingKey>,
+    config: NetworkConfig,
+    api_endpoint: Option<String>,
+    network: walletd_traits::Network,
+}
+
+impl NearWatchOnlyWallet {
+    fn new(account_id: String, verifying_key: Option<VerifyingKey>, config: NetworkConfig) -> Self {
+        let network = if config.is_mainnet {
+            walletd_traits::Network::mainnet(&config.chain_id)
+        } else {
+            walletd_traits::Network::testnet(&config.chain_id)
+        };
+        Self {
+            account_id,
+            verifying_key,
+            config,
+            api_endpoint: None,
+            network,
+        }
+    }
+
+    /// Watches a named or implicit account
+    pub fn from_address(account_id: &str, config: NetworkConfig) -> Result<Self> {
+        if !NearWallet::validate_account_id(account_id) {
+            return Err(NearError::InvalidAccountId(account_id.to_string()).into());
+        }
+        Ok(Self::new(account_id.to_string(), None, config))
+    }
+
+    /// Watches the implicit account of an Ed25519 public key
+    pub fn from_pubkey(pubkey: &[u8], config: NetworkConfig) -> Result<Self> {
+        let bytes: [u8; 32] = pubkey
+            .try_into()
+            .map_err(|_| NearError::KeyError("public key must be 32 bytes".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&bytes)
+            .map_err(|e| NearError::KeyError(e.to_string()))?;
+        Ok(Self::new(hex::encode(bytes), Some(verifying_key), config))
+    }
+
+    pub fn set_api_endpoint(&mut self, endpoint: &str) {
+        self.api_endpoint = Some(endpoint.to_string());
+    }
+
+    pub fn account_id(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Get public key in Near format, if known
+    pub fn public_key(&self) -> Option<String> {
+        self.verifying_key
+            .map(|key| format!("ed25519:{}", bs58::encode(key.as_bytes()).into_string()))
+    }
+
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
+    pub async fn get_balance(&self) -> Result<u128> {
+        if self.api_endpoint.is_none() {
+            return Ok(0);
+        }
+        Ok(0)
+    }
+
+    /// Verifies a signature made by the watched key
+    pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        use ed25519_dalek::{Signature, Verifier};
+        let (Some(key), Ok(sig_bytes)) = (self.verifying_key, <[u8; 64]>::try_from(signature)) else {
+            return false;
+        };
+        key.verify(message, &Signature::from_bytes(&sig_bytes)).is_ok()
+    }
+}
+
+#[async_trait::async_trait]
+impl walletd_traits::Wallet for NearWatchOnlyWallet {
+    fn address(&self) -> String {
+        self.account_id.clone()
+    }
+
+    async fn balance(&self) -> walletd_traits::WalletResult<walletd_traits::Amount> {
+        let yocto = self
+            .get_balance()
+            .await
+            .map_err(|e| walletd_traits::WalletError::NetworkError(e.to_string()))?;
+        Ok(walletd_traits::Amount::from_smallest_unit(yocto, 24))
+    }
+
+    fn network(&self) -> &walletd_traits::Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "NEAR"
+    }
+
+    fn decimals(&self) -> u8 {
+        24
+    }
+}
+
+impl walletd_traits::WatchOnly for NearWatchOnlyWallet {}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -340,14 +510,14 @@ mod tests {
     #[test]
     fn test_private_key_format() {
         let wallet = NearWallet::mainnet().unwrap();
-        let pk = wallet.private_key();
+        let pk = wallet.private_key().unwrap();
         assert!(pk.starts_with("ed25519:"));
     }
 
     #[test]
     fn test_private_key_hex() {
         let wallet = NearWallet::mainnet().unwrap();
-        let pk = wallet.private_key_hex();
+        let pk = wallet.private_key_hex().unwrap();
         assert!(pk.starts_with("0x"));
         assert_eq!(pk.len(), 66);
     }
@@ -359,25 +529,49 @@ mod tests {
         assert_eq!(wallet.account_id(), "myaccount.near");
     }
 
-    #[test]
-    fn test_sign_message() {
+    #[tokio::test]
+    async fn test_sign_message() {
         let wallet = NearWallet::mainnet().unwrap();
-        let sig = wallet.sign(b"Hello Near!");
+        let sig = wallet.sign(b"Hello Near!").await.unwrap();
         assert_eq!(sig.len(), 64);
     }
 
-    #[test]
-    fn test_verify_signature() {
+    #[tokio::test]
+    async fn test_verify_signature() {
         let wallet = NearWallet::mainnet().unwrap();
         let msg = b"Hello Near!";
-        let sig = wallet.sign(msg);
+        let sig = wallet.sign(msg).await.unwrap();
         assert!(wallet.verify(msg, &sig));
     }
 
+    #[tokio::test]
+    async fn test_from_signer() {
+        let wallet = NearWallet::from_signer(Box::new(Ed25519Signer::from_bytes(&[5u8; 32])), NetworkConfig::testnet()).unwrap();
+        let sig = wallet.sign(b"Hello Near!").await.unwrap();
+        assert!(wallet.verify(b"Hello Near!", &sig));
+        assert_eq!(wallet.private_key_hex().unwrap(), format!("0x{}", "05".repeat(32)));
+
+        let secp = walletd_traits::Secp256k1Signer::from_slice(&[5u8; 32]).unwrap();
+        assert!(NearWallet::from_signer(Box::new(secp), NetworkConfig::testnet()).is_err());
+    }
+
     #[test]
-    fn test_verify_wrong_message() {
+    fn test_from_encrypted() {
+        let mut wallet = NearWallet::from_private_key(&[8u8; 32], NetworkConfig::mainnet()).unwrap();
+        wallet.set_account_id("alice.near");
+        let credentials = keystore::encode_near_credentials("alice.near", &[8u8; 32]).unwrap();
+        let json = keystore::encrypt_key_with(KeyFormat::NearCredentials, credentials.as_bytes(), "pw", None, 10).unwrap();
+
+        let restored = NearWallet::from_encrypted(&json, "pw", NetworkConfig::mainnet()).unwrap();
+        assert_eq!(restored.account_id(), "alice.near");
+        assert_eq!(restored.public_key(), wallet.public_key());
+        assert!(NearWallet::from_encrypted(&json, "wrong", NetworkConfig::mainnet()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_verify_wrong_message() {
         let wallet = NearWallet::mainnet().unwrap();
-        let sig = wallet.sign(b"Hello Near!");
+        let sig = wallet.sign(b"Hello Near!").await.unwrap();
         assert!(!wallet.verify(b"Wrong message", &sig));
     }
 
@@ -436,6 +630,39 @@ mod tests {
         assert_eq!(balance, 0);
     }
 
+    #[tokio::test]
+    async fn test_watch_only_from_pubkey() {
+        let wallet = NearWallet::mainnet().unwrap();
+        let pubkey = hex::decode(wallet.public_key_hex()).unwrap();
+        let watch = NearWatchOnlyWallet::from_pubkey(&pubkey, NetworkConfig::mainnet()).unwrap();
+        assert_eq!(watch.account_id(), wallet.implicit_account_id());
+        assert_eq!(watch.public_key(), Some(wallet.public_key()));
+
+        let msg = b"Hello Near!";
+        assert!(watch.verify(msg, &wallet.sign(msg).await.unwrap()));
+        assert!(NearWatchOnlyWallet::from_pubkey(&[0u8; 31], NetworkConfig::mainnet()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_from_address() {
+        use walletd_traits::{Wallet, WatchOnly};
+
+        fn assert_watch_only<W: WatchOnly>(_: &W) {}
+
+        let watch = NearWatchOnlyWallet::from_address("alice.near", NetworkConfig::mainnet()).unwrap();
+        assert_watch_only(&watch);
+        assert_eq!(watch.address(), "alice.near");
+        assert!(watch.public_key().is_none());
+        assert_eq!(watch.balance().await.unwrap().decimals, 24);
+        assert!(NearWatchOnlyWallet::from_address("-bad", NetworkConfig::mainnet()).is_err());
+
+        let mut wallet = NearWallet::testnet().unwrap();
+        wallet.set_account_id("bob.testnet");
+        let watch = wallet.watch_only();
+        assert_eq!(watch.address(), "bob.testnet");
+        assert!(watch.network().is_testnet);
+    }
+
     #[test]
     fn test_is_mainnet() {
         let mainnet = NearWallet::mainnet().unwrap();
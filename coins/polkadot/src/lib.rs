@@ -7,11 +7,13 @@ use anyhow::Result;
 use bip39::Mnemonic;
 use blake2::{Blake2b, Digest};
 use blake2::digest::consts::U64;
-use ed25519_dalek::{SigningKey, VerifyingKey, SECRET_KEY_LENGTH};
+use ed25519_dalek::{VerifyingKey, SECRET_KEY_LENGTH};
 use rand::RngCore;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
+use walletd_traits::{Ed25519Signer, SignatureScheme, Signer};
+use walletd_error::WalletdError;
 
 // ============================================================================
 // ERRORS
@@ -33,6 +35,23 @@ pub enum PolkadotError {
     Other(#[from] anyhow::Error),
 }
 
+impl From<PolkadotError> for WalletdError {
+    fn from(e: PolkadotError) -> Self {
+        let error = match e {
+            PolkadotError::InvalidAddress(address) => WalletdError::InvalidAddress {
+                address,
+                reason: "not a valid SS58 address".to_string(),
+            },
+            PolkadotError::KeyError(m) => WalletdError::InvalidPrivateKey(m),
+            PolkadotError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            PolkadotError::NetworkError(m) => WalletdError::NetworkError(m),
+            PolkadotError::InsufficientBalance => WalletdError::TransactionFailed(e.to_string()),
+            PolkadotError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("polkadot")
+    }
+}
+
 // ============================================================================
 // CONFIG
 // ============================================================================
@@ -173,7 +192,7 @@ fn decode_ss58(address: &str) -> Result<(u8, [u8; 32])> {
 // ============================================================================
 
 pub struct PolkadotWallet {
-    signing_key: SigningKey,
+    signer: Box<dyn Signer>,
     verifying_key: VerifyingKey,
     config: NetworkConfig,
     api_endpoint: Option<String>,
@@ -184,16 +203,8 @@ impl PolkadotWallet {
         let mut csprng = rand::rngs::OsRng;
         let mut secret_bytes = [0u8; SECRET_KEY_LENGTH];
         csprng.fill_bytes(&mut secret_bytes);
-        
-        let signing_key = SigningKey::from_bytes(&secret_bytes);
-        let verifying_key = signing_key.verifying_key();
 
-        Ok(Self {
-            signing_key,
-            verifying_key,
-            config,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&secret_bytes, config)
     }
 
     pub fn polkadot() -> Result<Self> {
@@ -214,24 +225,28 @@ impl PolkadotWallet {
 
         let mut key_bytes = [0u8; 32];
         key_bytes.copy_from_slice(&seed[..32]);
-        
-        let signing_key = SigningKey::from_bytes(&key_bytes);
-        let verifying_key = signing_key.verifying_key();
 
-        Ok(Self {
-            signing_key,
-            verifying_key,
-            config,
-            api_endpoint: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn from_private_key(key: &[u8; 32], config: NetworkConfig) -> Result<Self> {
-        let signing_key = SigningKey::from_bytes(key);
-        let verifying_key = signing_key.verifying_key();
+        Self::from_signer(Box::new(Ed25519Signer::from_bytes(key)), config)
+    }
+
+    /// Creates a wallet backed by any Ed25519 signer (hardware, remote, HSM)
+    pub fn from_signer(signer: Box<dyn Signer>, config: NetworkConfig) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            anyhow::bail!("Polkadot wallet requires an ed25519 signer, got {}", signer.scheme());
+        }
+        let public_key: [u8; 32] = signer
+            .public_key()
+            .try_into()
+            .map_err(|_| PolkadotError::KeyError("public key must be 32 bytes".to_string()))?;
+        let verifying_key = VerifyingKey::from_bytes(&public_key)
+            .map_err(|e| PolkadotError::KeyError(e.to_string()))?;
 
         Ok(Self {
-            signing_key,
+            signer,
             verifying_key,
             config,
             api_endpoint: None,
@@ -252,8 +267,13 @@ impl PolkadotWallet {
         hex::encode(self.verifying_key.as_bytes())
     }
 
-    pub fn private_key(&self) -> String {
-        format!("0x{}", hex::encode(self.signing_key.as_bytes()))
+    /// Fails when the key is held by an external signer
+    pub fn private_key(&self) -> Result<String> {
+        let secret = self
+            .signer
+            .secret_key()
+            .ok_or_else(|| anyhow::anyhow!("private key is held by an external signer"))?;
+        Ok(format!("0x{}", hex::encode(secret)))
     }
 
     pub fn config(&self) -> &NetworkConfig {
@@ -280,10 +300,8 @@ impl PolkadotWallet {
         Ok(NetworkConfig::planck_to_dot(planck))
     }
 
-    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
-        use ed25519_dalek::Signer;
-        let signature = self.signing_key.sign(message);
-        signature.to_bytes().to_vec()
+    pub async fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        Ok(self.signer.sign_message(message).await?)
     }
 
     pub fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
@@ -309,8 +327,118 @@ impl PolkadotWallet {
             Err(_) => false,
         }
     }
+
+    /// Returns a read-only view of this wallet without the private key
+    pub fn watch_only(&self) -> PolkadotWatchOnlyWallet {
+        let mut watch = PolkadotWatchOnlyWallet::new(self.verifying_key.to_bytes(), self.config.clone());
+        watch.api_endpoint = self.api_endpoint.clone();
+        watch
+    }
 }
 
+// ============================================================================
+// WATCH-ONLY WALLET
+// ============================================================================
+
+/// Read-only Substrate wallet for monitoring an account without its private key
+pub struct PolkadotWatchOnlyWallet {
+    public_key: [u8; 32],
+    config: NetworkConfig,
+    api_endpoint: Option<String>,
+    network: walletd_traits::Network,
+}
+
+impl PolkadotWatchOnlyWallet {
+    fn new(public_key: [u8; 32], config: NetworkConfig) -> Self {
+        let network = if config.is_mainnet {
+            walletd_traits::Network::mainnet(&config.name)
+        } else {
+            walletd_traits::Network::testnet(&config.name)
+        };
+        Self {
+            public_key,
+            config,
+            api_endpoint: None,
+            network,
+        }
+    }
+
+    /// Watches an SS58 address, which must use the network's prefix
+    pub fn from_address(address: &str, config: NetworkConfig) -> Result<Self> {
+        let (prefix, public_key) = decode_ss58(address)
+            .map_err(|e| PolkadotError::InvalidAddress(format!("{}: {}", address, e)))?;
+        if prefix != config.ss58_prefix {
+            return Err(PolkadotError::InvalidAddress(format!(
+                "{} has prefix {}, expected {}",
+                address, prefix, config.ss58_prefix
+            ))
+            .into());
+        }
+        Ok(Self::new(public_key, config))
+    }
+
+    /// Watches the account of a 32-byte public key
+    pub fn from_pubkey(pubkey: &[u8], config: NetworkConfig) -> Result<Self> {
+        let public_key: [u8; 32] = pubkey
+            .try_into()
+            .map_err(|_| PolkadotError::KeyError("public key must be 32 bytes".to_string()))?;
+        Ok(Self::new(public_key, config))
+    }
+
+    pub fn set_api_endpoint(&mut self, endpoint: &str) {
+        self.api_endpoint = Some(endpoint.to_string());
+    }
+
+    /// Get SS58-encoded address
+    pub fn address(&self) -> String {
+        encode_ss58(self.config.ss58_prefix, &self.public_key)
+    }
+
+    pub fn public_key(&self) -> String {
+        hex::encode(self.public_key)
+    }
+
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
+    pub async fn get_balance(&self) -> Result<u128> {
+        if self.api_endpoint.is_none() {
+            return Ok(0);
+        }
+        Ok(0)
+    }
+}
+
+#[async_trait::async_trait]
+impl walletd_traits::Wallet for PolkadotWatchOnlyWallet {
+    fn address(&self) -> String {
+        PolkadotWatchOnlyWallet::address(self)
+    }
+
+    async fn balance(&self) -> walletd_traits::WalletResult<walletd_traits::Amount> {
+        let planck = self
+            .get_balance()
+            .await
+            .map_err(|e| walletd_traits::WalletError::NetworkError(e.to_string()))?;
+        Ok(walletd_traits::Amount::from_smallest_unit(planck, self.config.decimals))
+    }
+
+    fn network(&self) -> &walletd_traits::Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        &self.config.token_symbol
+    }
+
+    fn decimals(&self) -> u8 {
+        self.config.decimals
+    }
+}
+
+impl walletd_traits::WatchOnly for PolkadotWatchOnlyWallet {}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -391,30 +519,30 @@ mod tests {
     #[test]
     fn test_private_key_format() {
         let wallet = PolkadotWallet::polkadot().unwrap();
-        let pk = wallet.private_key();
+        let pk = wallet.private_key().unwrap();
         assert!(pk.starts_with("0x"));
         assert_eq!(pk.len(), 66);
     }
 
-    #[test]
-    fn test_sign_message() {
+    #[tokio::test]
+    async fn test_sign_message() {
         let wallet = PolkadotWallet::polkadot().unwrap();
-        let sig = wallet.sign(b"Hello Polkadot!");
+        let sig = wallet.sign(b"Hello Polkadot!").await.unwrap();
         assert_eq!(sig.len(), 64);
     }
 
-    #[test]
-    fn test_verify_signature() {
+    #[tokio::test]
+    async fn test_verify_signature() {
         let wallet = PolkadotWallet::polkadot().unwrap();
         let msg = b"Hello Polkadot!";
-        let sig = wallet.sign(msg);
+        let sig = wallet.sign(msg).await.unwrap();
         assert!(wallet.verify(msg, &sig));
     }
 
-    #[test]
-    fn test_verify_wrong_message() {
+    #[tokio::test]
+    async fn test_verify_wrong_message() {
         let wallet = PolkadotWallet::polkadot().unwrap();
-        let sig = wallet.sign(b"Hello Polkadot!");
+        let sig = wallet.sign(b"Hello Polkadot!").await.unwrap();
         assert!(!wallet.verify(b"Wrong message", &sig));
     }
 
@@ -462,4 +590,28 @@ mod tests {
         assert!(mainnet.is_mainnet());
         assert!(!testnet.is_mainnet());
     }
+
+    #[test]
+    fn test_watch_only_from_pubkey() {
+        let wallet = PolkadotWallet::from_mnemonic(TEST_MNEMONIC, NetworkConfig::polkadot()).unwrap();
+        let pubkey = hex::decode(wallet.public_key()).unwrap();
+        let watch = PolkadotWatchOnlyWallet::from_pubkey(&pubkey, NetworkConfig::polkadot()).unwrap();
+        assert_eq!(watch.address(), wallet.address());
+        assert_eq!(wallet.watch_only().address(), wallet.address());
+        assert!(PolkadotWatchOnlyWallet::from_pubkey(&[0u8; 33], NetworkConfig::polkadot()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_from_address() {
+        use walletd_traits::Wallet;
+
+        let wallet = PolkadotWallet::kusama().unwrap();
+        let watch = PolkadotWatchOnlyWallet::from_address(&wallet.address(), NetworkConfig::kusama()).unwrap();
+        assert_eq!(watch.public_key(), wallet.public_key());
+        assert_eq!(watch.currency_symbol(), "KSM");
+        assert_eq!(watch.balance().await.unwrap().decimals, 12);
+
+        // Addresses are tied to the network prefix
+        assert!(PolkadotWatchOnlyWallet::from_address(&wallet.address(), NetworkConfig::polkadot()).is_err());
+    }
 }
@@ -1,4 +1,5 @@
 use thiserror::Error;
+use walletd_error::WalletdError;
 
 #[derive(Error, Debug)]
 pub enum PolygonError {
@@ -26,3 +27,25 @@ pub enum PolygonError {
     #[error("Other error: {0}")]
     Other(#[from] anyhow::Error),
 }
+
+impl From<PolygonError> for WalletdError {
+    fn from(e: PolygonError) -> Self {
+        let error = match e {
+            PolygonError::RpcError(reason) => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason,
+            },
+            PolygonError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            PolygonError::WalletError(m) => WalletdError::InvalidState(m),
+            PolygonError::NetworkError(m) => WalletdError::NetworkError(m),
+            PolygonError::InvalidAddress(reason) => WalletdError::InvalidAddress {
+                address: String::new(),
+                reason,
+            },
+            PolygonError::InsufficientBalance => WalletdError::TransactionFailed(e.to_string()),
+            PolygonError::GasEstimationFailed(m) => WalletdError::TransactionBuildError(m),
+            PolygonError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("polygon")
+    }
+}
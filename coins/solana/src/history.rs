@@ -0,0 +1,288 @@
+//! Transaction history from `getSignaturesForAddress` and `getTransaction`
+
+use async_trait::async_trait;
+use solana_client::rpc_client::GetConfirmedSignaturesForAddress2Config;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_client::rpc_response::RpcConfirmedTransactionStatusWithSignature;
+use solana_sdk::{pubkey::Pubkey, signature::Signature};
+use solana_transaction_status_client_types::{
+    option_serializer::OptionSerializer, EncodedConfirmedTransactionWithStatusMeta,
+    EncodedTransaction, TransactionConfirmationStatus, UiMessage, UiTransactionEncoding,
+};
+use std::str::FromStr;
+use walletd_traits::{
+    Amount, HistoryPage, HistoryProvider, TransactionRecord, TransactionStatus, TxDirection,
+    TxHash, WalletError, WalletResult,
+};
+
+use crate::solana_client::SolanaClient;
+
+/// Most signatures `getSignaturesForAddress` returns per call
+pub const MAX_SIGNATURES_PER_PAGE: usize = 1_000;
+
+const SOL_DECIMALS: u8 = 9;
+
+/// Cursors are the signature of the last record of the previous page, so
+/// pages stay stable while new transactions arrive.
+///
+/// Amounts are the address's lamport balance change, less the fee when it
+/// paid it; `block_height` holds the slot.
+#[async_trait]
+impl HistoryProvider for SolanaClient {
+    async fn transactions(
+        &self,
+        address: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> WalletResult<HistoryPage> {
+        let pubkey = Pubkey::from_str(address)
+            .map_err(|_| WalletError::InvalidAddress(address.to_string()))?;
+        let before = cursor
+            .map(|cursor| {
+                Signature::from_str(cursor)
+                    .map_err(|_| WalletError::Other(format!("invalid cursor {}", cursor)))
+            })
+            .transpose()?;
+        let limit = limit.clamp(1, MAX_SIGNATURES_PER_PAGE);
+        let config = GetConfirmedSignaturesForAddress2Config {
+            before,
+            until: None,
+            limit: Some(limit),
+            commitment: Some(*self.commitment_level()),
+        };
+        let signatures = self
+            .rpc_client()
+            .get_signatures_for_address_with_config(&pubkey, config)
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+
+        let mut records = Vec::with_capacity(signatures.len());
+        for status in &signatures {
+            let signature = Signature::from_str(&status.signature).map_err(|_| {
+                WalletError::NetworkError(format!("bad signature {}", status.signature))
+            })?;
+            let config = RpcTransactionConfig {
+                encoding: Some(UiTransactionEncoding::Json),
+                commitment: Some(*self.commitment_level()),
+                max_supported_transaction_version: Some(0),
+            };
+            let tx = self
+                .rpc_client()
+                .get_transaction_with_config(&signature, config)
+                .await
+                .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+            records.extend(record(address, status, &tx));
+        }
+
+        let next_cursor = match signatures.last() {
+            Some(last) if signatures.len() == limit => Some(last.signature.clone()),
+            _ => None,
+        };
+        Ok(HistoryPage {
+            records,
+            next_cursor,
+        })
+    }
+}
+
+/// `tx` as seen from `address`, or `None` if it does not touch `address`
+fn record(
+    address: &str,
+    status: &RpcConfirmedTransactionStatusWithSignature,
+    tx: &EncodedConfirmedTransactionWithStatusMeta,
+) -> Option<TransactionRecord> {
+    let meta = tx.transaction.meta.as_ref()?;
+    let mut keys = match &tx.transaction.transaction {
+        EncodedTransaction::Json(ui) => match &ui.message {
+            UiMessage::Raw(message) => message.account_keys.clone(),
+            UiMessage::Parsed(message) => message
+                .account_keys
+                .iter()
+                .map(|key| key.pubkey.clone())
+                .collect(),
+        },
+        _ => return None,
+    };
+    // Address-table accounts follow the static keys, writable first
+    if let OptionSerializer::Some(loaded) = &meta.loaded_addresses {
+        keys.extend(loaded.writable.iter().cloned());
+        keys.extend(loaded.readonly.iter().cloned());
+    }
+
+    let deltas: Vec<i128> = meta
+        .pre_balances
+        .iter()
+        .zip(&meta.post_balances)
+        .map(|(pre, post)| *post as i128 - *pre as i128)
+        .collect();
+    let index = keys.iter().position(|key| key == address)?;
+    let paid_fee = index == 0;
+    // Fee aside, what left or reached the address
+    let delta = deltas.get(index)? + if paid_fee { meta.fee as i128 } else { 0 };
+
+    let (direction, counterparty) = if delta > 0 {
+        (
+            TxDirection::Incoming,
+            biggest(&keys, &deltas, index, |d| -d),
+        )
+    } else if delta < 0 {
+        (TxDirection::Outgoing, biggest(&keys, &deltas, index, |d| d))
+    } else {
+        (TxDirection::SelfTransfer, None)
+    };
+    let tx_status = if meta.err.is_some() {
+        TransactionStatus::Failed
+    } else if status.confirmation_status == Some(TransactionConfirmationStatus::Processed) {
+        TransactionStatus::Pending
+    } else {
+        TransactionStatus::Confirmed
+    };
+
+    Some(TransactionRecord {
+        hash: TxHash::new(status.signature.clone()),
+        direction,
+        counterparty,
+        amount: Amount::from_smallest_unit(delta.unsigned_abs(), SOL_DECIMALS),
+        fee: paid_fee.then(|| Amount::from_smallest_unit(meta.fee as u128, SOL_DECIMALS)),
+        status: tx_status,
+        timestamp: tx.block_time.and_then(|time| u64::try_from(time).ok()),
+        block_height: Some(tx.slot),
+    })
+}
+
+/// The other account whose balance moved most in the direction `score`
+/// favours
+fn biggest(
+    keys: &[String],
+    deltas: &[i128],
+    skip: usize,
+    score: fn(i128) -> i128,
+) -> Option<String> {
+    deltas
+        .iter()
+        .enumerate()
+        .filter(|(i, delta)| *i != skip && score(**delta) > 0)
+        .max_by_key(|(_, delta)| score(**delta))
+        .and_then(|(i, _)| keys.get(i).cloned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn signature(byte: u8) -> String {
+        Signature::from([byte; 64]).to_string()
+    }
+
+    fn signature_status(byte: u8) -> Value {
+        json!({
+            "signature": signature(byte),
+            "slot": 100,
+            "err": null,
+            "memo": null,
+            "blockTime": 1_700_000_000,
+            "confirmationStatus": "finalized",
+        })
+    }
+
+    /// `payer` sending 1 SOL to `to` with a 5000-lamport fee
+    fn transfer(byte: u8, payer: &Pubkey, to: &Pubkey, err: Value) -> Value {
+        json!({
+            "slot": 100,
+            "blockTime": 1_700_000_000,
+            "transaction": {
+                "signatures": [signature(byte)],
+                "message": {
+                    "header": {
+                        "numRequiredSignatures": 1,
+                        "numReadonlySignedAccounts": 0,
+                        "numReadonlyUnsignedAccounts": 1,
+                    },
+                    "accountKeys": [payer.to_string(), to.to_string(), Pubkey::default().to_string()],
+                    "recentBlockhash": Pubkey::default().to_string(),
+                    "instructions": [],
+                },
+            },
+            "meta": {
+                "err": err,
+                "status": {"Ok": null},
+                "fee": 5_000,
+                "preBalances": [3_000_000_000u64, 0, 1],
+                "postBalances": [1_999_995_000u64, 1_000_000_000, 1],
+            },
+        })
+    }
+
+    fn rpc_result(result: Value) -> ResponseTemplate {
+        ResponseTemplate::new(200)
+            .set_body_json(json!({"jsonrpc": "2.0", "id": 1, "result": result}))
+    }
+
+    #[tokio::test]
+    async fn test_transactions_page() {
+        let server = MockServer::start().await;
+        let (payer, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        Mock::given(method("POST"))
+            .and(body_partial_json(
+                json!({"method": "getSignaturesForAddress"}),
+            ))
+            .respond_with(rpc_result(json!([signature_status(1)])))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({"method": "getTransaction"})))
+            .respond_with(rpc_result(transfer(1, &payer, &to, Value::Null)))
+            .mount(&server)
+            .await;
+        let client = SolanaClient::new(&server.uri()).await.unwrap();
+
+        let page = client
+            .transactions(&payer.to_string(), None, 1)
+            .await
+            .unwrap();
+        let record = &page.records[0];
+        assert_eq!(record.hash.as_str(), signature(1));
+        assert_eq!(record.direction, TxDirection::Outgoing);
+        assert_eq!(record.counterparty, Some(to.to_string()));
+        assert_eq!(record.amount.smallest_unit(), 1_000_000_000);
+        assert_eq!(record.fee.unwrap().smallest_unit(), 5_000);
+        assert_eq!(record.status, TransactionStatus::Confirmed);
+        assert_eq!(record.timestamp, Some(1_700_000_000));
+        // A full page points at its last signature
+        assert_eq!(page.next_cursor, Some(signature(1)));
+
+        let page = client
+            .transactions(&to.to_string(), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(page.records[0].direction, TxDirection::Incoming);
+        assert_eq!(page.records[0].counterparty, Some(payer.to_string()));
+        assert_eq!(page.records[0].fee, None);
+        assert_eq!(page.next_cursor, None);
+    }
+
+    #[test]
+    fn test_failed_transaction_only_costs_the_fee() {
+        let (payer, to) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut tx = transfer(
+            2,
+            &payer,
+            &to,
+            json!({"InstructionError": [0, "InsufficientFunds"]}),
+        );
+        tx["meta"]["postBalances"] = json!([2_999_995_000u64, 0, 1]);
+        let tx: EncodedConfirmedTransactionWithStatusMeta = serde_json::from_value(tx).unwrap();
+        let status: RpcConfirmedTransactionStatusWithSignature =
+            serde_json::from_value(signature_status(2)).unwrap();
+
+        let record = record(&payer.to_string(), &status, &tx).unwrap();
+        assert_eq!(record.status, TransactionStatus::Failed);
+        assert_eq!(record.direction, TxDirection::SelfTransfer);
+        assert_eq!(record.amount.smallest_unit(), 0);
+        assert_eq!(record.fee.unwrap().smallest_unit(), 5_000);
+        assert!(super::record(&Pubkey::new_unique().to_string(), &status, &tx).is_none());
+    }
+}
@@ -3,6 +3,10 @@
 //pub use crate::solanaclient as SolanaClient;
 pub mod solana_account;
 pub mod solana_client;
+mod history;
+pub use history::MAX_SIGNATURES_PER_PAGE;
+mod traits_impl;
+pub use traits_impl::{ConnectedSolanaWallet, MAX_TRANSFERS_PER_TX};
 //use solana_sdk::bpf_loader::id as bpf_loader_id;
 
 /// An ERC20-like Token program for the Solana blockchain
@@ -52,6 +52,11 @@ impl SolanaAccount {
         self.keypair.pubkey()
     }
 
+    /// Returns the keypair that signs for the account.
+    pub(crate) fn keypair(&self) -> &Keypair {
+        &self.keypair
+    }
+
     /// Retrieves the account's balance in lamports using the provided `RpcClient`.
     ///
     /// # Errors
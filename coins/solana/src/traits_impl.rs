@@ -0,0 +1,250 @@
+//! Implementation of walletd-traits for SolanaAccount
+
+use async_trait::async_trait;
+use solana_sdk::{hash::Hash, message::Message, pubkey::Pubkey, transaction::Transaction};
+use solana_system_interface::instruction as system_instruction;
+use std::str::FromStr;
+use walletd_traits::{
+    Amount, BatchTransferReport, BatchTransferable, Network, SolanaTxParams, TransactionBuilder,
+    Transferable, TxHash, Wallet, WalletError, WalletResult,
+};
+
+use crate::solana_account::SolanaAccount;
+use crate::solana_client::SolanaClient;
+
+/// Most SOL transfers that fit in one transaction's 1232-byte packet
+pub const MAX_TRANSFERS_PER_TX: usize = 20;
+
+/// Base fee of a transaction with one signature, in lamports
+const LAMPORTS_PER_SIGNATURE: u128 = 5_000;
+
+/// Wrapper that holds a SolanaAccount with an RPC client for trait implementations
+pub struct ConnectedSolanaWallet {
+    /// The account that signs and pays
+    pub account: SolanaAccount,
+    /// RPC client
+    pub client: SolanaClient,
+    /// Network info
+    network: Network,
+}
+
+impl ConnectedSolanaWallet {
+    /// Creates a new connected wallet
+    pub fn new(account: SolanaAccount, client: SolanaClient, network: Network) -> Self {
+        Self {
+            account,
+            client,
+            network,
+        }
+    }
+
+    /// Builds and signs one transaction with a system transfer instruction
+    /// per recipient
+    pub fn batch_transaction(
+        &self,
+        transfers: &[(String, Amount)],
+        recent_blockhash: Hash,
+    ) -> WalletResult<Transaction> {
+        if transfers.is_empty() {
+            return Err(WalletError::Other("no transfers to send".to_string()));
+        }
+        if transfers.len() > MAX_TRANSFERS_PER_TX {
+            return Err(WalletError::NotSupported(format!(
+                "{} transfers exceed the {} that fit in one transaction",
+                transfers.len(),
+                MAX_TRANSFERS_PER_TX
+            )));
+        }
+        let from = self.account.pubkey();
+        let instructions = transfers
+            .iter()
+            .map(|(to, amount)| {
+                let to =
+                    Pubkey::from_str(to).map_err(|_| WalletError::InvalidAddress(to.clone()))?;
+                let lamports = u64::try_from(amount.smallest_unit())
+                    .map_err(|_| WalletError::Other(format!("{} lamports overflow", amount)))?;
+                Ok(system_instruction::transfer(&from, &to, lamports))
+            })
+            .collect::<WalletResult<Vec<_>>>()?;
+        let message = Message::new(&instructions, Some(&from));
+        Ok(Transaction::new(
+            &[self.account.keypair()],
+            message,
+            recent_blockhash,
+        ))
+    }
+
+    /// Builds, signs and submits one transaction paying every recipient
+    async fn send_batch(&self, transfers: &[(String, Amount)]) -> WalletResult<TxHash> {
+        let blockhash = self
+            .client
+            .rpc_client()
+            .get_latest_blockhash()
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+        let tx = self.batch_transaction(transfers, blockhash)?;
+        let signature = self
+            .client
+            .rpc_client()
+            .send_and_confirm_transaction(&tx)
+            .await
+            .map_err(|e| WalletError::TransactionFailed(e.to_string()))?;
+        Ok(TxHash::new(signature.to_string()))
+    }
+}
+
+#[async_trait]
+impl Wallet for ConnectedSolanaWallet {
+    fn address(&self) -> String {
+        self.account.pubkey().to_string()
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        let lamports = self
+            .client
+            .get_balance(&self.account.pubkey())
+            .await
+            .map_err(|e| WalletError::NetworkError(e.to_string()))?;
+        Ok(Amount::from_smallest_unit(lamports as u128, 9))
+    }
+
+    fn network(&self) -> &Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "SOL"
+    }
+
+    fn decimals(&self) -> u8 {
+        9
+    }
+}
+
+#[async_trait]
+impl Transferable for ConnectedSolanaWallet {
+    type TxParams = SolanaTxParams;
+
+    async fn transfer_with(&self, tx: TransactionBuilder<SolanaTxParams>) -> WalletResult<TxHash> {
+        // Only plain SOL transfers are wired up; refuse options we would silently drop
+        if tx.data.is_some() || tx.nonce.is_some() || tx.params != SolanaTxParams::default() {
+            return Err(WalletError::NotSupported(
+                "custom data, nonce, compute budget and memo options are not supported yet"
+                    .to_string(),
+            ));
+        }
+        let to = tx
+            .to
+            .ok_or_else(|| WalletError::InvalidAddress(String::new()))?;
+        let amount = tx
+            .amount
+            .ok_or_else(|| WalletError::Other("missing amount".to_string()))?;
+        self.send_batch(&[(to, amount)]).await
+    }
+
+    async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+        // One signature, no priority fee
+        Ok(Amount::from_smallest_unit(LAMPORTS_PER_SIGNATURE, 9))
+    }
+}
+
+/// Batches go out as one transaction with a transfer instruction per
+/// recipient, so they land or fail together
+#[async_trait]
+impl BatchTransferable for ConnectedSolanaWallet {
+    fn supports_native_batch(&self) -> bool {
+        true
+    }
+
+    async fn transfer_batch(
+        &self,
+        transfers: &[(String, Amount)],
+    ) -> WalletResult<BatchTransferReport> {
+        let outcome = self.send_batch(transfers).await;
+        Ok(BatchTransferReport::single_tx(transfers, outcome))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_sdk::signature::{Keypair, Signer};
+
+    async fn wallet() -> ConnectedSolanaWallet {
+        let account = SolanaAccount::new_from_bytes(Keypair::new().to_bytes()).unwrap();
+        let client = SolanaClient::new("https://api.devnet.solana.com")
+            .await
+            .unwrap();
+        ConnectedSolanaWallet::new(account, client, Network::testnet("solana-devnet"))
+    }
+
+    fn sol(lamports: u128) -> Amount {
+        Amount::from_smallest_unit(lamports, 9)
+    }
+
+    #[tokio::test]
+    async fn test_batch_transaction_has_instruction_per_recipient() {
+        let wallet = wallet().await;
+        let recipients: Vec<Pubkey> = (0..3).map(|_| Keypair::new().pubkey()).collect();
+        let transfers: Vec<_> = recipients
+            .iter()
+            .zip([1_000u128, 2_000, 3_000])
+            .map(|(to, lamports)| (to.to_string(), sol(lamports)))
+            .collect();
+
+        let tx = wallet
+            .batch_transaction(&transfers, Hash::new_unique())
+            .unwrap();
+        assert!(tx.verify().is_ok());
+        assert_eq!(tx.signatures.len(), 1);
+        assert_eq!(tx.message.account_keys[0], wallet.account.pubkey());
+
+        let from = wallet.account.pubkey();
+        let expected = [
+            system_instruction::transfer(&from, &recipients[0], 1_000),
+            system_instruction::transfer(&from, &recipients[1], 2_000),
+            system_instruction::transfer(&from, &recipients[2], 3_000),
+        ];
+        assert_eq!(tx.message.instructions.len(), 3);
+        for (compiled, expected) in tx.message.instructions.iter().zip(&expected) {
+            assert_eq!(compiled.data, expected.data);
+            let keys = &tx.message.account_keys;
+            assert_eq!(
+                keys[compiled.program_id_index as usize],
+                expected.program_id
+            );
+            assert_eq!(
+                keys[compiled.accounts[1] as usize],
+                expected.accounts[1].pubkey
+            );
+        }
+        assert!(wire_len(&tx) <= 1232);
+        assert!(wallet.supports_native_batch());
+    }
+
+    #[tokio::test]
+    async fn test_batch_transaction_limits() {
+        let wallet = wallet().await;
+        let full: Vec<_> = (0..MAX_TRANSFERS_PER_TX)
+            .map(|_| (Keypair::new().pubkey().to_string(), sol(1)))
+            .collect();
+        let tx = wallet.batch_transaction(&full, Hash::new_unique()).unwrap();
+        assert!(wire_len(&tx) <= 1232);
+
+        let mut over = full.clone();
+        over.push((Keypair::new().pubkey().to_string(), sol(1)));
+        assert!(matches!(
+            wallet.batch_transaction(&over, Hash::new_unique()),
+            Err(WalletError::NotSupported(_))
+        ));
+        assert!(matches!(
+            wallet.batch_transaction(&[("not-a-key".to_string(), sol(1))], Hash::new_unique()),
+            Err(WalletError::InvalidAddress(_))
+        ));
+    }
+
+    /// Wire size: signatures and message with compact-u16 length prefixes
+    fn wire_len(tx: &Transaction) -> usize {
+        1 + 64 * tx.signatures.len() + tx.message_data().len()
+    }
+}
@@ -25,18 +25,21 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
-use blake2::{Blake2b, Digest};
 use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
 
 /// Type alias for Blake2b with 256-bit output
 type Blake2b256 = Blake2b<U32>;
-use ed25519_dalek::{SigningKey, VerifyingKey, Signature, Signer};
+use ed25519_dalek::{Signature, Signer, SigningKey, VerifyingKey};
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
 
 // Re-export traits
+use walletd_core::SecretBytes;
+use walletd_error::WalletdError;
 pub use walletd_traits::WalletError;
+use walletd_traits::{keystore, KeyFormat, SignatureScheme as KeyScheme};
 
 /// SUI-specific errors
 #[derive(Error, Debug)]
@@ -54,8 +57,13 @@ pub enum SuiError {
     InvalidPrivateKey(String),
 
     /// Invalid address format
-    #[error("Invalid address: {0}")]
-    InvalidAddress(String),
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress {
+        /// The address as given
+        address: String,
+        /// Why it was rejected
+        reason: String,
+    },
 
     /// Signing error
     #[error("Signing failed: {0}")]
@@ -72,13 +80,37 @@ pub enum SuiError {
 
 impl From<SuiError> for WalletError {
     fn from(e: SuiError) -> Self {
-        WalletError::Other(e.to_string())
+        match e {
+            SuiError::InvalidAddress { address, .. } => WalletError::InvalidAddress(address),
+            SuiError::Network(m) => WalletError::NetworkError(m),
+            SuiError::InvalidMnemonic(_)
+            | SuiError::KeyDerivation(_)
+            | SuiError::InvalidPrivateKey(_)
+            | SuiError::SigningError(_) => WalletError::KeyError(e.to_string()),
+            SuiError::Serialization(_) => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<SuiError> for WalletdError {
+    fn from(e: SuiError) -> Self {
+        let error = match e {
+            SuiError::InvalidMnemonic(m) => WalletdError::InvalidMnemonic(m),
+            SuiError::KeyDerivation(m) => WalletdError::KeyDerivationError(m),
+            SuiError::InvalidPrivateKey(m) => WalletdError::InvalidPrivateKey(m),
+            SuiError::InvalidAddress { address, reason } => {
+                WalletdError::InvalidAddress { address, reason }
+            }
+            SuiError::SigningError(m) => WalletdError::SigningError(m),
+            SuiError::Serialization(m) => WalletdError::FormatError(m),
+            SuiError::Network(m) => WalletdError::NetworkError(m),
+        };
+        error.on_chain("sui")
     }
 }
 
 /// SUI network configuration
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
-#[derive(Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
 pub enum SuiNetwork {
     /// SUI Mainnet
     #[default]
@@ -122,7 +154,6 @@ impl SuiNetwork {
     }
 }
 
-
 impl fmt::Display for SuiNetwork {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -221,14 +252,17 @@ impl SuiAddress {
 
     /// Creates an address from a hex string
     pub fn from_hex(hex_str: &str) -> Result<Self, SuiError> {
+        let invalid = |reason: String| SuiError::InvalidAddress {
+            address: hex_str.to_string(),
+            reason,
+        };
         let hex_str = hex_str.trim_start_matches("0x");
         if hex_str.len() != 64 {
-            return Err(SuiError::InvalidAddress(
+            return Err(invalid(
                 "Address must be 64 hex characters".to_string(),
             ));
         }
-        let bytes = hex::decode(hex_str)
-            .map_err(|e| SuiError::InvalidAddress(e.to_string()))?;
+        let bytes = hex::decode(hex_str).map_err(|e| invalid(e.to_string()))?;
         let mut arr = [0u8; 32];
         arr.copy_from_slice(&bytes);
         Ok(Self(arr))
@@ -247,7 +281,7 @@ impl SuiAddress {
         hasher.update([0x00]); // Ed25519 flag
         hasher.update(pubkey.as_bytes());
         let hash = hasher.finalize();
-        
+
         let mut addr = [0u8; 32];
         addr.copy_from_slice(&hash[..32]);
         Self(addr)
@@ -269,7 +303,7 @@ impl std::str::FromStr for SuiAddress {
 
 /// SUI wallet
 pub struct SuiWallet {
-    signing_key: SigningKey,
+    secret_key: SecretBytes,
     verifying_key: VerifyingKey,
     address: SuiAddress,
     network: SuiNetwork,
@@ -287,7 +321,7 @@ impl SuiWallet {
         let address = SuiAddress::from_ed25519_pubkey(&verifying_key);
 
         Self {
-            signing_key,
+            secret_key: SecretBytes::from_slice(signing_key.as_bytes()),
             verifying_key,
             address,
             network,
@@ -307,7 +341,7 @@ impl SuiWallet {
         account: u32,
         address_index: u32,
     ) -> Result<Self, SuiError> {
-        use bip39::{Mnemonic, Language, Seed};
+        use bip39::{Language, Mnemonic, Seed};
 
         let mnemonic = Mnemonic::from_phrase(mnemonic, Language::English)
             .map_err(|e| SuiError::InvalidMnemonic(e.to_string()))?;
@@ -321,7 +355,7 @@ impl SuiWallet {
             44 | 0x80000000,              // purpose (hardened)
             Self::COIN_TYPE | 0x80000000, // coin type (hardened)
             account | 0x80000000,         // account (hardened)
-            0x80000000,               // change (hardened)
+            0x80000000,                   // change (hardened)
             address_index | 0x80000000,   // address index (hardened)
         ];
 
@@ -338,15 +372,12 @@ impl SuiWallet {
             ));
         }
 
-        let mut key_bytes = [0u8; 32];
-        key_bytes.copy_from_slice(bytes);
-
-        let signing_key = SigningKey::from_bytes(&key_bytes);
-        let verifying_key = signing_key.verifying_key();
+        let secret_key = SecretBytes::from_slice(bytes);
+        let verifying_key = Self::signing_key_of(&secret_key).verifying_key();
         let address = SuiAddress::from_ed25519_pubkey(&verifying_key);
 
         Ok(Self {
-            signing_key,
+            secret_key,
             verifying_key,
             address,
             network,
@@ -356,8 +387,7 @@ impl SuiWallet {
     /// Creates a wallet from a hex-encoded private key
     pub fn from_private_key_hex(hex_str: &str, network: SuiNetwork) -> Result<Self, SuiError> {
         let hex_str = hex_str.trim_start_matches("0x");
-        let bytes = hex::decode(hex_str)
-            .map_err(|e| SuiError::InvalidPrivateKey(e.to_string()))?;
+        let bytes = hex::decode(hex_str).map_err(|e| SuiError::InvalidPrivateKey(e.to_string()))?;
         Self::from_private_key_bytes(&bytes, network)
     }
 
@@ -381,21 +411,28 @@ impl SuiWallet {
         hex::encode(self.verifying_key.as_bytes())
     }
 
+    /// Returns the private key
+    ///
+    /// See [`SecretBytes`](walletd_core::SecretBytes#handle-with-care).
+    pub fn secret_key(&self) -> &SecretBytes {
+        &self.secret_key
+    }
+
     /// Returns the private key bytes
-    /// ⚠️ Handle with care!
+    #[deprecated(note = "use `secret_key`")]
     pub fn private_key(&self) -> &[u8; 32] {
-        self.signing_key.as_bytes()
+        self.key_bytes()
     }
 
     /// Returns the private key as hex
     /// ⚠️ Handle with care!
     pub fn private_key_hex(&self) -> String {
-        hex::encode(self.signing_key.as_bytes())
+        hex::encode(self.key_bytes())
     }
 
     /// Signs arbitrary data
     pub fn sign(&self, data: &[u8]) -> Signature {
-        self.signing_key.sign(data)
+        self.signing_key().sign(data)
     }
 
     /// Signs data and returns the signature as bytes
@@ -417,7 +454,7 @@ impl SuiWallet {
         let digest = hasher.finalize();
 
         // Sign the digest
-        let signature = self.signing_key.sign(&digest);
+        let signature = self.signing_key().sign(&digest);
 
         Ok(SuiSignature {
             scheme: SignatureScheme::Ed25519,
@@ -430,10 +467,68 @@ impl SuiWallet {
     pub fn to_keystore(&self) -> String {
         // SUI keystore format: base64(flag || private_key || public_key)
         let mut bytes = vec![0x00]; // Ed25519 flag
-        bytes.extend_from_slice(self.signing_key.as_bytes());
+        bytes.extend_from_slice(self.key_bytes());
         bytes.extend_from_slice(self.verifying_key.as_bytes());
         base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes)
     }
+
+    /// Imports a SUI keystore entry (either `to_keystore` layout)
+    pub fn from_keystore(entry: &str, network: SuiNetwork) -> Result<Self, SuiError> {
+        let (scheme, secret) = keystore::decode_sui_keystore(entry)
+            .map_err(|e| SuiError::InvalidPrivateKey(e.to_string()))?;
+        if scheme != KeyScheme::Ed25519 {
+            return Err(SuiError::InvalidPrivateKey(format!(
+                "unsupported key scheme {}",
+                scheme
+            )));
+        }
+        Self::from_private_key_bytes(&secret, network)
+    }
+
+    /// Exports the keystore entry encrypted with `password` (keystore v3 JSON)
+    pub fn export_encrypted(&self, password: &str) -> Result<String, SuiError> {
+        let entry = keystore::encode_sui_keystore(KeyScheme::Ed25519, self.key_bytes())
+            .map_err(|e| SuiError::Serialization(e.to_string()))?;
+        keystore::encrypt_key(
+            KeyFormat::SuiKeystore,
+            entry.as_bytes(),
+            password,
+            Some(&self.address.to_hex()),
+        )
+        .map_err(|e| SuiError::Serialization(e.to_string()))
+    }
+
+    /// Restores a wallet from [`export_encrypted`](Self::export_encrypted) output
+    pub fn from_encrypted(
+        json: &str,
+        password: &str,
+        network: SuiNetwork,
+    ) -> Result<Self, SuiError> {
+        let key = keystore::decrypt_key(json, password)
+            .map_err(|e| SuiError::InvalidPrivateKey(e.to_string()))?;
+        if key.format != KeyFormat::SuiKeystore {
+            return Err(SuiError::InvalidPrivateKey(format!(
+                "expected a SUI keystore entry, found {}",
+                key.format
+            )));
+        }
+        let entry = std::str::from_utf8(key.payload.expose_secret())
+            .map_err(|e| SuiError::InvalidPrivateKey(e.to_string()))?;
+        Self::from_keystore(entry, network)
+    }
+
+    fn key_bytes(&self) -> &[u8; 32] {
+        self.secret_key.as_array().expect("32-byte key")
+    }
+
+    /// Short-lived signing key; zeroized when dropped
+    fn signing_key(&self) -> SigningKey {
+        Self::signing_key_of(&self.secret_key)
+    }
+
+    fn signing_key_of(secret_key: &SecretBytes) -> SigningKey {
+        SigningKey::from_bytes(secret_key.as_array().expect("32-byte key"))
+    }
 }
 
 impl fmt::Debug for SuiWallet {
@@ -518,10 +613,10 @@ mod tests {
     fn test_sui_amount_arithmetic() {
         let a = SuiAmount::from_sui(1.0);
         let b = SuiAmount::from_sui(0.5);
-        
+
         let sum = a + b;
         assert!((sum.sui() - 1.5).abs() < 0.0001);
-        
+
         let diff = a - b;
         assert!((diff.sui() - 0.5).abs() < 0.0001);
     }
@@ -530,7 +625,7 @@ mod tests {
     fn test_sui_amount_checked_ops() {
         let a = SuiAmount::from_mist(100);
         let b = SuiAmount::from_mist(50);
-        
+
         assert!(a.checked_add(b).is_some());
         assert!(a.checked_sub(b).is_some());
         assert!(b.checked_sub(a).is_none());
@@ -575,12 +670,12 @@ mod tests {
     #[test]
     fn test_sui_wallet_from_mnemonic() {
         let wallet = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
-        
+
         // Address should be deterministic
         let addr = wallet.address().to_hex();
         assert!(addr.starts_with("0x"));
         assert_eq!(addr.len(), 66);
-        
+
         // Same mnemonic should produce same address
         let wallet2 = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
         assert_eq!(wallet.address(), wallet2.address());
@@ -588,10 +683,13 @@ mod tests {
 
     #[test]
     fn test_sui_wallet_different_accounts() {
-        let wallet0 = SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 0, 0).unwrap();
-        let wallet1 = SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 0, 1).unwrap();
-        let wallet2 = SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 1, 0).unwrap();
-        
+        let wallet0 =
+            SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 0, 0).unwrap();
+        let wallet1 =
+            SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 0, 1).unwrap();
+        let wallet2 =
+            SuiWallet::from_mnemonic_with_path(TEST_MNEMONIC, SuiNetwork::Mainnet, 1, 0).unwrap();
+
         // Different indices should produce different addresses
         assert_ne!(wallet0.address(), wallet1.address());
         assert_ne!(wallet0.address(), wallet2.address());
@@ -602,7 +700,7 @@ mod tests {
     fn test_sui_wallet_from_private_key() {
         let wallet1 = SuiWallet::new(SuiNetwork::Testnet);
         let private_key = wallet1.private_key_hex();
-        
+
         let wallet2 = SuiWallet::from_private_key_hex(&private_key, SuiNetwork::Testnet).unwrap();
         assert_eq!(wallet1.address(), wallet2.address());
     }
@@ -610,10 +708,10 @@ mod tests {
     #[test]
     fn test_sui_wallet_sign() {
         let wallet = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
-        
+
         let message = b"Hello, SUI!";
         let signature = wallet.sign(message);
-        
+
         // Verify signature
         use ed25519_dalek::Verifier;
         assert!(wallet.verifying_key.verify(message, &signature).is_ok());
@@ -622,10 +720,10 @@ mod tests {
     #[test]
     fn test_sui_wallet_sign_transaction() {
         let wallet = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
-        
+
         let tx_bytes = vec![1, 2, 3, 4, 5];
         let sig = wallet.sign_transaction(&tx_bytes).unwrap();
-        
+
         assert_eq!(sig.scheme, SignatureScheme::Ed25519);
         assert_eq!(sig.signature.len(), 64);
         assert_eq!(sig.public_key.len(), 32);
@@ -635,11 +733,11 @@ mod tests {
     fn test_sui_signature_serialization() {
         let wallet = SuiWallet::new(SuiNetwork::Testnet);
         let sig = wallet.sign_transaction(&[1, 2, 3]).unwrap();
-        
+
         let bytes = sig.to_bytes();
         assert_eq!(bytes.len(), 1 + 64 + 32); // flag + sig + pubkey
         assert_eq!(bytes[0], 0x00); // Ed25519 flag
-        
+
         let base64 = sig.to_base64();
         assert!(!base64.is_empty());
     }
@@ -648,16 +746,36 @@ mod tests {
     fn test_sui_wallet_keystore_export() {
         let wallet = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
         let keystore = wallet.to_keystore();
-        
+
         // Should be valid base64
         let decoded = base64::Engine::decode(&base64::engine::general_purpose::STANDARD, &keystore);
         assert!(decoded.is_ok());
-        
+
         let bytes = decoded.unwrap();
         assert_eq!(bytes.len(), 1 + 32 + 32); // flag + private + public
         assert_eq!(bytes[0], 0x00); // Ed25519 flag
     }
 
+    #[test]
+    fn test_sui_wallet_encrypted_import() {
+        let wallet = SuiWallet::from_mnemonic(TEST_MNEMONIC, SuiNetwork::Mainnet).unwrap();
+
+        let legacy = SuiWallet::from_keystore(&wallet.to_keystore(), SuiNetwork::Mainnet).unwrap();
+        assert_eq!(legacy.address(), wallet.address());
+
+        let entry = keystore::encode_sui_keystore(
+            KeyScheme::Ed25519,
+            wallet.secret_key().as_array().unwrap(),
+        )
+        .unwrap();
+        let json =
+            keystore::encrypt_key_with(KeyFormat::SuiKeystore, entry.as_bytes(), "pw", None, 10)
+                .unwrap();
+        let restored = SuiWallet::from_encrypted(&json, "pw", SuiNetwork::Mainnet).unwrap();
+        assert_eq!(restored.address(), wallet.address());
+        assert!(SuiWallet::from_encrypted(&json, "wrong", SuiNetwork::Mainnet).is_err());
+    }
+
     #[test]
     fn test_sui_wallet_invalid_mnemonic() {
         let result = SuiWallet::from_mnemonic("invalid mnemonic", SuiNetwork::Mainnet);
@@ -33,10 +33,11 @@ use serde::{Deserialize, Serialize};
 use sha2::{Sha256, Sha512, Digest};
 use std::fmt;
 use thiserror::Error;
-use zeroize::Zeroize;
 
 // Re-export traits
 pub use walletd_traits::WalletError;
+use walletd_core::SecretBytes;
+use walletd_error::WalletdError;
 
 /// TON-specific errors
 #[derive(Error, Debug)]
@@ -54,8 +55,13 @@ pub enum TonError {
     InvalidPrivateKey(String),
 
     /// Invalid address format
-    #[error("Invalid address: {0}")]
-    InvalidAddress(String),
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress {
+        /// The address as given
+        address: String,
+        /// Why it was rejected
+        reason: String,
+    },
 
     /// Signing error
     #[error("Signing failed: {0}")]
@@ -68,7 +74,30 @@ pub enum TonError {
 
 impl From<TonError> for WalletError {
     fn from(e: TonError) -> Self {
-        WalletError::Other(e.to_string())
+        match e {
+            TonError::InvalidAddress { address, .. } => WalletError::InvalidAddress(address),
+            TonError::InvalidMnemonic(_)
+            | TonError::KeyDerivation(_)
+            | TonError::InvalidPrivateKey(_)
+            | TonError::SigningError(_) => WalletError::KeyError(e.to_string()),
+            TonError::Serialization(_) => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<TonError> for WalletdError {
+    fn from(e: TonError) -> Self {
+        let error = match e {
+            TonError::InvalidMnemonic(m) => WalletdError::InvalidMnemonic(m),
+            TonError::KeyDerivation(m) => WalletdError::KeyDerivationError(m),
+            TonError::InvalidPrivateKey(m) => WalletdError::InvalidPrivateKey(m),
+            TonError::InvalidAddress { address, reason } => {
+                WalletdError::InvalidAddress { address, reason }
+            }
+            TonError::SigningError(m) => WalletdError::SigningError(m),
+            TonError::Serialization(m) => WalletdError::FormatError(m),
+        };
+        error.on_chain("ton")
     }
 }
 
@@ -226,22 +255,26 @@ impl TonAddress {
 
     /// Creates an address from raw format (workchain:hash)
     pub fn from_raw(raw: &str) -> Result<Self, TonError> {
+        let invalid = |reason: String| TonError::InvalidAddress {
+            address: raw.to_string(),
+            reason,
+        };
         let parts: Vec<&str> = raw.split(':').collect();
         if parts.len() != 2 {
-            return Err(TonError::InvalidAddress("Expected format: workchain:hash".to_string()));
+            return Err(invalid("Expected format: workchain:hash".to_string()));
         }
 
         let workchain = parts[0]
             .parse::<i8>()
-            .map_err(|_| TonError::InvalidAddress("Invalid workchain".to_string()))?;
+            .map_err(|_| invalid("Invalid workchain".to_string()))?;
 
         let hash_hex = parts[1];
         if hash_hex.len() != 64 {
-            return Err(TonError::InvalidAddress("Hash must be 64 hex chars".to_string()));
+            return Err(invalid("Hash must be 64 hex chars".to_string()));
         }
 
         let hash_bytes = hex::decode(hash_hex)
-            .map_err(|e| TonError::InvalidAddress(e.to_string()))?;
+            .map_err(|e| invalid(e.to_string()))?;
         
         let mut hash = [0u8; 32];
         hash.copy_from_slice(&hash_bytes);
@@ -251,16 +284,20 @@ impl TonAddress {
 
     /// Creates an address from user-friendly base64 format
     pub fn from_friendly(addr: &str) -> Result<Self, TonError> {
+        let invalid = |reason: String| TonError::InvalidAddress {
+            address: addr.to_string(),
+            reason,
+        };
         // Handle both standard base64 and URL-safe base64
         let addr = addr.replace('-', "+").replace('_', "/");
         
         let bytes = base64::Engine::decode(
             &base64::engine::general_purpose::STANDARD,
             &addr,
-        ).map_err(|e| TonError::InvalidAddress(e.to_string()))?;
+        ).map_err(|e| invalid(e.to_string()))?;
 
         if bytes.len() != 36 {
-            return Err(TonError::InvalidAddress("Invalid address length".to_string()));
+            return Err(invalid("Invalid address length".to_string()));
         }
 
         // Verify checksum
@@ -271,7 +308,7 @@ impl TonAddress {
         let expected_crc = ((checksum[0] as u16) << 8) | (checksum[1] as u16);
         
         if calculated_crc != expected_crc {
-            return Err(TonError::InvalidAddress("Invalid checksum".to_string()));
+            return Err(invalid("Invalid checksum".to_string()));
         }
 
         // Parse flags
@@ -349,7 +386,7 @@ const DEFAULT_WALLET_ID: u32 = 698983191;
 
 /// TON wallet
 pub struct TonWallet {
-    signing_key: SigningKey,
+    secret_key: SecretBytes,
     verifying_key: VerifyingKey,
     address: TonAddress,
     network: TonNetwork,
@@ -366,7 +403,7 @@ impl TonWallet {
         let address = Self::derive_address(&verifying_key, wallet_id, 0);
 
         Self {
-            signing_key,
+            secret_key: SecretBytes::from_slice(signing_key.as_bytes()),
             verifying_key,
             address,
             network,
@@ -447,20 +484,13 @@ impl TonWallet {
             ));
         }
 
-        let mut key_bytes = [0u8; 32];
-        key_bytes.copy_from_slice(bytes);
-
-        let signing_key = SigningKey::from_bytes(&key_bytes);
-        
-        // Zeroize the temporary key bytes
-        key_bytes.zeroize();
-        
-        let verifying_key = signing_key.verifying_key();
+        let secret_key = SecretBytes::from_slice(bytes);
+        let verifying_key = Self::signing_key_of(&secret_key).verifying_key();
         let wallet_id = DEFAULT_WALLET_ID;
         let address = Self::derive_address(&verifying_key, wallet_id, 0);
 
         Ok(Self {
-            signing_key,
+            secret_key,
             verifying_key,
             address,
             network,
@@ -535,21 +565,28 @@ impl TonWallet {
         hex::encode(self.verifying_key.as_bytes())
     }
 
+    /// Returns the private key
+    ///
+    /// See [`SecretBytes`](walletd_core::SecretBytes#handle-with-care).
+    pub fn secret_key(&self) -> &SecretBytes {
+        &self.secret_key
+    }
+
     /// Returns the private key bytes
-    /// ⚠️ Handle with care!
+    #[deprecated(note = "use `secret_key`")]
     pub fn private_key(&self) -> &[u8; 32] {
-        self.signing_key.as_bytes()
+        self.key_bytes()
     }
 
     /// Returns the private key as hex
     /// ⚠️ Handle with care!
     pub fn private_key_hex(&self) -> String {
-        hex::encode(self.signing_key.as_bytes())
+        hex::encode(self.key_bytes())
     }
 
     /// Signs arbitrary data
     pub fn sign(&self, data: &[u8]) -> Signature {
-        self.signing_key.sign(data)
+        self.signing_key().sign(data)
     }
 
     /// Signs data and returns the signature as bytes
@@ -564,7 +601,7 @@ impl TonWallet {
     /// - seqno (4 bytes) - sequence number
     /// - internal messages
     pub fn sign_message(&self, message: &[u8]) -> TonSignature {
-        let signature = self.signing_key.sign(message);
+        let signature = self.signing_key().sign(message);
 
         TonSignature {
             signature: signature.to_bytes().to_vec(),
@@ -584,6 +621,19 @@ impl TonWallet {
         body.extend_from_slice(&seqno.to_be_bytes());
         body
     }
+
+    fn key_bytes(&self) -> &[u8; 32] {
+        self.secret_key.as_array().expect("32-byte key")
+    }
+
+    /// Short-lived signing key; zeroized when dropped
+    fn signing_key(&self) -> SigningKey {
+        Self::signing_key_of(&self.secret_key)
+    }
+
+    fn signing_key_of(secret_key: &SecretBytes) -> SigningKey {
+        SigningKey::from_bytes(secret_key.as_array().expect("32-byte key"))
+    }
 }
 
 impl fmt::Debug for TonWallet {
@@ -633,6 +683,22 @@ mod tests {
     // Test mnemonic (24 words from BIP-39)
     const TEST_MNEMONIC: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon art";
 
+    #[test]
+    fn test_error_conversions() {
+        let err = TonWallet::from_mnemonic("abandon about", TonNetwork::Mainnet).unwrap_err();
+        assert!(matches!(WalletError::from(err), WalletError::KeyError(_)));
+
+        let err: WalletdError = TonAddress::from_raw("0:zz").unwrap_err().into();
+        assert_eq!(err.chain(), Some("ton"));
+        assert_eq!(err.code(), walletd_error::ErrorCode::InvalidAddress);
+        assert!(!err.is_retryable());
+        assert!(matches!(
+            err,
+            WalletdError::Chain { error, .. }
+                if matches!(&*error, WalletdError::InvalidAddress { address, .. } if address == "0:zz")
+        ));
+    }
+
     #[test]
     fn test_ton_amount_from_nano() {
         let amount = TonAmount::from_nano(1_000_000_000);
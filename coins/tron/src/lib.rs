@@ -4,12 +4,14 @@
 
 use anyhow::Result;
 use bip39::Mnemonic;
-use secp256k1::{Secp256k1, SecretKey, PublicKey};
+use secp256k1::PublicKey;
 use sha2::{Sha256, Digest};
 use sha3::Keccak256;
 use serde::{Deserialize, Serialize};
 use std::str::FromStr;
 use thiserror::Error;
+use walletd_traits::{keystore, KeyFormat, Secp256k1Signer, SignatureScheme, Signer};
+use walletd_error::WalletdError;
 
 // ============================================================================
 // ERRORS
@@ -33,6 +35,25 @@ pub enum TronError {
     Other(#[from] anyhow::Error),
 }
 
+impl From<TronError> for WalletdError {
+    fn from(e: TronError) -> Self {
+        let error = match e {
+            TronError::InvalidAddress(address) => WalletdError::InvalidAddress {
+                address,
+                reason: "not a valid Tron address".to_string(),
+            },
+            TronError::KeyError(m) => WalletdError::InvalidPrivateKey(m),
+            TronError::TransactionError(m) => WalletdError::TransactionFailed(m),
+            TronError::NetworkError(m) => WalletdError::NetworkError(m),
+            TronError::InsufficientBandwidth | TronError::InsufficientEnergy => {
+                WalletdError::TransactionFailed(e.to_string())
+            }
+            TronError::Other(e) => WalletdError::Other(e.to_string()),
+        };
+        error.on_chain("tron")
+    }
+}
+
 // ============================================================================
 // CONFIG
 // ============================================================================
@@ -103,7 +124,7 @@ impl NetworkConfig {
 // ============================================================================
 
 pub struct TronWallet {
-    secret_key: SecretKey,
+    signer: Box<dyn Signer>,
     public_key: PublicKey,
     config: NetworkConfig,
     api_key: Option<String>,
@@ -111,21 +132,11 @@ pub struct TronWallet {
 
 impl TronWallet {
     pub fn new(config: NetworkConfig) -> Result<Self> {
-        let secp = Secp256k1::new();
-        
         // Generate random 32-byte key
         let mut key_bytes = [0u8; 32];
         rand::RngCore::fill_bytes(&mut rand::thread_rng(), &mut key_bytes);
-        
-        let secret_key = SecretKey::from_slice(&key_bytes)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
 
-        Ok(Self {
-            secret_key,
-            public_key,
-            config,
-            api_key: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn mainnet() -> Result<Self> {
@@ -144,25 +155,23 @@ impl TronWallet {
         let mut key_bytes = [0u8; 32];
         key_bytes.copy_from_slice(&seed[..32]);
 
-        let secp = Secp256k1::new();
-        let secret_key = SecretKey::from_slice(&key_bytes)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
-
-        Ok(Self {
-            secret_key,
-            public_key,
-            config,
-            api_key: None,
-        })
+        Self::from_private_key(&key_bytes, config)
     }
 
     pub fn from_private_key(key: &[u8], config: NetworkConfig) -> Result<Self> {
-        let secp = Secp256k1::new();
-        let secret_key = SecretKey::from_slice(key)?;
-        let public_key = PublicKey::from_secret_key(&secp, &secret_key);
+        Self::from_signer(Box::new(Secp256k1Signer::from_slice(key)?), config)
+    }
+
+    /// Creates a wallet backed by any secp256k1 signer (hardware, remote, HSM)
+    pub fn from_signer(signer: Box<dyn Signer>, config: NetworkConfig) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            anyhow::bail!("Tron requires a secp256k1 signer, got {}", signer.scheme());
+        }
+        let public_key = PublicKey::from_slice(&signer.public_key())
+            .map_err(|e| TronError::KeyError(e.to_string()))?;
 
         Ok(Self {
-            secret_key,
+            signer,
             public_key,
             config,
             api_key: None,
@@ -181,26 +190,7 @@ impl TronWallet {
 
     /// Get Tron address (base58check encoded, starts with T)
     pub fn address(&self) -> String {
-        // Get uncompressed public key (65 bytes)
-        let pubkey_uncompressed = self.public_key.serialize_uncompressed();
-        
-        // Keccak256 hash of public key (skip first byte - 0x04 prefix)
-        let hash = Keccak256::digest(&pubkey_uncompressed[1..]);
-        
-        // Take last 20 bytes and add prefix
-        let mut address_bytes = vec![TRON_ADDRESS_PREFIX];
-        address_bytes.extend_from_slice(&hash[12..]);
-        
-        // Double SHA256 for checksum
-        let hash1 = Sha256::digest(&address_bytes);
-        let hash2 = Sha256::digest(&hash1);
-        let checksum = &hash2[..4];
-        
-        // Append checksum
-        address_bytes.extend_from_slice(checksum);
-        
-        // Base58 encode
-        bs58::encode(address_bytes).into_string()
+        base58_address(&self.public_key)
     }
 
     /// Get hex address (without base58 encoding)
@@ -218,8 +208,33 @@ impl TronWallet {
         hex::encode(self.public_key.serialize())
     }
 
-    pub fn private_key(&self) -> String {
-        format!("0x{}", hex::encode(self.secret_key.secret_bytes()))
+    /// Fails when the key is held by an external signer
+    pub fn private_key(&self) -> Result<String> {
+        let secret = self
+            .signer
+            .secret_key()
+            .ok_or_else(|| anyhow::anyhow!("private key is held by an external signer"))?;
+        Ok(format!("0x{}", hex::encode(secret)))
+    }
+
+    /// Exports the key as an Ethereum-compatible keystore v3 JSON file
+    pub fn export_encrypted(&self, password: &str) -> Result<String> {
+        let secret = self
+            .signer
+            .secret_key()
+            .ok_or_else(|| anyhow::anyhow!("private key is held by an external signer"))?;
+        // Keystore addresses are the 20-byte account ID without Tron's 0x41 prefix
+        let address = self.hex_address();
+        Ok(keystore::encrypt_key(KeyFormat::Web3Keystore, &secret, password, Some(&address[2..]))?)
+    }
+
+    /// Restores a wallet from a keystore v3 JSON file
+    pub fn from_encrypted(json: &str, password: &str, config: NetworkConfig) -> Result<Self> {
+        let key = keystore::decrypt_key(json, password)?;
+        if key.format != KeyFormat::Web3Keystore {
+            anyhow::bail!("expected a web3 keystore, found {}", key.format);
+        }
+        Self::from_private_key(key.payload.expose_secret(), config)
     }
 
     pub fn config(&self) -> &NetworkConfig {
@@ -243,12 +258,9 @@ impl TronWallet {
         Ok(NetworkConfig::sun_to_trx(sun))
     }
 
-    pub fn sign(&self, message: &[u8]) -> Vec<u8> {
-        let secp = Secp256k1::new();
-        let hash = Keccak256::digest(message);
-        let msg = secp256k1::Message::from_slice(&hash).unwrap();
-        let sig = secp.sign_ecdsa(&msg, &self.secret_key);
-        sig.serialize_compact().to_vec()
+    pub async fn sign(&self, message: &[u8]) -> Result<Vec<u8>> {
+        let hash: [u8; 32] = Keccak256::digest(message).into();
+        Ok(self.signer.sign_hash(&hash).await?)
     }
 
     /// Validate a Tron address
@@ -272,12 +284,142 @@ impl TronWallet {
         let checksum = &decoded[21..];
         
         let hash1 = Sha256::digest(address_bytes);
-        let hash2 = Sha256::digest(&hash1);
+        let hash2 = Sha256::digest(hash1);
         
         &hash2[..4] == checksum
     }
+
+    /// Returns a read-only view of this wallet without the private key
+    pub fn watch_only(&self) -> TronWatchOnlyWallet {
+        let mut watch = TronWatchOnlyWallet::new(self.address(), Some(self.public_key), self.config.clone());
+        watch.api_key = self.api_key.clone();
+        watch
+    }
+}
+
+/// Base58check Tron address of a secp256k1 public key
+fn base58_address(public_key: &PublicKey) -> String {
+    // Get uncompressed public key (65 bytes)
+    let pubkey_uncompressed = public_key.serialize_uncompressed();
+
+    // Keccak256 hash of public key (skip first byte - 0x04 prefix)
+    let hash = Keccak256::digest(&pubkey_uncompressed[1..]);
+
+    // Take last 20 bytes and add prefix
+    let mut address_bytes = vec![TRON_ADDRESS_PREFIX];
+    address_bytes.extend_from_slice(&hash[12..]);
+
+    // Double SHA256 for checksum
+    let hash1 = Sha256::digest(&address_bytes);
+    let hash2 = Sha256::digest(hash1);
+    let checksum = &hash2[..4];
+
+    // Append checksum
+    address_bytes.extend_from_slice(checksum);
+
+    // Base58 encode
+    bs58::encode(address_bytes).into_string()
 }
 
+// ============================================================================
+// WATCH-ONLY WALLET
+// ============================================================================
+
+/// Read-only Tron wallet for monitoring an address without its private key
+pub struct TronWatchOnlyWallet {
+    address: String,
+    public_key: Option<PublicKey>,
+    config: NetworkConfig,
+    api_key: Option<String>,
+    network: walletd_traits::Network,
+}
+
+impl TronWatchOnlyWallet {
+    fn new(address: String, public_key: Option<PublicKey>, config: NetworkConfig) -> Self {
+        let network = if config.is_mainnet {
+            walletd_traits::Network::mainnet(&config.name)
+        } else {
+            walletd_traits::Network::testnet(&config.name)
+        };
+        Self {
+            address,
+            public_key,
+            config,
+            api_key: None,
+            network,
+        }
+    }
+
+    /// Watches a base58check (T...) address
+    pub fn from_address(address: &str, config: NetworkConfig) -> Result<Self> {
+        if !TronWallet::validate_address(address) {
+            return Err(TronError::InvalidAddress(address.to_string()).into());
+        }
+        Ok(Self::new(address.to_string(), None, config))
+    }
+
+    /// Watches the address of a compressed or uncompressed secp256k1 public key
+    pub fn from_pubkey(pubkey: &[u8], config: NetworkConfig) -> Result<Self> {
+        let public_key = PublicKey::from_slice(pubkey)
+            .map_err(|e| TronError::KeyError(e.to_string()))?;
+        Ok(Self::new(base58_address(&public_key), Some(public_key), config))
+    }
+
+    pub fn set_api_key(&mut self, api_key: &str) {
+        self.api_key = Some(api_key.to_string());
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Compressed public key as hex, if known
+    pub fn public_key(&self) -> Option<String> {
+        self.public_key.map(|key| hex::encode(key.serialize()))
+    }
+
+    pub fn config(&self) -> &NetworkConfig {
+        &self.config
+    }
+
+    pub async fn get_balance(&self) -> Result<u64> {
+        if self.api_key.is_none() {
+            return Ok(0);
+        }
+        // Would query TronGrid API
+        Ok(0)
+    }
+}
+
+#[async_trait::async_trait]
+impl walletd_traits::Wallet for TronWatchOnlyWallet {
+    fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    async fn balance(&self) -> walletd_traits::WalletResult<walletd_traits::Amount> {
+        let sun = self
+            .get_balance()
+            .await
+            .map_err(|e| walletd_traits::WalletError::NetworkError(e.to_string()))?;
+        Ok(walletd_traits::Amount::from_smallest_unit(sun as u128, 6))
+    }
+
+    fn network(&self) -> &walletd_traits::Network {
+        &self.network
+    }
+
+    fn currency_symbol(&self) -> &str {
+        "TRX"
+    }
+
+    fn decimals(&self) -> u8 {
+        6
+    }
+}
+
+impl walletd_traits::WatchOnly for TronWatchOnlyWallet {}
+
 // ============================================================================
 // TESTS
 // ============================================================================
@@ -352,18 +494,43 @@ mod tests {
     #[test]
     fn test_private_key_format() {
         let wallet = TronWallet::mainnet().unwrap();
-        let pk = wallet.private_key();
+        let pk = wallet.private_key().unwrap();
         assert!(pk.starts_with("0x"));
         assert_eq!(pk.len(), 66);
     }
 
-    #[test]
-    fn test_sign_message() {
+    #[tokio::test]
+    async fn test_sign_message() {
         let wallet = TronWallet::mainnet().unwrap();
-        let sig = wallet.sign(b"Hello Tron!");
+        let sig = wallet.sign(b"Hello Tron!").await.unwrap();
         assert_eq!(sig.len(), 64);
     }
 
+    #[test]
+    fn test_from_encrypted() {
+        let key = [6u8; 32];
+        let json = keystore::encrypt_key_with(KeyFormat::Web3Keystore, &key, "pw", None, 10).unwrap();
+        let wallet = TronWallet::from_encrypted(&json, "pw", NetworkConfig::mainnet()).unwrap();
+        assert_eq!(wallet.private_key().unwrap(), format!("0x{}", "06".repeat(32)));
+        assert!(TronWallet::from_encrypted(&json, "wrong", NetworkConfig::mainnet()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_from_signer() {
+        let key = [3u8; 32];
+        let wallet = TronWallet::from_signer(Box::new(Secp256k1Signer::from_slice(&key).unwrap()), NetworkConfig::mainnet()).unwrap();
+        assert_eq!(wallet.address(), TronWallet::from_private_key(&key, NetworkConfig::mainnet()).unwrap().address());
+
+        let sig = wallet.sign(b"Hello Tron!").await.unwrap();
+        let hash = Keccak256::digest(b"Hello Tron!");
+        let msg = secp256k1::Message::from_slice(&hash).unwrap();
+        let sig = secp256k1::ecdsa::Signature::from_compact(&sig).unwrap();
+        assert!(secp256k1::Secp256k1::verification_only().verify_ecdsa(&msg, &sig, &wallet.public_key).is_ok());
+
+        let ed = walletd_traits::Ed25519Signer::from_bytes(&key);
+        assert!(TronWallet::from_signer(Box::new(ed), NetworkConfig::mainnet()).is_err());
+    }
+
     #[test]
     fn test_config_mainnet() {
         let config = NetworkConfig::mainnet();
@@ -403,4 +570,28 @@ mod tests {
         let wallet = TronWallet::from_private_key_hex(key, NetworkConfig::mainnet()).unwrap();
         assert!(wallet.address().starts_with('T'));
     }
+
+    #[test]
+    fn test_watch_only_from_pubkey() {
+        let wallet = TronWallet::from_mnemonic(TEST_MNEMONIC, NetworkConfig::mainnet()).unwrap();
+        let pubkey = hex::decode(wallet.public_key()).unwrap();
+        let watch = TronWatchOnlyWallet::from_pubkey(&pubkey, NetworkConfig::mainnet()).unwrap();
+        assert_eq!(watch.address(), wallet.address());
+        assert_eq!(watch.public_key(), Some(wallet.public_key()));
+        assert_eq!(wallet.watch_only().address(), wallet.address());
+        assert!(TronWatchOnlyWallet::from_pubkey(&[4u8; 12], NetworkConfig::mainnet()).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_watch_only_from_address() {
+        use walletd_traits::Wallet;
+
+        let address = TronWallet::mainnet().unwrap().address();
+        let watch = TronWatchOnlyWallet::from_address(&address, NetworkConfig::testnet()).unwrap();
+        assert_eq!(Wallet::address(&watch), address);
+        assert!(watch.public_key().is_none());
+        assert_eq!(watch.balance().await.unwrap().decimals, 6);
+        assert!(watch.network().is_testnet);
+        assert!(TronWatchOnlyWallet::from_address("TInvalid", NetworkConfig::mainnet()).is_err());
+    }
 }
@@ -0,0 +1,214 @@
+//! Cosmos payloads: `SignDoc` for `SIGN_MODE_DIRECT`
+//!
+//! The signature covers the SHA-256 of the encoded `SignDoc`; the signed
+//! payload is a `TxRaw` ready for `/cosmos/tx/v1beta1/txs`.
+
+use crate::{AirgapError, Result};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// A protobuf field value
+enum Field<'a> {
+    Varint,
+    Bytes(&'a [u8]),
+}
+
+/// Reads the top-level fields of a protobuf message
+fn fields(mut data: &[u8]) -> Result<Vec<(u64, Field<'_>)>> {
+    let mut out = Vec::new();
+    while !data.is_empty() {
+        let key = read_varint(&mut data)?;
+        let field = match key & 7 {
+            0 => {
+                read_varint(&mut data)?;
+                Field::Varint
+            }
+            2 => {
+                let len =
+                    usize::try_from(read_varint(&mut data)?).map_err(|_| invalid("bad length"))?;
+                if data.len() < len {
+                    return Err(invalid("truncated field"));
+                }
+                let (bytes, rest) = data.split_at(len);
+                data = rest;
+                Field::Bytes(bytes)
+            }
+            wire => return Err(invalid(&format!("unexpected wire type {}", wire))),
+        };
+        out.push((key >> 3, field));
+    }
+    Ok(out)
+}
+
+fn read_varint(data: &mut &[u8]) -> Result<u64> {
+    let mut value = 0u64;
+    for (i, byte) in data.iter().take(10).enumerate() {
+        value |= u64::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            *data = &data[i + 1..];
+            return Ok(value);
+        }
+    }
+    Err(invalid("bad varint"))
+}
+
+fn bytes_field(tag: u64, bytes: &[u8]) -> Vec<u8> {
+    let mut out = varint(tag << 3 | 2);
+    out.extend(varint(bytes.len() as u64));
+    out.extend_from_slice(bytes);
+    out
+}
+
+fn varint(mut value: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    while value >= 0x80 {
+        out.push(value as u8 | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+    out
+}
+
+/// The parts of a `SignDoc` needed to build a `TxRaw`
+struct SignDoc<'a> {
+    body: &'a [u8],
+    auth_info: &'a [u8],
+}
+
+fn parse(payload: &[u8]) -> Result<SignDoc<'_>> {
+    let (mut body, mut auth_info, mut chain_id) = (None, None, None);
+    for (tag, field) in fields(payload)? {
+        match (tag, field) {
+            (1, Field::Bytes(bytes)) => body = Some(bytes),
+            (2, Field::Bytes(bytes)) => auth_info = Some(bytes),
+            (3, Field::Bytes(bytes)) => chain_id = Some(bytes),
+            (4, Field::Varint) => {}
+            _ => return Err(invalid("unexpected SignDoc field")),
+        }
+    }
+    match (body, auth_info, chain_id) {
+        (Some(body), Some(auth_info), Some(chain_id)) if !chain_id.is_empty() => {
+            Ok(SignDoc { body, auth_info })
+        }
+        _ => Err(invalid("SignDoc needs body, auth info and chain id")),
+    }
+}
+
+/// The first length-delimited field with `tag`
+fn find_bytes(data: &[u8], tag: u64) -> Result<Option<&[u8]>> {
+    Ok(fields(data)?
+        .into_iter()
+        .find_map(|(t, field)| match field {
+            Field::Bytes(bytes) if t == tag => Some(bytes),
+            _ => None,
+        }))
+}
+
+/// Public keys of the `AuthInfo` signer infos, in order
+fn signer_keys(auth_info: &[u8]) -> Result<Vec<Vec<u8>>> {
+    let mut keys = Vec::new();
+    for (tag, field) in fields(auth_info)? {
+        let (1, Field::Bytes(signer_info)) = (tag, field) else {
+            continue;
+        };
+        // SignerInfo.public_key (Any) -> Any.value (PubKey) -> PubKey.key
+        let any = find_bytes(signer_info, 1)?;
+        let value = any.map(|any| find_bytes(any, 2)).transpose()?.flatten();
+        let key = value
+            .map(|value| find_bytes(value, 1))
+            .transpose()?
+            .flatten();
+        keys.push(key.unwrap_or_default().to_vec());
+    }
+    Ok(keys)
+}
+
+pub(crate) fn validate(payload: &[u8]) -> Result<()> {
+    parse(payload).map(|_| ())
+}
+
+pub(crate) async fn sign(payload: &[u8], signer: &dyn Signer) -> Result<(Vec<u8>, bool)> {
+    let doc = parse(payload)?;
+    if signer.scheme() != SignatureScheme::Secp256k1 {
+        return Err(AirgapError::NothingToSign(
+            "Cosmos signing needs a secp256k1 key".into(),
+        ));
+    }
+    match signer_keys(doc.auth_info)?.as_slice() {
+        [key] if *key == signer.public_key() => {}
+        [_] | [] => {
+            return Err(AirgapError::NothingToSign(
+                "the signer's key is not in the auth info".into(),
+            ))
+        }
+        _ => {
+            return Err(AirgapError::NotSupported(
+                "multi-signer Cosmos transactions".into(),
+            ))
+        }
+    }
+
+    // Secp256k1 signers hash the message with SHA-256, as Cosmos expects
+    let signature = signer.sign_message(payload).await?;
+    let mut tx = bytes_field(1, doc.body);
+    tx.extend(bytes_field(2, doc.auth_info));
+    tx.extend(bytes_field(3, &signature));
+    Ok((tx, true))
+}
+
+fn invalid(reason: &str) -> AirgapError {
+    AirgapError::Format(format!("Cosmos SignDoc: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sha2::{Digest, Sha256};
+    use walletd_traits::Secp256k1Signer;
+
+    fn sign_doc(public_key: &[u8]) -> (Vec<u8>, Vec<u8>, Vec<u8>) {
+        let body = bytes_field(2, b"memo");
+        let pub_key = bytes_field(1, public_key);
+        let any = [
+            bytes_field(1, b"/cosmos.crypto.secp256k1.PubKey"),
+            bytes_field(2, &pub_key),
+        ]
+        .concat();
+        let auth_info = bytes_field(1, &bytes_field(1, &any));
+        let mut doc = bytes_field(1, &body);
+        doc.extend(bytes_field(2, &auth_info));
+        doc.extend(bytes_field(3, b"cosmoshub-4"));
+        doc.extend([4 << 3, 42]);
+        (doc, body, auth_info)
+    }
+
+    #[tokio::test]
+    async fn test_sign_doc_to_tx_raw() {
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        let (doc, body, auth_info) = sign_doc(&signer.public_key());
+        let (tx, complete) = sign(&doc, &signer).await.unwrap();
+        assert!(complete);
+
+        let parsed = fields(&tx).unwrap();
+        assert!(matches!(parsed[0], (1, Field::Bytes(b)) if b == body.as_slice()));
+        assert!(matches!(parsed[1], (2, Field::Bytes(a)) if a == auth_info.as_slice()));
+        let (3, Field::Bytes(sig)) = parsed[2] else {
+            panic!("missing signature");
+        };
+        let hash: [u8; 32] = Sha256::digest(&doc).into();
+        let sig = secp256k1::ecdsa::Signature::from_compact(sig).unwrap();
+        let pk = secp256k1::PublicKey::from_slice(&signer.public_key()).unwrap();
+        let msg = secp256k1::Message::from_slice(&hash).unwrap();
+        assert!(secp256k1::SECP256K1.verify_ecdsa(&msg, &sig, &pk).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_foreign_key_and_bad_doc() {
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        let (doc, _, _) = sign_doc(&[2u8; 33]);
+        assert!(matches!(
+            sign(&doc, &signer).await,
+            Err(AirgapError::NothingToSign(_))
+        ));
+        assert!(validate(&bytes_field(1, b"body")).is_err());
+    }
+}
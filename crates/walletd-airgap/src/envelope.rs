@@ -0,0 +1,374 @@
+//! The unsigned and signed transaction envelopes
+
+use crate::{cosmos, evm, psbt, solana, AirgapError, Result};
+use ciborium::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use walletd_qr::Ur;
+use walletd_traits::{Chain, Signer};
+
+/// UR type of an [`UnsignedTransaction`]
+pub const UNSIGNED_UR_TYPE: &str = "walletd-unsigned-tx";
+
+/// UR type of a [`SignedTransaction`]
+pub const SIGNED_UR_TYPE: &str = "walletd-signed-tx";
+
+/// A transaction waiting for an offline signature
+///
+/// `hash` is the SHA-256 of the payload; both screens can show it so the
+/// user can confirm the offline device received what the host sent.
+/// Metadata is display hints from the host and is not signed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnsignedTransaction {
+    chain: Chain,
+    payload: Vec<u8>,
+    metadata: BTreeMap<String, String>,
+    hash: [u8; 32],
+}
+
+impl UnsignedTransaction {
+    /// Wraps a payload in the chain's unsigned format, checking that it
+    /// parses
+    pub fn new(chain: Chain, payload: Vec<u8>) -> Result<Self> {
+        validate(chain, &payload)?;
+        Ok(Self {
+            chain,
+            hash: Sha256::digest(&payload).into(),
+            payload,
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    /// Adds a display hint (e.g. `to`, `amount`, `fee`)
+    pub fn with_metadata(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata.insert(key.into(), value.into());
+        self
+    }
+
+    /// The chain
+    pub fn chain(&self) -> Chain {
+        self.chain
+    }
+
+    /// The unsigned payload
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Display hints
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// SHA-256 of the payload
+    pub fn hash(&self) -> [u8; 32] {
+        self.hash
+    }
+
+    /// Signs the payload with `signer`
+    pub async fn sign(&self, signer: &dyn Signer) -> Result<SignedTransaction> {
+        let (payload, complete) = match self.chain {
+            Chain::Ethereum => evm::sign(&self.payload, signer).await?,
+            Chain::Bitcoin => psbt::sign(&self.payload, signer).await?,
+            Chain::Solana => solana::sign(&self.payload, signer).await?,
+            Chain::Cosmos => cosmos::sign(&self.payload, signer).await?,
+            chain => return Err(unsupported(chain)),
+        };
+        Ok(SignedTransaction {
+            chain: self.chain,
+            payload,
+            request_hash: self.hash,
+            complete,
+        })
+    }
+
+    /// Encodes as a CBOR map: `{1: chain, 2: payload, 3: metadata, 4: hash}`
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        let metadata = self
+            .metadata
+            .iter()
+            .map(|(k, v)| (Value::Text(k.clone()), Value::Text(v.clone())))
+            .collect();
+        encode(vec![
+            (1, Value::Text(self.chain.to_string())),
+            (2, Value::Bytes(self.payload.clone())),
+            (3, Value::Map(metadata)),
+            (4, Value::Bytes(self.hash.to_vec())),
+        ])
+    }
+
+    /// Decodes and checks an envelope written by [`to_cbor`](Self::to_cbor)
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let mut map = decode(bytes)?;
+        let chain = take_chain(&mut map)?;
+        let payload = take_bytes(&mut map, 2)?;
+        let metadata = match map.remove(&3) {
+            None => BTreeMap::new(),
+            Some(Value::Map(entries)) => entries
+                .into_iter()
+                .map(|entry| match entry {
+                    (Value::Text(k), Value::Text(v)) => Ok((k, v)),
+                    _ => Err(format_error("metadata must map text to text")),
+                })
+                .collect::<Result<_>>()?,
+            Some(_) => return Err(format_error("metadata must be a map")),
+        };
+        let hash = take_bytes(&mut map, 4)?;
+
+        let tx = Self::new(chain, payload)?;
+        if hash != tx.hash {
+            return Err(AirgapError::HashMismatch(
+                "payload does not match the envelope hash".into(),
+            ));
+        }
+        Ok(Self { metadata, ..tx })
+    }
+
+    /// Wraps the CBOR in a `walletd-unsigned-tx` UR
+    pub fn to_ur(&self) -> Result<Ur> {
+        Ok(Ur::new(UNSIGNED_UR_TYPE, self.to_cbor()?)?)
+    }
+
+    /// Reads a `walletd-unsigned-tx` UR
+    pub fn from_ur(ur: &Ur) -> Result<Self> {
+        check_ur_type(ur, UNSIGNED_UR_TYPE)?;
+        Self::from_cbor(ur.cbor())
+    }
+}
+
+/// The offline signer's answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTransaction {
+    chain: Chain,
+    payload: Vec<u8>,
+    request_hash: [u8; 32],
+    complete: bool,
+}
+
+impl SignedTransaction {
+    /// The chain
+    pub fn chain(&self) -> Chain {
+        self.chain
+    }
+
+    /// The signed payload: a broadcastable transaction when
+    /// [`is_complete`](Self::is_complete), otherwise a partially signed
+    /// one for the next signer
+    pub fn payload(&self) -> &[u8] {
+        &self.payload
+    }
+
+    /// Hash of the [`UnsignedTransaction`] this answers
+    pub fn request_hash(&self) -> [u8; 32] {
+        self.request_hash
+    }
+
+    /// Whether every required signature is present
+    pub fn is_complete(&self) -> bool {
+        self.complete
+    }
+
+    /// Checks that this answers `request`
+    pub fn check_request(&self, request: &UnsignedTransaction) -> Result<()> {
+        if self.chain != request.chain || self.request_hash != request.hash {
+            return Err(AirgapError::HashMismatch(
+                "signed transaction answers a different request".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Encodes as a CBOR map: `{1: chain, 2: payload, 4: request hash,
+    /// 5: complete}`
+    pub fn to_cbor(&self) -> Result<Vec<u8>> {
+        encode(vec![
+            (1, Value::Text(self.chain.to_string())),
+            (2, Value::Bytes(self.payload.clone())),
+            (4, Value::Bytes(self.request_hash.to_vec())),
+            (5, Value::Bool(self.complete)),
+        ])
+    }
+
+    /// Decodes an envelope written by [`to_cbor`](Self::to_cbor)
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self> {
+        let mut map = decode(bytes)?;
+        let chain = take_chain(&mut map)?;
+        let payload = take_bytes(&mut map, 2)?;
+        let request_hash = take_bytes(&mut map, 4)?
+            .try_into()
+            .map_err(|_| format_error("request hash must be 32 bytes"))?;
+        let complete = match map.remove(&5) {
+            Some(Value::Bool(complete)) => complete,
+            _ => return Err(format_error("missing completion flag")),
+        };
+        Ok(Self {
+            chain,
+            payload,
+            request_hash,
+            complete,
+        })
+    }
+
+    /// Wraps the CBOR in a `walletd-signed-tx` UR
+    pub fn to_ur(&self) -> Result<Ur> {
+        Ok(Ur::new(SIGNED_UR_TYPE, self.to_cbor()?)?)
+    }
+
+    /// Reads a `walletd-signed-tx` UR
+    pub fn from_ur(ur: &Ur) -> Result<Self> {
+        check_ur_type(ur, SIGNED_UR_TYPE)?;
+        Self::from_cbor(ur.cbor())
+    }
+}
+
+fn validate(chain: Chain, payload: &[u8]) -> Result<()> {
+    match chain {
+        Chain::Ethereum => evm::validate(payload),
+        Chain::Bitcoin => psbt::validate(payload),
+        Chain::Solana => solana::validate(payload),
+        Chain::Cosmos => cosmos::validate(payload),
+        chain => Err(unsupported(chain)),
+    }
+}
+
+fn unsupported(chain: Chain) -> AirgapError {
+    AirgapError::NotSupported(format!("air-gapped signing for {}", chain))
+}
+
+fn format_error(reason: &str) -> AirgapError {
+    AirgapError::Format(reason.to_string())
+}
+
+fn check_ur_type(ur: &Ur, expected: &str) -> Result<()> {
+    if ur.ur_type() != expected {
+        return Err(AirgapError::Format(format!(
+            "expected a {} UR, got {}",
+            expected,
+            ur.ur_type()
+        )));
+    }
+    Ok(())
+}
+
+fn encode(entries: Vec<(u64, Value)>) -> Result<Vec<u8>> {
+    let map = Value::Map(
+        entries
+            .into_iter()
+            .map(|(k, v)| (Value::Integer(k.into()), v))
+            .collect(),
+    );
+    let mut out = Vec::new();
+    ciborium::into_writer(&map, &mut out).map_err(|e| AirgapError::Format(e.to_string()))?;
+    Ok(out)
+}
+
+fn decode(bytes: &[u8]) -> Result<BTreeMap<u64, Value>> {
+    let value: Value =
+        ciborium::from_reader(bytes).map_err(|e| AirgapError::Format(e.to_string()))?;
+    let Value::Map(entries) = value else {
+        return Err(format_error("envelope must be a CBOR map"));
+    };
+    entries
+        .into_iter()
+        .map(|(k, v)| {
+            let key = k
+                .as_integer()
+                .and_then(|k| u64::try_from(k).ok())
+                .ok_or_else(|| format_error("envelope keys must be unsigned integers"))?;
+            Ok((key, v))
+        })
+        .collect()
+}
+
+fn take_chain(map: &mut BTreeMap<u64, Value>) -> Result<Chain> {
+    match map.remove(&1) {
+        Some(Value::Text(chain)) => chain
+            .parse()
+            .map_err(|_| AirgapError::NotSupported(format!("unknown chain {}", chain))),
+        _ => Err(format_error("missing chain")),
+    }
+}
+
+fn take_bytes(map: &mut BTreeMap<u64, Value>, key: u64) -> Result<Vec<u8>> {
+    match map.remove(&key) {
+        Some(Value::Bytes(bytes)) => Ok(bytes),
+        _ => Err(AirgapError::Format(format!("missing byte string {}", key))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_qr::{UrDecoder, UrEncoder};
+    use walletd_traits::Secp256k1Signer;
+
+    // EIP-155 example transaction, unsigned
+    const EIP155_UNSIGNED: &str = "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080";
+
+    #[tokio::test]
+    async fn test_round_trip_over_animated_ur() {
+        let request =
+            UnsignedTransaction::new(Chain::Ethereum, hex::decode(EIP155_UNSIGNED).unwrap())
+                .unwrap()
+                .with_metadata("to", "0x3535353535353535353535353535353535353535")
+                .with_metadata("value", "1 ETH");
+
+        // Host to signer, in small frames
+        let mut encoder = UrEncoder::new(request.to_ur().unwrap(), 20).unwrap();
+        let mut decoder = UrDecoder::new();
+        while !decoder.is_complete() {
+            decoder.receive(&encoder.next_part()).unwrap();
+        }
+        let received = UnsignedTransaction::from_ur(decoder.result().unwrap()).unwrap();
+        assert_eq!(received, request);
+
+        // Signer to host
+        let signer = Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+        let signed = received.sign(&signer).await.unwrap();
+        let back = SignedTransaction::from_ur(&signed.to_ur().unwrap()).unwrap();
+        assert_eq!(back, signed);
+        assert!(back.is_complete());
+        back.check_request(&request).unwrap();
+
+        let other = UnsignedTransaction::new(
+            Chain::Ethereum,
+            hex::decode(EIP155_UNSIGNED.replace("09", "0a")).unwrap(),
+        )
+        .unwrap();
+        assert!(matches!(
+            back.check_request(&other),
+            Err(AirgapError::HashMismatch(_))
+        ));
+        assert!(matches!(
+            UnsignedTransaction::from_ur(&back.to_ur().unwrap()),
+            Err(AirgapError::Format(_))
+        ));
+    }
+
+    #[test]
+    fn test_rejects_tampering_and_unsupported_chains() {
+        let request =
+            UnsignedTransaction::new(Chain::Ethereum, hex::decode(EIP155_UNSIGNED).unwrap())
+                .unwrap();
+        let mut cbor = request.to_cbor().unwrap();
+        // Flip a byte inside the payload (the gas price)
+        let at = cbor
+            .windows(3)
+            .position(|w| w == [0x04, 0xa8, 0x17])
+            .unwrap();
+        cbor[at + 1] ^= 1;
+        assert!(matches!(
+            UnsignedTransaction::from_cbor(&cbor),
+            Err(AirgapError::HashMismatch(_))
+        ));
+
+        assert!(matches!(
+            UnsignedTransaction::new(Chain::Ton, vec![1, 2, 3]),
+            Err(AirgapError::NotSupported(_))
+        ));
+        assert!(matches!(
+            UnsignedTransaction::new(Chain::Ethereum, vec![0xc0]),
+            Err(AirgapError::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+//! EVM payloads: EIP-155 legacy RLP or EIP-2718 typed transactions
+//!
+//! The unsigned payload is exactly what gets hashed for signing:
+//! `rlp([nonce, gasPrice, gas, to, value, data, chainId, 0, 0])` or
+//! `type || rlp([chainId, ...])` for access-list (1) and EIP-1559 (2)
+//! transactions. Decoding and signing are done by [`walletd_traits::evm`].
+
+use crate::{AirgapError, Result};
+use walletd_traits::evm::{EvmError, TxRequest};
+use walletd_traits::{SignatureScheme, Signer};
+
+pub(crate) fn validate(payload: &[u8]) -> Result<()> {
+    TxRequest::decode(payload).map(|_| ()).map_err(Into::into)
+}
+
+pub(crate) async fn sign(payload: &[u8], signer: &dyn Signer) -> Result<(Vec<u8>, bool)> {
+    let tx = TxRequest::decode(payload)?;
+    if signer.scheme() != SignatureScheme::Secp256k1 {
+        return Err(AirgapError::NothingToSign(
+            "EVM signing needs a secp256k1 key".into(),
+        ));
+    }
+    Ok((tx.sign(signer).await?.raw, true))
+}
+
+impl From<EvmError> for AirgapError {
+    fn from(e: EvmError) -> Self {
+        match e {
+            EvmError::Invalid(reason) => {
+                AirgapError::Format(format!("EVM transaction: {}", reason))
+            }
+            EvmError::NotSupported(reason) => AirgapError::NotSupported(reason),
+            EvmError::Wallet(e) => AirgapError::Wallet(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::evm::Rlp;
+    use walletd_traits::Secp256k1Signer;
+
+    #[tokio::test]
+    async fn test_eip155_vector() {
+        // Example from EIP-155
+        let signer = Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+        let unsigned = hex::decode("ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080").unwrap();
+        let (raw, complete) = sign(&unsigned, &signer).await.unwrap();
+        assert!(complete);
+        assert_eq!(
+            hex::encode(raw),
+            "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_eip1559_signing() {
+        let signer = Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+        let fields = vec![
+            Rlp::uint(1),
+            Rlp::uint(0),
+            Rlp::uint(1_000_000_000),
+            Rlp::uint(30_000_000_000),
+            Rlp::uint(21_000),
+            Rlp::Bytes(vec![0x35; 20]),
+            Rlp::uint(1),
+            Rlp::Bytes(Vec::new()),
+            Rlp::List(Vec::new()),
+        ];
+        let unsigned = [vec![0x02], Rlp::List(fields.clone()).encode()].concat();
+        let (raw, _) = sign(&unsigned, &signer).await.unwrap();
+
+        assert_eq!(raw[0], 0x02);
+        let (Rlp::List(signed), _) = Rlp::decode(&raw[1..]).unwrap() else {
+            panic!("not a list");
+        };
+        assert_eq!(signed.len(), 12);
+        assert_eq!(signed[..9], fields[..]);
+        assert!(signed[9].as_uint().unwrap() <= 1);
+
+        assert!(matches!(
+            validate(&[0x03, 0xc0]),
+            Err(AirgapError::NotSupported(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rejects_hostile_payloads() {
+        let signer = Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+
+        // Legacy transaction with chainId = u64::MAX
+        let mut fields = vec![Rlp::uint(0); 6];
+        fields.extend([
+            Rlp::uint(u64::MAX.into()),
+            Rlp::Bytes(Vec::new()),
+            Rlp::Bytes(Vec::new()),
+        ]);
+        let unsigned = Rlp::List(fields).encode();
+        assert!(matches!(validate(&unsigned), Err(AirgapError::Format(_))));
+        assert!(matches!(
+            sign(&unsigned, &signer).await,
+            Err(AirgapError::Format(_))
+        ));
+
+        // Lists nested far past the depth limit fail instead of recursing
+        let deep = (0..64).fold(Rlp::List(Vec::new()), |inner, _| Rlp::List(vec![inner]));
+        assert!(matches!(
+            validate(&deep.encode()),
+            Err(AirgapError::Format(_))
+        ));
+    }
+}
@@ -0,0 +1,97 @@
+//! # WalletD Air-Gap
+//!
+//! Signing on a machine that never touches the network. The online host
+//! builds a transaction and exports it as an [`UnsignedTransaction`]; the
+//! offline walletd instance checks and signs it; the host imports the
+//! [`SignedTransaction`] and broadcasts it.
+//!
+//! Both envelopes encode as CBOR and travel as UR (BCR-2020-005), so they
+//! fit animated QR codes, files or USB sticks alike. The payload format
+//! follows the chain:
+//!
+//! | Chain | Unsigned payload | Signed payload |
+//! |-------|------------------|----------------|
+//! | Ethereum (any EVM) | EIP-155 RLP or EIP-2718 typed transaction | raw transaction |
+//! | Bitcoin | BIP-174 PSBT | raw transaction, or the PSBT if inputs remain unsigned |
+//! | Solana | legacy or v0 message | wire transaction |
+//! | Cosmos | `SignDoc` (`SIGN_MODE_DIRECT`) | `TxRaw` |
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_airgap::{SignedTransaction, UnsignedTransaction};
+//!
+//! // Online host
+//! let request = UnsignedTransaction::new(Chain::Ethereum, unsigned_rlp)?
+//!     .with_metadata("to", "0xfB69...d359");
+//! show_animated(UrEncoder::new(request.to_ur()?, 200)?);
+//!
+//! // Offline signer
+//! let request = UnsignedTransaction::from_ur(decoder.result().unwrap())?;
+//! let signed = request.sign(signer.as_ref()).await?;
+//! show_animated(UrEncoder::new(signed.to_ur()?, 200)?);
+//!
+//! // Online host again
+//! let signed = SignedTransaction::from_ur(&scanned)?;
+//! signed.check_request(&request)?;
+//! broadcast(signed.payload());
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod cosmos;
+pub mod envelope;
+mod evm;
+mod psbt;
+mod solana;
+
+pub use envelope::{SignedTransaction, UnsignedTransaction, SIGNED_UR_TYPE, UNSIGNED_UR_TYPE};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_qr::QrError;
+use walletd_traits::WalletError;
+
+/// Air-gapped signing errors
+#[derive(Error, Debug)]
+pub enum AirgapError {
+    /// A malformed envelope or transaction payload
+    #[error("Invalid format: {0}")]
+    Format(String),
+
+    /// The envelope hash does not match its payload, or a signed
+    /// transaction answers a different request
+    #[error("Hash mismatch: {0}")]
+    HashMismatch(String),
+
+    /// The signer's key does not sign anything in the transaction
+    #[error("Nothing to sign: {0}")]
+    NothingToSign(String),
+
+    /// The chain or transaction kind has no air-gapped format
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+
+    /// UR encoding failed
+    #[error(transparent)]
+    Qr(#[from] QrError),
+
+    /// The signer failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for air-gapped signing
+pub type Result<T> = std::result::Result<T, AirgapError>;
+
+impl From<AirgapError> for WalletdError {
+    fn from(e: AirgapError) -> Self {
+        match e {
+            AirgapError::Format(reason) => WalletdError::FormatError(reason),
+            AirgapError::NotSupported(reason) => WalletdError::NotSupported(reason),
+            AirgapError::Qr(e) => e.into(),
+            e => WalletdError::SigningError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,259 @@
+//! Bitcoin payloads: BIP-174 PSBTs
+//!
+//! Inputs paying to the signer's key as P2PKH, P2WPKH or P2SH-P2WPKH get a
+//! partial signature; other inputs are left alone. When every input is
+//! ours the PSBT is finalized and the signed payload is the raw
+//! transaction; otherwise it is the updated PSBT for the next signer.
+
+use crate::{AirgapError, Result};
+use bitcoin::hashes::Hash;
+use bitcoin::psbt::{Input, Psbt};
+use bitcoin::script::PushBytesBuf;
+use bitcoin::sighash::SighashCache;
+use bitcoin::{consensus, PublicKey, ScriptBuf, TxOut, Witness};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// How an input pays to the signer's key
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Spend {
+    P2pkh,
+    P2wpkh,
+    P2shP2wpkh,
+}
+
+pub(crate) fn validate(payload: &[u8]) -> Result<()> {
+    Psbt::deserialize(payload)
+        .map(|_| ())
+        .map_err(|e| AirgapError::Format(format!("PSBT: {}", e)))
+}
+
+pub(crate) async fn sign(payload: &[u8], signer: &dyn Signer) -> Result<(Vec<u8>, bool)> {
+    let mut psbt =
+        Psbt::deserialize(payload).map_err(|e| AirgapError::Format(format!("PSBT: {}", e)))?;
+    if signer.scheme() != SignatureScheme::Secp256k1 {
+        return Err(AirgapError::NothingToSign(
+            "Bitcoin signing needs a secp256k1 key".into(),
+        ));
+    }
+    let public_key = PublicKey::from_slice(&signer.public_key())
+        .map_err(|e| AirgapError::Format(e.to_string()))?;
+
+    let mut spends = Vec::with_capacity(psbt.inputs.len());
+    for index in 0..psbt.inputs.len() {
+        let Some((spend, prevout)) = classify(&psbt, index, &public_key)? else {
+            spends.push(None);
+            continue;
+        };
+        let input = &psbt.inputs[index];
+        let sighash_type = input
+            .ecdsa_hash_ty()
+            .map_err(|e| AirgapError::Format(e.to_string()))?;
+        let mut cache = SighashCache::new(&psbt.unsigned_tx);
+        let sighash = match spend {
+            Spend::P2pkh => cache
+                .legacy_signature_hash(index, &prevout.script_pubkey, sighash_type.to_u32())
+                .map(|h| h.to_byte_array()),
+            Spend::P2wpkh => cache
+                .p2wpkh_signature_hash(index, &prevout.script_pubkey, prevout.value, sighash_type)
+                .map(|h| h.to_byte_array()),
+            Spend::P2shP2wpkh => cache
+                .p2wpkh_signature_hash(index, &p2wpkh(&public_key)?, prevout.value, sighash_type)
+                .map(|h| h.to_byte_array()),
+        }
+        .map_err(|e| AirgapError::Format(e.to_string()))?;
+
+        let compact = signer.sign_hash(&sighash).await?;
+        let sig = bitcoin::secp256k1::ecdsa::Signature::from_compact(&compact)
+            .map_err(|e| AirgapError::Format(e.to_string()))?;
+        psbt.inputs[index].partial_sigs.insert(
+            public_key,
+            bitcoin::ecdsa::Signature {
+                sig,
+                hash_ty: sighash_type,
+            },
+        );
+        spends.push(Some(spend));
+    }
+
+    if spends.iter().all(Option::is_none) {
+        return Err(AirgapError::NothingToSign(
+            "no input pays to the signer's key".into(),
+        ));
+    }
+    if spends.iter().any(Option::is_none) {
+        return Ok((psbt.serialize(), false));
+    }
+
+    for (input, spend) in psbt.inputs.iter_mut().zip(spends.into_iter().flatten()) {
+        finalize(input, spend, &public_key)?;
+    }
+    let tx = psbt
+        .extract_tx()
+        .map_err(|e| AirgapError::Format(e.to_string()))?;
+    Ok((consensus::serialize(&tx), true))
+}
+
+/// Works out whether input `index` pays to `key`, and its previous output
+fn classify(psbt: &Psbt, index: usize, key: &PublicKey) -> Result<Option<(Spend, TxOut)>> {
+    let input = &psbt.inputs[index];
+    let prevout = match (&input.witness_utxo, &input.non_witness_utxo) {
+        (Some(utxo), _) => utxo.clone(),
+        (None, Some(tx)) => {
+            let vout = psbt.unsigned_tx.input[index].previous_output.vout as usize;
+            tx.output.get(vout).cloned().ok_or_else(|| {
+                AirgapError::Format(format!("input {} spends a missing output", index))
+            })?
+        }
+        (None, None) => return Ok(None),
+    };
+
+    let script = &prevout.script_pubkey;
+    let spend = if *script == ScriptBuf::new_p2pkh(&key.pubkey_hash()) {
+        Spend::P2pkh
+    } else if *script == p2wpkh(key)? {
+        Spend::P2wpkh
+    } else if input.redeem_script.as_ref() == Some(&p2wpkh(key)?)
+        && *script == ScriptBuf::new_p2sh(&p2wpkh(key)?.script_hash())
+    {
+        Spend::P2shP2wpkh
+    } else {
+        return Ok(None);
+    };
+    Ok(Some((spend, prevout)))
+}
+
+fn p2wpkh(key: &PublicKey) -> Result<ScriptBuf> {
+    let hash = key
+        .wpubkey_hash()
+        .ok_or_else(|| AirgapError::NotSupported("segwit needs a compressed key".into()))?;
+    Ok(ScriptBuf::new_p2wpkh(&hash))
+}
+
+fn finalize(input: &mut Input, spend: Spend, key: &PublicKey) -> Result<()> {
+    let sig = input.partial_sigs[key];
+    match spend {
+        Spend::P2pkh => {
+            let sig_bytes = PushBytesBuf::try_from(sig.to_vec())
+                .map_err(|e| AirgapError::Format(e.to_string()))?;
+            input.final_script_sig = Some(
+                ScriptBuf::builder()
+                    .push_slice(sig_bytes)
+                    .push_key(key)
+                    .into_script(),
+            );
+        }
+        Spend::P2wpkh | Spend::P2shP2wpkh => {
+            input.final_script_witness = Some(Witness::p2wpkh(&sig, &key.inner));
+            if spend == Spend::P2shP2wpkh {
+                let redeem = PushBytesBuf::try_from(p2wpkh(key)?.into_bytes())
+                    .map_err(|e| AirgapError::Format(e.to_string()))?;
+                input.final_script_sig =
+                    Some(ScriptBuf::builder().push_slice(redeem).into_script());
+            }
+        }
+    }
+    // BIP-174: the finalizer clears everything but the UTXOs
+    input.partial_sigs.clear();
+    input.sighash_type = None;
+    input.redeem_script = None;
+    input.bip32_derivation.clear();
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::secp256k1::{Message, Secp256k1};
+    use bitcoin::sighash::EcdsaSighashType;
+    use bitcoin::{Amount, OutPoint, Sequence, Transaction, TxIn, Txid};
+    use walletd_traits::Secp256k1Signer;
+
+    fn psbt(prevouts: &[TxOut]) -> Psbt {
+        let tx = Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: (0..prevouts.len())
+                .map(|i| TxIn {
+                    previous_output: OutPoint::new(Txid::all_zeros(), i as u32),
+                    sequence: Sequence::MAX,
+                    ..Default::default()
+                })
+                .collect(),
+            output: vec![TxOut {
+                value: Amount::from_sat(90_000),
+                script_pubkey: ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::all_zeros()),
+            }],
+        };
+        let mut psbt = Psbt::from_unsigned_tx(tx).unwrap();
+        for (input, prevout) in psbt.inputs.iter_mut().zip(prevouts) {
+            input.witness_utxo = Some(prevout.clone());
+        }
+        psbt
+    }
+
+    #[tokio::test]
+    async fn test_signs_and_finalizes_p2wpkh() {
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        let key = PublicKey::from_slice(&signer.public_key()).unwrap();
+        let prevout = TxOut {
+            value: Amount::from_sat(100_000),
+            script_pubkey: p2wpkh(&key).unwrap(),
+        };
+        let unsigned = psbt(std::slice::from_ref(&prevout));
+
+        let (raw, complete) = sign(&unsigned.serialize(), &signer).await.unwrap();
+        assert!(complete);
+        let tx: Transaction = consensus::deserialize(&raw).unwrap();
+        let witness: Vec<&[u8]> = tx.input[0].witness.iter().collect();
+        assert_eq!(witness.len(), 2);
+        assert_eq!(witness[1], signer.public_key().as_slice());
+
+        let sighash = SighashCache::new(&unsigned.unsigned_tx)
+            .p2wpkh_signature_hash(
+                0,
+                &prevout.script_pubkey,
+                prevout.value,
+                EcdsaSighashType::All,
+            )
+            .unwrap();
+        let sig = bitcoin::ecdsa::Signature::from_slice(witness[0]).unwrap();
+        let msg = Message::from_digest_slice(&sighash.to_byte_array()).unwrap();
+        assert!(Secp256k1::verification_only()
+            .verify_ecdsa(&msg, &sig.sig, &key.inner)
+            .is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_partial_and_foreign_psbts() {
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        let key = PublicKey::from_slice(&signer.public_key()).unwrap();
+        let other = PublicKey::from_slice(
+            &Secp256k1Signer::from_slice(&[2u8; 32])
+                .unwrap()
+                .public_key(),
+        )
+        .unwrap();
+        let ours = TxOut {
+            value: Amount::from_sat(50_000),
+            script_pubkey: ScriptBuf::new_p2pkh(&key.pubkey_hash()),
+        };
+        let theirs = TxOut {
+            value: Amount::from_sat(50_000),
+            script_pubkey: p2wpkh(&other).unwrap(),
+        };
+
+        let (out, complete) = sign(&psbt(&[ours, theirs.clone()]).serialize(), &signer)
+            .await
+            .unwrap();
+        assert!(!complete);
+        let signed = Psbt::deserialize(&out).unwrap();
+        assert!(signed.inputs[0].partial_sigs.contains_key(&key));
+        assert!(signed.inputs[1].partial_sigs.is_empty());
+
+        assert!(matches!(
+            sign(&psbt(&[theirs]).serialize(), &signer).await,
+            Err(AirgapError::NothingToSign(_))
+        ));
+    }
+}
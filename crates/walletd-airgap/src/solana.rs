@@ -0,0 +1,165 @@
+//! Solana payloads: legacy or v0 messages
+//!
+//! The signed payload is the wire transaction, with zeroed slots for any
+//! other required signers.
+
+use crate::{AirgapError, Result};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// The parts of a message that decide who signs
+struct Message<'a> {
+    required: usize,
+    keys: Vec<&'a [u8]>,
+}
+
+fn parse(message: &[u8]) -> Result<Message<'_>> {
+    let mut pos = 0;
+    match message.first() {
+        Some(&prefix) if prefix & 0x80 != 0 => {
+            if prefix & 0x7f != 0 {
+                return Err(AirgapError::NotSupported(format!(
+                    "Solana message version {}",
+                    prefix & 0x7f
+                )));
+            }
+            pos = 1;
+        }
+        Some(_) => {}
+        None => return Err(invalid("empty message")),
+    }
+
+    let header = message
+        .get(pos..pos + 3)
+        .ok_or_else(|| invalid("truncated header"))?;
+    let required = usize::from(header[0]);
+    pos += 3;
+
+    let (count, used) = decode_short_vec(&message[pos..])?;
+    pos += used;
+    let keys_end = pos + count * 32;
+    let keys: Vec<&[u8]> = message
+        .get(pos..keys_end)
+        .ok_or_else(|| invalid("truncated account keys"))?
+        .chunks(32)
+        .collect();
+    // A recent blockhash follows the keys
+    if message.len() < keys_end + 32 {
+        return Err(invalid("missing recent blockhash"));
+    }
+    if required == 0 || required > keys.len() {
+        return Err(invalid("bad signer count"));
+    }
+    Ok(Message { required, keys })
+}
+
+pub(crate) fn validate(payload: &[u8]) -> Result<()> {
+    parse(payload).map(|_| ())
+}
+
+pub(crate) async fn sign(payload: &[u8], signer: &dyn Signer) -> Result<(Vec<u8>, bool)> {
+    let message = parse(payload)?;
+    if signer.scheme() != SignatureScheme::Ed25519 {
+        return Err(AirgapError::NothingToSign(
+            "Solana signing needs an Ed25519 key".into(),
+        ));
+    }
+    let public_key = signer.public_key();
+    let index = message.keys[..message.required]
+        .iter()
+        .position(|key| *key == public_key.as_slice())
+        .ok_or_else(|| AirgapError::NothingToSign("the signer is not a required signer".into()))?;
+    let signature = signer.sign_message(payload).await?;
+
+    let mut tx = encode_short_vec(message.required);
+    let start = tx.len();
+    tx.resize(start + 64 * message.required, 0);
+    tx[start + 64 * index..start + 64 * (index + 1)].copy_from_slice(&signature);
+    tx.extend_from_slice(payload);
+    Ok((tx, message.required == 1))
+}
+
+fn decode_short_vec(data: &[u8]) -> Result<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in data.iter().take(3).enumerate() {
+        value |= usize::from(byte & 0x7f) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(invalid("bad compact length"))
+}
+
+fn encode_short_vec(mut value: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn invalid(reason: &str) -> AirgapError {
+    AirgapError::Format(format!("Solana message: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+    use walletd_traits::Ed25519Signer;
+
+    /// A transfer-shaped message with `signers` and one program key
+    fn message(version: bool, signers: &[Vec<u8>]) -> Vec<u8> {
+        let mut msg = if version { vec![0x80] } else { Vec::new() };
+        msg.extend([signers.len() as u8, 0, 1, signers.len() as u8 + 1]);
+        for key in signers {
+            msg.extend(key);
+        }
+        msg.extend([0u8; 32]); // system program
+        msg.extend([7u8; 32]); // recent blockhash
+        msg.extend([1, 1, 2, 0, 1, 0]); // one instruction
+        if version {
+            msg.push(0); // no address table lookups
+        }
+        msg
+    }
+
+    #[tokio::test]
+    async fn test_sign_message() {
+        let signer = Ed25519Signer::from_bytes(&[3u8; 32]);
+        let msg = message(true, &[signer.public_key()]);
+        let (tx, complete) = sign(&msg, &signer).await.unwrap();
+        assert!(complete);
+        assert_eq!(tx[0], 1);
+        assert_eq!(&tx[65..], &msg[..]);
+
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(&signer.public_key().try_into().unwrap())
+            .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&tx[1..65]).unwrap();
+        assert!(vk.verify(&msg, &sig).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_co_signed_and_foreign_messages() {
+        let signer = Ed25519Signer::from_bytes(&[3u8; 32]);
+        let msg = message(false, &[vec![9u8; 32], signer.public_key()]);
+        let (tx, complete) = sign(&msg, &signer).await.unwrap();
+        assert!(!complete);
+        assert_eq!(tx[0], 2);
+        assert_eq!(&tx[1..65], &[0u8; 64][..]);
+        assert_ne!(&tx[65..129], &[0u8; 64][..]);
+
+        let foreign = message(false, &[vec![9u8; 32]]);
+        assert!(matches!(
+            sign(&foreign, &signer).await,
+            Err(AirgapError::NothingToSign(_))
+        ));
+        assert!(validate(&msg[..40]).is_err());
+        assert_eq!(encode_short_vec(300), vec![0xac, 0x02]);
+        assert_eq!(decode_short_vec(&[0xac, 0x02]).unwrap(), (300, 2));
+    }
+}
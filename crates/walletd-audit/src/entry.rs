@@ -0,0 +1,153 @@
+//! Audit log entries
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walletd_traits::SignatureScheme;
+
+/// `prev_hash` of the first entry
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// What a signer was asked to sign
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RequestKind {
+    /// A 32-byte digest, usually a transaction's sighash
+    Hash,
+    /// An arbitrary message
+    Message,
+}
+
+/// A signature request, as recorded
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningRequest {
+    /// Chain the signature is for, e.g. `ethereum`
+    pub chain: String,
+    /// Wallet or account that signs, e.g. its address or keystore name
+    pub wallet: String,
+    /// Signature scheme of the key
+    pub scheme: SignatureScheme,
+    /// What was signed
+    pub kind: RequestKind,
+    /// The hash as given, or the SHA-256 of the message
+    pub digest: [u8; 32],
+    /// Who asked: a user, API client or service
+    pub requester: String,
+}
+
+impl SigningRequest {
+    /// A request to sign `hash`
+    pub fn hash(
+        chain: impl Into<String>,
+        wallet: impl Into<String>,
+        scheme: SignatureScheme,
+        hash: [u8; 32],
+    ) -> Self {
+        Self {
+            chain: chain.into(),
+            wallet: wallet.into(),
+            scheme,
+            kind: RequestKind::Hash,
+            digest: hash,
+            requester: String::new(),
+        }
+    }
+
+    /// A request to sign `message`, recorded by its SHA-256
+    pub fn message(
+        chain: impl Into<String>,
+        wallet: impl Into<String>,
+        scheme: SignatureScheme,
+        message: &[u8],
+    ) -> Self {
+        Self {
+            kind: RequestKind::Message,
+            ..Self::hash(chain, wallet, scheme, Sha256::digest(message).into())
+        }
+    }
+
+    /// Sets who asked
+    pub fn requester(mut self, requester: impl Into<String>) -> Self {
+        self.requester = requester.into();
+        self
+    }
+}
+
+/// Whether the signature was produced
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "outcome")]
+pub enum Decision {
+    /// The signature was produced and returned
+    Signed,
+    /// The signer or a policy refused, or signing failed
+    Rejected {
+        /// Why
+        reason: String,
+    },
+}
+
+/// One record of the audit log
+///
+/// `hash` is the SHA-256 of the entry's JSON with `hash` empty, and
+/// `prev_hash` is the previous entry's `hash`, so changing, removing or
+/// reordering any entry breaks every later link.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position in the log, from 0
+    pub seq: u64,
+    /// When the request was decided (Unix epoch seconds)
+    pub at: u64,
+    /// Chain the signature is for
+    pub chain: String,
+    /// Wallet or account that signs
+    pub wallet: String,
+    /// Signature scheme of the key
+    pub scheme: SignatureScheme,
+    /// What was signed
+    pub kind: RequestKind,
+    /// Hex of the hash as given, or of the SHA-256 of the message
+    pub digest: String,
+    /// Who asked
+    pub requester: String,
+    /// What happened
+    pub decision: Decision,
+    /// `hash` of the previous entry
+    pub prev_hash: String,
+    /// Hash of this entry
+    pub hash: String,
+}
+
+impl AuditEntry {
+    pub(crate) fn new(
+        seq: u64,
+        at: u64,
+        request: &SigningRequest,
+        decision: Decision,
+        prev_hash: String,
+    ) -> Self {
+        let mut entry = Self {
+            seq,
+            at,
+            chain: request.chain.clone(),
+            wallet: request.wallet.clone(),
+            scheme: request.scheme,
+            kind: request.kind,
+            digest: hex::encode(request.digest),
+            requester: request.requester.clone(),
+            decision,
+            prev_hash,
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+        entry
+    }
+
+    /// Hash of the entry's contents
+    pub fn compute_hash(&self) -> String {
+        let body = Self {
+            hash: String::new(),
+            ..self.clone()
+        };
+        let json = serde_json::to_vec(&body).expect("audit entry serializes");
+        hex::encode(Sha256::digest(json))
+    }
+}
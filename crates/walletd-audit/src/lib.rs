@@ -0,0 +1,88 @@
+//! # WalletD Audit
+//!
+//! An append-only record of every signature a WalletD instance is asked
+//! for, so compliance teams can reconstruct exactly what was signed, for
+//! whom and at whose request.
+//!
+//! Each [`AuditEntry`] holds the chain, wallet, digest, requester and
+//! decision, and the hash of the entry before it. Editing, dropping or
+//! reordering entries breaks the chain, which [`verify`] and
+//! [`AuditLog::open`] detect. [`AuditedSigner`] wraps any
+//! [`Signer`](walletd_traits::Signer) so nothing is signed without an
+//! entry, and the log exports to CSV or JSON.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_audit::{AuditLog, AuditedSigner, FileSink};
+//!
+//! let log = Arc::new(AuditLog::open(Box::new(FileSink::new("audit.jsonl")))?);
+//! let signer = AuditedSigner::new(keystore.unlock_signer("cosmos-main", &password)?, log.clone(), "cosmoshub", &address)
+//!     .for_requester("api:payments");
+//! let wallet = CosmosWallet::from_signer(Box::new(signer), NetworkConfig::cosmos_hub())?;
+//!
+//! // Later
+//! log.export_csv(std::fs::File::create("audit.csv")?)?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod entry;
+pub mod log;
+pub mod signer;
+pub mod sink;
+
+pub use entry::{AuditEntry, Decision, RequestKind, SigningRequest, GENESIS_HASH};
+pub use log::{verify, AuditLog};
+pub use signer::AuditedSigner;
+pub use sink::{AuditSink, FileSink, MemorySink};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Audit log errors
+#[derive(Error, Debug)]
+pub enum AuditError {
+    /// An entry does not match the chain
+    #[error("Audit log tampered at entry {seq}: {reason}")]
+    Tampered {
+        /// First entry that fails verification
+        seq: u64,
+        /// What is wrong with it
+        reason: String,
+    },
+
+    /// The stored log cannot be read
+    #[error("Corrupt audit log: {0}")]
+    Corrupt(String),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for the audit log
+pub type Result<T> = std::result::Result<T, AuditError>;
+
+impl From<AuditError> for WalletError {
+    fn from(e: AuditError) -> Self {
+        WalletError::Other(e.to_string())
+    }
+}
+
+impl From<AuditError> for WalletdError {
+    fn from(e: AuditError) -> Self {
+        match e {
+            AuditError::Tampered { .. } => WalletdError::InvalidState(e.to_string()),
+            AuditError::Corrupt(reason) => WalletdError::FormatError(reason),
+            AuditError::Io(e) => WalletdError::IoError(e.to_string()),
+            AuditError::Json(e) => WalletdError::JsonError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+//! The hash-chained log
+
+use crate::entry::{AuditEntry, Decision, SigningRequest, GENESIS_HASH};
+use crate::sink::AuditSink;
+use crate::{AuditError, Result};
+use std::fmt;
+use std::io::Write;
+use std::sync::Mutex;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// CSV header row
+const CSV_HEADER: &str =
+    "seq,at,chain,wallet,scheme,kind,digest,requester,decision,reason,prev_hash,hash";
+
+/// Records every signature request in an [`AuditSink`]
+///
+/// Opening a log verifies the stored chain, so a tampered log is noticed
+/// before anything is added to it. Publishing [`head`](Self::head)
+/// somewhere the operator cannot rewrite, such as a ticket or another
+/// system's log, also catches a log truncated and rebuilt from scratch.
+pub struct AuditLog {
+    sink: Box<dyn AuditSink>,
+    /// Next sequence number and the hash of the last entry
+    head: Mutex<(u64, String)>,
+}
+
+impl fmt::Debug for AuditLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AuditLog")
+            .field("head", &*self.lock())
+            .finish_non_exhaustive()
+    }
+}
+
+impl AuditLog {
+    /// Verifies the entries in `sink` and continues after the last one
+    pub fn open(sink: Box<dyn AuditSink>) -> Result<Self> {
+        let entries = sink.load()?;
+        verify(&entries)?;
+        let head = entries
+            .last()
+            .map_or((0, GENESIS_HASH.to_string()), |last| {
+                (last.seq + 1, last.hash.clone())
+            });
+        Ok(Self {
+            sink,
+            head: Mutex::new(head),
+        })
+    }
+
+    /// Appends the outcome of a request
+    pub fn record(&self, request: &SigningRequest, decision: Decision) -> Result<AuditEntry> {
+        self.record_at(request, decision, now())
+    }
+
+    /// Appends the outcome of a request decided at `at` (Unix epoch seconds)
+    pub fn record_at(
+        &self,
+        request: &SigningRequest,
+        decision: Decision,
+        at: u64,
+    ) -> Result<AuditEntry> {
+        let mut head = self.lock();
+        let entry = AuditEntry::new(head.0, at, request, decision, head.1.clone());
+        self.sink.append(&entry)?;
+        *head = (entry.seq + 1, entry.hash.clone());
+        Ok(entry)
+    }
+
+    /// Hash of the last entry, or [`GENESIS_HASH`] for an empty log
+    pub fn head(&self) -> String {
+        self.lock().1.clone()
+    }
+
+    /// Number of entries
+    pub fn len(&self) -> u64 {
+        self.lock().0
+    }
+
+    /// Whether nothing has been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Reads and verifies every entry, oldest first
+    pub fn entries(&self) -> Result<Vec<AuditEntry>> {
+        let entries = self.sink.load()?;
+        verify(&entries)?;
+        Ok(entries)
+    }
+
+    /// Writes every entry as CSV, returning the number of rows
+    pub fn export_csv<W: Write>(&self, mut out: W) -> Result<usize> {
+        let entries = self.entries()?;
+        writeln!(out, "{}", CSV_HEADER)?;
+        for entry in &entries {
+            let (decision, reason) = match &entry.decision {
+                Decision::Signed => ("signed", ""),
+                Decision::Rejected { reason } => ("rejected", reason.as_str()),
+            };
+            let fields = [
+                entry.seq.to_string(),
+                entry.at.to_string(),
+                entry.chain.clone(),
+                entry.wallet.clone(),
+                entry.scheme.to_string(),
+                serde_json::to_value(entry.kind)?
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_string(),
+                entry.digest.clone(),
+                entry.requester.clone(),
+                decision.to_string(),
+                reason.to_string(),
+                entry.prev_hash.clone(),
+                entry.hash.clone(),
+            ];
+            let line: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+            writeln!(out, "{}", line.join(","))?;
+        }
+        out.flush()?;
+        Ok(entries.len())
+    }
+
+    /// Writes every entry as a JSON array, returning the count
+    pub fn export_json<W: Write>(&self, mut out: W) -> Result<usize> {
+        let entries = self.entries()?;
+        serde_json::to_writer_pretty(&mut out, &entries)?;
+        out.flush()?;
+        Ok(entries.len())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, (u64, String)> {
+        self.head.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Checks that `entries` form an unbroken chain from the genesis hash
+pub fn verify(entries: &[AuditEntry]) -> Result<()> {
+    let mut prev_hash = GENESIS_HASH;
+    for (i, entry) in entries.iter().enumerate() {
+        let tampered = |reason: &str| AuditError::Tampered {
+            seq: i as u64,
+            reason: reason.to_string(),
+        };
+        if entry.seq != i as u64 {
+            return Err(tampered("sequence gap"));
+        }
+        if entry.prev_hash != prev_hash {
+            return Err(tampered("broken link to the previous entry"));
+        }
+        if entry.hash != entry.compute_hash() {
+            return Err(tampered("contents do not match the hash"));
+        }
+        prev_hash = &entry.hash;
+    }
+    Ok(())
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sink::FileSink;
+    use walletd_traits::SignatureScheme;
+
+    fn request(n: u8) -> SigningRequest {
+        SigningRequest::hash("ethereum", "0xabc", SignatureScheme::Secp256k1, [n; 32])
+            .requester("api:treasury")
+    }
+
+    #[test]
+    fn test_chain_survives_reopen_and_detects_tampering() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("audit.jsonl");
+        let log = AuditLog::open(Box::new(FileSink::new(&path))).unwrap();
+        assert_eq!(log.head(), GENESIS_HASH);
+        log.record_at(&request(1), Decision::Signed, 100).unwrap();
+        let second = log
+            .record_at(
+                &request(2),
+                Decision::Rejected {
+                    reason: "over limit".into(),
+                },
+                101,
+            )
+            .unwrap();
+        drop(log);
+
+        let log = AuditLog::open(Box::new(FileSink::new(&path))).unwrap();
+        assert_eq!(log.head(), second.hash);
+        let third = log.record_at(&request(3), Decision::Signed, 102).unwrap();
+        assert_eq!(third.seq, 2);
+        assert_eq!(third.prev_hash, second.hash);
+
+        // Rewrite the rejection as a signature
+        let json = std::fs::read_to_string(&path).unwrap();
+        std::fs::write(
+            &path,
+            json.replacen(
+                r#"{"outcome":"rejected","reason":"over limit"}"#,
+                r#"{"outcome":"signed"}"#,
+                1,
+            ),
+        )
+        .unwrap();
+        assert!(matches!(
+            AuditLog::open(Box::new(FileSink::new(&path))),
+            Err(AuditError::Tampered { seq: 1, .. })
+        ));
+
+        // Drop the first entry
+        let lines: Vec<&str> = json.lines().skip(1).collect();
+        std::fs::write(&path, lines.join("\n")).unwrap();
+        assert!(matches!(
+            AuditLog::open(Box::new(FileSink::new(&path))),
+            Err(AuditError::Tampered { seq: 0, .. })
+        ));
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let log = AuditLog::open(Box::new(crate::MemorySink::new())).unwrap();
+        log.record_at(
+            &SigningRequest::message("solana", "Abc", SignatureScheme::Ed25519, b"hi")
+                .requester("alice"),
+            Decision::Rejected {
+                reason: "denied, outside hours".into(),
+            },
+            100,
+        )
+        .unwrap();
+        let mut out = Vec::new();
+        assert_eq!(log.export_csv(&mut out).unwrap(), 1);
+        let csv = String::from_utf8(out).unwrap();
+        let row = csv.lines().nth(1).unwrap();
+        assert!(row.starts_with("0,100,solana,Abc,ed25519,message,8f434346"));
+        assert!(row.contains(r#"alice,rejected,"denied, outside hours","#));
+    }
+}
@@ -0,0 +1,149 @@
+//! Auditing [`Signer`] wrapper
+
+use crate::entry::{Decision, SigningRequest};
+use crate::log::AuditLog;
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::{SignatureScheme, Signer, WalletError, WalletResult};
+
+/// A signer that records every request in an [`AuditLog`]
+///
+/// Failed and refused requests are recorded too. If the entry cannot be
+/// written the signature is withheld, so nothing is signed off the record.
+/// Wrap a `walletd_policy::PolicySigner` to log its decisions. The secret
+/// key is never exposed.
+pub struct AuditedSigner {
+    inner: Arc<dyn Signer>,
+    log: Arc<AuditLog>,
+    chain: String,
+    wallet: String,
+    requester: String,
+}
+
+impl AuditedSigner {
+    /// Wraps `inner`, which signs for `wallet` on `chain`
+    pub fn new(
+        inner: Box<dyn Signer>,
+        log: Arc<AuditLog>,
+        chain: impl Into<String>,
+        wallet: impl Into<String>,
+    ) -> Self {
+        Self {
+            inner: inner.into(),
+            log,
+            chain: chain.into(),
+            wallet: wallet.into(),
+            requester: String::new(),
+        }
+    }
+
+    /// The same signer, recording requests as made by `requester`
+    pub fn for_requester(&self, requester: impl Into<String>) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            log: self.log.clone(),
+            chain: self.chain.clone(),
+            wallet: self.wallet.clone(),
+            requester: requester.into(),
+        }
+    }
+
+    /// Returns the audit log
+    pub fn log(&self) -> &Arc<AuditLog> {
+        &self.log
+    }
+
+    fn audit(
+        &self,
+        request: SigningRequest,
+        result: WalletResult<Vec<u8>>,
+    ) -> WalletResult<Vec<u8>> {
+        let decision = match &result {
+            Ok(_) => Decision::Signed,
+            Err(e) => Decision::Rejected {
+                reason: e.to_string(),
+            },
+        };
+        self.log
+            .record(&request.requester(self.requester.clone()), decision)
+            .map_err(|e| {
+                tracing::error!(error = %e, "audit log write failed; signature withheld");
+                WalletError::KeyError(format!("audit log: {}", e))
+            })?;
+        result
+    }
+}
+
+#[async_trait]
+impl Signer for AuditedSigner {
+    fn scheme(&self) -> SignatureScheme {
+        self.inner.scheme()
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        let request = SigningRequest::hash(&self.chain, &self.wallet, self.scheme(), *hash);
+        let result = self.inner.sign_hash(hash).await;
+        self.audit(request, result)
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        let request = SigningRequest::message(&self.chain, &self.wallet, self.scheme(), message);
+        let result = self.inner.sign_message(message).await;
+        self.audit(request, result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entry::RequestKind;
+    use crate::sink::{AuditSink, MemorySink};
+    use crate::{AuditEntry, Result};
+    use walletd_traits::Ed25519Signer;
+
+    struct BrokenSink;
+
+    impl AuditSink for BrokenSink {
+        fn append(&self, _entry: &AuditEntry) -> Result<()> {
+            Err(std::io::Error::other("disk full").into())
+        }
+
+        fn load(&self) -> Result<Vec<AuditEntry>> {
+            Ok(Vec::new())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_records_requests() {
+        let log = Arc::new(AuditLog::open(Box::new(MemorySink::new())).unwrap());
+        let signer = AuditedSigner::new(
+            Box::new(Ed25519Signer::from_bytes(&[5u8; 32])),
+            log.clone(),
+            "solana",
+            "treasury",
+        )
+        .for_requester("bob");
+        signer.sign_hash(&[1u8; 32]).await.unwrap();
+        signer.sign_message(b"hello").await.unwrap();
+        assert!(signer.secret_key().is_none());
+
+        let entries = log.entries().unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].digest, hex::encode([1u8; 32]));
+        assert_eq!(entries[1].kind, RequestKind::Message);
+        assert_eq!(entries[1].requester, "bob");
+        assert_eq!(entries[1].decision, Decision::Signed);
+
+        let unaudited = AuditedSigner::new(
+            Box::new(Ed25519Signer::from_bytes(&[5u8; 32])),
+            Arc::new(AuditLog::open(Box::new(BrokenSink)).unwrap()),
+            "solana",
+            "treasury",
+        );
+        assert!(unaudited.sign_hash(&[1u8; 32]).await.is_err());
+    }
+}
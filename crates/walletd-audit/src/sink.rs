@@ -0,0 +1,101 @@
+//! Storage of audit entries
+
+use crate::entry::AuditEntry;
+use crate::{AuditError, Result};
+use std::io::{BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Where audit entries are written
+///
+/// Entries are only ever appended. [`AuditLog`](crate::AuditLog) reads the
+/// whole log back on open to verify it and continue the chain.
+pub trait AuditSink: Send + Sync {
+    /// Appends one entry; it must be durable when this returns
+    fn append(&self, entry: &AuditEntry) -> Result<()>;
+
+    /// Reads every entry, oldest first
+    fn load(&self) -> Result<Vec<AuditEntry>>;
+}
+
+/// Keeps entries in memory only
+#[derive(Debug, Default)]
+pub struct MemorySink {
+    entries: Mutex<Vec<AuditEntry>>,
+}
+
+impl MemorySink {
+    /// Creates an empty sink
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl AuditSink for MemorySink {
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        self.entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(entry.clone());
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<AuditEntry>> {
+        Ok(self
+            .entries
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone())
+    }
+}
+
+/// Keeps entries in a JSON Lines file, one entry per line
+///
+/// The file is opened in append mode and synced after every entry. A
+/// missing file loads as empty.
+#[derive(Debug, Clone)]
+pub struct FileSink {
+    path: PathBuf,
+}
+
+impl FileSink {
+    /// Uses the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl AuditSink for FileSink {
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+        let mut file = std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        file.write_all(&line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    fn load(&self) -> Result<Vec<AuditEntry>> {
+        let file = match std::fs::File::open(&self.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let mut entries = Vec::new();
+        for (i, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line)
+                .map_err(|e| AuditError::Corrupt(format!("line {}: {}", i + 1, e)))?;
+            entries.push(entry);
+        }
+        Ok(entries)
+    }
+}
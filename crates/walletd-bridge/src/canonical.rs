@@ -0,0 +1,395 @@
+//! Canonical OP Stack deposit bridge
+//!
+//! Deposits go through the `L1StandardBridge` on Ethereum and are minted on
+//! the L2 once the sequencer derives the L1 block that holds them, usually
+//! within a few minutes. Progress is read from the L2's `L1Block`
+//! predeploy: once its L1 block number reaches the deposit's block, the
+//! deposit has been included.
+//!
+//! Withdrawals need a proof and a seven-day challenge period, so only the
+//! L1 → L2 direction is offered.
+
+use crate::{
+    read_allowance, word, Bridge, BridgeError, BridgeQuote, BridgeRequest, BridgeStatus,
+    BridgeTransfer, Result, NATIVE_TOKEN,
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+use walletd_provider::ProviderPool;
+use walletd_swap::approval::approval_calls;
+use walletd_swap::{AllowanceCheck, ApprovalMode, EvmCall};
+
+/// Ethereum mainnet
+const L1_CHAIN: u64 = 1;
+
+/// `depositETHTo(address,uint32,bytes)`
+const DEPOSIT_ETH_TO: &str = "9a2ac6d5";
+/// `depositERC20To(address,address,address,uint256,uint32,bytes)`
+const DEPOSIT_ERC20_TO: &str = "838b2520";
+/// `number()` on the `L1Block` predeploy
+const L1_BLOCK_NUMBER: &str = "0x8381f58a";
+/// `L1Block` predeploy, the same on every OP Stack chain
+const L1_BLOCK: &str = "0x4200000000000000000000000000000000000015";
+
+/// Gas the L2 may use to finalize the deposit, as the OP SDK defaults to
+const DEFAULT_MIN_GAS_LIMIT: u32 = 200_000;
+
+/// Deposits into an OP Stack L2 through its standard bridge
+#[derive(Debug, Clone)]
+pub struct OpStackBridge {
+    name: String,
+    l2_chain: u64,
+    l1_bridge: String,
+    pool: Arc<ProviderPool>,
+    l1_provider: String,
+    l2_provider: String,
+    min_gas_limit: u32,
+}
+
+impl OpStackBridge {
+    /// Deposits into `l2_chain` through the `L1StandardBridge` at
+    /// `l1_bridge`, reading Ethereum and the L2 through the pool's
+    /// `l1_provider` and `l2_provider`
+    pub fn new(
+        name: &str,
+        l2_chain: u64,
+        l1_bridge: &str,
+        pool: Arc<ProviderPool>,
+        l1_provider: &str,
+        l2_provider: &str,
+    ) -> Self {
+        Self {
+            name: name.to_string(),
+            l2_chain,
+            l1_bridge: l1_bridge.to_string(),
+            pool,
+            l1_provider: l1_provider.to_string(),
+            l2_provider: l2_provider.to_string(),
+            min_gas_limit: DEFAULT_MIN_GAS_LIMIT,
+        }
+    }
+
+    /// OP Mainnet
+    pub fn optimism(pool: Arc<ProviderPool>, l1_provider: &str, l2_provider: &str) -> Self {
+        Self::new(
+            "optimism",
+            10,
+            "0x99C9fc46f92E8a1c0deC1b1747d010903E884bE1",
+            pool,
+            l1_provider,
+            l2_provider,
+        )
+    }
+
+    /// Base
+    pub fn base(pool: Arc<ProviderPool>, l1_provider: &str, l2_provider: &str) -> Self {
+        Self::new(
+            "base",
+            8453,
+            "0x3154Cf16ccdb4C6d922629664174b904d80F2C35",
+            pool,
+            l1_provider,
+            l2_provider,
+        )
+    }
+
+    /// Gas the L2 may use to finalize a deposit (default 200,000)
+    pub fn with_min_gas_limit(mut self, gas: u32) -> Self {
+        self.min_gas_limit = gas;
+        self
+    }
+
+    fn check_route(&self, request: &BridgeRequest) -> Result<()> {
+        request.validate()?;
+        if !self.supports(request.from_chain, request.to_chain) {
+            return Err(BridgeError::UnsupportedRoute {
+                from_chain: request.from_chain,
+                to_chain: request.to_chain,
+            });
+        }
+        if request.is_native() != request.to_token.eq_ignore_ascii_case(NATIVE_TOKEN) {
+            return Err(BridgeError::InvalidRequest(
+                "ETH can only be bridged to ETH".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Calldata of the deposit
+    fn calldata(&self, request: &BridgeRequest) -> Result<Vec<u8>> {
+        let gas = format!("{:064x}", self.min_gas_limit);
+        let encoded = if request.is_native() {
+            // (to, minGasLimit, extraData)
+            format!(
+                "{}{}{}{:064x}{:064x}",
+                DEPOSIT_ETH_TO,
+                word(&request.recipient)?,
+                gas,
+                0x60,
+                0
+            )
+        } else {
+            // (l1Token, l2Token, to, amount, minGasLimit, extraData)
+            format!(
+                "{}{}{}{}{:064x}{}{:064x}{:064x}",
+                DEPOSIT_ERC20_TO,
+                word(&request.token)?,
+                word(&request.to_token)?,
+                word(&request.recipient)?,
+                request.amount,
+                gas,
+                0xc0,
+                0
+            )
+        };
+        Ok(hex::decode(encoded).expect("hex built above"))
+    }
+}
+
+#[async_trait]
+impl Bridge for OpStackBridge {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn supports(&self, from_chain: u64, to_chain: u64) -> bool {
+        from_chain == L1_CHAIN && to_chain == self.l2_chain
+    }
+
+    async fn quote(&self, request: &BridgeRequest) -> Result<BridgeQuote> {
+        self.check_route(request)?;
+        let approval = if request.is_native() {
+            None
+        } else {
+            let current = read_allowance(
+                &self.pool,
+                &self.l1_provider,
+                &request.token,
+                &request.sender,
+                &self.l1_bridge,
+            )
+            .await?;
+            Some(AllowanceCheck {
+                token: request.token.clone(),
+                spender: self.l1_bridge.clone(),
+                current,
+            })
+            .filter(|check| check.current < request.amount)
+        };
+        Ok(BridgeQuote {
+            bridge: self.name.clone(),
+            tool: "canonical".to_string(),
+            from_chain: request.from_chain,
+            to_chain: request.to_chain,
+            amount_in: request.amount,
+            amount_out: request.amount,
+            min_amount_out: request.amount,
+            fees: Vec::new(),
+            estimated_duration: Duration::from_secs(180),
+            approval,
+            raw: serde_json::Value::Null,
+        })
+    }
+
+    async fn build_transfer(
+        &self,
+        request: &BridgeRequest,
+        quote: &BridgeQuote,
+    ) -> Result<BridgeTransfer> {
+        self.check_route(request)?;
+        let approvals = match &quote.approval {
+            Some(check) => approval_calls(check, request.amount, ApprovalMode::Exact)
+                .ok_or_else(|| BridgeError::InvalidRequest("invalid token address".into()))?,
+            None => Vec::new(),
+        };
+        Ok(BridgeTransfer {
+            chain_id: L1_CHAIN,
+            approvals,
+            transaction: EvmCall {
+                to: self.l1_bridge.clone(),
+                data: self.calldata(request)?,
+                value: if request.is_native() {
+                    request.amount
+                } else {
+                    0
+                },
+                gas: None,
+            },
+        })
+    }
+
+    async fn track_status(&self, _quote: &BridgeQuote, source_tx: &str) -> Result<BridgeStatus> {
+        let Some(receipt) = self
+            .pool
+            .evm(&self.l1_provider)?
+            .get_transaction_receipt(source_tx)
+            .await?
+        else {
+            return Ok(BridgeStatus::Pending);
+        };
+        if receipt["status"].as_str() == Some("0x0") {
+            return Ok(BridgeStatus::Failed {
+                reason: "deposit reverted on L1".to_string(),
+            });
+        }
+        let deposit_block = quantity(&receipt["blockNumber"])?;
+        let l1_origin = quantity(&serde_json::Value::String(
+            self.pool
+                .evm(&self.l2_provider)?
+                .call(L1_BLOCK, L1_BLOCK_NUMBER)
+                .await?,
+        ))?;
+        if l1_origin >= deposit_block {
+            Ok(BridgeStatus::Completed {
+                destination_tx: None,
+                amount_received: None,
+            })
+        } else {
+            Ok(BridgeStatus::InFlight)
+        }
+    }
+}
+
+/// Parses a hex quantity or 32-byte word
+fn quantity(value: &serde_json::Value) -> Result<u64> {
+    let invalid = || BridgeError::InvalidResponse(format!("invalid quantity: {}", value));
+    let digits = value
+        .as_str()
+        .ok_or_else(invalid)?
+        .trim_start_matches("0x")
+        .trim_start_matches('0');
+    if digits.is_empty() {
+        return Ok(0);
+    }
+    u64::from_str_radix(digits, 16).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sha3::{Digest, Keccak256};
+    use walletd_provider::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SENDER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+
+    fn selector(signature: &str) -> String {
+        hex::encode(&Keccak256::digest(signature)[..4])
+    }
+
+    async fn rpc(server: &MockServer, request: serde_json::Value, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(request))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn bridge(l1: &MockServer, l2: &MockServer) -> OpStackBridge {
+        let pool = ProviderPool::new();
+        pool.add("ethereum", ProviderConfig::new(l1.uri())).unwrap();
+        pool.add("base", ProviderConfig::new(l2.uri())).unwrap();
+        OpStackBridge::base(Arc::new(pool), "ethereum", "base")
+    }
+
+    #[test]
+    fn test_selectors() {
+        assert_eq!(
+            selector("depositETHTo(address,uint32,bytes)"),
+            DEPOSIT_ETH_TO
+        );
+        assert_eq!(
+            selector("depositERC20To(address,address,address,uint256,uint32,bytes)"),
+            DEPOSIT_ERC20_TO
+        );
+        assert_eq!(format!("0x{}", selector("number()")), L1_BLOCK_NUMBER);
+        assert_eq!(selector("allowance(address,address)"), crate::ALLOWANCE);
+    }
+
+    #[tokio::test]
+    async fn test_eth_deposit() {
+        let (l1, l2) = (MockServer::start().await, MockServer::start().await);
+        let bridge = bridge(&l1, &l2);
+        let request = BridgeRequest::native(1, 8453, 10u128.pow(17), SENDER);
+        let quote = bridge.quote(&request).await.unwrap();
+        assert_eq!(quote.amount_out, 10u128.pow(17));
+        assert!(quote.approval.is_none());
+
+        let transfer = bridge.build_transfer(&request, &quote).await.unwrap();
+        assert!(transfer.approvals.is_empty());
+        assert_eq!(transfer.transaction.value, 10u128.pow(17));
+        assert_eq!(transfer.transaction.data.len(), 4 + 4 * 32);
+        assert_eq!(hex::encode(&transfer.transaction.data[..4]), DEPOSIT_ETH_TO);
+
+        assert!(matches!(
+            bridge
+                .quote(&BridgeRequest::native(8453, 1, 1, SENDER))
+                .await,
+            Err(BridgeError::UnsupportedRoute { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_erc20_deposit_and_status() {
+        let (l1, l2) = (MockServer::start().await, MockServer::start().await);
+        rpc(
+            &l1,
+            json!({"method": "eth_call", "params": [{"to": USDC}]}),
+            json!(format!("0x{:064x}", 5u8)),
+        )
+        .await;
+        rpc(
+            &l1,
+            json!({"method": "eth_getTransactionReceipt"}),
+            json!({"status": "0x1", "blockNumber": "0x1406f40"}),
+        )
+        .await;
+        rpc(
+            &l2,
+            json!({"method": "eth_call", "params": [{"to": L1_BLOCK}]}),
+            json!(format!("0x{:064x}", 0x1406f3fu64)),
+        )
+        .await;
+        let bridge = bridge(&l1, &l2);
+
+        let request = BridgeRequest::token(1, 8453, USDC, USDC_BASE, 1_000_000, SENDER);
+        let quote = bridge.quote(&request).await.unwrap();
+        assert_eq!(quote.approval.as_ref().unwrap().current, 5);
+        let transfer = bridge.build_transfer(&request, &quote).await.unwrap();
+        // Reset to zero, then approve the amount
+        assert_eq!(transfer.approvals.len(), 2);
+        assert_eq!(transfer.transaction.value, 0);
+        assert_eq!(transfer.transaction.data.len(), 4 + 7 * 32);
+
+        // The L2 has derived up to the block before the deposit
+        assert_eq!(
+            bridge.track_status(&quote, "0xabc").await.unwrap(),
+            BridgeStatus::InFlight
+        );
+        assert!(bridge
+            .quote(&BridgeRequest::token(
+                1,
+                8453,
+                USDC,
+                NATIVE_TOKEN,
+                1,
+                SENDER
+            ))
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_quantity() {
+        assert_eq!(quantity(&json!("0x1406f40")).unwrap(), 0x1406f40);
+        assert_eq!(quantity(&json!(format!("0x{:064x}", 0))).unwrap(), 0);
+        assert!(quantity(&json!(null)).is_err());
+    }
+}
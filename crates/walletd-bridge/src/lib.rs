@@ -0,0 +1,429 @@
+//! # WalletD Bridge
+//!
+//! Moves assets between the EVM chains walletd supports.
+//!
+//! A [`Bridge`] quotes a [`BridgeRequest`], builds the source-chain
+//! transaction (with any ERC-20 approvals it needs) and tracks the transfer
+//! until it lands on the destination chain.
+//!
+//! Adapters:
+//!
+//! - [`OpStackBridge`]: the canonical L1 → L2 deposit bridge of Optimism and
+//!   Base; no bridge fee, the amount arrives one-to-one
+//! - [`LiFi`]: the LI.FI aggregator, which routes through third-party
+//!   bridges between most EVM chains in both directions
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_bridge::{Bridge, BridgeRequest, BridgeStatus, OpStackBridge};
+//!
+//! let bridge = OpStackBridge::base(Arc::clone(&pool), "ethereum", "base");
+//! let request = BridgeRequest::native(1, 8453, 100_000_000_000_000_000, &address);
+//! let quote = bridge.quote(&request).await?;
+//! let transfer = bridge.build_transfer(&request, &quote).await?;
+//! let hashes = walletd_bridge::send_transfer(&eth_wallet, &transfer).await?;
+//!
+//! let source_tx = hashes.last().unwrap();
+//! while !bridge.track_status(&quote, &source_tx.0).await?.is_final() {
+//!     tokio::time::sleep(Duration::from_secs(30)).await;
+//! }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod canonical;
+pub mod lifi;
+
+pub use canonical::OpStackBridge;
+pub use lifi::LiFi;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_provider::{ProviderError, ProviderPool};
+use walletd_swap::{AllowanceCheck, EvmCall, Slippage};
+use walletd_traits::{Amount, EvmTxParams, TransactionBuilder, Transferable, TxHash, WalletError};
+
+pub use walletd_swap::NATIVE_TOKEN;
+
+/// An asset to move from one chain to another
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeRequest {
+    /// Source chain id
+    pub from_chain: u64,
+    /// Destination chain id
+    pub to_chain: u64,
+    /// Token on the source chain, or [`NATIVE_TOKEN`]
+    pub token: String,
+    /// The same asset on the destination chain
+    pub to_token: String,
+    /// Amount sent, smallest unit
+    pub amount: u128,
+    /// Address that sends on the source chain
+    pub sender: String,
+    /// Address that receives on the destination chain
+    pub recipient: String,
+    /// Accepted slippage, for routes that swap along the way
+    pub slippage: Slippage,
+}
+
+impl BridgeRequest {
+    /// Moves the native token (ETH on all supported L2s) to the same address
+    pub fn native(from_chain: u64, to_chain: u64, amount: u128, sender: &str) -> Self {
+        Self::token(
+            from_chain,
+            to_chain,
+            NATIVE_TOKEN,
+            NATIVE_TOKEN,
+            amount,
+            sender,
+        )
+    }
+
+    /// Moves an ERC-20 token to the same address
+    pub fn token(
+        from_chain: u64,
+        to_chain: u64,
+        token: &str,
+        to_token: &str,
+        amount: u128,
+        sender: &str,
+    ) -> Self {
+        Self {
+            from_chain,
+            to_chain,
+            token: token.to_string(),
+            to_token: to_token.to_string(),
+            amount,
+            sender: sender.to_string(),
+            recipient: sender.to_string(),
+            slippage: Slippage::default(),
+        }
+    }
+
+    /// Sends to another address on the destination chain
+    pub fn to(mut self, recipient: &str) -> Self {
+        self.recipient = recipient.to_string();
+        self
+    }
+
+    /// Sets the accepted slippage
+    pub fn with_slippage(mut self, slippage: Slippage) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// Whether the native token is sent, so no approval is needed
+    pub fn is_native(&self) -> bool {
+        self.token.eq_ignore_ascii_case(NATIVE_TOKEN)
+    }
+
+    /// Rejects empty amounts, missing addresses and same-chain transfers
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(BridgeError::InvalidRequest(reason.to_string()));
+        if self.amount == 0 {
+            return invalid("amount is zero");
+        }
+        if self.from_chain == self.to_chain {
+            return invalid("source and destination chain are the same");
+        }
+        if !is_address(&self.sender) || !is_address(&self.recipient) {
+            return invalid("sender and recipient must be EVM addresses");
+        }
+        Ok(())
+    }
+}
+
+/// A fee charged on top of gas
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeFee {
+    /// What the fee is for, e.g. `LP fee`
+    pub name: String,
+    /// Token the fee is paid in
+    pub token: String,
+    /// Fee, smallest unit
+    pub amount: u128,
+}
+
+/// A priced route from one bridge
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BridgeQuote {
+    /// Adapter that produced the quote
+    pub bridge: String,
+    /// Bridge protocol used, e.g. `stargate` or `canonical`
+    pub tool: String,
+    /// Source chain id
+    pub from_chain: u64,
+    /// Destination chain id
+    pub to_chain: u64,
+    /// Amount sent, smallest unit
+    pub amount_in: u128,
+    /// Expected amount received
+    pub amount_out: u128,
+    /// Least amount received after slippage
+    pub min_amount_out: u128,
+    /// Fees on top of source-chain gas
+    pub fees: Vec<BridgeFee>,
+    /// Typical time until the funds arrive
+    pub estimated_duration: Duration,
+    /// Set when the bridge contract must be approved first
+    pub approval: Option<AllowanceCheck>,
+    /// Adapter response, kept for building the transaction
+    #[serde(default)]
+    pub(crate) raw: Value,
+}
+
+/// Source-chain transactions of a transfer, in sending order
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BridgeTransfer {
+    /// Chain the transactions are sent on
+    pub chain_id: u64,
+    /// ERC-20 approvals to send first
+    pub approvals: Vec<EvmCall>,
+    /// The bridge call itself
+    pub transaction: EvmCall,
+}
+
+/// Where a transfer is
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum BridgeStatus {
+    /// The source transaction is not mined or not yet seen by the bridge
+    Pending,
+    /// Confirmed on the source chain, not yet delivered
+    InFlight,
+    /// Delivered on the destination chain
+    Completed {
+        /// Destination transaction, when the bridge reports it
+        destination_tx: Option<String>,
+        /// Amount received, when the bridge reports it
+        amount_received: Option<u128>,
+    },
+    /// The bridge gave up and returned the funds on the source chain
+    Refunded {
+        /// Bridge explanation
+        reason: String,
+    },
+    /// The transfer failed
+    Failed {
+        /// Bridge explanation
+        reason: String,
+    },
+}
+
+impl BridgeStatus {
+    /// Whether the status can no longer change
+    pub fn is_final(&self) -> bool {
+        matches!(
+            self,
+            BridgeStatus::Completed { .. }
+                | BridgeStatus::Refunded { .. }
+                | BridgeStatus::Failed { .. }
+        )
+    }
+}
+
+/// A way to move assets between chains
+#[async_trait]
+pub trait Bridge: Send + Sync {
+    /// Short name, e.g. `lifi`
+    fn name(&self) -> &str;
+
+    /// Whether the bridge moves assets from `from_chain` to `to_chain`
+    fn supports(&self, from_chain: u64, to_chain: u64) -> bool;
+
+    /// Prices a transfer, including the approval it needs
+    async fn quote(&self, request: &BridgeRequest) -> Result<BridgeQuote>;
+
+    /// Builds the source-chain transactions for a quote from this bridge
+    async fn build_transfer(
+        &self,
+        request: &BridgeRequest,
+        quote: &BridgeQuote,
+    ) -> Result<BridgeTransfer>;
+
+    /// Reports the progress of a transfer sent as `source_tx`
+    async fn track_status(&self, quote: &BridgeQuote, source_tx: &str) -> Result<BridgeStatus>;
+}
+
+/// Sends a transfer's approvals and bridge call from an EVM wallet
+///
+/// Returns the hashes in the order sent; the bridge call is last and is the
+/// one to pass to [`Bridge::track_status`].
+pub async fn send_transfer<W>(wallet: &W, transfer: &BridgeTransfer) -> Result<Vec<TxHash>>
+where
+    W: Transferable<TxParams = EvmTxParams> + ?Sized,
+{
+    if let Some(chain_id) = wallet.network().chain_id {
+        if chain_id != transfer.chain_id {
+            return Err(BridgeError::InvalidRequest(format!(
+                "transfer is sent on chain {} but the wallet is on {}",
+                transfer.chain_id, chain_id
+            )));
+        }
+    }
+    let mut hashes = Vec::with_capacity(transfer.approvals.len() + 1);
+    for call in transfer.approvals.iter().chain(Some(&transfer.transaction)) {
+        let mut tx = TransactionBuilder::new()
+            .to(call.to.clone())
+            .amount(Amount::from_smallest_unit(call.value, wallet.decimals()))
+            .data(call.data.clone());
+        if let Some(gas) = call.gas.filter(|gas| *gas > 0) {
+            tx = tx.gas_limit(gas);
+        }
+        let hash = wallet.transfer_with(tx).await?;
+        tracing::info!(to = %call.to, hash = %hash.0, "bridge transaction sent");
+        hashes.push(hash);
+    }
+    Ok(hashes)
+}
+
+/// Bridge errors
+#[derive(Error, Debug)]
+pub enum BridgeError {
+    /// The bridge does not connect these chains
+    #[error("No route from chain {from_chain} to chain {to_chain}")]
+    UnsupportedRoute {
+        /// Source chain id
+        from_chain: u64,
+        /// Destination chain id
+        to_chain: u64,
+    },
+
+    /// The request is malformed
+    #[error("Invalid bridge request: {0}")]
+    InvalidRequest(String),
+
+    /// The bridge is throttling requests
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay the bridge asked for
+        retry_after: Option<Duration>,
+    },
+
+    /// The bridge API answered with an error
+    #[error("{bridge} error: {message}")]
+    Api {
+        /// Adapter name
+        bridge: String,
+        /// Error message
+        message: String,
+    },
+
+    /// Unexpected response from the bridge
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// On-chain read failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// Sending failed
+    #[error("Wallet error: {0}")]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for bridge operations
+pub type Result<T> = std::result::Result<T, BridgeError>;
+
+impl From<BridgeError> for WalletdError {
+    fn from(e: BridgeError) -> Self {
+        match e {
+            BridgeError::Provider(e) => e.into(),
+            BridgeError::RateLimited { retry_after } => WalletdError::RateLimited {
+                retry_after_secs: retry_after.map_or(1, |d| d.as_secs().max(1)),
+            },
+            BridgeError::UnsupportedRoute { .. } => WalletdError::NotSupported(e.to_string()),
+            BridgeError::InvalidRequest(reason) => WalletdError::TransactionBuildError(reason),
+            BridgeError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            BridgeError::Api { .. } | BridgeError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+            BridgeError::Http(_) => WalletdError::NetworkError(e.to_string()),
+        }
+    }
+}
+
+/// `allowance(address,address)`
+const ALLOWANCE: &str = "dd62ed3e";
+
+/// Reads `token.allowance(owner, spender)` through the pool's `provider`
+pub(crate) async fn read_allowance(
+    pool: &ProviderPool,
+    provider: &str,
+    token: &str,
+    owner: &str,
+    spender: &str,
+) -> Result<u128> {
+    let data = format!("0x{}{}{}", ALLOWANCE, word(owner)?, word(spender)?);
+    let result = pool.evm(provider)?.call(token, &data).await?;
+    // Allowances above u128 are effectively unlimited
+    let digits = result.trim_start_matches("0x");
+    if digits.len() != 64 || !digits.is_ascii() {
+        return Err(BridgeError::InvalidResponse(format!(
+            "invalid allowance: {}",
+            result
+        )));
+    }
+    let (high, low) = digits.split_at(32);
+    if high.bytes().any(|b| b != b'0') {
+        return Ok(u128::MAX);
+    }
+    u128::from_str_radix(low, 16)
+        .map_err(|_| BridgeError::InvalidResponse(format!("invalid allowance: {}", result)))
+}
+
+/// Left-pads an address to a 32-byte ABI word, in hex
+pub(crate) fn word(address: &str) -> Result<String> {
+    if !is_address(address) {
+        return Err(BridgeError::InvalidRequest(format!(
+            "invalid EVM address: {}",
+            address
+        )));
+    }
+    Ok(format!("{:0>64}", address[2..].to_ascii_lowercase()))
+}
+
+fn is_address(address: &str) -> bool {
+    address.len() == 42
+        && address.starts_with("0x")
+        && address[2..].bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SENDER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    #[test]
+    fn test_validate() {
+        let request = BridgeRequest::native(1, 10, 1_000, SENDER);
+        assert!(request.validate().is_ok());
+        assert!(request.is_native());
+        assert_eq!(request.recipient, SENDER);
+
+        assert!(BridgeRequest::native(1, 1, 1_000, SENDER)
+            .validate()
+            .is_err());
+        assert!(BridgeRequest::native(1, 10, 0, SENDER).validate().is_err());
+        assert!(request.clone().to("vitalik.eth").validate().is_err());
+    }
+
+    #[test]
+    fn test_word() {
+        assert_eq!(
+            word(SENDER).unwrap(),
+            "00000000000000000000000070997970c51812dc3a010c7d01b50e0d17dc79c8"
+        );
+        assert!(word("0x1234").is_err());
+    }
+}
@@ -0,0 +1,453 @@
+//! LI.FI bridge aggregator
+//!
+//! `/quote` picks a bridge (Stargate, Across, Hop, ...) and returns a ready
+//! transaction; `/status` follows the transfer across both chains. LI.FI
+//! does not report allowances, so they are read on-chain when a provider
+//! for the source chain is configured and taken to be zero otherwise.
+
+use crate::{
+    read_allowance, Bridge, BridgeError, BridgeFee, BridgeQuote, BridgeRequest, BridgeStatus,
+    BridgeTransfer, Result,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use walletd_provider::ProviderPool;
+use walletd_resilience::HttpRetryClassifier;
+use walletd_swap::approval::approval_calls;
+use walletd_swap::{AllowanceCheck, ApprovalMode, EvmCall};
+
+/// Public API
+pub const LIFI_URL: &str = "https://li.quest/v1";
+
+/// Address LI.FI uses for native tokens
+const LIFI_NATIVE: &str = "0x0000000000000000000000000000000000000000";
+
+/// Chains LI.FI bridges between
+const CHAINS: &[u64] = &[1, 10, 56, 100, 137, 324, 8453, 42161, 43114, 59144, 534352];
+
+/// Transfers routed by LI.FI
+#[derive(Debug, Clone)]
+pub struct LiFi {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    integrator: Option<String>,
+    pool: Option<Arc<ProviderPool>>,
+    providers: HashMap<u64, String>,
+}
+
+impl Default for LiFi {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl LiFi {
+    /// Public API without a key
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: LIFI_URL.to_string(),
+            api_key: None,
+            integrator: None,
+            pool: None,
+            providers: HashMap::new(),
+        }
+    }
+
+    /// Sends an API key for higher rate limits
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(api_key.to_string());
+        self
+    }
+
+    /// Identifies the app to LI.FI, for analytics and fee sharing
+    pub fn with_integrator(mut self, integrator: &str) -> Self {
+        self.integrator = Some(integrator.to_string());
+        self
+    }
+
+    /// Uses another endpoint (proxy, mock server)
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Reads allowances on `chain_id` through the pool's `provider`
+    pub fn with_provider(mut self, pool: Arc<ProviderPool>, chain_id: u64, provider: &str) -> Self {
+        self.pool = Some(pool);
+        self.providers.insert(chain_id, provider.to_string());
+        self
+    }
+
+    async fn get(&self, endpoint: &str, query: &[(&str, String)]) -> Result<Value> {
+        let mut request = self
+            .client
+            .get(format!("{}/{}", self.base_url, endpoint))
+            .query(query)
+            .timeout(Duration::from_secs(30));
+        if let Some(key) = &self.api_key {
+            request = request.header("x-lifi-api-key", key);
+        }
+        let response = request.send().await?;
+        let status = response.status();
+        if HttpRetryClassifier::is_rate_limited(status.as_u16()) {
+            let retry_after = response
+                .headers()
+                .get("retry-after")
+                .and_then(|value| value.to_str().ok())
+                .and_then(HttpRetryClassifier::parse_retry_after);
+            return Err(BridgeError::RateLimited { retry_after });
+        }
+        let body: Value = response.json().await.unwrap_or(Value::Null);
+        if !status.is_success() {
+            return Err(BridgeError::Api {
+                bridge: self.name().to_string(),
+                message: body["message"]
+                    .as_str()
+                    .map(str::to_string)
+                    .unwrap_or_else(|| format!("HTTP {}", status)),
+            });
+        }
+        Ok(body)
+    }
+
+    async fn allowance(&self, request: &BridgeRequest, spender: &str) -> Result<u128> {
+        match (&self.pool, self.providers.get(&request.from_chain)) {
+            (Some(pool), Some(provider)) => {
+                read_allowance(pool, provider, &request.token, &request.sender, spender).await
+            }
+            _ => Ok(0),
+        }
+    }
+}
+
+fn lifi_token(token: &str) -> String {
+    if token.eq_ignore_ascii_case(crate::NATIVE_TOKEN) {
+        LIFI_NATIVE.to_string()
+    } else {
+        token.to_string()
+    }
+}
+
+/// Reads an integer sent as a decimal or `0x` hex string
+fn number(value: &Value, field: &str) -> Result<u128> {
+    value[field]
+        .as_str()
+        .and_then(|s| match s.strip_prefix("0x") {
+            Some(hex) => u128::from_str_radix(hex, 16).ok(),
+            None => s.parse().ok(),
+        })
+        .or_else(|| value[field].as_u64().map(u128::from))
+        .ok_or_else(|| BridgeError::InvalidResponse(format!("missing or invalid {}", field)))
+}
+
+#[async_trait]
+impl Bridge for LiFi {
+    fn name(&self) -> &str {
+        "lifi"
+    }
+
+    fn supports(&self, from_chain: u64, to_chain: u64) -> bool {
+        from_chain != to_chain && CHAINS.contains(&from_chain) && CHAINS.contains(&to_chain)
+    }
+
+    async fn quote(&self, request: &BridgeRequest) -> Result<BridgeQuote> {
+        request.validate()?;
+        if !self.supports(request.from_chain, request.to_chain) {
+            return Err(BridgeError::UnsupportedRoute {
+                from_chain: request.from_chain,
+                to_chain: request.to_chain,
+            });
+        }
+        let mut query = vec![
+            ("fromChain", request.from_chain.to_string()),
+            ("toChain", request.to_chain.to_string()),
+            ("fromToken", lifi_token(&request.token)),
+            ("toToken", lifi_token(&request.to_token)),
+            ("fromAmount", request.amount.to_string()),
+            ("fromAddress", request.sender.clone()),
+            ("toAddress", request.recipient.clone()),
+            (
+                "slippage",
+                (request.slippage.bps() as f64 / 10_000.0).to_string(),
+            ),
+        ];
+        if let Some(integrator) = &self.integrator {
+            query.push(("integrator", integrator.clone()));
+        }
+        let body = self.get("quote", &query).await?;
+        let estimate = &body["estimate"];
+
+        let approval = match estimate["approvalAddress"].as_str() {
+            Some(spender) if !request.is_native() => {
+                let current = self.allowance(request, spender).await?;
+                Some(AllowanceCheck {
+                    token: request.token.clone(),
+                    spender: spender.to_string(),
+                    current,
+                })
+                .filter(|check| check.current < request.amount)
+            }
+            _ => None,
+        };
+        let fees = estimate["feeCosts"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            // Included fees are already taken out of the output amount
+            .filter(|fee| fee["included"] != true)
+            .filter_map(|fee| {
+                Some(BridgeFee {
+                    name: fee["name"].as_str()?.to_string(),
+                    token: fee["token"]["address"].as_str()?.to_string(),
+                    amount: number(fee, "amount").ok()?,
+                })
+            })
+            .collect();
+
+        Ok(BridgeQuote {
+            bridge: self.name().to_string(),
+            tool: body["tool"].as_str().unwrap_or_default().to_string(),
+            from_chain: request.from_chain,
+            to_chain: request.to_chain,
+            amount_in: number(estimate, "fromAmount")?,
+            amount_out: number(estimate, "toAmount")?,
+            min_amount_out: number(estimate, "toAmountMin")?,
+            fees,
+            estimated_duration: Duration::from_secs(
+                estimate["executionDuration"].as_f64().unwrap_or(0.0) as u64,
+            ),
+            approval,
+            raw: body,
+        })
+    }
+
+    async fn build_transfer(
+        &self,
+        request: &BridgeRequest,
+        quote: &BridgeQuote,
+    ) -> Result<BridgeTransfer> {
+        let tx = &quote.raw["transactionRequest"];
+        let to = tx["to"]
+            .as_str()
+            .ok_or_else(|| BridgeError::InvalidResponse("missing transactionRequest".into()))?;
+        let data = tx["data"]
+            .as_str()
+            .and_then(|data| hex::decode(data.trim_start_matches("0x")).ok())
+            .ok_or_else(|| BridgeError::InvalidResponse("invalid calldata".into()))?;
+        let approvals = match &quote.approval {
+            Some(check) => approval_calls(check, request.amount, ApprovalMode::Exact)
+                .ok_or_else(|| BridgeError::InvalidResponse("invalid approval address".into()))?,
+            None => Vec::new(),
+        };
+        Ok(BridgeTransfer {
+            chain_id: quote.from_chain,
+            approvals,
+            transaction: EvmCall {
+                to: to.to_string(),
+                data,
+                value: number(tx, "value").unwrap_or(0),
+                gas: number(tx, "gasLimit")
+                    .ok()
+                    .and_then(|gas| u64::try_from(gas).ok()),
+            },
+        })
+    }
+
+    async fn track_status(&self, quote: &BridgeQuote, source_tx: &str) -> Result<BridgeStatus> {
+        let body = match self
+            .get(
+                "status",
+                &[
+                    ("txHash", source_tx.to_string()),
+                    ("bridge", quote.tool.clone()),
+                    ("fromChain", quote.from_chain.to_string()),
+                    ("toChain", quote.to_chain.to_string()),
+                ],
+            )
+            .await
+        {
+            Ok(body) => body,
+            // Until LI.FI indexes the source transaction
+            Err(BridgeError::Api { message, .. }) if message.contains("not found") => {
+                return Ok(BridgeStatus::Pending)
+            }
+            Err(e) => return Err(e),
+        };
+        let reason = || {
+            body["substatusMessage"]
+                .as_str()
+                .unwrap_or_default()
+                .to_string()
+        };
+        Ok(match body["status"].as_str().unwrap_or_default() {
+            "NOT_FOUND" => BridgeStatus::Pending,
+            "PENDING" => BridgeStatus::InFlight,
+            "DONE" if body["substatus"] == "REFUNDED" => {
+                BridgeStatus::Refunded { reason: reason() }
+            }
+            "DONE" => BridgeStatus::Completed {
+                destination_tx: body["receiving"]["txHash"].as_str().map(str::to_string),
+                amount_received: number(&body["receiving"], "amount").ok(),
+            },
+            "FAILED" | "INVALID" => BridgeStatus::Failed { reason: reason() },
+            other => {
+                return Err(BridgeError::InvalidResponse(format!(
+                    "unknown status {:?}",
+                    other
+                )))
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const SENDER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const USDC_ARB: &str = "0xaf88d065e77c8cC2239327C5EDb3A432268e5831";
+    const USDC_OP: &str = "0x0b2C639c533813f4Aa9D7837CAf62653d097Ff85";
+    const DIAMOND: &str = "0x1231DEB6f5749EF6cE6943a275A1D3E7486F4EaE";
+
+    #[tokio::test]
+    async fn test_quote_and_build() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/quote"))
+            .and(query_param("fromChain", "42161"))
+            .and(query_param("toChain", "10"))
+            .and(query_param("fromAmount", "100000000"))
+            .and(query_param("slippage", "0.005"))
+            .and(header("x-lifi-api-key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "id": "quote-1",
+                "tool": "across",
+                "estimate": {
+                    "fromAmount": "100000000",
+                    "toAmount": "99950000",
+                    "toAmountMin": "99450250",
+                    "approvalAddress": DIAMOND,
+                    "executionDuration": 52.5,
+                    "feeCosts": [
+                        {"name": "LP fee", "amount": "50000", "included": true,
+                         "token": {"address": USDC_ARB, "symbol": "USDC"}},
+                        {"name": "Relayer fee", "amount": "1200", "included": false,
+                         "token": {"address": USDC_ARB, "symbol": "USDC"}}
+                    ]
+                },
+                "transactionRequest": {
+                    "to": DIAMOND,
+                    "data": "0x1794958f",
+                    "value": "0x0",
+                    "gasLimit": "0x6ddd0",
+                    "chainId": 42161
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let lifi = LiFi::new().with_api_key("key").with_base_url(&server.uri());
+        let request = BridgeRequest::token(42161, 10, USDC_ARB, USDC_OP, 100_000_000, SENDER);
+        let quote = lifi.quote(&request).await.unwrap();
+        assert_eq!(quote.tool, "across");
+        assert_eq!(
+            (quote.amount_out, quote.min_amount_out),
+            (99_950_000, 99_450_250)
+        );
+        assert_eq!(quote.estimated_duration, Duration::from_secs(52));
+        assert_eq!(quote.fees.len(), 1);
+        assert_eq!(quote.fees[0].amount, 1_200);
+        // No provider configured: assume nothing is approved
+        assert_eq!(quote.approval.as_ref().unwrap().current, 0);
+
+        let transfer = lifi.build_transfer(&request, &quote).await.unwrap();
+        assert_eq!(transfer.chain_id, 42161);
+        assert_eq!(transfer.approvals.len(), 1);
+        assert_eq!(transfer.transaction.to, DIAMOND);
+        assert_eq!(transfer.transaction.gas, Some(450_000));
+    }
+
+    #[tokio::test]
+    async fn test_status() {
+        let server = MockServer::start().await;
+        for (hash, body) in [
+            (
+                "0x01",
+                json!({"status": "PENDING", "substatus": "WAIT_DESTINATION_TRANSACTION"}),
+            ),
+            (
+                "0x02",
+                json!({"status": "DONE", "substatus": "COMPLETED",
+                       "receiving": {"txHash": "0xdest", "amount": "99950000"}}),
+            ),
+            (
+                "0x03",
+                json!({"status": "DONE", "substatus": "REFUNDED",
+                       "substatusMessage": "Transfer refunded"}),
+            ),
+        ] {
+            Mock::given(method("GET"))
+                .and(path("/status"))
+                .and(query_param("txHash", hash))
+                .and(query_param("bridge", "across"))
+                .respond_with(ResponseTemplate::new(200).set_body_json(body))
+                .mount(&server)
+                .await;
+        }
+        Mock::given(method("GET"))
+            .and(path("/status"))
+            .and(query_param("txHash", "0x04"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_json(json!({"message": "Transaction was not found"})),
+            )
+            .mount(&server)
+            .await;
+
+        let lifi = LiFi::new().with_base_url(&server.uri());
+        let quote = BridgeQuote {
+            bridge: "lifi".into(),
+            tool: "across".into(),
+            from_chain: 42161,
+            to_chain: 10,
+            amount_in: 100_000_000,
+            amount_out: 99_950_000,
+            min_amount_out: 99_450_250,
+            fees: Vec::new(),
+            estimated_duration: Duration::from_secs(52),
+            approval: None,
+            raw: Value::Null,
+        };
+        assert_eq!(
+            lifi.track_status(&quote, "0x01").await.unwrap(),
+            BridgeStatus::InFlight
+        );
+        let done = lifi.track_status(&quote, "0x02").await.unwrap();
+        assert!(done.is_final());
+        assert_eq!(
+            done,
+            BridgeStatus::Completed {
+                destination_tx: Some("0xdest".into()),
+                amount_received: Some(99_950_000)
+            }
+        );
+        assert_eq!(
+            lifi.track_status(&quote, "0x03").await.unwrap(),
+            BridgeStatus::Refunded {
+                reason: "Transfer refunded".into()
+            }
+        );
+        assert_eq!(
+            lifi.track_status(&quote, "0x04").await.unwrap(),
+            BridgeStatus::Pending
+        );
+    }
+}
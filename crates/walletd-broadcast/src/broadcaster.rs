@@ -0,0 +1,499 @@
+//! Queued, rate-limited broadcasting of many transactions
+
+use crate::sender::{RawSender, SendError};
+use crate::{BroadcastError, Result};
+use futures_util::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use walletd_resilience::{AdaptiveRateLimiter, AimdConfig};
+use walletd_traits::TxHash;
+
+/// Identifies a transaction submitted to a [`Broadcaster`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ItemId(pub u64);
+
+impl fmt::Display for ItemId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "#{}", self.0)
+    }
+}
+
+/// A signed transaction waiting to be broadcast
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignedTx {
+    /// Chain name, e.g. `ethereum`
+    pub chain: String,
+    /// Signed transaction bytes
+    pub raw: Vec<u8>,
+    /// Signing account and its nonce, on chains that have one
+    pub nonce: Option<(String, u64)>,
+    /// Transactions that must be accepted before this one is sent
+    pub depends_on: Vec<ItemId>,
+    /// Caller's label, e.g. a payee or invoice number
+    pub label: Option<String>,
+}
+
+impl SignedTx {
+    /// A transaction for `chain`
+    pub fn new(chain: impl Into<String>, raw: Vec<u8>) -> Self {
+        Self {
+            chain: chain.into(),
+            raw,
+            nonce: None,
+            depends_on: Vec::new(),
+            label: None,
+        }
+    }
+
+    /// Sets the signing account and nonce
+    ///
+    /// Transactions of one account on one chain are sent in nonce order,
+    /// each after the one before it was accepted.
+    pub fn nonce(mut self, sender: impl Into<String>, nonce: u64) -> Self {
+        self.nonce = Some((sender.into(), nonce));
+        self
+    }
+
+    /// Sends this transaction only after `item` was accepted
+    pub fn after(mut self, item: ItemId) -> Self {
+        self.depends_on.push(item);
+        self
+    }
+
+    /// Sets a label carried into the report
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+}
+
+/// Where a submitted transaction stands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ItemStatus {
+    /// Waiting to be sent
+    Queued,
+    /// Accepted by the node
+    Sent(TxHash),
+    /// Rejected, or still throttled after every retry
+    Failed(String),
+    /// Not sent because a transaction it depends on did not go out
+    Skipped {
+        /// The dependency that was not sent
+        after: ItemId,
+    },
+}
+
+/// Outcome of one transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ItemReport {
+    /// Item the transaction was submitted as
+    pub id: ItemId,
+    /// Chain name
+    pub chain: String,
+    /// Caller's label
+    pub label: Option<String>,
+    /// Final status
+    pub status: ItemStatus,
+}
+
+/// Outcome of a [`Broadcaster::run`], in submission order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchReport {
+    /// One entry per transaction
+    pub items: Vec<ItemReport>,
+}
+
+impl BatchReport {
+    /// Status of `id`
+    pub fn status(&self, id: ItemId) -> Option<&ItemStatus> {
+        self.items.iter().find(|i| i.id == id).map(|i| &i.status)
+    }
+
+    /// Transactions accepted by their node
+    pub fn sent(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, ItemStatus::Sent(_)))
+    }
+
+    /// Transactions that failed
+    pub fn failed(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, ItemStatus::Failed(_)))
+    }
+
+    /// Transactions skipped after a failed dependency
+    pub fn skipped(&self) -> impl Iterator<Item = &ItemReport> {
+        self.items
+            .iter()
+            .filter(|i| matches!(i.status, ItemStatus::Skipped { .. }))
+    }
+
+    /// Whether every transaction was sent
+    pub fn all_sent(&self) -> bool {
+        self.sent().count() == self.items.len()
+    }
+}
+
+struct Lane {
+    sender: Arc<dyn RawSender>,
+    limiter: Arc<AdaptiveRateLimiter>,
+}
+
+#[derive(Default)]
+struct Queue {
+    next_id: u64,
+    items: Vec<(ItemId, SignedTx)>,
+}
+
+/// Broadcasts queued transactions across chains
+///
+/// Each chain has its own [`AdaptiveRateLimiter`]: sends wait for a token,
+/// and throttled sends slow the chain down and are retried. Transactions
+/// go out in waves, each wave holding every transaction whose dependencies
+/// were accepted, so one account's nonces are sent in order while other
+/// accounts and chains proceed in parallel. When a transaction fails,
+/// everything depending on it is skipped rather than sent into a nonce gap.
+pub struct Broadcaster {
+    lanes: HashMap<String, Lane>,
+    max_retries: u32,
+    queue: Mutex<Queue>,
+}
+
+impl fmt::Debug for Broadcaster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Broadcaster")
+            .field("chains", &self.lanes.keys().collect::<Vec<_>>())
+            .field("max_retries", &self.max_retries)
+            .field("queued", &self.queued())
+            .finish()
+    }
+}
+
+impl Default for Broadcaster {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Broadcaster {
+    /// A broadcaster with no chains, retrying throttled sends 3 times
+    pub fn new() -> Self {
+        Self {
+            lanes: HashMap::new(),
+            max_retries: 3,
+            queue: Mutex::new(Queue::default()),
+        }
+    }
+
+    /// Adds a chain, rate limited by `config`
+    pub fn chain(
+        self,
+        name: impl Into<String>,
+        sender: Arc<dyn RawSender>,
+        config: AimdConfig,
+    ) -> Self {
+        self.chain_with_limiter(name, sender, Arc::new(AdaptiveRateLimiter::new(config)))
+    }
+
+    /// Adds a chain sharing `limiter`, e.g. with the provider's other traffic
+    pub fn chain_with_limiter(
+        mut self,
+        name: impl Into<String>,
+        sender: Arc<dyn RawSender>,
+        limiter: Arc<AdaptiveRateLimiter>,
+    ) -> Self {
+        self.lanes.insert(name.into(), Lane { sender, limiter });
+        self
+    }
+
+    /// Sets how often a throttled send is retried
+    pub fn max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Queues a transaction for the next [`run`](Self::run)
+    ///
+    /// Dependencies must be queued already.
+    pub fn submit(&self, tx: SignedTx) -> Result<ItemId> {
+        if !self.lanes.contains_key(&tx.chain) {
+            return Err(BroadcastError::UnknownChain(tx.chain));
+        }
+        let mut queue = self.lock();
+        if let Some(missing) = tx
+            .depends_on
+            .iter()
+            .find(|dep| !queue.items.iter().any(|(id, _)| id == *dep))
+        {
+            return Err(BroadcastError::UnknownDependency(*missing));
+        }
+        let id = ItemId(queue.next_id);
+        queue.next_id += 1;
+        queue.items.push((id, tx));
+        Ok(id)
+    }
+
+    /// Number of transactions waiting for the next run
+    pub fn queued(&self) -> usize {
+        self.lock().items.len()
+    }
+
+    /// Queues `txs` and runs them
+    pub async fn broadcast(&self, txs: impl IntoIterator<Item = SignedTx>) -> Result<BatchReport> {
+        for tx in txs {
+            self.submit(tx)?;
+        }
+        Ok(self.run().await)
+    }
+
+    /// Sends everything queued and reports each transaction's outcome
+    pub async fn run(&self) -> BatchReport {
+        let items = std::mem::take(&mut self.lock().items);
+        let deps = dependencies(&items);
+        let mut status = vec![ItemStatus::Queued; items.len()];
+
+        loop {
+            let mut ready = Vec::new();
+            let mut changed = false;
+            for i in 0..items.len() {
+                if status[i] != ItemStatus::Queued {
+                    continue;
+                }
+                let blocked = deps[i]
+                    .iter()
+                    .find(|&&d| !matches!(status[d], ItemStatus::Queued | ItemStatus::Sent(_)));
+                if let Some(&d) = blocked {
+                    status[i] = ItemStatus::Skipped { after: items[d].0 };
+                    changed = true;
+                } else if deps[i]
+                    .iter()
+                    .all(|&d| matches!(status[d], ItemStatus::Sent(_)))
+                {
+                    ready.push(i);
+                }
+            }
+            if ready.is_empty() {
+                if changed {
+                    continue;
+                }
+                break;
+            }
+
+            let sends = ready.iter().map(|&i| {
+                let (_, tx) = &items[i];
+                self.send(&self.lanes[&tx.chain], &tx.raw)
+            });
+            let results = join_all(sends).await;
+            for (i, result) in ready.into_iter().zip(results) {
+                match &result {
+                    ItemStatus::Sent(hash) => {
+                        tracing::debug!(item = %items[i].0, chain = %items[i].1.chain, %hash, "Broadcast")
+                    }
+                    ItemStatus::Failed(reason) => {
+                        tracing::warn!(item = %items[i].0, chain = %items[i].1.chain, %reason, "Broadcast failed")
+                    }
+                    _ => {}
+                }
+                status[i] = result;
+            }
+        }
+
+        // Whatever is still queued waits on itself through its dependencies
+        for s in status.iter_mut().filter(|s| **s == ItemStatus::Queued) {
+            *s = ItemStatus::Failed("dependency cycle".into());
+        }
+        BatchReport {
+            items: items
+                .into_iter()
+                .zip(status)
+                .map(|((id, tx), status)| ItemReport {
+                    id,
+                    chain: tx.chain,
+                    label: tx.label,
+                    status,
+                })
+                .collect(),
+        }
+    }
+
+    async fn send(&self, lane: &Lane, raw: &[u8]) -> ItemStatus {
+        let mut retries = 0;
+        loop {
+            lane.limiter.acquire().await;
+            match lane.sender.send_raw(raw).await {
+                Ok(hash) => {
+                    lane.limiter.on_success();
+                    return ItemStatus::Sent(hash);
+                }
+                Err(SendError::Throttled { retry_after }) => {
+                    lane.limiter.on_throttled();
+                    if retries == self.max_retries {
+                        return ItemStatus::Failed("rate limited".into());
+                    }
+                    retries += 1;
+                    if let Some(delay) = retry_after {
+                        tokio::time::sleep(delay).await;
+                    }
+                }
+                Err(SendError::Failed(reason)) => return ItemStatus::Failed(reason),
+            }
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Queue> {
+        self.queue.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Indexes each item depends on: its explicit dependencies, plus the
+/// previous nonce of its account on its chain
+fn dependencies(items: &[(ItemId, SignedTx)]) -> Vec<Vec<usize>> {
+    let index: HashMap<ItemId, usize> = items
+        .iter()
+        .enumerate()
+        .map(|(i, (id, _))| (*id, i))
+        .collect();
+    let mut deps: Vec<Vec<usize>> = items
+        .iter()
+        .map(|(_, tx)| tx.depends_on.iter().map(|id| index[id]).collect())
+        .collect();
+
+    let mut accounts: HashMap<(&str, &str), Vec<(u64, usize)>> = HashMap::new();
+    for (i, (_, tx)) in items.iter().enumerate() {
+        if let Some((sender, nonce)) = &tx.nonce {
+            accounts
+                .entry((&tx.chain, sender))
+                .or_default()
+                .push((*nonce, i));
+        }
+    }
+    for mut nonces in accounts.into_values() {
+        nonces.sort_unstable();
+        for pair in nonces.windows(2) {
+            deps[pair[1].1].push(pair[0].1);
+        }
+    }
+    deps
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+
+    /// Records what was sent; rejects `bad` and throttles the first send
+    #[derive(Default)]
+    struct MockSender {
+        sent: Mutex<Vec<Vec<u8>>>,
+        throttled: Mutex<bool>,
+    }
+
+    #[async_trait]
+    impl RawSender for MockSender {
+        async fn send_raw(&self, raw: &[u8]) -> std::result::Result<TxHash, SendError> {
+            if !std::mem::replace(&mut *self.throttled.lock().unwrap(), true) {
+                return Err(SendError::Throttled { retry_after: None });
+            }
+            if raw == b"bad" {
+                return Err(SendError::Failed("nonce too low".into()));
+            }
+            self.sent.lock().unwrap().push(raw.to_vec());
+            Ok(TxHash::new(format!("0x{}", hex::encode(raw))))
+        }
+    }
+
+    fn broadcaster(eth: Arc<MockSender>, sol: Arc<MockSender>) -> Broadcaster {
+        Broadcaster::new()
+            .chain("ethereum", eth, AimdConfig::new("ethereum"))
+            .chain("solana", sol, AimdConfig::new("solana"))
+    }
+
+    #[tokio::test]
+    async fn test_nonce_order_and_retry() {
+        let eth = Arc::new(MockSender::default());
+        let sol = Arc::new(MockSender::default());
+        let broadcaster = broadcaster(eth.clone(), sol.clone());
+
+        let report = broadcaster
+            .broadcast([
+                SignedTx::new("ethereum", b"n2".to_vec()).nonce("0xabc", 2),
+                SignedTx::new("ethereum", b"n0".to_vec()).nonce("0xabc", 0),
+                SignedTx::new("solana", b"s".to_vec()).label("alice"),
+                SignedTx::new("ethereum", b"n1".to_vec()).nonce("0xabc", 1),
+            ])
+            .await
+            .unwrap();
+
+        assert!(report.all_sent());
+        assert_eq!(
+            *eth.sent.lock().unwrap(),
+            vec![b"n0".to_vec(), b"n1".to_vec(), b"n2".to_vec()]
+        );
+        assert_eq!(report.items[2].label.as_deref(), Some("alice"));
+        assert_eq!(
+            report.status(ItemId(2)),
+            Some(&ItemStatus::Sent(TxHash::new("0x73")))
+        );
+        assert_eq!(broadcaster.queued(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_failure_skips_dependents() {
+        let eth = Arc::new(MockSender::default());
+        let broadcaster = broadcaster(eth.clone(), Arc::default()).max_retries(0);
+
+        assert!(matches!(
+            broadcaster.submit(SignedTx::new("bitcoin", vec![])),
+            Err(BroadcastError::UnknownChain(_))
+        ));
+        assert!(matches!(
+            broadcaster.submit(SignedTx::new("ethereum", vec![]).after(ItemId(9))),
+            Err(BroadcastError::UnknownDependency(ItemId(9)))
+        ));
+
+        // The first send is throttled and, without retries, fails
+        let throttled = broadcaster
+            .submit(SignedTx::new("ethereum", b"t".to_vec()))
+            .unwrap();
+        let n0 = broadcaster
+            .submit(SignedTx::new("ethereum", b"n0".to_vec()).nonce("0xabc", 0))
+            .unwrap();
+        let n1 = broadcaster
+            .submit(SignedTx::new("ethereum", b"bad".to_vec()).nonce("0xabc", 1))
+            .unwrap();
+        let n2 = broadcaster
+            .submit(SignedTx::new("ethereum", b"n2".to_vec()).nonce("0xabc", 2))
+            .unwrap();
+        let payout = broadcaster
+            .submit(SignedTx::new("solana", b"p".to_vec()).after(n2))
+            .unwrap();
+        let report = broadcaster.run().await;
+
+        assert_eq!(
+            report.status(throttled),
+            Some(&ItemStatus::Failed("rate limited".into()))
+        );
+        assert!(matches!(report.status(n0), Some(ItemStatus::Sent(_))));
+        assert_eq!(
+            report.status(n1),
+            Some(&ItemStatus::Failed("nonce too low".into()))
+        );
+        assert_eq!(report.status(n2), Some(&ItemStatus::Skipped { after: n1 }));
+        assert_eq!(
+            report.status(payout),
+            Some(&ItemStatus::Skipped { after: n2 })
+        );
+        assert_eq!(
+            (
+                report.sent().count(),
+                report.failed().count(),
+                report.skipped().count()
+            ),
+            (1, 2, 2)
+        );
+    }
+}
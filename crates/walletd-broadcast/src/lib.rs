@@ -0,0 +1,70 @@
+//! # WalletD Broadcast
+//!
+//! Sends large batches of signed transactions, such as payroll runs and
+//! airdrops. A [`Broadcaster`] queues transactions for any number of
+//! chains and sends them:
+//!
+//! - within each chain's rate limit, backing off when the node throttles
+//! - in nonce order per account, each nonce only after the previous one
+//!   was accepted
+//! - after any explicit dependencies, even across chains
+//!
+//! and reports a status per transaction. When a transaction fails, the
+//! transactions depending on it are skipped.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_broadcast::{Broadcaster, SignedTx};
+//! use walletd_resilience::AimdConfig;
+//!
+//! let broadcaster = Broadcaster::new()
+//!     .chain("ethereum", Arc::new(eth_provider), AimdConfig::new("ethereum"));
+//!
+//! let report = broadcaster
+//!     .broadcast(payroll.iter().enumerate().map(|(i, payment)| {
+//!         SignedTx::new("ethereum", payment.raw.clone())
+//!             .nonce(&treasury, first_nonce + i as u64)
+//!             .label(&payment.employee)
+//!     }))
+//!     .await?;
+//! for item in report.failed().chain(report.skipped()) {
+//!     println!("{:?}: {:?}", item.label, item.status);
+//! }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod broadcaster;
+pub mod sender;
+
+pub use broadcaster::{BatchReport, Broadcaster, ItemId, ItemReport, ItemStatus, SignedTx};
+pub use sender::{RawSender, SendError};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+
+/// Broadcasting errors
+#[derive(Error, Debug)]
+pub enum BroadcastError {
+    /// No sender is registered for the chain
+    #[error("No broadcaster for chain {0}")]
+    UnknownChain(String),
+
+    /// A dependency is not queued
+    #[error("Dependency {0} is not queued")]
+    UnknownDependency(ItemId),
+}
+
+/// Result type for broadcasting
+pub type Result<T> = std::result::Result<T, BroadcastError>;
+
+impl From<BroadcastError> for WalletdError {
+    fn from(e: BroadcastError) -> Self {
+        match e {
+            BroadcastError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            BroadcastError::UnknownDependency(_) => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,58 @@
+//! Submitting one signed transaction to a chain
+
+use async_trait::async_trait;
+use base64::Engine;
+use std::time::Duration;
+use walletd_provider::{ChainProvider, Evm, ProviderError, Solana};
+use walletd_traits::TxHash;
+
+/// Why a transaction was not accepted
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SendError {
+    /// The endpoint asked us to slow down; the transaction may be resent
+    Throttled {
+        /// Delay the endpoint asked for, if any
+        retry_after: Option<Duration>,
+    },
+    /// The node rejected the transaction or could not be reached
+    Failed(String),
+}
+
+impl From<ProviderError> for SendError {
+    fn from(e: ProviderError) -> Self {
+        if e.is_rate_limited() {
+            SendError::Throttled {
+                retry_after: e.retry_after(),
+            }
+        } else {
+            SendError::Failed(e.to_string())
+        }
+    }
+}
+
+/// Submits signed transactions to one chain
+#[async_trait]
+pub trait RawSender: Send + Sync {
+    /// Broadcasts a signed transaction, returning its hash
+    async fn send_raw(&self, raw: &[u8]) -> Result<TxHash, SendError>;
+}
+
+#[async_trait]
+impl RawSender for ChainProvider<Evm> {
+    /// Sends with `eth_sendRawTransaction`
+    async fn send_raw(&self, raw: &[u8]) -> Result<TxHash, SendError> {
+        let hash = self
+            .send_raw_transaction(&format!("0x{}", hex::encode(raw)))
+            .await?;
+        Ok(TxHash::new(hash))
+    }
+}
+
+#[async_trait]
+impl RawSender for ChainProvider<Solana> {
+    /// Sends with `sendTransaction`
+    async fn send_raw(&self, raw: &[u8]) -> Result<TxHash, SendError> {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(raw);
+        Ok(TxHash::new(self.send_transaction(&encoded).await?))
+    }
+}
@@ -0,0 +1,74 @@
+//! Per-chain finality rules
+
+use crate::source::ChainTip;
+use serde::{Deserialize, Serialize};
+
+/// Depth used for chains without a known rule
+pub const DEFAULT_DEPTH: u64 = 12;
+
+/// When an included transaction can no longer be reorganized away
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "rule", content = "value")]
+pub enum Finality {
+    /// Final once buried under this many blocks (counting its own),
+    /// the usual rule on proof-of-work chains
+    Depth(u64),
+    /// Final once the chain's finalized checkpoint reaches its block:
+    /// the `finalized` block tag on EVM chains, `finalized` commitment on
+    /// Solana, GRANDPA on Polkadot
+    Checkpoint,
+    /// Final on inclusion (Tendermint, Aptos, Sui, Avalanche)
+    Instant,
+}
+
+impl Finality {
+    /// The customary rule for `chain`, if it is a known chain
+    pub fn for_chain(chain: &str) -> Option<Self> {
+        let rule = match chain.to_ascii_lowercase().as_str() {
+            "bitcoin" | "bitcoin-testnet" | "bitcoin-cash" | "bch" => Finality::Depth(6),
+            "litecoin" | "ltc" => Finality::Depth(12),
+            "dogecoin" | "doge" => Finality::Depth(40),
+            "cardano" => Finality::Depth(15),
+            // Solidified blocks
+            "tron" => Finality::Depth(19),
+            "ethereum" | "sepolia" | "polygon" | "base" | "arbitrum" | "optimism" | "bsc"
+            | "solana" | "polkadot" | "kusama" => Finality::Checkpoint,
+            "avalanche" | "fuji" | "cosmos" | "cosmoshub" | "osmosis" | "near" | "aptos"
+            | "sui" | "ton" => Finality::Instant,
+            _ => return None,
+        };
+        Some(rule)
+    }
+
+    /// Whether a transaction at `height` with `confirmations` is final
+    pub fn is_final(&self, height: u64, confirmations: u64, tip: &ChainTip) -> bool {
+        match self {
+            Finality::Depth(depth) => confirmations >= *depth,
+            Finality::Checkpoint => tip.finalized.is_some_and(|finalized| finalized >= height),
+            Finality::Instant => true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules() {
+        assert_eq!(Finality::for_chain("Bitcoin"), Some(Finality::Depth(6)));
+        assert_eq!(Finality::for_chain("ethereum"), Some(Finality::Checkpoint));
+        assert_eq!(Finality::for_chain("aptos"), Some(Finality::Instant));
+        assert_eq!(Finality::for_chain("unknown"), None);
+
+        let tip = ChainTip {
+            height: 110,
+            finalized: Some(100),
+        };
+        assert!(!Finality::Depth(6).is_final(105, 5, &tip));
+        assert!(Finality::Depth(6).is_final(105, 6, &tip));
+        assert!(Finality::Checkpoint.is_final(100, 11, &tip));
+        assert!(!Finality::Checkpoint.is_final(101, 10, &tip));
+        assert!(!Finality::Checkpoint.is_final(1, 110, &ChainTip::new(110)));
+    }
+}
@@ -0,0 +1,76 @@
+//! # WalletD Confirmations
+//!
+//! Follows broadcast transactions from the mempool to finality. On each
+//! poll a [`ConfirmationTracker`] reads every chain's tip from its
+//! [`ChainSource`], looks up the block each transaction is in and emits
+//! typed transitions:
+//!
+//! - `Pending` → `Confirmed { confirmations, .. }` as blocks pile on top
+//! - `Confirmed` → `Finalized` under the chain's [`Finality`] rule: a block
+//!   depth on proof-of-work chains, the finalized checkpoint on Ethereum,
+//!   Solana and Polkadot, inclusion itself on instant-finality chains
+//! - `Confirmed` → `Reorged` when the transaction's block leaves the
+//!   canonical chain. Tracking continues, so a transaction mined again in
+//!   another block goes back to `Confirmed`
+//!
+//! `walletd-mempool` covers the time
+//! before a transaction is mined: stuck, dropped and replaced transactions.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_confirmations::{ChainConfig, ConfirmationTracker, Finality, TxStatus};
+//!
+//! let tracker = Arc::new(
+//!     ConfirmationTracker::new()
+//!         .chain(ChainConfig::new("ethereum", Arc::new(evm_source)))
+//!         .chain(ChainConfig::new("bitcoin", Arc::new(esplora)).finality(Finality::Depth(3))),
+//! );
+//! let mut events = tracker.subscribe();
+//! tracker.track("ethereum", hash)?;
+//! tracker.clone().spawn(Duration::from_secs(12));
+//!
+//! while let Ok(event) = events.recv().await {
+//!     if let TxStatus::Reorged { .. } = event.status {
+//!         println!("{} was reorged out", event.hash.0);
+//!     }
+//! }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod finality;
+pub mod source;
+pub mod tracker;
+
+pub use finality::{Finality, DEFAULT_DEPTH};
+pub use source::{ChainSource, ChainTip, Inclusion};
+pub use tracker::{ChainConfig, ConfirmationEvent, ConfirmationTracker, TxStatus};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+
+/// Confirmation tracking errors
+#[derive(Error, Debug)]
+pub enum ConfirmationError {
+    /// No source is registered for the chain
+    #[error("No confirmation source for chain {0}")]
+    UnknownChain(String),
+
+    /// The transaction is already tracked
+    #[error("Transaction already tracked: {0}")]
+    AlreadyTracked(String),
+}
+
+/// Result type for confirmation tracking
+pub type Result<T> = std::result::Result<T, ConfirmationError>;
+
+impl From<ConfirmationError> for WalletdError {
+    fn from(e: ConfirmationError) -> Self {
+        match e {
+            ConfirmationError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            e => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+//! Chain data the tracker needs
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use walletd_traits::{TxHash, WalletResult};
+
+/// The head of the canonical chain
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainTip {
+    /// Height of the newest block
+    pub height: u64,
+    /// Height of the newest finalized block, on chains that report one
+    pub finalized: Option<u64>,
+}
+
+impl ChainTip {
+    /// A tip without a finalized checkpoint
+    pub fn new(height: u64) -> Self {
+        Self {
+            height,
+            finalized: None,
+        }
+    }
+
+    /// Sets the finalized checkpoint
+    pub fn finalized(mut self, height: u64) -> Self {
+        self.finalized = Some(height);
+        self
+    }
+}
+
+/// The canonical block a transaction is in
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Inclusion {
+    /// Block height
+    pub height: u64,
+    /// Block hash
+    pub block_hash: String,
+}
+
+impl Inclusion {
+    /// Inclusion in block `block_hash` at `height`
+    pub fn new(height: u64, block_hash: impl Into<String>) -> Self {
+        Self {
+            height,
+            block_hash: block_hash.into(),
+        }
+    }
+}
+
+/// Reads the canonical chain of one network
+///
+/// EVM sources typically use `eth_blockNumber`, the `finalized` block tag
+/// and the `blockNumber`/`blockHash` of `eth_getTransactionReceipt`; Esplora
+/// sources use `/blocks/tip/height` and `/tx/:txid/status`. Answers must
+/// reflect the node's current canonical chain, not a cache, or reorgs go
+/// unnoticed.
+#[async_trait]
+pub trait ChainSource: Send + Sync {
+    /// Returns the current tip
+    async fn tip(&self) -> WalletResult<ChainTip>;
+
+    /// Returns the canonical block containing `hash`, or `None` if it is
+    /// not in the chain (pending, dropped or reorganized out)
+    async fn inclusion(&self, hash: &TxHash) -> WalletResult<Option<Inclusion>>;
+}
@@ -0,0 +1,450 @@
+//! Confirmation tracking across chains
+
+use crate::finality::{Finality, DEFAULT_DEPTH};
+use crate::source::{ChainSource, ChainTip, Inclusion};
+use crate::{ConfirmationError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+use tokio::sync::broadcast;
+use walletd_traits::TxHash;
+
+/// Where a tracked transaction stands
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "status")]
+pub enum TxStatus {
+    /// Broadcast but not in a block yet
+    Pending,
+    /// In a block that may still be reorganized away
+    Confirmed {
+        /// Blocks on top of it, counting its own
+        confirmations: u64,
+        /// Block height
+        height: u64,
+        /// Block hash
+        block_hash: String,
+    },
+    /// Final under the chain's [`Finality`] rule; tracking stops
+    Finalized {
+        /// Block height
+        height: u64,
+        /// Block hash
+        block_hash: String,
+    },
+    /// The block it was in left the canonical chain and it is not in a
+    /// block any more; tracking continues in case it is mined again
+    Reorged {
+        /// Height of the abandoned block
+        height: u64,
+        /// Hash of the abandoned block
+        block_hash: String,
+    },
+}
+
+/// A status transition of a tracked transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ConfirmationEvent {
+    /// Chain name
+    pub chain: String,
+    /// Transaction hash
+    pub hash: TxHash,
+    /// New status
+    pub status: TxStatus,
+}
+
+/// Tracking setup for one chain
+pub struct ChainConfig {
+    chain: String,
+    source: Arc<dyn ChainSource>,
+    finality: Finality,
+}
+
+impl ChainConfig {
+    /// Tracks `chain` through `source`
+    ///
+    /// The finality rule defaults to [`Finality::for_chain`], or
+    /// [`DEFAULT_DEPTH`] blocks for chains it does not know.
+    pub fn new(chain: impl Into<String>, source: Arc<dyn ChainSource>) -> Self {
+        let chain = chain.into();
+        let finality = Finality::for_chain(&chain).unwrap_or(Finality::Depth(DEFAULT_DEPTH));
+        Self {
+            chain,
+            source,
+            finality,
+        }
+    }
+
+    /// Sets the finality rule
+    pub fn finality(mut self, finality: Finality) -> Self {
+        self.finality = finality;
+        self
+    }
+}
+
+type Key = (String, TxHash);
+
+/// Follows broadcast transactions until they are final
+///
+/// Each [`poll`](Self::poll) reads every chain's tip once and looks up every
+/// tracked transaction. A transaction whose block changed or disappeared
+/// since the last poll was caught in a reorg: it is reported as
+/// [`TxStatus::Reorged`], then as confirmed again if it was mined into
+/// another block.
+pub struct ConfirmationTracker {
+    chains: HashMap<String, ChainConfig>,
+    tracked: Mutex<HashMap<Key, TxStatus>>,
+    events: broadcast::Sender<ConfirmationEvent>,
+}
+
+impl Default for ConfirmationTracker {
+    fn default() -> Self {
+        Self {
+            chains: HashMap::new(),
+            tracked: Mutex::new(HashMap::new()),
+            events: broadcast::channel(256).0,
+        }
+    }
+}
+
+impl fmt::Debug for ConfirmationTracker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ConfirmationTracker")
+            .field("chains", &self.chains.keys().collect::<Vec<_>>())
+            .field("tracked", &self.lock().len())
+            .finish()
+    }
+}
+
+impl ConfirmationTracker {
+    /// Creates a tracker without chains
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chain, replacing any other with the same name
+    pub fn chain(mut self, chain: ChainConfig) -> Self {
+        self.chains.insert(chain.chain.clone(), chain);
+        self
+    }
+
+    /// Returns the finality rule used for `chain`
+    pub fn finality(&self, chain: &str) -> Option<Finality> {
+        self.chains.get(chain).map(|c| c.finality)
+    }
+
+    /// Starts tracking a broadcast transaction
+    pub fn track(&self, chain: &str, hash: TxHash) -> Result<()> {
+        if !self.chains.contains_key(chain) {
+            return Err(ConfirmationError::UnknownChain(chain.to_string()));
+        }
+        let mut tracked = self.lock();
+        let key = (chain.to_string(), hash);
+        if tracked.contains_key(&key) {
+            return Err(ConfirmationError::AlreadyTracked(key.1 .0));
+        }
+        tracked.insert(key, TxStatus::Pending);
+        Ok(())
+    }
+
+    /// Stops tracking a transaction
+    pub fn untrack(&self, chain: &str, hash: &TxHash) -> bool {
+        self.lock()
+            .remove(&(chain.to_string(), hash.clone()))
+            .is_some()
+    }
+
+    /// Returns the last known status of a tracked transaction
+    pub fn status(&self, chain: &str, hash: &TxHash) -> Option<TxStatus> {
+        self.lock().get(&(chain.to_string(), hash.clone())).cloned()
+    }
+
+    /// Returns the number of tracked transactions
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    /// Whether nothing is tracked
+    pub fn is_empty(&self) -> bool {
+        self.lock().is_empty()
+    }
+
+    /// Subscribes to events from later polls
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfirmationEvent> {
+        self.events.subscribe()
+    }
+
+    /// Checks every tracked transaction once
+    pub async fn poll(&self) -> Vec<ConfirmationEvent> {
+        let snapshot: Vec<(Key, TxStatus)> = self
+            .lock()
+            .iter()
+            .map(|(key, status)| (key.clone(), status.clone()))
+            .collect();
+
+        let mut tips: HashMap<&str, Option<ChainTip>> = HashMap::new();
+        let mut events = Vec::new();
+        for ((chain_name, hash), status) in snapshot {
+            let Some(chain) = self.chains.get(&chain_name) else {
+                continue;
+            };
+            if !tips.contains_key(chain_name.as_str()) {
+                let tip = match chain.source.tip().await {
+                    Ok(tip) => Some(tip),
+                    Err(e) => {
+                        tracing::warn!(chain = %chain_name, error = %e, "failed to read chain tip");
+                        None
+                    }
+                };
+                tips.insert(chain.chain.as_str(), tip);
+            }
+            let Some(tip) = &tips[chain.chain.as_str()] else {
+                continue;
+            };
+            let inclusion = match chain.source.inclusion(&hash).await {
+                Ok(inclusion) => inclusion,
+                Err(e) => {
+                    tracing::warn!(chain = %chain_name, hash = %hash.0, error = %e, "failed to look up transaction");
+                    continue;
+                }
+            };
+
+            let transitions = advance(&status, inclusion, tip, chain.finality);
+            let Some(latest) = transitions.last().cloned() else {
+                continue;
+            };
+            let mut tracked = self.lock();
+            let key = (chain_name, hash);
+            // Skip transactions untracked while we were checking
+            if !tracked.contains_key(&key) {
+                continue;
+            }
+            if matches!(latest, TxStatus::Finalized { .. }) {
+                tracked.remove(&key);
+            } else {
+                tracked.insert(key.clone(), latest);
+            }
+            events.extend(transitions.into_iter().map(|status| ConfirmationEvent {
+                chain: key.0.clone(),
+                hash: key.1.clone(),
+                status,
+            }));
+        }
+        for event in &events {
+            // Nobody listening is fine; the events are also returned
+            let _ = self.events.send(event.clone());
+        }
+        events
+    }
+
+    /// Polls every `interval` on the Tokio runtime
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.poll().await;
+            }
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<Key, TxStatus>> {
+        self.tracked.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The statuses a transaction passes through since `status`, oldest first
+fn advance(
+    status: &TxStatus,
+    inclusion: Option<Inclusion>,
+    tip: &ChainTip,
+    finality: Finality,
+) -> Vec<TxStatus> {
+    let mut transitions = Vec::new();
+    if let TxStatus::Confirmed {
+        height, block_hash, ..
+    } = status
+    {
+        if inclusion
+            .as_ref()
+            .is_none_or(|i| i.block_hash != *block_hash)
+        {
+            transitions.push(TxStatus::Reorged {
+                height: *height,
+                block_hash: block_hash.clone(),
+            });
+        }
+    }
+
+    let Some(Inclusion { height, block_hash }) = inclusion else {
+        return transitions;
+    };
+    // A lagging node may report a tip below the block
+    let confirmations = tip.height.saturating_sub(height) + 1;
+    let next = if finality.is_final(height, confirmations, tip) {
+        TxStatus::Finalized { height, block_hash }
+    } else {
+        TxStatus::Confirmed {
+            confirmations,
+            height,
+            block_hash,
+        }
+    };
+    if next != *status {
+        transitions.push(next);
+    }
+    transitions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use walletd_traits::WalletResult;
+
+    /// A chain whose tip and blocks the test sets
+    #[derive(Default)]
+    struct Chain {
+        tip: Mutex<ChainTip>,
+        blocks: Mutex<HashMap<String, Inclusion>>,
+    }
+
+    impl Chain {
+        fn set_tip(&self, tip: ChainTip) {
+            *self.tip.lock().unwrap() = tip;
+        }
+
+        fn include(&self, hash: &str, height: u64, block_hash: &str) {
+            self.blocks
+                .lock()
+                .unwrap()
+                .insert(hash.to_string(), Inclusion::new(height, block_hash));
+        }
+
+        fn orphan(&self, hash: &str) {
+            self.blocks.lock().unwrap().remove(hash);
+        }
+    }
+
+    #[async_trait]
+    impl ChainSource for Chain {
+        async fn tip(&self) -> WalletResult<ChainTip> {
+            Ok(self.tip.lock().unwrap().clone())
+        }
+
+        async fn inclusion(&self, hash: &TxHash) -> WalletResult<Option<Inclusion>> {
+            Ok(self.blocks.lock().unwrap().get(&hash.0).cloned())
+        }
+    }
+
+    fn statuses(events: &[ConfirmationEvent]) -> Vec<&TxStatus> {
+        events.iter().map(|e| &e.status).collect()
+    }
+
+    #[tokio::test]
+    async fn test_confirms_reorgs_and_finalizes_by_depth() {
+        let chain = Arc::new(Chain::default());
+        let tracker = ConfirmationTracker::new().chain(ChainConfig::new("bitcoin", chain.clone()));
+        let tx = TxHash::new("aa");
+        tracker.track("bitcoin", tx.clone()).unwrap();
+        assert!(tracker.track("bitcoin", tx.clone()).is_err());
+        assert!(tracker.track("dogecoin", tx.clone()).is_err());
+
+        chain.set_tip(ChainTip::new(100));
+        assert!(tracker.poll().await.is_empty());
+
+        chain.include("aa", 101, "b101");
+        chain.set_tip(ChainTip::new(102));
+        let events = tracker.poll().await;
+        assert_eq!(
+            statuses(&events),
+            [&TxStatus::Confirmed {
+                confirmations: 2,
+                height: 101,
+                block_hash: "b101".into(),
+            }]
+        );
+
+        // The block is orphaned and the transaction goes back to the mempool
+        chain.orphan("aa");
+        let events = tracker.poll().await;
+        assert_eq!(
+            statuses(&events),
+            [&TxStatus::Reorged {
+                height: 101,
+                block_hash: "b101".into(),
+            }]
+        );
+        assert!(tracker.poll().await.is_empty());
+
+        // Mined again in a competing block, then buried
+        chain.include("aa", 102, "b102'");
+        chain.set_tip(ChainTip::new(103));
+        assert!(matches!(
+            statuses(&tracker.poll().await)[..],
+            [TxStatus::Confirmed {
+                confirmations: 2,
+                ..
+            }]
+        ));
+        chain.set_tip(ChainTip::new(107));
+        let events = tracker.poll().await;
+        assert_eq!(
+            events,
+            vec![ConfirmationEvent {
+                chain: "bitcoin".into(),
+                hash: tx.clone(),
+                status: TxStatus::Finalized {
+                    height: 102,
+                    block_hash: "b102'".into(),
+                },
+            }]
+        );
+        assert!(tracker.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_replaced_block_and_checkpoint_finality() {
+        let chain = Arc::new(Chain::default());
+        let tracker = ConfirmationTracker::new().chain(ChainConfig::new("ethereum", chain.clone()));
+        assert_eq!(tracker.finality("ethereum"), Some(Finality::Checkpoint));
+        let mut events = tracker.subscribe();
+        tracker.track("ethereum", TxHash::new("0xa")).unwrap();
+
+        chain.include("0xa", 50, "0xb50");
+        chain.set_tip(ChainTip::new(60).finalized(40));
+        let first = tracker.poll().await;
+        assert_eq!(events.recv().await.unwrap(), first[0]);
+
+        // A reorg swaps the block under it in one poll
+        chain.include("0xa", 51, "0xb51");
+        chain.set_tip(ChainTip::new(61).finalized(40));
+        let events = tracker.poll().await;
+        assert_eq!(
+            statuses(&events),
+            [
+                &TxStatus::Reorged {
+                    height: 50,
+                    block_hash: "0xb50".into(),
+                },
+                &TxStatus::Confirmed {
+                    confirmations: 11,
+                    height: 51,
+                    block_hash: "0xb51".into(),
+                },
+            ]
+        );
+
+        // Depth alone never finalizes a checkpoint chain
+        chain.set_tip(ChainTip::new(200).finalized(50));
+        tracker.poll().await;
+        assert!(!tracker.is_empty());
+        chain.set_tip(ChainTip::new(201).finalized(51));
+        assert!(matches!(
+            statuses(&tracker.poll().await)[..],
+            [TxStatus::Finalized { height: 51, .. }]
+        ));
+        assert!(tracker.status("ethereum", &TxHash::new("0xa")).is_none());
+    }
+}
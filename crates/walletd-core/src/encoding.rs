@@ -0,0 +1,44 @@
+//! Wire-format helpers shared by the chain crates
+
+/// Decodes a Solana compact-u16 ("short vec" length), returning the value
+/// and how many bytes it took
+///
+/// Returns `None` if `bytes` ends before the value does or the value does
+/// not fit in 16 bits.
+///
+/// # Example
+/// ```
+/// use walletd_core::decode_compact_u16;
+///
+/// assert_eq!(decode_compact_u16(&[0x80, 0x01, 0xff]), Some((128, 2)));
+/// ```
+pub fn decode_compact_u16(bytes: &[u8]) -> Option<(usize, usize)> {
+    let mut value = 0usize;
+    for (i, byte) in bytes.iter().take(3).enumerate() {
+        value |= ((byte & 0x7f) as usize) << (7 * i);
+        if byte & 0x80 == 0 {
+            return (value <= u16::MAX as usize).then_some((value, i + 1));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_compact_u16() {
+        assert_eq!(decode_compact_u16(&[0x05]), Some((5, 1)));
+        assert_eq!(decode_compact_u16(&[0x80, 0x01]), Some((128, 2)));
+        assert_eq!(decode_compact_u16(&[0xff, 0xff, 0x03]), Some((0xffff, 3)));
+    }
+
+    #[test]
+    fn test_decode_compact_u16_rejects_truncated_and_oversized() {
+        assert_eq!(decode_compact_u16(&[]), None);
+        assert_eq!(decode_compact_u16(&[0x80]), None);
+        assert_eq!(decode_compact_u16(&[0xff, 0xff, 0x04]), None);
+        assert_eq!(decode_compact_u16(&[0xff, 0xff, 0xff, 0x01]), None);
+    }
+}
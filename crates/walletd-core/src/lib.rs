@@ -47,6 +47,16 @@ pub fn ct_eq_64(a: &[u8; 64], b: &[u8; 64]) -> bool {
 // Re-export zeroize for secure memory cleanup
 pub use zeroize::{Zeroize, ZeroizeOnDrop};
 
+mod encoding;
+#[cfg(feature = "secp256k1")]
+mod recovery;
+mod secret;
+
+pub use encoding::decode_compact_u16;
+#[cfg(feature = "secp256k1")]
+pub use recovery::recovery_id;
+pub use secret::SecretBytes;
+
 // ============================================================================
 // CORE TYPES
 // ============================================================================
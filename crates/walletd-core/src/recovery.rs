@@ -0,0 +1,55 @@
+//! secp256k1 recovery ids for signers that only return `r || s`
+
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use secp256k1::{Message, PublicKey};
+
+/// Finds the recovery id (0 or 1) under which the 64-byte compact
+/// `signature` of `hash` recovers to `public_key`
+///
+/// Returns `None` if the inputs are malformed or the signature is not by
+/// `public_key`.
+pub fn recovery_id(hash: &[u8; 32], signature: &[u8], public_key: &[u8]) -> Option<u8> {
+    let message = Message::from_slice(hash).ok()?;
+    let expected = PublicKey::from_slice(public_key).ok()?;
+    (0..2).find_map(|id| {
+        let signature =
+            RecoverableSignature::from_compact(signature, RecoveryId::from_i32(id).ok()?).ok()?;
+        (signature.recover(&message).ok()? == expected).then_some(id as u8)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::{Secp256k1, SecretKey};
+
+    #[test]
+    fn test_recovery_id() {
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let public_key = PublicKey::from_secret_key(&secp, &key).serialize();
+        for hash in [[1u8; 32], [2u8; 32], [3u8; 32]] {
+            let signed = secp.sign_ecdsa_recoverable(&Message::from_slice(&hash).unwrap(), &key);
+            let (id, compact) = signed.serialize_compact();
+            assert_eq!(
+                recovery_id(&hash, &compact, &public_key),
+                Some(id.to_i32() as u8)
+            );
+        }
+    }
+
+    #[test]
+    fn test_recovery_id_other_key() {
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&[7u8; 32]).unwrap();
+        let other = SecretKey::from_slice(&[8u8; 32]).unwrap();
+        let hash = [1u8; 32];
+        let signature = secp.sign_ecdsa(&Message::from_slice(&hash).unwrap(), &key);
+        let other = PublicKey::from_secret_key(&secp, &other).serialize();
+        assert_eq!(
+            recovery_id(&hash, &signature.serialize_compact(), &other),
+            None
+        );
+        assert_eq!(recovery_id(&hash, &[0u8; 10], &other), None);
+    }
+}
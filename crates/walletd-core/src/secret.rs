@@ -0,0 +1,170 @@
+//! Heap-allocated secrets that stay out of logs, swap and copies
+
+use std::fmt;
+use subtle::ConstantTimeEq;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Secret key material
+///
+/// SECURITY: the bytes live in one heap allocation that is:
+/// - locked into RAM with `mlock`/`VirtualLock` where the platform allows,
+///   so it is not written to swap (best effort: see [`is_locked`](Self::is_locked))
+/// - zeroized on drop
+/// - redacted from `Debug`
+/// - compared in constant time
+///
+/// There is deliberately no `Clone`, `Deref` or `Serialize`; reading the
+/// secret takes an explicit [`expose_secret`](Self::expose_secret).
+///
+/// # Handle with care
+///
+/// ⚠️ These guarantees cover this allocation only. Bytes read out through
+/// [`expose_secret`](Self::expose_secret) or [`as_array`](Self::as_array)
+/// and copied elsewhere (hex strings, `Vec`s, arrays) are ordinary memory:
+/// keep such copies short-lived and zeroize them yourself. Key accessors
+/// across the SDK return or borrow a `SecretBytes` and link here.
+///
+/// # Example
+/// ```
+/// use walletd_core::SecretBytes;
+///
+/// let key = SecretBytes::from_slice(&[7u8; 32]);
+/// assert_eq!(format!("{:?}", key), "SecretBytes([REDACTED; 32])");
+/// assert_eq!(key.as_array::<32>(), Some(&[7u8; 32]));
+/// ```
+pub struct SecretBytes {
+    bytes: Box<[u8]>,
+    #[cfg(not(target_arch = "wasm32"))]
+    lock: Option<region::LockGuard>,
+}
+
+impl SecretBytes {
+    /// Copies `bytes` into locked memory
+    ///
+    /// The caller remains responsible for zeroizing its own copy.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        let bytes: Box<[u8]> = bytes.into();
+        #[cfg(not(target_arch = "wasm32"))]
+        let lock = if bytes.is_empty() {
+            None
+        } else {
+            // Fails when RLIMIT_MEMLOCK is exhausted; the secret is still
+            // zeroized, it may just be swapped out
+            region::lock(bytes.as_ptr(), bytes.len()).ok()
+        };
+        Self {
+            bytes,
+            #[cfg(not(target_arch = "wasm32"))]
+            lock,
+        }
+    }
+
+    /// Borrows the secret
+    pub fn expose_secret(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Borrows the secret as a fixed-size key, if it is `N` bytes long
+    pub fn as_array<const N: usize>(&self) -> Option<&[u8; N]> {
+        (*self.bytes).try_into().ok()
+    }
+
+    /// Length in bytes
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Whether the secret is empty
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    /// Whether the memory is locked against swapping
+    pub fn is_locked(&self) -> bool {
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            self.lock.is_some()
+        }
+        #[cfg(target_arch = "wasm32")]
+        {
+            false
+        }
+    }
+}
+
+impl From<Vec<u8>> for SecretBytes {
+    /// Moves the secret into locked memory and zeroizes the vector
+    fn from(mut bytes: Vec<u8>) -> Self {
+        let secret = Self::from_slice(&bytes);
+        bytes.zeroize();
+        secret
+    }
+}
+
+impl<const N: usize> From<[u8; N]> for SecretBytes {
+    /// Copies the secret into locked memory and zeroizes the array passed
+    /// in
+    ///
+    /// Arrays are `Copy`, so that is a by-value copy: the caller's own
+    /// variable is left intact and must still be zeroized, e.g. with
+    /// [`Zeroizing`](zeroize::Zeroizing) or by building the secret with
+    /// [`from_slice`](Self::from_slice) straight from its source.
+    fn from(mut bytes: [u8; N]) -> Self {
+        let secret = Self::from_slice(&bytes);
+        bytes.zeroize();
+        secret
+    }
+}
+
+impl Drop for SecretBytes {
+    fn drop(&mut self) {
+        // Runs before the lock guard is dropped, so the bytes are wiped
+        // while still locked
+        self.bytes.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for SecretBytes {}
+
+impl fmt::Debug for SecretBytes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecretBytes([REDACTED; {}])", self.bytes.len())
+    }
+}
+
+impl PartialEq for SecretBytes {
+    /// SECURITY: constant time for secrets of equal length
+    fn eq(&self, other: &Self) -> bool {
+        self.bytes.len() == other.bytes.len() && bool::from(self.bytes.ct_eq(&other.bytes))
+    }
+}
+
+impl Eq for SecretBytes {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_redacted_and_constant_time_eq() {
+        let a = SecretBytes::from(vec![1u8, 2, 3]);
+        let b = SecretBytes::from([1u8, 2, 3]);
+        let c = SecretBytes::from_slice(&[1u8, 2, 4]);
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_ne!(a, SecretBytes::from_slice(&[1u8, 2]));
+
+        let debug = format!("{:?}", a);
+        assert_eq!(debug, "SecretBytes([REDACTED; 3])");
+        assert!(!debug.contains('1'));
+        assert_eq!(a.expose_secret(), &[1, 2, 3]);
+        assert!(a.as_array::<32>().is_none());
+    }
+
+    #[test]
+    fn test_empty_secret() {
+        let empty = SecretBytes::from_slice(&[]);
+        assert!(empty.is_empty());
+        assert!(!empty.is_locked());
+    }
+}
@@ -0,0 +1,481 @@
+//! `walletd-ctl` command line
+//!
+//! Scriptable access to the SDK for ops teams: mnemonics, per-chain
+//! addresses, balances through the provider pool, offline signing and
+//! broadcasting. Every command prints one JSON document on stdout; errors
+//! go to stderr with a non-zero exit code.
+//!
+//! The mnemonic is read from `--mnemonic-file` or `WALLETD_MNEMONIC`, never
+//! from an argument, so it does not end up in shell history or `ps`
+//! output. `WALLETD_PASSPHRASE` sets the optional BIP-39 passphrase.
+//!
+//! ```text
+//! walletd-ctl mnemonic --words 24 | jq -r .mnemonic > seed.txt
+//! walletd-ctl --mnemonic-file seed.txt address ethereum --count 5
+//! walletd-ctl balance ethereum 0x9858... --rpc https://eth.llamarpc.com
+//! walletd-ctl --mnemonic-file seed.txt sign-tx tx.json > signed.json
+//! walletd-ctl broadcast ethereum 0x02f8... --rpc https://eth.llamarpc.com
+//! ```
+//!
+//! `walletd-ctl serve` runs the `walletd-server` signing daemon instead, with
+//! wallets kept in a keystore file and the API token taken from
+//! `WALLETD_API_TOKEN`:
+//!
+//! ```text
+//! walletd-ctl serve --keystore wallets.json --rpc ethereum=https://eth.llamarpc.com
+//! ```
+
+#![forbid(unsafe_code)]
+
+use anyhow::{bail, Context, Result};
+use bip39::Mnemonic;
+use clap::{Parser, Subcommand};
+use rand::RngCore;
+use serde_json::{json, Value};
+use std::io::Read;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use walletd_hd::{HdChain, HdManager};
+use walletd_provider::{ProviderConfig, ProviderPool, Solana};
+use walletd_server::{evm, ServerConfig, WalletService};
+use walletd_traits::Amount;
+use zeroize::Zeroizing;
+
+/// Pool name of the endpoint given with `--rpc`
+const RPC: &str = "rpc";
+
+#[derive(Debug, Parser)]
+#[command(
+    name = "walletd-ctl",
+    version,
+    about = "WalletD multi-chain wallet tools"
+)]
+struct Cli {
+    /// File holding the BIP-39 mnemonic [default: $WALLETD_MNEMONIC]
+    #[arg(long, global = true, value_name = "PATH")]
+    mnemonic_file: Option<PathBuf>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Generates a new BIP-39 mnemonic
+    Mnemonic {
+        /// Number of words: 12, 15, 18, 21 or 24
+        #[arg(long, default_value_t = 24)]
+        words: usize,
+    },
+    /// Lists the chains accounts can be derived for
+    Chains,
+    /// Derives account addresses
+    Address {
+        /// Chain name or ticker, e.g. `ethereum`, `btc`, `sol`
+        chain: HdChain,
+        /// First account index
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+        /// Number of consecutive accounts
+        #[arg(long, default_value_t = 1)]
+        count: u32,
+    },
+    /// Reads a native or token balance (EVM chains and Solana)
+    Balance {
+        /// Chain name or ticker
+        chain: HdChain,
+        /// Account address
+        address: String,
+        /// ERC-20 contract or SPL mint instead of the native asset
+        #[arg(long)]
+        token: Option<String>,
+        /// Token decimals, used to format token balances
+        #[arg(long)]
+        decimals: Option<u8>,
+        /// JSON-RPC endpoint
+        #[arg(long, env = "WALLETD_RPC_URL")]
+        rpc: String,
+    },
+    /// Signs a 32-byte digest with an account key
+    SignHash {
+        /// Chain name or ticker
+        chain: HdChain,
+        /// Digest as 64 hex characters
+        hash: String,
+        /// Account index
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+    },
+    /// Signs a message; EIP-191 `personal_sign` on EVM chains
+    SignMessage {
+        /// Chain name or ticker
+        chain: HdChain,
+        /// UTF-8 message, or `0x` hex for raw bytes
+        message: String,
+        /// Account index
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+    },
+    /// Signs an EVM transaction offline from a JSON file (`-` for stdin)
+    SignTx {
+        /// Transaction JSON
+        file: PathBuf,
+        /// Account index
+        #[arg(long, default_value_t = 0)]
+        index: u32,
+    },
+    /// Broadcasts a signed transaction (EVM hex, or Solana base64)
+    Broadcast {
+        /// Chain name or ticker
+        chain: HdChain,
+        /// Signed transaction
+        raw: String,
+        /// JSON-RPC endpoint
+        #[arg(long, env = "WALLETD_RPC_URL")]
+        rpc: String,
+    },
+    /// Runs the signing daemon [token: $WALLETD_API_TOKEN]
+    Serve {
+        /// Keystore file holding the daemon's wallets
+        #[arg(long, value_name = "PATH")]
+        keystore: PathBuf,
+        /// Listen address
+        #[arg(long, default_value = walletd_server::http::DEFAULT_ADDR)]
+        listen: SocketAddr,
+        /// Chain endpoint for balances and sending, e.g. `base=https://...`
+        #[arg(long = "rpc", value_name = "CHAIN=URL")]
+        rpcs: Vec<String>,
+        /// Seconds an unlocked wallet stays unlocked
+        #[arg(long, default_value_t = 300)]
+        unlock_timeout: u64,
+    },
+}
+
+#[tokio::main]
+async fn main() {
+    let cli = Cli::parse();
+    match run(cli).await {
+        Ok(output) => println!(
+            "{}",
+            serde_json::to_string_pretty(&output).expect("JSON output")
+        ),
+        Err(e) => {
+            eprintln!("error: {:#}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+async fn run(cli: Cli) -> Result<Value> {
+    let mnemonic_file = cli.mnemonic_file.as_deref();
+    match cli.command {
+        Command::Mnemonic { words } => {
+            let mnemonic = generate_mnemonic(words)?;
+            Ok(json!({ "words": words, "mnemonic": mnemonic.to_string() }))
+        }
+        Command::Chains => Ok(HdChain::ALL
+            .iter()
+            .map(|chain| {
+                json!({
+                    "chain": chain.name(),
+                    "curve": chain.curve().to_string(),
+                    "coin_type": chain.coin_type(),
+                    "path": chain.path(0),
+                })
+            })
+            .collect()),
+        Command::Address {
+            chain,
+            index,
+            count,
+        } => {
+            let manager = load_manager(mnemonic_file)?;
+            let mut accounts = Vec::new();
+            for index in index..index.saturating_add(count) {
+                let account = manager.account(chain, index)?;
+                accounts.push(json!({
+                    "chain": chain.name(),
+                    "index": index,
+                    "path": account.path(),
+                    "address": account.address(),
+                }));
+            }
+            Ok(Value::Array(accounts))
+        }
+        Command::Balance {
+            chain,
+            address,
+            token,
+            decimals,
+            rpc,
+        } => balance(chain, &address, token.as_deref(), decimals, &rpc).await,
+        Command::SignHash { chain, hash, index } => {
+            let hash: [u8; 32] = decode_hex(&hash)?
+                .try_into()
+                .map_err(|_| anyhow::anyhow!("hash must be 32 bytes"))?;
+            let account = load_manager(mnemonic_file)?.account(chain, index)?;
+            let signature = account.signer().sign_hash(&hash).await?;
+            Ok(json!({
+                "address": account.address(),
+                "public_key": hex::encode(account.signer().public_key()),
+                "signature": format!("0x{}", hex::encode(signature)),
+            }))
+        }
+        Command::SignMessage {
+            chain,
+            message,
+            index,
+        } => {
+            let bytes = match message.strip_prefix("0x") {
+                Some(_) => decode_hex(&message)?,
+                None => message.into_bytes(),
+            };
+            let account = load_manager(mnemonic_file)?.account(chain, index)?;
+            let signature = if chain.is_evm() {
+                evm::personal_sign(account.signer(), &bytes).await?
+            } else {
+                account.signer().sign_message(&bytes).await?
+            };
+            Ok(json!({
+                "address": account.address(),
+                "public_key": hex::encode(account.signer().public_key()),
+                "signature": format!("0x{}", hex::encode(signature)),
+            }))
+        }
+        Command::SignTx { file, index } => {
+            let tx: Value = serde_json::from_str(&read_input(&file)?)
+                .context("transaction is not valid JSON")?;
+            let tx = evm::TxRequest::from_json(&tx)?;
+            let account = load_manager(mnemonic_file)?.account(HdChain::Ethereum, index)?;
+            let signed = tx.sign(account.signer()).await?;
+            Ok(json!({
+                "from": evm::address(account.signer())?,
+                "raw": format!("0x{}", hex::encode(&signed.raw)),
+                "hash": format!("0x{}", hex::encode(signed.hash)),
+            }))
+        }
+        Command::Broadcast { chain, raw, rpc } => {
+            let pool = pool(&rpc)?;
+            let hash = if chain.is_evm() {
+                let raw = match raw.strip_prefix("0x") {
+                    Some(_) => raw,
+                    None => format!("0x{}", raw),
+                };
+                pool.evm(RPC)?.send_raw_transaction(&raw).await?
+            } else if chain == HdChain::Solana {
+                pool.chain::<Solana>(RPC)?.send_transaction(&raw).await?
+            } else {
+                bail!("broadcasting on {} is not supported yet", chain)
+            };
+            Ok(json!({ "chain": chain.name(), "hash": hash }))
+        }
+        Command::Serve {
+            keystore,
+            listen,
+            rpcs,
+            unlock_timeout,
+        } => {
+            let service = WalletService::open(&keystore)?
+                .with_pool(Arc::new(chain_pool(&rpcs)?))
+                .with_unlock_timeout(Duration::from_secs(unlock_timeout));
+            let token = match std::env::var("WALLETD_API_TOKEN") {
+                Ok(token) => Zeroizing::new(token),
+                Err(_) => {
+                    let mut bytes = [0u8; 32];
+                    rand::rngs::OsRng.fill_bytes(&mut bytes);
+                    let token = Zeroizing::new(hex::encode(bytes));
+                    eprintln!(
+                        "WALLETD_API_TOKEN not set, using generated token {}",
+                        *token
+                    );
+                    token
+                }
+            };
+            let config = ServerConfig::new(token.as_str()).with_addr(listen);
+            eprintln!("walletd-ctl listening on {}", listen);
+            walletd_server::serve(Arc::new(service), config).await?;
+            Ok(Value::Null)
+        }
+    }
+}
+
+async fn balance(
+    chain: HdChain,
+    address: &str,
+    token: Option<&str>,
+    decimals: Option<u8>,
+    rpc: &str,
+) -> Result<Value> {
+    let pool = pool(rpc)?;
+    let (raw, native_decimals) = if chain.is_evm() {
+        let evm = pool.evm(RPC)?;
+        let raw = match token {
+            Some(token) => evm.erc20_balance(token, address).await?,
+            None => evm.get_balance(address).await?,
+        };
+        (raw, 18)
+    } else if chain == HdChain::Solana {
+        let solana = pool.chain::<Solana>(RPC)?;
+        let raw = match token {
+            Some(mint) => solana.get_token_balance(address, mint).await?,
+            None => solana.get_balance(address).await?,
+        };
+        (raw as u128, 9)
+    } else {
+        bail!("balance lookups on {} are not supported yet", chain)
+    };
+    let decimals = match token {
+        Some(_) => decimals,
+        None => Some(native_decimals),
+    };
+    Ok(json!({
+        "chain": chain.name(),
+        "address": address,
+        "token": token,
+        "balance": raw.to_string(),
+        "formatted": decimals.map(|d| Amount::from_smallest_unit(raw, d).to_string()),
+    }))
+}
+
+/// A mnemonic from fresh OS randomness
+fn generate_mnemonic(words: usize) -> Result<Mnemonic> {
+    if !matches!(words, 12 | 15 | 18 | 21 | 24) {
+        bail!("word count must be 12, 15, 18, 21 or 24");
+    }
+    let mut entropy = Zeroizing::new(vec![0u8; words / 3 * 4]);
+    rand::rngs::OsRng.fill_bytes(&mut entropy);
+    Ok(Mnemonic::from_entropy(&entropy)?)
+}
+
+/// Opens the wallet from `--mnemonic-file` or `WALLETD_MNEMONIC`
+fn load_manager(mnemonic_file: Option<&Path>) -> Result<HdManager> {
+    let phrase = Zeroizing::new(match mnemonic_file {
+        Some(path) => std::fs::read_to_string(path)
+            .with_context(|| format!("cannot read {}", path.display()))?,
+        None => std::env::var("WALLETD_MNEMONIC")
+            .context("no mnemonic: pass --mnemonic-file or set WALLETD_MNEMONIC")?,
+    });
+    let passphrase = Zeroizing::new(std::env::var("WALLETD_PASSPHRASE").unwrap_or_default());
+    Ok(HdManager::from_mnemonic(phrase.trim(), &passphrase)?)
+}
+
+fn pool(rpc: &str) -> Result<ProviderPool> {
+    let pool = ProviderPool::new();
+    pool.add(RPC, ProviderConfig::new(rpc))?;
+    Ok(pool)
+}
+
+/// A pool with one provider per `chain=url`, named after the chain
+fn chain_pool(rpcs: &[String]) -> Result<ProviderPool> {
+    let pool = ProviderPool::new();
+    for rpc in rpcs {
+        let (chain, url) = rpc
+            .split_once('=')
+            .with_context(|| format!("expected CHAIN=URL, got {}", rpc))?;
+        let chain: HdChain = chain.parse()?;
+        pool.add(chain.name(), ProviderConfig::new(url))?;
+    }
+    Ok(pool)
+}
+
+/// Reads a file, or stdin for `-`
+fn read_input(path: &Path) -> Result<String> {
+    if path == Path::new("-") {
+        let mut input = String::new();
+        std::io::stdin().read_to_string(&mut input)?;
+        return Ok(input);
+    }
+    std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value)).context("invalid hex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::CommandFactory;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_cli_definition() {
+        Cli::command().debug_assert();
+        let cli = Cli::try_parse_from(["walletd-ctl", "address", "eth", "--count", "3"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Command::Address {
+                chain: HdChain::Ethereum,
+                index: 0,
+                count: 3
+            }
+        ));
+        assert!(Cli::try_parse_from(["walletd-ctl", "address", "dogecoin"]).is_err());
+
+        let cli = Cli::try_parse_from([
+            "walletd-ctl",
+            "serve",
+            "--keystore",
+            "k.json",
+            "--rpc",
+            "eth=http://localhost:8545",
+        ])
+        .unwrap();
+        let Command::Serve { rpcs, listen, .. } = cli.command else {
+            panic!("expected serve");
+        };
+        assert_eq!(listen.port(), 8645);
+        assert!(chain_pool(&rpcs).unwrap().evm("ethereum").is_ok());
+        assert!(chain_pool(&["http://localhost".into()]).is_err());
+    }
+
+    #[test]
+    fn test_generate_mnemonic() {
+        assert_eq!(generate_mnemonic(12).unwrap().word_count(), 12);
+        assert_eq!(generate_mnemonic(24).unwrap().word_count(), 24);
+        assert!(generate_mnemonic(13).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_address_and_sign_from_file() {
+        let path = std::env::temp_dir().join(format!("walletd-ctl-{}.txt", std::process::id()));
+        std::fs::write(&path, format!("{}\n", PHRASE)).unwrap();
+        let file = path.to_str().unwrap();
+
+        let cli = Cli::try_parse_from([
+            "walletd-ctl",
+            "--mnemonic-file",
+            file,
+            "address",
+            "ethereum",
+        ]);
+        let output = run(cli.unwrap()).await.unwrap();
+        assert_eq!(
+            output[0]["address"],
+            "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+        );
+
+        let tx = std::env::temp_dir().join(format!("walletd-ctl-tx-{}.json", std::process::id()));
+        std::fs::write(
+            &tx,
+            r#"{"chainId": 1, "nonce": 0, "gas": 21000, "gasPrice": "0x3b9aca00", "to": "0x3535353535353535353535353535353535353535", "value": 1}"#,
+        )
+        .unwrap();
+        let cli = Cli::try_parse_from([
+            "walletd-ctl",
+            "--mnemonic-file",
+            file,
+            "sign-tx",
+            tx.to_str().unwrap(),
+        ]);
+        let output = run(cli.unwrap()).await.unwrap();
+        assert_eq!(output["from"], "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+        assert!(output["raw"].as_str().unwrap().starts_with("0xf8"));
+
+        std::fs::remove_file(path).unwrap();
+        std::fs::remove_file(tx).unwrap();
+    }
+}
@@ -27,17 +27,55 @@
 //!     Ok(())
 //! }
 //! ```
+//!
+//! ## Wire format
+//!
+//! With the `serde` feature, [`WalletdError`] serializes as an object tagged
+//! by `kind` (the variant name in snake_case), with the variant's fields
+//! under `data`. Unit variants have no `data`, and balances are decimal
+//! strings so they survive JavaScript's 53-bit numbers:
+//!
+//! ```json
+//! {"kind": "invalid_address", "data": {"address": "0x123", "reason": "too short"}}
+//! {"kind": "insufficient_balance", "data": {"have": "100", "need": "200"}}
+//! {"kind": "signing_error", "data": "bad key"}
+//! {"kind": "missing_private_key"}
+//! {"kind": "chain", "data": {"chain": "ton", "error": {"kind": "network_error", "data": "timeout"}}}
+//! ```
+//!
+//! [`ErrorCode`] serializes as its number. Codes this version does not know
+//! deserialize as [`ErrorCode::Unknown`], so older readers accept errors
+//! from newer writers.
+//!
+//! ## Telemetry
+//!
+//! [`WalletdError::report`] passes a redacted [`telemetry::ErrorReport`] to
+//! the hook set with [`telemetry::set_hook`], and with the `tracing` feature
+//! emits it as a structured event.
+//!
+//! ## Node errors
+//!
+//! [`rpc`] maps EVM and Solana JSON-RPC errors and Cosmos ABCI codes onto
+//! specific variants, e.g. a stale nonce onto [`WalletdError::InvalidNonce`].
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
 use thiserror::Error;
 
+pub mod rpc;
+pub mod telemetry;
+
 /// The main error type for WalletD operations.
 ///
 /// This enum covers all possible errors that can occur during wallet operations
 /// across all supported blockchains.
 #[derive(Error, Debug)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(tag = "kind", content = "data", rename_all = "snake_case")
+)]
 pub enum WalletdError {
     // ============ Address Errors ============
     /// Invalid address format or checksum
@@ -58,8 +96,10 @@ pub enum WalletdError {
     #[error("Insufficient balance: have {have}, need {need}")]
     InsufficientBalance {
         /// Available balance (in smallest unit)
+        #[cfg_attr(feature = "serde", serde(with = "amount_string"))]
         have: u128,
         /// Required balance (in smallest unit)
+        #[cfg_attr(feature = "serde", serde(with = "amount_string"))]
         need: u128,
     },
 
@@ -96,6 +136,14 @@ pub enum WalletdError {
     #[error("Transaction timed out waiting for confirmation")]
     TransactionTimeout,
 
+    /// Nonce or sequence number rejected (too low, too high or reused)
+    #[error("Invalid nonce: {0}")]
+    InvalidNonce(String),
+
+    /// Fee or gas price below what the node accepts
+    #[error("Fee too low: {0}")]
+    FeeTooLow(String),
+
     // ============ Key/Crypto Errors ============
     /// Invalid private key
     #[error("Invalid private key: {0}")]
@@ -154,6 +202,10 @@ pub enum WalletdError {
         retry_after_secs: u64,
     },
 
+    /// Network or node unavailable, with no more specific detail
+    #[error("Network error: {0}")]
+    NetworkError(String),
+
     /// Invalid chain ID
     #[error("Invalid chain ID: expected {expected}, got {got}")]
     ChainIdMismatch {
@@ -235,6 +287,19 @@ pub enum WalletdError {
     #[error("Base error: {0}")]
     BaseError(String),
 
+    /// Error raised by a chain crate, tagged with its source chain
+    ///
+    /// [`code`](WalletdError::code) and
+    /// [`is_retryable`](WalletdError::is_retryable) answer for the wrapped
+    /// error. Build with [`WalletdError::on_chain`].
+    #[error("{chain}: {error}")]
+    Chain {
+        /// Chain the error came from, e.g. `ton`
+        chain: String,
+        /// The categorized error
+        error: Box<WalletdError>,
+    },
+
     // ============ Feature/Support Errors ============
     /// Feature not supported
     #[error("Feature not supported: {0}")]
@@ -352,6 +417,10 @@ pub enum ErrorCode {
     TransactionNotFound = 3004,
     /// Transaction failed
     TransactionFailed = 3005,
+    /// Nonce rejected
+    InvalidNonce = 3006,
+    /// Fee too low
+    FeeTooLow = 3007,
     /// RPC connection error
     RpcConnectionError = 4001,
     /// RPC request error
@@ -360,14 +429,91 @@ pub enum ErrorCode {
     NetworkTimeout = 4003,
     /// Rate limited
     RateLimited = 4004,
+    /// Network error
+    NetworkError = 4005,
     /// Contract error
     ContractError = 5001,
     /// Not synced
     NotSynced = 6001,
+    /// Invalid, missing or underivable key material
+    KeyError = 7001,
     /// Not supported
     NotSupported = 9001,
 }
 
+impl ErrorCode {
+    /// Every known code
+    pub const ALL: [ErrorCode; 21] = [
+        ErrorCode::Unknown,
+        ErrorCode::InvalidAddress,
+        ErrorCode::AddressNotFound,
+        ErrorCode::InsufficientBalance,
+        ErrorCode::AmountOverflow,
+        ErrorCode::TransactionBuildError,
+        ErrorCode::SigningError,
+        ErrorCode::BroadcastError,
+        ErrorCode::TransactionNotFound,
+        ErrorCode::TransactionFailed,
+        ErrorCode::InvalidNonce,
+        ErrorCode::FeeTooLow,
+        ErrorCode::RpcConnectionError,
+        ErrorCode::RpcRequestError,
+        ErrorCode::NetworkTimeout,
+        ErrorCode::RateLimited,
+        ErrorCode::NetworkError,
+        ErrorCode::ContractError,
+        ErrorCode::NotSynced,
+        ErrorCode::KeyError,
+        ErrorCode::NotSupported,
+    ];
+
+    /// Numeric value of the code
+    pub fn as_u32(self) -> u32 {
+        self as u32
+    }
+
+    /// Looks up a code by its numeric value, `None` if it is not known
+    pub fn from_u32(code: u32) -> Option<Self> {
+        Self::ALL.into_iter().find(|c| c.as_u32() == code)
+    }
+}
+
+impl From<ErrorCode> for u32 {
+    fn from(code: ErrorCode) -> Self {
+        code.as_u32()
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for ErrorCode {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_u32(self.as_u32())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for ErrorCode {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let code = <u32 as serde::Deserialize>::deserialize(deserializer)?;
+        Ok(Self::from_u32(code).unwrap_or(ErrorCode::Unknown))
+    }
+}
+
+/// Serializes `u128` amounts as decimal strings
+#[cfg(feature = "serde")]
+mod amount_string {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(amount: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(amount)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        let s = <std::borrow::Cow<'de, str>>::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl WalletdError {
     /// Returns the error code for this error
     pub fn code(&self) -> ErrorCode {
@@ -381,12 +527,21 @@ impl WalletdError {
             WalletdError::BroadcastError(_) => ErrorCode::BroadcastError,
             WalletdError::TransactionNotFound(_) => ErrorCode::TransactionNotFound,
             WalletdError::TransactionFailed(_) => ErrorCode::TransactionFailed,
+            WalletdError::InvalidNonce(_) => ErrorCode::InvalidNonce,
+            WalletdError::FeeTooLow(_) => ErrorCode::FeeTooLow,
             WalletdError::RpcConnectionError { .. } => ErrorCode::RpcConnectionError,
             WalletdError::RpcRequestError { .. } => ErrorCode::RpcRequestError,
             WalletdError::NetworkTimeout { .. } => ErrorCode::NetworkTimeout,
             WalletdError::RateLimited { .. } => ErrorCode::RateLimited,
+            WalletdError::NetworkError(_) => ErrorCode::NetworkError,
             WalletdError::ContractError(_) => ErrorCode::ContractError,
             WalletdError::NotSynced => ErrorCode::NotSynced,
+            WalletdError::InvalidPrivateKey(_)
+            | WalletdError::InvalidPublicKey(_)
+            | WalletdError::InvalidMnemonic(_)
+            | WalletdError::KeyDerivationError(_)
+            | WalletdError::MissingPrivateKey => ErrorCode::KeyError,
+            WalletdError::Chain { error, .. } => error.code(),
             WalletdError::NotSupported(_) => ErrorCode::NotSupported,
             _ => ErrorCode::Unknown,
         }
@@ -394,24 +549,114 @@ impl WalletdError {
 
     /// Returns true if this error is retryable
     pub fn is_retryable(&self) -> bool {
-        matches!(
-            self,
-            WalletdError::NetworkTimeout { .. }
-                | WalletdError::RateLimited { .. }
-                | WalletdError::RpcConnectionError { .. }
-                | WalletdError::TransactionTimeout
-        )
+        match self {
+            WalletdError::Chain { error, .. } => error.is_retryable(),
+            _ => matches!(
+                self,
+                WalletdError::NetworkTimeout { .. }
+                    | WalletdError::RateLimited { .. }
+                    | WalletdError::RpcConnectionError { .. }
+                    | WalletdError::NetworkError(_)
+                    | WalletdError::TransactionTimeout
+            ),
+        }
     }
 
     /// Returns suggested retry delay in seconds, if applicable
     pub fn retry_after(&self) -> Option<u64> {
         match self {
+            WalletdError::Chain { error, .. } => error.retry_after(),
             WalletdError::RateLimited { retry_after_secs } => Some(*retry_after_secs),
             WalletdError::NetworkTimeout { seconds } => Some(*seconds / 2),
             _ if self.is_retryable() => Some(5),
             _ => None,
         }
     }
+
+    /// Variant name in snake_case, as used for `kind` in the wire format
+    pub fn kind(&self) -> &'static str {
+        match self {
+            WalletdError::InvalidAddress { .. } => "invalid_address",
+            WalletdError::AddressNotFound(_) => "address_not_found",
+            WalletdError::InsufficientBalance { .. } => "insufficient_balance",
+            WalletdError::AmountOverflow(_) => "amount_overflow",
+            WalletdError::InvalidAmount(_) => "invalid_amount",
+            WalletdError::TransactionBuildError(_) => "transaction_build_error",
+            WalletdError::SigningError(_) => "signing_error",
+            WalletdError::BroadcastError(_) => "broadcast_error",
+            WalletdError::TransactionNotFound(_) => "transaction_not_found",
+            WalletdError::TransactionFailed(_) => "transaction_failed",
+            WalletdError::TransactionTimeout => "transaction_timeout",
+            WalletdError::InvalidNonce(_) => "invalid_nonce",
+            WalletdError::FeeTooLow(_) => "fee_too_low",
+            WalletdError::InvalidPrivateKey(_) => "invalid_private_key",
+            WalletdError::InvalidPublicKey(_) => "invalid_public_key",
+            WalletdError::InvalidMnemonic(_) => "invalid_mnemonic",
+            WalletdError::KeyDerivationError(_) => "key_derivation_error",
+            WalletdError::MissingPrivateKey => "missing_private_key",
+            WalletdError::SignatureVerificationFailed(_) => "signature_verification_failed",
+            WalletdError::RpcConnectionError { .. } => "rpc_connection_error",
+            WalletdError::RpcRequestError { .. } => "rpc_request_error",
+            WalletdError::NetworkTimeout { .. } => "network_timeout",
+            WalletdError::RateLimited { .. } => "rate_limited",
+            WalletdError::NetworkError(_) => "network_error",
+            WalletdError::ChainIdMismatch { .. } => "chain_id_mismatch",
+            WalletdError::ContractError(_) => "contract_error",
+            WalletdError::ContractNotFound(_) => "contract_not_found",
+            WalletdError::AbiError(_) => "abi_error",
+            WalletdError::NotSynced => "not_synced",
+            WalletdError::WalletExists(_) => "wallet_exists",
+            WalletdError::WalletNotFound(_) => "wallet_not_found",
+            WalletdError::InvalidState(_) => "invalid_state",
+            WalletdError::HexError(_) => "hex_error",
+            WalletdError::JsonError(_) => "json_error",
+            WalletdError::FormatError(_) => "format_error",
+            WalletdError::BitcoinError(_) => "bitcoin_error",
+            WalletdError::EthereumError(_) => "ethereum_error",
+            WalletdError::SolanaError(_) => "solana_error",
+            WalletdError::IcpError(_) => "icp_error",
+            WalletdError::MoneroError(_) => "monero_error",
+            WalletdError::HederaError(_) => "hedera_error",
+            WalletdError::BaseError(_) => "base_error",
+            WalletdError::Chain { .. } => "chain",
+            WalletdError::NotSupported(_) => "not_supported",
+            WalletdError::NotImplemented(_) => "not_implemented",
+            WalletdError::IoError(_) => "io_error",
+            WalletdError::ConfigError(_) => "config_error",
+            WalletdError::Other(_) => "other",
+            WalletdError::External { .. } => "external",
+        }
+    }
+
+    /// Sends this error to the telemetry hook and tracing
+    ///
+    /// Returns the error so it can be reported inline:
+    /// `return Err(err.report())`. See [`telemetry`].
+    pub fn report(self) -> Self {
+        telemetry::emit(&telemetry::ErrorReport::new(&self));
+        self
+    }
+
+    /// Tags this error with the chain it came from
+    ///
+    /// An error that is already tagged keeps its original chain.
+    pub fn on_chain(self, chain: impl Into<String>) -> Self {
+        match self {
+            WalletdError::Chain { .. } => self,
+            error => WalletdError::Chain {
+                chain: chain.into(),
+                error: Box::new(error),
+            },
+        }
+    }
+
+    /// The chain this error came from, if it was tagged with one
+    pub fn chain(&self) -> Option<&str> {
+        match self {
+            WalletdError::Chain { chain, .. } => Some(chain),
+            _ => None,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -452,6 +697,66 @@ mod tests {
         assert_eq!(invalid.retry_after(), None);
     }
 
+    #[test]
+    fn test_chain_errors() {
+        let err = WalletdError::NetworkError("connection reset".into()).on_chain("ton");
+        assert_eq!(err.chain(), Some("ton"));
+        assert_eq!(err.code(), ErrorCode::NetworkError);
+        assert!(err.is_retryable());
+        assert_eq!(err.to_string(), "ton: Network error: connection reset");
+
+        let key = WalletdError::InvalidMnemonic("bad checksum".into()).on_chain("sui");
+        assert_eq!(key.code(), ErrorCode::KeyError);
+        assert!(!key.is_retryable());
+        assert_eq!(key.on_chain("aptos").chain(), Some("sui"));
+        assert_eq!(WalletdError::NotSynced.chain(), None);
+    }
+
+    #[test]
+    fn test_error_code_numbers() {
+        for code in ErrorCode::ALL {
+            assert_eq!(ErrorCode::from_u32(code.as_u32()), Some(code));
+        }
+        assert_eq!(u32::from(ErrorCode::RateLimited), 4004);
+        assert_eq!(ErrorCode::from_u32(1234), None);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_wire_format() {
+        let err = WalletdError::InsufficientBalance { have: u128::MAX, need: 1 };
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "kind": "insufficient_balance",
+                "data": {"have": u128::MAX.to_string(), "need": "1"}
+            })
+        );
+        assert_eq!(json["kind"], err.kind());
+        let back: WalletdError = serde_json::from_value(json).unwrap();
+        assert!(matches!(back, WalletdError::InsufficientBalance { have: u128::MAX, need: 1 }));
+
+        let unit = serde_json::to_string(&WalletdError::MissingPrivateKey).unwrap();
+        assert_eq!(unit, r#"{"kind":"missing_private_key"}"#);
+
+        let rpc: WalletdError = serde_json::from_str(
+            r#"{"data":{"method":"eth_call","reason":"reverted"},"kind":"rpc_request_error"}"#,
+        )
+        .unwrap();
+        assert_eq!(rpc.code(), ErrorCode::RpcRequestError);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_serde_error_code() {
+        assert_eq!(serde_json::to_string(&ErrorCode::InvalidAddress).unwrap(), "1001");
+        let known: ErrorCode = serde_json::from_str("4004").unwrap();
+        assert_eq!(known, ErrorCode::RateLimited);
+        let future: ErrorCode = serde_json::from_str("7777").unwrap();
+        assert_eq!(future, ErrorCode::Unknown);
+    }
+
     #[test]
     fn test_error_context() {
         let result: std::result::Result<(), std::io::Error> = 
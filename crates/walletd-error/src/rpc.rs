@@ -0,0 +1,279 @@
+//! Mapping of well-known node error codes to [`WalletdError`] variants
+//!
+//! Nodes report most failures through a handful of codes whose meaning
+//! depends on the chain family, with the detail only in the message. The
+//! tables here turn those into specific variants (a stale nonce becomes
+//! [`WalletdError::InvalidNonce`], an overdrawn account
+//! [`WalletdError::InsufficientBalance`]) so callers can branch on
+//! [`WalletdError::code`] instead of matching strings.
+//!
+//! Message patterns are checked before codes, since EVM nodes send nearly
+//! every transaction rejection as `-32000`. Anything unrecognized stays a
+//! [`WalletdError::RpcRequestError`].
+//!
+//! ```
+//! use walletd_error::{rpc, ErrorCode};
+//!
+//! let err = rpc::evm("eth_sendRawTransaction", -32000, "nonce too low");
+//! assert_eq!(err.code(), ErrorCode::InvalidNonce);
+//! ```
+
+use crate::WalletdError;
+
+/// What a code or message pattern maps to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Kind {
+    Format,
+    NotSupported,
+    Network,
+    RateLimited,
+    TxBuild,
+    TxFailed,
+    TxTimeout,
+    Broadcast,
+    Nonce,
+    FeeTooLow,
+    Contract,
+    Signature,
+    InsufficientFunds,
+    AddressNotFound,
+    InvalidAddress,
+    InvalidPublicKey,
+    InvalidAmount,
+}
+
+/// Standard JSON-RPC 2.0 and EIP-1474 codes
+const EVM_CODES: &[(i64, Kind)] = &[
+    (-32700, Kind::Format),
+    (-32601, Kind::NotSupported),
+    (-32002, Kind::Network),
+    (-32003, Kind::TxFailed),
+    (-32004, Kind::NotSupported),
+    (-32005, Kind::RateLimited),
+    // Geth's code for reverted calls and gas estimation
+    (3, Kind::Contract),
+];
+
+/// Geth, Erigon, Nethermind and Besu rejection messages
+const EVM_MESSAGES: &[(&str, Kind)] = &[
+    ("nonce too low", Kind::Nonce),
+    ("nonce too high", Kind::Nonce),
+    ("invalid nonce", Kind::Nonce),
+    ("insufficient funds", Kind::InsufficientFunds),
+    ("replacement transaction underpriced", Kind::FeeTooLow),
+    ("transaction underpriced", Kind::FeeTooLow),
+    ("max fee per gas less than block base fee", Kind::FeeTooLow),
+    ("fee cap less than block base fee", Kind::FeeTooLow),
+    ("intrinsic gas too low", Kind::TxBuild),
+    ("exceeds block gas limit", Kind::TxBuild),
+    ("gas limit reached", Kind::TxBuild),
+    ("invalid sender", Kind::Signature),
+    ("invalid signature", Kind::Signature),
+    ("already known", Kind::Broadcast),
+    ("known transaction", Kind::Broadcast),
+    ("txpool is full", Kind::Network),
+    ("execution reverted", Kind::Contract),
+    ("rate limit", Kind::RateLimited),
+];
+
+/// Solana RPC server error codes
+const SOLANA_CODES: &[(i64, Kind)] = &[
+    (-32700, Kind::Format),
+    (-32601, Kind::NotSupported),
+    (-32003, Kind::Signature),
+    (-32004, Kind::Network),
+    (-32005, Kind::Network),
+    (-32013, Kind::Signature),
+    (-32014, Kind::Network),
+    (-32015, Kind::NotSupported),
+    (-32016, Kind::Network),
+    (429, Kind::RateLimited),
+];
+
+/// Solana preflight failures, reported under `-32002`
+const SOLANA_MESSAGES: &[(&str, Kind)] = &[
+    ("blockhash not found", Kind::TxBuild),
+    ("insufficient lamports", Kind::InsufficientFunds),
+    ("insufficient funds", Kind::InsufficientFunds),
+    ("insufficientfundsforrent", Kind::InsufficientFunds),
+    ("insufficientfundsforfee", Kind::InsufficientFunds),
+    ("accountnotfound", Kind::AddressNotFound),
+    ("attempt to debit an account but found no record", Kind::AddressNotFound),
+    ("already been processed", Kind::Broadcast),
+    ("alreadyprocessed", Kind::Broadcast),
+    ("signature verification failure", Kind::Signature),
+    ("custom program error", Kind::Contract),
+    ("program failed to complete", Kind::Contract),
+    ("computational budget exceeded", Kind::TxBuild),
+];
+
+/// Cosmos SDK ABCI codes in the `sdk` codespace (`types/errors`)
+const COSMOS_SDK_CODES: &[(u32, Kind)] = &[
+    (2, Kind::Format),
+    (3, Kind::Nonce),
+    (4, Kind::Signature),
+    (5, Kind::InsufficientFunds),
+    (6, Kind::NotSupported),
+    (7, Kind::InvalidAddress),
+    (8, Kind::InvalidPublicKey),
+    (9, Kind::AddressNotFound),
+    (10, Kind::InvalidAmount),
+    (11, Kind::TxFailed),
+    (12, Kind::TxBuild),
+    (13, Kind::FeeTooLow),
+    (19, Kind::Broadcast),
+    (20, Kind::Network),
+    (21, Kind::TxBuild),
+    (22, Kind::AddressNotFound),
+    (30, Kind::TxTimeout),
+    (32, Kind::Nonce),
+];
+
+/// Maps an EVM JSON-RPC error
+pub fn evm(method: &str, code: i64, message: &str) -> WalletdError {
+    let kind = by_message(EVM_MESSAGES, message).or_else(|| by_code(EVM_CODES, code));
+    build(kind, method, message)
+}
+
+/// Maps a Solana JSON-RPC error, including preflight simulation failures
+pub fn solana(method: &str, code: i64, message: &str) -> WalletdError {
+    let kind = by_message(SOLANA_MESSAGES, message).or_else(|| by_code(SOLANA_CODES, code));
+    build(kind, method, message)
+}
+
+/// Maps a failed Cosmos transaction result by ABCI codespace, code and log
+///
+/// Only the `sdk` codespace has a table; module-specific codes become
+/// [`WalletdError::TransactionFailed`].
+pub fn cosmos(codespace: &str, code: u32, raw_log: &str) -> WalletdError {
+    if codespace != "sdk" {
+        return WalletdError::TransactionFailed(format!("{}/{}: {}", codespace, code, raw_log));
+    }
+    let kind = COSMOS_SDK_CODES
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, kind)| *kind)
+        .unwrap_or(Kind::TxFailed);
+    build(Some(kind), "broadcast_tx", raw_log)
+}
+
+fn by_message(table: &[(&str, Kind)], message: &str) -> Option<Kind> {
+    let message = message.to_ascii_lowercase();
+    table
+        .iter()
+        .find(|(pattern, _)| message.contains(pattern))
+        .map(|(_, kind)| *kind)
+}
+
+fn by_code(table: &[(i64, Kind)], code: i64) -> Option<Kind> {
+    table.iter().find(|(c, _)| *c == code).map(|(_, kind)| *kind)
+}
+
+fn build(kind: Option<Kind>, method: &str, message: &str) -> WalletdError {
+    let message = message.to_string();
+    let Some(kind) = kind else {
+        return WalletdError::RpcRequestError {
+            method: method.to_string(),
+            reason: message,
+        };
+    };
+    match kind {
+        Kind::Format => WalletdError::FormatError(message),
+        Kind::NotSupported => WalletdError::NotSupported(format!("{}: {}", method, message)),
+        Kind::Network => WalletdError::NetworkError(message),
+        // Providers rarely say how long; one second lets backoff take over
+        Kind::RateLimited => WalletdError::RateLimited { retry_after_secs: 1 },
+        Kind::TxBuild => WalletdError::TransactionBuildError(message),
+        Kind::TxFailed => WalletdError::TransactionFailed(message),
+        Kind::TxTimeout => WalletdError::TransactionTimeout,
+        Kind::Broadcast => WalletdError::BroadcastError(message),
+        Kind::Nonce => WalletdError::InvalidNonce(message),
+        Kind::FeeTooLow => WalletdError::FeeTooLow(message),
+        Kind::Contract => WalletdError::ContractError(message),
+        Kind::Signature => WalletdError::SignatureVerificationFailed(message),
+        Kind::InsufficientFunds => match balances(&message) {
+            Some((have, need)) => WalletdError::InsufficientBalance { have, need },
+            None => WalletdError::TransactionFailed(message),
+        },
+        Kind::AddressNotFound => WalletdError::AddressNotFound(message),
+        Kind::InvalidAddress => WalletdError::InvalidAddress {
+            address: String::new(),
+            reason: message,
+        },
+        Kind::InvalidPublicKey => WalletdError::InvalidPublicKey(message),
+        Kind::InvalidAmount => WalletdError::InvalidAmount(message),
+    }
+}
+
+/// Extracts (have, need) from the usual insufficient-funds messages
+///
+/// Geth: `... have 100 want 200`; Solana: `insufficient lamports 100, need
+/// 200`; Cosmos: `spendable balance 100uatom is smaller than 200uatom`.
+fn balances(message: &str) -> Option<(u128, u128)> {
+    const FORMS: &[(&str, &str)] = &[
+        ("have ", "want "),
+        ("insufficient lamports ", "need "),
+        ("balance ", "smaller than "),
+    ];
+    FORMS.iter().find_map(|(have, need)| {
+        Some((number_after(message, have)?, number_after(message, need)?))
+    })
+}
+
+fn number_after(message: &str, marker: &str) -> Option<u128> {
+    let start = message.find(marker)? + marker.len();
+    let digits: String = message[start..]
+        .chars()
+        .take_while(|c| c.is_ascii_digit())
+        .collect();
+    digits.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ErrorCode;
+
+    #[test]
+    fn test_evm_messages() {
+        let send = "eth_sendRawTransaction";
+        assert_eq!(evm(send, -32000, "nonce too low: next nonce 5, tx nonce 4").code(), ErrorCode::InvalidNonce);
+        assert_eq!(evm(send, -32000, "replacement transaction underpriced").code(), ErrorCode::FeeTooLow);
+        assert_eq!(evm("eth_call", 3, "execution reverted: ERC20: transfer amount exceeds balance").code(), ErrorCode::ContractError);
+
+        let err = evm(send, -32000, "insufficient funds for gas * price + value: address 0xabc have 100 want 250");
+        assert!(matches!(err, WalletdError::InsufficientBalance { have: 100, need: 250 }));
+        let err = evm(send, -32000, "insufficient funds for transfer");
+        assert_eq!(err.code(), ErrorCode::TransactionFailed);
+    }
+
+    #[test]
+    fn test_evm_codes() {
+        assert_eq!(evm("eth_foo", -32601, "the method eth_foo does not exist").code(), ErrorCode::NotSupported);
+        let limited = evm("eth_call", -32005, "daily request count exceeded");
+        assert!(limited.is_retryable());
+        let unknown = evm("eth_call", -32000, "header not found");
+        assert!(matches!(unknown, WalletdError::RpcRequestError { ref method, .. } if method == "eth_call"));
+    }
+
+    #[test]
+    fn test_solana() {
+        let send = "sendTransaction";
+        let msg = "Transaction simulation failed: Blockhash not found";
+        assert_eq!(solana(send, -32002, msg).code(), ErrorCode::TransactionBuildError);
+        let msg = "Transaction simulation failed: Error processing Instruction 0: custom program error: 0x1";
+        assert_eq!(solana(send, -32002, msg).code(), ErrorCode::ContractError);
+        let err = solana(send, -32002, "Transfer: insufficient lamports 100, need 200");
+        assert!(matches!(err, WalletdError::InsufficientBalance { have: 100, need: 200 }));
+        assert!(solana("getBalance", -32005, "Node is unhealthy").is_retryable());
+    }
+
+    #[test]
+    fn test_cosmos() {
+        let err = cosmos("sdk", 5, "spendable balance 10uatom is smaller than 100uatom: insufficient funds");
+        assert!(matches!(err, WalletdError::InsufficientBalance { have: 10, need: 100 }));
+        assert_eq!(cosmos("sdk", 32, "account sequence mismatch, expected 4, got 3").code(), ErrorCode::InvalidNonce);
+        assert_eq!(cosmos("sdk", 13, "insufficient fees").code(), ErrorCode::FeeTooLow);
+        assert_eq!(cosmos("wasm", 5, "execute wasm contract failed").code(), ErrorCode::TransactionFailed);
+    }
+}
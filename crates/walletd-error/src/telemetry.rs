@@ -0,0 +1,189 @@
+//! Error telemetry
+//!
+//! [`WalletdError::report`] turns an error into an [`ErrorReport`] holding
+//! only what is safe to ship to monitoring: code, kind, chain, retryability
+//! and a redacted message. The report goes to the hook installed with
+//! [`set_hook`], and, with the `tracing` feature, is emitted as a structured
+//! event on the `walletd::error` target.
+//!
+//! ```
+//! use walletd_error::{telemetry, WalletdError};
+//!
+//! telemetry::set_hook(|report| {
+//!     // e.g. bump a counter per (code, chain)
+//!     let _ = (report.code, report.chain.as_deref());
+//! });
+//! WalletdError::NetworkError("connection reset".into()).on_chain("ton").report();
+//! telemetry::clear_hook();
+//! ```
+
+use std::sync::RwLock;
+
+use crate::{ErrorCode, WalletdError};
+
+/// Placeholder for redacted text
+pub const REDACTED: &str = "[redacted]";
+
+/// Tokens at least this long that look like keys, hashes or addresses are
+/// redacted from messages
+const MIN_REDACTED_TOKEN: usize = 20;
+
+type Hook = Box<dyn Fn(&ErrorReport) + Send + Sync>;
+
+static HOOK: RwLock<Option<Hook>> = RwLock::new(None);
+
+/// Monitoring view of a [`WalletdError`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ErrorReport {
+    /// Error code
+    pub code: ErrorCode,
+    /// Variant name of the underlying error, see [`WalletdError::kind`]
+    pub kind: &'static str,
+    /// Chain the error came from, if tagged
+    pub chain: Option<String>,
+    /// Whether the operation may succeed if retried
+    pub retryable: bool,
+    /// Suggested retry delay in seconds
+    pub retry_after_secs: Option<u64>,
+    /// Error message with key material, hashes and addresses removed
+    pub context: String,
+}
+
+impl ErrorReport {
+    /// Builds the report for an error without emitting it
+    pub fn new(error: &WalletdError) -> Self {
+        let mut inner = error;
+        while let WalletdError::Chain { error, .. } = inner {
+            inner = error;
+        }
+        Self {
+            code: error.code(),
+            kind: inner.kind(),
+            chain: error.chain().map(str::to_string),
+            retryable: error.is_retryable(),
+            retry_after_secs: error.retry_after(),
+            context: redacted_context(inner),
+        }
+    }
+}
+
+/// Installs the process-wide hook that receives every reported error
+///
+/// Replaces any previous hook.
+pub fn set_hook(hook: impl Fn(&ErrorReport) + Send + Sync + 'static) {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = Some(Box::new(hook));
+}
+
+/// Removes the hook installed with [`set_hook`]
+pub fn clear_hook() {
+    *HOOK.write().unwrap_or_else(|e| e.into_inner()) = None;
+}
+
+/// Sends a report to the hook and, with the `tracing` feature, to tracing
+pub(crate) fn emit(report: &ErrorReport) {
+    #[cfg(feature = "tracing")]
+    {
+        let code = report.code.as_u32();
+        let chain = report.chain.as_deref().unwrap_or("");
+        if report.retryable {
+            tracing::warn!(
+                target: "walletd::error",
+                code,
+                kind = report.kind,
+                chain,
+                retryable = true,
+                retry_after_secs = report.retry_after_secs,
+                context = %report.context,
+                "wallet operation failed"
+            );
+        } else {
+            tracing::error!(
+                target: "walletd::error",
+                code,
+                kind = report.kind,
+                chain,
+                retryable = false,
+                context = %report.context,
+                "wallet operation failed"
+            );
+        }
+    }
+    if let Some(hook) = HOOK.read().unwrap_or_else(|e| e.into_inner()).as_ref() {
+        hook(report);
+    }
+}
+
+/// Message of `error` that is safe to log
+///
+/// Errors about key material keep only their kind. Otherwise any long token
+/// of letters and digits (private keys, seeds, hashes, addresses) is
+/// replaced with [`REDACTED`].
+pub fn redacted_context(error: &WalletdError) -> String {
+    if error.code() == ErrorCode::KeyError {
+        return REDACTED.to_string();
+    }
+    redact(&error.to_string())
+}
+
+fn redact(message: &str) -> String {
+    let mut out = String::with_capacity(message.len());
+    let mut token = String::new();
+    let flush = |token: &mut String, out: &mut String| {
+        if token.len() >= MIN_REDACTED_TOKEN {
+            out.push_str(REDACTED);
+        } else {
+            out.push_str(token);
+        }
+        token.clear();
+    };
+    for c in message.chars() {
+        if c.is_ascii_alphanumeric() {
+            token.push(c);
+        } else {
+            flush(&mut token, &mut out);
+            out.push(c);
+        }
+    }
+    flush(&mut token, &mut out);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_redaction() {
+        let err = WalletdError::InvalidAddress {
+            address: "0x9858EfFD232B4033E47d90003D41EC34EcaEda94".into(),
+            reason: "bad checksum".into(),
+        };
+        assert_eq!(
+            redacted_context(&err),
+            "Invalid address '[redacted]': bad checksum"
+        );
+
+        let key = WalletdError::InvalidMnemonic("abandon abandon about".into());
+        assert_eq!(redacted_context(&key), REDACTED);
+    }
+
+    #[test]
+    fn test_report_and_hook() {
+        let err = WalletdError::RateLimited { retry_after_secs: 3 }.on_chain("sui");
+        let report = ErrorReport::new(&err);
+        assert_eq!(report.code, ErrorCode::RateLimited);
+        assert_eq!(report.kind, "rate_limited");
+        assert_eq!(report.chain.as_deref(), Some("sui"));
+        assert!(report.retryable);
+        assert_eq!(report.retry_after_secs, Some(3));
+
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let sink = seen.clone();
+        set_hook(move |r| sink.lock().unwrap().push(r.clone()));
+        let err = err.report();
+        clear_hook();
+        err.report();
+        assert_eq!(*seen.lock().unwrap(), vec![report]);
+    }
+}
@@ -0,0 +1,150 @@
+//! Bitcoin fees from mempool.space
+//!
+//! Uses `/api/v1/fees/recommended`. The same API is served by self-hosted
+//! mempool instances and by the testnet and signet explorers, so point
+//! [`MempoolSpace::with_base_url`] at any of them.
+
+use crate::{get_json, now, FeeError, Result};
+use async_trait::async_trait;
+use walletd_traits::{FeeEstimator, FeeOption, FeeOptions, FeeRate, FeeSpeed, WalletResult};
+
+/// Public mainnet API
+pub const MEMPOOL_SPACE_URL: &str = "https://mempool.space/api";
+
+/// Fields used for each speed, slow to fast, with their confirmation target
+const TARGETS: [(FeeSpeed, &str, u64); 3] = [
+    (FeeSpeed::Slow, "hourFee", 3600),
+    (FeeSpeed::Standard, "halfHourFee", 1800),
+    (FeeSpeed::Fast, "fastestFee", 600),
+];
+
+/// Recommended Bitcoin fee rates from a mempool.space API
+#[derive(Debug, Clone)]
+pub struct MempoolSpace {
+    client: reqwest::Client,
+    base_url: String,
+    chain: String,
+}
+
+impl Default for MempoolSpace {
+    fn default() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: MEMPOOL_SPACE_URL.to_string(),
+            chain: "bitcoin".to_string(),
+        }
+    }
+}
+
+impl MempoolSpace {
+    /// Mainnet estimates from mempool.space
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses another instance, e.g. `https://mempool.space/testnet/api`
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the chain name reported in [`FeeOptions`] (default `bitcoin`)
+    pub fn with_chain(mut self, chain: &str) -> Self {
+        self.chain = chain.to_string();
+        self
+    }
+
+    async fn estimate(&self) -> Result<FeeOptions> {
+        let body = get_json(
+            self.client
+                .get(format!("{}/v1/fees/recommended", self.base_url)),
+        )
+        .await?;
+        let mut options = Vec::with_capacity(TARGETS.len());
+        for (speed, field, seconds) in TARGETS {
+            let rate = body[field]
+                .as_f64()
+                .ok_or_else(|| FeeError::InvalidResponse(format!("missing {}", field)))?;
+            options.push(FeeOption {
+                speed,
+                rate: FeeRate::SatPerVbyte(rate),
+                estimated_seconds: Some(seconds),
+            });
+        }
+        Ok(FeeOptions {
+            chain: self.chain.clone(),
+            options,
+            updated_at: now(),
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for MempoolSpace {
+    fn chain(&self) -> &str {
+        &self.chain
+    }
+
+    async fn fee_options(&self) -> WalletResult<FeeOptions> {
+        Ok(self.estimate().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::time::Duration;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_recommended_fees() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/fees/recommended"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "fastestFee": 24,
+                "halfHourFee": 18,
+                "hourFee": 12,
+                "economyFee": 6,
+                "minimumFee": 3
+            })))
+            .mount(&server)
+            .await;
+
+        let mempool = MempoolSpace::new().with_base_url(&server.uri());
+        let options = mempool.fee_options().await.unwrap();
+        assert_eq!(options.chain, "bitcoin");
+        let rates: Vec<_> = options.options.iter().map(|o| o.rate.clone()).collect();
+        assert_eq!(
+            rates,
+            [
+                FeeRate::SatPerVbyte(12.0),
+                FeeRate::SatPerVbyte(18.0),
+                FeeRate::SatPerVbyte(24.0)
+            ]
+        );
+        assert_eq!(
+            options.get(FeeSpeed::Fast).unwrap().estimated_seconds,
+            Some(600)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "20"))
+            .mount(&server)
+            .await;
+
+        let mempool = MempoolSpace::new().with_base_url(&server.uri());
+        match mempool.estimate().await {
+            Err(FeeError::RateLimited { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(20)))
+            }
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+    }
+}
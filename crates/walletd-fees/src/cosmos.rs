@@ -0,0 +1,173 @@
+//! Cosmos SDK gas prices
+//!
+//! Most Cosmos chains have no fee market: validators accept any gas price
+//! above their minimum, and the chain registry publishes low, average and
+//! high prices. Chains running Skip's `x/feemarket` module expose a live
+//! base price at `/feemarket/v1/gas_price/{denom}`; when an LCD endpoint is
+//! configured, that price is fetched and marked up for the faster options.
+
+use crate::{get_json, now, FeeError, Result};
+use async_trait::async_trait;
+use walletd_traits::{FeeEstimator, FeeOption, FeeOptions, FeeRate, FeeSpeed, WalletResult};
+
+/// Markup on the fee market base price, slow to fast
+const FEEMARKET_MARKUP: [f64; 3] = [1.0, 1.2, 1.5];
+
+/// Seconds to inclusion for every speed; blocks are not full on most chains
+const BLOCK_SECONDS: u64 = 6;
+
+const SPEEDS: [FeeSpeed; 3] = [FeeSpeed::Slow, FeeSpeed::Standard, FeeSpeed::Fast];
+
+/// Gas prices for a Cosmos SDK chain
+#[derive(Debug, Clone)]
+pub struct CosmosGasPrices {
+    chain: String,
+    denom: String,
+    prices: [f64; 3],
+    client: reqwest::Client,
+    lcd_url: Option<String>,
+}
+
+impl CosmosGasPrices {
+    /// Fixed prices, e.g. the chain registry's `low`, `average` and `high`
+    /// gas prices for `denom`
+    pub fn new(chain: &str, denom: &str, low: f64, average: f64, high: f64) -> Self {
+        Self {
+            chain: chain.to_string(),
+            denom: denom.to_string(),
+            prices: [low, average, high],
+            client: reqwest::Client::new(),
+            lcd_url: None,
+        }
+    }
+
+    /// Cosmos Hub defaults (0.005 / 0.025 / 0.03 uatom)
+    pub fn cosmoshub() -> Self {
+        Self::new("cosmoshub", "uatom", 0.005, 0.025, 0.03)
+    }
+
+    /// Osmosis defaults (0.0025 / 0.025 / 0.04 uosmo)
+    pub fn osmosis() -> Self {
+        Self::new("osmosis", "uosmo", 0.0025, 0.025, 0.04)
+    }
+
+    /// Reads the live price from an `x/feemarket` LCD endpoint
+    ///
+    /// The fixed prices are used if the endpoint fails.
+    pub fn with_feemarket(mut self, lcd_url: &str) -> Self {
+        self.lcd_url = Some(lcd_url.trim_end_matches('/').to_string());
+        self
+    }
+
+    async fn estimate(&self) -> Result<FeeOptions> {
+        let prices = match &self.lcd_url {
+            Some(url) => match self.feemarket_price(url).await {
+                Ok(base) => FEEMARKET_MARKUP.map(|markup| base * markup),
+                Err(e) => {
+                    tracing::debug!("feemarket price for {} unavailable: {}", self.chain, e);
+                    self.prices
+                }
+            },
+            None => self.prices,
+        };
+        Ok(FeeOptions {
+            chain: self.chain.clone(),
+            options: SPEEDS
+                .iter()
+                .zip(prices)
+                .map(|(&speed, amount)| FeeOption {
+                    speed,
+                    rate: FeeRate::CosmosGasPrice {
+                        amount,
+                        denom: self.denom.clone(),
+                    },
+                    estimated_seconds: Some(BLOCK_SECONDS),
+                })
+                .collect(),
+            updated_at: now(),
+        })
+    }
+
+    async fn feemarket_price(&self, url: &str) -> Result<f64> {
+        let body = get_json(
+            self.client
+                .get(format!("{}/feemarket/v1/gas_price/{}", url, self.denom)),
+        )
+        .await?;
+        body["price"]["amount"]
+            .as_str()
+            .and_then(|amount| amount.parse().ok())
+            .ok_or_else(|| FeeError::InvalidResponse("missing price.amount".into()))
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for CosmosGasPrices {
+    fn chain(&self) -> &str {
+        &self.chain
+    }
+
+    async fn fee_options(&self) -> WalletResult<FeeOptions> {
+        Ok(self.estimate().await?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn amounts(options: &FeeOptions) -> Vec<f64> {
+        options
+            .options
+            .iter()
+            .map(|o| match &o.rate {
+                FeeRate::CosmosGasPrice { amount, .. } => *amount,
+                other => panic!("unexpected rate {:?}", other),
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_fixed_prices() {
+        let options = CosmosGasPrices::cosmoshub().fee_options().await.unwrap();
+        assert_eq!(options.chain, "cosmoshub");
+        assert_eq!(amounts(&options), [0.005, 0.025, 0.03]);
+        assert_eq!(
+            options
+                .get(FeeSpeed::Standard)
+                .unwrap()
+                .rate
+                .max_fee(200_000),
+            5000
+        );
+    }
+
+    #[tokio::test]
+    async fn test_feemarket_price() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/feemarket/v1/gas_price/uosmo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "price": {"denom": "uosmo", "amount": "0.100000000000000000"}
+            })))
+            .mount(&server)
+            .await;
+
+        let options = CosmosGasPrices::osmosis()
+            .with_feemarket(&server.uri())
+            .fee_options()
+            .await
+            .unwrap();
+        let marked_up = amounts(&options);
+        assert!((marked_up[1] - 0.12).abs() < 1e-9);
+        assert!((marked_up[2] - 0.15).abs() < 1e-9);
+
+        let unreachable =
+            CosmosGasPrices::osmosis().with_feemarket(&format!("{}/down", server.uri()));
+        let options = unreachable.fee_options().await.unwrap();
+        assert_eq!(amounts(&options), [0.0025, 0.025, 0.04]);
+    }
+}
@@ -0,0 +1,245 @@
+//! EVM fees from `eth_feeHistory`
+//!
+//! Priority fees are the median of the 10th, 50th and 90th reward
+//! percentiles over recent blocks; the max fee leaves room for the base fee
+//! to double. Chains without a base fee get `eth_gasPrice` with a markup.
+
+use crate::{now, FeeError, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use std::time::Duration;
+use walletd_provider::ProviderPool;
+use walletd_traits::{FeeEstimator, FeeOption, FeeOptions, FeeRate, FeeSpeed, WalletResult};
+
+/// Reward percentiles asked for, slow to fast
+const PERCENTILES: [u8; 3] = [10, 50, 90];
+
+/// Blocks until inclusion each speed aims for, slow to fast
+const TARGET_BLOCKS: [u64; 3] = [6, 3, 1];
+
+/// Gas price markup in percent for legacy chains, slow to fast
+const LEGACY_MARKUP: [u128; 3] = [100, 110, 125];
+
+const SPEEDS: [FeeSpeed; 3] = [FeeSpeed::Slow, FeeSpeed::Standard, FeeSpeed::Fast];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FeeHistory {
+    #[serde(default)]
+    base_fee_per_gas: Vec<String>,
+    #[serde(default)]
+    reward: Vec<Vec<String>>,
+}
+
+/// EIP-1559 fee estimates read through a [`ProviderPool`]
+#[derive(Debug, Clone)]
+pub struct EvmFeeHistory {
+    pool: Arc<ProviderPool>,
+    provider: String,
+    blocks: u64,
+    block_time: Duration,
+}
+
+impl EvmFeeHistory {
+    /// Estimates for the chain registered in `pool` as `provider`
+    ///
+    /// The provider name doubles as the chain name in [`FeeOptions`].
+    pub fn new(pool: Arc<ProviderPool>, provider: &str) -> Self {
+        Self {
+            pool,
+            provider: provider.to_string(),
+            blocks: 20,
+            block_time: Duration::from_secs(12),
+        }
+    }
+
+    /// Sets how many recent blocks are sampled (default 20)
+    pub fn with_blocks(mut self, blocks: u64) -> Self {
+        self.blocks = blocks.clamp(1, 1024);
+        self
+    }
+
+    /// Sets the chain's block time, used for the time estimates (default 12s)
+    pub fn with_block_time(mut self, block_time: Duration) -> Self {
+        self.block_time = block_time;
+        self
+    }
+
+    async fn estimate(&self) -> Result<FeeOptions> {
+        let evm = self.pool.evm(&self.provider)?;
+        let history: FeeHistory = evm
+            .rpc_call(
+                "eth_feeHistory",
+                (format!("0x{:x}", self.blocks), "latest", PERCENTILES),
+            )
+            .await?;
+        let next_base_fee = match history.base_fee_per_gas.last() {
+            Some(hex) => quantity(hex)?,
+            None => 0,
+        };
+
+        let options = if next_base_fee == 0 {
+            let gas_price = evm.gas_price().await?;
+            SPEEDS
+                .iter()
+                .zip(LEGACY_MARKUP)
+                .zip(TARGET_BLOCKS)
+                .map(|((&speed, markup), blocks)| FeeOption {
+                    speed,
+                    rate: FeeRate::GasPrice(gas_price * markup / 100),
+                    estimated_seconds: Some(self.block_time.as_secs() * blocks),
+                })
+                .collect()
+        } else {
+            let mut options = Vec::with_capacity(SPEEDS.len());
+            for (i, (&speed, blocks)) in SPEEDS.iter().zip(TARGET_BLOCKS).enumerate() {
+                let priority = median_reward(&history.reward, i)?;
+                options.push(FeeOption {
+                    speed,
+                    rate: FeeRate::Eip1559 {
+                        max_fee_per_gas: next_base_fee * 2 + priority,
+                        max_priority_fee_per_gas: priority,
+                    },
+                    estimated_seconds: Some(self.block_time.as_secs() * blocks),
+                });
+            }
+            options
+        };
+
+        Ok(FeeOptions {
+            chain: self.provider.clone(),
+            options,
+            updated_at: now(),
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for EvmFeeHistory {
+    fn chain(&self) -> &str {
+        &self.provider
+    }
+
+    async fn fee_options(&self) -> WalletResult<FeeOptions> {
+        Ok(self.estimate().await?)
+    }
+}
+
+/// Median of one reward percentile across blocks, ignoring empty blocks
+fn median_reward(rewards: &[Vec<String>], percentile: usize) -> Result<u128> {
+    let mut values = Vec::with_capacity(rewards.len());
+    for block in rewards {
+        if let Some(hex) = block.get(percentile) {
+            let value = quantity(hex)?;
+            if value > 0 {
+                values.push(value);
+            }
+        }
+    }
+    values.sort_unstable();
+    Ok(values.get(values.len() / 2).copied().unwrap_or_default())
+}
+
+fn quantity(hex: &str) -> Result<u128> {
+    u128::from_str_radix(hex.trim_start_matches("0x"), 16)
+        .map_err(|_| FeeError::InvalidResponse(format!("invalid hex quantity: {}", hex)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use walletd_provider::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const GWEI: u128 = 1_000_000_000;
+
+    async fn mock_rpc(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn estimator(server: &MockServer) -> EvmFeeHistory {
+        let pool = ProviderPool::new();
+        pool.add("ethereum", ProviderConfig::new(server.uri()))
+            .unwrap();
+        EvmFeeHistory::new(Arc::new(pool), "ethereum").with_blocks(3)
+    }
+
+    #[tokio::test]
+    async fn test_eip1559_options() {
+        let server = MockServer::start().await;
+        mock_rpc(
+            &server,
+            "eth_feeHistory",
+            json!({
+                "oldestBlock": "0x1312d00",
+                "baseFeePerGas": ["0x2540be400", "0x2540be400", "0x2540be400", "0x4a817c800"],
+                "gasUsedRatio": [0.5, 0.4, 0.6],
+                "reward": [
+                    ["0x3b9aca00", "0x77359400", "0xb2d05e00"],
+                    ["0x0", "0x3b9aca00", "0x12a05f200"],
+                    ["0x5f5e100", "0x59682f00", "0x165a0bc00"]
+                ]
+            }),
+        )
+        .await;
+
+        let options = estimator(&server).fee_options().await.unwrap();
+        assert_eq!(options.chain, "ethereum");
+        let rate = |speed| options.get(speed).unwrap().rate.clone();
+        assert_eq!(
+            rate(FeeSpeed::Slow),
+            FeeRate::Eip1559 {
+                max_fee_per_gas: 40 * GWEI + GWEI,
+                max_priority_fee_per_gas: GWEI,
+            }
+        );
+        assert_eq!(
+            rate(FeeSpeed::Standard),
+            FeeRate::Eip1559 {
+                max_fee_per_gas: 40 * GWEI + 3 * GWEI / 2,
+                max_priority_fee_per_gas: 3 * GWEI / 2,
+            }
+        );
+        assert_eq!(
+            rate(FeeSpeed::Fast),
+            FeeRate::Eip1559 {
+                max_fee_per_gas: 45 * GWEI,
+                max_priority_fee_per_gas: 5 * GWEI,
+            }
+        );
+        assert_eq!(
+            options.get(FeeSpeed::Fast).unwrap().estimated_seconds,
+            Some(12)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_legacy_fallback() {
+        let server = MockServer::start().await;
+        mock_rpc(
+            &server,
+            "eth_feeHistory",
+            json!({"oldestBlock": "0x1", "baseFeePerGas": ["0x0", "0x0"], "reward": []}),
+        )
+        .await;
+        mock_rpc(&server, "eth_gasPrice", json!("0x12a05f200")).await;
+
+        let options = estimator(&server)
+            .with_block_time(Duration::from_secs(3))
+            .fee_options()
+            .await
+            .unwrap();
+        let standard = options.get(FeeSpeed::Standard).unwrap();
+        assert_eq!(standard.rate, FeeRate::GasPrice(5_500_000_000));
+        assert_eq!(standard.estimated_seconds, Some(9));
+    }
+}
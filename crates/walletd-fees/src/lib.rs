@@ -0,0 +1,139 @@
+//! # WalletD Fees
+//!
+//! Current network fees for every supported chain, normalized to slow,
+//! standard and fast [`FeeOption`](walletd_traits::FeeOption)s.
+//!
+//! Each backend implements [`FeeEstimator`](walletd_traits::FeeEstimator):
+//!
+//! - [`EvmFeeHistory`]: `eth_feeHistory` reward percentiles plus the next
+//!   base fee, with an `eth_gasPrice` fallback for pre-London chains
+//! - [`MempoolSpace`]: mempool.space recommended fees for Bitcoin
+//! - [`SolanaPriorityFees`]: `getRecentPrioritizationFees` percentiles
+//! - [`CosmosGasPrices`]: chain-registry gas prices, or the live base price
+//!   of chains running the `x/feemarket` module
+//!
+//! A [`FeeService`] puts them behind one API keyed by chain name and
+//! caches the answers for a short time.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_fees::{EvmFeeHistory, FeeService, MempoolSpace};
+//! use walletd_traits::FeeSpeed;
+//!
+//! let fees = FeeService::new()
+//!     .with_estimator(EvmFeeHistory::new(Arc::clone(&pool), "ethereum"))
+//!     .with_estimator(MempoolSpace::new());
+//!
+//! let fast = fees.fee("ethereum", FeeSpeed::Fast).await?;
+//! println!("{:?}, ~{:?}s", fast.rate, fast.estimated_seconds);
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod bitcoin;
+pub mod cosmos;
+pub mod evm;
+pub mod service;
+pub mod solana;
+
+pub use bitcoin::MempoolSpace;
+pub use cosmos::CosmosGasPrices;
+pub use evm::EvmFeeHistory;
+pub use service::FeeService;
+pub use solana::SolanaPriorityFees;
+
+use std::time::Duration;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_provider::ProviderError;
+use walletd_resilience::HttpRetryClassifier;
+use walletd_traits::WalletError;
+
+/// Fee estimation errors
+#[derive(Error, Debug)]
+pub enum FeeError {
+    /// No estimator is registered for the chain
+    #[error("No fee estimator for chain {0}")]
+    UnknownChain(String),
+
+    /// The fee source is throttling requests
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay the source asked for
+        retry_after: Option<Duration>,
+    },
+
+    /// Unexpected response from the fee source
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// RPC call failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// An estimator failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for fee estimation
+pub type Result<T> = std::result::Result<T, FeeError>;
+
+impl From<FeeError> for WalletError {
+    fn from(e: FeeError) -> Self {
+        match e {
+            FeeError::Wallet(e) => e,
+            FeeError::UnknownChain(chain) => WalletError::NotSupported(chain),
+            FeeError::InvalidResponse(_) => WalletError::Other(e.to_string()),
+            e => WalletError::NetworkError(e.to_string()),
+        }
+    }
+}
+
+impl From<FeeError> for WalletdError {
+    fn from(e: FeeError) -> Self {
+        match e {
+            FeeError::Provider(e) => e.into(),
+            FeeError::RateLimited { retry_after } => WalletdError::RateLimited {
+                retry_after_secs: retry_after.map_or(1, |d| d.as_secs().max(1)),
+            },
+            FeeError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            FeeError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            FeeError::Http(_) => WalletdError::NetworkError(e.to_string()),
+            FeeError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+        }
+    }
+}
+
+/// GETs a JSON document, turning 429 into [`FeeError::RateLimited`]
+pub(crate) async fn get_json(request: reqwest::RequestBuilder) -> Result<serde_json::Value> {
+    let response = request.timeout(Duration::from_secs(10)).send().await?;
+    let status = response.status();
+    if HttpRetryClassifier::is_rate_limited(status.as_u16()) {
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .and_then(HttpRetryClassifier::parse_retry_after);
+        return Err(FeeError::RateLimited { retry_after });
+    }
+    if !status.is_success() {
+        return Err(FeeError::InvalidResponse(format!("HTTP {}", status)));
+    }
+    Ok(response.json().await?)
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
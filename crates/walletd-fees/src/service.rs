@@ -0,0 +1,234 @@
+//! One fee API over every chain's estimator
+
+use crate::{FeeError, Result};
+use futures_util::future::join_all;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use walletd_traits::{FeeEstimator, FeeOption, FeeOptions, FeeSpeed};
+
+/// How long fee options are reused by default
+const DEFAULT_TTL: Duration = Duration::from_secs(15);
+
+/// How long a failed refresh may fall back to the last known options
+const DEFAULT_MAX_STALE: Duration = Duration::from_secs(300);
+
+/// Fee options for every registered chain, with caching
+///
+/// Options are cached per chain for the configured TTL (15 seconds by
+/// default). If a refresh fails, the last options are returned for up to
+/// five minutes, so a flaky fee source does not block sending.
+pub struct FeeService {
+    estimators: HashMap<String, Arc<dyn FeeEstimator>>,
+    ttl: Duration,
+    max_stale: Duration,
+    cache: Mutex<HashMap<String, (FeeOptions, Instant)>>,
+}
+
+impl Default for FeeService {
+    fn default() -> Self {
+        Self {
+            estimators: HashMap::new(),
+            ttl: DEFAULT_TTL,
+            max_stale: DEFAULT_MAX_STALE,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl fmt::Debug for FeeService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FeeService")
+            .field("chains", &self.chains())
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl FeeService {
+    /// Creates a service without estimators
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds an estimator, replacing any other for the same chain
+    pub fn with_estimator(mut self, estimator: impl FeeEstimator + 'static) -> Self {
+        self.estimators
+            .insert(estimator.chain().to_string(), Arc::new(estimator));
+        self
+    }
+
+    /// Sets how long options are reused before they are fetched again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Sets how long stale options may stand in for a failed refresh
+    pub fn with_max_stale(mut self, max_stale: Duration) -> Self {
+        self.max_stale = max_stale;
+        self
+    }
+
+    /// Chains with an estimator, sorted
+    pub fn chains(&self) -> Vec<String> {
+        let mut chains: Vec<String> = self.estimators.keys().cloned().collect();
+        chains.sort();
+        chains
+    }
+
+    /// Slow, standard and fast options for `chain`
+    pub async fn fee_options(&self, chain: &str) -> Result<FeeOptions> {
+        let estimator = self
+            .estimators
+            .get(chain)
+            .ok_or_else(|| FeeError::UnknownChain(chain.to_string()))?;
+        if let Some(options) = self.cached(chain, self.ttl) {
+            return Ok(options);
+        }
+        match estimator.fee_options().await {
+            Ok(options) => {
+                self.cache
+                    .lock()
+                    .expect("fee cache lock")
+                    .insert(chain.to_string(), (options.clone(), Instant::now()));
+                Ok(options)
+            }
+            Err(e) => match self.cached(chain, self.max_stale) {
+                Some(options) => {
+                    tracing::warn!(
+                        "fee refresh for {} failed, using stale options: {}",
+                        chain,
+                        e
+                    );
+                    Ok(options)
+                }
+                None => Err(e.into()),
+            },
+        }
+    }
+
+    /// The option for one speed on `chain`
+    pub async fn fee(&self, chain: &str, speed: FeeSpeed) -> Result<FeeOption> {
+        let options = self.fee_options(chain).await?;
+        options.get(speed).cloned().ok_or_else(|| {
+            FeeError::InvalidResponse(format!("no {:?} option for {}", speed, chain))
+        })
+    }
+
+    /// Options for every chain, fetched concurrently
+    ///
+    /// Chains whose estimator fails are logged and left out.
+    pub async fn all(&self) -> Vec<FeeOptions> {
+        let chains = self.chains();
+        let results = join_all(chains.iter().map(|chain| self.fee_options(chain))).await;
+        chains
+            .iter()
+            .zip(results)
+            .filter_map(|(chain, result)| match result {
+                Ok(options) => Some(options),
+                Err(e) => {
+                    tracing::debug!("no fee options for {}: {}", chain, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Drops every cached option
+    pub fn clear_cache(&self) {
+        self.cache.lock().expect("fee cache lock").clear();
+    }
+
+    fn cached(&self, chain: &str, max_age: Duration) -> Option<FeeOptions> {
+        let cache = self.cache.lock().expect("fee cache lock");
+        cache
+            .get(chain)
+            .filter(|(_, fetched)| fetched.elapsed() < max_age)
+            .map(|(options, _)| options.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicBool, AtomicU32, Ordering};
+    use walletd_traits::{FeeRate, WalletError, WalletResult};
+
+    #[derive(Default)]
+    struct Counting {
+        calls: Arc<AtomicU32>,
+        failing: Arc<AtomicBool>,
+    }
+
+    #[async_trait]
+    impl FeeEstimator for Counting {
+        fn chain(&self) -> &str {
+            "bitcoin"
+        }
+
+        async fn fee_options(&self) -> WalletResult<FeeOptions> {
+            let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+            if self.failing.load(Ordering::SeqCst) {
+                return Err(WalletError::NetworkError("down".into()));
+            }
+            Ok(FeeOptions {
+                chain: "bitcoin".into(),
+                options: vec![FeeOption {
+                    speed: FeeSpeed::Standard,
+                    rate: FeeRate::SatPerVbyte(call as f64),
+                    estimated_seconds: None,
+                }],
+                updated_at: 0,
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_and_stale_fallback() {
+        let estimator = Counting::default();
+        let (calls, failing) = (Arc::clone(&estimator.calls), Arc::clone(&estimator.failing));
+        let service = FeeService::new()
+            .with_estimator(estimator)
+            .with_ttl(Duration::ZERO);
+
+        let first = service.fee("bitcoin", FeeSpeed::Standard).await.unwrap();
+        assert_eq!(first.rate, FeeRate::SatPerVbyte(1.0));
+        let second = service.fee("bitcoin", FeeSpeed::Standard).await.unwrap();
+        assert_eq!(second.rate, FeeRate::SatPerVbyte(2.0));
+
+        failing.store(true, Ordering::SeqCst);
+        let stale = service.fee("bitcoin", FeeSpeed::Standard).await.unwrap();
+        assert_eq!(stale.rate, FeeRate::SatPerVbyte(2.0));
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+
+        service.clear_cache();
+        assert!(matches!(
+            service.fee_options("bitcoin").await,
+            Err(FeeError::Wallet(WalletError::NetworkError(_)))
+        ));
+        assert!(service.all().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_ttl_and_unknown_chain() {
+        let estimator = Counting::default();
+        let calls = Arc::clone(&estimator.calls);
+        let service = FeeService::new().with_estimator(estimator);
+
+        service.fee_options("bitcoin").await.unwrap();
+        service.fee_options("bitcoin").await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert_eq!(service.all().await.len(), 1);
+        assert!(matches!(
+            service.fee("bitcoin", FeeSpeed::Fast).await,
+            Err(FeeError::InvalidResponse(_))
+        ));
+        assert!(matches!(
+            service.fee_options("dogecoin").await,
+            Err(FeeError::UnknownChain(_))
+        ));
+    }
+}
@@ -0,0 +1,155 @@
+//! Solana priority fees from `getRecentPrioritizationFees`
+//!
+//! The node reports the lowest fee that landed a transaction in each of the
+//! last 150 slots. The 25th, 50th and 75th percentiles of those become the
+//! slow, standard and fast compute unit prices. Passing the writable
+//! accounts of a transaction narrows the sample to their local fee market.
+
+use crate::{now, Result};
+use async_trait::async_trait;
+use serde::Deserialize;
+use std::sync::Arc;
+use walletd_provider::{ProviderPool, Solana};
+use walletd_traits::{FeeEstimator, FeeOption, FeeOptions, FeeRate, FeeSpeed, WalletResult};
+
+/// Percentile used for each speed, slow to fast
+const PERCENTILES: [(FeeSpeed, usize); 3] = [
+    (FeeSpeed::Slow, 25),
+    (FeeSpeed::Standard, 50),
+    (FeeSpeed::Fast, 75),
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SlotFee {
+    prioritization_fee: u64,
+}
+
+/// Solana compute unit prices read through a [`ProviderPool`]
+#[derive(Debug, Clone)]
+pub struct SolanaPriorityFees {
+    pool: Arc<ProviderPool>,
+    provider: String,
+    accounts: Vec<String>,
+}
+
+impl SolanaPriorityFees {
+    /// Estimates from the provider registered in `pool` as `provider`
+    pub fn new(pool: Arc<ProviderPool>, provider: &str) -> Self {
+        Self {
+            pool,
+            provider: provider.to_string(),
+            accounts: Vec::new(),
+        }
+    }
+
+    /// Only samples fees paid by transactions writing to `accounts`
+    ///
+    /// The RPC accepts at most 128 accounts.
+    pub fn with_accounts(mut self, accounts: &[&str]) -> Self {
+        self.accounts = accounts.iter().take(128).map(|a| a.to_string()).collect();
+        self
+    }
+
+    async fn estimate(&self) -> Result<FeeOptions> {
+        let solana = self.pool.chain::<Solana>(&self.provider)?;
+        let params: Vec<Vec<String>> = if self.accounts.is_empty() {
+            Vec::new()
+        } else {
+            vec![self.accounts.clone()]
+        };
+        let slots: Vec<SlotFee> = solana
+            .rpc_call("getRecentPrioritizationFees", params)
+            .await?;
+        let mut fees: Vec<u64> = slots.iter().map(|s| s.prioritization_fee).collect();
+        fees.sort_unstable();
+
+        let options = PERCENTILES
+            .iter()
+            .map(|&(speed, percentile)| FeeOption {
+                speed,
+                rate: FeeRate::MicroLamportsPerCu(percentile_of(&fees, percentile)),
+                estimated_seconds: None,
+            })
+            .collect();
+        Ok(FeeOptions {
+            chain: self.provider.clone(),
+            options,
+            updated_at: now(),
+        })
+    }
+}
+
+#[async_trait]
+impl FeeEstimator for SolanaPriorityFees {
+    fn chain(&self) -> &str {
+        &self.provider
+    }
+
+    async fn fee_options(&self) -> WalletResult<FeeOptions> {
+        Ok(self.estimate().await?)
+    }
+}
+
+/// Nearest-rank percentile of sorted values, 0 when there are none
+fn percentile_of(sorted: &[u64], percentile: usize) -> u64 {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (percentile * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use walletd_provider::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const JUPITER: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
+
+    #[tokio::test]
+    async fn test_percentiles() {
+        let server = MockServer::start().await;
+        let slots: Vec<_> = [0, 0, 1_000, 5_000, 10_000, 20_000, 50_000, 100_000]
+            .iter()
+            .enumerate()
+            .map(|(i, fee)| json!({"slot": 300_000_000 + i, "prioritizationFee": fee}))
+            .collect();
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({
+                "method": "getRecentPrioritizationFees",
+                "params": [[JUPITER]]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": slots
+            })))
+            .mount(&server)
+            .await;
+
+        let pool = ProviderPool::new();
+        pool.add("solana", ProviderConfig::new(server.uri()))
+            .unwrap();
+        let estimator = SolanaPriorityFees::new(Arc::new(pool), "solana").with_accounts(&[JUPITER]);
+        let options = estimator.fee_options().await.unwrap();
+        let prices: Vec<_> = options.options.iter().map(|o| o.rate.clone()).collect();
+        assert_eq!(
+            prices,
+            [
+                FeeRate::MicroLamportsPerCu(0),
+                FeeRate::MicroLamportsPerCu(5_000),
+                FeeRate::MicroLamportsPerCu(20_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_percentile_of() {
+        assert_eq!(percentile_of(&[], 50), 0);
+        assert_eq!(percentile_of(&[7], 25), 7);
+        assert_eq!(percentile_of(&[1, 2, 3, 4], 75), 3);
+        assert_eq!(percentile_of(&[1, 2, 3, 4], 100), 4);
+    }
+}
@@ -0,0 +1,97 @@
+/*
+ * WalletD C API
+ *
+ * Status codes: WALLETD_OK on success, a negative WALLETD_ERR_* for
+ * invalid arguments and panics, otherwise one of the WALLETD_CODE_* values
+ * (walletd_error::ErrorCode). walletd_last_error_message() describes the
+ * last failure on the calling thread.
+ *
+ * Memory: release wallets with walletd_wallet_free, strings with
+ * walletd_string_free and byte buffers with walletd_bytes_free. Never pass
+ * library memory to free().
+ */
+
+#ifndef WALLETD_H
+#define WALLETD_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define WALLETD_ABI_VERSION 1
+
+/* FFI status codes */
+#define WALLETD_OK 0
+#define WALLETD_ERR_INVALID_ARGUMENT -1
+#define WALLETD_ERR_PANIC -2
+
+/* walletd_error::ErrorCode */
+#define WALLETD_CODE_UNKNOWN 0
+#define WALLETD_CODE_INVALID_ADDRESS 1001
+#define WALLETD_CODE_ADDRESS_NOT_FOUND 1002
+#define WALLETD_CODE_INSUFFICIENT_BALANCE 2001
+#define WALLETD_CODE_AMOUNT_OVERFLOW 2002
+#define WALLETD_CODE_TRANSACTION_BUILD_ERROR 3001
+#define WALLETD_CODE_SIGNING_ERROR 3002
+#define WALLETD_CODE_BROADCAST_ERROR 3003
+#define WALLETD_CODE_TRANSACTION_NOT_FOUND 3004
+#define WALLETD_CODE_TRANSACTION_FAILED 3005
+#define WALLETD_CODE_INVALID_NONCE 3006
+#define WALLETD_CODE_FEE_TOO_LOW 3007
+#define WALLETD_CODE_RPC_CONNECTION_ERROR 4001
+#define WALLETD_CODE_RPC_REQUEST_ERROR 4002
+#define WALLETD_CODE_NETWORK_TIMEOUT 4003
+#define WALLETD_CODE_RATE_LIMITED 4004
+#define WALLETD_CODE_NETWORK_ERROR 4005
+#define WALLETD_CODE_CONTRACT_ERROR 5001
+#define WALLETD_CODE_NOT_SYNCED 6001
+#define WALLETD_CODE_KEY_ERROR 7001
+#define WALLETD_CODE_NOT_SUPPORTED 9001
+
+/* Opaque HD wallet handle */
+typedef struct WalletdWallet WalletdWallet;
+
+/* Byte buffer owned by the library; data is NULL when len is 0 */
+typedef struct WalletdBytes {
+    uint8_t *data;
+    size_t len;
+} WalletdBytes;
+
+/* Library */
+uint32_t walletd_abi_version(void);
+const char *walletd_version(void);
+const char *walletd_last_error_message(void);
+void walletd_string_free(char *s);
+void walletd_bytes_free(WalletdBytes bytes);
+
+/* Mnemonics */
+int32_t walletd_generate_mnemonic(uint32_t words, char **out);
+bool walletd_validate_mnemonic(const char *phrase);
+
+/* Wallets; chains are names or tickers such as "ethereum", "btc", "sol" */
+int32_t walletd_wallet_from_mnemonic(const char *phrase, const char *passphrase,
+                                     WalletdWallet **out);
+void walletd_wallet_free(WalletdWallet *wallet);
+int32_t walletd_wallet_address(const WalletdWallet *wallet, const char *chain,
+                               uint32_t index, char **out);
+int32_t walletd_wallet_public_key(const WalletdWallet *wallet, const char *chain,
+                                  uint32_t index, WalletdBytes *out);
+int32_t walletd_wallet_sign_hash(const WalletdWallet *wallet, const char *chain,
+                                 uint32_t index, const uint8_t hash[32],
+                                 WalletdBytes *out);
+int32_t walletd_wallet_sign_message(const WalletdWallet *wallet, const char *chain,
+                                    uint32_t index, const uint8_t *message,
+                                    size_t len, WalletdBytes *out);
+int32_t walletd_wallet_sign_evm_transaction(const WalletdWallet *wallet,
+                                            uint32_t index, const char *tx_json,
+                                            char **out);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* WALLETD_H */
@@ -0,0 +1,330 @@
+//! # WalletD FFI
+//!
+//! A stable C ABI for embedding WalletD in C, C++, Go (cgo), Flutter
+//! (`dart:ffi`) and other languages with a C FFI. The matching header is
+//! `include/walletd.h`.
+//!
+//! Conventions:
+//!
+//! - Every fallible function returns an `int32_t` status: `WALLETD_OK`
+//!   (0), a negative FFI status (`WALLETD_ERR_INVALID_ARGUMENT`,
+//!   `WALLETD_ERR_PANIC`), or a positive [`ErrorCode`] value such as
+//!   `7001` (key error). [`walletd_last_error_message`] describes the last
+//!   failure on the calling thread.
+//! - Results are written to out-parameters, which are left untouched on
+//!   failure.
+//! - Wallets are opaque `WalletdWallet *` handles released with
+//!   [`walletd_wallet_free`]. Strings and byte buffers returned by the
+//!   library are released with [`walletd_string_free`] and
+//!   [`walletd_bytes_free`]; never with the caller's `free`.
+//! - Strings are NUL-terminated UTF-8. Chains are names or tickers
+//!   (`"ethereum"`, `"btc"`, `"sol"`).
+//! - Handles may be used from any thread, but not freed while in use.
+//!
+//! [`walletd_abi_version`] only changes when an existing signature or
+//! status code changes; new functions keep the version.
+//!
+//! ```c
+//! WalletdWallet *wallet = NULL;
+//! char *address = NULL;
+//! if (walletd_wallet_from_mnemonic(phrase, "", &wallet) != WALLETD_OK ||
+//!     walletd_wallet_address(wallet, "ethereum", 0, &address) != WALLETD_OK) {
+//!     fprintf(stderr, "walletd: %s\n", walletd_last_error_message());
+//! }
+//! walletd_string_free(address);
+//! walletd_wallet_free(wallet);
+//! ```
+
+#![deny(unsafe_op_in_unsafe_fn)]
+#![warn(missing_docs)]
+
+mod wallet;
+
+pub use wallet::*;
+
+use std::cell::RefCell;
+use std::ffi::{c_char, CStr, CString};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use walletd_error::{ErrorCode, WalletdError};
+use walletd_hd::HdError;
+use walletd_traits::evm::EvmError;
+use walletd_traits::WalletError;
+
+/// ABI version of this library
+pub const WALLETD_ABI_VERSION: u32 = 1;
+
+/// Success
+pub const WALLETD_OK: i32 = 0;
+
+/// A pointer was null, a string was not UTF-8, or an input was malformed
+pub const WALLETD_ERR_INVALID_ARGUMENT: i32 = -1;
+
+/// The library panicked; the call had no effect
+pub const WALLETD_ERR_PANIC: i32 = -2;
+
+/// A byte buffer owned by the library
+///
+/// Release with [`walletd_bytes_free`].
+#[repr(C)]
+#[derive(Debug)]
+pub struct WalletdBytes {
+    /// Start of the buffer, null when empty
+    pub data: *mut u8,
+    /// Length in bytes
+    pub len: usize,
+}
+
+impl WalletdBytes {
+    pub(crate) fn new(bytes: Vec<u8>) -> Self {
+        if bytes.is_empty() {
+            return Self {
+                data: std::ptr::null_mut(),
+                len: 0,
+            };
+        }
+        let mut bytes = bytes.into_boxed_slice();
+        let len = bytes.len();
+        let data = bytes.as_mut_ptr();
+        std::mem::forget(bytes);
+        Self { data, len }
+    }
+}
+
+/// A failed call: status code and message
+#[derive(Debug)]
+pub(crate) struct FfiError {
+    status: i32,
+    message: String,
+}
+
+impl FfiError {
+    pub(crate) fn invalid(message: impl Into<String>) -> Self {
+        Self {
+            status: WALLETD_ERR_INVALID_ARGUMENT,
+            message: message.into(),
+        }
+    }
+
+    fn code(code: ErrorCode, message: String) -> Self {
+        Self {
+            status: code.as_u32() as i32,
+            message,
+        }
+    }
+}
+
+impl From<WalletdError> for FfiError {
+    fn from(e: WalletdError) -> Self {
+        Self::code(e.code(), e.to_string())
+    }
+}
+
+impl From<HdError> for FfiError {
+    fn from(e: HdError) -> Self {
+        match e {
+            HdError::UnsupportedChain(_) => Self::invalid(e.to_string()),
+            e => WalletdError::from(e).into(),
+        }
+    }
+}
+
+impl From<WalletError> for FfiError {
+    fn from(e: WalletError) -> Self {
+        let code = match e {
+            WalletError::InvalidAddress(_) => ErrorCode::InvalidAddress,
+            WalletError::InsufficientBalance { .. } => ErrorCode::InsufficientBalance,
+            WalletError::TransactionFailed(_) => ErrorCode::TransactionFailed,
+            WalletError::NetworkError(_) => ErrorCode::NetworkError,
+            WalletError::KeyError(_) => ErrorCode::KeyError,
+            WalletError::NotSynced => ErrorCode::NotSynced,
+            WalletError::NotSupported(_) => ErrorCode::NotSupported,
+            _ => ErrorCode::Unknown,
+        };
+        Self::code(code, e.to_string())
+    }
+}
+
+impl From<EvmError> for FfiError {
+    fn from(e: EvmError) -> Self {
+        match e {
+            EvmError::Invalid(reason) => Self::invalid(reason),
+            e => WalletError::from(e).into(),
+        }
+    }
+}
+
+pub(crate) type FfiResult<T> = Result<T, FfiError>;
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+fn set_last_error(message: &str) {
+    let message = CString::new(message.replace('\0', " ")).expect("no interior NUL");
+    LAST_ERROR.with(|last| *last.borrow_mut() = Some(message));
+}
+
+/// Runs `f`, recording any error or panic, and returns its status
+pub(crate) fn guard(f: impl FnOnce() -> FfiResult<()>) -> i32 {
+    match catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => WALLETD_OK,
+        Ok(Err(e)) => {
+            set_last_error(&e.message);
+            e.status
+        }
+        Err(_) => {
+            set_last_error("walletd panicked");
+            WALLETD_ERR_PANIC
+        }
+    }
+}
+
+/// Borrows a C string argument
+///
+/// # Safety
+///
+/// `ptr` must be null or point to a NUL-terminated string that outlives
+/// the returned borrow.
+pub(crate) unsafe fn str_arg<'a>(ptr: *const c_char, name: &str) -> FfiResult<&'a str> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid(format!("{} is null", name)));
+    }
+    // SAFETY: non-null, and the caller guarantees NUL termination
+    unsafe { CStr::from_ptr(ptr) }
+        .to_str()
+        .map_err(|_| FfiError::invalid(format!("{} is not UTF-8", name)))
+}
+
+/// Hands a string to the caller, to be released with [`walletd_string_free`]
+pub(crate) fn into_c_string(s: String) -> FfiResult<*mut c_char> {
+    CString::new(s)
+        .map(CString::into_raw)
+        .map_err(|_| FfiError::invalid("result contains a NUL byte"))
+}
+
+/// Checks an out-parameter before any work is done
+pub(crate) fn out_arg<T>(ptr: *mut T, name: &str) -> FfiResult<()> {
+    if ptr.is_null() {
+        return Err(FfiError::invalid(format!("{} is null", name)));
+    }
+    Ok(())
+}
+
+/// ABI version, see [`WALLETD_ABI_VERSION`]
+#[no_mangle]
+pub extern "C" fn walletd_abi_version() -> u32 {
+    WALLETD_ABI_VERSION
+}
+
+/// Library version as a static string; do not free
+#[no_mangle]
+pub extern "C" fn walletd_version() -> *const c_char {
+    concat!(env!("CARGO_PKG_VERSION"), "\0").as_ptr().cast()
+}
+
+/// Message of the last failed call on this thread, or null
+///
+/// The pointer stays valid until the next failing call on the same thread;
+/// do not free it.
+#[no_mangle]
+pub extern "C" fn walletd_last_error_message() -> *const c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow()
+            .as_ref()
+            .map_or(std::ptr::null(), |message| message.as_ptr())
+    })
+}
+
+/// Releases a string returned by the library; null is ignored
+///
+/// The contents are zeroed first, since strings may hold mnemonics.
+///
+/// # Safety
+///
+/// `s` must be null or a string returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_string_free(s: *mut c_char) {
+    if s.is_null() {
+        return;
+    }
+    // SAFETY: the caller passes a pointer from `CString::into_raw`
+    let mut bytes = unsafe { CString::from_raw(s) }.into_bytes();
+    zeroize::Zeroize::zeroize(&mut bytes);
+}
+
+/// Releases a byte buffer returned by the library; empty buffers are ignored
+///
+/// # Safety
+///
+/// `bytes` must have been returned by this library and not yet freed.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_bytes_free(bytes: WalletdBytes) {
+    if bytes.data.is_null() {
+        return;
+    }
+    // SAFETY: `data` and `len` come from a leaked boxed slice
+    let slice = unsafe { std::slice::from_raw_parts_mut(bytes.data, bytes.len) };
+    drop(unsafe { Box::from_raw(slice as *mut [u8]) });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_declares_every_export() {
+        let header = include_str!("../include/walletd.h");
+        let sources = [include_str!("lib.rs"), include_str!("wallet.rs")];
+        let exports: Vec<&str> = sources
+            .iter()
+            .flat_map(|source| source.lines())
+            .filter_map(|line| line.split("extern \"C\" fn ").nth(1))
+            .filter_map(|rest| rest.split('(').next())
+            .collect();
+        assert!(exports.len() > 10);
+        for export in exports {
+            assert!(
+                header.contains(&format!("{}(", export)),
+                "{} missing from walletd.h",
+                export
+            );
+        }
+        for (name, value) in [
+            ("WALLETD_OK", WALLETD_OK),
+            ("WALLETD_ERR_INVALID_ARGUMENT", WALLETD_ERR_INVALID_ARGUMENT),
+            ("WALLETD_ERR_PANIC", WALLETD_ERR_PANIC),
+        ] {
+            assert!(header.contains(&format!("#define {} {}", name, value)));
+        }
+        for code in ErrorCode::ALL {
+            assert!(
+                header.contains(&format!(" {}\n", code.as_u32())),
+                "{:?}",
+                code
+            );
+        }
+    }
+
+    #[test]
+    fn test_errors_and_buffers() {
+        let status = guard(|| Err(FfiError::invalid("bad chain")));
+        assert_eq!(status, WALLETD_ERR_INVALID_ARGUMENT);
+        let message = unsafe { CStr::from_ptr(walletd_last_error_message()) };
+        assert_eq!(message.to_str().unwrap(), "bad chain");
+
+        let status = guard(|| Err(WalletError::KeyError("no key".into()).into()));
+        assert_eq!(status, 7001);
+        assert_eq!(guard(|| panic!("boom")), WALLETD_ERR_PANIC);
+        assert_eq!(guard(|| Ok(())), WALLETD_OK);
+
+        let version = unsafe { CStr::from_ptr(walletd_version()) };
+        assert_eq!(version.to_str().unwrap(), env!("CARGO_PKG_VERSION"));
+
+        unsafe {
+            walletd_bytes_free(WalletdBytes::new(vec![1, 2, 3]));
+            walletd_bytes_free(WalletdBytes::new(Vec::new()));
+            walletd_string_free(into_c_string("secret".into()).unwrap());
+            walletd_string_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,399 @@
+//! Mnemonics and HD wallet handles
+
+use crate::{guard, into_c_string, out_arg, str_arg, FfiError, FfiResult, WalletdBytes};
+use bip39::Mnemonic;
+use futures_executor::block_on;
+use rand::RngCore;
+use std::ffi::c_char;
+use walletd_hd::{HdChain, HdManager};
+use walletd_traits::{evm, Signer};
+use zeroize::Zeroizing;
+
+/// Opaque wallet handle
+///
+/// Created by [`walletd_wallet_from_mnemonic`] and released with
+/// [`walletd_wallet_free`], which zeroizes the seed.
+pub struct WalletdWallet {
+    manager: HdManager,
+}
+
+impl WalletdWallet {
+    fn signer(&self, chain: &str, index: u32) -> FfiResult<Box<dyn Signer>> {
+        Ok(self.manager.signer(parse_chain(chain)?, index)?)
+    }
+}
+
+fn parse_chain(chain: &str) -> FfiResult<HdChain> {
+    Ok(chain.parse()?)
+}
+
+/// Borrows a wallet handle
+///
+/// # Safety
+///
+/// `wallet` must be null or a live handle from this library.
+unsafe fn wallet_arg<'a>(wallet: *const WalletdWallet) -> FfiResult<&'a WalletdWallet> {
+    // SAFETY: the caller passes null or a live handle
+    unsafe { wallet.as_ref() }.ok_or_else(|| FfiError::invalid("wallet is null"))
+}
+
+/// Generates a BIP-39 mnemonic of 12, 15, 18, 21 or 24 words
+///
+/// # Safety
+///
+/// `out` must be valid for writes. Free the result with
+/// `walletd_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_generate_mnemonic(words: u32, out: *mut *mut c_char) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        if !matches!(words, 12 | 15 | 18 | 21 | 24) {
+            return Err(FfiError::invalid("word count must be 12, 15, 18, 21 or 24"));
+        }
+        let mut entropy = Zeroizing::new(vec![0u8; words as usize / 3 * 4]);
+        rand::rngs::OsRng.fill_bytes(&mut entropy);
+        let mnemonic =
+            Mnemonic::from_entropy(&entropy).map_err(|e| FfiError::invalid(e.to_string()))?;
+        let phrase = into_c_string(mnemonic.to_string())?;
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(phrase) };
+        Ok(())
+    })
+}
+
+/// Returns true if `phrase` is a valid English BIP-39 mnemonic
+///
+/// # Safety
+///
+/// `phrase` must be null or a NUL-terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_validate_mnemonic(phrase: *const c_char) -> bool {
+    // SAFETY: forwarded from this function's contract
+    unsafe { str_arg(phrase, "phrase") }
+        .is_ok_and(|phrase| Mnemonic::parse_normalized(phrase.trim()).is_ok())
+}
+
+/// Opens a wallet from a mnemonic and BIP-39 passphrase (null for none)
+///
+/// # Safety
+///
+/// `phrase` and `passphrase` must be null or NUL-terminated strings, and
+/// `out` valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_from_mnemonic(
+    phrase: *const c_char,
+    passphrase: *const c_char,
+    out: *mut *mut WalletdWallet,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        // SAFETY: forwarded from this function's contract
+        let phrase = unsafe { str_arg(phrase, "phrase") }?;
+        let passphrase = match passphrase.is_null() {
+            true => "",
+            // SAFETY: forwarded from this function's contract
+            false => unsafe { str_arg(passphrase, "passphrase") }?,
+        };
+        let manager = HdManager::from_mnemonic(phrase.trim(), passphrase)?;
+        let wallet = Box::into_raw(Box::new(WalletdWallet { manager }));
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(wallet) };
+        Ok(())
+    })
+}
+
+/// Releases a wallet handle; null is ignored
+///
+/// # Safety
+///
+/// `wallet` must be null or a handle from this library that is not used
+/// again.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_free(wallet: *mut WalletdWallet) {
+    if !wallet.is_null() {
+        // SAFETY: the handle came from `Box::into_raw` and is not reused
+        drop(unsafe { Box::from_raw(wallet) });
+    }
+}
+
+/// Writes the address of account `index` on `chain` to `out`
+///
+/// # Safety
+///
+/// `wallet` must be a live handle, `chain` a NUL-terminated string and
+/// `out` valid for writes. Free the result with `walletd_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_address(
+    wallet: *const WalletdWallet,
+    chain: *const c_char,
+    index: u32,
+    out: *mut *mut c_char,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        // SAFETY: forwarded from this function's contract
+        let (wallet, chain) = unsafe { (wallet_arg(wallet)?, str_arg(chain, "chain")?) };
+        let address = into_c_string(wallet.manager.address(parse_chain(chain)?, index)?)?;
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(address) };
+        Ok(())
+    })
+}
+
+/// Writes the public key of account `index` on `chain` to `out`
+///
+/// 33-byte compressed keys for secp256k1 chains, 32 bytes for Ed25519.
+///
+/// # Safety
+///
+/// `wallet` must be a live handle, `chain` a NUL-terminated string and
+/// `out` valid for writes. Free the result with `walletd_bytes_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_public_key(
+    wallet: *const WalletdWallet,
+    chain: *const c_char,
+    index: u32,
+    out: *mut WalletdBytes,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        // SAFETY: forwarded from this function's contract
+        let (wallet, chain) = unsafe { (wallet_arg(wallet)?, str_arg(chain, "chain")?) };
+        let public_key = wallet.signer(chain, index)?.public_key();
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(WalletdBytes::new(public_key)) };
+        Ok(())
+    })
+}
+
+/// Signs the 32 bytes at `hash` with account `index` on `chain`
+///
+/// Writes `r || s` for secp256k1 chains and a 64-byte Ed25519 signature.
+///
+/// # Safety
+///
+/// `wallet` must be a live handle, `chain` a NUL-terminated string, `hash`
+/// readable for 32 bytes and `out` valid for writes. Free the result with
+/// `walletd_bytes_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_sign_hash(
+    wallet: *const WalletdWallet,
+    chain: *const c_char,
+    index: u32,
+    hash: *const u8,
+    out: *mut WalletdBytes,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        if hash.is_null() {
+            return Err(FfiError::invalid("hash is null"));
+        }
+        // SAFETY: non-null and readable for 32 bytes per the contract
+        let hash: [u8; 32] = unsafe { hash.cast::<[u8; 32]>().read_unaligned() };
+        // SAFETY: forwarded from this function's contract
+        let (wallet, chain) = unsafe { (wallet_arg(wallet)?, str_arg(chain, "chain")?) };
+        let signature = block_on(wallet.signer(chain, index)?.sign_hash(&hash))?;
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(WalletdBytes::new(signature)) };
+        Ok(())
+    })
+}
+
+/// Signs `len` bytes at `message` with account `index` on `chain`
+///
+/// EVM chains use EIP-191 `personal_sign` and write `r || s || v`; other
+/// chains use their signer's message format.
+///
+/// # Safety
+///
+/// `wallet` must be a live handle, `chain` a NUL-terminated string,
+/// `message` readable for `len` bytes (or null with `len` 0) and `out`
+/// valid for writes. Free the result with `walletd_bytes_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_sign_message(
+    wallet: *const WalletdWallet,
+    chain: *const c_char,
+    index: u32,
+    message: *const u8,
+    len: usize,
+    out: *mut WalletdBytes,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        let message = match (message.is_null(), len) {
+            (true, 0) => &[][..],
+            (true, _) => return Err(FfiError::invalid("message is null")),
+            // SAFETY: non-null and readable for `len` bytes per the contract
+            (false, _) => unsafe { std::slice::from_raw_parts(message, len) },
+        };
+        // SAFETY: forwarded from this function's contract
+        let (wallet, chain) = unsafe { (wallet_arg(wallet)?, str_arg(chain, "chain")?) };
+        let signer = wallet.signer(chain, index)?;
+        let signature = match parse_chain(chain)?.is_evm() {
+            true => block_on(evm::personal_sign(signer.as_ref(), message))?,
+            false => block_on(signer.sign_message(message))?,
+        };
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(WalletdBytes::new(signature)) };
+        Ok(())
+    })
+}
+
+/// Signs an EVM transaction given as JSON with account `index`
+///
+/// `tx_json` is the `eth_sendTransaction` shape with `chainId`, `nonce`,
+/// `gas` and either `gasPrice` or the EIP-1559 fee fields. Writes
+/// `{"from", "raw", "hash"}` as JSON, with `raw` ready for
+/// `eth_sendRawTransaction`.
+///
+/// # Safety
+///
+/// `wallet` must be a live handle, `tx_json` a NUL-terminated string and
+/// `out` valid for writes. Free the result with `walletd_string_free`.
+#[no_mangle]
+pub unsafe extern "C" fn walletd_wallet_sign_evm_transaction(
+    wallet: *const WalletdWallet,
+    index: u32,
+    tx_json: *const c_char,
+    out: *mut *mut c_char,
+) -> i32 {
+    guard(|| {
+        out_arg(out, "out")?;
+        // SAFETY: forwarded from this function's contract
+        let (wallet, tx_json) = unsafe { (wallet_arg(wallet)?, str_arg(tx_json, "tx_json")?) };
+        let tx: serde_json::Value = serde_json::from_str(tx_json)
+            .map_err(|e| FfiError::invalid(format!("transaction is not JSON: {}", e)))?;
+        let tx = evm::TxRequest::from_json(&tx)?;
+        let signer = wallet.manager.signer(HdChain::Ethereum, index)?;
+        let signed = block_on(tx.sign(signer.as_ref()))?;
+        let result = serde_json::json!({
+            "from": evm::address(signer.as_ref())?,
+            "raw": format!("0x{}", hex::encode(&signed.raw)),
+            "hash": format!("0x{}", hex::encode(signed.hash)),
+        });
+        let result = into_c_string(result.to_string())?;
+        // SAFETY: checked non-null above, writable per the contract
+        unsafe { out.write(result) };
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        walletd_bytes_free, walletd_string_free, WALLETD_ERR_INVALID_ARGUMENT, WALLETD_OK,
+    };
+    use std::ffi::{CStr, CString};
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn take_string(s: *mut c_char) -> String {
+        let value = unsafe { CStr::from_ptr(s) }.to_str().unwrap().to_string();
+        unsafe { walletd_string_free(s) };
+        value
+    }
+
+    #[test]
+    fn test_wallet_lifecycle() {
+        let phrase = CString::new(PHRASE).unwrap();
+        let mut wallet = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                walletd_wallet_from_mnemonic(phrase.as_ptr(), std::ptr::null(), &mut wallet),
+                WALLETD_OK
+            );
+            assert!(walletd_validate_mnemonic(phrase.as_ptr()));
+
+            let mut address = std::ptr::null_mut();
+            let chain = CString::new("eth").unwrap();
+            assert_eq!(
+                walletd_wallet_address(wallet, chain.as_ptr(), 0, &mut address),
+                WALLETD_OK
+            );
+            assert_eq!(
+                take_string(address),
+                "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"
+            );
+
+            let mut signature = WalletdBytes::new(Vec::new());
+            let message = b"hello";
+            assert_eq!(
+                walletd_wallet_sign_message(
+                    wallet,
+                    chain.as_ptr(),
+                    0,
+                    message.as_ptr(),
+                    message.len(),
+                    &mut signature
+                ),
+                WALLETD_OK
+            );
+            assert_eq!(signature.len, 65);
+            walletd_bytes_free(signature);
+
+            let solana = CString::new("solana").unwrap();
+            let mut signature = WalletdBytes::new(Vec::new());
+            assert_eq!(
+                walletd_wallet_sign_hash(
+                    wallet,
+                    solana.as_ptr(),
+                    0,
+                    [7u8; 32].as_ptr(),
+                    &mut signature
+                ),
+                WALLETD_OK
+            );
+            assert_eq!(signature.len, 64);
+            walletd_bytes_free(signature);
+
+            let tx = CString::new(
+                r#"{"chainId": 1, "nonce": 0, "gas": 21000, "gasPrice": "1000000000", "to": "0x3535353535353535353535353535353535353535"}"#,
+            )
+            .unwrap();
+            let mut signed = std::ptr::null_mut();
+            assert_eq!(
+                walletd_wallet_sign_evm_transaction(wallet, 0, tx.as_ptr(), &mut signed),
+                WALLETD_OK
+            );
+            let signed: serde_json::Value = serde_json::from_str(&take_string(signed)).unwrap();
+            assert!(signed["raw"].as_str().unwrap().starts_with("0xf8"));
+
+            walletd_wallet_free(wallet);
+        }
+    }
+
+    #[test]
+    fn test_invalid_arguments() {
+        let mut out = std::ptr::null_mut();
+        unsafe {
+            assert_eq!(
+                walletd_generate_mnemonic(13, &mut out),
+                WALLETD_ERR_INVALID_ARGUMENT
+            );
+            assert!(out.is_null());
+            assert_eq!(walletd_generate_mnemonic(12, &mut out), WALLETD_OK);
+            assert_eq!(take_string(out).split(' ').count(), 12);
+
+            let mut wallet = std::ptr::null_mut();
+            assert_eq!(
+                walletd_wallet_from_mnemonic(std::ptr::null(), std::ptr::null(), &mut wallet),
+                WALLETD_ERR_INVALID_ARGUMENT
+            );
+            let bad = CString::new("not a mnemonic").unwrap();
+            assert_eq!(
+                walletd_wallet_from_mnemonic(bad.as_ptr(), std::ptr::null(), &mut wallet),
+                7001
+            );
+            assert!(wallet.is_null());
+
+            let chain = CString::new("dogecoin").unwrap();
+            let mut address = std::ptr::null_mut();
+            assert_eq!(
+                walletd_wallet_address(std::ptr::null(), chain.as_ptr(), 0, &mut address),
+                WALLETD_ERR_INVALID_ARGUMENT
+            );
+            walletd_wallet_free(std::ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,82 @@
+//! Address encodings for derived public keys
+
+use crate::chain::HdChain;
+use crate::{HdError, Result};
+use bech32::{Bech32, Hrp};
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use ripemd::Ripemd160;
+use sha2::Sha256;
+use sha3::{Keccak256, Sha3_256};
+
+/// Encodes a public key as `chain`'s default address
+///
+/// `public_key` is what the chain's [`Signer`](walletd_traits::Signer)
+/// returns: a 33-byte compressed SEC1 key or a 32-byte Ed25519 key.
+pub fn encode(chain: HdChain, public_key: &[u8]) -> Result<String> {
+    match chain {
+        HdChain::Bitcoin => {
+            let hrp = Hrp::parse("bc").expect("valid hrp");
+            bech32::segwit::encode_v0(hrp, &hash160(public_key))
+                .map_err(|e| HdError::Derivation(e.to_string()))
+        }
+        HdChain::Ethereum
+        | HdChain::Polygon
+        | HdChain::Arbitrum
+        | HdChain::Avalanche
+        | HdChain::Base => Ok(checksum_address(&keccak_address(public_key)?)),
+        HdChain::Tron => {
+            let mut payload = vec![0x41];
+            payload.extend_from_slice(&keccak_address(public_key)?);
+            Ok(bs58::encode(payload).with_check().into_string())
+        }
+        HdChain::Cosmos => {
+            let hrp = Hrp::parse("cosmos").expect("valid hrp");
+            bech32::encode::<Bech32>(hrp, &hash160(public_key))
+                .map_err(|e| HdError::Derivation(e.to_string()))
+        }
+        HdChain::Solana => Ok(bs58::encode(public_key).into_string()),
+        HdChain::Sui => {
+            let mut hasher = Blake2b::<U32>::new();
+            hasher.update([0x00]);
+            hasher.update(public_key);
+            Ok(format!("0x{}", hex::encode(hasher.finalize())))
+        }
+        HdChain::Aptos => {
+            let mut hasher = Sha3_256::new();
+            hasher.update(public_key);
+            hasher.update([0x00]);
+            Ok(format!("0x{}", hex::encode(hasher.finalize())))
+        }
+        HdChain::Near => Ok(hex::encode(public_key)),
+    }
+}
+
+fn hash160(data: &[u8]) -> [u8; 20] {
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+fn keccak_address(public_key: &[u8]) -> Result<[u8; 20]> {
+    let key = secp256k1::PublicKey::from_slice(public_key)
+        .map_err(|e| HdError::Derivation(e.to_string()))?;
+    let hash = Keccak256::digest(&key.serialize_uncompressed()[1..]);
+    let mut address = [0u8; 20];
+    address.copy_from_slice(&hash[12..]);
+    Ok(address)
+}
+
+/// EIP-55 mixed-case checksum
+fn checksum_address(address: &[u8; 20]) -> String {
+    let lower = hex::encode(address);
+    let hash = Keccak256::digest(lower.as_bytes());
+    let mut out = String::from("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = (hash[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+        out.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    out
+}
@@ -0,0 +1,154 @@
+//! BIP-85 deterministic entropy
+//!
+//! Derives independent child secrets from the master key: a child mnemonic
+//! can seed a hot wallet or a test wallet, and is always recoverable from
+//! the master mnemonic, while nothing about the master leaks from the child.
+//!
+//! Each application has its own path under `m/83696968'`; the derived key is
+//! hashed with HMAC-SHA512 (key `bip-entropy-from-k`) and truncated.
+
+use crate::chain::HARDENED;
+use crate::{HdError, Result};
+use bip32::{ChildNumber, XPrv};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+const PURPOSE: u32 = 83696968;
+const APP_BIP39: u32 = 39;
+const APP_HEX: u32 = 128169;
+const LANGUAGE_ENGLISH: u32 = 0;
+
+/// BIP-39 child mnemonic `index` with `words` words (12, 15, 18, 21 or 24),
+/// at `m/83696968'/39'/0'/{words}'/{index}'`
+pub fn mnemonic(seed: &[u8], words: u8, index: u32) -> Result<Zeroizing<String>> {
+    mnemonic_from_root(&root(seed)?, words, index)
+}
+
+/// `num_bytes` bytes (16 to 64) of child entropy as hex, at
+/// `m/83696968'/128169'/{num_bytes}'/{index}'`
+pub fn hex(seed: &[u8], num_bytes: u8, index: u32) -> Result<Zeroizing<String>> {
+    hex_from_root(&root(seed)?, num_bytes, index)
+}
+
+fn root(seed: &[u8]) -> Result<XPrv> {
+    XPrv::new(seed).map_err(|e| HdError::Derivation(e.to_string()))
+}
+
+fn mnemonic_from_root(root: &XPrv, words: u8, index: u32) -> Result<Zeroizing<String>> {
+    if !matches!(words, 12 | 15 | 18 | 21 | 24) {
+        return Err(HdError::InvalidPath(format!(
+            "{} words is not a BIP-39 length",
+            words
+        )));
+    }
+    let entropy = entropy(root, &[APP_BIP39, LANGUAGE_ENGLISH, words as u32, index])?;
+    let len = words as usize * 4 / 3;
+    let mnemonic = bip39::Mnemonic::from_entropy(&entropy[..len])
+        .map_err(|e| HdError::Derivation(e.to_string()))?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+fn hex_from_root(root: &XPrv, num_bytes: u8, index: u32) -> Result<Zeroizing<String>> {
+    if !(16..=64).contains(&num_bytes) {
+        return Err(HdError::InvalidPath(format!(
+            "{} bytes is outside 16-64",
+            num_bytes
+        )));
+    }
+    let entropy = entropy(root, &[APP_HEX, num_bytes as u32, index])?;
+    Ok(Zeroizing::new(::hex::encode(
+        &entropy[..num_bytes as usize],
+    )))
+}
+
+/// Derives `m/83696968'/{path}'` (every level hardened) and returns the
+/// 64 bytes of entropy for it
+fn entropy(root: &XPrv, path: &[u32]) -> Result<Zeroizing<[u8; 64]>> {
+    let mut key = root
+        .derive_child(ChildNumber(PURPOSE | HARDENED))
+        .map_err(derivation)?;
+    for &index in path {
+        if index >= HARDENED {
+            return Err(HdError::InvalidPath(format!(
+                "index {} is out of range",
+                index
+            )));
+        }
+        key = key
+            .derive_child(ChildNumber(index | HARDENED))
+            .map_err(derivation)?;
+    }
+    let secret = Zeroizing::new(key.private_key().to_bytes());
+    let mut mac =
+        Hmac::<Sha512>::new_from_slice(b"bip-entropy-from-k").expect("HMAC takes any key length");
+    mac.update(&secret);
+    Ok(Zeroizing::new(mac.finalize().into_bytes().into()))
+}
+
+fn derivation(e: bip32::Error) -> HdError {
+    HdError::Derivation(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // Master key of the BIP-85 test vectors
+    const ROOT: &str = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb";
+
+    fn root() -> XPrv {
+        XPrv::from_str(ROOT).unwrap()
+    }
+
+    #[test]
+    fn test_entropy_vector() {
+        // Test case 1: m/83696968'/0'/0'
+        let entropy = entropy(&root(), &[0, 0]).unwrap();
+        assert_eq!(
+            ::hex::encode(&entropy[..]),
+            "efecfbccffea313214232d29e71563d941229afb4338c21f9517c41aaa0d16f0\
+             0b83d2a09ef747e7a64e8e2bd5a14869e693da66ce94ac2da570ab7ee48618f7"
+        );
+    }
+
+    #[test]
+    fn test_mnemonic_vectors() {
+        let cases = [
+            (12, "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose"),
+            (
+                18,
+                "near account window bike charge season chef number sketch tomorrow excuse sniff \
+                 circle vital hockey outdoor supply token",
+            ),
+            (
+                24,
+                "puppy ocean match cereal symbol another shed magic wrap hammer bulb intact gadget \
+                 divorce twin tonight reason outdoor destroy simple truth cigar social volcano",
+            ),
+        ];
+        for (words, expected) in cases {
+            assert_eq!(*mnemonic_from_root(&root(), words, 0).unwrap(), expected);
+        }
+        assert!(mnemonic_from_root(&root(), 13, 0).is_err());
+    }
+
+    #[test]
+    fn test_hex_vector() {
+        let hex = hex_from_root(&root(), 64, 0).unwrap();
+        assert_eq!(
+            *hex,
+            "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f8785\
+             55d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+        );
+        assert!(hex_from_root(&root(), 8, 0).is_err());
+    }
+
+    #[test]
+    fn test_children_are_independent() {
+        let a = mnemonic_from_root(&root(), 12, 0).unwrap();
+        let b = mnemonic_from_root(&root(), 12, 1).unwrap();
+        assert_ne!(*a, *b);
+    }
+}
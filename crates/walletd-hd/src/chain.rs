@@ -0,0 +1,232 @@
+//! Per-chain derivation rules
+//!
+//! Each chain fixes a curve, a SLIP-44 coin type and a path template. The
+//! account index goes where the chain's reference wallet puts it, so account
+//! `n` here is account `n` in MetaMask, Phantom, Keplr, Sui Wallet and so on.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+use crate::HdError;
+
+/// Hardened derivation offset
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// Elliptic curve a chain's keys live on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Curve {
+    /// secp256k1, derived with BIP-32
+    Secp256k1,
+    /// Ed25519, derived with SLIP-10 (hardened indexes only)
+    Ed25519,
+}
+
+impl fmt::Display for Curve {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Curve::Secp256k1 => f.write_str("secp256k1"),
+            Curve::Ed25519 => f.write_str("ed25519"),
+        }
+    }
+}
+
+/// A chain the manager can derive accounts for
+///
+/// Polkadot (sr25519), Cardano (BIP32-Ed25519) and TON (its own mnemonic
+/// scheme) do not use BIP-32 or SLIP-10 and are not covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HdChain {
+    /// Bitcoin, native SegWit (BIP-84)
+    Bitcoin,
+    /// Ethereum
+    Ethereum,
+    /// Polygon PoS
+    Polygon,
+    /// Arbitrum One
+    Arbitrum,
+    /// Avalanche C-Chain
+    Avalanche,
+    /// Base
+    Base,
+    /// Tron
+    Tron,
+    /// Cosmos Hub
+    Cosmos,
+    /// Solana
+    Solana,
+    /// Sui
+    Sui,
+    /// Aptos
+    Aptos,
+    /// NEAR
+    Near,
+}
+
+impl HdChain {
+    /// Every supported chain
+    pub const ALL: [HdChain; 12] = [
+        HdChain::Bitcoin,
+        HdChain::Ethereum,
+        HdChain::Polygon,
+        HdChain::Arbitrum,
+        HdChain::Avalanche,
+        HdChain::Base,
+        HdChain::Tron,
+        HdChain::Cosmos,
+        HdChain::Solana,
+        HdChain::Sui,
+        HdChain::Aptos,
+        HdChain::Near,
+    ];
+
+    /// Curve the chain signs with
+    pub fn curve(&self) -> Curve {
+        match self {
+            HdChain::Solana | HdChain::Sui | HdChain::Aptos | HdChain::Near => Curve::Ed25519,
+            _ => Curve::Secp256k1,
+        }
+    }
+
+    /// SLIP-44 coin type (EVM chains share Ethereum's so one address works
+    /// on all of them)
+    pub fn coin_type(&self) -> u32 {
+        match self {
+            HdChain::Bitcoin => 0,
+            HdChain::Ethereum
+            | HdChain::Polygon
+            | HdChain::Arbitrum
+            | HdChain::Avalanche
+            | HdChain::Base => 60,
+            HdChain::Tron => 195,
+            HdChain::Cosmos => 118,
+            HdChain::Solana => 501,
+            HdChain::Sui => 784,
+            HdChain::Aptos => 637,
+            HdChain::Near => 397,
+        }
+    }
+
+    /// Returns true for Ethereum and the EVM chains that share its keys
+    pub fn is_evm(&self) -> bool {
+        self.coin_type() == 60
+    }
+
+    /// Derivation path of account `index`
+    pub fn path(&self, index: u32) -> String {
+        let coin = self.coin_type();
+        match self {
+            HdChain::Bitcoin => format!("m/84'/{}'/{}'/0/0", coin, index),
+            HdChain::Ethereum
+            | HdChain::Polygon
+            | HdChain::Arbitrum
+            | HdChain::Avalanche
+            | HdChain::Base
+            | HdChain::Tron
+            | HdChain::Cosmos => format!("m/44'/{}'/0'/0/{}", coin, index),
+            HdChain::Solana => format!("m/44'/{}'/{}'/0'", coin, index),
+            HdChain::Sui | HdChain::Aptos => format!("m/44'/{}'/{}'/0'/0'", coin, index),
+            HdChain::Near => format!("m/44'/{}'/{}'", coin, index),
+        }
+    }
+
+    /// Lowercase chain name, as used in config and serialized indexes
+    pub fn name(&self) -> &'static str {
+        match self {
+            HdChain::Bitcoin => "bitcoin",
+            HdChain::Ethereum => "ethereum",
+            HdChain::Polygon => "polygon",
+            HdChain::Arbitrum => "arbitrum",
+            HdChain::Avalanche => "avalanche",
+            HdChain::Base => "base",
+            HdChain::Tron => "tron",
+            HdChain::Cosmos => "cosmos",
+            HdChain::Solana => "solana",
+            HdChain::Sui => "sui",
+            HdChain::Aptos => "aptos",
+            HdChain::Near => "near",
+        }
+    }
+}
+
+impl fmt::Display for HdChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for HdChain {
+    type Err = HdError;
+
+    /// Parses a chain name or ticker, case-insensitively
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let chain = match s.trim().to_ascii_lowercase().as_str() {
+            "bitcoin" | "btc" => HdChain::Bitcoin,
+            "ethereum" | "eth" => HdChain::Ethereum,
+            "polygon" | "matic" | "pol" => HdChain::Polygon,
+            "arbitrum" | "arb" => HdChain::Arbitrum,
+            "avalanche" | "avax" => HdChain::Avalanche,
+            "base" => HdChain::Base,
+            "tron" | "trx" => HdChain::Tron,
+            "cosmos" | "atom" => HdChain::Cosmos,
+            "solana" | "sol" => HdChain::Solana,
+            "sui" => HdChain::Sui,
+            "aptos" | "apt" => HdChain::Aptos,
+            "near" => HdChain::Near,
+            _ => return Err(HdError::UnsupportedChain(s.to_string())),
+        };
+        Ok(chain)
+    }
+}
+
+impl TryFrom<walletd_traits::Chain> for HdChain {
+    type Error = HdError;
+
+    fn try_from(chain: walletd_traits::Chain) -> Result<Self, Self::Error> {
+        match chain {
+            walletd_traits::Chain::Bitcoin => Ok(HdChain::Bitcoin),
+            walletd_traits::Chain::Ethereum => Ok(HdChain::Ethereum),
+            walletd_traits::Chain::Solana => Ok(HdChain::Solana),
+            walletd_traits::Chain::Cosmos => Ok(HdChain::Cosmos),
+            // TON keys come from its own mnemonic scheme, not a derivation path
+            _ => Err(HdError::UnsupportedChain(chain.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paths() {
+        assert_eq!(HdChain::Ethereum.path(3), "m/44'/60'/0'/0/3");
+        assert_eq!(HdChain::Base.path(0), HdChain::Ethereum.path(0));
+        assert_eq!(HdChain::Bitcoin.path(1), "m/84'/0'/1'/0/0");
+        assert_eq!(HdChain::Solana.path(2), "m/44'/501'/2'/0'");
+        assert_eq!(HdChain::Near.path(0), "m/44'/397'/0'");
+    }
+
+    #[test]
+    fn test_ed25519_paths_are_hardened() {
+        for chain in HdChain::ALL.iter().filter(|c| c.curve() == Curve::Ed25519) {
+            let path = chain.path(7);
+            assert!(
+                path.split('/').skip(1).all(|part| part.ends_with('\'')),
+                "{}",
+                path
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse() {
+        for chain in HdChain::ALL {
+            assert_eq!(chain.name().parse::<HdChain>().unwrap(), chain);
+        }
+        assert_eq!("ATOM".parse::<HdChain>().unwrap(), HdChain::Cosmos);
+        assert!("polkadot".parse::<HdChain>().is_err());
+    }
+}
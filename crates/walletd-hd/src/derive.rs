@@ -0,0 +1,145 @@
+//! BIP-39 seeds, BIP-32 (secp256k1) and SLIP-10 (Ed25519) derivation
+
+use crate::chain::{Curve, HARDENED};
+use crate::{HdError, Result};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use zeroize::Zeroizing;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Parses `m/44'/60'/0'/0/0` into raw indexes; `h` marks hardened indexes as
+/// well as `'`
+pub fn parse_path(path: &str) -> Result<Vec<u32>> {
+    let invalid = || HdError::InvalidPath(path.to_string());
+    let body = path
+        .strip_prefix("m/")
+        .or_else(|| path.strip_prefix('m'))
+        .ok_or_else(invalid)?;
+    if body.is_empty() {
+        return Ok(Vec::new());
+    }
+    body.split('/')
+        .map(|part| {
+            let (digits, hardened) = match part.strip_suffix(['\'', 'h', 'H']) {
+                Some(digits) => (digits, true),
+                None => (part, false),
+            };
+            let index: u32 = digits.parse().map_err(|_| invalid())?;
+            if index >= HARDENED {
+                return Err(invalid());
+            }
+            Ok(if hardened { index | HARDENED } else { index })
+        })
+        .collect()
+}
+
+/// BIP-39 seed for a mnemonic and optional passphrase
+pub fn seed(phrase: &str, passphrase: &str) -> Result<Zeroizing<[u8; 64]>> {
+    let mnemonic = bip39::Mnemonic::parse_normalized(phrase)
+        .map_err(|e| HdError::InvalidMnemonic(e.to_string()))?;
+    Ok(Zeroizing::new(mnemonic.to_seed(passphrase)))
+}
+
+/// Derives the 32-byte private key at `path` on `curve`
+///
+/// For Ed25519 this is the key seed that [`Ed25519Signer`] takes.
+///
+/// [`Ed25519Signer`]: walletd_traits::Ed25519Signer
+pub fn derive_key(seed: &[u8], curve: Curve, path: &str) -> Result<Zeroizing<[u8; 32]>> {
+    let indexes = parse_path(path)?;
+    match curve {
+        Curve::Secp256k1 => bip32(seed, &indexes),
+        Curve::Ed25519 => slip10_ed25519(seed, &indexes),
+    }
+}
+
+fn bip32(seed: &[u8], indexes: &[u32]) -> Result<Zeroizing<[u8; 32]>> {
+    let mut key = bip32::XPrv::new(seed).map_err(|e| HdError::Derivation(e.to_string()))?;
+    for &index in indexes {
+        key = key
+            .derive_child(bip32::ChildNumber(index))
+            .map_err(|e| HdError::Derivation(e.to_string()))?;
+    }
+    Ok(Zeroizing::new(key.private_key().to_bytes().into()))
+}
+
+fn slip10_ed25519(seed: &[u8], indexes: &[u32]) -> Result<Zeroizing<[u8; 32]>> {
+    if let Some(index) = indexes.iter().find(|i| *i & HARDENED == 0) {
+        return Err(HdError::InvalidPath(format!(
+            "Ed25519 derivation needs hardened indexes, got {}",
+            index
+        )));
+    }
+    let (mut key, mut chain_code) = split(hmac(b"ed25519 seed", &[seed]));
+    for &index in indexes {
+        (key, chain_code) = split(hmac(
+            &chain_code[..],
+            &[&[0u8], &key[..], &index.to_be_bytes()],
+        ));
+    }
+    Ok(key)
+}
+
+fn hmac(key: &[u8], parts: &[&[u8]]) -> Zeroizing<[u8; 64]> {
+    let mut mac = HmacSha512::new_from_slice(key).expect("HMAC takes any key length");
+    for part in parts {
+        mac.update(part);
+    }
+    Zeroizing::new(mac.finalize().into_bytes().into())
+}
+
+fn split(output: Zeroizing<[u8; 64]>) -> (Zeroizing<[u8; 32]>, Zeroizing<[u8; 32]>) {
+    let mut key = Zeroizing::new([0u8; 32]);
+    let mut chain_code = Zeroizing::new([0u8; 32]);
+    key.copy_from_slice(&output[..32]);
+    chain_code.copy_from_slice(&output[32..]);
+    (key, chain_code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_path() {
+        assert_eq!(
+            parse_path("m/44'/60'/0'/0/1").unwrap(),
+            vec![44 | HARDENED, 60 | HARDENED, HARDENED, 0, 1]
+        );
+        assert_eq!(parse_path("m").unwrap(), Vec::<u32>::new());
+        assert!(parse_path("44'/60'").is_err());
+        assert!(parse_path("m/x").is_err());
+    }
+
+    #[test]
+    fn test_slip10_vector() {
+        // SLIP-10 test vector 1 for ed25519, chain m/0'/1'
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = derive_key(&seed, Curve::Ed25519, "m/0'/1'").unwrap();
+        assert_eq!(
+            hex::encode(*key),
+            "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2"
+        );
+        assert!(derive_key(&seed, Curve::Ed25519, "m/0").is_err());
+    }
+
+    #[test]
+    fn test_bip32_vector() {
+        // BIP-32 test vector 1, chain m/0'/1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        let key = derive_key(&seed, Curve::Secp256k1, "m/0'/1").unwrap();
+        assert_eq!(
+            hex::encode(*key),
+            "3c6cb8d0f6a264c91ea8b5030fadaa8e538b020f0a387421a12de9319dc93368"
+        );
+    }
+
+    #[test]
+    fn test_invalid_mnemonic() {
+        assert!(matches!(
+            seed("abandon abandon", ""),
+            Err(HdError::InvalidMnemonic(_))
+        ));
+    }
+}
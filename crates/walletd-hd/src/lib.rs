@@ -0,0 +1,121 @@
+//! # WalletD HD
+//!
+//! One mnemonic, every chain. [`HdManager`] turns a BIP-39 mnemonic and
+//! passphrase into accounts on each supported chain, with the coin type,
+//! curve and path layout the chain's own wallets use:
+//!
+//! - secp256k1 chains (Bitcoin, EVM, Tron, Cosmos) derive with BIP-32
+//! - Ed25519 chains (Solana, Sui, Aptos, NEAR) derive with SLIP-10
+//!
+//! Each [`HdAccount`] carries its path, address and a [`Signer`], which plugs
+//! into any wallet's `from_signer` constructor. With the `tron`, `cosmos` and
+//! `near` features the manager builds those wallets directly.
+//!
+//! [`HdManager::child_mnemonic`] derives BIP-85 child mnemonics: separate
+//! wallets that are recoverable from the master mnemonic alone.
+//!
+//! [`slip39`] splits a seed into Shamir shares for custodians, and
+//! [`HdManager::from_slip39`] opens a wallet from them.
+//!
+//! [`vanity`] grinds random keys for addresses with a chosen prefix or
+//! suffix on EVM chains, Tron and Solana.
+//!
+//! Store the mnemonic itself with `walletd-keystore`; the manager only keeps
+//! the derived seed in memory.
+//!
+//! ## Example
+//!
+//! ```
+//! use walletd_hd::{HdChain, HdManager};
+//!
+//! # fn main() -> walletd_hd::Result<()> {
+//! let phrase = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+//! let mut manager = HdManager::from_mnemonic(phrase, "")?;
+//!
+//! let eth = manager.next_account(HdChain::Ethereum)?;
+//! let sol = manager.next_account(HdChain::Solana)?;
+//! assert_eq!(eth.address(), "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+//! assert_eq!(sol.path(), "m/44'/501'/0'/0'");
+//! assert_eq!(manager.next_index(HdChain::Ethereum), 1);
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Signer`]: walletd_traits::Signer
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod address;
+pub mod bip85;
+pub mod chain;
+pub mod derive;
+pub mod manager;
+pub mod slip39;
+pub mod vanity;
+
+pub use chain::{Curve, HdChain};
+pub use manager::{HdAccount, HdManager};
+pub use vanity::{CancelToken, VanityGenerator, VanityKey, VanityProgress};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+
+/// HD derivation errors
+#[derive(Error, Debug)]
+pub enum HdError {
+    /// The mnemonic is not valid BIP-39
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+
+    /// The derivation path is malformed or not usable on the curve
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+
+    /// Key derivation or encoding failed
+    #[error("Derivation failed: {0}")]
+    Derivation(String),
+
+    /// The chain has no BIP-32 or SLIP-10 derivation
+    #[error("Unsupported chain: {0}")]
+    UnsupportedChain(String),
+
+    /// SLIP-39 share generation or recovery failed
+    #[error(transparent)]
+    Slip39(#[from] slip39::Slip39Error),
+
+    /// The chain wallet rejected the derived signer
+    #[error("Wallet error: {0}")]
+    Wallet(String),
+
+    /// A vanity pattern cannot match any address of the chain
+    #[error("Invalid vanity pattern: {0}")]
+    InvalidPattern(String),
+
+    /// A vanity search was cancelled or ran out of attempts
+    #[error("Vanity search stopped after {0} attempts")]
+    Cancelled(u64),
+}
+
+/// Result type for HD operations
+pub type Result<T> = std::result::Result<T, HdError>;
+
+impl From<HdError> for WalletdError {
+    fn from(e: HdError) -> Self {
+        match e {
+            HdError::InvalidMnemonic(reason) => WalletdError::InvalidMnemonic(reason),
+            HdError::InvalidPath(reason) | HdError::Derivation(reason) => {
+                WalletdError::KeyDerivationError(reason)
+            }
+            HdError::UnsupportedChain(chain) => {
+                WalletdError::NotSupported(format!("HD derivation for {}", chain))
+            }
+            HdError::Slip39(e) => WalletdError::InvalidMnemonic(e.to_string()),
+            HdError::Wallet(reason) => WalletdError::External {
+                message: format!("hd: {}", reason),
+            },
+            HdError::InvalidPattern(_) => WalletdError::FormatError(e.to_string()),
+            HdError::Cancelled(_) => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
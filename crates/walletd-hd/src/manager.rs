@@ -0,0 +1,408 @@
+//! The seed-holding account manager
+
+use crate::address;
+use crate::bip85;
+use crate::chain::{Curve, HdChain, HARDENED};
+use crate::derive::{derive_key, seed};
+use crate::slip39::{self, Slip39};
+use crate::{HdError, Result};
+use std::collections::BTreeMap;
+use std::fmt;
+use walletd_traits::{Ed25519Signer, Secp256k1Signer, Signer};
+use zeroize::Zeroizing;
+
+/// One derived account
+pub struct HdAccount {
+    chain: HdChain,
+    index: u32,
+    path: String,
+    address: String,
+    signer: Box<dyn Signer>,
+}
+
+impl HdAccount {
+    /// Chain the account belongs to
+    pub fn chain(&self) -> HdChain {
+        self.chain
+    }
+
+    /// Account index
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+
+    /// Derivation path
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// Default address on the chain's main network
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Signer holding the account's key
+    pub fn signer(&self) -> &dyn Signer {
+        self.signer.as_ref()
+    }
+
+    /// Takes the signer, e.g. for a wallet's `from_signer` constructor
+    pub fn into_signer(self) -> Box<dyn Signer> {
+        self.signer
+    }
+}
+
+impl fmt::Debug for HdAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HdAccount")
+            .field("chain", &self.chain)
+            .field("index", &self.index)
+            .field("path", &self.path)
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Derives accounts on every supported chain from one BIP-39 seed
+///
+/// The manager keeps the seed in memory (zeroized on drop) and, per chain,
+/// the index of the next unused account. Indexes are plain data: save
+/// [`indexes`](Self::indexes) alongside the encrypted mnemonic and restore
+/// them with [`with_indexes`](Self::with_indexes). EVM chains share keys but
+/// are counted separately, so opening a Polygon account does not consume an
+/// Ethereum one.
+#[derive(Clone)]
+pub struct HdManager {
+    seed: Zeroizing<Vec<u8>>,
+    next: BTreeMap<HdChain, u32>,
+}
+
+impl HdManager {
+    /// Creates a manager from a mnemonic phrase and BIP-39 passphrase (use
+    /// `""` for none)
+    pub fn from_mnemonic(phrase: &str, passphrase: &str) -> Result<Self> {
+        Ok(Self {
+            seed: Zeroizing::new(seed(phrase, passphrase)?.to_vec()),
+            next: BTreeMap::new(),
+        })
+    }
+
+    /// Creates a manager from a 64-byte BIP-39 seed
+    pub fn from_seed(seed: [u8; 64]) -> Self {
+        let seed = Zeroizing::new(seed);
+        Self {
+            seed: Zeroizing::new(seed.to_vec()),
+            next: BTreeMap::new(),
+        }
+    }
+
+    /// Creates a manager from SLIP-39 shares, using the recovered master
+    /// secret as the BIP-32 seed (as Trezor does)
+    pub fn from_slip39<S: AsRef<str>>(shares: &[S], passphrase: &str) -> Result<Self> {
+        Ok(Self {
+            seed: slip39::combine(shares, passphrase)?,
+            next: BTreeMap::new(),
+        })
+    }
+
+    /// Splits this manager's seed into SLIP-39 shares
+    ///
+    /// Recovering with [`from_slip39`](Self::from_slip39) gives back the same
+    /// accounts. A seed from a BIP-39 mnemonic is 64 bytes, so each share is
+    /// 59 words; the mnemonic itself cannot be recovered from the shares.
+    pub fn slip39_backup(
+        &self,
+        scheme: &Slip39,
+        passphrase: &str,
+    ) -> Result<Vec<Vec<Zeroizing<String>>>> {
+        scheme.split(&self.seed, passphrase)
+    }
+
+    /// Restores saved account indexes (builder style)
+    pub fn with_indexes(mut self, indexes: BTreeMap<HdChain, u32>) -> Self {
+        self.next = indexes;
+        self
+    }
+
+    /// Next unused account index per chain; chains never used are absent
+    pub fn indexes(&self) -> &BTreeMap<HdChain, u32> {
+        &self.next
+    }
+
+    /// Index the next [`next_account`](Self::next_account) call will use
+    pub fn next_index(&self, chain: HdChain) -> u32 {
+        self.next.get(&chain).copied().unwrap_or(0)
+    }
+
+    /// Sets the next unused account index for `chain`, e.g. after discovering
+    /// funded accounts on chain
+    pub fn set_next_index(&mut self, chain: HdChain, index: u32) {
+        self.next.insert(chain, index);
+    }
+
+    /// Derives account `index` on `chain`
+    ///
+    /// This does not change the tracked indexes.
+    pub fn account(&self, chain: HdChain, index: u32) -> Result<HdAccount> {
+        if index >= HARDENED {
+            return Err(HdError::InvalidPath(format!(
+                "account index {} is out of range",
+                index
+            )));
+        }
+        let path = chain.path(index);
+        let signer = self.signer_at(chain.curve(), &path)?;
+        let address = address::encode(chain, &signer.public_key())?;
+        Ok(HdAccount {
+            chain,
+            index,
+            path,
+            address,
+            signer,
+        })
+    }
+
+    /// Derives the next unused account on `chain` and marks it used
+    pub fn next_account(&mut self, chain: HdChain) -> Result<HdAccount> {
+        let index = self.next_index(chain);
+        let account = self.account(chain, index)?;
+        self.next.insert(chain, index + 1);
+        Ok(account)
+    }
+
+    /// Derives every account opened so far on `chain`
+    pub fn accounts(&self, chain: HdChain) -> Result<Vec<HdAccount>> {
+        (0..self.next_index(chain))
+            .map(|index| self.account(chain, index))
+            .collect()
+    }
+
+    /// Address of account `index` on `chain`
+    pub fn address(&self, chain: HdChain, index: u32) -> Result<String> {
+        Ok(self.account(chain, index)?.address)
+    }
+
+    /// Signer for account `index` on `chain`
+    pub fn signer(&self, chain: HdChain, index: u32) -> Result<Box<dyn Signer>> {
+        Ok(self.account(chain, index)?.signer)
+    }
+
+    /// Signer for an arbitrary path, for wallets that do not follow the
+    /// standard layout
+    pub fn signer_at(&self, curve: Curve, path: &str) -> Result<Box<dyn Signer>> {
+        let key = derive_key(&self.seed[..], curve, path)?;
+        Ok(match curve {
+            Curve::Secp256k1 => Box::new(
+                Secp256k1Signer::from_slice(&key[..])
+                    .map_err(|e| HdError::Derivation(e.to_string()))?,
+            ),
+            Curve::Ed25519 => Box::new(Ed25519Signer::from_bytes(&key)),
+        })
+    }
+
+    /// BIP-85 child mnemonic `index` with `words` words
+    ///
+    /// The child is an independent wallet (for a hot wallet, a test wallet,
+    /// an app) that can always be regenerated from this manager's mnemonic.
+    pub fn child_mnemonic(&self, words: u8, index: u32) -> Result<Zeroizing<String>> {
+        bip85::mnemonic(&self.seed[..], words, index)
+    }
+
+    /// Manager for the BIP-85 child mnemonic `index`, with no passphrase and
+    /// fresh account indexes
+    pub fn child(&self, words: u8, index: u32) -> Result<HdManager> {
+        HdManager::from_mnemonic(&self.child_mnemonic(words, index)?, "")
+    }
+
+    /// Tron wallet for account `index`
+    #[cfg(feature = "tron")]
+    pub fn tron_wallet(
+        &self,
+        index: u32,
+        config: walletd_tron::NetworkConfig,
+    ) -> Result<walletd_tron::TronWallet> {
+        walletd_tron::TronWallet::from_signer(self.signer(HdChain::Tron, index)?, config)
+            .map_err(|e| HdError::Wallet(e.to_string()))
+    }
+
+    /// Cosmos wallet for account `index`
+    ///
+    /// Other Cosmos SDK chains share coin type 118; pass their config to get
+    /// the same key under their bech32 prefix.
+    #[cfg(feature = "cosmos")]
+    pub fn cosmos_wallet(
+        &self,
+        index: u32,
+        config: walletd_cosmos::NetworkConfig,
+    ) -> Result<walletd_cosmos::CosmosWallet> {
+        walletd_cosmos::CosmosWallet::from_signer(self.signer(HdChain::Cosmos, index)?, config)
+            .map_err(|e| HdError::Wallet(e.to_string()))
+    }
+
+    /// NEAR wallet for account `index`, using its implicit account ID
+    #[cfg(feature = "near")]
+    pub fn near_wallet(
+        &self,
+        index: u32,
+        config: walletd_near::NetworkConfig,
+    ) -> Result<walletd_near::NearWallet> {
+        walletd_near::NearWallet::from_signer(self.signer(HdChain::Near, index)?, config)
+            .map_err(|e| HdError::Wallet(e.to_string()))
+    }
+}
+
+impl fmt::Debug for HdManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HdManager")
+            .field("seed", &"<redacted>")
+            .field("indexes", &self.next)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MNEMONIC: &str =
+        "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    fn manager() -> HdManager {
+        HdManager::from_mnemonic(MNEMONIC, "").unwrap()
+    }
+
+    #[test]
+    fn test_known_addresses() {
+        let manager = manager();
+        let cases = [
+            (
+                HdChain::Bitcoin,
+                "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+            ),
+            (
+                HdChain::Ethereum,
+                "0x9858EfFD232B4033E47d90003D41EC34EcaEda94",
+            ),
+            (HdChain::Base, "0x9858EfFD232B4033E47d90003D41EC34EcaEda94"),
+            (HdChain::Tron, "TUEZSdKsoDHQMeZwihtdoBiN46zxhGWYdH"),
+            (
+                HdChain::Cosmos,
+                "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4",
+            ),
+            (
+                HdChain::Solana,
+                "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk",
+            ),
+            (
+                HdChain::Sui,
+                "0x5e93a736d04fbb25737aa40bee40171ef79f65fae833749e3c089fe7cc2161f1",
+            ),
+            (
+                HdChain::Aptos,
+                "0xeb663b681209e7087d681c5d3eed12aaa8e1915e7c87794542c3f96e94b3d3bf",
+            ),
+            (
+                HdChain::Near,
+                "5510e2b44cae6eb807e3e0e45d579dda058c274abcba15e5cb84636f5d1ee412",
+            ),
+        ];
+        for (chain, expected) in cases {
+            assert_eq!(manager.address(chain, 0).unwrap(), expected, "{}", chain);
+        }
+    }
+
+    #[test]
+    fn test_curves_match_chain() {
+        let manager = manager();
+        for chain in HdChain::ALL {
+            let signer = manager.signer(chain, 0).unwrap();
+            let expected = match chain.curve() {
+                Curve::Secp256k1 => walletd_traits::SignatureScheme::Secp256k1,
+                Curve::Ed25519 => walletd_traits::SignatureScheme::Ed25519,
+            };
+            assert_eq!(signer.scheme(), expected, "{}", chain);
+        }
+    }
+
+    #[test]
+    fn test_index_tracking() {
+        let mut manager = manager();
+        assert_eq!(manager.next_account(HdChain::Ethereum).unwrap().index(), 0);
+        let second = manager.next_account(HdChain::Ethereum).unwrap();
+        assert_eq!(second.index(), 1);
+        assert_eq!(second.path(), "m/44'/60'/0'/0/1");
+        assert_ne!(
+            second.address(),
+            manager.address(HdChain::Ethereum, 0).unwrap()
+        );
+        assert_eq!(manager.next_index(HdChain::Polygon), 0);
+
+        let accounts = manager.accounts(HdChain::Ethereum).unwrap();
+        assert_eq!(accounts.len(), 2);
+
+        let json = serde_json::to_string(manager.indexes()).unwrap();
+        assert_eq!(json, r#"{"ethereum":2}"#);
+        let restored = HdManager::from_mnemonic(MNEMONIC, "")
+            .unwrap()
+            .with_indexes(serde_json::from_str(&json).unwrap());
+        assert_eq!(restored.next_index(HdChain::Ethereum), 2);
+    }
+
+    #[test]
+    fn test_passphrase_changes_accounts() {
+        let with_passphrase = HdManager::from_mnemonic(MNEMONIC, "TREZOR").unwrap();
+        assert_ne!(
+            with_passphrase.address(HdChain::Ethereum, 0).unwrap(),
+            manager().address(HdChain::Ethereum, 0).unwrap()
+        );
+    }
+
+    #[cfg(feature = "tron")]
+    #[test]
+    fn test_tron_wallet_matches_account() {
+        let manager = manager();
+        let wallet = manager
+            .tron_wallet(0, walletd_tron::NetworkConfig::mainnet())
+            .unwrap();
+        assert_eq!(wallet.address(), manager.address(HdChain::Tron, 0).unwrap());
+    }
+
+    #[test]
+    fn test_child_manager() {
+        let manager = manager();
+        let phrase = manager.child_mnemonic(12, 0).unwrap();
+        assert_eq!(phrase.split(' ').count(), 12);
+        let child = manager.child(12, 0).unwrap();
+        assert_eq!(
+            child.address(HdChain::Ethereum, 0).unwrap(),
+            HdManager::from_mnemonic(&phrase, "")
+                .unwrap()
+                .address(HdChain::Ethereum, 0)
+                .unwrap()
+        );
+        assert_ne!(
+            child.address(HdChain::Ethereum, 0).unwrap(),
+            manager.address(HdChain::Ethereum, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_slip39_backup_restores_accounts() {
+        let manager = manager();
+        let scheme = Slip39::single(2, 3).with_iteration_exponent(0);
+        let groups = manager.slip39_backup(&scheme, "").unwrap();
+        assert_eq!(groups[0][0].split(' ').count(), 59);
+        let restored = HdManager::from_slip39(&groups[0][1..], "").unwrap();
+        assert_eq!(
+            restored.address(HdChain::Solana, 0).unwrap(),
+            manager.address(HdChain::Solana, 0).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_debug_redacts_seed() {
+        let debug = format!("{:?}", manager());
+        assert!(debug.contains("<redacted>"));
+        assert!(!debug.contains("5eb00bbd"));
+    }
+}
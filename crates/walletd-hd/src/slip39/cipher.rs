@@ -0,0 +1,112 @@
+//! Passphrase encryption of the master secret
+//!
+//! A four-round Feistel network with PBKDF2-HMAC-SHA256 as round function.
+//! Any passphrase decrypts to some secret, which gives plausible deniability
+//! the same way a BIP-39 passphrase does.
+
+use pbkdf2::pbkdf2_hmac;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+const BASE_ITERATION_COUNT: u32 = 10_000;
+const ROUND_COUNT: u8 = 4;
+const CUSTOMIZATION: &[u8] = b"shamir";
+
+/// Encrypts `secret` (even length) under `passphrase`
+pub(super) fn encrypt(
+    secret: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Zeroizing<Vec<u8>> {
+    feistel(
+        secret,
+        passphrase,
+        iteration_exponent,
+        identifier,
+        extendable,
+        0..ROUND_COUNT,
+    )
+}
+
+/// Reverses [`encrypt`]
+pub(super) fn decrypt(
+    encrypted: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+) -> Zeroizing<Vec<u8>> {
+    feistel(
+        encrypted,
+        passphrase,
+        iteration_exponent,
+        identifier,
+        extendable,
+        (0..ROUND_COUNT).rev(),
+    )
+}
+
+fn feistel(
+    input: &[u8],
+    passphrase: &[u8],
+    iteration_exponent: u8,
+    identifier: u16,
+    extendable: bool,
+    rounds: impl Iterator<Item = u8>,
+) -> Zeroizing<Vec<u8>> {
+    let half = input.len() / 2;
+    let mut left = Zeroizing::new(input[..half].to_vec());
+    let mut right = Zeroizing::new(input[half..].to_vec());
+
+    // Extendable backups omit the identifier so new groups can be added
+    // under a fresh identifier later
+    let mut salt = Vec::new();
+    if !extendable {
+        salt.extend_from_slice(CUSTOMIZATION);
+        salt.extend_from_slice(&identifier.to_be_bytes());
+    }
+    let iterations = (BASE_ITERATION_COUNT << iteration_exponent) / ROUND_COUNT as u32;
+
+    for round in rounds {
+        let mut password = Zeroizing::new(vec![round]);
+        password.extend_from_slice(passphrase);
+        let mut round_salt = salt.clone();
+        round_salt.extend_from_slice(&right);
+
+        let mut f = Zeroizing::new(vec![0u8; half]);
+        pbkdf2_hmac::<Sha256>(&password, &round_salt, iterations, &mut f);
+        for (l, f) in left.iter_mut().zip(f.iter()) {
+            *l ^= f;
+        }
+        std::mem::swap(&mut left, &mut right);
+    }
+
+    let mut out = Zeroizing::new(Vec::with_capacity(input.len()));
+    out.extend_from_slice(&right);
+    out.extend_from_slice(&left);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let secret = [7u8; 16];
+        for extendable in [false, true] {
+            let encrypted = encrypt(&secret, b"TREZOR", 0, 0x1234, extendable);
+            assert_ne!(*encrypted, secret.to_vec());
+            assert_eq!(
+                *decrypt(&encrypted, b"TREZOR", 0, 0x1234, extendable),
+                secret.to_vec()
+            );
+            assert_ne!(
+                *decrypt(&encrypted, b"", 0, 0x1234, extendable),
+                secret.to_vec()
+            );
+        }
+    }
+}
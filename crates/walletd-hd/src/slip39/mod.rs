@@ -0,0 +1,621 @@
+//! SLIP-39 Shamir backups
+//!
+//! Splits a master secret into mnemonic shares arranged in groups: the
+//! secret comes back from any `group_threshold` groups, each group opened
+//! with `member_threshold` of its shares. A 2-of-3 across custodians, or
+//! "the owner's 1-of-1 plus 2-of-3 trustees", are both one call.
+//!
+//! The secret is encrypted under an optional passphrase before it is
+//! split, and shares carry an RS1024 checksum so typos are caught per share.
+//! Backups are compatible with Trezor and other SLIP-39 wallets.
+//!
+//! ```
+//! use walletd_hd::slip39::{self, Slip39};
+//!
+//! # fn main() -> walletd_hd::Result<()> {
+//! let secret = [0x5a; 16];
+//! let groups = Slip39::new(1, &[(2, 3)]).with_iteration_exponent(0).split(&secret, "")?;
+//! let shares: Vec<&str> = groups[0][1..].iter().map(|s| s.as_str()).collect();
+//! assert_eq!(*slip39::combine(&shares, "")?, secret.to_vec());
+//! # Ok(())
+//! # }
+//! ```
+
+mod cipher;
+mod shamir;
+mod wordlist;
+
+use crate::Result;
+use rand::Rng;
+use std::collections::BTreeMap;
+use thiserror::Error;
+use wordlist::WORDS;
+use zeroize::Zeroizing;
+
+pub use shamir::MAX_SHARES;
+
+const RADIX_BITS: usize = 10;
+/// Identifier and iteration exponent (2 words), share parameters (2 words),
+/// checksum (3 words)
+const METADATA_WORDS: usize = 7;
+const CHECKSUM_WORDS: usize = 3;
+/// 128-bit secrets give the shortest share
+const MIN_MNEMONIC_WORDS: usize = 20;
+const MIN_SECRET_BYTES: usize = 16;
+const ID_BITS: u32 = 15;
+
+/// SLIP-39 errors
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Slip39Error {
+    /// The split parameters, secret or passphrase are not allowed
+    #[error("Invalid SLIP-39 scheme: {0}")]
+    InvalidScheme(String),
+
+    /// A share is malformed (unknown word, length, padding or checksum)
+    #[error("Invalid SLIP-39 share: {0}")]
+    InvalidShare(String),
+
+    /// The shares do not belong together
+    #[error("Shares do not match: {0}")]
+    InvalidShares(String),
+
+    /// Not enough groups or members to recover the secret
+    #[error("Not enough shares: {0}")]
+    InsufficientShares(String),
+
+    /// The recovered secret failed its digest check (shares from different
+    /// backups, or a corrupted share)
+    #[error("Recovered secret failed the digest check")]
+    InvalidDigest,
+}
+
+/// Split parameters: groups, thresholds and encryption settings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slip39 {
+    group_threshold: u8,
+    groups: Vec<(u8, u8)>,
+    iteration_exponent: u8,
+    extendable: bool,
+}
+
+impl Slip39 {
+    /// Creates a scheme needing `group_threshold` of `groups`, each given as
+    /// `(member_threshold, member_count)`
+    pub fn new(group_threshold: u8, groups: &[(u8, u8)]) -> Self {
+        Self {
+            group_threshold,
+            groups: groups.to_vec(),
+            iteration_exponent: 1,
+            extendable: true,
+        }
+    }
+
+    /// Creates a single-group `threshold`-of-`count` scheme
+    pub fn single(threshold: u8, count: u8) -> Self {
+        Self::new(1, &[(threshold, count)])
+    }
+
+    /// Sets the passphrase stretching: PBKDF2 runs `10000 << exponent`
+    /// iterations (default 1)
+    pub fn with_iteration_exponent(mut self, exponent: u8) -> Self {
+        self.iteration_exponent = exponent;
+        self
+    }
+
+    /// Marks the backup extendable (the default), so more groups can be
+    /// created later for the same secret and passphrase
+    pub fn with_extendable(mut self, extendable: bool) -> Self {
+        self.extendable = extendable;
+        self
+    }
+
+    /// Splits `secret` (16 bytes or more, even length) into mnemonic shares,
+    /// one list per group
+    ///
+    /// The passphrase must be printable ASCII; use `""` for none.
+    pub fn split(&self, secret: &[u8], passphrase: &str) -> Result<Vec<Vec<Zeroizing<String>>>> {
+        self.validate(secret, passphrase)?;
+        let identifier = rand::thread_rng().gen::<u16>() & ((1 << ID_BITS) - 1);
+        let encrypted = cipher::encrypt(
+            secret,
+            passphrase.as_bytes(),
+            self.iteration_exponent,
+            identifier,
+            self.extendable,
+        );
+
+        let group_shares =
+            shamir::split(self.group_threshold, self.groups.len() as u8, &encrypted)?;
+        let mut out = Vec::with_capacity(self.groups.len());
+        for (&(member_threshold, member_count), (group_index, group_secret)) in
+            self.groups.iter().zip(group_shares.iter())
+        {
+            let members = shamir::split(member_threshold, member_count, group_secret)?;
+            let group = members
+                .into_iter()
+                .map(|(member_index, value)| {
+                    Share {
+                        identifier,
+                        extendable: self.extendable,
+                        iteration_exponent: self.iteration_exponent,
+                        group_index: *group_index,
+                        group_threshold: self.group_threshold,
+                        group_count: self.groups.len() as u8,
+                        member_index,
+                        member_threshold,
+                        value,
+                    }
+                    .to_mnemonic()
+                })
+                .collect();
+            out.push(group);
+        }
+        Ok(out)
+    }
+
+    fn validate(&self, secret: &[u8], passphrase: &str) -> std::result::Result<(), Slip39Error> {
+        let invalid = |reason: String| Err(Slip39Error::InvalidScheme(reason));
+        if secret.len() < MIN_SECRET_BYTES || !secret.len().is_multiple_of(2) {
+            return invalid(format!(
+                "secret must be an even number of bytes, at least 16 (got {})",
+                secret.len()
+            ));
+        }
+        if !passphrase.bytes().all(|b| (32..=126).contains(&b)) {
+            return invalid("passphrase must be printable ASCII".to_string());
+        }
+        if self.iteration_exponent > 15 {
+            return invalid(format!(
+                "iteration exponent {} is above 15",
+                self.iteration_exponent
+            ));
+        }
+        if self.groups.is_empty() || self.groups.len() > MAX_SHARES as usize {
+            return invalid(format!("between 1 and {} groups", MAX_SHARES));
+        }
+        if self.group_threshold == 0 || self.group_threshold as usize > self.groups.len() {
+            return invalid(format!(
+                "group threshold {} must be between 1 and the group count {}",
+                self.group_threshold,
+                self.groups.len()
+            ));
+        }
+        for &(threshold, count) in &self.groups {
+            if threshold == 1 && count > 1 {
+                return invalid("a group with threshold 1 must have a single share".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+/// One decoded share
+#[derive(Clone, PartialEq, Eq)]
+pub struct Share {
+    identifier: u16,
+    extendable: bool,
+    iteration_exponent: u8,
+    group_index: u8,
+    group_threshold: u8,
+    group_count: u8,
+    member_index: u8,
+    member_threshold: u8,
+    value: Zeroizing<Vec<u8>>,
+}
+
+impl Share {
+    /// Parses a mnemonic share, checking its words, padding and checksum
+    pub fn parse(mnemonic: &str) -> Result<Self> {
+        Ok(Self::decode(mnemonic)?)
+    }
+
+    /// Random identifier shared by every share of one backup
+    pub fn identifier(&self) -> u16 {
+        self.identifier
+    }
+
+    /// Group this share belongs to (0-based)
+    pub fn group_index(&self) -> u8 {
+        self.group_index
+    }
+
+    /// Groups needed to recover the secret
+    pub fn group_threshold(&self) -> u8 {
+        self.group_threshold
+    }
+
+    /// Groups in the backup
+    pub fn group_count(&self) -> u8 {
+        self.group_count
+    }
+
+    /// Index of this share within its group (0-based)
+    pub fn member_index(&self) -> u8 {
+        self.member_index
+    }
+
+    /// Shares of this group needed to recover the group
+    pub fn member_threshold(&self) -> u8 {
+        self.member_threshold
+    }
+
+    fn decode(mnemonic: &str) -> std::result::Result<Self, Slip39Error> {
+        let invalid = |reason: &str| Slip39Error::InvalidShare(reason.to_string());
+        let words = mnemonic
+            .split_whitespace()
+            .map(|word| {
+                let word = word.to_lowercase();
+                WORDS
+                    .binary_search(&word.as_str())
+                    .map(|i| i as u16)
+                    .map_err(|_| Slip39Error::InvalidShare(format!("unknown word {:?}", word)))
+            })
+            .collect::<std::result::Result<Vec<u16>, _>>()?;
+        if words.len() < MIN_MNEMONIC_WORDS {
+            return Err(invalid("too few words"));
+        }
+        let value_words = &words[4..words.len() - CHECKSUM_WORDS];
+        let padding = (RADIX_BITS * (words.len() - METADATA_WORDS)) % 16;
+        if padding > 8 {
+            return Err(invalid("invalid length"));
+        }
+
+        let id_exp = (words[0] as u32) << 10 | words[1] as u32;
+        let identifier = (id_exp >> 5) as u16;
+        let extendable = (id_exp >> 4) & 1 == 1;
+        let iteration_exponent = (id_exp & 0xF) as u8;
+        if rs1024::polymod(customization(extendable), &words) != 1 {
+            return Err(invalid("checksum mismatch"));
+        }
+
+        let params = (words[2] as u32) << 10 | words[3] as u32;
+        let nibble = |shift: u32| ((params >> shift) & 0xF) as u8;
+        let (group_threshold, group_count) = (nibble(12) + 1, nibble(8) + 1);
+        if group_threshold > group_count {
+            return Err(invalid("group threshold is above the group count"));
+        }
+
+        Ok(Self {
+            identifier,
+            extendable,
+            iteration_exponent,
+            group_index: nibble(16),
+            group_threshold,
+            group_count,
+            member_index: nibble(4),
+            member_threshold: nibble(0) + 1,
+            value: unpack(value_words, padding).ok_or_else(|| invalid("invalid padding"))?,
+        })
+    }
+
+    fn to_mnemonic(&self) -> Zeroizing<String> {
+        let id_exp = (self.identifier as u32) << 5
+            | (self.extendable as u32) << 4
+            | self.iteration_exponent as u32;
+        let params = (self.group_index as u32) << 16
+            | ((self.group_threshold - 1) as u32) << 12
+            | ((self.group_count - 1) as u32) << 8
+            | (self.member_index as u32) << 4
+            | (self.member_threshold - 1) as u32;
+
+        let mut words = Zeroizing::new(vec![
+            (id_exp >> 10) as u16,
+            (id_exp & 1023) as u16,
+            (params >> 10) as u16,
+            (params & 1023) as u16,
+        ]);
+        words.extend(pack(&self.value).iter());
+        let checksum = rs1024::polymod(
+            customization(self.extendable),
+            &[&words[..], &[0u16; CHECKSUM_WORDS][..]].concat(),
+        ) ^ 1;
+        words.extend(
+            (0..CHECKSUM_WORDS)
+                .rev()
+                .map(|i| ((checksum >> (10 * i)) & 1023) as u16),
+        );
+
+        Zeroizing::new(
+            words
+                .iter()
+                .map(|&w| WORDS[w as usize])
+                .collect::<Vec<_>>()
+                .join(" "),
+        )
+    }
+}
+
+impl std::fmt::Debug for Share {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Share")
+            .field("identifier", &self.identifier)
+            .field(
+                "group",
+                &format!(
+                    "{} ({} of {})",
+                    self.group_index, self.group_threshold, self.group_count
+                ),
+            )
+            .field(
+                "member",
+                &format!("{} ({} needed)", self.member_index, self.member_threshold),
+            )
+            .finish_non_exhaustive()
+    }
+}
+
+/// Recovers the master secret from mnemonic shares
+///
+/// Pass at least `member_threshold` shares from each of at least
+/// `group_threshold` groups; extra shares and incomplete groups are ignored.
+pub fn combine<S: AsRef<str>>(mnemonics: &[S], passphrase: &str) -> Result<Zeroizing<Vec<u8>>> {
+    let shares = mnemonics
+        .iter()
+        .map(|m| Share::decode(m.as_ref()))
+        .collect::<std::result::Result<Vec<_>, _>>()?;
+    Ok(combine_shares(&shares, passphrase)?)
+}
+
+fn combine_shares(
+    shares: &[Share],
+    passphrase: &str,
+) -> std::result::Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    let first = shares
+        .first()
+        .ok_or_else(|| Slip39Error::InsufficientShares("no shares given".to_string()))?;
+    let mismatch = |reason: &str| Err(Slip39Error::InvalidShares(reason.to_string()));
+    for share in shares {
+        if (share.identifier, share.extendable, share.iteration_exponent)
+            != (first.identifier, first.extendable, first.iteration_exponent)
+        {
+            return mismatch("shares come from different backups");
+        }
+        if (share.group_threshold, share.group_count) != (first.group_threshold, first.group_count)
+        {
+            return mismatch("group parameters differ");
+        }
+        if share.value.len() != first.value.len() {
+            return mismatch("shares have different lengths");
+        }
+    }
+
+    let mut groups: BTreeMap<u8, Vec<&Share>> = BTreeMap::new();
+    for share in shares {
+        let group = groups.entry(share.group_index).or_default();
+        if let Some(other) = group.first() {
+            if other.member_threshold != share.member_threshold {
+                return mismatch("member thresholds differ within a group");
+            }
+        }
+        if group
+            .iter()
+            .any(|other| other.member_index == share.member_index)
+        {
+            return mismatch("duplicate share in a group");
+        }
+        group.push(share);
+    }
+
+    let complete: Vec<(u8, &[&Share])> = groups
+        .iter()
+        .filter(|(_, members)| members.len() >= members[0].member_threshold as usize)
+        .map(|(index, members)| (*index, &members[..members[0].member_threshold as usize]))
+        .take(first.group_threshold as usize)
+        .collect();
+    if complete.len() < first.group_threshold as usize {
+        return Err(Slip39Error::InsufficientShares(format!(
+            "{} complete groups of {} needed",
+            complete.len(),
+            first.group_threshold
+        )));
+    }
+
+    let mut group_secrets = Vec::with_capacity(complete.len());
+    for (index, members) in &complete {
+        let points: Vec<(u8, &[u8])> = members
+            .iter()
+            .map(|s| (s.member_index, s.value.as_slice()))
+            .collect();
+        group_secrets.push((
+            *index,
+            shamir::recover(members[0].member_threshold, &points)?,
+        ));
+    }
+    let points: Vec<(u8, &[u8])> = group_secrets
+        .iter()
+        .map(|(i, v)| (*i, v.as_slice()))
+        .collect();
+    let encrypted = shamir::recover(first.group_threshold, &points)?;
+
+    Ok(cipher::decrypt(
+        &encrypted,
+        passphrase.as_bytes(),
+        first.iteration_exponent,
+        first.identifier,
+        first.extendable,
+    ))
+}
+
+fn customization(extendable: bool) -> &'static [u8] {
+    if extendable {
+        b"shamir_extendable"
+    } else {
+        b"shamir"
+    }
+}
+
+/// Packs bytes into 10-bit words, zero-padding at the front
+fn pack(value: &[u8]) -> Zeroizing<Vec<u16>> {
+    let mut words = Zeroizing::new(Vec::with_capacity((value.len() * 8).div_ceil(RADIX_BITS)));
+    let mut acc: u32 = 0;
+    let mut bits = (RADIX_BITS - value.len() * 8 % RADIX_BITS) % RADIX_BITS;
+    for &byte in value {
+        acc = acc << 8 | byte as u32;
+        bits += 8;
+        while bits >= RADIX_BITS {
+            bits -= RADIX_BITS;
+            words.push(((acc >> bits) & 1023) as u16);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    words
+}
+
+/// Reverses [`pack`]; `None` if the padding bits are not zero
+fn unpack(words: &[u16], padding: usize) -> Option<Zeroizing<Vec<u8>>> {
+    let mut out = Zeroizing::new(Vec::with_capacity((words.len() * RADIX_BITS - padding) / 8));
+    let mut acc: u32 = 0;
+    let mut bits = 0;
+    for (i, &word) in words.iter().enumerate() {
+        acc = acc << RADIX_BITS | word as u32;
+        bits += RADIX_BITS;
+        if i == 0 {
+            if word >> (RADIX_BITS - padding) != 0 {
+                return None;
+            }
+            bits -= padding;
+        }
+        while bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+        }
+        acc &= (1 << bits) - 1;
+    }
+    Some(out)
+}
+
+/// Reed-Solomon checksum over GF(1024)
+mod rs1024 {
+    const GEN: [u32; 10] = [
+        0x00E0_E040,
+        0x01C1_C080,
+        0x0383_8100,
+        0x0707_0200,
+        0x0E0E_0009,
+        0x1C0C_2412,
+        0x3808_6C24,
+        0x3090_FC48,
+        0x21B1_F890,
+        0x03F3_F120,
+    ];
+
+    pub(super) fn polymod(customization: &[u8], words: &[u16]) -> u32 {
+        let values = customization
+            .iter()
+            .map(|&b| b as u32)
+            .chain(words.iter().map(|&w| w as u32));
+        let mut chk: u32 = 1;
+        for value in values {
+            let b = chk >> 20;
+            chk = (chk & 0xFFFFF) << 10 ^ value;
+            for (i, gen) in GEN.iter().enumerate() {
+                if (b >> i) & 1 == 1 {
+                    chk ^= gen;
+                }
+            }
+        }
+        chk
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn as_strs(shares: &[Zeroizing<String>]) -> Vec<&str> {
+        shares.iter().map(|s| s.as_str()).collect()
+    }
+
+    #[test]
+    fn test_pack_roundtrip() {
+        for len in [16usize, 18, 32, 64] {
+            let value: Vec<u8> = (0..len as u8).map(|b| b.wrapping_mul(37)).collect();
+            let words = pack(&value);
+            let padding = (RADIX_BITS * words.len()) % 16;
+            assert_eq!(*unpack(&words, padding).unwrap(), value);
+        }
+    }
+
+    #[test]
+    fn test_reference_vector() {
+        // Trezor vector 4: 2-of-3, passphrase "TREZOR"
+        let shares = [
+            "shadow pistol academic always adequate wildlife fancy gross oasis cylinder mustang wrist rescue view short owner flip making coding armed",
+            "shadow pistol academic acid actress prayer class unknown daughter sweater depict flip twice unkind craft early superior advocate guest smoking",
+        ];
+        assert_eq!(
+            hex::encode(&*combine(&shares, "TREZOR").unwrap()),
+            "b43ceb7e57a0ea8766221624d01b0864"
+        );
+        let share = Share::parse(shares[0]).unwrap();
+        assert_eq!((share.group_threshold(), share.member_threshold()), (1, 2));
+        assert!(combine(&shares[..1], "TREZOR").is_err());
+    }
+
+    #[test]
+    fn test_two_level_roundtrip() {
+        let secret: Vec<u8> = (0..32).collect();
+        let groups = Slip39::new(2, &[(1, 1), (2, 3), (3, 5)])
+            .with_iteration_exponent(0)
+            .split(&secret, "hunter2")
+            .unwrap();
+        assert_eq!(
+            groups.iter().map(Vec::len).collect::<Vec<_>>(),
+            vec![1, 3, 5]
+        );
+        assert_eq!(groups[0][0].split(' ').count(), 33);
+
+        let mut chosen = as_strs(&groups[0]);
+        chosen.extend(as_strs(&groups[2][1..4]));
+        assert_eq!(*combine(&chosen, "hunter2").unwrap(), secret);
+
+        // Incomplete groups are skipped; one complete group is not enough
+        let mut short = as_strs(&groups[1][..1]);
+        short.extend(as_strs(&groups[2][..3]));
+        assert!(matches!(
+            combine(&short, "hunter2"),
+            Err(crate::HdError::Slip39(Slip39Error::InsufficientShares(_)))
+        ));
+
+        // The wrong passphrase decrypts to a different secret
+        assert_ne!(*combine(&chosen, "").unwrap(), secret);
+    }
+
+    #[test]
+    fn test_non_extendable_roundtrip() {
+        let secret = [0xA5u8; 16];
+        let groups = Slip39::single(2, 2)
+            .with_extendable(false)
+            .with_iteration_exponent(0)
+            .split(&secret, "")
+            .unwrap();
+        assert_eq!(*combine(&as_strs(&groups[0]), "").unwrap(), secret.to_vec());
+    }
+
+    #[test]
+    fn test_invalid_schemes() {
+        let secret = [1u8; 16];
+        assert!(Slip39::single(1, 3).split(&secret, "").is_err());
+        assert!(Slip39::new(3, &[(1, 1), (1, 1)])
+            .split(&secret, "")
+            .is_err());
+        assert!(Slip39::single(2, 17).split(&secret, "").is_err());
+        assert!(Slip39::single(2, 3).split(&secret[..15], "").is_err());
+        assert!(Slip39::single(2, 3).split(&secret, "pässword").is_err());
+    }
+
+    #[test]
+    fn test_typo_is_caught() {
+        let groups = Slip39::single(1, 1)
+            .with_iteration_exponent(0)
+            .split(&[9u8; 16], "")
+            .unwrap();
+        let mut words: Vec<&str> = groups[0][0].split(' ').collect();
+        words[5] = if words[5] == "academic" {
+            "acid"
+        } else {
+            "academic"
+        };
+        assert!(Share::parse(&words.join(" ")).is_err());
+    }
+}
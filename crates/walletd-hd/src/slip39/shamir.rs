@@ -0,0 +1,193 @@
+//! Shamir secret sharing over GF(256)
+//!
+//! Field arithmetic uses the Rijndael polynomial `x^8 + x^4 + x^3 + x + 1`
+//! with 3 as generator. A split secret is the polynomial's value at
+//! [`SECRET_INDEX`]; the value at [`DIGEST_INDEX`] holds a digest that lets
+//! recovery detect wrong or mixed shares.
+
+use super::Slip39Error;
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sha2::Sha256;
+use zeroize::Zeroizing;
+
+/// x-coordinate of the shared secret
+const SECRET_INDEX: u8 = 255;
+/// x-coordinate of the digest share
+const DIGEST_INDEX: u8 = 254;
+const DIGEST_LENGTH: usize = 4;
+
+/// Most shares in one split
+pub const MAX_SHARES: u8 = 16;
+
+/// A share's x-coordinate and value
+pub(super) type Point = (u8, Zeroizing<Vec<u8>>);
+
+struct Tables {
+    exp: [u8; 255],
+    log: [u8; 256],
+}
+
+const TABLES: Tables = tables();
+
+const fn tables() -> Tables {
+    let mut exp = [0u8; 255];
+    let mut log = [0u8; 256];
+    let mut poly: u16 = 1;
+    let mut i = 0;
+    while i < 255 {
+        exp[i] = poly as u8;
+        log[poly as usize] = i as u8;
+        // Multiply by the generator 3, reducing modulo the field polynomial
+        poly = (poly << 1) ^ poly;
+        if poly & 0x100 != 0 {
+            poly ^= 0x11B;
+        }
+        i += 1;
+    }
+    Tables { exp, log }
+}
+
+fn log(x: u8) -> usize {
+    TABLES.log[x as usize] as usize
+}
+
+/// Evaluates the polynomial through `shares` at `x`
+pub(super) fn interpolate(
+    shares: &[(u8, &[u8])],
+    x: u8,
+) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    for (i, (xi, value)) in shares.iter().enumerate() {
+        if shares[..i].iter().any(|(xj, _)| xj == xi) {
+            return Err(Slip39Error::InvalidShares(
+                "share indexes must be unique".to_string(),
+            ));
+        }
+        if value.len() != shares[0].1.len() {
+            return Err(Slip39Error::InvalidShares(
+                "share values must have the same length".to_string(),
+            ));
+        }
+    }
+    if let Some((_, value)) = shares.iter().find(|(xi, _)| *xi == x) {
+        return Ok(Zeroizing::new(value.to_vec()));
+    }
+
+    // Lagrange basis polynomials evaluated in log space; log(0) is taken as
+    // 0 so the i == j term drops out of the sum
+    let log_prod: usize = shares.iter().map(|(xi, _)| log(xi ^ x)).sum();
+    let mut result = Zeroizing::new(vec![0u8; shares[0].1.len()]);
+    for (xi, value) in shares {
+        let denominator: usize = shares.iter().map(|(xj, _)| log(xi ^ xj)).sum();
+        let log_basis = (log_prod + 255 * shares.len() - log(xi ^ x) - denominator) % 255;
+        for (out, &byte) in result.iter_mut().zip(value.iter()) {
+            if byte != 0 {
+                *out ^= TABLES.exp[(log(byte) + log_basis) % 255];
+            }
+        }
+    }
+    Ok(result)
+}
+
+/// Splits `secret` into `count` shares, any `threshold` of which recover it
+pub(super) fn split(threshold: u8, count: u8, secret: &[u8]) -> Result<Vec<Point>, Slip39Error> {
+    if threshold == 0 || threshold > count {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "threshold {} must be between 1 and the share count {}",
+            threshold, count
+        )));
+    }
+    if count > MAX_SHARES {
+        return Err(Slip39Error::InvalidScheme(format!(
+            "at most {} shares",
+            MAX_SHARES
+        )));
+    }
+    if threshold == 1 {
+        return Ok((0..count)
+            .map(|i| (i, Zeroizing::new(secret.to_vec())))
+            .collect());
+    }
+
+    let mut rng = rand::thread_rng();
+    let mut shares: Vec<Point> = (0..threshold - 2)
+        .map(|i| {
+            let mut value = Zeroizing::new(vec![0u8; secret.len()]);
+            rng.fill_bytes(&mut value);
+            (i, value)
+        })
+        .collect();
+
+    let mut digest_share = Zeroizing::new(vec![0u8; secret.len()]);
+    rng.fill_bytes(&mut digest_share[DIGEST_LENGTH..]);
+    let digest = digest(&digest_share[DIGEST_LENGTH..], secret);
+    digest_share[..DIGEST_LENGTH].copy_from_slice(&digest);
+
+    let mut base: Vec<(u8, &[u8])> = shares.iter().map(|(x, v)| (*x, v.as_slice())).collect();
+    base.push((DIGEST_INDEX, &digest_share));
+    base.push((SECRET_INDEX, secret));
+    let rest = (threshold - 2..count)
+        .map(|i| Ok((i, interpolate(&base, i)?)))
+        .collect::<Result<Vec<_>, Slip39Error>>()?;
+    shares.extend(rest);
+    Ok(shares)
+}
+
+/// Recovers the secret from `threshold` shares and checks its digest
+pub(super) fn recover(
+    threshold: u8,
+    shares: &[(u8, &[u8])],
+) -> Result<Zeroizing<Vec<u8>>, Slip39Error> {
+    if threshold == 1 {
+        return Ok(Zeroizing::new(shares[0].1.to_vec()));
+    }
+    let secret = interpolate(shares, SECRET_INDEX)?;
+    let digest_share = interpolate(shares, DIGEST_INDEX)?;
+    if digest_share[..DIGEST_LENGTH] != digest(&digest_share[DIGEST_LENGTH..], &secret) {
+        return Err(Slip39Error::InvalidDigest);
+    }
+    Ok(secret)
+}
+
+fn digest(random: &[u8], secret: &[u8]) -> [u8; DIGEST_LENGTH] {
+    let mut mac = Hmac::<Sha256>::new_from_slice(random).expect("HMAC takes any key length");
+    mac.update(secret);
+    let mut out = [0u8; DIGEST_LENGTH];
+    out.copy_from_slice(&mac.finalize().into_bytes()[..DIGEST_LENGTH]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tables() {
+        assert_eq!(TABLES.exp[0], 1);
+        assert_eq!(TABLES.exp[1], 3);
+        assert_eq!(TABLES.exp[254], 0xF6);
+        for x in 1..=255u8 {
+            assert_eq!(TABLES.exp[log(x)], x);
+        }
+    }
+
+    #[test]
+    fn test_split_recover_any_subset() {
+        let secret = [0x42u8; 16];
+        let shares = split(3, 5, &secret).unwrap();
+        for subset in [[0, 1, 2], [0, 3, 4], [4, 2, 1]] {
+            let chosen: Vec<(u8, &[u8])> = subset
+                .iter()
+                .map(|&i| (shares[i].0, shares[i].1.as_slice()))
+                .collect();
+            assert_eq!(*recover(3, &chosen).unwrap(), secret.to_vec());
+        }
+
+        // Two shares of a 3-of-5 give a wrong secret, caught by the digest
+        let chosen: Vec<(u8, &[u8])> = shares[..2]
+            .iter()
+            .map(|(x, v)| (*x, v.as_slice()))
+            .collect();
+        assert!(recover(2, &chosen).is_err());
+    }
+}
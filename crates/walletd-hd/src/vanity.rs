@@ -0,0 +1,447 @@
+//! Vanity address generation
+//!
+//! Grinds random keys on every core until one's address matches a prefix
+//! and/or suffix. Vanity keys are random, not derived from a mnemonic:
+//! back the winning key up on its own, e.g. in a `walletd-keystore` file.
+//!
+//! Each extra character multiplies the expected work by the alphabet size
+//! (16 for EVM hex, 58 for base58), so check
+//! [`VanityGenerator::expected_attempts`] before starting a long search.
+
+use crate::address;
+use crate::chain::{Curve, HdChain};
+use crate::{HdError, Result};
+use rand::RngCore;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+use walletd_traits::{Ed25519Signer, Secp256k1Signer, Signer};
+use zeroize::Zeroizing;
+
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Attempts a worker makes between updates of the shared counter
+const BATCH: u64 = 256;
+
+/// Stops a running search from another thread
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// A token that has not been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops every search using this token
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether [`cancel`](Self::cancel) was called
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Progress of a running search
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VanityProgress {
+    /// Keys tried so far
+    pub attempts: u64,
+    /// Time since the search started
+    pub elapsed: Duration,
+    /// Keys tried per second
+    pub rate: f64,
+    /// Keys a search needs on average
+    pub expected_attempts: f64,
+}
+
+impl VanityProgress {
+    /// Chance that a match would have been found by now
+    pub fn probability(&self) -> f64 {
+        1.0 - (-(self.attempts as f64) / self.expected_attempts).exp()
+    }
+}
+
+/// The winning key of a search
+///
+/// The secret is zeroized on drop and never printed by `Debug`.
+pub struct VanityKey {
+    chain: HdChain,
+    address: String,
+    secret: Zeroizing<[u8; 32]>,
+    attempts: u64,
+}
+
+impl VanityKey {
+    /// Chain the address is for
+    pub fn chain(&self) -> HdChain {
+        self.chain
+    }
+
+    /// The matching address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Keys tried across all workers
+    pub fn attempts(&self) -> u64 {
+        self.attempts
+    }
+
+    /// Raw 32-byte secret key, for storing in a keystore
+    pub fn secret_bytes(&self) -> &[u8; 32] {
+        &self.secret
+    }
+
+    /// Signer holding the key, for a wallet's `from_signer` constructor
+    pub fn signer(&self) -> Result<Box<dyn Signer>> {
+        Ok(match self.chain.curve() {
+            Curve::Secp256k1 => Box::new(
+                Secp256k1Signer::from_slice(&self.secret[..])
+                    .map_err(|e| HdError::Derivation(e.to_string()))?,
+            ),
+            Curve::Ed25519 => Box::new(Ed25519Signer::from_bytes(&self.secret)),
+        })
+    }
+}
+
+impl fmt::Debug for VanityKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VanityKey")
+            .field("chain", &self.chain)
+            .field("address", &self.address)
+            .field("attempts", &self.attempts)
+            .finish_non_exhaustive()
+    }
+}
+
+type ProgressFn = Arc<dyn Fn(&VanityProgress) + Send + Sync>;
+
+/// Address and secret of the first match
+type Winner = Mutex<Option<(String, Zeroizing<[u8; 32]>)>>;
+
+/// Searches for a key whose address matches a pattern
+///
+/// Supports EVM chains (hex after `0x`), Tron and Solana (base58). Patterns
+/// are matched against the address as displayed: Tron prefixes start with
+/// `T`. Matching ignores case on EVM chains unless
+/// [`case_sensitive`](Self::case_sensitive) asks for the EIP-55 checksum
+/// form, and is case-sensitive on base58 chains.
+pub struct VanityGenerator {
+    chain: HdChain,
+    prefix: String,
+    suffix: String,
+    case_sensitive: bool,
+    threads: usize,
+    max_attempts: Option<u64>,
+    cancel: CancelToken,
+    progress: Option<(Duration, ProgressFn)>,
+}
+
+impl fmt::Debug for VanityGenerator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VanityGenerator")
+            .field("chain", &self.chain)
+            .field("prefix", &self.prefix)
+            .field("suffix", &self.suffix)
+            .field("case_sensitive", &self.case_sensitive)
+            .field("threads", &self.threads)
+            .field("max_attempts", &self.max_attempts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl VanityGenerator {
+    /// A search on `chain` using every available core
+    pub fn new(chain: HdChain) -> Result<Self> {
+        if !chain.is_evm() && !matches!(chain, HdChain::Tron | HdChain::Solana) {
+            return Err(HdError::UnsupportedChain(format!(
+                "vanity addresses on {}",
+                chain
+            )));
+        }
+        Ok(Self {
+            chain,
+            prefix: String::new(),
+            suffix: String::new(),
+            case_sensitive: !chain.is_evm(),
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            max_attempts: None,
+            cancel: CancelToken::new(),
+            progress: None,
+        })
+    }
+
+    /// Requires the address to start with `prefix` (after `0x` on EVM chains)
+    pub fn prefix(mut self, prefix: impl Into<String>) -> Result<Self> {
+        let mut prefix = prefix.into();
+        if let Some(hex) = prefix.strip_prefix("0x").filter(|_| self.chain.is_evm()) {
+            prefix = hex.to_string();
+        }
+        self.check_pattern(&prefix)?;
+        if self.chain == HdChain::Tron && !prefix.is_empty() && !prefix.starts_with('T') {
+            return Err(HdError::InvalidPattern(format!(
+                "Tron addresses start with T, not {}",
+                prefix
+            )));
+        }
+        self.prefix = prefix;
+        Ok(self)
+    }
+
+    /// Requires the address to end with `suffix`
+    pub fn suffix(mut self, suffix: impl Into<String>) -> Result<Self> {
+        let suffix = suffix.into();
+        self.check_pattern(&suffix)?;
+        self.suffix = suffix;
+        Ok(self)
+    }
+
+    /// Matches letter case too; on EVM chains, against the EIP-55 checksum
+    pub fn case_sensitive(mut self, case_sensitive: bool) -> Self {
+        self.case_sensitive = case_sensitive;
+        self
+    }
+
+    /// Sets the number of worker threads
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Gives up after `attempts` keys
+    pub fn max_attempts(mut self, attempts: u64) -> Self {
+        self.max_attempts = Some(attempts);
+        self
+    }
+
+    /// Stops the search when `token` is cancelled
+    pub fn cancel_token(mut self, token: CancelToken) -> Self {
+        self.cancel = token;
+        self
+    }
+
+    /// Calls `report` every `interval` while searching
+    pub fn on_progress(
+        mut self,
+        interval: Duration,
+        report: impl Fn(&VanityProgress) + Send + Sync + 'static,
+    ) -> Self {
+        self.progress = Some((interval, Arc::new(report)));
+        self
+    }
+
+    /// Keys a search needs on average
+    pub fn expected_attempts(&self) -> f64 {
+        let pattern = format!("{}{}", self.prefix, self.suffix);
+        let (alphabet, pattern) = match self.chain {
+            // Every Tron address starts with T
+            HdChain::Tron => (58.0, pattern.get(1..).unwrap_or_default().to_string()),
+            _ if self.chain.is_evm() => (16.0, pattern),
+            _ => (58.0, pattern),
+        };
+        let mut expected = f64::powi(alphabet, pattern.len() as i32);
+        if self.chain.is_evm() && self.case_sensitive {
+            // Each letter's case is a coin flip in the checksum
+            expected *= f64::powi(
+                2.0,
+                pattern.chars().filter(char::is_ascii_alphabetic).count() as i32,
+            );
+        }
+        expected
+    }
+
+    /// Runs the search on the configured threads, blocking until a key
+    /// matches or the search is cancelled or out of attempts
+    pub fn generate(&self) -> Result<VanityKey> {
+        if self.prefix.is_empty() && self.suffix.is_empty() {
+            return Err(HdError::InvalidPattern("empty pattern".into()));
+        }
+        let attempts = AtomicU64::new(0);
+        let done = AtomicBool::new(false);
+        let winner: Winner = Mutex::new(None);
+        let started = Instant::now();
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads {
+                scope.spawn(|| self.work(&attempts, &done, &winner));
+            }
+            let (interval, report) = match &self.progress {
+                Some((interval, report)) => (*interval, Some(report)),
+                None => (Duration::from_millis(50), None),
+            };
+            let mut last_report = Instant::now();
+            while !done.load(Ordering::SeqCst) {
+                if self.cancel.is_cancelled()
+                    || self
+                        .max_attempts
+                        .is_some_and(|max| attempts.load(Ordering::Relaxed) >= max)
+                {
+                    done.store(true, Ordering::SeqCst);
+                    break;
+                }
+                thread::sleep(interval.min(Duration::from_millis(50)));
+                if let Some(report) = report.filter(|_| last_report.elapsed() >= interval) {
+                    last_report = Instant::now();
+                    let elapsed = started.elapsed();
+                    let tried = attempts.load(Ordering::Relaxed);
+                    report(&VanityProgress {
+                        attempts: tried,
+                        elapsed,
+                        rate: tried as f64 / elapsed.as_secs_f64().max(f64::EPSILON),
+                        expected_attempts: self.expected_attempts(),
+                    });
+                }
+            }
+        });
+
+        let attempts = attempts.load(Ordering::SeqCst);
+        let winner = winner.into_inner().unwrap_or_else(|e| e.into_inner());
+        match winner {
+            Some((address, secret)) => Ok(VanityKey {
+                chain: self.chain,
+                address,
+                secret,
+                attempts,
+            }),
+            None => Err(HdError::Cancelled(attempts)),
+        }
+    }
+
+    fn work(&self, attempts: &AtomicU64, done: &AtomicBool, winner: &Winner) {
+        let secp = secp256k1::Secp256k1::signing_only();
+        let mut rng = rand::thread_rng();
+        let mut secret = Zeroizing::new([0u8; 32]);
+        while !done.load(Ordering::Relaxed) {
+            for _ in 0..BATCH {
+                rng.fill_bytes(secret.as_mut());
+                let public_key = match self.chain.curve() {
+                    Curve::Secp256k1 => match secp256k1::SecretKey::from_slice(&secret[..]) {
+                        Ok(key) => key.public_key(&secp).serialize().to_vec(),
+                        Err(_) => continue,
+                    },
+                    Curve::Ed25519 => Ed25519Signer::from_bytes(&secret).public_key(),
+                };
+                let Ok(address) = address::encode(self.chain, &public_key) else {
+                    continue;
+                };
+                if self.matches(&address) {
+                    let mut winner = winner.lock().unwrap_or_else(|e| e.into_inner());
+                    if winner.is_none() {
+                        *winner = Some((address, secret.clone()));
+                    }
+                    done.store(true, Ordering::SeqCst);
+                    break;
+                }
+            }
+            attempts.fetch_add(BATCH, Ordering::Relaxed);
+        }
+    }
+
+    fn matches(&self, address: &str) -> bool {
+        let address = address.strip_prefix("0x").unwrap_or(address);
+        if self.case_sensitive {
+            address.starts_with(&self.prefix) && address.ends_with(&self.suffix)
+        } else {
+            let address = address.to_ascii_lowercase();
+            address.starts_with(&self.prefix.to_ascii_lowercase())
+                && address.ends_with(&self.suffix.to_ascii_lowercase())
+        }
+    }
+
+    fn check_pattern(&self, pattern: &str) -> Result<()> {
+        let invalid = if self.chain.is_evm() {
+            pattern.chars().find(|c| !c.is_ascii_hexdigit())
+        } else {
+            pattern.chars().find(|c| !BASE58_ALPHABET.contains(*c))
+        };
+        match invalid {
+            Some(c) => Err(HdError::InvalidPattern(format!(
+                "{:?} cannot appear in a {} address",
+                c, self.chain
+            ))),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evm_and_solana_match() {
+        let key = VanityGenerator::new(HdChain::Ethereum)
+            .unwrap()
+            .prefix("a")
+            .unwrap()
+            .suffix("B")
+            .unwrap()
+            .threads(2)
+            .generate()
+            .unwrap();
+        let lower = key.address().to_ascii_lowercase();
+        assert!(lower.starts_with("0xa") && lower.ends_with('b'));
+        let signer = key.signer().unwrap();
+        assert_eq!(
+            address::encode(HdChain::Ethereum, &signer.public_key()).unwrap(),
+            key.address()
+        );
+        assert!(key.attempts() > 0);
+        assert!(!format!("{:?}", key).contains(&hex::encode(key.secret_bytes())));
+
+        let key = VanityGenerator::new(HdChain::Solana)
+            .unwrap()
+            .prefix("S")
+            .unwrap()
+            .generate()
+            .unwrap();
+        assert!(key.address().starts_with('S'));
+        assert_eq!(
+            bs58::encode(key.signer().unwrap().public_key()).into_string(),
+            key.address()
+        );
+    }
+
+    #[test]
+    fn test_invalid_patterns_and_cancel() {
+        let evm = VanityGenerator::new(HdChain::Base).unwrap();
+        assert!(matches!(evm.prefix("0xg"), Err(HdError::InvalidPattern(_))));
+        let sol = VanityGenerator::new(HdChain::Solana).unwrap();
+        assert!(matches!(sol.suffix("0"), Err(HdError::InvalidPattern(_))));
+        let tron = VanityGenerator::new(HdChain::Tron).unwrap();
+        assert!(tron.prefix("Abc").is_err());
+        assert!(matches!(
+            VanityGenerator::new(HdChain::Bitcoin),
+            Err(HdError::UnsupportedChain(_))
+        ));
+
+        let tron = VanityGenerator::new(HdChain::Tron)
+            .unwrap()
+            .prefix("TWa11et")
+            .unwrap();
+        assert_eq!(tron.expected_attempts(), f64::powi(58.0, 6));
+
+        let token = CancelToken::new();
+        let reports = Arc::new(AtomicU64::new(0));
+        let seen = reports.clone();
+        let search = tron.cancel_token(token.clone()).on_progress(
+            Duration::from_millis(10),
+            move |progress| {
+                assert!(progress.probability() < 0.5);
+                seen.fetch_add(1, Ordering::SeqCst);
+            },
+        );
+        let canceller = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(100));
+            token.cancel();
+        });
+        assert!(matches!(search.generate(), Err(HdError::Cancelled(_))));
+        canceller.join().unwrap();
+        assert!(reports.load(Ordering::SeqCst) > 0);
+    }
+}
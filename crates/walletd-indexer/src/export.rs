@@ -0,0 +1,137 @@
+//! CSV and JSON export
+//!
+//! Exports ignore the query's limit and cursor and write every matching
+//! transaction, newest first.
+
+use crate::query::{direction_str, status_str, StoredTransaction, TxQuery};
+use crate::{Indexer, Result};
+use std::io::Write;
+
+/// CSV header row
+const CSV_HEADER: &str =
+    "chain,address,hash,direction,counterparty,amount,fee,status,timestamp,block_height";
+
+impl Indexer {
+    /// Writes matching transactions as CSV, returning the number of rows
+    ///
+    /// Amounts and fees are exact decimals in whole units, e.g. `1.5`.
+    pub fn export_csv<W: Write>(&self, query: &TxQuery, mut out: W) -> Result<usize> {
+        writeln!(out, "{}", CSV_HEADER)?;
+        let mut rows = 0;
+        self.for_each(&query.unpaged(), None, |stored, _| {
+            let record = &stored.record;
+            let fields = [
+                stored.chain,
+                stored.address,
+                record.hash.as_str().to_string(),
+                direction_str(record.direction).to_string(),
+                record.counterparty.clone().unwrap_or_default(),
+                record.amount.to_string(),
+                record.fee.map(|fee| fee.to_string()).unwrap_or_default(),
+                status_str(record.status).to_string(),
+                record.timestamp.map(|t| t.to_string()).unwrap_or_default(),
+                record
+                    .block_height
+                    .map(|h| h.to_string())
+                    .unwrap_or_default(),
+            ];
+            let line: Vec<String> = fields.iter().map(|field| escape(field)).collect();
+            writeln!(out, "{}", line.join(","))?;
+            rows += 1;
+            Ok(())
+        })?;
+        out.flush()?;
+        Ok(rows)
+    }
+
+    /// Writes matching transactions as a JSON array, returning the count
+    pub fn export_json<W: Write>(&self, query: &TxQuery, mut out: W) -> Result<usize> {
+        let mut items: Vec<StoredTransaction> = Vec::new();
+        self.for_each(&query.unpaged(), None, |stored, _| {
+            items.push(stored);
+            Ok(())
+        })?;
+        serde_json::to_writer_pretty(&mut out, &items)?;
+        out.flush()?;
+        Ok(items.len())
+    }
+}
+
+/// Quotes a CSV field if it contains a separator, quote or line break
+fn escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::{Amount, TransactionRecord, TransactionStatus, TxDirection, TxHash};
+
+    fn index() -> Indexer {
+        let index = Indexer::in_memory().unwrap();
+        let wallet = index.add_wallet("bitcoin", "bc1qxyz").unwrap();
+        index
+            .ingest(
+                wallet,
+                &[
+                    TransactionRecord {
+                        hash: TxHash::new("aa"),
+                        direction: TxDirection::Outgoing,
+                        counterparty: Some("label, with \"quotes\"".to_string()),
+                        amount: Amount::from_smallest_unit(150_000_000, 8),
+                        fee: Some(Amount::from_smallest_unit(1_410, 8)),
+                        status: TransactionStatus::Confirmed,
+                        timestamp: Some(1_700_000_000),
+                        block_height: Some(820_000),
+                    },
+                    TransactionRecord {
+                        hash: TxHash::new("bb"),
+                        direction: TxDirection::Incoming,
+                        counterparty: None,
+                        amount: Amount::from_smallest_unit(42, 8),
+                        fee: None,
+                        status: TransactionStatus::Pending,
+                        timestamp: None,
+                        block_height: None,
+                    },
+                ],
+            )
+            .unwrap();
+        index
+    }
+
+    #[test]
+    fn test_export_csv() {
+        let mut out = Vec::new();
+        let rows = index()
+            .export_csv(&TxQuery::new().limit(1), &mut out)
+            .unwrap();
+        assert_eq!(rows, 2);
+        let csv = String::from_utf8(out).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], CSV_HEADER);
+        assert_eq!(
+            lines[1],
+            "bitcoin,bc1qxyz,bb,incoming,,0.00000042,,pending,,"
+        );
+        assert_eq!(
+            lines[2],
+            "bitcoin,bc1qxyz,aa,outgoing,\"label, with \"\"quotes\"\"\",1.50000000,\
+             0.00001410,confirmed,1700000000,820000"
+        );
+    }
+
+    #[test]
+    fn test_export_json() {
+        let mut out = Vec::new();
+        let query = TxQuery::new().direction(TxDirection::Outgoing);
+        assert_eq!(index().export_json(&query, &mut out).unwrap(), 1);
+        let items: Vec<StoredTransaction> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(items[0].record.hash.as_str(), "aa");
+        assert_eq!(items[0].record.fee.unwrap().smallest_unit(), 1_410);
+    }
+}
@@ -0,0 +1,578 @@
+//! The index: wallets, ingestion and sync
+//!
+//! Transactions are keyed by wallet and hash, so ingesting the same record
+//! twice is harmless. A record that is seen again with a new status, block
+//! or fee updates the stored row; amount and direction never change.
+
+use crate::query::{direction_str, status_str, to_i64, TxQuery};
+use crate::{schema, IndexerError, Result};
+use async_trait::async_trait;
+use futures_util::StreamExt;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::path::Path;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walletd_traits::{
+    EventStream, HistoryPage, HistoryProvider, TransactionRecord, TxDirection, WalletError,
+    WalletEvent, WalletResult,
+};
+
+/// Identifies a wallet in the index
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct WalletId(pub i64);
+
+impl fmt::Display for WalletId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// A wallet tracked by the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WalletInfo {
+    /// Index id
+    pub id: WalletId,
+    /// Chain name, e.g. `ethereum`
+    pub chain: String,
+    /// Address as registered
+    pub address: String,
+    /// Last successful sync, Unix seconds
+    pub synced_at: Option<u64>,
+}
+
+/// How far [`Indexer::sync_with`] walks back
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SyncOptions {
+    /// Records requested per page
+    pub page_size: usize,
+    /// Stop after this many pages
+    pub max_pages: Option<usize>,
+    /// Keep going past pages that are already indexed
+    ///
+    /// Needed for the first sync after transactions were only partly
+    /// indexed, e.g. from events.
+    pub full: bool,
+}
+
+impl Default for SyncOptions {
+    fn default() -> Self {
+        Self {
+            page_size: 50,
+            max_pages: None,
+            full: false,
+        }
+    }
+}
+
+/// Outcome of a sync or ingest
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SyncReport {
+    /// New transactions
+    pub inserted: usize,
+    /// Known transactions whose status, block or fee changed
+    pub updated: usize,
+    /// Pages fetched from the source
+    pub pages: usize,
+}
+
+impl SyncReport {
+    /// Whether anything was written
+    pub fn changed(&self) -> bool {
+        self.inserted > 0 || self.updated > 0
+    }
+}
+
+/// SQLite-backed transaction history
+///
+/// All methods take `&self`; the connection is behind a mutex, so an
+/// `Indexer` can be shared between tasks in an `Arc`.
+#[derive(Debug)]
+pub struct Indexer {
+    conn: Mutex<Connection>,
+}
+
+impl Indexer {
+    /// Opens or creates an index at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::with_connection(Connection::open(path)?)
+    }
+
+    /// Creates a throwaway index in memory
+    pub fn in_memory() -> Result<Self> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut conn: Connection) -> Result<Self> {
+        schema::migrate(&mut conn)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    pub(crate) fn conn(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().expect("history index lock")
+    }
+
+    /// Starts tracking a wallet, returning its id
+    ///
+    /// Adding a wallet that is already tracked returns the existing id.
+    pub fn add_wallet(&self, chain: &str, address: &str) -> Result<WalletId> {
+        let conn = self.conn();
+        conn.execute(
+            "INSERT OR IGNORE INTO wallets (chain, address) VALUES (?1, ?2)",
+            params![chain, address],
+        )?;
+        Ok(WalletId(conn.query_row(
+            "SELECT id FROM wallets WHERE chain = ?1 AND address = ?2",
+            params![chain, address],
+            |row| row.get(0),
+        )?))
+    }
+
+    /// Looks up a tracked wallet by chain and address
+    pub fn find_wallet(&self, chain: &str, address: &str) -> Result<Option<WalletId>> {
+        Ok(self
+            .conn()
+            .query_row(
+                "SELECT id FROM wallets WHERE chain = ?1 AND address = ?2",
+                params![chain, address],
+                |row| row.get(0).map(WalletId),
+            )
+            .optional()?)
+    }
+
+    /// Returns a tracked wallet
+    pub fn wallet(&self, id: WalletId) -> Result<WalletInfo> {
+        self.conn()
+            .query_row(
+                "SELECT id, chain, address, synced_at FROM wallets WHERE id = ?1",
+                [id.0],
+                wallet_from_row,
+            )
+            .optional()?
+            .ok_or_else(|| IndexerError::WalletNotFound(id.to_string()))
+    }
+
+    /// Lists tracked wallets
+    pub fn wallets(&self) -> Result<Vec<WalletInfo>> {
+        let conn = self.conn();
+        let mut stmt =
+            conn.prepare("SELECT id, chain, address, synced_at FROM wallets ORDER BY id")?;
+        let wallets = stmt
+            .query_map([], wallet_from_row)?
+            .collect::<rusqlite::Result<_>>()?;
+        Ok(wallets)
+    }
+
+    /// Stops tracking a wallet and deletes its transactions
+    pub fn remove_wallet(&self, id: WalletId) -> Result<()> {
+        if self
+            .conn()
+            .execute("DELETE FROM wallets WHERE id = ?1", [id.0])?
+            == 0
+        {
+            return Err(IndexerError::WalletNotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Stores records for a wallet
+    ///
+    /// New records are inserted and known ones updated, all in one
+    /// database transaction.
+    pub fn ingest(&self, wallet: WalletId, records: &[TransactionRecord]) -> Result<SyncReport> {
+        let mut conn = self.conn();
+        let tx = conn.transaction()?;
+        let mut report = SyncReport::default();
+        {
+            let mut insert = tx.prepare_cached(
+                "INSERT OR IGNORE INTO transactions (wallet_id, hash, direction, counterparty,
+                     amount, decimals, fee, status, timestamp, block_height, first_seen)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)",
+            )?;
+            let mut update = tx.prepare_cached(
+                "UPDATE transactions
+                 SET status = ?3, timestamp = ?4, block_height = ?5,
+                     fee = COALESCE(?6, fee), counterparty = COALESCE(?7, counterparty)
+                 WHERE wallet_id = ?1 AND hash = ?2
+                   AND (status IS NOT ?3 OR timestamp IS NOT ?4 OR block_height IS NOT ?5
+                        OR (?6 IS NOT NULL AND fee IS NOT ?6)
+                        OR (?7 IS NOT NULL AND counterparty IS NOT ?7))",
+            )?;
+            let first_seen = to_i64(now());
+            for record in records {
+                let fee = record.fee.map(|fee| fee.smallest_unit().to_string());
+                let status = status_str(record.status);
+                let timestamp = record.timestamp.map(to_i64);
+                let block_height = record.block_height.map(to_i64);
+                let inserted = insert.execute(params![
+                    wallet.0,
+                    record.hash.as_str(),
+                    direction_str(record.direction),
+                    record.counterparty,
+                    record.amount.smallest_unit().to_string(),
+                    record.amount.decimals,
+                    fee,
+                    status,
+                    timestamp,
+                    block_height,
+                    first_seen,
+                ]);
+                match inserted {
+                    Ok(1) => report.inserted += 1,
+                    Ok(_) => {
+                        report.updated += update.execute(params![
+                            wallet.0,
+                            record.hash.as_str(),
+                            status,
+                            timestamp,
+                            block_height,
+                            fee,
+                            record.counterparty,
+                        ])?
+                    }
+                    Err(e) if is_foreign_key_violation(&e) => {
+                        return Err(IndexerError::WalletNotFound(wallet.to_string()))
+                    }
+                    Err(e) => return Err(e.into()),
+                }
+            }
+        }
+        tx.commit()?;
+        Ok(report)
+    }
+
+    /// Fetches new history for a wallet from `source`
+    ///
+    /// Walks back from the newest page and stops at the first page that is
+    /// already fully indexed.
+    pub async fn sync(&self, wallet: WalletId, source: &dyn HistoryProvider) -> Result<SyncReport> {
+        self.sync_with(wallet, source, SyncOptions::default()).await
+    }
+
+    /// Like [`sync`](Self::sync) with explicit paging options
+    pub async fn sync_with(
+        &self,
+        wallet: WalletId,
+        source: &dyn HistoryProvider,
+        options: SyncOptions,
+    ) -> Result<SyncReport> {
+        let address = self.wallet(wallet)?.address;
+        let mut report = SyncReport::default();
+        let mut cursor: Option<String> = None;
+        while options.max_pages.is_none_or(|max| report.pages < max) {
+            let page = source
+                .transactions(&address, cursor.as_deref(), options.page_size)
+                .await?;
+            report.pages += 1;
+            let written = self.ingest(wallet, &page.records)?;
+            report.inserted += written.inserted;
+            report.updated += written.updated;
+            if !options.full && !written.changed() {
+                break;
+            }
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        self.conn().execute(
+            "UPDATE wallets SET synced_at = ?2 WHERE id = ?1",
+            params![wallet.0, to_i64(now())],
+        )?;
+        tracing::debug!(
+            "Synced wallet {}: {} new, {} updated over {} pages",
+            wallet,
+            report.inserted,
+            report.updated,
+            report.pages
+        );
+        Ok(report)
+    }
+
+    /// Applies a live event to the index, returning whether anything changed
+    ///
+    /// Incoming transactions are stored as pending; confirmations and
+    /// failures update a stored transaction. Until the next sync brings the
+    /// block time, a confirmed transaction is dated when it was confirmed.
+    pub fn apply_event(&self, wallet: WalletId, event: &WalletEvent) -> Result<bool> {
+        match event {
+            WalletEvent::IncomingTransaction { hash, from, amount } => {
+                let report = self.ingest(
+                    wallet,
+                    &[TransactionRecord {
+                        hash: hash.clone(),
+                        direction: TxDirection::Incoming,
+                        counterparty: from.clone(),
+                        amount: *amount,
+                        fee: None,
+                        status: walletd_traits::TransactionStatus::Pending,
+                        timestamp: None,
+                        block_height: None,
+                    }],
+                )?;
+                Ok(report.inserted > 0)
+            }
+            WalletEvent::TransactionConfirmed { hash, block_height } => Ok(self.conn().execute(
+                "UPDATE transactions
+                 SET status = 'confirmed', block_height = COALESCE(?3, block_height),
+                     timestamp = COALESCE(timestamp, ?4)
+                 WHERE wallet_id = ?1 AND hash = ?2 AND status != 'confirmed'",
+                params![
+                    wallet.0,
+                    hash.as_str(),
+                    block_height.map(to_i64),
+                    to_i64(now())
+                ],
+            )? > 0),
+            WalletEvent::TransactionFailed { hash, .. } => Ok(self.conn().execute(
+                "UPDATE transactions SET status = 'failed'
+                 WHERE wallet_id = ?1 AND hash = ?2 AND status != 'failed'",
+                params![wallet.0, hash.as_str()],
+            )? > 0),
+            WalletEvent::BalanceChanged { .. } => Ok(false),
+        }
+    }
+
+    /// Applies events from a subscription until the stream ends
+    ///
+    /// Stream errors are logged and skipped. Returns the number of events
+    /// that changed the index.
+    pub async fn follow(&self, wallet: WalletId, mut events: EventStream) -> Result<usize> {
+        let mut applied = 0;
+        while let Some(event) = events.next().await {
+            match event {
+                Ok(event) => applied += usize::from(self.apply_event(wallet, &event)?),
+                Err(e) => tracing::warn!("Event stream error for wallet {}: {}", wallet, e),
+            }
+        }
+        Ok(applied)
+    }
+
+    /// Serves indexed history for `chain` through [`HistoryProvider`]
+    pub fn history(&self, chain: &str) -> LocalHistory<'_> {
+        LocalHistory {
+            index: self,
+            chain: chain.to_string(),
+        }
+    }
+}
+
+/// Indexed history of one chain as a [`HistoryProvider`]
+///
+/// Addresses that are not tracked have an empty history.
+#[derive(Debug)]
+pub struct LocalHistory<'a> {
+    index: &'a Indexer,
+    chain: String,
+}
+
+#[async_trait]
+impl HistoryProvider for LocalHistory<'_> {
+    async fn transactions(
+        &self,
+        address: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> WalletResult<HistoryPage> {
+        let to_wallet_error = |e: IndexerError| WalletError::Other(e.to_string());
+        let Some(wallet) = self
+            .index
+            .find_wallet(&self.chain, address)
+            .map_err(to_wallet_error)?
+        else {
+            return Ok(HistoryPage::default());
+        };
+        let mut query = TxQuery::new().wallet(wallet).limit(limit);
+        if let Some(cursor) = cursor {
+            query = query.after(cursor);
+        }
+        let page = self.index.query(&query).map_err(to_wallet_error)?;
+        Ok(HistoryPage {
+            records: page.items.into_iter().map(|tx| tx.record).collect(),
+            next_cursor: page.next_cursor,
+        })
+    }
+}
+
+fn wallet_from_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<WalletInfo> {
+    Ok(WalletInfo {
+        id: WalletId(row.get(0)?),
+        chain: row.get(1)?,
+        address: row.get(2)?,
+        synced_at: row.get::<_, Option<i64>>(3)?.map(|t| t as u64),
+    })
+}
+
+fn is_foreign_key_violation(e: &rusqlite::Error) -> bool {
+    matches!(
+        e.sqlite_error_code(),
+        Some(rusqlite::ErrorCode::ConstraintViolation)
+    )
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use walletd_traits::{Amount, TransactionStatus, TxHash};
+
+    fn record(hash: &str, timestamp: Option<u64>) -> TransactionRecord {
+        TransactionRecord {
+            hash: TxHash::new(hash),
+            direction: TxDirection::Incoming,
+            counterparty: Some("0xsender".to_string()),
+            amount: Amount::from_smallest_unit(1_000_000_000_000_000_000, 18),
+            fee: None,
+            status: if timestamp.is_some() {
+                TransactionStatus::Confirmed
+            } else {
+                TransactionStatus::Pending
+            },
+            timestamp,
+            block_height: timestamp.map(|t| t / 12),
+        }
+    }
+
+    /// Serves a fixed history, newest first, with index cursors
+    struct FakeExplorer {
+        records: Vec<TransactionRecord>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl HistoryProvider for FakeExplorer {
+        async fn transactions(
+            &self,
+            _address: &str,
+            cursor: Option<&str>,
+            limit: usize,
+        ) -> WalletResult<HistoryPage> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let start: usize = cursor.map_or(0, |c| c.parse().unwrap());
+            let end = (start + limit).min(self.records.len());
+            Ok(HistoryPage {
+                records: self.records[start..end].to_vec(),
+                next_cursor: (end < self.records.len()).then(|| end.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sync_stops_at_known_history() {
+        let index = Indexer::in_memory().unwrap();
+        let wallet = index.add_wallet("ethereum", "0xabc").unwrap();
+        assert_eq!(index.add_wallet("ethereum", "0xabc").unwrap(), wallet);
+
+        let mut explorer = FakeExplorer {
+            records: (0..10)
+                .rev()
+                .map(|i| record(&format!("0x{:02}", i), Some(1_700_000_000 + i)))
+                .collect(),
+            calls: AtomicUsize::new(0),
+        };
+        let options = SyncOptions {
+            page_size: 3,
+            ..SyncOptions::default()
+        };
+        let report = index.sync_with(wallet, &explorer, options).await.unwrap();
+        assert_eq!((report.inserted, report.pages), (10, 4));
+        assert!(index.wallet(wallet).unwrap().synced_at.is_some());
+
+        // Two new transactions: only the first page is fetched
+        explorer
+            .records
+            .insert(0, record("0x11", Some(1_700_000_011)));
+        explorer.records.insert(0, record("0x12", None));
+        explorer.calls.store(0, Ordering::SeqCst);
+        let report = index.sync_with(wallet, &explorer, options).await.unwrap();
+        assert_eq!(report.inserted, 2);
+        assert_eq!(explorer.calls.load(Ordering::SeqCst), 2);
+
+        // The pending transaction is confirmed on the next sync
+        explorer.records[0] = record("0x12", Some(1_700_000_012));
+        let report = index.sync_with(wallet, &explorer, options).await.unwrap();
+        assert_eq!((report.inserted, report.updated), (0, 1));
+        let pending = index
+            .count(&TxQuery::new().status(TransactionStatus::Pending))
+            .unwrap();
+        assert_eq!(pending, 0);
+    }
+
+    #[test]
+    fn test_events() {
+        let index = Indexer::in_memory().unwrap();
+        let wallet = index.add_wallet("solana", "So1").unwrap();
+        let hash = TxHash::new("sig1");
+        let incoming = WalletEvent::IncomingTransaction {
+            hash: hash.clone(),
+            from: None,
+            amount: Amount::from_smallest_unit(5_000, 9),
+        };
+        assert!(index.apply_event(wallet, &incoming).unwrap());
+        assert!(!index.apply_event(wallet, &incoming).unwrap());
+
+        let confirmed = WalletEvent::TransactionConfirmed {
+            hash: hash.clone(),
+            block_height: Some(250_000_000),
+        };
+        assert!(index.apply_event(wallet, &confirmed).unwrap());
+        assert!(!index.apply_event(wallet, &confirmed).unwrap());
+
+        let page = index.query(&TxQuery::new().wallet(wallet)).unwrap();
+        let stored = &page.items[0].record;
+        assert_eq!(stored.status, TransactionStatus::Confirmed);
+        assert_eq!(stored.block_height, Some(250_000_000));
+        assert!(stored.timestamp.is_some());
+
+        let unknown = WalletEvent::TransactionFailed {
+            hash: TxHash::new("sig2"),
+            reason: None,
+        };
+        assert!(!index.apply_event(wallet, &unknown).unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_local_history_and_removal() {
+        let index = Indexer::in_memory().unwrap();
+        let wallet = index.add_wallet("ethereum", "0xabc").unwrap();
+        let records: Vec<_> = (0..5)
+            .map(|i| record(&format!("0x{}", i), Some(1_700_000_000 + i)))
+            .collect();
+        assert_eq!(index.ingest(wallet, &records).unwrap().inserted, 5);
+
+        let history = index.history("ethereum");
+        let all = history.recent_transactions("0xabc", 100).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[0].hash.as_str(), "0x4");
+        let page = history.transactions("0xabc", None, 2).await.unwrap();
+        let next = history
+            .transactions("0xabc", page.next_cursor.as_deref(), 2)
+            .await
+            .unwrap();
+        assert_eq!(next.records[0].hash.as_str(), "0x2");
+        assert!(history
+            .transactions("0xother", None, 2)
+            .await
+            .unwrap()
+            .records
+            .is_empty());
+
+        index.remove_wallet(wallet).unwrap();
+        assert_eq!(index.count(&TxQuery::new()).unwrap(), 0);
+        assert!(matches!(
+            index.ingest(wallet, &records),
+            Err(IndexerError::WalletNotFound(_))
+        ));
+    }
+}
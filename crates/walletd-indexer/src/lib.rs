@@ -0,0 +1,105 @@
+//! # WalletD Indexer
+//!
+//! A local, offline copy of each wallet's transaction history.
+//!
+//! An [`Indexer`] keeps transactions in a SQLite database. History arrives
+//! from any [`HistoryProvider`](walletd_traits::HistoryProvider) (explorer,
+//! node, remote indexer) through [`Indexer::sync`], which only walks back
+//! until it reaches transactions it already has, and from live
+//! [`WalletEvent`](walletd_traits::WalletEvent) subscriptions through
+//! [`Indexer::follow`]. Apps then page through history with [`TxQuery`]
+//! without touching the network, and export it as CSV or JSON.
+//!
+//! The index also implements `HistoryProvider` itself (see
+//! [`Indexer::history`]), so it can stand in for a remote source.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use walletd_indexer::{Indexer, TxQuery};
+//! # async fn run(explorer: &dyn walletd_traits::HistoryProvider) -> walletd_indexer::Result<()> {
+//! let index = Indexer::open("history.db")?;
+//! let wallet = index.add_wallet("ethereum", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")?;
+//! index.sync(wallet, explorer).await?;
+//!
+//! let page = index.query(&TxQuery::new().wallet(wallet).limit(20))?;
+//! for tx in &page.items {
+//!     println!("{} {:?} {}", tx.record.hash.as_str(), tx.record.direction, tx.record.amount.human_readable());
+//! }
+//! if let Some(cursor) = page.next_cursor {
+//!     let older = index.query(&TxQuery::new().wallet(wallet).after(&cursor))?;
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod export;
+pub mod index;
+pub mod query;
+mod schema;
+
+pub use index::{Indexer, LocalHistory, SyncOptions, SyncReport, WalletId, WalletInfo};
+pub use query::{Page, StoredTransaction, TxQuery};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Indexer errors
+#[derive(Error, Debug)]
+pub enum IndexerError {
+    /// No wallet with this id or address
+    #[error("Wallet not found: {0}")]
+    WalletNotFound(String),
+
+    /// The pagination cursor was not produced by this index
+    #[error("Invalid cursor: {0}")]
+    InvalidCursor(String),
+
+    /// A stored row could not be decoded
+    #[error("Corrupt record: {0}")]
+    Corrupt(String),
+
+    /// Database written by a newer version
+    #[error("Unsupported schema version {0}")]
+    UnsupportedSchema(i64),
+
+    /// The history source failed
+    #[error("History source error: {0}")]
+    Source(#[from] WalletError),
+
+    /// SQLite error
+    #[error("Database error: {0}")]
+    Database(#[from] rusqlite::Error),
+
+    /// Export write failed
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON serialization error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for indexer operations
+pub type Result<T> = std::result::Result<T, IndexerError>;
+
+impl From<IndexerError> for WalletdError {
+    fn from(e: IndexerError) -> Self {
+        match e {
+            IndexerError::WalletNotFound(name) => WalletdError::WalletNotFound(name),
+            IndexerError::InvalidCursor(_) => WalletdError::InvalidState(e.to_string()),
+            IndexerError::Corrupt(reason) => WalletdError::FormatError(reason),
+            IndexerError::UnsupportedSchema(_) => WalletdError::NotSupported(e.to_string()),
+            IndexerError::Source(e) => WalletdError::External {
+                message: e.to_string(),
+            },
+            IndexerError::Database(e) => WalletdError::IoError(e.to_string()),
+            IndexerError::Io(e) => WalletdError::IoError(e.to_string()),
+            IndexerError::Json(e) => WalletdError::JsonError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,430 @@
+//! Filtering and pagination of indexed transactions
+//!
+//! Results are ordered newest first, pending transactions before confirmed
+//! ones. Pages are keyset-paginated: a cursor marks the last row returned,
+//! so pages stay stable while new transactions are indexed.
+
+use crate::index::WalletId;
+use crate::{Indexer, IndexerError, Result};
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Row};
+use serde::{Deserialize, Serialize};
+use walletd_traits::{Amount, TransactionRecord, TransactionStatus, TxDirection, TxHash};
+
+/// Rows per page when no limit is set
+const DEFAULT_LIMIT: usize = 50;
+
+/// A transaction in the index
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredTransaction {
+    /// Wallet the transaction belongs to
+    pub wallet: WalletId,
+    /// Chain of the wallet
+    pub chain: String,
+    /// Address of the wallet
+    pub address: String,
+    /// The transaction as seen from the wallet
+    pub record: TransactionRecord,
+    /// When the index first saw the transaction, Unix seconds
+    pub first_seen: u64,
+}
+
+/// One page of query results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Page {
+    /// Transactions, newest first
+    pub items: Vec<StoredTransaction>,
+    /// Cursor for the next (older) page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Filters for [`Indexer::query`](crate::Indexer::query)
+///
+/// Every filter is optional; an empty query returns all wallets' history.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxQuery {
+    wallet: Option<WalletId>,
+    chain: Option<String>,
+    direction: Option<TxDirection>,
+    status: Option<TransactionStatus>,
+    counterparty: Option<String>,
+    since: Option<u64>,
+    until: Option<u64>,
+    limit: Option<usize>,
+    cursor: Option<String>,
+}
+
+impl TxQuery {
+    /// A query matching every transaction
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only transactions of `wallet`
+    pub fn wallet(mut self, wallet: WalletId) -> Self {
+        self.wallet = Some(wallet);
+        self
+    }
+
+    /// Only wallets on `chain`
+    pub fn chain(mut self, chain: &str) -> Self {
+        self.chain = Some(chain.to_string());
+        self
+    }
+
+    /// Only incoming, outgoing or self transfers
+    pub fn direction(mut self, direction: TxDirection) -> Self {
+        self.direction = Some(direction);
+        self
+    }
+
+    /// Only transactions with `status`
+    pub fn status(mut self, status: TransactionStatus) -> Self {
+        self.status = Some(status);
+        self
+    }
+
+    /// Only transactions with this counterparty
+    pub fn counterparty(mut self, address: &str) -> Self {
+        self.counterparty = Some(address.to_string());
+        self
+    }
+
+    /// Only transactions at or after `timestamp` (Unix seconds)
+    ///
+    /// Pending transactions have no timestamp and are left out.
+    pub fn since(mut self, timestamp: u64) -> Self {
+        self.since = Some(timestamp);
+        self
+    }
+
+    /// Only transactions before `timestamp` (Unix seconds)
+    ///
+    /// Pending transactions have no timestamp and are left out.
+    pub fn until(mut self, timestamp: u64) -> Self {
+        self.until = Some(timestamp);
+        self
+    }
+
+    /// Page size (default 50)
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Continues after the page that returned `cursor`
+    pub fn after(mut self, cursor: &str) -> Self {
+        self.cursor = Some(cursor.to_string());
+        self
+    }
+
+    /// Rows per page
+    pub(crate) fn page_size(&self) -> usize {
+        self.limit.unwrap_or(DEFAULT_LIMIT)
+    }
+
+    /// Returns the query with paging removed, for exports
+    pub(crate) fn unpaged(&self) -> Self {
+        Self {
+            limit: None,
+            cursor: None,
+            ..self.clone()
+        }
+    }
+
+    /// Builds the `WHERE` clause and its parameters
+    pub(crate) fn filter(&self) -> Result<(String, Vec<Value>)> {
+        let mut clauses = Vec::new();
+        let mut params = Vec::new();
+        let mut push = |clause: &str, value: Value| {
+            params.push(value);
+            clauses.push(clause.replace('?', &format!("?{}", params.len())));
+        };
+        if let Some(wallet) = self.wallet {
+            push("t.wallet_id = ?", Value::Integer(wallet.0));
+        }
+        if let Some(chain) = &self.chain {
+            push("w.chain = ?", Value::Text(chain.clone()));
+        }
+        if let Some(direction) = self.direction {
+            push(
+                "t.direction = ?",
+                Value::Text(direction_str(direction).to_string()),
+            );
+        }
+        if let Some(status) = self.status {
+            push("t.status = ?", Value::Text(status_str(status).to_string()));
+        }
+        if let Some(counterparty) = &self.counterparty {
+            push("t.counterparty = ?", Value::Text(counterparty.clone()));
+        }
+        if let Some(since) = self.since {
+            push("t.timestamp >= ?", Value::Integer(to_i64(since)));
+        }
+        if let Some(until) = self.until {
+            push("t.timestamp < ?", Value::Integer(to_i64(until)));
+        }
+        if let Some(cursor) = &self.cursor {
+            let (sort_key, id) = parse_cursor(cursor)?;
+            let n = params.len();
+            params.extend([Value::Integer(sort_key), Value::Integer(id)]);
+            clauses.push(format!(
+                "(t.sort_key < ?{a} OR (t.sort_key = ?{a} AND t.id < ?{b}))",
+                a = n + 1,
+                b = n + 2
+            ));
+        }
+        let clause = if clauses.is_empty() {
+            String::new()
+        } else {
+            format!("WHERE {}", clauses.join(" AND "))
+        };
+        Ok((clause, params))
+    }
+}
+
+impl Indexer {
+    /// Returns one page of transactions matching `query`
+    pub fn query(&self, query: &TxQuery) -> Result<Page> {
+        let limit = query.page_size();
+        let mut items: Vec<(StoredTransaction, String)> = Vec::with_capacity(limit.min(1024));
+        let mut next_cursor = None;
+        self.for_each(query, Some(limit + 1), |stored, cursor| {
+            if items.len() == limit {
+                next_cursor = items.last().map(|(_, cursor)| cursor.clone());
+            } else {
+                items.push((stored, cursor));
+            }
+            Ok(())
+        })?;
+        Ok(Page {
+            items: items.into_iter().map(|(stored, _)| stored).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Counts transactions matching `query`, ignoring its limit and cursor
+    pub fn count(&self, query: &TxQuery) -> Result<u64> {
+        let (filter, params) = query.unpaged().filter()?;
+        let sql = format!(
+            "SELECT COUNT(*) FROM transactions t JOIN wallets w ON w.id = t.wallet_id {}",
+            filter
+        );
+        let count: i64 = self
+            .conn()
+            .query_row(&sql, params_from_iter(params), |row| row.get(0))?;
+        Ok(count as u64)
+    }
+
+    /// Calls `f` with each matching row, newest first, up to `limit` rows
+    pub(crate) fn for_each(
+        &self,
+        query: &TxQuery,
+        limit: Option<usize>,
+        mut f: impl FnMut(StoredTransaction, String) -> Result<()>,
+    ) -> Result<()> {
+        let (filter, params) = query.filter()?;
+        let mut sql = format!(
+            "SELECT {} FROM transactions t JOIN wallets w ON w.id = t.wallet_id {} \
+             ORDER BY t.sort_key DESC, t.id DESC",
+            COLUMNS, filter
+        );
+        if let Some(limit) = limit {
+            sql.push_str(&format!(" LIMIT {}", limit));
+        }
+        let conn = self.conn();
+        let mut stmt = conn.prepare(&sql)?;
+        let mut rows = stmt.query(params_from_iter(params))?;
+        while let Some(row) = rows.next()? {
+            let (stored, cursor) = from_row(row).map_err(unwrap_corrupt)?;
+            f(stored, cursor)?;
+        }
+        Ok(())
+    }
+}
+
+/// Columns read by [`from_row`], in order
+pub(crate) const COLUMNS: &str = "t.id, t.sort_key, t.wallet_id, w.chain, w.address, t.hash, \
+     t.direction, t.counterparty, t.amount, t.decimals, t.fee, t.status, t.timestamp, \
+     t.block_height, t.first_seen";
+
+/// Decodes a row selected with [`COLUMNS`], returning it with its cursor
+pub(crate) fn from_row(row: &Row<'_>) -> rusqlite::Result<(StoredTransaction, String)> {
+    let id: i64 = row.get(0)?;
+    let sort_key: i64 = row.get(1)?;
+    let decimals: u8 = row.get(9)?;
+    let amount = |index: usize| -> rusqlite::Result<Option<Amount>> {
+        row.get::<_, Option<String>>(index)?
+            .map(|value| {
+                value
+                    .parse::<u128>()
+                    .map(|value| Amount::from_smallest_unit(value, decimals))
+                    .map_err(|e| corrupt(index, e.to_string()))
+            })
+            .transpose()
+    };
+    let direction: String = row.get(6)?;
+    let status: String = row.get(11)?;
+    let record = TransactionRecord {
+        hash: TxHash::new(row.get::<_, String>(5)?),
+        direction: parse_direction(&direction).ok_or_else(|| corrupt(6, direction.clone()))?,
+        counterparty: row.get(7)?,
+        amount: amount(8)?.ok_or_else(|| corrupt(8, "missing amount".to_string()))?,
+        fee: amount(10)?,
+        status: parse_status(&status).ok_or_else(|| corrupt(11, status.clone()))?,
+        timestamp: row.get::<_, Option<i64>>(12)?.map(|t| t as u64),
+        block_height: row.get::<_, Option<i64>>(13)?.map(|h| h as u64),
+    };
+    let stored = StoredTransaction {
+        wallet: WalletId(row.get(2)?),
+        chain: row.get(3)?,
+        address: row.get(4)?,
+        record,
+        first_seen: row.get::<_, i64>(14)? as u64,
+    };
+    Ok((stored, format!("{}:{}", sort_key, id)))
+}
+
+fn corrupt(column: usize, reason: String) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(
+        column,
+        rusqlite::types::Type::Text,
+        Box::new(IndexerError::Corrupt(reason)),
+    )
+}
+
+/// Recovers [`IndexerError::Corrupt`] from a row decoding error
+fn unwrap_corrupt(e: rusqlite::Error) -> IndexerError {
+    match e {
+        rusqlite::Error::FromSqlConversionFailure(column, ty, inner) => {
+            match inner.downcast::<IndexerError>() {
+                Ok(inner) => *inner,
+                Err(inner) => rusqlite::Error::FromSqlConversionFailure(column, ty, inner).into(),
+            }
+        }
+        e => e.into(),
+    }
+}
+
+fn parse_cursor(cursor: &str) -> Result<(i64, i64)> {
+    cursor
+        .split_once(':')
+        .and_then(|(sort_key, id)| Some((sort_key.parse().ok()?, id.parse().ok()?)))
+        .ok_or_else(|| IndexerError::InvalidCursor(cursor.to_string()))
+}
+
+/// Clamps a Unix timestamp or height into SQLite's integer range
+pub(crate) fn to_i64(value: u64) -> i64 {
+    i64::try_from(value).unwrap_or(i64::MAX)
+}
+
+pub(crate) fn direction_str(direction: TxDirection) -> &'static str {
+    match direction {
+        TxDirection::Incoming => "incoming",
+        TxDirection::Outgoing => "outgoing",
+        TxDirection::SelfTransfer => "self",
+    }
+}
+
+fn parse_direction(s: &str) -> Option<TxDirection> {
+    match s {
+        "incoming" => Some(TxDirection::Incoming),
+        "outgoing" => Some(TxDirection::Outgoing),
+        "self" => Some(TxDirection::SelfTransfer),
+        _ => None,
+    }
+}
+
+pub(crate) fn status_str(status: TransactionStatus) -> &'static str {
+    match status {
+        TransactionStatus::Pending => "pending",
+        TransactionStatus::Confirmed => "confirmed",
+        TransactionStatus::Failed => "failed",
+        TransactionStatus::Unknown => "unknown",
+    }
+}
+
+fn parse_status(s: &str) -> Option<TransactionStatus> {
+    match s {
+        "pending" => Some(TransactionStatus::Pending),
+        "confirmed" => Some(TransactionStatus::Confirmed),
+        "failed" => Some(TransactionStatus::Failed),
+        "unknown" => Some(TransactionStatus::Unknown),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Amount;
+
+    #[test]
+    fn test_filter_numbering() {
+        let (clause, params) = TxQuery::new()
+            .wallet(WalletId(3))
+            .status(TransactionStatus::Confirmed)
+            .after("100:7")
+            .filter()
+            .unwrap();
+        assert_eq!(
+            clause,
+            "WHERE t.wallet_id = ?1 AND t.status = ?2 AND \
+             (t.sort_key < ?3 OR (t.sort_key = ?3 AND t.id < ?4))"
+        );
+        assert_eq!(params.len(), 4);
+        assert!(matches!(
+            TxQuery::new().after("nope").filter(),
+            Err(IndexerError::InvalidCursor(_))
+        ));
+    }
+
+    #[test]
+    fn test_keyset_pagination() {
+        let index = Indexer::in_memory().unwrap();
+        let a = index.add_wallet("ethereum", "0xa").unwrap();
+        let b = index.add_wallet("polygon", "0xa").unwrap();
+        let record = |hash: &str, timestamp: Option<u64>| TransactionRecord {
+            hash: TxHash::new(hash),
+            direction: TxDirection::Outgoing,
+            counterparty: Some("0xb".to_string()),
+            amount: Amount::from_smallest_unit(1, 18),
+            fee: None,
+            status: match timestamp {
+                Some(_) => TransactionStatus::Confirmed,
+                None => TransactionStatus::Pending,
+            },
+            timestamp,
+            block_height: None,
+        };
+        // Equal timestamps are ordered by insertion, newest first
+        let records = [
+            record("t1", Some(100)),
+            record("t2", Some(200)),
+            record("t3", Some(200)),
+            record("t4", None),
+            record("t5", Some(50)),
+        ];
+        index.ingest(a, &records).unwrap();
+        index.ingest(b, &[record("p1", Some(300))]).unwrap();
+
+        let mut hashes = Vec::new();
+        let mut query = TxQuery::new().wallet(a).limit(2);
+        loop {
+            let page = index.query(&query).unwrap();
+            hashes.extend(page.items.iter().map(|tx| tx.record.hash.0.clone()));
+            match page.next_cursor {
+                Some(cursor) => query = query.after(&cursor),
+                None => break,
+            }
+        }
+        assert_eq!(hashes, ["t4", "t3", "t2", "t1", "t5"]);
+
+        assert_eq!(index.count(&TxQuery::new()).unwrap(), 6);
+        assert_eq!(index.count(&TxQuery::new().chain("polygon")).unwrap(), 1);
+        assert_eq!(
+            index.count(&TxQuery::new().since(100).until(300)).unwrap(),
+            3
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Database schema and migrations
+//!
+//! The schema version is kept in `PRAGMA user_version`. Each entry of
+//! [`MIGRATIONS`] upgrades the database by one version.
+
+use crate::{IndexerError, Result};
+use rusqlite::Connection;
+
+/// Migrations in order; the database version is the number applied
+const MIGRATIONS: &[&str] = &[
+    // 1: wallets and their transactions
+    "CREATE TABLE wallets (
+        id INTEGER PRIMARY KEY,
+        chain TEXT NOT NULL,
+        address TEXT NOT NULL,
+        synced_at INTEGER,
+        UNIQUE (chain, address)
+    );
+    CREATE TABLE transactions (
+        id INTEGER PRIMARY KEY,
+        wallet_id INTEGER NOT NULL REFERENCES wallets(id) ON DELETE CASCADE,
+        hash TEXT NOT NULL,
+        direction TEXT NOT NULL,
+        counterparty TEXT,
+        amount TEXT NOT NULL,
+        decimals INTEGER NOT NULL,
+        fee TEXT,
+        status TEXT NOT NULL,
+        timestamp INTEGER,
+        block_height INTEGER,
+        first_seen INTEGER NOT NULL,
+        -- Pending transactions sort before every confirmed one
+        sort_key INTEGER GENERATED ALWAYS AS (COALESCE(timestamp, 9223372036854775807)) VIRTUAL,
+        UNIQUE (wallet_id, hash)
+    );
+    CREATE INDEX transactions_by_time ON transactions (wallet_id, sort_key DESC, id DESC);
+    CREATE INDEX transactions_by_counterparty ON transactions (counterparty);",
+];
+
+/// Brings the database up to the current schema
+pub(crate) fn migrate(conn: &mut Connection) -> Result<()> {
+    conn.pragma_update(None, "foreign_keys", true)?;
+    let version: i64 = conn.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    let target = MIGRATIONS.len() as i64;
+    if version > target {
+        return Err(IndexerError::UnsupportedSchema(version));
+    }
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        let tx = conn.transaction()?;
+        tx.execute_batch(migration)?;
+        tx.pragma_update(None, "user_version", index as i64 + 1)?;
+        tx.commit()?;
+        tracing::debug!("Migrated history index to schema {}", index + 1);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_migrate_is_idempotent() {
+        let mut conn = Connection::open_in_memory().unwrap();
+        migrate(&mut conn).unwrap();
+        migrate(&mut conn).unwrap();
+        let version: i64 = conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len() as i64);
+
+        conn.pragma_update(None, "user_version", 99).unwrap();
+        assert!(matches!(
+            migrate(&mut conn),
+            Err(IndexerError::UnsupportedSchema(99))
+        ));
+    }
+}
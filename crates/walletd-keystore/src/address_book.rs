@@ -0,0 +1,458 @@
+//! Labeled contacts for send flows
+//!
+//! Contacts are public data, so they are stored in plain text next to the
+//! encrypted accounts of a [`Keystore`](crate::Keystore) and saved with it.
+//! Every address is validated and normalized with a [`ChainRegistry`] when
+//! it is added, so a contact can be trusted as a send destination.
+
+use crate::store::{now, validate_name};
+use crate::{KeystoreError, Result};
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use walletd_traits::{Chain, ChainRegistry, WalletError};
+
+/// A labeled address on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Contact {
+    /// Name shown to the user
+    pub label: String,
+    /// Chain the address belongs to
+    pub chain: Chain,
+    /// Address in canonical form
+    pub address: String,
+    /// Free-form note
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// Creation time, Unix seconds
+    pub created: u64,
+    /// Last time funds were sent to the contact, Unix seconds
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_used: Option<u64>,
+}
+
+/// Labeled contacts, one address per label and chain
+///
+/// The same label can hold an address on several chains ("Alice" on
+/// Ethereum and on Bitcoin), but a label is unique per chain and an address
+/// belongs to one contact only.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct AddressBook {
+    #[serde(skip)]
+    registry: ChainRegistry,
+    contacts: Vec<Contact>,
+}
+
+impl AddressBook {
+    /// Creates an empty address book with the default mainnet rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Uses `registry` to validate addresses added from now on (e.g.
+    /// testnet rules)
+    pub fn with_registry(mut self, registry: ChainRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Adds a contact
+    ///
+    /// The address is validated for `chain` and stored in canonical form.
+    /// Fails with [`KeystoreError::ContactExists`] if the label is taken on
+    /// `chain` or the address is already saved.
+    pub fn insert(&mut self, label: &str, chain: Chain, address: &str) -> Result<&Contact> {
+        validate_name(label)?;
+        let address = self
+            .registry
+            .normalize(chain, address)
+            .map_err(|e| invalid_address(address.trim(), e))?;
+        if self.get(label, chain).is_some() {
+            return Err(KeystoreError::ContactExists(format!(
+                "{} on {}",
+                label, chain
+            )));
+        }
+        if let Some(existing) = self.find(chain, &address) {
+            return Err(KeystoreError::ContactExists(format!(
+                "{} is saved as {}",
+                address, existing.label
+            )));
+        }
+        self.contacts.push(Contact {
+            label: label.to_string(),
+            chain,
+            address,
+            note: None,
+            created: now(),
+            last_used: None,
+        });
+        Ok(self.contacts.last().expect("contact was just added"))
+    }
+
+    /// Adds a contact on the chain its address belongs to
+    ///
+    /// Fails with [`KeystoreError::InvalidAddress`] if the address is valid
+    /// on no chain, or on several (pass the chain to [`insert`](Self::insert)
+    /// then).
+    pub fn insert_detected(&mut self, label: &str, address: &str) -> Result<&Contact> {
+        match self.registry.detect(address)[..] {
+            [chain] => self.insert(label, chain, address),
+            [] => Err(KeystoreError::InvalidAddress {
+                address: address.trim().to_string(),
+                reason: "not an address on any known chain".to_string(),
+            }),
+            ref chains => Err(KeystoreError::InvalidAddress {
+                address: address.trim().to_string(),
+                reason: format!(
+                    "valid on {}; choose a chain",
+                    chains
+                        .iter()
+                        .map(Chain::to_string)
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+            }),
+        }
+    }
+
+    /// Returns the contact `label` on `chain`
+    pub fn get(&self, label: &str, chain: Chain) -> Option<&Contact> {
+        self.contacts
+            .iter()
+            .find(|c| c.chain == chain && c.label == label)
+    }
+
+    /// Returns the contact saved for an address, in any accepted spelling
+    ///
+    /// Send flows use this to show "Alice" instead of a raw address.
+    pub fn find(&self, chain: Chain, address: &str) -> Option<&Contact> {
+        let address = self
+            .registry
+            .normalize(chain, address)
+            .unwrap_or_else(|_| address.trim().to_string());
+        self.contacts
+            .iter()
+            .find(|c| c.chain == chain && c.address == address)
+    }
+
+    /// Sets or clears the note of a contact
+    pub fn set_note(&mut self, label: &str, chain: Chain, note: Option<&str>) -> Result<()> {
+        self.contact_mut(label, chain)?.note = note.map(str::to_string);
+        Ok(())
+    }
+
+    /// Renames a contact
+    pub fn rename(&mut self, label: &str, chain: Chain, to: &str) -> Result<()> {
+        validate_name(to)?;
+        if self.get(to, chain).is_some() {
+            return Err(KeystoreError::ContactExists(format!("{} on {}", to, chain)));
+        }
+        self.contact_mut(label, chain)?.label = to.to_string();
+        Ok(())
+    }
+
+    /// Removes a contact
+    pub fn remove(&mut self, label: &str, chain: Chain) -> Result<Contact> {
+        let index = self
+            .contacts
+            .iter()
+            .position(|c| c.chain == chain && c.label == label)
+            .ok_or_else(|| not_found(label, chain))?;
+        Ok(self.contacts.remove(index))
+    }
+
+    /// Records a send to `address`, returning the contact if it is saved
+    pub fn mark_used(&mut self, chain: Chain, address: &str) -> Option<&Contact> {
+        let address = self.find(chain, address)?.address.clone();
+        let contact = self
+            .contacts
+            .iter_mut()
+            .find(|c| c.chain == chain && c.address == address)?;
+        contact.last_used = Some(now());
+        Some(contact)
+    }
+
+    /// Contacts on `chain`, most recently used first, then by label
+    pub fn for_chain(&self, chain: Chain) -> Vec<&Contact> {
+        let mut contacts: Vec<&Contact> =
+            self.contacts.iter().filter(|c| c.chain == chain).collect();
+        contacts.sort_by(|a, b| {
+            b.last_used
+                .cmp(&a.last_used)
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        contacts
+    }
+
+    /// Up to `limit` contacts that have been sent to, most recent first
+    ///
+    /// With a chain, only contacts on that chain are returned.
+    pub fn recent(&self, chain: Option<Chain>, limit: usize) -> Vec<&Contact> {
+        let mut contacts: Vec<&Contact> = self
+            .contacts
+            .iter()
+            .filter(|c| c.last_used.is_some() && chain.is_none_or(|chain| c.chain == chain))
+            .collect();
+        contacts.sort_by_key(|c| Reverse(c.last_used));
+        contacts.truncate(limit);
+        contacts
+    }
+
+    /// Contacts matching `query`, best match first
+    ///
+    /// Labels are matched case-insensitively: exact matches rank above
+    /// prefixes, prefixes above substrings and substrings above fuzzy
+    /// matches (the query's characters in order, e.g. `alc` for `Alice`).
+    /// An address prefix also matches. Ties go to the most recently used.
+    pub fn search(&self, query: &str) -> Vec<&Contact> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(u8, &Contact)> = self
+            .contacts
+            .iter()
+            .filter_map(|c| match_score(&query, c).map(|score| (score, c)))
+            .collect();
+        matches.sort_by(|(sa, a), (sb, b)| {
+            sb.cmp(sa)
+                .then_with(|| b.last_used.cmp(&a.last_used))
+                .then_with(|| a.label.cmp(&b.label))
+        });
+        matches.into_iter().map(|(_, c)| c).collect()
+    }
+
+    /// Iterates over all contacts in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = &Contact> {
+        self.contacts.iter()
+    }
+
+    /// Number of contacts
+    pub fn len(&self) -> usize {
+        self.contacts.len()
+    }
+
+    /// Returns true if there are no contacts
+    pub fn is_empty(&self) -> bool {
+        self.contacts.is_empty()
+    }
+
+    fn contact_mut(&mut self, label: &str, chain: Chain) -> Result<&mut Contact> {
+        self.contacts
+            .iter_mut()
+            .find(|c| c.chain == chain && c.label == label)
+            .ok_or_else(|| not_found(label, chain))
+    }
+}
+
+fn invalid_address(address: &str, e: WalletError) -> KeystoreError {
+    let reason = match e {
+        // Validators report "<address>: <reason>"
+        WalletError::InvalidAddress(msg) => msg
+            .strip_prefix(address)
+            .and_then(|m| m.strip_prefix(": "))
+            .map(str::to_string)
+            .unwrap_or(msg),
+        e => e.to_string(),
+    };
+    KeystoreError::InvalidAddress {
+        address: address.to_string(),
+        reason,
+    }
+}
+
+fn not_found(label: &str, chain: Chain) -> KeystoreError {
+    KeystoreError::ContactNotFound(format!("{} on {}", label, chain))
+}
+
+/// Ranks how well `query` (lowercase) matches a contact; `None` if not at all
+fn match_score(query: &str, contact: &Contact) -> Option<u8> {
+    let label = contact.label.to_lowercase();
+    if label == query {
+        Some(5)
+    } else if label.starts_with(query) {
+        Some(4)
+    } else if label.contains(query) {
+        Some(3)
+    } else if contact.address.to_lowercase().starts_with(query) {
+        Some(2)
+    } else if is_subsequence(query, &label) {
+        Some(1)
+    } else {
+        None
+    }
+}
+
+fn is_subsequence(needle: &str, haystack: &str) -> bool {
+    let mut haystack = haystack.chars();
+    needle.chars().all(|n| haystack.any(|h| h == n))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ETH: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const BTC: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+    const SOL: &str = "So11111111111111111111111111111111111111112";
+
+    #[test]
+    fn test_insert_validates_and_normalizes() {
+        let mut book = AddressBook::new();
+        let contact = book
+            .insert(
+                "Alice",
+                Chain::Ethereum,
+                &format!(" {} ", ETH.to_lowercase()),
+            )
+            .unwrap();
+        assert_eq!(contact.address, ETH);
+        book.insert("Alice", Chain::Bitcoin, BTC).unwrap();
+
+        let err = book
+            .insert(
+                "Bob",
+                Chain::Ethereum,
+                "0x5aAeb6053f3E94C9b9A09f33669435E7Ef1BeAed",
+            )
+            .unwrap_err();
+        assert!(
+            matches!(&err, KeystoreError::InvalidAddress { reason, .. } if !reason.starts_with("0x"))
+        );
+        assert!(matches!(
+            book.insert(
+                "Alice",
+                Chain::Ethereum,
+                "0x0000000000000000000000000000000000000001"
+            ),
+            Err(KeystoreError::ContactExists(_))
+        ));
+        assert!(matches!(
+            book.insert("Bob", Chain::Ethereum, ETH),
+            Err(KeystoreError::ContactExists(_))
+        ));
+        assert!(matches!(
+            book.insert("", Chain::Bitcoin, BTC),
+            Err(KeystoreError::InvalidName(_))
+        ));
+        assert_eq!(book.len(), 2);
+        assert_eq!(
+            book.find(Chain::Ethereum, &ETH.to_lowercase())
+                .unwrap()
+                .label,
+            "Alice"
+        );
+        assert!(book.find(Chain::Solana, ETH).is_none());
+    }
+
+    #[test]
+    fn test_insert_detected() {
+        let mut book = AddressBook::new();
+        assert_eq!(
+            book.insert_detected("Treasury", ETH).unwrap().chain,
+            Chain::Ethereum
+        );
+        assert_eq!(
+            book.insert_detected("Cold", BTC).unwrap().chain,
+            Chain::Bitcoin
+        );
+        assert_eq!(
+            book.insert_detected("Wrapped SOL", SOL).unwrap().chain,
+            Chain::Solana
+        );
+        assert!(matches!(
+            book.insert_detected("Nobody", "not an address"),
+            Err(KeystoreError::InvalidAddress { .. })
+        ));
+    }
+
+    #[test]
+    fn test_search_ranking() {
+        let mut book = AddressBook::new();
+        book.insert("Alice", Chain::Ethereum, ETH).unwrap();
+        book.insert("Malice Corp", Chain::Bitcoin, BTC).unwrap();
+        book.insert("Al", Chain::Solana, SOL).unwrap();
+
+        let labels = |query| {
+            book.search(query)
+                .iter()
+                .map(|c| c.label.as_str())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(labels("al"), ["Al", "Alice", "Malice Corp"]);
+        assert_eq!(labels("ALICE"), ["Alice", "Malice Corp"]);
+        assert_eq!(labels("mcrp"), ["Malice Corp"]);
+        assert_eq!(labels("0x5aae"), ["Alice"]);
+        assert!(labels("zz").is_empty());
+        assert!(labels(" ").is_empty());
+    }
+
+    #[test]
+    fn test_last_used_tracking() {
+        let mut book = AddressBook::new();
+        book.insert("Alice", Chain::Ethereum, ETH).unwrap();
+        book.insert(
+            "Bob",
+            Chain::Ethereum,
+            "0x0000000000000000000000000000000000000001",
+        )
+        .unwrap();
+        book.insert("Cold", Chain::Bitcoin, BTC).unwrap();
+        assert!(book.recent(None, 5).is_empty());
+
+        assert_eq!(
+            book.mark_used(Chain::Ethereum, &ETH.to_lowercase())
+                .unwrap()
+                .label,
+            "Alice"
+        );
+        assert!(book
+            .mark_used(
+                Chain::Ethereum,
+                "0x0000000000000000000000000000000000000002"
+            )
+            .is_none());
+        book.contact_mut("Cold", Chain::Bitcoin).unwrap().last_used = Some(1);
+
+        let recent: Vec<_> = book
+            .recent(None, 5)
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(recent, ["Alice", "Cold"]);
+        assert_eq!(book.recent(Some(Chain::Bitcoin), 5).len(), 1);
+        assert_eq!(book.recent(None, 1).len(), 1);
+        let ethereum: Vec<_> = book
+            .for_chain(Chain::Ethereum)
+            .iter()
+            .map(|c| c.label.as_str())
+            .collect();
+        assert_eq!(ethereum, ["Alice", "Bob"]);
+    }
+
+    #[test]
+    fn test_edit_and_remove() {
+        let mut book = AddressBook::new();
+        book.insert("Alice", Chain::Ethereum, ETH).unwrap();
+        book.set_note("Alice", Chain::Ethereum, Some("payroll"))
+            .unwrap();
+        book.rename("Alice", Chain::Ethereum, "Alice B").unwrap();
+        assert_eq!(
+            book.get("Alice B", Chain::Ethereum)
+                .unwrap()
+                .note
+                .as_deref(),
+            Some("payroll")
+        );
+        assert!(matches!(
+            book.remove("Alice", Chain::Ethereum),
+            Err(KeystoreError::ContactNotFound(_))
+        ));
+        assert_eq!(
+            book.remove("Alice B", Chain::Ethereum).unwrap().address,
+            ETH
+        );
+        assert!(book.is_empty());
+    }
+}
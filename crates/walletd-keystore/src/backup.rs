@@ -0,0 +1,222 @@
+//! Encrypted full-wallet backups
+//!
+//! A backup is one JSON archive holding everything needed to move a wallet
+//! to another device: the [`Keystore`] with its address book, the next HD
+//! account index per chain, and application settings. The archive is sealed
+//! as a whole under the backup password, so not even addresses or contact
+//! labels are readable without it. Keystore entries stay sealed under their
+//! own passwords inside.
+
+use crate::crypto::{Cipher, Kdf, Sealed};
+use crate::store::{now, write_private, Keystore};
+use crate::{KeystoreError, Result};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::Path;
+use zeroize::Zeroizing;
+
+/// `format` field of every backup archive
+pub const BACKUP_FORMAT: &str = "walletd-backup";
+
+/// Current backup archive version
+pub const BACKUP_VERSION: u32 = 1;
+
+/// Everything a backup carries
+#[derive(Debug, Clone, Default)]
+pub struct WalletBackup {
+    /// Accounts and address book
+    pub keystore: Keystore,
+    /// Next unused account index per chain, as in `HdManager::indexes`
+    pub account_indexes: BTreeMap<String, u32>,
+    /// Application settings
+    pub settings: BTreeMap<String, Value>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Archive {
+    format: String,
+    version: u32,
+    created: u64,
+    crypto: Sealed,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Contents {
+    keystore: Value,
+    #[serde(default)]
+    account_indexes: BTreeMap<String, u32>,
+    #[serde(default)]
+    settings: BTreeMap<String, Value>,
+}
+
+impl WalletBackup {
+    /// Creates a backup of `keystore`
+    pub fn new(keystore: Keystore) -> Self {
+        Self {
+            keystore,
+            ..Self::default()
+        }
+    }
+
+    /// Records the next unused account index on `chain`
+    pub fn with_account_index(mut self, chain: impl Into<String>, next: u32) -> Self {
+        self.account_indexes.insert(chain.into(), next);
+        self
+    }
+
+    /// Records a setting
+    pub fn with_setting(mut self, key: impl Into<String>, value: impl Into<Value>) -> Self {
+        self.settings.insert(key.into(), value.into());
+        self
+    }
+
+    /// Encrypts the backup into an archive with Argon2id and AES-256-GCM
+    pub fn seal(&self, password: &str) -> Result<String> {
+        self.seal_with(password, Kdf::argon2id(), Cipher::Aes256Gcm)
+    }
+
+    /// Encrypts the backup with a chosen KDF and cipher
+    pub fn seal_with(&self, password: &str, kdf: Kdf, cipher: Cipher) -> Result<String> {
+        let contents = Contents {
+            keystore: self.keystore.to_value()?,
+            account_indexes: self.account_indexes.clone(),
+            settings: self.settings.clone(),
+        };
+        let plaintext = Zeroizing::new(serde_json::to_vec(&contents)?);
+        let archive = Archive {
+            format: BACKUP_FORMAT.to_string(),
+            version: BACKUP_VERSION,
+            created: now(),
+            crypto: Sealed::seal(&plaintext, password, &aad(BACKUP_VERSION), kdf, cipher)?,
+        };
+        Ok(serde_json::to_string_pretty(&archive)?)
+    }
+
+    /// Decrypts an archive
+    ///
+    /// A wrong password and a modified archive both fail with
+    /// [`KeystoreError::WrongPassword`].
+    pub fn open(archive: &str, password: &str) -> Result<Self> {
+        let archive: Archive = serde_json::from_str(archive)
+            .map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?;
+        if archive.format != BACKUP_FORMAT {
+            return Err(KeystoreError::InvalidFormat(format!(
+                "not a wallet backup: {:?}",
+                archive.format
+            )));
+        }
+        if archive.version != BACKUP_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(archive.version));
+        }
+        let plaintext = archive.crypto.open(password, &aad(archive.version))?;
+        let contents: Contents = serde_json::from_slice(&plaintext)
+            .map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?;
+        Ok(Self {
+            keystore: Keystore::from_value(contents.keystore)?,
+            account_indexes: contents.account_indexes,
+            settings: contents.settings,
+        })
+    }
+}
+
+/// Writes an encrypted backup to `path`
+///
+/// Like [`Keystore::save`], the file is replaced atomically and on Unix
+/// created with mode `0600`.
+pub fn export(path: impl AsRef<Path>, password: &str, backup: &WalletBackup) -> Result<()> {
+    write_private(path.as_ref(), backup.seal(password)?.as_bytes())
+}
+
+/// Reads and decrypts a backup written by [`export`]
+pub fn restore(path: impl AsRef<Path>, password: &str) -> Result<WalletBackup> {
+    WalletBackup::open(&std::fs::read_to_string(path)?, password)
+}
+
+/// Binds the format and version into the tag
+fn aad(version: u32) -> Vec<u8> {
+    format!("{}/{}", BACKUP_FORMAT, version).into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Chain;
+
+    const FAST: Kdf = Kdf::Argon2id {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_backup_roundtrip() {
+        let mut keystore = Keystore::new().with_kdf(FAST);
+        keystore
+            .insert_mnemonic("seed", PHRASE, "entry-pw")
+            .unwrap();
+        keystore
+            .address_book_mut()
+            .insert(
+                "alice",
+                Chain::Ethereum,
+                "0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045",
+            )
+            .unwrap();
+        let backup = WalletBackup::new(keystore)
+            .with_account_index("ethereum", 3)
+            .with_setting("currency", "EUR");
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("wallet.backup");
+        std::fs::write(
+            &path,
+            backup
+                .seal_with("backup-pw", FAST, Cipher::XChaCha20Poly1305)
+                .unwrap(),
+        )
+        .unwrap();
+        assert!(!std::fs::read_to_string(&path).unwrap().contains("alice"));
+
+        let restored = restore(&path, "backup-pw").unwrap();
+        assert_eq!(restored.account_indexes["ethereum"], 3);
+        assert_eq!(restored.settings["currency"], "EUR");
+        assert_eq!(restored.keystore.address_book().len(), 1);
+        assert_eq!(
+            restored
+                .keystore
+                .unlock_mnemonic("seed", "entry-pw")
+                .unwrap()
+                .as_str(),
+            PHRASE
+        );
+        assert!(matches!(
+            restore(&path, "entry-pw"),
+            Err(KeystoreError::WrongPassword)
+        ));
+    }
+
+    #[test]
+    fn test_rejects_other_archives() {
+        let sealed = WalletBackup::default()
+            .seal_with("pw", FAST, Cipher::Aes256Gcm)
+            .unwrap();
+        let mut archive: Value = serde_json::from_str(&sealed).unwrap();
+
+        archive["version"] = 2.into();
+        assert!(matches!(
+            WalletBackup::open(&archive.to_string(), "pw"),
+            Err(KeystoreError::UnsupportedVersion(2))
+        ));
+        archive["format"] = "keystore".into();
+        assert!(matches!(
+            WalletBackup::open(&archive.to_string(), "pw"),
+            Err(KeystoreError::InvalidFormat(_))
+        ));
+        assert!(WalletBackup::open(&sealed, "pw")
+            .unwrap()
+            .keystore
+            .is_empty());
+    }
+}
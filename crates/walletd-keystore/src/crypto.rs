@@ -0,0 +1,269 @@
+//! Password-based encryption of a single secret
+//!
+//! Each secret gets its own random salt and nonce. The password is stretched
+//! with the configured [`Kdf`] into a 256-bit key, which seals the secret with
+//! the configured [`Cipher`]. AEAD tags make a wrong password and a tampered
+//! file indistinguishable: both fail with [`KeystoreError::WrongPassword`].
+
+use crate::{KeystoreError, Result};
+use aes_gcm::aead::{Aead, KeyInit, Payload};
+use aes_gcm::Aes256Gcm;
+use chacha20poly1305::XChaCha20Poly1305;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+/// Most memory a KDF may use, in KiB (1 GiB)
+pub const MAX_KDF_MEMORY_KIB: u64 = 1024 * 1024;
+/// Most Argon2 passes
+pub const MAX_ARGON2_T_COST: u32 = 64;
+/// Most Argon2 lanes or scrypt parallelism
+pub const MAX_KDF_PARALLELISM: u32 = 16;
+/// Largest scrypt `log_n`
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+/// Largest scrypt block size
+pub const MAX_SCRYPT_R: u32 = 32;
+
+/// Password key derivation function and cost
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "name", rename_all = "lowercase")]
+pub enum Kdf {
+    /// Argon2id (RFC 9106)
+    Argon2id {
+        /// Memory in KiB
+        m_cost: u32,
+        /// Iterations
+        t_cost: u32,
+        /// Lanes
+        p_cost: u32,
+    },
+    /// scrypt with `N = 2^log_n`
+    Scrypt {
+        /// Log2 of the CPU/memory cost
+        log_n: u8,
+        /// Block size
+        r: u32,
+        /// Parallelism
+        p: u32,
+    },
+}
+
+impl Kdf {
+    /// Argon2id with the OWASP minimum (19 MiB, 2 passes, 1 lane)
+    pub const fn argon2id() -> Self {
+        Kdf::Argon2id {
+            m_cost: 19 * 1024,
+            t_cost: 2,
+            p_cost: 1,
+        }
+    }
+
+    /// scrypt at geth's "standard" cost (`N = 2^18, r = 8, p = 1`)
+    pub const fn scrypt() -> Self {
+        Kdf::Scrypt {
+            log_n: walletd_traits::keystore::STANDARD_SCRYPT_LOG_N,
+            r: 8,
+            p: 1,
+        }
+    }
+
+    /// Rejects costs above the `MAX_*` bounds
+    ///
+    /// Costs are read from keystore files, so an unchecked file could make
+    /// opening it take unbounded memory or time.
+    pub fn check(&self) -> Result<()> {
+        let within = match *self {
+            Kdf::Argon2id { m_cost, t_cost, p_cost } => {
+                u64::from(m_cost) <= MAX_KDF_MEMORY_KIB && t_cost <= MAX_ARGON2_T_COST && p_cost <= MAX_KDF_PARALLELISM
+            }
+            Kdf::Scrypt { log_n, r, p } => {
+                // scrypt needs 128 * r * N bytes
+                log_n <= MAX_SCRYPT_LOG_N
+                    && r <= MAX_SCRYPT_R
+                    && p <= MAX_KDF_PARALLELISM
+                    && (128 * u64::from(r)) << log_n <= MAX_KDF_MEMORY_KIB * 1024
+            }
+        };
+        if within {
+            Ok(())
+        } else {
+            Err(KeystoreError::InvalidFormat(format!("KDF cost too high: {:?}", self)))
+        }
+    }
+
+    /// Stretches `password` into a 256-bit key, after [`Kdf::check`]
+    pub fn derive(&self, password: &str, salt: &[u8]) -> Result<Zeroizing<[u8; 32]>> {
+        self.check()?;
+        let mut key = Zeroizing::new([0u8; 32]);
+        match *self {
+            Kdf::Argon2id { m_cost, t_cost, p_cost } => {
+                let params = argon2::Params::new(m_cost, t_cost, p_cost, Some(32))
+                    .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+                argon2::Argon2::new(argon2::Algorithm::Argon2id, argon2::Version::V0x13, params)
+                    .hash_password_into(password.as_bytes(), salt, key.as_mut())
+                    .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+            }
+            Kdf::Scrypt { log_n, r, p } => {
+                let params = scrypt::Params::new(log_n, r, p, 32)
+                    .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+                scrypt::scrypt(password.as_bytes(), salt, &params, key.as_mut())
+                    .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+            }
+        }
+        Ok(key)
+    }
+}
+
+impl Default for Kdf {
+    fn default() -> Self {
+        Self::argon2id()
+    }
+}
+
+/// Authenticated cipher sealing the secret
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Cipher {
+    /// AES-256-GCM, 96-bit nonce
+    #[default]
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    /// XChaCha20-Poly1305, 192-bit nonce
+    #[serde(rename = "xchacha20-poly1305")]
+    XChaCha20Poly1305,
+}
+
+impl Cipher {
+    fn nonce_len(self) -> usize {
+        match self {
+            Cipher::Aes256Gcm => 12,
+            Cipher::XChaCha20Poly1305 => 24,
+        }
+    }
+}
+
+/// A secret sealed under a password
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Sealed {
+    /// Key derivation function and cost
+    pub kdf: Kdf,
+    /// KDF salt, hex
+    pub salt: String,
+    /// Cipher
+    pub cipher: Cipher,
+    /// Cipher nonce, hex
+    pub nonce: String,
+    /// Ciphertext with authentication tag, hex
+    pub ciphertext: String,
+}
+
+impl Sealed {
+    /// Encrypts `secret`, binding `aad` into the tag
+    pub fn seal(secret: &[u8], password: &str, aad: &[u8], kdf: Kdf, cipher: Cipher) -> Result<Self> {
+        let mut rng = rand::thread_rng();
+        let mut salt = [0u8; 32];
+        rng.fill_bytes(&mut salt);
+        let mut nonce = vec![0u8; cipher.nonce_len()];
+        rng.fill_bytes(&mut nonce);
+
+        let key = kdf.derive(password, &salt)?;
+        let payload = Payload { msg: secret, aad };
+        let ciphertext = match cipher {
+            Cipher::Aes256Gcm => Aes256Gcm::new(key.as_ref().into()).encrypt(nonce.as_slice().into(), payload),
+            Cipher::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key.as_ref().into()).encrypt(nonce.as_slice().into(), payload)
+            }
+        }
+        .map_err(|e| KeystoreError::Crypto(e.to_string()))?;
+
+        Ok(Self {
+            kdf,
+            salt: hex::encode(salt),
+            cipher,
+            nonce: hex::encode(nonce),
+            ciphertext: hex::encode(ciphertext),
+        })
+    }
+
+    /// Decrypts the secret, checking `aad`
+    pub fn open(&self, password: &str, aad: &[u8]) -> Result<Zeroizing<Vec<u8>>> {
+        let salt = decode_hex("salt", &self.salt)?;
+        let nonce = decode_hex("nonce", &self.nonce)?;
+        let ciphertext = decode_hex("ciphertext", &self.ciphertext)?;
+        if nonce.len() != self.cipher.nonce_len() {
+            return Err(KeystoreError::InvalidFormat(format!("nonce must be {} bytes", self.cipher.nonce_len())));
+        }
+
+        let key = self.kdf.derive(password, &salt)?;
+        let payload = Payload { msg: &ciphertext, aad };
+        match self.cipher {
+            Cipher::Aes256Gcm => Aes256Gcm::new(key.as_ref().into()).decrypt(nonce.as_slice().into(), payload),
+            Cipher::XChaCha20Poly1305 => {
+                XChaCha20Poly1305::new(key.as_ref().into()).decrypt(nonce.as_slice().into(), payload)
+            }
+        }
+        .map(Zeroizing::new)
+        .map_err(|_| KeystoreError::WrongPassword)
+    }
+}
+
+fn decode_hex(field: &str, s: &str) -> Result<Vec<u8>> {
+    hex::decode(s).map_err(|e| KeystoreError::InvalidFormat(format!("{}: {}", field, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAST_ARGON2: Kdf = Kdf::Argon2id { m_cost: 64, t_cost: 1, p_cost: 1 };
+    const FAST_SCRYPT: Kdf = Kdf::Scrypt { log_n: 4, r: 8, p: 1 };
+
+    #[test]
+    fn test_seal_open_all_combinations() {
+        for kdf in [FAST_ARGON2, FAST_SCRYPT] {
+            for cipher in [Cipher::Aes256Gcm, Cipher::XChaCha20Poly1305] {
+                let sealed = Sealed::seal(b"secret", "pw", b"alice", kdf, cipher).unwrap();
+                assert_eq!(sealed.open("pw", b"alice").unwrap().as_slice(), b"secret");
+                assert!(matches!(sealed.open("pw2", b"alice"), Err(KeystoreError::WrongPassword)));
+                assert!(matches!(sealed.open("pw", b"bob"), Err(KeystoreError::WrongPassword)));
+            }
+        }
+    }
+
+    #[test]
+    fn test_kdf_is_deterministic() {
+        let key = FAST_ARGON2.derive("password", b"somesaltsomesalt").unwrap();
+        let again = FAST_ARGON2.derive("password", b"somesaltsomesalt").unwrap();
+        assert_eq!(*key, *again);
+        assert_ne!(*key, *FAST_SCRYPT.derive("password", b"somesaltsomesalt").unwrap());
+    }
+
+    #[test]
+    fn test_open_rejects_excessive_kdf_cost() {
+        let sealed = Sealed::seal(b"k", "pw", b"", FAST_SCRYPT, Cipher::Aes256Gcm).unwrap();
+        let hostile = [
+            Kdf::Argon2id { m_cost: u32::MAX, t_cost: 1, p_cost: 1 },
+            Kdf::Argon2id { m_cost: 64, t_cost: u32::MAX, p_cost: 1 },
+            Kdf::Argon2id { m_cost: 64, t_cost: 1, p_cost: 1 << 20 },
+            Kdf::Scrypt { log_n: 63, r: 8, p: 1 },
+            Kdf::Scrypt { log_n: 20, r: 32, p: 1 },
+            Kdf::Scrypt { log_n: 4, r: 8, p: u32::MAX },
+        ];
+        for kdf in hostile {
+            let file = Sealed { kdf, ..sealed.clone() };
+            assert!(matches!(file.open("pw", b""), Err(KeystoreError::InvalidFormat(_))), "{:?}", kdf);
+        }
+        assert!(Kdf::argon2id().check().is_ok());
+        assert!(Kdf::scrypt().check().is_ok());
+        assert!(Kdf::Scrypt { log_n: 20, r: 8, p: 1 }.check().is_ok());
+    }
+
+    #[test]
+    fn test_serialized_form() {
+        let sealed = Sealed::seal(b"k", "pw", b"", FAST_SCRYPT, Cipher::XChaCha20Poly1305).unwrap();
+        let value = serde_json::to_value(&sealed).unwrap();
+        assert_eq!(value["kdf"]["name"], "scrypt");
+        assert_eq!(value["kdf"]["log_n"], 4);
+        assert_eq!(value["cipher"], "xchacha20-poly1305");
+        assert_eq!(value["nonce"].as_str().unwrap().len(), 48);
+    }
+}
@@ -0,0 +1,288 @@
+//! Key material in the operating system's secret store
+//!
+//! Desktop apps should not leave keystore passwords or hot keys in plain
+//! files. A [`Keyring`] keeps them in a [`SecretBackend`]: `OsKeyring`
+//! (feature `os-keyring`) uses the macOS Keychain, the Windows Credential
+//! Manager or the Linux Secret Service; [`MemoryBackend`] is for tests.
+
+use crate::store::{signer_for, validate_name};
+use crate::{KeystoreError, Result, SecretKind};
+use rand::RngCore;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use walletd_traits::Signer;
+use zeroize::Zeroizing;
+
+/// Prefix of entries holding keystore passwords
+const PASSWORD_PREFIX: &str = "keystore-password:";
+
+/// Prefix of entries holding hot keys
+const KEY_PREFIX: &str = "key:";
+
+/// A store of small named secrets
+pub trait SecretBackend: Send + Sync {
+    /// Reads a secret; `None` if there is none
+    fn get(&self, name: &str) -> Result<Option<Zeroizing<Vec<u8>>>>;
+
+    /// Writes a secret, replacing any previous one
+    fn set(&self, name: &str, secret: &[u8]) -> Result<()>;
+
+    /// Deletes a secret; `false` if there was none
+    fn delete(&self, name: &str) -> Result<bool>;
+}
+
+/// Keeps secrets in memory only
+#[derive(Default)]
+pub struct MemoryBackend {
+    secrets: Mutex<HashMap<String, Zeroizing<Vec<u8>>>>,
+}
+
+impl MemoryBackend {
+    /// Creates an empty backend
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl fmt::Debug for MemoryBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoryBackend").finish_non_exhaustive()
+    }
+}
+
+impl SecretBackend for MemoryBackend {
+    fn get(&self, name: &str) -> Result<Option<Zeroizing<Vec<u8>>>> {
+        Ok(self
+            .secrets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(name)
+            .cloned())
+    }
+
+    fn set(&self, name: &str, secret: &[u8]) -> Result<()> {
+        self.secrets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(name.to_string(), Zeroizing::new(secret.to_vec()));
+        Ok(())
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        Ok(self
+            .secrets
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(name)
+            .is_some())
+    }
+}
+
+/// The operating system's secret store
+///
+/// Entries are filed under `service`, e.g. the app's bundle identifier, so
+/// apps sharing a machine do not see each other's keys. On Linux a Secret
+/// Service provider (GNOME Keyring, KWallet) must be running.
+#[cfg(feature = "os-keyring")]
+#[derive(Debug, Clone)]
+pub struct OsKeyring {
+    service: String,
+}
+
+#[cfg(feature = "os-keyring")]
+impl OsKeyring {
+    /// Uses the entries of `service`
+    pub fn new(service: impl Into<String>) -> Self {
+        Self {
+            service: service.into(),
+        }
+    }
+
+    fn entry(&self, name: &str) -> Result<keyring::Entry> {
+        keyring::Entry::new(&self.service, name).map_err(keyring_error)
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+impl SecretBackend for OsKeyring {
+    fn get(&self, name: &str) -> Result<Option<Zeroizing<Vec<u8>>>> {
+        match self.entry(name)?.get_secret() {
+            Ok(secret) => Ok(Some(Zeroizing::new(secret))),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+
+    fn set(&self, name: &str, secret: &[u8]) -> Result<()> {
+        self.entry(name)?.set_secret(secret).map_err(keyring_error)
+    }
+
+    fn delete(&self, name: &str) -> Result<bool> {
+        match self.entry(name)?.delete_credential() {
+            Ok(()) => Ok(true),
+            Err(keyring::Error::NoEntry) => Ok(false),
+            Err(e) => Err(keyring_error(e)),
+        }
+    }
+}
+
+#[cfg(feature = "os-keyring")]
+fn keyring_error(e: keyring::Error) -> KeystoreError {
+    KeystoreError::Keyring(e.to_string())
+}
+
+/// Keystore passwords and hot keys kept in a [`SecretBackend`]
+///
+/// [`keystore_password`](Self::keystore_password) gives a [`Keystore`]
+/// file a random password that lives only in the secret store, so the file
+/// alone is useless. Hot keys, which must sign without a prompt, can be
+/// kept in the store directly with [`insert_signer`](Self::insert_signer).
+///
+/// [`Keystore`]: crate::Keystore
+pub struct Keyring<B> {
+    backend: B,
+}
+
+impl<B> fmt::Debug for Keyring<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Keyring").finish_non_exhaustive()
+    }
+}
+
+impl<B: SecretBackend> Keyring<B> {
+    /// Uses `backend`
+    pub fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    /// The password of the keystore named `id`, created on first use
+    ///
+    /// The password is 32 random bytes, hex-encoded. Deleting it makes
+    /// every account sealed with it unrecoverable, so keep a backup.
+    pub fn keystore_password(&self, id: &str) -> Result<Zeroizing<String>> {
+        validate_name(id)?;
+        let name = format!("{}{}", PASSWORD_PREFIX, id);
+        if let Some(password) = self.backend.get(&name)? {
+            return String::from_utf8(password.to_vec())
+                .map(Zeroizing::new)
+                .map_err(|_| KeystoreError::InvalidFormat(format!("password of {}", id)));
+        }
+        let mut bytes = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(bytes.as_mut());
+        let password = Zeroizing::new(hex::encode(bytes.as_ref()));
+        self.backend.set(&name, password.as_bytes())?;
+        Ok(password)
+    }
+
+    /// Forgets the password of the keystore named `id`
+    pub fn remove_keystore_password(&self, id: &str) -> Result<bool> {
+        self.backend.delete(&format!("{}{}", PASSWORD_PREFIX, id))
+    }
+
+    /// Stores the key held by an in-memory signer under `name`
+    ///
+    /// Fails with [`KeystoreError::AccountExists`] if `name` is taken and
+    /// [`KeystoreError::KeyUnavailable`] for hardware and remote signers.
+    pub fn insert_signer(&self, name: &str, signer: &dyn Signer) -> Result<()> {
+        validate_name(name)?;
+        let entry = format!("{}{}", KEY_PREFIX, name);
+        if self.backend.get(&entry)?.is_some() {
+            return Err(KeystoreError::AccountExists(name.to_string()));
+        }
+        let secret = Zeroizing::new(
+            signer
+                .secret_key()
+                .ok_or_else(|| KeystoreError::KeyUnavailable(name.to_string()))?,
+        );
+        let kind = SecretKind::Key {
+            scheme: signer.scheme(),
+        };
+        // The kind, a NUL, then the raw key
+        let mut value = Zeroizing::new(serde_json::to_vec(&kind)?);
+        value.push(0);
+        value.extend_from_slice(&secret);
+        self.backend.set(&entry, &value)
+    }
+
+    /// Loads the key stored under `name` into a signer
+    pub fn unlock_signer(&self, name: &str) -> Result<Box<dyn Signer>> {
+        let value = self
+            .backend
+            .get(&format!("{}{}", KEY_PREFIX, name))?
+            .ok_or_else(|| KeystoreError::AccountNotFound(name.to_string()))?;
+        let split = value
+            .iter()
+            .position(|&b| b == 0)
+            .ok_or_else(|| KeystoreError::InvalidFormat(format!("keyring entry {}", name)))?;
+        match serde_json::from_slice(&value[..split])? {
+            SecretKind::Key { scheme } => signer_for(scheme, &value[split + 1..]),
+            kind => Err(KeystoreError::WrongKind {
+                name: name.to_string(),
+                kind,
+            }),
+        }
+    }
+
+    /// Deletes the key stored under `name`
+    pub fn remove_signer(&self, name: &str) -> Result<()> {
+        if self.backend.delete(&format!("{}{}", KEY_PREFIX, name))? {
+            Ok(())
+        } else {
+            Err(KeystoreError::AccountNotFound(name.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Kdf, Keystore};
+    use walletd_traits::{Ed25519Signer, Secp256k1Signer};
+
+    #[test]
+    fn test_keystore_password() {
+        let keyring = Keyring::new(MemoryBackend::new());
+        let password = keyring.keystore_password("main").unwrap();
+        assert_eq!(password.len(), 64);
+        assert_eq!(*keyring.keystore_password("main").unwrap(), *password);
+        assert_ne!(*keyring.keystore_password("other").unwrap(), *password);
+
+        let mut store = Keystore::new().with_kdf(Kdf::Argon2id {
+            m_cost: 64,
+            t_cost: 1,
+            p_cost: 1,
+        });
+        let signer = Ed25519Signer::from_bytes(&[5u8; 32]);
+        store
+            .insert_signer("near", &signer, &password, None)
+            .unwrap();
+        let password = keyring.keystore_password("main").unwrap();
+        let unlocked = store.unlock_signer("near", &password).unwrap();
+        assert_eq!(unlocked.public_key(), signer.public_key());
+
+        assert!(keyring.remove_keystore_password("main").unwrap());
+        assert_ne!(*keyring.keystore_password("main").unwrap(), *password);
+    }
+
+    #[test]
+    fn test_hot_keys() {
+        let keyring = Keyring::new(MemoryBackend::new());
+        let signer = Secp256k1Signer::from_slice(&[7u8; 32]).unwrap();
+        keyring.insert_signer("hot", &signer).unwrap();
+        assert!(matches!(
+            keyring.insert_signer("hot", &signer),
+            Err(KeystoreError::AccountExists(_))
+        ));
+
+        let unlocked = keyring.unlock_signer("hot").unwrap();
+        assert_eq!(unlocked.scheme(), signer.scheme());
+        assert_eq!(unlocked.public_key(), signer.public_key());
+
+        keyring.remove_signer("hot").unwrap();
+        assert!(matches!(
+            keyring.unlock_signer("hot"),
+            Err(KeystoreError::AccountNotFound(_))
+        ));
+    }
+}
@@ -0,0 +1,171 @@
+//! # WalletD Keystore
+//!
+//! Encrypted at-rest storage for private keys and mnemonics.
+//!
+//! A [`Keystore`] is a JSON file holding any number of named accounts. Each
+//! account is sealed on its own: the password is stretched with Argon2id
+//! (or scrypt) and the secret encrypted with AES-256-GCM (or
+//! XChaCha20-Poly1305). Accounts can be exchanged with geth, MetaMask and
+//! other tools through Web3 Secret Storage (keystore v3) import and export.
+//!
+//! Chain wallets are persisted through the [`Signer`](walletd_traits::Signer)
+//! abstraction: [`Keystore::insert_signer`] stores any in-memory signer and
+//! [`Keystore::unlock_signer`] returns one ready for a wallet's
+//! `from_signer` constructor. Mnemonics are stored with
+//! [`Keystore::insert_mnemonic`] for wallets derived from a seed.
+//!
+//! The same file keeps an [`AddressBook`] of labeled send destinations,
+//! validated per chain on insert. A [`WalletBackup`] packs the keystore,
+//! account indexes and settings into one encrypted archive for moving to
+//! another device.
+//!
+//! With the `os-keyring` feature, a [`Keyring`] keeps keystore passwords
+//! and hot keys in the operating system's secret store instead of files.
+//!
+//! ## Example
+//!
+//! ```
+//! use walletd_keystore::{Kdf, Keystore};
+//! use walletd_traits::{Ed25519Signer, Signer};
+//!
+//! # fn main() -> walletd_keystore::Result<()> {
+//! let mut store = Keystore::new();
+//! # let mut store = store.with_kdf(Kdf::Argon2id { m_cost: 64, t_cost: 1, p_cost: 1 });
+//! let signer = Ed25519Signer::from_bytes(&[7u8; 32]);
+//! store.insert_signer("near-main", &signer, "correct horse", None)?;
+//!
+//! let json = store.to_json()?;
+//! let unlocked = Keystore::from_json(&json)?.unlock_signer("near-main", "correct horse")?;
+//! assert_eq!(unlocked.public_key(), signer.public_key());
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod address_book;
+pub mod backup;
+pub mod crypto;
+pub mod keyring;
+pub mod store;
+
+pub use address_book::{AddressBook, Contact};
+pub use backup::WalletBackup;
+pub use crypto::{Cipher, Kdf, Sealed};
+#[cfg(feature = "os-keyring")]
+pub use keyring::OsKeyring;
+pub use keyring::{Keyring, MemoryBackend, SecretBackend};
+pub use store::{Account, Keystore, SecretKind, Unlocked};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+
+/// Keystore errors
+#[derive(Error, Debug)]
+pub enum KeystoreError {
+    /// Wrong password, or the entry was tampered with
+    #[error("Wrong password or corrupted entry")]
+    WrongPassword,
+
+    /// No account with this name
+    #[error("Account not found: {0}")]
+    AccountNotFound(String),
+
+    /// An account with this name already exists
+    #[error("Account already exists: {0}")]
+    AccountExists(String),
+
+    /// Account names must be non-empty and free of control characters
+    #[error("Invalid account name: {0:?}")]
+    InvalidName(String),
+
+    /// The secret is not valid for its kind
+    #[error("Invalid key: {0}")]
+    InvalidKey(String),
+
+    /// The account holds a different kind of secret than requested
+    #[error("Account {name} holds a {kind:?}")]
+    WrongKind {
+        /// Account name
+        name: String,
+        /// What the account holds
+        kind: SecretKind,
+    },
+
+    /// The address is not valid for the contact's chain
+    #[error("Invalid address {address}: {reason}")]
+    InvalidAddress {
+        /// Address as given
+        address: String,
+        /// Why it was rejected
+        reason: String,
+    },
+
+    /// No contact with this label
+    #[error("Contact not found: {0}")]
+    ContactNotFound(String),
+
+    /// The label or address is already saved
+    #[error("Contact already exists: {0}")]
+    ContactExists(String),
+
+    /// The signer does not expose its key (hardware, remote)
+    #[error("Signer for {0} does not expose its key")]
+    KeyUnavailable(String),
+
+    /// Malformed keystore document
+    #[error("Invalid keystore: {0}")]
+    InvalidFormat(String),
+
+    /// Keystore written by a newer version
+    #[error("Unsupported keystore version {0}")]
+    UnsupportedVersion(u32),
+
+    /// Key derivation or encryption failure
+    #[error("Crypto error: {0}")]
+    Crypto(String),
+
+    /// The OS secret store failed or is unavailable
+    #[error("Keyring error: {0}")]
+    Keyring(String),
+
+    /// File I/O error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON serialization error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for keystore operations
+pub type Result<T> = std::result::Result<T, KeystoreError>;
+
+impl From<KeystoreError> for WalletdError {
+    fn from(e: KeystoreError) -> Self {
+        match e {
+            KeystoreError::WrongPassword | KeystoreError::Crypto(_) => {
+                WalletdError::KeyDerivationError(e.to_string())
+            }
+            KeystoreError::AccountNotFound(name) => WalletdError::WalletNotFound(name),
+            KeystoreError::AccountExists(name) => WalletdError::WalletExists(name),
+            KeystoreError::InvalidKey(reason) => WalletdError::InvalidPrivateKey(reason),
+            KeystoreError::KeyUnavailable(_) => WalletdError::MissingPrivateKey,
+            KeystoreError::InvalidAddress { address, reason } => {
+                WalletdError::InvalidAddress { address, reason }
+            }
+            KeystoreError::ContactNotFound(label) => WalletdError::AddressNotFound(label),
+            KeystoreError::InvalidName(_)
+            | KeystoreError::WrongKind { .. }
+            | KeystoreError::ContactExists(_) => {
+                WalletdError::InvalidState(e.to_string())
+            }
+            KeystoreError::InvalidFormat(reason) => WalletdError::FormatError(reason),
+            KeystoreError::UnsupportedVersion(_) => WalletdError::NotSupported(e.to_string()),
+            KeystoreError::Keyring(reason) => WalletdError::IoError(reason),
+            KeystoreError::Io(e) => WalletdError::IoError(e.to_string()),
+            KeystoreError::Json(e) => WalletdError::JsonError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,608 @@
+//! Multi-account keystore file
+
+use crate::address_book::AddressBook;
+use crate::crypto::{Cipher, Kdf, Sealed};
+use crate::{KeystoreError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use walletd_traits::keystore::{decrypt_key, encrypt_key_with, KeyFormat, STANDARD_SCRYPT_LOG_N};
+use walletd_traits::{Ed25519Signer, Secp256k1Signer, SignatureScheme, Signer};
+use zeroize::Zeroizing;
+
+/// Current file format version
+pub const FORMAT_VERSION: u32 = 1;
+
+/// What an account's secret is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "kebab-case")]
+pub enum SecretKind {
+    /// Raw 32-byte private key for `scheme`
+    Key {
+        /// Signature scheme of the key
+        scheme: SignatureScheme,
+    },
+    /// BIP-39 mnemonic phrase, UTF-8
+    Mnemonic,
+    /// Key in a chain's own encoding (WIF, SUI keystore entry, ...)
+    Encoded {
+        /// Encoding of the secret
+        format: KeyFormat,
+    },
+}
+
+/// One named entry of a [`Keystore`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Account {
+    /// What the secret is
+    pub kind: SecretKind,
+    /// Address the key controls, if recorded
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub address: Option<String>,
+    /// Creation time, Unix seconds
+    pub created: u64,
+    crypto: Sealed,
+}
+
+impl Account {
+    /// Key derivation function protecting this account
+    pub fn kdf(&self) -> Kdf {
+        self.crypto.kdf
+    }
+
+    /// Cipher protecting this account
+    pub fn cipher(&self) -> Cipher {
+        self.crypto.cipher
+    }
+}
+
+/// A decrypted account secret
+///
+/// The secret is zeroized on drop and left out of `Debug`.
+pub struct Unlocked {
+    /// What the secret is
+    pub kind: SecretKind,
+    /// Address recorded for the account
+    pub address: Option<String>,
+    /// Plaintext secret
+    pub secret: Zeroizing<Vec<u8>>,
+}
+
+impl fmt::Debug for Unlocked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Unlocked")
+            .field("kind", &self.kind)
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    accounts: BTreeMap<String, Account>,
+    #[serde(default, skip_serializing_if = "AddressBook::is_empty")]
+    contacts: AddressBook,
+}
+
+/// Password-encrypted key storage with multiple named accounts
+///
+/// Every account is sealed separately, with its own salt, nonce and
+/// password, so unlocking one never exposes another. The account name and
+/// kind are bound into the authentication tag: entries cannot be swapped or
+/// relabelled without the password.
+///
+/// New accounts use Argon2id and AES-256-GCM unless configured otherwise
+/// with [`with_kdf`](Self::with_kdf) and [`with_cipher`](Self::with_cipher).
+///
+/// The file also carries the user's [`AddressBook`], unencrypted.
+#[derive(Debug, Clone, Default)]
+pub struct Keystore {
+    kdf: Kdf,
+    cipher: Cipher,
+    accounts: BTreeMap<String, Account>,
+    contacts: AddressBook,
+}
+
+impl Keystore {
+    /// Creates an empty keystore
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the key derivation function for accounts added from now on
+    pub fn with_kdf(mut self, kdf: Kdf) -> Self {
+        self.kdf = kdf;
+        self
+    }
+
+    /// Sets the cipher for accounts added from now on
+    pub fn with_cipher(mut self, cipher: Cipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Parses a keystore document
+    pub fn from_json(json: &str) -> Result<Self> {
+        Self::from_value(
+            serde_json::from_str(json).map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?,
+        )
+    }
+
+    /// Serializes the keystore
+    pub fn to_json(&self) -> Result<String> {
+        Ok(serde_json::to_string_pretty(&self.to_value()?)?)
+    }
+
+    /// Parses a keystore document embedded in another one
+    pub(crate) fn from_value(value: serde_json::Value) -> Result<Self> {
+        let file: KeystoreFile =
+            serde_json::from_value(value).map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?;
+        if file.version != FORMAT_VERSION {
+            return Err(KeystoreError::UnsupportedVersion(file.version));
+        }
+        Ok(Self {
+            accounts: file.accounts,
+            contacts: file.contacts,
+            ..Self::default()
+        })
+    }
+
+    /// The keystore document, for embedding in another one
+    pub(crate) fn to_value(&self) -> Result<serde_json::Value> {
+        let file = KeystoreFile {
+            version: FORMAT_VERSION,
+            accounts: self.accounts.clone(),
+            contacts: self.contacts.clone(),
+        };
+        Ok(serde_json::to_value(&file)?)
+    }
+
+    /// Reads a keystore file
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        Self::from_json(&std::fs::read_to_string(path)?)
+    }
+
+    /// Writes the keystore to `path`
+    ///
+    /// Writes a sibling temporary file and renames it over `path`, so a crash
+    /// never leaves a truncated keystore. On Unix the file is created with
+    /// mode `0600`.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        write_private(path.as_ref(), self.to_json()?.as_bytes())
+    }
+
+    /// Returns the account called `name`
+    pub fn get(&self, name: &str) -> Option<&Account> {
+        self.accounts.get(name)
+    }
+
+    /// Returns true if an account called `name` exists
+    pub fn contains(&self, name: &str) -> bool {
+        self.accounts.contains_key(name)
+    }
+
+    /// Iterates over accounts in name order
+    pub fn accounts(&self) -> impl Iterator<Item = (&str, &Account)> {
+        self.accounts.iter().map(|(name, account)| (name.as_str(), account))
+    }
+
+    /// Number of accounts
+    pub fn len(&self) -> usize {
+        self.accounts.len()
+    }
+
+    /// Returns true if the keystore has no accounts
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    /// Encrypts and adds a secret
+    ///
+    /// Raw keys are checked against their scheme and mnemonics must be UTF-8.
+    /// Fails with [`KeystoreError::AccountExists`] if `name` is taken.
+    pub fn insert(
+        &mut self,
+        name: &str,
+        kind: SecretKind,
+        secret: &[u8],
+        password: &str,
+        address: Option<&str>,
+    ) -> Result<()> {
+        validate_name(name)?;
+        if self.contains(name) {
+            return Err(KeystoreError::AccountExists(name.to_string()));
+        }
+        validate_secret(kind, secret)?;
+        let account = Account {
+            kind,
+            address: address.map(str::to_string),
+            created: now(),
+            crypto: Sealed::seal(secret, password, &aad(name, kind), self.kdf, self.cipher)?,
+        };
+        self.accounts.insert(name.to_string(), account);
+        Ok(())
+    }
+
+    /// Adds the key held by an in-memory signer
+    ///
+    /// Fails with [`KeystoreError::KeyUnavailable`] for hardware and remote
+    /// signers, which never expose their key.
+    pub fn insert_signer(
+        &mut self,
+        name: &str,
+        signer: &dyn Signer,
+        password: &str,
+        address: Option<&str>,
+    ) -> Result<()> {
+        let secret = Zeroizing::new(
+            signer
+                .secret_key()
+                .ok_or_else(|| KeystoreError::KeyUnavailable(name.to_string()))?,
+        );
+        let kind = SecretKind::Key {
+            scheme: signer.scheme(),
+        };
+        self.insert(name, kind, &secret, password, address)
+    }
+
+    /// Adds a mnemonic phrase
+    pub fn insert_mnemonic(&mut self, name: &str, phrase: &str, password: &str) -> Result<()> {
+        self.insert(name, SecretKind::Mnemonic, phrase.as_bytes(), password, None)
+    }
+
+    /// Decrypts an account
+    pub fn unlock(&self, name: &str, password: &str) -> Result<Unlocked> {
+        let account = self.account(name)?;
+        let secret = account.crypto.open(password, &aad(name, account.kind))?;
+        Ok(Unlocked {
+            kind: account.kind,
+            address: account.address.clone(),
+            secret,
+        })
+    }
+
+    /// Decrypts a raw key account into a signer
+    ///
+    /// Chain wallets built from a signer (`from_signer`) are unlocked this way.
+    pub fn unlock_signer(&self, name: &str, password: &str) -> Result<Box<dyn Signer>> {
+        let unlocked = self.unlock(name, password)?;
+        match unlocked.kind {
+            SecretKind::Key { scheme } => signer_for(scheme, &unlocked.secret),
+            kind => Err(KeystoreError::WrongKind {
+                name: name.to_string(),
+                kind,
+            }),
+        }
+    }
+
+    /// Decrypts a mnemonic account
+    pub fn unlock_mnemonic(&self, name: &str, password: &str) -> Result<Zeroizing<String>> {
+        let unlocked = self.unlock(name, password)?;
+        if unlocked.kind != SecretKind::Mnemonic {
+            return Err(KeystoreError::WrongKind {
+                name: name.to_string(),
+                kind: unlocked.kind,
+            });
+        }
+        let phrase = std::str::from_utf8(&unlocked.secret)
+            .map_err(|e| KeystoreError::InvalidFormat(e.to_string()))?;
+        Ok(Zeroizing::new(phrase.to_string()))
+    }
+
+    /// Removes an account
+    pub fn remove(&mut self, name: &str) -> Result<Account> {
+        self.accounts
+            .remove(name)
+            .ok_or_else(|| KeystoreError::AccountNotFound(name.to_string()))
+    }
+
+    /// Renames an account
+    ///
+    /// Needs the password because the name is bound into the ciphertext.
+    pub fn rename(&mut self, from: &str, to: &str, password: &str) -> Result<()> {
+        validate_name(to)?;
+        if self.contains(to) {
+            return Err(KeystoreError::AccountExists(to.to_string()));
+        }
+        let account = self.account(from)?;
+        let secret = account.crypto.open(password, &aad(from, account.kind))?;
+        let crypto = Sealed::seal(
+            &secret,
+            password,
+            &aad(to, account.kind),
+            account.crypto.kdf,
+            account.crypto.cipher,
+        )?;
+        let mut account = self.accounts.remove(from).expect("account exists");
+        account.crypto = crypto;
+        self.accounts.insert(to.to_string(), account);
+        Ok(())
+    }
+
+    /// Re-encrypts an account under a new password
+    ///
+    /// Also upgrades it to the keystore's current KDF and cipher.
+    pub fn change_password(&mut self, name: &str, old: &str, new: &str) -> Result<()> {
+        let (kdf, cipher) = (self.kdf, self.cipher);
+        let account = self
+            .accounts
+            .get_mut(name)
+            .ok_or_else(|| KeystoreError::AccountNotFound(name.to_string()))?;
+        let aad = aad(name, account.kind);
+        let secret = account.crypto.open(old, &aad)?;
+        account.crypto = Sealed::seal(&secret, new, &aad, kdf, cipher)?;
+        Ok(())
+    }
+
+    /// Imports a Web3 Secret Storage (keystore v3) file as a new account
+    ///
+    /// The account keeps the same password. Raw v3 keys become secp256k1
+    /// [`SecretKind::Key`] accounts; files with a walletd `format` field keep
+    /// that encoding. Files asking for a KDF cost above the
+    /// [`walletd_traits::keystore`] bounds fail with
+    /// [`KeystoreError::InvalidFormat`] before any key derivation.
+    pub fn import_web3(&mut self, name: &str, json: &str, password: &str) -> Result<()> {
+        let key = decrypt_key(json, password).map_err(|e| match e {
+            walletd_traits::WalletError::KeyError(msg) if msg.starts_with("wrong password") => {
+                KeystoreError::WrongPassword
+            }
+            e => KeystoreError::InvalidFormat(e.to_string()),
+        })?;
+        let (kind, address) = match key.format {
+            KeyFormat::Web3Keystore => (
+                SecretKind::Key {
+                    scheme: SignatureScheme::Secp256k1,
+                },
+                key.address.as_deref().map(|a| format!("0x{}", a)),
+            ),
+            format => (SecretKind::Encoded { format }, key.address.clone()),
+        };
+        self.insert(name, kind, key.payload.expose_secret(), password, address.as_deref())
+    }
+
+    /// Exports an account as a Web3 Secret Storage (keystore v3) file
+    ///
+    /// Uses scrypt at the standard geth cost, readable by geth, MetaMask and
+    /// other v3 tools.
+    pub fn export_web3(&self, name: &str, password: &str) -> Result<String> {
+        self.export_web3_with(name, password, STANDARD_SCRYPT_LOG_N)
+    }
+
+    /// Like [`export_web3`](Self::export_web3) with scrypt `N = 2^log_n`
+    pub fn export_web3_with(&self, name: &str, password: &str, log_n: u8) -> Result<String> {
+        let unlocked = self.unlock(name, password)?;
+        let format = match unlocked.kind {
+            SecretKind::Key {
+                scheme: SignatureScheme::Secp256k1,
+            } => KeyFormat::Web3Keystore,
+            SecretKind::Encoded { format } => format,
+            kind => {
+                return Err(KeystoreError::WrongKind {
+                    name: name.to_string(),
+                    kind,
+                })
+            }
+        };
+        encrypt_key_with(format, &unlocked.secret, password, unlocked.address.as_deref(), log_n)
+            .map_err(|e| KeystoreError::Crypto(e.to_string()))
+    }
+
+    /// Saved contacts
+    pub fn address_book(&self) -> &AddressBook {
+        &self.contacts
+    }
+
+    /// Saved contacts, for editing
+    pub fn address_book_mut(&mut self) -> &mut AddressBook {
+        &mut self.contacts
+    }
+
+    fn account(&self, name: &str) -> Result<&Account> {
+        self.accounts
+            .get(name)
+            .ok_or_else(|| KeystoreError::AccountNotFound(name.to_string()))
+    }
+}
+
+/// Builds an in-memory signer for a raw key
+pub(crate) fn signer_for(scheme: SignatureScheme, secret: &[u8]) -> Result<Box<dyn Signer>> {
+    match scheme {
+        SignatureScheme::Ed25519 => {
+            let secret: &[u8; 32] = secret
+                .try_into()
+                .map_err(|_| KeystoreError::InvalidKey("Ed25519 key must be 32 bytes".to_string()))?;
+            Ok(Box::new(Ed25519Signer::from_bytes(secret)))
+        }
+        SignatureScheme::Secp256k1 => Secp256k1Signer::from_slice(secret)
+            .map(|s| Box::new(s) as Box<dyn Signer>)
+            .map_err(|e| KeystoreError::InvalidKey(e.to_string())),
+        scheme => Err(KeystoreError::InvalidKey(format!("unsupported scheme {}", scheme))),
+    }
+}
+
+fn validate_secret(kind: SecretKind, secret: &[u8]) -> Result<()> {
+    match kind {
+        SecretKind::Key { scheme } => signer_for(scheme, secret).map(drop),
+        SecretKind::Mnemonic => std::str::from_utf8(secret)
+            .map(drop)
+            .map_err(|_| KeystoreError::InvalidKey("mnemonic must be UTF-8".to_string())),
+        SecretKind::Encoded { .. } if secret.is_empty() => {
+            Err(KeystoreError::InvalidKey("empty key".to_string()))
+        }
+        SecretKind::Encoded { .. } => Ok(()),
+    }
+}
+
+pub(crate) fn validate_name(name: &str) -> Result<()> {
+    if name.is_empty() || name.chars().any(char::is_control) {
+        return Err(KeystoreError::InvalidName(name.to_string()));
+    }
+    Ok(())
+}
+
+/// Associated data binding a ciphertext to its account name and kind
+fn aad(name: &str, kind: SecretKind) -> Vec<u8> {
+    let mut aad = name.as_bytes().to_vec();
+    aad.push(0);
+    aad.extend(serde_json::to_vec(&kind).expect("SecretKind serializes"));
+    aad
+}
+
+/// Atomically replaces `path` with `contents`, readable only by the owner
+pub(crate) fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    use std::io::Write;
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(&tmp)?;
+    file.write_all(contents)?;
+    file.sync_all()?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keystore() -> Keystore {
+        Keystore::new().with_kdf(Kdf::Argon2id {
+            m_cost: 64,
+            t_cost: 1,
+            p_cost: 1,
+        })
+    }
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+    #[test]
+    fn test_multiple_accounts_roundtrip() {
+        let mut store = keystore();
+        let ed = Ed25519Signer::from_bytes(&[7u8; 32]);
+        store.insert_signer("sui-main", &ed, "pw1", Some("0xabc")).unwrap();
+        store.insert_mnemonic("seed", PHRASE, "pw2").unwrap();
+        assert!(matches!(
+            store.insert_mnemonic("seed", PHRASE, "pw2"),
+            Err(KeystoreError::AccountExists(_))
+        ));
+
+        let store = Keystore::from_json(&store.to_json().unwrap()).unwrap();
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get("sui-main").unwrap().address.as_deref(), Some("0xabc"));
+        let signer = store.unlock_signer("sui-main", "pw1").unwrap();
+        assert_eq!(signer.public_key(), ed.public_key());
+        assert_eq!(store.unlock_mnemonic("seed", "pw2").unwrap().as_str(), PHRASE);
+
+        assert!(matches!(store.unlock("seed", "pw1"), Err(KeystoreError::WrongPassword)));
+        assert!(matches!(store.unlock_signer("seed", "pw2"), Err(KeystoreError::WrongKind { .. })));
+        assert!(matches!(store.unlock("nope", "pw1"), Err(KeystoreError::AccountNotFound(_))));
+    }
+
+    #[test]
+    fn test_entries_cannot_be_swapped() {
+        let mut store = keystore();
+        store.insert_mnemonic("a", PHRASE, "pw").unwrap();
+        let mut value: serde_json::Value = serde_json::from_str(&store.to_json().unwrap()).unwrap();
+        let entry = value["accounts"]["a"].take();
+        value["accounts"] = serde_json::json!({ "b": entry });
+        let tampered = Keystore::from_json(&value.to_string()).unwrap();
+        assert!(matches!(tampered.unlock("b", "pw"), Err(KeystoreError::WrongPassword)));
+    }
+
+    #[test]
+    fn test_rename_and_change_password() {
+        let mut store = keystore().with_cipher(Cipher::XChaCha20Poly1305);
+        store.insert_mnemonic("old", PHRASE, "pw").unwrap();
+        store.rename("old", "new", "pw").unwrap();
+        assert!(!store.contains("old"));
+        store.change_password("new", "pw", "better").unwrap();
+        assert_eq!(store.unlock_mnemonic("new", "better").unwrap().as_str(), PHRASE);
+        assert!(store.unlock("new", "pw").is_err());
+        assert_eq!(store.get("new").unwrap().cipher(), Cipher::XChaCha20Poly1305);
+    }
+
+    #[test]
+    fn test_insert_validation() {
+        let mut store = keystore();
+        let secp = SecretKind::Key {
+            scheme: SignatureScheme::Secp256k1,
+        };
+        assert!(matches!(store.insert("k", secp, &[0u8; 32], "pw", None), Err(KeystoreError::InvalidKey(_))));
+        assert!(matches!(store.insert("", secp, &[1u8; 32], "pw", None), Err(KeystoreError::InvalidName(_))));
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_web3_roundtrip() {
+        let mut store = keystore();
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        store.insert_signer("eth", &signer, "pw", Some("0x1a642f0e3c3af545e7acbd38b07251b3990914f1")).unwrap();
+
+        let v3 = store.export_web3_with("eth", "pw", 4).unwrap();
+        let mut other = keystore();
+        other.import_web3("imported", &v3, "pw").unwrap();
+        let imported = other.unlock("imported", "pw").unwrap();
+        assert_eq!(imported.secret.as_slice(), [1u8; 32]);
+        assert_eq!(imported.address.as_deref(), Some("0x1a642f0e3c3af545e7acbd38b07251b3990914f1"));
+        assert!(matches!(other.import_web3("x", &v3, "wrong"), Err(KeystoreError::WrongPassword)));
+        let hostile = v3.replace(r#""n":16"#, r#""n":4294967296"#);
+        assert!(matches!(other.import_web3("x", &hostile, "pw"), Err(KeystoreError::InvalidFormat(_))));
+
+        store.insert_mnemonic("seed", PHRASE, "pw").unwrap();
+        assert!(matches!(store.export_web3_with("seed", "pw", 4), Err(KeystoreError::WrongKind { .. })));
+    }
+
+    #[test]
+    fn test_save_and_load() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("keystore.json");
+        let mut store = keystore();
+        store.insert_mnemonic("seed", PHRASE, "pw").unwrap();
+        store.save(&path).unwrap();
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        let loaded = Keystore::load(&path).unwrap();
+        assert_eq!(loaded.unlock_mnemonic("seed", "pw").unwrap().as_str(), PHRASE);
+        assert!(!format!("{:?}", loaded.unlock("seed", "pw").unwrap()).contains("abandon"));
+    }
+
+    #[test]
+    fn test_address_book_persists() {
+        let mut store = keystore();
+        assert!(!store.to_json().unwrap().contains("contacts"));
+        store
+            .address_book_mut()
+            .insert("Alice", walletd_traits::Chain::Bitcoin, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq")
+            .unwrap();
+        store
+            .address_book_mut()
+            .mark_used(walletd_traits::Chain::Bitcoin, "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq");
+
+        let loaded = Keystore::from_json(&store.to_json().unwrap()).unwrap();
+        let alice = loaded.address_book().get("Alice", walletd_traits::Chain::Bitcoin).unwrap();
+        assert!(alice.last_used.is_some());
+        assert_eq!(loaded.address_book().len(), 1);
+    }
+}
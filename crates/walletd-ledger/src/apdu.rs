@@ -0,0 +1,228 @@
+//! APDU framing, status words and BIP-32 paths
+
+use crate::{LedgerError, Result};
+use std::fmt;
+use std::str::FromStr;
+
+/// Largest data field of a short APDU
+pub const MAX_DATA: usize = 255;
+
+/// Hardened derivation offset
+pub const HARDENED: u32 = 0x8000_0000;
+
+/// A command APDU
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Apdu {
+    /// Instruction class (selects the app protocol)
+    pub cla: u8,
+    /// Instruction
+    pub ins: u8,
+    /// First parameter
+    pub p1: u8,
+    /// Second parameter
+    pub p2: u8,
+    /// Command data, at most [`MAX_DATA`] bytes
+    pub data: Vec<u8>,
+}
+
+impl Apdu {
+    /// Creates a command
+    pub fn new(cla: u8, ins: u8, p1: u8, p2: u8, data: Vec<u8>) -> Self {
+        Self { cla, ins, p1, p2, data }
+    }
+
+    /// Serializes to `CLA INS P1 P2 Lc DATA`
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        if self.data.len() > MAX_DATA {
+            return Err(LedgerError::InvalidRequest(format!(
+                "APDU data is {} bytes, limit is {}",
+                self.data.len(),
+                MAX_DATA
+            )));
+        }
+        let mut bytes = Vec::with_capacity(5 + self.data.len());
+        bytes.extend_from_slice(&[self.cla, self.ins, self.p1, self.p2, self.data.len() as u8]);
+        bytes.extend_from_slice(&self.data);
+        Ok(bytes)
+    }
+}
+
+/// Status word of a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StatusWord(pub u16);
+
+impl StatusWord {
+    /// Success
+    pub const OK: StatusWord = StatusWord(0x9000);
+    /// The user rejected the request on the device
+    pub const DENIED: StatusWord = StatusWord(0x6985);
+    /// PIN not entered or the device is locked
+    pub const SECURITY_STATUS: StatusWord = StatusWord(0x6982);
+    /// The device is locked (newer firmware)
+    pub const LOCKED: StatusWord = StatusWord(0x5515);
+    /// Instruction class not supported, usually the wrong app is open
+    pub const CLA_NOT_SUPPORTED: StatusWord = StatusWord(0x6E00);
+    /// Instruction not supported by the open app
+    pub const INS_NOT_SUPPORTED: StatusWord = StatusWord(0x6D00);
+    /// No app is open (dashboard)
+    pub const APP_NOT_OPEN: StatusWord = StatusWord(0x6E01);
+    /// Invalid command data
+    pub const WRONG_DATA: StatusWord = StatusWord(0x6A80);
+    /// Blind signing is disabled in the app settings
+    pub const BLIND_SIGNING_DISABLED: StatusWord = StatusWord(0x6808);
+    /// The Bitcoin app interrupted the command with a client request
+    pub const INTERRUPTED: StatusWord = StatusWord(0xE000);
+
+    /// Turns a non-success status into the matching error
+    pub fn check(self) -> Result<()> {
+        match self {
+            StatusWord::OK => Ok(()),
+            StatusWord::DENIED => Err(LedgerError::UserRejected),
+            StatusWord::SECURITY_STATUS | StatusWord::LOCKED => Err(LedgerError::Locked),
+            StatusWord::CLA_NOT_SUPPORTED | StatusWord::INS_NOT_SUPPORTED | StatusWord::APP_NOT_OPEN => {
+                Err(LedgerError::AppNotOpen(self))
+            }
+            StatusWord::BLIND_SIGNING_DISABLED => Err(LedgerError::BlindSigningDisabled),
+            sw => Err(LedgerError::Status(sw)),
+        }
+    }
+}
+
+impl fmt::Display for StatusWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#06x}", self.0)
+    }
+}
+
+/// Splits a raw response into data and status word
+pub fn split_response(mut response: Vec<u8>) -> Result<(Vec<u8>, StatusWord)> {
+    if response.len() < 2 {
+        return Err(LedgerError::InvalidResponse("response shorter than a status word".to_string()));
+    }
+    let sw = u16::from_be_bytes([response[response.len() - 2], response[response.len() - 1]]);
+    response.truncate(response.len() - 2);
+    Ok((response, StatusWord(sw)))
+}
+
+/// Cursor over length-prefixed response fields
+pub(crate) struct Reader<'a>(pub(crate) &'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.0.len() < n {
+            return Err(LedgerError::InvalidResponse("response truncated".to_string()));
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn take_prefixed(&mut self) -> Result<&'a [u8]> {
+        let len = self.take(1)?[0] as usize;
+        self.take(len)
+    }
+}
+
+/// A BIP-32 derivation path
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DerivationPath(Vec<u32>);
+
+impl DerivationPath {
+    /// Creates a path from raw indexes (hardened ones include [`HARDENED`])
+    pub fn new(indexes: Vec<u32>) -> Self {
+        Self(indexes)
+    }
+
+    /// Path indexes
+    pub fn indexes(&self) -> &[u32] {
+        &self.0
+    }
+
+    /// Serializes as `count || index (u32 BE)...`, as every Ledger app expects
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(1 + 4 * self.0.len());
+        bytes.push(self.0.len() as u8);
+        for index in &self.0 {
+            bytes.extend_from_slice(&index.to_be_bytes());
+        }
+        bytes
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = LedgerError;
+
+    /// Parses `m/44'/60'/0'/0/0`; the `m/` prefix is optional and `h` marks
+    /// hardened indexes as well as `'`
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = || LedgerError::InvalidPath(s.to_string());
+        let body = s.strip_prefix("m/").or_else(|| s.strip_prefix('m')).unwrap_or(s);
+        if body.is_empty() {
+            return Ok(Self(Vec::new()));
+        }
+        let indexes = body
+            .split('/')
+            .map(|part| {
+                let (digits, hardened) = match part.strip_suffix(['\'', 'h', 'H']) {
+                    Some(digits) => (digits, true),
+                    None => (part, false),
+                };
+                let index: u32 = digits.parse().map_err(|_| invalid())?;
+                if index >= HARDENED {
+                    return Err(invalid());
+                }
+                Ok(if hardened { index | HARDENED } else { index })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if indexes.len() > 10 {
+            return Err(invalid());
+        }
+        Ok(Self(indexes))
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("m")?;
+        for index in &self.0 {
+            if index & HARDENED != 0 {
+                write!(f, "/{}'", index & !HARDENED)?;
+            } else {
+                write!(f, "/{}", index)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_path_roundtrip() {
+        let path: DerivationPath = "m/44'/60'/0'/0/5".parse().unwrap();
+        assert_eq!(path.indexes(), &[44 | HARDENED, 60 | HARDENED, HARDENED, 0, 5]);
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0/5");
+        assert_eq!("44h/501h/0h".parse::<DerivationPath>().unwrap().to_string(), "m/44'/501'/0'");
+        assert_eq!(
+            hex::encode(path.to_bytes()),
+            "058000002c8000003c800000000000000000000005"
+        );
+        assert!("m/44'/x".parse::<DerivationPath>().is_err());
+        assert!("m/2147483648".parse::<DerivationPath>().is_err());
+    }
+
+    #[test]
+    fn test_apdu_and_status() {
+        let apdu = Apdu::new(0xE0, 0x02, 0x01, 0x00, vec![0xAA, 0xBB]);
+        assert_eq!(apdu.to_bytes().unwrap(), vec![0xE0, 0x02, 0x01, 0x00, 0x02, 0xAA, 0xBB]);
+        assert!(Apdu::new(0xE0, 0x02, 0, 0, vec![0; 256]).to_bytes().is_err());
+
+        let (data, sw) = split_response(vec![0x01, 0x69, 0x85]).unwrap();
+        assert_eq!(data, vec![0x01]);
+        assert!(matches!(sw.check(), Err(LedgerError::UserRejected)));
+        assert!(matches!(StatusWord(0x6E00).check(), Err(LedgerError::AppNotOpen(_))));
+        assert!(StatusWord::OK.check().is_ok());
+    }
+}
@@ -0,0 +1,503 @@
+//! Bitcoin app, protocol of app version 2.1 and later (`CLA 0xE1`)
+//!
+//! The app never receives large inputs in one command. It receives Merkle
+//! roots and commitments instead, and interrupts the command (status
+//! `0xE000`) to ask the host for the preimages and Merkle proofs it needs.
+//! [`ClientCommands`] answers those requests from what the host registered
+//! beforehand.
+//!
+//! Addresses are shown for the standard single-key wallet policies (BIP-44,
+//! 49, 84 and 86), which the app accepts without registration. PSBT signing
+//! is not implemented yet; it needs PSBTv2 maps merkleized the same way.
+
+use crate::apdu::{Apdu, DerivationPath, StatusWord, HARDENED};
+use crate::transport::{exchange_raw, Transport};
+use crate::{LedgerError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::sync::Arc;
+
+const CLA: u8 = 0xE1;
+const CLA_FRAMEWORK: u8 = 0xF8;
+const INS_CONTINUE: u8 = 0x01;
+const INS_GET_EXTENDED_PUBKEY: u8 = 0x00;
+const INS_GET_WALLET_ADDRESS: u8 = 0x03;
+const INS_GET_MASTER_FINGERPRINT: u8 = 0x05;
+const INS_SIGN_MESSAGE: u8 = 0x10;
+const PROTOCOL_VERSION: u8 = 0x01;
+
+/// Wallet policy serialization version 2
+const WALLET_POLICY_V2: u8 = 0x02;
+/// Messages are committed to in chunks of this size
+const MESSAGE_CHUNK: usize = 64;
+
+/// Script type of a standard single-key account
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AddressKind {
+    /// P2PKH, BIP-44
+    Legacy,
+    /// P2SH-P2WPKH, BIP-49
+    NestedSegwit,
+    /// P2WPKH, BIP-84
+    NativeSegwit,
+    /// P2TR key path, BIP-86
+    Taproot,
+}
+
+impl AddressKind {
+    fn purpose(self) -> u32 {
+        match self {
+            AddressKind::Legacy => 44,
+            AddressKind::NestedSegwit => 49,
+            AddressKind::NativeSegwit => 84,
+            AddressKind::Taproot => 86,
+        }
+    }
+
+    fn descriptor_template(self) -> &'static str {
+        match self {
+            AddressKind::Legacy => "pkh(@0/**)",
+            AddressKind::NestedSegwit => "sh(wpkh(@0/**))",
+            AddressKind::NativeSegwit => "wpkh(@0/**)",
+            AddressKind::Taproot => "tr(@0/**)",
+        }
+    }
+}
+
+/// Client for the Ledger Bitcoin app
+#[derive(Clone)]
+pub struct BitcoinApp {
+    transport: Arc<dyn Transport>,
+    testnet: bool,
+}
+
+impl BitcoinApp {
+    /// Wraps a transport for the mainnet app
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
+        Self {
+            transport,
+            testnet: false,
+        }
+    }
+
+    /// Targets the Bitcoin Test app (coin type `1'`, `tpub` keys)
+    pub fn testnet(mut self) -> Self {
+        self.testnet = true;
+        self
+    }
+
+    /// Fingerprint of the device's master key
+    pub async fn master_fingerprint(&self) -> Result<[u8; 4]> {
+        let data = self.run(INS_GET_MASTER_FINGERPRINT, Vec::new(), &mut ClientCommands::default()).await?;
+        data.get(..4)
+            .and_then(|f| f.try_into().ok())
+            .ok_or_else(|| LedgerError::InvalidResponse("fingerprint is not 4 bytes".to_string()))
+    }
+
+    /// Extended public key (`xpub`/`tpub`) at `path`, optionally confirmed on
+    /// the device
+    pub async fn get_extended_pubkey(&self, path: &DerivationPath, display: bool) -> Result<String> {
+        let mut data = vec![display as u8];
+        data.extend_from_slice(&path.to_bytes());
+        let xpub = self.run(INS_GET_EXTENDED_PUBKEY, data, &mut ClientCommands::default()).await?;
+        String::from_utf8(xpub).map_err(|e| LedgerError::InvalidResponse(e.to_string()))
+    }
+
+    /// Compressed public key at `path`
+    pub async fn get_public_key(&self, path: &DerivationPath) -> Result<[u8; 33]> {
+        public_key_from_xpub(&self.get_extended_pubkey(path, false).await?)
+    }
+
+    /// Address `index` of a standard account
+    ///
+    /// With `display` the device derives the address itself, shows it and
+    /// waits for the user to confirm, so a compromised host cannot substitute
+    /// its own receive address.
+    pub async fn get_address(
+        &self,
+        kind: AddressKind,
+        account: u32,
+        change: bool,
+        index: u32,
+        display: bool,
+    ) -> Result<String> {
+        let coin = if self.testnet { 1 } else { 0 };
+        let account_path = DerivationPath::new(vec![
+            kind.purpose() | HARDENED,
+            coin | HARDENED,
+            account | HARDENED,
+        ]);
+        let fingerprint = self.master_fingerprint().await?;
+        let xpub = self.get_extended_pubkey(&account_path, false).await?;
+        let key_info = format!("[{}{}]{}", hex::encode(fingerprint), &account_path.to_string()[1..], xpub);
+
+        let mut client = ClientCommands::default();
+        let template = kind.descriptor_template();
+        let policy = serialize_policy(template, &[key_info.as_bytes()], &mut client);
+        let wallet_id: [u8; 32] = Sha256::digest(&policy).into();
+
+        let mut data = vec![display as u8];
+        data.extend_from_slice(&wallet_id);
+        // Default policies need no registration HMAC
+        data.extend_from_slice(&[0u8; 32]);
+        data.push(change as u8);
+        data.extend_from_slice(&index.to_be_bytes());
+        let address = self.run(INS_GET_WALLET_ADDRESS, data, &mut client).await?;
+        String::from_utf8(address).map_err(|e| LedgerError::InvalidResponse(e.to_string()))
+    }
+
+    /// Signs a message with the BIP-137 "Bitcoin Signed Message" scheme
+    ///
+    /// Returns the 65-byte `header || r || s` signature; base64 of it is the
+    /// usual text form.
+    pub async fn sign_message(&self, path: &DerivationPath, message: &[u8]) -> Result<[u8; 65]> {
+        let mut client = ClientCommands::default();
+        let chunks: Vec<&[u8]> = message.chunks(MESSAGE_CHUNK).collect();
+        let root = client.add_list(&chunks);
+
+        let mut data = path.to_bytes();
+        data.extend_from_slice(&varint(message.len() as u64));
+        data.extend_from_slice(&root);
+        let signature = self.run(INS_SIGN_MESSAGE, data, &mut client).await?;
+        signature
+            .get(..65)
+            .and_then(|s| s.try_into().ok())
+            .ok_or_else(|| LedgerError::InvalidResponse(format!("signature is {} bytes", signature.len())))
+    }
+
+    /// Sends a command and serves the app's client requests until it finishes
+    async fn run(&self, ins: u8, data: Vec<u8>, client: &mut ClientCommands) -> Result<Vec<u8>> {
+        let mut apdu = Apdu::new(CLA, ins, 0x00, PROTOCOL_VERSION, data);
+        loop {
+            let (response, sw) = exchange_raw(self.transport.as_ref(), &apdu).await?;
+            if sw != StatusWord::INTERRUPTED {
+                sw.check()?;
+                return Ok(response);
+            }
+            apdu = Apdu::new(CLA_FRAMEWORK, INS_CONTINUE, 0x00, 0x00, client.execute(&response)?);
+        }
+    }
+}
+
+/// Serializes a version 2 wallet policy and registers its preimages
+fn serialize_policy(template: &str, keys: &[&[u8]], client: &mut ClientCommands) -> Vec<u8> {
+    let keys_root = client.add_list(keys);
+    client.add_preimage(template.as_bytes().to_vec());
+
+    // Unnamed: default policies must have an empty name
+    let mut policy = vec![WALLET_POLICY_V2, 0x00];
+    policy.extend_from_slice(&varint(template.len() as u64));
+    policy.extend_from_slice(&Sha256::digest(template.as_bytes()));
+    policy.extend_from_slice(&varint(keys.len() as u64));
+    policy.extend_from_slice(&keys_root);
+    client.add_preimage(policy.clone());
+    policy
+}
+
+/// Extracts the compressed public key from a base58check extended key
+fn public_key_from_xpub(xpub: &str) -> Result<[u8; 33]> {
+    let bytes = bs58::decode(xpub)
+        .with_check(None)
+        .into_vec()
+        .map_err(|e| LedgerError::InvalidResponse(format!("bad extended key: {}", e)))?;
+    if bytes.len() != 78 {
+        return Err(LedgerError::InvalidResponse(format!("extended key is {} bytes", bytes.len())));
+    }
+    let mut key = [0u8; 33];
+    key.copy_from_slice(&bytes[45..78]);
+    Ok(key)
+}
+
+const CMD_YIELD: u8 = 0x10;
+const CMD_GET_PREIMAGE: u8 = 0x40;
+const CMD_GET_MERKLE_LEAF_PROOF: u8 = 0x41;
+const CMD_GET_MERKLE_LEAF_INDEX: u8 = 0x42;
+const CMD_GET_MORE_ELEMENTS: u8 = 0xA0;
+
+/// Host side of the app's client command protocol
+#[derive(Default)]
+pub struct ClientCommands {
+    preimages: HashMap<[u8; 32], Vec<u8>>,
+    trees: HashMap<[u8; 32], Vec<[u8; 32]>>,
+    queue: VecDeque<Vec<u8>>,
+    yielded: Vec<Vec<u8>>,
+}
+
+impl ClientCommands {
+    /// Registers a preimage the app may ask for by its SHA-256
+    pub fn add_preimage(&mut self, preimage: Vec<u8>) {
+        self.preimages.insert(Sha256::digest(&preimage).into(), preimage);
+    }
+
+    /// Registers a list committed to by Merkle root, returning the root
+    pub fn add_list(&mut self, elements: &[&[u8]]) -> [u8; 32] {
+        let leaves: Vec<[u8; 32]> = elements.iter().map(|e| leaf_hash(e)).collect();
+        for element in elements {
+            let mut preimage = vec![0x00];
+            preimage.extend_from_slice(element);
+            self.add_preimage(preimage);
+        }
+        let root = merkle_root(&leaves);
+        self.trees.insert(root, leaves);
+        root
+    }
+
+    /// Data the app yielded while running, in order
+    pub fn yielded(&self) -> &[Vec<u8>] {
+        &self.yielded
+    }
+
+    /// Answers one client request
+    pub fn execute(&mut self, request: &[u8]) -> Result<Vec<u8>> {
+        let bad = |what: &str| LedgerError::InvalidResponse(format!("malformed {} request", what));
+        let (&command, body) = request.split_first().ok_or_else(|| bad("empty"))?;
+        match command {
+            CMD_YIELD => {
+                self.yielded.push(body.to_vec());
+                Ok(Vec::new())
+            }
+            CMD_GET_PREIMAGE => {
+                let hash: [u8; 32] = body.get(1..33).and_then(|h| h.try_into().ok()).ok_or_else(|| bad("preimage"))?;
+                let preimage = self
+                    .preimages
+                    .get(&hash)
+                    .ok_or_else(|| LedgerError::InvalidRequest(format!("unknown preimage {}", hex::encode(hash))))?;
+                let mut response = varint(preimage.len() as u64);
+                let take = preimage.len().min(255 - response.len() - 1);
+                response.push(take as u8);
+                response.extend_from_slice(&preimage[..take]);
+                self.queue.extend(preimage[take..].iter().map(|b| vec![*b]));
+                Ok(response)
+            }
+            CMD_GET_MERKLE_LEAF_PROOF => {
+                let root: [u8; 32] = body.get(..32).and_then(|h| h.try_into().ok()).ok_or_else(|| bad("proof"))?;
+                let mut rest = &body[32..];
+                let size = read_varint(&mut rest).ok_or_else(|| bad("proof"))? as usize;
+                let index = read_varint(&mut rest).ok_or_else(|| bad("proof"))? as usize;
+                let leaves = self.tree(&root)?;
+                if leaves.len() != size || index >= size {
+                    return Err(bad("proof"));
+                }
+                let proof = merkle_proof(leaves, index);
+                let mut response = leaves[index].to_vec();
+                response.push(proof.len() as u8);
+                let take = proof.len().min((255 - 32 - 2) / 32);
+                response.push(take as u8);
+                for hash in &proof[..take] {
+                    response.extend_from_slice(hash);
+                }
+                self.queue.extend(proof[take..].iter().map(|h| h.to_vec()));
+                Ok(response)
+            }
+            CMD_GET_MERKLE_LEAF_INDEX => {
+                let root: [u8; 32] = body.get(..32).and_then(|h| h.try_into().ok()).ok_or_else(|| bad("index"))?;
+                let leaf = body.get(32..64).ok_or_else(|| bad("index"))?;
+                let position = self.tree(&root)?.iter().position(|l| l.as_slice() == leaf);
+                let mut response = vec![position.is_some() as u8];
+                response.extend_from_slice(&varint(position.unwrap_or_default() as u64));
+                Ok(response)
+            }
+            CMD_GET_MORE_ELEMENTS => {
+                let size = self.queue.front().map(Vec::len).ok_or_else(|| bad("more elements"))?;
+                let mut elements = Vec::new();
+                let mut count = 0u8;
+                while let Some(element) = self.queue.front() {
+                    if element.len() != size || elements.len() + size > 253 {
+                        break;
+                    }
+                    elements.extend(self.queue.pop_front().unwrap_or_default());
+                    count += 1;
+                }
+                let mut response = vec![count, size as u8];
+                response.extend(elements);
+                Ok(response)
+            }
+            other => Err(LedgerError::InvalidRequest(format!("unknown client command {:#04x}", other))),
+        }
+    }
+
+    fn tree(&self, root: &[u8; 32]) -> Result<&Vec<[u8; 32]>> {
+        self.trees
+            .get(root)
+            .ok_or_else(|| LedgerError::InvalidRequest(format!("unknown Merkle root {}", hex::encode(root))))
+    }
+}
+
+fn leaf_hash(element: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x00]);
+    hasher.update(element);
+    hasher.finalize().into()
+}
+
+fn combine(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    hasher.update([0x01]);
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Largest power of two strictly below `n` (`n >= 2`)
+fn split_point(n: usize) -> usize {
+    let mut k = 1;
+    while k * 2 < n {
+        k *= 2;
+    }
+    k
+}
+
+/// Root of the app's Merkle tree: left subtree is the largest power of two
+/// strictly below the leaf count
+fn merkle_root(leaves: &[[u8; 32]]) -> [u8; 32] {
+    match leaves.len() {
+        0 => [0u8; 32],
+        1 => leaves[0],
+        n => {
+            let k = split_point(n);
+            combine(&merkle_root(&leaves[..k]), &merkle_root(&leaves[k..]))
+        }
+    }
+}
+
+/// Sibling hashes from the leaf up to the root
+fn merkle_proof(leaves: &[[u8; 32]], index: usize) -> Vec<[u8; 32]> {
+    if leaves.len() <= 1 {
+        return Vec::new();
+    }
+    let k = split_point(leaves.len());
+    if index < k {
+        let mut proof = merkle_proof(&leaves[..k], index);
+        proof.push(merkle_root(&leaves[k..]));
+        proof
+    } else {
+        let mut proof = merkle_proof(&leaves[k..], index - k);
+        proof.push(merkle_root(&leaves[..k]));
+        proof
+    }
+}
+
+/// Bitcoin compact-size integer
+fn varint(n: u64) -> Vec<u8> {
+    match n {
+        0..=0xFC => vec![n as u8],
+        0xFD..=0xFFFF => [&[0xFD][..], &(n as u16).to_le_bytes()].concat(),
+        0x1_0000..=0xFFFF_FFFF => [&[0xFE][..], &(n as u32).to_le_bytes()].concat(),
+        _ => [&[0xFF][..], &n.to_le_bytes()].concat(),
+    }
+}
+
+fn read_varint(input: &mut &[u8]) -> Option<u64> {
+    let (&first, rest) = input.split_first()?;
+    let width = match first {
+        0xFD => 2,
+        0xFE => 4,
+        0xFF => 8,
+        n => {
+            *input = rest;
+            return Some(n as u64);
+        }
+    };
+    let bytes = rest.get(..width)?;
+    let mut buf = [0u8; 8];
+    buf[..width].copy_from_slice(bytes);
+    *input = &rest[width..];
+    Some(u64::from_le_bytes(buf))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    fn verify(leaf: [u8; 32], index: usize, size: usize, proof: &[[u8; 32]]) -> [u8; 32] {
+        // Walk back down the tree to learn which side each sibling is on
+        let mut sides = Vec::new();
+        let (mut index, mut size) = (index, size);
+        while size > 1 {
+            let k = split_point(size);
+            if index < k {
+                sides.push(false);
+                size = k;
+            } else {
+                sides.push(true);
+                index -= k;
+                size -= k;
+            }
+        }
+        let mut hash = leaf;
+        for (sibling, leaf_on_right) in proof.iter().zip(sides.iter().rev()) {
+            hash = if *leaf_on_right { combine(sibling, &hash) } else { combine(&hash, sibling) };
+        }
+        hash
+    }
+
+    #[test]
+    fn test_merkle_proofs() {
+        for n in 1..=9usize {
+            let leaves: Vec<[u8; 32]> = (0..n).map(|i| leaf_hash(&[i as u8])).collect();
+            let root = merkle_root(&leaves);
+            for (i, leaf) in leaves.iter().enumerate() {
+                assert_eq!(verify(*leaf, i, n, &merkle_proof(&leaves, i)), root, "n={} i={}", n, i);
+            }
+        }
+        // Three leaves: (l0 || l1) on the left, l2 on the right
+        let leaves = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        assert_eq!(merkle_root(&leaves), combine(&combine(&leaves[0], &leaves[1]), &leaves[2]));
+    }
+
+    #[test]
+    fn test_client_commands() {
+        let mut client = ClientCommands::default();
+        let long = vec![0x42u8; 300];
+        client.add_preimage(long.clone());
+        let hash: [u8; 32] = Sha256::digest(&long).into();
+
+        let response = client.execute(&[&[CMD_GET_PREIMAGE, 0x00][..], &hash].concat()).unwrap();
+        assert_eq!(&response[..3], &[0xFD, 0x2C, 0x01]);
+        assert_eq!(response[3] as usize, 251);
+        let more = client.execute(&[CMD_GET_MORE_ELEMENTS]).unwrap();
+        assert_eq!((more[0], more[1]), (49, 1));
+
+        let elements: Vec<Vec<u8>> = (0..10u8).map(|i| vec![i]).collect();
+        let refs: Vec<&[u8]> = elements.iter().map(Vec::as_slice).collect();
+        let root = client.add_list(&refs);
+        let request = [&[CMD_GET_MERKLE_LEAF_INDEX][..], &root, &leaf_hash(&[7])].concat();
+        assert_eq!(client.execute(&request).unwrap(), vec![1, 7]);
+        let request = [&[CMD_GET_MERKLE_LEAF_PROOF][..], &root, &[10, 3]].concat();
+        let response = client.execute(&request).unwrap();
+        assert_eq!(&response[..32], &leaf_hash(&[3]));
+        assert_eq!((response[32], response[33]), (4, 4));
+    }
+
+    #[tokio::test]
+    async fn test_sign_message_serves_preimages() {
+        let message = b"hello";
+        let leaf = leaf_hash(message);
+        let root = merkle_root(&[leaf]);
+        let path: DerivationPath = "m/84'/0'/0'/0/0".parse().unwrap();
+
+        let sign = [&[CLA, INS_SIGN_MESSAGE, 0x00, PROTOCOL_VERSION, 21 + 1 + 32][..], &path.to_bytes(), &[5], &root].concat();
+        let ask = [&[CMD_GET_PREIMAGE, 0x00][..], &leaf, &[0xE0, 0x00]].concat();
+        let answer = [&[CLA_FRAMEWORK, INS_CONTINUE, 0x00, 0x00, 8, 6, 6, 0x00][..], message].concat();
+        let signature = format!("1f{}9000", "55".repeat(64));
+        let transport = MockTransport::default()
+            .expect(&hex::encode(sign), &hex::encode(ask))
+            .expect(&hex::encode(answer), &signature);
+
+        let app = BitcoinApp::new(Arc::new(transport));
+        let sig = app.sign_message(&path, message).await.unwrap();
+        assert_eq!(sig[0], 0x1f);
+        assert_eq!(&sig[1..], &[0x55; 64]);
+    }
+
+    #[test]
+    fn test_public_key_from_xpub() {
+        // BIP-32 test vector 1, chain m
+        let xpub = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+        assert_eq!(
+            hex::encode(public_key_from_xpub(xpub).unwrap()),
+            "0339a36013301597daef41fbe593a02cc513d0b55527ec2df1050e2e8ff49c85c2"
+        );
+    }
+}
@@ -0,0 +1,183 @@
+//! Ethereum app (`CLA 0xE0`)
+//!
+//! Covers every EVM chain: the app signs by chain ID embedded in the
+//! transaction, and shows the address for any `m/44'/60'/...` path.
+
+use crate::apdu::{Apdu, DerivationPath, Reader, MAX_DATA};
+use crate::transport::{exchange, Transport};
+use crate::{LedgerError, Result};
+use std::sync::Arc;
+
+const CLA: u8 = 0xE0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+const INS_SIGN_PERSONAL_MESSAGE: u8 = 0x08;
+const INS_SIGN_EIP712_HASHED: u8 = 0x0C;
+
+const P1_FIRST: u8 = 0x00;
+const P1_MORE: u8 = 0x80;
+
+/// Default account path, `m/44'/60'/0'/0/0`
+pub const DEFAULT_PATH: &str = "m/44'/60'/0'/0/0";
+
+/// Address and public key reported by the app
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EthereumAddress {
+    /// Uncompressed SEC1 public key (65 bytes)
+    pub public_key: Vec<u8>,
+    /// EIP-55 checksummed address with `0x` prefix
+    pub address: String,
+}
+
+/// Recoverable ECDSA signature as returned by the app
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EthereumSignature {
+    /// Recovery value: `27/28` for messages, chain-adjusted for legacy
+    /// transactions, `0/1` parity for typed transactions
+    pub v: u8,
+    /// `r` component
+    pub r: [u8; 32],
+    /// `s` component
+    pub s: [u8; 32],
+}
+
+impl EthereumSignature {
+    fn parse(data: &[u8]) -> Result<Self> {
+        if data.len() < 65 {
+            return Err(LedgerError::InvalidResponse(format!("signature is {} bytes", data.len())));
+        }
+        let mut r = [0u8; 32];
+        let mut s = [0u8; 32];
+        r.copy_from_slice(&data[1..33]);
+        s.copy_from_slice(&data[33..65]);
+        Ok(Self { v: data[0], r, s })
+    }
+
+    /// `r || s || v`, the 65-byte form used by `personal_sign`
+    pub fn to_rsv(&self) -> [u8; 65] {
+        let mut out = [0u8; 65];
+        out[..32].copy_from_slice(&self.r);
+        out[32..64].copy_from_slice(&self.s);
+        out[64] = self.v;
+        out
+    }
+}
+
+/// Client for the Ledger Ethereum app
+#[derive(Clone)]
+pub struct EthereumApp {
+    transport: Arc<dyn Transport>,
+}
+
+impl EthereumApp {
+    /// Wraps a transport
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Reads the address at `path`
+    ///
+    /// With `display` the device shows the address and waits for the user to
+    /// confirm it matches, which is how a receive address should be checked.
+    pub async fn get_address(&self, path: &DerivationPath, display: bool) -> Result<EthereumAddress> {
+        let apdu = Apdu::new(CLA, INS_GET_ADDRESS, display as u8, 0x00, path.to_bytes());
+        let data = exchange(self.transport.as_ref(), &apdu).await?;
+        let mut reader = Reader(&data);
+        let public_key = reader.take_prefixed()?.to_vec();
+        let address = std::str::from_utf8(reader.take_prefixed()?)
+            .map_err(|e| LedgerError::InvalidResponse(e.to_string()))?;
+        Ok(EthereumAddress {
+            public_key,
+            address: format!("0x{}", address),
+        })
+    }
+
+    /// Signs an unsigned transaction: RLP for legacy transactions, or the
+    /// type byte followed by the RLP payload for EIP-2718 transactions
+    pub async fn sign_transaction(&self, path: &DerivationPath, unsigned_tx: &[u8]) -> Result<EthereumSignature> {
+        let mut first = path.to_bytes();
+        first.extend_from_slice(unsigned_tx);
+        self.send_chunked(INS_SIGN_TRANSACTION, first).await
+    }
+
+    /// Signs with `personal_sign` (EIP-191 version `0x45`)
+    pub async fn sign_personal_message(&self, path: &DerivationPath, message: &[u8]) -> Result<EthereumSignature> {
+        let mut first = path.to_bytes();
+        first.extend_from_slice(&(message.len() as u32).to_be_bytes());
+        first.extend_from_slice(message);
+        self.send_chunked(INS_SIGN_PERSONAL_MESSAGE, first).await
+    }
+
+    /// Signs EIP-712 typed data given its domain separator and message hash
+    ///
+    /// The device shows only the two hashes, so it needs nothing else from
+    /// the typed data.
+    pub async fn sign_eip712_hashed(
+        &self,
+        path: &DerivationPath,
+        domain_separator: &[u8; 32],
+        message_hash: &[u8; 32],
+    ) -> Result<EthereumSignature> {
+        let mut data = path.to_bytes();
+        data.extend_from_slice(domain_separator);
+        data.extend_from_slice(message_hash);
+        let apdu = Apdu::new(CLA, INS_SIGN_EIP712_HASHED, 0x00, 0x00, data);
+        EthereumSignature::parse(&exchange(self.transport.as_ref(), &apdu).await?)
+    }
+
+    /// Sends `payload` in 255-byte chunks, first with `P1_FIRST` then `P1_MORE`
+    async fn send_chunked(&self, ins: u8, payload: Vec<u8>) -> Result<EthereumSignature> {
+        let mut response = Vec::new();
+        for (i, chunk) in payload.chunks(MAX_DATA).enumerate() {
+            let p1 = if i == 0 { P1_FIRST } else { P1_MORE };
+            let apdu = Apdu::new(CLA, ins, p1, 0x00, chunk.to_vec());
+            response = exchange(self.transport.as_ref(), &apdu).await?;
+        }
+        EthereumSignature::parse(&response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    fn path() -> DerivationPath {
+        DEFAULT_PATH.parse().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_address() {
+        let pubkey = format!("04{}", "11".repeat(64));
+        let addr = hex::encode("9858EfFD232B4033E47d90003D41EC34EcaEda94");
+        let response = format!("41{}28{}9000", pubkey, addr);
+        let transport = MockTransport::default().expect(
+            "e002010015058000002c8000003c800000000000000000000000",
+            &response,
+        );
+        let app = EthereumApp::new(Arc::new(transport));
+        let address = app.get_address(&path(), true).await.unwrap();
+        assert_eq!(address.address, "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+        assert_eq!(address.public_key.len(), 65);
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_is_chunked() {
+        let tx = vec![0xAB; 300];
+        let signature = format!("01{}{}9000", "22".repeat(32), "33".repeat(32));
+        let transport = MockTransport::default().reply("9000").reply(&signature);
+        let app = EthereumApp::new(Arc::new(transport));
+        let sig = app.sign_transaction(&path(), &tx).await.unwrap();
+        assert_eq!(sig.v, 1);
+        assert_eq!(sig.r, [0x22; 32]);
+        assert_eq!(sig.to_rsv()[64], 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejection() {
+        let transport = MockTransport::default().reply("6985");
+        let app = EthereumApp::new(Arc::new(transport));
+        let err = app.sign_personal_message(&path(), b"hello").await.unwrap_err();
+        assert!(matches!(err, LedgerError::UserRejected));
+    }
+}
@@ -0,0 +1,306 @@
+//! # WalletD Ledger
+//!
+//! Ledger hardware wallet support. Keys stay on the device: the host sends
+//! APDUs to the Ethereum, Bitcoin or Solana app and gets back public keys,
+//! addresses confirmed on screen, and signatures the user approved.
+//!
+//! - [`EthereumApp`], [`BitcoinApp`], [`SolanaApp`] expose each app's
+//!   commands (addresses, transaction and message signing)
+//! - [`LedgerSigner`] implements [`Signer`] for one account, so wallets built
+//!   with `from_signer` can use the device
+//! - [`HidTransport`] (feature `hid`) talks to a device over USB;
+//!   [`TcpTransport`] to the Speculos emulator
+//!
+//! ## Example
+//!
+//! ```no_run
+//! # async fn run() -> walletd_ledger::Result<()> {
+//! use std::sync::Arc;
+//! use walletd_ledger::{LedgerApp, LedgerSigner, TcpTransport};
+//!
+//! let transport = Arc::new(TcpTransport::connect("127.0.0.1:9999").await?);
+//! let signer = LedgerSigner::connect(transport, LedgerApp::Solana, "m/44'/501'/0'/0'".parse()?).await?;
+//! println!("verify on device: {}", signer.verify_address().await?);
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod apdu;
+pub mod bitcoin;
+pub mod ethereum;
+pub mod solana;
+pub mod transport;
+
+pub use apdu::{Apdu, DerivationPath, StatusWord};
+pub use bitcoin::{AddressKind, BitcoinApp};
+pub use ethereum::{EthereumAddress, EthereumApp, EthereumSignature};
+pub use solana::SolanaApp;
+#[cfg(feature = "hid")]
+pub use transport::HidTransport;
+pub use transport::{TcpTransport, Transport};
+
+use apdu::HARDENED;
+use async_trait::async_trait;
+use std::sync::Arc;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::{SignatureScheme, Signer, WalletError, WalletResult};
+
+/// Ledger errors
+#[derive(Error, Debug)]
+pub enum LedgerError {
+    /// No Ledger is connected
+    #[error("No Ledger device found")]
+    DeviceNotFound,
+
+    /// The transport failed
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    /// The user rejected the request on the device
+    #[error("Rejected on the device")]
+    UserRejected,
+
+    /// The device is locked
+    #[error("Device is locked, enter the PIN")]
+    Locked,
+
+    /// The expected app is not open
+    #[error("Open the right app on the device (status {0})")]
+    AppNotOpen(StatusWord),
+
+    /// The app refuses to sign data it cannot display
+    #[error("Enable blind signing in the app settings")]
+    BlindSigningDisabled,
+
+    /// Any other error status
+    #[error("Device returned status {0}")]
+    Status(StatusWord),
+
+    /// Invalid derivation path
+    #[error("Invalid derivation path: {0}")]
+    InvalidPath(String),
+
+    /// The request cannot be sent as built
+    #[error("Invalid request: {0}")]
+    InvalidRequest(String),
+
+    /// The device's response could not be parsed
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// The app does not offer this operation
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+}
+
+/// Result type for Ledger operations
+pub type Result<T> = std::result::Result<T, LedgerError>;
+
+impl From<LedgerError> for WalletError {
+    fn from(e: LedgerError) -> Self {
+        match e {
+            LedgerError::NotSupported(reason) => WalletError::NotSupported(reason),
+            LedgerError::DeviceNotFound | LedgerError::Transport(_) => WalletError::Other(e.to_string()),
+            e => WalletError::KeyError(e.to_string()),
+        }
+    }
+}
+
+impl From<LedgerError> for WalletdError {
+    fn from(e: LedgerError) -> Self {
+        match e {
+            LedgerError::UserRejected | LedgerError::BlindSigningDisabled => WalletdError::SigningError(e.to_string()),
+            LedgerError::InvalidPath(reason) => WalletdError::KeyDerivationError(reason),
+            LedgerError::NotSupported(reason) => WalletdError::NotSupported(reason),
+            LedgerError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            e => WalletdError::External {
+                message: format!("ledger: {}", e),
+            },
+        }
+    }
+}
+
+/// Ledger app an account lives in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LedgerApp {
+    /// Ethereum app (all EVM chains)
+    Ethereum,
+    /// Bitcoin app
+    Bitcoin,
+    /// Solana app
+    Solana,
+}
+
+/// A [`Signer`] backed by one account on a Ledger
+///
+/// The public key is read once at [`connect`](Self::connect). Every signature
+/// needs approval on the device.
+///
+/// Only the Solana app signs arbitrary bytes, so [`Signer::sign_hash`] and
+/// [`Signer::sign_message`] work for Solana accounts. The Ethereum and
+/// Bitcoin apps only sign what they can display (transactions, prefixed
+/// messages), and fail with [`WalletError::NotSupported`]; use
+/// [`EthereumApp`] and [`BitcoinApp`] for those.
+pub struct LedgerSigner {
+    transport: Arc<dyn Transport>,
+    app: LedgerApp,
+    path: DerivationPath,
+    public_key: Vec<u8>,
+}
+
+impl LedgerSigner {
+    /// Opens the account at `path` in `app`, reading its public key
+    pub async fn connect(transport: Arc<dyn Transport>, app: LedgerApp, path: DerivationPath) -> Result<Self> {
+        let public_key = match app {
+            LedgerApp::Ethereum => {
+                let address = EthereumApp::new(transport.clone()).get_address(&path, false).await?;
+                compress(&address.public_key)?.to_vec()
+            }
+            LedgerApp::Bitcoin => BitcoinApp::new(transport.clone()).get_public_key(&path).await?.to_vec(),
+            LedgerApp::Solana => SolanaApp::new(transport.clone()).get_public_key(&path, false).await?.to_vec(),
+        };
+        Ok(Self {
+            transport,
+            app,
+            path,
+            public_key,
+        })
+    }
+
+    /// App the account lives in
+    pub fn app(&self) -> LedgerApp {
+        self.app
+    }
+
+    /// Derivation path of the account
+    pub fn path(&self) -> &DerivationPath {
+        &self.path
+    }
+
+    /// Shows the account's address on the device and returns it once the
+    /// user confirms
+    ///
+    /// Bitcoin paths must follow BIP-44/49/84/86
+    /// (`m/purpose'/coin'/account'/change/index`).
+    pub async fn verify_address(&self) -> Result<String> {
+        match self.app {
+            LedgerApp::Ethereum => Ok(EthereumApp::new(self.transport.clone())
+                .get_address(&self.path, true)
+                .await?
+                .address),
+            LedgerApp::Solana => SolanaApp::new(self.transport.clone()).get_address(&self.path, true).await,
+            LedgerApp::Bitcoin => {
+                let invalid = || LedgerError::InvalidPath(format!("{} is not a standard account path", self.path));
+                let &[purpose, coin, account, change, index] = self.path.indexes() else {
+                    return Err(invalid());
+                };
+                let kind = match purpose {
+                    p if p == 44 | HARDENED => AddressKind::Legacy,
+                    p if p == 49 | HARDENED => AddressKind::NestedSegwit,
+                    p if p == 84 | HARDENED => AddressKind::NativeSegwit,
+                    p if p == 86 | HARDENED => AddressKind::Taproot,
+                    _ => return Err(invalid()),
+                };
+                if account & HARDENED == 0 || change > 1 {
+                    return Err(invalid());
+                }
+                let mut app = BitcoinApp::new(self.transport.clone());
+                if coin == 1 | HARDENED {
+                    app = app.testnet();
+                }
+                app.get_address(kind, account & !HARDENED, change == 1, index, true).await
+            }
+        }
+    }
+
+    fn unsupported(&self) -> WalletError {
+        let app = match self.app {
+            LedgerApp::Ethereum => "Ethereum app only signs transactions and prefixed messages; use EthereumApp",
+            _ => "Bitcoin app only signs PSBTs and prefixed messages; use BitcoinApp",
+        };
+        WalletError::NotSupported(app.to_string())
+    }
+}
+
+#[async_trait]
+impl Signer for LedgerSigner {
+    fn scheme(&self) -> SignatureScheme {
+        match self.app {
+            LedgerApp::Solana => SignatureScheme::Ed25519,
+            LedgerApp::Ethereum | LedgerApp::Bitcoin => SignatureScheme::Secp256k1,
+        }
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.clone()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        self.sign_message(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        match self.app {
+            LedgerApp::Solana => Ok(SolanaApp::new(self.transport.clone())
+                .sign_message(&self.path, message)
+                .await?
+                .to_vec()),
+            _ => Err(self.unsupported()),
+        }
+    }
+}
+
+/// Compresses a 65-byte uncompressed SEC1 key
+fn compress(public_key: &[u8]) -> Result<[u8; 33]> {
+    if public_key.len() != 65 || public_key[0] != 0x04 {
+        return Err(LedgerError::InvalidResponse("expected an uncompressed public key".to_string()));
+    }
+    let mut out = [0u8; 33];
+    out[0] = 0x02 | (public_key[64] & 1);
+    out[1..].copy_from_slice(&public_key[1..33]);
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use transport::mock::MockTransport;
+
+    #[tokio::test]
+    async fn test_solana_signer() {
+        let transport = MockTransport::default()
+            .reply(&format!("{}9000", "ab".repeat(32)))
+            .reply(&format!("{}9000", "cd".repeat(64)));
+        let path = "m/44'/501'/0'/0'".parse().unwrap();
+        let signer = LedgerSigner::connect(Arc::new(transport), LedgerApp::Solana, path).await.unwrap();
+        assert_eq!(signer.scheme(), SignatureScheme::Ed25519);
+        assert_eq!(signer.public_key(), vec![0xab; 32]);
+        assert!(signer.secret_key().is_none());
+        assert_eq!(signer.sign_message(b"tx").await.unwrap(), vec![0xcd; 64]);
+    }
+
+    #[tokio::test]
+    async fn test_ethereum_signer_compresses_key() {
+        let mut pubkey = vec![0x04];
+        pubkey.extend([0x11; 32]);
+        pubkey.extend([0x23; 32]);
+        let response = format!("41{}02{}9000", hex::encode(&pubkey), hex::encode("ab"));
+        let transport = MockTransport::default().reply(&response);
+        let path = ethereum::DEFAULT_PATH.parse().unwrap();
+        let signer = LedgerSigner::connect(Arc::new(transport), LedgerApp::Ethereum, path).await.unwrap();
+        let key = signer.public_key();
+        assert_eq!(key[0], 0x03);
+        assert_eq!(&key[1..], &[0x11; 32]);
+        assert!(matches!(signer.sign_hash(&[0; 32]).await, Err(WalletError::NotSupported(_))));
+    }
+
+    #[test]
+    fn test_error_conversion() {
+        assert!(matches!(WalletdError::from(LedgerError::UserRejected), WalletdError::SigningError(_)));
+        assert!(matches!(WalletError::from(LedgerError::Locked), WalletError::KeyError(_)));
+    }
+}
@@ -0,0 +1,123 @@
+//! Solana app (`CLA 0xE0`)
+//!
+//! Solana keys are Ed25519 with every path level hardened
+//! (`m/44'/501'/account'/change'`). The app signs serialized transaction
+//! messages; arbitrary bytes are only accepted with blind signing enabled.
+
+use crate::apdu::{Apdu, DerivationPath, HARDENED, MAX_DATA};
+use crate::transport::{exchange, Transport};
+use crate::{LedgerError, Result};
+use std::sync::Arc;
+
+const CLA: u8 = 0xE0;
+const INS_GET_PUBKEY: u8 = 0x05;
+const INS_SIGN_MESSAGE: u8 = 0x06;
+
+const P1_NON_CONFIRM: u8 = 0x00;
+const P1_CONFIRM: u8 = 0x01;
+const P2_EXTEND: u8 = 0x01;
+const P2_MORE: u8 = 0x02;
+
+/// Default account path, `m/44'/501'/0'/0'`
+pub const DEFAULT_PATH: &str = "m/44'/501'/0'/0'";
+
+/// Client for the Ledger Solana app
+#[derive(Clone)]
+pub struct SolanaApp {
+    transport: Arc<dyn Transport>,
+}
+
+impl SolanaApp {
+    /// Wraps a transport
+    pub fn new(transport: Arc<dyn Transport>) -> Self {
+        Self { transport }
+    }
+
+    /// Reads the Ed25519 public key at `path`, optionally confirming the
+    /// address on the device
+    pub async fn get_public_key(&self, path: &DerivationPath, display: bool) -> Result<[u8; 32]> {
+        check_path(path)?;
+        let p1 = if display { P1_CONFIRM } else { P1_NON_CONFIRM };
+        let apdu = Apdu::new(CLA, INS_GET_PUBKEY, p1, 0x00, path.to_bytes());
+        let data = exchange(self.transport.as_ref(), &apdu).await?;
+        data.get(..32)
+            .and_then(|key| key.try_into().ok())
+            .ok_or_else(|| LedgerError::InvalidResponse(format!("public key is {} bytes", data.len())))
+    }
+
+    /// Returns the base58 address at `path`
+    pub async fn get_address(&self, path: &DerivationPath, display: bool) -> Result<String> {
+        Ok(bs58::encode(self.get_public_key(path, display).await?).into_string())
+    }
+
+    /// Signs a serialized transaction message, returning the 64-byte signature
+    pub async fn sign_message(&self, path: &DerivationPath, message: &[u8]) -> Result<[u8; 64]> {
+        check_path(path)?;
+        // One signer, then its path, then the message
+        let mut payload = vec![1u8];
+        payload.extend_from_slice(&path.to_bytes());
+        payload.extend_from_slice(message);
+
+        let chunks: Vec<&[u8]> = payload.chunks(MAX_DATA).collect();
+        let mut response = Vec::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            let mut p2 = 0;
+            if i > 0 {
+                p2 |= P2_EXTEND;
+            }
+            if i + 1 < chunks.len() {
+                p2 |= P2_MORE;
+            }
+            let apdu = Apdu::new(CLA, INS_SIGN_MESSAGE, P1_CONFIRM, p2, chunk.to_vec());
+            response = exchange(self.transport.as_ref(), &apdu).await?;
+        }
+        response
+            .get(..64)
+            .and_then(|sig| sig.try_into().ok())
+            .ok_or_else(|| LedgerError::InvalidResponse(format!("signature is {} bytes", response.len())))
+    }
+}
+
+fn check_path(path: &DerivationPath) -> Result<()> {
+    if path.indexes().iter().any(|i| i & HARDENED == 0) {
+        return Err(LedgerError::InvalidPath(format!("{} (Ed25519 needs hardened indexes)", path)));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transport::mock::MockTransport;
+
+    #[tokio::test]
+    async fn test_get_address() {
+        let transport = MockTransport::default().expect(
+            "e005000011048000002c800001f58000000080000000",
+            &format!("{}9000", "00".repeat(31) + "01"),
+        );
+        let app = SolanaApp::new(Arc::new(transport));
+        let path = DEFAULT_PATH.parse().unwrap();
+        assert_eq!(app.get_address(&path, false).await.unwrap(), "11111111111111111111111111111112");
+        assert!(app.get_address(&"m/44'/501'/0/0".parse().unwrap(), false).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sign_message_chunks_flags() {
+        let message = vec![0x5A; 400];
+        let transport = MockTransport::default()
+            .reply("9000")
+            .reply(&format!("{}9000", "44".repeat(64)));
+        let app = SolanaApp::new(Arc::new(transport));
+        let sig = app.sign_message(&DEFAULT_PATH.parse().unwrap(), &message).await.unwrap();
+        assert_eq!(sig, [0x44; 64]);
+
+        // First chunk: P2_MORE; last chunk: P2_EXTEND
+        let header = |p2: &str| format!("e00601{}ff", p2);
+        let transport = MockTransport::default()
+            .expect(&(header("02") + "01048000002c800001f58000000080000000" + &"5a".repeat(237)), "9000")
+            .expect(&format!("e0060101a3{}", "5a".repeat(163)), &format!("{}9000", "44".repeat(64)));
+        let app = SolanaApp::new(Arc::new(transport));
+        app.sign_message(&DEFAULT_PATH.parse().unwrap(), &message).await.unwrap();
+    }
+}
@@ -0,0 +1,279 @@
+//! Device transports
+//!
+//! A [`Transport`] moves raw APDUs to a device and back. [`HidTransport`]
+//! (feature `hid`) talks to a Ledger over USB; [`TcpTransport`] talks to the
+//! Speculos emulator's APDU port, which is how the apps are tested in CI.
+
+use crate::apdu::{split_response, Apdu, StatusWord};
+use crate::{LedgerError, Result};
+use async_trait::async_trait;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpStream, ToSocketAddrs};
+use tokio::sync::Mutex;
+
+#[cfg(feature = "hid")]
+pub use hid::HidTransport;
+
+/// A channel to a Ledger device
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Sends a serialized APDU and returns the response, status word included
+    async fn exchange(&self, command: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Sends `apdu` and returns the response data and status word unchecked
+pub async fn exchange_raw(transport: &dyn Transport, apdu: &Apdu) -> Result<(Vec<u8>, StatusWord)> {
+    split_response(transport.exchange(&apdu.to_bytes()?).await?)
+}
+
+/// Sends `apdu` and returns the response data, failing on any status but
+/// [`StatusWord::OK`]
+pub async fn exchange(transport: &dyn Transport, apdu: &Apdu) -> Result<Vec<u8>> {
+    let (data, sw) = exchange_raw(transport, apdu).await?;
+    sw.check()?;
+    Ok(data)
+}
+
+/// Speculos emulator transport (APDU over TCP, default port 9999)
+///
+/// Frames are `length (u32 BE) || apdu`; responses are `length (u32 BE) ||
+/// data || status word`, where the length excludes the status word.
+pub struct TcpTransport {
+    stream: Mutex<TcpStream>,
+}
+
+impl TcpTransport {
+    /// Connects to a Speculos APDU port
+    pub async fn connect(addr: impl ToSocketAddrs) -> Result<Self> {
+        let stream = TcpStream::connect(addr).await.map_err(|e| LedgerError::Transport(e.to_string()))?;
+        Ok(Self {
+            stream: Mutex::new(stream),
+        })
+    }
+}
+
+#[async_trait]
+impl Transport for TcpTransport {
+    async fn exchange(&self, command: &[u8]) -> Result<Vec<u8>> {
+        let io = |e: std::io::Error| LedgerError::Transport(e.to_string());
+        let mut stream = self.stream.lock().await;
+        stream.write_all(&(command.len() as u32).to_be_bytes()).await.map_err(io)?;
+        stream.write_all(command).await.map_err(io)?;
+        let len = stream.read_u32().await.map_err(io)? as usize;
+        let mut response = vec![0u8; len + 2];
+        stream.read_exact(&mut response).await.map_err(io)?;
+        Ok(response)
+    }
+}
+
+/// Ledger HID framing: 64-byte packets on channel `0x0101`, tag `0x05`
+#[cfg_attr(not(feature = "hid"), allow(dead_code))]
+mod framing {
+    use crate::{LedgerError, Result};
+
+    pub const PACKET_SIZE: usize = 64;
+    const CHANNEL: u16 = 0x0101;
+    const TAG: u8 = 0x05;
+    const HEADER: usize = 5;
+
+    /// Splits an APDU into HID packets
+    pub fn wrap(command: &[u8]) -> Vec<[u8; PACKET_SIZE]> {
+        let mut payload = Vec::with_capacity(command.len() + 2);
+        payload.extend_from_slice(&(command.len() as u16).to_be_bytes());
+        payload.extend_from_slice(command);
+        payload
+            .chunks(PACKET_SIZE - HEADER)
+            .enumerate()
+            .map(|(seq, chunk)| {
+                let mut packet = [0u8; PACKET_SIZE];
+                packet[..2].copy_from_slice(&CHANNEL.to_be_bytes());
+                packet[2] = TAG;
+                packet[3..5].copy_from_slice(&(seq as u16).to_be_bytes());
+                packet[HEADER..HEADER + chunk.len()].copy_from_slice(chunk);
+                packet
+            })
+            .collect()
+    }
+
+    /// Reassembles a response from packets, asking `next` for each one
+    pub fn unwrap(mut next: impl FnMut() -> Result<[u8; PACKET_SIZE]>) -> Result<Vec<u8>> {
+        let mut response = Vec::new();
+        let mut expected = None;
+        let mut seq = 0u16;
+        loop {
+            let packet = next()?;
+            if u16::from_be_bytes([packet[0], packet[1]]) != CHANNEL || packet[2] != TAG {
+                return Err(LedgerError::Transport("unexpected HID channel or tag".to_string()));
+            }
+            if u16::from_be_bytes([packet[3], packet[4]]) != seq {
+                return Err(LedgerError::Transport("HID packet out of sequence".to_string()));
+            }
+            let mut body = &packet[HEADER..];
+            if seq == 0 {
+                expected = Some(u16::from_be_bytes([body[0], body[1]]) as usize);
+                body = &body[2..];
+            }
+            let total = expected.unwrap_or_default();
+            let take = body.len().min(total - response.len());
+            response.extend_from_slice(&body[..take]);
+            if response.len() == total {
+                return Ok(response);
+            }
+            seq += 1;
+        }
+    }
+}
+
+#[cfg(feature = "hid")]
+mod hid {
+    use super::framing::{self, PACKET_SIZE};
+    use super::Transport;
+    use crate::{LedgerError, Result};
+    use async_trait::async_trait;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    /// Ledger's USB vendor ID
+    pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+    /// FIDO usage page, used by the U2F interface which we skip
+    const FIDO_USAGE_PAGE: u16 = 0xf1d0;
+    /// How long a read waits before giving up; covers on-device confirmation
+    const READ_TIMEOUT: Duration = Duration::from_secs(120);
+
+    /// USB HID transport to a connected Ledger
+    pub struct HidTransport {
+        device: Arc<Mutex<hidapi::HidDevice>>,
+    }
+
+    impl HidTransport {
+        /// Opens the first connected Ledger
+        pub fn open() -> Result<Self> {
+            let api = hidapi::HidApi::new().map_err(|e| LedgerError::Transport(e.to_string()))?;
+            let info = api
+                .device_list()
+                .find(|d| {
+                    d.vendor_id() == LEDGER_VENDOR_ID
+                        && d.usage_page() != FIDO_USAGE_PAGE
+                        && (d.interface_number() == 0 || d.interface_number() == -1)
+                })
+                .ok_or(LedgerError::DeviceNotFound)?;
+            let device = info.open_device(&api).map_err(|e| LedgerError::Transport(e.to_string()))?;
+            Ok(Self {
+                device: Arc::new(Mutex::new(device)),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Transport for HidTransport {
+        async fn exchange(&self, command: &[u8]) -> Result<Vec<u8>> {
+            let device = self.device.clone();
+            let command = command.to_vec();
+            // hidapi blocks, and reads wait for the user to confirm
+            tokio::task::spawn_blocking(move || {
+                let device = device.lock().map_err(|_| LedgerError::Transport("device poisoned".to_string()))?;
+                let hid = |e: hidapi::HidError| LedgerError::Transport(e.to_string());
+                for packet in framing::wrap(&command) {
+                    // Leading zero is the HID report ID
+                    let mut report = [0u8; PACKET_SIZE + 1];
+                    report[1..].copy_from_slice(&packet);
+                    device.write(&report).map_err(hid)?;
+                }
+                framing::unwrap(|| {
+                    let mut packet = [0u8; PACKET_SIZE];
+                    let n = device
+                        .read_timeout(&mut packet, READ_TIMEOUT.as_millis() as i32)
+                        .map_err(hid)?;
+                    if n == 0 {
+                        return Err(LedgerError::Transport("timed out waiting for the device".to_string()));
+                    }
+                    Ok(packet)
+                })
+            })
+            .await
+            .map_err(|e| LedgerError::Transport(e.to_string()))?
+        }
+    }
+}
+
+/// Scripted transport for tests: checks each command and replies in order
+#[cfg(test)]
+pub(crate) mod mock {
+    use super::Transport;
+    use crate::Result;
+    use async_trait::async_trait;
+    use std::collections::VecDeque;
+    use std::sync::Mutex;
+
+    /// Expected command (if checked) and the response to it
+    type Step = (Option<Vec<u8>>, Vec<u8>);
+
+    #[derive(Default)]
+    pub struct MockTransport {
+        script: Mutex<VecDeque<Step>>,
+    }
+
+    impl MockTransport {
+        /// Replies `response` (hex, status word included) to the next command
+        pub fn reply(self, response: &str) -> Self {
+            self.script.lock().unwrap().push_back((None, hex::decode(response).unwrap()));
+            self
+        }
+
+        /// Expects the next command to be `command` (hex) and replies `response`
+        pub fn expect(self, command: &str, response: &str) -> Self {
+            let command = hex::decode(command).unwrap();
+            self.script
+                .lock()
+                .unwrap()
+                .push_back((Some(command), hex::decode(response).unwrap()));
+            self
+        }
+    }
+
+    #[async_trait]
+    impl Transport for MockTransport {
+        async fn exchange(&self, command: &[u8]) -> Result<Vec<u8>> {
+            let (expected, response) = self.script.lock().unwrap().pop_front().expect("unscripted APDU");
+            if let Some(expected) = expected {
+                assert_eq!(hex::encode(command), hex::encode(expected));
+            }
+            Ok(response)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hid_framing_roundtrip() {
+        let command: Vec<u8> = (0..150u8).collect();
+        let packets = framing::wrap(&command);
+        assert_eq!(packets.len(), 3);
+        assert_eq!(&packets[0][..7], &[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 150]);
+        assert_eq!(&packets[2][..5], &[0x01, 0x01, 0x05, 0x00, 0x02]);
+
+        let mut packets = packets.into_iter();
+        let response = framing::unwrap(|| Ok(packets.next().unwrap())).unwrap();
+        assert_eq!(response, command);
+    }
+
+    #[tokio::test]
+    async fn test_tcp_transport() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            let len = socket.read_u32().await.unwrap() as usize;
+            let mut apdu = vec![0u8; len];
+            socket.read_exact(&mut apdu).await.unwrap();
+            assert_eq!(apdu, vec![0xE0, 0x06, 0x00, 0x00, 0x00]);
+            socket.write_all(&[0, 0, 0, 2, 0xAB, 0xCD, 0x90, 0x00]).await.unwrap();
+        });
+        let transport = TcpTransport::connect(addr).await.unwrap();
+        let data = exchange(&transport, &Apdu::new(0xE0, 0x06, 0, 0, vec![])).await.unwrap();
+        assert_eq!(data, vec![0xAB, 0xCD]);
+    }
+}
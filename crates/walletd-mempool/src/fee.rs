@@ -0,0 +1,198 @@
+//! Fee rate comparisons and replacement rates
+//!
+//! Rates of different kinds (say a legacy gas price against EIP-1559 fees)
+//! are not comparable; the functions here return `None` for them.
+
+use walletd_traits::FeeRate;
+
+/// Returns whether `rate` pays less than `market`
+///
+/// EIP-1559 fees are below the market if either the fee cap or the tip is.
+pub fn is_below(rate: &FeeRate, market: &FeeRate) -> Option<bool> {
+    match (rate, market) {
+        (
+            FeeRate::Eip1559 {
+                max_fee_per_gas: fee,
+                max_priority_fee_per_gas: tip,
+            },
+            FeeRate::Eip1559 {
+                max_fee_per_gas: market_fee,
+                max_priority_fee_per_gas: market_tip,
+            },
+        ) => Some(fee < market_fee || tip < market_tip),
+        (FeeRate::GasPrice(price), FeeRate::GasPrice(market)) => Some(price < market),
+        (FeeRate::SatPerVbyte(rate), FeeRate::SatPerVbyte(market)) => Some(rate < market),
+        (FeeRate::MicroLamportsPerCu(price), FeeRate::MicroLamportsPerCu(market)) => {
+            Some(price < market)
+        }
+        (
+            FeeRate::CosmosGasPrice { amount, denom },
+            FeeRate::CosmosGasPrice {
+                amount: market,
+                denom: market_denom,
+            },
+        ) if denom == market_denom => Some(amount < market),
+        _ => None,
+    }
+}
+
+/// Returns whether `rate` pays more than `cap` allows
+pub fn exceeds(rate: &FeeRate, cap: &FeeRate) -> Option<bool> {
+    match (rate, cap) {
+        (
+            FeeRate::Eip1559 {
+                max_fee_per_gas: fee,
+                max_priority_fee_per_gas: tip,
+            },
+            FeeRate::Eip1559 {
+                max_fee_per_gas: cap_fee,
+                max_priority_fee_per_gas: cap_tip,
+            },
+        ) => Some(fee > cap_fee || tip > cap_tip),
+        _ => is_below(cap, rate),
+    }
+}
+
+/// The rate for a replacement of a transaction paying `old`
+///
+/// Replacements must outbid the original: every component is raised by at
+/// least `min_increase_percent` (EVM nodes require 10%), and Bitcoin rates
+/// by at least 1 sat/vB to cover the relay fee of the replacement (BIP 125).
+/// The result is never below `market`.
+pub fn replacement_rate(
+    old: &FeeRate,
+    market: Option<&FeeRate>,
+    min_increase_percent: u32,
+) -> Option<FeeRate> {
+    let raise = |value: u128, market: u128| bump_int(value, min_increase_percent).max(market);
+    let factor = 1.0 + f64::from(min_increase_percent) / 100.0;
+    let bumped = match (old, market) {
+        (
+            FeeRate::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            },
+            market,
+        ) => {
+            let (market_fee, market_tip) = match market {
+                Some(FeeRate::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                }) => (*max_fee_per_gas, *max_priority_fee_per_gas),
+                Some(_) => return None,
+                None => (0, 0),
+            };
+            let tip = raise(*max_priority_fee_per_gas, market_tip);
+            FeeRate::Eip1559 {
+                max_fee_per_gas: raise(*max_fee_per_gas, market_fee).max(tip),
+                max_priority_fee_per_gas: tip,
+            }
+        }
+        (FeeRate::GasPrice(price), None) => FeeRate::GasPrice(raise(*price, 0)),
+        (FeeRate::GasPrice(price), Some(FeeRate::GasPrice(market))) => {
+            FeeRate::GasPrice(raise(*price, *market))
+        }
+        (FeeRate::SatPerVbyte(rate), market) => {
+            let market = match market {
+                Some(FeeRate::SatPerVbyte(market)) => *market,
+                Some(_) => return None,
+                None => 0.0,
+            };
+            FeeRate::SatPerVbyte((rate * factor).max(rate + 1.0).max(market))
+        }
+        (FeeRate::MicroLamportsPerCu(price), market) => {
+            let market = match market {
+                Some(FeeRate::MicroLamportsPerCu(market)) => *market,
+                Some(_) => return None,
+                None => 0,
+            };
+            let bumped = bump_int(u128::from(*price), min_increase_percent).max(1);
+            FeeRate::MicroLamportsPerCu(u64::try_from(bumped).unwrap_or(u64::MAX).max(market))
+        }
+        (FeeRate::CosmosGasPrice { amount, denom }, market) => {
+            let market = match market {
+                Some(FeeRate::CosmosGasPrice {
+                    amount,
+                    denom: market_denom,
+                }) if market_denom == denom => *amount,
+                Some(_) => return None,
+                None => 0.0,
+            };
+            FeeRate::CosmosGasPrice {
+                amount: (amount * factor).max(market),
+                denom: denom.clone(),
+            }
+        }
+        (FeeRate::GasPrice(_), Some(_)) => return None,
+    };
+    Some(bumped)
+}
+
+/// Raises `value` by `percent`, rounding up and adding at least one unit
+fn bump_int(value: u128, percent: u32) -> u128 {
+    let raised = value
+        .saturating_mul(100 + u128::from(percent))
+        .div_ceil(100);
+    raised.max(value.saturating_add(1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const GWEI: u128 = 1_000_000_000;
+
+    #[test]
+    fn test_comparisons() {
+        let ours = FeeRate::Eip1559 {
+            max_fee_per_gas: 30 * GWEI,
+            max_priority_fee_per_gas: GWEI,
+        };
+        let market = FeeRate::Eip1559 {
+            max_fee_per_gas: 25 * GWEI,
+            max_priority_fee_per_gas: 2 * GWEI,
+        };
+        assert_eq!(is_below(&ours, &market), Some(true));
+        assert_eq!(is_below(&ours, &FeeRate::GasPrice(GWEI)), None);
+        assert_eq!(
+            is_below(&FeeRate::SatPerVbyte(5.0), &FeeRate::SatPerVbyte(4.0)),
+            Some(false)
+        );
+        assert_eq!(
+            exceeds(&FeeRate::GasPrice(101), &FeeRate::GasPrice(100)),
+            Some(true)
+        );
+        assert_eq!(exceeds(&ours, &market), Some(true));
+    }
+
+    #[test]
+    fn test_replacement_rates() {
+        let old = FeeRate::Eip1559 {
+            max_fee_per_gas: 30 * GWEI,
+            max_priority_fee_per_gas: GWEI,
+        };
+        let market = FeeRate::Eip1559 {
+            max_fee_per_gas: 25 * GWEI,
+            max_priority_fee_per_gas: 2 * GWEI,
+        };
+        assert_eq!(
+            replacement_rate(&old, Some(&market), 10),
+            Some(FeeRate::Eip1559 {
+                max_fee_per_gas: 33 * GWEI,
+                max_priority_fee_per_gas: 2 * GWEI,
+            })
+        );
+        assert_eq!(
+            replacement_rate(&FeeRate::SatPerVbyte(2.0), None, 10),
+            Some(FeeRate::SatPerVbyte(3.0))
+        );
+        assert_eq!(
+            replacement_rate(&FeeRate::MicroLamportsPerCu(0), None, 10),
+            Some(FeeRate::MicroLamportsPerCu(1))
+        );
+        assert_eq!(
+            replacement_rate(&FeeRate::GasPrice(10), Some(&market), 10),
+            None
+        );
+    }
+}
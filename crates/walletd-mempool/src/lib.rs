@@ -0,0 +1,83 @@
+//! # WalletD Mempool
+//!
+//! Watches a wallet's pending transactions until they settle. On each poll
+//! a [`MempoolMonitor`] asks every chain's [`TxTracker`] where each
+//! transaction stands and reports:
+//!
+//! - confirmed or failed transactions
+//! - stuck transactions: pending past [`BumpPolicy::stuck_after`] and
+//!   paying less than the market rate for the target speed
+//! - dropped transactions, missing from the mempool for
+//!   [`BumpPolicy::drop_after`]
+//! - transactions replaced by someone else
+//!
+//! With a [`FeeBumper`] and [`BumpPolicy::auto_bump`], stuck transactions
+//! are replaced at a higher fee (RBF on Bitcoin, same-nonce replacement on
+//! EVM chains) and the replacement is tracked in their place. Earlier
+//! versions are still watched, since any of them may be the one that
+//! confirms.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_mempool::{BumpPolicy, ChainMonitor, MempoolMonitor, PendingTx};
+//!
+//! let monitor = Arc::new(MempoolMonitor::new().chain(
+//!     ChainMonitor::new("ethereum", Arc::new(evm_tracker))
+//!         .estimator(Arc::new(fee_history))
+//!         .bumper(Arc::new(evm_bumper))
+//!         .policy(BumpPolicy::default().auto_bump(true)),
+//! ));
+//! let mut events = monitor.subscribe();
+//! monitor.track(PendingTx::new("ethereum", hash, rate).nonce(7))?;
+//! monitor.clone().spawn(Duration::from_secs(15));
+//!
+//! while let Ok(event) = events.recv().await {
+//!     println!("{:?}", event);
+//! }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod fee;
+pub mod monitor;
+
+pub use monitor::{
+    BumpPolicy, ChainMonitor, FeeBumper, MempoolEvent, MempoolMonitor, PendingTx, TxState,
+    TxTracker,
+};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Mempool monitoring errors
+#[derive(Error, Debug)]
+pub enum MempoolError {
+    /// No monitor is registered for the chain
+    #[error("No mempool monitor for chain {0}")]
+    UnknownChain(String),
+
+    /// The transaction is already tracked
+    #[error("Transaction already tracked: {0}")]
+    AlreadyTracked(String),
+
+    /// A tracker, estimator or bumper failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for mempool monitoring
+pub type Result<T> = std::result::Result<T, MempoolError>;
+
+impl From<MempoolError> for WalletdError {
+    fn from(e: MempoolError) -> Self {
+        match e {
+            MempoolError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            e => WalletdError::External {
+                message: e.to_string(),
+            },
+        }
+    }
+}
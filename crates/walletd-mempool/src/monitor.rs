@@ -0,0 +1,720 @@
+//! Pending transaction tracking
+
+use crate::fee::{exceeds, is_below, replacement_rate};
+use crate::{MempoolError, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::broadcast;
+use walletd_traits::{FeeEstimator, FeeRate, FeeSpeed, TxHash, WalletResult};
+
+/// A submitted transaction that has not settled yet
+#[derive(Debug, Clone, PartialEq)]
+pub struct PendingTx {
+    /// Chain name, e.g. `ethereum`
+    pub chain: String,
+    /// Transaction hash
+    pub hash: TxHash,
+    /// Fee rate the transaction pays
+    pub fee_rate: FeeRate,
+    /// Account nonce, on chains that have one
+    pub nonce: Option<u64>,
+    /// When the transaction was broadcast (Unix seconds)
+    pub submitted_at: u64,
+    /// Signed transaction bytes, for bumpers that rebuild from them
+    pub raw: Option<Vec<u8>>,
+}
+
+impl PendingTx {
+    /// A transaction broadcast now
+    pub fn new(chain: impl Into<String>, hash: TxHash, fee_rate: FeeRate) -> Self {
+        Self {
+            chain: chain.into(),
+            hash,
+            fee_rate,
+            nonce: None,
+            submitted_at: now(),
+            raw: None,
+        }
+    }
+
+    /// Sets the account nonce
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// Sets the broadcast time
+    pub fn submitted_at(mut self, timestamp: u64) -> Self {
+        self.submitted_at = timestamp;
+        self
+    }
+
+    /// Attaches the signed transaction
+    pub fn raw(mut self, raw: Vec<u8>) -> Self {
+        self.raw = Some(raw);
+        self
+    }
+}
+
+/// Where a transaction stands, as seen by a [`TxTracker`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TxState {
+    /// In the mempool
+    Pending,
+    /// Included in a block
+    Confirmed {
+        /// Block height, if known
+        block_height: Option<u64>,
+    },
+    /// Included but reverted, or rejected
+    Failed {
+        /// Failure reason, if known
+        reason: Option<String>,
+    },
+    /// A conflicting transaction (same nonce or inputs) took its place
+    Replaced {
+        /// The conflicting transaction, if known
+        by: Option<TxHash>,
+    },
+    /// Neither in the mempool nor in a block
+    NotFound,
+}
+
+/// Reports the state of transactions on one chain
+///
+/// EVM trackers typically combine `eth_getTransactionReceipt`,
+/// `eth_getTransactionByHash` and the account nonce; Bitcoin trackers look
+/// up the transaction and the spenders of its inputs.
+#[async_trait]
+pub trait TxTracker: Send + Sync {
+    /// Returns the state of `tx`
+    async fn state(&self, tx: &PendingTx) -> WalletResult<TxState>;
+}
+
+/// Replaces a stuck transaction with one paying a higher fee
+///
+/// Bitcoin bumpers re-sign the same inputs under BIP 125 replace-by-fee;
+/// EVM bumpers re-sign the same nonce with higher fees.
+#[async_trait]
+pub trait FeeBumper: Send + Sync {
+    /// Broadcasts a replacement for `tx` paying `fee_rate`, returning it
+    async fn bump(&self, tx: &PendingTx, fee_rate: &FeeRate) -> WalletResult<PendingTx>;
+}
+
+/// When a transaction counts as stuck or dropped, and how to bump it
+#[derive(Debug, Clone, PartialEq)]
+pub struct BumpPolicy {
+    /// Time pending before a transaction can count as stuck
+    pub stuck_after: Duration,
+    /// Time missing from the mempool before a transaction counts as dropped
+    pub drop_after: Duration,
+    /// Market speed a transaction must pay to not count as stuck
+    pub target: FeeSpeed,
+    /// Minimum raise of each fee component per bump, in percent
+    pub min_increase_percent: u32,
+    /// Highest rate a bump may pay
+    pub max_fee_rate: Option<FeeRate>,
+    /// Most replacements per transaction
+    pub max_bumps: u32,
+    /// Bump stuck transactions automatically
+    pub auto_bump: bool,
+}
+
+impl Default for BumpPolicy {
+    fn default() -> Self {
+        Self {
+            stuck_after: Duration::from_secs(600),
+            drop_after: Duration::from_secs(1800),
+            target: FeeSpeed::Standard,
+            min_increase_percent: 10,
+            max_fee_rate: None,
+            max_bumps: 3,
+            auto_bump: false,
+        }
+    }
+}
+
+impl BumpPolicy {
+    /// Sets how long a transaction may pend before it can count as stuck
+    pub fn stuck_after(mut self, duration: Duration) -> Self {
+        self.stuck_after = duration;
+        self
+    }
+
+    /// Sets how long a transaction may be missing before it counts as dropped
+    pub fn drop_after(mut self, duration: Duration) -> Self {
+        self.drop_after = duration;
+        self
+    }
+
+    /// Sets the market speed transactions must keep up with
+    pub fn target(mut self, speed: FeeSpeed) -> Self {
+        self.target = speed;
+        self
+    }
+
+    /// Sets the minimum raise per bump, in percent
+    pub fn min_increase_percent(mut self, percent: u32) -> Self {
+        self.min_increase_percent = percent;
+        self
+    }
+
+    /// Caps the rate bumps may pay
+    pub fn max_fee_rate(mut self, rate: FeeRate) -> Self {
+        self.max_fee_rate = Some(rate);
+        self
+    }
+
+    /// Sets the most replacements per transaction
+    pub fn max_bumps(mut self, bumps: u32) -> Self {
+        self.max_bumps = bumps;
+        self
+    }
+
+    /// Turns automatic bumping on or off
+    pub fn auto_bump(mut self, enabled: bool) -> Self {
+        self.auto_bump = enabled;
+        self
+    }
+}
+
+/// Monitoring setup for one chain
+pub struct ChainMonitor {
+    chain: String,
+    tracker: Arc<dyn TxTracker>,
+    estimator: Option<Arc<dyn FeeEstimator>>,
+    bumper: Option<Arc<dyn FeeBumper>>,
+    policy: BumpPolicy,
+}
+
+impl ChainMonitor {
+    /// Monitors `chain` with `tracker`
+    pub fn new(chain: impl Into<String>, tracker: Arc<dyn TxTracker>) -> Self {
+        Self {
+            chain: chain.into(),
+            tracker,
+            estimator: None,
+            bumper: None,
+            policy: BumpPolicy::default(),
+        }
+    }
+
+    /// Compares pending fees with this estimator's market rates
+    ///
+    /// Without one, any transaction pending past
+    /// [`BumpPolicy::stuck_after`] counts as stuck.
+    pub fn estimator(mut self, estimator: Arc<dyn FeeEstimator>) -> Self {
+        self.estimator = Some(estimator);
+        self
+    }
+
+    /// Replaces stuck transactions with this bumper
+    pub fn bumper(mut self, bumper: Arc<dyn FeeBumper>) -> Self {
+        self.bumper = Some(bumper);
+        self
+    }
+
+    /// Sets the bump policy
+    pub fn policy(mut self, policy: BumpPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+}
+
+/// Something that happened to a tracked transaction
+#[derive(Debug, Clone, PartialEq)]
+pub enum MempoolEvent {
+    /// The transaction (or one of its versions) was included
+    Confirmed {
+        /// Version that confirmed
+        hash: TxHash,
+        /// Block height, if known
+        block_height: Option<u64>,
+    },
+    /// The transaction failed
+    Failed {
+        /// Version that failed
+        hash: TxHash,
+        /// Failure reason, if known
+        reason: Option<String>,
+    },
+    /// The transaction pays too little to confirm soon
+    Stuck {
+        /// Stuck version
+        hash: TxHash,
+        /// Rate it pays
+        fee_rate: FeeRate,
+        /// Market rate for the target speed, if known
+        market: Option<FeeRate>,
+    },
+    /// The transaction left the mempool without confirming
+    Dropped {
+        /// Dropped version
+        hash: TxHash,
+    },
+    /// A transaction not sent by the monitor replaced it
+    Replaced {
+        /// Replaced version
+        hash: TxHash,
+        /// The replacement, if known
+        by: Option<TxHash>,
+    },
+    /// The monitor replaced a stuck transaction
+    Bumped {
+        /// Stuck version
+        replaced: TxHash,
+        /// Replacement
+        by: TxHash,
+        /// Rate the replacement pays
+        fee_rate: FeeRate,
+    },
+    /// A bump was not sent
+    BumpFailed {
+        /// Stuck version
+        hash: TxHash,
+        /// Why not
+        reason: String,
+    },
+}
+
+/// A transaction and its replacements
+#[derive(Clone)]
+struct Tracked {
+    /// Versions, oldest first; the last is current
+    versions: Vec<PendingTx>,
+    /// When the current version was first seen missing
+    missing_since: Option<u64>,
+    /// Whether the current version was reported stuck
+    stuck_reported: bool,
+}
+
+/// What a poll decided for a tracked transaction
+enum Outcome {
+    Keep(Tracked),
+    Done,
+}
+
+/// Tracks pending transactions across chains
+pub struct MempoolMonitor {
+    chains: HashMap<String, ChainMonitor>,
+    tracked: Mutex<HashMap<TxHash, Tracked>>,
+    events: broadcast::Sender<MempoolEvent>,
+}
+
+impl Default for MempoolMonitor {
+    fn default() -> Self {
+        Self {
+            chains: HashMap::new(),
+            tracked: Mutex::new(HashMap::new()),
+            events: broadcast::channel(256).0,
+        }
+    }
+}
+
+impl fmt::Debug for MempoolMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MempoolMonitor")
+            .field("chains", &self.chains.keys().collect::<Vec<_>>())
+            .field("tracked", &self.lock().len())
+            .finish()
+    }
+}
+
+impl MempoolMonitor {
+    /// Creates a monitor without chains
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a chain, replacing any other with the same name
+    pub fn chain(mut self, chain: ChainMonitor) -> Self {
+        self.chains.insert(chain.chain.clone(), chain);
+        self
+    }
+
+    /// Starts tracking a transaction
+    pub fn track(&self, tx: PendingTx) -> Result<()> {
+        if !self.chains.contains_key(&tx.chain) {
+            return Err(MempoolError::UnknownChain(tx.chain));
+        }
+        let mut tracked = self.lock();
+        if tracked.contains_key(&tx.hash) {
+            return Err(MempoolError::AlreadyTracked(tx.hash.0));
+        }
+        tracked.insert(
+            tx.hash.clone(),
+            Tracked {
+                versions: vec![tx],
+                missing_since: None,
+                stuck_reported: false,
+            },
+        );
+        Ok(())
+    }
+
+    /// Stops tracking a transaction, by its original hash
+    pub fn untrack(&self, hash: &TxHash) -> bool {
+        self.lock().remove(hash).is_some()
+    }
+
+    /// Returns the current version of every tracked transaction
+    pub fn pending(&self) -> Vec<PendingTx> {
+        self.lock()
+            .values()
+            .filter_map(|t| t.versions.last().cloned())
+            .collect()
+    }
+
+    /// Subscribes to events from later polls
+    pub fn subscribe(&self) -> broadcast::Receiver<MempoolEvent> {
+        self.events.subscribe()
+    }
+
+    /// Checks every tracked transaction once
+    pub async fn poll(&self) -> Vec<MempoolEvent> {
+        self.poll_at(now()).await
+    }
+
+    /// Checks every tracked transaction once, at `now` (Unix seconds)
+    pub async fn poll_at(&self, now: u64) -> Vec<MempoolEvent> {
+        let snapshot: Vec<(TxHash, Tracked)> = self
+            .lock()
+            .iter()
+            .map(|(hash, tracked)| (hash.clone(), tracked.clone()))
+            .collect();
+
+        let mut events = Vec::new();
+        for (original, tracked) in snapshot {
+            let Some(chain) = self.chains.get(&tracked.versions[0].chain) else {
+                continue;
+            };
+            let outcome = check(chain, tracked, now, &mut events).await;
+            let mut state = self.lock();
+            // Skip transactions untracked while we were checking
+            if let Some(entry) = state.get_mut(&original) {
+                match outcome {
+                    Outcome::Keep(tracked) => *entry = tracked,
+                    Outcome::Done => {
+                        state.remove(&original);
+                    }
+                }
+            }
+        }
+        for event in &events {
+            // Nobody listening is fine; the events are also returned
+            let _ = self.events.send(event.clone());
+        }
+        events
+    }
+
+    /// Polls every `interval` on the Tokio runtime
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.poll().await;
+            }
+        })
+    }
+
+    fn lock(&self) -> MutexGuard<'_, HashMap<TxHash, Tracked>> {
+        self.tracked.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+async fn check(
+    chain: &ChainMonitor,
+    mut tracked: Tracked,
+    now: u64,
+    events: &mut Vec<MempoolEvent>,
+) -> Outcome {
+    // Any version may be the one that lands, so look at all of them
+    let mut current_state = None;
+    for (i, version) in tracked.versions.iter().enumerate().rev() {
+        let state = match chain.tracker.state(version).await {
+            Ok(state) => state,
+            Err(e) => {
+                tracing::warn!(hash = %version.hash.0, error = %e, "failed to check transaction");
+                return Outcome::Keep(tracked);
+            }
+        };
+        match state {
+            TxState::Confirmed { block_height } => {
+                events.push(MempoolEvent::Confirmed {
+                    hash: version.hash.clone(),
+                    block_height,
+                });
+                return Outcome::Done;
+            }
+            TxState::Failed { reason } => {
+                events.push(MempoolEvent::Failed {
+                    hash: version.hash.clone(),
+                    reason,
+                });
+                return Outcome::Done;
+            }
+            state if i == tracked.versions.len() - 1 => current_state = Some(state),
+            _ => {}
+        }
+    }
+
+    let current = tracked
+        .versions
+        .last()
+        .expect("tracked transactions have a version");
+    match current_state {
+        Some(TxState::Replaced { by }) => {
+            events.push(MempoolEvent::Replaced {
+                hash: current.hash.clone(),
+                by,
+            });
+            return Outcome::Done;
+        }
+        Some(TxState::NotFound) => {
+            let since = *tracked.missing_since.get_or_insert(now);
+            if now.saturating_sub(since) >= chain.policy.drop_after.as_secs() {
+                events.push(MempoolEvent::Dropped {
+                    hash: current.hash.clone(),
+                });
+                return Outcome::Done;
+            }
+            return Outcome::Keep(tracked);
+        }
+        _ => tracked.missing_since = None,
+    }
+
+    let policy = &chain.policy;
+    if tracked.stuck_reported
+        || now.saturating_sub(current.submitted_at) < policy.stuck_after.as_secs()
+    {
+        return Outcome::Keep(tracked);
+    }
+    let market = match &chain.estimator {
+        None => None,
+        Some(estimator) => match estimator.fee_options().await {
+            Ok(options) => options.get(policy.target).map(|o| o.rate.clone()),
+            Err(e) => {
+                tracing::warn!(chain = %chain.chain, error = %e, "failed to fetch fee options");
+                return Outcome::Keep(tracked);
+            }
+        },
+    };
+    let stuck = match &market {
+        Some(market) => is_below(&current.fee_rate, market).unwrap_or(false),
+        None => chain.estimator.is_none(),
+    };
+    if !stuck {
+        return Outcome::Keep(tracked);
+    }
+
+    tracked.stuck_reported = true;
+    events.push(MempoolEvent::Stuck {
+        hash: current.hash.clone(),
+        fee_rate: current.fee_rate.clone(),
+        market: market.clone(),
+    });
+    let Some(bumper) = chain.bumper.as_ref().filter(|_| policy.auto_bump) else {
+        return Outcome::Keep(tracked);
+    };
+
+    let bump_failed = |reason: &str| MempoolEvent::BumpFailed {
+        hash: current.hash.clone(),
+        reason: reason.to_string(),
+    };
+    if tracked.versions.len() > policy.max_bumps as usize {
+        events.push(bump_failed("bump limit reached"));
+        return Outcome::Keep(tracked);
+    }
+    let Some(rate) = replacement_rate(
+        &current.fee_rate,
+        market.as_ref(),
+        policy.min_increase_percent,
+    ) else {
+        events.push(bump_failed("market rate is of a different kind"));
+        return Outcome::Keep(tracked);
+    };
+    if let Some(cap) = &policy.max_fee_rate {
+        if exceeds(&rate, cap).unwrap_or(true) {
+            events.push(bump_failed("replacement would exceed the maximum fee rate"));
+            return Outcome::Keep(tracked);
+        }
+    }
+    match bumper.bump(current, &rate).await {
+        Ok(replacement) => {
+            events.push(MempoolEvent::Bumped {
+                replaced: current.hash.clone(),
+                by: replacement.hash.clone(),
+                fee_rate: replacement.fee_rate.clone(),
+            });
+            tracked.versions.push(replacement);
+            tracked.stuck_reported = false;
+        }
+        Err(e) => events.push(bump_failed(&e.to_string())),
+    }
+    Outcome::Keep(tracked)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::{FeeOption, FeeOptions};
+
+    /// Reports states from a table, `Pending` by default
+    #[derive(Default)]
+    struct Table(Mutex<HashMap<String, TxState>>);
+
+    impl Table {
+        fn set(&self, hash: &str, state: TxState) {
+            self.0.lock().unwrap().insert(hash.to_string(), state);
+        }
+    }
+
+    #[async_trait]
+    impl TxTracker for Table {
+        async fn state(&self, tx: &PendingTx) -> WalletResult<TxState> {
+            Ok(self
+                .0
+                .lock()
+                .unwrap()
+                .get(&tx.hash.0)
+                .cloned()
+                .unwrap_or(TxState::Pending))
+        }
+    }
+
+    struct Market(u128);
+
+    #[async_trait]
+    impl FeeEstimator for Market {
+        fn chain(&self) -> &str {
+            "ethereum"
+        }
+
+        async fn fee_options(&self) -> WalletResult<FeeOptions> {
+            Ok(FeeOptions {
+                chain: "ethereum".into(),
+                options: vec![FeeOption {
+                    speed: FeeSpeed::Standard,
+                    rate: FeeRate::GasPrice(self.0),
+                    estimated_seconds: None,
+                }],
+                updated_at: 0,
+            })
+        }
+    }
+
+    struct Bumper;
+
+    #[async_trait]
+    impl FeeBumper for Bumper {
+        async fn bump(&self, tx: &PendingTx, fee_rate: &FeeRate) -> WalletResult<PendingTx> {
+            let hash = TxHash::new(format!("{}-bumped", tx.hash.0));
+            Ok(PendingTx::new(&tx.chain, hash, fee_rate.clone())
+                .submitted_at(tx.submitted_at + 700))
+        }
+    }
+
+    fn monitor(tracker: Arc<Table>, policy: BumpPolicy) -> MempoolMonitor {
+        MempoolMonitor::new().chain(
+            ChainMonitor::new("ethereum", tracker)
+                .estimator(Arc::new(Market(100)))
+                .bumper(Arc::new(Bumper))
+                .policy(policy),
+        )
+    }
+
+    fn tx(hash: &str, price: u128) -> PendingTx {
+        PendingTx::new("ethereum", TxHash::new(hash), FeeRate::GasPrice(price)).submitted_at(0)
+    }
+
+    #[tokio::test]
+    async fn test_stuck_bump_and_confirm() {
+        let table = Arc::new(Table::default());
+        let policy = BumpPolicy::default()
+            .auto_bump(true)
+            .max_fee_rate(FeeRate::GasPrice(150));
+        let monitor = monitor(table.clone(), policy);
+        monitor.track(tx("0xa", 50)).unwrap();
+        monitor.track(tx("0xb", 200)).unwrap();
+        assert!(monitor.track(tx("0xa", 50)).is_err());
+
+        // Not stuck yet, and 0xb pays above the market
+        assert!(monitor.poll_at(60).await.is_empty());
+
+        let events = monitor.poll_at(601).await;
+        assert!(matches!(&events[0], MempoolEvent::Stuck { hash, .. } if hash.0 == "0xa"));
+        assert_eq!(
+            events[1],
+            MempoolEvent::Bumped {
+                replaced: TxHash::new("0xa"),
+                by: TxHash::new("0xa-bumped"),
+                fee_rate: FeeRate::GasPrice(100),
+            }
+        );
+        assert_eq!(events.len(), 2);
+
+        // The original confirms after all
+        table.set(
+            "0xa",
+            TxState::Confirmed {
+                block_height: Some(9),
+            },
+        );
+        table.set("0xb", TxState::Failed { reason: None });
+        let events = monitor.poll_at(700).await;
+        assert!(events.contains(&MempoolEvent::Confirmed {
+            hash: TxHash::new("0xa"),
+            block_height: Some(9),
+        }));
+        assert!(matches!(&events[..], [_, _]));
+        assert!(monitor.pending().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_dropped_replaced_and_capped() {
+        let table = Arc::new(Table::default());
+        let policy = BumpPolicy::default()
+            .auto_bump(true)
+            .max_fee_rate(FeeRate::GasPrice(99));
+        let monitor = monitor(table.clone(), policy);
+        let mut events = monitor.subscribe();
+        monitor.track(tx("0xdrop", 200)).unwrap();
+        monitor.track(tx("0xswap", 200)).unwrap();
+        monitor.track(tx("0xcheap", 1)).unwrap();
+        table.set("0xdrop", TxState::NotFound);
+        table.set("0xswap", TxState::Replaced { by: None });
+
+        let first = monitor.poll_at(601).await;
+        assert!(first.contains(&MempoolEvent::Replaced {
+            hash: TxHash::new("0xswap"),
+            by: None,
+        }));
+        // Catching up with the market would break the cap
+        assert!(first
+            .iter()
+            .any(|e| matches!(e, MempoolEvent::BumpFailed { hash, .. } if hash.0 == "0xcheap")));
+        assert_eq!(first.len(), 3);
+        assert_eq!(events.recv().await.unwrap(), first[0]);
+
+        // Stuck transactions are reported once
+        let later = monitor.poll_at(601 + 1800).await;
+        assert_eq!(
+            later,
+            vec![MempoolEvent::Dropped {
+                hash: TxHash::new("0xdrop"),
+            }]
+        );
+        assert_eq!(monitor.pending().len(), 1);
+    }
+}
@@ -0,0 +1,256 @@
+//! BIP-322 simple signatures
+//!
+//! The message is committed to by a virtual `to_spend` transaction paying
+//! the signer's address; the signature is the witness of a virtual
+//! `to_sign` transaction spending it, base64-encoded. Native SegWit v0
+//! (P2WPKH) addresses are supported.
+
+use crate::{
+    base64_field, hash160, MessageError, MessageSigner, MessageStandard, Result, SignedMessage,
+};
+use async_trait::async_trait;
+use base64::Engine;
+use bech32::{Fe32, Hrp};
+use sha2::{Digest, Sha256};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// `SIGHASH_ALL`
+const SIGHASH_ALL: u8 = 0x01;
+
+/// Signs BIP-322 messages for a P2WPKH address
+pub struct Bip322Signer {
+    signer: Box<dyn Signer>,
+    address: String,
+    program: [u8; 20],
+}
+
+impl std::fmt::Debug for Bip322Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Bip322Signer")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Bip322Signer {
+    /// Signs for the key's P2WPKH address with human-readable part `hrp`
+    /// (`bc` on mainnet, `tb` on testnet and signet)
+    pub fn new(signer: Box<dyn Signer>, hrp: &str) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            return Err(MessageError::Unsupported(
+                "BIP-322 needs a secp256k1 key".into(),
+            ));
+        }
+        let hrp = Hrp::parse(hrp).map_err(|e| MessageError::InvalidAddress(e.to_string()))?;
+        let program = hash160(&signer.public_key());
+        let address = bech32::segwit::encode_v0(hrp, &program)
+            .map_err(|e| MessageError::InvalidAddress(e.to_string()))?;
+        Ok(Self {
+            signer,
+            address,
+            program,
+        })
+    }
+}
+
+#[async_trait]
+impl MessageSigner for Bip322Signer {
+    fn standard(&self) -> MessageStandard {
+        MessageStandard::Bip322
+    }
+
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage> {
+        let sighash = sighash(&self.program, message.as_bytes());
+        let compact = self.signer.sign_hash(&sighash).await?;
+        let mut signature = secp256k1::ecdsa::Signature::from_compact(&compact)
+            .map_err(|e| MessageError::Malformed(format!("signature: {}", e)))?
+            .serialize_der()
+            .to_vec();
+        signature.push(SIGHASH_ALL);
+
+        let public_key = self.signer.public_key();
+        let mut witness = vec![2];
+        for item in [&signature, &public_key] {
+            // DER signatures and public keys are under 0xfd bytes, so their
+            // compact-size lengths are one byte
+            witness.push(item.len() as u8);
+            witness.extend_from_slice(item);
+        }
+        Ok(SignedMessage {
+            standard: MessageStandard::Bip322,
+            address: self.address.clone(),
+            message: message.to_string(),
+            signature: base64::engine::general_purpose::STANDARD.encode(witness),
+            public_key: None,
+            nep413: None,
+        })
+    }
+}
+
+/// Tagged hash of the message, committed to by `to_spend`
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    let tag = Sha256::digest(b"BIP0322-signed-message");
+    let mut hasher = Sha256::new();
+    hasher.update(tag);
+    hasher.update(tag);
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Checks a P2WPKH witness against the message's address
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    let (_, version, program) = bech32::segwit::decode(&signed.address)
+        .map_err(|e| MessageError::InvalidAddress(format!("{}: {}", signed.address, e)))?;
+    let program: [u8; 20] = program
+        .try_into()
+        .ok()
+        .filter(|_| version == Fe32::Q)
+        .ok_or_else(|| MessageError::Unsupported("BIP-322 for non-P2WPKH addresses".into()))?;
+
+    let witness = base64_field(&signed.signature, "BIP-322 signature")?;
+    let [signature, public_key] =
+        parse_witness(&witness).ok_or_else(|| MessageError::Malformed("BIP-322 witness".into()))?;
+    if hash160(&public_key) != program {
+        return Err(MessageError::BadSignature(signed.address.clone()));
+    }
+    let (sighash_type, der) = signature
+        .split_last()
+        .ok_or_else(|| MessageError::Malformed("BIP-322 signature".into()))?;
+    if *sighash_type != SIGHASH_ALL {
+        return Err(MessageError::Unsupported(
+            "sighash types other than ALL".into(),
+        ));
+    }
+
+    let signature = secp256k1::ecdsa::Signature::from_der(der)
+        .map_err(|e| MessageError::Malformed(format!("signature: {}", e)))?;
+    let key = secp256k1::PublicKey::from_slice(&public_key)
+        .map_err(|e| MessageError::Malformed(format!("public key: {}", e)))?;
+    let hash = secp256k1::Message::from_slice(&sighash(&program, signed.message.as_bytes()))
+        .expect("32-byte hash");
+    secp256k1::SECP256K1
+        .verify_ecdsa(&hash, &signature, &key)
+        .map_err(|_| MessageError::BadSignature(signed.address.clone()))
+}
+
+/// BIP-143 signature hash of `to_sign`'s only input
+fn sighash(program: &[u8; 20], message: &[u8]) -> [u8; 32] {
+    let script_pubkey = [&[0x00, 0x14][..], program].concat();
+
+    // to_spend: spends a null outpoint, commits to the message and pays
+    // the address
+    let mut to_spend = 0u32.to_le_bytes().to_vec();
+    to_spend.push(1);
+    to_spend.extend_from_slice(&[0u8; 32]);
+    to_spend.extend_from_slice(&u32::MAX.to_le_bytes());
+    to_spend.extend_from_slice(&[34, 0x00, 0x20]);
+    to_spend.extend_from_slice(&message_hash(message));
+    to_spend.extend_from_slice(&0u32.to_le_bytes());
+    to_spend.push(1);
+    to_spend.extend_from_slice(&0u64.to_le_bytes());
+    to_spend.push(script_pubkey.len() as u8);
+    to_spend.extend_from_slice(&script_pubkey);
+    to_spend.extend_from_slice(&0u32.to_le_bytes());
+    let txid = sha256d(&to_spend);
+
+    // to_sign: spends to_spend:0 into a single OP_RETURN output
+    let outpoint = [&txid[..], &0u32.to_le_bytes()].concat();
+    let script_code = [&[0x19, 0x76, 0xa9, 0x14][..], program, &[0x88, 0xac]].concat();
+    let outputs = [&0u64.to_le_bytes()[..], &[1, 0x6a]].concat();
+
+    let mut preimage = 0u32.to_le_bytes().to_vec();
+    preimage.extend_from_slice(&sha256d(&outpoint));
+    preimage.extend_from_slice(&sha256d(&0u32.to_le_bytes()));
+    preimage.extend_from_slice(&outpoint);
+    preimage.extend_from_slice(&script_code);
+    preimage.extend_from_slice(&0u64.to_le_bytes());
+    preimage.extend_from_slice(&0u32.to_le_bytes());
+    preimage.extend_from_slice(&sha256d(&outputs));
+    preimage.extend_from_slice(&0u32.to_le_bytes());
+    preimage.extend_from_slice(&u32::from(SIGHASH_ALL).to_le_bytes());
+    sha256d(&preimage)
+}
+
+fn sha256d(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+/// Splits a two-item witness stack
+fn parse_witness(witness: &[u8]) -> Option<[Vec<u8>; 2]> {
+    let (&count, mut rest) = witness.split_first()?;
+    if count != 2 {
+        return None;
+    }
+    let mut items = Vec::with_capacity(2);
+    for _ in 0..2 {
+        let (&len, tail) = rest.split_first()?;
+        if len >= 0xfd || tail.len() < len as usize {
+            return None;
+        }
+        let (item, tail) = tail.split_at(len as usize);
+        items.push(item.to_vec());
+        rest = tail;
+    }
+    if !rest.is_empty() {
+        return None;
+    }
+    items.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Secp256k1Signer;
+
+    /// Key and address of the BIP-322 test vectors
+    fn signer() -> Bip322Signer {
+        let wif = bs58::decode("L3VFeEujGtevx9w18HD1fhRbCH67Az2dpCymeRE1SoPK6XQtaN2k")
+            .with_check(None)
+            .into_vec()
+            .unwrap();
+        let key = Secp256k1Signer::from_slice(&wif[1..33]).unwrap();
+        Bip322Signer::new(Box::new(key), "bc").unwrap()
+    }
+
+    #[test]
+    fn test_message_hash_vectors() {
+        assert_eq!(
+            hex::encode(message_hash(b"")),
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+        );
+        assert_eq!(
+            hex::encode(message_hash(b"Hello World")),
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_vector() {
+        let signer = signer();
+        assert_eq!(
+            signer.address(),
+            "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l"
+        );
+        // The BIP lists two valid signatures; RFC 6979 nonces give the second
+        let signed = signer.sign_message("Hello World").await.unwrap();
+        assert_eq!(
+            signed.signature,
+            "AkgwRQIhAOzyynlqt93lOKJr+wmmxIens//zPzl9tqIOua93wO6MAiBi5n5EyAcPScOjf1lAqIUIQtr3zKNeavYabHyR8eGhowEhAsfxIAMZZEKUPYWI4BruhAQjzFT8FSFSajuFwrDL1Yhy"
+        );
+        verify(&SignedMessage {
+            signature: "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=".into(),
+            ..signed.clone()
+        })
+        .unwrap();
+        verify(&signed).unwrap();
+        let other = SignedMessage {
+            message: "Hello World!".into(),
+            ..signed
+        };
+        assert!(matches!(verify(&other), Err(MessageError::BadSignature(_))));
+    }
+}
@@ -0,0 +1,151 @@
+//! ADR-36 arbitrary data signatures
+//!
+//! The message is wrapped in an Amino JSON sign doc holding a single
+//! `sign/MsgSignData` with an empty chain id, zero fee and zero account
+//! number and sequence, so it can never be replayed as a transaction. The
+//! SHA-256 of the doc is signed, as Keplr's `signArbitrary` does.
+
+use crate::{
+    base64_field, hash160, MessageError, MessageSigner, MessageStandard, Result, SignedMessage,
+};
+use async_trait::async_trait;
+use base64::Engine;
+use bech32::{Bech32, Hrp};
+use sha2::{Digest, Sha256};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// Signs ADR-36 messages for a Cosmos SDK account
+pub struct Adr36Signer {
+    signer: Box<dyn Signer>,
+    address: String,
+}
+
+impl std::fmt::Debug for Adr36Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Adr36Signer")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Adr36Signer {
+    /// Signs for the key's address with prefix `hrp`, e.g. `cosmos`, `osmo`
+    pub fn new(signer: Box<dyn Signer>, hrp: &str) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            return Err(MessageError::Unsupported(
+                "ADR-36 needs a secp256k1 key".into(),
+            ));
+        }
+        let address = address(hrp, &signer.public_key())?;
+        Ok(Self { signer, address })
+    }
+}
+
+#[async_trait]
+impl MessageSigner for Adr36Signer {
+    fn standard(&self) -> MessageStandard {
+        MessageStandard::Adr36
+    }
+
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage> {
+        let doc = sign_doc(&self.address, message.as_bytes());
+        let signature = self.signer.sign_message(doc.as_bytes()).await?;
+        let base64 = base64::engine::general_purpose::STANDARD;
+        Ok(SignedMessage {
+            standard: MessageStandard::Adr36,
+            address: self.address.clone(),
+            message: message.to_string(),
+            signature: base64.encode(signature),
+            public_key: Some(base64.encode(self.signer.public_key())),
+            nep413: None,
+        })
+    }
+}
+
+/// The canonical (sorted, compact) Amino JSON sign doc
+pub fn sign_doc(signer: &str, data: &[u8]) -> String {
+    let data = base64::engine::general_purpose::STANDARD.encode(data);
+    format!(
+        concat!(
+            r#"{{"account_number":"0","chain_id":"","fee":{{"amount":[],"gas":"0"}},"memo":"","#,
+            r#""msgs":[{{"type":"sign/MsgSignData","value":{{"data":{},"signer":{}}}}}],"sequence":"0"}}"#
+        ),
+        serde_json::Value::from(data),
+        serde_json::Value::from(signer)
+    )
+}
+
+/// Checks the signature and that the public key belongs to the address
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    let public_key = base64_field(
+        signed
+            .public_key
+            .as_deref()
+            .ok_or_else(|| MessageError::Malformed("ADR-36 message without public key".into()))?,
+        "public key",
+    )?;
+    let (hrp, _) = bech32::decode(&signed.address)
+        .map_err(|e| MessageError::InvalidAddress(format!("{}: {}", signed.address, e)))?;
+    if address(hrp.as_str(), &public_key)? != signed.address {
+        return Err(MessageError::BadSignature(signed.address.clone()));
+    }
+
+    let signature = secp256k1::ecdsa::Signature::from_compact(&base64_field(
+        &signed.signature,
+        "ADR-36 signature",
+    )?)
+    .map_err(|e| MessageError::Malformed(format!("signature: {}", e)))?;
+    let key = secp256k1::PublicKey::from_slice(&public_key)
+        .map_err(|e| MessageError::Malformed(format!("public key: {}", e)))?;
+    let doc = sign_doc(&signed.address, signed.message.as_bytes());
+    let hash =
+        secp256k1::Message::from_slice(&Sha256::digest(doc.as_bytes())).expect("32-byte hash");
+    secp256k1::SECP256K1
+        .verify_ecdsa(&hash, &signature, &key)
+        .map_err(|_| MessageError::BadSignature(signed.address.clone()))
+}
+
+fn address(hrp: &str, public_key: &[u8]) -> Result<String> {
+    let hrp = Hrp::parse(hrp).map_err(|e| MessageError::InvalidAddress(e.to_string()))?;
+    bech32::encode::<Bech32>(hrp, &hash160(public_key))
+        .map_err(|e| MessageError::InvalidAddress(e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Secp256k1Signer;
+
+    #[test]
+    fn test_sign_doc() {
+        assert_eq!(
+            sign_doc("cosmos1abc", b"hi"),
+            r#"{"account_number":"0","chain_id":"","fee":{"amount":[],"gas":"0"},"memo":"","msgs":[{"type":"sign/MsgSignData","value":{"data":"aGk=","signer":"cosmos1abc"}}],"sequence":"0"}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        let key = Secp256k1Signer::from_slice(&[3u8; 32]).unwrap();
+        let signer = Adr36Signer::new(Box::new(key), "osmo").unwrap();
+        assert!(signer.address().starts_with("osmo1"));
+        let signed = signer.sign_message("Sign in to app.example").await.unwrap();
+        verify(&signed).unwrap();
+
+        let other_key = Secp256k1Signer::from_slice(&[4u8; 32]).unwrap();
+        let impostor = SignedMessage {
+            public_key: Some(
+                base64::engine::general_purpose::STANDARD.encode(other_key.public_key()),
+            ),
+            ..signed
+        };
+        assert!(matches!(
+            verify(&impostor),
+            Err(MessageError::BadSignature(_))
+        ));
+    }
+}
@@ -0,0 +1,183 @@
+//! EIP-191 personal messages
+//!
+//! The message is prefixed with `\x19Ethereum Signed Message:\n` and its
+//! length in bytes, hashed with Keccak-256 and signed; the signature is
+//! `r || s || v` with `v` = 27 or 28, as returned by `personal_sign`.
+
+use crate::{MessageError, MessageSigner, MessageStandard, Result, SignedMessage};
+use async_trait::async_trait;
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use sha3::{Digest, Keccak256};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// Signs EIP-191 personal messages
+pub struct Eip191Signer {
+    signer: Box<dyn Signer>,
+    address: String,
+}
+
+impl std::fmt::Debug for Eip191Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Eip191Signer")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Eip191Signer {
+    /// Signs with a secp256k1 key
+    pub fn new(signer: Box<dyn Signer>) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            return Err(MessageError::Unsupported(
+                "EIP-191 needs a secp256k1 key".into(),
+            ));
+        }
+        let key = secp256k1::PublicKey::from_slice(&signer.public_key())
+            .map_err(|e| MessageError::Malformed(format!("public key: {}", e)))?;
+        Ok(Self {
+            address: address(&key),
+            signer,
+        })
+    }
+}
+
+#[async_trait]
+impl MessageSigner for Eip191Signer {
+    fn standard(&self) -> MessageStandard {
+        MessageStandard::Eip191
+    }
+
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage> {
+        let hash = hash_message(message.as_bytes());
+        let mut signature = self.signer.sign_hash(&hash).await?;
+        let message_hash = secp256k1::Message::from_slice(&hash).expect("32-byte hash");
+        let v = (0..2)
+            .find(|&id| {
+                recover_key(&message_hash, &signature, id)
+                    .is_some_and(|key| address(&key) == self.address)
+            })
+            .ok_or_else(|| MessageError::BadSignature("the signer's own key".into()))?;
+        signature.push(27 + v as u8);
+        Ok(SignedMessage {
+            standard: MessageStandard::Eip191,
+            address: self.address.clone(),
+            message: message.to_string(),
+            signature: format!("0x{}", hex::encode(signature)),
+            public_key: None,
+            nep413: None,
+        })
+    }
+}
+
+/// Keccak-256 of the prefixed message
+pub fn hash_message(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// Recovers the checksummed address that signed `message`
+pub fn recover(message: &str, signature: &str) -> Result<String> {
+    let bytes = hex::decode(signature.trim_start_matches("0x"))
+        .ok()
+        .filter(|b| b.len() == 65)
+        .ok_or_else(|| MessageError::Malformed("EIP-191 signature".into()))?;
+    // Some signers return v as 0/1 rather than 27/28
+    let v = match bytes[64] {
+        v @ (0 | 1) => v,
+        v @ (27 | 28) => v - 27,
+        _ => return Err(MessageError::Malformed("EIP-191 recovery id".into())),
+    };
+    let hash =
+        secp256k1::Message::from_slice(&hash_message(message.as_bytes())).expect("32-byte hash");
+    let key = recover_key(&hash, &bytes[..64], v.into())
+        .ok_or_else(|| MessageError::BadSignature(message.into()))?;
+    Ok(address(&key))
+}
+
+/// Checks that the signature recovers to the message's address
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    let signer = recover(&signed.message, &signed.signature)?;
+    if signer.eq_ignore_ascii_case(&signed.address) {
+        Ok(())
+    } else {
+        Err(MessageError::BadSignature(signed.address.clone()))
+    }
+}
+
+fn recover_key(
+    hash: &secp256k1::Message,
+    signature: &[u8],
+    id: i32,
+) -> Option<secp256k1::PublicKey> {
+    RecoverableSignature::from_compact(signature, RecoveryId::from_i32(id).ok()?)
+        .ok()?
+        .recover(hash)
+        .ok()
+}
+
+/// EIP-55 checksummed address of a public key
+fn address(key: &secp256k1::PublicKey) -> String {
+    let hash = Keccak256::digest(&key.serialize_uncompressed()[1..]);
+    let lower = hex::encode(&hash[12..]);
+    let checksum = Keccak256::digest(lower.as_bytes());
+    let mut out = String::from("0x");
+    for (i, c) in lower.chars().enumerate() {
+        let nibble = (checksum[i / 2] >> (4 * (1 - i % 2))) & 0x0f;
+        out.push(if nibble >= 8 {
+            c.to_ascii_uppercase()
+        } else {
+            c
+        });
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Secp256k1Signer;
+
+    fn signer() -> Eip191Signer {
+        // Private key 1
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        Eip191Signer::new(Box::new(Secp256k1Signer::from_slice(&secret).unwrap())).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_sign_and_recover() {
+        let signer = signer();
+        assert_eq!(
+            signer.address(),
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf"
+        );
+        let signed = signer.sign_message("hello").await.unwrap();
+        assert_eq!(signed.signature.len(), 2 + 130);
+        assert_eq!(
+            recover("hello", &signed.signature).unwrap(),
+            signer.address()
+        );
+        verify(&signed).unwrap();
+
+        let tampered = SignedMessage {
+            message: "hello!".into(),
+            ..signed
+        };
+        assert!(verify(&tampered).is_err());
+    }
+
+    #[test]
+    fn test_hash_message() {
+        // keccak256("\x19Ethereum Signed Message:\n5hello")
+        assert_eq!(
+            hex::encode(hash_message(b"hello")),
+            "50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750"
+        );
+    }
+}
@@ -0,0 +1,191 @@
+//! # WalletD Message
+//!
+//! Signs and verifies off-chain messages the way each chain's wallets do.
+//! Every chain wraps a personal message in its own envelope before signing,
+//! so that a signed message can never pass for a transaction:
+//!
+//! | Standard | Chains | Envelope |
+//! |----------|--------|----------|
+//! | [EIP-191](evm) | EVM | `\x19Ethereum Signed Message:\n<len>`, Keccak-256 |
+//! | [BIP-322](bitcoin) | Bitcoin | Virtual `to_spend`/`to_sign` transactions |
+//! | [ADR-36](cosmos) | Cosmos SDK | Amino `MsgSignData` sign doc |
+//! | [NEP-413](near) | NEAR | Borsh payload with nonce and recipient |
+//! | [SIWS](solana) | Solana | None: the message bytes are signed as-is |
+//!
+//! A [`MessageSigner`] applies the envelope and returns a
+//! [`SignedMessage`], which [`verify`] checks whatever the chain. Together
+//! with [`SignInMessage`] (EIP-4361, Sign-In with Solana and CAIP-122) this is enough
+//! to implement "Sign in with X" once for every chain.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_message::{evm::Eip191Signer, verify_sign_in, MessageSigner, SignInMessage};
+//!
+//! // Client
+//! let signer = Eip191Signer::new(account.into_signer())?;
+//! let request = SignInMessage::new("Ethereum", "app.example", signer.address(), "https://app.example", "1", &nonce);
+//! let signed = signer.sign_message(&request.to_string()).await?;
+//!
+//! // Server
+//! let message = verify_sign_in(&signed, "app.example", &nonce)?;
+//! println!("{} signed in", message.address);
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod bitcoin;
+pub mod cosmos;
+pub mod evm;
+pub mod near;
+pub mod signin;
+pub mod solana;
+
+pub use near::Nep413Payload;
+pub use signin::{verify_sign_in, verify_sign_in_at, SignInMessage};
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// A chain's personal-message signing standard
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MessageStandard {
+    /// EIP-191 `personal_sign` (EVM)
+    Eip191,
+    /// BIP-322 simple signatures (Bitcoin)
+    Bip322,
+    /// ADR-36 arbitrary data (Cosmos SDK)
+    Adr36,
+    /// NEP-413 off-chain messages (NEAR)
+    Nep413,
+    /// Solana `signMessage`, as used by Sign-In with Solana
+    Siws,
+}
+
+impl fmt::Display for MessageStandard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            MessageStandard::Eip191 => "EIP-191",
+            MessageStandard::Bip322 => "BIP-322",
+            MessageStandard::Adr36 => "ADR-36",
+            MessageStandard::Nep413 => "NEP-413",
+            MessageStandard::Siws => "SIWS",
+        })
+    }
+}
+
+/// A message with its signature, in the encodings the chain's wallets use
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SignedMessage {
+    /// Standard the message was signed under
+    pub standard: MessageStandard,
+    /// Signing address or account
+    pub address: String,
+    /// The message as shown to the user
+    pub message: String,
+    /// Signature: `0x` hex (EIP-191), base64 (BIP-322, ADR-36, NEP-413) or
+    /// base58 (SIWS)
+    pub signature: String,
+    /// Public key, for standards whose signatures do not recover it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub public_key: Option<String>,
+    /// NEP-413 nonce, recipient and callback
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub nep413: Option<Nep413Payload>,
+}
+
+/// Signs personal messages under one chain's standard
+#[async_trait]
+pub trait MessageSigner: Send + Sync {
+    /// Standard applied to every message
+    fn standard(&self) -> MessageStandard;
+
+    /// Address or account the signatures belong to
+    fn address(&self) -> &str;
+
+    /// Wraps `message` in the standard's envelope and signs it
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage>;
+}
+
+/// Checks a signed message under its standard
+///
+/// Succeeds when the signature is valid for [`SignedMessage::address`].
+/// NEAR named accounts are the exception: see [`near::verify`].
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    match signed.standard {
+        MessageStandard::Eip191 => evm::verify(signed),
+        MessageStandard::Bip322 => bitcoin::verify(signed),
+        MessageStandard::Adr36 => cosmos::verify(signed),
+        MessageStandard::Nep413 => near::verify(signed),
+        MessageStandard::Siws => solana::verify(signed),
+    }
+}
+
+/// Message signing errors
+#[derive(Error, Debug)]
+pub enum MessageError {
+    /// The signature does not match the message and address
+    #[error("Signature does not match {0}")]
+    BadSignature(String),
+
+    /// The signature, public key or payload cannot be decoded
+    #[error("Malformed {0}")]
+    Malformed(String),
+
+    /// The address is not one the standard can sign for
+    #[error("Invalid address: {0}")]
+    InvalidAddress(String),
+
+    /// The key cannot sign under the standard
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// A sign-in message is malformed, expired or for another site
+    #[error("Sign-in rejected: {0}")]
+    SignIn(String),
+
+    /// The signer failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for message signing
+pub type Result<T> = std::result::Result<T, MessageError>;
+
+impl From<MessageError> for WalletdError {
+    fn from(e: MessageError) -> Self {
+        match e {
+            MessageError::BadSignature(_) | MessageError::SignIn(_) => {
+                WalletdError::SignatureVerificationFailed(e.to_string())
+            }
+            MessageError::Malformed(_) => WalletdError::FormatError(e.to_string()),
+            MessageError::InvalidAddress(address) => WalletdError::InvalidAddress {
+                address,
+                reason: "not usable for message signing".into(),
+            },
+            MessageError::Unsupported(_) => WalletdError::NotSupported(e.to_string()),
+            MessageError::Wallet(e) => WalletdError::SigningError(e.to_string()),
+        }
+    }
+}
+
+/// RIPEMD-160 of SHA-256, as in Bitcoin and Cosmos addresses
+pub(crate) fn hash160(data: &[u8]) -> [u8; 20] {
+    use ripemd::Ripemd160;
+    use sha2::{Digest, Sha256};
+    Ripemd160::digest(Sha256::digest(data)).into()
+}
+
+/// Decodes a base64 field, naming it in the error
+pub(crate) fn base64_field(value: &str, what: &str) -> Result<Vec<u8>> {
+    use base64::Engine;
+    base64::engine::general_purpose::STANDARD
+        .decode(value)
+        .map_err(|e| MessageError::Malformed(format!("{}: {}", what, e)))
+}
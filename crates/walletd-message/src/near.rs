@@ -0,0 +1,230 @@
+//! NEP-413 off-chain messages
+//!
+//! The message, a 32-byte nonce, the recipient and an optional callback URL
+//! are Borsh-encoded behind the tag `2^31 + 413`; the SHA-256 of the result
+//! is signed with the account's Ed25519 key.
+
+use crate::{base64_field, MessageError, MessageSigner, MessageStandard, Result, SignedMessage};
+use async_trait::async_trait;
+use base64::Engine;
+use ed25519_dalek::{Signature, VerifyingKey};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// Borsh prefix separating NEP-413 payloads from transactions
+const NEP413_TAG: u32 = (1 << 31) + 413;
+
+/// NEP-413 fields signed along with the message
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Nep413Payload {
+    /// Base64 32-byte nonce, chosen by the app to prevent replay
+    pub nonce: String,
+    /// Who the message is for, e.g. the app's domain or account
+    pub recipient: String,
+    /// Where the wallet should redirect after signing
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub callback_url: Option<String>,
+}
+
+/// Signs NEP-413 messages for a NEAR account
+pub struct Nep413Signer {
+    signer: Box<dyn Signer>,
+    account_id: String,
+    recipient: String,
+    callback_url: Option<String>,
+}
+
+impl std::fmt::Debug for Nep413Signer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Nep413Signer")
+            .field("account_id", &self.account_id)
+            .field("recipient", &self.recipient)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Nep413Signer {
+    /// Signs as `account_id`, whose access key `signer` holds, for
+    /// `recipient`
+    pub fn new(
+        signer: Box<dyn Signer>,
+        account_id: impl Into<String>,
+        recipient: impl Into<String>,
+    ) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            return Err(MessageError::Unsupported(
+                "NEP-413 needs an Ed25519 key".into(),
+            ));
+        }
+        Ok(Self {
+            signer,
+            account_id: account_id.into(),
+            recipient: recipient.into(),
+            callback_url: None,
+        })
+    }
+
+    /// Sets the callback URL
+    pub fn with_callback_url(mut self, url: impl Into<String>) -> Self {
+        self.callback_url = Some(url.into());
+        self
+    }
+
+    /// Signs `message` with the app's `nonce`
+    pub async fn sign_with_nonce(&self, message: &str, nonce: [u8; 32]) -> Result<SignedMessage> {
+        let hash = payload_hash(
+            message,
+            &nonce,
+            &self.recipient,
+            self.callback_url.as_deref(),
+        );
+        let signature = self.signer.sign_hash(&hash).await?;
+        let base64 = base64::engine::general_purpose::STANDARD;
+        Ok(SignedMessage {
+            standard: MessageStandard::Nep413,
+            address: self.account_id.clone(),
+            message: message.to_string(),
+            signature: base64.encode(signature),
+            public_key: Some(format!(
+                "ed25519:{}",
+                bs58::encode(self.signer.public_key()).into_string()
+            )),
+            nep413: Some(Nep413Payload {
+                nonce: base64.encode(nonce),
+                recipient: self.recipient.clone(),
+                callback_url: self.callback_url.clone(),
+            }),
+        })
+    }
+}
+
+#[async_trait]
+impl MessageSigner for Nep413Signer {
+    fn standard(&self) -> MessageStandard {
+        MessageStandard::Nep413
+    }
+
+    fn address(&self) -> &str {
+        &self.account_id
+    }
+
+    /// Signs with a random nonce
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage> {
+        let mut nonce = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut nonce);
+        self.sign_with_nonce(message, nonce).await
+    }
+}
+
+/// SHA-256 of the tagged Borsh payload
+pub fn payload_hash(
+    message: &str,
+    nonce: &[u8; 32],
+    recipient: &str,
+    callback_url: Option<&str>,
+) -> [u8; 32] {
+    let mut payload = NEP413_TAG.to_le_bytes().to_vec();
+    borsh_string(&mut payload, message);
+    payload.extend_from_slice(nonce);
+    borsh_string(&mut payload, recipient);
+    match callback_url {
+        Some(url) => {
+            payload.push(1);
+            borsh_string(&mut payload, url);
+        }
+        None => payload.push(0),
+    }
+    Sha256::digest(payload).into()
+}
+
+/// Checks the signature against the message's public key
+///
+/// For implicit accounts (64 hex characters) the key must also be the
+/// account's own. For named accounts the key cannot be tied to the account
+/// offline: look it up with the `view_access_key` RPC query, and prefer a
+/// full-access key for sign-in.
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    let payload = signed
+        .nep413
+        .as_ref()
+        .ok_or_else(|| MessageError::Malformed("NEP-413 message without payload".into()))?;
+    let public_key = signed
+        .public_key
+        .as_deref()
+        .and_then(|k| k.strip_prefix("ed25519:"))
+        .and_then(|k| bs58::decode(k).into_vec().ok())
+        .and_then(|k| <[u8; 32]>::try_from(k).ok())
+        .ok_or_else(|| MessageError::Malformed("NEP-413 public key".into()))?;
+    let is_implicit =
+        signed.address.len() == 64 && signed.address.chars().all(|c| c.is_ascii_hexdigit());
+    if is_implicit
+        && !signed
+            .address
+            .eq_ignore_ascii_case(&hex::encode(public_key))
+    {
+        return Err(MessageError::BadSignature(signed.address.clone()));
+    }
+
+    let nonce: [u8; 32] = base64_field(&payload.nonce, "NEP-413 nonce")?
+        .try_into()
+        .map_err(|_| MessageError::Malformed("NEP-413 nonce is not 32 bytes".into()))?;
+    let hash = payload_hash(
+        &signed.message,
+        &nonce,
+        &payload.recipient,
+        payload.callback_url.as_deref(),
+    );
+    let signature = Signature::from_slice(&base64_field(&signed.signature, "NEP-413 signature")?)
+        .map_err(|e| MessageError::Malformed(format!("signature: {}", e)))?;
+    VerifyingKey::from_bytes(&public_key)
+        .map_err(|e| MessageError::Malformed(format!("public key: {}", e)))?
+        .verify_strict(&hash, &signature)
+        .map_err(|_| MessageError::BadSignature(signed.address.clone()))
+}
+
+fn borsh_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Ed25519Signer;
+
+    #[tokio::test]
+    async fn test_sign_and_verify() {
+        let key = Ed25519Signer::from_bytes(&[9u8; 32]);
+        let implicit = hex::encode(key.public_key());
+        let signer = Nep413Signer::new(Box::new(key), implicit.clone(), "app.example")
+            .unwrap()
+            .with_callback_url("https://app.example/done");
+        let signed = signer.sign_with_nonce("Sign in", [1u8; 32]).await.unwrap();
+        assert_eq!(signed.address, implicit);
+        verify(&signed).unwrap();
+
+        let json = serde_json::to_value(signed.nep413.as_ref().unwrap()).unwrap();
+        assert_eq!(json["callbackUrl"], "https://app.example/done");
+
+        let replayed = SignedMessage {
+            nep413: Some(Nep413Payload {
+                recipient: "evil.example".into(),
+                ..signed.nep413.clone().unwrap()
+            }),
+            ..signed.clone()
+        };
+        assert!(matches!(
+            verify(&replayed),
+            Err(MessageError::BadSignature(_))
+        ));
+
+        let other_account = SignedMessage {
+            address: "00".repeat(32),
+            ..signed
+        };
+        assert!(verify(&other_account).is_err());
+    }
+}
@@ -0,0 +1,400 @@
+//! "Sign in with X" messages
+//!
+//! The text format of EIP-4361 (Sign-In with Ethereum), generalised to any
+//! chain by CAIP-122 and used by Sign-In with Solana:
+//!
+//! ```text
+//! app.example wants you to sign in with your Ethereum account:
+//! 0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf
+//!
+//! Sign in to App
+//!
+//! URI: https://app.example
+//! Version: 1
+//! Chain ID: 1
+//! Nonce: 32891756
+//! Issued At: 2026-01-01T00:00:00Z
+//! ```
+//!
+//! The server issues the nonce, the client signs the message under its
+//! chain's standard and the server checks it with [`verify_sign_in`].
+
+use crate::{verify, MessageError, MessageStandard, Result, SignedMessage};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const HEADER: &str = " wants you to sign in with your ";
+
+/// A sign-in request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SignInMessage {
+    /// Chain name shown in the header, e.g. `Ethereum`, `Solana`
+    pub chain: String,
+    /// Domain asking for the sign-in
+    pub domain: String,
+    /// Account signing in
+    pub address: String,
+    /// Human-readable statement
+    pub statement: Option<String>,
+    /// Resource the sign-in is for
+    pub uri: String,
+    /// Message version, `1`
+    pub version: String,
+    /// Chain id: EIP-155 id on EVM chains, cluster or network elsewhere
+    pub chain_id: String,
+    /// Server-issued nonce
+    pub nonce: String,
+    /// Creation time, Unix seconds
+    pub issued_at: u64,
+    /// When the message stops being valid, Unix seconds
+    pub expiration_time: Option<u64>,
+    /// When the message becomes valid, Unix seconds
+    pub not_before: Option<u64>,
+}
+
+impl SignInMessage {
+    /// A request issued now
+    pub fn new(
+        chain: impl Into<String>,
+        domain: impl Into<String>,
+        address: impl Into<String>,
+        uri: impl Into<String>,
+        chain_id: impl Into<String>,
+        nonce: impl Into<String>,
+    ) -> Self {
+        Self {
+            chain: chain.into(),
+            domain: domain.into(),
+            address: address.into(),
+            statement: None,
+            uri: uri.into(),
+            version: "1".into(),
+            chain_id: chain_id.into(),
+            nonce: nonce.into(),
+            issued_at: now(),
+            expiration_time: None,
+            not_before: None,
+        }
+    }
+
+    /// Sets the statement
+    pub fn statement(mut self, statement: impl Into<String>) -> Self {
+        self.statement = Some(statement.into());
+        self
+    }
+
+    /// Sets the creation time
+    pub fn issued_at(mut self, timestamp: u64) -> Self {
+        self.issued_at = timestamp;
+        self
+    }
+
+    /// Sets the expiration time
+    pub fn expires_at(mut self, timestamp: u64) -> Self {
+        self.expiration_time = Some(timestamp);
+        self
+    }
+
+    /// Sets the start of validity
+    pub fn not_before(mut self, timestamp: u64) -> Self {
+        self.not_before = Some(timestamp);
+        self
+    }
+
+    /// Parses the text format
+    ///
+    /// Request ids and resources are accepted but not kept.
+    pub fn parse(text: &str) -> Result<Self> {
+        let malformed = |what: &str| MessageError::SignIn(format!("malformed {}", what));
+        let mut lines = text.lines();
+        let (domain, chain) = lines
+            .next()
+            .and_then(|l| l.strip_suffix(" account:"))
+            .and_then(|l| l.split_once(HEADER))
+            .ok_or_else(|| malformed("header"))?;
+        let address = lines.next().ok_or_else(|| malformed("address"))?;
+        if lines.next() != Some("") {
+            return Err(malformed("address"));
+        }
+
+        let mut lines = lines.peekable();
+        let mut statement = None;
+        if lines.peek().is_some_and(|l| !l.starts_with("URI: ")) {
+            statement = lines.next().map(str::to_string);
+            if lines.next() != Some("") {
+                return Err(malformed("statement"));
+            }
+        }
+
+        let mut message = Self::new(chain, domain, address, "", "", "");
+        message.statement = statement;
+        let (mut uri, mut version, mut chain_id, mut nonce, mut issued_at) =
+            (None, None, None, None, None);
+        for line in lines {
+            let Some((key, value)) = line.split_once(": ") else {
+                continue;
+            };
+            let time = || parse_rfc3339(value).ok_or_else(|| malformed(key));
+            match key {
+                "URI" => uri = Some(value.to_string()),
+                "Version" => version = Some(value.to_string()),
+                "Chain ID" => chain_id = Some(value.to_string()),
+                "Nonce" => nonce = Some(value.to_string()),
+                "Issued At" => issued_at = Some(time()?),
+                "Expiration Time" => message.expiration_time = Some(time()?),
+                "Not Before" => message.not_before = Some(time()?),
+                _ => {}
+            }
+        }
+        message.uri = uri.ok_or_else(|| malformed("URI"))?;
+        message.version = version.ok_or_else(|| malformed("version"))?;
+        message.chain_id = chain_id.ok_or_else(|| malformed("chain id"))?;
+        message.nonce = nonce.ok_or_else(|| malformed("nonce"))?;
+        message.issued_at = issued_at.ok_or_else(|| malformed("issue time"))?;
+        Ok(message)
+    }
+}
+
+impl fmt::Display for SignInMessage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "{}{}{} account:", self.domain, HEADER, self.chain)?;
+        writeln!(f, "{}", self.address)?;
+        writeln!(f)?;
+        if let Some(statement) = &self.statement {
+            writeln!(f, "{}", statement)?;
+            writeln!(f)?;
+        }
+        writeln!(f, "URI: {}", self.uri)?;
+        writeln!(f, "Version: {}", self.version)?;
+        writeln!(f, "Chain ID: {}", self.chain_id)?;
+        writeln!(f, "Nonce: {}", self.nonce)?;
+        write!(f, "Issued At: {}", format_rfc3339(self.issued_at))?;
+        if let Some(expiration) = self.expiration_time {
+            write!(f, "\nExpiration Time: {}", format_rfc3339(expiration))?;
+        }
+        if let Some(not_before) = self.not_before {
+            write!(f, "\nNot Before: {}", format_rfc3339(not_before))?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks a signed sign-in message for `domain` with the issued `nonce`
+pub fn verify_sign_in(signed: &SignedMessage, domain: &str, nonce: &str) -> Result<SignInMessage> {
+    verify_sign_in_at(signed, domain, nonce, now())
+}
+
+/// Like [`verify_sign_in`] at Unix time `now`
+///
+/// Checks the domain, nonce, validity window and that the message names
+/// the signing account, then the signature itself.
+pub fn verify_sign_in_at(
+    signed: &SignedMessage,
+    domain: &str,
+    nonce: &str,
+    now: u64,
+) -> Result<SignInMessage> {
+    let message = SignInMessage::parse(&signed.message)?;
+    if message.domain != domain {
+        return Err(MessageError::SignIn(format!(
+            "message is for {}",
+            message.domain
+        )));
+    }
+    if message.nonce != nonce {
+        return Err(MessageError::SignIn("nonce does not match".into()));
+    }
+    // EVM addresses may differ in checksum case only
+    let same_account = match signed.standard {
+        MessageStandard::Eip191 => message.address.eq_ignore_ascii_case(&signed.address),
+        _ => message.address == signed.address,
+    };
+    if !same_account {
+        return Err(MessageError::SignIn(format!(
+            "message names {}",
+            message.address
+        )));
+    }
+    if message.expiration_time.is_some_and(|t| now >= t) {
+        return Err(MessageError::SignIn("message expired".into()));
+    }
+    if message.not_before.is_some_and(|t| now < t) {
+        return Err(MessageError::SignIn("message not yet valid".into()));
+    }
+    verify(signed)?;
+    Ok(message)
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// `YYYY-MM-DDTHH:MM:SSZ`
+fn format_rfc3339(timestamp: u64) -> String {
+    let (days, secs) = ((timestamp / 86_400) as i64, timestamp % 86_400);
+    // Days to civil date (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3_600,
+        secs / 60 % 60,
+        secs % 60
+    )
+}
+
+/// Parses `YYYY-MM-DDTHH:MM:SS[.fff](Z|±HH:MM)`, dropping fractions
+fn parse_rfc3339(value: &str) -> Option<u64> {
+    let number = |range: std::ops::Range<usize>| -> Option<i64> {
+        let digits = value.get(range)?;
+        digits
+            .bytes()
+            .all(|b| b.is_ascii_digit())
+            .then(|| digits.parse().ok())?
+    };
+    let separators = [(4, b'-'), (7, b'-'), (10, b'T'), (13, b':'), (16, b':')];
+    if separators.iter().any(|&(i, c)| {
+        !value
+            .as_bytes()
+            .get(i)
+            .is_some_and(|b| b.eq_ignore_ascii_case(&c))
+    }) {
+        return None;
+    }
+    let (year, month, day) = (number(0..4)?, number(5..7)?, number(8..10)?);
+    let (hour, minute, second) = (number(11..13)?, number(14..16)?, number(17..19)?);
+    if !(1..=12).contains(&month)
+        || !(1..=31).contains(&day)
+        || hour > 23
+        || minute > 59
+        || second > 60
+    {
+        return None;
+    }
+
+    let mut rest = value.get(19..)?;
+    if let Some(fraction) = rest.strip_prefix('.') {
+        rest = fraction.trim_start_matches(|c: char| c.is_ascii_digit());
+    }
+    let offset = match rest.as_bytes() {
+        [b'Z' | b'z'] => 0,
+        [sign @ (b'+' | b'-'), ..] if rest.len() == 6 && &rest[3..4] == ":" => {
+            let hours: i64 = rest[1..3].parse().ok()?;
+            let minutes: i64 = rest[4..6].parse().ok()?;
+            let offset = hours * 3_600 + minutes * 60;
+            if *sign == b'+' {
+                offset
+            } else {
+                -offset
+            }
+        }
+        _ => return None,
+    };
+
+    // Civil date to days (Howard Hinnant's algorithm)
+    let y = if month <= 2 { year - 1 } else { year };
+    let era = y.div_euclid(400);
+    let yoe = y.rem_euclid(400);
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+    u64::try_from(days * 86_400 + hour * 3_600 + minute * 60 + second - offset).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::evm::Eip191Signer;
+    use crate::solana::SiwsSigner;
+    use crate::MessageSigner;
+    use walletd_traits::{Ed25519Signer, Secp256k1Signer};
+
+    #[test]
+    fn test_format_and_parse() {
+        let message = SignInMessage::new(
+            "Ethereum",
+            "app.example",
+            "0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf",
+            "https://app.example",
+            "1",
+            "32891756",
+        )
+        .statement("Sign in to App")
+        .issued_at(1_767_225_600)
+        .expires_at(1_767_229_200);
+        let text = message.to_string();
+        assert!(text.starts_with(
+            "app.example wants you to sign in with your Ethereum account:\n0x7E5F4552091A69125d5DfCb7b8C2659029395Bdf\n\nSign in to App\n\nURI: https://app.example\n"
+        ));
+        assert!(
+            text.contains("Issued At: 2026-01-01T00:00:00Z\nExpiration Time: 2026-01-01T01:00:00Z")
+        );
+        assert_eq!(SignInMessage::parse(&text).unwrap(), message);
+
+        assert_eq!(
+            parse_rfc3339("2026-01-01T02:00:00.123+02:00"),
+            Some(1_767_225_600)
+        );
+        assert_eq!(parse_rfc3339("1970-01-01T00:00:00Z"), Some(0));
+        assert_eq!(format_rfc3339(951_782_400), "2000-02-29T00:00:00Z");
+        assert_eq!(parse_rfc3339("2026-13-01T00:00:00Z"), None);
+    }
+
+    #[tokio::test]
+    async fn test_verify_sign_in() {
+        let evm =
+            Eip191Signer::new(Box::new(Secp256k1Signer::from_slice(&[1u8; 32]).unwrap())).unwrap();
+        let sol = SiwsSigner::new(Box::new(Ed25519Signer::from_bytes(&[2u8; 32]))).unwrap();
+        for (chain, signer) in [("Ethereum", &evm as &dyn MessageSigner), ("Solana", &sol)] {
+            let message = SignInMessage::new(
+                chain,
+                "app.example",
+                signer.address(),
+                "https://app.example",
+                "1",
+                "n0nce",
+            )
+            .issued_at(1_000)
+            .expires_at(2_000);
+            let signed = signer.sign_message(&message.to_string()).await.unwrap();
+
+            let accepted = verify_sign_in_at(&signed, "app.example", "n0nce", 1_500).unwrap();
+            assert_eq!(accepted.address, signer.address());
+            for (domain, nonce, now) in [
+                ("evil.example", "n0nce", 1_500),
+                ("app.example", "other", 1_500),
+                ("app.example", "n0nce", 2_000),
+            ] {
+                assert!(matches!(
+                    verify_sign_in_at(&signed, domain, nonce, now),
+                    Err(MessageError::SignIn(_))
+                ));
+            }
+        }
+
+        // A valid signature over a message naming someone else
+        let message = SignInMessage::new(
+            "Solana",
+            "app.example",
+            evm.address(),
+            "https://app.example",
+            "1",
+            "n0nce",
+        );
+        let signed = sol.sign_message(&message.to_string()).await.unwrap();
+        assert!(verify_sign_in(&signed, "app.example", "n0nce").is_err());
+    }
+}
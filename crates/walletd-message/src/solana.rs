@@ -0,0 +1,78 @@
+//! Solana `signMessage`
+//!
+//! Solana wallets sign the UTF-8 message bytes directly with the account's
+//! Ed25519 key; the address is the public key. Sign-In with Solana builds
+//! on this by signing a [`SignInMessage`](crate::SignInMessage).
+
+use crate::{MessageError, MessageSigner, MessageStandard, Result, SignedMessage};
+use async_trait::async_trait;
+use ed25519_dalek::{Signature, VerifyingKey};
+use walletd_traits::{SignatureScheme, Signer};
+
+/// Signs messages for a Solana account
+pub struct SiwsSigner {
+    signer: Box<dyn Signer>,
+    address: String,
+}
+
+impl std::fmt::Debug for SiwsSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SiwsSigner")
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SiwsSigner {
+    /// Signs with an Ed25519 key
+    pub fn new(signer: Box<dyn Signer>) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            return Err(MessageError::Unsupported(
+                "Solana messages need an Ed25519 key".into(),
+            ));
+        }
+        let address = bs58::encode(signer.public_key()).into_string();
+        Ok(Self { signer, address })
+    }
+}
+
+#[async_trait]
+impl MessageSigner for SiwsSigner {
+    fn standard(&self) -> MessageStandard {
+        MessageStandard::Siws
+    }
+
+    fn address(&self) -> &str {
+        &self.address
+    }
+
+    async fn sign_message(&self, message: &str) -> Result<SignedMessage> {
+        let signature = self.signer.sign_message(message.as_bytes()).await?;
+        Ok(SignedMessage {
+            standard: MessageStandard::Siws,
+            address: self.address.clone(),
+            message: message.to_string(),
+            signature: bs58::encode(signature).into_string(),
+            public_key: None,
+            nep413: None,
+        })
+    }
+}
+
+/// Checks the signature against the address's key
+pub fn verify(signed: &SignedMessage) -> Result<()> {
+    let public_key: [u8; 32] = bs58::decode(&signed.address)
+        .into_vec()
+        .ok()
+        .and_then(|k| k.try_into().ok())
+        .ok_or_else(|| MessageError::InvalidAddress(signed.address.clone()))?;
+    let signature = bs58::decode(&signed.signature)
+        .into_vec()
+        .ok()
+        .and_then(|s| Signature::from_slice(&s).ok())
+        .ok_or_else(|| MessageError::Malformed("Solana signature".into()))?;
+    VerifyingKey::from_bytes(&public_key)
+        .map_err(|_| MessageError::InvalidAddress(signed.address.clone()))?
+        .verify_strict(signed.message.as_bytes(), &signature)
+        .map_err(|_| MessageError::BadSignature(signed.address.clone()))
+}
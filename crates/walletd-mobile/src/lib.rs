@@ -0,0 +1,246 @@
+//! # WalletD Mobile
+//!
+//! Kotlin (Android) and Swift (iOS) bindings for the WalletD SDK, generated
+//! with [UniFFI](https://mozilla.github.io/uniffi-rs/) from the exports in
+//! this crate. Mobile apps get:
+//!
+//! - mnemonic generation and validation
+//! - [`Wallet`]: per-chain addresses, hash and message signing, and offline
+//!   EVM transaction signing from a BIP-39 mnemonic
+//! - [`Rpc`]: balances, nonces, gas prices and broadcasting over JSON-RPC
+//!
+//! Chains are passed by name or ticker (`"ethereum"`, `"btc"`, `"sol"`),
+//! byte strings as `ByteArray` / `Data`, and amounts as decimal strings in
+//! the chain's smallest unit.
+//!
+//! ## Generating bindings
+//!
+//! ```text
+//! cargo build -p walletd-mobile --release
+//! cargo run -p walletd-mobile --features bindgen --bin uniffi-bindgen -- \
+//!     generate --library target/release/libwalletd_mobile.so \
+//!     --language kotlin --out-dir bindings/kotlin
+//! ```
+//!
+//! Use `--language swift` with the `.a`/`.dylib` for iOS, and build the
+//! library for each target (`aarch64-linux-android`, `aarch64-apple-ios`,
+//! ...) with the usual NDK or Xcode toolchains.
+//!
+//! ## Kotlin
+//!
+//! ```kotlin
+//! val wallet = Wallet.fromMnemonic(generateMnemonic(24u), "")
+//! val address = wallet.address("ethereum", 0u)
+//! val signed = wallet.signEvmTransaction(0u, txJson)
+//! Rpc("https://eth.llamarpc.com").sendRawTransaction(signed.raw)
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+mod rpc;
+mod wallet;
+
+pub use rpc::Rpc;
+pub use wallet::{Account, SignedTransaction, Wallet};
+
+use bip39::Mnemonic;
+use rand::RngCore;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_hd::{HdChain, HdError};
+use walletd_provider::ProviderError;
+use walletd_traits::evm::EvmError;
+use walletd_traits::WalletError;
+use zeroize::Zeroizing;
+
+uniffi::setup_scaffolding!();
+
+/// Errors surfaced to Kotlin and Swift
+///
+/// Each maps to a Kotlin exception subclass or a Swift error case.
+#[derive(Error, Debug, uniffi::Error)]
+pub enum MobileError {
+    /// A parameter was malformed (bad hex, unknown chain, ...)
+    #[error("Invalid input: {reason}")]
+    InvalidInput {
+        /// What was wrong
+        reason: String,
+    },
+
+    /// Key derivation failed or the mnemonic is invalid
+    #[error("Key derivation failed: {reason}")]
+    Derivation {
+        /// Underlying error
+        reason: String,
+    },
+
+    /// Signing failed
+    #[error("Signing failed: {reason}")]
+    Signing {
+        /// Underlying error
+        reason: String,
+    },
+
+    /// The RPC call failed
+    #[error("Network error: {reason}")]
+    Network {
+        /// Underlying error
+        reason: String,
+    },
+
+    /// The operation is not available for this chain
+    #[error("Not supported: {reason}")]
+    NotSupported {
+        /// What is missing
+        reason: String,
+    },
+}
+
+/// Result type for the mobile API
+pub type Result<T> = std::result::Result<T, MobileError>;
+
+impl MobileError {
+    pub(crate) fn invalid(reason: impl Into<String>) -> Self {
+        MobileError::InvalidInput {
+            reason: reason.into(),
+        }
+    }
+}
+
+impl From<HdError> for MobileError {
+    fn from(e: HdError) -> Self {
+        match e {
+            HdError::UnsupportedChain(_) => MobileError::invalid(e.to_string()),
+            e => MobileError::Derivation {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<WalletError> for MobileError {
+    fn from(e: WalletError) -> Self {
+        match e {
+            WalletError::NotSupported(reason) => MobileError::NotSupported { reason },
+            WalletError::NetworkError(reason) => MobileError::Network { reason },
+            e => MobileError::Signing {
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
+impl From<ProviderError> for MobileError {
+    fn from(e: ProviderError) -> Self {
+        MobileError::Network {
+            reason: e.to_string(),
+        }
+    }
+}
+
+impl From<EvmError> for MobileError {
+    fn from(e: EvmError) -> Self {
+        match e {
+            EvmError::Invalid(reason) => MobileError::InvalidInput { reason },
+            e => WalletError::from(e).into(),
+        }
+    }
+}
+
+impl From<MobileError> for WalletdError {
+    fn from(e: MobileError) -> Self {
+        match e {
+            MobileError::InvalidInput { reason } => WalletdError::FormatError(reason),
+            MobileError::Derivation { reason } => WalletdError::KeyDerivationError(reason),
+            MobileError::Signing { reason } => WalletdError::SigningError(reason),
+            MobileError::Network { reason } => WalletdError::NetworkError(reason),
+            MobileError::NotSupported { reason } => WalletdError::NotSupported(reason),
+        }
+    }
+}
+
+/// A chain accounts can be derived for
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct ChainInfo {
+    /// Chain name, accepted wherever a chain is expected
+    pub name: String,
+    /// `secp256k1` or `ed25519`
+    pub curve: String,
+    /// SLIP-44 coin type
+    pub coin_type: u32,
+    /// Derivation path of account 0
+    pub path: String,
+}
+
+/// WalletD version
+#[uniffi::export]
+pub fn version() -> String {
+    env!("CARGO_PKG_VERSION").to_string()
+}
+
+/// Generates a BIP-39 mnemonic of 12, 15, 18, 21 or 24 words
+#[uniffi::export]
+pub fn generate_mnemonic(words: u8) -> Result<String> {
+    if !matches!(words, 12 | 15 | 18 | 21 | 24) {
+        return Err(MobileError::invalid(
+            "word count must be 12, 15, 18, 21 or 24",
+        ));
+    }
+    let mut entropy = Zeroizing::new(vec![0u8; words as usize / 3 * 4]);
+    rand::rngs::OsRng.fill_bytes(&mut entropy);
+    let mnemonic = Mnemonic::from_entropy(&entropy).map_err(|e| MobileError::Derivation {
+        reason: e.to_string(),
+    })?;
+    Ok(mnemonic.to_string())
+}
+
+/// Returns true if `phrase` is a valid English BIP-39 mnemonic
+#[uniffi::export]
+pub fn validate_mnemonic(phrase: String) -> bool {
+    Mnemonic::parse_normalized(phrase.trim()).is_ok()
+}
+
+/// Chains [`Wallet`] can derive accounts for
+#[uniffi::export]
+pub fn supported_chains() -> Vec<ChainInfo> {
+    HdChain::ALL
+        .iter()
+        .map(|chain| ChainInfo {
+            name: chain.name().to_string(),
+            curve: chain.curve().to_string(),
+            coin_type: chain.coin_type(),
+            path: chain.path(0),
+        })
+        .collect()
+}
+
+pub(crate) fn parse_chain(chain: &str) -> Result<HdChain> {
+    Ok(chain.parse()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mnemonics_and_chains() {
+        let phrase = generate_mnemonic(12).unwrap();
+        assert_eq!(phrase.split(' ').count(), 12);
+        assert!(validate_mnemonic(phrase));
+        assert!(!validate_mnemonic("abandon abandon".into()));
+        assert!(matches!(
+            generate_mnemonic(13),
+            Err(MobileError::InvalidInput { .. })
+        ));
+
+        let chains = supported_chains();
+        let ethereum = chains.iter().find(|c| c.name == "ethereum").unwrap();
+        assert_eq!(ethereum.coin_type, 60);
+        assert_eq!(ethereum.path, "m/44'/60'/0'/0/0");
+        assert!(matches!(
+            parse_chain("dogecoin"),
+            Err(MobileError::InvalidInput { .. })
+        ));
+    }
+}
@@ -0,0 +1,126 @@
+//! JSON-RPC helpers exported to Kotlin and Swift
+//!
+//! Calls run on a Tokio runtime managed by UniFFI and surface as `suspend`
+//! functions in Kotlin and `async` functions in Swift.
+
+use crate::Result;
+use std::sync::Arc;
+use walletd_provider::{ChainProvider, Evm, ProviderConfig, ProviderPool, Solana};
+
+/// Pool name of the single endpoint
+const RPC: &str = "rpc";
+
+/// One JSON-RPC endpoint, EVM or Solana
+///
+/// Requests go through the SDK's provider, with its retries, rate limit
+/// handling and fallbacks. Amounts are decimal strings in the smallest unit.
+#[derive(uniffi::Object)]
+pub struct Rpc {
+    pool: ProviderPool,
+}
+
+#[uniffi::export(async_runtime = "tokio")]
+impl Rpc {
+    /// Connects to `url`
+    #[uniffi::constructor]
+    pub fn new(url: String) -> Result<Arc<Self>> {
+        let pool = ProviderPool::new();
+        pool.add(RPC, ProviderConfig::new(url))?;
+        Ok(Arc::new(Self { pool }))
+    }
+
+    /// EVM chain id
+    pub async fn chain_id(&self) -> Result<u64> {
+        Ok(self.evm()?.chain_id().await?)
+    }
+
+    /// Native balance in wei
+    pub async fn evm_balance(&self, address: String) -> Result<String> {
+        Ok(self.evm()?.get_balance(&address).await?.to_string())
+    }
+
+    /// ERC-20 balance in the token's smallest unit
+    pub async fn erc20_balance(&self, token: String, owner: String) -> Result<String> {
+        Ok(self.evm()?.erc20_balance(&token, &owner).await?.to_string())
+    }
+
+    /// Next nonce for `address`
+    pub async fn nonce(&self, address: String) -> Result<u64> {
+        Ok(self.evm()?.get_transaction_count(&address).await?)
+    }
+
+    /// Current gas price in wei
+    pub async fn gas_price(&self) -> Result<String> {
+        Ok(self.evm()?.gas_price().await?.to_string())
+    }
+
+    /// Broadcasts a signed EVM transaction, returning its hash
+    pub async fn send_raw_transaction(&self, raw: String) -> Result<String> {
+        Ok(self.evm()?.send_raw_transaction(&raw).await?)
+    }
+
+    /// SOL balance in lamports, or an SPL balance when `mint` is set
+    pub async fn solana_balance(&self, address: String, mint: Option<String>) -> Result<u64> {
+        let solana = self.solana()?;
+        Ok(match mint {
+            Some(mint) => solana.get_token_balance(&address, &mint).await?,
+            None => solana.get_balance(&address).await?,
+        })
+    }
+
+    /// Latest blockhash for building Solana transactions
+    pub async fn latest_blockhash(&self) -> Result<String> {
+        Ok(self.solana()?.get_latest_blockhash().await?)
+    }
+
+    /// Broadcasts a base64 Solana transaction, returning its signature
+    pub async fn send_solana_transaction(&self, tx_base64: String) -> Result<String> {
+        Ok(self.solana()?.send_transaction(&tx_base64).await?)
+    }
+}
+
+impl Rpc {
+    fn evm(&self) -> Result<ChainProvider<Evm>> {
+        Ok(self.pool.evm(RPC)?)
+    }
+
+    fn solana(&self) -> Result<ChainProvider<Solana>> {
+        Ok(self.pool.chain::<Solana>(RPC)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_evm_calls() {
+        let server = MockServer::start().await;
+        for (rpc_method, result) in [
+            ("eth_getBalance", "0xde0b6b3a7640000"),
+            ("eth_getTransactionCount", "0x7"),
+        ] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(json!({ "method": rpc_method })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "jsonrpc": "2.0", "id": 1, "result": result
+                })))
+                .mount(&server)
+                .await;
+        }
+        let rpc = Rpc::new(server.uri()).unwrap();
+        let address = "0x9858EfFD232B4033E47d90003D41EC34EcaEda94".to_string();
+        assert_eq!(
+            rpc.evm_balance(address.clone()).await.unwrap(),
+            "1000000000000000000"
+        );
+        assert_eq!(rpc.nonce(address).await.unwrap(), 7);
+        assert!(matches!(
+            rpc.gas_price().await,
+            Err(crate::MobileError::Network { .. })
+        ));
+    }
+}
@@ -0,0 +1,176 @@
+//! HD wallet object exported to Kotlin and Swift
+
+use crate::{parse_chain, MobileError, Result};
+use std::sync::Arc;
+use walletd_hd::{HdChain, HdManager};
+use walletd_traits::{evm, Signer};
+
+/// One derived account
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct Account {
+    /// Chain name
+    pub chain: String,
+    /// Account index
+    pub index: u32,
+    /// Derivation path
+    pub path: String,
+    /// Default address on the chain's main network
+    pub address: String,
+    /// Compressed secp256k1 or raw Ed25519 public key
+    pub public_key: Vec<u8>,
+}
+
+/// A signed EVM transaction
+#[derive(Debug, Clone, PartialEq, Eq, uniffi::Record)]
+pub struct SignedTransaction {
+    /// Sender address
+    pub from: String,
+    /// `0x` hex encoding for `eth_sendRawTransaction`
+    pub raw: String,
+    /// `0x` hex transaction hash
+    pub hash: String,
+}
+
+/// A multi-chain wallet from a BIP-39 mnemonic
+///
+/// Keys are derived on demand and never leave Rust; the seed is zeroized
+/// when the object is released.
+#[derive(uniffi::Object)]
+pub struct Wallet {
+    manager: HdManager,
+}
+
+#[uniffi::export]
+impl Wallet {
+    /// Opens a wallet from a mnemonic and optional BIP-39 passphrase
+    #[uniffi::constructor]
+    pub fn from_mnemonic(phrase: String, passphrase: String) -> Result<Arc<Self>> {
+        let manager = HdManager::from_mnemonic(phrase.trim(), &passphrase)?;
+        Ok(Arc::new(Self { manager }))
+    }
+
+    /// Address of account `index` on `chain`
+    pub fn address(&self, chain: String, index: u32) -> Result<String> {
+        Ok(self.manager.address(parse_chain(&chain)?, index)?)
+    }
+
+    /// Path, address and public key of account `index` on `chain`
+    pub fn account(&self, chain: String, index: u32) -> Result<Account> {
+        let chain = parse_chain(&chain)?;
+        let account = self.manager.account(chain, index)?;
+        Ok(Account {
+            chain: chain.name().to_string(),
+            index,
+            path: account.path().to_string(),
+            address: account.address().to_string(),
+            public_key: account.signer().public_key(),
+        })
+    }
+
+    /// Signs a 32-byte digest with the account key
+    ///
+    /// Returns `r || s` for secp256k1 chains and a 64-byte Ed25519 signature.
+    pub async fn sign_hash(&self, chain: String, index: u32, hash: Vec<u8>) -> Result<Vec<u8>> {
+        let hash: [u8; 32] = hash
+            .try_into()
+            .map_err(|_| MobileError::invalid("hash must be 32 bytes"))?;
+        let signer = self.signer(&chain, index)?;
+        Ok(signer.sign_hash(&hash).await?)
+    }
+
+    /// Signs a message; EIP-191 `personal_sign` (`r || s || v`) on EVM chains
+    pub async fn sign_message(
+        &self,
+        chain: String,
+        index: u32,
+        message: Vec<u8>,
+    ) -> Result<Vec<u8>> {
+        let evm = parse_chain(&chain)?.is_evm();
+        let signer = self.signer(&chain, index)?;
+        if evm {
+            Ok(evm::personal_sign(signer.as_ref(), &message).await?)
+        } else {
+            Ok(signer.sign_message(&message).await?)
+        }
+    }
+
+    /// Signs an EVM transaction given as JSON
+    ///
+    /// Takes the `eth_sendTransaction` shape with `chainId`, `nonce`, `gas`
+    /// and either `gasPrice` or the EIP-1559 fee fields set; use [`Rpc`] to
+    /// look them up first.
+    ///
+    /// [`Rpc`]: crate::Rpc
+    pub async fn sign_evm_transaction(
+        &self,
+        index: u32,
+        tx_json: String,
+    ) -> Result<SignedTransaction> {
+        let tx: serde_json::Value = serde_json::from_str(&tx_json)
+            .map_err(|e| MobileError::invalid(format!("transaction is not JSON: {}", e)))?;
+        let tx = evm::TxRequest::from_json(&tx)?;
+        let signer = self.manager.signer(HdChain::Ethereum, index)?;
+        let signed = tx.sign(signer.as_ref()).await?;
+        Ok(SignedTransaction {
+            from: evm::address(signer.as_ref())?,
+            raw: format!("0x{}", hex::encode(&signed.raw)),
+            hash: format!("0x{}", hex::encode(signed.hash)),
+        })
+    }
+}
+
+impl Wallet {
+    fn signer(&self, chain: &str, index: u32) -> Result<Box<dyn Signer>> {
+        Ok(self.manager.signer(parse_chain(chain)?, index)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const ADDRESS: &str = "0x9858EfFD232B4033E47d90003D41EC34EcaEda94";
+
+    #[tokio::test]
+    async fn test_wallet_exports() {
+        let wallet = Wallet::from_mnemonic(PHRASE.into(), String::new()).unwrap();
+        assert_eq!(wallet.address("eth".into(), 0).unwrap(), ADDRESS);
+        let account = wallet.account("solana".into(), 0).unwrap();
+        assert_eq!(account.public_key.len(), 32);
+        assert_eq!(account.path, "m/44'/501'/0'/0'");
+
+        let signature = wallet
+            .sign_message("ethereum".into(), 0, b"hello".to_vec())
+            .await
+            .unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!(matches!(
+            wallet.sign_hash("eth".into(), 0, vec![0; 31]).await,
+            Err(MobileError::InvalidInput { .. })
+        ));
+
+        let signed = wallet
+            .sign_evm_transaction(
+                0,
+                format!(
+                    r#"{{"chainId": 1, "nonce": 0, "gas": 21000, "gasPrice": "1000000000", "to": "{}"}}"#,
+                    ADDRESS
+                ),
+            )
+            .await
+            .unwrap();
+        assert_eq!(signed.from, ADDRESS);
+        assert!(signed.raw.starts_with("0xf8"));
+        assert!(matches!(
+            wallet
+                .sign_evm_transaction(0, r#"{"nonce": 0}"#.into())
+                .await,
+            Err(MobileError::InvalidInput { .. })
+        ));
+        assert!(matches!(
+            Wallet::from_mnemonic("not a mnemonic".into(), String::new()),
+            Err(MobileError::Derivation { .. })
+        ));
+    }
+}
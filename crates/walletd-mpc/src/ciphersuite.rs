@@ -0,0 +1,568 @@
+//! FROST ciphersuites (RFC 9591 section 6)
+
+use crate::{MpcError, Result};
+use ff::{Field, PrimeField};
+use group::Group;
+use sha2::{Digest, Sha256, Sha512};
+use std::fmt;
+use walletd_traits::SignatureScheme;
+use zeroize::Zeroize;
+
+/// A prime-order group with the hash functions FROST needs
+pub trait Ciphersuite: fmt::Debug + Clone + Copy + PartialEq + Eq + Send + Sync + 'static {
+    /// Group element
+    type Element: Group<Scalar = Self::Scalar>;
+    /// Scalar field element
+    type Scalar: PrimeField + Zeroize;
+
+    /// Context string prefixed to every hash
+    const CONTEXT: &'static [u8];
+    /// Serialized element length
+    const ELEMENT_LEN: usize;
+    /// Serialized scalar length
+    const SCALAR_LEN: usize;
+    /// Scheme reported by signers using this suite
+    const SCHEME: SignatureScheme;
+
+    /// Encodes an element
+    fn serialize_element(element: &Self::Element) -> Vec<u8>;
+
+    /// Decodes an element, rejecting the identity and non-canonical encodings
+    fn deserialize_element(bytes: &[u8]) -> Result<Self::Element>;
+
+    /// Encodes a scalar
+    fn serialize_scalar(scalar: &Self::Scalar) -> Vec<u8>;
+
+    /// Decodes a canonical scalar
+    fn deserialize_scalar(bytes: &[u8]) -> Result<Self::Scalar>;
+
+    /// H1: binding factors
+    fn h1(m: &[u8]) -> Self::Scalar;
+    /// H2: the signature challenge
+    fn h2(m: &[u8]) -> Self::Scalar;
+    /// H3: nonce derivation
+    fn h3(m: &[u8]) -> Self::Scalar;
+    /// H4: message digest in the binding factor input
+    fn h4(m: &[u8]) -> Vec<u8>;
+    /// H5: commitment list digest in the binding factor input
+    fn h5(m: &[u8]) -> Vec<u8>;
+    /// Challenge for DKG proofs of knowledge
+    fn hdkg(m: &[u8]) -> Self::Scalar;
+
+    /// The key signatures verify under, derived from the group key
+    ///
+    /// The identity tweak by default; taproot suites move to the BIP-341
+    /// output key.
+    fn tweak(verifying_key: &Self::Element) -> KeyTweak<Self> {
+        KeyTweak {
+            output_key: *verifying_key,
+            share_factor: Self::Scalar::ONE,
+            offset: Self::Scalar::ZERO,
+        }
+    }
+
+    /// `ONE`, or `-ONE` if signers must negate their nonces so the group
+    /// commitment has the form the signature encoding requires
+    fn nonce_factor(_group_commitment: &Self::Element) -> Self::Scalar {
+        Self::Scalar::ONE
+    }
+
+    /// The signature challenge for commitment `r` under `output_key`
+    fn challenge(r: &Self::Element, output_key: &Self::Element, message: &[u8]) -> Self::Scalar {
+        let mut input = Self::serialize_element(r);
+        input.extend(Self::serialize_element(output_key));
+        input.extend_from_slice(message);
+        Self::h2(&input)
+    }
+
+    /// Encodes the output key the way the chain expects
+    fn serialize_output_key(output_key: &Self::Element) -> Vec<u8> {
+        Self::serialize_element(output_key)
+    }
+
+    /// Encodes a signature as `R || z`
+    fn serialize_signature(r: &Self::Element, z: &Self::Scalar) -> Vec<u8> {
+        let mut bytes = Self::serialize_element(r);
+        bytes.extend(Self::serialize_scalar(z));
+        bytes
+    }
+
+    /// Decodes a signature written by
+    /// [`serialize_signature`](Ciphersuite::serialize_signature)
+    fn deserialize_signature(bytes: &[u8]) -> Result<(Self::Element, Self::Scalar)> {
+        if bytes.len() != Self::ELEMENT_LEN + Self::SCALAR_LEN {
+            return Err(MpcError::Encoding("wrong signature length".into()));
+        }
+        let (r, z) = bytes.split_at(Self::ELEMENT_LEN);
+        Ok((Self::deserialize_element(r)?, Self::deserialize_scalar(z)?))
+    }
+
+    /// Checks `z·G == R + c·PK`
+    fn verify_equation(
+        r: &Self::Element,
+        z: &Self::Scalar,
+        public_key: &Self::Element,
+        challenge: &Self::Scalar,
+    ) -> bool {
+        Self::Element::generator() * *z == *r + *public_key * *challenge
+    }
+}
+
+/// How the group key maps to the key signatures verify under:
+/// `output_key = share_factor·PK + offset·G`
+///
+/// Signers scale their share by `share_factor`; the coordinator adds
+/// `offset·c` to the aggregated `z`, so no signer needs the tweak secret.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyTweak<C: Ciphersuite> {
+    /// Key signatures verify under
+    pub output_key: C::Element,
+    /// Factor applied to every signing share
+    pub share_factor: C::Scalar,
+    /// Scalar added to the group secret
+    pub offset: C::Scalar,
+}
+
+/// FROST(Ed25519, SHA-512); signatures verify as plain Ed25519
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ed25519Sha512;
+
+impl Ed25519Sha512 {
+    /// Expands a 32-byte Ed25519 seed into its signing scalar, so an existing
+    /// key can be split with [`split`](crate::split)
+    pub fn secret_from_seed(seed: &[u8; 32]) -> curve25519_dalek::Scalar {
+        let hash = Sha512::digest(seed);
+        let mut bytes: [u8; 32] = hash[..32].try_into().expect("32 bytes");
+        bytes[0] &= 248;
+        bytes[31] &= 127;
+        bytes[31] |= 64;
+        let scalar = curve25519_dalek::Scalar::from_bytes_mod_order(bytes);
+        bytes.zeroize();
+        scalar
+    }
+
+    fn hash_to_scalar(parts: &[&[u8]]) -> curve25519_dalek::Scalar {
+        let mut hasher = Sha512::new();
+        for part in parts {
+            hasher.update(part);
+        }
+        curve25519_dalek::Scalar::from_bytes_mod_order_wide(&hasher.finalize().into())
+    }
+}
+
+impl Ciphersuite for Ed25519Sha512 {
+    type Element = curve25519_dalek::EdwardsPoint;
+    type Scalar = curve25519_dalek::Scalar;
+
+    const CONTEXT: &'static [u8] = b"FROST-ED25519-SHA512-v1";
+    const ELEMENT_LEN: usize = 32;
+    const SCALAR_LEN: usize = 32;
+    const SCHEME: SignatureScheme = SignatureScheme::Ed25519;
+
+    fn serialize_element(element: &Self::Element) -> Vec<u8> {
+        element.compress().to_bytes().to_vec()
+    }
+
+    fn deserialize_element(bytes: &[u8]) -> Result<Self::Element> {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| MpcError::Encoding("Ed25519 point must be 32 bytes".into()))?;
+        let point = curve25519_dalek::edwards::CompressedEdwardsY(bytes)
+            .decompress()
+            .filter(|p| p.compress().to_bytes() == bytes)
+            .ok_or_else(|| MpcError::Encoding("invalid Ed25519 point".into()))?;
+        if point.is_identity().into() || !point.is_torsion_free() {
+            return Err(MpcError::Encoding(
+                "Ed25519 point not in the prime-order subgroup".into(),
+            ));
+        }
+        Ok(point)
+    }
+
+    fn serialize_scalar(scalar: &Self::Scalar) -> Vec<u8> {
+        scalar.to_bytes().to_vec()
+    }
+
+    fn deserialize_scalar(bytes: &[u8]) -> Result<Self::Scalar> {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| MpcError::Encoding("Ed25519 scalar must be 32 bytes".into()))?;
+        Option::from(curve25519_dalek::Scalar::from_canonical_bytes(bytes))
+            .ok_or_else(|| MpcError::Encoding("non-canonical Ed25519 scalar".into()))
+    }
+
+    fn h1(m: &[u8]) -> Self::Scalar {
+        Self::hash_to_scalar(&[Self::CONTEXT, b"rho", m])
+    }
+
+    fn h2(m: &[u8]) -> Self::Scalar {
+        // No context string, so signatures match RFC 8032
+        Self::hash_to_scalar(&[m])
+    }
+
+    fn h3(m: &[u8]) -> Self::Scalar {
+        Self::hash_to_scalar(&[Self::CONTEXT, b"nonce", m])
+    }
+
+    fn h4(m: &[u8]) -> Vec<u8> {
+        Sha512::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"msg")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn h5(m: &[u8]) -> Vec<u8> {
+        Sha512::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"com")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn hdkg(m: &[u8]) -> Self::Scalar {
+        Self::hash_to_scalar(&[Self::CONTEXT, b"dkg", m])
+    }
+
+    fn verify_equation(
+        r: &Self::Element,
+        z: &Self::Scalar,
+        public_key: &Self::Element,
+        challenge: &Self::Scalar,
+    ) -> bool {
+        // Cofactored, as RFC 9591 requires for Ed25519
+        let lhs = curve25519_dalek::EdwardsPoint::mul_base(z);
+        (lhs - r - public_key * challenge)
+            .mul_by_cofactor()
+            .is_identity()
+            .into()
+    }
+}
+
+/// FROST(secp256k1, SHA-256); signatures are Schnorr, not ECDSA
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secp256k1Sha256;
+
+/// hash_to_field from RFC 9380 with expand_message_xmd(SHA-256), L = 48
+fn secp256k1_hash_to_scalar(context: &[u8], tag: &[u8], m: &[u8]) -> k256::Scalar {
+    let mut dst = context.to_vec();
+    dst.extend_from_slice(tag);
+    let uniform = expand_message_xmd(m, &dst, 48);
+
+    let base = k256::Scalar::from(256u64);
+    uniform.iter().fold(k256::Scalar::ZERO, |acc, byte| {
+        acc * base + k256::Scalar::from(u64::from(*byte))
+    })
+}
+
+fn expand_message_xmd(msg: &[u8], dst: &[u8], len: usize) -> Vec<u8> {
+    let ell = len.div_ceil(32);
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+    let b0 = Sha256::new()
+        .chain_update([0u8; 64])
+        .chain_update(msg)
+        .chain_update((len as u16).to_be_bytes())
+        .chain_update([0u8])
+        .chain_update(&dst_prime)
+        .finalize();
+    let mut block = Sha256::new()
+        .chain_update(b0)
+        .chain_update([1u8])
+        .chain_update(&dst_prime)
+        .finalize();
+
+    let mut out = block.to_vec();
+    for i in 2..=ell {
+        let mixed: Vec<u8> = b0.iter().zip(block.iter()).map(|(a, b)| a ^ b).collect();
+        block = Sha256::new()
+            .chain_update(mixed)
+            .chain_update([i as u8])
+            .chain_update(&dst_prime)
+            .finalize();
+        out.extend_from_slice(&block);
+    }
+    out.truncate(len);
+    out
+}
+
+impl Ciphersuite for Secp256k1Sha256 {
+    type Element = k256::ProjectivePoint;
+    type Scalar = k256::Scalar;
+
+    const CONTEXT: &'static [u8] = b"FROST-secp256k1-SHA256-v1";
+    const ELEMENT_LEN: usize = 33;
+    const SCALAR_LEN: usize = 32;
+    const SCHEME: SignatureScheme = SignatureScheme::Secp256k1Schnorr;
+
+    fn serialize_element(element: &Self::Element) -> Vec<u8> {
+        use k256::elliptic_curve::sec1::ToEncodedPoint;
+        element
+            .to_affine()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec()
+    }
+
+    fn deserialize_element(bytes: &[u8]) -> Result<Self::Element> {
+        use k256::elliptic_curve::sec1::FromEncodedPoint;
+        if bytes.len() != Self::ELEMENT_LEN {
+            return Err(MpcError::Encoding(
+                "secp256k1 point must be 33 bytes".into(),
+            ));
+        }
+        let encoded = k256::EncodedPoint::from_bytes(bytes)
+            .map_err(|_| MpcError::Encoding("invalid secp256k1 point".into()))?;
+        let affine: Option<k256::AffinePoint> =
+            k256::AffinePoint::from_encoded_point(&encoded).into();
+        let point = affine
+            .map(k256::ProjectivePoint::from)
+            .ok_or_else(|| MpcError::Encoding("invalid secp256k1 point".into()))?;
+        if point.is_identity().into() {
+            return Err(MpcError::Encoding("secp256k1 point is the identity".into()));
+        }
+        Ok(point)
+    }
+
+    fn serialize_scalar(scalar: &Self::Scalar) -> Vec<u8> {
+        scalar.to_bytes().to_vec()
+    }
+
+    fn deserialize_scalar(bytes: &[u8]) -> Result<Self::Scalar> {
+        let bytes: [u8; 32] = bytes
+            .try_into()
+            .map_err(|_| MpcError::Encoding("secp256k1 scalar must be 32 bytes".into()))?;
+        Option::from(k256::Scalar::from_repr(k256::FieldBytes::from(bytes)))
+            .ok_or_else(|| MpcError::Encoding("non-canonical secp256k1 scalar".into()))
+    }
+
+    fn h1(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"rho", m)
+    }
+
+    fn h2(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"chal", m)
+    }
+
+    fn h3(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"nonce", m)
+    }
+
+    fn h4(m: &[u8]) -> Vec<u8> {
+        Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"msg")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn h5(m: &[u8]) -> Vec<u8> {
+        Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"com")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn hdkg(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"dkg", m)
+    }
+}
+
+/// FROST(secp256k1, SHA-256) producing BIP-340 signatures for a taproot
+/// key-path spend
+///
+/// Signatures verify under the BIP-86 output key: the group key tweaked by
+/// `TapTweak` with no script tree. [`verifying_key_bytes`] is that key
+/// x-only, ready for a P2TR output, and signatures are 64 bytes.
+///
+/// [`verifying_key_bytes`]: crate::PublicKeyPackage::verifying_key_bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Secp256k1Taproot;
+
+impl Secp256k1Taproot {
+    /// `-1` if `point` has an odd y coordinate, `1` otherwise
+    fn y_sign(point: &k256::ProjectivePoint) -> k256::Scalar {
+        use k256::elliptic_curve::point::AffineCoordinates;
+        if bool::from(point.to_affine().y_is_odd()) {
+            -k256::Scalar::ONE
+        } else {
+            k256::Scalar::ONE
+        }
+    }
+
+    fn x_only(point: &k256::ProjectivePoint) -> [u8; 32] {
+        use k256::elliptic_curve::point::AffineCoordinates;
+        point.to_affine().x().into()
+    }
+
+    /// BIP-340 tagged hash, reduced to a scalar
+    fn tagged_hash(tag: &[u8], parts: &[&[u8]]) -> k256::Scalar {
+        use k256::elliptic_curve::ops::Reduce;
+        let tag = Sha256::digest(tag);
+        let mut hasher = Sha256::new().chain_update(tag).chain_update(tag);
+        for part in parts {
+            hasher.update(part);
+        }
+        <k256::Scalar as Reduce<k256::U256>>::reduce_bytes(&hasher.finalize())
+    }
+}
+
+impl Ciphersuite for Secp256k1Taproot {
+    type Element = k256::ProjectivePoint;
+    type Scalar = k256::Scalar;
+
+    const CONTEXT: &'static [u8] = b"FROST-secp256k1-SHA256-TR-v1";
+    const ELEMENT_LEN: usize = 33;
+    const SCALAR_LEN: usize = 32;
+    const SCHEME: SignatureScheme = SignatureScheme::Bip340;
+
+    fn serialize_element(element: &Self::Element) -> Vec<u8> {
+        Secp256k1Sha256::serialize_element(element)
+    }
+
+    fn deserialize_element(bytes: &[u8]) -> Result<Self::Element> {
+        Secp256k1Sha256::deserialize_element(bytes)
+    }
+
+    fn serialize_scalar(scalar: &Self::Scalar) -> Vec<u8> {
+        Secp256k1Sha256::serialize_scalar(scalar)
+    }
+
+    fn deserialize_scalar(bytes: &[u8]) -> Result<Self::Scalar> {
+        Secp256k1Sha256::deserialize_scalar(bytes)
+    }
+
+    fn h1(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"rho", m)
+    }
+
+    fn h2(m: &[u8]) -> Self::Scalar {
+        Self::tagged_hash(b"BIP0340/challenge", &[m])
+    }
+
+    fn h3(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"nonce", m)
+    }
+
+    fn h4(m: &[u8]) -> Vec<u8> {
+        Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"msg")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn h5(m: &[u8]) -> Vec<u8> {
+        Sha256::new()
+            .chain_update(Self::CONTEXT)
+            .chain_update(b"com")
+            .chain_update(m)
+            .finalize()
+            .to_vec()
+    }
+
+    fn hdkg(m: &[u8]) -> Self::Scalar {
+        secp256k1_hash_to_scalar(Self::CONTEXT, b"dkg", m)
+    }
+
+    fn tweak(verifying_key: &Self::Element) -> KeyTweak<Self> {
+        // BIP-341 with no script tree: Q = lift_x(P) + H_TapTweak(P.x)·G,
+        // then negated if needed so Q has an even y as BIP-340 requires
+        let p_sign = Self::y_sign(verifying_key);
+        let t = Self::tagged_hash(b"TapTweak", &[&Self::x_only(verifying_key)]);
+        let q = *verifying_key * p_sign + k256::ProjectivePoint::GENERATOR * t;
+        let q_sign = Self::y_sign(&q);
+        KeyTweak {
+            output_key: q * q_sign,
+            share_factor: q_sign * p_sign,
+            offset: q_sign * t,
+        }
+    }
+
+    fn nonce_factor(group_commitment: &Self::Element) -> Self::Scalar {
+        Self::y_sign(group_commitment)
+    }
+
+    fn challenge(r: &Self::Element, output_key: &Self::Element, message: &[u8]) -> Self::Scalar {
+        Self::h2(&[&Self::x_only(r)[..], &Self::x_only(output_key), message].concat())
+    }
+
+    fn serialize_output_key(output_key: &Self::Element) -> Vec<u8> {
+        Self::x_only(output_key).to_vec()
+    }
+
+    fn serialize_signature(r: &Self::Element, z: &Self::Scalar) -> Vec<u8> {
+        let mut bytes = Self::x_only(r).to_vec();
+        bytes.extend(Self::serialize_scalar(z));
+        bytes
+    }
+
+    fn deserialize_signature(bytes: &[u8]) -> Result<(Self::Element, Self::Scalar)> {
+        if bytes.len() != 64 {
+            return Err(MpcError::Encoding(
+                "BIP-340 signature must be 64 bytes".into(),
+            ));
+        }
+        // lift_x: the point with this x and an even y
+        let r = Self::deserialize_element(&[&[0x02], &bytes[..32]].concat())?;
+        Ok((r, Self::deserialize_scalar(&bytes[32..])?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expand_message_xmd() {
+        // RFC 9380 appendix K.1, DST "QUUX-V01-CS02-with-expander-SHA256-128"
+        let out = expand_message_xmd(b"", b"QUUX-V01-CS02-with-expander-SHA256-128", 0x20);
+        assert_eq!(
+            out.iter().map(|b| format!("{:02x}", b)).collect::<String>(),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+    }
+
+    #[test]
+    fn test_taproot_tweak_matches_bip86() {
+        // BIP-86 test vector, first receiving address of account 0
+        let internal =
+            hex::decode("02cc8a4bc64d897bddc5fbc2f670f7a8ba0b386779106cf1223c6fc5d7cd6fc115")
+                .unwrap();
+        let key = Secp256k1Taproot::deserialize_element(&internal).unwrap();
+        let tweak = Secp256k1Taproot::tweak(&key);
+        assert_eq!(
+            hex::encode(Secp256k1Taproot::serialize_output_key(&tweak.output_key)),
+            "a60869f0dbcf1dc659c9cecbaf8050135ea9e8cdc487053f1dc6880949dc684c"
+        );
+        let g = k256::ProjectivePoint::GENERATOR;
+        assert_eq!(
+            tweak.output_key,
+            key * tweak.share_factor + g * tweak.offset
+        );
+    }
+
+    #[test]
+    fn test_element_round_trip_and_rejection() {
+        let g = k256::ProjectivePoint::GENERATOR;
+        let bytes = Secp256k1Sha256::serialize_element(&g);
+        assert_eq!(bytes.len(), 33);
+        assert_eq!(Secp256k1Sha256::deserialize_element(&bytes).unwrap(), g);
+        assert!(Secp256k1Sha256::deserialize_element(&[0u8; 33]).is_err());
+
+        let g = <Ed25519Sha512 as Ciphersuite>::Element::generator();
+        let bytes = Ed25519Sha512::serialize_element(&g);
+        assert_eq!(Ed25519Sha512::deserialize_element(&bytes).unwrap(), g);
+        // The identity and a small-order point
+        let mut identity = [0u8; 32];
+        identity[0] = 1;
+        assert!(Ed25519Sha512::deserialize_element(&identity).is_err());
+        assert!(Ed25519Sha512::deserialize_element(&[0u8; 32]).is_err());
+        assert!(Ed25519Sha512::deserialize_scalar(&[0xff; 32]).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Byte encoding shared by the wire types
+
+use crate::{Ciphersuite, Identifier, MpcError, Result};
+
+#[derive(Default)]
+pub(crate) struct Writer(Vec<u8>);
+
+impl Writer {
+    pub(crate) fn u16(mut self, value: u16) -> Self {
+        self.0.extend_from_slice(&value.to_be_bytes());
+        self
+    }
+
+    pub(crate) fn identifier(self, id: Identifier) -> Self {
+        self.u16(id.get())
+    }
+
+    pub(crate) fn element<C: Ciphersuite>(mut self, element: &C::Element) -> Self {
+        self.0.extend_from_slice(&C::serialize_element(element));
+        self
+    }
+
+    pub(crate) fn scalar<C: Ciphersuite>(mut self, scalar: &C::Scalar) -> Self {
+        self.0.extend_from_slice(&C::serialize_scalar(scalar));
+        self
+    }
+
+    pub(crate) fn bytes(mut self, bytes: &[u8]) -> Self {
+        self.0
+            .extend_from_slice(&(bytes.len() as u32).to_be_bytes());
+        self.0.extend_from_slice(bytes);
+        self
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.0
+    }
+}
+
+pub(crate) struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self(bytes)
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(MpcError::Encoding("unexpected end of input".into()));
+        }
+        let (head, tail) = self.0.split_at(len);
+        self.0 = tail;
+        Ok(head)
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_be_bytes(
+            self.take(2)?.try_into().expect("2 bytes"),
+        ))
+    }
+
+    pub(crate) fn identifier(&mut self) -> Result<Identifier> {
+        Identifier::new(self.u16()?)
+    }
+
+    pub(crate) fn element<C: Ciphersuite>(&mut self) -> Result<C::Element> {
+        C::deserialize_element(self.take(C::ELEMENT_LEN)?)
+    }
+
+    pub(crate) fn scalar<C: Ciphersuite>(&mut self) -> Result<C::Scalar> {
+        C::deserialize_scalar(self.take(C::SCALAR_LEN)?)
+    }
+
+    pub(crate) fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = u32::from_be_bytes(self.take(4)?.try_into().expect("4 bytes"));
+        self.take(len as usize)
+    }
+
+    pub(crate) fn finish(self) -> Result<()> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(MpcError::Encoding("trailing bytes".into()))
+        }
+    }
+}
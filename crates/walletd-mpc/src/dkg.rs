@@ -0,0 +1,371 @@
+//! Distributed key generation (Pedersen DKG with proofs of knowledge)
+//!
+//! No party ever holds the whole key. Each participant:
+//!
+//! 1. calls [`part1`] and broadcasts its [`Round1Package`]
+//! 2. calls [`part2`] with everyone else's round 1 packages and sends each
+//!    [`Round2Package`] privately to its recipient
+//! 3. calls [`part3`] with the round 2 packages addressed to it
+//!
+//! Round 1 packages must reach everyone unchanged (an authenticated
+//! broadcast); round 2 packages need a confidential channel.
+
+use crate::codec::{Reader, Writer};
+use crate::keys::{evaluate_polynomial, validate_params, VssCommitment};
+use crate::{Ciphersuite, Identifier, KeyPackage, MpcError, PublicKeyPackage, Result};
+use ff::Field;
+use group::Group;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Round 1 state kept by a participant
+pub struct Round1SecretPackage<C: Ciphersuite> {
+    identifier: Identifier,
+    coefficients: Vec<C::Scalar>,
+    commitment: VssCommitment<C>,
+    max_signers: u16,
+}
+
+/// Round 1 broadcast: a polynomial commitment and a proof of knowledge of
+/// its constant term
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Round1Package<C: Ciphersuite> {
+    commitment: VssCommitment<C>,
+    proof_r: C::Element,
+    proof_mu: C::Scalar,
+}
+
+/// Round 2 state kept by a participant
+pub struct Round2SecretPackage<C: Ciphersuite> {
+    identifier: Identifier,
+    own_share: C::Scalar,
+    commitment: VssCommitment<C>,
+    received: BTreeMap<Identifier, VssCommitment<C>>,
+}
+
+/// Round 2 message: the sender's polynomial evaluated at the recipient
+#[derive(Clone, PartialEq, Eq)]
+pub struct Round2Package<C: Ciphersuite> {
+    signing_share: C::Scalar,
+}
+
+/// Round 2 packages keyed by recipient
+pub type Round2Outbox<C> = BTreeMap<Identifier, Round2Package<C>>;
+
+/// Starts key generation for `identifier`
+pub fn part1<C: Ciphersuite>(
+    identifier: Identifier,
+    max_signers: u16,
+    min_signers: u16,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<(Round1SecretPackage<C>, Round1Package<C>)> {
+    validate_params(max_signers, min_signers)?;
+    if identifier.get() > max_signers {
+        return Err(MpcError::InvalidParameters(format!(
+            "identifier {} exceeds max_signers",
+            identifier
+        )));
+    }
+
+    let coefficients: Vec<C::Scalar> = (0..min_signers)
+        .map(|_| C::Scalar::random(&mut *rng))
+        .collect();
+    let commitment = VssCommitment::<C>::from_coefficients(&coefficients);
+
+    let mut k = C::Scalar::random(&mut *rng);
+    let proof_r = C::Element::generator() * k;
+    let challenge = proof_challenge::<C>(identifier, &commitment, &proof_r);
+    let proof_mu = k + coefficients[0] * challenge;
+    k.zeroize();
+
+    let package = Round1Package {
+        commitment: commitment.clone(),
+        proof_r,
+        proof_mu,
+    };
+    let secret = Round1SecretPackage {
+        identifier,
+        coefficients,
+        commitment,
+        max_signers,
+    };
+    Ok((secret, package))
+}
+
+/// Verifies everyone else's round 1 packages and computes their shares
+pub fn part2<C: Ciphersuite>(
+    secret: Round1SecretPackage<C>,
+    round1_packages: &BTreeMap<Identifier, Round1Package<C>>,
+) -> Result<(Round2SecretPackage<C>, Round2Outbox<C>)> {
+    check_senders(
+        secret.identifier,
+        secret.max_signers,
+        round1_packages.keys(),
+    )?;
+
+    let mut outgoing = BTreeMap::new();
+    for (id, package) in round1_packages {
+        if package.commitment.0.len() != secret.coefficients.len() {
+            return Err(MpcError::InvalidProof(*id));
+        }
+        let challenge = proof_challenge::<C>(*id, &package.commitment, &package.proof_r);
+        if C::Element::generator() * package.proof_mu
+            != package.proof_r + package.commitment.verifying_key() * challenge
+        {
+            return Err(MpcError::InvalidProof(*id));
+        }
+        outgoing.insert(
+            *id,
+            Round2Package {
+                signing_share: evaluate_polynomial::<C>(&secret.coefficients, *id),
+            },
+        );
+    }
+
+    let state = Round2SecretPackage {
+        identifier: secret.identifier,
+        own_share: evaluate_polynomial::<C>(&secret.coefficients, secret.identifier),
+        commitment: secret.commitment.clone(),
+        received: round1_packages
+            .iter()
+            .map(|(id, package)| (*id, package.commitment.clone()))
+            .collect(),
+    };
+    Ok((state, outgoing))
+}
+
+/// Verifies the shares addressed to this participant and derives its key
+pub fn part3<C: Ciphersuite>(
+    secret: &Round2SecretPackage<C>,
+    round2_packages: &BTreeMap<Identifier, Round2Package<C>>,
+) -> Result<(KeyPackage<C>, PublicKeyPackage<C>)> {
+    if !round2_packages.keys().eq(secret.received.keys()) {
+        return Err(MpcError::InvalidParameters(
+            "round 2 packages must come from every round 1 sender".into(),
+        ));
+    }
+
+    let mut signing_share = secret.own_share;
+    for (id, package) in round2_packages {
+        let expected = secret.received[id].evaluate(secret.identifier);
+        if C::Element::generator() * package.signing_share != expected {
+            return Err(MpcError::InvalidShare(*id));
+        }
+        signing_share += package.signing_share;
+    }
+
+    let group =
+        VssCommitment::sum(std::iter::once(&secret.commitment).chain(secret.received.values()));
+    let identifiers = std::iter::once(secret.identifier).chain(secret.received.keys().copied());
+    let mut identifiers: Vec<Identifier> = identifiers.collect();
+    identifiers.sort();
+
+    let public = PublicKeyPackage::from_commitment(&group, identifiers.into_iter());
+    let key = KeyPackage::new(
+        secret.identifier,
+        signing_share,
+        group.verifying_key(),
+        public.min_signers(),
+    );
+    signing_share.zeroize();
+    Ok((key, public))
+}
+
+fn check_senders<'a>(
+    own: Identifier,
+    max_signers: u16,
+    senders: impl ExactSizeIterator<Item = &'a Identifier>,
+) -> Result<()> {
+    if senders.len() != usize::from(max_signers) - 1 {
+        return Err(MpcError::InvalidParameters(format!(
+            "expected {} round 1 packages",
+            max_signers - 1
+        )));
+    }
+    for id in senders {
+        if *id == own || id.get() > max_signers {
+            return Err(MpcError::InvalidParameters(format!(
+                "unexpected round 1 sender {}",
+                id
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn proof_challenge<C: Ciphersuite>(
+    identifier: Identifier,
+    commitment: &VssCommitment<C>,
+    r: &C::Element,
+) -> C::Scalar {
+    let mut input = C::serialize_scalar(&identifier.to_scalar::<C>());
+    input.extend(C::serialize_element(&commitment.verifying_key()));
+    input.extend(C::serialize_element(r));
+    C::hdkg(&input)
+}
+
+impl<C: Ciphersuite> Round1Package<C> {
+    /// Encodes the package for broadcast
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default().u16(self.commitment.0.len() as u16);
+        for element in &self.commitment.0 {
+            writer = writer.element::<C>(element);
+        }
+        writer
+            .element::<C>(&self.proof_r)
+            .scalar::<C>(&self.proof_mu)
+            .finish()
+    }
+
+    /// Decodes a package written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let count = reader.u16()?;
+        if count == 0 {
+            return Err(MpcError::Encoding("empty commitment".into()));
+        }
+        let coefficients = (0..count)
+            .map(|_| reader.element::<C>())
+            .collect::<Result<Vec<_>>>()?;
+        let package = Self {
+            commitment: VssCommitment(coefficients),
+            proof_r: reader.element::<C>()?,
+            proof_mu: reader.scalar::<C>()?,
+        };
+        reader.finish()?;
+        Ok(package)
+    }
+}
+
+impl<C: Ciphersuite> Round2Package<C> {
+    /// Encodes the package; send it only to its recipient
+    pub fn to_bytes(&self) -> Vec<u8> {
+        C::serialize_scalar(&self.signing_share)
+    }
+
+    /// Decodes a package written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            signing_share: C::deserialize_scalar(bytes)?,
+        })
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for Round1SecretPackage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Round1SecretPackage")
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for Round2SecretPackage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Round2SecretPackage")
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for Round2Package<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Round2Package").finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> Drop for Round1SecretPackage<C> {
+    fn drop(&mut self) {
+        self.coefficients.zeroize();
+    }
+}
+
+impl<C: Ciphersuite> Drop for Round2SecretPackage<C> {
+    fn drop(&mut self) {
+        self.own_share.zeroize();
+    }
+}
+
+impl<C: Ciphersuite> Drop for Round2Package<C> {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Secp256k1Sha256;
+
+    type C = Secp256k1Sha256;
+
+    #[test]
+    fn test_dkg_produces_consistent_keys() {
+        let mut rng = rand::thread_rng();
+        let ids: Vec<Identifier> = (1..=3).map(|i| Identifier::new(i).unwrap()).collect();
+
+        let mut round1_secrets = BTreeMap::new();
+        let mut round1 = BTreeMap::new();
+        for id in &ids {
+            let (secret, package) = part1::<C>(*id, 3, 2, &mut rng).unwrap();
+            // Packages travel as bytes
+            let package = Round1Package::from_bytes(&package.to_bytes()).unwrap();
+            round1_secrets.insert(*id, secret);
+            round1.insert(*id, package);
+        }
+
+        let mut round2_secrets = BTreeMap::new();
+        let mut inbox: BTreeMap<Identifier, BTreeMap<Identifier, Round2Package<C>>> =
+            BTreeMap::new();
+        for (id, secret) in round1_secrets {
+            let others = round1
+                .iter()
+                .filter(|(other, _)| **other != id)
+                .map(|(other, p)| (*other, p.clone()))
+                .collect();
+            let (state, outgoing) = part2(secret, &others).unwrap();
+            for (to, package) in outgoing {
+                inbox.entry(to).or_default().insert(id, package);
+            }
+            round2_secrets.insert(id, state);
+        }
+
+        let results: Vec<_> = ids
+            .iter()
+            .map(|id| part3(&round2_secrets[id], &inbox[id]).unwrap())
+            .collect();
+        for (key, public) in &results {
+            assert_eq!(public, &results[0].1);
+            assert_eq!(key.verifying_key(), results[0].1.verifying_key());
+            assert_eq!(
+                Some(key.verifying_share()),
+                public.verifying_shares().get(&key.identifier())
+            );
+        }
+
+        // A corrupted share names its sender
+        let id1 = ids[0];
+        let mut tampered = inbox[&id1].clone();
+        tampered.get_mut(&ids[2]).unwrap().signing_share += k256::Scalar::ONE;
+        assert!(matches!(
+            part3(&round2_secrets[&id1], &tampered),
+            Err(MpcError::InvalidShare(id)) if id == ids[2]
+        ));
+    }
+
+    #[test]
+    fn test_bad_proof_rejected() {
+        let mut rng = rand::thread_rng();
+        let ids: Vec<Identifier> = (1..=2).map(|i| Identifier::new(i).unwrap()).collect();
+        let (secret, _) = part1::<C>(ids[0], 2, 2, &mut rng).unwrap();
+        let (_, mut package) = part1::<C>(ids[1], 2, 2, &mut rng).unwrap();
+        package.proof_mu += k256::Scalar::ONE;
+
+        let round1 = BTreeMap::from([(ids[1], package)]);
+        assert!(matches!(
+            part2(secret, &round1),
+            Err(MpcError::InvalidProof(id)) if id == ids[1]
+        ));
+    }
+}
@@ -0,0 +1,464 @@
+//! Identifiers, key shares and trusted-dealer key generation
+
+use crate::codec::{Reader, Writer};
+use crate::{Ciphersuite, MpcError, Result};
+use ff::Field;
+use group::Group;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// A participant's index, from 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Identifier(u16);
+
+impl Identifier {
+    /// Creates an identifier; zero is reserved for the group secret
+    pub fn new(index: u16) -> Result<Self> {
+        if index == 0 {
+            return Err(MpcError::InvalidParameters(
+                "identifier must be nonzero".into(),
+            ));
+        }
+        Ok(Self(index))
+    }
+
+    /// The index
+    pub fn get(self) -> u16 {
+        self.0
+    }
+
+    pub(crate) fn to_scalar<C: Ciphersuite>(self) -> C::Scalar {
+        C::Scalar::from(u64::from(self.0))
+    }
+}
+
+impl fmt::Display for Identifier {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+/// Feldman VSS commitment to a secret polynomial, one element per coefficient
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VssCommitment<C: Ciphersuite>(pub(crate) Vec<C::Element>);
+
+impl<C: Ciphersuite> VssCommitment<C> {
+    pub(crate) fn from_coefficients(coefficients: &[C::Scalar]) -> Self {
+        Self(
+            coefficients
+                .iter()
+                .map(|c| C::Element::generator() * *c)
+                .collect(),
+        )
+    }
+
+    /// The committed coefficients
+    pub fn coefficients(&self) -> &[C::Element] {
+        &self.0
+    }
+
+    /// Commitment to the constant term, i.e. the public key
+    pub fn verifying_key(&self) -> C::Element {
+        self.0[0]
+    }
+
+    /// Commitment to the share for `id`
+    pub fn evaluate(&self, id: Identifier) -> C::Element {
+        let x = id.to_scalar::<C>();
+        self.0
+            .iter()
+            .rev()
+            .fold(C::Element::identity(), |acc, c| acc * x + *c)
+    }
+
+    pub(crate) fn sum<'a>(commitments: impl Iterator<Item = &'a Self>) -> Self {
+        let mut total: Vec<C::Element> = Vec::new();
+        for commitment in commitments {
+            if total.is_empty() {
+                total = commitment.0.clone();
+            } else {
+                for (t, c) in total.iter_mut().zip(&commitment.0) {
+                    *t += *c;
+                }
+            }
+        }
+        Self(total)
+    }
+}
+
+/// A share handed out by a trusted dealer, checked on receipt against the
+/// dealer's commitment
+#[derive(Clone)]
+pub struct SecretShare<C: Ciphersuite> {
+    identifier: Identifier,
+    signing_share: C::Scalar,
+    commitment: VssCommitment<C>,
+}
+
+impl<C: Ciphersuite> SecretShare<C> {
+    /// The recipient
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// The dealer's commitment
+    pub fn commitment(&self) -> &VssCommitment<C> {
+        &self.commitment
+    }
+
+    /// Checks the share against the commitment
+    pub fn verify(&self) -> Result<()> {
+        if C::Element::generator() * self.signing_share != self.commitment.evaluate(self.identifier)
+        {
+            return Err(MpcError::InvalidShare(self.identifier));
+        }
+        Ok(())
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for SecretShare<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SecretShare")
+            .field("identifier", &self.identifier)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> Drop for SecretShare<C> {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+/// Everything one participant needs to sign
+#[derive(Clone)]
+pub struct KeyPackage<C: Ciphersuite> {
+    identifier: Identifier,
+    signing_share: C::Scalar,
+    verifying_share: C::Element,
+    verifying_key: C::Element,
+    min_signers: u16,
+}
+
+impl<C: Ciphersuite> KeyPackage<C> {
+    pub(crate) fn new(
+        identifier: Identifier,
+        signing_share: C::Scalar,
+        verifying_key: C::Element,
+        min_signers: u16,
+    ) -> Self {
+        Self {
+            identifier,
+            signing_share,
+            verifying_share: C::Element::generator() * signing_share,
+            verifying_key,
+            min_signers,
+        }
+    }
+
+    /// This participant
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    pub(crate) fn signing_share(&self) -> &C::Scalar {
+        &self.signing_share
+    }
+
+    /// Public counterpart of this participant's share
+    pub fn verifying_share(&self) -> &C::Element {
+        &self.verifying_share
+    }
+
+    /// The group public key
+    pub fn verifying_key(&self) -> &C::Element {
+        &self.verifying_key
+    }
+
+    /// Signers needed to produce a signature
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    /// Encodes the package, including the secret share
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Writer::default()
+            .identifier(self.identifier)
+            .u16(self.min_signers)
+            .scalar::<C>(&self.signing_share)
+            .element::<C>(&self.verifying_key)
+            .finish()
+    }
+
+    /// Decodes a package written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let identifier = reader.identifier()?;
+        let min_signers = reader.u16()?;
+        let signing_share = reader.scalar::<C>()?;
+        let verifying_key = reader.element::<C>()?;
+        reader.finish()?;
+        Ok(Self::new(
+            identifier,
+            signing_share,
+            verifying_key,
+            min_signers,
+        ))
+    }
+}
+
+impl<C: Ciphersuite> TryFrom<SecretShare<C>> for KeyPackage<C> {
+    type Error = MpcError;
+
+    fn try_from(share: SecretShare<C>) -> Result<Self> {
+        share.verify()?;
+        Ok(Self::new(
+            share.identifier,
+            share.signing_share,
+            share.commitment.verifying_key(),
+            share.commitment.0.len() as u16,
+        ))
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for KeyPackage<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPackage")
+            .field("identifier", &self.identifier)
+            .field("verifying_key", &self.verifying_key)
+            .field("min_signers", &self.min_signers)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> Drop for KeyPackage<C> {
+    fn drop(&mut self) {
+        self.signing_share.zeroize();
+    }
+}
+
+/// Public data shared by the group: used to verify signature shares
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PublicKeyPackage<C: Ciphersuite> {
+    verifying_shares: BTreeMap<Identifier, C::Element>,
+    verifying_key: C::Element,
+    min_signers: u16,
+}
+
+impl<C: Ciphersuite> PublicKeyPackage<C> {
+    pub(crate) fn from_commitment(
+        commitment: &VssCommitment<C>,
+        identifiers: impl Iterator<Item = Identifier>,
+    ) -> Self {
+        Self {
+            verifying_shares: identifiers
+                .map(|id| (id, commitment.evaluate(id)))
+                .collect(),
+            verifying_key: commitment.verifying_key(),
+            min_signers: commitment.0.len() as u16,
+        }
+    }
+
+    /// Each participant's public share
+    pub fn verifying_shares(&self) -> &BTreeMap<Identifier, C::Element> {
+        &self.verifying_shares
+    }
+
+    /// The group public key
+    pub fn verifying_key(&self) -> &C::Element {
+        &self.verifying_key
+    }
+
+    /// The key signatures verify under, encoded the way the chain expects:
+    /// the group key, or the x-only output key for taproot
+    pub fn verifying_key_bytes(&self) -> Vec<u8> {
+        C::serialize_output_key(&C::tweak(&self.verifying_key).output_key)
+    }
+
+    /// Signers needed to produce a signature
+    pub fn min_signers(&self) -> u16 {
+        self.min_signers
+    }
+
+    /// Encodes the package
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default()
+            .u16(self.min_signers)
+            .element::<C>(&self.verifying_key)
+            .u16(self.verifying_shares.len() as u16);
+        for (id, share) in &self.verifying_shares {
+            writer = writer.identifier(*id).element::<C>(share);
+        }
+        writer.finish()
+    }
+
+    /// Decodes a package written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let min_signers = reader.u16()?;
+        let verifying_key = reader.element::<C>()?;
+        let mut verifying_shares = BTreeMap::new();
+        for _ in 0..reader.u16()? {
+            let id = reader.identifier()?;
+            verifying_shares.insert(id, reader.element::<C>()?);
+        }
+        reader.finish()?;
+        validate_params(verifying_shares.len() as u16, min_signers)?;
+        Ok(Self {
+            verifying_shares,
+            verifying_key,
+            min_signers,
+        })
+    }
+}
+
+/// Generates a fresh key and splits it into `max_signers` shares, any
+/// `min_signers` of which can sign
+///
+/// The dealer briefly knows the whole key; use [`dkg`](crate::dkg) when
+/// no single party should.
+pub fn generate_with_dealer<C: Ciphersuite>(
+    max_signers: u16,
+    min_signers: u16,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<(Vec<SecretShare<C>>, PublicKeyPackage<C>)> {
+    let mut secret = C::Scalar::random(&mut *rng);
+    let result = split::<C>(&secret, max_signers, min_signers, rng);
+    secret.zeroize();
+    result
+}
+
+/// Splits an existing secret, e.g. to move a single-key address under
+/// threshold control without changing the address
+pub fn split<C: Ciphersuite>(
+    secret: &C::Scalar,
+    max_signers: u16,
+    min_signers: u16,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> Result<(Vec<SecretShare<C>>, PublicKeyPackage<C>)> {
+    validate_params(max_signers, min_signers)?;
+    if bool::from(secret.is_zero()) {
+        return Err(MpcError::InvalidParameters("secret must be nonzero".into()));
+    }
+
+    let mut coefficients = vec![*secret];
+    coefficients.extend((1..min_signers).map(|_| C::Scalar::random(&mut *rng)));
+    let commitment = VssCommitment::<C>::from_coefficients(&coefficients);
+
+    let identifiers = (1..=max_signers).map(Identifier);
+    let shares = identifiers
+        .clone()
+        .map(|id| SecretShare {
+            identifier: id,
+            signing_share: evaluate_polynomial::<C>(&coefficients, id),
+            commitment: commitment.clone(),
+        })
+        .collect();
+    coefficients.zeroize();
+
+    Ok((
+        shares,
+        PublicKeyPackage::from_commitment(&commitment, identifiers),
+    ))
+}
+
+pub(crate) fn validate_params(max_signers: u16, min_signers: u16) -> Result<()> {
+    if min_signers < 2 {
+        return Err(MpcError::InvalidParameters(
+            "min_signers must be at least 2".into(),
+        ));
+    }
+    if max_signers < min_signers {
+        return Err(MpcError::InvalidParameters(
+            "max_signers must be at least min_signers".into(),
+        ));
+    }
+    Ok(())
+}
+
+pub(crate) fn evaluate_polynomial<C: Ciphersuite>(
+    coefficients: &[C::Scalar],
+    id: Identifier,
+) -> C::Scalar {
+    let x = id.to_scalar::<C>();
+    coefficients
+        .iter()
+        .rev()
+        .fold(C::Scalar::ZERO, |acc, c| acc * x + c)
+}
+
+/// Lagrange coefficient for `id` interpolating at zero over `signers`
+pub(crate) fn lagrange<C: Ciphersuite>(
+    id: Identifier,
+    signers: impl Iterator<Item = Identifier>,
+) -> C::Scalar {
+    let x = id.to_scalar::<C>();
+    let (mut num, mut den) = (C::Scalar::ONE, C::Scalar::ONE);
+    for other in signers.filter(|other| *other != id) {
+        let xj = other.to_scalar::<C>();
+        num *= xj;
+        den *= xj - x;
+    }
+    // Distinct nonzero identifiers keep the denominator invertible
+    num * den.invert().unwrap_or(C::Scalar::ZERO)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Ed25519Sha512, Secp256k1Sha256};
+
+    #[test]
+    fn test_dealer_shares_interpolate() {
+        let mut rng = rand::thread_rng();
+        let (shares, public) = generate_with_dealer::<Secp256k1Sha256>(5, 3, &mut rng).unwrap();
+        assert_eq!(public.verifying_shares().len(), 5);
+
+        let packages: Vec<KeyPackage<_>> = shares
+            .into_iter()
+            .map(|s| KeyPackage::try_from(s).unwrap())
+            .collect();
+        let ids = [
+            packages[0].identifier(),
+            packages[2].identifier(),
+            packages[4].identifier(),
+        ];
+        let secret = ids
+            .iter()
+            .map(|id| {
+                let pkg = &packages[usize::from(id.get()) - 1];
+                lagrange::<Secp256k1Sha256>(*id, ids.iter().copied()) * pkg.signing_share()
+            })
+            .fold(k256::Scalar::ZERO, |a, b| a + b);
+        assert_eq!(
+            k256::ProjectivePoint::GENERATOR * secret,
+            *public.verifying_key()
+        );
+
+        let bytes = public.to_bytes();
+        assert_eq!(PublicKeyPackage::from_bytes(&bytes).unwrap(), public);
+        let pkg = KeyPackage::<Secp256k1Sha256>::from_bytes(&packages[1].to_bytes()).unwrap();
+        assert_eq!(pkg.verifying_share(), packages[1].verifying_share());
+    }
+
+    #[test]
+    fn test_split_existing_key_and_tampered_share() {
+        let mut rng = rand::thread_rng();
+        let seed = [9u8; 32];
+        let secret = Ed25519Sha512::secret_from_seed(&seed);
+        let (mut shares, public) = split::<Ed25519Sha512>(&secret, 3, 2, &mut rng).unwrap();
+        let expected = ed25519_dalek::SigningKey::from_bytes(&seed).verifying_key();
+        assert_eq!(public.verifying_key_bytes(), expected.to_bytes());
+
+        shares[1].signing_share += curve25519_dalek::Scalar::ONE;
+        assert!(matches!(
+            KeyPackage::try_from(shares.remove(1)),
+            Err(MpcError::InvalidShare(id)) if id.get() == 2
+        ));
+
+        assert!(generate_with_dealer::<Ed25519Sha512>(3, 1, &mut rng).is_err());
+        assert!(generate_with_dealer::<Ed25519Sha512>(2, 3, &mut rng).is_err());
+    }
+}
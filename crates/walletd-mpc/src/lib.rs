@@ -0,0 +1,123 @@
+//! # WalletD MPC
+//!
+//! FROST threshold signatures (RFC 9591) for Ed25519 and secp256k1, so one
+//! ordinary address can be controlled by any `t` of `n` parties without an
+//! on-chain multisig contract:
+//!
+//! - [`generate_with_dealer`] / [`split`]: trusted-dealer key generation,
+//!   including splitting an existing key
+//! - [`dkg`]: distributed key generation, where no party sees the whole key
+//! - [`signing`]: the two-round protocol (nonce commitment, partial
+//!   signatures, aggregation with per-share verification)
+//! - [`ThresholdSigner`]: runs the protocol across [`Participant`]s and
+//!   implements [`Signer`](walletd_traits::Signer), so code written against
+//!   `Box<dyn Signer>` can use a threshold key
+//!
+//! Ed25519 signatures are standard RFC 8032 signatures. [`Secp256k1Sha256`]
+//! output is an RFC 9591 Schnorr signature
+//! ([`SignatureScheme::Secp256k1Schnorr`]), not ECDSA; [`Secp256k1Taproot`]
+//! produces BIP-340 signatures for a taproot key-path spend
+//! ([`SignatureScheme::Bip340`]).
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_mpc::{generate_with_dealer, Ed25519Sha512, LocalParticipant, ThresholdSigner};
+//!
+//! let (shares, public) = generate_with_dealer::<Ed25519Sha512>(3, 2, &mut rand::thread_rng())?;
+//! let participants = shares
+//!     .into_iter()
+//!     .map(|share| Ok(Box::new(LocalParticipant::new(share.try_into()?)) as _))
+//!     .collect::<Result<Vec<_>, MpcError>>()?;
+//! let signer = ThresholdSigner::new(public, participants)?;
+//! let signature = signer.sign_message(&message).await?;
+//! ```
+//!
+//! [`SignatureScheme::Secp256k1Schnorr`]: walletd_traits::SignatureScheme::Secp256k1Schnorr
+//! [`SignatureScheme::Bip340`]: walletd_traits::SignatureScheme::Bip340
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod ciphersuite;
+mod codec;
+pub mod dkg;
+pub mod keys;
+pub mod signer;
+pub mod signing;
+
+pub use ciphersuite::{Ciphersuite, Ed25519Sha512, KeyTweak, Secp256k1Sha256, Secp256k1Taproot};
+pub use keys::{
+    generate_with_dealer, split, Identifier, KeyPackage, PublicKeyPackage, SecretShare,
+    VssCommitment,
+};
+pub use signer::{LocalParticipant, Participant, ThresholdSigner};
+pub use signing::{
+    aggregate, commit, sign, Signature, SignatureShare, SigningCommitments, SigningNonces,
+    SigningPackage,
+};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Threshold signing errors
+#[derive(Error, Debug)]
+pub enum MpcError {
+    /// Bad thresholds, identifiers or message sets
+    #[error("Invalid parameters: {0}")]
+    InvalidParameters(String),
+
+    /// Malformed bytes, points or scalars
+    #[error("Invalid encoding: {0}")]
+    Encoding(String),
+
+    /// A DKG participant's proof of knowledge failed
+    #[error("Invalid proof of knowledge from participant {0}")]
+    InvalidProof(Identifier),
+
+    /// A key share or signature share failed verification
+    #[error("Invalid share from participant {0}")]
+    InvalidShare(Identifier),
+
+    /// The aggregated signature does not verify
+    #[error("Invalid signature")]
+    InvalidSignature,
+
+    /// Too few participants are available
+    #[error("Not enough signers: have {have}, need {need}")]
+    NotEnoughSigners {
+        /// Signers available
+        have: usize,
+        /// Threshold
+        need: usize,
+    },
+
+    /// A remote participant could not be reached or refused
+    #[error("Participant {identifier} failed: {reason}")]
+    Participant {
+        /// The participant
+        identifier: Identifier,
+        /// What went wrong
+        reason: String,
+    },
+}
+
+/// Result type for threshold operations
+pub type Result<T> = std::result::Result<T, MpcError>;
+
+impl From<MpcError> for WalletError {
+    fn from(e: MpcError) -> Self {
+        WalletError::KeyError(e.to_string())
+    }
+}
+
+impl From<MpcError> for WalletdError {
+    fn from(e: MpcError) -> Self {
+        match e {
+            MpcError::Encoding(reason) => WalletdError::FormatError(reason),
+            MpcError::InvalidSignature => WalletdError::SignatureVerificationFailed(e.to_string()),
+            e => WalletdError::SigningError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,277 @@
+//! Threshold keys as a [`Signer`] backend
+
+use crate::{
+    aggregate, commit, sign, Ciphersuite, Identifier, KeyPackage, MpcError, PublicKeyPackage,
+    Result, Signature, SignatureShare, SigningCommitments, SigningNonces, SigningPackage,
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Mutex;
+use walletd_traits::{SignatureScheme, Signer, WalletResult};
+
+/// One party to a signing session: a local share, or a proxy for a remote
+/// device or server
+#[async_trait]
+pub trait Participant<C: Ciphersuite>: Send + Sync {
+    /// This participant's identifier
+    fn identifier(&self) -> Identifier;
+
+    /// Round one: returns fresh commitments, keeping the nonces
+    async fn commit(&self) -> Result<SigningCommitments<C>>;
+
+    /// Round two: signs with the nonces behind this participant's
+    /// commitments in `package`
+    async fn sign(&self, package: &SigningPackage<C>) -> Result<SignatureShare<C>>;
+}
+
+/// A participant whose share is held in this process
+pub struct LocalParticipant<C: Ciphersuite> {
+    key: KeyPackage<C>,
+    pending: Mutex<Vec<SigningNonces<C>>>,
+}
+
+impl<C: Ciphersuite> LocalParticipant<C> {
+    /// Wraps a key package
+    pub fn new(key: KeyPackage<C>) -> Self {
+        Self {
+            key,
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for LocalParticipant<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("LocalParticipant")
+            .field("key", &self.key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl<C: Ciphersuite> Participant<C> for LocalParticipant<C> {
+    fn identifier(&self) -> Identifier {
+        self.key.identifier()
+    }
+
+    async fn commit(&self) -> Result<SigningCommitments<C>> {
+        let nonces = commit(&self.key, &mut rand::thread_rng());
+        let commitments = *nonces.commitments();
+        self.pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(nonces);
+        Ok(commitments)
+    }
+
+    async fn sign(&self, package: &SigningPackage<C>) -> Result<SignatureShare<C>> {
+        let ours = package.commitments().get(&self.key.identifier());
+        let nonces = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            let index = pending
+                .iter()
+                .position(|n| Some(n.commitments()) == ours)
+                .ok_or_else(|| {
+                    MpcError::InvalidParameters("no pending nonces match the package".into())
+                })?;
+            pending.swap_remove(index)
+        };
+        sign(package, nonces, &self.key)
+    }
+}
+
+/// A [`Signer`] backed by a threshold key
+///
+/// Each signature runs both FROST rounds over the first `min_signers`
+/// participants that answer round one; participants that fail to commit
+/// are skipped. Messages are signed as-is: both ciphersuites hash
+/// internally, and [`sign_hash`](Signer::sign_hash) signs the digest bytes.
+pub struct ThresholdSigner<C: Ciphersuite> {
+    public: PublicKeyPackage<C>,
+    participants: Vec<Box<dyn Participant<C>>>,
+}
+
+impl<C: Ciphersuite> ThresholdSigner<C> {
+    /// Creates a signer; every participant must belong to `public`
+    pub fn new(
+        public: PublicKeyPackage<C>,
+        participants: Vec<Box<dyn Participant<C>>>,
+    ) -> Result<Self> {
+        let mut seen = BTreeSet::new();
+        for participant in &participants {
+            let id = participant.identifier();
+            if !public.verifying_shares().contains_key(&id) || !seen.insert(id) {
+                return Err(MpcError::InvalidParameters(format!(
+                    "unknown or duplicate participant {}",
+                    id
+                )));
+            }
+        }
+        let need = usize::from(public.min_signers());
+        if participants.len() < need {
+            return Err(MpcError::NotEnoughSigners {
+                have: participants.len(),
+                need,
+            });
+        }
+        Ok(Self {
+            public,
+            participants,
+        })
+    }
+
+    /// The group's public data
+    pub fn public_key_package(&self) -> &PublicKeyPackage<C> {
+        &self.public
+    }
+
+    /// Runs a signing session and returns the verified signature
+    pub async fn sign(&self, message: &[u8]) -> Result<Signature<C>> {
+        let need = usize::from(self.public.min_signers());
+        let mut signers = Vec::with_capacity(need);
+        let mut commitments = Vec::with_capacity(need);
+        for participant in &self.participants {
+            if signers.len() == need {
+                break;
+            }
+            if let Ok(c) = participant.commit().await {
+                if c.identifier() == participant.identifier() {
+                    signers.push(participant);
+                    commitments.push(c);
+                }
+            }
+        }
+        if signers.len() < need {
+            return Err(MpcError::NotEnoughSigners {
+                have: signers.len(),
+                need,
+            });
+        }
+
+        let package = SigningPackage::new(commitments, message)?;
+        let mut shares = BTreeMap::new();
+        for participant in signers {
+            let id = participant.identifier();
+            let share = participant
+                .sign(&package)
+                .await
+                .map_err(|e| MpcError::Participant {
+                    identifier: id,
+                    reason: e.to_string(),
+                })?;
+            if share.identifier() != id {
+                return Err(MpcError::InvalidShare(id));
+            }
+            shares.insert(id, share);
+        }
+        aggregate(&package, &shares, &self.public)
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for ThresholdSigner<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ThresholdSigner")
+            .field("verifying_key", self.public.verifying_key())
+            .field("min_signers", &self.public.min_signers())
+            .field("participants", &self.participants.len())
+            .finish()
+    }
+}
+
+#[async_trait]
+impl<C: Ciphersuite> Signer for ThresholdSigner<C> {
+    fn scheme(&self) -> SignatureScheme {
+        C::SCHEME
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.public.verifying_key_bytes()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        self.sign_message(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        Ok(ThresholdSigner::sign(self, message).await?.to_bytes())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_with_dealer, Ed25519Sha512, Secp256k1Sha256};
+
+    struct Offline(Identifier);
+
+    #[async_trait]
+    impl<C: Ciphersuite> Participant<C> for Offline {
+        fn identifier(&self) -> Identifier {
+            self.0
+        }
+
+        async fn commit(&self) -> Result<SigningCommitments<C>> {
+            Err(MpcError::Participant {
+                identifier: self.0,
+                reason: "unreachable".into(),
+            })
+        }
+
+        async fn sign(&self, _: &SigningPackage<C>) -> Result<SignatureShare<C>> {
+            unreachable!("never committed")
+        }
+    }
+
+    fn participants<C: Ciphersuite>(
+        n: u16,
+        t: u16,
+        offline: &[u16],
+    ) -> (PublicKeyPackage<C>, Vec<Box<dyn Participant<C>>>) {
+        let (shares, public) = generate_with_dealer::<C>(n, t, &mut rand::thread_rng()).unwrap();
+        let participants = shares
+            .into_iter()
+            .map(|share| -> Box<dyn Participant<C>> {
+                if offline.contains(&share.identifier().get()) {
+                    Box::new(Offline(share.identifier()))
+                } else {
+                    Box::new(LocalParticipant::new(share.try_into().unwrap()))
+                }
+            })
+            .collect();
+        (public, participants)
+    }
+
+    #[tokio::test]
+    async fn test_threshold_signer_as_ed25519_signer() {
+        let (public, parts) = participants::<Ed25519Sha512>(3, 2, &[1]);
+        let signer: Box<dyn Signer> = Box::new(ThresholdSigner::new(public, parts).unwrap());
+        assert_eq!(signer.scheme(), SignatureScheme::Ed25519);
+
+        let sig = signer.sign_message(b"transfer").await.unwrap();
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(&signer.public_key().try_into().unwrap())
+            .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&sig).unwrap();
+        assert!(vk.verify_strict(b"transfer", &sig).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_threshold_signer_secp256k1_and_availability() {
+        let (public, parts) = participants::<Secp256k1Sha256>(3, 2, &[]);
+        let signer = ThresholdSigner::new(public.clone(), parts).unwrap();
+        assert_eq!(Signer::scheme(&signer), SignatureScheme::Secp256k1Schnorr);
+        assert_eq!(Signer::public_key(&signer).len(), 33);
+        let sig = Signer::sign_hash(&signer, &[7u8; 32]).await.unwrap();
+        Signature::<Secp256k1Sha256>::from_bytes(&sig)
+            .unwrap()
+            .verify(public.verifying_key(), &[7u8; 32])
+            .unwrap();
+
+        let (public, parts) = participants::<Secp256k1Sha256>(3, 2, &[1, 3]);
+        let signer = ThresholdSigner::new(public, parts).unwrap();
+        assert!(matches!(
+            signer.sign(b"m").await,
+            Err(MpcError::NotEnoughSigners { have: 1, need: 2 })
+        ));
+    }
+}
@@ -0,0 +1,694 @@
+//! The two-round FROST signing protocol (RFC 9591 section 5)
+//!
+//! Each signer calls [`commit`] and sends its [`SigningCommitments`] to a
+//! coordinator, which builds a [`SigningPackage`]. Signers answer with a
+//! [`SignatureShare`] from [`sign`], and the coordinator combines them with
+//! [`aggregate`].
+
+use crate::codec::{Reader, Writer};
+use crate::keys::lagrange;
+use crate::{Ciphersuite, Identifier, KeyPackage, MpcError, PublicKeyPackage, Result};
+use ff::Field;
+use group::Group;
+use rand::{CryptoRng, RngCore};
+use std::collections::BTreeMap;
+use std::fmt;
+use zeroize::Zeroize;
+
+/// Secret nonces for one signing session; consumed by [`sign`]
+pub struct SigningNonces<C: Ciphersuite> {
+    hiding: C::Scalar,
+    binding: C::Scalar,
+    commitments: SigningCommitments<C>,
+}
+
+/// Public commitments to a signer's nonces
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SigningCommitments<C: Ciphersuite> {
+    identifier: Identifier,
+    hiding: C::Element,
+    binding: C::Element,
+}
+
+/// The message and the commitments of everyone signing it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SigningPackage<C: Ciphersuite> {
+    commitments: BTreeMap<Identifier, SigningCommitments<C>>,
+    message: Vec<u8>,
+}
+
+/// One signer's contribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SignatureShare<C: Ciphersuite> {
+    identifier: Identifier,
+    share: C::Scalar,
+}
+
+/// An aggregated Schnorr signature
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Signature<C: Ciphersuite> {
+    r: C::Element,
+    z: C::Scalar,
+}
+
+/// Round one: generates single-use nonces for `key`
+pub fn commit<C: Ciphersuite>(
+    key: &KeyPackage<C>,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> SigningNonces<C> {
+    let hiding = nonce_generate::<C>(key.signing_share(), rng);
+    let binding = nonce_generate::<C>(key.signing_share(), rng);
+    SigningNonces {
+        hiding,
+        binding,
+        commitments: SigningCommitments {
+            identifier: key.identifier(),
+            hiding: C::Element::generator() * hiding,
+            binding: C::Element::generator() * binding,
+        },
+    }
+}
+
+/// Round two: computes this signer's share
+///
+/// Taking `nonces` by value keeps them from being reused, which would leak
+/// the signing share.
+pub fn sign<C: Ciphersuite>(
+    package: &SigningPackage<C>,
+    nonces: SigningNonces<C>,
+    key: &KeyPackage<C>,
+) -> Result<SignatureShare<C>> {
+    let need = usize::from(key.min_signers());
+    if package.commitments.len() < need {
+        return Err(MpcError::NotEnoughSigners {
+            have: package.commitments.len(),
+            need,
+        });
+    }
+    if package.commitments.get(&key.identifier()) != Some(&nonces.commitments) {
+        return Err(MpcError::InvalidParameters(
+            "signing package does not contain this signer's commitments".into(),
+        ));
+    }
+
+    let factors = binding_factors(key.verifying_key(), package);
+    let r = group_commitment(package, &factors);
+    let nonce_factor = C::nonce_factor(&r);
+    let tweak = C::tweak(key.verifying_key());
+    let challenge = C::challenge(&(r * nonce_factor), &tweak.output_key, &package.message);
+    let lambda = lagrange::<C>(key.identifier(), package.commitments.keys().copied());
+
+    let share = nonce_factor * (nonces.hiding + nonces.binding * factors[&key.identifier()])
+        + lambda * tweak.share_factor * *key.signing_share() * challenge;
+    Ok(SignatureShare {
+        identifier: key.identifier(),
+        share,
+    })
+}
+
+/// Verifies every share and combines them into a signature
+///
+/// A bad share fails with [`MpcError::InvalidShare`] naming its signer, so
+/// the coordinator can exclude it and retry.
+pub fn aggregate<C: Ciphersuite>(
+    package: &SigningPackage<C>,
+    shares: &BTreeMap<Identifier, SignatureShare<C>>,
+    public: &PublicKeyPackage<C>,
+) -> Result<Signature<C>> {
+    let need = usize::from(public.min_signers());
+    if package.commitments.len() < need {
+        return Err(MpcError::NotEnoughSigners {
+            have: package.commitments.len(),
+            need,
+        });
+    }
+    if !shares.keys().eq(package.commitments.keys()) {
+        return Err(MpcError::InvalidParameters(
+            "shares must come from exactly the committed signers".into(),
+        ));
+    }
+
+    let factors = binding_factors(public.verifying_key(), package);
+    let r = group_commitment(package, &factors);
+    let nonce_factor = C::nonce_factor(&r);
+    let r = r * nonce_factor;
+    let tweak = C::tweak(public.verifying_key());
+    let challenge = C::challenge(&r, &tweak.output_key, &package.message);
+
+    let mut z = C::Scalar::ZERO;
+    for (id, share) in shares {
+        let verifying_share = public
+            .verifying_shares()
+            .get(id)
+            .ok_or(MpcError::InvalidShare(*id))?;
+        let commitments = &package.commitments[id];
+        let lambda = lagrange::<C>(*id, package.commitments.keys().copied());
+        let expected = (commitments.hiding + commitments.binding * factors[id]) * nonce_factor
+            + *verifying_share * (challenge * lambda * tweak.share_factor);
+        if C::Element::generator() * share.share != expected {
+            return Err(MpcError::InvalidShare(*id));
+        }
+        z += share.share;
+    }
+    z += challenge * tweak.offset;
+
+    let signature = Signature { r, z };
+    signature.verify(public.verifying_key(), &package.message)?;
+    Ok(signature)
+}
+
+fn nonce_generate<C: Ciphersuite>(
+    secret: &C::Scalar,
+    rng: &mut (impl RngCore + CryptoRng),
+) -> C::Scalar {
+    let mut input = [0u8; 32].to_vec();
+    rng.fill_bytes(&mut input);
+    input.extend(C::serialize_scalar(secret));
+    let nonce = C::h3(&input);
+    input.zeroize();
+    nonce
+}
+
+fn binding_factors<C: Ciphersuite>(
+    verifying_key: &C::Element,
+    package: &SigningPackage<C>,
+) -> BTreeMap<Identifier, C::Scalar> {
+    let mut encoded = Vec::new();
+    for (id, commitments) in &package.commitments {
+        encoded.extend(C::serialize_scalar(&id.to_scalar::<C>()));
+        encoded.extend(C::serialize_element(&commitments.hiding));
+        encoded.extend(C::serialize_element(&commitments.binding));
+    }
+
+    let mut prefix = C::serialize_element(verifying_key);
+    prefix.extend(C::h4(&package.message));
+    prefix.extend(C::h5(&encoded));
+    package
+        .commitments
+        .keys()
+        .map(|id| {
+            let mut input = prefix.clone();
+            input.extend(C::serialize_scalar(&id.to_scalar::<C>()));
+            (*id, C::h1(&input))
+        })
+        .collect()
+}
+
+fn group_commitment<C: Ciphersuite>(
+    package: &SigningPackage<C>,
+    factors: &BTreeMap<Identifier, C::Scalar>,
+) -> C::Element {
+    package
+        .commitments
+        .iter()
+        .fold(C::Element::identity(), |acc, (id, c)| {
+            acc + c.hiding + c.binding * factors[id]
+        })
+}
+
+impl<C: Ciphersuite> SigningNonces<C> {
+    /// The public commitments to send to the coordinator
+    pub fn commitments(&self) -> &SigningCommitments<C> {
+        &self.commitments
+    }
+}
+
+impl<C: Ciphersuite> fmt::Debug for SigningNonces<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SigningNonces")
+            .field("commitments", &self.commitments)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<C: Ciphersuite> Drop for SigningNonces<C> {
+    fn drop(&mut self) {
+        self.hiding.zeroize();
+        self.binding.zeroize();
+    }
+}
+
+impl<C: Ciphersuite> SigningCommitments<C> {
+    /// The committing signer
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Encodes the commitments
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Writer::default()
+            .identifier(self.identifier)
+            .element::<C>(&self.hiding)
+            .element::<C>(&self.binding)
+            .finish()
+    }
+
+    /// Decodes commitments written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let commitments = Self {
+            identifier: reader.identifier()?,
+            hiding: reader.element::<C>()?,
+            binding: reader.element::<C>()?,
+        };
+        reader.finish()?;
+        Ok(commitments)
+    }
+}
+
+impl<C: Ciphersuite> SigningPackage<C> {
+    /// Builds a package; each signer may commit once
+    pub fn new(
+        commitments: impl IntoIterator<Item = SigningCommitments<C>>,
+        message: impl Into<Vec<u8>>,
+    ) -> Result<Self> {
+        let mut map = BTreeMap::new();
+        for commitment in commitments {
+            if map.insert(commitment.identifier, commitment).is_some() {
+                return Err(MpcError::InvalidParameters(format!(
+                    "duplicate commitments from {}",
+                    commitment.identifier
+                )));
+            }
+        }
+        Ok(Self {
+            commitments: map,
+            message: message.into(),
+        })
+    }
+
+    /// Commitments by signer
+    pub fn commitments(&self) -> &BTreeMap<Identifier, SigningCommitments<C>> {
+        &self.commitments
+    }
+
+    /// The message being signed
+    pub fn message(&self) -> &[u8] {
+        &self.message
+    }
+
+    /// Encodes the package
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut writer = Writer::default().u16(self.commitments.len() as u16);
+        for commitment in self.commitments.values() {
+            writer = writer
+                .identifier(commitment.identifier)
+                .element::<C>(&commitment.hiding)
+                .element::<C>(&commitment.binding);
+        }
+        writer.bytes(&self.message).finish()
+    }
+
+    /// Decodes a package written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let mut commitments = Vec::new();
+        for _ in 0..reader.u16()? {
+            commitments.push(SigningCommitments {
+                identifier: reader.identifier()?,
+                hiding: reader.element::<C>()?,
+                binding: reader.element::<C>()?,
+            });
+        }
+        let message = reader.bytes()?.to_vec();
+        reader.finish()?;
+        Self::new(commitments, message)
+    }
+}
+
+impl<C: Ciphersuite> SignatureShare<C> {
+    /// The signer
+    pub fn identifier(&self) -> Identifier {
+        self.identifier
+    }
+
+    /// Encodes the share
+    pub fn to_bytes(&self) -> Vec<u8> {
+        Writer::default()
+            .identifier(self.identifier)
+            .scalar::<C>(&self.share)
+            .finish()
+    }
+
+    /// Decodes a share written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let mut reader = Reader::new(bytes);
+        let share = Self {
+            identifier: reader.identifier()?,
+            share: reader.scalar::<C>()?,
+        };
+        reader.finish()?;
+        Ok(share)
+    }
+}
+
+impl<C: Ciphersuite> Signature<C> {
+    /// Checks the signature against a group public key, under the suite's
+    /// [`tweak`](Ciphersuite::tweak) of it
+    pub fn verify(&self, verifying_key: &C::Element, message: &[u8]) -> Result<()> {
+        let output_key = C::tweak(verifying_key).output_key;
+        let c = C::challenge(&self.r, &output_key, message);
+        if C::verify_equation(&self.r, &self.z, &output_key, &c) {
+            Ok(())
+        } else {
+            Err(MpcError::InvalidSignature)
+        }
+    }
+
+    /// Encodes as `R || z`: a standard Ed25519 signature, 65 bytes for
+    /// secp256k1, or a 64-byte BIP-340 signature for taproot
+    pub fn to_bytes(&self) -> Vec<u8> {
+        C::serialize_signature(&self.r, &self.z)
+    }
+
+    /// Decodes a signature written by [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        let (r, z) = C::deserialize_signature(bytes)?;
+        Ok(Self { r, z })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate_with_dealer, Ed25519Sha512, Secp256k1Sha256, Secp256k1Taproot};
+
+    fn sign_with<C: Ciphersuite>(
+        keys: &[KeyPackage<C>],
+        public: &PublicKeyPackage<C>,
+        message: &[u8],
+    ) -> Result<Signature<C>> {
+        let mut rng = rand::thread_rng();
+        let nonces: Vec<_> = keys.iter().map(|k| commit(k, &mut rng)).collect();
+        let package = SigningPackage::new(nonces.iter().map(|n| *n.commitments()), message)?;
+        let package = SigningPackage::from_bytes(&package.to_bytes())?;
+        let shares = nonces
+            .into_iter()
+            .zip(keys)
+            .map(|(n, k)| sign(&package, n, k).map(|s| (k.identifier(), s)))
+            .collect::<Result<BTreeMap<_, _>>>()?;
+        aggregate(&package, &shares, public)
+    }
+
+    fn dealer<C: Ciphersuite>(n: u16, t: u16) -> (Vec<KeyPackage<C>>, PublicKeyPackage<C>) {
+        let (shares, public) = generate_with_dealer::<C>(n, t, &mut rand::thread_rng()).unwrap();
+        let keys = shares
+            .into_iter()
+            .map(|s| KeyPackage::try_from(s).unwrap())
+            .collect();
+        (keys, public)
+    }
+
+    #[test]
+    fn test_ed25519_signature_verifies_as_rfc8032() {
+        let (keys, public) = dealer::<Ed25519Sha512>(5, 3);
+        let signature = sign_with(
+            &[keys[4].clone(), keys[0].clone(), keys[2].clone()],
+            &public,
+            b"hello",
+        )
+        .unwrap();
+
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(
+            &public.verifying_key_bytes().try_into().unwrap(),
+        )
+        .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&signature.to_bytes()).unwrap();
+        assert!(vk.verify_strict(b"hello", &sig).is_ok());
+        assert_eq!(
+            Signature::from_bytes(&signature.to_bytes()).unwrap(),
+            signature
+        );
+    }
+
+    #[test]
+    fn test_secp256k1_signature_and_threshold() {
+        let (keys, public) = dealer::<Secp256k1Sha256>(3, 2);
+        let signature = sign_with(&keys[1..], &public, b"hello").unwrap();
+        assert_eq!(signature.to_bytes().len(), 65);
+        signature.verify(public.verifying_key(), b"hello").unwrap();
+        assert!(matches!(
+            signature.verify(public.verifying_key(), b"other"),
+            Err(MpcError::InvalidSignature)
+        ));
+
+        assert!(matches!(
+            sign_with(&keys[..1], &public, b"hello"),
+            Err(MpcError::NotEnoughSigners { have: 1, need: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_taproot_signature_verifies_as_bip340() {
+        // Several keys and messages, so both parities of the group key,
+        // output key and nonce commitment come up
+        for i in 0..8u8 {
+            let (keys, public) = dealer::<Secp256k1Taproot>(3, 2);
+            let message = [i; 32];
+            let signature = sign_with(&keys[..2], &public, &message).unwrap();
+            let bytes = signature.to_bytes();
+            assert_eq!(bytes.len(), 64);
+            assert_eq!(Signature::from_bytes(&bytes).unwrap(), signature);
+
+            let vk =
+                k256::schnorr::VerifyingKey::from_bytes(&public.verifying_key_bytes()).unwrap();
+            let sig = k256::schnorr::Signature::try_from(&bytes[..]).unwrap();
+            assert!(vk.verify_raw(&message, &sig).is_ok());
+            assert!(vk.verify_raw(b"other", &sig).is_err());
+        }
+    }
+
+    /// Replays fixed nonce randomness, as the RFC 9591 vectors do
+    struct FixedRandomness(Vec<u8>);
+
+    impl RngCore for FixedRandomness {
+        fn next_u32(&mut self) -> u32 {
+            unimplemented!()
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            unimplemented!()
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            let rest = self.0.split_off(dest.len());
+            dest.copy_from_slice(&self.0);
+            self.0 = rest;
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> std::result::Result<(), rand::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    impl CryptoRng for FixedRandomness {}
+
+    /// A 2-of-3 key from the vector's secret and polynomial coefficient
+    fn vector_keys<C: Ciphersuite>(
+        secret: &str,
+        coefficient: &str,
+    ) -> (Vec<KeyPackage<C>>, PublicKeyPackage<C>) {
+        let coefficients =
+            [secret, coefficient].map(|c| C::deserialize_scalar(&hex::decode(c).unwrap()).unwrap());
+        let commitment = crate::keys::VssCommitment::<C>::from_coefficients(&coefficients);
+        let keys: Vec<_> = (1..=3)
+            .map(|i| {
+                let id = Identifier::new(i).unwrap();
+                let share = crate::keys::evaluate_polynomial::<C>(&coefficients, id);
+                KeyPackage::new(id, share, commitment.verifying_key(), 2)
+            })
+            .collect();
+        let public =
+            PublicKeyPackage::from_commitment(&commitment, keys.iter().map(KeyPackage::identifier));
+        (keys, public)
+    }
+
+    fn vector_commit<C: Ciphersuite>(
+        key: &KeyPackage<C>,
+        hiding_randomness: &str,
+        binding_randomness: &str,
+    ) -> SigningNonces<C> {
+        let randomness = [hiding_randomness, binding_randomness].concat();
+        commit(key, &mut FixedRandomness(hex::decode(randomness).unwrap()))
+    }
+
+    fn scalar_hex<C: Ciphersuite>(scalar: &C::Scalar) -> String {
+        hex::encode(C::serialize_scalar(scalar))
+    }
+
+    fn element_hex<C: Ciphersuite>(element: &C::Element) -> String {
+        hex::encode(C::serialize_element(element))
+    }
+
+    #[test]
+    fn test_rfc9591_ed25519_vector() {
+        // RFC 9591 appendix E.1, FROST(Ed25519, SHA-512), signers 1 and 3
+        let (keys, public) = vector_keys::<Ed25519Sha512>(
+            "7b1c33d3f5291d85de664833beb1ad469f7fb6025a0ec78b3a790c6e13a98304",
+            "178199860edd8c62f5212ee91eff1295d0d670ab4ed4506866bae57e7030b204",
+        );
+        assert_eq!(
+            element_hex::<Ed25519Sha512>(keys[0].verifying_key()),
+            "15d21ccd7ee42959562fc8aa63224c8851fb3ec85a3faf66040d380fb9738673"
+        );
+        let shares: Vec<_> = keys
+            .iter()
+            .map(|k| scalar_hex::<Ed25519Sha512>(k.signing_share()))
+            .collect();
+        assert_eq!(
+            shares,
+            [
+                "929dcc590407aae7d388761cddb0c0db6f5627aea8e217f4a033f2ec83d93509",
+                "a91e66e012e4364ac9aaa405fcafd370402d9859f7b6685c07eed76bf409e80d",
+                "d3cb090a075eb154e82fdb4b3cb507f110040905468bb9c46da8bdea643a9a02",
+            ]
+        );
+
+        let p1 = vector_commit(
+            &keys[0],
+            "0fd2e39e111cdc266f6c0f4d0fd45c947761f1f5d3cb583dfcb9bbaf8d4c9fec",
+            "69cd85f631d5f7f2721ed5e40519b1366f340a87c2f6856363dbdcda348a7501",
+        );
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&p1.hiding),
+            "812d6104142944d5a55924de6d49940956206909f2acaeedecda2b726e630407"
+        );
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&p1.binding),
+            "b1110165fc2334149750b28dd813a39244f315cff14d4e89e6142f262ed83301"
+        );
+        assert_eq!(
+            element_hex::<Ed25519Sha512>(&p1.commitments.hiding),
+            "b5aa8ab305882a6fc69cbee9327e5a45e54c08af61ae77cb8207be3d2ce13de3"
+        );
+        assert_eq!(
+            element_hex::<Ed25519Sha512>(&p1.commitments.binding),
+            "67e98ab55aa310c3120418e5050c9cf76cf387cb20ac9e4b6fdb6f82a469f932"
+        );
+
+        let p3 = vector_commit(
+            &keys[2],
+            "86d64a260059e495d0fb4fcc17ea3da7452391baa494d4b00321098ed2a0062f",
+            "13e6b25afb2eba51716a9a7d44130c0dbae0004a9ef8d7b5550c8a0e07c61775",
+        );
+        assert_eq!(
+            element_hex::<Ed25519Sha512>(&p3.commitments.hiding),
+            "cfbdb165bd8aad6eb79deb8d287bcc0ab6658ae57fdcc98ed12c0669e90aec91"
+        );
+        assert_eq!(
+            element_hex::<Ed25519Sha512>(&p3.commitments.binding),
+            "7487bc41a6e712eea2f2af24681b58b1cf1da278ea11fe4e8b78398965f13552"
+        );
+
+        let package =
+            SigningPackage::new([*p1.commitments(), *p3.commitments()], b"test".to_vec()).unwrap();
+        let factors = binding_factors(keys[0].verifying_key(), &package);
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&factors[&keys[0].identifier()]),
+            "f2cb9d7dd9beff688da6fcc83fa89046b3479417f47f55600b106760eb3b5603"
+        );
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&factors[&keys[2].identifier()]),
+            "b087686bf35a13f3dc78e780a34b0fe8a77fef1b9938c563f5573d71d8d7890f"
+        );
+
+        let share1 = sign(&package, p1, &keys[0]).unwrap();
+        let share3 = sign(&package, p3, &keys[2]).unwrap();
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&share1.share),
+            "001719ab5a53ee1a12095cd088fd149702c0720ce5fd2f29dbecf24b7281b603"
+        );
+        assert_eq!(
+            scalar_hex::<Ed25519Sha512>(&share3.share),
+            "bd86125de990acc5e1f13781d8e32c03a9bbd4c53539bbc106058bfd14326007"
+        );
+
+        let shares = BTreeMap::from([(share1.identifier, share1), (share3.identifier, share3)]);
+        let signature = aggregate(&package, &shares, &public).unwrap();
+        assert_eq!(
+            hex::encode(signature.to_bytes()),
+            "36282629c383bb820a88b71cae937d41f2f2adfcc3d02e55507e2fb9e2dd3cbe\
+             bd9d2b0844e49ae0f3fa935161e1419aab7b47d21a37ebeae1f17d4987b3160b"
+        );
+
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(
+            &public.verifying_key_bytes().try_into().unwrap(),
+        )
+        .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&signature.to_bytes()).unwrap();
+        assert!(vk.verify_strict(b"test", &sig).is_ok());
+    }
+
+    #[test]
+    fn test_rfc9591_secp256k1_vector() {
+        // RFC 9591 appendix E.5, FROST(secp256k1, SHA-256): key shares and
+        // signer 1's round-one output
+        let (keys, _) = vector_keys::<Secp256k1Sha256>(
+            "0d004150d27c3bf2a42f312683d35fac7394b1e9e318249c1bfe7f0795a83114",
+            "fbf85eadae3058ea14f19148bb72b45e4399c0b16028acaf0395c9b03c823579",
+        );
+        assert_eq!(
+            element_hex::<Secp256k1Sha256>(keys[0].verifying_key()),
+            "02f37c34b66ced1fb51c34a90bdae006901f10625cc06c4f64663b0eae87d87b4f"
+        );
+        let shares: Vec<_> = keys
+            .iter()
+            .map(|k| scalar_hex::<Secp256k1Sha256>(k.signing_share()))
+            .collect();
+        assert_eq!(
+            shares,
+            [
+                "08f89ffe80ac94dcb920c26f3f46140bfc7f95b493f8310f5fc1ea2b01f4254c",
+                "04f0feac2edcedc6ce1253b7fab8c86b856a797f44d83d82a385554e6e401984",
+                "00e95d59dd0d46b0e303e500b62b7ccb0e555d49f5b849f5e748c071da8c0dbc",
+            ]
+        );
+
+        let p1 = vector_commit(
+            &keys[0],
+            "7ea5ed09af19f6ff21040c07ec2d2adbd35b759da5a401d4c99dd26b82391cb2",
+            "47acab018f116020c10cb9b9abdc7ac10aae1b48ca6e36dc15acb6ec9be5cdc5",
+        );
+        assert_eq!(
+            scalar_hex::<Secp256k1Sha256>(&p1.hiding),
+            "841d3a6450d7580b4da83c8e618414d0f024391f2aeb511d7579224420aa81f0"
+        );
+        assert_eq!(
+            scalar_hex::<Secp256k1Sha256>(&p1.binding),
+            "8d2624f532af631377f33cf44b5ac5f849067cae2eacb88680a31e77c79b5a80"
+        );
+        assert_eq!(
+            element_hex::<Secp256k1Sha256>(&p1.commitments.hiding),
+            "03c699af97d26bb4d3f05232ec5e1938c12f1e6ae97643c8f8f11c9820303f1904"
+        );
+        assert_eq!(
+            element_hex::<Secp256k1Sha256>(&p1.commitments.binding),
+            "02fa2aaccd51b948c9dc1a325d77226e98a5a3fe65fe9ba213761a60123040a45e"
+        );
+    }
+
+    #[test]
+    fn test_bad_share_identifies_signer() {
+        let mut rng = rand::thread_rng();
+        let (keys, public) = dealer::<Secp256k1Sha256>(3, 2);
+        let nonces: Vec<_> = keys[..2].iter().map(|k| commit(k, &mut rng)).collect();
+        let package =
+            SigningPackage::new(nonces.iter().map(|n| *n.commitments()), b"m".to_vec()).unwrap();
+
+        let mut shares = BTreeMap::new();
+        for (n, k) in nonces.into_iter().zip(&keys) {
+            shares.insert(k.identifier(), sign(&package, n, k).unwrap());
+        }
+        let cheater = keys[1].identifier();
+        shares.get_mut(&cheater).unwrap().share += k256::Scalar::ONE;
+        assert!(matches!(
+            aggregate(&package, &shares, &public),
+            Err(MpcError::InvalidShare(id)) if id == cheater
+        ));
+
+        // Nonces must match the package
+        let stray = commit(&keys[2], &mut rng);
+        assert!(sign(&package, stray, &keys[2]).is_err());
+    }
+}
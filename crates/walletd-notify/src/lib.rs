@@ -0,0 +1,97 @@
+//! # WalletD Notify
+//!
+//! Delivers wallet events to webhooks and channels. A [`Notifier`] watches
+//! addresses through their [`EventStream`](walletd_traits::EventStream)s
+//! and turns events into [`Notification`]s:
+//!
+//! - an incoming transfer was seen
+//! - a transaction reached the configured number of confirmations
+//! - a transaction failed or was dropped
+//! - a balance changed
+//!
+//! Each notification goes to every [`Sink`]. [`WebhookSink`] POSTs JSON,
+//! signs it with HMAC-SHA256 when given a secret, and retries with
+//! exponential backoff; [`ChannelSink`] hands notifications to a Tokio
+//! channel for in-process consumers.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_notify::{Notifier, WebhookSink};
+//!
+//! let notifier = Notifier::new()
+//!     .watch(wallet.address(), wallet.subscribe().await?)
+//!     .confirmations(6, tip_source, Duration::from_secs(30))
+//!     .sink(Arc::new(WebhookSink::new("https://example.com/hooks").with_secret(secret)));
+//! notifier.spawn();
+//!
+//! // Receiving side
+//! walletd_notify::verify_signature(secret, signature_header, &body, now, 300)?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod notification;
+pub mod notifier;
+pub mod sink;
+
+pub use notification::{Notification, NotificationEvent};
+pub use notifier::{Notifier, TipSource};
+pub use sink::{sign_payload, verify_signature, ChannelSink, Sink, WebhookSink, SIGNATURE_HEADER};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Notification errors
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    /// HTTP transport error
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// The webhook refused the notification and retrying will not help
+    #[error("Webhook rejected delivery with status {0}")]
+    Rejected(u16),
+
+    /// Every delivery attempt failed
+    #[error("Delivery failed after {attempts} attempts: {reason}")]
+    DeliveryFailed {
+        /// Attempts made
+        attempts: u32,
+        /// Last failure
+        reason: String,
+    },
+
+    /// The receiving channel was dropped
+    #[error("Notification channel closed")]
+    ChannelClosed,
+
+    /// A webhook signature is malformed, stale or wrong
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+
+    /// JSON encoding error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// An event stream or tip source failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for notifications
+pub type Result<T> = std::result::Result<T, NotifyError>;
+
+impl From<NotifyError> for WalletdError {
+    fn from(e: NotifyError) -> Self {
+        match e {
+            NotifyError::Json(e) => WalletdError::JsonError(e.to_string()),
+            NotifyError::InvalidSignature(reason) => {
+                WalletdError::SignatureVerificationFailed(reason)
+            }
+            e => WalletdError::NetworkError(e.to_string()),
+        }
+    }
+}
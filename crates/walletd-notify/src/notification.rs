@@ -0,0 +1,143 @@
+//! Notification payloads
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use walletd_traits::{Amount, TxHash};
+
+/// What happened
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data", rename_all = "snake_case")]
+pub enum NotificationEvent {
+    /// Funds arrived at the watched address
+    IncomingTransfer {
+        /// Transaction hash
+        hash: TxHash,
+        /// Sender, if known
+        from: Option<String>,
+        /// Amount received
+        amount: Amount,
+    },
+    /// A transaction reached the required confirmations
+    Confirmed {
+        /// Transaction hash
+        hash: TxHash,
+        /// Block height it was included in
+        block_height: Option<u64>,
+        /// Confirmations when the notification was sent
+        confirmations: u64,
+    },
+    /// A transaction failed or was dropped
+    Failed {
+        /// Transaction hash
+        hash: TxHash,
+        /// Failure reason, if known
+        reason: Option<String>,
+    },
+    /// The balance of the watched address changed
+    BalanceChanged {
+        /// Previous balance
+        old: Amount,
+        /// New balance
+        new: Amount,
+    },
+}
+
+impl NotificationEvent {
+    /// Short name, as in the JSON `type` field
+    pub fn name(&self) -> &'static str {
+        match self {
+            NotificationEvent::IncomingTransfer { .. } => "incoming_transfer",
+            NotificationEvent::Confirmed { .. } => "confirmed",
+            NotificationEvent::Failed { .. } => "failed",
+            NotificationEvent::BalanceChanged { .. } => "balance_changed",
+        }
+    }
+}
+
+/// An event about a watched address, as delivered to sinks
+///
+/// In JSON the event is tagged by type:
+/// `{"id": ..., "address": ..., "timestamp": ..., "event": {"type": "confirmed", "data": {...}}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Notification {
+    /// Stable ID derived from the address and event, so receivers can drop
+    /// duplicates from retries
+    pub id: String,
+    /// Watched address
+    pub address: String,
+    /// When the notification was created (Unix seconds)
+    pub timestamp: u64,
+    /// The event
+    pub event: NotificationEvent,
+}
+
+impl Notification {
+    /// Creates a notification for `address`
+    pub fn new(address: impl Into<String>, event: NotificationEvent, timestamp: u64) -> Self {
+        let address = address.into();
+        let mut hasher = Sha256::new();
+        hasher.update(address.as_bytes());
+        hasher.update([0]);
+        hasher.update(serde_json::to_vec(&event).unwrap_or_default());
+        let id = hex::encode(&hasher.finalize()[..16]);
+        Self {
+            id,
+            address,
+            timestamp,
+            event,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json_shape() {
+        let n = Notification::new(
+            "0xabc",
+            NotificationEvent::Confirmed {
+                hash: TxHash::new("0x01"),
+                block_height: Some(100),
+                confirmations: 6,
+            },
+            1_700_000_000,
+        );
+        let json = serde_json::to_value(&n).unwrap();
+        assert_eq!(json["event"]["type"], "confirmed");
+        assert_eq!(json["address"], "0xabc");
+        assert_eq!(json["event"]["data"]["confirmations"], 6);
+        assert_eq!(json["id"].as_str().unwrap().len(), 32);
+        assert_eq!(serde_json::from_value::<Notification>(json).unwrap(), n);
+
+        let incoming = Notification::new(
+            "0xabc",
+            NotificationEvent::IncomingTransfer {
+                hash: TxHash::new("0x01"),
+                from: None,
+                amount: Amount::from_smallest_unit(10u128.pow(20), 18),
+            },
+            1_700_000_000,
+        );
+        let json = serde_json::to_string(&incoming).unwrap();
+        assert_eq!(
+            serde_json::from_str::<Notification>(&json).unwrap(),
+            incoming
+        );
+    }
+
+    #[test]
+    fn test_id_is_stable() {
+        let event = NotificationEvent::Failed {
+            hash: TxHash::new("0x02"),
+            reason: None,
+        };
+        let a = Notification::new("addr", event.clone(), 1);
+        let b = Notification::new("addr", event.clone(), 2);
+        let c = Notification::new("other", event, 1);
+        assert_eq!(a.id, b.id);
+        assert_ne!(a.id, c.id);
+        assert_eq!(a.event.name(), "failed");
+    }
+}
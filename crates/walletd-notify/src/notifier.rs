@@ -0,0 +1,279 @@
+//! Event watching and dispatch
+
+use crate::sink::now;
+use crate::{Notification, NotificationEvent, Result, Sink};
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
+use std::time::Duration;
+use walletd_traits::{EventStream, TxHash, WalletEvent, WalletEvents, WalletResult};
+
+/// Source of the current chain height, for counting confirmations
+#[async_trait]
+pub trait TipSource: Send + Sync {
+    /// Returns the height of the latest block
+    async fn tip_height(&self) -> WalletResult<u64>;
+}
+
+struct Confirmations {
+    required: u64,
+    tip: Arc<dyn TipSource>,
+    poll_interval: Duration,
+}
+
+/// A confirmed transaction waiting for more blocks
+struct Pending {
+    address: String,
+    hash: TxHash,
+    block_height: u64,
+}
+
+/// Watches addresses and delivers their events to sinks
+///
+/// Without [`confirmations`](Notifier::confirmations), a
+/// [`WalletEvent::TransactionConfirmed`] is delivered straight away with one
+/// confirmation. With it, the notifier holds the event until the tip source
+/// reports enough blocks on top.
+///
+/// A sink that fails (after its own retries) is logged and skipped; the
+/// other sinks still receive the notification.
+#[derive(Default)]
+pub struct Notifier {
+    watches: Vec<(String, EventStream)>,
+    sinks: Vec<Arc<dyn Sink>>,
+    confirmations: Option<Confirmations>,
+}
+
+impl Notifier {
+    /// Creates a notifier with nothing to watch
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Watches `address` through an event stream
+    pub fn watch(mut self, address: impl Into<String>, events: EventStream) -> Self {
+        self.watches.push((address.into(), events));
+        self
+    }
+
+    /// Subscribes to a wallet's events
+    pub async fn watch_wallet<W: WalletEvents>(self, wallet: &W) -> Result<Self> {
+        let events = wallet.subscribe().await?;
+        Ok(self.watch(wallet.address(), events))
+    }
+
+    /// Adds a destination for notifications
+    pub fn sink(mut self, sink: Arc<dyn Sink>) -> Self {
+        self.sinks.push(sink);
+        self
+    }
+
+    /// Delays confirmation notifications until `required` confirmations,
+    /// polling `tip` every `poll_interval`
+    pub fn confirmations(
+        mut self,
+        required: u64,
+        tip: Arc<dyn TipSource>,
+        poll_interval: Duration,
+    ) -> Self {
+        self.confirmations = Some(Confirmations {
+            required: required.max(1),
+            tip,
+            poll_interval,
+        });
+        self
+    }
+
+    /// Runs until every stream has ended and no confirmations are pending
+    pub async fn run(self) {
+        let Notifier {
+            watches,
+            sinks,
+            confirmations,
+        } = self;
+        let mut events = stream::select_all(
+            watches
+                .into_iter()
+                .map(|(address, events)| events.map(move |event| (address.clone(), event))),
+        );
+        let mut streams_done = false;
+        let mut pending: Vec<Pending> = Vec::new();
+        let poll_interval = confirmations
+            .as_ref()
+            .map_or(Duration::from_secs(60), |c| c.poll_interval);
+        let mut ticker = tokio::time::interval(poll_interval);
+        ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+        while !streams_done || !pending.is_empty() {
+            tokio::select! {
+                next = events.next(), if !streams_done => match next {
+                    None => streams_done = true,
+                    Some((address, Err(e))) => {
+                        tracing::warn!(%address, error = %e, "event stream error");
+                    }
+                    Some((address, Ok(event))) => {
+                        let event = match (event, &confirmations) {
+                            (
+                                WalletEvent::TransactionConfirmed {
+                                    hash,
+                                    block_height: Some(block_height),
+                                },
+                                Some(_),
+                            ) => {
+                                pending.push(Pending { address, hash, block_height });
+                                continue;
+                            }
+                            (event, _) => convert(event),
+                        };
+                        dispatch(&sinks, Notification::new(address, event, now())).await;
+                    }
+                },
+                _ = ticker.tick(), if !pending.is_empty() => {
+                    if let Some(confirmations) = &confirmations {
+                        check_pending(&sinks, confirmations, &mut pending).await;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Runs the notifier on the Tokio runtime
+    pub fn spawn(self) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(self.run())
+    }
+}
+
+fn convert(event: WalletEvent) -> NotificationEvent {
+    match event {
+        WalletEvent::BalanceChanged { old, new, .. } => {
+            NotificationEvent::BalanceChanged { old, new }
+        }
+        WalletEvent::IncomingTransaction { hash, from, amount } => {
+            NotificationEvent::IncomingTransfer { hash, from, amount }
+        }
+        WalletEvent::TransactionConfirmed { hash, block_height } => NotificationEvent::Confirmed {
+            hash,
+            block_height,
+            confirmations: 1,
+        },
+        WalletEvent::TransactionFailed { hash, reason } => {
+            NotificationEvent::Failed { hash, reason }
+        }
+    }
+}
+
+async fn check_pending(
+    sinks: &[Arc<dyn Sink>],
+    confirmations: &Confirmations,
+    pending: &mut Vec<Pending>,
+) {
+    let tip = match confirmations.tip.tip_height().await {
+        Ok(tip) => tip,
+        Err(e) => {
+            tracing::warn!(error = %e, "failed to fetch chain tip");
+            return;
+        }
+    };
+    let mut waiting = Vec::new();
+    for tx in pending.drain(..) {
+        let count = (tip + 1).saturating_sub(tx.block_height);
+        if count < confirmations.required {
+            waiting.push(tx);
+            continue;
+        }
+        let event = NotificationEvent::Confirmed {
+            hash: tx.hash,
+            block_height: Some(tx.block_height),
+            confirmations: count,
+        };
+        dispatch(sinks, Notification::new(tx.address, event, now())).await;
+    }
+    *pending = waiting;
+}
+
+async fn dispatch(sinks: &[Arc<dyn Sink>], notification: Notification) {
+    for sink in sinks {
+        if let Err(e) = sink.deliver(&notification).await {
+            tracing::warn!(id = %notification.id, error = %e, "notification not delivered");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ChannelSink;
+    use std::sync::atomic::{AtomicU64, Ordering};
+    use walletd_traits::{Amount, WalletError};
+
+    struct Tip(AtomicU64);
+
+    #[async_trait]
+    impl TipSource for Tip {
+        async fn tip_height(&self) -> WalletResult<u64> {
+            // Each poll mines a block
+            Ok(self.0.fetch_add(1, Ordering::SeqCst))
+        }
+    }
+
+    fn events(items: Vec<WalletResult<WalletEvent>>) -> EventStream {
+        Box::pin(stream::iter(items))
+    }
+
+    #[tokio::test]
+    async fn test_events_reach_sinks() {
+        let (sink, mut rx) = ChannelSink::channel(8);
+        let stream = events(vec![
+            Ok(WalletEvent::IncomingTransaction {
+                hash: TxHash::new("0x01"),
+                from: Some("0xfrom".into()),
+                amount: Amount::from_smallest_unit(5, 18),
+            }),
+            Err(WalletError::NetworkError("blip".into())),
+            Ok(WalletEvent::TransactionFailed {
+                hash: TxHash::new("0x02"),
+                reason: None,
+            }),
+        ]);
+        Notifier::new()
+            .watch("0xabc", stream)
+            .sink(Arc::new(sink))
+            .run()
+            .await;
+
+        let first = rx.recv().await.unwrap();
+        assert_eq!(first.address, "0xabc");
+        assert!(matches!(
+            first.event,
+            NotificationEvent::IncomingTransfer { .. }
+        ));
+        assert!(matches!(
+            rx.recv().await.unwrap().event,
+            NotificationEvent::Failed { .. }
+        ));
+        assert!(rx.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_waits_for_confirmations() {
+        let (sink, mut rx) = ChannelSink::channel(8);
+        let stream = events(vec![Ok(WalletEvent::TransactionConfirmed {
+            hash: TxHash::new("0x03"),
+            block_height: Some(100),
+        })]);
+        let tip = Arc::new(Tip(AtomicU64::new(100)));
+        Notifier::new()
+            .watch("0xabc", stream)
+            .sink(Arc::new(sink))
+            .confirmations(3, tip.clone(), Duration::from_millis(1))
+            .run()
+            .await;
+
+        let NotificationEvent::Confirmed { confirmations, .. } = rx.recv().await.unwrap().event
+        else {
+            panic!("expected a confirmation");
+        };
+        assert_eq!(confirmations, 3);
+        assert_eq!(tip.0.load(Ordering::SeqCst), 103);
+    }
+}
@@ -0,0 +1,309 @@
+//! Notification destinations
+
+use crate::{Notification, NotifyError, Result};
+use async_trait::async_trait;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tokio::sync::mpsc;
+use walletd_resilience::{BackoffConfig, ExponentialBackoff, HttpRetryClassifier};
+
+/// Header carrying the webhook signature
+pub const SIGNATURE_HEADER: &str = "X-Walletd-Signature";
+
+/// Somewhere notifications are delivered
+#[async_trait]
+pub trait Sink: Send + Sync {
+    /// Delivers one notification, retrying as the sink sees fit
+    async fn deliver(&self, notification: &Notification) -> Result<()>;
+}
+
+/// Delivers notifications to an in-process channel
+#[derive(Debug, Clone)]
+pub struct ChannelSink {
+    sender: mpsc::Sender<Notification>,
+}
+
+impl ChannelSink {
+    /// Wraps a channel sender
+    pub fn new(sender: mpsc::Sender<Notification>) -> Self {
+        Self { sender }
+    }
+
+    /// Creates a sink and the receiver it feeds
+    pub fn channel(capacity: usize) -> (Self, mpsc::Receiver<Notification>) {
+        let (sender, receiver) = mpsc::channel(capacity);
+        (Self::new(sender), receiver)
+    }
+}
+
+#[async_trait]
+impl Sink for ChannelSink {
+    async fn deliver(&self, notification: &Notification) -> Result<()> {
+        self.sender
+            .send(notification.clone())
+            .await
+            .map_err(|_| NotifyError::ChannelClosed)
+    }
+}
+
+/// POSTs notifications as JSON to a URL
+///
+/// With a secret, each request carries a [`SIGNATURE_HEADER`] of the form
+/// `t=<unix seconds>,v1=<hex HMAC-SHA256 of "<t>.<body>">`; receivers check
+/// it with [`verify_signature`]. Timeouts, 408, 425, 429 and 5xx responses
+/// are retried with backoff; other 4xx responses are not.
+#[derive(Debug, Clone)]
+pub struct WebhookSink {
+    client: reqwest::Client,
+    url: String,
+    secret: Option<Vec<u8>>,
+    backoff: BackoffConfig,
+}
+
+impl WebhookSink {
+    /// Creates a sink for `url`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.into(),
+            secret: None,
+            backoff: BackoffConfig::default(),
+        }
+    }
+
+    /// Signs payloads with `secret`
+    pub fn with_secret(mut self, secret: impl AsRef<[u8]>) -> Self {
+        self.secret = Some(secret.as_ref().to_vec());
+        self
+    }
+
+    /// Sets the retry schedule
+    pub fn with_backoff(mut self, backoff: BackoffConfig) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Uses a preconfigured HTTP client
+    pub fn with_client(mut self, client: reqwest::Client) -> Self {
+        self.client = client;
+        self
+    }
+
+    /// Makes one delivery attempt, returning a server-requested delay on
+    /// retryable failures
+    async fn attempt(&self, notification: &Notification, body: &[u8]) -> Attempt {
+        let mut request = self
+            .client
+            .post(&self.url)
+            .header("Content-Type", "application/json")
+            .header("X-Walletd-Event", notification.event.name())
+            .header("X-Walletd-Delivery", &notification.id)
+            .body(body.to_vec());
+        if let Some(secret) = &self.secret {
+            request = request.header(SIGNATURE_HEADER, sign_payload(secret, now(), body));
+        }
+
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => return Attempt::Retry(e.to_string(), None),
+        };
+        let status = response.status().as_u16();
+        if response.status().is_success() {
+            Attempt::Done
+        } else if HttpRetryClassifier::is_status_retryable(status) {
+            let headers = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?)));
+            let hint = HttpRetryClassifier::retry_after_from_headers(headers);
+            Attempt::Retry(format!("status {}", status), hint)
+        } else {
+            Attempt::Rejected(status)
+        }
+    }
+}
+
+enum Attempt {
+    Done,
+    Retry(String, Option<Duration>),
+    Rejected(u16),
+}
+
+#[async_trait]
+impl Sink for WebhookSink {
+    async fn deliver(&self, notification: &Notification) -> Result<()> {
+        let body = serde_json::to_vec(notification)?;
+        let mut backoff = ExponentialBackoff::new(self.backoff.clone());
+        let mut attempts = 0;
+        loop {
+            attempts += 1;
+            let (reason, hint) = match self.attempt(notification, &body).await {
+                Attempt::Done => return Ok(()),
+                Attempt::Rejected(status) => return Err(NotifyError::Rejected(status)),
+                Attempt::Retry(reason, hint) => (reason, hint),
+            };
+            // A receiver asking for more than the longest backoff delay is not waited out
+            if hint.is_some_and(|hint| hint > self.backoff.max_delay) {
+                return Err(NotifyError::DeliveryFailed { attempts, reason });
+            }
+            // The first backoff slot is the initial attempt
+            match backoff.next().filter(|_| backoff.can_retry()) {
+                Some(delay) => {
+                    tracing::debug!(url = %self.url, attempts, %reason, "webhook delivery failed, retrying");
+                    tokio::time::sleep(delay.max(hint.unwrap_or_default())).await;
+                }
+                None => return Err(NotifyError::DeliveryFailed { attempts, reason }),
+            }
+        }
+    }
+}
+
+/// Builds a [`SIGNATURE_HEADER`] value for `body` sent at `timestamp`
+pub fn sign_payload(secret: &[u8], timestamp: u64, body: &[u8]) -> String {
+    format!(
+        "t={},v1={}",
+        timestamp,
+        hex::encode(mac(secret, timestamp, body).finalize().into_bytes())
+    )
+}
+
+/// Checks a [`SIGNATURE_HEADER`] value against the received body
+///
+/// Signatures more than `tolerance_secs` away from `now` are rejected to
+/// stop replays.
+pub fn verify_signature(
+    secret: &[u8],
+    header: &str,
+    body: &[u8],
+    now: u64,
+    tolerance_secs: u64,
+) -> Result<()> {
+    let invalid = |reason: &str| NotifyError::InvalidSignature(reason.to_string());
+    let (mut timestamp, mut signature) = (None, None);
+    for part in header.split(',') {
+        match part.trim().split_once('=') {
+            Some(("t", value)) => timestamp = value.parse::<u64>().ok(),
+            Some(("v1", value)) => signature = hex::decode(value).ok(),
+            _ => {}
+        }
+    }
+    let timestamp = timestamp.ok_or_else(|| invalid("missing timestamp"))?;
+    let signature = signature.ok_or_else(|| invalid("missing v1 signature"))?;
+    if timestamp.abs_diff(now) > tolerance_secs {
+        return Err(invalid("timestamp outside tolerance"));
+    }
+    mac(secret, timestamp, body)
+        .verify_slice(&signature)
+        .map_err(|_| invalid("signature mismatch"))
+}
+
+fn mac(secret: &[u8], timestamp: u64, body: &[u8]) -> Hmac<Sha256> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body);
+    mac
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::NotificationEvent;
+    use walletd_traits::TxHash;
+    use wiremock::matchers::{header, header_exists, method};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    fn notification() -> Notification {
+        Notification::new(
+            "0xabc",
+            NotificationEvent::Failed {
+                hash: TxHash::new("0x01"),
+                reason: Some("dropped".into()),
+            },
+            1,
+        )
+    }
+
+    fn fast() -> BackoffConfig {
+        BackoffConfig::new()
+            .with_initial_delay(Duration::from_millis(1))
+            .with_jitter(0.0)
+            .with_max_attempts(3)
+    }
+
+    #[tokio::test]
+    async fn test_signed_delivery_with_retry() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(header("X-Walletd-Event", "failed"))
+            .and(header_exists(SIGNATURE_HEADER))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        let sink = WebhookSink::new(server.uri())
+            .with_secret(b"whsec")
+            .with_backoff(fast());
+        sink.deliver(&notification()).await.unwrap();
+
+        let requests: Vec<Request> = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), 2);
+        let signature = requests[1].headers[SIGNATURE_HEADER].to_str().unwrap();
+        assert!(verify_signature(b"whsec", signature, &requests[1].body, now(), 60).is_ok());
+        assert!(verify_signature(b"other", signature, &requests[1].body, now(), 60).is_err());
+        assert!(verify_signature(b"whsec", signature, b"tampered", now(), 60).is_err());
+        assert!(verify_signature(b"whsec", signature, &requests[1].body, now() + 600, 60).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_rejected_and_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::new(server.uri()).with_backoff(fast());
+        assert!(matches!(
+            sink.deliver(&notification()).await,
+            Err(NotifyError::Rejected(400))
+        ));
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(3)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::new(server.uri()).with_backoff(fast());
+        assert!(matches!(
+            sink.deliver(&notification()).await,
+            Err(NotifyError::DeliveryFailed { attempts: 3, .. })
+        ));
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503).insert_header("Retry-After", "86400"))
+            .expect(1)
+            .mount(&server)
+            .await;
+        let sink = WebhookSink::new(server.uri()).with_backoff(fast());
+        assert!(matches!(
+            sink.deliver(&notification()).await,
+            Err(NotifyError::DeliveryFailed { attempts: 1, .. })
+        ));
+    }
+}
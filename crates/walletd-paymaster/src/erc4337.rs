@@ -0,0 +1,628 @@
+//! ERC-4337 user operations, bundlers and ERC-7677 paymasters
+//!
+//! User operations use the v0.6 EntryPoint layout with a single
+//! `paymasterAndData` field. Calls go through the smart account's
+//! `execute(address,uint256,bytes)`, as in the reference SimpleAccount.
+
+use crate::sponsor::{GasRequest, GasSponsor, Submission};
+use crate::{PaymasterError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::fmt;
+use std::sync::Arc;
+use walletd_core::recovery_id;
+use walletd_provider::{ProviderError, RpcClient};
+use walletd_traits::{GasPolicy, Signer, WalletError};
+
+/// EntryPoint v0.6 address, the same on every chain
+pub const ENTRY_POINT_V06: &str = "0x5FF137D4b0FDCD49DcA30c7CF57E578a026d2789";
+
+/// A well-formed signature that recovers to no account, for gas estimation
+const DUMMY_SIGNATURE: &str = "fffffffffffffffffffffffffffffff0000000000000000000000000000000007aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa1c";
+
+/// An ERC-4337 user operation (EntryPoint v0.6)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UserOperation {
+    /// Smart account address
+    pub sender: String,
+    /// Account nonce (key in the upper 192 bits, sequence in the lower 64)
+    #[serde(with = "quantity")]
+    pub nonce: u128,
+    /// Factory call deploying the account; empty once deployed
+    #[serde(with = "bytes")]
+    pub init_code: Vec<u8>,
+    /// Call the account executes
+    #[serde(with = "bytes")]
+    pub call_data: Vec<u8>,
+    /// Gas for the execution phase
+    #[serde(with = "quantity")]
+    pub call_gas_limit: u128,
+    /// Gas for validation, including the paymaster's
+    #[serde(with = "quantity")]
+    pub verification_gas_limit: u128,
+    /// Gas paid to the bundler for overhead
+    #[serde(with = "quantity")]
+    pub pre_verification_gas: u128,
+    /// Max fee per gas
+    #[serde(with = "quantity")]
+    pub max_fee_per_gas: u128,
+    /// Max priority fee per gas
+    #[serde(with = "quantity")]
+    pub max_priority_fee_per_gas: u128,
+    /// Paymaster address followed by its data; empty when self-paid
+    #[serde(with = "bytes")]
+    pub paymaster_and_data: Vec<u8>,
+    /// Account owner's signature over [`hash`](Self::hash)
+    #[serde(with = "bytes")]
+    pub signature: Vec<u8>,
+}
+
+impl UserOperation {
+    /// The user operation hash the account owner signs
+    pub fn hash(&self, entry_point: &str, chain_id: u64) -> Result<[u8; 32]> {
+        let mut packed = Vec::with_capacity(10 * 32);
+        packed.extend_from_slice(&address_word(&self.sender)?);
+        packed.extend_from_slice(&uint_word(self.nonce));
+        packed.extend_from_slice(&keccak(&self.init_code));
+        packed.extend_from_slice(&keccak(&self.call_data));
+        for value in [
+            self.call_gas_limit,
+            self.verification_gas_limit,
+            self.pre_verification_gas,
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+        ] {
+            packed.extend_from_slice(&uint_word(value));
+        }
+        packed.extend_from_slice(&keccak(&self.paymaster_and_data));
+
+        let mut outer = Vec::with_capacity(3 * 32);
+        outer.extend_from_slice(&keccak(&packed));
+        outer.extend_from_slice(&address_word(entry_point)?);
+        outer.extend_from_slice(&uint_word(u128::from(chain_id)));
+        Ok(keccak(&outer))
+    }
+
+    /// Signs the operation as its owner: an EIP-191 signature over the hash,
+    /// encoded `r || s || v`
+    pub async fn sign(
+        &mut self,
+        signer: &dyn Signer,
+        entry_point: &str,
+        chain_id: u64,
+    ) -> Result<()> {
+        let mut prefixed = b"\x19Ethereum Signed Message:\n32".to_vec();
+        prefixed.extend_from_slice(&self.hash(entry_point, chain_id)?);
+        let digest = keccak(&prefixed);
+        let mut signature = signer.sign_hash(&digest).await?;
+        let v = recovery_id(&digest, &signature, &signer.public_key())
+            .ok_or_else(|| WalletError::KeyError("signature does not match the signer".into()))?;
+        signature.push(27 + v);
+        self.signature = signature;
+        Ok(())
+    }
+}
+
+/// Calldata for the smart account's `execute(address,uint256,bytes)`
+pub fn execute_call(to: &str, value: u128, data: &[u8]) -> Result<Vec<u8>> {
+    let mut call = keccak(b"execute(address,uint256,bytes)")[..4].to_vec();
+    call.extend_from_slice(&address_word(to)?);
+    call.extend_from_slice(&uint_word(value));
+    call.extend_from_slice(&uint_word(3 * 32));
+    call.extend_from_slice(&uint_word(data.len() as u128));
+    call.extend_from_slice(data);
+    call.resize(call.len() + (32 - data.len() % 32) % 32, 0);
+    Ok(call)
+}
+
+/// Gas limits estimated by a bundler
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GasEstimate {
+    /// Gas paid to the bundler for overhead
+    #[serde(with = "quantity")]
+    pub pre_verification_gas: u128,
+    /// Gas for validation
+    #[serde(with = "quantity")]
+    pub verification_gas_limit: u128,
+    /// Gas for the execution phase
+    #[serde(with = "quantity")]
+    pub call_gas_limit: u128,
+}
+
+/// An ERC-4337 bundler's JSON-RPC endpoint
+///
+/// Also used for the `eth_*` reads a user operation needs, which bundler
+/// endpoints forward to a node.
+#[derive(Clone)]
+pub struct Bundler {
+    url: String,
+    client: Arc<RpcClient>,
+}
+
+impl fmt::Debug for Bundler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Bundler").field("url", &self.url).finish()
+    }
+}
+
+impl Bundler {
+    /// Uses the bundler at `url`
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Ok(Self::with_client(url, Arc::new(RpcClient::new()?)))
+    }
+
+    /// Uses the bundler at `url` through a shared client
+    pub fn with_client(url: impl Into<String>, client: Arc<RpcClient>) -> Self {
+        Self {
+            url: url.into(),
+            client,
+        }
+    }
+
+    /// Reads the account's next nonce for key 0 from the EntryPoint
+    pub async fn nonce(&self, entry_point: &str, sender: &str) -> Result<u128> {
+        let mut call = keccak(b"getNonce(address,uint192)")[..4].to_vec();
+        call.extend_from_slice(&address_word(sender)?);
+        call.extend_from_slice(&uint_word(0));
+        let result: String = self
+            .client
+            .rpc_call(
+                &self.url,
+                "eth_call",
+                json!([{ "to": entry_point, "data": hex_string(&call) }, "latest"]),
+            )
+            .await?;
+        // A 32-byte word, but with key 0 the value is a 64-bit sequence
+        let digits = result.trim_start_matches("0x").trim_start_matches('0');
+        if digits.is_empty() {
+            return Ok(0);
+        }
+        u128::from_str_radix(digits, 16)
+            .map_err(|_| PaymasterError::InvalidResponse(format!("nonce {}", result)))
+    }
+
+    /// Current fees: twice the base fee plus the suggested tip, and the tip
+    pub async fn fees(&self) -> Result<(u128, u128)> {
+        let gas_price: String = self
+            .client
+            .rpc_call(&self.url, "eth_gasPrice", json!([]))
+            .await?;
+        let tip: String = self
+            .client
+            .rpc_call(&self.url, "eth_maxPriorityFeePerGas", json!([]))
+            .await?;
+        let (gas_price, tip) = match (parse_quantity(&gas_price), parse_quantity(&tip)) {
+            (Some(gas_price), Some(tip)) => (gas_price, tip),
+            _ => return Err(PaymasterError::InvalidResponse("gas price".into())),
+        };
+        let base_fee = gas_price.saturating_sub(tip);
+        Ok((base_fee.saturating_mul(2).saturating_add(tip), tip))
+    }
+
+    /// Estimates gas limits for `op`
+    pub async fn estimate(&self, op: &UserOperation, entry_point: &str) -> Result<GasEstimate> {
+        Ok(self
+            .client
+            .rpc_call(
+                &self.url,
+                "eth_estimateUserOperationGas",
+                json!([op, entry_point]),
+            )
+            .await?)
+    }
+
+    /// Sends `op`, returning its user operation hash
+    pub async fn send(&self, op: &UserOperation, entry_point: &str) -> Result<String> {
+        Ok(self
+            .client
+            .rpc_call(&self.url, "eth_sendUserOperation", json!([op, entry_point]))
+            .await?)
+    }
+}
+
+/// An ERC-7677 paymaster service
+#[derive(Clone)]
+pub struct Paymaster {
+    url: String,
+    client: Arc<RpcClient>,
+    context: Value,
+}
+
+impl fmt::Debug for Paymaster {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Paymaster").field("url", &self.url).finish()
+    }
+}
+
+impl Paymaster {
+    /// Uses the paymaster service at `url`
+    pub fn new(url: impl Into<String>) -> Result<Self> {
+        Ok(Self::with_client(url, Arc::new(RpcClient::new()?)))
+    }
+
+    /// Uses the paymaster service at `url` through a shared client
+    pub fn with_client(url: impl Into<String>, client: Arc<RpcClient>) -> Self {
+        Self {
+            url: url.into(),
+            client,
+            context: json!({}),
+        }
+    }
+
+    /// Sets the service-specific context sent with every request, e.g. a
+    /// sponsorship policy id
+    pub fn with_context(mut self, context: Value) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// Placeholder `paymasterAndData` for gas estimation
+    pub async fn stub_data(
+        &self,
+        op: &UserOperation,
+        entry_point: &str,
+        chain_id: u64,
+        context: &Value,
+    ) -> Result<Vec<u8>> {
+        self.request(
+            "pm_getPaymasterStubData",
+            op,
+            entry_point,
+            chain_id,
+            context,
+        )
+        .await
+    }
+
+    /// Final `paymasterAndData` for the estimated operation
+    pub async fn data(
+        &self,
+        op: &UserOperation,
+        entry_point: &str,
+        chain_id: u64,
+        context: &Value,
+    ) -> Result<Vec<u8>> {
+        self.request("pm_getPaymasterData", op, entry_point, chain_id, context)
+            .await
+    }
+
+    async fn request(
+        &self,
+        method: &str,
+        op: &UserOperation,
+        entry_point: &str,
+        chain_id: u64,
+        context: &Value,
+    ) -> Result<Vec<u8>> {
+        let params = json!([op, entry_point, format!("{:#x}", chain_id), context]);
+        let result: Value = match self.client.rpc_call(&self.url, method, params).await {
+            Ok(result) => result,
+            // Paymasters answer operations they won't pay for with an RPC error
+            Err(ProviderError::RpcError { message, .. }) => {
+                return Err(PaymasterError::Rejected(message))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let data = result["paymasterAndData"].as_str().ok_or_else(|| {
+            PaymasterError::InvalidResponse(format!("{} without paymasterAndData", method))
+        })?;
+        decode_hex(data)
+    }
+}
+
+/// Sends calls as user operations from a smart account
+///
+/// The account is `account`, owned by `signer`; the EOA of the wrapped
+/// wallet does not appear on chain. Token payment is offered only when the
+/// paymaster was set up for it with [`accept_tokens`](Self::accept_tokens),
+/// and only without a `max_amount`, since ERC-7677 has no fee quotes.
+pub struct Erc4337Sponsor {
+    chain_id: u64,
+    account: String,
+    entry_point: String,
+    bundler: Bundler,
+    paymaster: Paymaster,
+    signer: Arc<dyn Signer>,
+    accept_tokens: bool,
+}
+
+impl fmt::Debug for Erc4337Sponsor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Erc4337Sponsor")
+            .field("chain_id", &self.chain_id)
+            .field("account", &self.account)
+            .field("entry_point", &self.entry_point)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Erc4337Sponsor {
+    /// Creates a sponsor for `account` on `chain_id`
+    pub fn new(
+        chain_id: u64,
+        account: impl Into<String>,
+        bundler: Bundler,
+        paymaster: Paymaster,
+        signer: Arc<dyn Signer>,
+    ) -> Self {
+        Self {
+            chain_id,
+            account: account.into(),
+            entry_point: ENTRY_POINT_V06.to_string(),
+            bundler,
+            paymaster,
+            signer,
+            accept_tokens: false,
+        }
+    }
+
+    /// Uses another EntryPoint deployment
+    pub fn with_entry_point(mut self, entry_point: impl Into<String>) -> Self {
+        self.entry_point = entry_point.into();
+        self
+    }
+
+    /// Offers ERC-20 gas payment; the token goes to the paymaster as
+    /// `token` in the request context
+    pub fn accept_tokens(mut self, accept: bool) -> Self {
+        self.accept_tokens = accept;
+        self
+    }
+}
+
+#[async_trait]
+impl GasSponsor for Erc4337Sponsor {
+    fn name(&self) -> &str {
+        "erc4337"
+    }
+
+    fn supports(&self, chain_id: u64, policy: &GasPolicy) -> bool {
+        chain_id == self.chain_id
+            && match policy {
+                GasPolicy::Native => false,
+                GasPolicy::PreferSponsored | GasPolicy::Sponsored => true,
+                GasPolicy::Token { max_amount, .. } => self.accept_tokens && max_amount.is_none(),
+            }
+    }
+
+    async fn submit(&self, request: &GasRequest) -> Result<Submission> {
+        let mut context = self.paymaster.context.clone();
+        if let GasPolicy::Token { token, .. } = &request.policy {
+            context["token"] = json!(token);
+        }
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            match (request.max_fee_per_gas, request.max_priority_fee_per_gas) {
+                (Some(fee), Some(tip)) => (fee, tip),
+                _ => self.bundler.fees().await?,
+            };
+        let mut op = UserOperation {
+            sender: self.account.clone(),
+            nonce: self.bundler.nonce(&self.entry_point, &self.account).await?,
+            call_data: execute_call(&request.to, request.value, &request.data)?,
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            signature: decode_hex(DUMMY_SIGNATURE)?,
+            ..Default::default()
+        };
+
+        op.paymaster_and_data = self
+            .paymaster
+            .stub_data(&op, &self.entry_point, self.chain_id, &context)
+            .await?;
+        let gas = self.bundler.estimate(&op, &self.entry_point).await?;
+        op.pre_verification_gas = gas.pre_verification_gas;
+        op.verification_gas_limit = gas.verification_gas_limit;
+        op.call_gas_limit = gas.call_gas_limit;
+        op.paymaster_and_data = self
+            .paymaster
+            .data(&op, &self.entry_point, self.chain_id, &context)
+            .await?;
+
+        op.sign(self.signer.as_ref(), &self.entry_point, self.chain_id)
+            .await?;
+        let hash = self.bundler.send(&op, &self.entry_point).await?;
+        Ok(Submission {
+            sponsor: self.name().to_string(),
+            id: hash,
+        })
+    }
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+fn address_word(address: &str) -> Result<[u8; 32]> {
+    let bytes = decode_hex(address)?;
+    if bytes.len() != 20 {
+        return Err(WalletError::InvalidAddress(address.to_string()).into());
+    }
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+fn decode_hex(value: &str) -> Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| PaymasterError::InvalidResponse(format!("bad hex {}: {}", value, e)))
+}
+
+fn hex_string(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+/// Parses a `0x` hex or decimal quantity
+fn parse_quantity(value: &str) -> Option<u128> {
+    match value.strip_prefix("0x") {
+        Some("") => Some(0),
+        Some(digits) => u128::from_str_radix(digits, 16).ok(),
+        None => value.parse().ok(),
+    }
+}
+
+mod quantity {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&format!("{:#x}", value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+        match Value::deserialize(deserializer)? {
+            Value::String(value) => super::parse_quantity(&value)
+                .ok_or_else(|| D::Error::custom(format!("invalid quantity {}", value))),
+            Value::Number(value) => value
+                .as_u64()
+                .map(u128::from)
+                .ok_or_else(|| D::Error::custom(format!("invalid quantity {}", value))),
+            other => Err(D::Error::custom(format!("invalid quantity {}", other))),
+        }
+    }
+}
+
+mod bytes {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(value: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&super::hex_string(value))
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        hex::decode(value.trim_start_matches("0x")).map_err(D::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Secp256k1Signer;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ACCOUNT: &str = "0x1111111111111111111111111111111111111111";
+    const RECIPIENT: &str = "0x2222222222222222222222222222222222222222";
+    const PAYMASTER: &str = "0x3333333333333333333333333333333333333333";
+
+    fn signer() -> Arc<dyn Signer> {
+        Arc::new(Secp256k1Signer::from_slice(&[7u8; 32]).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_execute_call_and_signing() {
+        let call = execute_call(RECIPIENT, 5, &[0xab; 4]).unwrap();
+        assert_eq!(hex::encode(&call[..4]), "b61d27f6");
+        assert_eq!(call.len(), 4 + 5 * 32);
+        assert!(execute_call("0x1234", 0, &[]).is_err());
+
+        let mut op = UserOperation {
+            sender: ACCOUNT.into(),
+            call_data: call,
+            ..Default::default()
+        };
+        let hash = op.hash(ENTRY_POINT_V06, 137).unwrap();
+        assert_ne!(hash, op.hash(ENTRY_POINT_V06, 1).unwrap());
+
+        op.sign(signer().as_ref(), ENTRY_POINT_V06, 137)
+            .await
+            .unwrap();
+        assert_eq!(op.signature.len(), 65);
+        assert!(matches!(op.signature[64], 27 | 28));
+        // The signature is not part of the hash
+        assert_eq!(op.hash(ENTRY_POINT_V06, 137).unwrap(), hash);
+
+        let json = serde_json::to_value(&op).unwrap();
+        assert_eq!(json["nonce"], "0x0");
+        assert_eq!(serde_json::from_value::<UserOperation>(json).unwrap(), op);
+    }
+
+    async fn rpc(server: &MockServer, rpc_method: &str, result: Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(json!({ "method": rpc_method })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_sponsored_user_operation() {
+        let server = MockServer::start().await;
+        rpc(&server, "eth_call", json!(format!("0x{:064x}", 5))).await;
+        rpc(
+            &server,
+            "pm_getPaymasterStubData",
+            json!({ "paymasterAndData": PAYMASTER }),
+        )
+        .await;
+        rpc(
+            &server,
+            "eth_estimateUserOperationGas",
+            json!({
+                "preVerificationGas": "0xb000",
+                "verificationGasLimit": "0x20000",
+                "callGasLimit": 50000
+            }),
+        )
+        .await;
+        rpc(
+            &server,
+            "pm_getPaymasterData",
+            json!({ "paymasterAndData": format!("{}{}", PAYMASTER, "ab".repeat(32)) }),
+        )
+        .await;
+        rpc(&server, "eth_sendUserOperation", json!("0xuserophash")).await;
+
+        let sponsor = Erc4337Sponsor::new(
+            137,
+            ACCOUNT,
+            Bundler::new(server.uri()).unwrap(),
+            Paymaster::new(server.uri()).unwrap(),
+            signer(),
+        );
+        assert!(sponsor.supports(137, &GasPolicy::Sponsored));
+        assert!(!sponsor.supports(
+            137,
+            &GasPolicy::Token {
+                token: "0xusdc".into(),
+                max_amount: None
+            }
+        ));
+
+        let request = GasRequest {
+            chain_id: 137,
+            from: "0xowner".into(),
+            to: RECIPIENT.into(),
+            value: 0,
+            data: vec![],
+            gas_limit: None,
+            max_fee_per_gas: Some(100),
+            max_priority_fee_per_gas: Some(2),
+            policy: GasPolicy::Sponsored,
+        };
+        let submission = sponsor.submit(&request).await.unwrap();
+        assert_eq!(submission.id, "0xuserophash");
+
+        let requests = server.received_requests().await.unwrap();
+        let sent: Value = serde_json::from_slice(&requests.last().unwrap().body).unwrap();
+        let op: UserOperation = serde_json::from_value(sent["params"][0].clone()).unwrap();
+        assert_eq!(op.nonce, 5);
+        assert_eq!(op.call_gas_limit, 50_000);
+        assert_eq!(op.paymaster_and_data.len(), 52);
+        assert_eq!(sent["params"][1], ENTRY_POINT_V06);
+    }
+}
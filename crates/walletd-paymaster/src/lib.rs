@@ -0,0 +1,134 @@
+//! # WalletD Paymaster
+//!
+//! Gas abstraction for EVM chains: transfers whose gas is sponsored or paid
+//! in an ERC-20 token instead of the native currency.
+//!
+//! The [`GasPolicy`] on a transaction builder says who pays. A
+//! [`GasStation`] hands anything but native payment to the first
+//! [`GasSponsor`] that supports it:
+//!
+//! - [`Erc4337Sponsor`]: user operations from a smart account, with an
+//!   ERC-7677 paymaster and an ERC-4337 bundler
+//! - [`GelatoRelay`]: gas station relays as used on Polygon and Avalanche,
+//!   sponsored or paid from the call in a fee token
+//!
+//! [`GasWallet`] wraps an EVM wallet so `transfer_with` follows the policy.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_paymaster::{Bundler, Erc4337Sponsor, GasStation, GasWallet, Paymaster};
+//! use walletd_traits::{GasPolicy, TransactionBuilder};
+//!
+//! let sponsor = Erc4337Sponsor::new(
+//!     137,
+//!     smart_account,
+//!     Bundler::new(bundler_url)?,
+//!     Paymaster::new(paymaster_url)?,
+//!     owner_signer,
+//! );
+//! let wallet = GasWallet::new(polygon_wallet, 137, GasStation::new().with_sponsor(Arc::new(sponsor)));
+//!
+//! let tx = TransactionBuilder::new()
+//!     .to(recipient)
+//!     .data(transfer_call)
+//!     .gas_policy(GasPolicy::Sponsored);
+//! let user_op_hash = wallet.transfer_with(tx).await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod erc4337;
+pub mod relay;
+pub mod sponsor;
+pub mod wallet;
+
+pub use erc4337::{
+    Bundler, Erc4337Sponsor, GasEstimate, Paymaster, UserOperation, ENTRY_POINT_V06,
+};
+pub use relay::{GelatoRelay, GELATO_API};
+pub use sponsor::{GasRequest, GasSponsor, GasStation, Submission};
+pub use wallet::GasWallet;
+pub use walletd_traits::GasPolicy;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_provider::ProviderError;
+use walletd_traits::{Amount, WalletError};
+
+/// Gas abstraction errors
+#[derive(Error, Debug)]
+pub enum PaymasterError {
+    /// No sponsor supports the chain and policy
+    #[error("No gas sponsor for {0}")]
+    NoSponsor(String),
+
+    /// The paymaster or relay declined the transaction
+    #[error("Gas sponsor declined: {0}")]
+    Rejected(String),
+
+    /// The quoted token fee is above the caller's limit
+    #[error("Gas fee {quoted} exceeds the limit of {max}")]
+    FeeExceeded {
+        /// Fee quoted by the relay
+        quoted: Amount,
+        /// Most the caller would pay
+        max: Amount,
+    },
+
+    /// Unexpected response from a bundler, paymaster or relay
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// Request to a bundler, paymaster or relay failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// Building or signing the transaction failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+impl PaymasterError {
+    /// Whether the sponsor turned the transaction down before submitting
+    /// it, so another sponsor may be tried
+    pub fn is_declined(&self) -> bool {
+        matches!(
+            self,
+            PaymasterError::NoSponsor(_)
+                | PaymasterError::Rejected(_)
+                | PaymasterError::FeeExceeded { .. }
+        )
+    }
+}
+
+/// Result type for gas abstraction
+pub type Result<T> = std::result::Result<T, PaymasterError>;
+
+impl From<PaymasterError> for WalletError {
+    fn from(e: PaymasterError) -> Self {
+        match e {
+            PaymasterError::Wallet(e) => e,
+            PaymasterError::NoSponsor(_) => WalletError::NotSupported(e.to_string()),
+            PaymasterError::Provider(_) => WalletError::NetworkError(e.to_string()),
+            e => WalletError::TransactionFailed(e.to_string()),
+        }
+    }
+}
+
+impl From<PaymasterError> for WalletdError {
+    fn from(e: PaymasterError) -> Self {
+        match e {
+            PaymasterError::Provider(e) => e.into(),
+            PaymasterError::NoSponsor(_) => WalletdError::NotSupported(e.to_string()),
+            PaymasterError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            PaymasterError::Rejected(_) | PaymasterError::FeeExceeded { .. } => {
+                WalletdError::TransactionFailed(e.to_string())
+            }
+            PaymasterError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+        }
+    }
+}
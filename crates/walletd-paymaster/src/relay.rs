@@ -0,0 +1,266 @@
+//! Gas station relays
+
+use crate::sponsor::{GasRequest, GasSponsor, Submission};
+use crate::{PaymasterError, Result};
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::fmt;
+use std::sync::Arc;
+use walletd_provider::RpcClient;
+use walletd_traits::{Amount, GasPolicy};
+
+/// Gelato relay API
+pub const GELATO_API: &str = "https://api.gelato.digital";
+
+/// Gas limit assumed for fee quotes when the request sets none
+const DEFAULT_GAS_LIMIT: u64 = 200_000;
+
+/// A Gelato-style gas relay
+///
+/// The relay sends the call itself, so `to` sees the relay as the caller and
+/// must authenticate the user from the calldata: a smart account, or a
+/// contract supporting ERC-2771 or Gelato's relay context. Sponsored calls
+/// need a sponsor API key; token payment uses `callWithSyncFee`, where the
+/// target pays the relay from the call. Calls cannot carry native value.
+#[derive(Clone)]
+pub struct GelatoRelay {
+    base_url: String,
+    api_key: Option<String>,
+    chains: Option<Vec<u64>>,
+    client: Arc<RpcClient>,
+}
+
+impl fmt::Debug for GelatoRelay {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GelatoRelay")
+            .field("base_url", &self.base_url)
+            .field("chains", &self.chains)
+            .finish_non_exhaustive()
+    }
+}
+
+impl GelatoRelay {
+    /// Uses the public Gelato API
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            base_url: GELATO_API.to_string(),
+            api_key: None,
+            chains: None,
+            client: Arc::new(RpcClient::new()?),
+        })
+    }
+
+    /// Uses another relay with the same API
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Sets the sponsor API key, enabling sponsored calls
+    pub fn with_api_key(mut self, key: impl Into<String>) -> Self {
+        self.api_key = Some(key.into());
+        self
+    }
+
+    /// Limits the relay to these chain ids
+    pub fn with_chains(mut self, chains: &[u64]) -> Self {
+        self.chains = Some(chains.to_vec());
+        self
+    }
+
+    /// Quotes the fee for `gas_limit` gas paid in `token`, in the token's
+    /// smallest unit
+    pub async fn estimate_fee(&self, chain_id: u64, token: &str, gas_limit: u64) -> Result<u128> {
+        let url = format!(
+            "{}/oracles/{}/estimate?paymentToken={}&gasLimit={}&isHighPriority=false",
+            self.base_url, chain_id, token, gas_limit
+        );
+        let response: Value = self.client.get(&url).await?;
+        response["estimatedFee"]
+            .as_str()
+            .and_then(|fee| fee.parse().ok())
+            .ok_or_else(|| rejection(&response, "fee estimate"))
+    }
+
+    async fn relay(&self, path: &str, body: Value) -> Result<Submission> {
+        let url = format!("{}/relays/v2/{}", self.base_url, path);
+        let response: Value = self.client.post_json(&url, body).await?;
+        let task = response["taskId"]
+            .as_str()
+            .ok_or_else(|| rejection(&response, "task id"))?;
+        Ok(Submission {
+            sponsor: self.name().to_string(),
+            id: task.to_string(),
+        })
+    }
+}
+
+#[async_trait]
+impl GasSponsor for GelatoRelay {
+    fn name(&self) -> &str {
+        "gelato"
+    }
+
+    fn supports(&self, chain_id: u64, policy: &GasPolicy) -> bool {
+        let chain = self
+            .chains
+            .as_ref()
+            .is_none_or(|chains| chains.contains(&chain_id));
+        chain
+            && match policy {
+                GasPolicy::Native => false,
+                GasPolicy::PreferSponsored | GasPolicy::Sponsored => self.api_key.is_some(),
+                GasPolicy::Token { .. } => true,
+            }
+    }
+
+    async fn submit(&self, request: &GasRequest) -> Result<Submission> {
+        if request.value != 0 {
+            return Err(PaymasterError::Rejected(
+                "relayed calls cannot send native value".into(),
+            ));
+        }
+        let data = format!("0x{}", hex::encode(&request.data));
+        match &request.policy {
+            GasPolicy::Token { token, max_amount } => {
+                if let Some(max) = max_amount {
+                    let gas_limit = request.gas_limit.unwrap_or(DEFAULT_GAS_LIMIT);
+                    let fee = self
+                        .estimate_fee(request.chain_id, token, gas_limit)
+                        .await?;
+                    if fee > max.value {
+                        return Err(PaymasterError::FeeExceeded {
+                            quoted: Amount::from_smallest_unit(fee, max.decimals),
+                            max: *max,
+                        });
+                    }
+                }
+                self.relay(
+                    "call-with-sync-fee",
+                    json!({
+                        "chainId": request.chain_id.to_string(),
+                        "target": request.to,
+                        "data": data,
+                        "feeToken": token,
+                        "isRelayContext": true,
+                    }),
+                )
+                .await
+            }
+            _ => {
+                let key = self.api_key.as_ref().ok_or_else(|| {
+                    PaymasterError::NoSponsor("gelato without a sponsor API key".into())
+                })?;
+                self.relay(
+                    "sponsored-call",
+                    json!({
+                        "chainId": request.chain_id.to_string(),
+                        "target": request.to,
+                        "data": data,
+                        "sponsorApiKey": key,
+                    }),
+                )
+                .await
+            }
+        }
+    }
+}
+
+/// The relay's error message, or a note that `what` was missing
+fn rejection(response: &Value, what: &str) -> PaymasterError {
+    match response["message"].as_str() {
+        Some(message) => PaymasterError::Rejected(message.to_string()),
+        None => PaymasterError::InvalidResponse(format!("no {} in {}", what, response)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const USDC: &str = "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359";
+
+    fn request(max_amount: Option<Amount>) -> GasRequest {
+        GasRequest {
+            chain_id: 137,
+            from: "0xsender".into(),
+            to: "0xtarget".into(),
+            value: 0,
+            data: vec![0x01, 0x02],
+            gas_limit: Some(100_000),
+            max_fee_per_gas: None,
+            max_priority_fee_per_gas: None,
+            policy: GasPolicy::Token {
+                token: USDC.into(),
+                max_amount,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_fee_limit() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/oracles/137/estimate"))
+            .and(query_param("gasLimit", "100000"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "estimatedFee": "25000"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/relays/v2/call-with-sync-fee"))
+            .and(body_partial_json(
+                json!({ "feeToken": USDC, "data": "0x0102" }),
+            ))
+            .respond_with(ResponseTemplate::new(201).set_body_json(json!({ "taskId": "0xtask" })))
+            .mount(&server)
+            .await;
+
+        let relay = GelatoRelay::new()
+            .unwrap()
+            .with_base_url(server.uri())
+            .with_chains(&[137]);
+        assert!(!relay.supports(137, &GasPolicy::Sponsored));
+        assert!(!relay.supports(1, &request(None).policy));
+
+        let submission = relay
+            .submit(&request(Some(Amount::from_smallest_unit(30_000, 6))))
+            .await
+            .unwrap();
+        assert_eq!(submission.id, "0xtask");
+
+        let err = relay
+            .submit(&request(Some(Amount::from_smallest_unit(20_000, 6))))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, PaymasterError::FeeExceeded { .. }));
+        assert!(err.is_declined());
+    }
+
+    #[tokio::test]
+    async fn test_sponsored_call_rejection() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/relays/v2/sponsored-call"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "message": "Insufficient balance in 1Balance"
+            })))
+            .mount(&server)
+            .await;
+
+        let relay = GelatoRelay::new()
+            .unwrap()
+            .with_base_url(server.uri())
+            .with_api_key("key");
+        let mut request = request(None);
+        request.policy = GasPolicy::Sponsored;
+        assert!(relay.supports(137, &request.policy));
+        assert!(matches!(
+            relay.submit(&request).await,
+            Err(PaymasterError::Rejected(message)) if message.contains("1Balance")
+        ));
+    }
+}
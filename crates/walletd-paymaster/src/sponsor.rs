@@ -0,0 +1,241 @@
+//! Gas sponsors and routing between them
+
+use crate::{PaymasterError, Result};
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+use walletd_traits::{EvmTxParams, GasPolicy, TransactionBuilder, WalletError};
+
+/// A call whose gas someone other than the sender's native balance pays
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GasRequest {
+    /// EVM chain id
+    pub chain_id: u64,
+    /// Sender address
+    pub from: String,
+    /// Target address
+    pub to: String,
+    /// Native value in wei
+    pub value: u128,
+    /// Calldata
+    pub data: Vec<u8>,
+    /// Gas limit, if the caller set one
+    pub gas_limit: Option<u64>,
+    /// Max fee per gas in wei, if the caller set one
+    pub max_fee_per_gas: Option<u128>,
+    /// Max priority fee per gas in wei, if the caller set one
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Who pays
+    pub policy: GasPolicy,
+}
+
+impl GasRequest {
+    /// Builds a request from an EVM transaction builder
+    pub fn from_builder(
+        chain_id: u64,
+        from: impl Into<String>,
+        tx: &TransactionBuilder<EvmTxParams>,
+    ) -> Result<Self> {
+        let to = tx
+            .to
+            .clone()
+            .ok_or_else(|| WalletError::InvalidAddress("missing recipient".into()))?;
+        let params = &tx.params;
+        Ok(Self {
+            chain_id,
+            from: from.into(),
+            to,
+            value: tx.amount.map_or(0, |amount| amount.value),
+            data: tx.data.clone().unwrap_or_default(),
+            gas_limit: params.gas_limit,
+            max_fee_per_gas: params.max_fee_per_gas.map(|fee| fee.value),
+            max_priority_fee_per_gas: params.max_priority_fee_per_gas.map(|fee| fee.value),
+            policy: params.gas_policy.clone(),
+        })
+    }
+}
+
+/// A call accepted by a sponsor
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Submission {
+    /// Name of the sponsor that took it
+    pub sponsor: String,
+    /// The sponsor's id for it: a user operation hash or a relay task id
+    pub id: String,
+}
+
+/// A paymaster or relay that gets calls on chain without native gas
+#[async_trait]
+pub trait GasSponsor: Send + Sync {
+    /// Short name for logs and [`Submission::sponsor`]
+    fn name(&self) -> &str;
+
+    /// Whether this sponsor can carry out `policy` on `chain_id`
+    fn supports(&self, chain_id: u64, policy: &GasPolicy) -> bool;
+
+    /// Submits the call
+    ///
+    /// Errors for which [`PaymasterError::is_declined`] holds mean nothing
+    /// was submitted.
+    async fn submit(&self, request: &GasRequest) -> Result<Submission>;
+}
+
+/// Routes calls to the first sponsor that takes them
+#[derive(Clone, Default)]
+pub struct GasStation {
+    sponsors: Vec<Arc<dyn GasSponsor>>,
+}
+
+impl fmt::Debug for GasStation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("GasStation")
+            .field(
+                "sponsors",
+                &self.sponsors.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+impl GasStation {
+    /// Creates a station without sponsors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a sponsor; earlier sponsors are tried first
+    pub fn with_sponsor(mut self, sponsor: Arc<dyn GasSponsor>) -> Self {
+        self.sponsors.push(sponsor);
+        self
+    }
+
+    /// Submits `request` according to its policy
+    ///
+    /// Returns `None` when the sender should pay native gas: for
+    /// [`GasPolicy::Native`], and for [`GasPolicy::PreferSponsored`] when
+    /// every sponsor declined. Sponsors that decline are skipped; any other
+    /// error is returned at once, since the call may have been submitted.
+    pub async fn submit(&self, request: &GasRequest) -> Result<Option<Submission>> {
+        if request.policy == GasPolicy::Native {
+            return Ok(None);
+        }
+        let mut declined = None;
+        for sponsor in self
+            .sponsors
+            .iter()
+            .filter(|sponsor| sponsor.supports(request.chain_id, &request.policy))
+        {
+            match sponsor.submit(request).await {
+                Ok(submission) => return Ok(Some(submission)),
+                Err(e) if e.is_declined() => {
+                    tracing::debug!(sponsor = sponsor.name(), error = %e, "gas sponsor declined");
+                    declined = Some(e);
+                }
+                Err(e) => return Err(e),
+            }
+        }
+        if request.policy == GasPolicy::PreferSponsored {
+            return Ok(None);
+        }
+        Err(declined.unwrap_or_else(|| {
+            PaymasterError::NoSponsor(format!(
+                "{:?} on chain {}",
+                request.policy, request.chain_id
+            ))
+        }))
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use walletd_traits::Amount;
+
+    pub(crate) struct FixedSponsor {
+        pub name: &'static str,
+        pub accept: bool,
+    }
+
+    #[async_trait]
+    impl GasSponsor for FixedSponsor {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn supports(&self, chain_id: u64, policy: &GasPolicy) -> bool {
+            chain_id == 137 && *policy != GasPolicy::Native
+        }
+
+        async fn submit(&self, _request: &GasRequest) -> Result<Submission> {
+            if !self.accept {
+                return Err(PaymasterError::Rejected("over budget".into()));
+            }
+            Ok(Submission {
+                sponsor: self.name.to_string(),
+                id: "0xtask".into(),
+            })
+        }
+    }
+
+    fn request(policy: GasPolicy, chain_id: u64) -> GasRequest {
+        let tx = TransactionBuilder::new()
+            .to("0xrecipient")
+            .amount(Amount::from_smallest_unit(0, 18))
+            .data(vec![0xa9, 0x05, 0x9c, 0xbb])
+            .gas_policy(policy);
+        GasRequest::from_builder(chain_id, "0xsender", &tx).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_station_routes_by_policy() {
+        let station = GasStation::new()
+            .with_sponsor(Arc::new(FixedSponsor {
+                name: "picky",
+                accept: false,
+            }))
+            .with_sponsor(Arc::new(FixedSponsor {
+                name: "generous",
+                accept: true,
+            }));
+
+        assert!(station
+            .submit(&request(GasPolicy::Native, 137))
+            .await
+            .unwrap()
+            .is_none());
+        let submission = station
+            .submit(&request(GasPolicy::Sponsored, 137))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(submission.sponsor, "generous");
+
+        // Nobody serves chain 1
+        assert!(matches!(
+            station.submit(&request(GasPolicy::Sponsored, 1)).await,
+            Err(PaymasterError::NoSponsor(_))
+        ));
+        assert!(station
+            .submit(&request(GasPolicy::PreferSponsored, 1))
+            .await
+            .unwrap()
+            .is_none());
+    }
+
+    #[tokio::test]
+    async fn test_declines_surface_when_required() {
+        let station = GasStation::new().with_sponsor(Arc::new(FixedSponsor {
+            name: "picky",
+            accept: false,
+        }));
+        assert!(matches!(
+            station.submit(&request(GasPolicy::Sponsored, 137)).await,
+            Err(PaymasterError::Rejected(_))
+        ));
+        assert!(station
+            .submit(&request(GasPolicy::PreferSponsored, 137))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
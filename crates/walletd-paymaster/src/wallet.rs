@@ -0,0 +1,165 @@
+//! Wallet wrapper that follows the gas policy of each transfer
+
+use crate::sponsor::{GasRequest, GasStation};
+use async_trait::async_trait;
+use walletd_traits::{
+    Amount, EvmTxParams, GasPolicy, Network, TransactionBuilder, Transferable, TxHash, Wallet,
+    WalletResult,
+};
+
+/// An EVM wallet whose transfers can be sponsored or paid in tokens
+///
+/// Transfers with [`GasPolicy::Native`] go to the inner wallet unchanged.
+/// Others go to the [`GasStation`]; the returned hash is then the sponsor's
+/// id (a user operation hash or relay task id), not a transaction hash.
+/// With [`GasPolicy::PreferSponsored`] the inner wallet pays when no
+/// sponsor takes the transfer.
+pub struct GasWallet<W> {
+    inner: W,
+    chain_id: u64,
+    station: GasStation,
+}
+
+impl<W> GasWallet<W> {
+    /// Wraps a wallet on `chain_id`
+    pub fn new(inner: W, chain_id: u64, station: GasStation) -> Self {
+        Self {
+            inner,
+            chain_id,
+            station,
+        }
+    }
+
+    /// Returns the gas station
+    pub fn station(&self) -> &GasStation {
+        &self.station
+    }
+
+    /// Returns the wrapped wallet
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<W: Wallet> Wallet for GasWallet<W> {
+    fn address(&self) -> String {
+        self.inner.address()
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        self.inner.balance().await
+    }
+
+    fn network(&self) -> &Network {
+        self.inner.network()
+    }
+
+    fn currency_symbol(&self) -> &str {
+        self.inner.currency_symbol()
+    }
+
+    fn decimals(&self) -> u8 {
+        self.inner.decimals()
+    }
+}
+
+#[async_trait]
+impl<W: Transferable<TxParams = EvmTxParams>> Transferable for GasWallet<W> {
+    type TxParams = EvmTxParams;
+
+    async fn transfer_with(&self, mut tx: TransactionBuilder<EvmTxParams>) -> WalletResult<TxHash> {
+        if tx.params.gas_policy != GasPolicy::Native {
+            let request = GasRequest::from_builder(self.chain_id, self.inner.address(), &tx)?;
+            if let Some(submission) = self.station.submit(&request).await? {
+                return Ok(TxHash::new(submission.id));
+            }
+            tx.params.gas_policy = GasPolicy::Native;
+        }
+        self.inner.transfer_with(tx).await
+    }
+
+    async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount> {
+        self.inner.estimate_fee(to, amount).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sponsor::tests::FixedSponsor;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    struct Eoa {
+        network: Network,
+        sent: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Wallet for Eoa {
+        fn address(&self) -> String {
+            "0xsender".into()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "POL"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl Transferable for Eoa {
+        type TxParams = EvmTxParams;
+
+        async fn transfer_with(&self, tx: TransactionBuilder<EvmTxParams>) -> WalletResult<TxHash> {
+            assert_eq!(tx.params.gas_policy, GasPolicy::Native);
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok(TxHash::new("0xnative"))
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transfers_follow_gas_policy() {
+        let eoa = Eoa {
+            network: Network::mainnet("polygon"),
+            sent: AtomicU32::new(0),
+        };
+        let station = GasStation::new().with_sponsor(Arc::new(FixedSponsor {
+            name: "relay",
+            accept: false,
+        }));
+        let wallet = GasWallet::new(eoa, 137, station);
+        let tx = || TransactionBuilder::new().to("0xrecipient");
+
+        assert_eq!(wallet.transfer_with(tx()).await.unwrap().0, "0xnative");
+        assert_eq!(
+            wallet
+                .transfer_with(tx().gas_policy(GasPolicy::PreferSponsored))
+                .await
+                .unwrap()
+                .0,
+            "0xnative"
+        );
+        assert!(wallet
+            .transfer_with(tx().gas_policy(GasPolicy::Sponsored))
+            .await
+            .is_err());
+        assert_eq!(wallet.into_inner().sent.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,362 @@
+//! Building, pricing and sending a payout
+
+use crate::executor::PayoutExecutor;
+use crate::plan::{Batch, ChainCost, CostReport, Payout, PayoutPlan};
+use crate::strategy::Strategy;
+use crate::{PayoutError, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use walletd_traits::{Amount, BatchTransferReport, TransferResult};
+
+/// Payout setup for one chain
+pub struct ChainConfig {
+    chain: String,
+    executor: Arc<dyn PayoutExecutor>,
+    strategy: Option<Strategy>,
+    max_recipients: Option<usize>,
+}
+
+impl ChainConfig {
+    /// Pays `chain` through `executor`
+    ///
+    /// The strategy defaults to [`Strategy::for_chain`] when the executor
+    /// can batch, `Individual` otherwise.
+    pub fn new(chain: impl Into<String>, executor: Arc<dyn PayoutExecutor>) -> Self {
+        Self {
+            chain: chain.into(),
+            executor,
+            strategy: None,
+            max_recipients: None,
+        }
+    }
+
+    /// Overrides the strategy
+    pub fn strategy(mut self, strategy: Strategy) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    /// Caps recipients per transaction, e.g. for SPL token transfers,
+    /// which fit fewer per Solana transaction than SOL transfers
+    pub fn max_recipients(mut self, max: usize) -> Self {
+        self.max_recipients = Some(max);
+        self
+    }
+
+    fn resolved_strategy(&self) -> Strategy {
+        self.strategy.unwrap_or_else(|| {
+            if self.executor.native_batch() {
+                Strategy::for_chain(&self.chain)
+            } else {
+                Strategy::Individual
+            }
+        })
+    }
+
+    fn limit(&self) -> usize {
+        self.max_recipients
+            .unwrap_or_else(|| self.resolved_strategy().max_recipients())
+    }
+}
+
+impl fmt::Debug for ChainConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainConfig")
+            .field("chain", &self.chain)
+            .field("strategy", &self.strategy)
+            .field("max_recipients", &self.max_recipients)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Collects payments across chains and sends each chain's share in as few
+/// transactions as it allows
+#[derive(Debug, Default)]
+pub struct PayoutBuilder {
+    chains: HashMap<String, ChainConfig>,
+    payouts: Vec<Payout>,
+}
+
+impl PayoutBuilder {
+    /// An empty payout with no chains
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a chain
+    pub fn chain(mut self, config: ChainConfig) -> Self {
+        self.chains.insert(config.chain.clone(), config);
+        self
+    }
+
+    /// Adds a payment of `amount` to `to` on `chain`
+    pub fn pay(&mut self, chain: &str, to: impl Into<String>, amount: Amount) -> Result<&mut Self> {
+        let to = to.into();
+        if !self.chains.contains_key(chain) {
+            return Err(PayoutError::UnknownChain(chain.to_string()));
+        }
+        if amount.smallest_unit() == 0 {
+            return Err(PayoutError::ZeroAmount(to));
+        }
+        let mixed = self
+            .payouts
+            .iter()
+            .any(|p| p.chain == chain && p.amount.decimals != amount.decimals);
+        if mixed {
+            return Err(PayoutError::MixedDecimals(chain.to_string()));
+        }
+        self.payouts.push(Payout {
+            chain: chain.to_string(),
+            to,
+            amount,
+        });
+        Ok(self)
+    }
+
+    /// Payments added so far, in order
+    pub fn payouts(&self) -> &[Payout] {
+        &self.payouts
+    }
+
+    /// Groups the payments into transactions
+    pub fn plan(&self) -> PayoutPlan {
+        PayoutPlan::new(
+            &self.payouts,
+            |chain| self.chains[chain].resolved_strategy(),
+            |chain| self.chains[chain].limit(),
+        )
+    }
+
+    /// Estimates the fees of the plan against paying every recipient
+    /// separately, without sending anything
+    pub async fn dry_run(&self) -> Result<CostReport> {
+        let mut report = CostReport::default();
+        for chain_plan in self.plan().chains {
+            let executor = &self.chains[&chain_plan.chain].executor;
+            let recipients: Vec<(String, Amount)> = chain_plan
+                .batches
+                .iter()
+                .flat_map(|b| b.recipients.iter().cloned())
+                .collect();
+            let overflow = || PayoutError::Overflow(chain_plan.chain.clone());
+
+            let mut fee: Option<Amount> = None;
+            for batch in &chain_plan.batches {
+                let estimate = executor.estimate(batch).await?;
+                fee = Some(match fee {
+                    None => estimate,
+                    Some(sum) => sum.checked_add(estimate).ok_or_else(overflow)?,
+                });
+            }
+            let individual = Batch {
+                strategy: Strategy::Individual,
+                recipients,
+            };
+            let individual_fee = if chain_plan.strategy.is_batched() {
+                executor.estimate(&individual).await?
+            } else {
+                fee.unwrap_or(Amount::zero(0))
+            };
+
+            report.chains.push(ChainCost {
+                recipients: chain_plan.recipients(),
+                transactions: chain_plan.transactions(),
+                total: individual.total().ok_or_else(overflow)?,
+                fee: fee.unwrap_or(Amount::zero(0)),
+                individual_fee,
+                strategy: chain_plan.strategy,
+                chain: chain_plan.chain,
+            });
+        }
+        Ok(report)
+    }
+
+    /// Sends the plan, chain by chain
+    ///
+    /// A failed transaction does not stop the payout; its recipients are
+    /// reported as failed and the remaining transactions still go out.
+    pub async fn execute(&self) -> PayoutReport {
+        let mut report = PayoutReport::default();
+        for chain_plan in self.plan().chains {
+            let executor = &self.chains[&chain_plan.chain].executor;
+            let mut batches = Vec::with_capacity(chain_plan.batches.len());
+            for batch in &chain_plan.batches {
+                let outcome = match executor.execute(batch).await {
+                    Ok(outcome) => outcome,
+                    Err(e) => {
+                        tracing::warn!(chain = %chain_plan.chain, error = %e, "payout batch failed");
+                        BatchTransferReport::single_tx(&batch.recipients, Err(e))
+                    }
+                };
+                batches.push(outcome);
+            }
+            report.chains.push(ChainPayout {
+                chain: chain_plan.chain,
+                strategy: chain_plan.strategy,
+                batches,
+            });
+        }
+        report
+    }
+}
+
+/// What was sent on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainPayout {
+    /// Chain name
+    pub chain: String,
+    /// Strategy the chain was paid with
+    pub strategy: Strategy,
+    /// One report per planned transaction
+    pub batches: Vec<BatchTransferReport>,
+}
+
+/// Outcome of a payout
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutReport {
+    /// Per-chain outcomes, in plan order
+    pub chains: Vec<ChainPayout>,
+}
+
+impl PayoutReport {
+    /// Returns the payments that failed
+    pub fn failed(&self) -> impl Iterator<Item = &TransferResult> {
+        self.chains
+            .iter()
+            .flat_map(|c| c.batches.iter())
+            .flat_map(BatchTransferReport::failed)
+    }
+
+    /// Returns true if every payment was submitted
+    pub fn is_complete(&self) -> bool {
+        self.failed().next().is_none()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use walletd_traits::{TxHash, WalletError, WalletResult};
+
+    /// Charges 100 per transaction plus 10 per recipient
+    #[derive(Default)]
+    struct MockExecutor {
+        native: bool,
+        sent: Mutex<Vec<usize>>,
+        fail_second: bool,
+    }
+
+    #[async_trait]
+    impl PayoutExecutor for MockExecutor {
+        fn native_batch(&self) -> bool {
+            self.native
+        }
+
+        async fn estimate(&self, batch: &Batch) -> WalletResult<Amount> {
+            let n = batch.recipients.len() as u128;
+            let fee = match batch.strategy {
+                Strategy::Individual => 110 * n,
+                _ => 100 + 10 * n,
+            };
+            Ok(Amount::from_smallest_unit(fee, 9))
+        }
+
+        async fn execute(&self, batch: &Batch) -> WalletResult<BatchTransferReport> {
+            let mut sent = self.sent.lock().unwrap();
+            sent.push(batch.recipients.len());
+            if self.fail_second && sent.len() == 2 {
+                return Err(WalletError::TransactionFailed("blockhash expired".into()));
+            }
+            let hash = TxHash::new(format!("tx{}", sent.len()));
+            Ok(BatchTransferReport {
+                results: batch
+                    .recipients
+                    .iter()
+                    .map(|(to, amount)| TransferResult {
+                        to: to.clone(),
+                        amount: *amount,
+                        tx_hash: Some(hash.clone()),
+                        error: None,
+                    })
+                    .collect(),
+                batch_tx: Some(hash),
+            })
+        }
+    }
+
+    fn sol(value: u128) -> Amount {
+        Amount::from_smallest_unit(value, 9)
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        let solana = Arc::new(MockExecutor {
+            native: true,
+            ..Default::default()
+        });
+        let near = Arc::new(MockExecutor::default());
+        let mut payout = PayoutBuilder::new()
+            .chain(ChainConfig::new("solana", solana.clone()).max_recipients(4))
+            .chain(ChainConfig::new("near", near));
+        for i in 0..10 {
+            payout.pay("solana", format!("sol{i}"), sol(1_000)).unwrap();
+        }
+        payout.pay("near", "alice.near", sol(5)).unwrap();
+        payout.pay("near", "bob.near", sol(5)).unwrap();
+
+        assert!(matches!(
+            payout.pay("cosmos", "addr", sol(1)),
+            Err(PayoutError::UnknownChain(_))
+        ));
+        assert!(matches!(
+            payout.pay("solana", "addr", sol(0)),
+            Err(PayoutError::ZeroAmount(_))
+        ));
+        assert!(matches!(
+            payout.pay("solana", "addr", Amount::from_smallest_unit(1, 6)),
+            Err(PayoutError::MixedDecimals(_))
+        ));
+
+        let report = payout.dry_run().await.unwrap();
+        let cost = report.chain("solana").unwrap();
+        assert_eq!(cost.strategy, Strategy::MultiInstruction);
+        assert_eq!((cost.recipients, cost.transactions), (10, 3));
+        assert_eq!(cost.total, sol(10_000));
+        // 4 + 4 + 2 recipients
+        assert_eq!(cost.fee, sol(140 + 140 + 120));
+        assert_eq!(cost.individual_fee, sol(1_100));
+        assert_eq!(cost.savings(), sol(700));
+
+        let near = report.chain("near").unwrap();
+        assert_eq!(near.strategy, Strategy::Individual);
+        assert_eq!(near.transactions, 2);
+        assert_eq!(near.fee, near.individual_fee);
+        assert!(solana.sent.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_execute_continues_after_failure() {
+        let executor = Arc::new(MockExecutor {
+            native: true,
+            fail_second: true,
+            ..Default::default()
+        });
+        let mut payout = PayoutBuilder::new()
+            .chain(ChainConfig::new("solana", executor.clone()).max_recipients(2));
+        for i in 0..5 {
+            payout.pay("solana", format!("sol{i}"), sol(1)).unwrap();
+        }
+
+        let report = payout.execute().await;
+        assert_eq!(*executor.sent.lock().unwrap(), [2, 2, 1]);
+        assert!(!report.is_complete());
+        let failed: Vec<_> = report.failed().map(|r| r.to.as_str()).collect();
+        assert_eq!(failed, ["sol2", "sol3"]);
+        let batches = &report.chains[0].batches;
+        assert_eq!(batches[2].batch_tx, Some(TxHash::new("tx3")));
+    }
+}
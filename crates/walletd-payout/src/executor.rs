@@ -0,0 +1,268 @@
+//! Sending planned batches on one chain
+
+use crate::plan::Batch;
+use crate::strategy::Strategy;
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::{
+    Amount, BatchTransferReport, BatchTransferable, FeeEstimator, FeeSpeed, TransferResult,
+    WalletError, WalletResult,
+};
+
+/// Prices and sends the transactions of one chain
+///
+/// Wallets that implement [`BatchTransferable`] use [`WalletExecutor`].
+/// Implement this directly for chains whose transactions are built outside
+/// a wallet, such as a remote signing service.
+#[async_trait]
+pub trait PayoutExecutor: Send + Sync {
+    /// Whether batches can be sent as one transaction; if not, every
+    /// recipient is paid separately
+    fn native_batch(&self) -> bool;
+
+    /// Estimated total fee of sending `batch`, in the native asset
+    async fn estimate(&self, batch: &Batch) -> WalletResult<Amount>;
+
+    /// Sends `batch`, one transaction unless its strategy is `Individual`
+    async fn execute(&self, batch: &Batch) -> WalletResult<BatchTransferReport>;
+}
+
+/// A [`PayoutExecutor`] over a [`BatchTransferable`] wallet
+///
+/// Individual transfers are priced with the wallet's `estimate_fee`.
+/// Batched transactions are priced with the strategy's
+/// [`CostModel`](crate::strategy::CostModel) at the rate from the fee
+/// source set with [`fees`](WalletExecutor::fees).
+pub struct WalletExecutor<W> {
+    wallet: W,
+    fees: Option<(Arc<dyn FeeEstimator>, FeeSpeed)>,
+}
+
+impl<W: BatchTransferable> WalletExecutor<W> {
+    /// Wraps `wallet`
+    pub fn new(wallet: W) -> Self {
+        Self { wallet, fees: None }
+    }
+
+    /// Prices batched transactions at `speed` from `estimator`
+    pub fn fees(mut self, estimator: Arc<dyn FeeEstimator>, speed: FeeSpeed) -> Self {
+        self.fees = Some((estimator, speed));
+        self
+    }
+
+    /// The wrapped wallet
+    pub fn wallet(&self) -> &W {
+        &self.wallet
+    }
+}
+
+#[async_trait]
+impl<W: BatchTransferable> PayoutExecutor for WalletExecutor<W> {
+    fn native_batch(&self) -> bool {
+        self.wallet.supports_native_batch()
+    }
+
+    async fn estimate(&self, batch: &Batch) -> WalletResult<Amount> {
+        if batch.strategy == Strategy::Individual {
+            let mut total: Option<Amount> = None;
+            for (to, amount) in &batch.recipients {
+                let fee = self.wallet.estimate_fee(to, *amount).await?;
+                total = match total {
+                    None => Some(fee),
+                    Some(sum) => Some(sum.checked_add(fee).ok_or_else(|| {
+                        WalletError::Other("fee estimates do not add up".to_string())
+                    })?),
+                };
+            }
+            return Ok(total.unwrap_or(Amount::zero(self.wallet.decimals())));
+        }
+
+        let (estimator, speed) = self.fees.as_ref().ok_or_else(|| {
+            WalletError::NotSupported("pricing batched transactions needs a fee source".into())
+        })?;
+        let options = estimator.fee_options().await?;
+        let option = options
+            .get(*speed)
+            .ok_or_else(|| WalletError::Other(format!("no {:?} fee option", speed)))?;
+        let fee = batch
+            .strategy
+            .fee(&option.rate, batch.recipients.len())
+            .ok_or_else(|| {
+                WalletError::NotSupported(format!(
+                    "{:?} fee rate for a {:?} transaction",
+                    option.rate, batch.strategy
+                ))
+            })?;
+        // Fees are paid in the native asset, whatever the batch pays out
+        Ok(Amount::from_smallest_unit(fee, self.wallet.decimals()))
+    }
+
+    async fn execute(&self, batch: &Batch) -> WalletResult<BatchTransferReport> {
+        if batch.strategy.is_batched() {
+            return self.wallet.transfer_batch(&batch.recipients).await;
+        }
+        let mut report = BatchTransferReport::default();
+        for (to, amount) in &batch.recipients {
+            let outcome = self.wallet.transfer(to, *amount).await;
+            report.results.push(TransferResult {
+                to: to.clone(),
+                amount: *amount,
+                error: outcome.as_ref().err().map(|e| e.to_string()),
+                tx_hash: outcome.ok(),
+            });
+        }
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+    use walletd_traits::{
+        FeeOption, FeeOptions, FeeRate, Network, TransactionBuilder, Transferable, TxHash, Wallet,
+    };
+
+    /// An EVM wallet paying out a 6-decimal token, with 18-decimal gas
+    struct MockWallet {
+        network: Network,
+        batches: Mutex<Vec<usize>>,
+    }
+
+    #[async_trait]
+    impl Wallet for MockWallet {
+        fn address(&self) -> String {
+            "0xpayer".to_string()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl Transferable for MockWallet {
+        type TxParams = walletd_traits::EvmTxParams;
+
+        async fn transfer_with(&self, _tx: TransactionBuilder) -> WalletResult<TxHash> {
+            Ok(TxHash::new("0xsingle"))
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Ok(Amount::from_smallest_unit(21_000, 18))
+        }
+    }
+
+    #[async_trait]
+    impl BatchTransferable for MockWallet {
+        fn supports_native_batch(&self) -> bool {
+            true
+        }
+
+        async fn transfer_batch(
+            &self,
+            transfers: &[(String, Amount)],
+        ) -> WalletResult<BatchTransferReport> {
+            self.batches.lock().unwrap().push(transfers.len());
+            Ok(BatchTransferReport::single_tx(
+                transfers,
+                Ok(TxHash::new("0xbatch")),
+            ))
+        }
+    }
+
+    struct FixedGasPrice;
+
+    #[async_trait]
+    impl FeeEstimator for FixedGasPrice {
+        fn chain(&self) -> &str {
+            "ethereum"
+        }
+
+        async fn fee_options(&self) -> WalletResult<FeeOptions> {
+            Ok(FeeOptions {
+                chain: "ethereum".to_string(),
+                options: vec![FeeOption {
+                    speed: FeeSpeed::Standard,
+                    rate: FeeRate::GasPrice(1),
+                    estimated_seconds: None,
+                }],
+                updated_at: 0,
+            })
+        }
+    }
+
+    fn executor() -> WalletExecutor<MockWallet> {
+        let wallet = MockWallet {
+            network: Network::mainnet("ethereum"),
+            batches: Mutex::new(Vec::new()),
+        };
+        WalletExecutor::new(wallet).fees(Arc::new(FixedGasPrice), FeeSpeed::Standard)
+    }
+
+    fn usdc(units: u128) -> Amount {
+        Amount::from_smallest_unit(units, 6)
+    }
+
+    #[tokio::test]
+    async fn test_fees_are_in_the_native_asset() {
+        let executor = executor();
+        let batch = Batch {
+            strategy: Strategy::Disperse,
+            recipients: vec![("0xa".to_string(), usdc(5)), ("0xb".to_string(), usdc(7))],
+        };
+        // 26,000 + 2 * 12,000 gas at 1 wei
+        let fee = executor.estimate(&batch).await.unwrap();
+        assert_eq!(fee, Amount::from_smallest_unit(50_000, 18));
+
+        let single = Batch {
+            strategy: Strategy::Individual,
+            recipients: batch.recipients.clone(),
+        };
+        assert_eq!(
+            executor.estimate(&single).await.unwrap(),
+            Amount::from_smallest_unit(42_000, 18)
+        );
+        let empty = Batch {
+            strategy: Strategy::Individual,
+            recipients: Vec::new(),
+        };
+        assert_eq!(executor.estimate(&empty).await.unwrap(), Amount::zero(18));
+    }
+
+    #[tokio::test]
+    async fn test_batches_go_through_the_wallet() {
+        let executor = executor();
+        assert!(executor.native_batch());
+        let batch = Batch {
+            strategy: Strategy::Disperse,
+            recipients: vec![("0xa".to_string(), usdc(5)), ("0xb".to_string(), usdc(7))],
+        };
+        let report = executor.execute(&batch).await.unwrap();
+        assert_eq!(report.batch_tx, Some(TxHash::new("0xbatch")));
+        assert_eq!(*executor.wallet().batches.lock().unwrap(), [2]);
+
+        let single = Batch {
+            strategy: Strategy::Individual,
+            recipients: batch.recipients,
+        };
+        let report = executor.execute(&single).await.unwrap();
+        assert_eq!(report.batch_tx, None);
+        assert!(report
+            .results
+            .iter()
+            .all(|r| r.tx_hash == Some(TxHash::new("0xsingle"))));
+    }
+}
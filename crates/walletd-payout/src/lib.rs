@@ -0,0 +1,98 @@
+//! # WalletD Payout
+//!
+//! Pays many recipients across chains in as few transactions as each chain
+//! allows. Payments are grouped by chain and sent with that chain's
+//! [`Strategy`]:
+//!
+//! - Bitcoin and other UTXO chains: one transaction with an output per
+//!   recipient
+//! - Solana: one transaction with a transfer instruction per recipient
+//! - Cosmos SDK chains: one transaction with a `MsgSend` per recipient
+//! - EVM chains: one call to the Disperse contract
+//! - anything else: a transfer per recipient
+//!
+//! The batched transactions are built by the chain wallets' native
+//! [`BatchTransferable`](walletd_traits::BatchTransferable) implementations,
+//! such as `ConnectedBitcoinWallet`, `ConnectedSolanaWallet`, `CosmosWallet`
+//! and `ConnectedEthereumWallet`; wrap one in a [`WalletExecutor`]. A chain
+//! whose executor cannot batch is paid a transfer per recipient.
+//!
+//! Batches are split at each chain's transaction size limits. A dry run
+//! prices the plan against paying every recipient separately without
+//! sending anything.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_payout::{ChainConfig, PayoutBuilder, WalletExecutor};
+//!
+//! let mut payout = PayoutBuilder::new()
+//!     .chain(ChainConfig::new("bitcoin", Arc::new(WalletExecutor::new(btc).fees(mempool, FeeSpeed::Slow))))
+//!     .chain(ChainConfig::new("solana", Arc::new(WalletExecutor::new(sol).fees(priority, FeeSpeed::Standard))));
+//! for (address, amount) in bitcoin_payees {
+//!     payout.pay("bitcoin", address, amount)?;
+//! }
+//! payout.pay("solana", "9WzDX...", Amount::from_smallest_unit(50_000_000, 9))?;
+//!
+//! for cost in payout.dry_run().await?.chains {
+//!     println!("{}: {} txs, fee {:?}, saves {:?}", cost.chain, cost.transactions, cost.fee, cost.savings());
+//! }
+//! let report = payout.execute().await;
+//! assert!(report.is_complete());
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod builder;
+pub mod executor;
+pub mod plan;
+pub mod strategy;
+
+pub use builder::{ChainConfig, ChainPayout, PayoutBuilder, PayoutReport};
+pub use executor::{PayoutExecutor, WalletExecutor};
+pub use plan::{Batch, ChainCost, ChainPlan, CostReport, Payout, PayoutPlan};
+pub use strategy::{CostModel, Strategy, SOLANA_SIGNATURE_FEE};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Payout errors
+#[derive(Error, Debug)]
+pub enum PayoutError {
+    /// No executor is registered for the chain
+    #[error("No payout executor for chain {0}")]
+    UnknownChain(String),
+
+    /// A payment of zero
+    #[error("Zero amount for {0}")]
+    ZeroAmount(String),
+
+    /// Payments on one chain use different decimals
+    #[error("Mixed decimals in payments on {0}")]
+    MixedDecimals(String),
+
+    /// Amounts or fees do not fit in a `u128`
+    #[error("Overflow adding up {0}")]
+    Overflow(String),
+
+    /// A wallet or fee source failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for payouts
+pub type Result<T> = std::result::Result<T, PayoutError>;
+
+impl From<PayoutError> for WalletdError {
+    fn from(e: PayoutError) -> Self {
+        match e {
+            PayoutError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            PayoutError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+            e => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
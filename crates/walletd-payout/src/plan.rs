@@ -0,0 +1,233 @@
+//! Grouping payouts into transactions, and what they cost
+
+use crate::strategy::Strategy;
+use serde::{Deserialize, Serialize};
+use walletd_traits::Amount;
+
+/// One payment in a payout
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Payout {
+    /// Chain name, e.g. `ethereum`
+    pub chain: String,
+    /// Recipient address
+    pub to: String,
+    /// Amount in the chain's native asset
+    pub amount: Amount,
+}
+
+/// Payments sent in one transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Batch {
+    /// How the transaction pays its recipients
+    pub strategy: Strategy,
+    /// Recipients and amounts, in request order
+    pub recipients: Vec<(String, Amount)>,
+}
+
+impl Batch {
+    /// Sum of the amounts, or `None` on overflow
+    pub fn total(&self) -> Option<Amount> {
+        let (_, first) = self.recipients.first()?;
+        self.recipients[1..]
+            .iter()
+            .try_fold(*first, |sum, (_, amount)| sum.checked_add(*amount))
+    }
+}
+
+/// The transactions paying one chain's recipients
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainPlan {
+    /// Chain name
+    pub chain: String,
+    /// Strategy the chain is paid with
+    pub strategy: Strategy,
+    /// One entry per transaction
+    pub batches: Vec<Batch>,
+}
+
+impl ChainPlan {
+    /// Number of recipients
+    pub fn recipients(&self) -> usize {
+        self.batches.iter().map(|b| b.recipients.len()).sum()
+    }
+
+    /// Number of transactions
+    pub fn transactions(&self) -> usize {
+        self.batches.len()
+    }
+}
+
+/// Transactions for a whole payout, one [`ChainPlan`] per chain in order of
+/// first appearance
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PayoutPlan {
+    /// Per-chain plans
+    pub chains: Vec<ChainPlan>,
+}
+
+impl PayoutPlan {
+    /// Splits `payouts` into transactions of at most `limit(chain)`
+    /// recipients, sent with `strategy(chain)`
+    ///
+    /// A transaction left with a single recipient is sent as a plain
+    /// transfer, which is cheaper than a batch of one.
+    pub fn new(
+        payouts: &[Payout],
+        strategy: impl Fn(&str) -> Strategy,
+        limit: impl Fn(&str) -> usize,
+    ) -> Self {
+        let mut grouped: Vec<(String, Vec<(String, Amount)>)> = Vec::new();
+        for payout in payouts {
+            let recipient = (payout.to.clone(), payout.amount);
+            match grouped.iter_mut().find(|(chain, _)| *chain == payout.chain) {
+                Some((_, recipients)) => recipients.push(recipient),
+                None => grouped.push((payout.chain.clone(), vec![recipient])),
+            }
+        }
+
+        let chains = grouped
+            .into_iter()
+            .map(|(chain, recipients)| {
+                let strategy = strategy(&chain);
+                let size = if strategy.is_batched() {
+                    limit(&chain).max(1)
+                } else {
+                    1
+                };
+                let batches = recipients
+                    .chunks(size)
+                    .map(|chunk| Batch {
+                        strategy: if chunk.len() == 1 {
+                            Strategy::Individual
+                        } else {
+                            strategy
+                        },
+                        recipients: chunk.to_vec(),
+                    })
+                    .collect();
+                ChainPlan {
+                    chain,
+                    strategy,
+                    batches,
+                }
+            })
+            .collect();
+        Self { chains }
+    }
+
+    /// Total number of transactions
+    pub fn transactions(&self) -> usize {
+        self.chains.iter().map(ChainPlan::transactions).sum()
+    }
+}
+
+/// Estimated cost of paying one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChainCost {
+    /// Chain name
+    pub chain: String,
+    /// Strategy the chain is paid with
+    pub strategy: Strategy,
+    /// Number of recipients
+    pub recipients: usize,
+    /// Number of transactions
+    pub transactions: usize,
+    /// Amount paid out
+    pub total: Amount,
+    /// Estimated fees of the plan
+    pub fee: Amount,
+    /// Estimated fees of paying every recipient separately
+    pub individual_fee: Amount,
+}
+
+impl ChainCost {
+    /// Fees saved by batching
+    pub fn savings(&self) -> Amount {
+        self.individual_fee
+            .checked_sub(self.fee)
+            .unwrap_or(Amount::zero(self.fee.decimals))
+    }
+}
+
+/// Dry-run estimate of a payout, nothing is sent
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostReport {
+    /// Per-chain costs, in plan order
+    pub chains: Vec<ChainCost>,
+}
+
+impl CostReport {
+    /// Cost for `chain`
+    pub fn chain(&self, chain: &str) -> Option<&ChainCost> {
+        self.chains.iter().find(|c| c.chain == chain)
+    }
+
+    /// Total number of transactions
+    pub fn transactions(&self) -> usize {
+        self.chains.iter().map(|c| c.transactions).sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payout(chain: &str, to: &str, value: u128) -> Payout {
+        Payout {
+            chain: chain.to_string(),
+            to: to.to_string(),
+            amount: Amount::from_smallest_unit(value, 8),
+        }
+    }
+
+    #[test]
+    fn test_plan_groups_and_splits() {
+        let mut payouts: Vec<Payout> = (0..5)
+            .map(|i| payout("solana", &format!("s{i}"), 1))
+            .collect();
+        payouts.insert(1, payout("bitcoin", "b0", 10));
+        payouts.push(payout("bitcoin", "b1", 20));
+        payouts.push(payout("near", "n0", 3));
+
+        let plan = PayoutPlan::new(&payouts, Strategy::for_chain, |_| 2);
+        let chains: Vec<_> = plan.chains.iter().map(|c| c.chain.as_str()).collect();
+        assert_eq!(chains, ["solana", "bitcoin", "near"]);
+
+        let solana = &plan.chains[0];
+        assert_eq!(solana.recipients(), 5);
+        assert_eq!(solana.transactions(), 3);
+        assert_eq!(solana.batches[0].strategy, Strategy::MultiInstruction);
+        assert_eq!(solana.batches[0].recipients[1].0, "s1");
+        assert_eq!(solana.batches[2].strategy, Strategy::Individual);
+
+        let bitcoin = &plan.chains[1];
+        assert_eq!(bitcoin.transactions(), 1);
+        assert_eq!(
+            bitcoin.batches[0].total(),
+            Some(Amount::from_smallest_unit(30, 8))
+        );
+
+        assert_eq!(plan.chains[2].strategy, Strategy::Individual);
+        assert_eq!(plan.transactions(), 5);
+    }
+
+    #[test]
+    fn test_savings() {
+        let cost = ChainCost {
+            chain: "bitcoin".to_string(),
+            strategy: Strategy::MultiOutput,
+            recipients: 10,
+            transactions: 1,
+            total: Amount::from_smallest_unit(1_000, 8),
+            fee: Amount::from_smallest_unit(420, 8),
+            individual_fee: Amount::from_smallest_unit(1_410, 8),
+        };
+        assert_eq!(cost.savings(), Amount::from_smallest_unit(990, 8));
+
+        let worse = ChainCost {
+            individual_fee: Amount::from_smallest_unit(100, 8),
+            ..cost
+        };
+        assert_eq!(worse.savings(), Amount::zero(8));
+    }
+}
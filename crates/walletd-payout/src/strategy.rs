@@ -0,0 +1,178 @@
+//! How each chain pays many recipients
+
+use serde::{Deserialize, Serialize};
+use walletd_traits::FeeRate;
+
+/// Base fee of a Solana transaction, lamports per signature
+pub const SOLANA_SIGNATURE_FEE: u128 = 5_000;
+
+/// How a chain sends a payout
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strategy {
+    /// One UTXO transaction with an output per recipient
+    MultiOutput,
+    /// One Solana transaction with a transfer instruction per recipient
+    MultiInstruction,
+    /// One Cosmos SDK transaction with a `MsgSend` per recipient
+    MultiMsg,
+    /// One call to the Disperse contract on an EVM chain
+    Disperse,
+    /// A separate transaction per recipient
+    Individual,
+}
+
+impl Strategy {
+    /// The most efficient strategy for `chain`, or `Individual` for chains
+    /// without a known way to batch
+    pub fn for_chain(chain: &str) -> Self {
+        match chain.to_ascii_lowercase().as_str() {
+            "bitcoin" | "bitcoin-testnet" | "litecoin" | "ltc" | "dogecoin" | "doge"
+            | "bitcoin-cash" | "bch" => Strategy::MultiOutput,
+            "solana" | "solana-devnet" => Strategy::MultiInstruction,
+            "cosmos" | "cosmoshub" | "osmosis" => Strategy::MultiMsg,
+            "ethereum" | "sepolia" | "polygon" | "base" | "arbitrum" | "optimism" | "bsc"
+            | "avalanche" | "fuji" => Strategy::Disperse,
+            _ => Strategy::Individual,
+        }
+    }
+
+    /// Whether one transaction pays several recipients
+    pub fn is_batched(&self) -> bool {
+        *self != Strategy::Individual
+    }
+
+    /// Default number of recipients per transaction
+    ///
+    /// Solana is bound by the 1232-byte packet, EVM chains by keeping the
+    /// call well under block gas limits, the others by standardness and
+    /// node defaults.
+    pub fn max_recipients(&self) -> usize {
+        match self {
+            Strategy::MultiOutput => 2_500,
+            Strategy::MultiInstruction => 20,
+            Strategy::MultiMsg => 100,
+            Strategy::Disperse => 200,
+            Strategy::Individual => 1,
+        }
+    }
+
+    /// Typical size of a transaction under this strategy
+    pub fn cost_model(&self) -> Option<CostModel> {
+        let model = match self {
+            // vbytes: one P2WPKH input and change, P2WPKH recipients
+            Strategy::MultiOutput => CostModel::new(110, 31, 141),
+            // Compute units: compute budget instructions plus transfers
+            Strategy::MultiInstruction => CostModel::new(300, 150, 450),
+            // Gas for bank sends
+            Strategy::MultiMsg => CostModel::new(65_000, 20_000, 85_000),
+            // Gas for `disperseEther` to existing accounts; a recipient
+            // with no prior state costs 25,000 more
+            Strategy::Disperse => CostModel::new(26_000, 12_000, 21_000),
+            Strategy::Individual => return None,
+        };
+        Some(model)
+    }
+
+    /// Estimated fee, in the chain's smallest unit, of one transaction
+    /// paying `recipients` at `rate`
+    ///
+    /// Returns `None` for `Individual` and for rates of another chain
+    /// family. EIP-1559 rates are priced at `max_fee_per_gas`, so the
+    /// estimate is an upper bound.
+    pub fn fee(&self, rate: &FeeRate, recipients: usize) -> Option<u128> {
+        let units = self.cost_model()?.units(recipients) as u128;
+        match (self, rate) {
+            (Strategy::MultiOutput, FeeRate::SatPerVbyte(rate)) => {
+                Some((units as f64 * rate).ceil() as u128)
+            }
+            (Strategy::MultiInstruction, FeeRate::MicroLamportsPerCu(price)) => {
+                Some(SOLANA_SIGNATURE_FEE + (units * *price as u128).div_ceil(1_000_000))
+            }
+            (Strategy::MultiMsg, FeeRate::CosmosGasPrice { amount, .. }) => {
+                Some((units as f64 * amount).ceil() as u128)
+            }
+            (
+                Strategy::Disperse,
+                FeeRate::Eip1559 {
+                    max_fee_per_gas, ..
+                },
+            ) => units.checked_mul(*max_fee_per_gas),
+            (Strategy::Disperse, FeeRate::GasPrice(price)) => units.checked_mul(*price),
+            _ => None,
+        }
+    }
+}
+
+/// Size of a transaction in the chain's fee unit: vbytes, gas or compute
+/// units
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CostModel {
+    /// Fixed part of a batched transaction
+    pub tx_base: u64,
+    /// Added per recipient of a batched transaction
+    pub per_recipient: u64,
+    /// A plain single-recipient transfer
+    pub single: u64,
+}
+
+impl CostModel {
+    /// A model from its three sizes
+    pub fn new(tx_base: u64, per_recipient: u64, single: u64) -> Self {
+        Self {
+            tx_base,
+            per_recipient,
+            single,
+        }
+    }
+
+    /// Size of a transaction paying `recipients`
+    pub fn units(&self, recipients: usize) -> u64 {
+        if recipients <= 1 {
+            return self.single;
+        }
+        self.tx_base + self.per_recipient * recipients as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strategy_for_chain() {
+        assert_eq!(Strategy::for_chain("Bitcoin"), Strategy::MultiOutput);
+        assert_eq!(Strategy::for_chain("solana"), Strategy::MultiInstruction);
+        assert_eq!(Strategy::for_chain("osmosis"), Strategy::MultiMsg);
+        assert_eq!(Strategy::for_chain("base"), Strategy::Disperse);
+        assert_eq!(Strategy::for_chain("near"), Strategy::Individual);
+        assert!(!Strategy::Individual.is_batched());
+        assert_eq!(Strategy::Individual.max_recipients(), 1);
+    }
+
+    #[test]
+    fn test_fee_estimates() {
+        let model = Strategy::MultiOutput.cost_model().unwrap();
+        assert_eq!(model.units(1), 141);
+        assert_eq!(model.units(10), 420);
+        assert_eq!(
+            Strategy::MultiOutput.fee(&FeeRate::SatPerVbyte(2.5), 10),
+            Some(1_050)
+        );
+
+        // 300 + 20 * 150 CU at 1 lamport each, plus the signature
+        let sol = Strategy::MultiInstruction.fee(&FeeRate::MicroLamportsPerCu(1_000_000), 20);
+        assert_eq!(sol, Some(5_000 + 3_300));
+        let cheap = Strategy::MultiInstruction.fee(&FeeRate::MicroLamportsPerCu(1_000), 20);
+        assert_eq!(cheap, Some(5_000 + 4));
+
+        let gwei = 1_000_000_000;
+        let rate = FeeRate::Eip1559 {
+            max_fee_per_gas: 30 * gwei,
+            max_priority_fee_per_gas: gwei,
+        };
+        assert_eq!(Strategy::Disperse.fee(&rate, 1), Some(21_000 * 30 * gwei));
+        assert_eq!(Strategy::Disperse.fee(&FeeRate::SatPerVbyte(1.0), 5), None);
+        assert_eq!(Strategy::Individual.fee(&FeeRate::GasPrice(1), 5), None);
+    }
+}
@@ -0,0 +1,421 @@
+//! Policy evaluation and spending state
+
+use crate::policy::{normalize, LimitWindow, Policy, SigningRule, Violation};
+use crate::{PolicyError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Mutex, MutexGuard};
+use std::time::{SystemTime, UNIX_EPOCH};
+use walletd_traits::evm::Erc20Call;
+use walletd_traits::{Amount, ThresholdStatus};
+
+/// A transfer waiting for a policy decision
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TransferIntent {
+    /// Recipient address
+    pub to: String,
+    /// Amount to send
+    pub amount: Amount,
+    /// Token contract or mint; `None` for the native currency
+    pub token: Option<String>,
+}
+
+impl TransferIntent {
+    /// A native currency transfer
+    pub fn native(to: impl Into<String>, amount: Amount) -> Self {
+        Self {
+            to: to.into(),
+            amount,
+            token: None,
+        }
+    }
+
+    /// A token transfer
+    pub fn token(token: impl Into<String>, to: impl Into<String>, amount: Amount) -> Self {
+        Self {
+            to: to.into(),
+            amount,
+            token: Some(token.into()),
+        }
+    }
+
+    /// The token transfer an ERC-20 `transfer` or `approve` call to `token`
+    /// makes, or `None` for any other call data
+    ///
+    /// An approval is treated as a transfer of the allowance to the spender.
+    /// Amounts above `u128::MAX` saturate, so they still exceed any limit.
+    pub fn erc20_call(token: impl Into<String>, data: &[u8]) -> Option<Self> {
+        let call = Erc20Call::decode(data)?;
+        Some(Self::token(
+            token,
+            call.recipient,
+            Amount::from_smallest_unit(call.amount, 0),
+        ))
+    }
+
+    /// Stable ID used to collect approvals for this transfer
+    pub fn id(&self) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(normalize(&self.to));
+        hasher.update([0]);
+        hasher.update(self.amount.value.to_be_bytes());
+        hasher.update([0]);
+        if let Some(token) = &self.token {
+            hasher.update(normalize(token));
+        }
+        hex::encode(hasher.finalize())
+    }
+
+    fn token_key(&self) -> Option<String> {
+        self.token.as_deref().map(normalize)
+    }
+}
+
+/// Kind of raw signing call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SigningKind {
+    /// A 32-byte digest
+    Hash,
+    /// An arbitrary message
+    Message,
+}
+
+/// A transfer the engine has allowed
+///
+/// The amount is counted against the spending limits and any approvals are
+/// used up. Pass it to [`PolicyEngine::release`] if the transfer is not
+/// sent after all.
+#[derive(Debug)]
+#[must_use = "release the authorization if the transfer is not sent"]
+pub struct Authorization {
+    spend: u64,
+    intent: String,
+    approvals: Option<BTreeSet<String>>,
+}
+
+struct Spend {
+    id: u64,
+    at: u64,
+    token: Option<String>,
+    amount: u128,
+}
+
+#[derive(Default)]
+struct State {
+    spends: Vec<Spend>,
+    approvals: BTreeMap<String, BTreeSet<String>>,
+    next_spend: u64,
+}
+
+/// Evaluates a [`Policy`] and tracks spending and approvals
+pub struct PolicyEngine {
+    policy: Policy,
+    state: Mutex<State>,
+}
+
+impl PolicyEngine {
+    /// Creates an engine with no spending history
+    pub fn new(policy: Policy) -> Self {
+        Self {
+            policy,
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Returns the policy
+    pub fn policy(&self) -> &Policy {
+        &self.policy
+    }
+
+    /// Checks a transfer without recording it
+    pub fn check(&self, intent: &TransferIntent) -> Result<()> {
+        self.check_at(intent, now())
+    }
+
+    /// Checks a transfer at `now` (Unix seconds) without recording it
+    pub fn check_at(&self, intent: &TransferIntent, now: u64) -> Result<()> {
+        self.evaluate(&self.lock(), intent, now)
+    }
+
+    /// Checks a transfer and reserves its amount against the limits
+    pub fn authorize(&self, intent: &TransferIntent) -> Result<Authorization> {
+        self.authorize_at(intent, now())
+    }
+
+    /// Checks a transfer at `now` (Unix seconds) and reserves its amount
+    pub fn authorize_at(&self, intent: &TransferIntent, now: u64) -> Result<Authorization> {
+        let mut state = self.lock();
+        self.evaluate(&state, intent, now)?;
+
+        let horizon = self
+            .policy
+            .limits
+            .iter()
+            .filter_map(|limit| match limit.window {
+                LimitWindow::Rolling { seconds } => Some(seconds),
+                LimitWindow::PerTransaction => None,
+            })
+            .max()
+            .unwrap_or(0);
+        state
+            .spends
+            .retain(|spend| spend.at.saturating_add(horizon) > now);
+
+        let id = intent.id();
+        let approvals = if self.needs_approval(intent) {
+            state.approvals.remove(&id)
+        } else {
+            None
+        };
+        let spend = state.next_spend;
+        state.next_spend += 1;
+        state.spends.push(Spend {
+            id: spend,
+            at: now,
+            token: intent.token_key(),
+            amount: intent.amount.value,
+        });
+        Ok(Authorization {
+            spend,
+            intent: id,
+            approvals,
+        })
+    }
+
+    /// Returns an unused authorization's amount and approvals
+    pub fn release(&self, authorization: Authorization) {
+        let mut state = self.lock();
+        state.spends.retain(|spend| spend.id != authorization.spend);
+        if let Some(approvals) = authorization.approvals {
+            state
+                .approvals
+                .entry(authorization.intent)
+                .or_default()
+                .extend(approvals);
+        }
+    }
+
+    /// Records an approval for the intent with ID `id`
+    pub fn approve(&self, id: &str, approver: &str) -> Result<ThresholdStatus> {
+        let rule = self
+            .policy
+            .approvals
+            .as_ref()
+            .filter(|rule| rule.approvers.contains(approver))
+            .ok_or_else(|| PolicyError::UnknownApprover(approver.to_string()))?;
+        let mut state = self.lock();
+        let approvals = state.approvals.entry(id.to_string()).or_default();
+        approvals.insert(approver.to_string());
+        Ok(ThresholdStatus {
+            collected: approvals.len() as u32,
+            required: rule.threshold,
+        })
+    }
+
+    /// Returns the approval progress of the intent with ID `id`
+    pub fn approval_status(&self, id: &str) -> Option<ThresholdStatus> {
+        let rule = self.policy.approvals.as_ref()?;
+        Some(ThresholdStatus {
+            collected: self.lock().approvals.get(id).map_or(0, |a| a.len() as u32),
+            required: rule.threshold,
+        })
+    }
+
+    /// Checks a raw signing call
+    pub fn check_signing(&self, kind: SigningKind) -> Result<()> {
+        self.check_signing_at(kind, now())
+    }
+
+    /// Checks a raw signing call at `now` (Unix seconds)
+    pub fn check_signing_at(&self, kind: SigningKind, now: u64) -> Result<()> {
+        let denied = match (self.policy.signing, kind) {
+            (SigningRule::Allow, _) | (SigningRule::MessagesOnly, SigningKind::Message) => None,
+            (SigningRule::MessagesOnly, SigningKind::Hash)
+            | (SigningRule::Deny, SigningKind::Hash) => Some("hash"),
+            (SigningRule::Deny, SigningKind::Message) => Some("message"),
+        };
+        if let Some(kind) = denied {
+            return Err(PolicyError::Denied(Violation::SigningNotAllowed(
+                kind.into(),
+            )));
+        }
+        self.policy.check_time(now).map_err(PolicyError::Denied)
+    }
+
+    fn evaluate(&self, state: &State, intent: &TransferIntent, now: u64) -> Result<()> {
+        if intent.to.trim().is_empty() {
+            return Err(PolicyError::Denied(Violation::MissingDestination));
+        }
+        self.policy.check_time(now).map_err(PolicyError::Denied)?;
+        self.policy
+            .check_destination(&intent.to)
+            .map_err(PolicyError::Denied)?;
+        self.policy
+            .check_token(intent.token.as_deref())
+            .map_err(PolicyError::Denied)?;
+
+        let token = intent.token_key();
+        let amount = intent.amount.value;
+        for limit in self
+            .policy
+            .limits
+            .iter()
+            .filter(|l| l.covers(token.as_deref()))
+        {
+            match limit.window {
+                LimitWindow::PerTransaction if amount > limit.max => {
+                    return Err(PolicyError::Denied(Violation::PerTransactionLimit {
+                        limit: limit.max,
+                        amount,
+                    }));
+                }
+                LimitWindow::PerTransaction => {}
+                LimitWindow::Rolling { seconds } => {
+                    let spent: u128 = state
+                        .spends
+                        .iter()
+                        .filter(|s| s.token == token && s.at.saturating_add(seconds) > now)
+                        .map(|s| s.amount)
+                        .sum();
+                    if spent.saturating_add(amount) > limit.max {
+                        return Err(PolicyError::Denied(Violation::RollingLimit {
+                            limit: limit.max,
+                            spent,
+                            amount,
+                            seconds,
+                        }));
+                    }
+                }
+            }
+        }
+
+        if let Some(rule) = self
+            .policy
+            .approvals
+            .as_ref()
+            .filter(|_| self.needs_approval(intent))
+        {
+            let id = intent.id();
+            let collected = state.approvals.get(&id).map_or(0, |a| a.len() as u32);
+            let status = ThresholdStatus {
+                collected,
+                required: rule.threshold,
+            };
+            if !status.is_met() {
+                return Err(PolicyError::ApprovalRequired { id, status });
+            }
+        }
+        Ok(())
+    }
+
+    fn needs_approval(&self, intent: &TransferIntent) -> bool {
+        self.policy
+            .approvals
+            .as_ref()
+            .is_some_and(|rule| intent.token.is_some() || intent.amount.value > rule.above)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ApprovalRule, SpendingLimit};
+    use std::time::Duration;
+
+    fn eth(value: u128) -> Amount {
+        Amount::from_smallest_unit(value, 18)
+    }
+
+    #[test]
+    fn test_spending_limits() {
+        let engine = PolicyEngine::new(
+            Policy::new()
+                .limit(SpendingLimit::per_transaction(50))
+                .limit(SpendingLimit::rolling(100, Duration::from_secs(3600)))
+                .limit(SpendingLimit::per_transaction(5).for_token("0xToken")),
+        );
+        let t = 1_000_000;
+
+        assert!(matches!(
+            engine.check_at(&TransferIntent::native("a", eth(51)), t),
+            Err(PolicyError::Denied(Violation::PerTransactionLimit { .. }))
+        ));
+        let first = engine
+            .authorize_at(&TransferIntent::native("a", eth(50)), t)
+            .unwrap();
+        let _second = engine
+            .authorize_at(&TransferIntent::native("a", eth(40)), t + 10)
+            .unwrap();
+        assert!(matches!(
+            engine.check_at(&TransferIntent::native("a", eth(20)), t + 20),
+            Err(PolicyError::Denied(Violation::RollingLimit {
+                spent: 90,
+                ..
+            }))
+        ));
+
+        // Releasing a reservation and letting the window roll both free room
+        engine.release(first);
+        assert!(engine
+            .check_at(&TransferIntent::native("a", eth(20)), t + 20)
+            .is_ok());
+        assert!(engine
+            .check_at(&TransferIntent::native("a", eth(50)), t + 3610)
+            .is_ok());
+
+        // Token limits are separate from native ones
+        assert!(engine
+            .check_at(&TransferIntent::token("0xtoken", "a", eth(6)), t)
+            .is_err());
+        assert!(engine
+            .check_at(&TransferIntent::token("0xOther", "a", eth(60)), t)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_approvals_and_signing() {
+        let engine = PolicyEngine::new(
+            Policy::new()
+                .approvals(ApprovalRule::new(["alice", "bob", "carol"], 2).above(10))
+                .signing(SigningRule::MessagesOnly),
+        );
+        assert!(engine
+            .authorize_at(&TransferIntent::native("a", eth(10)), 0)
+            .is_ok());
+
+        let intent = TransferIntent::native("a", eth(11));
+        let Err(PolicyError::ApprovalRequired { id, status }) = engine.check_at(&intent, 0) else {
+            panic!("approval should be required");
+        };
+        assert_eq!(status.remaining(), 2);
+        assert!(matches!(
+            engine.approve(&id, "mallory"),
+            Err(PolicyError::UnknownApprover(_))
+        ));
+        engine.approve(&id, "alice").unwrap();
+        assert!(engine.approve(&id, "bob").unwrap().is_met());
+
+        // Approvals are spent by the transfer and come back on release
+        let auth = engine.authorize_at(&intent, 0).unwrap();
+        assert!(engine.check_at(&intent, 0).is_err());
+        engine.release(auth);
+        assert!(engine.check_at(&intent, 0).is_ok());
+
+        assert!(engine.check_signing_at(SigningKind::Message, 0).is_ok());
+        assert!(matches!(
+            engine.check_signing_at(SigningKind::Hash, 0),
+            Err(PolicyError::Denied(Violation::SigningNotAllowed(_)))
+        ));
+    }
+}
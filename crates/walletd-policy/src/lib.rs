@@ -0,0 +1,104 @@
+//! # WalletD Policy
+//!
+//! Organizational controls enforced before a signature is produced. A
+//! [`Policy`] combines:
+//!
+//! - per-transaction and rolling-window spending limits, for the native
+//!   currency or a specific token
+//! - destination allow and deny lists
+//! - a token allow list
+//! - UTC time windows outside which nothing is sent or signed
+//! - M-of-N approvals for transfers above a threshold
+//! - a rule for raw hash and message signing
+//!
+//! A [`PolicyEngine`] evaluates the policy and keeps the spending and
+//! approval state. [`PolicyWallet`] wraps any wallet so every
+//! [`Transferable::transfer`](walletd_traits::Transferable::transfer),
+//! token transfer and message signature goes through the engine first;
+//! [`PolicySigner`] does the same for a bare [`Signer`](walletd_traits::Signer).
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use std::time::Duration;
+//! use walletd_policy::{ApprovalRule, Policy, PolicyWallet, SpendingLimit, TimeWindow};
+//!
+//! let policy = Policy::new()
+//!     .limit(SpendingLimit::per_transaction(5 * ETH))
+//!     .limit(SpendingLimit::rolling(20 * ETH, Duration::from_secs(86_400)))
+//!     .deny_destination("0x000000000000000000000000000000000000dEaD")
+//!     .time_window(TimeWindow::daily(9 * 60, 17 * 60).weekdays())
+//!     .approvals(ApprovalRule::new(["alice", "bob", "carol"], 2).above(ETH));
+//!
+//! let wallet = PolicyWallet::new(eth_wallet, policy);
+//! match wallet.engine().check(&TransferIntent::native(to, amount)) {
+//!     Err(PolicyError::ApprovalRequired { id, .. }) => {
+//!         wallet.engine().approve(&id, "alice")?;
+//!         wallet.engine().approve(&id, "bob")?;
+//!     }
+//!     other => other?,
+//! }
+//! wallet.transfer(to, amount).await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod engine;
+pub mod policy;
+pub mod signer;
+pub mod wallet;
+
+pub use engine::{Authorization, PolicyEngine, SigningKind, TransferIntent};
+pub use policy::{
+    ApprovalRule, LimitWindow, Policy, SigningRule, SpendingLimit, TimeWindow, Violation, Weekday,
+};
+pub use signer::PolicySigner;
+pub use wallet::PolicyWallet;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::{ThresholdStatus, WalletError};
+
+/// Policy errors
+#[derive(Error, Debug)]
+pub enum PolicyError {
+    /// The operation breaks a policy rule
+    #[error("Denied by policy: {0}")]
+    Denied(Violation),
+
+    /// The transfer needs more approvals
+    #[error("Approval required for {id}: {} of {} approvals", .status.collected, .status.required)]
+    ApprovalRequired {
+        /// Intent ID to approve
+        id: String,
+        /// Approvals collected so far
+        status: ThresholdStatus,
+    },
+
+    /// The approver is not in the approver set
+    #[error("Unknown approver: {0}")]
+    UnknownApprover(String),
+
+    /// The wrapped wallet or signer failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for policy operations
+pub type Result<T> = std::result::Result<T, PolicyError>;
+
+impl From<PolicyError> for WalletError {
+    fn from(e: PolicyError) -> Self {
+        match e {
+            PolicyError::Wallet(e) => e,
+            e => WalletError::TransactionFailed(e.to_string()),
+        }
+    }
+}
+
+impl From<PolicyError> for WalletdError {
+    fn from(e: PolicyError) -> Self {
+        WalletdError::TransactionFailed(e.to_string())
+    }
+}
@@ -0,0 +1,432 @@
+//! Policy rules
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use std::time::Duration;
+use thiserror::Error;
+
+/// The set of rules a wallet must satisfy
+///
+/// A default policy allows everything; each builder call adds a restriction.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Policy {
+    /// Spending limits
+    pub limits: Vec<SpendingLimit>,
+    /// If set, only these destinations may receive funds
+    pub allowed_destinations: Option<BTreeSet<String>>,
+    /// Destinations that may never receive funds
+    pub denied_destinations: BTreeSet<String>,
+    /// If set, only these tokens may be transferred
+    pub allowed_tokens: Option<BTreeSet<String>>,
+    /// Windows in which transfers and signatures are allowed; empty means
+    /// any time
+    pub time_windows: Vec<TimeWindow>,
+    /// Approvals needed for large transfers
+    pub approvals: Option<ApprovalRule>,
+    /// What raw signing calls may do
+    pub signing: SigningRule,
+    /// Whether transfers may carry data other than a recognized token
+    /// transfer or approval
+    #[serde(default)]
+    pub allow_contract_calls: bool,
+}
+
+impl Policy {
+    /// Creates a policy that allows everything
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a spending limit
+    pub fn limit(mut self, limit: SpendingLimit) -> Self {
+        self.limits.push(limit);
+        self
+    }
+
+    /// Adds a destination to the allow list
+    ///
+    /// Once any destination is allowed, all others are refused.
+    pub fn allow_destination(mut self, address: impl AsRef<str>) -> Self {
+        self.allowed_destinations
+            .get_or_insert_with(BTreeSet::new)
+            .insert(normalize(address.as_ref()));
+        self
+    }
+
+    /// Adds a destination to the deny list
+    pub fn deny_destination(mut self, address: impl AsRef<str>) -> Self {
+        self.denied_destinations.insert(normalize(address.as_ref()));
+        self
+    }
+
+    /// Adds a token contract or mint to the allow list
+    ///
+    /// Once any token is allowed, transfers of other tokens are refused.
+    /// Native transfers are not affected.
+    pub fn allow_token(mut self, token: impl AsRef<str>) -> Self {
+        self.allowed_tokens
+            .get_or_insert_with(BTreeSet::new)
+            .insert(normalize(token.as_ref()));
+        self
+    }
+
+    /// Adds a time window
+    pub fn time_window(mut self, window: TimeWindow) -> Self {
+        self.time_windows.push(window);
+        self
+    }
+
+    /// Sets the approval rule
+    pub fn approvals(mut self, rule: ApprovalRule) -> Self {
+        self.approvals = Some(rule);
+        self
+    }
+
+    /// Sets the signing rule
+    pub fn signing(mut self, rule: SigningRule) -> Self {
+        self.signing = rule;
+        self
+    }
+
+    /// Allows transfers carrying arbitrary data
+    ///
+    /// By default a transfer with data is refused unless the data is an
+    /// ERC-20 `transfer` or `approve`, which is checked as a token transfer.
+    /// Other contract calls, and memos on chains that carry them as data,
+    /// are then checked only as a native transfer to the destination.
+    pub fn allow_contract_calls(mut self) -> Self {
+        self.allow_contract_calls = true;
+        self
+    }
+
+    /// Checks the destination lists
+    pub(crate) fn check_destination(&self, to: &str) -> Result<(), Violation> {
+        let to = normalize(to);
+        if self.denied_destinations.contains(&to) {
+            return Err(Violation::DestinationDenied(to));
+        }
+        match &self.allowed_destinations {
+            Some(allowed) if !allowed.contains(&to) => Err(Violation::DestinationNotAllowed(to)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the token allow list
+    pub(crate) fn check_token(&self, token: Option<&str>) -> Result<(), Violation> {
+        match (token.map(normalize), &self.allowed_tokens) {
+            (Some(token), Some(allowed)) if !allowed.contains(&token) => {
+                Err(Violation::TokenNotAllowed(token))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Checks the time windows at `now` (Unix seconds)
+    pub(crate) fn check_time(&self, now: u64) -> Result<(), Violation> {
+        if self.time_windows.is_empty() || self.time_windows.iter().any(|w| w.contains(now)) {
+            Ok(())
+        } else {
+            Err(Violation::OutsideTimeWindow)
+        }
+    }
+}
+
+/// A cap on the amount sent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SpendingLimit {
+    /// Token the limit applies to; `None` for the native currency
+    pub token: Option<String>,
+    /// Maximum amount in the smallest unit
+    pub max: u128,
+    /// Period the maximum covers
+    pub window: LimitWindow,
+}
+
+impl SpendingLimit {
+    /// Caps each transfer of the native currency
+    pub fn per_transaction(max: u128) -> Self {
+        Self {
+            token: None,
+            max,
+            window: LimitWindow::PerTransaction,
+        }
+    }
+
+    /// Caps the native currency sent within any rolling `window`
+    pub fn rolling(max: u128, window: Duration) -> Self {
+        Self {
+            token: None,
+            max,
+            window: LimitWindow::Rolling {
+                seconds: window.as_secs(),
+            },
+        }
+    }
+
+    /// Applies the limit to a token instead of the native currency
+    pub fn for_token(mut self, token: impl AsRef<str>) -> Self {
+        self.token = Some(normalize(token.as_ref()));
+        self
+    }
+
+    /// Returns true if the limit covers `token`
+    pub(crate) fn covers(&self, token: Option<&str>) -> bool {
+        self.token.as_deref() == token
+    }
+}
+
+/// Period a [`SpendingLimit`] covers
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LimitWindow {
+    /// Each transfer on its own
+    PerTransaction,
+    /// All transfers within the last `seconds`
+    Rolling {
+        /// Window length
+        seconds: u64,
+    },
+}
+
+/// Day of the week, in UTC
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum Weekday {
+    /// Monday
+    Monday,
+    /// Tuesday
+    Tuesday,
+    /// Wednesday
+    Wednesday,
+    /// Thursday
+    Thursday,
+    /// Friday
+    Friday,
+    /// Saturday
+    Saturday,
+    /// Sunday
+    Sunday,
+}
+
+impl Weekday {
+    const ALL: [Weekday; 7] = [
+        Weekday::Monday,
+        Weekday::Tuesday,
+        Weekday::Wednesday,
+        Weekday::Thursday,
+        Weekday::Friday,
+        Weekday::Saturday,
+        Weekday::Sunday,
+    ];
+
+    /// The weekday of a Unix timestamp
+    pub fn from_timestamp(secs: u64) -> Self {
+        // 1970-01-01 was a Thursday
+        Self::ALL[((secs / 86_400 + 3) % 7) as usize]
+    }
+}
+
+/// Hours of the day in which operations are allowed, in UTC
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeWindow {
+    /// Days the window applies to
+    pub days: BTreeSet<Weekday>,
+    /// Start, in minutes after midnight
+    pub start_minute: u32,
+    /// End (exclusive), in minutes after midnight; a window that ends
+    /// before it starts runs past midnight
+    pub end_minute: u32,
+}
+
+impl TimeWindow {
+    /// A window open every day from `start_minute` to `end_minute`
+    pub fn daily(start_minute: u32, end_minute: u32) -> Self {
+        Self {
+            days: Weekday::ALL.into_iter().collect(),
+            start_minute,
+            end_minute,
+        }
+    }
+
+    /// Restricts the window to the given days
+    pub fn on(mut self, days: impl IntoIterator<Item = Weekday>) -> Self {
+        self.days = days.into_iter().collect();
+        self
+    }
+
+    /// Restricts the window to Monday through Friday
+    pub fn weekdays(self) -> Self {
+        self.on(Weekday::ALL[..5].iter().copied())
+    }
+
+    /// Returns true if `now` (Unix seconds) falls in the window
+    pub fn contains(&self, now: u64) -> bool {
+        if !self.days.contains(&Weekday::from_timestamp(now)) {
+            return false;
+        }
+        let minute = ((now % 86_400) / 60) as u32;
+        if self.start_minute <= self.end_minute {
+            (self.start_minute..self.end_minute).contains(&minute)
+        } else {
+            minute >= self.start_minute || minute < self.end_minute
+        }
+    }
+}
+
+/// M-of-N approvals for transfers above a threshold
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApprovalRule {
+    /// Who may approve
+    pub approvers: BTreeSet<String>,
+    /// Approvals needed
+    pub threshold: u32,
+    /// Native amounts at or below this need no approval; token transfers
+    /// always need approval
+    pub above: u128,
+}
+
+impl ApprovalRule {
+    /// Requires `threshold` of `approvers` for every transfer
+    pub fn new<S: Into<String>>(approvers: impl IntoIterator<Item = S>, threshold: u32) -> Self {
+        Self {
+            approvers: approvers.into_iter().map(Into::into).collect(),
+            threshold,
+            above: 0,
+        }
+    }
+
+    /// Only requires approval for native amounts above `amount`
+    pub fn above(mut self, amount: u128) -> Self {
+        self.above = amount;
+        self
+    }
+}
+
+/// What [`Signer`](walletd_traits::Signer) calls may sign
+///
+/// Raw signatures carry no amount or destination, so the only controls are
+/// whether they are allowed at all and the time windows.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SigningRule {
+    /// Hashes and messages may be signed
+    #[default]
+    Allow,
+    /// Only messages may be signed; blind hash signing is refused
+    MessagesOnly,
+    /// Nothing may be signed outside a policy-checked transfer
+    Deny,
+}
+
+/// A broken policy rule
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum Violation {
+    /// A single transfer exceeds a per-transaction limit
+    #[error("amount {amount} exceeds the per-transaction limit of {limit}")]
+    PerTransactionLimit {
+        /// Limit
+        limit: u128,
+        /// Requested amount
+        amount: u128,
+    },
+
+    /// A transfer would exceed a rolling limit
+    #[error("amount {amount} on top of {spent} spent exceeds the limit of {limit} per {seconds}s")]
+    RollingLimit {
+        /// Limit
+        limit: u128,
+        /// Amount already spent in the window
+        spent: u128,
+        /// Requested amount
+        amount: u128,
+        /// Window length
+        seconds: u64,
+    },
+
+    /// The destination is on the deny list
+    #[error("destination {0} is denied")]
+    DestinationDenied(String),
+
+    /// The destination is not on the allow list
+    #[error("destination {0} is not allowed")]
+    DestinationNotAllowed(String),
+
+    /// The token is not on the allow list
+    #[error("token {0} is not allowed")]
+    TokenNotAllowed(String),
+
+    /// The current time is outside every time window
+    #[error("outside the allowed time windows")]
+    OutsideTimeWindow,
+
+    /// The signing rule refuses the call
+    #[error("{0} signing is not allowed")]
+    SigningNotAllowed(String),
+
+    /// The transfer has no recipient
+    #[error("transfer has no recipient")]
+    MissingDestination,
+
+    /// The transfer carries data the policy cannot check
+    #[error("contract call to {0} is not allowed")]
+    ContractCallNotAllowed(String),
+}
+
+/// Canonical form of an address for comparison
+///
+/// Hex addresses are case-insensitive (EIP-55 only adds a checksum); other
+/// encodings such as base58 are compared exactly.
+pub(crate) fn normalize(address: &str) -> String {
+    let address = address.trim();
+    if address.starts_with("0x") || address.starts_with("0X") {
+        address.to_ascii_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_windows() {
+        // 2024-01-01 was a Monday
+        let monday = 1_704_067_200;
+        assert_eq!(Weekday::from_timestamp(monday), Weekday::Monday);
+        assert_eq!(
+            Weekday::from_timestamp(monday + 6 * 86_400),
+            Weekday::Sunday
+        );
+
+        let office = TimeWindow::daily(9 * 60, 17 * 60).weekdays();
+        assert!(office.contains(monday + 9 * 3600));
+        assert!(!office.contains(monday + 17 * 3600));
+        assert!(!office.contains(monday + 5 * 86_400 + 10 * 3600));
+
+        let night = TimeWindow::daily(22 * 60, 6 * 60);
+        assert!(night.contains(monday + 23 * 3600));
+        assert!(night.contains(monday + 3600));
+        assert!(!night.contains(monday + 12 * 3600));
+    }
+
+    #[test]
+    fn test_destination_and_token_lists() {
+        let policy = Policy::new()
+            .allow_destination("0xAbC0000000000000000000000000000000000001")
+            .deny_destination("0xabc0000000000000000000000000000000000002")
+            .allow_token("USDC-mint");
+
+        assert!(policy
+            .check_destination("0xabc0000000000000000000000000000000000001")
+            .is_ok());
+        assert!(matches!(
+            policy.check_destination("0xABC0000000000000000000000000000000000002"),
+            Err(Violation::DestinationDenied(_))
+        ));
+        assert!(matches!(
+            policy.check_destination("0xabc0000000000000000000000000000000000003"),
+            Err(Violation::DestinationNotAllowed(_))
+        ));
+        assert!(policy.check_token(None).is_ok());
+        assert!(policy.check_token(Some("USDC-mint")).is_ok());
+        assert!(policy.check_token(Some("usdc-mint")).is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//! Policy-enforcing [`Signer`] wrapper
+
+use crate::engine::{PolicyEngine, SigningKind};
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::{SignatureScheme, Signer, WalletError, WalletResult};
+
+/// A signer whose signatures are checked against a policy's signing rule
+/// and time windows
+///
+/// The secret key is never exposed, since a caller holding it could sign
+/// outside the policy.
+pub struct PolicySigner {
+    inner: Box<dyn Signer>,
+    engine: Arc<PolicyEngine>,
+}
+
+impl PolicySigner {
+    /// Wraps a signer
+    pub fn new(inner: Box<dyn Signer>, engine: Arc<PolicyEngine>) -> Self {
+        Self { inner, engine }
+    }
+
+    /// Returns the policy engine
+    pub fn engine(&self) -> &Arc<PolicyEngine> {
+        &self.engine
+    }
+
+    fn check(&self, kind: SigningKind) -> WalletResult<()> {
+        self.engine
+            .check_signing(kind)
+            .map_err(|e| WalletError::KeyError(e.to_string()))
+    }
+}
+
+#[async_trait]
+impl Signer for PolicySigner {
+    fn scheme(&self) -> SignatureScheme {
+        self.inner.scheme()
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.inner.public_key()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        self.check(SigningKind::Hash)?;
+        self.inner.sign_hash(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        self.check(SigningKind::Message)?;
+        self.inner.sign_message(message).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Policy, SigningRule};
+    use walletd_traits::Ed25519Signer;
+
+    fn signer(rule: SigningRule) -> PolicySigner {
+        PolicySigner::new(
+            Box::new(Ed25519Signer::from_bytes(&[5u8; 32])),
+            Arc::new(PolicyEngine::new(Policy::new().signing(rule))),
+        )
+    }
+
+    #[tokio::test]
+    async fn test_signing_rules() {
+        let allow = signer(SigningRule::Allow);
+        assert_eq!(allow.sign_hash(&[1u8; 32]).await.unwrap().len(), 64);
+        assert!(allow.secret_key().is_none());
+
+        let messages = signer(SigningRule::MessagesOnly);
+        assert!(messages.sign_message(b"hello").await.is_ok());
+        assert!(matches!(
+            messages.sign_hash(&[1u8; 32]).await,
+            Err(WalletError::KeyError(_))
+        ));
+
+        let deny = signer(SigningRule::Deny);
+        assert!(deny.sign_message(b"hello").await.is_err());
+    }
+}
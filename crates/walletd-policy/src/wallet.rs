@@ -0,0 +1,318 @@
+//! Policy-enforcing wallet wrapper
+
+use crate::engine::{PolicyEngine, SigningKind, TransferIntent};
+use crate::policy::{Policy, Violation};
+use crate::PolicyError;
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::{
+    Amount, BatchTransferable, Network, Signable, TokenWallet, TransactionBuilder, Transferable,
+    TxHash, Wallet, WalletResult,
+};
+
+/// A wallet whose transfers and signatures are checked against a policy
+///
+/// Each transfer is authorized before it reaches the inner wallet; if the
+/// inner wallet fails, the reserved amount and approvals are released.
+/// Batches go through [`transfer`](Transferable::transfer) one payment at a
+/// time, so native batching in the inner wallet is not used. Transfers with
+/// data are refused unless it is an ERC-20 `transfer` or `approve` or the
+/// policy [allows contract calls](Policy::allow_contract_calls).
+pub struct PolicyWallet<W> {
+    inner: W,
+    engine: Arc<PolicyEngine>,
+}
+
+impl<W> PolicyWallet<W> {
+    /// Wraps a wallet with a new engine for `policy`
+    pub fn new(inner: W, policy: Policy) -> Self {
+        Self::with_engine(inner, Arc::new(PolicyEngine::new(policy)))
+    }
+
+    /// Wraps a wallet with an engine shared with other wallets or signers
+    pub fn with_engine(inner: W, engine: Arc<PolicyEngine>) -> Self {
+        Self { inner, engine }
+    }
+
+    /// Returns the policy engine, e.g. to record approvals
+    pub fn engine(&self) -> &Arc<PolicyEngine> {
+        &self.engine
+    }
+
+    /// Returns the wrapped wallet
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    async fn guarded<F>(&self, intent: TransferIntent, send: F) -> WalletResult<TxHash>
+    where
+        F: std::future::Future<Output = WalletResult<TxHash>> + Send,
+    {
+        let authorization = self.engine.authorize(&intent)?;
+        let result = send.await;
+        if result.is_err() {
+            self.engine.release(authorization);
+        }
+        result
+    }
+}
+
+#[async_trait]
+impl<W: Wallet> Wallet for PolicyWallet<W> {
+    fn address(&self) -> String {
+        self.inner.address()
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        self.inner.balance().await
+    }
+
+    fn network(&self) -> &Network {
+        self.inner.network()
+    }
+
+    fn currency_symbol(&self) -> &str {
+        self.inner.currency_symbol()
+    }
+
+    fn decimals(&self) -> u8 {
+        self.inner.decimals()
+    }
+}
+
+#[async_trait]
+impl<W: Transferable> Transferable for PolicyWallet<W> {
+    type TxParams = W::TxParams;
+
+    async fn transfer_with(&self, tx: TransactionBuilder<Self::TxParams>) -> WalletResult<TxHash> {
+        let to = tx
+            .to
+            .clone()
+            .ok_or(PolicyError::Denied(Violation::MissingDestination))?;
+        let amount = tx
+            .amount
+            .unwrap_or_else(|| Amount::from_smallest_unit(0, self.inner.decimals()));
+        let intent = match tx.data.as_deref().filter(|data| !data.is_empty()) {
+            None => TransferIntent::native(to, amount),
+            Some(data) => match TransferIntent::erc20_call(&to, data) {
+                Some(intent) if amount.value == 0 => intent,
+                _ if self.engine.policy().allow_contract_calls => {
+                    TransferIntent::native(to, amount)
+                }
+                _ => return Err(PolicyError::Denied(Violation::ContractCallNotAllowed(to)).into()),
+            },
+        };
+        self.guarded(intent, self.inner.transfer_with(tx)).await
+    }
+
+    async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount> {
+        self.inner.estimate_fee(to, amount).await
+    }
+}
+
+#[async_trait]
+impl<W: BatchTransferable> BatchTransferable for PolicyWallet<W> {}
+
+#[async_trait]
+impl<W: TokenWallet> TokenWallet for PolicyWallet<W> {
+    type TokenInfo = W::TokenInfo;
+
+    async fn token_balance(&self, token_address: &str) -> WalletResult<Amount> {
+        self.inner.token_balance(token_address).await
+    }
+
+    async fn transfer_token(
+        &self,
+        token_address: &str,
+        to: &str,
+        amount: Amount,
+    ) -> WalletResult<TxHash> {
+        self.guarded(
+            TransferIntent::token(token_address, to, amount),
+            self.inner.transfer_token(token_address, to, amount),
+        )
+        .await
+    }
+
+    async fn token_info(&self, token_address: &str) -> WalletResult<Self::TokenInfo> {
+        self.inner.token_info(token_address).await
+    }
+}
+
+#[async_trait]
+impl<W: Signable> Signable for PolicyWallet<W> {
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        self.engine.check_signing(SigningKind::Message)?;
+        self.inner.sign_message(message).await
+    }
+
+    async fn verify_message(
+        &self,
+        message: &[u8],
+        signature: &[u8],
+        address: &str,
+    ) -> WalletResult<bool> {
+        self.inner.verify_message(message, signature, address).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SpendingLimit;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use walletd_traits::WalletError;
+
+    struct MockWallet {
+        network: Network,
+        sent: AtomicU32,
+        fail: bool,
+    }
+
+    impl MockWallet {
+        fn new(fail: bool) -> Self {
+            Self {
+                network: Network::mainnet("ethereum"),
+                sent: AtomicU32::new(0),
+                fail,
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Wallet for MockWallet {
+        fn address(&self) -> String {
+            "0xsender".into()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::from_smallest_unit(1_000, 18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl Transferable for MockWallet {
+        type TxParams = ();
+
+        async fn transfer_with(&self, _tx: TransactionBuilder<()>) -> WalletResult<TxHash> {
+            if self.fail {
+                return Err(WalletError::NetworkError("down".into()));
+            }
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok(TxHash::new("0xhash"))
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Ok(Amount::from_smallest_unit(1, 18))
+        }
+    }
+
+    impl BatchTransferable for MockWallet {}
+
+    #[tokio::test]
+    async fn test_transfers_are_checked() {
+        let wallet = PolicyWallet::new(
+            MockWallet::new(false),
+            Policy::new()
+                .deny_destination("0xbad")
+                .limit(SpendingLimit::per_transaction(100)),
+        );
+        let amount = |v| Amount::from_smallest_unit(v, 18);
+
+        assert!(wallet.transfer("0xgood", amount(100)).await.is_ok());
+        assert!(matches!(
+            wallet.transfer("0xBAD", amount(1)).await,
+            Err(WalletError::TransactionFailed(_))
+        ));
+        assert!(wallet.transfer("0xgood", amount(101)).await.is_err());
+
+        let report = wallet
+            .transfer_batch(&[("0xgood".into(), amount(5)), ("0xbad".into(), amount(5))])
+            .await
+            .unwrap();
+        assert_eq!(report.succeeded().count(), 1);
+        assert_eq!(wallet.inner.sent.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_failed_transfer_releases_limit() {
+        let policy = Policy::new().limit(SpendingLimit::rolling(
+            100,
+            std::time::Duration::from_secs(60),
+        ));
+        let failing = PolicyWallet::new(MockWallet::new(true), policy);
+        let amount = Amount::from_smallest_unit(100, 18);
+
+        assert!(matches!(
+            failing.transfer("0xgood", amount).await,
+            Err(WalletError::NetworkError(_))
+        ));
+        // The failed attempt did not use up the window
+        let working = PolicyWallet::with_engine(MockWallet::new(false), failing.engine().clone());
+        assert!(working.transfer("0xgood", amount).await.is_ok());
+        assert!(working.transfer("0xgood", amount).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_calldata_is_checked() {
+        const TOKEN: &str = "0x1111111111111111111111111111111111111111";
+        const ATTACKER: &str = "0x2222222222222222222222222222222222222222";
+        let call = |selector: [u8; 4], value: u8| {
+            let mut data = selector.to_vec();
+            data.extend([0; 12]);
+            data.extend([0x22; 20]);
+            data.extend([0; 31]);
+            data.push(value);
+            TransactionBuilder::with_params(()).to(TOKEN).data(data)
+        };
+        let transfer = [0xa9, 0x05, 0x9c, 0xbb];
+        let approve = [0x09, 0x5e, 0xa7, 0xb3];
+
+        let wallet = PolicyWallet::new(
+            MockWallet::new(false),
+            Policy::new()
+                .allow_destination(TOKEN)
+                .limit(SpendingLimit::per_transaction(100).for_token(TOKEN)),
+        );
+        // ERC-20 calls are checked against the real recipient or spender
+        assert!(wallet.transfer_with(call(transfer, 5)).await.is_err());
+        assert!(wallet.transfer_with(call(approve, 5)).await.is_err());
+        // Other call data is refused outright
+        let unknown = TransactionBuilder::with_params(())
+            .to(TOKEN)
+            .data(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(matches!(
+            wallet.transfer_with(unknown).await,
+            Err(WalletError::TransactionFailed(e)) if e.contains("contract call")
+        ));
+
+        let wallet = PolicyWallet::new(
+            MockWallet::new(false),
+            Policy::new()
+                .allow_destination(ATTACKER)
+                .limit(SpendingLimit::per_transaction(100).for_token(TOKEN)),
+        );
+        assert!(wallet.transfer_with(call(transfer, 100)).await.is_ok());
+        assert!(wallet.transfer_with(call(transfer, 101)).await.is_err());
+        assert_eq!(wallet.inner.sent.load(Ordering::SeqCst), 1);
+
+        let permissive =
+            PolicyWallet::new(MockWallet::new(false), Policy::new().allow_contract_calls());
+        let unknown = TransactionBuilder::with_params(())
+            .to(TOKEN)
+            .data(vec![0xde, 0xad, 0xbe, 0xef]);
+        assert!(permissive.transfer_with(unknown).await.is_ok());
+    }
+}
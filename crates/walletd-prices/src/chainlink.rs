@@ -0,0 +1,306 @@
+//! Chainlink on-chain price feeds
+//!
+//! Reads `latestRoundData()` from aggregator contracts through a
+//! [`ProviderPool`], so the pool's failover and caching apply. Prices older
+//! than the configured maximum age are rejected rather than silently used.
+
+use crate::oracle::PriceSource;
+use crate::{now, Currency, Price, PriceError, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use walletd_provider::ProviderPool;
+
+/// `decimals()`
+const DECIMALS: &str = "0x313ce567";
+/// `latestRoundData()`
+const LATEST_ROUND_DATA: &str = "0xfeaf968c";
+
+/// Longest heartbeat of the mainnet feeds (24 hours) plus an hour of slack
+const DEFAULT_MAX_AGE: Duration = Duration::from_secs(25 * 3600);
+
+/// Ethereum mainnet aggregators
+const MAINNET_FEEDS: &[(&str, Currency, &str)] = &[
+    (
+        "BTC",
+        Currency::Usd,
+        "0xF4030086522a5bEEa4988F8cA5B36dbC97BeE88c",
+    ),
+    (
+        "DAI",
+        Currency::Usd,
+        "0xAed0c38402a5d19df6E4c03F4E2DceD6e29c1ee9",
+    ),
+    (
+        "ETH",
+        Currency::Usd,
+        "0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419",
+    ),
+    (
+        "EUR",
+        Currency::Usd,
+        "0xb49f677943BC038e9857d61E7d053CaA2C1734C1",
+    ),
+    (
+        "LINK",
+        Currency::Usd,
+        "0x2c1d072e956AFFC0D435Cb7AC38EF18d24d9127c",
+    ),
+    (
+        "USDC",
+        Currency::Usd,
+        "0x8fFfFfd4AfB6115b954Bd326cbe7B4BA576818f6",
+    ),
+    (
+        "USDT",
+        Currency::Usd,
+        "0x3E7d1eAB13ad0104d2750B8863b489D65364e32D",
+    ),
+];
+
+/// Prices from Chainlink aggregator contracts
+///
+/// Without a direct `X/EUR` feed, EUR prices are derived from `X/USD` and
+/// the `EUR/USD` feed.
+#[derive(Debug)]
+pub struct Chainlink {
+    pool: Arc<ProviderPool>,
+    provider: String,
+    feeds: HashMap<(String, Currency), String>,
+    max_age: Duration,
+    decimals: Mutex<HashMap<String, u8>>,
+}
+
+impl Chainlink {
+    /// A source without feeds, reading through the EVM provider `provider`
+    pub fn new(pool: Arc<ProviderPool>, provider: &str) -> Self {
+        Self {
+            pool,
+            provider: provider.to_string(),
+            feeds: HashMap::new(),
+            max_age: DEFAULT_MAX_AGE,
+            decimals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// The Ethereum mainnet feeds for BTC, ETH, LINK, the main stablecoins
+    /// and EUR/USD
+    pub fn mainnet(pool: Arc<ProviderPool>, provider: &str) -> Self {
+        MAINNET_FEEDS.iter().fold(
+            Self::new(pool, provider),
+            |source, (symbol, currency, feed)| source.with_feed(symbol, *currency, feed),
+        )
+    }
+
+    /// Adds the aggregator quoting `symbol` in `currency`
+    pub fn with_feed(mut self, symbol: &str, currency: Currency, aggregator: &str) -> Self {
+        self.feeds.insert(
+            (symbol.to_ascii_uppercase(), currency),
+            aggregator.to_string(),
+        );
+        self
+    }
+
+    /// Rejects prices not updated within `max_age` (default 25 hours)
+    pub fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// Reads a feed, returning the price and its update time
+    async fn read(&self, symbol: &str, aggregator: &str) -> Result<(f64, u64)> {
+        let evm = self.pool.evm(&self.provider)?;
+        let cached = self
+            .decimals
+            .lock()
+            .expect("decimals lock")
+            .get(aggregator)
+            .copied();
+        let decimals = match cached {
+            Some(decimals) => decimals,
+            None => {
+                let words = words(&evm.call(aggregator, DECIMALS).await?)?;
+                let decimals = words
+                    .first()
+                    .and_then(|w| u8::try_from(*w).ok())
+                    .ok_or_else(|| PriceError::InvalidResponse("invalid decimals".to_string()))?;
+                self.decimals
+                    .lock()
+                    .expect("decimals lock")
+                    .insert(aggregator.to_string(), decimals);
+                decimals
+            }
+        };
+
+        // (roundId, answer, startedAt, updatedAt, answeredInRound)
+        let round = evm.call(aggregator, LATEST_ROUND_DATA).await?;
+        let words = words(&round)?;
+        let (Some(&answer), Some(&updated_at)) = (words.get(1), words.get(3)) else {
+            return Err(PriceError::InvalidResponse(format!(
+                "short latestRoundData from {}",
+                aggregator
+            )));
+        };
+        if answer == 0 {
+            return Err(PriceError::InvalidResponse(format!(
+                "non-positive answer from {}",
+                aggregator
+            )));
+        }
+        let updated_at = u64::try_from(updated_at)
+            .map_err(|_| PriceError::InvalidResponse("invalid updatedAt".to_string()))?;
+        let age = Duration::from_secs(now().saturating_sub(updated_at));
+        if age > self.max_age {
+            return Err(PriceError::Stale {
+                symbol: symbol.to_string(),
+                age,
+            });
+        }
+        Ok((answer as f64 / 10f64.powi(decimals as i32), updated_at))
+    }
+}
+
+#[async_trait]
+impl PriceSource for Chainlink {
+    fn name(&self) -> &str {
+        "chainlink"
+    }
+
+    async fn price(&self, symbol: &str, currency: Currency) -> Result<Price> {
+        let price = |value, updated_at| Price {
+            symbol: symbol.to_string(),
+            currency,
+            value,
+            source: self.name().to_string(),
+            updated_at,
+        };
+        if let Some(feed) = self.feeds.get(&(symbol.to_string(), currency)) {
+            let (value, updated_at) = self.read(symbol, feed).await?;
+            return Ok(price(value, updated_at));
+        }
+
+        // Cross through USD: X/EUR = (X/USD) / (EUR/USD)
+        let usd = self.feeds.get(&(symbol.to_string(), Currency::Usd));
+        let eur_usd = self.feeds.get(&("EUR".to_string(), Currency::Usd));
+        match (currency, usd, eur_usd) {
+            (Currency::Eur, Some(usd), Some(eur_usd)) => {
+                let (in_usd, updated) = self.read(symbol, usd).await?;
+                let (rate, rate_updated) = self.read("EUR", eur_usd).await?;
+                Ok(price(in_usd / rate, updated.min(rate_updated)))
+            }
+            _ => Err(PriceError::NotFound {
+                symbol: symbol.to_string(),
+                currency,
+            }),
+        }
+    }
+}
+
+/// Splits ABI return data into 32-byte words
+///
+/// Words are returned as their low 128 bits; a word with any of the high
+/// bits set (including a negative `int256`) is returned as 0.
+fn words(hex: &str) -> Result<Vec<u128>> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    if digits.is_empty() || !digits.len().is_multiple_of(64) || !digits.is_ascii() {
+        return Err(PriceError::InvalidResponse(format!(
+            "invalid ABI data: {}",
+            hex
+        )));
+    }
+    digits
+        .as_bytes()
+        .chunks(64)
+        .map(|word| {
+            let word = std::str::from_utf8(word).expect("ASCII checked above");
+            let (high, low) = word.split_at(32);
+            let low = u128::from_str_radix(low, 16)
+                .map_err(|_| PriceError::InvalidResponse(format!("invalid ABI data: {}", hex)))?;
+            Ok(if high.bytes().all(|b| b == b'0') {
+                low
+            } else {
+                0
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_provider::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const ETH_USD: &str = "0x5f4eC3Df9cbd43714FE2740f5E3616155c5b8419";
+    const EUR_USD: &str = "0xb49f677943BC038e9857d61E7d053CaA2C1734C1";
+
+    fn word(value: u128) -> String {
+        format!("{:064x}", value)
+    }
+
+    async fn mock_call(server: &MockServer, to: &str, data: &str, result: String) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({
+                "method": "eth_call",
+                "params": [{"to": to, "data": data}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    async fn mock_feed(server: &MockServer, feed: &str, answer: u128, updated_at: u64) {
+        mock_call(server, feed, DECIMALS, format!("0x{}", word(8))).await;
+        let round = [1, answer, 0, updated_at as u128, 1].map(word).concat();
+        mock_call(server, feed, LATEST_ROUND_DATA, format!("0x{}", round)).await;
+    }
+
+    async fn pool(server: &MockServer) -> Arc<ProviderPool> {
+        let pool = ProviderPool::new();
+        pool.add("ethereum", ProviderConfig::new(server.uri()))
+            .unwrap();
+        Arc::new(pool)
+    }
+
+    #[tokio::test]
+    async fn test_usd_and_derived_eur() {
+        let server = MockServer::start().await;
+        mock_feed(&server, ETH_USD, 330_000_000_000, now()).await;
+        mock_feed(&server, EUR_USD, 110_000_000, now() - 60).await;
+        let source = Chainlink::mainnet(pool(&server).await, "ethereum");
+
+        let usd = source.price("ETH", Currency::Usd).await.unwrap();
+        assert_eq!(usd.value, 3300.0);
+        let eur = source.price("ETH", Currency::Eur).await.unwrap();
+        assert!((eur.value - 3000.0).abs() < 1e-6);
+        assert_eq!(eur.updated_at, now() - 60);
+        assert!(matches!(
+            source.price("DOGE", Currency::Usd).await,
+            Err(PriceError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_stale_feed_rejected() {
+        let server = MockServer::start().await;
+        mock_feed(&server, ETH_USD, 330_000_000_000, now() - 7200).await;
+        let source = Chainlink::mainnet(pool(&server).await, "ethereum")
+            .with_max_age(Duration::from_secs(3600));
+        assert!(matches!(
+            source.price("ETH", Currency::Usd).await,
+            Err(PriceError::Stale { .. })
+        ));
+    }
+
+    #[test]
+    fn test_words() {
+        let data = format!("0x{}{}", word(7), "f".repeat(64));
+        assert_eq!(words(&data).unwrap(), [7, 0]);
+        assert!(words("0x").is_err());
+        assert!(words("0x1234").is_err());
+    }
+}
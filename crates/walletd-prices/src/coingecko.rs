@@ -0,0 +1,253 @@
+//! CoinGecko REST source
+//!
+//! Uses `/simple/price`, which returns many assets in one request. The free
+//! API allows a few calls per minute, so requests go through an
+//! [`AdaptiveRateLimiter`] that starts slow and backs off further whenever
+//! CoinGecko answers with HTTP 429.
+
+use crate::oracle::PriceSource;
+use crate::{now, Currency, Price, PriceError, Result};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+use walletd_resilience::{AdaptiveRateLimiter, AimdConfig, HttpRetryClassifier};
+
+/// Public API
+pub const PUBLIC_URL: &str = "https://api.coingecko.com/api/v3";
+/// Paid API
+pub const PRO_URL: &str = "https://pro-api.coingecko.com/api/v3";
+
+/// CoinGecko ids of common tickers
+const DEFAULT_IDS: &[(&str, &str)] = &[
+    ("ADA", "cardano"),
+    ("APT", "aptos"),
+    ("ARB", "arbitrum"),
+    ("ATOM", "cosmos"),
+    ("AVAX", "avalanche-2"),
+    ("BTC", "bitcoin"),
+    ("DAI", "dai"),
+    ("DOT", "polkadot"),
+    ("ETH", "ethereum"),
+    ("HBAR", "hedera-hashgraph"),
+    ("ICP", "internet-computer"),
+    ("LINK", "chainlink"),
+    ("MATIC", "matic-network"),
+    ("NEAR", "near"),
+    ("OP", "optimism"),
+    ("POL", "polygon-ecosystem-token"),
+    ("SOL", "solana"),
+    ("SUI", "sui"),
+    ("TON", "the-open-network"),
+    ("TRX", "tron"),
+    ("USDC", "usd-coin"),
+    ("USDT", "tether"),
+    ("WBTC", "wrapped-bitcoin"),
+    ("WETH", "weth"),
+    ("XMR", "monero"),
+];
+
+/// Prices from the CoinGecko API
+///
+/// Tickers are mapped to CoinGecko ids; common ones are built in and more
+/// can be added with [`with_id`](Self::with_id).
+#[derive(Debug)]
+pub struct CoinGecko {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<(&'static str, String)>,
+    ids: HashMap<String, String>,
+    limiter: AdaptiveRateLimiter,
+}
+
+impl Default for CoinGecko {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CoinGecko {
+    /// Public API without a key
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: PUBLIC_URL.to_string(),
+            api_key: None,
+            ids: DEFAULT_IDS
+                .iter()
+                .map(|(symbol, id)| (symbol.to_string(), id.to_string()))
+                .collect(),
+            limiter: AdaptiveRateLimiter::new(
+                AimdConfig::new("coingecko")
+                    .with_initial_rate(0.5)
+                    .with_bounds(0.1, 10.0)
+                    .with_burst(3),
+            ),
+        }
+    }
+
+    /// Paid API with a Pro key
+    pub fn pro(api_key: &str) -> Self {
+        let mut source = Self::new().with_base_url(PRO_URL);
+        source.api_key = Some(("x-cg-pro-api-key", api_key.to_string()));
+        source
+    }
+
+    /// Sends a Demo API key with each request
+    pub fn with_api_key(mut self, api_key: &str) -> Self {
+        self.api_key = Some(("x-cg-demo-api-key", api_key.to_string()));
+        self
+    }
+
+    /// Uses another endpoint (proxy, mock server)
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    /// Maps a ticker to a CoinGecko id, replacing any built-in mapping
+    pub fn with_id(mut self, symbol: &str, id: &str) -> Self {
+        self.ids.insert(symbol.to_ascii_uppercase(), id.to_string());
+        self
+    }
+
+    async fn fetch(&self, ids: &[&str], currency: Currency) -> Result<serde_json::Value> {
+        self.limiter.acquire().await;
+        let mut request = self
+            .client
+            .get(format!("{}/simple/price", self.base_url))
+            .query(&[
+                ("ids", ids.join(",").as_str()),
+                ("vs_currencies", currency.code()),
+                ("include_last_updated_at", "true"),
+            ])
+            .timeout(Duration::from_secs(15));
+        if let Some((header, key)) = &self.api_key {
+            request = request.header(*header, key);
+        }
+        let response = request.send().await?;
+
+        let status = response.status();
+        if HttpRetryClassifier::is_rate_limited(status.as_u16()) {
+            self.limiter.on_throttled();
+            let headers: Vec<(String, String)> = response
+                .headers()
+                .iter()
+                .filter_map(|(name, value)| {
+                    Some((name.to_string(), value.to_str().ok()?.to_string()))
+                })
+                .collect();
+            let retry_after = HttpRetryClassifier::retry_after_from_headers(
+                headers
+                    .iter()
+                    .map(|(name, value)| (name.as_str(), value.as_str())),
+            );
+            return Err(PriceError::RateLimited { retry_after });
+        }
+        if !status.is_success() {
+            return Err(PriceError::InvalidResponse(format!("HTTP {}", status)));
+        }
+        self.limiter.on_success();
+        Ok(response.json().await?)
+    }
+}
+
+#[async_trait]
+impl PriceSource for CoinGecko {
+    fn name(&self) -> &str {
+        "coingecko"
+    }
+
+    async fn price(&self, symbol: &str, currency: Currency) -> Result<Price> {
+        self.prices(&[symbol.to_string()], currency)
+            .await?
+            .pop()
+            .ok_or_else(|| PriceError::NotFound {
+                symbol: symbol.to_string(),
+                currency,
+            })
+    }
+
+    async fn prices(&self, symbols: &[String], currency: Currency) -> Result<Vec<Price>> {
+        let wanted: Vec<(&String, &str)> = symbols
+            .iter()
+            .filter_map(|symbol| Some((symbol, self.ids.get(symbol)?.as_str())))
+            .collect();
+        if wanted.is_empty() {
+            return Ok(Vec::new());
+        }
+        let ids: Vec<&str> = wanted.iter().map(|(_, id)| *id).collect();
+        let body = self.fetch(&ids, currency).await?;
+
+        Ok(wanted
+            .into_iter()
+            .filter_map(|(symbol, id)| {
+                let entry = &body[id];
+                Some(Price {
+                    symbol: symbol.clone(),
+                    currency,
+                    value: entry[currency.code()].as_f64()?,
+                    source: self.name().to_string(),
+                    updated_at: entry["last_updated_at"].as_u64().unwrap_or_else(now),
+                })
+            })
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[tokio::test]
+    async fn test_batch_prices() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/simple/price"))
+            .and(query_param("ids", "ethereum,bitcoin"))
+            .and(query_param("vs_currencies", "eur"))
+            .and(header("x-cg-demo-api-key", "demo"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ethereum": {"eur": 2750.5, "last_updated_at": 1_700_000_000},
+                "bitcoin": {"eur": 61000.0, "last_updated_at": 1_700_000_001}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let source = CoinGecko::new()
+            .with_base_url(&server.uri())
+            .with_api_key("demo");
+        let symbols = ["ETH", "BTC", "NOTACOIN"].map(str::to_string);
+        let prices = source.prices(&symbols, Currency::Eur).await.unwrap();
+        assert_eq!(prices.len(), 2);
+        assert_eq!(prices[0].value, 2750.5);
+        assert_eq!(prices[1].updated_at, 1_700_000_001);
+        assert!(matches!(
+            source.price("NOTACOIN", Currency::Eur).await,
+            Err(PriceError::NotFound { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limited() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "30"))
+            .mount(&server)
+            .await;
+
+        let source = CoinGecko::new()
+            .with_base_url(&server.uri())
+            .with_id("wif", "dogwifcoin");
+        let rate = source.limiter.current_rate();
+        match source.price("WIF", Currency::Usd).await {
+            Err(PriceError::RateLimited { retry_after }) => {
+                assert_eq!(retry_after, Some(Duration::from_secs(30)))
+            }
+            other => panic!("expected rate limit, got {:?}", other),
+        }
+        assert!(source.limiter.current_rate() < rate);
+    }
+}
@@ -0,0 +1,176 @@
+//! # WalletD Prices
+//!
+//! Fiat prices for crypto assets, to show what balances and fees are worth.
+//!
+//! A [`PriceOracle`] asks its [`PriceSource`]s in order until one answers,
+//! caches each price for a configurable time and values
+//! [`PortfolioSnapshot`](walletd_provider::PortfolioSnapshot)s and fee
+//! [`Amount`](walletd_traits::Amount)s in USD or EUR.
+//!
+//! Built-in sources:
+//!
+//! - [`CoinGecko`]: REST API, any listed asset; rate limited adaptively
+//! - [`Chainlink`]: on-chain price feeds read through a
+//!   [`ProviderPool`](walletd_provider::ProviderPool), with a staleness check
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use std::sync::Arc;
+//! use walletd_prices::{Chainlink, CoinGecko, Currency, PriceOracle};
+//!
+//! let oracle = PriceOracle::new()
+//!     .with_source(CoinGecko::new())
+//!     .with_source(Chainlink::mainnet(Arc::clone(&pool), "ethereum"));
+//!
+//! let eth = oracle.price("ETH", Currency::Usd).await?;
+//! let valuation = oracle.value_snapshot(&snapshot, Currency::Eur).await;
+//! println!("Total: {:.2} EUR", valuation.total);
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod chainlink;
+pub mod coingecko;
+pub mod oracle;
+
+pub use chainlink::Chainlink;
+pub use coingecko::CoinGecko;
+pub use oracle::{PriceOracle, PriceSource, Valuation, ValuedBalance};
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_provider::ProviderError;
+
+/// Fiat currency prices are quoted in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Currency {
+    /// US dollar
+    Usd,
+    /// Euro
+    Eur,
+}
+
+impl Currency {
+    /// Lowercase ISO 4217 code, as used by most price APIs
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "usd",
+            Currency::Eur => "eur",
+        }
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+        })
+    }
+}
+
+impl std::str::FromStr for Currency {
+    type Err = PriceError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "usd" => Ok(Currency::Usd),
+            "eur" => Ok(Currency::Eur),
+            _ => Err(PriceError::UnsupportedCurrency(s.to_string())),
+        }
+    }
+}
+
+/// Price of one whole unit of an asset
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Price {
+    /// Asset ticker, uppercase
+    pub symbol: String,
+    /// Quote currency
+    pub currency: Currency,
+    /// Price of one whole unit
+    pub value: f64,
+    /// Name of the source that supplied it
+    pub source: String,
+    /// When the source last updated the price, Unix seconds
+    pub updated_at: u64,
+}
+
+/// Price lookup errors
+#[derive(Error, Debug)]
+pub enum PriceError {
+    /// The source has no price for this asset
+    #[error("No {currency} price for {symbol}")]
+    NotFound {
+        /// Asset ticker
+        symbol: String,
+        /// Quote currency
+        currency: Currency,
+    },
+
+    /// Unknown currency code
+    #[error("Unsupported currency: {0}")]
+    UnsupportedCurrency(String),
+
+    /// The source is throttling requests
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay the source asked for
+        retry_after: Option<Duration>,
+    },
+
+    /// The price is older than the source allows
+    #[error("Price for {symbol} is stale: updated {age:?} ago")]
+    Stale {
+        /// Asset ticker
+        symbol: String,
+        /// Age of the price
+        age: Duration,
+    },
+
+    /// Unexpected response from the source
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// On-chain read failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+/// Result type for price lookups
+pub type Result<T> = std::result::Result<T, PriceError>;
+
+impl From<PriceError> for WalletdError {
+    fn from(e: PriceError) -> Self {
+        match e {
+            PriceError::Provider(e) => e.into(),
+            PriceError::RateLimited { retry_after } => WalletdError::RateLimited {
+                retry_after_secs: retry_after.map_or(1, |d| d.as_secs().max(1)),
+            },
+            PriceError::NotFound { .. } | PriceError::UnsupportedCurrency(_) => {
+                WalletdError::NotSupported(e.to_string())
+            }
+            PriceError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            PriceError::Stale { .. } | PriceError::Http(_) => {
+                WalletdError::NetworkError(e.to_string())
+            }
+        }
+    }
+}
+
+fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
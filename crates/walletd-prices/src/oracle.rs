@@ -0,0 +1,388 @@
+//! Price sources and the caching oracle in front of them
+
+use crate::{Currency, Price, PriceError, Result};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use walletd_provider::portfolio::Balance;
+use walletd_provider::PortfolioSnapshot;
+use walletd_traits::Amount;
+
+/// How long prices are reused by default
+const DEFAULT_TTL: Duration = Duration::from_secs(60);
+
+/// A place to look up prices
+#[async_trait]
+pub trait PriceSource: Send + Sync {
+    /// Name recorded in [`Price::source`]
+    fn name(&self) -> &str;
+
+    /// Price of one whole unit of `symbol` (uppercase ticker)
+    async fn price(&self, symbol: &str, currency: Currency) -> Result<Price>;
+
+    /// Prices of several assets
+    ///
+    /// Assets without a price are left out. The default asks for each one
+    /// in turn; sources with a batch endpoint override it.
+    async fn prices(&self, symbols: &[String], currency: Currency) -> Result<Vec<Price>> {
+        let mut prices = Vec::new();
+        for symbol in symbols {
+            match self.price(symbol, currency).await {
+                Ok(price) => prices.push(price),
+                Err(PriceError::NotFound { .. }) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(prices)
+    }
+}
+
+/// A balance with its fiat value
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValuedBalance {
+    /// The balance
+    pub balance: Balance,
+    /// Price of one whole unit, if known
+    pub price: Option<f64>,
+    /// Value of the balance, if the price is known
+    pub value: Option<f64>,
+}
+
+/// Fiat value of a portfolio snapshot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Valuation {
+    /// Quote currency
+    pub currency: Currency,
+    /// Every balance of the snapshot, in order
+    pub balances: Vec<ValuedBalance>,
+    /// Sum of the known values
+    pub total: f64,
+    /// Symbols no source had a price for
+    pub missing: Vec<String>,
+}
+
+impl Valuation {
+    /// Returns true if every balance could be valued
+    pub fn is_complete(&self) -> bool {
+        self.missing.is_empty()
+    }
+}
+
+/// Looks up prices from several sources, with caching
+///
+/// Sources are tried in the order they were added; the first answer wins,
+/// so put the preferred source first and fallbacks after it. Prices are
+/// cached per symbol and currency for the configured TTL (60 seconds by
+/// default).
+pub struct PriceOracle {
+    sources: Vec<Arc<dyn PriceSource>>,
+    ttl: Duration,
+    cache: Mutex<HashMap<(String, Currency), (Price, Instant)>>,
+}
+
+impl Default for PriceOracle {
+    fn default() -> Self {
+        Self {
+            sources: Vec::new(),
+            ttl: DEFAULT_TTL,
+            cache: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl fmt::Debug for PriceOracle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PriceOracle")
+            .field(
+                "sources",
+                &self.sources.iter().map(|s| s.name()).collect::<Vec<_>>(),
+            )
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl PriceOracle {
+    /// Creates an oracle without sources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a source, tried after the ones already added
+    pub fn with_source(mut self, source: impl PriceSource + 'static) -> Self {
+        self.sources.push(Arc::new(source));
+        self
+    }
+
+    /// Sets how long a price is reused before it is fetched again
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Price of one whole unit of `symbol`
+    ///
+    /// Returns the error of the last source if none of them has a price.
+    pub async fn price(&self, symbol: &str, currency: Currency) -> Result<Price> {
+        let symbol = symbol.trim().to_ascii_uppercase();
+        if let Some(price) = self.cached(&symbol, currency) {
+            return Ok(price);
+        }
+        let mut last_error = None;
+        for source in &self.sources {
+            match source.price(&symbol, currency).await {
+                Ok(price) => {
+                    self.store(&price);
+                    return Ok(price);
+                }
+                Err(e) => {
+                    tracing::debug!(
+                        "{} has no {} price for {}: {}",
+                        source.name(),
+                        currency,
+                        symbol,
+                        e
+                    );
+                    last_error = Some(e);
+                }
+            }
+        }
+        Err(last_error.unwrap_or(PriceError::NotFound { symbol, currency }))
+    }
+
+    /// Prices of several assets, keyed by uppercase symbol
+    ///
+    /// Each source is asked once for the symbols still missing. Assets no
+    /// source knows are left out; source errors are logged and the next
+    /// source is tried.
+    pub async fn prices(&self, symbols: &[&str], currency: Currency) -> HashMap<String, Price> {
+        let mut found = HashMap::new();
+        let mut missing: Vec<String> = Vec::new();
+        for symbol in symbols.iter().map(|s| s.trim().to_ascii_uppercase()) {
+            match self.cached(&symbol, currency) {
+                Some(price) => {
+                    found.insert(symbol, price);
+                }
+                None if !missing.contains(&symbol) => missing.push(symbol),
+                None => {}
+            }
+        }
+        for source in &self.sources {
+            if missing.is_empty() {
+                break;
+            }
+            match source.prices(&missing, currency).await {
+                Ok(prices) => {
+                    for price in prices {
+                        self.store(&price);
+                        missing.retain(|s| *s != price.symbol);
+                        found.insert(price.symbol.clone(), price);
+                    }
+                }
+                Err(e) => tracing::debug!("{} failed for {:?}: {}", source.name(), missing, e),
+            }
+        }
+        found
+    }
+
+    /// Fiat value of an amount of `symbol`, e.g. a fee estimate
+    pub async fn value(&self, symbol: &str, amount: &Amount, currency: Currency) -> Result<f64> {
+        let price = self.price(symbol, currency).await?;
+        Ok(amount.human_readable() * price.value)
+    }
+
+    /// Fiat value of every balance in a portfolio snapshot
+    ///
+    /// Balances whose price cannot be found keep a `None` value and their
+    /// symbol is listed in [`Valuation::missing`].
+    pub async fn value_snapshot(
+        &self,
+        snapshot: &PortfolioSnapshot,
+        currency: Currency,
+    ) -> Valuation {
+        let symbols: BTreeSet<&str> = snapshot
+            .balances
+            .iter()
+            .map(|b| b.asset.symbol.as_str())
+            .collect();
+        let symbols: Vec<&str> = symbols.into_iter().collect();
+        let prices = self.prices(&symbols, currency).await;
+
+        let balances: Vec<ValuedBalance> = snapshot
+            .balances
+            .iter()
+            .map(|balance| {
+                let price = prices
+                    .get(&balance.asset.symbol.to_ascii_uppercase())
+                    .map(|p| p.value);
+                ValuedBalance {
+                    balance: balance.clone(),
+                    price,
+                    value: price.map(|p| balance.value() * p),
+                }
+            })
+            .collect();
+        let total = balances.iter().filter_map(|b| b.value).sum();
+        let missing = symbols
+            .into_iter()
+            .filter(|s| !prices.contains_key(&s.to_ascii_uppercase()))
+            .map(str::to_string)
+            .collect();
+        Valuation {
+            currency,
+            balances,
+            total,
+            missing,
+        }
+    }
+
+    /// Drops every cached price
+    pub fn clear_cache(&self) {
+        self.cache.lock().expect("price cache lock").clear();
+    }
+
+    fn cached(&self, symbol: &str, currency: Currency) -> Option<Price> {
+        let cache = self.cache.lock().expect("price cache lock");
+        cache
+            .get(&(symbol.to_string(), currency))
+            .filter(|(_, fetched)| fetched.elapsed() < self.ttl)
+            .map(|(price, _)| price.clone())
+    }
+
+    fn store(&self, price: &Price) {
+        self.cache.lock().expect("price cache lock").insert(
+            (price.symbol.clone(), price.currency),
+            (price.clone(), Instant::now()),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use walletd_provider::portfolio::Asset;
+
+    /// Fixed prices in USD; EUR is 0.9 of USD
+    struct Fixed {
+        name: &'static str,
+        prices: HashMap<&'static str, f64>,
+        calls: Arc<AtomicUsize>,
+    }
+
+    impl Fixed {
+        fn new(name: &'static str, prices: &[(&'static str, f64)]) -> Self {
+            Self {
+                name,
+                prices: prices.iter().copied().collect(),
+                calls: Arc::new(AtomicUsize::new(0)),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl PriceSource for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn price(&self, symbol: &str, currency: Currency) -> Result<Price> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let usd = self
+                .prices
+                .get(symbol)
+                .ok_or_else(|| PriceError::NotFound {
+                    symbol: symbol.to_string(),
+                    currency,
+                })?;
+            Ok(Price {
+                symbol: symbol.to_string(),
+                currency,
+                value: if currency == Currency::Eur {
+                    usd * 0.9
+                } else {
+                    *usd
+                },
+                source: self.name.to_string(),
+                updated_at: 0,
+            })
+        }
+    }
+
+    fn balance(symbol: &str, raw: u128, decimals: u8) -> Balance {
+        Balance {
+            provider: "ethereum".to_string(),
+            address: "0xabc".to_string(),
+            asset: Asset::native(symbol, decimals),
+            raw,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fallback_and_cache() {
+        let primary = Fixed::new("primary", &[("ETH", 3000.0)]);
+        let calls = Arc::clone(&primary.calls);
+        let oracle = PriceOracle::new()
+            .with_source(primary)
+            .with_source(Fixed::new("fallback", &[("ETH", 1.0), ("SOL", 150.0)]));
+
+        let eth = oracle.price("eth", Currency::Usd).await.unwrap();
+        assert_eq!((eth.value, eth.source.as_str()), (3000.0, "primary"));
+        oracle.price("ETH", Currency::Usd).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let sol = oracle.price("SOL", Currency::Eur).await.unwrap();
+        assert_eq!((sol.value, sol.source.as_str()), (135.0, "fallback"));
+        assert!(matches!(
+            oracle.price("DOGE", Currency::Usd).await,
+            Err(PriceError::NotFound { .. })
+        ));
+
+        // SOL and DOGE were asked of the primary first
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        oracle.clear_cache();
+        oracle.price("ETH", Currency::Usd).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn test_ttl_expiry() {
+        let source = Fixed::new("fixed", &[("BTC", 60_000.0)]);
+        let calls = Arc::clone(&source.calls);
+        let oracle = PriceOracle::new()
+            .with_source(source)
+            .with_ttl(Duration::ZERO);
+        oracle.price("BTC", Currency::Usd).await.unwrap();
+        oracle.price("BTC", Currency::Usd).await.unwrap();
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_value_snapshot_and_fee() {
+        let oracle =
+            PriceOracle::new().with_source(Fixed::new("fixed", &[("ETH", 2000.0), ("USDC", 1.0)]));
+        let snapshot = PortfolioSnapshot {
+            taken_at: 1,
+            balances: vec![
+                balance("ETH", 1_500_000_000_000_000_000, 18),
+                balance("USDC", 250_000_000, 6),
+                balance("PEPE", 1, 0),
+            ],
+            errors: Vec::new(),
+        };
+
+        let valuation = oracle.value_snapshot(&snapshot, Currency::Usd).await;
+        assert_eq!(valuation.total, 3250.0);
+        assert_eq!(valuation.balances[0].value, Some(3000.0));
+        assert_eq!(valuation.balances[2].value, None);
+        assert_eq!(valuation.missing, ["PEPE"]);
+        assert!(!valuation.is_complete());
+
+        let fee = Amount::from_smallest_unit(21_000 * 20_000_000_000, 18);
+        let usd = oracle.value("ETH", &fee, Currency::Usd).await.unwrap();
+        assert!((usd - 0.84).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,186 @@
+//! Per-method response cache policy
+//!
+//! Decides how long a response may be cached based on the RPC method, and
+//! builds cache keys from the method and its JSON-encoded parameters so that
+//! different calls never share an entry.
+
+use serde::Serialize;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// How long responses for an RPC method may be cached
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CacheTtl {
+    /// Never cache (writes, nonces, subscriptions)
+    Never,
+    /// Cache for a fixed duration
+    For(Duration),
+    /// Cache for the lifetime of the provider (immutable data such as chain id)
+    Forever,
+}
+
+impl CacheTtl {
+    /// Cache for the given number of seconds
+    pub fn secs(secs: u64) -> Self {
+        CacheTtl::For(Duration::from_secs(secs))
+    }
+
+    /// Returns the expiry duration, `None` meaning the entry never expires
+    pub(crate) fn duration(&self) -> Option<Duration> {
+        match self {
+            CacheTtl::For(ttl) => Some(*ttl),
+            CacheTtl::Never | CacheTtl::Forever => None,
+        }
+    }
+}
+
+/// Method names that indicate a state-changing or stateful call
+const WRITE_MARKERS: &[&str] = &["send", "submit", "broadcast", "subscribe", "filter", "nonce"];
+
+/// Methods whose results must always be fresh, whatever the default TTL
+const FRESH_METHODS: &[&str] = &[
+    "eth_getTransactionCount",
+    "eth_estimateGas",
+    "getLatestBlockhash",
+    "getRecentBlockhash",
+    "isBlockhashValid",
+    "system_accountNextIndex",
+];
+
+/// Per-method cache policy
+///
+/// Methods without an explicit entry use the default TTL, except that nonce
+/// and blockhash queries ([`FRESH_METHODS`]) and methods whose names look like
+/// writes (`send`, `submit`, `broadcast`, ...) are never cached.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CachePolicy {
+    /// TTL for methods without an explicit entry
+    pub default_ttl: CacheTtl,
+    /// Explicit per-method TTLs
+    pub methods: HashMap<String, CacheTtl>,
+}
+
+impl CachePolicy {
+    /// Creates an empty policy with the given default TTL
+    pub fn new(default_ttl: CacheTtl) -> Self {
+        Self {
+            default_ttl,
+            methods: HashMap::new(),
+        }
+    }
+
+    /// Sets the TTL for a method
+    pub fn with_method(mut self, method: impl Into<String>, ttl: CacheTtl) -> Self {
+        self.methods.insert(method.into(), ttl);
+        self
+    }
+
+    /// Sets the default TTL for methods without an explicit entry
+    pub fn with_default_ttl(mut self, ttl: CacheTtl) -> Self {
+        self.default_ttl = ttl;
+        self
+    }
+
+    /// Returns the TTL that applies to a method
+    pub fn ttl_for(&self, method: &str) -> CacheTtl {
+        if let Some(ttl) = self.methods.get(method) {
+            return *ttl;
+        }
+        let lower = method.to_ascii_lowercase();
+        if FRESH_METHODS.contains(&method) || WRITE_MARKERS.iter().any(|marker| lower.contains(marker)) {
+            CacheTtl::Never
+        } else {
+            self.default_ttl
+        }
+    }
+}
+
+impl Default for CachePolicy {
+    /// Sensible defaults for EVM and Solana JSON-RPC
+    ///
+    /// Methods that are not listed are not cached.
+    fn default() -> Self {
+        Self::new(CacheTtl::Never)
+            // Immutable for the lifetime of a connection
+            .with_method("eth_chainId", CacheTtl::Forever)
+            .with_method("net_version", CacheTtl::Forever)
+            .with_method("getGenesisHash", CacheTtl::Forever)
+            // Final once they exist, unless reorged out (missing results are
+            // never cached)
+            .with_method("eth_getTransactionReceipt", CacheTtl::secs(60))
+            .with_method("eth_getBlockByHash", CacheTtl::secs(60))
+            .with_method("eth_getTransactionByHash", CacheTtl::secs(60))
+            .with_method("getTransaction", CacheTtl::secs(60))
+            // Reads of contract state
+            .with_method("eth_call", CacheTtl::secs(10))
+            .with_method("eth_getCode", CacheTtl::secs(10))
+            .with_method("getAccountInfo", CacheTtl::secs(10))
+            // Fast-moving state
+            .with_method("eth_getBalance", CacheTtl::secs(5))
+            .with_method("eth_blockNumber", CacheTtl::secs(2))
+            .with_method("eth_gasPrice", CacheTtl::secs(5))
+            .with_method("eth_maxPriorityFeePerGas", CacheTtl::secs(5))
+            .with_method("getBalance", CacheTtl::secs(5))
+            .with_method("getSlot", CacheTtl::secs(1))
+            // Must always be fresh
+            .with_method("eth_getTransactionCount", CacheTtl::Never)
+            .with_method("eth_estimateGas", CacheTtl::Never)
+            .with_method("eth_sendRawTransaction", CacheTtl::Never)
+            .with_method("eth_sendTransaction", CacheTtl::Never)
+            .with_method("getLatestBlockhash", CacheTtl::Never)
+            .with_method("sendTransaction", CacheTtl::Never)
+    }
+}
+
+/// Builds a cache key from a method name and its parameters
+///
+/// The key embeds the full JSON encoding of the parameters, so two different
+/// calls can never collide on the same entry.
+pub fn cache_key<P: Serialize + ?Sized>(method: &str, params: &P) -> crate::Result<String> {
+    Ok(format!("{}:{}", method, serde_json::to_string(params)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy() {
+        let policy = CachePolicy::default();
+        assert_eq!(policy.ttl_for("eth_chainId"), CacheTtl::Forever);
+        assert_eq!(policy.ttl_for("eth_getBalance"), CacheTtl::secs(5));
+        assert_eq!(policy.ttl_for("eth_sendRawTransaction"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("eth_call"), CacheTtl::secs(10));
+        assert_eq!(policy.ttl_for("eth_getTransactionReceipt"), CacheTtl::secs(60));
+        assert_eq!(policy.ttl_for("eth_getLogs"), CacheTtl::Never);
+    }
+
+    #[test]
+    fn test_write_methods_never_cached_by_default() {
+        let policy = CachePolicy::new(CacheTtl::secs(60));
+        assert_eq!(policy.ttl_for("sendRawTransaction"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("broadcast_tx_sync"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("suix_subscribeEvent"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("eth_newFilter"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("eth_getTransactionCount"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("getLatestBlockhash"), CacheTtl::Never);
+        assert_eq!(policy.ttl_for("getAccountInfo"), CacheTtl::secs(60));
+    }
+
+    #[test]
+    fn test_explicit_entry_overrides_heuristic() {
+        let policy = CachePolicy::default().with_method("eth_getFilterLogs", CacheTtl::secs(30));
+        assert_eq!(policy.ttl_for("eth_getFilterLogs"), CacheTtl::secs(30));
+    }
+
+    #[test]
+    fn test_cache_key_depends_on_method_and_params() {
+        let a = cache_key("eth_getBalance", &("0xabc", "latest")).unwrap();
+        let b = cache_key("eth_getBalance", &("0xdef", "latest")).unwrap();
+        let c = cache_key("eth_getCode", &("0xabc", "latest")).unwrap();
+        assert_ne!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(a, cache_key("eth_getBalance", &("0xabc", "latest")).unwrap());
+        assert_eq!(a, r#"eth_getBalance:["0xabc","latest"]"#);
+    }
+}
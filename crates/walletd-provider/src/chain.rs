@@ -0,0 +1,278 @@
+//! Typed chain providers
+//!
+//! A [`ChainProvider`] is a handle to a provider in a [`ProviderPool`] that
+//! knows which chain family it talks to, so callers get chain-appropriate
+//! convenience methods instead of hand-written JSON-RPC calls. The handle
+//! shares endpoint health, caching and statistics with the pool.
+
+use crate::{HttpProvider, ProviderError, ProviderPool, Result};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+/// Marker trait for a chain family
+pub trait Chain: Send + Sync + 'static {
+    /// Pool name used by the family's default accessor
+    const DEFAULT_NAME: &'static str;
+}
+
+/// EVM-compatible chains (Ethereum, Base, Polygon, ...)
+#[derive(Debug, Clone, Copy)]
+pub struct Evm;
+
+impl Chain for Evm {
+    const DEFAULT_NAME: &'static str = "ethereum";
+}
+
+/// Solana
+#[derive(Debug, Clone, Copy)]
+pub struct Solana;
+
+impl Chain for Solana {
+    const DEFAULT_NAME: &'static str = "solana";
+}
+
+/// Typed handle to a pooled provider for chain family `C`
+///
+/// Dereferences to [`HttpProvider`] for raw `rpc_call` access.
+pub struct ChainProvider<C: Chain> {
+    inner: HttpProvider,
+    _chain: PhantomData<C>,
+}
+
+impl<C: Chain> Deref for ChainProvider<C> {
+    type Target = HttpProvider;
+
+    fn deref(&self) -> &HttpProvider {
+        &self.inner
+    }
+}
+
+impl<C: Chain> std::fmt::Debug for ChainProvider<C> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChainProvider")
+            .field("chain", &std::any::type_name::<C>())
+            .field("url", &self.inner.managed.config.url)
+            .finish()
+    }
+}
+
+impl ProviderPool {
+    /// Returns a typed handle to the provider registered as `name`
+    pub fn chain<C: Chain>(&self, name: &str) -> Result<ChainProvider<C>> {
+        let managed = self.get(name)?;
+        Ok(ChainProvider {
+            inner: HttpProvider::from_managed(managed, self.shared_client()?),
+            _chain: PhantomData,
+        })
+    }
+
+    /// Returns an EVM handle to the provider registered as `name`
+    pub fn evm(&self, name: &str) -> Result<ChainProvider<Evm>> {
+        self.chain(name)
+    }
+
+    /// Returns a Solana handle to the provider registered as `"solana"`
+    pub fn solana(&self) -> Result<ChainProvider<Solana>> {
+        self.chain(Solana::DEFAULT_NAME)
+    }
+}
+
+impl ChainProvider<Evm> {
+    /// Returns the chain id (`eth_chainId`)
+    pub async fn chain_id(&self) -> Result<u64> {
+        let hex: String = self.rpc_call("eth_chainId", ()).await?;
+        parse_quantity(&hex).and_then(to_u64)
+    }
+
+    /// Returns the latest block number (`eth_blockNumber`)
+    pub async fn block_number(&self) -> Result<u64> {
+        let hex: String = self.rpc_call("eth_blockNumber", ()).await?;
+        parse_quantity(&hex).and_then(to_u64)
+    }
+
+    /// Returns an account balance in wei at the latest block
+    pub async fn get_balance(&self, address: &str) -> Result<u128> {
+        let hex: String = self.rpc_call("eth_getBalance", (address, "latest")).await?;
+        parse_quantity(&hex)
+    }
+
+    /// Returns an account's ERC-20 balance in the token's base units
+    ///
+    /// Calls `balanceOf(owner)` on the token contract at the latest block.
+    pub async fn erc20_balance(&self, token: &str, owner: &str) -> Result<u128> {
+        let owner_hex = owner.strip_prefix("0x").unwrap_or(owner);
+        if owner_hex.len() != 40 || !owner_hex.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(ProviderError::InvalidConfig(format!("invalid EVM address: {}", owner)));
+        }
+        let data = format!("0x70a08231{:0>64}", owner_hex.to_ascii_lowercase());
+        parse_quantity(&self.call(token, &data).await?)
+    }
+
+    /// Calls a contract at the latest block (`eth_call`), returning the
+    /// hex-encoded return data
+    pub async fn call(&self, to: &str, data: &str) -> Result<String> {
+        let call = serde_json::json!({ "to": to, "data": data });
+        self.rpc_call("eth_call", (call, "latest")).await
+    }
+
+    /// Returns the pending nonce for an account
+    pub async fn get_transaction_count(&self, address: &str) -> Result<u64> {
+        let hex: String = self
+            .rpc_call("eth_getTransactionCount", (address, "pending"))
+            .await?;
+        parse_quantity(&hex).and_then(to_u64)
+    }
+
+    /// Returns the current gas price in wei
+    pub async fn gas_price(&self) -> Result<u128> {
+        let hex: String = self.rpc_call("eth_gasPrice", ()).await?;
+        parse_quantity(&hex)
+    }
+
+    /// Broadcasts a signed transaction, returning its hash
+    pub async fn send_raw_transaction(&self, raw_tx_hex: &str) -> Result<String> {
+        self.rpc_call("eth_sendRawTransaction", [raw_tx_hex]).await
+    }
+
+    /// Returns a transaction receipt, or `None` if it is not mined yet
+    pub async fn get_transaction_receipt(&self, tx_hash: &str) -> Result<Option<serde_json::Value>> {
+        match self
+            .rpc_call::<_, serde_json::Value>("eth_getTransactionReceipt", [tx_hash])
+            .await
+        {
+            Ok(receipt) => Ok(Some(receipt)),
+            // A null result is reported as a missing result
+            Err(ProviderError::RpcError { code: -1, .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+impl ChainProvider<Solana> {
+    /// Returns the current slot
+    pub async fn get_slot(&self) -> Result<u64> {
+        self.rpc_call("getSlot", ()).await
+    }
+
+    /// Returns an account balance in lamports
+    pub async fn get_balance(&self, pubkey: &str) -> Result<u64> {
+        let response: serde_json::Value = self.rpc_call("getBalance", [pubkey]).await?;
+        response["value"]
+            .as_u64()
+            .ok_or_else(|| ProviderError::InvalidResponse("missing balance value".to_string()))
+    }
+
+    /// Returns an owner's balance of an SPL token in base units
+    ///
+    /// Sums every token account the owner holds for `mint`; an owner without
+    /// one has a balance of zero.
+    pub async fn get_token_balance(&self, owner: &str, mint: &str) -> Result<u64> {
+        let params = serde_json::json!([owner, {"mint": mint}, {"encoding": "jsonParsed"}]);
+        let response: serde_json::Value = self.rpc_call("getTokenAccountsByOwner", params).await?;
+        let accounts = response["value"]
+            .as_array()
+            .ok_or_else(|| ProviderError::InvalidResponse("missing token accounts".to_string()))?;
+        accounts.iter().try_fold(0u64, |total, account| {
+            let amount = account["account"]["data"]["parsed"]["info"]["tokenAmount"]["amount"]
+                .as_str()
+                .and_then(|a| a.parse::<u64>().ok())
+                .ok_or_else(|| ProviderError::InvalidResponse("missing token amount".to_string()))?;
+            Ok(total.saturating_add(amount))
+        })
+    }
+
+    /// Returns the latest blockhash
+    pub async fn get_latest_blockhash(&self) -> Result<String> {
+        let response: serde_json::Value = self.rpc_call("getLatestBlockhash", ()).await?;
+        response["value"]["blockhash"]
+            .as_str()
+            .map(str::to_string)
+            .ok_or_else(|| ProviderError::InvalidResponse("missing blockhash".to_string()))
+    }
+
+    /// Broadcasts a base64-encoded signed transaction, returning its signature
+    pub async fn send_transaction(&self, tx_base64: &str) -> Result<String> {
+        let params = serde_json::json!([tx_base64, {"encoding": "base64"}]);
+        self.rpc_call("sendTransaction", params).await
+    }
+}
+
+/// Parses a `0x`-prefixed hex quantity
+fn parse_quantity(hex: &str) -> Result<u128> {
+    let digits = hex.strip_prefix("0x").unwrap_or(hex);
+    u128::from_str_radix(digits, 16)
+        .map_err(|_| ProviderError::InvalidResponse(format!("invalid hex quantity: {}", hex)))
+}
+
+fn to_u64(value: u128) -> Result<u64> {
+    u64::try_from(value)
+        .map_err(|_| ProviderError::InvalidResponse(format!("quantity out of range: {}", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn mock(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"method": rpc_method})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    #[tokio::test]
+    async fn test_evm_provider() {
+        let server = MockServer::start().await;
+        mock(&server, "eth_chainId", serde_json::json!("0x2105")).await;
+        mock(&server, "eth_getBalance", serde_json::json!("0xde0b6b3a7640000")).await;
+
+        let pool = ProviderPool::new();
+        pool.add("base", ProviderConfig::new(server.uri())).unwrap();
+        let base = pool.evm("base").unwrap();
+
+        assert_eq!(base.chain_id().await.unwrap(), 8453);
+        assert_eq!(base.get_balance("0xabc").await.unwrap(), 1_000_000_000_000_000_000);
+
+        // Statistics are shared with the pooled provider
+        assert_eq!(pool.get("base").unwrap().stats().await[0].total_requests, 2);
+    }
+
+    #[tokio::test]
+    async fn test_solana_provider() {
+        let server = MockServer::start().await;
+        mock(&server, "getBalance", serde_json::json!({"context": {"slot": 1}, "value": 5000})).await;
+        mock(
+            &server,
+            "getLatestBlockhash",
+            serde_json::json!({"context": {"slot": 1}, "value": {"blockhash": "EkSn", "lastValidBlockHeight": 9}}),
+        )
+        .await;
+
+        let pool = ProviderPool::new();
+        pool.add("solana", ProviderConfig::new(server.uri())).unwrap();
+        let solana = pool.solana().unwrap();
+
+        assert_eq!(solana.get_balance("11111111111111111111111111111111").await.unwrap(), 5000);
+        assert_eq!(solana.get_latest_blockhash().await.unwrap(), "EkSn");
+    }
+
+    #[test]
+    fn test_missing_provider() {
+        let pool = ProviderPool::new();
+        assert!(matches!(pool.solana(), Err(ProviderError::NotFound(_))));
+    }
+
+    #[test]
+    fn test_parse_quantity() {
+        assert_eq!(parse_quantity("0x0").unwrap(), 0);
+        assert_eq!(parse_quantity("0xff").unwrap(), 255);
+        assert!(parse_quantity("0xzz").is_err());
+        assert!(to_u64(u128::MAX).is_err());
+    }
+}
@@ -0,0 +1,190 @@
+//! Persistent disk-backed response cache
+//!
+//! Enabled with the `disk-cache` feature. Entries are stored in a [`sled`]
+//! database together with their absolute expiry time, so immutable data
+//! (receipts, historical blocks, token metadata) survives process restarts.
+//! Only entries cached for at least [`MIN_PERSIST_TTL`] are written to disk;
+//! short-lived data stays in memory.
+
+use crate::{ProviderError, Result};
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Shortest TTL for which an entry is persisted
+pub const MIN_PERSIST_TTL: Duration = Duration::from_secs(60);
+
+/// Expiry marker for entries that never expire
+const NEVER_EXPIRES: u64 = u64::MAX;
+
+/// Disk-backed cache of RPC responses
+#[derive(Debug, Clone)]
+pub struct DiskCache {
+    db: sled::Db,
+}
+
+impl DiskCache {
+    /// Opens (or creates) a cache database at `path`
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let db = sled::open(path).map_err(|e| ProviderError::Cache(e.to_string()))?;
+        Ok(Self { db })
+    }
+
+    /// Opens a cache that is deleted when dropped (useful for tests)
+    pub fn temporary() -> Result<Self> {
+        let db = sled::Config::new()
+            .temporary(true)
+            .open()
+            .map_err(|e| ProviderError::Cache(e.to_string()))?;
+        Ok(Self { db })
+    }
+
+    /// Gets an entry if present and not expired
+    ///
+    /// Returns the data and its remaining TTL (`None` meaning it never expires).
+    /// Expired entries are removed.
+    pub fn get(&self, key: &str) -> Option<(Vec<u8>, Option<Duration>)> {
+        let raw = self.db.get(key).ok()??;
+        let (expiry, data) = decode(&raw)?;
+        if expiry == NEVER_EXPIRES {
+            return Some((data.to_vec(), None));
+        }
+        match expiry.checked_sub(unix_now()) {
+            Some(remaining) if remaining > 0 => {
+                Some((data.to_vec(), Some(Duration::from_secs(remaining))))
+            }
+            _ => {
+                let _ = self.db.remove(key);
+                None
+            }
+        }
+    }
+
+    /// Stores an entry; `ttl` of `None` means it never expires
+    ///
+    /// Entries with a TTL shorter than [`MIN_PERSIST_TTL`] are ignored.
+    pub fn insert(&self, key: &str, data: &[u8], ttl: Option<Duration>) -> Result<()> {
+        let expiry = match ttl {
+            None => NEVER_EXPIRES,
+            Some(ttl) if ttl < MIN_PERSIST_TTL => return Ok(()),
+            Some(ttl) => unix_now().saturating_add(ttl.as_secs()),
+        };
+        let mut value = Vec::with_capacity(8 + data.len());
+        value.extend_from_slice(&expiry.to_be_bytes());
+        value.extend_from_slice(data);
+        self.db
+            .insert(key, value)
+            .map_err(|e| ProviderError::Cache(e.to_string()))?;
+        Ok(())
+    }
+
+    /// Removes expired entries, returning how many were removed
+    pub fn purge_expired(&self) -> usize {
+        let now = unix_now();
+        let mut removed = 0;
+        for (key, value) in self.db.iter().flatten() {
+            if decode(&value).is_none_or(|(expiry, _)| expiry <= now) && self.db.remove(key).is_ok() {
+                removed += 1;
+            }
+        }
+        removed
+    }
+
+    /// Returns the number of stored entries (including expired ones not yet purged)
+    pub fn len(&self) -> usize {
+        self.db.len()
+    }
+
+    /// Returns true if the cache holds no entries
+    pub fn is_empty(&self) -> bool {
+        self.db.is_empty()
+    }
+
+    /// Flushes pending writes to disk
+    pub fn flush(&self) -> Result<()> {
+        self.db
+            .flush()
+            .map_err(|e| ProviderError::Cache(e.to_string()))?;
+        Ok(())
+    }
+}
+
+fn decode(raw: &[u8]) -> Option<(u64, &[u8])> {
+    let (expiry, data) = raw.split_first_chunk::<8>()?;
+    Some((u64::from_be_bytes(*expiry), data))
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip_and_expiry() {
+        let cache = DiskCache::temporary().unwrap();
+        cache.insert("forever", b"0x1", None).unwrap();
+        cache.insert("hour", b"0x2", Some(Duration::from_secs(3600))).unwrap();
+        cache.insert("short", b"0x3", Some(Duration::from_secs(5))).unwrap();
+
+        assert_eq!(cache.get("forever"), Some((b"0x1".to_vec(), None)));
+        let (data, ttl) = cache.get("hour").unwrap();
+        assert_eq!(data, b"0x2");
+        assert!(ttl.unwrap() <= Duration::from_secs(3600));
+        assert!(cache.get("short").is_none());
+        assert_eq!(cache.len(), 2);
+    }
+
+    #[test]
+    fn test_expired_entries_are_dropped() {
+        let cache = DiskCache::temporary().unwrap();
+        let mut stale = 1u64.to_be_bytes().to_vec();
+        stale.extend_from_slice(b"old");
+        cache.db.insert("stale", stale).unwrap();
+        cache.insert("fresh", b"new", None).unwrap();
+
+        assert_eq!(cache.purge_expired(), 1);
+        assert!(cache.get("stale").is_none());
+        assert!(cache.get("fresh").is_some());
+    }
+
+    #[test]
+    fn test_provider_reads_through_to_disk() {
+        use crate::{ManagedProvider, ProviderConfig};
+
+        let disk = DiskCache::temporary().unwrap();
+        let config = ProviderConfig::new("https://example.com").with_disk_cache(disk.clone());
+        let params = ["0xabc"];
+
+        let first = ManagedProvider::new(config.clone()).unwrap();
+        first.cache_call("eth_getTransactionReceipt", &params, b"{}".to_vec());
+        first.cache_call("eth_blockNumber", &(), b"\"0x1\"".to_vec());
+        assert_eq!(disk.len(), 1);
+
+        // A fresh provider (e.g. after a restart) is served from disk
+        let second = ManagedProvider::new(config).unwrap();
+        assert_eq!(
+            second.get_cached_call("eth_getTransactionReceipt", &params),
+            Some(b"{}".to_vec())
+        );
+        assert!(second.get_cached_call("eth_blockNumber", &()).is_none());
+    }
+
+    #[test]
+    fn test_survives_reopen() {
+        let dir = std::env::temp_dir().join(format!("walletd-disk-cache-{}", std::process::id()));
+        {
+            let cache = DiskCache::open(&dir).unwrap();
+            cache.insert("receipt", b"{}", None).unwrap();
+            cache.flush().unwrap();
+        }
+        let cache = DiskCache::open(&dir).unwrap();
+        assert_eq!(cache.get("receipt").map(|(data, _)| data), Some(b"{}".to_vec()));
+        drop(cache);
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
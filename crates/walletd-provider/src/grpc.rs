@@ -0,0 +1,298 @@
+//! gRPC transport
+//!
+//! Enabled with the `grpc` feature. A [`GrpcProvider`] runs tonic calls
+//! through the same endpoint selection, circuit breakers, concurrency limits,
+//! retries and statistics as [`HttpProvider`](crate::HttpProvider), so chains
+//! with gRPC APIs (e.g. Cosmos SDK) share the provider machinery.
+//!
+//! ```ignore
+//! let provider = pool.grpc("cosmoshub")?;
+//! let balance = provider
+//!     .call(|channel| async move {
+//!         QueryClient::new(channel).balance(request.clone()).await
+//!     })
+//!     .await?;
+//! ```
+
+use crate::{ManagedProvider, ProbeResult, ProviderConfig, ProviderError, ProviderPool, Result};
+use dashmap::DashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tonic::metadata::{AsciiMetadataKey, AsciiMetadataValue};
+use tonic::service::interceptor::InterceptedService;
+use tonic::service::Interceptor;
+use tonic::transport::{Channel, Endpoint};
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
+use walletd_resilience::{BackoffConfig, ExponentialBackoff};
+
+/// Channel handed to gRPC clients, with the endpoint's configured headers attached
+pub type GrpcChannel = InterceptedService<Channel, MetadataInjector>;
+
+/// Interceptor adding configured headers as gRPC metadata
+#[derive(Debug, Clone, Default)]
+pub struct MetadataInjector {
+    metadata: Arc<Vec<(AsciiMetadataKey, AsciiMetadataValue)>>,
+}
+
+impl MetadataInjector {
+    fn new(headers: &[(String, String)]) -> Self {
+        let metadata = headers
+            .iter()
+            .filter_map(|(name, value)| {
+                let key = AsciiMetadataKey::from_bytes(name.to_ascii_lowercase().as_bytes()).ok()?;
+                Some((key, value.parse().ok()?))
+            })
+            .collect();
+        Self {
+            metadata: Arc::new(metadata),
+        }
+    }
+}
+
+impl Interceptor for MetadataInjector {
+    fn call(&mut self, mut request: tonic::Request<()>) -> std::result::Result<tonic::Request<()>, tonic::Status> {
+        for (key, value) in self.metadata.iter() {
+            request.metadata_mut().insert(key.clone(), value.clone());
+        }
+        Ok(request)
+    }
+}
+
+impl From<tonic::Status> for ProviderError {
+    fn from(status: tonic::Status) -> Self {
+        ProviderError::Grpc {
+            code: status.code() as i32,
+            message: status.message().to_string(),
+        }
+    }
+}
+
+/// Provider that issues gRPC calls with failover and health tracking
+#[derive(Debug)]
+pub struct GrpcProvider {
+    managed: Arc<ManagedProvider>,
+    channels: DashMap<String, Channel>,
+}
+
+impl GrpcProvider {
+    /// Creates a new gRPC provider
+    pub fn new(config: ProviderConfig) -> Result<Self> {
+        Ok(Self::from_managed(Arc::new(ManagedProvider::new(config)?)))
+    }
+
+    fn from_managed(managed: Arc<ManagedProvider>) -> Self {
+        Self {
+            managed,
+            channels: DashMap::new(),
+        }
+    }
+
+    /// Returns a channel to `url`, connecting lazily on first use
+    pub fn channel(&self, url: &str) -> Result<GrpcChannel> {
+        let channel = match self.channels.get(url) {
+            Some(channel) => channel.clone(),
+            None => {
+                let timeout = Duration::from_secs(self.managed.config.timeout_secs);
+                let channel = Endpoint::from_shared(url.to_string())
+                    .map_err(|e| ProviderError::InvalidUrl(e.to_string()))?
+                    .timeout(timeout)
+                    .connect_timeout(timeout)
+                    .connect_lazy();
+                self.channels.insert(url.to_string(), channel.clone());
+                channel
+            }
+        };
+        let injector = MetadataInjector::new(&self.managed.config.headers_for(url));
+        Ok(InterceptedService::new(channel, injector))
+    }
+
+    /// Runs a gRPC call against the selected endpoint
+    ///
+    /// `call` receives a channel for the endpoint and builds its own client.
+    /// Transient failures (`UNAVAILABLE`, `DEADLINE_EXCEEDED`, ...) are retried
+    /// with backoff and may fail over to another endpoint.
+    pub async fn call<T, F, Fut>(&self, call: F) -> Result<T>
+    where
+        F: Fn(GrpcChannel) -> Fut,
+        Fut: Future<Output = std::result::Result<tonic::Response<T>, tonic::Status>>,
+    {
+        let config = &self.managed.config;
+        let mut backoff = ExponentialBackoff::new(
+            BackoffConfig::new()
+                .with_initial_delay(Duration::from_millis(config.retry_delay_ms))
+                .with_max_attempts(config.max_retries),
+        );
+
+        let mut url = self.managed.current_url().await;
+        self.managed.check_circuit(&url).await?;
+
+        loop {
+            let channel = self.channel(&url)?;
+            let permit = self.managed.acquire_slot(&url).await;
+            let start = Instant::now();
+            let outcome = call(channel).await;
+            drop(permit);
+            let error = match outcome {
+                Ok(response) => {
+                    let elapsed = start.elapsed().as_millis() as u64;
+                    self.managed.record_success_for(&url, elapsed).await;
+                    return Ok(response.into_inner());
+                }
+                Err(status) => ProviderError::from(status),
+            };
+            if error.is_endpoint_failure() {
+                self.managed.record_failure_for(&url).await;
+            }
+
+            if !error.is_retryable() {
+                return Err(error);
+            }
+            let Some(delay) = backoff.next() else {
+                return Err(error);
+            };
+
+            let next_url = self.managed.current_url().await;
+            if self.managed.check_circuit(&next_url).await.is_err() {
+                return Err(error);
+            }
+            if next_url == url {
+                tracing::debug!("Retrying gRPC call on {} in {:?}: {}", url, delay, error);
+                tokio::time::sleep(delay).await;
+            } else {
+                tracing::info!("Retrying gRPC call with failover endpoint: {}", next_url);
+            }
+            url = next_url;
+        }
+    }
+
+    /// Probes every endpoint with the standard `grpc.health.v1` check
+    ///
+    /// Servers that do not implement the health service but answer with
+    /// `UNIMPLEMENTED` are considered reachable and therefore healthy.
+    pub async fn check_health(&self) -> Vec<ProbeResult> {
+        let mut results = Vec::new();
+        for url in self.managed.config.all_urls() {
+            let start = Instant::now();
+            let error = match self.channel(url) {
+                Ok(channel) => {
+                    let request = HealthCheckRequest {
+                        service: String::new(),
+                    };
+                    match HealthClient::new(channel).check(request).await {
+                        Ok(response) if response.get_ref().status == ServingStatus::Serving as i32 => None,
+                        Ok(response) => Some(format!("status {}", response.get_ref().status)),
+                        Err(status) if status.code() == tonic::Code::Unimplemented => None,
+                        Err(status) => Some(status.to_string()),
+                    }
+                }
+                Err(e) => Some(e.to_string()),
+            };
+            let latency_ms = start.elapsed().as_millis() as u64;
+            match error {
+                None => self.managed.record_success_for(url, latency_ms).await,
+                Some(_) => self.managed.record_failure_for(url).await,
+            }
+            results.push(ProbeResult {
+                url: url.to_string(),
+                healthy: error.is_none(),
+                latency_ms,
+                error,
+            });
+        }
+        results
+    }
+
+    /// Returns endpoint statistics
+    pub async fn stats(&self) -> Vec<crate::EndpointInfo> {
+        self.managed.stats().await
+    }
+}
+
+impl ProviderPool {
+    /// Returns a gRPC handle to the provider registered as `name`
+    ///
+    /// The handle shares endpoint health and statistics with the pool.
+    pub fn grpc(&self, name: &str) -> Result<GrpcProvider> {
+        Ok(GrpcProvider::from_managed(self.get(name)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EndpointHealth;
+
+    async fn health_server(status: ServingStatus) -> String {
+        let (mut reporter, service) = tonic_health::server::health_reporter();
+        reporter.set_service_status("", match status {
+            ServingStatus::Serving => tonic_health::ServingStatus::Serving,
+            _ => tonic_health::ServingStatus::NotServing,
+        })
+        .await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            tonic::transport::Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming),
+        );
+        format!("http://{}", addr)
+    }
+
+    #[tokio::test]
+    async fn test_call_records_success() {
+        let url = health_server(ServingStatus::Serving).await;
+        let provider = GrpcProvider::new(ProviderConfig::new(&url).with_bearer_token("t")).unwrap();
+
+        let response = provider
+            .call(|channel| async move {
+                HealthClient::new(channel)
+                    .check(HealthCheckRequest { service: String::new() })
+                    .await
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.status, ServingStatus::Serving as i32);
+        assert_eq!(provider.stats().await[0].health, EndpointHealth::Healthy);
+    }
+
+    #[tokio::test]
+    async fn test_call_fails_over_to_live_endpoint() {
+        let live = health_server(ServingStatus::Serving).await;
+        let config = ProviderConfig::new("http://127.0.0.1:9")
+            .with_fallback(&live)
+            .with_retry_delay(1)
+            .with_timeout(2);
+        let provider = GrpcProvider::new(config).unwrap();
+
+        let response = provider
+            .call(|channel| async move {
+                HealthClient::new(channel)
+                    .check(HealthCheckRequest { service: String::new() })
+                    .await
+            })
+            .await
+            .unwrap();
+        assert_eq!(response.status, ServingStatus::Serving as i32);
+        let stats = provider.stats().await;
+        assert_eq!(stats[0].total_failures, 1);
+        assert_eq!(stats[1].total_failures, 0);
+    }
+
+    #[tokio::test]
+    async fn test_check_health() {
+        let serving = health_server(ServingStatus::Serving).await;
+        let not_serving = health_server(ServingStatus::NotServing).await;
+        let provider =
+            GrpcProvider::new(ProviderConfig::new(&serving).with_fallback(&not_serving)).unwrap();
+
+        let results = provider.check_health().await;
+        assert!(results[0].healthy);
+        assert!(!results[1].healthy);
+    }
+}
@@ -0,0 +1,289 @@
+//! Active endpoint health checking
+//!
+//! Periodically probes every endpoint of every provider in a [`ProviderPool`]
+//! with a cheap RPC call, so endpoint health reflects reality even when no
+//! traffic is flowing, and recovered endpoints are promoted back.
+
+use crate::{
+    CircuitState, EndpointHealth, ManagedProvider, ProviderPool, RpcClient, SelectionStrategy,
+};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::task::JoinHandle;
+
+/// Longest the background task sleeps, so newly added providers are picked up promptly
+const MAX_IDLE: Duration = Duration::from_secs(1);
+
+/// Result of probing a single endpoint
+#[derive(Debug, Clone)]
+pub struct ProbeResult {
+    /// The endpoint URL
+    pub url: String,
+    /// Whether the probe succeeded
+    pub healthy: bool,
+    /// Probe round-trip time in milliseconds
+    pub latency_ms: u64,
+    /// Error message if the probe failed
+    pub error: Option<String>,
+}
+
+impl ManagedProvider {
+    /// Probes every endpoint once with the configured health-check method
+    ///
+    /// Results are recorded against each endpoint's statistics. With the
+    /// [`SelectionStrategy::Failover`] strategy, the earliest healthy endpoint
+    /// is re-promoted if traffic had failed over past it.
+    pub async fn check_health(&self, client: &RpcClient) -> Vec<ProbeResult> {
+        let urls: Vec<String> = self.stats().await.into_iter().map(|e| e.url).collect();
+        let timeout = Duration::from_secs(self.config.timeout_secs);
+        let method = self.config.health_check_method.as_str();
+
+        let probes = urls.iter().map(|url| async move {
+            let headers = self.config.headers_for(url);
+            let start = Instant::now();
+            let outcome = tokio::time::timeout(
+                timeout,
+                client.rpc_call_with_headers::<_, serde_json::Value>(
+                    url,
+                    method,
+                    Vec::<()>::new(),
+                    &headers,
+                ),
+            )
+            .await;
+            let latency_ms = start.elapsed().as_millis() as u64;
+            let error = match outcome {
+                Ok(Ok(_)) => None,
+                Ok(Err(e)) => Some(e.to_string()),
+                Err(_) => Some(format!("Probe timed out after {}s", timeout.as_secs())),
+            };
+            ProbeResult {
+                url: url.clone(),
+                healthy: error.is_none(),
+                latency_ms,
+                error,
+            }
+        });
+        let results = futures_util::future::join_all(probes).await;
+
+        {
+            let mut endpoints = self.endpoints.write().await;
+            for (endpoint, result) in endpoints.iter_mut().zip(&results) {
+                if result.healthy {
+                    endpoint.record_success(result.latency_ms);
+                } else {
+                    tracing::debug!(
+                        "Health probe failed for {}: {}",
+                        result.url,
+                        result.error.as_deref().unwrap_or_default()
+                    );
+                    endpoint.record_failure();
+                }
+            }
+        }
+
+        if self.config.selection == SelectionStrategy::Failover {
+            self.promote_recovered().await;
+        }
+
+        results
+    }
+
+    /// Switches back to the earliest healthy endpoint ahead of the current one
+    async fn promote_recovered(&self) {
+        let mut idx = self.current_endpoint_idx.write().await;
+        let endpoints = self.endpoints.read().await;
+
+        if let Some(recovered) = endpoints[..*idx]
+            .iter()
+            .zip(&self.breakers)
+            .position(|(e, breaker)| {
+                e.health == EndpointHealth::Healthy && breaker.state() != CircuitState::Open
+            })
+        {
+            tracing::info!(
+                "Promoting recovered endpoint {} over {}",
+                endpoints[recovered].url,
+                endpoints[*idx].url
+            );
+            crate::telemetry::record_failover(
+                self.label(),
+                &endpoints[*idx].url,
+                &endpoints[recovered].url,
+            );
+            *idx = recovered;
+        }
+    }
+}
+
+impl ProviderPool {
+    /// Starts a background task that health-checks every provider
+    ///
+    /// Each provider is probed every `health_check_interval_secs` (a value of
+    /// 0 disables checks for that provider). The task stops when the returned
+    /// handle is dropped or [`HealthCheckHandle::stop`] is called.
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn start_health_checks(self: &Arc<Self>) -> HealthCheckHandle {
+        let pool = Arc::clone(self);
+        let task = tokio::spawn(async move {
+            let client = match RpcClient::new() {
+                Ok(client) => client,
+                Err(e) => {
+                    tracing::error!("Failed to create health-check client: {}", e);
+                    return;
+                }
+            };
+            let mut last_checked: HashMap<String, Instant> = HashMap::new();
+
+            loop {
+                let now = Instant::now();
+                let mut next_due = now + MAX_IDLE;
+                let mut due = Vec::new();
+
+                for entry in pool.providers.iter() {
+                    let interval = entry.value().config.health_check_interval_secs;
+                    if interval == 0 {
+                        continue;
+                    }
+                    let interval = Duration::from_secs(interval);
+                    match last_checked.get(entry.key()) {
+                        Some(last) if now.duration_since(*last) < interval => {
+                            next_due = next_due.min(*last + interval);
+                        }
+                        _ => due.push((entry.key().clone(), entry.value().clone())),
+                    }
+                }
+                last_checked.retain(|name, _| pool.providers.contains_key(name));
+
+                for (name, provider) in due {
+                    provider.check_health(&client).await;
+                    last_checked.insert(name, Instant::now());
+                }
+
+                tokio::time::sleep_until(next_due.into()).await;
+            }
+        });
+
+        HealthCheckHandle { task }
+    }
+}
+
+/// Handle to a running background health-check task
+///
+/// The task is stopped when the handle is dropped.
+#[derive(Debug)]
+pub struct HealthCheckHandle {
+    task: JoinHandle<()>,
+}
+
+impl HealthCheckHandle {
+    /// Stops the health-check task
+    pub fn stop(self) {
+        self.task.abort();
+    }
+
+    /// Returns true if the task is still running
+    pub fn is_running(&self) -> bool {
+        !self.task.is_finished()
+    }
+}
+
+impl Drop for HealthCheckHandle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    async fn healthy_server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"method": "web3_clientVersion"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "Geth/v1.13.0"
+            })))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    async fn failing_server() -> MockServer {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .mount(&server)
+            .await;
+        server
+    }
+
+    #[tokio::test]
+    async fn test_check_health_records_results() {
+        let good = healthy_server().await;
+        let bad = failing_server().await;
+        let config = ProviderConfig::new(good.uri()).with_fallback(bad.uri());
+        let provider = ManagedProvider::new(config).unwrap();
+
+        let results = provider.check_health(&RpcClient::new().unwrap()).await;
+        assert_eq!(results.len(), 2);
+        assert!(results[0].healthy);
+        assert!(!results[1].healthy);
+        assert!(results[1].error.is_some());
+
+        let stats = provider.stats().await;
+        assert_eq!(stats[0].health, EndpointHealth::Healthy);
+        assert_eq!(stats[1].health, EndpointHealth::Unhealthy);
+    }
+
+    #[tokio::test]
+    async fn test_check_health_promotes_recovered_primary() {
+        let primary = healthy_server().await;
+        let fallback = healthy_server().await;
+        let config = ProviderConfig::new(primary.uri())
+            .with_fallback(fallback.uri())
+            .with_selection(SelectionStrategy::Failover);
+        let provider = ManagedProvider::new(config).unwrap();
+
+        for _ in 0..5 {
+            provider.record_failure().await;
+        }
+        assert_eq!(provider.current_url().await, fallback.uri());
+
+        provider.check_health(&RpcClient::new().unwrap()).await;
+        assert_eq!(provider.current_url().await, primary.uri());
+    }
+
+    #[tokio::test]
+    async fn test_background_health_checks() {
+        let good = healthy_server().await;
+        let pool = Arc::new(ProviderPool::new());
+        pool.add("eth", ProviderConfig::new(good.uri())).unwrap();
+        pool.add("disabled", ProviderConfig::new(good.uri()).with_health_check_interval(0))
+            .unwrap();
+
+        let handle = pool.start_health_checks();
+        assert!(handle.is_running());
+
+        let provider = pool.get("eth").unwrap();
+        let mut checked = false;
+        for _ in 0..50 {
+            if provider.stats().await[0].total_requests > 0 {
+                checked = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        assert!(checked, "provider was never probed");
+        assert_eq!(provider.stats().await[0].health, EndpointHealth::Healthy);
+        assert_eq!(pool.get("disabled").unwrap().stats().await[0].total_requests, 0);
+
+        handle.stop();
+    }
+}
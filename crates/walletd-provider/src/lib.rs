@@ -9,9 +9,18 @@
 //! - Connection pooling with configurable limits
 //! - Automatic health checking and reconnection
 //! - Multiple endpoint support with failover
+//! - Typed per-chain handles (`pool.evm("base")`, `pool.solana()`)
+//! - Concurrent multi-chain balance snapshots ([`Portfolio`])
+//! - Per-endpoint circuit breakers
+//! - Archive/trace-aware routing
+//! - Latency/weight-based endpoint selection
 //! - Request rate limiting
-//! - Caching for common queries
+//! - Per-method response caching, optionally persisted to disk (`disk-cache` feature)
+//! - Request/response middleware
 //! - HTTP client with connection reuse
+//! - WebSocket transport with reconnect and subscriptions
+//! - gRPC transport sharing the same failover machinery (`grpc` feature)
+//! - Prometheus-style metrics (`metrics` feature)
 //!
 //! ## Example
 //!
@@ -35,14 +44,50 @@
 
 use dashmap::DashMap;
 use governor::{Quota, RateLimiter, clock::DefaultClock, state::{InMemoryState, NotKeyed}};
+use reqwest::header::{HeaderName, HeaderValue};
 use reqwest::Client;
 use serde::{de::DeserializeOwned, Serialize};
+use std::collections::{HashMap, HashSet};
 use std::num::NonZeroU32;
-use std::sync::Arc;
+use std::sync::atomic::AtomicU64;
+use std::sync::{Arc, OnceLock};
 use std::time::{Duration, Instant};
 use thiserror::Error;
-use tokio::sync::RwLock;
+use tokio::sync::{RwLock, Semaphore, SemaphorePermit};
 use url::Url;
+use walletd_error::WalletdError;
+use walletd_resilience::{
+    AdaptiveRateLimiter, BackoffConfig, CircuitBreaker, ExponentialBackoff, HttpRetryClassifier,
+    RpcRetryClassifier,
+};
+
+pub mod cache;
+pub mod chain;
+#[cfg(feature = "disk-cache")]
+pub mod disk_cache;
+#[cfg(feature = "grpc")]
+pub mod grpc;
+pub mod health;
+pub mod middleware;
+pub mod portfolio;
+pub mod routing;
+pub mod telemetry;
+pub mod ws;
+
+pub use cache::{cache_key, CachePolicy, CacheTtl};
+pub use chain::{Chain, ChainProvider, Evm, Solana};
+#[cfg(feature = "disk-cache")]
+pub use disk_cache::DiskCache;
+#[cfg(feature = "grpc")]
+pub use grpc::{GrpcChannel, GrpcProvider};
+pub use health::{HealthCheckHandle, ProbeResult};
+pub use middleware::{ErrorAction, LoggingMiddleware, Middleware, RpcRequest};
+pub use portfolio::{Portfolio, PortfolioSnapshot, TrackedWallet};
+pub use routing::EndpointCapability;
+#[cfg(feature = "metrics")]
+pub use telemetry::describe_metrics;
+pub use ws::{ConnectionState, Subscription, WsClient, WsConfig};
+pub use walletd_resilience::{CircuitBreakerConfig, CircuitState};
 
 /// Provider-related errors
 #[derive(Error, Debug)]
@@ -51,6 +96,10 @@ pub enum ProviderError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    /// Invalid provider configuration
+    #[error("Invalid configuration: {0}")]
+    InvalidConfig(String),
+
     /// Connection failed
     #[error("Connection failed: {0}")]
     ConnectionFailed(String),
@@ -63,22 +112,80 @@ pub enum ProviderError {
     #[error("Request timeout after {0}s")]
     Timeout(u64),
 
-    /// Rate limited
-    #[error("Rate limited")]
-    RateLimited,
+    /// Rate limited (HTTP 429)
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay requested by `Retry-After` or the provider's rate-limit headers
+        retry_after: Option<Duration>,
+    },
+
+    /// Response cache error
+    #[error("Cache error: {0}")]
+    Cache(String),
+
+    /// Invalid HTTP header name or value
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
 
     /// All endpoints failed
     #[error("All endpoints failed")]
     AllEndpointsFailed,
 
+    /// No endpoint offers a capability the request needs
+    #[error("No endpoint supports {method}: requires {capability:?} capability")]
+    NoCapableEndpoint {
+        /// RPC method
+        method: String,
+        /// Missing capability
+        capability: EndpointCapability,
+    },
+
+    /// Endpoint circuit breaker is open
+    #[error("Circuit open for {url}, retry after {retry_after:?}")]
+    CircuitOpen {
+        /// Endpoint URL
+        url: String,
+        /// Time until the breaker allows a trial request
+        retry_after: Duration,
+    },
+
     /// HTTP request error
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    /// Non-success HTTP status without a JSON-RPC error body
+    #[error("HTTP status {status}")]
+    HttpStatus {
+        /// Status code
+        status: u16,
+        /// Delay requested by a `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+
     /// JSON serialization error
     #[error("JSON error: {0}")]
     Json(#[from] serde_json::Error),
 
+    /// gRPC call failed
+    #[error("gRPC error: code={code}, message={message}")]
+    Grpc {
+        /// gRPC status code
+        code: i32,
+        /// Status message
+        message: String,
+    },
+
+    /// Response body exceeded the configured size limit
+    #[error("Response exceeds {limit} bytes")]
+    ResponseTooLarge {
+        /// Configured limit in bytes
+        limit: usize,
+    },
+
+    /// Response could not be interpreted
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
     /// RPC error response
     #[error("RPC error: code={code}, message={message}")]
     RpcError {
@@ -89,12 +196,125 @@ pub enum ProviderError {
     },
 }
 
+impl ProviderError {
+    /// Returns true if the request may succeed when retried
+    ///
+    /// Timeouts, connection failures, rate limiting (HTTP 429 or JSON-RPC
+    /// `-32005`) and transient HTTP or gRPC statuses are retryable. Other JSON-RPC
+    /// errors are not, since codes like `-32000` usually mean the request
+    /// itself was rejected.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            ProviderError::Timeout(_)
+            | ProviderError::RateLimited { .. }
+            | ProviderError::ConnectionFailed(_) => true,
+            ProviderError::Http(e) => e.is_timeout() || e.is_connect(),
+            ProviderError::HttpStatus { status, .. } => HttpRetryClassifier::is_status_retryable(*status),
+            ProviderError::RpcError { code, .. } => RpcRetryClassifier::is_rate_limited(*code),
+            // DEADLINE_EXCEEDED, RESOURCE_EXHAUSTED, ABORTED, UNAVAILABLE
+            ProviderError::Grpc { code, .. } => matches!(code, 4 | 8 | 10 | 14),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the error says the endpoint is unhealthy
+    ///
+    /// Only these count against an endpoint's circuit breaker: transport
+    /// errors, timeouts, HTTP 5xx, rate limits and unavailable gRPC statuses.
+    /// JSON-RPC errors such as `execution reverted` or `nonce too low` come
+    /// from a working node rejecting the request.
+    pub fn is_endpoint_failure(&self) -> bool {
+        match self {
+            ProviderError::Timeout(_)
+            | ProviderError::RateLimited { .. }
+            | ProviderError::ConnectionFailed(_)
+            | ProviderError::Http(_) => true,
+            ProviderError::HttpStatus { status, .. } => {
+                *status >= 500 || HttpRetryClassifier::is_rate_limited(*status)
+            }
+            ProviderError::RpcError { code, .. } => RpcRetryClassifier::is_rate_limited(*code),
+            ProviderError::Grpc { code, .. } => matches!(code, 4 | 8 | 10 | 14),
+            _ => false,
+        }
+    }
+
+    /// Returns true if the endpoint asked us to slow down
+    ///
+    /// Covers HTTP 429 and the JSON-RPC limit codes (`-32005`, and `429` as
+    /// sent by some providers).
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            ProviderError::RateLimited { .. } => true,
+            ProviderError::HttpStatus { status, .. } => HttpRetryClassifier::is_rate_limited(*status),
+            ProviderError::RpcError { code, .. } => RpcRetryClassifier::is_rate_limited(*code),
+            _ => false,
+        }
+    }
+
+    /// Returns the delay the server asked for before retrying, if any
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            ProviderError::RateLimited { retry_after } | ProviderError::HttpStatus { retry_after, .. } => {
+                *retry_after
+            }
+            ProviderError::CircuitOpen { retry_after, .. } => Some(*retry_after),
+            _ => None,
+        }
+    }
+}
+
+impl From<ProviderError> for WalletdError {
+    /// Rate limits keep their delay, rounded up to whole seconds (one second
+    /// when the provider gave none). JSON-RPC errors other than rate limits
+    /// become [`WalletdError::RpcRequestError`]; use [`walletd_error::rpc`]
+    /// for chain-specific codes.
+    fn from(e: ProviderError) -> Self {
+        if e.is_rate_limited() {
+            let retry_after_secs = e
+                .retry_after()
+                .map(|d| d.as_secs() + u64::from(d.subsec_nanos() > 0))
+                .unwrap_or(1);
+            return WalletdError::RateLimited { retry_after_secs };
+        }
+        match e {
+            ProviderError::Timeout(seconds) => WalletdError::NetworkTimeout { seconds },
+            ProviderError::CircuitOpen { ref url, .. } => WalletdError::RpcConnectionError {
+                url: url.clone(),
+                reason: e.to_string(),
+            },
+            ProviderError::ConnectionFailed(reason) => WalletdError::RpcConnectionError {
+                url: String::new(),
+                reason,
+            },
+            ProviderError::InvalidUrl(_)
+            | ProviderError::InvalidConfig(_)
+            | ProviderError::InvalidHeader(_)
+            | ProviderError::NotFound(_) => WalletdError::ConfigError(e.to_string()),
+            ProviderError::NoCapableEndpoint { .. } => WalletdError::NotSupported(e.to_string()),
+            ProviderError::Json(e) => WalletdError::JsonError(e.to_string()),
+            ProviderError::RpcError { code, message } => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason: format!("code {}: {}", code, message),
+            },
+            e if e.is_retryable() || matches!(e, ProviderError::AllEndpointsFailed) => {
+                WalletdError::NetworkError(e.to_string())
+            }
+            e => WalletdError::RpcRequestError {
+                method: String::new(),
+                reason: e.to_string(),
+            },
+        }
+    }
+}
+
 /// Result type for provider operations
 pub type Result<T> = std::result::Result<T, ProviderError>;
 
 /// Configuration for a provider endpoint
 #[derive(Debug, Clone)]
 pub struct ProviderConfig {
+    /// Provider name used to label metrics (set by [`ProviderPool::add`] if empty)
+    pub name: Option<String>,
     /// Primary RPC URL
     pub url: String,
     /// Fallback URLs
@@ -105,29 +325,77 @@ pub struct ProviderConfig {
     pub max_retries: u32,
     /// Retry delay in milliseconds
     pub retry_delay_ms: u64,
+    /// Longest server-requested retry delay to wait out, in milliseconds
+    pub max_retry_delay_ms: u64,
     /// Enable request caching
     pub enable_cache: bool,
-    /// Cache TTL in seconds
-    pub cache_ttl_secs: u64,
-    /// Health check interval in seconds
+    /// Per-method cache policy
+    pub cache_policy: CachePolicy,
+    /// Persistent cache layer behind the in-memory cache
+    #[cfg(feature = "disk-cache")]
+    pub disk_cache: Option<DiskCache>,
+    /// Health check interval in seconds (0 disables background checks)
     pub health_check_interval_secs: u64,
+    /// RPC method used to probe endpoint health
+    pub health_check_method: String,
+    /// Endpoint selection strategy
+    pub selection: SelectionStrategy,
+    /// Static endpoint weights keyed by URL (default weight is 1)
+    pub endpoint_weights: HashMap<String, u32>,
+    /// Seconds before an unhealthy endpoint is considered for traffic again
+    pub recovery_secs: u64,
+    /// Circuit breaker settings applied to each endpoint
+    pub circuit_breaker: CircuitBreakerConfig,
+    /// HTTP headers sent to every endpoint
+    pub headers: Vec<(String, String)>,
+    /// Per-endpoint HTTP headers keyed by URL, overriding same-named defaults
+    pub endpoint_headers: HashMap<String, Vec<(String, String)>>,
+    /// Maximum in-flight requests per endpoint (`None` is unlimited)
+    pub max_in_flight: Option<usize>,
+    /// Per-endpoint in-flight limits keyed by URL, overriding `max_in_flight`
+    pub endpoint_max_in_flight: HashMap<String, usize>,
+    /// Capabilities (archive, trace) offered by each endpoint, keyed by URL
+    pub endpoint_capabilities: HashMap<String, HashSet<EndpointCapability>>,
+    /// Maximum response body size in bytes (`None` is unlimited)
+    pub max_response_bytes: Option<usize>,
 }
 
 impl ProviderConfig {
     /// Creates a new provider configuration with the given URL
     pub fn new(url: impl Into<String>) -> Self {
         Self {
+            name: None,
             url: url.into(),
             fallback_urls: Vec::new(),
             timeout_secs: 30,
             max_retries: 3,
             retry_delay_ms: 1000,
+            max_retry_delay_ms: 30_000,
             enable_cache: true,
-            cache_ttl_secs: 10,
+            cache_policy: CachePolicy::default(),
+            #[cfg(feature = "disk-cache")]
+            disk_cache: None,
             health_check_interval_secs: 60,
+            health_check_method: "web3_clientVersion".to_string(),
+            selection: SelectionStrategy::default(),
+            endpoint_weights: HashMap::new(),
+            recovery_secs: 30,
+            circuit_breaker: CircuitBreakerConfig::default(),
+            headers: Vec::new(),
+            endpoint_headers: HashMap::new(),
+            max_in_flight: None,
+            endpoint_max_in_flight: HashMap::new(),
+            endpoint_capabilities: HashMap::new(),
+            max_response_bytes: None,
         }
     }
 
+    /// Sets the provider name used to label metrics
+    pub fn with_name(mut self, name: impl Into<String>) -> Self {
+        self.name = Some(name.into());
+        self
+    }
+
     /// Adds a fallback URL
     pub fn with_fallback(mut self, url: impl Into<String>) -> Self {
         self.fallback_urls.push(url.into());
@@ -152,24 +420,212 @@ impl ProviderConfig {
         self
     }
 
+    /// Sets the longest `Retry-After` delay to wait before retrying an endpoint
+    ///
+    /// Longer requests fail over to another endpoint, or return the
+    /// rate-limit error if there is none.
+    pub fn with_max_retry_delay(mut self, ms: u64) -> Self {
+        self.max_retry_delay_ms = ms;
+        self
+    }
+
     /// Enables or disables caching
     pub fn with_cache(mut self, enable: bool) -> Self {
         self.enable_cache = enable;
         self
     }
 
-    /// Sets the cache TTL
+    /// Sets the cache TTL for methods without an explicit policy entry
     pub fn with_cache_ttl(mut self, secs: u64) -> Self {
-        self.cache_ttl_secs = secs;
+        self.cache_policy.default_ttl = CacheTtl::secs(secs);
         self
     }
 
+    /// Sets the TTL for a specific RPC method
+    pub fn with_method_cache(mut self, method: impl Into<String>, ttl: CacheTtl) -> Self {
+        self.cache_policy.methods.insert(method.into(), ttl);
+        self
+    }
+
+    /// Replaces the per-method cache policy
+    pub fn with_cache_policy(mut self, policy: CachePolicy) -> Self {
+        self.cache_policy = policy;
+        self
+    }
+
+    /// Persists cached responses to a disk cache so they survive restarts
+    ///
+    /// Only entries cached for at least [`disk_cache::MIN_PERSIST_TTL`] are
+    /// written to disk.
+    #[cfg(feature = "disk-cache")]
+    pub fn with_disk_cache(mut self, cache: DiskCache) -> Self {
+        self.disk_cache = Some(cache);
+        self
+    }
+
+    /// Sets the background health check interval
+    pub fn with_health_check_interval(mut self, secs: u64) -> Self {
+        self.health_check_interval_secs = secs;
+        self
+    }
+
+    /// Sets the RPC method used to probe endpoint health
+    /// (e.g. `web3_clientVersion` for EVM chains, `getHealth` for Solana)
+    pub fn with_health_check_method(mut self, method: impl Into<String>) -> Self {
+        self.health_check_method = method.into();
+        self
+    }
+
+    /// Sets the endpoint selection strategy
+    pub fn with_selection(mut self, selection: SelectionStrategy) -> Self {
+        self.selection = selection;
+        self
+    }
+
+    /// Sets a static weight for an endpoint
+    ///
+    /// Weights scale an endpoint's score, so an endpoint with weight 2 is
+    /// preferred over an equally fast and reliable endpoint with weight 1.
+    pub fn with_endpoint_weight(mut self, url: impl Into<String>, weight: u32) -> Self {
+        self.endpoint_weights.insert(url.into(), weight);
+        self
+    }
+
+    /// Sets how long an unhealthy endpoint is skipped before being retried
+    pub fn with_recovery_period(mut self, secs: u64) -> Self {
+        self.recovery_secs = secs;
+        self
+    }
+
+    /// Sets the circuit breaker settings applied to each endpoint
+    ///
+    /// The breaker name is replaced by the endpoint URL.
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = config;
+        self
+    }
+
+    /// Adds an HTTP header sent to every endpoint (e.g. an API key header)
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends `Authorization: Bearer <token>` to every endpoint
+    pub fn with_bearer_token(self, token: impl AsRef<str>) -> Self {
+        let value = format!("Bearer {}", token.as_ref());
+        self.with_header("Authorization", value)
+    }
+
+    /// Sends HTTP basic auth credentials to every endpoint
+    pub fn with_basic_auth(self, username: impl AsRef<str>, password: impl AsRef<str>) -> Self {
+        let value = basic_auth_value(username.as_ref(), password.as_ref());
+        self.with_header("Authorization", value)
+    }
+
+    /// Adds an HTTP header sent only to `url`, overriding a same-named default
+    pub fn with_endpoint_header(
+        mut self,
+        url: impl Into<String>,
+        name: impl Into<String>,
+        value: impl Into<String>,
+    ) -> Self {
+        self.endpoint_headers
+            .entry(url.into())
+            .or_default()
+            .push((name.into(), value.into()));
+        self
+    }
+
+    /// Sends `Authorization: Bearer <token>` only to `url`
+    pub fn with_endpoint_bearer_token(self, url: impl Into<String>, token: impl AsRef<str>) -> Self {
+        let value = format!("Bearer {}", token.as_ref());
+        self.with_endpoint_header(url, "Authorization", value)
+    }
+
+    /// Sends HTTP basic auth credentials only to `url`
+    pub fn with_endpoint_basic_auth(
+        self,
+        url: impl Into<String>,
+        username: impl AsRef<str>,
+        password: impl AsRef<str>,
+    ) -> Self {
+        let value = basic_auth_value(username.as_ref(), password.as_ref());
+        self.with_endpoint_header(url, "Authorization", value)
+    }
+
+    /// Limits how many requests may be in flight to each endpoint at once
+    ///
+    /// Further requests wait for a slot, so bursts cannot saturate a
+    /// rate-limited RPC.
+    pub fn with_max_in_flight(mut self, limit: usize) -> Self {
+        self.max_in_flight = Some(limit);
+        self
+    }
+
+    /// Limits in-flight requests to a specific endpoint
+    pub fn with_endpoint_max_in_flight(mut self, url: impl Into<String>, limit: usize) -> Self {
+        self.endpoint_max_in_flight.insert(url.into(), limit);
+        self
+    }
+
+    /// Tags an endpoint with a capability such as archive state or tracing
+    ///
+    /// Requests that need the capability are only routed to tagged endpoints.
+    pub fn with_endpoint_capability(
+        mut self,
+        url: impl Into<String>,
+        capability: EndpointCapability,
+    ) -> Self {
+        self.endpoint_capabilities
+            .entry(url.into())
+            .or_default()
+            .insert(capability);
+        self
+    }
+
+    /// Rejects responses larger than `bytes`
+    pub fn with_max_response_size(mut self, bytes: usize) -> Self {
+        self.max_response_bytes = Some(bytes);
+        self
+    }
+
+    /// Returns the in-flight request limit for `url`
+    pub fn max_in_flight_for(&self, url: &str) -> Option<usize> {
+        self.endpoint_max_in_flight.get(url).copied().or(self.max_in_flight)
+    }
+
+    /// Returns the headers to send to `url`
+    ///
+    /// Per-endpoint headers replace default headers with the same name
+    /// (compared case-insensitively).
+    pub fn headers_for(&self, url: &str) -> Vec<(String, String)> {
+        let overrides = self.endpoint_headers.get(url).map(Vec::as_slice).unwrap_or_default();
+        self.headers
+            .iter()
+            .filter(|(name, _)| !overrides.iter().any(|(o, _)| o.eq_ignore_ascii_case(name)))
+            .chain(overrides)
+            .cloned()
+            .collect()
+    }
+
     /// Validates the configuration
     pub fn validate(&self) -> Result<()> {
         Url::parse(&self.url).map_err(|e| ProviderError::InvalidUrl(e.to_string()))?;
         for url in &self.fallback_urls {
             Url::parse(url).map_err(|e| ProviderError::InvalidUrl(e.to_string()))?;
         }
+        if self.max_in_flight == Some(0) || self.endpoint_max_in_flight.values().any(|&n| n == 0) {
+            return Err(ProviderError::InvalidConfig(
+                "in-flight limit must be at least 1".to_string(),
+            ));
+        }
+        for (name, value) in self.headers.iter().chain(self.endpoint_headers.values().flatten()) {
+            HeaderName::from_bytes(name.as_bytes())
+                .map_err(|_| ProviderError::InvalidHeader(name.clone()))?;
+            HeaderValue::from_str(value)
+                .map_err(|_| ProviderError::InvalidHeader(format!("invalid value for {}", name)))?;
+        }
         Ok(())
     }
 
@@ -181,12 +637,41 @@ impl ProviderConfig {
     }
 }
 
+/// Builds a `Basic` authorization header value
+fn basic_auth_value(username: &str, password: &str) -> String {
+    use base64::Engine;
+    let credentials = format!("{}:{}", username, password);
+    format!("Basic {}", base64::engine::general_purpose::STANDARD.encode(credentials))
+}
+
 impl Default for ProviderConfig {
     fn default() -> Self {
         Self::new("http://localhost:8545")
     }
 }
 
+/// How a [`ManagedProvider`] chooses which endpoint to send requests to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionStrategy {
+    /// Stay on the current endpoint and rotate to the next non-unhealthy one on failure
+    Failover,
+    /// Prefer the best-scoring endpoint by EWMA latency, success rate and weight
+    ///
+    /// Ties (within a small margin) go to the endpoint listed first, so traffic
+    /// returns to the primary once it has recovered.
+    #[default]
+    Scored,
+}
+
+/// Smoothing factor for latency and success EWMAs
+const EWMA_ALPHA: f64 = 0.3;
+
+/// Latency assumed for endpoints that have not served a request yet
+const UNMEASURED_LATENCY_MS: f64 = 500.0;
+
+/// Relative score difference within which earlier-listed endpoints win
+const SELECTION_MARGIN: f64 = 0.2;
+
 /// Health status of an endpoint
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum EndpointHealth {
@@ -217,6 +702,16 @@ pub struct EndpointInfo {
     pub total_failures: u64,
     /// Average response time in milliseconds
     pub avg_response_ms: u64,
+    /// Exponentially weighted moving average of response time in milliseconds
+    pub ewma_latency_ms: f64,
+    /// Exponentially weighted moving average of request success (0.0 - 1.0)
+    pub ewma_success: f64,
+    /// Static selection weight
+    pub weight: u32,
+    /// State of the endpoint's circuit breaker
+    pub circuit_state: CircuitState,
+    /// Requests currently in flight (only tracked for endpoints with a limit)
+    pub in_flight: usize,
 }
 
 impl EndpointInfo {
@@ -229,12 +724,23 @@ impl EndpointInfo {
             total_requests: 0,
             total_failures: 0,
             avg_response_ms: 0,
+            ewma_latency_ms: 0.0,
+            ewma_success: 1.0,
+            weight: 1,
+            circuit_state: CircuitState::Closed,
+            in_flight: 0,
         }
     }
 
     fn record_success(&mut self, response_time_ms: u64) {
         self.last_success = Some(Instant::now());
         self.total_requests += 1;
+        self.ewma_latency_ms = if self.ewma_latency_ms == 0.0 {
+            response_time_ms as f64
+        } else {
+            EWMA_ALPHA * response_time_ms as f64 + (1.0 - EWMA_ALPHA) * self.ewma_latency_ms
+        };
+        self.ewma_success = EWMA_ALPHA + (1.0 - EWMA_ALPHA) * self.ewma_success;
         // Update rolling average
         self.avg_response_ms = (self.avg_response_ms * (self.total_requests - 1) + response_time_ms)
             / self.total_requests;
@@ -249,7 +755,8 @@ impl EndpointInfo {
         self.last_failure = Some(Instant::now());
         self.total_requests += 1;
         self.total_failures += 1;
-        
+        self.ewma_success *= 1.0 - EWMA_ALPHA;
+
         // Mark unhealthy if failure rate > 50%
         let failure_rate = self.total_failures as f64 / self.total_requests as f64;
         if failure_rate > 0.5 {
@@ -267,6 +774,38 @@ impl EndpointInfo {
             1.0 - (self.total_failures as f64 / self.total_requests as f64)
         }
     }
+
+    /// Returns the selection score for this endpoint, or `None` if it should
+    /// not receive traffic right now
+    ///
+    /// The score is `weight * success / latency`. Unhealthy endpoints are
+    /// excluded until `recovery` has passed since their last failure, and
+    /// failures are forgotten over the same period so a recovered endpoint
+    /// can win traffic back.
+    pub fn score(&self, recovery: Duration) -> Option<f64> {
+        let since_failure = self.last_failure.map(|t| t.elapsed());
+        if self.health == EndpointHealth::Unhealthy
+            && since_failure.is_some_and(|elapsed| elapsed < recovery)
+        {
+            return None;
+        }
+
+        let decay = match since_failure {
+            Some(elapsed) if !recovery.is_zero() => {
+                (-elapsed.as_secs_f64() / recovery.as_secs_f64()).exp()
+            }
+            Some(_) => 0.0,
+            None => 1.0,
+        };
+        let success = 1.0 - (1.0 - self.ewma_success) * decay;
+        let latency = if self.ewma_latency_ms > 0.0 {
+            self.ewma_latency_ms
+        } else {
+            UNMEASURED_LATENCY_MS
+        };
+
+        Some(self.weight as f64 * success.max(0.01) / (latency + 1.0))
+    }
 }
 
 /// A cached response
@@ -274,12 +813,26 @@ impl EndpointInfo {
 struct CachedResponse {
     data: Vec<u8>,
     cached_at: Instant,
-    ttl: Duration,
+    /// `None` means the entry never expires
+    ttl: Option<Duration>,
 }
 
 impl CachedResponse {
     fn is_valid(&self) -> bool {
-        self.cached_at.elapsed() < self.ttl
+        self.ttl.is_none_or(|ttl| self.cached_at.elapsed() < ttl)
+    }
+}
+
+/// Semaphore bounding in-flight requests to one endpoint
+#[derive(Debug)]
+struct ConcurrencyLimit {
+    semaphore: Semaphore,
+    limit: usize,
+}
+
+impl ConcurrencyLimit {
+    fn in_flight(&self) -> usize {
+        self.limit - self.semaphore.available_permits()
     }
 }
 
@@ -288,6 +841,12 @@ impl CachedResponse {
 pub struct ManagedProvider {
     config: ProviderConfig,
     endpoints: RwLock<Vec<EndpointInfo>>,
+    /// One breaker per endpoint, in the same order as `endpoints`
+    breakers: Vec<CircuitBreaker>,
+    /// In-flight limits per endpoint, in the same order as `endpoints`
+    limits: Vec<Option<ConcurrencyLimit>>,
+    /// Latest observed block number (0 if unknown)
+    latest_block: AtomicU64,
     cache: DashMap<String, CachedResponse>,
     current_endpoint_idx: RwLock<usize>,
 }
@@ -297,32 +856,158 @@ impl ManagedProvider {
     pub fn new(config: ProviderConfig) -> Result<Self> {
         config.validate()?;
         
-        let mut endpoints = vec![EndpointInfo::new(config.url.clone())];
-        for url in &config.fallback_urls {
-            endpoints.push(EndpointInfo::new(url.clone()));
-        }
+        let endpoints = config
+            .all_urls()
+            .into_iter()
+            .map(|url| {
+                let mut endpoint = EndpointInfo::new(url.to_string());
+                if let Some(weight) = config.endpoint_weights.get(url) {
+                    endpoint.weight = *weight;
+                }
+                endpoint
+            })
+            .collect();
+        let breakers = config
+            .all_urls()
+            .into_iter()
+            .map(|url| {
+                let mut breaker_config = config.circuit_breaker.clone();
+                breaker_config.name = url.to_string();
+                CircuitBreaker::new(breaker_config)
+            })
+            .collect();
+        let limits = config
+            .all_urls()
+            .into_iter()
+            .map(|url| {
+                config.max_in_flight_for(url).map(|limit| ConcurrencyLimit {
+                    semaphore: Semaphore::new(limit),
+                    limit,
+                })
+            })
+            .collect();
 
         Ok(Self {
             config,
             endpoints: RwLock::new(endpoints),
+            breakers,
+            limits,
+            latest_block: AtomicU64::new(0),
             cache: DashMap::new(),
             current_endpoint_idx: RwLock::new(0),
         })
     }
 
     /// Returns the current active endpoint URL
+    ///
+    /// With [`SelectionStrategy::Scored`] this re-evaluates endpoint scores and
+    /// may switch to a better endpoint. With either strategy, endpoints whose
+    /// circuit breaker is open are skipped while another endpoint is available.
     pub async fn current_url(&self) -> String {
+        match self.config.selection {
+            SelectionStrategy::Scored => self.select_endpoint().await,
+            SelectionStrategy::Failover => self.skip_open_circuit().await,
+        }
         let idx = *self.current_endpoint_idx.read().await;
         let endpoints = self.endpoints.read().await;
         endpoints.get(idx).map(|e| e.url.clone()).unwrap_or_else(|| self.config.url.clone())
     }
 
+    /// Picks the best-scoring endpoint and makes it current
+    async fn select_endpoint(&self) {
+        let mut idx = self.current_endpoint_idx.write().await;
+        let endpoints = self.endpoints.read().await;
+        let recovery = Duration::from_secs(self.config.recovery_secs);
+
+        let mut best: Option<(usize, f64)> = None;
+        for (i, endpoint) in endpoints.iter().enumerate() {
+            let Some(score) = endpoint.score(recovery) else {
+                continue;
+            };
+            if self.breakers[i].can_execute().await.is_err() {
+                continue;
+            }
+            // Earlier endpoints win within the margin, so the order is stable
+            // and traffic returns to the primary when it is comparable
+            match best {
+                Some((_, best_score)) if score <= best_score * (1.0 + SELECTION_MARGIN) => {}
+                _ => best = Some((i, score)),
+            }
+        }
+
+        if let Some((best_idx, _)) = best {
+            if best_idx != *idx {
+                tracing::info!(
+                    "Switching endpoint from {} to {}",
+                    endpoints[*idx].url,
+                    endpoints[best_idx].url
+                );
+                telemetry::record_failover(
+                    self.label(),
+                    &endpoints[*idx].url,
+                    &endpoints[best_idx].url,
+                );
+                *idx = best_idx;
+            }
+        }
+    }
+
+    /// Moves off the current endpoint if its circuit breaker is open
+    async fn skip_open_circuit(&self) {
+        let mut idx = self.current_endpoint_idx.write().await;
+        if self.breakers[*idx].can_execute().await.is_ok() {
+            return;
+        }
+
+        let urls = self.config.all_urls();
+        for i in 1..urls.len() {
+            let next_idx = (*idx + i) % urls.len();
+            if self.breakers[next_idx].can_execute().await.is_ok() {
+                tracing::info!("Circuit open for {}, switching to {}", urls[*idx], urls[next_idx]);
+                telemetry::record_failover(self.label(), urls[*idx], urls[next_idx]);
+                *idx = next_idx;
+                return;
+            }
+        }
+    }
+
+    /// Waits for an in-flight slot on `url`
+    ///
+    /// Returns `None` if the endpoint has no limit. The slot is released when
+    /// the returned permit is dropped.
+    pub async fn acquire_slot(&self, url: &str) -> Option<SemaphorePermit<'_>> {
+        let idx = self.config.all_urls().iter().position(|u| *u == url)?;
+        let limit = self.limits[idx].as_ref()?;
+        // The semaphore is never closed, so acquire cannot fail
+        limit.semaphore.acquire().await.ok()
+    }
+
+    /// Returns an error if the circuit breaker for `url` is open
+    pub async fn check_circuit(&self, url: &str) -> Result<()> {
+        let Some(idx) = self.config.all_urls().iter().position(|u| *u == url) else {
+            return Ok(());
+        };
+        self.breakers[idx]
+            .can_execute()
+            .await
+            .map_err(|e| ProviderError::CircuitOpen {
+                url: url.to_string(),
+                retry_after: e.retry_after,
+            })
+    }
+
     /// Records a successful request
     pub async fn record_success(&self, response_time_ms: u64) {
         let idx = *self.current_endpoint_idx.read().await;
         let mut endpoints = self.endpoints.write().await;
         if let Some(endpoint) = endpoints.get_mut(idx) {
             endpoint.record_success(response_time_ms);
+            self.breakers[idx].record_success().await;
+            telemetry::record_success(
+                self.label(),
+                &endpoint.url,
+                Duration::from_millis(response_time_ms),
+            );
         }
     }
 
@@ -333,27 +1018,83 @@ impl ManagedProvider {
         
         if let Some(endpoint) = endpoints.get_mut(*idx) {
             endpoint.record_failure();
+            self.breakers[*idx].record_failure().await;
+            telemetry::record_failure(self.label(), &endpoint.url);
+        }
+
+        if self.config.selection == SelectionStrategy::Scored {
+            return;
         }
 
         // Try to failover to next healthy endpoint
         let num_endpoints = endpoints.len();
         for i in 1..num_endpoints {
             let next_idx = (*idx + i) % num_endpoints;
-            if endpoints[next_idx].health != EndpointHealth::Unhealthy {
+            if endpoints[next_idx].health != EndpointHealth::Unhealthy
+                && self.breakers[next_idx].state() != CircuitState::Open
+            {
                 tracing::info!(
                     "Failing over from {} to {}",
                     endpoints[*idx].url,
                     endpoints[next_idx].url
                 );
+                telemetry::record_failover(
+                    self.label(),
+                    &endpoints[*idx].url,
+                    &endpoints[next_idx].url,
+                );
                 *idx = next_idx;
                 return;
             }
         }
     }
 
+    /// Records a successful request to `url`, which need not be the current endpoint
+    pub(crate) async fn record_success_for(&self, url: &str, response_time_ms: u64) {
+        if self.current_url_unchanged(url).await {
+            return self.record_success(response_time_ms).await;
+        }
+        let Some(idx) = self.config.all_urls().iter().position(|u| *u == url) else {
+            return;
+        };
+        self.endpoints.write().await[idx].record_success(response_time_ms);
+        self.breakers[idx].record_success().await;
+        telemetry::record_success(self.label(), url, Duration::from_millis(response_time_ms));
+    }
+
+    /// Records a failed request to `url`; only failures on the current
+    /// endpoint trigger failover
+    pub(crate) async fn record_failure_for(&self, url: &str) {
+        if self.current_url_unchanged(url).await {
+            return self.record_failure().await;
+        }
+        let Some(idx) = self.config.all_urls().iter().position(|u| *u == url) else {
+            return;
+        };
+        self.endpoints.write().await[idx].record_failure();
+        self.breakers[idx].record_failure().await;
+        telemetry::record_failure(self.label(), url);
+    }
+
+    /// Returns true if `url` is the current endpoint, without re-running selection
+    async fn current_url_unchanged(&self, url: &str) -> bool {
+        let idx = *self.current_endpoint_idx.read().await;
+        self.config.all_urls().get(idx) == Some(&url)
+    }
+
+    /// Returns the name used to label this provider's metrics
+    pub(crate) fn label(&self) -> &str {
+        self.config.name.as_deref().unwrap_or(&self.config.url)
+    }
+
     /// Returns endpoint statistics
     pub async fn stats(&self) -> Vec<EndpointInfo> {
-        self.endpoints.read().await.clone()
+        let mut endpoints = self.endpoints.read().await.clone();
+        for ((endpoint, breaker), limit) in endpoints.iter_mut().zip(&self.breakers).zip(&self.limits) {
+            endpoint.circuit_state = breaker.state();
+            endpoint.in_flight = limit.as_ref().map_or(0, ConcurrencyLimit::in_flight);
+        }
+        endpoints
     }
 
     /// Gets a cached response if valid
@@ -361,27 +1102,77 @@ impl ManagedProvider {
         if !self.config.enable_cache {
             return None;
         }
-        self.cache.get(key).and_then(|entry| {
+        let cached = self.cache.get(key).and_then(|entry| {
             if entry.is_valid() {
                 Some(entry.data.clone())
             } else {
                 None
             }
-        })
+        });
+
+        #[cfg(feature = "disk-cache")]
+        if cached.is_none() {
+            let (data, ttl) = self.config.disk_cache.as_ref()?.get(key)?;
+            self.cache.insert(key.to_string(), CachedResponse {
+                data: data.clone(),
+                cached_at: Instant::now(),
+                ttl,
+            });
+            return Some(data);
+        }
+
+        cached
     }
 
-    /// Caches a response
+    /// Caches a response using the policy's default TTL
     pub fn cache_response(&self, key: String, data: Vec<u8>) {
-        if !self.config.enable_cache {
+        self.cache_with_ttl(key, data, self.config.cache_policy.default_ttl);
+    }
+
+    /// Caches a response with an explicit TTL
+    pub fn cache_with_ttl(&self, key: String, data: Vec<u8>, ttl: CacheTtl) {
+        if !self.config.enable_cache || ttl == CacheTtl::Never {
             return;
         }
+
+        #[cfg(feature = "disk-cache")]
+        if let Some(disk) = &self.config.disk_cache {
+            if let Err(e) = disk.insert(&key, &data, ttl.duration()) {
+                tracing::warn!("Failed to persist cache entry {}: {}", key, e);
+            }
+        }
+
         self.cache.insert(key, CachedResponse {
             data,
             cached_at: Instant::now(),
-            ttl: Duration::from_secs(self.config.cache_ttl_secs),
+            ttl: ttl.duration(),
         });
     }
 
+    /// Gets a cached RPC result for `method` called with `params`
+    ///
+    /// Always misses for methods the cache policy never caches.
+    pub fn get_cached_call<P: Serialize + ?Sized>(&self, method: &str, params: &P) -> Option<Vec<u8>> {
+        if !self.config.enable_cache || self.config.cache_policy.ttl_for(method) == CacheTtl::Never {
+            return None;
+        }
+        let cached = self.get_cached(&cache_key(method, params).ok()?);
+        telemetry::record_cache_lookup(self.label(), cached.is_some());
+        cached
+    }
+
+    /// Caches an RPC result for `method` called with `params`, using the
+    /// method's TTL from the cache policy
+    pub fn cache_call<P: Serialize + ?Sized>(&self, method: &str, params: &P, data: Vec<u8>) {
+        let ttl = self.config.cache_policy.ttl_for(method);
+        if ttl == CacheTtl::Never {
+            return;
+        }
+        if let Ok(key) = cache_key(method, params) {
+            self.cache_with_ttl(key, data, ttl);
+        }
+    }
+
     /// Clears expired cache entries
     pub fn clear_expired_cache(&self) {
         self.cache.retain(|_, v| v.is_valid());
@@ -407,6 +1198,10 @@ pub struct HttpClientConfig {
     pub user_agent: String,
     /// Enable gzip compression
     pub gzip: bool,
+    /// Maximum response body size in bytes (`None` is unlimited)
+    ///
+    /// Applies to buffered responses; [`RpcClient::get_stream`] is not limited.
+    pub max_response_bytes: Option<usize>,
 }
 
 impl Default for HttpClientConfig {
@@ -418,6 +1213,7 @@ impl Default for HttpClientConfig {
             request_timeout_secs: 30,
             user_agent: format!("WalletD/{}", env!("CARGO_PKG_VERSION")),
             gzip: true,
+            max_response_bytes: None,
         }
     }
 }
@@ -489,11 +1285,20 @@ pub struct JsonRpcError {
     pub data: Option<serde_json::Value>,
 }
 
+/// Request pacing used by [`RpcClient`]
+enum Limiter {
+    /// Static governor quota
+    Fixed(RateLimiter<NotKeyed, InMemoryState, DefaultClock>),
+    /// AIMD limiter fed by JSON-RPC responses
+    Adaptive(Arc<AdaptiveRateLimiter>),
+}
+
 /// HTTP client with connection pooling and rate limiting
 pub struct RpcClient {
     client: Client,
-    rate_limiter: Option<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    rate_limiter: Option<Limiter>,
     request_id: std::sync::atomic::AtomicU64,
+    max_response_bytes: Option<usize>,
 }
 
 impl RpcClient {
@@ -507,6 +1312,34 @@ impl RpcClient {
         http_config: HttpClientConfig,
         rate_limit: Option<RateLimitConfig>,
     ) -> Result<Self> {
+        let rate_limiter = rate_limit
+            .map(|config| -> Result<Limiter> {
+                let per_second = NonZeroU32::new(config.requests_per_second).ok_or_else(|| {
+                    ProviderError::InvalidConfig("requests_per_second must be non-zero".into())
+                })?;
+                let burst = NonZeroU32::new(config.burst_size).ok_or_else(|| {
+                    ProviderError::InvalidConfig("burst_size must be non-zero".into())
+                })?;
+                let quota = Quota::per_second(per_second).allow_burst(burst);
+                Ok(Limiter::Fixed(RateLimiter::direct(quota)))
+            })
+            .transpose()?;
+        Self::build(http_config, rate_limiter)
+    }
+
+    /// Creates a new RPC client paced by an adaptive (AIMD) rate limiter
+    ///
+    /// The limiter speeds up while JSON-RPC calls succeed and backs off when
+    /// the endpoint answers with HTTP 429 or `-32005`. Share one limiter
+    /// between clients that hit the same endpoint.
+    pub fn with_adaptive_rate_limit(
+        http_config: HttpClientConfig,
+        limiter: Arc<AdaptiveRateLimiter>,
+    ) -> Result<Self> {
+        Self::build(http_config, Some(Limiter::Adaptive(limiter)))
+    }
+
+    fn build(http_config: HttpClientConfig, rate_limiter: Option<Limiter>) -> Result<Self> {
         let client = Client::builder()
             .pool_max_idle_per_host(http_config.pool_max_idle_per_host)
             .pool_idle_timeout(Duration::from_secs(http_config.pool_idle_timeout_secs))
@@ -517,16 +1350,11 @@ impl RpcClient {
             .build()
             .map_err(|e: reqwest::Error| ProviderError::ConnectionFailed(e.to_string()))?;
 
-        let rate_limiter = rate_limit.map(|config| {
-            let quota = Quota::per_second(NonZeroU32::new(config.requests_per_second).unwrap())
-                .allow_burst(NonZeroU32::new(config.burst_size).unwrap());
-            RateLimiter::direct(quota)
-        });
-
         Ok(Self {
             client,
             rate_limiter,
             request_id: std::sync::atomic::AtomicU64::new(1),
+            max_response_bytes: http_config.max_response_bytes,
         })
     }
 
@@ -536,21 +1364,82 @@ impl RpcClient {
         P: Serialize,
         R: DeserializeOwned,
     {
-        // Check rate limit
-        if let Some(limiter) = &self.rate_limiter {
-            limiter.until_ready().await;
+        self.rpc_call_with_headers(url, method, params, &[]).await
+    }
+
+    /// Makes a JSON-RPC request with extra HTTP headers
+    pub async fn rpc_call_with_headers<P, R>(
+        &self,
+        url: &str,
+        method: &str,
+        params: P,
+        headers: &[(String, String)],
+    ) -> Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        self.wait_for_rate_limit().await;
+        let result = self.send_rpc(url, method, params, headers).await;
+
+        if let Some(Limiter::Adaptive(limiter)) = &self.rate_limiter {
+            match &result {
+                Ok(_) => limiter.on_success(),
+                Err(e) if e.is_rate_limited() => limiter.on_throttled(),
+                Err(_) => {}
+            }
         }
+        result
+    }
 
+    async fn send_rpc<P, R>(
+        &self,
+        url: &str,
+        method: &str,
+        params: P,
+        headers: &[(String, String)],
+    ) -> Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
         let id = self.request_id.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
         let request = JsonRpcRequest::new(method, params, id);
 
-        let response = self.client
-            .post(url)
-            .json(&request)
-            .send()
-            .await?;
+        let mut builder = self.client.post(url).json(&request);
+        for (name, value) in headers {
+            builder = builder.header(name.as_str(), value.as_str());
+        }
+        let response = builder.send().await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let retry_after = HttpRetryClassifier::retry_after_from_headers(
+                response
+                    .headers()
+                    .iter()
+                    .filter_map(|(name, value)| Some((name.as_str(), value.to_str().ok()?))),
+            );
+            if HttpRetryClassifier::is_rate_limited(status.as_u16()) {
+                return Err(ProviderError::RateLimited { retry_after });
+            }
+            // Some nodes report JSON-RPC errors with a non-2xx status
+            let body = self.read_body(response).await?;
+            if let Ok(JsonRpcResponse::<serde_json::Value> { error: Some(error), .. }) =
+                serde_json::from_slice(&body)
+            {
+                return Err(ProviderError::RpcError {
+                    code: error.code,
+                    message: error.message,
+                });
+            }
+            return Err(ProviderError::HttpStatus {
+                status: status.as_u16(),
+                retry_after,
+            });
+        }
 
-        let rpc_response: JsonRpcResponse<R> = response.json().await?;
+        let rpc_response: JsonRpcResponse<R> = serde_json::from_slice(&self.read_body(response).await?)?;
 
         if let Some(error) = rpc_response.error {
             return Err(ProviderError::RpcError {
@@ -571,10 +1460,7 @@ impl RpcClient {
         url: &str,
         body: impl Serialize,
     ) -> Result<T> {
-        // Check rate limit
-        if let Some(limiter) = &self.rate_limiter {
-            limiter.until_ready().await;
-        }
+        self.wait_for_rate_limit().await;
 
         let response = self.client
             .post(url)
@@ -582,32 +1468,64 @@ impl RpcClient {
             .send()
             .await?;
 
-        let result: T = response.json().await?;
+        let result: T = serde_json::from_slice(&self.read_body(response).await?)?;
         Ok(result)
     }
 
     /// Makes a GET request
     pub async fn get<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
-        // Check rate limit
-        if let Some(limiter) = &self.rate_limiter {
-            limiter.until_ready().await;
-        }
+        self.wait_for_rate_limit().await;
 
         let response = self.client.get(url).send().await?;
-        let result: T = response.json().await?;
+        let result: T = serde_json::from_slice(&self.read_body(response).await?)?;
         Ok(result)
     }
 
     /// Makes a GET request and returns raw bytes
     pub async fn get_bytes(&self, url: &str) -> Result<Vec<u8>> {
-        // Check rate limit
-        if let Some(limiter) = &self.rate_limiter {
-            limiter.until_ready().await;
-        }
+        self.wait_for_rate_limit().await;
 
         let response = self.client.get(url).send().await?;
-        let bytes = response.bytes().await?;
-        Ok(bytes.to_vec())
+        self.read_body(response).await
+    }
+
+    /// Makes a GET request and streams the body chunk by chunk
+    ///
+    /// The response size limit does not apply, so large downloads can be
+    /// processed without buffering them in memory.
+    pub async fn get_stream(&self, url: &str) -> Result<ByteStream> {
+        self.wait_for_rate_limit().await;
+
+        let response = self.client.get(url).send().await?.error_for_status()?;
+        Ok(ByteStream { response })
+    }
+
+    /// Waits until the rate limiter (if any) allows another request
+    async fn wait_for_rate_limit(&self) {
+        match &self.rate_limiter {
+            Some(Limiter::Fixed(limiter)) => limiter.until_ready().await,
+            Some(Limiter::Adaptive(limiter)) => limiter.acquire().await,
+            None => {}
+        }
+    }
+
+    /// Reads a response body, enforcing the configured size limit
+    async fn read_body(&self, mut response: reqwest::Response) -> Result<Vec<u8>> {
+        let Some(limit) = self.max_response_bytes else {
+            return Ok(response.bytes().await?.to_vec());
+        };
+        if response.content_length().is_some_and(|len| len > limit as u64) {
+            return Err(ProviderError::ResponseTooLarge { limit });
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            if body.len() + chunk.len() > limit {
+                return Err(ProviderError::ResponseTooLarge { limit });
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
     }
 
     /// Returns the number of requests made
@@ -616,6 +1534,24 @@ impl RpcClient {
     }
 }
 
+/// Streaming response body returned by [`RpcClient::get_stream`]
+#[derive(Debug)]
+pub struct ByteStream {
+    response: reqwest::Response,
+}
+
+impl ByteStream {
+    /// Returns the total body size if the server reported it
+    pub fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
+    }
+
+    /// Returns the next chunk of the body, or `None` at the end
+    pub async fn next_chunk(&mut self) -> Result<Option<Vec<u8>>> {
+        Ok(self.response.chunk().await?.map(|chunk| chunk.to_vec()))
+    }
+}
+
 impl Default for RpcClient {
     fn default() -> Self {
         Self::new().expect("Failed to create RPC client")
@@ -638,7 +1574,8 @@ impl std::fmt::Debug for RpcClient {
 /// A provider with integrated HTTP client
 pub struct HttpProvider {
     managed: Arc<ManagedProvider>,
-    client: RpcClient,
+    client: Arc<RpcClient>,
+    middleware: Vec<Arc<dyn Middleware>>,
 }
 
 impl HttpProvider {
@@ -648,49 +1585,130 @@ impl HttpProvider {
         
         let http_config = HttpClientConfig {
             request_timeout_secs: config.timeout_secs,
+            max_response_bytes: config.max_response_bytes,
             ..Default::default()
         };
         
         let rate_limit = Some(RateLimitConfig::default());
         let client = RpcClient::with_config(http_config, rate_limit)?;
 
-        Ok(Self {
-            managed: Arc::new(managed),
+        Ok(Self::from_managed(Arc::new(managed), Arc::new(client)))
+    }
+
+    /// Creates a provider on top of an existing managed provider and client
+    pub(crate) fn from_managed(managed: Arc<ManagedProvider>, client: Arc<RpcClient>) -> Self {
+        Self {
+            managed,
             client,
-        })
+            middleware: Vec::new(),
+        }
     }
 
     /// Makes an RPC call with automatic failover
+    ///
+    /// Results are served from and stored in the provider's cache according to
+    /// the per-method [`CachePolicy`]. Calls that miss the cache pass through
+    /// the provider's [`Middleware`] stack.
     pub async fn rpc_call<P, R>(&self, method: &str, params: P) -> Result<R>
     where
         P: Serialize + Clone,
         R: DeserializeOwned,
     {
-        let start = Instant::now();
-        let url = self.managed.current_url().await;
-        
-        match self.client.rpc_call(&url, method, params.clone()).await {
-            Ok(result) => {
-                let elapsed = start.elapsed().as_millis() as u64;
-                self.managed.record_success(elapsed).await;
-                Ok(result)
+        if let Some(cached) = self.managed.get_cached_call(method, &params) {
+            return Ok(serde_json::from_slice(&cached)?);
+        }
+
+        let value = self
+            .call_with_middleware(method, serde_json::to_value(&params)?)
+            .await?;
+        if !value.is_null() {
+            self.managed.cache_call(method, &params, serde_json::to_vec(&value)?);
+        }
+        if method == "eth_blockNumber" {
+            if let Some(number) = value
+                .as_str()
+                .and_then(|hex| u64::from_str_radix(hex.trim_start_matches("0x"), 16).ok())
+            {
+                self.managed.observe_block_number(number);
             }
-            Err(e) => {
-                self.managed.record_failure().await;
-                
-                // Try failover
-                let new_url = self.managed.current_url().await;
-                if new_url != url {
-                    tracing::info!("Retrying with failover endpoint: {}", new_url);
-                    self.client.rpc_call(&new_url, method, params).await
-                } else {
-                    Err(e)
+        }
+        Ok(serde_json::from_value(value)?)
+    }
+
+    async fn call_with_failover(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+        extra_headers: &[(String, String)],
+    ) -> Result<serde_json::Value> {
+        let config = &self.managed.config;
+        let mut backoff = ExponentialBackoff::new(
+            BackoffConfig::new()
+                .with_initial_delay(Duration::from_millis(config.retry_delay_ms))
+                .with_max_delay(Duration::from_millis(config.max_retry_delay_ms))
+                .with_max_attempts(config.max_retries),
+        );
+
+        let mut url = self.managed.endpoint_for(method, &params).await?;
+        self.managed.check_circuit(&url).await?;
+
+        loop {
+            let mut headers = config.headers_for(&url);
+            headers.extend_from_slice(extra_headers);
+            let permit = self.managed.acquire_slot(&url).await;
+            let start = Instant::now();
+            let outcome = self
+                .client
+                .rpc_call_with_headers(&url, method, params.clone(), &headers)
+                .await;
+            drop(permit);
+            let elapsed = start.elapsed().as_millis() as u64;
+            let error = match outcome {
+                Ok(result) => {
+                    self.managed.record_success_for(&url, elapsed).await;
+                    return Ok(result);
+                }
+                // The node answered; a rejected request says nothing about its health
+                Err(e @ ProviderError::RpcError { .. }) if !e.is_endpoint_failure() => {
+                    self.managed.record_success_for(&url, elapsed).await;
+                    return Err(e);
                 }
+                Err(e) => e,
+            };
+            if error.is_endpoint_failure() {
+                self.managed.record_failure_for(&url).await;
+            }
+
+            if !error.is_retryable() {
+                return Err(error);
             }
+            let Some(delay) = backoff.next() else {
+                return Err(error);
+            };
+
+            // Fail over immediately; back off before retrying the same endpoint
+            let Ok(next_url) = self.managed.endpoint_for(method, &params).await else {
+                return Err(error);
+            };
+            if self.managed.check_circuit(&next_url).await.is_err() {
+                return Err(error);
+            }
+            if next_url == url {
+                let requested = error.retry_after().unwrap_or_default();
+                if requested > Duration::from_millis(config.max_retry_delay_ms) {
+                    return Err(error);
+                }
+                let delay = delay.max(requested);
+                tracing::debug!("Retrying {} on {} in {:?}: {}", method, url, delay, error);
+                tokio::time::sleep(delay).await;
+            } else {
+                tracing::info!("Retrying with failover endpoint: {}", next_url);
+            }
+            url = next_url;
         }
     }
 
-    /// Returns endpoint statistics
+    /// Returns endpoint statistics, including each endpoint's circuit breaker state
     pub async fn stats(&self) -> Vec<EndpointInfo> {
         self.managed.stats().await
     }
@@ -705,6 +1723,8 @@ impl HttpProvider {
 #[derive(Debug, Default)]
 pub struct ProviderPool {
     providers: DashMap<String, Arc<ManagedProvider>>,
+    /// HTTP client shared by typed chain handles
+    client: OnceLock<Arc<RpcClient>>,
 }
 
 impl ProviderPool {
@@ -712,13 +1732,28 @@ impl ProviderPool {
     pub fn new() -> Self {
         Self {
             providers: DashMap::new(),
+            client: OnceLock::new(),
         }
     }
 
+    /// Returns the HTTP client shared by typed chain handles
+    pub(crate) fn shared_client(&self) -> Result<Arc<RpcClient>> {
+        if let Some(client) = self.client.get() {
+            return Ok(client.clone());
+        }
+        let client = Arc::new(RpcClient::with_config(
+            HttpClientConfig::default(),
+            Some(RateLimitConfig::default()),
+        )?);
+        Ok(self.client.get_or_init(|| client).clone())
+    }
+
     /// Adds a provider to the pool
-    pub fn add(&self, name: impl Into<String>, config: ProviderConfig) -> Result<()> {
+    pub fn add(&self, name: impl Into<String>, mut config: ProviderConfig) -> Result<()> {
+        let name = name.into();
+        config.name.get_or_insert_with(|| name.clone());
         let provider = ManagedProvider::new(config)?;
-        self.providers.insert(name.into(), Arc::new(provider));
+        self.providers.insert(name, Arc::new(provider));
         Ok(())
     }
 
@@ -795,18 +1830,21 @@ pub mod presets {
     pub fn solana_mainnet() -> ProviderConfig {
         ProviderConfig::new("https://api.mainnet-beta.solana.com")
             .with_timeout(30)
+            .with_health_check_method("getHealth")
     }
 
     /// Solana Devnet provider configuration
     pub fn solana_devnet() -> ProviderConfig {
         ProviderConfig::new("https://api.devnet.solana.com")
             .with_timeout(30)
+            .with_health_check_method("getHealth")
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use walletd_resilience::AimdConfig;
 
     #[test]
     fn test_provider_config() {
@@ -908,6 +1946,31 @@ mod tests {
         assert!(client.is_ok());
     }
 
+    #[test]
+    fn test_rpc_client_rejects_zero_rate_limit() {
+        for (requests_per_second, burst_size) in [(0, 10), (5, 0)] {
+            let rate_limit = Some(RateLimitConfig { requests_per_second, burst_size });
+            let result = RpcClient::with_config(HttpClientConfig::default(), rate_limit);
+            assert!(matches!(result, Err(ProviderError::InvalidConfig(_))));
+        }
+    }
+
+    #[test]
+    fn test_rpc_client_with_adaptive_rate_limit() {
+        let limiter = Arc::new(AdaptiveRateLimiter::new(AimdConfig::new("test")));
+        let client = RpcClient::with_adaptive_rate_limit(HttpClientConfig::default(), limiter);
+        assert!(client.is_ok());
+        assert!(format!("{:?}", client.unwrap()).contains("has_rate_limiter: true"));
+    }
+
+    #[test]
+    fn test_is_rate_limited() {
+        assert!(ProviderError::RpcError { code: -32005, message: "limit exceeded".into() }.is_rate_limited());
+        assert!(ProviderError::HttpStatus { status: 429, retry_after: None }.is_rate_limited());
+        assert!(!ProviderError::HttpStatus { status: 503, retry_after: None }.is_rate_limited());
+        assert!(!ProviderError::RpcError { code: -32000, message: "reverted".into() }.is_rate_limited());
+    }
+
     #[test]
     fn test_json_rpc_request() {
         let request = JsonRpcRequest::new("eth_blockNumber", Vec::<()>::new(), 1);
@@ -997,6 +2060,518 @@ mod tests {
         assert_eq!(info.health, EndpointHealth::Degraded);
     }
 
+    #[tokio::test]
+    async fn test_scored_selection_prefers_fastest_endpoint() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://fast.example.com");
+        let provider = ManagedProvider::new(config).unwrap();
+
+        {
+            let mut endpoints = provider.endpoints.write().await;
+            endpoints[0].record_success(400);
+            endpoints[1].record_success(40);
+        }
+        assert!(provider.current_url().await.contains("fast"));
+    }
+
+    #[tokio::test]
+    async fn test_scored_selection_prefers_primary_within_margin() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://fallback.example.com");
+        let provider = ManagedProvider::new(config).unwrap();
+
+        {
+            let mut endpoints = provider.endpoints.write().await;
+            endpoints[0].record_success(100);
+            endpoints[1].record_success(95);
+        }
+        assert!(provider.current_url().await.contains("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_scored_selection_respects_weights() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://paid.example.com")
+            .with_endpoint_weight("https://paid.example.com", 5);
+        let provider = ManagedProvider::new(config).unwrap();
+
+        {
+            let mut endpoints = provider.endpoints.write().await;
+            assert_eq!(endpoints[1].weight, 5);
+            endpoints[0].record_success(100);
+            endpoints[1].record_success(200);
+        }
+        assert!(provider.current_url().await.contains("paid"));
+    }
+
+    #[tokio::test]
+    async fn test_scored_selection_recovers_to_primary() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://fallback.example.com")
+            .with_recovery_period(30);
+        let provider = ManagedProvider::new(config).unwrap();
+
+        for _ in 0..5 {
+            provider.record_failure().await;
+        }
+        assert!(provider.current_url().await.contains("fallback"));
+        provider.record_success(100).await;
+
+        // Once the recovery period has passed, the primary's failures are
+        // forgotten and it wins traffic back
+        {
+            let mut endpoints = provider.endpoints.write().await;
+            endpoints[0].ewma_latency_ms = 100.0;
+            endpoints[0].last_failure = Instant::now().checked_sub(Duration::from_secs(300));
+        }
+        provider.breakers[0].force_close().await;
+        assert!(provider.current_url().await.contains("primary"));
+    }
+
+    #[tokio::test]
+    async fn test_failover_strategy_is_sticky() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://fast.example.com")
+            .with_selection(SelectionStrategy::Failover);
+        let provider = ManagedProvider::new(config).unwrap();
+
+        {
+            let mut endpoints = provider.endpoints.write().await;
+            endpoints[0].record_success(400);
+            endpoints[1].record_success(40);
+        }
+        assert!(provider.current_url().await.contains("primary"));
+    }
+
+    #[test]
+    fn test_endpoint_score() {
+        let recovery = Duration::from_secs(30);
+        let mut info = EndpointInfo::new("https://example.com".into());
+        let unmeasured = info.score(recovery).unwrap();
+
+        info.record_success(50);
+        let fast = info.score(recovery).unwrap();
+        assert!(fast > unmeasured);
+
+        info.record_failure();
+        assert!(info.score(recovery).unwrap() < fast);
+
+        for _ in 0..5 {
+            info.record_failure();
+        }
+        assert_eq!(info.health, EndpointHealth::Unhealthy);
+        assert!(info.score(recovery).is_none());
+    }
+
+    #[test]
+    fn test_method_cache_policy() {
+        let config = ProviderConfig::new("https://example.com")
+            .with_method_cache("eth_getBalance", CacheTtl::secs(60));
+        let provider = ManagedProvider::new(config).unwrap();
+        let params = ("0xabc", "latest");
+
+        provider.cache_call("eth_getBalance", &params, b"\"0x1\"".to_vec());
+        assert!(provider.get_cached_call("eth_getBalance", &params).is_some());
+        assert!(provider.get_cached_call("eth_getBalance", &("0xdef", "latest")).is_none());
+
+        // Writes are never cached, even if a response is offered
+        provider.cache_call("eth_sendRawTransaction", &["0xf86c"], b"\"0xhash\"".to_vec());
+        assert!(provider.get_cached_call("eth_sendRawTransaction", &["0xf86c"]).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_uses_method_cache() {
+        use wiremock::matchers::{body_partial_json, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"method": "eth_chainId"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"method": "eth_sendRawTransaction"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0xhash"
+            })))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri())).unwrap();
+        for _ in 0..2 {
+            let chain_id: String = provider.rpc_call("eth_chainId", ()).await.unwrap();
+            assert_eq!(chain_id, "0x1");
+            let hash: String = provider.rpc_call("eth_sendRawTransaction", ["0xf86c"]).await.unwrap();
+            assert_eq!(hash, "0xhash");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_open_circuit_skips_endpoint() {
+        let config = ProviderConfig::new("https://primary.example.com")
+            .with_fallback("https://fallback.example.com")
+            .with_recovery_period(0)
+            .with_circuit_breaker(
+                CircuitBreakerConfig::default()
+                    .with_failure_threshold(2)
+                    .with_reset_timeout(Duration::from_secs(60)),
+            );
+        let provider = ManagedProvider::new(config).unwrap();
+
+        provider.record_failure().await;
+        assert_eq!(provider.current_url().await, "https://primary.example.com");
+        provider.record_failure().await;
+        assert_eq!(provider.current_url().await, "https://fallback.example.com");
+
+        let stats = provider.stats().await;
+        assert_eq!(stats[0].circuit_state, CircuitState::Open);
+        assert_eq!(stats[1].circuit_state, CircuitState::Closed);
+        assert!(matches!(
+            provider.check_circuit("https://primary.example.com").await,
+            Err(ProviderError::CircuitOpen { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_stops_calling_open_circuit() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri())
+            .with_circuit_breaker(CircuitBreakerConfig::default().with_failure_threshold(2));
+        let provider = HttpProvider::new(config).unwrap();
+
+        for _ in 0..2 {
+            let result: Result<String> = provider.rpc_call("eth_blockNumber", ()).await;
+            assert!(result.is_err());
+        }
+        let result: Result<String> = provider.rpc_call("eth_blockNumber", ()).await;
+        assert!(matches!(result, Err(ProviderError::CircuitOpen { .. })));
+        assert_eq!(provider.stats().await[0].circuit_state, CircuitState::Open);
+    }
+
+    #[tokio::test]
+    async fn test_long_retry_after_is_not_waited_out() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "86400"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri())).unwrap();
+        let call = provider.rpc_call::<_, String>("eth_blockNumber", ());
+        let result = tokio::time::timeout(Duration::from_secs(5), call).await.unwrap();
+        assert_eq!(result.unwrap_err().retry_after(), Some(Duration::from_secs(86_400)));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_errors_keep_circuit_closed() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "error": {"code": 3, "message": "execution reverted"}
+            })))
+            .expect(5)
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri())
+            .with_circuit_breaker(CircuitBreakerConfig::default().with_failure_threshold(2));
+        let provider = HttpProvider::new(config).unwrap();
+        for _ in 0..5 {
+            let result: Result<String> = provider.rpc_call("eth_call", [serde_json::json!({})]).await;
+            assert!(matches!(result, Err(ProviderError::RpcError { code: 3, .. })));
+        }
+        let stats = provider.stats().await;
+        assert_eq!(stats[0].circuit_state, CircuitState::Closed);
+        assert_eq!(stats[0].total_failures, 0);
+    }
+
+    #[test]
+    fn test_endpoint_headers_override_defaults() {
+        let config = ProviderConfig::new("https://eth-mainnet.g.alchemy.com/v2")
+            .with_fallback("https://mainnet.infura.io/v3")
+            .with_header("X-Client", "walletd")
+            .with_bearer_token("alchemy-key")
+            .with_endpoint_basic_auth("https://mainnet.infura.io/v3", "project", "secret");
+
+        let primary = config.headers_for("https://eth-mainnet.g.alchemy.com/v2");
+        assert!(primary.contains(&("Authorization".into(), "Bearer alchemy-key".into())));
+        assert!(primary.contains(&("X-Client".into(), "walletd".into())));
+
+        let fallback = config.headers_for("https://mainnet.infura.io/v3");
+        assert_eq!(fallback.len(), 2);
+        assert!(fallback.contains(&("Authorization".into(), "Basic cHJvamVjdDpzZWNyZXQ=".into())));
+        assert!(config.validate().is_ok());
+
+        let invalid = ProviderConfig::new("https://example.com").with_header("Bad Header", "x");
+        assert!(matches!(invalid.validate(), Err(ProviderError::InvalidHeader(_))));
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_sends_headers() {
+        use wiremock::matchers::{header, method};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("Authorization", "Bearer secret"))
+            .and(header("X-Api-Key", "abc"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x10"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri())
+            .with_bearer_token("secret")
+            .with_endpoint_header(server.uri(), "X-Api-Key", "abc");
+        let provider = HttpProvider::new(config).unwrap();
+        let block: String = provider.rpc_call("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block, "0x10");
+    }
+
+    #[tokio::test]
+    async fn test_response_size_limit() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let logs: Vec<String> = (0..100).map(|i| format!("0x{:064x}", i)).collect();
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": logs
+            })))
+            .mount(&server)
+            .await;
+
+        let limited = HttpProvider::new(ProviderConfig::new(server.uri()).with_max_response_size(1024)).unwrap();
+        let result: Result<Vec<String>> = limited.rpc_call("eth_getLogs", [serde_json::json!({})]).await;
+        assert!(matches!(result, Err(ProviderError::ResponseTooLarge { limit: 1024 })));
+
+        let unlimited = HttpProvider::new(ProviderConfig::new(server.uri())).unwrap();
+        let result: Vec<String> = unlimited.rpc_call("eth_getLogs", [serde_json::json!({})]).await.unwrap();
+        assert_eq!(result.len(), 100);
+    }
+
+    #[tokio::test]
+    async fn test_get_stream() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let body = vec![7u8; 256 * 1024];
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(200).set_body_bytes(body.clone()))
+            .mount(&server)
+            .await;
+
+        let client = RpcClient::with_config(
+            HttpClientConfig {
+                max_response_bytes: Some(1024),
+                ..Default::default()
+            },
+            None,
+        )
+        .unwrap();
+        assert!(matches!(
+            client.get_bytes(&server.uri()).await,
+            Err(ProviderError::ResponseTooLarge { .. })
+        ));
+
+        let mut stream = client.get_stream(&server.uri()).await.unwrap();
+        assert_eq!(stream.content_length(), Some(body.len() as u64));
+        let mut total = 0;
+        while let Some(chunk) = stream.next_chunk().await.unwrap() {
+            total += chunk.len();
+        }
+        assert_eq!(total, body.len());
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_per_endpoint() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}))
+                    .set_delay(Duration::from_millis(100)),
+            )
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri()).with_cache(false).with_max_in_flight(2);
+        let provider = Arc::new(HttpProvider::new(config).unwrap());
+
+        let calls: Vec<_> = (0..4)
+            .map(|_| {
+                let provider = provider.clone();
+                tokio::spawn(async move {
+                    provider.rpc_call::<_, String>("eth_blockNumber", ()).await
+                })
+            })
+            .collect();
+
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        assert_eq!(provider.stats().await[0].in_flight, 2);
+        assert_eq!(server.received_requests().await.unwrap().len(), 2);
+
+        for call in calls {
+            assert_eq!(call.await.unwrap().unwrap(), "0x1");
+        }
+        assert_eq!(provider.stats().await[0].in_flight, 0);
+    }
+
+    #[test]
+    fn test_endpoint_concurrency_override() {
+        let config = ProviderConfig::new("https://free-tier.example.com")
+            .with_fallback("https://paid.example.com")
+            .with_max_in_flight(16)
+            .with_endpoint_max_in_flight("https://free-tier.example.com", 2);
+        assert_eq!(config.max_in_flight_for("https://free-tier.example.com"), Some(2));
+        assert_eq!(config.max_in_flight_for("https://paid.example.com"), Some(16));
+        assert!(ProviderConfig::default().max_in_flight_for("http://localhost:8545").is_none());
+        assert!(ProviderConfig::default().with_max_in_flight(0).validate().is_err());
+    }
+
+    #[test]
+    fn test_error_classification() {
+        assert!(ProviderError::HttpStatus { status: 429, retry_after: None }.is_retryable());
+        assert!(ProviderError::HttpStatus { status: 503, retry_after: None }.is_retryable());
+        assert!(!ProviderError::HttpStatus { status: 401, retry_after: None }.is_retryable());
+        assert!(ProviderError::RpcError { code: -32005, message: "limit exceeded".into() }.is_retryable());
+        assert!(!ProviderError::RpcError { code: -32000, message: "nonce too low".into() }.is_retryable());
+        assert!(ProviderError::Timeout(30).is_retryable());
+        assert_eq!(
+            ProviderError::HttpStatus { status: 429, retry_after: Some(Duration::from_secs(2)) }
+                .retry_after(),
+            Some(Duration::from_secs(2))
+        );
+    }
+
+    #[test]
+    fn test_into_walletd_error() {
+        let limited = ProviderError::RateLimited { retry_after: Some(Duration::from_millis(1500)) };
+        assert!(matches!(WalletdError::from(limited), WalletdError::RateLimited { retry_after_secs: 2 }));
+        let limited = ProviderError::RpcError { code: -32005, message: "limit exceeded".into() };
+        assert!(matches!(WalletdError::from(limited), WalletdError::RateLimited { retry_after_secs: 1 }));
+        assert!(matches!(WalletdError::from(ProviderError::Timeout(30)), WalletdError::NetworkTimeout { seconds: 30 }));
+        let unavailable = WalletdError::from(ProviderError::HttpStatus { status: 503, retry_after: None });
+        assert!(unavailable.is_retryable());
+        let rejected = WalletdError::from(ProviderError::RpcError { code: -32000, message: "nonce too low".into() });
+        assert!(!rejected.is_retryable());
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers_become_retry_after() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "7"))
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri())
+            .with_max_retries(0)
+            .with_circuit_breaker(CircuitBreakerConfig::default().with_failure_threshold(10));
+        let provider = HttpProvider::new(config).unwrap();
+        let err = provider.rpc_call::<_, String>("eth_blockNumber", ()).await.unwrap_err();
+        assert!(matches!(err, ProviderError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(7)));
+        assert!(matches!(WalletdError::from(err), WalletdError::RateLimited { retry_after_secs: 7 }));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_call_retries_with_backoff() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": "0x1"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri()).with_retry_delay(1);
+        let provider = HttpProvider::new(config).unwrap();
+        let block: String = provider.rpc_call("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block, "0x1");
+    }
+
+    #[tokio::test]
+    async fn test_rpc_call_gives_up_after_max_retries() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = ProviderConfig::new(server.uri())
+            .with_max_retries(2)
+            .with_retry_delay(1)
+            .with_circuit_breaker(CircuitBreakerConfig::default().with_failure_threshold(10));
+        let provider = HttpProvider::new(config).unwrap();
+        let result: Result<String> = provider.rpc_call("eth_blockNumber", ()).await;
+        assert!(matches!(result, Err(ProviderError::HttpStatus { status: 503, .. })));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_call_does_not_retry_rejected_requests() {
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1,
+                "error": {"code": -32000, "message": "insufficient funds"}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri())).unwrap();
+        let result: Result<String> = provider.rpc_call("eth_sendRawTransaction", ["0x00"]).await;
+        assert!(matches!(result, Err(ProviderError::RpcError { code: -32000, .. })));
+    }
+
     #[test]
     fn test_presets() {
         let eth = presets::ethereum_mainnet();
@@ -0,0 +1,287 @@
+//! Request/response middleware for [`HttpProvider`]
+//!
+//! Middleware is stacked on a provider with [`HttpProvider::with_middleware`].
+//! `on_request` hooks run in the order middleware was added, `on_response`
+//! hooks run in reverse order, and every `on_error` hook sees each failure.
+
+use crate::{HttpProvider, ProviderError, Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// An outgoing JSON-RPC request as seen by middleware
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcRequest {
+    /// Method name
+    pub method: String,
+    /// Parameters
+    pub params: serde_json::Value,
+    /// Extra HTTP headers, sent in addition to the provider's configured headers
+    pub headers: Vec<(String, String)>,
+}
+
+impl RpcRequest {
+    /// Creates a request with no extra headers
+    pub fn new(method: impl Into<String>, params: serde_json::Value) -> Self {
+        Self {
+            method: method.into(),
+            params,
+            headers: Vec::new(),
+        }
+    }
+}
+
+/// What to do after a request fails
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorAction {
+    /// Return the error to the caller
+    Fail,
+    /// Retry the request immediately
+    Retry,
+    /// Retry the request after a delay
+    RetryAfter(Duration),
+}
+
+/// Hooks around every network call made by an [`HttpProvider`]
+///
+/// All hooks have pass-through defaults, so implementors only override what
+/// they need. Cached responses bypass middleware entirely.
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Called before the request is sent
+    ///
+    /// May modify the request (e.g. add a signature header). Returning
+    /// `Ok(Some(result))` skips the network call and uses `result` as the
+    /// response, which is useful for injecting mocks in tests.
+    async fn on_request(&self, request: &mut RpcRequest) -> Result<Option<serde_json::Value>> {
+        let _ = request;
+        Ok(None)
+    }
+
+    /// Called with the JSON result of a successful request; may modify it
+    async fn on_response(&self, request: &RpcRequest, response: &mut serde_json::Value) -> Result<()> {
+        let _ = (request, response);
+        Ok(())
+    }
+
+    /// Called when a request fails; decides whether it should be retried
+    ///
+    /// The request is retried if any middleware asks for it, up to the
+    /// provider's `max_retries`.
+    async fn on_error(&self, request: &RpcRequest, error: &ProviderError) -> ErrorAction {
+        let _ = (request, error);
+        ErrorAction::Fail
+    }
+}
+
+/// Middleware that logs every request, response and error with `tracing`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingMiddleware;
+
+#[async_trait]
+impl Middleware for LoggingMiddleware {
+    async fn on_request(&self, request: &mut RpcRequest) -> Result<Option<serde_json::Value>> {
+        tracing::debug!(method = %request.method, params = %request.params, "RPC request");
+        Ok(None)
+    }
+
+    async fn on_response(&self, request: &RpcRequest, response: &mut serde_json::Value) -> Result<()> {
+        tracing::debug!(method = %request.method, result = %response, "RPC response");
+        Ok(())
+    }
+
+    async fn on_error(&self, request: &RpcRequest, error: &ProviderError) -> ErrorAction {
+        tracing::warn!(method = %request.method, "RPC error: {}", error);
+        ErrorAction::Fail
+    }
+}
+
+impl HttpProvider {
+    /// Adds a middleware to the provider's stack
+    pub fn with_middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Runs a request through the middleware stack, retrying as requested
+    pub(crate) async fn call_with_middleware(
+        &self,
+        method: &str,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        let mut retries = 0;
+        loop {
+            let mut request = RpcRequest::new(method, params.clone());
+            let error = match self.execute(&mut request).await {
+                Ok(response) => return Ok(response),
+                Err(e) => e,
+            };
+
+            // Any retry request wins, and the longest requested delay is used
+            let mut action = ErrorAction::Fail;
+            for middleware in &self.middleware {
+                action = match (action, middleware.on_error(&request, &error).await) {
+                    (current, ErrorAction::Fail) => current,
+                    (ErrorAction::RetryAfter(a), ErrorAction::RetryAfter(b)) => {
+                        ErrorAction::RetryAfter(a.max(b))
+                    }
+                    (ErrorAction::RetryAfter(a), ErrorAction::Retry) => ErrorAction::RetryAfter(a),
+                    (_, requested) => requested,
+                };
+            }
+
+            if action == ErrorAction::Fail || retries >= self.managed.config.max_retries {
+                return Err(error);
+            }
+            retries += 1;
+            if let ErrorAction::RetryAfter(delay) = action {
+                tokio::time::sleep(delay).await;
+            }
+        }
+    }
+
+    async fn execute(&self, request: &mut RpcRequest) -> Result<serde_json::Value> {
+        let mut injected = None;
+        for middleware in &self.middleware {
+            if let Some(response) = middleware.on_request(request).await? {
+                injected = Some(response);
+                break;
+            }
+        }
+
+        let mut response = match injected {
+            Some(response) => response,
+            None => {
+                self.call_with_failover(&request.method, request.params.clone(), &request.headers)
+                    .await?
+            }
+        };
+
+        for middleware in self.middleware.iter().rev() {
+            middleware.on_response(request, &mut response).await?;
+        }
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderConfig;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use wiremock::matchers::{header, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    #[derive(Default)]
+    struct MockChainId;
+
+    #[async_trait]
+    impl Middleware for MockChainId {
+        async fn on_request(&self, request: &mut RpcRequest) -> Result<Option<serde_json::Value>> {
+            Ok((request.method == "eth_chainId").then(|| serde_json::json!("0x539")))
+        }
+    }
+
+    struct SignRequests;
+
+    #[async_trait]
+    impl Middleware for SignRequests {
+        async fn on_request(&self, request: &mut RpcRequest) -> Result<Option<serde_json::Value>> {
+            request.headers.push(("X-Signature".into(), format!("sig:{}", request.method)));
+            Ok(None)
+        }
+
+        async fn on_response(&self, _: &RpcRequest, response: &mut serde_json::Value) -> Result<()> {
+            *response = serde_json::json!(format!("{}!", response.as_str().unwrap_or_default()));
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct RetryOnce {
+        seen: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Middleware for RetryOnce {
+        async fn on_error(&self, _: &RpcRequest, _: &ProviderError) -> ErrorAction {
+            if self.seen.fetch_add(1, Ordering::SeqCst) == 0 {
+                ErrorAction::RetryAfter(Duration::from_millis(1))
+            } else {
+                ErrorAction::Fail
+            }
+        }
+    }
+
+    fn ok_response(result: &str) -> ResponseTemplate {
+        ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "result": result
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_injected_response_skips_network() {
+        let provider = HttpProvider::new(ProviderConfig::new("http://127.0.0.1:9"))
+            .unwrap()
+            .with_middleware(MockChainId);
+        let chain_id: String = provider.rpc_call("eth_chainId", ()).await.unwrap();
+        assert_eq!(chain_id, "0x539");
+    }
+
+    #[tokio::test]
+    async fn test_request_and_response_hooks() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(header("X-Signature", "sig:eth_blockNumber"))
+            .respond_with(ok_response("0x10"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri()).with_cache(false))
+            .unwrap()
+            .with_middleware(LoggingMiddleware)
+            .with_middleware(SignRequests);
+        let block: String = provider.rpc_call("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block, "0x10!");
+    }
+
+    #[tokio::test]
+    async fn test_on_error_retries() {
+        // -32000 is not retried by the provider itself
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "error": {"code": -32000, "message": "header not found"}
+            })))
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ok_response("0x20"))
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri()).with_cache(false))
+            .unwrap()
+            .with_middleware(RetryOnce::default());
+        let block: String = provider.rpc_call("eth_blockNumber", ()).await.unwrap();
+        assert_eq!(block, "0x20");
+    }
+
+    #[tokio::test]
+    async fn test_without_retry_errors_propagate() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let provider = HttpProvider::new(ProviderConfig::new(server.uri()).with_max_retries(0))
+            .unwrap()
+            .with_middleware(LoggingMiddleware);
+        let result: Result<String> = provider.rpc_call("eth_blockNumber", ()).await;
+        assert!(result.is_err());
+    }
+}
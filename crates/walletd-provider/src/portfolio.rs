@@ -0,0 +1,499 @@
+//! Multi-chain balance snapshots
+//!
+//! A [`Portfolio`] lists wallets across chains together with the tokens to
+//! track for each. [`Portfolio::snapshot`] fetches every native and token
+//! balance concurrently through a [`ProviderPool`], so endpoint failover,
+//! caching and rate limits apply as for any other call.
+//!
+//! One slow or failing chain does not fail the snapshot: its balances are
+//! reported in [`PortfolioSnapshot::errors`] and everything else is returned.
+//!
+//! ```ignore
+//! use walletd_provider::portfolio::{Portfolio, TrackedWallet};
+//!
+//! let portfolio = Portfolio::new()
+//!     .with_wallet(
+//!         TrackedWallet::evm("base", "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed")
+//!             .with_token("USDC", "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913", 6),
+//!     )
+//!     .with_wallet(TrackedWallet::solana("9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM"));
+//!
+//! let snapshot = portfolio.snapshot(&pool).await;
+//! for balance in &snapshot.balances {
+//!     println!("{} {} on {}", balance.formatted(), balance.asset.symbol, balance.provider);
+//! }
+//! ```
+
+use crate::{Chain, ProviderError, ProviderPool, Result, Solana};
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Default time allowed for each balance request
+const DEFAULT_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// How a wallet's balances are queried
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChainFamily {
+    /// EVM JSON-RPC; tokens are ERC-20 contracts
+    Evm,
+    /// Solana JSON-RPC; tokens are SPL mints
+    Solana,
+}
+
+/// A native coin or token tracked by a portfolio
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct Asset {
+    /// Ticker shown to the user
+    pub symbol: String,
+    /// Decimal places of the base unit
+    pub decimals: u8,
+    /// Token contract or mint; `None` for the chain's native coin
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub contract: Option<String>,
+}
+
+impl Asset {
+    /// The chain's native coin
+    pub fn native(symbol: &str, decimals: u8) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            decimals,
+            contract: None,
+        }
+    }
+
+    /// A token at `contract` (ERC-20 address or SPL mint)
+    pub fn token(symbol: &str, contract: &str, decimals: u8) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            decimals,
+            contract: Some(contract.to_string()),
+        }
+    }
+
+    /// Returns true for the chain's native coin
+    pub fn is_native(&self) -> bool {
+        self.contract.is_none()
+    }
+}
+
+/// A wallet address on one pooled provider and the assets to track for it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TrackedWallet {
+    /// Name of the provider in the pool (`"ethereum"`, `"base"`, `"solana"`)
+    pub provider: String,
+    /// Chain family of the provider
+    pub family: ChainFamily,
+    /// Wallet address
+    pub address: String,
+    /// Native coin of the chain
+    pub native: Asset,
+    /// Tokens to track
+    #[serde(default)]
+    pub tokens: Vec<Asset>,
+}
+
+impl TrackedWallet {
+    /// An EVM wallet on the provider registered as `provider`
+    ///
+    /// The native coin defaults to ETH; see [`with_native`](Self::with_native).
+    pub fn evm(provider: &str, address: &str) -> Self {
+        Self {
+            provider: provider.to_string(),
+            family: ChainFamily::Evm,
+            address: address.to_string(),
+            native: Asset::native("ETH", 18),
+            tokens: Vec::new(),
+        }
+    }
+
+    /// A Solana wallet on the provider registered as `"solana"`
+    pub fn solana(address: &str) -> Self {
+        Self {
+            provider: Solana::DEFAULT_NAME.to_string(),
+            family: ChainFamily::Solana,
+            address: address.to_string(),
+            native: Asset::native("SOL", 9),
+            tokens: Vec::new(),
+        }
+    }
+
+    /// Sets the native coin (e.g. POL on Polygon, AVAX on Avalanche)
+    pub fn with_native(mut self, symbol: &str, decimals: u8) -> Self {
+        self.native = Asset::native(symbol, decimals);
+        self
+    }
+
+    /// Tracks a token (ERC-20 contract or SPL mint)
+    pub fn with_token(mut self, symbol: &str, contract: &str, decimals: u8) -> Self {
+        self.tokens.push(Asset::token(symbol, contract, decimals));
+        self
+    }
+
+    fn assets(&self) -> impl Iterator<Item = &Asset> {
+        std::iter::once(&self.native).chain(&self.tokens)
+    }
+}
+
+/// One fetched balance
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Balance {
+    /// Provider the balance was read from
+    pub provider: String,
+    /// Wallet address
+    pub address: String,
+    /// What the balance is of
+    pub asset: Asset,
+    /// Amount in base units (wei, lamports, token units)
+    pub raw: u128,
+}
+
+impl Balance {
+    /// Amount in whole units as a decimal string, without trailing zeros
+    pub fn formatted(&self) -> String {
+        format_units(self.raw, self.asset.decimals)
+    }
+
+    /// Amount in whole units as a float, for display and charting only
+    pub fn value(&self) -> f64 {
+        self.raw as f64 / 10f64.powi(self.asset.decimals as i32)
+    }
+}
+
+/// A balance that could not be fetched
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BalanceError {
+    /// Provider the balance was requested from
+    pub provider: String,
+    /// Wallet address
+    pub address: String,
+    /// What the balance is of
+    pub asset: Asset,
+    /// Why the request failed
+    pub error: String,
+}
+
+/// Balances of every tracked wallet at one point in time
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PortfolioSnapshot {
+    /// Time the snapshot was started, Unix seconds
+    pub taken_at: u64,
+    /// Fetched balances, in portfolio order
+    pub balances: Vec<Balance>,
+    /// Balances that could not be fetched
+    pub errors: Vec<BalanceError>,
+}
+
+impl PortfolioSnapshot {
+    /// Returns true if every balance was fetched
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Balances read from `provider`
+    pub fn for_provider<'a>(&'a self, provider: &'a str) -> impl Iterator<Item = &'a Balance> {
+        self.balances.iter().filter(move |b| b.provider == provider)
+    }
+
+    /// Sum of every balance with this symbol, in whole units
+    ///
+    /// Adds holdings across chains and wallets (e.g. USDC on Ethereum and on
+    /// Solana), so it is only meaningful for assets with the same value
+    /// everywhere.
+    pub fn total(&self, symbol: &str) -> f64 {
+        self.balances
+            .iter()
+            .filter(|b| b.asset.symbol == symbol)
+            .map(Balance::value)
+            .sum()
+    }
+
+    /// Providers with at least one failed request
+    pub fn failed_providers(&self) -> Vec<&str> {
+        let mut providers: Vec<&str> = Vec::new();
+        for error in &self.errors {
+            if !providers.contains(&error.provider.as_str()) {
+                providers.push(&error.provider);
+            }
+        }
+        providers
+    }
+}
+
+/// Wallets across chains whose balances are fetched together
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Portfolio {
+    wallets: Vec<TrackedWallet>,
+    #[serde(skip, default = "default_timeout")]
+    timeout: Duration,
+}
+
+impl Default for Portfolio {
+    fn default() -> Self {
+        Self {
+            wallets: Vec::new(),
+            timeout: DEFAULT_TIMEOUT,
+        }
+    }
+}
+
+impl Portfolio {
+    /// Creates an empty portfolio
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a wallet
+    pub fn with_wallet(mut self, wallet: TrackedWallet) -> Self {
+        self.add(wallet);
+        self
+    }
+
+    /// Sets how long each balance request may take before it is reported
+    /// as failed (default 15 seconds)
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Adds a wallet
+    pub fn add(&mut self, wallet: TrackedWallet) {
+        self.wallets.push(wallet);
+    }
+
+    /// Tracked wallets
+    pub fn wallets(&self) -> &[TrackedWallet] {
+        &self.wallets
+    }
+
+    /// Fetches every balance concurrently
+    ///
+    /// Never fails as a whole: missing providers, RPC errors and timeouts
+    /// are recorded per balance in [`PortfolioSnapshot::errors`].
+    pub async fn snapshot(&self, pool: &ProviderPool) -> PortfolioSnapshot {
+        let taken_at = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        let requests = self.wallets.iter().flat_map(|wallet| {
+            wallet.assets().map(move |asset| async move {
+                let result =
+                    match tokio::time::timeout(self.timeout, fetch(pool, wallet, asset)).await {
+                        Ok(result) => result,
+                        Err(_) => Err(ProviderError::Timeout(self.timeout.as_secs())),
+                    };
+                (wallet, asset, result)
+            })
+        });
+        let results = futures_util::future::join_all(requests).await;
+
+        let mut snapshot = PortfolioSnapshot {
+            taken_at,
+            ..PortfolioSnapshot::default()
+        };
+        for (wallet, asset, result) in results {
+            match result {
+                Ok(raw) => snapshot.balances.push(Balance {
+                    provider: wallet.provider.clone(),
+                    address: wallet.address.clone(),
+                    asset: asset.clone(),
+                    raw,
+                }),
+                Err(e) => {
+                    tracing::debug!(
+                        "Balance of {} for {} on {} failed: {}",
+                        asset.symbol,
+                        wallet.address,
+                        wallet.provider,
+                        e
+                    );
+                    snapshot.errors.push(BalanceError {
+                        provider: wallet.provider.clone(),
+                        address: wallet.address.clone(),
+                        asset: asset.clone(),
+                        error: e.to_string(),
+                    });
+                }
+            }
+        }
+        snapshot
+    }
+}
+
+fn default_timeout() -> Duration {
+    DEFAULT_TIMEOUT
+}
+
+async fn fetch(pool: &ProviderPool, wallet: &TrackedWallet, asset: &Asset) -> Result<u128> {
+    match (wallet.family, &asset.contract) {
+        (ChainFamily::Evm, None) => {
+            pool.evm(&wallet.provider)?
+                .get_balance(&wallet.address)
+                .await
+        }
+        (ChainFamily::Evm, Some(token)) => {
+            pool.evm(&wallet.provider)?
+                .erc20_balance(token, &wallet.address)
+                .await
+        }
+        (ChainFamily::Solana, None) => {
+            let solana = pool.chain::<Solana>(&wallet.provider)?;
+            solana.get_balance(&wallet.address).await.map(u128::from)
+        }
+        (ChainFamily::Solana, Some(mint)) => {
+            let solana = pool.chain::<Solana>(&wallet.provider)?;
+            solana
+                .get_token_balance(&wallet.address, mint)
+                .await
+                .map(u128::from)
+        }
+    }
+}
+
+/// Formats base units as a decimal string (`1500000, 6` is `"1.5"`)
+pub fn format_units(raw: u128, decimals: u8) -> String {
+    let scale = 10u128.checked_pow(decimals as u32);
+    let Some(scale) = scale else {
+        // More than 38 decimals: every u128 amount is below one whole unit
+        return format!("0.{:0>width$}", raw, width = decimals as usize)
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string();
+    };
+    let whole = raw / scale;
+    let fraction = raw % scale;
+    if fraction == 0 {
+        return whole.to_string();
+    }
+    let fraction = format!("{:0>width$}", fraction, width = decimals as usize);
+    format!("{}.{}", whole, fraction.trim_end_matches('0'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const EVM_ADDRESS: &str = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+    const USDC_BASE: &str = "0x833589fCD6eDb6E08f4c7C32D4f71b54bdA02913";
+    const SOL_ADDRESS: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    async fn mock(server: &MockServer, rpc_method: &str, result: serde_json::Value) {
+        Mock::given(method("POST"))
+            .and(body_partial_json(serde_json::json!({"method": rpc_method})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "jsonrpc": "2.0", "id": 1, "result": result
+            })))
+            .mount(server)
+            .await;
+    }
+
+    fn token_account(amount: &str) -> serde_json::Value {
+        serde_json::json!({
+            "pubkey": "ATA",
+            "account": {"data": {"parsed": {"info": {"tokenAmount": {"amount": amount, "decimals": 6}}}}}
+        })
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_across_chains() {
+        let base = MockServer::start().await;
+        mock(
+            &base,
+            "eth_getBalance",
+            serde_json::json!("0xde0b6b3a7640000"),
+        )
+        .await;
+        mock(
+            &base,
+            "eth_call",
+            serde_json::json!("0x00000000000000000000000000000000000000000000000000000000004c4b40"),
+        )
+        .await;
+        let solana = MockServer::start().await;
+        mock(
+            &solana,
+            "getBalance",
+            serde_json::json!({"context": {"slot": 1}, "value": 1_500_000_000u64}),
+        )
+        .await;
+        mock(
+            &solana,
+            "getTokenAccountsByOwner",
+            serde_json::json!({"context": {"slot": 1}, "value": [token_account("2500000"), token_account("500000")]}),
+        )
+        .await;
+
+        let pool = ProviderPool::new();
+        pool.add("base", ProviderConfig::new(base.uri())).unwrap();
+        pool.add("solana", ProviderConfig::new(solana.uri()))
+            .unwrap();
+
+        let portfolio = Portfolio::new()
+            .with_wallet(TrackedWallet::evm("base", EVM_ADDRESS).with_token("USDC", USDC_BASE, 6))
+            .with_wallet(TrackedWallet::solana(SOL_ADDRESS).with_token("USDC", USDC_MINT, 6))
+            .with_wallet(TrackedWallet::evm("polygon", EVM_ADDRESS).with_native("POL", 18));
+        let snapshot = portfolio.snapshot(&pool).await;
+
+        let formatted: Vec<(String, String)> = snapshot
+            .balances
+            .iter()
+            .map(|b| (b.asset.symbol.clone(), b.formatted()))
+            .collect();
+        assert_eq!(
+            formatted,
+            [
+                ("ETH".to_string(), "1".to_string()),
+                ("USDC".to_string(), "5".to_string()),
+                ("SOL".to_string(), "1.5".to_string()),
+                ("USDC".to_string(), "3".to_string()),
+            ]
+        );
+        assert_eq!(snapshot.total("USDC"), 8.0);
+        assert_eq!(snapshot.for_provider("solana").count(), 2);
+
+        // The unregistered chain is reported without failing the rest
+        assert!(!snapshot.is_complete());
+        assert_eq!(snapshot.failed_providers(), ["polygon"]);
+        assert_eq!(snapshot.errors[0].asset.symbol, "POL");
+        assert!(snapshot.taken_at > 0);
+    }
+
+    #[tokio::test]
+    async fn test_slow_chain_times_out() {
+        let slow = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"jsonrpc": "2.0", "id": 1, "result": "0x1"}))
+                    .set_delay(Duration::from_secs(5)),
+            )
+            .mount(&slow)
+            .await;
+        let pool = ProviderPool::new();
+        pool.add("ethereum", ProviderConfig::new(slow.uri()))
+            .unwrap();
+
+        let snapshot = Portfolio::new()
+            .with_wallet(TrackedWallet::evm("ethereum", EVM_ADDRESS))
+            .with_timeout(Duration::from_millis(100))
+            .snapshot(&pool)
+            .await;
+        assert!(snapshot.balances.is_empty());
+        assert_eq!(snapshot.errors.len(), 1);
+    }
+
+    #[test]
+    fn test_format_units() {
+        assert_eq!(format_units(0, 18), "0");
+        assert_eq!(format_units(1_500_000, 6), "1.5");
+        assert_eq!(format_units(1, 18), "0.000000000000000001");
+        assert_eq!(format_units(42, 0), "42");
+        assert_eq!(format_units(5, 40), format!("0.{}5", "0".repeat(39)));
+    }
+}
@@ -0,0 +1,203 @@
+//! Capability-aware request routing
+//!
+//! Endpoints can be tagged with the capabilities they offer (archive state,
+//! tracing). Requests that need historical state or trace APIs are routed
+//! only to capable endpoints; everything else uses normal endpoint selection.
+
+use crate::{ManagedProvider, ProviderError, Result};
+use std::sync::atomic::Ordering;
+
+/// Blocks behind the head that a full (non-archive) node still serves state for
+pub const ARCHIVE_DEPTH: u64 = 128;
+
+/// Optional capability offered by an endpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EndpointCapability {
+    /// Serves state at any historical block
+    Archive,
+    /// Serves `debug_*` and `trace_*` methods
+    Trace,
+}
+
+/// Returns the position of the block parameter for state-reading methods
+fn block_param_index(method: &str) -> Option<usize> {
+    match method {
+        "eth_getBalance" | "eth_getCode" | "eth_getTransactionCount" | "eth_call" => Some(1),
+        "eth_getStorageAt" | "eth_getProof" => Some(2),
+        _ => None,
+    }
+}
+
+/// Returns the capability a request needs, if any
+///
+/// Trace methods need [`EndpointCapability::Trace`]. State reads at an
+/// explicit block need [`EndpointCapability::Archive`] unless the block is
+/// known to be within [`ARCHIVE_DEPTH`] of `head`. Block tags such as
+/// `latest` never need an archive node.
+pub fn required_capability(
+    method: &str,
+    params: &serde_json::Value,
+    head: Option<u64>,
+) -> Option<EndpointCapability> {
+    if method.starts_with("debug_trace") || method.starts_with("trace_") {
+        return Some(EndpointCapability::Trace);
+    }
+
+    let block = params.get(block_param_index(method)?)?;
+    let block_number = match block {
+        serde_json::Value::String(tag) if !tag.starts_with("0x") => return None,
+        serde_json::Value::String(hex) => hex,
+        // EIP-1898 block selectors
+        serde_json::Value::Object(selector) => match selector.get("blockNumber") {
+            Some(serde_json::Value::String(hex)) => hex,
+            // A block hash gives no depth information
+            _ => return Some(EndpointCapability::Archive),
+        },
+        _ => return None,
+    };
+
+    let number = u64::from_str_radix(block_number.trim_start_matches("0x"), 16).ok()?;
+    match head {
+        Some(head) if number.saturating_add(ARCHIVE_DEPTH) >= head => None,
+        _ => Some(EndpointCapability::Archive),
+    }
+}
+
+impl ManagedProvider {
+    /// Returns true if `url` is tagged with `capability`
+    pub fn supports(&self, url: &str, capability: EndpointCapability) -> bool {
+        self.config
+            .endpoint_capabilities
+            .get(url)
+            .is_some_and(|caps| caps.contains(&capability))
+    }
+
+    /// Returns the latest block number observed from `eth_blockNumber`
+    pub fn latest_block(&self) -> Option<u64> {
+        match self.latest_block.load(Ordering::Relaxed) {
+            0 => None,
+            n => Some(n),
+        }
+    }
+
+    /// Records the chain head, used to decide whether a block is historical
+    pub fn observe_block_number(&self, number: u64) {
+        self.latest_block.fetch_max(number, Ordering::Relaxed);
+    }
+
+    /// Picks the endpoint to send `method` to
+    ///
+    /// Requests without special requirements use [`ManagedProvider::current_url`].
+    /// Otherwise the current endpoint is used if capable, then the first
+    /// capable endpoint whose circuit is not open.
+    pub async fn endpoint_for(&self, method: &str, params: &serde_json::Value) -> Result<String> {
+        let current = self.current_url().await;
+        let Some(capability) = required_capability(method, params, self.latest_block()) else {
+            return Ok(current);
+        };
+        if self.supports(&current, capability) {
+            return Ok(current);
+        }
+
+        let mut fallback = None;
+        for url in self.config.all_urls() {
+            if !self.supports(url, capability) {
+                continue;
+            }
+            if self.check_circuit(url).await.is_ok() {
+                return Ok(url.to_string());
+            }
+            fallback.get_or_insert(url);
+        }
+
+        // Let the caller report the open circuit rather than a missing endpoint
+        fallback
+            .map(str::to_string)
+            .ok_or_else(|| ProviderError::NoCapableEndpoint {
+                method: method.to_string(),
+                capability,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ProviderConfig;
+    use serde_json::json;
+
+    #[test]
+    fn test_required_capability() {
+        assert_eq!(
+            required_capability("debug_traceTransaction", &json!(["0xabc"]), None),
+            Some(EndpointCapability::Trace)
+        );
+        assert_eq!(required_capability("eth_call", &json!([{}, "latest"]), None), None);
+        assert_eq!(
+            required_capability("eth_call", &json!([{}, "0x10"]), None),
+            Some(EndpointCapability::Archive)
+        );
+        assert_eq!(required_capability("eth_call", &json!([{}, "0x1000"]), Some(0x1010)), None);
+        assert_eq!(
+            required_capability("eth_getStorageAt", &json!(["0xa", "0x0", "0x10"]), Some(0x1010)),
+            Some(EndpointCapability::Archive)
+        );
+        assert_eq!(
+            required_capability("eth_getBalance", &json!(["0xa", {"blockHash": "0xff"}]), None),
+            Some(EndpointCapability::Archive)
+        );
+        assert_eq!(required_capability("eth_getBlockByNumber", &json!(["0x1", false]), None), None);
+    }
+
+    #[tokio::test]
+    async fn test_routes_to_capable_endpoint() {
+        let config = ProviderConfig::new("https://full.example.com")
+            .with_fallback("https://archive.example.com")
+            .with_endpoint_capability("https://archive.example.com", EndpointCapability::Archive);
+        let provider = ManagedProvider::new(config).unwrap();
+        provider.observe_block_number(20_000_000);
+
+        let recent = json!(["0xabc", format!("0x{:x}", 19_999_990)]);
+        assert_eq!(
+            provider.endpoint_for("eth_getBalance", &recent).await.unwrap(),
+            "https://full.example.com"
+        );
+        let old = json!(["0xabc", "0x1"]);
+        assert_eq!(
+            provider.endpoint_for("eth_getBalance", &old).await.unwrap(),
+            "https://archive.example.com"
+        );
+        assert!(matches!(
+            provider.endpoint_for("trace_block", &json!(["0x1"])).await,
+            Err(ProviderError::NoCapableEndpoint { capability: EndpointCapability::Trace, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_http_provider_sends_trace_calls_to_trace_endpoint() {
+        use crate::HttpProvider;
+        use wiremock::matchers::method;
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let full = MockServer::start().await;
+        let trace = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "jsonrpc": "2.0", "id": 1, "result": {"calls": []}
+            })))
+            .expect(1)
+            .mount(&trace)
+            .await;
+
+        let config = ProviderConfig::new(full.uri())
+            .with_fallback(trace.uri())
+            .with_endpoint_capability(trace.uri(), EndpointCapability::Trace);
+        let provider = HttpProvider::new(config).unwrap();
+        let result: serde_json::Value = provider
+            .rpc_call("debug_traceTransaction", ["0xabc"])
+            .await
+            .unwrap();
+        assert_eq!(result, json!({"calls": []}));
+        assert!(full.received_requests().await.unwrap().is_empty());
+    }
+}
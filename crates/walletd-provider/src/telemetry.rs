@@ -0,0 +1,126 @@
+//! Provider metrics
+//!
+//! With the `metrics` feature enabled, request counts, errors, latencies,
+//! cache hits and failover events are reported through the [`metrics`] facade
+//! with Prometheus-style names. Install any recorder (for example
+//! `metrics-exporter-prometheus`) to export them. Without the feature every
+//! function here is a no-op.
+
+use std::time::Duration;
+
+/// Total RPC requests, labelled by `provider` and `endpoint`
+pub const REQUESTS_TOTAL: &str = "walletd_provider_requests_total";
+/// Failed RPC requests, labelled by `provider` and `endpoint`
+pub const ERRORS_TOTAL: &str = "walletd_provider_errors_total";
+/// Successful request latency in seconds, labelled by `provider` and `endpoint`
+pub const REQUEST_DURATION_SECONDS: &str = "walletd_provider_request_duration_seconds";
+/// Cache lookups served from cache, labelled by `provider`
+pub const CACHE_HITS_TOTAL: &str = "walletd_provider_cache_hits_total";
+/// Cache lookups that missed, labelled by `provider`
+pub const CACHE_MISSES_TOTAL: &str = "walletd_provider_cache_misses_total";
+/// Endpoint switches, labelled by `provider`, `from` and `to`
+pub const FAILOVERS_TOTAL: &str = "walletd_provider_failovers_total";
+
+/// Registers descriptions and units for all provider metrics
+///
+/// Call once after installing a recorder so exporters can emit `HELP` lines.
+#[cfg(feature = "metrics")]
+pub fn describe_metrics() {
+    use metrics::{describe_counter, describe_histogram, Unit};
+
+    describe_counter!(REQUESTS_TOTAL, "Total RPC requests per endpoint");
+    describe_counter!(ERRORS_TOTAL, "Failed RPC requests per endpoint");
+    describe_histogram!(
+        REQUEST_DURATION_SECONDS,
+        Unit::Seconds,
+        "Latency of successful RPC requests"
+    );
+    describe_counter!(CACHE_HITS_TOTAL, "RPC calls served from the response cache");
+    describe_counter!(CACHE_MISSES_TOTAL, "RPC calls that missed the response cache");
+    describe_counter!(FAILOVERS_TOTAL, "Switches from one endpoint to another");
+}
+
+pub(crate) fn record_success(provider: &str, endpoint: &str, elapsed: Duration) {
+    #[cfg(feature = "metrics")]
+    {
+        let labels = [("provider", provider.to_string()), ("endpoint", endpoint.to_string())];
+        metrics::counter!(REQUESTS_TOTAL, &labels).increment(1);
+        metrics::histogram!(REQUEST_DURATION_SECONDS, &labels).record(elapsed.as_secs_f64());
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (provider, endpoint, elapsed);
+}
+
+pub(crate) fn record_failure(provider: &str, endpoint: &str) {
+    #[cfg(feature = "metrics")]
+    {
+        let labels = [("provider", provider.to_string()), ("endpoint", endpoint.to_string())];
+        metrics::counter!(REQUESTS_TOTAL, &labels).increment(1);
+        metrics::counter!(ERRORS_TOTAL, &labels).increment(1);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (provider, endpoint);
+}
+
+pub(crate) fn record_cache_lookup(provider: &str, hit: bool) {
+    #[cfg(feature = "metrics")]
+    {
+        let name = if hit { CACHE_HITS_TOTAL } else { CACHE_MISSES_TOTAL };
+        metrics::counter!(name, "provider" => provider.to_string()).increment(1);
+    }
+    #[cfg(not(feature = "metrics"))]
+    let _ = (provider, hit);
+}
+
+pub(crate) fn record_failover(provider: &str, from: &str, to: &str) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(
+        FAILOVERS_TOTAL,
+        "provider" => provider.to_string(),
+        "from" => from.to_string(),
+        "to" => to.to_string()
+    )
+    .increment(1);
+    #[cfg(not(feature = "metrics"))]
+    let _ = (provider, from, to);
+}
+
+#[cfg(all(test, feature = "metrics"))]
+mod tests {
+    use super::*;
+    use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+    use metrics_util::MetricKind;
+
+    #[test]
+    fn test_records_counters_and_histograms() {
+        let recorder = DebuggingRecorder::new();
+        let snapshotter = recorder.snapshotter();
+
+        metrics::with_local_recorder(&recorder, || {
+            record_success("eth", "https://a", Duration::from_millis(50));
+            record_failure("eth", "https://a");
+            record_cache_lookup("eth", true);
+            record_failover("eth", "https://a", "https://b");
+        });
+
+        let snapshot = snapshotter.snapshot().into_vec();
+        let counter = |name: &str| {
+            snapshot
+                .iter()
+                .filter(|(key, ..)| key.kind() == MetricKind::Counter && key.key().name() == name)
+                .map(|(.., value)| match value {
+                    DebugValue::Counter(n) => *n,
+                    _ => 0,
+                })
+                .sum::<u64>()
+        };
+        assert_eq!(counter(REQUESTS_TOTAL), 2);
+        assert_eq!(counter(ERRORS_TOTAL), 1);
+        assert_eq!(counter(CACHE_HITS_TOTAL), 1);
+        assert_eq!(counter(CACHE_MISSES_TOTAL), 0);
+        assert_eq!(counter(FAILOVERS_TOTAL), 1);
+        assert!(snapshot
+            .iter()
+            .any(|(key, ..)| key.key().name() == REQUEST_DURATION_SECONDS));
+    }
+}
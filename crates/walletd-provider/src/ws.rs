@@ -0,0 +1,764 @@
+//! WebSocket JSON-RPC transport
+//!
+//! A persistent WebSocket connection with automatic reconnect and
+//! `eth_subscribe`-style subscriptions, for chains that push notifications
+//! instead of requiring the client to poll.
+
+use crate::{ProviderError, Result};
+use dashmap::DashMap;
+use futures_util::{SinkExt, StreamExt};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot, watch};
+use tokio_tungstenite::tungstenite::Message;
+use url::Url;
+
+/// Configuration for a WebSocket client
+#[derive(Debug, Clone)]
+pub struct WsConfig {
+    /// WebSocket URL (`ws://` or `wss://`)
+    pub url: String,
+    /// Request timeout in seconds
+    pub request_timeout_secs: u64,
+    /// Interval between keep-alive pings in seconds (0 disables pings)
+    pub ping_interval_secs: u64,
+    /// Initial reconnect delay in milliseconds
+    pub reconnect_delay_ms: u64,
+    /// Maximum reconnect delay in milliseconds
+    pub max_reconnect_delay_ms: u64,
+    /// Maximum consecutive reconnect attempts (`None` retries forever)
+    pub max_reconnect_attempts: Option<u32>,
+}
+
+impl WsConfig {
+    /// Creates a new WebSocket configuration with the given URL
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            request_timeout_secs: 30,
+            ping_interval_secs: 30,
+            reconnect_delay_ms: 500,
+            max_reconnect_delay_ms: 30_000,
+            max_reconnect_attempts: None,
+        }
+    }
+
+    /// Sets the request timeout
+    pub fn with_timeout(mut self, secs: u64) -> Self {
+        self.request_timeout_secs = secs;
+        self
+    }
+
+    /// Sets the keep-alive ping interval
+    pub fn with_ping_interval(mut self, secs: u64) -> Self {
+        self.ping_interval_secs = secs;
+        self
+    }
+
+    /// Sets the initial and maximum reconnect delays
+    pub fn with_reconnect_delay(mut self, initial_ms: u64, max_ms: u64) -> Self {
+        self.reconnect_delay_ms = initial_ms;
+        self.max_reconnect_delay_ms = max_ms;
+        self
+    }
+
+    /// Limits the number of consecutive reconnect attempts
+    pub fn with_max_reconnect_attempts(mut self, attempts: u32) -> Self {
+        self.max_reconnect_attempts = Some(attempts);
+        self
+    }
+
+    /// Validates the configuration
+    pub fn validate(&self) -> Result<()> {
+        let url = Url::parse(&self.url).map_err(|e| ProviderError::InvalidUrl(e.to_string()))?;
+        match url.scheme() {
+            "ws" | "wss" => Ok(()),
+            other => Err(ProviderError::InvalidUrl(format!(
+                "expected ws:// or wss:// scheme, got {}://",
+                other
+            ))),
+        }
+    }
+
+    fn reconnect_delay(&self, attempt: u32) -> Duration {
+        let delay = self
+            .reconnect_delay_ms
+            .saturating_mul(1u64 << attempt.min(16));
+        Duration::from_millis(delay.min(self.max_reconnect_delay_ms))
+    }
+}
+
+/// Connection state of a WebSocket client
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Initial connection in progress
+    Connecting,
+    /// Connected and ready
+    Connected,
+    /// Connection lost, reconnecting
+    Reconnecting,
+    /// Closed (explicitly or after exhausting reconnect attempts)
+    Closed,
+}
+
+enum Command {
+    Send(String),
+    Close,
+}
+
+enum Pending {
+    Call(oneshot::Sender<Result<Value>>),
+    Subscribe {
+        local_id: u64,
+        reply: Option<oneshot::Sender<Result<Value>>>,
+    },
+}
+
+struct SubscriptionEntry {
+    method: String,
+    params: Value,
+    server_id: Option<String>,
+    sender: mpsc::UnboundedSender<Value>,
+}
+
+struct Shared {
+    config: WsConfig,
+    request_id: AtomicU64,
+    next_subscription: AtomicU64,
+    pending: DashMap<u64, Pending>,
+    subscriptions: DashMap<u64, SubscriptionEntry>,
+    routes: DashMap<String, u64>,
+}
+
+impl Shared {
+    fn next_id(&self) -> u64 {
+        self.request_id.fetch_add(1, Ordering::SeqCst)
+    }
+
+    fn encode<P: Serialize>(&self, id: u64, method: &str, params: P) -> Result<String> {
+        Ok(serde_json::to_string(&crate::JsonRpcRequest::new(method, params, id))?)
+    }
+
+    fn handle_text(&self, text: &str) {
+        match serde_json::from_str::<Value>(text) {
+            Ok(Value::Array(items)) => items.into_iter().for_each(|item| self.handle_value(item)),
+            Ok(value) => self.handle_value(value),
+            Err(e) => tracing::warn!("Ignoring malformed WebSocket message: {}", e),
+        }
+    }
+
+    fn handle_value(&self, mut value: Value) {
+        // Responses carry an id; notifications carry a method and a subscription id
+        if let Some(id) = value.get("id").and_then(Value::as_u64) {
+            if let Some((_, pending)) = self.pending.remove(&id) {
+                self.resolve(pending, value);
+            }
+            return;
+        }
+
+        let Some(params) = value.get_mut("params") else {
+            return;
+        };
+        let Some(server_id) = params.get("subscription").map(subscription_key) else {
+            return;
+        };
+        let result = params.get_mut("result").map(Value::take).unwrap_or(Value::Null);
+
+        if let Some(local_id) = self.routes.get(&server_id).map(|r| *r) {
+            if let Some(entry) = self.subscriptions.get(&local_id) {
+                let _ = entry.sender.send(result);
+            }
+        }
+    }
+
+    fn resolve(&self, pending: Pending, response: Value) {
+        let result = parse_response(response);
+        match pending {
+            Pending::Call(reply) => {
+                let _ = reply.send(result);
+            }
+            Pending::Subscribe { local_id, reply } => {
+                if let Ok(server_id) = &result {
+                    let key = subscription_key(server_id);
+                    if let Some(mut entry) = self.subscriptions.get_mut(&local_id) {
+                        if let Some(old) = entry.server_id.replace(key.clone()) {
+                            self.routes.remove(&old);
+                        }
+                        self.routes.insert(key, local_id);
+                    }
+                } else if reply.is_none() {
+                    tracing::warn!("Failed to restore subscription {} after reconnect", local_id);
+                }
+                if let Some(reply) = reply {
+                    let _ = reply.send(result);
+                }
+            }
+        }
+    }
+
+    fn fail_pending(&self, reason: &str) {
+        let ids: Vec<u64> = self.pending.iter().map(|r| *r.key()).collect();
+        for id in ids {
+            if let Some((_, pending)) = self.pending.remove(&id) {
+                let err = || Err(ProviderError::ConnectionFailed(reason.to_string()));
+                match pending {
+                    Pending::Call(reply) => {
+                        let _ = reply.send(err());
+                    }
+                    Pending::Subscribe { reply: Some(reply), .. } => {
+                        let _ = reply.send(err());
+                    }
+                    Pending::Subscribe { reply: None, .. } => {}
+                }
+            }
+        }
+    }
+}
+
+fn subscription_key(id: &Value) -> String {
+    match id {
+        Value::String(s) => s.clone(),
+        other => other.to_string(),
+    }
+}
+
+fn parse_response(mut response: Value) -> Result<Value> {
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        return Err(ProviderError::RpcError {
+            code: error.get("code").and_then(Value::as_i64).unwrap_or(-1),
+            message: error
+                .get("message")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+    match response.get_mut("result").map(Value::take) {
+        Some(result) => Ok(result),
+        None => Err(ProviderError::RpcError {
+            code: -1,
+            message: "No result in response".to_string(),
+        }),
+    }
+}
+
+/// WebSocket JSON-RPC client with automatic reconnect and subscriptions
+///
+/// The connection is owned by a background task. Requests are multiplexed over
+/// it by id, and notifications are routed to [`Subscription`]s by subscription id.
+/// After a reconnect, active subscriptions are re-established transparently.
+#[derive(Clone)]
+pub struct WsClient {
+    shared: Arc<Shared>,
+    commands: mpsc::UnboundedSender<Command>,
+    state: watch::Receiver<ConnectionState>,
+}
+
+impl WsClient {
+    /// Connects to a WebSocket endpoint
+    ///
+    /// Returns once the initial connection is established. Later disconnects
+    /// are handled by reconnecting in the background.
+    pub async fn connect(config: WsConfig) -> Result<Self> {
+        config.validate()?;
+
+        let shared = Arc::new(Shared {
+            config,
+            request_id: AtomicU64::new(1),
+            next_subscription: AtomicU64::new(1),
+            pending: DashMap::new(),
+            subscriptions: DashMap::new(),
+            routes: DashMap::new(),
+        });
+
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        let (state_tx, state) = watch::channel(ConnectionState::Connecting);
+        let (ready_tx, ready_rx) = oneshot::channel();
+
+        tokio::spawn(run_connection(shared.clone(), command_rx, state_tx, ready_tx));
+
+        ready_rx
+            .await
+            .map_err(|_| ProviderError::ConnectionFailed("WebSocket task exited".to_string()))??;
+
+        Ok(Self {
+            shared,
+            commands,
+            state,
+        })
+    }
+
+    /// Returns the current connection state
+    pub fn state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Returns the endpoint URL
+    pub fn url(&self) -> &str {
+        &self.shared.config.url
+    }
+
+    /// Makes a JSON-RPC request over the WebSocket
+    pub async fn rpc_call<P, R>(&self, method: &str, params: P) -> Result<R>
+    where
+        P: Serialize,
+        R: DeserializeOwned,
+    {
+        let (tx, rx) = oneshot::channel();
+        let value = self.request(method, params, Pending::Call(tx), rx).await?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Subscribes using `eth_subscribe` / `eth_unsubscribe`
+    ///
+    /// # Example
+    ///
+    /// ```ignore
+    /// let mut heads = client.subscribe::<_, serde_json::Value>(["newHeads"]).await?;
+    /// while let Some(head) = heads.next().await {
+    ///     println!("new block: {}", head?["number"]);
+    /// }
+    /// ```
+    pub async fn subscribe<P, T>(&self, params: P) -> Result<Subscription<T>>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        self.subscribe_with("eth_subscribe", "eth_unsubscribe", params)
+            .await
+    }
+
+    /// Subscribes using chain-specific subscribe/unsubscribe methods
+    /// (e.g. Solana's `accountSubscribe` / `accountUnsubscribe`)
+    pub async fn subscribe_with<P, T>(
+        &self,
+        subscribe_method: &str,
+        unsubscribe_method: &str,
+        params: P,
+    ) -> Result<Subscription<T>>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let params = serde_json::to_value(params)?;
+        let local_id = self.shared.next_subscription.fetch_add(1, Ordering::SeqCst);
+        let (sender, receiver) = mpsc::unbounded_channel();
+
+        self.shared.subscriptions.insert(
+            local_id,
+            SubscriptionEntry {
+                method: subscribe_method.to_string(),
+                params: params.clone(),
+                server_id: None,
+                sender,
+            },
+        );
+
+        let (tx, rx) = oneshot::channel();
+        let pending = Pending::Subscribe {
+            local_id,
+            reply: Some(tx),
+        };
+        if let Err(e) = self.request(subscribe_method, params, pending, rx).await {
+            self.shared.subscriptions.remove(&local_id);
+            return Err(e);
+        }
+
+        Ok(Subscription {
+            local_id,
+            unsubscribe_method: unsubscribe_method.to_string(),
+            receiver,
+            shared: self.shared.clone(),
+            commands: self.commands.clone(),
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the number of active subscriptions
+    pub fn subscription_count(&self) -> usize {
+        self.shared.subscriptions.len()
+    }
+
+    /// Closes the connection and ends all subscriptions
+    pub fn close(&self) {
+        let _ = self.commands.send(Command::Close);
+    }
+
+    async fn request<P: Serialize>(
+        &self,
+        method: &str,
+        params: P,
+        pending: Pending,
+        rx: oneshot::Receiver<Result<Value>>,
+    ) -> Result<Value> {
+        let id = self.shared.next_id();
+        let text = self.shared.encode(id, method, params)?;
+
+        self.shared.pending.insert(id, pending);
+        if self.commands.send(Command::Send(text)).is_err() {
+            self.shared.pending.remove(&id);
+            return Err(ProviderError::ConnectionFailed("WebSocket closed".to_string()));
+        }
+
+        let timeout_secs = self.shared.config.request_timeout_secs;
+        match tokio::time::timeout(Duration::from_secs(timeout_secs), rx).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(ProviderError::ConnectionFailed("WebSocket closed".to_string())),
+            Err(_) => {
+                self.shared.pending.remove(&id);
+                Err(ProviderError::Timeout(timeout_secs))
+            }
+        }
+    }
+}
+
+impl std::fmt::Debug for WsClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WsClient")
+            .field("url", &self.shared.config.url)
+            .field("state", &self.state())
+            .field("subscriptions", &self.subscription_count())
+            .finish()
+    }
+}
+
+/// A stream of notifications for one subscription
+///
+/// Dropping the subscription unsubscribes on the server.
+pub struct Subscription<T> {
+    local_id: u64,
+    unsubscribe_method: String,
+    receiver: mpsc::UnboundedReceiver<Value>,
+    shared: Arc<Shared>,
+    commands: mpsc::UnboundedSender<Command>,
+    _marker: PhantomData<T>,
+}
+
+impl<T: DeserializeOwned> Subscription<T> {
+    /// Waits for the next notification
+    ///
+    /// Returns `None` once the client has been closed.
+    pub async fn next(&mut self) -> Option<Result<T>> {
+        let value = self.receiver.recv().await?;
+        Some(serde_json::from_value(value).map_err(ProviderError::from))
+    }
+
+    /// Returns the current server-assigned subscription id
+    ///
+    /// The id changes when the subscription is restored after a reconnect.
+    pub fn server_id(&self) -> Option<String> {
+        self.shared
+            .subscriptions
+            .get(&self.local_id)
+            .and_then(|entry| entry.server_id.clone())
+    }
+}
+
+impl<T> Drop for Subscription<T> {
+    fn drop(&mut self) {
+        let Some((_, entry)) = self.shared.subscriptions.remove(&self.local_id) else {
+            return;
+        };
+        if let Some(server_id) = entry.server_id {
+            self.shared.routes.remove(&server_id);
+            let id = self.shared.next_id();
+            if let Ok(text) = self.shared.encode(id, &self.unsubscribe_method, [server_id]) {
+                let _ = self.commands.send(Command::Send(text));
+            }
+        }
+    }
+}
+
+impl<T> std::fmt::Debug for Subscription<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("local_id", &self.local_id)
+            .field("unsubscribe_method", &self.unsubscribe_method)
+            .finish()
+    }
+}
+
+async fn run_connection(
+    shared: Arc<Shared>,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+    state: watch::Sender<ConnectionState>,
+    ready: oneshot::Sender<Result<()>>,
+) {
+    let config = shared.config.clone();
+    let mut ready = Some(ready);
+    let mut attempt = 0u32;
+
+    loop {
+        let mut socket = match tokio_tungstenite::connect_async(config.url.as_str()).await {
+            Ok((socket, _)) => socket,
+            Err(e) => {
+                if let Some(ready) = ready.take() {
+                    let _ = state.send(ConnectionState::Closed);
+                    let _ = ready.send(Err(ProviderError::ConnectionFailed(e.to_string())));
+                    return;
+                }
+                if config.max_reconnect_attempts.is_some_and(|max| attempt >= max) {
+                    tracing::warn!("Giving up on {} after {} reconnect attempts", config.url, attempt);
+                    break;
+                }
+                let delay = config.reconnect_delay(attempt);
+                attempt += 1;
+                tracing::debug!("Reconnect to {} failed ({}), retrying in {:?}", config.url, e, delay);
+                tokio::time::sleep(delay).await;
+                continue;
+            }
+        };
+
+        attempt = 0;
+        let _ = state.send(ConnectionState::Connected);
+        match ready.take() {
+            Some(ready) => {
+                let _ = ready.send(Ok(()));
+            }
+            None => {
+                tracing::info!("Reconnected to {}", config.url);
+                if resubscribe_all(&shared, &mut socket).await.is_err() {
+                    let _ = state.send(ConnectionState::Reconnecting);
+                    continue;
+                }
+            }
+        }
+
+        let mut ping = (config.ping_interval_secs > 0).then(|| {
+            let period = Duration::from_secs(config.ping_interval_secs);
+            tokio::time::interval_at(tokio::time::Instant::now() + period, period)
+        });
+
+        let closed = loop {
+            tokio::select! {
+                command = commands.recv() => match command {
+                    Some(Command::Send(text)) => {
+                        if socket.send(Message::Text(text)).await.is_err() {
+                            break false;
+                        }
+                    }
+                    Some(Command::Close) | None => {
+                        let _ = socket.close(None).await;
+                        break true;
+                    }
+                },
+                message = socket.next() => match message {
+                    Some(Ok(Message::Text(text))) => shared.handle_text(&text),
+                    Some(Ok(Message::Binary(bytes))) => {
+                        if let Ok(text) = std::str::from_utf8(&bytes) {
+                            shared.handle_text(text);
+                        }
+                    }
+                    Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break false,
+                    Some(Ok(_)) => {}
+                },
+                _ = async { ping.as_mut().unwrap().tick().await }, if ping.is_some() => {
+                    if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                        break false;
+                    }
+                }
+            }
+        };
+
+        if closed {
+            break;
+        }
+
+        tracing::warn!("WebSocket connection to {} lost, reconnecting", config.url);
+        let _ = state.send(ConnectionState::Reconnecting);
+        // Requests in flight were lost with the connection; queued ones are stale
+        while let Ok(command) = commands.try_recv() {
+            if matches!(command, Command::Close) {
+                shared.fail_pending("WebSocket closed");
+                let _ = state.send(ConnectionState::Closed);
+                shared.subscriptions.clear();
+                return;
+            }
+        }
+        shared.fail_pending("WebSocket connection lost");
+        for mut entry in shared.subscriptions.iter_mut() {
+            entry.server_id = None;
+        }
+        shared.routes.clear();
+    }
+
+    shared.fail_pending("WebSocket closed");
+    let _ = state.send(ConnectionState::Closed);
+    shared.subscriptions.clear();
+    shared.routes.clear();
+}
+
+async fn resubscribe_all<S>(shared: &Shared, socket: &mut S) -> std::result::Result<(), ()>
+where
+    S: futures_util::Sink<Message> + Unpin,
+{
+    let entries: Vec<(u64, String, Value)> = shared
+        .subscriptions
+        .iter()
+        .map(|entry| (*entry.key(), entry.method.clone(), entry.params.clone()))
+        .collect();
+
+    for (local_id, method, params) in entries {
+        let id = shared.next_id();
+        let Ok(text) = shared.encode(id, &method, params) else {
+            continue;
+        };
+        shared.pending.insert(id, Pending::Subscribe { local_id, reply: None });
+        if socket.send(Message::Text(text)).await.is_err() {
+            return Err(());
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    /// Minimal JSON-RPC server: answers `eth_subscribe` with a subscription id
+    /// and immediately pushes one notification; echoes params for other methods.
+    /// Closes each connection after `drop_after` messages, if set.
+    async fn spawn_server(drop_after: Option<usize>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            let mut connection = 0u32;
+            while let Ok((stream, _)) = listener.accept().await {
+                connection += 1;
+                let mut ws = tokio_tungstenite::accept_async(stream).await.unwrap();
+                let mut handled = 0usize;
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let request: Value = serde_json::from_str(&text).unwrap();
+                    let id = request["id"].clone();
+                    let method = request["method"].as_str().unwrap_or_default();
+
+                    let replies = if method == "eth_subscribe" {
+                        let sub = format!("0xsub{}", connection);
+                        vec![
+                            serde_json::json!({"jsonrpc": "2.0", "id": id, "result": sub}),
+                            serde_json::json!({
+                                "jsonrpc": "2.0",
+                                "method": "eth_subscription",
+                                "params": {"subscription": sub, "result": {"connection": connection}}
+                            }),
+                        ]
+                    } else if method == "fail" {
+                        vec![serde_json::json!({
+                            "jsonrpc": "2.0", "id": id,
+                            "error": {"code": -32601, "message": "method not found"}
+                        })]
+                    } else {
+                        vec![serde_json::json!({"jsonrpc": "2.0", "id": id, "result": request["params"]})]
+                    };
+
+                    for reply in replies {
+                        ws.send(Message::Text(reply.to_string())).await.unwrap();
+                    }
+
+                    handled += 1;
+                    if drop_after == Some(handled) {
+                        break;
+                    }
+                }
+            }
+        });
+
+        format!("ws://{}", addr)
+    }
+
+    #[test]
+    fn test_ws_config_validation() {
+        assert!(WsConfig::new("wss://eth.example.com").validate().is_ok());
+        assert!(WsConfig::new("https://eth.example.com").validate().is_err());
+        assert!(WsConfig::new("not a url").validate().is_err());
+    }
+
+    #[test]
+    fn test_reconnect_delay_is_capped() {
+        let config = WsConfig::new("ws://localhost").with_reconnect_delay(100, 1_000);
+        assert_eq!(config.reconnect_delay(0), Duration::from_millis(100));
+        assert_eq!(config.reconnect_delay(2), Duration::from_millis(400));
+        assert_eq!(config.reconnect_delay(10), Duration::from_millis(1_000));
+        assert_eq!(config.reconnect_delay(u32::MAX), Duration::from_millis(1_000));
+    }
+
+    #[tokio::test]
+    async fn test_connect_failure() {
+        let result = WsClient::connect(WsConfig::new("ws://127.0.0.1:1")).await;
+        assert!(matches!(result, Err(ProviderError::ConnectionFailed(_))));
+    }
+
+    #[tokio::test]
+    async fn test_rpc_call_roundtrip() {
+        let url = spawn_server(None).await;
+        let client = WsClient::connect(WsConfig::new(url)).await.unwrap();
+        assert_eq!(client.state(), ConnectionState::Connected);
+
+        let echoed: Vec<String> = client.rpc_call("echo", ["a", "b"]).await.unwrap();
+        assert_eq!(echoed, vec!["a", "b"]);
+
+        let err = client.rpc_call::<_, Value>("fail", ()).await.unwrap_err();
+        assert!(matches!(err, ProviderError::RpcError { code: -32601, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_subscription_receives_notifications() {
+        let url = spawn_server(None).await;
+        let client = WsClient::connect(WsConfig::new(url)).await.unwrap();
+
+        let mut sub = client.subscribe::<_, Value>(["newHeads"]).await.unwrap();
+        assert_eq!(sub.server_id().as_deref(), Some("0xsub1"));
+
+        let event = sub.next().await.unwrap().unwrap();
+        assert_eq!(event["connection"], 1);
+
+        assert_eq!(client.subscription_count(), 1);
+        drop(sub);
+        assert_eq!(client.subscription_count(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_restores_subscriptions() {
+        // The server drops every connection after two messages
+        let url = spawn_server(Some(2)).await;
+        let config = WsConfig::new(url).with_reconnect_delay(10, 100);
+        let client = WsClient::connect(config).await.unwrap();
+
+        let mut sub = client.subscribe::<_, Value>(["newHeads"]).await.unwrap();
+        assert_eq!(sub.next().await.unwrap().unwrap()["connection"], 1);
+
+        // Second message closes the first connection
+        let _: Value = client.rpc_call("echo", ["x"]).await.unwrap();
+
+        // The subscription is re-established on the new connection
+        let event = tokio::time::timeout(Duration::from_secs(5), sub.next())
+            .await
+            .unwrap()
+            .unwrap()
+            .unwrap();
+        assert_eq!(event["connection"], 2);
+        assert_eq!(sub.server_id().as_deref(), Some("0xsub2"));
+        assert_eq!(client.state(), ConnectionState::Connected);
+    }
+
+    #[tokio::test]
+    async fn test_close_ends_subscriptions() {
+        let url = spawn_server(None).await;
+        let client = WsClient::connect(WsConfig::new(url)).await.unwrap();
+        let mut sub = client.subscribe::<_, Value>(["newHeads"]).await.unwrap();
+        let _ = sub.next().await;
+
+        client.close();
+        assert!(tokio::time::timeout(Duration::from_secs(5), sub.next())
+            .await
+            .unwrap()
+            .is_none());
+        assert_eq!(client.state(), ConnectionState::Closed);
+    }
+}
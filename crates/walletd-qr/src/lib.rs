@@ -0,0 +1,89 @@
+//! # WalletD QR
+//!
+//! QR codes for receive screens, checkout pages and air-gapped signers:
+//!
+//! - [`Qr`]: renders addresses, [`PaymentRequest`]s or any text as PNG or
+//!   SVG bytes
+//! - [`parse_scanned`]: turns scanned text into a validated address,
+//!   payment request or UR fragment
+//! - [`ur`]: Uniform Resources (BCR-2020-005), which split payloads too
+//!   large for one code (PSBTs, typed data) into an animated sequence of
+//!   fountain-coded fragments
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_qr::{parse_scanned, Qr, QrOptions, Scanned};
+//!
+//! let png = Qr::for_payment(&request)?.to_png(&QrOptions::default())?;
+//!
+//! match parse_scanned(&text)? {
+//!     Scanned::Payment(request) => prefill_send(request),
+//!     Scanned::Address { address, chains } => pick_chain(address, chains),
+//!     Scanned::Ur(part) => decoder.receive(&part)?,
+//! }
+//! ```
+//!
+//! [`PaymentRequest`]: walletd_traits::PaymentRequest
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod render;
+pub mod scan;
+pub mod ur;
+
+pub use render::{ErrorCorrection, Qr, QrOptions};
+pub use scan::{parse_scanned, Scanned};
+pub use ur::{Ur, UrDecoder, UrEncoder};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// QR errors
+#[derive(Error, Debug)]
+pub enum QrError {
+    /// The data does not fit in a QR code
+    #[error("Cannot encode QR code: {0}")]
+    Encode(String),
+
+    /// Writing the PNG failed
+    #[error("PNG error: {0}")]
+    Png(String),
+
+    /// A malformed or inconsistent UR part
+    #[error("Invalid UR: {0}")]
+    Ur(String),
+
+    /// Scanned text is not an address, payment URI or UR
+    #[error("Unrecognized QR content: {0}")]
+    Unrecognized(String),
+
+    /// Address or payment request validation failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for QR operations
+pub type Result<T> = std::result::Result<T, QrError>;
+
+impl From<QrError> for WalletdError {
+    fn from(e: QrError) -> Self {
+        match e {
+            QrError::Wallet(WalletError::InvalidAddress(message)) => {
+                // Validators report "<address>: <reason>"
+                let (address, reason) = message.split_once(": ").unwrap_or(("", &message));
+                WalletdError::InvalidAddress {
+                    address: address.to_string(),
+                    reason: reason.to_string(),
+                }
+            }
+            QrError::Wallet(WalletError::NotSupported(reason)) => {
+                WalletdError::NotSupported(reason)
+            }
+            QrError::Png(_) => WalletdError::IoError(e.to_string()),
+            e => WalletdError::FormatError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,222 @@
+//! Rendering QR codes as PNG and SVG
+
+use crate::{QrError, Result};
+use qrcode::{Color, EcLevel, QrCode};
+use std::fmt::Write;
+use walletd_traits::{Chain, ChainRegistry, PaymentRequest};
+
+/// How much of the code may be damaged and still scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorCorrection {
+    /// About 7%; densest, for animated UR frames
+    Low,
+    /// About 15%
+    #[default]
+    Medium,
+    /// About 25%
+    Quartile,
+    /// About 30%; for printed codes or codes with a logo overlay
+    High,
+}
+
+impl From<ErrorCorrection> for EcLevel {
+    fn from(level: ErrorCorrection) -> Self {
+        match level {
+            ErrorCorrection::Low => EcLevel::L,
+            ErrorCorrection::Medium => EcLevel::M,
+            ErrorCorrection::Quartile => EcLevel::Q,
+            ErrorCorrection::High => EcLevel::H,
+        }
+    }
+}
+
+/// Image options
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QrOptions {
+    /// Pixels per module (PNG) or user units per module (SVG)
+    pub module_size: u32,
+    /// Blank border in modules; scanners expect at least 4
+    pub quiet_zone: u32,
+}
+
+impl Default for QrOptions {
+    fn default() -> Self {
+        Self {
+            module_size: 8,
+            quiet_zone: 4,
+        }
+    }
+}
+
+/// An encoded QR code
+#[derive(Clone)]
+pub struct Qr {
+    data: String,
+    code: QrCode,
+}
+
+impl std::fmt::Debug for Qr {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Qr")
+            .field("data", &self.data)
+            .field("width", &self.width())
+            .finish()
+    }
+}
+
+impl Qr {
+    /// Encodes arbitrary text
+    pub fn new(data: impl Into<String>, level: ErrorCorrection) -> Result<Self> {
+        let data = data.into();
+        let code = QrCode::with_error_correction_level(data.as_bytes(), level.into())
+            .map_err(|e| QrError::Encode(e.to_string()))?;
+        Ok(Self { data, code })
+    }
+
+    /// Encodes a receive address, validated and normalized for `chain`
+    ///
+    /// Chains with a payment URI scheme get a bare URI (`bitcoin:bc1...`)
+    /// so scanning wallets know the chain; others get the address itself.
+    pub fn for_address(chain: Chain, address: &str) -> Result<Self> {
+        let address = ChainRegistry::with_defaults().normalize(chain, address)?;
+        let data = PaymentRequest::new(chain, address.clone())
+            .to_uri()
+            .unwrap_or(address);
+        Self::new(data, ErrorCorrection::Medium)
+    }
+
+    /// Encodes a payment request as its chain's URI
+    pub fn for_payment(request: &PaymentRequest) -> Result<Self> {
+        Self::new(request.to_uri()?, ErrorCorrection::Medium)
+    }
+
+    /// Encodes one UR part, uppercased so it fits QR alphanumeric mode
+    pub fn for_ur_part(part: &str) -> Result<Self> {
+        Self::new(part.to_ascii_uppercase(), ErrorCorrection::Low)
+    }
+
+    /// The encoded text
+    pub fn data(&self) -> &str {
+        &self.data
+    }
+
+    /// Modules per side, without the quiet zone
+    pub fn width(&self) -> usize {
+        self.code.width()
+    }
+
+    /// Whether the module at (`x`, `y`) is dark
+    pub fn is_dark(&self, x: usize, y: usize) -> bool {
+        self.code[(x, y)] == Color::Dark
+    }
+
+    /// Renders an 8-bit grayscale PNG
+    pub fn to_png(&self, options: &QrOptions) -> Result<Vec<u8>> {
+        let scale = options.module_size.max(1) as usize;
+        let quiet = options.quiet_zone as usize;
+        let side = (self.width() + 2 * quiet) * scale;
+        let side_u32 = u32::try_from(side).map_err(|_| QrError::Png("image too large".into()))?;
+
+        let mut pixels = vec![0xffu8; side * side];
+        for y in 0..self.width() {
+            for x in (0..self.width()).filter(|x| self.is_dark(*x, y)) {
+                for row in 0..scale {
+                    let start = ((y + quiet) * scale + row) * side + (x + quiet) * scale;
+                    pixels[start..start + scale].fill(0);
+                }
+            }
+        }
+
+        let mut out = Vec::new();
+        let mut encoder = png::Encoder::new(&mut out, side_u32, side_u32);
+        encoder.set_color(png::ColorType::Grayscale);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder
+            .write_header()
+            .map_err(|e| QrError::Png(e.to_string()))?;
+        writer
+            .write_image_data(&pixels)
+            .map_err(|e| QrError::Png(e.to_string()))?;
+        writer.finish().map_err(|e| QrError::Png(e.to_string()))?;
+        Ok(out)
+    }
+
+    /// Renders a standalone SVG document
+    pub fn to_svg(&self, options: &QrOptions) -> String {
+        let scale = options.module_size.max(1) as usize;
+        let quiet = options.quiet_zone as usize;
+        let side = self.width() + 2 * quiet;
+
+        let mut path = String::new();
+        for y in 0..self.width() {
+            for x in (0..self.width()).filter(|x| self.is_dark(*x, y)) {
+                let _ = write!(path, "M{} {}h1v1h-1z", x + quiet, y + quiet);
+            }
+        }
+        format!(
+            concat!(
+                r#"<?xml version="1.0" encoding="UTF-8"?>"#,
+                r#"<svg xmlns="http://www.w3.org/2000/svg" width="{px}" height="{px}" viewBox="0 0 {side} {side}" shape-rendering="crispEdges">"#,
+                r##"<rect width="{side}" height="{side}" fill="#fff"/><path fill="#000" d="{path}"/></svg>"##
+            ),
+            px = side * scale,
+            side = side,
+            path = path
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::Amount;
+
+    #[test]
+    fn test_png_and_svg() {
+        let qr = Qr::for_address(
+            Chain::Ethereum,
+            "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359",
+        )
+        .unwrap();
+        assert_eq!(
+            qr.data(),
+            "ethereum:0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359"
+        );
+
+        let options = QrOptions {
+            module_size: 2,
+            quiet_zone: 4,
+        };
+        let png = qr.to_png(&options).unwrap();
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        let side = ((qr.width() + 8) * 2) as u32;
+        assert_eq!(u32::from_be_bytes(png[16..20].try_into().unwrap()), side);
+
+        let svg = qr.to_svg(&options);
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(&format!(r#"width="{}""#, side)));
+        // The top-left finder pattern starts one quiet zone in
+        assert!(qr.is_dark(0, 0));
+        assert!(svg.contains("M4 4h1v1h-1z"));
+    }
+
+    #[test]
+    fn test_payment_and_invalid_input() {
+        let request = PaymentRequest::new(Chain::Bitcoin, "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa")
+            .with_amount(Amount::from_smallest_unit(50_000, 8));
+        let qr = Qr::for_payment(&request).unwrap();
+        assert_eq!(
+            qr.data(),
+            "bitcoin:1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa?amount=0.0005"
+        );
+
+        assert!(matches!(
+            Qr::for_address(Chain::Solana, "not-base58!"),
+            Err(QrError::Wallet(_))
+        ));
+        assert!(matches!(
+            Qr::new("x".repeat(8000), ErrorCorrection::High),
+            Err(QrError::Encode(_))
+        ));
+    }
+}
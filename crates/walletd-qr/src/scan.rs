@@ -0,0 +1,86 @@
+//! Parsing scanned QR text
+
+use crate::{QrError, Result};
+use walletd_traits::{Chain, ChainRegistry, PaymentRequest};
+
+/// What a scanned code contains
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Scanned {
+    /// A bare address and the chains it is valid on
+    Address {
+        /// The address, normalized when only one chain matches
+        address: String,
+        /// Matching chains, in [`Chain::ALL`] order
+        chains: Vec<Chain>,
+    },
+    /// A payment URI
+    Payment(PaymentRequest),
+    /// One UR part, lowercased; feed it to a [`UrDecoder`](crate::UrDecoder)
+    Ur(String),
+}
+
+/// Classifies and validates scanned text
+///
+/// UR parts are recognized but not decoded, since animated codes need
+/// many scans. Anything with a scheme must be a supported payment URI.
+pub fn parse_scanned(text: &str) -> Result<Scanned> {
+    let text = text.trim();
+    if text.len() > 3 && text[..3].eq_ignore_ascii_case("ur:") {
+        return Ok(Scanned::Ur(text.to_ascii_lowercase()));
+    }
+    // Raw TON addresses (`0:<hex>`) contain a colon but are not URIs
+    let registry = ChainRegistry::with_defaults();
+    let chains = registry.detect(text);
+    if text.contains(':') && chains.is_empty() {
+        return Ok(Scanned::Payment(PaymentRequest::parse(text)?));
+    }
+    match chains.as_slice() {
+        [] => Err(QrError::Unrecognized(text.to_string())),
+        [chain] => Ok(Scanned::Address {
+            address: registry.normalize(*chain, text)?,
+            chains,
+        }),
+        _ => Ok(Scanned::Address {
+            address: text.to_string(),
+            chains,
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_scanned() {
+        match parse_scanned(" 0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359\n").unwrap() {
+            Scanned::Address { address, chains } => {
+                assert_eq!(address, "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359");
+                assert_eq!(chains, vec![Chain::Ethereum]);
+            }
+            other => panic!("unexpected {:?}", other),
+        }
+
+        let uri = "BITCOIN:1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa?amount=0.1";
+        assert!(matches!(
+            parse_scanned(uri).unwrap(),
+            Scanned::Payment(PaymentRequest {
+                chain: Chain::Bitcoin,
+                ..
+            })
+        ));
+        assert_eq!(
+            parse_scanned("UR:BYTES/HDCXAEAE").unwrap(),
+            Scanned::Ur("ur:bytes/hdcxaeae".into())
+        );
+
+        assert!(matches!(
+            parse_scanned("hello world"),
+            Err(QrError::Unrecognized(_))
+        ));
+        assert!(matches!(
+            parse_scanned("litecoin:abc"),
+            Err(QrError::Wallet(_))
+        ));
+    }
+}
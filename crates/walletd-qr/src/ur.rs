@@ -0,0 +1,647 @@
+//! Uniform Resources (BCR-2020-005)
+//!
+//! A UR is a typed CBOR message encoded as minimal bytewords, e.g.
+//! `ur:crypto-psbt/hdosjojk...`. Messages too large for one QR code are
+//! split into equal fragments and sent as an animated sequence of parts
+//! `ur:<type>/<seq>-<count>/...`. After the first `count` parts the
+//! [`UrEncoder`] emits fountain-coded parts that each XOR a pseudo-random
+//! set of fragments, so a [`UrDecoder`] can finish from whichever frames
+//! it happens to catch.
+//!
+//! ```ignore
+//! let mut encoder = UrEncoder::new(Ur::from_bytes("crypto-psbt", &psbt)?, 200)?;
+//! loop { show(Qr::for_ur_part(&encoder.next_part())?); }
+//!
+//! let mut decoder = UrDecoder::new();
+//! while !decoder.is_complete() { decoder.receive(&scan())?; }
+//! let psbt = decoder.result().unwrap().payload()?;
+//! ```
+
+use crate::{QrError, Result};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeSet;
+
+/// Bytewords (BCR-2020-012); minimal encoding uses each word's first and
+/// last letter
+const BYTEWORDS: [&str; 256] = [
+    "able", "acid", "also", "apex", "aqua", "arch", "atom", "aunt", "away", "axis", "back", "bald",
+    "barn", "belt", "beta", "bias", "blue", "body", "brag", "brew", "bulb", "buzz", "calm", "cash",
+    "cats", "chef", "city", "claw", "code", "cola", "cook", "cost", "crux", "curl", "cusp", "cyan",
+    "dark", "data", "days", "deli", "dice", "diet", "door", "down", "draw", "drop", "drum", "dull",
+    "duty", "each", "easy", "echo", "edge", "epic", "even", "exam", "exit", "eyes", "fact", "fair",
+    "fern", "figs", "film", "fish", "fizz", "flap", "flew", "flux", "foxy", "free", "frog", "fuel",
+    "fund", "gala", "game", "gear", "gems", "gift", "girl", "glow", "good", "gray", "grim", "guru",
+    "gush", "gyro", "half", "hang", "hard", "hawk", "heat", "help", "high", "hill", "holy", "hope",
+    "horn", "huts", "iced", "idea", "idle", "inch", "inky", "into", "iris", "iron", "item", "jade",
+    "jazz", "join", "jolt", "jowl", "judo", "jugs", "jump", "junk", "jury", "keep", "keno", "kept",
+    "keys", "kick", "kiln", "king", "kite", "kiwi", "knob", "lamb", "lava", "lazy", "leaf", "legs",
+    "liar", "limp", "lion", "list", "logo", "loud", "love", "luau", "luck", "lung", "main", "many",
+    "math", "maze", "memo", "menu", "meow", "mild", "mint", "miss", "monk", "nail", "navy", "need",
+    "news", "next", "noon", "note", "numb", "obey", "oboe", "omit", "onyx", "open", "oval", "owls",
+    "paid", "part", "peck", "play", "plus", "poem", "pool", "pose", "puff", "puma", "purr", "quad",
+    "quiz", "race", "ramp", "real", "redo", "rich", "road", "rock", "roof", "ruby", "ruin", "runs",
+    "rust", "safe", "saga", "scar", "sets", "silk", "skew", "slot", "soap", "solo", "song", "stub",
+    "surf", "swan", "taco", "task", "taxi", "tent", "tied", "time", "tiny", "toil", "tomb", "toys",
+    "trip", "tuna", "twin", "ugly", "undo", "unit", "urge", "user", "vast", "very", "veto", "vial",
+    "vibe", "view", "visa", "void", "vows", "wall", "wand", "warm", "wasp", "wave", "waxy", "webs",
+    "what", "when", "whiz", "wolf", "work", "yank", "yawn", "yell", "yoga", "yurt", "zaps", "zero",
+    "zest", "zinc", "zone", "zoom",
+];
+
+/// Fragments are never shorter than this, so tiny limits do not explode
+/// into thousands of parts
+const MIN_FRAGMENT_LEN: usize = 10;
+
+/// A typed CBOR message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ur {
+    ur_type: String,
+    cbor: Vec<u8>,
+}
+
+impl Ur {
+    /// A UR holding an already CBOR-encoded message
+    ///
+    /// Types are lowercase letters, digits and `-` (e.g. `crypto-psbt`).
+    pub fn new(ur_type: &str, cbor: Vec<u8>) -> Result<Self> {
+        let valid = !ur_type.is_empty()
+            && ur_type
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'-');
+        if !valid {
+            return Err(QrError::Ur(format!("invalid type {:?}", ur_type)));
+        }
+        Ok(Self {
+            ur_type: ur_type.to_string(),
+            cbor,
+        })
+    }
+
+    /// A UR whose message is a CBOR byte string, as for `bytes` and
+    /// `crypto-psbt`
+    pub fn from_bytes(ur_type: &str, payload: &[u8]) -> Result<Self> {
+        let mut cbor = cbor_head(2, payload.len() as u64);
+        cbor.extend_from_slice(payload);
+        Self::new(ur_type, cbor)
+    }
+
+    /// The type, e.g. `crypto-psbt`
+    pub fn ur_type(&self) -> &str {
+        &self.ur_type
+    }
+
+    /// The CBOR message
+    pub fn cbor(&self) -> &[u8] {
+        &self.cbor
+    }
+
+    /// Unwraps a message that is a single CBOR byte string
+    pub fn payload(&self) -> Result<Vec<u8>> {
+        let mut reader = Cbor::new(&self.cbor);
+        let payload = reader.bytes()?;
+        if !reader.is_empty() {
+            return Err(QrError::Ur("trailing data after byte string".into()));
+        }
+        Ok(payload.to_vec())
+    }
+
+    /// Single-part encoding, for messages that fit in one code
+    pub fn to_single_part(&self) -> String {
+        format!("ur:{}/{}", self.ur_type, bytewords_encode(&self.cbor))
+    }
+}
+
+/// Splits a UR into an endless sequence of parts
+#[derive(Debug, Clone)]
+pub struct UrEncoder {
+    ur: Ur,
+    fragments: Vec<Vec<u8>>,
+    checksum: u32,
+    seq: u32,
+}
+
+impl UrEncoder {
+    /// Splits `ur` into fragments of at most `max_fragment_len` bytes
+    ///
+    /// Around 200 bytes keeps each frame in a QR code that scans easily on
+    /// a phone.
+    pub fn new(ur: Ur, max_fragment_len: usize) -> Result<Self> {
+        if max_fragment_len < MIN_FRAGMENT_LEN {
+            return Err(QrError::Ur(format!(
+                "max_fragment_len must be at least {}",
+                MIN_FRAGMENT_LEN
+            )));
+        }
+        let len = ur.cbor.len().max(1);
+        let count = len.div_ceil(max_fragment_len);
+        let fragment_len = len.div_ceil(count);
+        let mut padded = ur.cbor.clone();
+        padded.resize(fragment_len * count, 0);
+        Ok(Self {
+            checksum: crc32fast::hash(&ur.cbor),
+            fragments: padded.chunks(fragment_len).map(<[u8]>::to_vec).collect(),
+            ur,
+            seq: 0,
+        })
+    }
+
+    /// Number of fragments; a decoder needs at least this many parts
+    pub fn fragment_count(&self) -> usize {
+        self.fragments.len()
+    }
+
+    /// Whether the whole message fits in one part
+    pub fn is_single_part(&self) -> bool {
+        self.fragments.len() == 1
+    }
+
+    /// The next part to display
+    ///
+    /// Single-part URs always return the same string. Multi-part URs cycle
+    /// through the plain fragments once, then continue with fountain parts.
+    pub fn next_part(&mut self) -> String {
+        if self.is_single_part() {
+            return self.ur.to_single_part();
+        }
+        self.seq = self.seq.wrapping_add(1).max(1);
+        let count = self.fragments.len() as u32;
+        let mut fragment = vec![0u8; self.fragments[0].len()];
+        for index in choose_fragments(self.seq, count, self.checksum) {
+            xor_into(&mut fragment, &self.fragments[index]);
+        }
+
+        let mut part = vec![0x85];
+        for value in [
+            self.seq as u64,
+            count as u64,
+            self.ur.cbor.len() as u64,
+            self.checksum as u64,
+        ] {
+            part.extend(cbor_head(0, value));
+        }
+        part.extend(cbor_head(2, fragment.len() as u64));
+        part.extend(fragment);
+        format!(
+            "ur:{}/{}-{}/{}",
+            self.ur.ur_type,
+            self.seq,
+            count,
+            bytewords_encode(&part)
+        )
+    }
+}
+
+/// Reassembles a UR from scanned parts, in any order
+#[derive(Debug, Clone, Default)]
+pub struct UrDecoder {
+    ur_type: Option<String>,
+    /// `(count, message_len, checksum)` of the sequence being decoded
+    header: Option<(usize, usize, u32)>,
+    fragments: Vec<Option<Vec<u8>>>,
+    mixed: Vec<(BTreeSet<usize>, Vec<u8>)>,
+    result: Option<Ur>,
+}
+
+impl UrDecoder {
+    /// An empty decoder
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a scanned part; duplicates and parts after completion are ignored
+    ///
+    /// Fails on malformed parts and on parts from a different UR than the
+    /// ones already received.
+    pub fn receive(&mut self, part: &str) -> Result<()> {
+        if self.result.is_some() {
+            return Ok(());
+        }
+        let part = part.trim().to_ascii_lowercase();
+        let rest = part
+            .strip_prefix("ur:")
+            .ok_or_else(|| QrError::Ur("missing ur: prefix".into()))?;
+        let segments: Vec<&str> = rest.split('/').collect();
+        let (ur_type, sequence, body) = match segments.as_slice() {
+            [ur_type, body] => (*ur_type, None, *body),
+            [ur_type, sequence, body] => (*ur_type, Some(*sequence), *body),
+            _ => {
+                return Err(QrError::Ur(
+                    "expected ur:<type>/[<seq>-<count>/]<data>".into(),
+                ))
+            }
+        };
+        if let Some(expected) = &self.ur_type {
+            if expected != ur_type {
+                return Err(QrError::Ur(format!(
+                    "part of a {} UR while decoding {}",
+                    ur_type, expected
+                )));
+            }
+        }
+        let body = bytewords_decode(body)?;
+
+        let Some(sequence) = sequence else {
+            self.result = Some(Ur::new(ur_type, body)?);
+            return Ok(());
+        };
+        let (seq, count, message_len, checksum, fragment) = parse_part(&body)?;
+        if sequence != format!("{}-{}", seq, count) {
+            return Err(QrError::Ur("sequence does not match part header".into()));
+        }
+        if count == 0 || seq == 0 || fragment.len() != message_len.div_ceil(count) {
+            return Err(QrError::Ur("inconsistent part header".into()));
+        }
+        match self.header {
+            None => {
+                Ur::new(ur_type, Vec::new())?;
+                self.ur_type = Some(ur_type.to_string());
+                self.header = Some((count, message_len, checksum));
+                self.fragments = vec![None; count];
+            }
+            Some(header) if header != (count, message_len, checksum) => {
+                return Err(QrError::Ur("part belongs to a different message".into()));
+            }
+            Some(_) => {}
+        }
+
+        let indexes = choose_fragments(seq, count as u32, checksum)
+            .into_iter()
+            .collect();
+        self.add(indexes, fragment.to_vec());
+        self.try_finish()
+    }
+
+    /// Whether the message is complete
+    pub fn is_complete(&self) -> bool {
+        self.result.is_some()
+    }
+
+    /// Share of fragments recovered so far, from 0.0 to 1.0
+    pub fn progress(&self) -> f64 {
+        if self.result.is_some() {
+            return 1.0;
+        }
+        match self.fragments.len() {
+            0 => 0.0,
+            count => self.fragments.iter().flatten().count() as f64 / count as f64,
+        }
+    }
+
+    /// The decoded UR, once complete
+    pub fn result(&self) -> Option<&Ur> {
+        self.result.as_ref()
+    }
+
+    /// Reduces a part by known fragments, recovering any it isolates
+    fn add(&mut self, indexes: BTreeSet<usize>, data: Vec<u8>) {
+        let mut queue = vec![(indexes, data)];
+        while let Some((mut indexes, mut data)) = queue.pop() {
+            for index in indexes.clone() {
+                if let Some(known) = &self.fragments[index] {
+                    xor_into(&mut data, known);
+                    indexes.remove(&index);
+                }
+            }
+            match indexes.len() {
+                0 => {}
+                1 => {
+                    let index = *indexes.first().expect("one index");
+                    self.fragments[index] = Some(data);
+                    // Mixed parts containing it may now reduce further
+                    let (reducible, rest) = std::mem::take(&mut self.mixed)
+                        .into_iter()
+                        .partition(|(mixed, _)| mixed.contains(&index));
+                    self.mixed = rest;
+                    queue.extend(reducible);
+                }
+                _ => {
+                    if !self.mixed.iter().any(|(mixed, _)| *mixed == indexes) {
+                        self.mixed.push((indexes, data));
+                    }
+                }
+            }
+        }
+    }
+
+    fn try_finish(&mut self) -> Result<()> {
+        let Some((_, message_len, checksum)) = self.header else {
+            return Ok(());
+        };
+        if self.fragments.iter().any(Option::is_none) {
+            return Ok(());
+        }
+        let mut message: Vec<u8> = self.fragments.iter().flatten().flatten().copied().collect();
+        message.truncate(message_len);
+        if crc32fast::hash(&message) != checksum {
+            return Err(QrError::Ur("message checksum mismatch".into()));
+        }
+        let ur_type = self.ur_type.as_deref().unwrap_or_default();
+        self.result = Some(Ur::new(ur_type, message)?);
+        self.mixed.clear();
+        Ok(())
+    }
+}
+
+/// Fragment indexes XORed into part `seq` of `count`
+///
+/// Parts up to `count` carry one fragment each; later ones draw a degree
+/// from a 1/k distribution and that many fragments, using Xoshiro256**
+/// seeded from the sequence number and message checksum.
+fn choose_fragments(seq: u32, count: u32, checksum: u32) -> Vec<usize> {
+    if seq <= count {
+        return vec![seq as usize - 1];
+    }
+    let mut seed = seq.to_be_bytes().to_vec();
+    seed.extend(checksum.to_be_bytes());
+    let mut rng = Xoshiro256::from_seed(&seed);
+
+    let weights: Vec<f64> = (1..=count).map(|k| 1.0 / k as f64).collect();
+    let degree = AliasSampler::new(&weights).sample(&mut rng) + 1;
+
+    let mut remaining: Vec<usize> = (0..count as usize).collect();
+    let mut shuffled = Vec::with_capacity(remaining.len());
+    while !remaining.is_empty() {
+        let index = rng.next_int(0, remaining.len() as u64 - 1) as usize;
+        shuffled.push(remaining.remove(index));
+    }
+    shuffled.truncate(degree);
+    shuffled
+}
+
+/// Xoshiro256** seeded from the SHA-256 of a byte string
+struct Xoshiro256([u64; 4]);
+
+impl Xoshiro256 {
+    fn from_seed(seed: &[u8]) -> Self {
+        let digest = Sha256::digest(seed);
+        let mut state = [0u64; 4];
+        for (word, chunk) in state.iter_mut().zip(digest.chunks(8)) {
+            *word = u64::from_be_bytes(chunk.try_into().expect("8-byte chunk"));
+        }
+        Self(state)
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        let s = &mut self.0;
+        let result = s[1].wrapping_mul(5).rotate_left(7).wrapping_mul(9);
+        let t = s[1] << 17;
+        s[2] ^= s[0];
+        s[3] ^= s[1];
+        s[1] ^= s[2];
+        s[0] ^= s[3];
+        s[2] ^= t;
+        s[3] = s[3].rotate_left(45);
+        result
+    }
+
+    fn next_double(&mut self) -> f64 {
+        self.next_u64() as f64 / (u64::MAX as f64 + 1.0)
+    }
+
+    /// Uniform integer in `low..=high`
+    fn next_int(&mut self, low: u64, high: u64) -> u64 {
+        (self.next_double() * (high - low + 1) as f64) as u64 + low
+    }
+}
+
+/// Vose's alias method, as specified for UR fragment degrees
+struct AliasSampler {
+    probs: Vec<f64>,
+    aliases: Vec<usize>,
+}
+
+impl AliasSampler {
+    fn new(weights: &[f64]) -> Self {
+        let n = weights.len();
+        let total: f64 = weights.iter().sum();
+        let mut scaled: Vec<f64> = weights.iter().map(|w| w * n as f64 / total).collect();
+        let (mut small, mut large) = (Vec::new(), Vec::new());
+        for i in (0..n).rev() {
+            if scaled[i] < 1.0 {
+                small.push(i);
+            } else {
+                large.push(i);
+            }
+        }
+
+        let mut probs = vec![0.0; n];
+        let mut aliases = vec![0; n];
+        while let (Some(&a), Some(&g)) = (small.last(), large.last()) {
+            small.pop();
+            large.pop();
+            probs[a] = scaled[a];
+            aliases[a] = g;
+            scaled[g] += scaled[a] - 1.0;
+            if scaled[g] < 1.0 {
+                small.push(g);
+            } else {
+                large.push(g);
+            }
+        }
+        for i in large.into_iter().chain(small) {
+            probs[i] = 1.0;
+        }
+        Self { probs, aliases }
+    }
+
+    fn sample(&self, rng: &mut Xoshiro256) -> usize {
+        let r1 = rng.next_double();
+        let r2 = rng.next_double();
+        let i = (self.probs.len() as f64 * r1) as usize;
+        if r2 < self.probs[i] {
+            i
+        } else {
+            self.aliases[i]
+        }
+    }
+}
+
+/// Minimal bytewords with the trailing CRC-32
+fn bytewords_encode(data: &[u8]) -> String {
+    let checksum = crc32fast::hash(data).to_be_bytes();
+    data.iter()
+        .chain(&checksum)
+        .flat_map(|byte| {
+            let word = BYTEWORDS[*byte as usize].as_bytes();
+            [word[0] as char, word[3] as char]
+        })
+        .collect()
+}
+
+fn bytewords_decode(text: &str) -> Result<Vec<u8>> {
+    let bytes = text.as_bytes();
+    if !bytes.len().is_multiple_of(2) || bytes.len() < 10 {
+        return Err(QrError::Ur("bytewords too short or odd length".into()));
+    }
+    let mut data = bytes
+        .chunks(2)
+        .map(|pair| {
+            BYTEWORDS
+                .iter()
+                .position(|word| word.as_bytes()[0] == pair[0] && word.as_bytes()[3] == pair[1])
+                .map(|byte| byte as u8)
+                .ok_or_else(|| QrError::Ur("invalid byteword".into()))
+        })
+        .collect::<Result<Vec<u8>>>()?;
+    let checksum = data.split_off(data.len() - 4);
+    if crc32fast::hash(&data).to_be_bytes()[..] != checksum[..] {
+        return Err(QrError::Ur("bytewords checksum mismatch".into()));
+    }
+    Ok(data)
+}
+
+/// Decodes a part body `[seq, count, message_len, checksum, fragment]`
+fn parse_part(body: &[u8]) -> Result<(u32, usize, usize, u32, &[u8])> {
+    let mut reader = Cbor::new(body);
+    if reader.head(4)? != 5 {
+        return Err(QrError::Ur("part is not a 5-element array".into()));
+    }
+    let too_large = || QrError::Ur("part header out of range".into());
+    let seq = u32::try_from(reader.head(0)?).map_err(|_| too_large())?;
+    let count = usize::try_from(reader.head(0)?).map_err(|_| too_large())?;
+    let message_len = usize::try_from(reader.head(0)?).map_err(|_| too_large())?;
+    let checksum = u32::try_from(reader.head(0)?).map_err(|_| too_large())?;
+    let fragment = reader.bytes()?;
+    if !reader.is_empty() {
+        return Err(QrError::Ur("trailing data in part".into()));
+    }
+    Ok((seq, count, message_len, checksum, fragment))
+}
+
+fn cbor_head(major: u8, value: u64) -> Vec<u8> {
+    let major = major << 5;
+    match value {
+        0..=23 => vec![major | value as u8],
+        24..=0xff => vec![major | 24, value as u8],
+        0x100..=0xffff => [vec![major | 25], (value as u16).to_be_bytes().to_vec()].concat(),
+        0x1_0000..=0xffff_ffff => {
+            [vec![major | 26], (value as u32).to_be_bytes().to_vec()].concat()
+        }
+        _ => [vec![major | 27], value.to_be_bytes().to_vec()].concat(),
+    }
+}
+
+/// Just enough of a CBOR reader for UR part headers
+struct Cbor<'a> {
+    data: &'a [u8],
+}
+
+impl<'a> Cbor<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { data }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    fn take(&mut self, n: usize) -> Result<&'a [u8]> {
+        if self.data.len() < n {
+            return Err(QrError::Ur("truncated CBOR".into()));
+        }
+        let (head, rest) = self.data.split_at(n);
+        self.data = rest;
+        Ok(head)
+    }
+
+    /// Reads an item head of the given major type, returning its argument
+    fn head(&mut self, major: u8) -> Result<u64> {
+        let initial = self.take(1)?[0];
+        if initial >> 5 != major {
+            return Err(QrError::Ur(format!("expected CBOR major type {}", major)));
+        }
+        let width = match initial & 0x1f {
+            info @ 0..=23 => return Ok(info as u64),
+            24 => 1,
+            25 => 2,
+            26 => 4,
+            27 => 8,
+            _ => return Err(QrError::Ur("unsupported CBOR length".into())),
+        };
+        Ok(self
+            .take(width)?
+            .iter()
+            .fold(0u64, |value, byte| value << 8 | *byte as u64))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = usize::try_from(self.head(2)?)
+            .map_err(|_| QrError::Ur("byte string too long".into()))?;
+        self.take(len)
+    }
+}
+
+fn xor_into(target: &mut [u8], other: &[u8]) {
+    for (a, b) in target.iter_mut().zip(other) {
+        *a ^= b;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bytewords_and_rng() {
+        assert_eq!(bytewords_encode(&[0, 1, 2, 128, 255]), "aeadaolazmjendeoti");
+        assert_eq!(
+            bytewords_decode("aeadaolazmjendeoti").unwrap(),
+            [0, 1, 2, 128, 255]
+        );
+        assert!(bytewords_decode("aeadaolazmjendeotk").is_err());
+        let pairs: BTreeSet<(u8, u8)> = BYTEWORDS
+            .iter()
+            .map(|w| (w.as_bytes()[0], w.as_bytes()[3]))
+            .collect();
+        assert_eq!(pairs.len(), 256);
+
+        // Test vector from the reference implementation
+        let mut rng = Xoshiro256::from_seed(b"Wolf");
+        let numbers: Vec<u64> = (0..8).map(|_| rng.next_u64() % 100).collect();
+        assert_eq!(numbers, [42, 81, 85, 8, 82, 84, 76, 73]);
+    }
+
+    #[test]
+    fn test_single_part() {
+        let ur = Ur::from_bytes("bytes", b"hello").unwrap();
+        let mut encoder = UrEncoder::new(ur.clone(), 100).unwrap();
+        assert!(encoder.is_single_part());
+        let part = encoder.next_part();
+        assert!(part.starts_with("ur:bytes/"));
+
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&part.to_ascii_uppercase()).unwrap();
+        assert_eq!(decoder.result(), Some(&ur));
+        assert_eq!(decoder.result().unwrap().payload().unwrap(), b"hello");
+        assert!(Ur::new("Bad Type", Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_fountain_parts_recover_missed_frames() {
+        let payload: Vec<u8> = (0..1000u32).map(|i| (i * 7 % 251) as u8).collect();
+        let ur = Ur::from_bytes("crypto-psbt", &payload).unwrap();
+        let mut encoder = UrEncoder::new(ur.clone(), 100).unwrap();
+        assert_eq!(encoder.fragment_count(), 11);
+
+        // Drop every third frame, including some of the plain fragments
+        let mut decoder = UrDecoder::new();
+        for i in 0..200 {
+            let part = encoder.next_part();
+            if i % 3 != 0 {
+                decoder.receive(&part).unwrap();
+            }
+            if decoder.is_complete() {
+                break;
+            }
+        }
+        assert!(decoder.is_complete());
+        assert_eq!(decoder.progress(), 1.0);
+        assert_eq!(decoder.result().unwrap().payload().unwrap(), payload);
+
+        let mut other = UrEncoder::new(Ur::from_bytes("bytes", &payload).unwrap(), 100).unwrap();
+        let mut decoder = UrDecoder::new();
+        decoder.receive(&encoder.next_part()).unwrap();
+        assert!(decoder.receive(&other.next_part()).is_err());
+        assert!(decoder.progress() < 1.0);
+    }
+}
@@ -3,6 +3,7 @@
 //! Implements retry delays that grow exponentially with random jitter
 //! to prevent thundering herd problems.
 
+use crate::timeout::Deadline;
 use rand::Rng;
 use std::time::Duration;
 
@@ -183,19 +184,50 @@ impl Iterator for ExponentialBackoff {
 }
 
 /// Execute a function with exponential backoff retries
+///
+/// Inside a [`Deadline::scope`] no new attempt is started, and no retry delay
+/// is slept, once it would run past the deadline.
 pub async fn with_backoff<F, Fut, T, E>(
+    config: BackoffConfig,
+    f: F,
+) -> Result<T, BackoffError<E>>
+where
+    F: FnMut() -> Fut,
+    Fut: std::future::Future<Output = Result<T, E>>,
+    E: std::fmt::Debug,
+{
+    with_backoff_hinted(config, f, |_| None).await
+}
+
+/// Like [`with_backoff`], but waits at least as long as the failed attempt
+/// asked for
+///
+/// `retry_after` extracts a server-requested delay from an error, e.g. a
+/// `Retry-After` header carried in a rate-limit error. The longer of that
+/// and the backoff delay is slept. A request to wait longer than
+/// [`BackoffConfig::max_delay`] ends the retries instead.
+pub async fn with_backoff_hinted<F, Fut, T, E, H>(
     config: BackoffConfig,
     mut f: F,
+    retry_after: H,
 ) -> Result<T, BackoffError<E>>
 where
     F: FnMut() -> Fut,
     Fut: std::future::Future<Output = Result<T, E>>,
     E: std::fmt::Debug,
+    H: Fn(&E) -> Option<Duration>,
 {
     let mut backoff = ExponentialBackoff::new(config);
     let mut last_error = None;
 
+    let deadline = Deadline::current();
+
     while backoff.can_retry() {
+        if deadline.as_ref().is_some_and(Deadline::is_expired) {
+            tracing::debug!(attempt = backoff.attempt(), "Deadline expired, not retrying");
+            break;
+        }
+
         match f().await {
             Ok(result) => return Ok(result),
             Err(e) => {
@@ -205,9 +237,19 @@ where
                     error = ?e,
                     "Operation failed, will retry"
                 );
+                let hint = retry_after(&e);
                 last_error = Some(e);
 
                 if let Some(delay) = backoff.next() {
+                    if hint.is_some_and(|hint| hint > backoff.config.max_delay) {
+                        tracing::debug!(hint = ?hint, "Requested delay exceeds max delay, giving up");
+                        break;
+                    }
+                    let delay = delay.max(hint.unwrap_or_default());
+                    if deadline.as_ref().is_some_and(|d| !d.has_time_for(delay)) {
+                        tracing::debug!(delay = ?delay, "Retry delay exceeds deadline, giving up");
+                        break;
+                    }
                     if backoff.can_retry() {
                         tracing::trace!(delay = ?delay, "Waiting before retry");
                         tokio::time::sleep(delay).await;
@@ -487,6 +529,55 @@ mod tests {
         assert_eq!(err.last_error, Some("always fails"));
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_with_backoff_respects_deadline() {
+        let config = BackoffConfig::new()
+            .with_max_attempts(10)
+            .with_initial_delay(Duration::from_secs(1))
+            .with_jitter(0.0);
+        let attempts = std::sync::atomic::AtomicU32::new(0);
+
+        let result: Result<(), _> = Deadline::new(Duration::from_millis(2500))
+            .scope(with_backoff(config, || {
+                attempts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                async { Err::<(), _>("always fails") }
+            }))
+            .await;
+
+        // Attempts at 0s and 1s; the 2s delay before the third would overrun
+        assert!(result.is_err());
+        assert_eq!(attempts.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_with_backoff_hinted_waits_requested_delay() {
+        let config = BackoffConfig::new()
+            .with_max_attempts(2)
+            .with_initial_delay(Duration::from_millis(10))
+            .with_jitter(0.0);
+        let start = tokio::time::Instant::now();
+
+        let result: Result<(), _> = with_backoff_hinted(
+            config,
+            || async { Err::<(), _>(Duration::from_secs(30)) },
+            |requested| Some(*requested),
+        )
+        .await;
+
+        assert_eq!(result.unwrap_err().attempts, 2);
+        assert!(start.elapsed() >= Duration::from_secs(30));
+
+        let start = tokio::time::Instant::now();
+        let result: Result<(), _> = with_backoff_hinted(
+            BackoffConfig::new().with_max_attempts(3),
+            || async { Err::<(), _>(Duration::from_secs(86_400)) },
+            |requested| Some(*requested),
+        )
+        .await;
+        assert_eq!(result.unwrap_err().attempts, 1);
+        assert!(start.elapsed() < Duration::from_secs(1));
+    }
+
     #[test]
     fn test_decorrelated_jitter() {
         let config = BackoffConfig::new()
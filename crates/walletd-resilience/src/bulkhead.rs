@@ -0,0 +1,372 @@
+//! Bulkhead pattern implementation
+//!
+//! Limits concurrent executions per resource so one slow dependency (e.g. a
+//! lagging chain's RPC) cannot tie up every task in the runtime.
+
+use crate::timeout::Deadline;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Configuration for a bulkhead
+#[derive(Debug, Clone)]
+pub struct BulkheadConfig {
+    /// Maximum concurrent executions
+    pub max_concurrent: usize,
+    /// Maximum callers waiting for a slot
+    pub max_queue: usize,
+    /// Maximum time a caller waits for a slot
+    pub max_wait: Duration,
+    /// Name for logging/metrics
+    pub name: String,
+}
+
+impl Default for BulkheadConfig {
+    fn default() -> Self {
+        Self {
+            max_concurrent: 10,
+            max_queue: 100,
+            max_wait: Duration::from_secs(5),
+            name: "default".to_string(),
+        }
+    }
+}
+
+impl BulkheadConfig {
+    /// Create a new config with a name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set maximum concurrent executions
+    pub fn with_max_concurrent(mut self, max: usize) -> Self {
+        self.max_concurrent = max;
+        self
+    }
+
+    /// Set maximum queue length (0 = reject immediately when full)
+    pub fn with_max_queue(mut self, max: usize) -> Self {
+        self.max_queue = max;
+        self
+    }
+
+    /// Set maximum wait time for a slot
+    pub fn with_max_wait(mut self, wait: Duration) -> Self {
+        self.max_wait = wait;
+        self
+    }
+}
+
+/// Why a bulkhead rejected a call
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BulkheadRejection {
+    /// All slots busy and the wait queue is full
+    QueueFull,
+    /// Waited `max_wait` without getting a slot
+    WaitTimeout,
+}
+
+/// Error when a bulkhead rejects a call
+#[derive(Debug, Clone)]
+pub struct BulkheadFullError {
+    /// Name of the bulkhead
+    pub name: String,
+    /// Rejection reason
+    pub reason: BulkheadRejection,
+}
+
+impl std::fmt::Display for BulkheadFullError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.reason {
+            BulkheadRejection::QueueFull => write!(f, "Bulkhead '{}' is full", self.name),
+            BulkheadRejection::WaitTimeout => {
+                write!(f, "Bulkhead '{}' timed out waiting for a slot", self.name)
+            }
+        }
+    }
+}
+
+impl std::error::Error for BulkheadFullError {}
+
+/// Error type for bulkhead operations
+#[derive(Debug)]
+pub enum BulkheadError<E> {
+    /// Bulkhead rejected the call
+    Full(BulkheadFullError),
+    /// Inner operation error
+    Inner(E),
+}
+
+impl<E: std::fmt::Display> std::fmt::Display for BulkheadError<E> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full(e) => write!(f, "{}", e),
+            Self::Inner(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl<E: std::error::Error + 'static> std::error::Error for BulkheadError<E> {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Full(e) => Some(e),
+            Self::Inner(e) => Some(e),
+        }
+    }
+}
+
+/// A held bulkhead slot; released on drop
+#[derive(Debug)]
+pub struct BulkheadPermit {
+    _permit: OwnedSemaphorePermit,
+}
+
+/// Bulkhead metrics
+#[derive(Debug, Clone)]
+pub struct BulkheadMetrics {
+    /// Calls currently executing
+    pub active: usize,
+    /// Calls waiting for a slot
+    pub queued: usize,
+    /// Calls rejected since creation
+    pub rejected: u64,
+}
+
+/// Concurrency limiter with a bounded wait queue
+#[derive(Debug)]
+pub struct Bulkhead {
+    config: BulkheadConfig,
+    semaphore: Arc<Semaphore>,
+    queued: AtomicUsize,
+    rejected: AtomicU64,
+}
+
+impl Bulkhead {
+    /// Create a new bulkhead with config
+    pub fn new(config: BulkheadConfig) -> Self {
+        Self {
+            semaphore: Arc::new(Semaphore::new(config.max_concurrent)),
+            config,
+            queued: AtomicUsize::new(0),
+            rejected: AtomicU64::new(0),
+        }
+    }
+
+    /// Create with default config and name
+    pub fn with_name(name: impl Into<String>) -> Self {
+        Self::new(BulkheadConfig::new(name))
+    }
+
+    /// Get the config
+    pub fn config(&self) -> &BulkheadConfig {
+        &self.config
+    }
+
+    /// Acquire a slot, waiting in the queue if necessary
+    ///
+    /// The wait never outlasts the current [`Deadline`], if any.
+    pub async fn acquire(&self) -> Result<BulkheadPermit, BulkheadFullError> {
+        if let Ok(permit) = self.semaphore.clone().try_acquire_owned() {
+            return Ok(BulkheadPermit { _permit: permit });
+        }
+
+        // Reserve a queue position
+        let reserved = self
+            .queued
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| {
+                (n < self.config.max_queue).then_some(n + 1)
+            })
+            .is_ok();
+        if !reserved {
+            return Err(self.reject(BulkheadRejection::QueueFull));
+        }
+
+        let max_wait = match Deadline::current() {
+            Some(deadline) => self.config.max_wait.min(deadline.remaining()),
+            None => self.config.max_wait,
+        };
+        let result = tokio::time::timeout(max_wait, self.semaphore.clone().acquire_owned()).await;
+        self.queued.fetch_sub(1, Ordering::SeqCst);
+
+        match result {
+            Ok(Ok(permit)) => Ok(BulkheadPermit { _permit: permit }),
+            // The semaphore is never closed, so only the timeout can fail
+            _ => Err(self.reject(BulkheadRejection::WaitTimeout)),
+        }
+    }
+
+    /// Execute a function inside the bulkhead
+    pub async fn execute<F, Fut, T, E>(&self, f: F) -> Result<T, BulkheadError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        let _permit = self.acquire().await.map_err(BulkheadError::Full)?;
+        f().await.map_err(BulkheadError::Inner)
+    }
+
+    /// Get metrics
+    pub fn metrics(&self) -> BulkheadMetrics {
+        BulkheadMetrics {
+            active: self.config.max_concurrent - self.semaphore.available_permits(),
+            queued: self.queued.load(Ordering::SeqCst),
+            rejected: self.rejected.load(Ordering::SeqCst),
+        }
+    }
+
+    fn reject(&self, reason: BulkheadRejection) -> BulkheadFullError {
+        self.rejected.fetch_add(1, Ordering::SeqCst);
+        tracing::warn!(bulkhead = %self.config.name, ?reason, "Bulkhead rejected call");
+        BulkheadFullError {
+            name: self.config.name.clone(),
+            reason,
+        }
+    }
+}
+
+/// Bulkheads keyed by resource name (e.g. one per chain or endpoint)
+#[derive(Debug)]
+pub struct BulkheadRegistry {
+    defaults: BulkheadConfig,
+    bulkheads: Mutex<HashMap<String, Arc<Bulkhead>>>,
+}
+
+impl BulkheadRegistry {
+    /// Create a registry; new bulkheads copy `defaults` with their own name
+    pub fn new(defaults: BulkheadConfig) -> Self {
+        Self {
+            defaults,
+            bulkheads: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Register a bulkhead with its own config, replacing any existing one
+    pub fn register(&self, config: BulkheadConfig) -> Arc<Bulkhead> {
+        let bulkhead = Arc::new(Bulkhead::new(config));
+        self.lock()
+            .insert(bulkhead.config.name.clone(), bulkhead.clone());
+        bulkhead
+    }
+
+    /// Get the bulkhead for `name`, creating it from the defaults if needed
+    pub fn get(&self, name: &str) -> Arc<Bulkhead> {
+        self.lock()
+            .entry(name.to_string())
+            .or_insert_with(|| {
+                Arc::new(Bulkhead::new(BulkheadConfig {
+                    name: name.to_string(),
+                    ..self.defaults.clone()
+                }))
+            })
+            .clone()
+    }
+
+    /// Execute a function inside the bulkhead for `name`
+    pub async fn execute<F, Fut, T, E>(&self, name: &str, f: F) -> Result<T, BulkheadError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: std::future::Future<Output = Result<T, E>>,
+    {
+        self.get(name).execute(f).await
+    }
+
+    /// Metrics for every bulkhead
+    pub fn metrics(&self) -> HashMap<String, BulkheadMetrics> {
+        self.lock()
+            .iter()
+            .map(|(name, bulkhead)| (name.clone(), bulkhead.metrics()))
+            .collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, HashMap<String, Arc<Bulkhead>>> {
+        self.bulkheads.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl Default for BulkheadRegistry {
+    fn default() -> Self {
+        Self::new(BulkheadConfig::default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_limits_concurrency() {
+        let bulkhead = Bulkhead::new(BulkheadConfig::new("test").with_max_concurrent(2));
+
+        let p1 = bulkhead.acquire().await.unwrap();
+        let _p2 = bulkhead.acquire().await.unwrap();
+        assert_eq!(bulkhead.metrics().active, 2);
+
+        drop(p1);
+        assert_eq!(bulkhead.metrics().active, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rejects_when_queue_full() {
+        let bulkhead = Bulkhead::new(
+            BulkheadConfig::new("test")
+                .with_max_concurrent(1)
+                .with_max_queue(0),
+        );
+
+        let _permit = bulkhead.acquire().await.unwrap();
+        let err = bulkhead.acquire().await.unwrap_err();
+        assert_eq!(err.reason, BulkheadRejection::QueueFull);
+        assert_eq!(bulkhead.metrics().rejected, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_timeout() {
+        let bulkhead = Bulkhead::new(
+            BulkheadConfig::new("test")
+                .with_max_concurrent(1)
+                .with_max_wait(Duration::from_millis(100)),
+        );
+
+        let _permit = bulkhead.acquire().await.unwrap();
+        let err = bulkhead.acquire().await.unwrap_err();
+        assert_eq!(err.reason, BulkheadRejection::WaitTimeout);
+        assert_eq!(bulkhead.metrics().queued, 0);
+    }
+
+    #[tokio::test]
+    async fn test_queued_caller_gets_slot() {
+        let bulkhead = Arc::new(Bulkhead::new(BulkheadConfig::new("test").with_max_concurrent(1)));
+
+        let permit = bulkhead.acquire().await.unwrap();
+        let waiter = {
+            let bulkhead = bulkhead.clone();
+            tokio::spawn(async move { bulkhead.execute(|| async { Ok::<_, &str>(7) }).await.unwrap() })
+        };
+        tokio::task::yield_now().await;
+        assert_eq!(bulkhead.metrics().queued, 1);
+
+        drop(permit);
+        assert_eq!(waiter.await.unwrap(), 7);
+    }
+
+    #[tokio::test]
+    async fn test_registry_isolates_resources() {
+        let registry = BulkheadRegistry::new(
+            BulkheadConfig::default()
+                .with_max_concurrent(1)
+                .with_max_queue(0),
+        );
+
+        let _slow = registry.get("solana").acquire().await.unwrap();
+        assert!(registry.get("solana").acquire().await.is_err());
+
+        let result = registry.execute("ethereum", || async { Ok::<_, &str>(1) }).await;
+        assert_eq!(result.unwrap(), 1);
+        assert_eq!(registry.metrics()["solana"].rejected, 1);
+    }
+}
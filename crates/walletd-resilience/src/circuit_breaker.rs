@@ -2,6 +2,7 @@
 //!
 //! Prevents cascading failures by stopping requests to unhealthy services.
 
+use crate::timeout::Deadline;
 use std::sync::atomic::{AtomicU64, AtomicU8, Ordering};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
@@ -228,16 +229,32 @@ impl CircuitBreaker {
     }
 
     /// Execute a function with circuit breaker protection
+    ///
+    /// Inside a [`Deadline::scope`] the call is cut off when the deadline
+    /// expires. That is the caller running out of time, not the service
+    /// failing, so it is not recorded as a failure.
     pub async fn execute<F, Fut, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
     where
         F: FnOnce() -> Fut,
         Fut: std::future::Future<Output = Result<T, E>>,
     {
+        let deadline = Deadline::current();
+        if deadline.as_ref().is_some_and(Deadline::is_expired) {
+            return Err(CircuitBreakerError::DeadlineExceeded);
+        }
+
         self.can_execute()
             .await
             .map_err(CircuitBreakerError::CircuitOpen)?;
 
-        match f().await {
+        let result = match deadline {
+            Some(deadline) => tokio::time::timeout(deadline.remaining(), f())
+                .await
+                .map_err(|_| CircuitBreakerError::DeadlineExceeded)?,
+            None => f().await,
+        };
+
+        match result {
             Ok(result) => {
                 self.record_success().await;
                 Ok(result)
@@ -295,6 +312,8 @@ impl CircuitBreaker {
 pub enum CircuitBreakerError<E> {
     /// Circuit is open
     CircuitOpen(CircuitOpenError),
+    /// The current [`Deadline`] expired before the call completed
+    DeadlineExceeded,
     /// Inner operation error
     Inner(E),
 }
@@ -303,6 +322,7 @@ impl<E: std::fmt::Display> std::fmt::Display for CircuitBreakerError<E> {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::CircuitOpen(e) => write!(f, "{}", e),
+            Self::DeadlineExceeded => write!(f, "Deadline expired"),
             Self::Inner(e) => write!(f, "{}", e),
         }
     }
@@ -312,6 +332,7 @@ impl<E: std::error::Error + 'static> std::error::Error for CircuitBreakerError<E
     fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
         match self {
             Self::CircuitOpen(e) => Some(e),
+            Self::DeadlineExceeded => None,
             Self::Inner(e) => Some(e),
         }
     }
@@ -398,6 +419,21 @@ mod tests {
         assert_eq!(cb.state(), CircuitState::Open);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline_cuts_call_without_failure() {
+        let cb = CircuitBreaker::new(CircuitBreakerConfig::new("test").with_failure_threshold(1));
+
+        let result = Deadline::new(Duration::from_secs(1))
+            .scope(cb.execute(|| async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok::<_, &str>(())
+            }))
+            .await;
+
+        assert!(matches!(result, Err(CircuitBreakerError::DeadlineExceeded)));
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
     #[tokio::test]
     async fn test_execute_success() {
         let cb = CircuitBreaker::with_name("test");
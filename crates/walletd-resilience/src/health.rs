@@ -1,11 +1,17 @@
 //! Health check utilities for monitoring service health
 //!
 //! Provides periodic health checking and status tracking.
+//!
+//! Results can be recorded from outside (e.g. after each real request) or
+//! produced by probes that [`HealthChecker::probe`] runs on a schedule.
 
+use rand::Rng;
 use std::collections::HashMap;
-use std::sync::Arc;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 
 /// Health status
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -155,6 +161,56 @@ impl HealthCheckerConfig {
     }
 }
 
+/// When and how often a probe runs
+#[derive(Debug, Clone)]
+pub struct ProbeSchedule {
+    /// Interval between probes
+    pub interval: Duration,
+    /// Random spread applied to each interval (0.0 - 1.0)
+    ///
+    /// Keeps probes for many targets from firing in lockstep.
+    pub jitter: f64,
+    /// Timeout for a single probe
+    pub timeout: Duration,
+}
+
+impl ProbeSchedule {
+    /// Create a schedule with the given interval
+    pub fn new(interval: Duration) -> Self {
+        Self {
+            interval,
+            jitter: 0.1,
+            timeout: Duration::from_secs(10),
+        }
+    }
+
+    /// Set jitter
+    pub fn with_jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set probe timeout
+    pub fn with_timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    fn next_delay(&self) -> Duration {
+        if self.jitter <= 0.0 {
+            return self.interval;
+        }
+        let factor = 1.0 + rand::thread_rng().gen_range(-self.jitter..=self.jitter);
+        self.interval.mul_f64(factor)
+    }
+}
+
+impl From<&HealthCheckerConfig> for ProbeSchedule {
+    fn from(config: &HealthCheckerConfig) -> Self {
+        Self::new(config.check_interval).with_timeout(config.check_timeout)
+    }
+}
+
 /// Service health state
 #[derive(Debug)]
 struct ServiceHealth {
@@ -225,9 +281,13 @@ impl ServiceHealth {
 }
 
 /// Health checker for monitoring multiple services
+///
+/// Background probes started with [`probe`](Self::probe) are stopped when the
+/// checker is dropped.
 pub struct HealthChecker {
     config: HealthCheckerConfig,
     services: Arc<RwLock<HashMap<String, ServiceHealth>>>,
+    probes: Mutex<HashMap<String, JoinHandle<()>>>,
 }
 
 impl HealthChecker {
@@ -236,6 +296,7 @@ impl HealthChecker {
         Self {
             config,
             services: Arc::new(RwLock::new(HashMap::new())),
+            probes: Mutex::new(HashMap::new()),
         }
     }
 
@@ -268,6 +329,76 @@ impl HealthChecker {
         }
     }
 
+    /// Run `probe` once with a timeout and record the outcome
+    pub async fn check<F, Fut, E>(&self, name: &str, timeout: Duration, probe: F) -> HealthCheckResult
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<(), E>>,
+        E: std::fmt::Display,
+    {
+        let start = tokio::time::Instant::now();
+        let result = match tokio::time::timeout(timeout, probe()).await {
+            Ok(Ok(())) => HealthCheckResult::healthy(name, start.elapsed()),
+            Ok(Err(e)) => HealthCheckResult::unhealthy(name, e.to_string()),
+            Err(_) => HealthCheckResult::unhealthy(name, format!("probe timed out after {:?}", timeout)),
+        };
+        self.record(result.clone()).await;
+        result
+    }
+
+    /// Probe a service in the background on `schedule`
+    ///
+    /// The first probe runs immediately. Starting a probe for a name that
+    /// already has one replaces it. Requires a Tokio runtime.
+    pub fn probe<F, Fut, E>(&self, name: impl Into<String>, schedule: ProbeSchedule, probe: F)
+    where
+        F: Fn() -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send,
+        E: std::fmt::Display,
+    {
+        let name = name.into();
+        let checker = HealthChecker::new(self.config.clone()).sharing(&self.services);
+        let task_name = name.clone();
+        let handle = tokio::spawn(async move {
+            loop {
+                let result = checker.check(&task_name, schedule.timeout, &probe).await;
+                if let Some(error) = &result.error {
+                    tracing::debug!(service = %task_name, %error, "Health probe failed");
+                }
+                tokio::time::sleep(schedule.next_delay()).await;
+            }
+        });
+
+        if let Some(previous) = self.lock_probes().insert(name, handle) {
+            previous.abort();
+        }
+    }
+
+    /// Stop the background probe for a service, if any
+    pub fn stop_probe(&self, name: &str) -> bool {
+        match self.lock_probes().remove(name) {
+            Some(handle) => {
+                handle.abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Names of services with a running background probe
+    pub fn probed_services(&self) -> Vec<String> {
+        self.lock_probes().keys().cloned().collect()
+    }
+
+    fn sharing(mut self, services: &Arc<RwLock<HashMap<String, ServiceHealth>>>) -> Self {
+        self.services = services.clone();
+        self
+    }
+
+    fn lock_probes(&self) -> std::sync::MutexGuard<'_, HashMap<String, JoinHandle<()>>> {
+        self.probes.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
     /// Get health status of a service
     pub async fn status(&self, name: &str) -> HealthStatus {
         let services = self.services.read().await;
@@ -358,6 +489,14 @@ impl HealthChecker {
     }
 }
 
+impl Drop for HealthChecker {
+    fn drop(&mut self) {
+        for (_, handle) in self.lock_probes().drain() {
+            handle.abort();
+        }
+    }
+}
+
 /// Health report for all services
 #[derive(Debug)]
 pub struct HealthReport {
@@ -517,6 +656,63 @@ mod tests {
         assert!(checker.is_healthy().await);
     }
 
+    #[test]
+    fn test_probe_schedule_jitter() {
+        let schedule = ProbeSchedule::new(Duration::from_secs(10)).with_jitter(0.2);
+        for _ in 0..50 {
+            let delay = schedule.next_delay();
+            assert!(delay >= Duration::from_secs(8) && delay <= Duration::from_secs(12));
+        }
+        assert_eq!(ProbeSchedule::new(Duration::from_secs(10)).with_jitter(0.0).next_delay(), Duration::from_secs(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_check_records_timeout() {
+        let checker = HealthChecker::new(HealthCheckerConfig::default().with_failure_threshold(1));
+
+        let result = checker
+            .check("rpc", Duration::from_secs(1), || async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Ok::<_, String>(())
+            })
+            .await;
+
+        assert!(result.error.unwrap().contains("timed out"));
+        assert_eq!(checker.status("rpc").await, HealthStatus::Unhealthy);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_background_probe() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+
+        let checker = HealthChecker::new(HealthCheckerConfig::default().with_failure_threshold(2));
+        let calls = Arc::new(AtomicU32::new(0));
+        let probe_calls = calls.clone();
+
+        // Healthy for the first two probes, then failing
+        checker.probe(
+            "rpc",
+            ProbeSchedule::new(Duration::from_secs(10)).with_jitter(0.0),
+            move || {
+                let n = probe_calls.fetch_add(1, Ordering::SeqCst);
+                async move { if n < 2 { Ok(()) } else { Err("connection refused") } }
+            },
+        );
+        assert_eq!(checker.probed_services(), vec!["rpc".to_string()]);
+
+        tokio::time::sleep(Duration::from_secs(15)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(checker.status("rpc").await, HealthStatus::Healthy);
+
+        tokio::time::sleep(Duration::from_secs(20)).await;
+        assert_eq!(checker.status("rpc").await, HealthStatus::Unhealthy);
+
+        assert!(checker.stop_probe("rpc"));
+        let stopped_at = calls.load(Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_secs(60)).await;
+        assert_eq!(calls.load(Ordering::SeqCst), stopped_at);
+    }
+
     #[test]
     fn test_health_status_display() {
         assert_eq!(HealthStatus::Healthy.to_string(), "healthy");
@@ -0,0 +1,119 @@
+//! Hedged requests
+//!
+//! Cuts tail latency by racing a second attempt against a slow first one.
+//! Only hedge idempotent reads (balances, block heights); never broadcasts.
+
+use std::future::Future;
+use std::time::Duration;
+
+/// Run `primary`, and if it hasn't finished after `delay`, start `secondary`
+/// and return whichever succeeds first.
+///
+/// - If `primary` finishes within `delay`, its result is returned as-is and
+///   `secondary` is never started.
+/// - Once both are running, the first success wins and the other is dropped.
+///   If one fails, the other is awaited; if both fail, the last error is
+///   returned.
+pub async fn hedge<T, E, P, PF, S, SF>(delay: Duration, primary: P, secondary: S) -> Result<T, E>
+where
+    P: FnOnce() -> PF,
+    PF: Future<Output = Result<T, E>>,
+    S: FnOnce() -> SF,
+    SF: Future<Output = Result<T, E>>,
+{
+    let primary = primary();
+    tokio::pin!(primary);
+
+    tokio::select! {
+        result = &mut primary => return result,
+        _ = tokio::time::sleep(delay) => {}
+    }
+
+    tracing::debug!(?delay, "Primary request slow, sending hedged request");
+    let secondary = secondary();
+    tokio::pin!(secondary);
+
+    tokio::select! {
+        result = &mut primary => match result {
+            Ok(value) => Ok(value),
+            Err(_) => secondary.await,
+        },
+        result = &mut secondary => match result {
+            Ok(value) => {
+                tracing::debug!("Hedged request won");
+                Ok(value)
+            }
+            Err(_) => primary.await,
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use tokio::time::sleep;
+
+    async fn respond(after: Duration, result: Result<&'static str, &'static str>) -> Result<&'static str, &'static str> {
+        sleep(after).await;
+        result
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_fast_primary_skips_secondary() {
+        let started = AtomicBool::new(false);
+        let result = hedge(
+            Duration::from_millis(100),
+            || respond(Duration::from_millis(10), Ok("primary")),
+            || {
+                started.store(true, Ordering::SeqCst);
+                respond(Duration::ZERO, Ok("secondary"))
+            },
+        )
+        .await;
+
+        assert_eq!(result, Ok("primary"));
+        assert!(!started.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_slow_primary_is_hedged() {
+        let result = hedge(
+            Duration::from_millis(100),
+            || respond(Duration::from_secs(5), Ok("primary")),
+            || respond(Duration::from_millis(50), Ok("secondary")),
+        )
+        .await;
+        assert_eq!(result, Ok("secondary"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_primary_can_still_win() {
+        let result = hedge(
+            Duration::from_millis(100),
+            || respond(Duration::from_millis(120), Ok("primary")),
+            || respond(Duration::from_secs(1), Ok("secondary")),
+        )
+        .await;
+        assert_eq!(result, Ok("primary"));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_failure_falls_back_to_other_attempt() {
+        let result = hedge(
+            Duration::from_millis(100),
+            || respond(Duration::from_millis(500), Ok("primary")),
+            || respond(Duration::from_millis(10), Err("secondary down")),
+        )
+        .await;
+        assert_eq!(result, Ok("primary"));
+
+        let result = hedge(
+            Duration::from_millis(100),
+            || respond(Duration::from_millis(200), Err("primary down")),
+            || respond(Duration::from_millis(300), Err("secondary down")),
+        )
+        .await;
+        assert_eq!(result, Err("secondary down"));
+    }
+}
@@ -9,7 +9,11 @@
 //! - **Exponential Backoff**: Retry failed operations with increasing delays
 //! - **Retry Policies**: Classify errors and determine retry strategies
 //! - **Timeouts**: Configurable timeouts with adaptive adjustments
-//! - **Health Checks**: Monitor service health and track status
+//! - **Deadlines**: One time budget for a whole operation, honored by every layer
+//! - **Health Checks**: Monitor service health and track status, with optional background probes
+//! - **Bulkheads**: Cap concurrent calls per resource so one slow service can't starve others
+//! - **Hedged Requests**: Race a backup request against a slow one to cut tail latency
+//! - **Adaptive Rate Limiting**: Find an endpoint's rate limit at runtime with AIMD
 //!
 //! ## Quick Start
 //!
@@ -124,6 +128,29 @@
 //! assert_eq!(fast.request, Duration::from_secs(5));
 //! ```
 //!
+//! ## Deadlines
+//!
+//! Give a whole operation one budget instead of stacking per-layer timeouts:
+//!
+//! ```rust
+//! use walletd_resilience::{with_backoff, BackoffConfig, CircuitBreaker, Deadline, DeadlineError};
+//! use std::time::Duration;
+//!
+//! # async fn example() {
+//! let cb = CircuitBreaker::with_name("rpc");
+//!
+//! // Retries, their delays and the breaker-wrapped calls all stop at 10s total
+//! let result = Deadline::new(Duration::from_secs(10))
+//!     .execute(async {
+//!         with_backoff(BackoffConfig::default(), || cb.execute(|| async { Ok::<_, &str>(42) }))
+//!             .await
+//!     })
+//!     .await;
+//!
+//! assert!(!matches!(result, Err(DeadlineError::Expired)));
+//! # }
+//! ```
+//!
 //! ## Health Checks
 //!
 //! Monitor service health:
@@ -143,20 +170,109 @@
 //! assert_eq!(checker.status("rpc_1").await, HealthStatus::Healthy);
 //! # }
 //! ```
+//!
+//! Or let the checker probe services itself:
+//!
+//! ```rust,no_run
+//! use walletd_resilience::{HealthChecker, ProbeSchedule};
+//! use std::time::Duration;
+//!
+//! # async fn get_block_number(_url: &str) -> Result<u64, String> { Ok(0) }
+//! # async fn example() {
+//! let checker = HealthChecker::default_config();
+//! checker.probe(
+//!     "rpc_1",
+//!     ProbeSchedule::new(Duration::from_secs(15))
+//!         .with_jitter(0.2)
+//!         .with_timeout(Duration::from_secs(3)),
+//!     || async { get_block_number("https://rpc-1.example").await.map(|_| ()) },
+//! );
+//! # }
+//! ```
+//!
+//! ## Bulkheads
+//!
+//! Isolate resources from each other:
+//!
+//! ```rust
+//! use walletd_resilience::{BulkheadConfig, BulkheadRegistry};
+//! use std::time::Duration;
+//!
+//! # async fn example() {
+//! let bulkheads = BulkheadRegistry::new(
+//!     BulkheadConfig::default()
+//!         .with_max_concurrent(8)     // 8 in-flight calls per chain
+//!         .with_max_queue(32)         // then up to 32 waiting
+//!         .with_max_wait(Duration::from_secs(2)),
+//! );
+//!
+//! let balance = bulkheads
+//!     .execute("solana", || async { Ok::<_, &str>(42u64) })
+//!     .await;
+//! # }
+//! ```
+//!
+//! ## Hedged Requests
+//!
+//! Ask a second endpoint if the first is slow:
+//!
+//! ```rust
+//! use walletd_resilience::hedge;
+//! use std::time::Duration;
+//!
+//! # async fn get_balance(_url: &str) -> Result<u64, String> { Ok(0) }
+//! # async fn example() {
+//! let balance = hedge(
+//!     Duration::from_millis(250), // roughly the p95 latency
+//!     || get_balance("https://rpc-a.example"),
+//!     || get_balance("https://rpc-b.example"),
+//! )
+//! .await;
+//! # }
+//! ```
+//!
+//! ## Adaptive Rate Limiting
+//!
+//! Speed up while an endpoint keeps up, back off when it pushes back:
+//!
+//! ```rust
+//! use walletd_resilience::{AdaptiveRateLimiter, AimdConfig};
+//!
+//! # async fn example() {
+//! let limiter = AdaptiveRateLimiter::new(
+//!     AimdConfig::new("public_rpc")
+//!         .with_initial_rate(10.0)
+//!         .with_bounds(1.0, 200.0),
+//! );
+//!
+//! limiter.acquire().await;
+//! // ... send the request, then report how it went
+//! limiter.on_throttled(); // got HTTP 429 / JSON-RPC -32005
+//! assert_eq!(limiter.current_rate(), 5.0);
+//! # }
+//! ```
 
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
 pub mod backoff;
+pub mod bulkhead;
 pub mod circuit_breaker;
 pub mod health;
+pub mod hedge;
+pub mod rate_limit;
 pub mod retry_policy;
 pub mod timeout;
 
 // Re-export main types
 pub use backoff::{
     BackoffConfig, BackoffError, DecorrelatedJitter, ExponentialBackoff,
-    with_backoff, with_default_backoff,
+    with_backoff, with_backoff_hinted, with_default_backoff,
+};
+
+pub use bulkhead::{
+    Bulkhead, BulkheadConfig, BulkheadError, BulkheadFullError, BulkheadMetrics,
+    BulkheadPermit, BulkheadRegistry, BulkheadRejection,
 };
 
 pub use circuit_breaker::{
@@ -166,9 +282,13 @@ pub use circuit_breaker::{
 
 pub use health::{
     HealthCheckResult, HealthChecker, HealthCheckerConfig,
-    HealthReport, HealthStatus, ServiceHealthReport,
+    HealthReport, HealthStatus, ProbeSchedule, ServiceHealthReport,
 };
 
+pub use hedge::hedge;
+
+pub use rate_limit::{AdaptiveRateLimiter, AimdConfig};
+
 pub use retry_policy::{
     BlockchainRetryPolicy, DefaultRetryClassifier, HttpRetryClassifier,
     RetryClassifier, RetryPolicy, RpcRetryClassifier,
@@ -0,0 +1,295 @@
+//! Adaptive rate limiting
+//!
+//! Public RPC endpoints rarely document their limits, and the limits change
+//! with load. [`AdaptiveRateLimiter`] finds the limit at runtime using AIMD
+//! (additive increase, multiplicative decrease): the allowed rate creeps up
+//! while requests succeed and is cut sharply when the endpoint answers with
+//! HTTP 429 or JSON-RPC `-32005`.
+
+use std::sync::Mutex;
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// Configuration for an adaptive rate limiter
+#[derive(Debug, Clone)]
+pub struct AimdConfig {
+    /// Starting rate in requests per second
+    pub initial_rate: f64,
+    /// Rate never drops below this
+    pub min_rate: f64,
+    /// Rate never rises above this
+    pub max_rate: f64,
+    /// Requests per second gained for each second of successful traffic
+    pub additive_increase: f64,
+    /// Factor applied to the rate when throttled (0.0 - 1.0)
+    pub multiplicative_decrease: f64,
+    /// Maximum requests that may be sent back-to-back
+    pub burst: u32,
+    /// Minimum time between two decreases
+    ///
+    /// Requests already in flight when the limit is hit usually all come back
+    /// throttled; counting them as one signal avoids collapsing to `min_rate`.
+    pub decrease_cooldown: Duration,
+    /// Name for logging/metrics
+    pub name: String,
+}
+
+impl Default for AimdConfig {
+    fn default() -> Self {
+        Self {
+            initial_rate: 10.0,
+            min_rate: 1.0,
+            max_rate: 100.0,
+            additive_increase: 1.0,
+            multiplicative_decrease: 0.5,
+            burst: 20,
+            decrease_cooldown: Duration::from_secs(1),
+            name: "default".to_string(),
+        }
+    }
+}
+
+impl AimdConfig {
+    /// Create a new config with a name
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Set the starting rate
+    pub fn with_initial_rate(mut self, rate: f64) -> Self {
+        self.initial_rate = rate;
+        self
+    }
+
+    /// Set the lower and upper rate bounds
+    pub fn with_bounds(mut self, min_rate: f64, max_rate: f64) -> Self {
+        self.min_rate = min_rate;
+        self.max_rate = max_rate;
+        self
+    }
+
+    /// Set the additive increase
+    pub fn with_additive_increase(mut self, increase: f64) -> Self {
+        self.additive_increase = increase;
+        self
+    }
+
+    /// Set the multiplicative decrease factor
+    pub fn with_multiplicative_decrease(mut self, factor: f64) -> Self {
+        self.multiplicative_decrease = factor.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the burst size
+    pub fn with_burst(mut self, burst: u32) -> Self {
+        self.burst = burst.max(1);
+        self
+    }
+
+    /// Set the cooldown between decreases
+    pub fn with_decrease_cooldown(mut self, cooldown: Duration) -> Self {
+        self.decrease_cooldown = cooldown;
+        self
+    }
+}
+
+#[derive(Debug)]
+struct BucketState {
+    rate: f64,
+    tokens: f64,
+    last_refill: Instant,
+    last_decrease: Option<Instant>,
+}
+
+impl BucketState {
+    fn refill(&mut self, now: Instant, capacity: f64) {
+        let elapsed = now.duration_since(self.last_refill).as_secs_f64();
+        self.tokens = (self.tokens + elapsed * self.rate).min(capacity);
+        self.last_refill = now;
+    }
+}
+
+/// Token-bucket rate limiter whose rate follows AIMD feedback
+///
+/// Call [`acquire`](Self::acquire) before each request and report the outcome
+/// with [`on_success`](Self::on_success) or [`on_throttled`](Self::on_throttled).
+#[derive(Debug)]
+pub struct AdaptiveRateLimiter {
+    config: AimdConfig,
+    state: Mutex<BucketState>,
+}
+
+impl AdaptiveRateLimiter {
+    /// Create a new limiter with config
+    pub fn new(config: AimdConfig) -> Self {
+        let rate = config.initial_rate.clamp(config.min_rate, config.max_rate);
+        Self {
+            state: Mutex::new(BucketState {
+                rate,
+                tokens: config.burst as f64,
+                last_refill: Instant::now(),
+                last_decrease: None,
+            }),
+            config,
+        }
+    }
+
+    /// Create with default config and name
+    pub fn with_name(name: impl Into<String>) -> Self {
+        Self::new(AimdConfig::new(name))
+    }
+
+    /// Get the config
+    pub fn config(&self) -> &AimdConfig {
+        &self.config
+    }
+
+    /// Current allowed rate in requests per second
+    pub fn current_rate(&self) -> f64 {
+        self.lock().rate
+    }
+
+    /// Take a token if one is available without waiting
+    pub fn try_acquire(&self) -> bool {
+        let mut state = self.lock();
+        state.refill(Instant::now(), self.config.burst as f64);
+        if state.tokens >= 1.0 {
+            state.tokens -= 1.0;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Wait until a request may be sent
+    pub async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut state = self.lock();
+                state.refill(Instant::now(), self.config.burst as f64);
+                if state.tokens >= 1.0 {
+                    state.tokens -= 1.0;
+                    return;
+                }
+                Duration::from_secs_f64((1.0 - state.tokens) / state.rate)
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Record a successful request
+    ///
+    /// Each success adds `additive_increase / rate`, so running at the full
+    /// rate for one second raises it by `additive_increase`.
+    pub fn on_success(&self) {
+        let mut state = self.lock();
+        state.rate = (state.rate + self.config.additive_increase / state.rate).min(self.config.max_rate);
+    }
+
+    /// Record a throttled request (HTTP 429, JSON-RPC -32005)
+    pub fn on_throttled(&self) {
+        let now = Instant::now();
+        let mut state = self.lock();
+        if let Some(last) = state.last_decrease {
+            if now.duration_since(last) < self.config.decrease_cooldown {
+                return;
+            }
+        }
+
+        state.refill(now, self.config.burst as f64);
+        state.rate = (state.rate * self.config.multiplicative_decrease).max(self.config.min_rate);
+        // Drop any saved-up burst; the endpoint has just told us to slow down
+        state.tokens = state.tokens.min(0.0);
+        state.last_decrease = Some(now);
+        tracing::warn!(limiter = %self.config.name, rate = state.rate, "Rate limited, backing off");
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BucketState> {
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_builder() {
+        let config = AimdConfig::new("test")
+            .with_initial_rate(5.0)
+            .with_bounds(2.0, 50.0)
+            .with_multiplicative_decrease(1.5)
+            .with_burst(0);
+
+        assert_eq!(config.initial_rate, 5.0);
+        assert_eq!(config.min_rate, 2.0);
+        assert_eq!(config.max_rate, 50.0);
+        assert_eq!(config.multiplicative_decrease, 1.0);
+        assert_eq!(config.burst, 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_burst_then_paced() {
+        let limiter = AdaptiveRateLimiter::new(
+            AimdConfig::new("test").with_initial_rate(10.0).with_burst(2),
+        );
+
+        assert!(limiter.try_acquire());
+        assert!(limiter.try_acquire());
+        assert!(!limiter.try_acquire());
+
+        let start = Instant::now();
+        limiter.acquire().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_success_increases_rate() {
+        let limiter = AdaptiveRateLimiter::new(
+            AimdConfig::new("test").with_initial_rate(10.0).with_bounds(1.0, 11.0),
+        );
+
+        for _ in 0..10 {
+            limiter.on_success();
+        }
+        let rate = limiter.current_rate();
+        assert!(rate > 10.9 && rate <= 11.0, "rate = {}", rate);
+
+        for _ in 0..100 {
+            limiter.on_success();
+        }
+        assert_eq!(limiter.current_rate(), 11.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_throttle_halves_rate_once_per_cooldown() {
+        let limiter = AdaptiveRateLimiter::new(AimdConfig::new("test").with_initial_rate(40.0));
+
+        limiter.on_throttled();
+        limiter.on_throttled();
+        assert_eq!(limiter.current_rate(), 20.0);
+        assert!(!limiter.try_acquire());
+
+        tokio::time::advance(Duration::from_secs(1)).await;
+        limiter.on_throttled();
+        assert_eq!(limiter.current_rate(), 10.0);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_rate_floor() {
+        let limiter = AdaptiveRateLimiter::new(
+            AimdConfig::new("test")
+                .with_initial_rate(4.0)
+                .with_bounds(3.0, 100.0)
+                .with_decrease_cooldown(Duration::ZERO),
+        );
+
+        for _ in 0..5 {
+            limiter.on_throttled();
+        }
+        assert_eq!(limiter.current_rate(), 3.0);
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! Determines which errors should trigger retries and how.
 
-use std::time::Duration;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 /// Trait for classifying errors as retryable or not
 pub trait RetryClassifier<E> {
@@ -101,14 +101,59 @@ impl HttpRetryClassifier {
     }
     
     /// Get retry delay from Retry-After header value
+    ///
+    /// Accepts delta-seconds and HTTP-dates; a date in the past means no wait.
+    /// The value is server-controlled and unbounded, so callers must cap it
+    /// before sleeping.
     pub fn parse_retry_after(value: &str) -> Option<Duration> {
-        // Try to parse as seconds
+        let value = value.trim();
         if let Ok(secs) = value.parse::<u64>() {
             return Some(Duration::from_secs(secs));
         }
-        
-        // Could also parse HTTP-date format here
-        None
+        let at = httpdate::parse_http_date(value).ok()?;
+        Some(at.duration_since(SystemTime::now()).unwrap_or_default())
+    }
+
+    /// Delay requested by a rate-limited response's headers
+    ///
+    /// Checks, in order: `Retry-After`; `RateLimit-Reset`,
+    /// `X-RateLimit-Reset` and `X-RateLimit-Reset-After` (seconds until the
+    /// window resets, or a Unix timestamp for large values); and finally an
+    /// exhausted `X-RateLimit-Remaining`, which providers with per-second
+    /// quotas send without a reset time, treated as one second.
+    pub fn retry_after_from_headers<'a>(
+        headers: impl IntoIterator<Item = (&'a str, &'a str)>,
+    ) -> Option<Duration> {
+        let mut retry_after = None;
+        let mut reset = None;
+        let mut exhausted = false;
+        for (name, value) in headers {
+            let value = value.trim();
+            match name.to_ascii_lowercase().as_str() {
+                "retry-after" => retry_after = Self::parse_retry_after(value),
+                "ratelimit-reset" | "x-ratelimit-reset" | "x-ratelimit-reset-after" => {
+                    reset = reset.or_else(|| Self::parse_reset(value));
+                }
+                "ratelimit-remaining" | "x-ratelimit-remaining" => exhausted |= value == "0",
+                _ => {}
+            }
+        }
+        retry_after
+            .or(reset)
+            .or(exhausted.then_some(Duration::from_secs(1)))
+    }
+
+    /// Parses a reset header: seconds (possibly fractional) or a Unix timestamp
+    fn parse_reset(value: &str) -> Option<Duration> {
+        // Anything past 2001 is a timestamp rather than a delay
+        const TIMESTAMP_THRESHOLD: f64 = 1_000_000_000.0;
+        let secs: f64 = value.parse().ok().filter(|s: &f64| s.is_finite() && *s >= 0.0)?;
+        let delay = Duration::try_from_secs_f64(secs).ok()?;
+        if secs < TIMESTAMP_THRESHOLD {
+            return Some(delay);
+        }
+        let at = UNIX_EPOCH.checked_add(delay)?;
+        Some(at.duration_since(SystemTime::now()).unwrap_or_default())
     }
 }
 
@@ -121,12 +166,16 @@ impl RpcRetryClassifier {
     pub fn is_code_retryable(code: i64) -> bool {
         matches!(
             code,
-            -32099..=-32000 | // Server errors
-            -32603          | // Internal error
-            -32005            // Limit exceeded
+            -32099..=-32000 | // Server errors (includes -32005 limit exceeded)
+            -32603            // Internal error
         )
     }
     
+    /// Check if RPC error code means the caller is being rate limited
+    pub fn is_rate_limited(code: i64) -> bool {
+        matches!(code, -32005 | 429)
+    }
+    
     /// Common retryable RPC error messages
     pub fn is_message_retryable(message: &str) -> bool {
         let msg = message.to_lowercase();
@@ -377,6 +426,42 @@ mod tests {
             Some(Duration::from_secs(60))
         );
         assert!(HttpRetryClassifier::parse_retry_after("invalid").is_none());
+        assert_eq!(
+            HttpRetryClassifier::parse_retry_after("Wed, 21 Oct 2015 07:28:00 GMT"),
+            Some(Duration::ZERO)
+        );
+        let later = httpdate::fmt_http_date(SystemTime::now() + Duration::from_secs(120));
+        let delay = HttpRetryClassifier::parse_retry_after(&later).unwrap();
+        assert!(delay > Duration::from_secs(100) && delay <= Duration::from_secs(120));
+    }
+
+    #[test]
+    fn test_retry_after_from_headers() {
+        let delay = |headers: &[(&str, &str)]| {
+            HttpRetryClassifier::retry_after_from_headers(headers.iter().copied())
+        };
+        assert_eq!(
+            delay(&[("Retry-After", "7"), ("X-RateLimit-Reset", "30")]),
+            Some(Duration::from_secs(7))
+        );
+        assert_eq!(delay(&[("ratelimit-reset", "12")]), Some(Duration::from_secs(12)));
+        assert_eq!(
+            delay(&[("x-ratelimit-reset-after", "0.5")]),
+            Some(Duration::from_millis(500))
+        );
+        let reset_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs() + 60;
+        let reset_at = reset_at.to_string();
+        let until_reset = delay(&[("X-RateLimit-Reset", reset_at.as_str())]).unwrap();
+        assert!(until_reset > Duration::from_secs(50) && until_reset <= Duration::from_secs(60));
+        assert_eq!(
+            delay(&[("X-RateLimit-Limit", "25"), ("X-RateLimit-Remaining", "0")]),
+            Some(Duration::from_secs(1))
+        );
+        assert_eq!(delay(&[("X-RateLimit-Remaining", "3")]), None);
+
+        for hostile in ["1e20", "1e400", "NaN", "inf", "-5"] {
+            assert_eq!(delay(&[("x-ratelimit-reset", hostile)]), None);
+        }
     }
     
     #[test]
@@ -386,6 +471,9 @@ mod tests {
         
         assert!(!RpcRetryClassifier::is_code_retryable(-32600)); // Invalid request
         assert!(!RpcRetryClassifier::is_code_retryable(-32601)); // Method not found
+        
+        assert!(RpcRetryClassifier::is_rate_limited(-32005));
+        assert!(!RpcRetryClassifier::is_rate_limited(-32000));
     }
     
     #[test]
@@ -4,7 +4,11 @@
 
 use std::future::Future;
 use std::time::Duration;
-use tokio::time::timeout;
+use tokio::time::{timeout, Instant};
+
+tokio::task_local! {
+    static CURRENT_DEADLINE: Deadline;
+}
 
 /// Timeout configuration for different operations
 #[derive(Debug, Clone)]
@@ -125,12 +129,19 @@ impl std::fmt::Display for TimeoutError {
 impl std::error::Error for TimeoutError {}
 
 /// Execute a future with a timeout
+///
+/// Inside a [`Deadline::scope`] the timeout is shortened to the time left
+/// before the deadline.
 pub async fn with_timeout<T>(
     duration: Duration,
     operation: impl Into<String>,
     future: impl Future<Output = T>,
 ) -> Result<T, TimeoutError> {
     let op = operation.into();
+    let duration = match Deadline::current() {
+        Some(deadline) => duration.min(deadline.remaining()),
+        None => duration,
+    };
     timeout(duration, future)
         .await
         .map_err(|_| TimeoutError {
@@ -156,9 +167,15 @@ pub async fn with_connect_timeout<T>(
 }
 
 /// Deadline tracking for complex operations
+///
+/// A deadline can be made the *current* deadline for everything awaited
+/// inside [`scope`](Self::scope) or [`execute`](Self::execute). Timeouts,
+/// [`with_backoff`](crate::with_backoff) and
+/// [`CircuitBreaker::execute`](crate::CircuitBreaker::execute) all respect
+/// the current deadline, so a caller-level budget holds across every layer.
 #[derive(Debug, Clone)]
 pub struct Deadline {
-    start: std::time::Instant,
+    start: Instant,
     timeout: Duration,
 }
 
@@ -166,11 +183,31 @@ impl Deadline {
     /// Create a new deadline
     pub fn new(timeout: Duration) -> Self {
         Self {
-            start: std::time::Instant::now(),
+            start: Instant::now(),
             timeout,
         }
     }
 
+    /// The deadline of the enclosing [`scope`](Self::scope), if any
+    pub fn current() -> Option<Deadline> {
+        CURRENT_DEADLINE.try_with(Deadline::clone).ok()
+    }
+
+    /// Run `future` with this deadline as the current one
+    ///
+    /// Nested scopes never extend an outer deadline: if the enclosing
+    /// deadline expires first, it stays in effect.
+    pub async fn scope<F: Future>(self, future: F) -> F::Output {
+        CURRENT_DEADLINE.scope(self.min_with_current(), future).await
+    }
+
+    fn min_with_current(self) -> Deadline {
+        match Self::current() {
+            Some(outer) if outer.remaining() < self.remaining() => outer,
+            _ => self,
+        }
+    }
+
     /// Check if deadline has passed
     pub fn is_expired(&self) -> bool {
         self.start.elapsed() >= self.timeout
@@ -192,15 +229,18 @@ impl Deadline {
     }
 
     /// Execute with remaining time as timeout
+    ///
+    /// The deadline is also made current for `future` (see [`scope`](Self::scope)).
     pub async fn execute<T, E>(
         &self,
         future: impl Future<Output = Result<T, E>>,
     ) -> Result<T, DeadlineError<E>> {
-        if self.is_expired() {
+        let deadline = self.clone().min_with_current();
+        if deadline.is_expired() {
             return Err(DeadlineError::Expired);
         }
 
-        match timeout(self.remaining(), future).await {
+        match timeout(deadline.remaining(), deadline.clone().scope(future)).await {
             Ok(Ok(result)) => Ok(result),
             Ok(Err(e)) => Err(DeadlineError::Inner(e)),
             Err(_) => Err(DeadlineError::Expired),
@@ -410,6 +450,41 @@ mod tests {
         assert_eq!(result.unwrap(), 42);
     }
 
+    #[tokio::test(start_paused = true)]
+    async fn test_deadline_scope_caps_timeouts() {
+        assert!(Deadline::current().is_none());
+
+        let result = Deadline::new(Duration::from_secs(2))
+            .scope(async {
+                assert!(Deadline::current().is_some());
+                with_timeout(Duration::from_secs(30), "slow_op", async {
+                    tokio::time::sleep(Duration::from_secs(10)).await;
+                })
+                .await
+            })
+            .await;
+
+        assert_eq!(result.unwrap_err().duration, Duration::from_secs(2));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_nested_deadline_cannot_extend_outer() {
+        let outer = Deadline::new(Duration::from_secs(1));
+        let result: Result<Result<(), DeadlineError<&str>>, DeadlineError<&str>> = outer
+            .execute(async {
+                Ok(Deadline::new(Duration::from_secs(60))
+                    .execute(async {
+                        assert!(Deadline::current().unwrap().remaining() <= Duration::from_secs(1));
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        Ok(())
+                    })
+                    .await)
+            })
+            .await;
+
+        assert!(matches!(result, Ok(Err(DeadlineError::Expired))));
+    }
+
     #[test]
     fn test_adaptive_timeout_initial() {
         let at = AdaptiveTimeout::new(
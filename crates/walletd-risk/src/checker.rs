@@ -0,0 +1,256 @@
+//! Send-time checks on a destination
+
+use crate::history::{normalize, similarity, KnownAddresses};
+use crate::lists::RiskList;
+use crate::report::{RiskReport, RiskWarning};
+use std::fmt;
+use std::sync::{Arc, RwLock};
+use walletd_traits::TransactionRecord;
+
+/// Default number of matching characters at each end for a lookalike
+const DEFAULT_LOOKALIKE: usize = 3;
+
+/// Checks destinations against history and risk lists
+///
+/// Learn the wallet's history first; a checker that knows nothing can only
+/// consult its lists and flag every destination as new.
+pub struct RiskChecker {
+    chain: String,
+    known: RwLock<KnownAddresses>,
+    lists: Vec<Arc<dyn RiskList>>,
+    min_prefix: usize,
+    min_suffix: usize,
+}
+
+impl fmt::Debug for RiskChecker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lists: Vec<&str> = self.lists.iter().map(|list| list.name()).collect();
+        f.debug_struct("RiskChecker")
+            .field("chain", &self.chain)
+            .field("lists", &lists)
+            .field("min_prefix", &self.min_prefix)
+            .field("min_suffix", &self.min_suffix)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RiskChecker {
+    /// Creates a checker for `chain`, which is passed to the risk lists
+    pub fn new(chain: impl Into<String>) -> Self {
+        Self {
+            chain: chain.into(),
+            known: RwLock::new(KnownAddresses::new()),
+            lists: Vec::new(),
+            min_prefix: DEFAULT_LOOKALIKE,
+            min_suffix: DEFAULT_LOOKALIKE,
+        }
+    }
+
+    /// Starts from already learned addresses
+    pub fn with_known(self, known: KnownAddresses) -> Self {
+        *self.known.write().unwrap_or_else(|e| e.into_inner()) = known;
+        self
+    }
+
+    /// Adds a risk list
+    pub fn with_list(mut self, list: Arc<dyn RiskList>) -> Self {
+        self.lists.push(list);
+        self
+    }
+
+    /// Sets how many characters must match at each end for a lookalike
+    /// (default 3 and 3)
+    pub fn with_lookalike(mut self, prefix: usize, suffix: usize) -> Self {
+        self.min_prefix = prefix.max(1);
+        self.min_suffix = suffix.max(1);
+        self
+    }
+
+    /// Treats transfers below `value` (smallest unit) as dust
+    pub fn with_dust_threshold(self, value: u128) -> Self {
+        let known = self.known().with_dust_threshold(value);
+        self.with_known(known)
+    }
+
+    /// Learns the counterparty of one transaction
+    pub fn learn(&self, record: &TransactionRecord) {
+        self.known
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .learn(record);
+    }
+
+    /// Learns the counterparties of many transactions
+    pub fn learn_all<'a>(&self, records: impl IntoIterator<Item = &'a TransactionRecord>) {
+        self.known
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .learn_all(records);
+    }
+
+    /// Records that the wallet has just paid `address`
+    pub fn record_payment(&self, address: &str) {
+        self.known
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .record_payment(address);
+    }
+
+    /// Returns a copy of what has been learned
+    pub fn known(&self) -> KnownAddresses {
+        self.known.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// Checks `destination`
+    ///
+    /// A list that fails to answer yields [`RiskWarning::Unchecked`] rather
+    /// than an error, so the caller decides whether to send anyway.
+    pub async fn check(&self, destination: &str) -> RiskReport {
+        let address = normalize(destination);
+        let mut warnings = self.check_history(&address);
+        for list in &self.lists {
+            match list.lookup(&self.chain, &address).await {
+                Ok(Some(hit)) => warnings.push(RiskWarning::Listed {
+                    address: address.clone(),
+                    hit,
+                }),
+                Ok(None) => {}
+                Err(e) => warnings.push(RiskWarning::Unchecked {
+                    list: list.name().to_string(),
+                    reason: e.to_string(),
+                }),
+            }
+        }
+        RiskReport::new(warnings)
+    }
+
+    fn check_history(&self, address: &str) -> Vec<RiskWarning> {
+        let known = self.known.read().unwrap_or_else(|e| e.into_inner());
+        if known.is_paid(address) {
+            return Vec::new();
+        }
+        let mut warnings = Vec::new();
+        let lookalike = known
+            .paid()
+            .map(|paid| (paid, similarity(address, paid)))
+            .filter(|(_, s)| s.prefix >= self.min_prefix && s.suffix >= self.min_suffix)
+            .max_by_key(|(_, s)| s.prefix + s.suffix);
+        if let Some((paid, s)) = lookalike {
+            warnings.push(RiskWarning::Lookalike {
+                address: address.to_string(),
+                resembles: paid.to_string(),
+                prefix: s.prefix,
+                suffix: s.suffix,
+            });
+        }
+        if let Some(transfers) = known.dust_only(address) {
+            warnings.push(RiskWarning::ZeroValueSpam {
+                address: address.to_string(),
+                transfers,
+            });
+        }
+        warnings.push(RiskWarning::NewRecipient {
+            address: address.to_string(),
+        });
+        warnings
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::lists::StaticRiskList;
+    use crate::report::Severity;
+    use crate::{Result, RiskHit};
+    use async_trait::async_trait;
+    use walletd_traits::{Amount, TransactionStatus, TxDirection, TxHash};
+
+    const FRIEND: &str = "0x7a250d5630B4cF539739dF2C5dAcb4c659F2488D";
+    const POISON: &str = "0x7a2500000000000000000000000000000002488d";
+
+    struct Down;
+
+    #[async_trait]
+    impl RiskList for Down {
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        async fn lookup(&self, _chain: &str, _address: &str) -> Result<Option<RiskHit>> {
+            Err(crate::RiskError::Lookup {
+                list: "down".into(),
+                reason: "timed out".into(),
+            })
+        }
+    }
+
+    fn record(direction: TxDirection, counterparty: &str, value: u128) -> TransactionRecord {
+        TransactionRecord {
+            hash: TxHash::new("0xhash"),
+            direction,
+            counterparty: Some(counterparty.to_string()),
+            amount: Amount::from_smallest_unit(value, 6),
+            fee: None,
+            status: TransactionStatus::Confirmed,
+            timestamp: None,
+            block_height: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poisoned_destination() {
+        let mut ofac = StaticRiskList::new("ofac");
+        ofac.insert(POISON, "sanctions", Severity::Critical);
+        let checker = RiskChecker::new("ethereum")
+            .with_list(Arc::new(ofac))
+            .with_list(Arc::new(Down));
+        checker.learn_all(&[
+            record(TxDirection::Outgoing, FRIEND, 1_000_000),
+            record(TxDirection::Outgoing, POISON, 0),
+        ]);
+
+        let report = checker.check(POISON).await;
+        let kinds: Vec<Severity> = report.warnings.iter().map(|w| w.severity()).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                Severity::Critical,
+                Severity::Critical,
+                Severity::Warning,
+                Severity::Warning,
+                Severity::Info
+            ]
+        );
+        assert!(matches!(
+            &report.warnings[0],
+            RiskWarning::Lookalike { resembles, prefix: 5, suffix: 5, .. }
+                if resembles == &FRIEND.to_lowercase()
+        ));
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| matches!(w, RiskWarning::ZeroValueSpam { transfers: 1, .. })));
+        assert!(report
+            .warnings
+            .iter()
+            .any(|w| matches!(w, RiskWarning::Unchecked { list, .. } if list == "down")));
+    }
+
+    #[tokio::test]
+    async fn test_known_destination() {
+        let checker = RiskChecker::new("ethereum").with_lookalike(4, 4);
+        checker.learn(&record(TxDirection::Outgoing, FRIEND, 1_000_000));
+        assert!(checker
+            .check(&FRIEND.to_uppercase().replacen("0X", "0x", 1))
+            .await
+            .is_clear());
+
+        let report = checker
+            .check("0x7a2000000000000000000000000000000002488d")
+            .await;
+        assert_eq!(report.highest(), Some(Severity::Info));
+
+        checker.record_payment(POISON);
+        assert!(checker.check(POISON).await.is_clear());
+    }
+}
@@ -0,0 +1,209 @@
+//! What the wallet's history says about counterparties
+
+use std::collections::HashMap;
+use walletd_traits::{TransactionRecord, TransactionStatus, TxDirection};
+
+/// How much two addresses look alike at their ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Similarity {
+    /// Matching characters at the start
+    pub prefix: usize,
+    /// Matching characters at the end
+    pub suffix: usize,
+}
+
+/// Compares the ends of two addresses
+///
+/// The parts every address shares are skipped: a `0x` prefix, or the
+/// human-readable part of a bech32 address. EVM addresses compare without
+/// case.
+pub fn similarity(a: &str, b: &str) -> Similarity {
+    let a: Vec<char> = body(&normalize(a)).chars().collect();
+    let b: Vec<char> = body(&normalize(b)).chars().collect();
+    if a == b {
+        return Similarity {
+            prefix: a.len(),
+            suffix: a.len(),
+        };
+    }
+    let prefix = a.iter().zip(&b).take_while(|(x, y)| x == y).count();
+    let suffix = a
+        .iter()
+        .rev()
+        .zip(b.iter().rev())
+        .take_while(|(x, y)| x == y)
+        .count();
+    Similarity { prefix, suffix }
+}
+
+/// Lowercases EVM addresses; other formats are case-sensitive
+pub(crate) fn normalize(address: &str) -> String {
+    let address = address.trim();
+    if address.starts_with("0x") || address.starts_with("0X") {
+        address.to_ascii_lowercase()
+    } else {
+        address.to_string()
+    }
+}
+
+fn body(address: &str) -> &str {
+    if let Some(hex) = address.strip_prefix("0x") {
+        return hex;
+    }
+    // bech32: lowercase, human-readable part, then `1`
+    match address.rfind('1') {
+        Some(i)
+            if i > 0
+                && address[..i].chars().all(|c| c.is_ascii_lowercase())
+                && !address.chars().any(|c| c.is_ascii_uppercase()) =>
+        {
+            &address[i + 1..]
+        }
+        _ => address,
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Contact {
+    paid: usize,
+    received: usize,
+    dust: usize,
+}
+
+/// Counterparties seen in a wallet's history
+///
+/// Only transfers above the dust threshold make an address known. Poisoned
+/// history consists of zero-value transfers, often forged to look outgoing,
+/// so those are counted separately.
+#[derive(Debug, Clone, Default)]
+pub struct KnownAddresses {
+    contacts: HashMap<String, Contact>,
+    dust_below: u128,
+}
+
+impl KnownAddresses {
+    /// Creates an empty set where only zero-value transfers are dust
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Treats transfers below `value` (smallest unit) as dust too
+    pub fn with_dust_threshold(mut self, value: u128) -> Self {
+        self.dust_below = value;
+        self
+    }
+
+    /// Records the counterparty of one transaction
+    pub fn learn(&mut self, record: &TransactionRecord) {
+        let Some(counterparty) = &record.counterparty else {
+            return;
+        };
+        if record.status == TransactionStatus::Failed
+            || record.direction == TxDirection::SelfTransfer
+        {
+            return;
+        }
+        let contact = self.contacts.entry(normalize(counterparty)).or_default();
+        let value = record.amount.value;
+        if value == 0 || value < self.dust_below {
+            contact.dust += 1;
+        } else if record.direction == TxDirection::Outgoing {
+            contact.paid += 1;
+        } else {
+            contact.received += 1;
+        }
+    }
+
+    /// Records the counterparties of many transactions
+    pub fn learn_all<'a>(&mut self, records: impl IntoIterator<Item = &'a TransactionRecord>) {
+        for record in records {
+            self.learn(record);
+        }
+    }
+
+    /// Records a payment made outside of history, e.g. one just sent
+    pub fn record_payment(&mut self, address: &str) {
+        self.contacts.entry(normalize(address)).or_default().paid += 1;
+    }
+
+    /// Whether the wallet has paid `address` more than dust
+    pub fn is_paid(&self, address: &str) -> bool {
+        self.contacts
+            .get(&normalize(address))
+            .is_some_and(|contact| contact.paid > 0)
+    }
+
+    /// Number of dust transfers, if `address` appears in nothing else
+    pub fn dust_only(&self, address: &str) -> Option<usize> {
+        self.contacts
+            .get(&normalize(address))
+            .filter(|contact| contact.paid == 0 && contact.received == 0 && contact.dust > 0)
+            .map(|contact| contact.dust)
+    }
+
+    /// Addresses the wallet has paid more than dust
+    pub fn paid(&self) -> impl Iterator<Item = &str> {
+        self.contacts
+            .iter()
+            .filter(|(_, contact)| contact.paid > 0)
+            .map(|(address, _)| address.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::{Amount, TxHash};
+
+    fn record(direction: TxDirection, counterparty: &str, value: u128) -> TransactionRecord {
+        TransactionRecord {
+            hash: TxHash::new("0xhash"),
+            direction,
+            counterparty: Some(counterparty.to_string()),
+            amount: Amount::from_smallest_unit(value, 6),
+            fee: None,
+            status: TransactionStatus::Confirmed,
+            timestamp: Some(1_700_000_000),
+            block_height: Some(1),
+        }
+    }
+
+    #[test]
+    fn test_similarity() {
+        let real = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        let fake = "0xa0b8600000000000000000000000000000006eb48";
+        assert_eq!(
+            similarity(real, fake),
+            Similarity {
+                prefix: 5,
+                suffix: 6
+            }
+        );
+        assert_eq!(
+            similarity(
+                "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu",
+                "cosmos1qyp0000000000000000000000000000000v7xu"
+            ),
+            Similarity {
+                prefix: 3,
+                suffix: 4
+            }
+        );
+    }
+
+    #[test]
+    fn test_dust_is_not_a_payment() {
+        let mut known = KnownAddresses::new().with_dust_threshold(10_000);
+        known.learn_all(&[
+            record(TxDirection::Outgoing, "0xFRIEND", 5_000_000),
+            record(TxDirection::Outgoing, "0xpoison", 0),
+            record(TxDirection::Incoming, "0xpoison", 1),
+            record(TxDirection::Incoming, "0xpayer", 2_000_000),
+        ]);
+        assert!(known.is_paid("0xfriend"));
+        assert!(!known.is_paid("0xpoison"));
+        assert_eq!(known.dust_only("0xpoison"), Some(2));
+        assert_eq!(known.dust_only("0xpayer"), None);
+        assert_eq!(known.paid().collect::<Vec<_>>(), vec!["0xfriend"]);
+    }
+}
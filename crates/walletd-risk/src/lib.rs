@@ -0,0 +1,96 @@
+//! # WalletD Risk
+//!
+//! Checks a destination address before a transfer is signed and reports
+//! typed [`RiskWarning`]s:
+//!
+//! - **Lookalikes**: the address shares its first and last characters with
+//!   an address the wallet has paid before, but is a different address.
+//!   Address poisoning relies on users copying such an address from their
+//!   history, because wallets show only its ends.
+//! - **Zero-value spam**: the address only appears in history through
+//!   zero-value (or dust) transfers, which is how poisoners plant it there.
+//! - **Risk lists**: sanctions or risk lists reached through [`RiskList`],
+//!   such as a [`StaticRiskList`] or the [`ChainalysisSanctions`] API.
+//! - **New recipients**: the wallet has never paid the address.
+//!
+//! [`RiskWallet`] runs the checks on every transfer and refuses those with a
+//! warning at or above a chosen [`Severity`].
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_risk::{ChainalysisSanctions, RiskChecker, Severity};
+//!
+//! let checker = RiskChecker::new("ethereum")
+//!     .with_list(Arc::new(ChainalysisSanctions::new(api_key)));
+//! checker.learn_all(&history.recent_transactions(&address, 500).await?);
+//!
+//! let report = checker.check(&destination).await;
+//! for warning in &report.warnings {
+//!     println!("[{:?}] {}", warning.severity(), warning);
+//! }
+//! if report.highest() == Some(Severity::Critical) {
+//!     // ask the user to confirm the full address
+//! }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod checker;
+pub mod history;
+pub mod lists;
+pub mod report;
+pub mod wallet;
+
+pub use checker::RiskChecker;
+pub use history::{similarity, KnownAddresses, Similarity};
+pub use lists::{ChainalysisSanctions, RiskHit, RiskList, StaticRiskList};
+pub use report::{RiskReport, RiskWarning, Severity};
+pub use wallet::RiskWallet;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Risk check errors
+#[derive(Error, Debug)]
+pub enum RiskError {
+    /// The transfer has warnings at or above the blocking severity
+    #[error("Transfer blocked: {0}")]
+    Blocked(RiskReport),
+
+    /// A risk list could not be read
+    #[error("Risk list {list} failed: {reason}")]
+    Lookup {
+        /// Name of the list
+        list: String,
+        /// What went wrong
+        reason: String,
+    },
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Result type for risk checks
+pub type Result<T> = std::result::Result<T, RiskError>;
+
+impl From<RiskError> for WalletError {
+    fn from(e: RiskError) -> Self {
+        match e {
+            RiskError::Blocked(_) => WalletError::TransactionFailed(e.to_string()),
+            e => WalletError::NetworkError(e.to_string()),
+        }
+    }
+}
+
+impl From<RiskError> for WalletdError {
+    fn from(e: RiskError) -> Self {
+        match e {
+            RiskError::Blocked(_) => WalletdError::TransactionFailed(e.to_string()),
+            e => WalletdError::NetworkError(e.to_string()),
+        }
+    }
+}
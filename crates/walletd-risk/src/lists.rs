@@ -0,0 +1,252 @@
+//! Sanctions and risk lists
+
+use crate::history::normalize;
+use crate::report::Severity;
+use crate::{Result, RiskError};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Chainalysis public sanctions API
+pub const CHAINALYSIS_API: &str = "https://public.chainalysis.com";
+
+/// What a list says about an address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskHit {
+    /// Name of the list
+    pub source: String,
+    /// Category, e.g. `sanctions` or `scam`
+    pub category: String,
+    /// Details from the list
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// How serious the listing is
+    pub severity: Severity,
+}
+
+/// A source of address listings
+#[async_trait]
+pub trait RiskList: Send + Sync {
+    /// Name for warnings and logs
+    fn name(&self) -> &str;
+
+    /// Looks up `address` on `chain`
+    async fn lookup(&self, chain: &str, address: &str) -> Result<Option<RiskHit>>;
+}
+
+/// A list held in memory, for any chain
+///
+/// Fill it from a downloaded list, e.g. the addresses in OFAC's SDN list.
+#[derive(Debug, Clone)]
+pub struct StaticRiskList {
+    name: String,
+    entries: HashMap<String, RiskHit>,
+}
+
+impl StaticRiskList {
+    /// Creates an empty list
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            entries: HashMap::new(),
+        }
+    }
+
+    /// Creates a list from one address per line
+    ///
+    /// Blank lines and lines starting with `#` are skipped.
+    pub fn from_lines(
+        name: impl Into<String>,
+        text: &str,
+        category: &str,
+        severity: Severity,
+    ) -> Self {
+        let mut list = Self::new(name);
+        for line in text.lines().map(str::trim) {
+            if !line.is_empty() && !line.starts_with('#') {
+                list.insert(line, category, severity);
+            }
+        }
+        list
+    }
+
+    /// Adds an address
+    pub fn insert(&mut self, address: &str, category: &str, severity: Severity) {
+        let hit = RiskHit {
+            source: self.name.clone(),
+            category: category.to_string(),
+            description: None,
+            severity,
+        };
+        self.entries.insert(normalize(address), hit);
+    }
+
+    /// Number of listed addresses
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the list is empty
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+#[async_trait]
+impl RiskList for StaticRiskList {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    async fn lookup(&self, _chain: &str, address: &str) -> Result<Option<RiskHit>> {
+        Ok(self.entries.get(&normalize(address)).cloned())
+    }
+}
+
+/// The Chainalysis sanctions screening API
+///
+/// Free with an API key; covers sanctioned addresses on every chain.
+#[derive(Clone)]
+pub struct ChainalysisSanctions {
+    base_url: String,
+    api_key: String,
+    client: reqwest::Client,
+}
+
+impl fmt::Debug for ChainalysisSanctions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainalysisSanctions")
+            .field("base_url", &self.base_url)
+            .finish_non_exhaustive()
+    }
+}
+
+#[derive(Deserialize)]
+struct Screening {
+    #[serde(default)]
+    identifications: Vec<Identification>,
+}
+
+#[derive(Deserialize)]
+struct Identification {
+    category: String,
+    name: Option<String>,
+    description: Option<String>,
+}
+
+impl ChainalysisSanctions {
+    /// Uses the public API with `api_key`
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            base_url: CHAINALYSIS_API.to_string(),
+            api_key: api_key.into(),
+            client: reqwest::Client::new(),
+        }
+    }
+
+    /// Uses another endpoint with the same API
+    pub fn with_base_url(mut self, url: impl Into<String>) -> Self {
+        self.base_url = url.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl RiskList for ChainalysisSanctions {
+    fn name(&self) -> &str {
+        "chainalysis"
+    }
+
+    async fn lookup(&self, _chain: &str, address: &str) -> Result<Option<RiskHit>> {
+        let url = format!("{}/api/v1/address/{}", self.base_url, address.trim());
+        let screening: Screening = self
+            .client
+            .get(&url)
+            .header("X-API-Key", &self.api_key)
+            .header("Accept", "application/json")
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        let Some(found) = screening.identifications.into_iter().next() else {
+            return Ok(None);
+        };
+        if found.category.is_empty() {
+            return Err(RiskError::Lookup {
+                list: self.name().to_string(),
+                reason: "identification without a category".into(),
+            });
+        }
+        Ok(Some(RiskHit {
+            source: self.name().to_string(),
+            category: found.category,
+            description: found.description.or(found.name),
+            severity: Severity::Critical,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const TORNADO: &str = "0x8589427373D6D84E98730D7795D8f6f8731FDA16";
+
+    #[tokio::test]
+    async fn test_static_list() {
+        let list = StaticRiskList::from_lines(
+            "ofac",
+            &format!("# SDN digital currency addresses\n{}\n\n", TORNADO),
+            "sanctions",
+            Severity::Critical,
+        );
+        assert_eq!(list.len(), 1);
+        let hit = list
+            .lookup("ethereum", &TORNADO.to_lowercase())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            (hit.source.as_str(), hit.severity),
+            ("ofac", Severity::Critical)
+        );
+        assert!(list.lookup("ethereum", "0xabc").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_chainalysis() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path(format!("/api/v1/address/{}", TORNADO)))
+            .and(header("X-API-Key", "key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "identifications": [{
+                    "category": "sanctions",
+                    "name": "SANCTIONS: OFAC SDN Tornado Cash 2022-08-08",
+                    "description": "Tornado Cash is a mixer",
+                    "url": "https://home.treasury.gov/"
+                }]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/address/0xclean"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({ "identifications": [] })),
+            )
+            .mount(&server)
+            .await;
+
+        let list = ChainalysisSanctions::new("key").with_base_url(server.uri());
+        let hit = list.lookup("ethereum", TORNADO).await.unwrap().unwrap();
+        assert_eq!(hit.category, "sanctions");
+        assert_eq!(hit.description.as_deref(), Some("Tornado Cash is a mixer"));
+        assert!(list.lookup("ethereum", "0xclean").await.unwrap().is_none());
+        assert!(list.lookup("ethereum", "0xmissing").await.is_err());
+    }
+}
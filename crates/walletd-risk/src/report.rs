@@ -0,0 +1,162 @@
+//! Typed warnings about a destination
+
+use crate::lists::RiskHit;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// How serious a warning is
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// Worth showing, no action needed
+    Info,
+    /// The user should look again before sending
+    Warning,
+    /// Likely loss of funds or a legal problem; send only after explicit
+    /// confirmation
+    Critical,
+}
+
+/// Something that may be wrong with a destination
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RiskWarning {
+    /// The destination looks like an address the wallet has paid before
+    Lookalike {
+        /// The destination
+        address: String,
+        /// The known address it resembles
+        resembles: String,
+        /// Matching characters at the start
+        prefix: usize,
+        /// Matching characters at the end
+        suffix: usize,
+    },
+    /// The destination only appears in history through zero-value or dust
+    /// transfers
+    ZeroValueSpam {
+        /// The destination
+        address: String,
+        /// Number of such transfers
+        transfers: usize,
+    },
+    /// The destination is on a sanctions or risk list
+    Listed {
+        /// The destination
+        address: String,
+        /// What the list says
+        hit: RiskHit,
+    },
+    /// A risk list could not be checked
+    Unchecked {
+        /// Name of the list
+        list: String,
+        /// Why the check failed
+        reason: String,
+    },
+    /// The wallet has never paid the destination
+    NewRecipient {
+        /// The destination
+        address: String,
+    },
+    /// The transfer calls a contract with data that could not be decoded, so
+    /// where the funds end up was not checked
+    UnscreenedCall {
+        /// The contract called
+        contract: String,
+    },
+}
+
+impl RiskWarning {
+    /// How serious the warning is
+    pub fn severity(&self) -> Severity {
+        match self {
+            RiskWarning::Lookalike { .. } => Severity::Critical,
+            RiskWarning::Listed { hit, .. } => hit.severity,
+            RiskWarning::ZeroValueSpam { .. }
+            | RiskWarning::Unchecked { .. }
+            | RiskWarning::UnscreenedCall { .. } => Severity::Warning,
+            RiskWarning::NewRecipient { .. } => Severity::Info,
+        }
+    }
+}
+
+impl fmt::Display for RiskWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RiskWarning::Lookalike {
+                address, resembles, ..
+            } => write!(
+                f,
+                "{} looks like {}, an address you have paid before, but is different",
+                address, resembles
+            ),
+            RiskWarning::ZeroValueSpam { address, transfers } => write!(
+                f,
+                "{} only appears in your history through {} zero-value transfer(s)",
+                address, transfers
+            ),
+            RiskWarning::Listed { address, hit } => {
+                write!(
+                    f,
+                    "{} is listed by {} as {}",
+                    address, hit.source, hit.category
+                )
+            }
+            RiskWarning::Unchecked { list, reason } => {
+                write!(f, "could not check {}: {}", list, reason)
+            }
+            RiskWarning::NewRecipient { address } => {
+                write!(f, "you have not sent to {} before", address)
+            }
+            RiskWarning::UnscreenedCall { contract } => write!(
+                f,
+                "the call to {} was not decoded, so its recipient was not checked",
+                contract
+            ),
+        }
+    }
+}
+
+/// Warnings about one destination
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RiskReport {
+    /// Warnings, most severe first
+    pub warnings: Vec<RiskWarning>,
+}
+
+impl RiskReport {
+    pub(crate) fn new(mut warnings: Vec<RiskWarning>) -> Self {
+        warnings.sort_by_key(|warning| std::cmp::Reverse(warning.severity()));
+        Self { warnings }
+    }
+
+    /// Whether there are no warnings at all
+    pub fn is_clear(&self) -> bool {
+        self.warnings.is_empty()
+    }
+
+    /// The most serious severity, if any
+    pub fn highest(&self) -> Option<Severity> {
+        self.warnings.first().map(RiskWarning::severity)
+    }
+
+    /// Warnings at or above `severity`
+    pub fn at_least(&self, severity: Severity) -> impl Iterator<Item = &RiskWarning> {
+        self.warnings
+            .iter()
+            .filter(move |warning| warning.severity() >= severity)
+    }
+}
+
+impl fmt::Display for RiskReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.warnings.first() {
+            Some(first) if self.warnings.len() > 1 => {
+                write!(f, "{} (+{} more)", first, self.warnings.len() - 1)
+            }
+            Some(first) => first.fmt(f),
+            None => f.write_str("no warnings"),
+        }
+    }
+}
@@ -0,0 +1,288 @@
+//! Wallet wrapper that checks every destination before sending
+
+use crate::checker::RiskChecker;
+use crate::report::{RiskReport, RiskWarning, Severity};
+use crate::RiskError;
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::evm::Erc20Call;
+use walletd_traits::{
+    Amount, Network, TokenWallet, TransactionBuilder, Transferable, TxHash, Wallet, WalletError,
+    WalletResult,
+};
+
+/// A wallet whose transfers are checked by a [`RiskChecker`]
+///
+/// For ERC-20 `transfer` and `approve` calls the token recipient or spender
+/// is checked as well as the contract; other call data gets a
+/// [`RiskWarning::UnscreenedCall`] warning. Transfers with a warning at or above the blocking severity fail with
+/// [`RiskError::Blocked`] before anything is signed; lesser warnings are
+/// logged. Once the user has confirmed a destination, send through
+/// [`RiskWallet::inner`]. Every sent transfer is recorded as a payment, so
+/// the destination is trusted next time.
+pub struct RiskWallet<W> {
+    inner: W,
+    checker: Arc<RiskChecker>,
+    block_at: Severity,
+}
+
+impl<W> RiskWallet<W> {
+    /// Wraps a wallet, blocking critical warnings
+    pub fn new(inner: W, checker: Arc<RiskChecker>) -> Self {
+        Self {
+            inner,
+            checker,
+            block_at: Severity::Critical,
+        }
+    }
+
+    /// Blocks warnings at or above `severity` instead
+    pub fn with_block_at(mut self, severity: Severity) -> Self {
+        self.block_at = severity;
+        self
+    }
+
+    /// Returns the checker
+    pub fn checker(&self) -> &Arc<RiskChecker> {
+        &self.checker
+    }
+
+    /// Returns the wrapped wallet, for sends the user has confirmed
+    pub fn inner(&self) -> &W {
+        &self.inner
+    }
+
+    /// Returns the wrapped wallet
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+
+    async fn screen(&self, to: &str) -> WalletResult<RiskReport> {
+        self.screen_with(to, Vec::new()).await
+    }
+
+    async fn screen_with(&self, to: &str, extra: Vec<RiskWarning>) -> WalletResult<RiskReport> {
+        let mut warnings = self.checker.check(to).await.warnings;
+        warnings.extend(extra);
+        let report = RiskReport::new(warnings);
+        if report.at_least(self.block_at).next().is_some() {
+            return Err(RiskError::Blocked(report).into());
+        }
+        for warning in &report.warnings {
+            tracing::warn!(severity = ?warning.severity(), "{}", warning);
+        }
+        Ok(report)
+    }
+}
+
+#[async_trait]
+impl<W: Wallet> Wallet for RiskWallet<W> {
+    fn address(&self) -> String {
+        self.inner.address()
+    }
+
+    async fn balance(&self) -> WalletResult<Amount> {
+        self.inner.balance().await
+    }
+
+    fn network(&self) -> &Network {
+        self.inner.network()
+    }
+
+    fn currency_symbol(&self) -> &str {
+        self.inner.currency_symbol()
+    }
+
+    fn decimals(&self) -> u8 {
+        self.inner.decimals()
+    }
+}
+
+#[async_trait]
+impl<W: Transferable> Transferable for RiskWallet<W> {
+    type TxParams = W::TxParams;
+
+    async fn transfer_with(&self, tx: TransactionBuilder<W::TxParams>) -> WalletResult<TxHash> {
+        let to = tx
+            .to
+            .clone()
+            .ok_or_else(|| WalletError::InvalidAddress("missing recipient".into()))?;
+        let data = tx.data.as_deref().unwrap_or_default();
+        // Whoever ends up with the funds is trusted once the transfer is sent
+        let paid = if data.is_empty() {
+            self.screen(&to).await?;
+            Some(to)
+        } else if let Some(call) = Erc20Call::decode(data) {
+            self.screen(&to).await?;
+            self.screen(&call.recipient).await?;
+            (!call.approve).then_some(call.recipient)
+        } else {
+            let unscreened = RiskWarning::UnscreenedCall {
+                contract: to.clone(),
+            };
+            self.screen_with(&to, vec![unscreened]).await?;
+            None
+        };
+        let hash = self.inner.transfer_with(tx).await?;
+        if let Some(paid) = paid {
+            self.checker.record_payment(&paid);
+        }
+        Ok(hash)
+    }
+
+    async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount> {
+        self.inner.estimate_fee(to, amount).await
+    }
+}
+
+#[async_trait]
+impl<W: TokenWallet> TokenWallet for RiskWallet<W> {
+    type TokenInfo = W::TokenInfo;
+
+    async fn token_balance(&self, token_address: &str) -> WalletResult<Amount> {
+        self.inner.token_balance(token_address).await
+    }
+
+    async fn transfer_token(
+        &self,
+        token_address: &str,
+        to: &str,
+        amount: Amount,
+    ) -> WalletResult<TxHash> {
+        self.screen(to).await?;
+        let hash = self.inner.transfer_token(token_address, to, amount).await?;
+        self.checker.record_payment(to);
+        Ok(hash)
+    }
+
+    async fn token_info(&self, token_address: &str) -> WalletResult<Self::TokenInfo> {
+        self.inner.token_info(token_address).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    struct Mock {
+        network: Network,
+        sent: AtomicU32,
+    }
+
+    #[async_trait]
+    impl Wallet for Mock {
+        fn address(&self) -> String {
+            "0xsender".into()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl Transferable for Mock {
+        type TxParams = ();
+
+        async fn transfer_with(&self, _tx: TransactionBuilder<()>) -> WalletResult<TxHash> {
+            self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok(TxHash::new("0xsent"))
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_blocks_lookalike() {
+        let checker = Arc::new(RiskChecker::new("ethereum"));
+        checker.record_payment("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let wallet = RiskWallet::new(
+            Mock {
+                network: Network::mainnet("ethereum"),
+                sent: AtomicU32::new(0),
+            },
+            checker.clone(),
+        );
+        let amount = Amount::from_smallest_unit(1, 18);
+
+        let err = wallet
+            .transfer("0xd8da000000000000000000000000000000096045", amount)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, WalletError::TransactionFailed(m) if m.contains("looks like")));
+        assert_eq!(wallet.inner().sent.load(Ordering::SeqCst), 0);
+
+        // New recipients only warn, and are trusted once paid
+        let stranger = "0x1111111111111111111111111111111111111111";
+        wallet.transfer(stranger, amount).await.unwrap();
+        assert!(checker.check(stranger).await.is_clear());
+
+        let strict = RiskWallet::new(wallet.into_inner(), checker).with_block_at(Severity::Info);
+        assert!(strict
+            .transfer("0x2222222222222222222222222222222222222222", amount)
+            .await
+            .is_err());
+        assert_eq!(strict.inner().sent.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_screens_calldata_recipient() {
+        let checker = Arc::new(RiskChecker::new("ethereum"));
+        checker.record_payment("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045");
+        let wallet = RiskWallet::new(
+            Mock {
+                network: Network::mainnet("ethereum"),
+                sent: AtomicU32::new(0),
+            },
+            checker.clone(),
+        );
+        let usdc = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        let transfer = |recipient: [u8; 20]| {
+            let mut data = vec![0xa9, 0x05, 0x9c, 0xbb];
+            data.extend([0u8; 12]);
+            data.extend(recipient);
+            data.extend([0u8; 31]);
+            data.push(1);
+            TransactionBuilder::with_params(()).to(usdc).data(data)
+        };
+
+        // A token transfer to a lookalike of a paid address is blocked
+        let mut lookalike = [0u8; 20];
+        lookalike[..2].copy_from_slice(&[0xd8, 0xda]);
+        lookalike[17..].copy_from_slice(&[0x09, 0x60, 0x45]);
+        assert!(wallet.transfer_with(transfer(lookalike)).await.is_err());
+        assert_eq!(wallet.inner().sent.load(Ordering::SeqCst), 0);
+
+        // The token recipient, not the contract, is trusted once paid
+        wallet.transfer_with(transfer([0x11; 20])).await.unwrap();
+        assert!(checker
+            .check("0x1111111111111111111111111111111111111111")
+            .await
+            .is_clear());
+        assert!(!checker.check(usdc).await.is_clear());
+
+        // Other call data is flagged as unscreened
+        let call = TransactionBuilder::with_params(())
+            .to(usdc)
+            .data(vec![0xde, 0xad]);
+        let strict = RiskWallet::new(wallet.into_inner(), checker).with_block_at(Severity::Warning);
+        let err = strict.transfer_with(call).await.unwrap_err();
+        assert!(matches!(err, WalletError::TransactionFailed(m) if m.contains("not decoded")));
+        assert_eq!(strict.inner().sent.load(Ordering::SeqCst), 1);
+    }
+}
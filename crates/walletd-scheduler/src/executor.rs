@@ -0,0 +1,43 @@
+//! Sending scheduled transfers
+
+use crate::transfer::ScheduledTransfer;
+use async_trait::async_trait;
+use std::sync::Arc;
+use walletd_traits::{Amount, Transferable, TxHash, WalletResult};
+
+/// Signs and broadcasts scheduled transfers on one chain
+#[async_trait]
+pub trait TransferExecutor: Send + Sync {
+    /// Estimates the fee of sending `transfer` now
+    async fn estimate_fee(&self, transfer: &ScheduledTransfer) -> WalletResult<Amount>;
+
+    /// Signs and broadcasts `transfer`
+    async fn execute(&self, transfer: &ScheduledTransfer) -> WalletResult<TxHash>;
+}
+
+/// Sends through a [`Transferable`] wallet, which signs with its configured
+/// signer and uses the chain's default transaction options
+#[derive(Debug)]
+pub struct WalletExecutor<W> {
+    wallet: Arc<W>,
+}
+
+impl<W> WalletExecutor<W> {
+    /// Sends from `wallet`
+    pub fn new(wallet: Arc<W>) -> Self {
+        Self { wallet }
+    }
+}
+
+#[async_trait]
+impl<W: Transferable + 'static> TransferExecutor for WalletExecutor<W> {
+    async fn estimate_fee(&self, transfer: &ScheduledTransfer) -> WalletResult<Amount> {
+        self.wallet
+            .estimate_fee(&transfer.to, transfer.amount)
+            .await
+    }
+
+    async fn execute(&self, transfer: &ScheduledTransfer) -> WalletResult<TxHash> {
+        self.wallet.transfer(&transfer.to, transfer.amount).await
+    }
+}
@@ -0,0 +1,110 @@
+//! # WalletD Scheduler
+//!
+//! Delayed and recurring transfers, for dollar-cost averaging, payroll and
+//! other sends that should happen later or on a timetable. A [`Scheduler`]:
+//!
+//! - persists transfer intents through a [`ScheduleStore`], so they
+//!   survive restarts
+//! - re-estimates the fee when a transfer falls due and defers it while
+//!   the fee is above the transfer's cap
+//! - signs and broadcasts through a [`TransferExecutor`] per chain, such as
+//!   a [`WalletExecutor`] over a wallet built with its signer
+//! - retries failed sends with back-off and gives up after a limit
+//! - lets transfers be paused, resumed and cancelled at any time
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_scheduler::{FileStore, Schedule, ScheduledTransfer, Scheduler, WalletExecutor};
+//!
+//! let scheduler = Arc::new(
+//!     Scheduler::new(Arc::new(FileStore::new("schedule.json")))?
+//!         .with_executor("ethereum", Arc::new(WalletExecutor::new(wallet))),
+//! );
+//!
+//! // 0.1 ETH every week, 52 times, never paying more than 0.002 ETH in fees
+//! let id = scheduler.schedule(
+//!     ScheduledTransfer::new("ethereum", exchange, Amount::from_decimal_str("0.1", 18)?,
+//!         Schedule::every(start, 7 * 24 * 3600).times(52))
+//!         .max_fee(Amount::from_decimal_str("0.002", 18)?),
+//! )?;
+//!
+//! scheduler.clone().spawn(Duration::from_secs(30));
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod executor;
+pub mod scheduler;
+pub mod store;
+pub mod transfer;
+
+pub use executor::{TransferExecutor, WalletExecutor};
+pub use scheduler::{RunOutcome, Scheduler};
+pub use store::{FileStore, MemoryStore, ScheduleStore};
+pub use transfer::{RunRecord, Schedule, ScheduledTransfer, TransferStatus};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Scheduling errors
+#[derive(Error, Debug)]
+pub enum SchedulerError {
+    /// No executor is registered for the chain
+    #[error("No executor for chain {0}")]
+    UnknownChain(String),
+
+    /// No transfer has the id
+    #[error("Unknown scheduled transfer {0}")]
+    UnknownTransfer(String),
+
+    /// The schedule can never run or never ends sensibly
+    #[error("Invalid schedule: {0}")]
+    InvalidSchedule(String),
+
+    /// The transfer's status does not allow the change
+    #[error("Invalid state: {0}")]
+    InvalidState(String),
+
+    /// The store holds data this version cannot read
+    #[error("Invalid schedule store: {0}")]
+    InvalidStore(String),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The wallet failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for scheduling
+pub type Result<T> = std::result::Result<T, SchedulerError>;
+
+impl From<SchedulerError> for WalletError {
+    fn from(e: SchedulerError) -> Self {
+        match e {
+            SchedulerError::Wallet(e) => e,
+            SchedulerError::UnknownChain(chain) => WalletError::NotSupported(chain),
+            e => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<SchedulerError> for WalletdError {
+    fn from(e: SchedulerError) -> Self {
+        match e {
+            SchedulerError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            SchedulerError::InvalidSchedule(_) => WalletdError::ConfigError(e.to_string()),
+            SchedulerError::Wallet(e) => WalletdError::BroadcastError(e.to_string()),
+            e => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,568 @@
+//! Running scheduled transfers
+
+use crate::executor::TransferExecutor;
+use crate::store::ScheduleStore;
+use crate::transfer::{RunRecord, ScheduledTransfer, TransferStatus};
+use crate::{Result, SchedulerError};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use walletd_traits::{Amount, TxHash};
+
+/// Failed attempts in a row before a transfer is marked failed
+const DEFAULT_MAX_FAILURES: u32 = 3;
+
+/// Wait before the first retry; doubles with every further failure
+const DEFAULT_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// What happened to one due transfer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunOutcome {
+    /// The transfer was broadcast
+    Sent {
+        /// Transfer id
+        id: String,
+        /// Broadcast transaction
+        tx_hash: TxHash,
+    },
+    /// The fee was above the transfer's cap; it is tried again later
+    Deferred {
+        /// Transfer id
+        id: String,
+        /// Estimated fee
+        fee: Amount,
+        /// Next attempt (Unix epoch seconds)
+        next_run: u64,
+    },
+    /// The attempt failed; it is tried again later
+    Retrying {
+        /// Transfer id
+        id: String,
+        /// Why it failed
+        error: String,
+        /// Next attempt (Unix epoch seconds)
+        next_run: u64,
+    },
+    /// The attempt failed and the transfer gave up
+    Failed {
+        /// Transfer id
+        id: String,
+        /// Why it failed
+        error: String,
+    },
+}
+
+/// Sends delayed and recurring transfers when they fall due
+///
+/// Every change is saved to the [`ScheduleStore`] before it takes effect,
+/// and a transfer is marked in flight while it is being sent. A transfer
+/// still in flight when the scheduler is next created may have been
+/// broadcast, so it is paused rather than sent again; check the chain
+/// before resuming it.
+pub struct Scheduler {
+    executors: HashMap<String, Arc<dyn TransferExecutor>>,
+    store: Arc<dyn ScheduleStore>,
+    transfers: Mutex<BTreeMap<String, ScheduledTransfer>>,
+    running: tokio::sync::Mutex<()>,
+    max_failures: u32,
+    retry_delay: Duration,
+}
+
+impl fmt::Debug for Scheduler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Scheduler")
+            .field("chains", &self.executors.keys().collect::<Vec<_>>())
+            .field("max_failures", &self.max_failures)
+            .field("retry_delay", &self.retry_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Scheduler {
+    /// Loads the transfers in `store`
+    pub fn new(store: Arc<dyn ScheduleStore>) -> Result<Self> {
+        let mut transfers = store.load()?;
+        let mut interrupted = false;
+        for transfer in transfers.values_mut().filter(|t| t.in_flight) {
+            tracing::warn!(id = %transfer.id, "scheduled transfer interrupted while sending");
+            transfer.in_flight = false;
+            transfer.status = TransferStatus::Paused;
+            transfer.record(RunRecord {
+                at: now(),
+                fee: None,
+                tx_hash: None,
+                error: Some("interrupted while sending; check the chain before resuming".into()),
+            });
+            interrupted = true;
+        }
+        if interrupted {
+            store.save(&transfers)?;
+        }
+        Ok(Self {
+            executors: HashMap::new(),
+            store,
+            transfers: Mutex::new(transfers),
+            running: tokio::sync::Mutex::new(()),
+            max_failures: DEFAULT_MAX_FAILURES,
+            retry_delay: DEFAULT_RETRY_DELAY,
+        })
+    }
+
+    /// Sends transfers on `chain` through `executor`
+    pub fn with_executor(
+        mut self,
+        chain: impl Into<String>,
+        executor: Arc<dyn TransferExecutor>,
+    ) -> Self {
+        self.executors.insert(chain.into(), executor);
+        self
+    }
+
+    /// Gives up on a transfer after `max_failures` failed attempts in a row
+    pub fn with_max_failures(mut self, max_failures: u32) -> Self {
+        self.max_failures = max_failures.max(1);
+        self
+    }
+
+    /// Waits `delay` before the first retry, doubling after each failure
+    pub fn with_retry_delay(mut self, delay: Duration) -> Self {
+        self.retry_delay = delay;
+        self
+    }
+
+    /// Adds a transfer and returns its id
+    pub fn schedule(&self, mut transfer: ScheduledTransfer) -> Result<String> {
+        transfer.schedule.validate()?;
+        if !self.executors.contains_key(&transfer.chain) {
+            return Err(SchedulerError::UnknownChain(transfer.chain));
+        }
+        let id = format!("{:016x}", rand::random::<u64>());
+        transfer.id = id.clone();
+        transfer.status = TransferStatus::Active;
+        transfer.next_run = Some(transfer.schedule.first());
+        transfer.runs = 0;
+        transfer.failures = 0;
+        transfer.in_flight = false;
+        transfer.history.clear();
+        self.update(|transfers| {
+            transfers.insert(id.clone(), transfer);
+            Ok(())
+        })?;
+        tracing::debug!(%id, "scheduled transfer");
+        Ok(id)
+    }
+
+    /// Cancels a transfer; a send already in flight still completes
+    pub fn cancel(&self, id: &str) -> Result<()> {
+        self.transition(id, |transfer| match transfer.status {
+            TransferStatus::Active | TransferStatus::Paused => {
+                transfer.status = TransferStatus::Cancelled;
+                transfer.next_run = None;
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// Holds an active transfer until [`resume`](Self::resume)
+    pub fn pause(&self, id: &str) -> Result<()> {
+        self.transition(id, |transfer| {
+            let active = transfer.status == TransferStatus::Active;
+            if active {
+                transfer.status = TransferStatus::Paused;
+            }
+            active
+        })
+    }
+
+    /// Reactivates a paused or failed transfer
+    ///
+    /// A run that fell due while paused is sent on the next pass.
+    pub fn resume(&self, id: &str) -> Result<()> {
+        self.transition(id, |transfer| match transfer.status {
+            TransferStatus::Paused | TransferStatus::Failed => {
+                transfer.status = TransferStatus::Active;
+                transfer.failures = 0;
+                transfer.next_run.get_or_insert_with(now);
+                true
+            }
+            _ => false,
+        })
+    }
+
+    /// The transfer with `id`
+    pub fn get(&self, id: &str) -> Option<ScheduledTransfer> {
+        self.lock().get(id).cloned()
+    }
+
+    /// Every transfer, including finished ones
+    pub fn list(&self) -> Vec<ScheduledTransfer> {
+        self.lock().values().cloned().collect()
+    }
+
+    /// Sends every transfer that is due now
+    pub async fn run_due(&self) -> Result<Vec<RunOutcome>> {
+        self.run_due_at(now()).await
+    }
+
+    /// Sends every transfer due at `now` (Unix epoch seconds)
+    ///
+    /// The fee is re-estimated just before each send. Passes never overlap,
+    /// and transfers are sent one at a time in id order.
+    pub async fn run_due_at(&self, now: u64) -> Result<Vec<RunOutcome>> {
+        let _running = self.running.lock().await;
+        let due: Vec<String> = self
+            .lock()
+            .values()
+            .filter(|t| t.status == TransferStatus::Active && t.next_run.is_some_and(|n| n <= now))
+            .map(|t| t.id.clone())
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(due.len());
+        for id in due {
+            // Paused or cancelled while earlier transfers were being sent
+            let Some(transfer) = self.get(&id).filter(|t| t.status == TransferStatus::Active)
+            else {
+                continue;
+            };
+            outcomes.push(self.run_one(transfer, now).await?);
+        }
+        Ok(outcomes)
+    }
+
+    /// Runs due transfers every `interval` on the Tokio runtime
+    pub fn spawn(self: Arc<Self>, interval: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                if let Err(e) = self.run_due().await {
+                    tracing::warn!(error = %e, "scheduled transfer pass failed");
+                }
+            }
+        })
+    }
+
+    async fn run_one(&self, transfer: ScheduledTransfer, now: u64) -> Result<RunOutcome> {
+        let id = transfer.id.clone();
+        let Some(executor) = self.executors.get(&transfer.chain) else {
+            let error = SchedulerError::UnknownChain(transfer.chain.clone()).to_string();
+            return self.fail(&id, None, error, now);
+        };
+
+        let fee = match executor.estimate_fee(&transfer).await {
+            Ok(fee) => fee,
+            Err(e) => return self.fail(&id, None, format!("fee estimate: {}", e), now),
+        };
+        if let Some(max_fee) = transfer.max_fee.filter(|max| fee.value > max.value) {
+            let next_run = now + self.retry_delay.as_secs();
+            self.update(|transfers| {
+                let transfer = entry(transfers, &id)?;
+                transfer.next_run = Some(next_run);
+                transfer.record(RunRecord {
+                    at: now,
+                    fee: Some(fee),
+                    tx_hash: None,
+                    error: Some(format!("fee {} above cap {}", fee.value, max_fee.value)),
+                });
+                Ok(())
+            })?;
+            return Ok(RunOutcome::Deferred { id, fee, next_run });
+        }
+
+        self.update(|transfers| {
+            entry(transfers, &id)?.in_flight = true;
+            Ok(())
+        })?;
+        match executor.execute(&transfer).await {
+            Ok(tx_hash) => {
+                self.update(|transfers| {
+                    let transfer = entry(transfers, &id)?;
+                    transfer.in_flight = false;
+                    transfer.runs += 1;
+                    transfer.failures = 0;
+                    transfer.record(RunRecord {
+                        at: now,
+                        fee: Some(fee),
+                        tx_hash: Some(tx_hash.clone()),
+                        error: None,
+                    });
+                    if transfer.status == TransferStatus::Active {
+                        transfer.next_run = transfer.schedule.next_after(now, transfer.runs);
+                        if transfer.next_run.is_none() {
+                            transfer.status = TransferStatus::Completed;
+                        }
+                    }
+                    Ok(())
+                })?;
+                tracing::info!(%id, tx_hash = %tx_hash, "sent scheduled transfer");
+                Ok(RunOutcome::Sent { id, tx_hash })
+            }
+            Err(e) => self.fail(&id, Some(fee), e.to_string(), now),
+        }
+    }
+
+    fn fail(&self, id: &str, fee: Option<Amount>, error: String, now: u64) -> Result<RunOutcome> {
+        let (max_failures, retry_delay) = (self.max_failures, self.retry_delay);
+        let next_run = self.update(|transfers| {
+            let transfer = entry(transfers, id)?;
+            transfer.in_flight = false;
+            transfer.failures += 1;
+            transfer.record(RunRecord {
+                at: now,
+                fee,
+                tx_hash: None,
+                error: Some(error.clone()),
+            });
+            if transfer.status != TransferStatus::Active {
+                return Ok(None);
+            }
+            if transfer.failures >= max_failures {
+                transfer.status = TransferStatus::Failed;
+                transfer.next_run = None;
+                return Ok(None);
+            }
+            let backoff = retry_delay.as_secs() << (transfer.failures - 1).min(16);
+            transfer.next_run = Some(now + backoff);
+            Ok(transfer.next_run)
+        })?;
+        tracing::warn!(%id, %error, "scheduled transfer failed");
+        let id = id.to_string();
+        Ok(match next_run {
+            Some(next_run) => RunOutcome::Retrying {
+                id,
+                error,
+                next_run,
+            },
+            None => RunOutcome::Failed { id, error },
+        })
+    }
+
+    /// Applies `change` to one transfer; `false` means the transfer's
+    /// status does not allow it
+    fn transition(
+        &self,
+        id: &str,
+        change: impl FnOnce(&mut ScheduledTransfer) -> bool,
+    ) -> Result<()> {
+        self.update(|transfers| {
+            let transfer = entry(transfers, id)?;
+            if change(transfer) {
+                Ok(())
+            } else {
+                Err(SchedulerError::InvalidState(format!(
+                    "transfer {} is {:?}",
+                    id, transfer.status
+                )))
+            }
+        })
+    }
+
+    /// Applies `change` and saves; nothing changes if either fails
+    fn update<R>(
+        &self,
+        change: impl FnOnce(&mut BTreeMap<String, ScheduledTransfer>) -> Result<R>,
+    ) -> Result<R> {
+        let mut transfers = self.lock();
+        let mut updated = transfers.clone();
+        let result = change(&mut updated)?;
+        self.store.save(&updated)?;
+        *transfers = updated;
+        Ok(result)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, ScheduledTransfer>> {
+        self.transfers.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn entry<'a>(
+    transfers: &'a mut BTreeMap<String, ScheduledTransfer>,
+    id: &str,
+) -> Result<&'a mut ScheduledTransfer> {
+    transfers
+        .get_mut(id)
+        .ok_or_else(|| SchedulerError::UnknownTransfer(id.to_string()))
+}
+
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::store::{FileStore, MemoryStore};
+    use crate::transfer::Schedule;
+    use async_trait::async_trait;
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+    use walletd_traits::{WalletError, WalletResult};
+
+    #[derive(Default)]
+    struct MockExecutor {
+        fee: AtomicU64,
+        failing: AtomicU32,
+        sent: AtomicU32,
+    }
+
+    #[async_trait]
+    impl TransferExecutor for MockExecutor {
+        async fn estimate_fee(&self, _transfer: &ScheduledTransfer) -> WalletResult<Amount> {
+            Ok(Amount::from_smallest_unit(
+                self.fee.load(Ordering::SeqCst) as u128,
+                18,
+            ))
+        }
+
+        async fn execute(&self, transfer: &ScheduledTransfer) -> WalletResult<TxHash> {
+            if self.failing.load(Ordering::SeqCst) > 0 {
+                self.failing.fetch_sub(1, Ordering::SeqCst);
+                return Err(WalletError::NetworkError("timeout".into()));
+            }
+            let n = self.sent.fetch_add(1, Ordering::SeqCst);
+            Ok(TxHash::new(format!("{}-{}", transfer.to, n)))
+        }
+    }
+
+    fn eth(value: u128) -> Amount {
+        Amount::from_smallest_unit(value, 18)
+    }
+
+    #[tokio::test]
+    async fn test_recurring_with_fee_cap_and_retries() {
+        let executor = Arc::new(MockExecutor::default());
+        let scheduler = Scheduler::new(Arc::new(MemoryStore::new()))
+            .unwrap()
+            .with_executor("ethereum", executor.clone())
+            .with_retry_delay(Duration::from_secs(10))
+            .with_max_failures(2);
+        let id = scheduler
+            .schedule(
+                ScheduledTransfer::new(
+                    "ethereum",
+                    "0xdca",
+                    eth(100),
+                    Schedule::every(1000, 3600).times(2),
+                )
+                .max_fee(eth(50)),
+            )
+            .unwrap();
+
+        assert!(scheduler.run_due_at(999).await.unwrap().is_empty());
+
+        executor.fee.store(80, Ordering::SeqCst);
+        assert_eq!(
+            scheduler.run_due_at(1000).await.unwrap(),
+            vec![RunOutcome::Deferred {
+                id: id.clone(),
+                fee: eth(80),
+                next_run: 1010
+            }]
+        );
+
+        executor.fee.store(20, Ordering::SeqCst);
+        executor.failing.store(1, Ordering::SeqCst);
+        assert!(matches!(
+            &scheduler.run_due_at(1010).await.unwrap()[..],
+            [RunOutcome::Retrying { next_run: 1020, .. }]
+        ));
+        assert!(matches!(
+            &scheduler.run_due_at(1020).await.unwrap()[..],
+            [RunOutcome::Sent { .. }]
+        ));
+        assert_eq!(scheduler.get(&id).unwrap().next_run, Some(4600));
+
+        scheduler.run_due_at(4600).await.unwrap();
+        let done = scheduler.get(&id).unwrap();
+        assert_eq!(done.status, TransferStatus::Completed);
+        assert_eq!(done.runs, 2);
+        assert_eq!(done.history.len(), 4);
+        assert!(scheduler.cancel(&id).is_err());
+
+        executor.failing.store(2, Ordering::SeqCst);
+        let once = scheduler
+            .schedule(ScheduledTransfer::new(
+                "ethereum",
+                "0xpay",
+                eth(1),
+                Schedule::once(5000),
+            ))
+            .unwrap();
+        scheduler.run_due_at(5000).await.unwrap();
+        assert!(matches!(
+            &scheduler.run_due_at(5010).await.unwrap()[..],
+            [RunOutcome::Failed { .. }]
+        ));
+        assert_eq!(scheduler.get(&once).unwrap().status, TransferStatus::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_pause_and_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = Arc::new(FileStore::new(dir.path().join("schedule.json")));
+        let executor = Arc::new(MockExecutor::default());
+        let scheduler = Scheduler::new(store.clone())
+            .unwrap()
+            .with_executor("ethereum", executor.clone());
+        assert!(matches!(
+            scheduler.schedule(ScheduledTransfer::new(
+                "bitcoin",
+                "bc1q",
+                eth(1),
+                Schedule::once(0)
+            )),
+            Err(SchedulerError::UnknownChain(_))
+        ));
+
+        let paused = scheduler
+            .schedule(ScheduledTransfer::new(
+                "ethereum",
+                "0xa",
+                eth(1),
+                Schedule::once(100),
+            ))
+            .unwrap();
+        let cancelled = scheduler
+            .schedule(ScheduledTransfer::new(
+                "ethereum",
+                "0xb",
+                eth(1),
+                Schedule::once(100),
+            ))
+            .unwrap();
+        scheduler.pause(&paused).unwrap();
+        scheduler.cancel(&cancelled).unwrap();
+        assert!(scheduler.run_due_at(100).await.unwrap().is_empty());
+        assert_eq!(executor.sent.load(Ordering::SeqCst), 0);
+
+        // A send interrupted by a crash is paused on restart
+        let mut transfers = store.load().unwrap();
+        transfers.get_mut(&paused).unwrap().status = TransferStatus::Active;
+        transfers.get_mut(&paused).unwrap().in_flight = true;
+        store.save(&transfers).unwrap();
+        let restarted = Scheduler::new(store.clone())
+            .unwrap()
+            .with_executor("ethereum", executor.clone());
+        let interrupted = restarted.get(&paused).unwrap();
+        assert_eq!(interrupted.status, TransferStatus::Paused);
+        assert!(!interrupted.in_flight);
+        assert_eq!(
+            restarted.get(&cancelled).unwrap().status,
+            TransferStatus::Cancelled
+        );
+
+        restarted.resume(&paused).unwrap();
+        assert!(matches!(
+            &restarted.run_due_at(200).await.unwrap()[..],
+            [RunOutcome::Sent { .. }]
+        ));
+        assert_eq!(
+            store.load().unwrap()[&paused].status,
+            TransferStatus::Completed
+        );
+    }
+}
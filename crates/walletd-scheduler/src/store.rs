@@ -0,0 +1,109 @@
+//! Persistence of scheduled transfers
+
+use crate::transfer::ScheduledTransfer;
+use crate::{Result, SchedulerError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Current store format version
+const FORMAT_VERSION: u32 = 1;
+
+/// Where scheduled transfers are kept between runs
+///
+/// Keys are transfer ids. The scheduler saves after every change, so
+/// implementations should write atomically.
+pub trait ScheduleStore: Send + Sync {
+    /// Loads every transfer
+    fn load(&self) -> Result<BTreeMap<String, ScheduledTransfer>>;
+
+    /// Replaces every transfer
+    fn save(&self, transfers: &BTreeMap<String, ScheduledTransfer>) -> Result<()>;
+}
+
+/// Keeps transfers in memory only
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    transfers: Mutex<BTreeMap<String, ScheduledTransfer>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl ScheduleStore for MemoryStore {
+    fn load(&self) -> Result<BTreeMap<String, ScheduledTransfer>> {
+        Ok(self
+            .transfers
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone())
+    }
+
+    fn save(&self, transfers: &BTreeMap<String, ScheduledTransfer>) -> Result<()> {
+        *self.transfers.lock().unwrap_or_else(|e| e.into_inner()) = transfers.clone();
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    transfers: BTreeMap<String, ScheduledTransfer>,
+}
+
+/// Keeps transfers in a JSON file
+///
+/// Saves write a sibling temporary file and rename it over the target, so a
+/// crash never leaves a truncated file. A missing file loads as empty.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Uses the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl ScheduleStore for FileStore {
+    fn load(&self) -> Result<BTreeMap<String, ScheduledTransfer>> {
+        let json = match std::fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let file: StoreFile = serde_json::from_str(&json)?;
+        if file.version != FORMAT_VERSION {
+            return Err(SchedulerError::InvalidStore(format!(
+                "unsupported version {}",
+                file.version
+            )));
+        }
+        Ok(file.transfers)
+    }
+
+    fn save(&self, transfers: &BTreeMap<String, ScheduledTransfer>) -> Result<()> {
+        use std::io::Write;
+
+        let file = StoreFile {
+            version: FORMAT_VERSION,
+            transfers: transfers.clone(),
+        };
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut out = std::fs::File::create(&tmp)?;
+        out.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+        out.sync_all()?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
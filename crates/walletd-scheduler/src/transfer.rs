@@ -0,0 +1,279 @@
+//! Scheduled transfer intents
+
+use crate::{Result, SchedulerError};
+use serde::{Deserialize, Serialize};
+use walletd_traits::{Amount, TxHash};
+
+/// How many past runs a transfer keeps in its history
+pub(crate) const HISTORY_LIMIT: usize = 50;
+
+/// When a transfer runs
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum Schedule {
+    /// Once, at or after `at` (Unix epoch seconds)
+    Once {
+        /// When to send
+        at: u64,
+    },
+    /// Every `interval` seconds from `start`
+    Every {
+        /// First run (Unix epoch seconds)
+        start: u64,
+        /// Seconds between runs
+        interval: u64,
+        /// Stop after this many successful runs
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        count: Option<u32>,
+        /// Do not run after this time (Unix epoch seconds)
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        until: Option<u64>,
+    },
+}
+
+impl Schedule {
+    /// Runs once at `at`
+    pub fn once(at: u64) -> Self {
+        Schedule::Once { at }
+    }
+
+    /// Runs every `interval` seconds from `start`, with no end
+    pub fn every(start: u64, interval: u64) -> Self {
+        Schedule::Every {
+            start,
+            interval,
+            count: None,
+            until: None,
+        }
+    }
+
+    /// Stops a recurring schedule after `count` runs
+    pub fn times(self, count: u32) -> Self {
+        match self {
+            Schedule::Every {
+                start,
+                interval,
+                until,
+                ..
+            } => Schedule::Every {
+                start,
+                interval,
+                count: Some(count),
+                until,
+            },
+            once => once,
+        }
+    }
+
+    /// Stops a recurring schedule at `until`
+    pub fn until(self, until: u64) -> Self {
+        match self {
+            Schedule::Every {
+                start,
+                interval,
+                count,
+                ..
+            } => Schedule::Every {
+                start,
+                interval,
+                count,
+                until: Some(until),
+            },
+            once => once,
+        }
+    }
+
+    /// First run
+    pub fn first(&self) -> u64 {
+        match self {
+            Schedule::Once { at } => *at,
+            Schedule::Every { start, .. } => *start,
+        }
+    }
+
+    /// The first slot after `now`, given `runs` successful runs so far;
+    /// `None` once the schedule is over
+    ///
+    /// Slots missed while the scheduler was down are skipped rather than
+    /// sent in a burst.
+    pub fn next_after(&self, now: u64, runs: u32) -> Option<u64> {
+        match self {
+            Schedule::Once { .. } => None,
+            Schedule::Every {
+                start,
+                interval,
+                count,
+                until,
+            } => {
+                if count.is_some_and(|count| runs >= count) {
+                    return None;
+                }
+                let next = if now < *start {
+                    *start
+                } else {
+                    start + ((now - start) / interval + 1) * interval
+                };
+                until.is_none_or(|until| next <= until).then_some(next)
+            }
+        }
+    }
+
+    pub(crate) fn validate(&self) -> Result<()> {
+        match self {
+            Schedule::Every { interval: 0, .. } => Err(SchedulerError::InvalidSchedule(
+                "interval must be positive".into(),
+            )),
+            Schedule::Every { count: Some(0), .. } => Err(SchedulerError::InvalidSchedule(
+                "count must be positive".into(),
+            )),
+            Schedule::Every {
+                start,
+                until: Some(until),
+                ..
+            } if until < start => Err(SchedulerError::InvalidSchedule(
+                "ends before it starts".into(),
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Where a transfer is in its life
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferStatus {
+    /// Waiting for its next run
+    Active,
+    /// Held until resumed
+    Paused,
+    /// Cancelled; never runs again
+    Cancelled,
+    /// Every run has been sent
+    Completed,
+    /// Gave up after too many failed attempts
+    Failed,
+}
+
+/// One attempt to send a scheduled transfer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RunRecord {
+    /// When the attempt was made (Unix epoch seconds)
+    pub at: u64,
+    /// Fee estimated just before sending
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee: Option<Amount>,
+    /// Hash of the broadcast transaction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tx_hash: Option<TxHash>,
+    /// Why the attempt did not send
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// A future or recurring transfer
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScheduledTransfer {
+    /// Id assigned by the scheduler
+    #[serde(default)]
+    pub id: String,
+    /// Chain whose executor sends it, e.g. `ethereum`
+    pub chain: String,
+    /// Recipient address
+    pub to: String,
+    /// Amount per run
+    pub amount: Amount,
+    /// When it runs
+    pub schedule: Schedule,
+    /// Skip a run while the estimated fee is above this
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_fee: Option<Amount>,
+    /// Free-form note, e.g. `payroll: alice`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    /// Current status
+    pub status: TransferStatus,
+    /// When it runs next, while active or paused
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub next_run: Option<u64>,
+    /// Successful runs so far
+    #[serde(default)]
+    pub runs: u32,
+    /// Failed attempts since the last successful run
+    #[serde(default)]
+    pub failures: u32,
+    /// Set while a send is in progress; still set after a restart means the
+    /// transaction may or may not have been broadcast
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub in_flight: bool,
+    /// Most recent attempts, oldest first
+    #[serde(default)]
+    pub history: Vec<RunRecord>,
+}
+
+impl ScheduledTransfer {
+    /// Sends `amount` to `to` on `chain` according to `schedule`
+    pub fn new(
+        chain: impl Into<String>,
+        to: impl Into<String>,
+        amount: Amount,
+        schedule: Schedule,
+    ) -> Self {
+        Self {
+            id: String::new(),
+            chain: chain.into(),
+            to: to.into(),
+            amount,
+            next_run: Some(schedule.first()),
+            schedule,
+            max_fee: None,
+            label: None,
+            status: TransferStatus::Active,
+            runs: 0,
+            failures: 0,
+            in_flight: false,
+            history: Vec::new(),
+        }
+    }
+
+    /// Skips runs while the fee is above `max_fee`
+    pub fn max_fee(mut self, max_fee: Amount) -> Self {
+        self.max_fee = Some(max_fee);
+        self
+    }
+
+    /// Sets a note
+    pub fn label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    pub(crate) fn record(&mut self, run: RunRecord) {
+        self.history.push(run);
+        if self.history.len() > HISTORY_LIMIT {
+            self.history.remove(0);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_next_after() {
+        let schedule = Schedule::every(1000, 60).times(3);
+        assert_eq!(schedule.next_after(900, 0), Some(1000));
+        assert_eq!(schedule.next_after(1000, 1), Some(1060));
+        // Missed slots are skipped
+        assert_eq!(schedule.next_after(1250, 1), Some(1300));
+        assert_eq!(schedule.next_after(1060, 3), None);
+
+        let bounded = Schedule::every(1000, 60).until(1100);
+        assert_eq!(bounded.next_after(1000, 1), Some(1060));
+        assert_eq!(bounded.next_after(1060, 2), None);
+        assert_eq!(Schedule::once(1000).next_after(1000, 1), None);
+
+        assert!(Schedule::every(1000, 0).validate().is_err());
+        assert!(Schedule::every(1000, 60).until(999).validate().is_err());
+    }
+}
@@ -0,0 +1,94 @@
+//! # WalletD Sequence
+//!
+//! One place to hand out account sequence numbers on every account-based
+//! chain: EVM nonces, Cosmos SDK sequences, Aptos sequence numbers and TON
+//! seqnos. A [`SequenceManager`]:
+//!
+//! - serializes sends per account, so two tasks never build transactions
+//!   with the same number
+//! - hands out numbers ahead of the chain, so several transactions can be
+//!   pending at once
+//! - recovers when the chain disagrees, using the expected value from the
+//!   node's error where there is one (see [`sequence_mismatch`])
+//! - persists reservations through a [`SequenceStore`], so a restart
+//!   neither reuses a number that may have been broadcast nor leaves a gap
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_sequence::{FileStore, SequenceManager};
+//!
+//! let sequences = SequenceManager::new(Arc::new(FileStore::new("sequences.json")))?
+//!     .with_source("ethereum", Arc::new(evm_nonces))
+//!     .with_source("cosmoshub", Arc::new(cosmos_sequences));
+//!
+//! let hash = sequences
+//!     .send("ethereum", &address, |nonce| async move {
+//!         wallet.transfer_with(tx.clone().nonce(nonce)).await
+//!     })
+//!     .await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod manager;
+pub mod store;
+
+pub use manager::{sequence_mismatch, SequenceLease, SequenceManager, SequenceSource};
+pub use store::{AccountRecord, FileStore, MemoryStore, SequenceStore};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Sequence coordination errors
+#[derive(Error, Debug)]
+pub enum SequenceError {
+    /// No sequence source is registered for the chain
+    #[error("No sequence source for chain {0}")]
+    UnknownChain(String),
+
+    /// The store holds data this version cannot read
+    #[error("Invalid sequence store: {0}")]
+    InvalidStore(String),
+
+    /// IO error
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+
+    /// JSON error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The sequence source or the send failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for sequence coordination
+pub type Result<T> = std::result::Result<T, SequenceError>;
+
+impl From<SequenceError> for WalletError {
+    fn from(e: SequenceError) -> Self {
+        match e {
+            SequenceError::Wallet(e) => e,
+            SequenceError::UnknownChain(chain) => WalletError::NotSupported(chain),
+            e => WalletError::Other(e.to_string()),
+        }
+    }
+}
+
+impl From<SequenceError> for WalletdError {
+    fn from(e: SequenceError) -> Self {
+        match e {
+            SequenceError::UnknownChain(_) => WalletdError::NotSupported(e.to_string()),
+            SequenceError::InvalidStore(reason) => WalletdError::FormatError(reason),
+            SequenceError::Io(e) => WalletdError::IoError(e.to_string()),
+            SequenceError::Json(e) => WalletdError::JsonError(e.to_string()),
+            SequenceError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,474 @@
+//! Per-account sequence leases
+
+use crate::store::{AccountRecord, SequenceStore};
+use crate::{Result, SequenceError};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use tokio::sync::OwnedMutexGuard;
+use walletd_traits::WalletResult;
+
+/// How far local numbering may run ahead of the chain before it is
+/// treated as a gap left by dropped transactions
+const DEFAULT_MAX_GAP: u64 = 64;
+
+/// How many times [`SequenceManager::send`] retries after a mismatch
+const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// Reads the next sequence number an account may use from the chain
+///
+/// EVM sources return the `pending` transaction count, Cosmos sources the
+/// account's `sequence`, Aptos sources `sequence_number` and TON sources
+/// the wallet's `seqno` get-method.
+#[async_trait]
+pub trait SequenceSource: Send + Sync {
+    /// Returns the next sequence number for `address`
+    async fn next_sequence(&self, address: &str) -> WalletResult<u64>;
+}
+
+struct Slot {
+    record: AccountRecord,
+    /// Whether the record was checked against the chain in this process
+    synced: bool,
+}
+
+/// Hands out sequence numbers for accounts on every chain
+///
+/// The first lease for an account in a process reads the chain; later
+/// leases count on from the last committed number, so several
+/// transactions can be pending at once. A lease that is dropped without
+/// [`commit`](SequenceLease::commit) returns its number.
+pub struct SequenceManager {
+    sources: HashMap<String, Arc<dyn SequenceSource>>,
+    store: Arc<dyn SequenceStore>,
+    slots: Mutex<HashMap<String, Arc<tokio::sync::Mutex<Slot>>>>,
+    records: Mutex<BTreeMap<String, AccountRecord>>,
+    max_gap: u64,
+    max_retries: u32,
+}
+
+impl fmt::Debug for SequenceManager {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SequenceManager")
+            .field("chains", &self.sources.keys().collect::<Vec<_>>())
+            .field("max_gap", &self.max_gap)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SequenceManager {
+    /// Creates a manager, loading records from `store`
+    pub fn new(store: Arc<dyn SequenceStore>) -> Result<Self> {
+        let records = store.load()?;
+        Ok(Self {
+            sources: HashMap::new(),
+            store,
+            slots: Mutex::new(HashMap::new()),
+            records: Mutex::new(records),
+            max_gap: DEFAULT_MAX_GAP,
+            max_retries: DEFAULT_MAX_RETRIES,
+        })
+    }
+
+    /// Adds the sequence source for `chain`
+    pub fn with_source(
+        mut self,
+        chain: impl Into<String>,
+        source: Arc<dyn SequenceSource>,
+    ) -> Self {
+        self.sources.insert(chain.into(), source);
+        self
+    }
+
+    /// Sets how far local numbering may run ahead of the chain
+    pub fn with_max_gap(mut self, max_gap: u64) -> Self {
+        self.max_gap = max_gap;
+        self
+    }
+
+    /// Sets how many times [`send`](Self::send) retries after a mismatch
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Waits for the account's turn and leases its next sequence number
+    pub async fn acquire(&self, chain: &str, address: &str) -> Result<SequenceLease<'_>> {
+        let source = self.source(chain)?;
+        let key = account_key(chain, address);
+        let slot = {
+            let mut slots = self.slots.lock().unwrap_or_else(|e| e.into_inner());
+            let record = self.records().get(&key).cloned().unwrap_or_default();
+            slots
+                .entry(key.clone())
+                .or_insert_with(|| {
+                    Arc::new(tokio::sync::Mutex::new(Slot {
+                        record,
+                        synced: false,
+                    }))
+                })
+                .clone()
+        };
+        let mut slot = slot.lock_owned().await;
+
+        // An in-flight number means the last run stopped mid-send
+        if !slot.synced || slot.record.in_flight.is_some() {
+            let chain_next = source.next_sequence(address).await?;
+            let local = slot.record.next;
+            slot.record.next = if local >= chain_next && local - chain_next <= self.max_gap {
+                local
+            } else {
+                if local > chain_next {
+                    tracing::warn!(%key, local, chain_next, "sequence gap, resetting to the chain");
+                }
+                chain_next
+            };
+            slot.synced = true;
+        }
+        slot.record.in_flight = Some(slot.record.next);
+        self.persist(&key, &slot.record)?;
+
+        Ok(SequenceLease {
+            manager: self,
+            key,
+            address: address.to_string(),
+            source,
+            slot: Some(slot),
+        })
+    }
+
+    /// Runs `send` with the account's next sequence number
+    ///
+    /// On success the number is committed. If the error looks like a
+    /// sequence mismatch (see [`sequence_mismatch`]), the number is
+    /// corrected and `send` is called again. Other errors release the
+    /// number; if the send may still have reached the chain, the next
+    /// send's mismatch recovery covers it.
+    pub async fn send<T, F, Fut>(&self, chain: &str, address: &str, mut send: F) -> Result<T>
+    where
+        F: FnMut(u64) -> Fut,
+        Fut: Future<Output = WalletResult<T>>,
+    {
+        let mut lease = self.acquire(chain, address).await?;
+        let mut retries = 0;
+        loop {
+            let error = match send(lease.sequence()).await {
+                Ok(value) => {
+                    lease.commit()?;
+                    return Ok(value);
+                }
+                Err(e) => e,
+            };
+            match sequence_mismatch(&error.to_string()) {
+                Some(expected) if retries < self.max_retries => {
+                    retries += 1;
+                    tracing::debug!(key = %lease.key, error = %error, "sequence mismatch, resyncing");
+                    lease.resync(expected).await?;
+                }
+                _ => {
+                    lease.release()?;
+                    return Err(error.into());
+                }
+            }
+        }
+    }
+
+    /// Returns the next number the account would get, if known
+    pub fn peek(&self, chain: &str, address: &str) -> Option<u64> {
+        self.records()
+            .get(&account_key(chain, address))
+            .map(|record| record.next)
+    }
+
+    /// Makes the next lease for the account read the chain again
+    pub async fn reset(&self, chain: &str, address: &str) {
+        let slot = self
+            .slots
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(&account_key(chain, address))
+            .cloned();
+        if let Some(slot) = slot {
+            slot.lock().await.synced = false;
+        }
+    }
+
+    fn source(&self, chain: &str) -> Result<Arc<dyn SequenceSource>> {
+        self.sources
+            .get(chain)
+            .cloned()
+            .ok_or_else(|| SequenceError::UnknownChain(chain.to_string()))
+    }
+
+    fn persist(&self, key: &str, record: &AccountRecord) -> Result<()> {
+        let mut records = self.records();
+        records.insert(key.to_string(), record.clone());
+        self.store.save(&records)
+    }
+
+    fn records(&self) -> std::sync::MutexGuard<'_, BTreeMap<String, AccountRecord>> {
+        self.records.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// The exclusive right to use one account's next sequence number
+///
+/// Other leases for the account wait until this one is committed,
+/// released or dropped.
+pub struct SequenceLease<'a> {
+    manager: &'a SequenceManager,
+    key: String,
+    address: String,
+    source: Arc<dyn SequenceSource>,
+    slot: Option<OwnedMutexGuard<Slot>>,
+}
+
+impl fmt::Debug for SequenceLease<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SequenceLease")
+            .field("key", &self.key)
+            .field("sequence", &self.sequence())
+            .finish()
+    }
+}
+
+impl SequenceLease<'_> {
+    /// The leased sequence number
+    pub fn sequence(&self) -> u64 {
+        let slot = self.slot.as_ref().expect("lease is live until consumed");
+        slot.record.in_flight.unwrap_or(slot.record.next)
+    }
+
+    /// Records that a transaction with this number was broadcast
+    pub fn commit(mut self) -> Result<()> {
+        let mut slot = self.slot.take().expect("lease is live until consumed");
+        let used = slot.record.in_flight.take().unwrap_or(slot.record.next);
+        slot.record.next = used + 1;
+        self.manager.persist(&self.key, &slot.record)
+    }
+
+    /// Returns the number unused
+    pub fn release(mut self) -> Result<()> {
+        self.finish_unused()
+    }
+
+    /// Replaces the number after the chain rejected it
+    ///
+    /// Uses `expected` when the node said what it wanted, and reads the
+    /// chain otherwise. Returns the new number.
+    pub async fn resync(&mut self, expected: Option<u64>) -> Result<u64> {
+        let next = match expected {
+            Some(next) => next,
+            None => self.source.next_sequence(&self.address).await?,
+        };
+        let slot = self.slot.as_mut().expect("lease is live until consumed");
+        slot.record.next = next;
+        slot.record.in_flight = Some(next);
+        self.manager.persist(&self.key, &slot.record)?;
+        Ok(next)
+    }
+
+    fn finish_unused(&mut self) -> Result<()> {
+        match self.slot.take() {
+            Some(mut slot) => {
+                slot.record.in_flight = None;
+                self.manager.persist(&self.key, &slot.record)
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+impl Drop for SequenceLease<'_> {
+    fn drop(&mut self) {
+        if let Err(e) = self.finish_unused() {
+            tracing::warn!(key = %self.key, error = %e, "failed to persist released sequence");
+        }
+    }
+}
+
+/// Recognizes sequence mismatch errors from nodes
+///
+/// Returns `None` for unrelated errors, `Some(None)` for a mismatch that
+/// does not say which number was expected and `Some(Some(n))` when it does.
+/// Covers geth-style `nonce too low` / `nonce too high`, Cosmos SDK
+/// `account sequence mismatch, expected N, got M`, Aptos
+/// `SEQUENCE_NUMBER_TOO_OLD` / `SEQUENCE_NUMBER_TOO_NEW` and TON wallet
+/// exit code 33.
+pub fn sequence_mismatch(message: &str) -> Option<Option<u64>> {
+    const MARKERS: [&str; 8] = [
+        "nonce too low",
+        "nonce too high",
+        "invalid nonce",
+        "account sequence mismatch",
+        "incorrect account sequence",
+        "sequence_number_too_old",
+        "sequence_number_too_new",
+        "exit code 33",
+    ];
+    let lower = message.to_ascii_lowercase();
+    if !MARKERS.iter().any(|marker| lower.contains(marker)) {
+        return None;
+    }
+    let expected = ["expected ", "next nonce ", "state: "]
+        .iter()
+        .find_map(|marker| number_after(&lower, marker));
+    Some(expected)
+}
+
+fn number_after(text: &str, marker: &str) -> Option<u64> {
+    let start = text.find(marker)? + marker.len();
+    let digits: String = text[start..]
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .collect();
+    digits.parse().ok()
+}
+
+fn account_key(chain: &str, address: &str) -> String {
+    let address = address.trim();
+    if address.starts_with("0x") || address.starts_with("0X") {
+        format!("{}:{}", chain, address.to_ascii_lowercase())
+    } else {
+        format!("{}:{}", chain, address)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::MemoryStore;
+    use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+    use walletd_traits::WalletError;
+
+    struct Chain {
+        next: AtomicU64,
+        reads: AtomicU32,
+    }
+
+    impl Chain {
+        fn new(next: u64) -> Arc<Self> {
+            Arc::new(Self {
+                next: AtomicU64::new(next),
+                reads: AtomicU32::new(0),
+            })
+        }
+    }
+
+    #[async_trait]
+    impl SequenceSource for Chain {
+        async fn next_sequence(&self, _address: &str) -> WalletResult<u64> {
+            self.reads.fetch_add(1, Ordering::SeqCst);
+            Ok(self.next.load(Ordering::SeqCst))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_sends_get_consecutive_numbers() {
+        let chain = Chain::new(5);
+        let manager = Arc::new(
+            SequenceManager::new(Arc::new(MemoryStore::new()))
+                .unwrap()
+                .with_source("ethereum", chain.clone()),
+        );
+
+        let tasks: Vec<_> = (0..10)
+            .map(|_| {
+                let manager = manager.clone();
+                tokio::spawn(async move {
+                    manager
+                        .send("ethereum", "0xABC", |nonce| async move {
+                            tokio::task::yield_now().await;
+                            Ok(nonce)
+                        })
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect();
+        let mut used = Vec::new();
+        for task in tasks {
+            used.push(task.await.unwrap());
+        }
+        used.sort_unstable();
+        assert_eq!(used, (5..15).collect::<Vec<_>>());
+        assert_eq!(chain.reads.load(Ordering::SeqCst), 1);
+        assert_eq!(manager.peek("ethereum", "0xabc"), Some(15));
+
+        // A failed send gives its number back
+        let failed: Result<()> = manager
+            .send("ethereum", "0xabc", |_| async {
+                Err(WalletError::NetworkError("down".into()))
+            })
+            .await;
+        assert!(failed.is_err());
+        assert_eq!(manager.peek("ethereum", "0xabc"), Some(15));
+        assert!(matches!(
+            manager.acquire("solana", "x").await,
+            Err(SequenceError::UnknownChain(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_mismatch_recovery_and_restart() {
+        let store = Arc::new(MemoryStore::new());
+        let chain = Chain::new(5);
+        let manager = SequenceManager::new(store.clone())
+            .unwrap()
+            .with_source("cosmoshub", chain.clone());
+
+        let attempts = AtomicU32::new(0);
+        let used = manager
+            .send("cosmoshub", "cosmos1xyz", |sequence| {
+                let first = attempts.fetch_add(1, Ordering::SeqCst) == 0;
+                async move {
+                    if first {
+                        Err(WalletError::TransactionFailed(
+                            "account sequence mismatch, expected 8, got 5: incorrect account sequence".into(),
+                        ))
+                    } else {
+                        Ok(sequence)
+                    }
+                }
+            })
+            .await
+            .unwrap();
+        assert_eq!(used, 8);
+
+        // Crash while number 9 is in flight
+        let lease = manager.acquire("cosmoshub", "cosmos1xyz").await.unwrap();
+        assert_eq!(lease.sequence(), 9);
+        std::mem::forget(lease);
+        assert_eq!(
+            store.load().unwrap()["cosmoshub:cosmos1xyz"].in_flight,
+            Some(9)
+        );
+
+        // The chain saw it, so the next run moves past it
+        chain.next.store(10, Ordering::SeqCst);
+        let restarted = SequenceManager::new(store)
+            .unwrap()
+            .with_source("cosmoshub", chain);
+        let lease = restarted.acquire("cosmoshub", "cosmos1xyz").await.unwrap();
+        assert_eq!(lease.sequence(), 10);
+    }
+
+    #[test]
+    fn test_sequence_mismatch_messages() {
+        assert_eq!(
+            sequence_mismatch("nonce too low: address 0xabc, tx: 3 state: 5"),
+            Some(Some(5))
+        );
+        assert_eq!(
+            sequence_mismatch("Transaction failed: nonce too high"),
+            Some(None)
+        );
+        assert_eq!(
+            sequence_mismatch("Invalid transaction: SEQUENCE_NUMBER_TOO_OLD"),
+            Some(None)
+        );
+        assert_eq!(sequence_mismatch("insufficient funds for gas"), None);
+    }
+}
@@ -0,0 +1,149 @@
+//! Persistence of account sequences
+
+use crate::{Result, SequenceError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Current store format version
+const FORMAT_VERSION: u32 = 1;
+
+/// What the manager remembers about one account
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountRecord {
+    /// Next number to hand out
+    pub next: u64,
+    /// Number leased but not yet committed or released; after a crash it
+    /// may or may not have been broadcast
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub in_flight: Option<u64>,
+}
+
+/// Where account records are kept between runs
+///
+/// Keys are `chain:address`. The manager saves after every change, so
+/// implementations should write atomically.
+pub trait SequenceStore: Send + Sync {
+    /// Loads every record
+    fn load(&self) -> Result<BTreeMap<String, AccountRecord>>;
+
+    /// Replaces every record
+    fn save(&self, records: &BTreeMap<String, AccountRecord>) -> Result<()>;
+}
+
+/// Keeps records in memory only
+#[derive(Debug, Default)]
+pub struct MemoryStore {
+    records: Mutex<BTreeMap<String, AccountRecord>>,
+}
+
+impl MemoryStore {
+    /// Creates an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl SequenceStore for MemoryStore {
+    fn load(&self) -> Result<BTreeMap<String, AccountRecord>> {
+        Ok(self
+            .records
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone())
+    }
+
+    fn save(&self, records: &BTreeMap<String, AccountRecord>) -> Result<()> {
+        *self.records.lock().unwrap_or_else(|e| e.into_inner()) = records.clone();
+        Ok(())
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct StoreFile {
+    version: u32,
+    accounts: BTreeMap<String, AccountRecord>,
+}
+
+/// Keeps records in a JSON file
+///
+/// Saves write a sibling temporary file and rename it over the target, so a
+/// crash never leaves a truncated file. A missing file loads as empty.
+#[derive(Debug, Clone)]
+pub struct FileStore {
+    path: PathBuf,
+}
+
+impl FileStore {
+    /// Uses the file at `path`
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        Self {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl SequenceStore for FileStore {
+    fn load(&self) -> Result<BTreeMap<String, AccountRecord>> {
+        let json = match std::fs::read_to_string(&self.path) {
+            Ok(json) => json,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(BTreeMap::new()),
+            Err(e) => return Err(e.into()),
+        };
+        let file: StoreFile = serde_json::from_str(&json)?;
+        if file.version != FORMAT_VERSION {
+            return Err(SequenceError::InvalidStore(format!(
+                "unsupported version {}",
+                file.version
+            )));
+        }
+        Ok(file.accounts)
+    }
+
+    fn save(&self, records: &BTreeMap<String, AccountRecord>) -> Result<()> {
+        use std::io::Write;
+
+        let file = StoreFile {
+            version: FORMAT_VERSION,
+            accounts: records.clone(),
+        };
+        let mut tmp = self.path.as_os_str().to_owned();
+        tmp.push(".tmp");
+        let mut out = std::fs::File::create(&tmp)?;
+        out.write_all(serde_json::to_string_pretty(&file)?.as_bytes())?;
+        out.sync_all()?;
+        std::fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_roundtrip() {
+        let dir = tempfile::tempdir().unwrap();
+        let store = FileStore::new(dir.path().join("sequences.json"));
+        assert!(store.load().unwrap().is_empty());
+
+        let mut records = BTreeMap::new();
+        records.insert(
+            "ethereum:0xabc".to_string(),
+            AccountRecord {
+                next: 7,
+                in_flight: Some(6),
+            },
+        );
+        store.save(&records).unwrap();
+        assert_eq!(store.load().unwrap(), records);
+
+        std::fs::write(
+            dir.path().join("sequences.json"),
+            r#"{"version":9,"accounts":{}}"#,
+        )
+        .unwrap();
+        assert!(matches!(store.load(), Err(SequenceError::InvalidStore(_))));
+    }
+}
@@ -0,0 +1,183 @@
+//! Authenticated JSON-RPC over HTTP
+//!
+//! Requests are `POST /` with a JSON-RPC body and an
+//! `Authorization: Bearer <token>` header. Anything else gets `401` or
+//! `405` before reaching the wallet service. The daemon binds the loopback
+//! interface by default; there is no TLS, so put a TLS proxy in front of it
+//! if it has to be reachable from other hosts.
+//!
+//! Only JSON-RPC is served. A gRPC transport would wrap the same
+//! [`WalletService::call`] and is not provided yet.
+
+use crate::service::WalletService;
+use crate::Result;
+use axum::extract::State;
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::post;
+use axum::{Json, Router};
+use serde_json::Value;
+use std::fmt;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use walletd_core::ct_eq;
+use zeroize::Zeroizing;
+
+/// Default listen address
+pub const DEFAULT_ADDR: &str = "127.0.0.1:8645";
+
+/// Listener settings for [`serve`]
+#[derive(Clone)]
+pub struct ServerConfig {
+    token: Zeroizing<String>,
+    addr: SocketAddr,
+}
+
+impl fmt::Debug for ServerConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServerConfig")
+            .field("addr", &self.addr)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ServerConfig {
+    /// Requires `token` as the bearer token, listening on [`DEFAULT_ADDR`]
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: Zeroizing::new(token.into()),
+            addr: DEFAULT_ADDR.parse().expect("valid default address"),
+        }
+    }
+
+    /// Sets the listen address
+    pub fn with_addr(mut self, addr: SocketAddr) -> Self {
+        self.addr = addr;
+        self
+    }
+}
+
+#[derive(Clone)]
+struct AppState {
+    service: Arc<WalletService>,
+    token: Arc<Zeroizing<String>>,
+}
+
+/// Routes for the wallet API, for embedding in another axum app
+///
+/// Fails if `token` is empty.
+pub fn router(service: Arc<WalletService>, token: &str) -> Result<Router> {
+    if token.is_empty() {
+        return Err(crate::ServerError::InvalidParams(
+            "the API token must not be empty".into(),
+        ));
+    }
+    Ok(Router::new().route("/", post(rpc)).with_state(AppState {
+        service,
+        token: Arc::new(Zeroizing::new(token.to_string())),
+    }))
+}
+
+/// Serves the wallet API until the process stops
+pub async fn serve(service: Arc<WalletService>, config: ServerConfig) -> Result<()> {
+    let listener = tokio::net::TcpListener::bind(config.addr).await?;
+    serve_on(listener, service, config).await
+}
+
+/// Serves the wallet API on an already bound listener
+pub async fn serve_on(
+    listener: tokio::net::TcpListener,
+    service: Arc<WalletService>,
+    config: ServerConfig,
+) -> Result<()> {
+    let addr = listener.local_addr()?;
+    let app = router(service, &config.token)?;
+    if !addr.ip().is_loopback() {
+        tracing::warn!("wallet API listening on non-loopback address {}", addr);
+    }
+    tracing::info!("wallet API listening on {}", addr);
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn rpc(State(state): State<AppState>, headers: HeaderMap, body: String) -> Response {
+    let authorized = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|token| ct_eq(token.as_bytes(), state.token.as_bytes()));
+    if !authorized {
+        return StatusCode::UNAUTHORIZED.into_response();
+    }
+    let response = match serde_json::from_str::<Value>(&body) {
+        Ok(request) => state.service.handle(request).await,
+        Err(_) => serde_json::json!({
+            "jsonrpc": "2.0",
+            "id": null,
+            "error": { "code": -32700, "message": "Parse error" },
+        }),
+    };
+    Json(response).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use walletd_keystore::Keystore;
+
+    #[tokio::test]
+    async fn test_bearer_auth() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let service = Arc::new(WalletService::new(Keystore::new()));
+        tokio::spawn(serve_on(listener, service, ServerConfig::new("s3cret")));
+
+        let client = reqwest::Client::new();
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "wallet_list" });
+        for token in [None, Some("wrong")] {
+            let mut builder = client.post(&url).json(&request);
+            if let Some(token) = token {
+                builder = builder.bearer_auth(token);
+            }
+            assert_eq!(builder.send().await.unwrap().status(), 401);
+        }
+
+        let response: Value = client
+            .post(&url)
+            .bearer_auth("s3cret")
+            .json(&request)
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["result"], json!([]));
+
+        let response: Value = client
+            .post(&url)
+            .bearer_auth("s3cret")
+            .body("{")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(response["error"]["code"], -32700);
+    }
+
+    #[tokio::test]
+    async fn test_empty_token_is_rejected() {
+        let service = Arc::new(WalletService::new(Keystore::new()));
+        assert!(matches!(
+            router(service.clone(), ""),
+            Err(crate::ServerError::InvalidParams(_))
+        ));
+        assert!(router(service.clone(), "s3cret").is_ok());
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        assert!(serve_on(listener, service, ServerConfig::new("")).await.is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! # WalletD Server
+//!
+//! Runs WalletD as a local signing daemon, so services in any language can
+//! create wallets, read balances and sign or send transactions over
+//! JSON-RPC 2.0.
+//!
+//! A [`WalletService`] keeps the wallets in a `walletd-keystore` file:
+//! mnemonics and raw keys are encrypted at rest and only decrypted on
+//! `wallet_unlock` (for a limited time) or per call with a password.
+//! [`serve`] exposes it over HTTP on the loopback interface, with every
+//! request authenticated by a bearer token.
+//!
+//! Methods (params are JSON objects):
+//!
+//! | Method | Params |
+//! |--------|--------|
+//! | `wallet_create` | `name`, `password`, `words?` |
+//! | `wallet_import` | `name`, `password`, `mnemonic` or `privateKey` + `scheme` |
+//! | `wallet_list` | |
+//! | `wallet_unlock` | `name`, `password`, `timeoutSecs?` |
+//! | `wallet_lock` | `name` |
+//! | `wallet_address` | `name`, `chain`, `index?` |
+//! | `wallet_balance` | `chain`, `address`, `token?` |
+//! | `wallet_signHash` | `name`, `chain`, `hash`, `index?` |
+//! | `wallet_signMessage` | `name`, `chain`, `message`, `index?` |
+//! | `wallet_signTransaction` | `name`, `tx` or `uri`, `index?` |
+//! | `wallet_send` | `name`, `chain`, `tx` or `uri`, `index?` |
+//!
+//! Signing methods also accept a `password` instead of an unlocked session.
+//! Transactions are EVM only for now; `wallet_send` fills in a missing
+//! chain id, nonce, gas limit and gas price from the chain's provider. A
+//! scanned EIP-681 payment link can be passed as `uri` in place of `tx`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_server::{serve, ServerConfig, WalletService};
+//!
+//! let service = WalletService::open("wallets.json")?.with_pool(pool);
+//! serve(Arc::new(service), ServerConfig::new(token)).await?;
+//! ```
+//!
+//! ```text
+//! curl -H "Authorization: Bearer $TOKEN" localhost:8645 -d \
+//!   '{"jsonrpc":"2.0","id":1,"method":"wallet_address","params":{"name":"ops","chain":"ethereum"}}'
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod http;
+pub mod service;
+
+pub use http::{router, serve, serve_on, ServerConfig};
+pub use service::WalletService;
+pub use walletd_traits::evm;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_hd::HdError;
+use walletd_keystore::KeystoreError;
+use walletd_provider::ProviderError;
+use walletd_traits::evm::EvmError;
+use walletd_traits::WalletError;
+
+/// Wallet service errors
+#[derive(Error, Debug)]
+pub enum ServerError {
+    /// Unknown JSON-RPC method
+    #[error("Method not found: {0}")]
+    MethodNotFound(String),
+
+    /// Missing or malformed parameters
+    #[error("Invalid params: {0}")]
+    InvalidParams(String),
+
+    /// The wallet needs `wallet_unlock` or a password
+    #[error("Wallet {0} is locked")]
+    Locked(String),
+
+    /// The operation is not available for this chain or wallet
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+
+    /// Keystore failure (wrong password, unknown wallet, ...)
+    #[error(transparent)]
+    Keystore(#[from] KeystoreError),
+
+    /// Key derivation failure
+    #[error(transparent)]
+    Hd(#[from] HdError),
+
+    /// RPC call to a chain failed
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+
+    /// Signing failure
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+
+    /// Listener or keystore file I/O failed
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+impl ServerError {
+    /// JSON-RPC error code
+    ///
+    /// Standard codes for protocol errors, `-32001` to `-32006` for
+    /// application errors.
+    pub fn code(&self) -> i64 {
+        match self {
+            ServerError::MethodNotFound(_) => -32601,
+            ServerError::InvalidParams(_) | ServerError::Hd(_) => -32602,
+            ServerError::Locked(_) => -32001,
+            ServerError::Keystore(KeystoreError::WrongPassword) => -32002,
+            ServerError::Keystore(_) => -32003,
+            ServerError::NotSupported(_) => -32004,
+            ServerError::Provider(_) => -32005,
+            ServerError::Wallet(_) => -32006,
+            ServerError::Io(_) => -32603,
+        }
+    }
+}
+
+impl From<EvmError> for ServerError {
+    fn from(e: EvmError) -> Self {
+        match e {
+            EvmError::Invalid(reason) => ServerError::InvalidParams(reason),
+            EvmError::NotSupported(reason) => ServerError::NotSupported(reason),
+            EvmError::Wallet(e) => ServerError::Wallet(e),
+        }
+    }
+}
+
+/// Result type for wallet service operations
+pub type Result<T> = std::result::Result<T, ServerError>;
+
+impl From<ServerError> for WalletdError {
+    fn from(e: ServerError) -> Self {
+        match e {
+            ServerError::Keystore(e) => e.into(),
+            ServerError::Hd(e) => e.into(),
+            ServerError::Provider(e) => e.into(),
+            ServerError::NotSupported(reason) => WalletdError::NotSupported(reason),
+            ServerError::Io(e) => WalletdError::IoError(e.to_string()),
+            e => WalletdError::External {
+                message: e.to_string(),
+            },
+        }
+    }
+}
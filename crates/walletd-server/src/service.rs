@@ -0,0 +1,799 @@
+//! JSON-RPC wallet methods over a keystore
+
+use crate::{evm, Result, ServerError};
+use bip39::Mnemonic;
+use rand::RngCore;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use walletd_hd::{Curve, HdChain, HdManager};
+use walletd_keystore::{Kdf, Keystore, SecretKind};
+use walletd_provider::{ProviderPool, Solana};
+use walletd_traits::{
+    Amount, Ed25519Signer, PaymentRequest, Secp256k1Signer, SignatureScheme, Signer, WalletError,
+};
+use zeroize::Zeroizing;
+
+/// How long `wallet_unlock` keeps a wallet open by default
+const DEFAULT_UNLOCK_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Decrypted key material of an unlocked wallet
+enum Keys {
+    Hd(HdManager),
+    Key(Arc<dyn Signer>),
+}
+
+impl Keys {
+    fn signer(&self, chain: HdChain, index: u32) -> Result<Arc<dyn Signer>> {
+        match self {
+            Keys::Hd(manager) => Ok(Arc::from(manager.signer(chain, index)?)),
+            Keys::Key(signer) => {
+                if index != 0 {
+                    return Err(ServerError::InvalidParams(
+                        "key wallets only have index 0".into(),
+                    ));
+                }
+                let scheme = match chain.curve() {
+                    Curve::Secp256k1 => SignatureScheme::Secp256k1,
+                    Curve::Ed25519 => SignatureScheme::Ed25519,
+                };
+                if signer.scheme() != scheme {
+                    return Err(ServerError::NotSupported(format!(
+                        "{} needs a {} key",
+                        chain, scheme
+                    )));
+                }
+                Ok(Arc::clone(signer))
+            }
+        }
+    }
+
+    fn address(&self, chain: HdChain, index: u32) -> Result<String> {
+        match self {
+            Keys::Hd(manager) => Ok(manager.address(chain, index)?),
+            Keys::Key(_) => {
+                let signer = self.signer(chain, index)?;
+                Ok(walletd_hd::address::encode(chain, &signer.public_key())?)
+            }
+        }
+    }
+}
+
+/// Wallet operations backed by a [`Keystore`]
+///
+/// Every wallet is a keystore account: a mnemonic for `wallet_create` and
+/// mnemonic imports, a raw key for key imports. Secrets are decrypted into
+/// memory by `wallet_unlock` until the timeout passes or `wallet_lock` is
+/// called; a signing call may instead pass the `password` to decrypt for
+/// that call only.
+///
+/// Chain RPC endpoints come from the [`ProviderPool`], looked up by chain
+/// name (`ethereum`, `base`, `solana`, ...).
+pub struct WalletService {
+    keystore: Arc<Mutex<Keystore>>,
+    path: Option<PathBuf>,
+    pool: Arc<ProviderPool>,
+    unlock_timeout: Duration,
+    sessions: Mutex<HashMap<String, (Arc<Keys>, Instant)>>,
+}
+
+impl fmt::Debug for WalletService {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalletService")
+            .field("path", &self.path)
+            .field("unlock_timeout", &self.unlock_timeout)
+            .finish_non_exhaustive()
+    }
+}
+
+impl WalletService {
+    /// Serves wallets from an in-memory keystore
+    pub fn new(keystore: Keystore) -> Self {
+        Self {
+            keystore: Arc::new(Mutex::new(keystore)),
+            path: None,
+            pool: Arc::new(ProviderPool::new()),
+            unlock_timeout: DEFAULT_UNLOCK_TIMEOUT,
+            sessions: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Serves wallets from a keystore file, created on the first write
+    ///
+    /// The file is saved after every `wallet_create` and `wallet_import`.
+    pub fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let keystore = if path.exists() {
+            Keystore::load(&path)?
+        } else {
+            Keystore::new()
+        };
+        let mut service = Self::new(keystore);
+        service.path = Some(path);
+        Ok(service)
+    }
+
+    /// Sets the providers used for balances and `wallet_send`
+    pub fn with_pool(mut self, pool: Arc<ProviderPool>) -> Self {
+        self.pool = pool;
+        self
+    }
+
+    /// Sets the key derivation function for wallets added from now on
+    pub fn with_kdf(self, kdf: Kdf) -> Self {
+        {
+            let mut keystore = self.keystore.lock().expect("keystore lock");
+            *keystore = std::mem::take(&mut *keystore).with_kdf(kdf);
+        }
+        self
+    }
+
+    /// Sets the default `wallet_unlock` timeout (5 minutes)
+    pub fn with_unlock_timeout(mut self, timeout: Duration) -> Self {
+        self.unlock_timeout = timeout;
+        self
+    }
+
+    /// Handles a JSON-RPC 2.0 request or batch
+    pub async fn handle(&self, request: Value) -> Value {
+        match request {
+            Value::Array(requests) if !requests.is_empty() => {
+                let mut responses = Vec::with_capacity(requests.len());
+                for request in requests {
+                    responses.push(self.handle_one(request).await);
+                }
+                Value::Array(responses)
+            }
+            request => self.handle_one(request).await,
+        }
+    }
+
+    async fn handle_one(&self, request: Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let Some(method) = request
+            .get("method")
+            .and_then(Value::as_str)
+            .filter(|_| request["jsonrpc"] == "2.0")
+        else {
+            return json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": -32600, "message": "Invalid request" },
+            });
+        };
+        let params = request.get("params").cloned().unwrap_or(json!({}));
+        match self.call(method, params).await {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => {
+                tracing::debug!("{} failed: {}", method, e);
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "error": { "code": e.code(), "message": e.to_string() },
+                })
+            }
+        }
+    }
+
+    /// Runs one method with named params
+    pub async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        if !params.is_object() {
+            return Err(ServerError::InvalidParams(
+                "params must be an object".into(),
+            ));
+        }
+        match method {
+            "wallet_create" => self.create(&params).await,
+            "wallet_import" => self.import(&params).await,
+            "wallet_list" => self.list(),
+            "wallet_unlock" => self.unlock(&params).await,
+            "wallet_lock" => {
+                let name = str_param(&params, "name")?;
+                let locked = self.sessions().remove(name).is_some();
+                Ok(json!({ "name": name, "locked": locked }))
+            }
+            "wallet_address" => {
+                let name = str_param(&params, "name")?;
+                let chain = chain_param(&params)?;
+                let index = index_param(&params)?;
+                let keys = self.keys(name, &params).await?;
+                Ok(json!({
+                    "chain": chain.name(),
+                    "index": index,
+                    "path": chain.path(index),
+                    "address": keys.address(chain, index)?,
+                }))
+            }
+            "wallet_balance" => self.balance(&params).await,
+            "wallet_signHash" => self.sign_hash(&params).await,
+            "wallet_signMessage" => self.sign_message(&params).await,
+            "wallet_signTransaction" => self.sign_transaction(&params, false).await,
+            "wallet_send" => self.sign_transaction(&params, true).await,
+            _ => Err(ServerError::MethodNotFound(method.to_string())),
+        }
+    }
+
+    async fn create(&self, params: &Value) -> Result<Value> {
+        let name = str_param(params, "name")?.to_string();
+        let password = password_param(params)?;
+        let words = match params.get("words") {
+            None | Some(Value::Null) => 24,
+            Some(words) => words
+                .as_u64()
+                .filter(|w| matches!(w, 12 | 15 | 18 | 21 | 24))
+                .ok_or_else(|| {
+                    ServerError::InvalidParams("words must be 12, 15, 18, 21 or 24".into())
+                })?,
+        };
+        let mut entropy = Zeroizing::new(vec![0u8; words as usize / 3 * 4]);
+        rand::rngs::OsRng.fill_bytes(&mut entropy);
+        let phrase = Zeroizing::new(
+            Mnemonic::from_entropy(&entropy)
+                .map_err(|e| ServerError::InvalidParams(e.to_string()))?
+                .to_string(),
+        );
+        self.write_keystore(move |keystore| {
+            Ok(keystore.insert_mnemonic(&name, &phrase, &password)?)
+        })
+        .await?;
+        Ok(json!({ "name": str_param(params, "name")?, "kind": "mnemonic", "words": words }))
+    }
+
+    async fn import(&self, params: &Value) -> Result<Value> {
+        let name = str_param(params, "name")?.to_string();
+        let password = password_param(params)?;
+        let (kind, secret) = match (
+            params.get("mnemonic").and_then(Value::as_str),
+            params.get("privateKey").and_then(Value::as_str),
+        ) {
+            (Some(phrase), None) => {
+                let phrase = Mnemonic::parse_normalized(phrase.trim())
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                (
+                    SecretKind::Mnemonic,
+                    Zeroizing::new(phrase.to_string().into_bytes()),
+                )
+            }
+            (None, Some(key)) => {
+                let scheme = match params.get("scheme").and_then(Value::as_str) {
+                    None | Some("secp256k1") => SignatureScheme::Secp256k1,
+                    Some("ed25519") => SignatureScheme::Ed25519,
+                    Some(other) => {
+                        return Err(ServerError::InvalidParams(format!(
+                            "unknown scheme {}",
+                            other
+                        )))
+                    }
+                };
+                let key = Zeroizing::new(decode_hex(key, "privateKey")?);
+                (SecretKind::Key { scheme }, key)
+            }
+            _ => {
+                return Err(ServerError::InvalidParams(
+                    "pass either mnemonic or privateKey".into(),
+                ))
+            }
+        };
+        self.write_keystore(move |keystore| {
+            Ok(keystore.insert(&name, kind, &secret, &password, None)?)
+        })
+        .await?;
+        Ok(json!({ "name": str_param(params, "name")?, "kind": kind }))
+    }
+
+    fn list(&self) -> Result<Value> {
+        let sessions = self.sessions();
+        let keystore = self.keystore.lock().expect("keystore lock");
+        Ok(keystore
+            .accounts()
+            .map(|(name, account)| {
+                json!({
+                    "name": name,
+                    "kind": account.kind,
+                    "address": account.address,
+                    "created": account.created,
+                    "unlocked": sessions
+                        .get(name)
+                        .is_some_and(|(_, expires)| *expires > Instant::now()),
+                })
+            })
+            .collect())
+    }
+
+    async fn unlock(&self, params: &Value) -> Result<Value> {
+        let name = str_param(params, "name")?;
+        let password = password_param(params)?;
+        let timeout = match params.get("timeoutSecs").and_then(Value::as_u64) {
+            Some(secs) => Duration::from_secs(secs),
+            None => self.unlock_timeout,
+        };
+        let keys = self.open_keys(name, password).await?;
+        self.sessions()
+            .insert(name.to_string(), (Arc::new(keys), Instant::now() + timeout));
+        Ok(json!({ "name": name, "expiresIn": timeout.as_secs() }))
+    }
+
+    async fn balance(&self, params: &Value) -> Result<Value> {
+        let chain = chain_param(params)?;
+        let address = str_param(params, "address")?;
+        let token = params.get("token").and_then(Value::as_str);
+        let raw = if chain.is_evm() {
+            let evm = self.pool.evm(chain.name())?;
+            match token {
+                Some(token) => evm.erc20_balance(token, address).await?,
+                None => evm.get_balance(address).await?,
+            }
+        } else if chain == HdChain::Solana {
+            let solana = self.pool.chain::<Solana>(chain.name())?;
+            let raw = match token {
+                Some(mint) => solana.get_token_balance(address, mint).await?,
+                None => solana.get_balance(address).await?,
+            };
+            raw as u128
+        } else {
+            return Err(ServerError::NotSupported(format!(
+                "balance lookups on {}",
+                chain
+            )));
+        };
+        let decimals = if chain.is_evm() { 18 } else { 9 };
+        Ok(json!({
+            "chain": chain.name(),
+            "address": address,
+            "token": token,
+            "balance": raw.to_string(),
+            "formatted": token
+                .is_none()
+                .then(|| Amount::from_smallest_unit(raw, decimals).to_string()),
+        }))
+    }
+
+    async fn sign_hash(&self, params: &Value) -> Result<Value> {
+        let (chain, index, signer) = self.signer(params).await?;
+        let hash: [u8; 32] = decode_hex(str_param(params, "hash")?, "hash")?
+            .try_into()
+            .map_err(|_| ServerError::InvalidParams("hash must be 32 bytes".into()))?;
+        let signature = signer.sign_hash(&hash).await?;
+        signed(chain, index, &*signer, &signature)
+    }
+
+    async fn sign_message(&self, params: &Value) -> Result<Value> {
+        let (chain, index, signer) = self.signer(params).await?;
+        let message = str_param(params, "message")?;
+        let bytes = match message.strip_prefix("0x") {
+            Some(_) => decode_hex(message, "message")?,
+            None => message.as_bytes().to_vec(),
+        };
+        let signature = if chain.is_evm() {
+            evm::personal_sign(&*signer, &bytes).await?
+        } else {
+            signer.sign_message(&bytes).await?
+        };
+        signed(chain, index, &*signer, &signature)
+    }
+
+    /// Signs an EVM transaction, filling in and broadcasting it if `send`
+    ///
+    /// The transaction is the `tx` object, or built from an EIP-681 `uri`.
+    async fn sign_transaction(&self, params: &Value, send: bool) -> Result<Value> {
+        let mut params = params.clone();
+        if params.get("chain").is_none() && !send {
+            params["chain"] = json!(HdChain::Ethereum.name());
+        }
+        let (chain, _, signer) = self.signer(&params).await?;
+        if !chain.is_evm() {
+            return Err(ServerError::NotSupported(format!(
+                "transactions on {}",
+                chain
+            )));
+        }
+        let from = evm::address(&*signer)?;
+        let mut tx = match params.get("uri").and_then(Value::as_str) {
+            Some(uri) => {
+                let request = PaymentRequest::parse(uri)
+                    .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                evm::payment_tx(&request)?
+            }
+            None => params
+                .get("tx")
+                .filter(|tx| tx.is_object())
+                .cloned()
+                .ok_or_else(|| ServerError::InvalidParams("missing tx object or uri".into()))?,
+        };
+        if send {
+            self.fill_transaction(chain, &from, &mut tx).await?;
+        }
+        let signed = evm::TxRequest::from_json(&tx)?.sign(&*signer).await?;
+        let raw = format!("0x{}", hex::encode(&signed.raw));
+        let mut result = json!({
+            "from": from,
+            "raw": raw,
+            "hash": format!("0x{}", hex::encode(signed.hash)),
+        });
+        if send {
+            let hash = self
+                .pool
+                .evm(chain.name())?
+                .send_raw_transaction(&raw)
+                .await?;
+            result["hash"] = json!(hash);
+        }
+        Ok(result)
+    }
+
+    /// Sets a missing chain id, nonce, gas limit and gas price from the chain
+    async fn fill_transaction(&self, chain: HdChain, from: &str, tx: &mut Value) -> Result<()> {
+        let evm = self.pool.evm(chain.name())?;
+        if tx.get("chainId").is_none() {
+            tx["chainId"] = json!(evm.chain_id().await?);
+        }
+        if tx.get("nonce").is_none() {
+            tx["nonce"] = json!(evm.get_transaction_count(from).await?);
+        }
+        if tx.get("gas").is_none() && tx.get("gasLimit").is_none() {
+            let mut call = json!({ "from": from });
+            for field in ["to", "value", "data", "input"] {
+                if let Some(value) = tx.get(field) {
+                    call[field] = value.clone();
+                }
+            }
+            let gas: String = evm.rpc_call("eth_estimateGas", vec![call]).await?;
+            tx["gas"] = json!(gas);
+        }
+        if ["gasPrice", "maxFeePerGas", "maxPriorityFeePerGas"]
+            .iter()
+            .all(|field| tx.get(field).is_none())
+        {
+            tx["gasPrice"] = json!(evm.gas_price().await?.to_string());
+        }
+        Ok(())
+    }
+
+    /// The signer for the request's `name`, `chain` and `index`
+    async fn signer(&self, params: &Value) -> Result<(HdChain, u32, Arc<dyn Signer>)> {
+        let name = str_param(params, "name")?;
+        let chain = chain_param(params)?;
+        let index = index_param(params)?;
+        let keys = self.keys(name, params).await?;
+        Ok((chain, index, keys.signer(chain, index)?))
+    }
+
+    /// Keys from the `password` param, or from an unlocked session
+    async fn keys(&self, name: &str, params: &Value) -> Result<Arc<Keys>> {
+        if params.get("password").is_some() {
+            let password = password_param(params)?;
+            return Ok(Arc::new(self.open_keys(name, password).await?));
+        }
+        let mut sessions = self.sessions();
+        match sessions.get(name) {
+            Some((keys, expires)) if *expires > Instant::now() => Ok(Arc::clone(keys)),
+            Some(_) => {
+                sessions.remove(name);
+                Err(ServerError::Locked(name.to_string()))
+            }
+            None => Err(ServerError::Locked(name.to_string())),
+        }
+    }
+
+    /// Decrypts a wallet, off the async executor since the KDF is slow
+    async fn open_keys(&self, name: &str, password: Zeroizing<String>) -> Result<Keys> {
+        let keystore = Arc::clone(&self.keystore);
+        let name = name.to_string();
+        run_blocking(move || {
+            let unlocked = keystore
+                .lock()
+                .expect("keystore lock")
+                .unlock(&name, &password)?;
+            match unlocked.kind {
+                SecretKind::Mnemonic => {
+                    let phrase = std::str::from_utf8(&unlocked.secret)
+                        .map_err(|e| ServerError::InvalidParams(e.to_string()))?;
+                    Ok(Keys::Hd(HdManager::from_mnemonic(phrase, "")?))
+                }
+                SecretKind::Key {
+                    scheme: SignatureScheme::Secp256k1,
+                } => Ok(Keys::Key(Arc::new(Secp256k1Signer::from_slice(
+                    &unlocked.secret,
+                )?))),
+                SecretKind::Key {
+                    scheme: SignatureScheme::Ed25519,
+                } => {
+                    let key: &[u8; 32] = unlocked.secret.as_slice().try_into().map_err(|_| {
+                        WalletError::KeyError("Ed25519 key must be 32 bytes".into())
+                    })?;
+                    Ok(Keys::Key(Arc::new(Ed25519Signer::from_bytes(key))))
+                }
+                kind => Err(ServerError::NotSupported(format!(
+                    "wallets of kind {:?}",
+                    kind
+                ))),
+            }
+        })
+        .await
+    }
+
+    /// Changes the keystore and saves it, off the async executor
+    async fn write_keystore(
+        &self,
+        change: impl FnOnce(&mut Keystore) -> Result<()> + Send + 'static,
+    ) -> Result<()> {
+        let keystore = Arc::clone(&self.keystore);
+        let path = self.path.clone();
+        run_blocking(move || {
+            let mut keystore = keystore.lock().expect("keystore lock");
+            let mut updated = keystore.clone();
+            change(&mut updated)?;
+            if let Some(path) = path {
+                updated.save(path)?;
+            }
+            *keystore = updated;
+            Ok(())
+        })
+        .await
+    }
+
+    fn sessions(&self) -> std::sync::MutexGuard<'_, HashMap<String, (Arc<Keys>, Instant)>> {
+        self.sessions.lock().expect("session lock")
+    }
+}
+
+async fn run_blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| ServerError::Io(std::io::Error::other(e)))?
+}
+
+fn signed(chain: HdChain, index: u32, signer: &dyn Signer, signature: &[u8]) -> Result<Value> {
+    let address = match chain.is_evm() {
+        true => evm::address(signer)?,
+        false => walletd_hd::address::encode(chain, &signer.public_key())?,
+    };
+    Ok(json!({
+        "chain": chain.name(),
+        "index": index,
+        "address": address,
+        "publicKey": format!("0x{}", hex::encode(signer.public_key())),
+        "signature": format!("0x{}", hex::encode(signature)),
+    }))
+}
+
+fn str_param<'a>(params: &'a Value, name: &str) -> Result<&'a str> {
+    params
+        .get(name)
+        .and_then(Value::as_str)
+        .ok_or_else(|| ServerError::InvalidParams(format!("missing {}", name)))
+}
+
+fn password_param(params: &Value) -> Result<Zeroizing<String>> {
+    Ok(Zeroizing::new(str_param(params, "password")?.to_string()))
+}
+
+fn chain_param(params: &Value) -> Result<HdChain> {
+    Ok(str_param(params, "chain")?.parse()?)
+}
+
+fn index_param(params: &Value) -> Result<u32> {
+    match params.get("index") {
+        None | Some(Value::Null) => Ok(0),
+        Some(index) => index
+            .as_u64()
+            .and_then(|i| u32::try_from(i).ok())
+            .ok_or_else(|| ServerError::InvalidParams("invalid index".into())),
+    }
+}
+
+fn decode_hex(value: &str, field: &str) -> Result<Vec<u8>> {
+    hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|_| ServerError::InvalidParams(format!("invalid hex in {}", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_provider::ProviderConfig;
+    use wiremock::matchers::{body_partial_json, method};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const PHRASE: &str = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+    const ADDRESS: &str = "0x9858EfFD232B4033E47d90003D41EC34EcaEda94";
+
+    const FAST_KDF: Kdf = Kdf::Argon2id {
+        m_cost: 64,
+        t_cost: 1,
+        p_cost: 1,
+    };
+
+    fn service() -> WalletService {
+        WalletService::new(Keystore::new()).with_kdf(FAST_KDF)
+    }
+
+    async fn rpc(service: &WalletService, method: &str, params: Value) -> Value {
+        service
+            .handle(json!({ "jsonrpc": "2.0", "id": 7, "method": method, "params": params }))
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_import_unlock_and_sign() {
+        let service = service();
+        let imported = rpc(
+            &service,
+            "wallet_import",
+            json!({ "name": "ops", "password": "pw", "mnemonic": PHRASE }),
+        )
+        .await;
+        assert_eq!(imported["id"], 7);
+        assert_eq!(imported["result"]["kind"]["type"], "mnemonic");
+
+        let locked = rpc(
+            &service,
+            "wallet_address",
+            json!({ "name": "ops", "chain": "eth" }),
+        )
+        .await;
+        assert_eq!(locked["error"]["code"], -32001);
+
+        let address = rpc(
+            &service,
+            "wallet_address",
+            json!({ "name": "ops", "chain": "eth", "password": "pw" }),
+        )
+        .await;
+        assert_eq!(address["result"]["address"], ADDRESS);
+
+        let wrong = rpc(
+            &service,
+            "wallet_unlock",
+            json!({ "name": "ops", "password": "x" }),
+        )
+        .await;
+        assert_eq!(wrong["error"]["code"], -32002);
+        rpc(
+            &service,
+            "wallet_unlock",
+            json!({ "name": "ops", "password": "pw" }),
+        )
+        .await;
+        assert_eq!(
+            service.call("wallet_list", json!({})).await.unwrap()[0]["unlocked"],
+            true
+        );
+
+        let signed = rpc(
+            &service,
+            "wallet_signTransaction",
+            json!({ "name": "ops", "tx": {
+                "chainId": 1, "nonce": 0, "to": ADDRESS, "value": "1000",
+                "gas": 21000, "gasPrice": "0x3b9aca00"
+            }}),
+        )
+        .await;
+        assert_eq!(signed["result"]["from"], ADDRESS);
+        assert!(signed["result"]["raw"]
+            .as_str()
+            .unwrap()
+            .starts_with("0xf8"));
+
+        let message = rpc(
+            &service,
+            "wallet_signMessage",
+            json!({ "name": "ops", "chain": "solana", "message": "hello" }),
+        )
+        .await;
+        assert_eq!(message["result"]["signature"].as_str().unwrap().len(), 130);
+
+        rpc(&service, "wallet_lock", json!({ "name": "ops" })).await;
+        let hash = rpc(
+            &service,
+            "wallet_signHash",
+            json!({ "name": "ops", "chain": "eth", "hash": format!("0x{}", "11".repeat(32)) }),
+        )
+        .await;
+        assert_eq!(hash["error"]["code"], -32001);
+    }
+
+    #[tokio::test]
+    async fn test_create_persists_and_key_import() {
+        let dir = std::env::temp_dir().join(format!("walletd-server-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("wallets.json");
+        let _ = std::fs::remove_file(&path);
+
+        let service = WalletService::open(&path).unwrap().with_kdf(FAST_KDF);
+        service
+            .call(
+                "wallet_import",
+                json!({ "name": "hot", "password": "pw", "privateKey": "11".repeat(32) }),
+            )
+            .await
+            .unwrap();
+        let reopened = WalletService::open(&path).unwrap();
+        let address = reopened
+            .call(
+                "wallet_address",
+                json!({ "name": "hot", "chain": "base", "password": "pw" }),
+            )
+            .await
+            .unwrap();
+        assert!(address["address"].as_str().unwrap().starts_with("0x"));
+
+        let wrong_curve = reopened
+            .call(
+                "wallet_address",
+                json!({ "name": "hot", "chain": "sol", "password": "pw" }),
+            )
+            .await;
+        assert!(matches!(wrong_curve, Err(ServerError::NotSupported(_))));
+        let duplicate = reopened
+            .call(
+                "wallet_create",
+                json!({ "name": "hot", "password": "pw", "words": 12 }),
+            )
+            .await;
+        assert!(matches!(duplicate, Err(ServerError::Keystore(_))));
+        assert!(matches!(
+            reopened.call("wallet_delete", json!({})).await,
+            Err(ServerError::MethodNotFound(_))
+        ));
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_send_fills_transaction() {
+        let server = MockServer::start().await;
+        for (rpc_method, result) in [
+            ("eth_chainId", json!("0x2105")),
+            ("eth_getTransactionCount", json!("0x5")),
+            ("eth_estimateGas", json!("0x5208")),
+            ("eth_gasPrice", json!("0x3b9aca00")),
+            (
+                "eth_sendRawTransaction",
+                json!(format!("0x{}", "ab".repeat(32))),
+            ),
+        ] {
+            Mock::given(method("POST"))
+                .and(body_partial_json(json!({ "method": rpc_method })))
+                .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                    "jsonrpc": "2.0", "id": 1, "result": result
+                })))
+                .mount(&server)
+                .await;
+        }
+        let pool = ProviderPool::new();
+        pool.add("base", ProviderConfig::new(server.uri())).unwrap();
+        let service = service().with_pool(Arc::new(pool));
+        service
+            .call(
+                "wallet_import",
+                json!({ "name": "ops", "password": "pw", "mnemonic": PHRASE }),
+            )
+            .await
+            .unwrap();
+
+        let sent = service
+            .call(
+                "wallet_send",
+                json!({ "name": "ops", "chain": "base", "password": "pw",
+                        "tx": { "to": ADDRESS, "value": "1" } }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(sent["hash"], format!("0x{}", "ab".repeat(32)));
+        assert_eq!(sent["from"], ADDRESS);
+
+        let paid = service
+            .call(
+                "wallet_send",
+                json!({ "name": "ops", "chain": "base", "password": "pw",
+                        "uri": format!("ethereum:{}@8453?value=1e15", ADDRESS) }),
+            )
+            .await
+            .unwrap();
+        assert_eq!(paid["hash"], sent["hash"]);
+    }
+}
@@ -0,0 +1,185 @@
+//! Sponsored ephemeral accounts on Sui and Aptos
+//!
+//! A session key is its own account on these chains. It never holds gas:
+//! transactions name a sponsor as gas owner (Sui) or fee payer (Aptos), the
+//! session key signs as sender and the sponsor co-signs. The account only
+//! needs the objects or funds the session works with, and is abandoned when
+//! the session ends.
+
+use crate::key::SessionKey;
+use crate::{Result, SessionError};
+use base64::Engine;
+use blake2::digest::consts::U32;
+use blake2::Blake2b;
+use serde::{Deserialize, Serialize};
+use sha3::{Digest, Sha3_256};
+use walletd_traits::SignatureScheme;
+
+/// Sui intent for transaction data: scope, version, app id
+const SUI_TRANSACTION_INTENT: [u8; 3] = [0, 0, 0];
+/// Sui and Aptos scheme flag for Ed25519
+const ED25519_FLAG: u8 = 0;
+/// Aptos domain separator for transactions with a fee payer
+const APTOS_RAW_TRANSACTION_WITH_DATA: &[u8] = b"APTOS::RawTransactionWithData";
+
+/// Chains with sponsored ephemeral accounts
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EphemeralChain {
+    /// Sui, with sponsored transactions
+    Sui,
+    /// Aptos, with fee payer transactions
+    Aptos,
+}
+
+/// The sender's half of a sponsored transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SponsoredSignature {
+    /// Ephemeral account address
+    pub sender: String,
+    /// Account expected to pay gas
+    pub sponsor: String,
+    /// Ed25519 public key, `0x` hex
+    pub public_key: String,
+    /// Sui: base64 `flag || signature || public key`; Aptos: `0x` hex
+    /// signature
+    pub signature: String,
+}
+
+/// A session key used as a sponsored account
+#[derive(Debug)]
+pub struct EphemeralAccount<'a> {
+    session: &'a SessionKey,
+    chain: EphemeralChain,
+    sponsor: String,
+}
+
+impl<'a> EphemeralAccount<'a> {
+    /// Uses an Ed25519 session key on `chain`, with gas paid by `sponsor`
+    pub fn new(
+        session: &'a SessionKey,
+        chain: EphemeralChain,
+        sponsor: impl Into<String>,
+    ) -> Result<Self> {
+        if session.scheme() != SignatureScheme::Ed25519 {
+            return Err(SessionError::Unsupported(format!(
+                "{:?} ephemeral accounts must be Ed25519",
+                chain
+            )));
+        }
+        Ok(Self {
+            session,
+            chain,
+            sponsor: sponsor.into(),
+        })
+    }
+
+    /// The account's address
+    pub fn address(&self) -> String {
+        let key = self.session.public_key();
+        let hash: [u8; 32] = match self.chain {
+            EphemeralChain::Sui => {
+                let mut hasher = Blake2b::<U32>::new();
+                hasher.update([ED25519_FLAG]);
+                hasher.update(&key);
+                hasher.finalize().into()
+            }
+            EphemeralChain::Aptos => {
+                let mut hasher = Sha3_256::new();
+                hasher.update(&key);
+                hasher.update([ED25519_FLAG]);
+                hasher.finalize().into()
+            }
+        };
+        format!("0x{}", hex::encode(hash))
+    }
+
+    /// Account paying gas
+    pub fn sponsor(&self) -> &str {
+        &self.sponsor
+    }
+
+    /// Signs a sponsored transaction calling `function` on `package`
+    ///
+    /// `transaction` is the BCS-encoded Sui `TransactionData` with the
+    /// sponsor as gas owner, or the BCS-encoded Aptos
+    /// `RawTransactionWithData` of the fee payer variant. `value` is what
+    /// the call moves out of the account, counted against the session's
+    /// limit.
+    pub async fn sign_sponsored(
+        &self,
+        package: &str,
+        function: &str,
+        value: u128,
+        transaction: &[u8],
+    ) -> Result<SponsoredSignature> {
+        self.session.authorize(package, function, value)?;
+        let signer = self.session.signer();
+        let public_key = signer.public_key();
+        let signature = match self.chain {
+            EphemeralChain::Sui => {
+                let mut hasher = Blake2b::<U32>::new();
+                hasher.update(SUI_TRANSACTION_INTENT);
+                hasher.update(transaction);
+                let digest: [u8; 32] = hasher.finalize().into();
+                let mut serialized = vec![ED25519_FLAG];
+                serialized.extend(signer.sign_hash(&digest).await?);
+                serialized.extend_from_slice(&public_key);
+                base64::engine::general_purpose::STANDARD.encode(serialized)
+            }
+            EphemeralChain::Aptos => {
+                let mut message = Sha3_256::digest(APTOS_RAW_TRANSACTION_WITH_DATA).to_vec();
+                message.extend_from_slice(transaction);
+                format!("0x{}", hex::encode(signer.sign_message(&message).await?))
+            }
+        };
+        Ok(SponsoredSignature {
+            sender: self.address(),
+            sponsor: self.sponsor.clone(),
+            public_key: format!("0x{}", hex::encode(public_key)),
+            signature,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::SessionScope;
+    use ed25519_dalek::{Signature, VerifyingKey};
+    use walletd_traits::Ed25519Signer;
+
+    #[tokio::test]
+    async fn test_sponsored_signatures() {
+        let scope = SessionScope::new().target("0x2").method("transfer");
+        let session =
+            SessionKey::from_signer(Box::new(Ed25519Signer::from_bytes(&[7u8; 32])), scope);
+        let key: [u8; 32] = session.public_key().try_into().unwrap();
+        let vk = VerifyingKey::from_bytes(&key).unwrap();
+
+        let sui = EphemeralAccount::new(&session, EphemeralChain::Sui, "0xsponsor").unwrap();
+        let signed = sui
+            .sign_sponsored("0x2", "transfer", 0, b"tx")
+            .await
+            .unwrap();
+        let raw = base64::engine::general_purpose::STANDARD
+            .decode(&signed.signature)
+            .unwrap();
+        assert_eq!((raw.len(), raw[0]), (97, ED25519_FLAG));
+        assert_eq!(&raw[65..], &key);
+        let mut intent = SUI_TRANSACTION_INTENT.to_vec();
+        intent.extend_from_slice(b"tx");
+        let digest = Blake2b::<U32>::digest(&intent);
+        assert!(vk
+            .verify_strict(&digest, &Signature::from_slice(&raw[1..65]).unwrap())
+            .is_ok());
+
+        let aptos = EphemeralAccount::new(&session, EphemeralChain::Aptos, "0xsponsor").unwrap();
+        assert_ne!(aptos.address(), sui.address());
+        assert_eq!(aptos.address().len(), 66);
+        assert!(matches!(
+            aptos.sign_sponsored("0x1", "transfer", 0, b"tx").await,
+            Err(SessionError::TargetNotAllowed(_))
+        ));
+    }
+}
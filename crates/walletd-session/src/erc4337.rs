@@ -0,0 +1,355 @@
+//! Session keys of ERC-4337 smart accounts
+//!
+//! Targets the session key plugin of Alchemy's Modular Account (ERC-6900).
+//! The account owner installs the key once with [`add_session_key`], whose
+//! permission updates mirror the [`SessionScope`](crate::SessionScope):
+//! allowed contracts and selectors, the validity window, a native spend
+//! limit and a gas spend limit. Afterwards the session key alone signs user
+//! operations calling `executeWithSessionKey`.
+
+use crate::key::{ScopedCall, SessionKey};
+use crate::scope::now;
+use crate::{Result, SessionError};
+use sha3::{Digest, Keccak256};
+use walletd_paymaster::UserOperation;
+use walletd_traits::SignatureScheme;
+
+/// `ContractAccessControlType.None`: the key may call any contract
+const ACCESS_CONTROL_NONE: u128 = 2;
+
+/// One call made with a session key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Call {
+    /// Contract called
+    pub target: String,
+    /// Native value sent, in wei
+    pub value: u128,
+    /// Calldata
+    pub data: Vec<u8>,
+}
+
+/// The session key's address
+pub fn session_address(session: &SessionKey) -> Result<String> {
+    if session.scheme() != SignatureScheme::Secp256k1 {
+        return Err(SessionError::Unsupported(
+            "EVM session keys must be secp256k1".into(),
+        ));
+    }
+    let key = secp256k1::PublicKey::from_slice(&session.public_key())
+        .map_err(|e| SessionError::InvalidInput(e.to_string()))?;
+    let hash = keccak(&key.serialize_uncompressed()[1..]);
+    Ok(format!("0x{}", hex::encode(&hash[12..])))
+}
+
+/// The 4-byte selector of a function signature or `0x`-prefixed selector
+pub fn selector(method: &str) -> Result<[u8; 4]> {
+    if let Some(hex_selector) = method.strip_prefix("0x") {
+        let bytes = hex::decode(hex_selector)
+            .map_err(|e| SessionError::InvalidInput(format!("selector {}: {}", method, e)))?;
+        return bytes.try_into().map_err(|_| {
+            SessionError::InvalidInput(format!("selector {} is not 4 bytes", method))
+        });
+    }
+    if !method.contains('(') {
+        return Err(SessionError::InvalidInput(format!(
+            "{} is not a function signature",
+            method
+        )));
+    }
+    let hash = keccak(method.replace(' ', "").as_bytes());
+    Ok([hash[0], hash[1], hash[2], hash[3]])
+}
+
+/// Calldata for the account's `addSessionKey(address,bytes32,bytes[])`
+///
+/// `tag` is an arbitrary label for the key.
+pub fn add_session_key(session: &SessionKey, tag: [u8; 32]) -> Result<Vec<u8>> {
+    let scope = session.scope();
+    let mut updates = Vec::new();
+    if scope.targets.is_empty() {
+        updates.push(call(
+            "setAccessListType(uint8)",
+            &[uint_word(ACCESS_CONTROL_NONE)],
+        ));
+    }
+    for target in &scope.targets {
+        let check_selectors = !scope.methods.is_empty();
+        updates.push(call(
+            "updateAccessListAddressEntry(address,bool,bool)",
+            &[
+                address_word(target)?,
+                uint_word(1),
+                uint_word(check_selectors.into()),
+            ],
+        ));
+        for method in &scope.methods {
+            let mut selector_word = [0u8; 32];
+            selector_word[..4].copy_from_slice(&selector(method)?);
+            updates.push(call(
+                "updateAccessListFunctionEntry(address,bytes4,bool)",
+                &[address_word(target)?, selector_word, uint_word(1)],
+            ));
+        }
+    }
+    if scope.valid_after.is_some() || scope.valid_until.is_some() {
+        updates.push(call(
+            "setTimeRange(uint48,uint48)",
+            &[
+                uint_word(scope.valid_after.unwrap_or_default().into()),
+                uint_word(scope.valid_until.unwrap_or_default().into()),
+            ],
+        ));
+    }
+    if let Some(limit) = scope.value_limit {
+        updates.push(call(
+            "setNativeTokenSpendLimit(uint256,uint48)",
+            &[uint_word(limit), uint_word(0)],
+        ));
+    }
+    if let Some(allowance) = scope.fee_allowance {
+        updates.push(call(
+            "setGasSpendLimit(uint256,uint48)",
+            &[uint_word(allowance), uint_word(0)],
+        ));
+    }
+
+    let mut data = call(
+        "addSessionKey(address,bytes32,bytes[])",
+        &[
+            address_word(&session_address(session)?)?,
+            tag,
+            uint_word(3 * 32),
+        ],
+    );
+    let updates: Vec<Vec<u8>> = updates.iter().map(|u| encode_bytes(u)).collect();
+    data.extend(dynamic_array(&updates));
+    Ok(data)
+}
+
+/// Calldata for the account's `removeSessionKey(address,bytes32)`
+///
+/// `predecessor` is the key before this one in the account's list, as
+/// returned by the plugin's `findPredecessor`.
+pub fn remove_session_key(session: &SessionKey, predecessor: [u8; 32]) -> Result<Vec<u8>> {
+    Ok(call(
+        "removeSessionKey(address,bytes32)",
+        &[address_word(&session_address(session)?)?, predecessor],
+    ))
+}
+
+/// Calldata for the account's
+/// `executeWithSessionKey((address,uint256,bytes)[],address)`
+pub fn execute_with_session_key(session: &SessionKey, calls: &[Call]) -> Result<Vec<u8>> {
+    let mut data = call(
+        "executeWithSessionKey((address,uint256,bytes)[],address)",
+        &[uint_word(2 * 32), address_word(&session_address(session)?)?],
+    );
+    let calls = calls
+        .iter()
+        .map(|c| {
+            let mut tuple = address_word(&c.target)?.to_vec();
+            tuple.extend_from_slice(&uint_word(c.value));
+            tuple.extend_from_slice(&uint_word(3 * 32));
+            tuple.extend(encode_bytes(&c.data));
+            Ok(tuple)
+        })
+        .collect::<Result<Vec<_>>>()?;
+    data.extend(dynamic_array(&calls));
+    Ok(data)
+}
+
+/// Authorizes `calls`, sets them as the operation's calldata and signs it
+/// with the session key
+pub async fn sign_user_operation(
+    session: &SessionKey,
+    op: &mut UserOperation,
+    calls: &[Call],
+    entry_point: &str,
+    chain_id: u64,
+) -> Result<()> {
+    let allowed: Vec<[u8; 4]> = session
+        .scope()
+        .methods
+        .iter()
+        .map(|m| selector(m))
+        .collect::<Result<_>>()?;
+    let methods: Vec<String> = calls
+        .iter()
+        .map(|c| format!("0x{}", hex::encode(c.data.get(..4).unwrap_or_default())))
+        .collect();
+    let scoped: Vec<ScopedCall<'_>> = calls
+        .iter()
+        .zip(&methods)
+        .map(|(c, method)| ScopedCall {
+            target: &c.target,
+            method,
+            method_allowed: allowed.is_empty()
+                || c.data
+                    .get(..4)
+                    .is_some_and(|s| allowed.iter().any(|a| a == s)),
+            value: c.value,
+        })
+        .collect();
+    session.authorize_calls(&scoped, now())?;
+
+    op.call_data = execute_with_session_key(session, calls)?;
+    op.sign(session.signer(), entry_point, chain_id).await?;
+    Ok(())
+}
+
+fn keccak(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
+
+/// A function call with static arguments
+fn call(signature: &str, args: &[[u8; 32]]) -> Vec<u8> {
+    let mut data = keccak(signature.as_bytes())[..4].to_vec();
+    for arg in args {
+        data.extend_from_slice(arg);
+    }
+    data
+}
+
+fn address_word(address: &str) -> Result<[u8; 32]> {
+    let bytes = hex::decode(address.trim_start_matches("0x"))
+        .ok()
+        .filter(|b| b.len() == 20)
+        .ok_or_else(|| SessionError::InvalidInput(format!("invalid address {}", address)))?;
+    let mut word = [0u8; 32];
+    word[12..].copy_from_slice(&bytes);
+    Ok(word)
+}
+
+fn uint_word(value: u128) -> [u8; 32] {
+    let mut word = [0u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// `bytes` tail: length, then the data padded to a word
+fn encode_bytes(data: &[u8]) -> Vec<u8> {
+    let mut out = uint_word(data.len() as u128).to_vec();
+    out.extend_from_slice(data);
+    out.resize(out.len() + (32 - data.len() % 32) % 32, 0);
+    out
+}
+
+/// Array of dynamic elements: length, offsets, then the encoded elements
+fn dynamic_array(elements: &[Vec<u8>]) -> Vec<u8> {
+    let mut out = uint_word(elements.len() as u128).to_vec();
+    let mut offset = elements.len() * 32;
+    for element in elements {
+        out.extend_from_slice(&uint_word(offset as u128));
+        offset += element.len();
+    }
+    for element in elements {
+        out.extend_from_slice(element);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::SessionScope;
+    use walletd_traits::Secp256k1Signer;
+
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    fn session(scope: SessionScope) -> SessionKey {
+        SessionKey::from_signer(
+            Box::new(Secp256k1Signer::from_slice(&[1u8; 32]).unwrap()),
+            scope,
+        )
+    }
+
+    #[test]
+    fn test_add_session_key_calldata() {
+        let session = session(
+            SessionScope::new()
+                .target(USDC)
+                .method("transfer(address,uint256)")
+                .valid_until(1_900_000_000)
+                .fee_allowance(10u128.pow(16)),
+        );
+        assert_eq!(
+            session_address(&session).unwrap(),
+            "0x1a642f0e3c3af545e7acbd38b07251b3990914f1"
+        );
+        assert_eq!(
+            selector("transfer(address, uint256)").unwrap(),
+            [0xa9, 0x05, 0x9c, 0xbb]
+        );
+
+        let data = add_session_key(&session, [7u8; 32]).unwrap();
+        assert_eq!(
+            &data[..4],
+            &keccak(b"addSessionKey(address,bytes32,bytes[])")[..4]
+        );
+        assert_eq!(&data[4 + 32..4 + 64], &[7u8; 32]);
+        // access list entry, selector entry, time range, gas limit
+        assert_eq!(data[4 + 96 + 31], 4);
+        let selector_entry = keccak(b"updateAccessListFunctionEntry(address,bytes4,bool)");
+        assert!(data.windows(4).any(|w| w == &selector_entry[..4]));
+        assert!(data
+            .windows(32)
+            .any(|w| w[..4] == [0xa9, 0x05, 0x9c, 0xbb] && w[4..].iter().all(|&b| b == 0)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_user_operation() {
+        let session = session(
+            SessionScope::new()
+                .target(USDC)
+                .method("transfer(address,uint256)")
+                .value_limit(0),
+        );
+        let transfer = Call {
+            target: USDC.into(),
+            value: 0,
+            data: hex::decode("a9059cbb").unwrap(),
+        };
+        let mut op = UserOperation {
+            sender: "0x0000000000000000000000000000000000000abc".into(),
+            ..Default::default()
+        };
+        sign_user_operation(
+            &session,
+            &mut op,
+            std::slice::from_ref(&transfer),
+            walletd_paymaster::ENTRY_POINT_V06,
+            1,
+        )
+        .await
+        .unwrap();
+        assert_eq!(op.signature.len(), 65);
+        assert_eq!(
+            &op.call_data[..4],
+            &keccak(b"executeWithSessionKey((address,uint256,bytes)[],address)")[..4]
+        );
+
+        let approve = Call {
+            data: hex::decode("095ea7b3").unwrap(),
+            ..transfer.clone()
+        };
+        assert!(matches!(
+            sign_user_operation(&session, &mut op, &[transfer.clone(), approve], walletd_paymaster::ENTRY_POINT_V06, 1).await,
+            Err(SessionError::MethodNotAllowed(m)) if m == "0x095ea7b3"
+        ));
+        let paying = Call {
+            value: 1,
+            ..transfer
+        };
+        assert!(matches!(
+            sign_user_operation(
+                &session,
+                &mut op,
+                &[paying],
+                walletd_paymaster::ENTRY_POINT_V06,
+                1
+            )
+            .await,
+            Err(SessionError::LimitExceeded { .. })
+        ));
+    }
+}
@@ -0,0 +1,260 @@
+//! Session keys and local scope enforcement
+
+use crate::scope::{now, SessionScope};
+use crate::{Result, SessionError};
+use rand::RngCore;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use walletd_traits::{Ed25519Signer, Secp256k1Signer, SignatureScheme, Signer};
+use zeroize::Zeroizing;
+
+/// One call checked against a scope
+pub(crate) struct ScopedCall<'a> {
+    pub target: &'a str,
+    pub method: &'a str,
+    pub method_allowed: bool,
+    pub value: u128,
+}
+
+/// A key limited to a [`SessionScope`]
+///
+/// Every signature goes through [`authorize`](Self::authorize) first, which
+/// checks the validity window, target and method and counts value against
+/// the limit. Value is counted when authorized, even if the transaction is
+/// never sent, so the limit errs on the safe side.
+///
+/// The scope is enforced here for every chain; how much of it the chain
+/// itself enforces depends on how the key was registered.
+pub struct SessionKey {
+    signer: Box<dyn Signer>,
+    scope: SessionScope,
+    spent: Mutex<u128>,
+    revoked: AtomicBool,
+}
+
+impl fmt::Debug for SessionKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SessionKey")
+            .field("scheme", &self.signer.scheme())
+            .field("public_key", &hex::encode(self.signer.public_key()))
+            .field("scope", &self.scope)
+            .field("spent", &self.spent())
+            .field("revoked", &self.is_revoked())
+            .finish()
+    }
+}
+
+impl SessionKey {
+    /// Generates a fresh in-memory key
+    pub fn generate(scheme: SignatureScheme, scope: SessionScope) -> Result<Self> {
+        let mut secret = Zeroizing::new([0u8; 32]);
+        rand::thread_rng().fill_bytes(secret.as_mut());
+        let signer: Box<dyn Signer> = match scheme {
+            SignatureScheme::Ed25519 => Box::new(Ed25519Signer::from_bytes(&secret)),
+            SignatureScheme::Secp256k1 => Box::new(Secp256k1Signer::from_slice(secret.as_ref())?),
+            scheme => {
+                return Err(SessionError::Unsupported(format!(
+                    "{} session keys",
+                    scheme
+                )))
+            }
+        };
+        Ok(Self::from_signer(signer, scope))
+    }
+
+    /// Uses an existing key, e.g. one restored from a keystore
+    pub fn from_signer(signer: Box<dyn Signer>, scope: SessionScope) -> Self {
+        Self {
+            signer,
+            scope,
+            spent: Mutex::new(0),
+            revoked: AtomicBool::new(false),
+        }
+    }
+
+    /// Restores the value already spent, after a restart
+    pub fn with_spent(self, spent: u128) -> Self {
+        *self.spent.lock().unwrap_or_else(|e| e.into_inner()) = spent;
+        self
+    }
+
+    /// Signature scheme of the key
+    pub fn scheme(&self) -> SignatureScheme {
+        self.signer.scheme()
+    }
+
+    /// Public key (32 bytes for Ed25519, 33-byte compressed for secp256k1)
+    pub fn public_key(&self) -> Vec<u8> {
+        self.signer.public_key()
+    }
+
+    /// The key's limits
+    pub fn scope(&self) -> &SessionScope {
+        &self.scope
+    }
+
+    /// Value authorized so far
+    pub fn spent(&self) -> u128 {
+        *self.spent.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    /// Whether the validity window has ended
+    pub fn is_expired(&self) -> bool {
+        self.scope.valid_until.is_some_and(|until| now() >= until)
+    }
+
+    /// Stops the key from signing anything else
+    ///
+    /// Only local: remove the key on-chain too, e.g. with
+    /// [`near::FunctionCallKey::delete_key_action`](crate::near::FunctionCallKey::delete_key_action).
+    pub fn revoke(&self) {
+        self.revoked.store(true, Ordering::SeqCst);
+    }
+
+    /// Whether the key was revoked
+    pub fn is_revoked(&self) -> bool {
+        self.revoked.load(Ordering::SeqCst)
+    }
+
+    /// Checks a call against the scope and counts its value
+    pub fn authorize(&self, target: &str, method: &str, value: u128) -> Result<()> {
+        self.authorize_at(target, method, value, now())
+    }
+
+    /// Like [`authorize`](Self::authorize) at Unix time `now`
+    pub fn authorize_at(&self, target: &str, method: &str, value: u128, now: u64) -> Result<()> {
+        self.authorize_calls(
+            &[ScopedCall {
+                target,
+                method,
+                method_allowed: self.scope.allows_method(method),
+                value,
+            }],
+            now,
+        )
+    }
+
+    /// Authorizes a call, then signs a 32-byte digest of it
+    pub async fn sign_hash(
+        &self,
+        target: &str,
+        method: &str,
+        value: u128,
+        hash: &[u8; 32],
+    ) -> Result<Vec<u8>> {
+        self.authorize(target, method, value)?;
+        Ok(self.signer.sign_hash(hash).await?)
+    }
+
+    /// Checks every call before counting any value
+    pub(crate) fn authorize_calls(&self, calls: &[ScopedCall<'_>], now: u64) -> Result<()> {
+        if self.is_revoked() {
+            return Err(SessionError::Revoked);
+        }
+        if let Some(after) = self.scope.valid_after.filter(|&after| now < after) {
+            return Err(SessionError::NotYetValid(after));
+        }
+        if let Some(until) = self.scope.valid_until.filter(|&until| now >= until) {
+            return Err(SessionError::Expired(until));
+        }
+        for call in calls {
+            if !self.scope.allows_target(call.target) {
+                return Err(SessionError::TargetNotAllowed(call.target.to_string()));
+            }
+            if !call.method_allowed {
+                return Err(SessionError::MethodNotAllowed(call.method.to_string()));
+            }
+        }
+        let requested = calls
+            .iter()
+            .try_fold(0u128, |sum, call| sum.checked_add(call.value))
+            .unwrap_or(u128::MAX);
+        let mut spent = self.spent.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(limit) = self.scope.value_limit {
+            let remaining = limit.saturating_sub(*spent);
+            if requested > remaining {
+                return Err(SessionError::LimitExceeded {
+                    requested,
+                    remaining,
+                });
+            }
+        }
+        *spent = spent.saturating_add(requested);
+        Ok(())
+    }
+
+    pub(crate) fn signer(&self) -> &dyn Signer {
+        self.signer.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scope_enforced() {
+        let scope = SessionScope::new()
+            .target("game.near")
+            .method("play")
+            .value_limit(100)
+            .valid_after(1_000)
+            .valid_until(2_000);
+        let session = SessionKey::generate(SignatureScheme::Ed25519, scope).unwrap();
+
+        assert!(matches!(
+            session.authorize_at("game.near", "play", 0, 999),
+            Err(SessionError::NotYetValid(1_000))
+        ));
+        assert!(matches!(
+            session.authorize_at("game.near", "play", 0, 2_000),
+            Err(SessionError::Expired(2_000))
+        ));
+        assert!(matches!(
+            session.authorize_at("bank.near", "play", 0, 1_500),
+            Err(SessionError::TargetNotAllowed(_))
+        ));
+        assert!(matches!(
+            session.authorize_at("game.near", "withdraw", 0, 1_500),
+            Err(SessionError::MethodNotAllowed(_))
+        ));
+
+        session
+            .authorize_at("game.near", "play", 60, 1_500)
+            .unwrap();
+        let err = session
+            .authorize_at("game.near", "play", 60, 1_500)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SessionError::LimitExceeded {
+                requested: 60,
+                remaining: 40
+            }
+        ));
+        assert!(err.is_denied());
+        assert_eq!(session.spent(), 60);
+
+        session.revoke();
+        assert!(matches!(
+            session.authorize_at("game.near", "play", 0, 1_500),
+            Err(SessionError::Revoked)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_sign_hash_checks_scope() {
+        let session = SessionKey::generate(
+            SignatureScheme::Secp256k1,
+            SessionScope::new().valid_until(1),
+        )
+        .unwrap();
+        assert_eq!(session.public_key().len(), 33);
+        assert!(session.is_expired());
+        assert!(matches!(
+            session.sign_hash("0xabc", "f()", 0, &[0u8; 32]).await,
+            Err(SessionError::Expired(1))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+//! # WalletD Session
+//!
+//! Scoped session keys: short-lived keys that an application may use on its
+//! own, limited to certain contracts and methods, a spending limit and a
+//! validity window, so the user's main key signs once instead of on every
+//! action.
+//!
+//! A [`SessionKey`] holds a fresh key and its [`SessionScope`], and refuses
+//! to sign anything outside the scope. Each chain then gets the scope
+//! enforced on-chain as far as it can:
+//!
+//! - [`near`]: function-call access keys, limited to one receiver, a set of
+//!   methods and a fee allowance
+//! - [`erc4337`]: session keys of an ERC-4337 smart account through the
+//!   session key plugin, with call, spend, gas and time limits
+//! - [`ephemeral`]: Sui and Aptos ephemeral accounts whose gas is paid by a
+//!   sponsor
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_session::{near::FunctionCallKey, SessionKey, SessionScope};
+//!
+//! let scope = SessionScope::new()
+//!     .target("game.near")
+//!     .method("play")
+//!     .fee_allowance(250_000_000_000_000_000_000_000)
+//!     .valid_for(Duration::from_secs(3600));
+//! let session = SessionKey::generate(SignatureScheme::Ed25519, scope)?;
+//!
+//! // Signed once by the account's full-access key
+//! let add_key = FunctionCallKey::from_session(&session)?.add_key_action();
+//!
+//! // Later, without the user
+//! let signature = walletd_session::near::sign_transaction(&session, "game.near", "play", 0, &tx).await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod ephemeral;
+pub mod erc4337;
+pub mod key;
+pub mod near;
+pub mod scope;
+
+pub use ephemeral::{EphemeralAccount, EphemeralChain, SponsoredSignature};
+pub use key::SessionKey;
+pub use scope::SessionScope;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_paymaster::PaymasterError;
+use walletd_traits::WalletError;
+
+/// Session key errors
+#[derive(Error, Debug)]
+pub enum SessionError {
+    /// The session has expired
+    #[error("Session expired at {0}")]
+    Expired(u64),
+
+    /// The session is not valid yet
+    #[error("Session not valid before {0}")]
+    NotYetValid(u64),
+
+    /// The session was revoked
+    #[error("Session revoked")]
+    Revoked,
+
+    /// The target is outside the session's scope
+    #[error("Target not allowed: {0}")]
+    TargetNotAllowed(String),
+
+    /// The method is outside the session's scope
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(String),
+
+    /// The call would exceed the session's value limit
+    #[error("Value limit exceeded: {requested} requested, {remaining} remaining")]
+    LimitExceeded {
+        /// Value of the call, in the smallest unit
+        requested: u128,
+        /// Value left in the session
+        remaining: u128,
+    },
+
+    /// The chain cannot express this key or scope
+    #[error("Unsupported: {0}")]
+    Unsupported(String),
+
+    /// Malformed address, selector or other input
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// Building or signing a user operation failed
+    #[error(transparent)]
+    Paymaster(#[from] PaymasterError),
+
+    /// Signer error
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+impl SessionError {
+    /// Whether the session refused the action, rather than failing
+    pub fn is_denied(&self) -> bool {
+        matches!(
+            self,
+            SessionError::Expired(_)
+                | SessionError::NotYetValid(_)
+                | SessionError::Revoked
+                | SessionError::TargetNotAllowed(_)
+                | SessionError::MethodNotAllowed(_)
+                | SessionError::LimitExceeded { .. }
+        )
+    }
+}
+
+/// Result type for session key operations
+pub type Result<T> = std::result::Result<T, SessionError>;
+
+impl From<SessionError> for WalletError {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::Wallet(e) => e,
+            SessionError::Paymaster(e) => e.into(),
+            SessionError::Unsupported(_) => WalletError::NotSupported(e.to_string()),
+            SessionError::InvalidInput(_) => WalletError::Other(e.to_string()),
+            e => WalletError::KeyError(e.to_string()),
+        }
+    }
+}
+
+impl From<SessionError> for WalletdError {
+    fn from(e: SessionError) -> Self {
+        match e {
+            SessionError::Paymaster(e) => e.into(),
+            SessionError::Unsupported(_) => WalletdError::NotSupported(e.to_string()),
+            SessionError::InvalidInput(reason) => WalletdError::FormatError(reason),
+            SessionError::Wallet(_) => WalletdError::SigningError(e.to_string()),
+            e => WalletdError::InvalidState(e.to_string()),
+        }
+    }
+}
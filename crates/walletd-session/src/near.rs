@@ -0,0 +1,229 @@
+//! NEAR function-call access keys
+//!
+//! A function-call key may only call methods of one receiver, cannot attach
+//! a deposit, and pays fees from its allowance. NEAR keys do not expire:
+//! the validity window is enforced by the [`SessionKey`] alone, and the key
+//! should be deleted on-chain when the session ends.
+
+use crate::key::SessionKey;
+use crate::{Result, SessionError};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use walletd_traits::SignatureScheme;
+
+/// Borsh index of `Action::AddKey`
+const ACTION_ADD_KEY: u8 = 5;
+/// Borsh index of `Action::DeleteKey`
+const ACTION_DELETE_KEY: u8 = 6;
+/// Borsh index of `KeyType::ED25519`
+const KEY_TYPE_ED25519: u8 = 0;
+/// Borsh index of `AccessKeyPermission::FunctionCall`
+const PERMISSION_FUNCTION_CALL: u8 = 0;
+
+/// A session key as a NEAR function-call access key
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FunctionCallKey {
+    public_key: [u8; 32],
+    receiver_id: String,
+    method_names: Vec<String>,
+    allowance: Option<u128>,
+}
+
+impl FunctionCallKey {
+    /// Maps a session's scope onto a function-call key
+    ///
+    /// The session needs an Ed25519 key and exactly one target, and cannot
+    /// have a value limit, since function-call keys never attach deposits.
+    /// The fee allowance becomes the key's allowance; without one the key
+    /// may spend fees without limit.
+    pub fn from_session(session: &SessionKey) -> Result<Self> {
+        if session.scheme() != SignatureScheme::Ed25519 {
+            return Err(SessionError::Unsupported(
+                "NEAR access keys must be Ed25519".into(),
+            ));
+        }
+        let scope = session.scope();
+        let [receiver_id] = scope.targets.as_slice() else {
+            return Err(SessionError::Unsupported(
+                "NEAR function-call keys need exactly one receiver".into(),
+            ));
+        };
+        if scope.value_limit.is_some_and(|limit| limit > 0) {
+            return Err(SessionError::Unsupported(
+                "NEAR function-call keys cannot attach deposits".into(),
+            ));
+        }
+        let public_key = session
+            .public_key()
+            .try_into()
+            .map_err(|_| SessionError::InvalidInput("Ed25519 key must be 32 bytes".into()))?;
+        Ok(Self {
+            public_key,
+            receiver_id: receiver_id.clone(),
+            method_names: scope.methods.clone(),
+            allowance: scope.fee_allowance,
+        })
+    }
+
+    /// Public key as `ed25519:<base58>`
+    pub fn public_key(&self) -> String {
+        format!("ed25519:{}", bs58::encode(self.public_key).into_string())
+    }
+
+    /// Contract the key may call
+    pub fn receiver_id(&self) -> &str {
+        &self.receiver_id
+    }
+
+    /// Methods the key may call; empty allows all
+    pub fn method_names(&self) -> &[String] {
+        &self.method_names
+    }
+
+    /// Fee allowance in yoctoNEAR; `None` is unlimited
+    pub fn allowance(&self) -> Option<u128> {
+        self.allowance
+    }
+
+    /// Borsh-encoded `AddKey` action, for a transaction signed by a
+    /// full-access key of the account
+    pub fn add_key_action(&self) -> Vec<u8> {
+        let mut action = vec![ACTION_ADD_KEY, KEY_TYPE_ED25519];
+        action.extend_from_slice(&self.public_key);
+        action.extend_from_slice(&0u64.to_le_bytes());
+        action.push(PERMISSION_FUNCTION_CALL);
+        match self.allowance {
+            Some(allowance) => {
+                action.push(1);
+                action.extend_from_slice(&allowance.to_le_bytes());
+            }
+            None => action.push(0),
+        }
+        borsh_string(&mut action, &self.receiver_id);
+        action.extend_from_slice(&(self.method_names.len() as u32).to_le_bytes());
+        for method in &self.method_names {
+            borsh_string(&mut action, method);
+        }
+        action
+    }
+
+    /// Borsh-encoded `DeleteKey` action ending the session on-chain
+    pub fn delete_key_action(&self) -> Vec<u8> {
+        let mut action = vec![ACTION_DELETE_KEY, KEY_TYPE_ED25519];
+        action.extend_from_slice(&self.public_key);
+        action
+    }
+
+    /// The `AddKey` action as JSON, as shown by NEAR RPC
+    pub fn to_json(&self) -> Value {
+        json!({
+            "AddKey": {
+                "public_key": self.public_key(),
+                "access_key": {
+                    "nonce": 0,
+                    "permission": {
+                        "FunctionCall": {
+                            "allowance": self.allowance.map(|a| a.to_string()),
+                            "receiver_id": self.receiver_id,
+                            "method_names": self.method_names,
+                        }
+                    }
+                }
+            }
+        })
+    }
+}
+
+/// Signs a Borsh-encoded transaction calling `method` on `receiver_id`
+///
+/// NEAR signs the SHA-256 hash of the transaction. Function-call keys
+/// cannot attach a deposit, so `deposit` must be zero.
+pub async fn sign_transaction(
+    session: &SessionKey,
+    receiver_id: &str,
+    method: &str,
+    deposit: u128,
+    transaction: &[u8],
+) -> Result<Vec<u8>> {
+    if deposit != 0 {
+        return Err(SessionError::Unsupported(
+            "NEAR function-call keys cannot attach deposits".into(),
+        ));
+    }
+    let hash: [u8; 32] = Sha256::digest(transaction).into();
+    session.sign_hash(receiver_id, method, 0, &hash).await
+}
+
+fn borsh_string(out: &mut Vec<u8>, s: &str) {
+    out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+    out.extend_from_slice(s.as_bytes());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::scope::SessionScope;
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    #[tokio::test]
+    async fn test_function_call_key() {
+        let scope = SessionScope::new()
+            .target("game.near")
+            .method("play")
+            .fee_allowance(250_000_000_000_000_000_000_000);
+        let session = SessionKey::generate(SignatureScheme::Ed25519, scope).unwrap();
+        let key = FunctionCallKey::from_session(&session).unwrap();
+
+        let action = key.add_key_action();
+        assert_eq!(&action[..2], &[ACTION_ADD_KEY, KEY_TYPE_ED25519]);
+        assert_eq!(&action[2..34], session.public_key().as_slice());
+        assert_eq!(
+            &action[34..43],
+            &[0, 0, 0, 0, 0, 0, 0, 0, PERMISSION_FUNCTION_CALL]
+        );
+        assert_eq!(action[43], 1);
+        assert_eq!(
+            u128::from_le_bytes(action[44..60].try_into().unwrap()),
+            250_000_000_000_000_000_000_000
+        );
+        assert_eq!(&action[60..64], &9u32.to_le_bytes());
+        assert_eq!(&action[64..73], b"game.near");
+        assert_eq!(
+            &action[73..],
+            &[1, 0, 0, 0, 4, 0, 0, 0, b'p', b'l', b'a', b'y']
+        );
+        assert_eq!(key.delete_key_action()[0], ACTION_DELETE_KEY);
+        assert_eq!(
+            key.to_json()["AddKey"]["access_key"]["permission"]["FunctionCall"]["receiver_id"],
+            "game.near"
+        );
+
+        let signature = sign_transaction(&session, "game.near", "play", 0, b"tx")
+            .await
+            .unwrap();
+        let hash: [u8; 32] = Sha256::digest(b"tx").into();
+        let vk = VerifyingKey::from_bytes(&session.public_key().try_into().unwrap()).unwrap();
+        assert!(vk
+            .verify_strict(&hash, &Signature::from_slice(&signature).unwrap())
+            .is_ok());
+        assert!(sign_transaction(&session, "game.near", "play", 1, b"tx")
+            .await
+            .is_err());
+    }
+
+    #[test]
+    fn test_scope_must_fit() {
+        let two = SessionScope::new().target("a.near").target("b.near");
+        let session = SessionKey::generate(SignatureScheme::Ed25519, two).unwrap();
+        assert!(matches!(
+            FunctionCallKey::from_session(&session),
+            Err(SessionError::Unsupported(_))
+        ));
+        let secp = SessionKey::generate(
+            SignatureScheme::Secp256k1,
+            SessionScope::new().target("a.near"),
+        )
+        .unwrap();
+        assert!(FunctionCallKey::from_session(&secp).is_err());
+    }
+}
@@ -0,0 +1,95 @@
+//! What a session key may do
+
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Limits of a session key
+///
+/// Empty `targets` or `methods` allow any. Times are Unix seconds.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionScope {
+    /// Contracts or receivers the key may call
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub targets: Vec<String>,
+    /// Methods the key may call on them
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub methods: Vec<String>,
+    /// Total value the key may move, in the native smallest unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_limit: Option<u128>,
+    /// Total fees the key may spend, in the native smallest unit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fee_allowance: Option<u128>,
+    /// Start of validity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_after: Option<u64>,
+    /// End of validity
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_until: Option<u64>,
+}
+
+impl SessionScope {
+    /// Creates an unrestricted scope
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Allows calls to `target`
+    pub fn target(mut self, target: impl Into<String>) -> Self {
+        self.targets.push(target.into());
+        self
+    }
+
+    /// Allows calls of `method`: a method name, or a function signature such
+    /// as `transfer(address,uint256)` on EVM chains
+    pub fn method(mut self, method: impl Into<String>) -> Self {
+        self.methods.push(method.into());
+        self
+    }
+
+    /// Limits the total value moved
+    pub fn value_limit(mut self, limit: u128) -> Self {
+        self.value_limit = Some(limit);
+        self
+    }
+
+    /// Limits the total fees spent
+    pub fn fee_allowance(mut self, allowance: u128) -> Self {
+        self.fee_allowance = Some(allowance);
+        self
+    }
+
+    /// Starts validity at `timestamp`
+    pub fn valid_after(mut self, timestamp: u64) -> Self {
+        self.valid_after = Some(timestamp);
+        self
+    }
+
+    /// Ends validity at `timestamp`
+    pub fn valid_until(mut self, timestamp: u64) -> Self {
+        self.valid_until = Some(timestamp);
+        self
+    }
+
+    /// Ends validity `duration` from now
+    pub fn valid_for(self, duration: Duration) -> Self {
+        self.valid_until(now().saturating_add(duration.as_secs()))
+    }
+
+    /// Whether `target` is allowed
+    pub fn allows_target(&self, target: &str) -> bool {
+        self.targets.is_empty() || self.targets.iter().any(|t| t.eq_ignore_ascii_case(target))
+    }
+
+    /// Whether `method` is allowed
+    pub fn allows_method(&self, method: &str) -> bool {
+        self.methods.is_empty() || self.methods.iter().any(|m| m == method)
+    }
+}
+
+pub(crate) fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
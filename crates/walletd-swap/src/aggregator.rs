@@ -0,0 +1,308 @@
+//! Aggregator interface and swap types
+//!
+//! Amounts are integers in the token's smallest unit, as the aggregators
+//! take and return them.
+
+use crate::{Result, Slippage, SwapError, NATIVE_TOKEN};
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use walletd_resilience::HttpRetryClassifier;
+
+/// Chain a swap runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum SwapChain {
+    /// EVM chain by chain id
+    Evm(u64),
+    /// Solana mainnet
+    Solana,
+}
+
+impl fmt::Display for SwapChain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SwapChain::Evm(chain_id) => write!(f, "eip155:{}", chain_id),
+            SwapChain::Solana => f.write_str("solana"),
+        }
+    }
+}
+
+/// What to swap
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SwapRequest {
+    /// Chain to swap on
+    pub chain: SwapChain,
+    /// Token sold: contract address, [`NATIVE_TOKEN`] or Solana mint
+    pub sell_token: String,
+    /// Token bought
+    pub buy_token: String,
+    /// Amount sold, smallest unit
+    pub sell_amount: u128,
+    /// Address that sells and receives
+    pub taker: String,
+    /// Accepted slippage
+    pub slippage: Slippage,
+}
+
+impl SwapRequest {
+    /// Swap on an EVM chain
+    pub fn evm(
+        chain_id: u64,
+        sell_token: &str,
+        buy_token: &str,
+        amount: u128,
+        taker: &str,
+    ) -> Self {
+        Self {
+            chain: SwapChain::Evm(chain_id),
+            sell_token: sell_token.to_string(),
+            buy_token: buy_token.to_string(),
+            sell_amount: amount,
+            taker: taker.to_string(),
+            slippage: Slippage::default(),
+        }
+    }
+
+    /// Swap between two Solana mints
+    pub fn solana(input_mint: &str, output_mint: &str, amount: u128, taker: &str) -> Self {
+        Self {
+            chain: SwapChain::Solana,
+            sell_token: input_mint.to_string(),
+            buy_token: output_mint.to_string(),
+            sell_amount: amount,
+            taker: taker.to_string(),
+            slippage: Slippage::default(),
+        }
+    }
+
+    /// Sets the accepted slippage
+    pub fn with_slippage(mut self, slippage: Slippage) -> Self {
+        self.slippage = slippage;
+        self
+    }
+
+    /// Whether the chain's native token is sold, so no approval is needed
+    pub fn sells_native(&self) -> bool {
+        matches!(self.chain, SwapChain::Evm(_))
+            && self.sell_token.eq_ignore_ascii_case(NATIVE_TOKEN)
+    }
+
+    /// Rejects empty amounts, missing addresses and swaps to the same token
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |reason: &str| Err(SwapError::InvalidRequest(reason.to_string()));
+        if self.sell_amount == 0 {
+            return invalid("sell amount is zero");
+        }
+        if self.sell_token.is_empty() || self.buy_token.is_empty() || self.taker.is_empty() {
+            return invalid("tokens and taker are required");
+        }
+        if self.sell_token.eq_ignore_ascii_case(&self.buy_token) {
+            return invalid("sell and buy token are the same");
+        }
+        Ok(())
+    }
+}
+
+/// Allowance the taker has granted the aggregator's spender
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AllowanceCheck {
+    /// Token to approve
+    pub token: String,
+    /// Contract that pulls the sold tokens
+    pub spender: String,
+    /// Current allowance, smallest unit
+    pub current: u128,
+}
+
+/// A priced route from one aggregator
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Quote {
+    /// Aggregator that produced the quote
+    pub aggregator: String,
+    /// Chain of the swap
+    pub chain: SwapChain,
+    /// Token sold
+    pub sell_token: String,
+    /// Token bought
+    pub buy_token: String,
+    /// Amount sold, smallest unit
+    pub sell_amount: u128,
+    /// Expected output, smallest unit
+    pub buy_amount: u128,
+    /// Output guaranteed on-chain after slippage
+    pub min_buy_amount: u128,
+    /// Price impact in percent, when the aggregator reports it
+    pub price_impact: Option<f64>,
+    /// Gas units, for EVM quotes
+    pub gas_estimate: Option<u64>,
+    /// Liquidity sources used, e.g. `Uniswap_V3`
+    pub route: Vec<String>,
+    /// Set when the spender must be approved before swapping
+    pub approval: Option<AllowanceCheck>,
+    /// Aggregator response, kept for building the transaction
+    #[serde(default)]
+    pub(crate) raw: Value,
+}
+
+/// A contract call on an EVM chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmCall {
+    /// Contract address
+    pub to: String,
+    /// Calldata
+    pub data: Vec<u8>,
+    /// Native value sent, wei
+    pub value: u128,
+    /// Gas limit suggested by the aggregator
+    pub gas: Option<u64>,
+}
+
+/// An unsigned swap transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SwapTransaction {
+    /// Call to the aggregator's router
+    Evm(EvmCall),
+    /// Serialized Solana transaction awaiting the taker's signature
+    Solana {
+        /// Transaction bytes with an empty signature slot
+        transaction: Vec<u8>,
+        /// Last block height the transaction's blockhash is valid for
+        last_valid_block_height: Option<u64>,
+    },
+}
+
+/// A DEX aggregator API
+#[async_trait]
+pub trait Aggregator: Send + Sync {
+    /// Short name, e.g. `0x`
+    fn name(&self) -> &str;
+
+    /// Whether the aggregator serves this chain
+    fn supports(&self, chain: SwapChain) -> bool;
+
+    /// Prices a swap, including the approval it needs
+    async fn quote(&self, request: &SwapRequest) -> Result<Quote>;
+
+    /// Builds the transaction for a quote from this aggregator
+    async fn build(&self, request: &SwapRequest, quote: &Quote) -> Result<SwapTransaction>;
+}
+
+/// Sends a request and decodes the JSON body, mapping throttling and errors
+pub(crate) async fn send_json(aggregator: &str, request: reqwest::RequestBuilder) -> Result<Value> {
+    let response = request.send().await?;
+    let status = response.status();
+    if HttpRetryClassifier::is_rate_limited(status.as_u16()) {
+        let retry_after = response
+            .headers()
+            .get("retry-after")
+            .and_then(|value| value.to_str().ok())
+            .and_then(HttpRetryClassifier::parse_retry_after);
+        return Err(SwapError::RateLimited { retry_after });
+    }
+    let body: Value = match response.json().await {
+        Ok(body) => body,
+        Err(_) if !status.is_success() => Value::Null,
+        Err(e) => return Err(e.into()),
+    };
+    if !status.is_success() {
+        let message = ["description", "reason", "message", "error"]
+            .iter()
+            .find_map(|field| body[*field].as_str())
+            .map(str::to_string)
+            .unwrap_or_else(|| format!("HTTP {}", status));
+        return Err(SwapError::Api {
+            aggregator: aggregator.to_string(),
+            message,
+        });
+    }
+    Ok(body)
+}
+
+/// Reads an integer sent as a decimal string or a JSON number
+pub(crate) fn amount(value: &Value, field: &str) -> Result<u128> {
+    match &value[field] {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64().map(u128::from),
+        _ => None,
+    }
+    .ok_or_else(|| SwapError::InvalidResponse(format!("missing or invalid {}", field)))
+}
+
+/// Reads an optional integer sent as a string or number
+pub(crate) fn optional_u64(value: &Value) -> Option<u64> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Number(n) => n.as_u64(),
+        _ => None,
+    }
+}
+
+/// Decodes `0x`-prefixed calldata
+pub(crate) fn calldata(value: &Value) -> Result<Vec<u8>> {
+    value
+        .as_str()
+        .and_then(|s| hex::decode(s.trim_start_matches("0x")).ok())
+        .ok_or_else(|| SwapError::InvalidResponse("missing or invalid calldata".into()))
+}
+
+/// Reads the `{to, data, value, gas}` object both EVM aggregators return
+pub(crate) fn evm_call(tx: &Value) -> Result<EvmCall> {
+    let to = tx["to"]
+        .as_str()
+        .ok_or_else(|| SwapError::InvalidResponse("missing transaction target".into()))?;
+    Ok(EvmCall {
+        to: to.to_string(),
+        data: calldata(&tx["data"])?,
+        value: if tx["value"].is_null() {
+            0
+        } else {
+            amount(tx, "value")?
+        },
+        gas: optional_u64(&tx["gas"]),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_validate() {
+        let request = SwapRequest::evm(
+            1,
+            NATIVE_TOKEN,
+            "0xdac17f958d2ee523a2206206994597c13d831ec7",
+            1,
+            "0xabc",
+        );
+        assert!(request.validate().is_ok());
+        assert!(request.sells_native());
+
+        let mut same = request.clone();
+        same.buy_token = NATIVE_TOKEN.to_lowercase();
+        assert!(same.validate().is_err());
+
+        let mut empty = request;
+        empty.sell_amount = 0;
+        assert!(matches!(
+            empty.validate(),
+            Err(SwapError::InvalidRequest(_))
+        ));
+    }
+
+    #[test]
+    fn test_evm_call() {
+        let call = evm_call(&json!({
+            "to": "0x0000000000001ff3684f28c67538d4d072c22734",
+            "data": "0x2213bc0b",
+            "value": "1000",
+            "gas": "210000"
+        }))
+        .unwrap();
+        assert_eq!(call.data, vec![0x22, 0x13, 0xbc, 0x0b]);
+        assert_eq!((call.value, call.gas), (1000, Some(210_000)));
+        assert!(evm_call(&json!({"data": "0x"})).is_err());
+    }
+}
@@ -0,0 +1,109 @@
+//! ERC-20 approvals for aggregator spenders
+
+use crate::aggregator::{AllowanceCheck, EvmCall};
+use serde::{Deserialize, Serialize};
+
+/// `approve(address,uint256)`
+const APPROVE_SELECTOR: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+
+/// How much allowance to grant when a swap needs more
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ApprovalMode {
+    /// Exactly the amount sold; every swap needs a new approval
+    #[default]
+    Exact,
+    /// The maximum `uint256`; later swaps through the same spender skip it
+    Unlimited,
+}
+
+/// Calldata for `approve(spender, amount)`
+pub fn approve_calldata(spender: &str, amount: Option<u128>) -> Option<Vec<u8>> {
+    let spender = hex::decode(spender.trim_start_matches("0x")).ok()?;
+    if spender.len() != 20 {
+        return None;
+    }
+    let mut data = Vec::with_capacity(68);
+    data.extend_from_slice(&APPROVE_SELECTOR);
+    data.extend_from_slice(&[0u8; 12]);
+    data.extend_from_slice(&spender);
+    match amount {
+        Some(amount) => {
+            data.extend_from_slice(&[0u8; 16]);
+            data.extend_from_slice(&amount.to_be_bytes());
+        }
+        None => data.extend_from_slice(&[0xff; 32]),
+    }
+    Some(data)
+}
+
+/// Approval transactions needed before selling `amount`
+///
+/// Tokens such as USDT revert when a non-zero allowance is changed to
+/// another non-zero value, so an existing allowance is reset to zero first.
+pub fn approval_calls(
+    check: &AllowanceCheck,
+    amount: u128,
+    mode: ApprovalMode,
+) -> Option<Vec<EvmCall>> {
+    if check.current >= amount {
+        return Some(Vec::new());
+    }
+    let call = |data| EvmCall {
+        to: check.token.clone(),
+        data,
+        value: 0,
+        gas: None,
+    };
+    let mut calls = Vec::new();
+    if check.current > 0 {
+        calls.push(call(approve_calldata(&check.spender, Some(0))?));
+    }
+    let granted = match mode {
+        ApprovalMode::Exact => Some(amount),
+        ApprovalMode::Unlimited => None,
+    };
+    calls.push(call(approve_calldata(&check.spender, granted)?));
+    Some(calls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SPENDER: &str = "0x0000000000001fF3684f28c67538d4D072C22734";
+
+    #[test]
+    fn test_approve_calldata() {
+        let data = approve_calldata(SPENDER, Some(1_000_000)).unwrap();
+        assert_eq!(
+            hex::encode(&data),
+            "095ea7b3\
+             0000000000000000000000000000000000001ff3684f28c67538d4d072c22734\
+             00000000000000000000000000000000000000000000000000000000000f4240"
+        );
+        assert_eq!(&approve_calldata(SPENDER, None).unwrap()[36..], &[0xff; 32]);
+        assert!(approve_calldata("0x1234", Some(1)).is_none());
+    }
+
+    #[test]
+    fn test_approval_calls() {
+        let mut check = AllowanceCheck {
+            token: "0xdAC17F958D2ee523a2206206994597C13D831ec7".into(),
+            spender: SPENDER.into(),
+            current: 0,
+        };
+        let calls = approval_calls(&check, 500, ApprovalMode::Exact).unwrap();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].to, check.token);
+
+        check.current = 100;
+        let calls = approval_calls(&check, 500, ApprovalMode::Unlimited).unwrap();
+        assert_eq!(calls.len(), 2);
+        assert_eq!(calls[0].data, approve_calldata(SPENDER, Some(0)).unwrap());
+
+        check.current = 500;
+        assert!(approval_calls(&check, 500, ApprovalMode::Exact)
+            .unwrap()
+            .is_empty());
+    }
+}
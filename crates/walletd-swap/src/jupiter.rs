@@ -0,0 +1,284 @@
+//! Jupiter Swap API (Solana)
+//!
+//! `/quote` prices the route and `/swap` turns the quote into a versioned
+//! transaction with the taker's signature slot left empty. SPL tokens need
+//! no approval; the taker signs the transaction itself.
+
+use crate::aggregator::{self, Aggregator, Quote, SwapChain, SwapRequest, SwapTransaction};
+use crate::{Result, SwapError};
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::json;
+use std::time::Duration;
+use walletd_core::decode_compact_u16;
+use walletd_traits::{SignatureScheme, Signer};
+
+/// Free API
+pub const JUPITER_URL: &str = "https://lite-api.jup.ag/swap/v1";
+/// Paid API, requires a key
+pub const JUPITER_PRO_URL: &str = "https://api.jup.ag/swap/v1";
+
+/// Quotes and transactions from Jupiter
+#[derive(Debug, Clone)]
+pub struct Jupiter {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+}
+
+impl Default for Jupiter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Jupiter {
+    /// Free API without a key
+    pub fn new() -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: JUPITER_URL.to_string(),
+            api_key: None,
+        }
+    }
+
+    /// Paid API with a key
+    pub fn pro(api_key: &str) -> Self {
+        Self {
+            api_key: Some(api_key.to_string()),
+            ..Self::new().with_base_url(JUPITER_PRO_URL)
+        }
+    }
+
+    /// Uses another endpoint (proxy, mock server)
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    fn request(&self, builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        let builder = builder.timeout(Duration::from_secs(15));
+        match &self.api_key {
+            Some(key) => builder.header("x-api-key", key),
+            None => builder,
+        }
+    }
+}
+
+#[async_trait]
+impl Aggregator for Jupiter {
+    fn name(&self) -> &str {
+        "jupiter"
+    }
+
+    fn supports(&self, chain: SwapChain) -> bool {
+        chain == SwapChain::Solana
+    }
+
+    async fn quote(&self, request: &SwapRequest) -> Result<Quote> {
+        if request.chain != SwapChain::Solana {
+            return Err(SwapError::UnsupportedChain(request.chain));
+        }
+        let body = aggregator::send_json(
+            self.name(),
+            self.request(self.client.get(format!("{}/quote", self.base_url)).query(&[
+                ("inputMint", request.sell_token.clone()),
+                ("outputMint", request.buy_token.clone()),
+                ("amount", request.sell_amount.to_string()),
+                ("slippageBps", request.slippage.bps().to_string()),
+                ("swapMode", "ExactIn".to_string()),
+            ])),
+        )
+        .await
+        .map_err(|e| match e {
+            SwapError::Api { ref message, .. } if message.contains("route") => SwapError::NoRoute {
+                sell_token: request.sell_token.clone(),
+                buy_token: request.buy_token.clone(),
+            },
+            e => e,
+        })?;
+
+        Ok(Quote {
+            aggregator: self.name().to_string(),
+            chain: SwapChain::Solana,
+            sell_token: request.sell_token.clone(),
+            buy_token: request.buy_token.clone(),
+            sell_amount: aggregator::amount(&body, "inAmount")?,
+            buy_amount: aggregator::amount(&body, "outAmount")?,
+            min_buy_amount: aggregator::amount(&body, "otherAmountThreshold")?,
+            // Reported as a fraction
+            price_impact: body["priceImpactPct"]
+                .as_str()
+                .and_then(|s| s.parse::<f64>().ok())
+                .map(|fraction| fraction * 100.0),
+            gas_estimate: None,
+            route: body["routePlan"]
+                .as_array()
+                .map(|steps| {
+                    steps
+                        .iter()
+                        .filter_map(|step| Some(step["swapInfo"]["label"].as_str()?.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            approval: None,
+            raw: body,
+        })
+    }
+
+    async fn build(&self, request: &SwapRequest, quote: &Quote) -> Result<SwapTransaction> {
+        let body = aggregator::send_json(
+            self.name(),
+            self.request(
+                self.client
+                    .post(format!("{}/swap", self.base_url))
+                    .json(&json!({
+                        "quoteResponse": quote.raw,
+                        "userPublicKey": request.taker,
+                        "wrapAndUnwrapSol": true,
+                        "dynamicComputeUnitLimit": true,
+                    })),
+            ),
+        )
+        .await?;
+        let transaction = body["swapTransaction"]
+            .as_str()
+            .and_then(|tx| base64::engine::general_purpose::STANDARD.decode(tx).ok())
+            .ok_or_else(|| SwapError::InvalidResponse("missing swapTransaction".into()))?;
+        Ok(SwapTransaction::Solana {
+            transaction,
+            last_valid_block_height: body["lastValidBlockHeight"].as_u64(),
+        })
+    }
+}
+
+/// Fills the signer's slot in a serialized Solana transaction
+///
+/// Works for legacy and versioned messages. The signer must be one of the
+/// message's required signers.
+pub async fn sign_solana_transaction(signer: &dyn Signer, transaction: &[u8]) -> Result<Vec<u8>> {
+    if signer.scheme() != SignatureScheme::Ed25519 {
+        return Err(SwapError::InvalidRequest(
+            "Solana transactions need an Ed25519 signer".into(),
+        ));
+    }
+    let malformed = || SwapError::InvalidResponse("malformed Solana transaction".into());
+
+    let (signatures, prefix) = decode_compact_u16(transaction).ok_or_else(malformed)?;
+    let message = transaction
+        .get(prefix + signatures * 64..)
+        .ok_or_else(malformed)?;
+    // Versioned messages start with 0x80 | version
+    let header = usize::from(message.first().ok_or_else(malformed)? & 0x80 != 0);
+    let required = *message.get(header).ok_or_else(malformed)? as usize;
+    let keys_at = header + 3;
+    let (key_count, key_prefix) =
+        decode_compact_u16(message.get(keys_at..).ok_or_else(malformed)?).ok_or_else(malformed)?;
+    if signatures != required || key_count < required {
+        return Err(malformed());
+    }
+    let keys = message
+        .get(keys_at + key_prefix..keys_at + key_prefix + required * 32)
+        .ok_or_else(malformed)?;
+    let public_key = signer.public_key();
+    let index = keys
+        .chunks(32)
+        .position(|key| key == public_key.as_slice())
+        .ok_or_else(|| SwapError::InvalidRequest("signer is not a required signer".into()))?;
+
+    let signature = signer.sign_message(message).await?;
+    let mut signed = transaction.to_vec();
+    let slot = prefix + index * 64;
+    signed[slot..slot + 64].copy_from_slice(&signature);
+    Ok(signed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const USDC: &str = "EPjFWdd5AufqSSqeM2qM1xzybapC8G4wDGGkZwyTDt1v";
+    const TAKER: &str = "9WzDXwBbmkg8ZTbNMqUxvQRAyrZzDsGYdLVL9zYtAWWM";
+
+    #[tokio::test]
+    async fn test_quote_and_build() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/quote"))
+            .and(query_param("inputMint", crate::WRAPPED_SOL))
+            .and(query_param("slippageBps", "50"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "inputMint": crate::WRAPPED_SOL,
+                "inAmount": "100000000",
+                "outputMint": USDC,
+                "outAmount": "14512345",
+                "otherAmountThreshold": "14439783",
+                "swapMode": "ExactIn",
+                "slippageBps": 50,
+                "priceImpactPct": "0.0012",
+                "routePlan": [{"swapInfo": {"label": "Whirlpool"}, "percent": 100}]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/swap"))
+            .and(body_partial_json(json!({
+                "userPublicKey": TAKER,
+                "quoteResponse": {"outAmount": "14512345"}
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "swapTransaction": "AQID",
+                "lastValidBlockHeight": 279_632_475u64
+            })))
+            .mount(&server)
+            .await;
+
+        let jupiter = Jupiter::new().with_base_url(&server.uri());
+        let request = SwapRequest::solana(crate::WRAPPED_SOL, USDC, 100_000_000, TAKER);
+        let quote = jupiter.quote(&request).await.unwrap();
+        assert_eq!(
+            (quote.buy_amount, quote.min_buy_amount),
+            (14_512_345, 14_439_783)
+        );
+        assert!((quote.price_impact.unwrap() - 0.12).abs() < 1e-9);
+        assert_eq!(quote.route, ["Whirlpool"]);
+        assert!(quote.approval.is_none());
+
+        let tx = jupiter.build(&request, &quote).await.unwrap();
+        assert_eq!(
+            tx,
+            SwapTransaction::Solana {
+                transaction: vec![1, 2, 3],
+                last_valid_block_height: Some(279_632_475)
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction() {
+        let signer = walletd_traits::Ed25519Signer::from_bytes(&[7u8; 32]);
+        let public_key = signer.public_key();
+
+        // One signature slot, then a v0 message: header, one key, blockhash, no instructions
+        let mut message = vec![0x80, 1, 0, 0, 1];
+        message.extend_from_slice(&public_key);
+        message.extend_from_slice(&[9u8; 32]);
+        message.extend_from_slice(&[0, 0]);
+        let mut tx = vec![1];
+        tx.extend_from_slice(&[0u8; 64]);
+        tx.extend_from_slice(&message);
+
+        let signed = sign_solana_transaction(&signer, &tx).await.unwrap();
+        assert_eq!(&signed[65..], message.as_slice());
+        assert_eq!(
+            signed[1..65],
+            signer.sign_message(&message).await.unwrap()[..]
+        );
+
+        let other = walletd_traits::Ed25519Signer::from_bytes(&[8u8; 32]);
+        assert!(sign_solana_transaction(&other, &tx).await.is_err());
+        assert!(sign_solana_transaction(&signer, &tx[..40]).await.is_err());
+    }
+}
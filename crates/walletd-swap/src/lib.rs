@@ -0,0 +1,242 @@
+//! # WalletD Swap
+//!
+//! Token swaps from inside the wallet, routed through DEX aggregators so
+//! apps do not have to integrate each one.
+//!
+//! - [`Aggregator`]s quote a [`SwapRequest`] and build the transaction:
+//!   [`ZeroEx`] and [`OneInch`] on EVM chains, [`Jupiter`] on Solana
+//! - [`Swapper`] asks every aggregator that serves the chain, keeps the
+//!   best quote that passes the [`Slippage`] and price impact limits and
+//!   turns it into a [`SwapPlan`] with any ERC-20 approvals it needs
+//! - [`Swapper::execute_evm`] sends the plan through a walletd EVM wallet;
+//!   Solana plans are signed with [`sign_solana_transaction`]
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_swap::{OneInch, Slippage, SwapRequest, Swapper, ZeroEx, NATIVE_TOKEN};
+//!
+//! let swapper = Swapper::new()
+//!     .with_aggregator(ZeroEx::new(zero_ex_key))
+//!     .with_aggregator(OneInch::new(one_inch_key));
+//!
+//! let request = SwapRequest::evm(1, USDC, NATIVE_TOKEN, 1_000_000_000, &address)
+//!     .with_slippage(Slippage::from_bps(30)?);
+//! let plan = swapper.plan(&request).await?;
+//! println!("{} via {}", plan.quote.buy_amount, plan.quote.aggregator);
+//! let hashes = swapper.execute_evm(&wallet, &plan).await?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod aggregator;
+pub mod approval;
+pub mod jupiter;
+pub mod oneinch;
+pub mod swapper;
+pub mod zerox;
+
+pub use aggregator::{
+    Aggregator, AllowanceCheck, EvmCall, Quote, SwapChain, SwapRequest, SwapTransaction,
+};
+pub use approval::ApprovalMode;
+pub use jupiter::{sign_solana_transaction, Jupiter};
+pub use oneinch::OneInch;
+pub use swapper::{SwapPlan, Swapper};
+pub use zerox::ZeroEx;
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::time::Duration;
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// Address EVM aggregators use for the chain's native token
+pub const NATIVE_TOKEN: &str = "0xEeeeeEeeeEeEeeEeEeEeeEEEeeeeEeeeeeeeEEeE";
+
+/// Mint Jupiter uses for native SOL
+pub const WRAPPED_SOL: &str = "So11111111111111111111111111111111111111112";
+
+/// Maximum price movement accepted between quote and execution
+///
+/// Stored in basis points: 50 is 0.5%.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub struct Slippage(u32);
+
+impl Slippage {
+    /// Highest slippage accepted at all: 50%
+    pub const MAX_BPS: u32 = 5_000;
+
+    /// Slippage in basis points
+    pub fn from_bps(bps: u32) -> Result<Self> {
+        if bps > Self::MAX_BPS {
+            return Err(SwapError::SlippageTooHigh { bps });
+        }
+        Ok(Self(bps))
+    }
+
+    /// Slippage as a percentage, e.g. `0.5`
+    pub fn from_percent(percent: f64) -> Result<Self> {
+        if !percent.is_finite() || percent < 0.0 {
+            return Err(SwapError::InvalidRequest(format!(
+                "invalid slippage {}%",
+                percent
+            )));
+        }
+        Self::from_bps((percent * 100.0).round() as u32)
+    }
+
+    /// Basis points
+    pub fn bps(&self) -> u32 {
+        self.0
+    }
+
+    /// Percentage, e.g. `0.5`
+    pub fn percent(&self) -> f64 {
+        self.0 as f64 / 100.0
+    }
+
+    /// Least output accepted when `amount` is quoted
+    pub fn min_out(&self, amount: u128) -> u128 {
+        let keep = 10_000 - self.0 as u128;
+        amount / 10_000 * keep + amount % 10_000 * keep / 10_000
+    }
+}
+
+impl Default for Slippage {
+    /// 0.5%
+    fn default() -> Self {
+        Self(50)
+    }
+}
+
+impl fmt::Display for Slippage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
+
+/// Swap errors
+#[derive(Error, Debug)]
+pub enum SwapError {
+    /// No configured aggregator serves the chain
+    #[error("No aggregator for {0}")]
+    UnsupportedChain(SwapChain),
+
+    /// The request is malformed
+    #[error("Invalid swap request: {0}")]
+    InvalidRequest(String),
+
+    /// No aggregator found a route
+    #[error("No route from {sell_token} to {buy_token}")]
+    NoRoute {
+        /// Token sold
+        sell_token: String,
+        /// Token bought
+        buy_token: String,
+    },
+
+    /// Slippage above [`Slippage::MAX_BPS`] or the swapper's limit
+    #[error("Slippage of {bps} bps is too high")]
+    SlippageTooHigh {
+        /// Requested slippage
+        bps: u32,
+    },
+
+    /// The quote moves the price more than allowed
+    #[error("Price impact of {impact:.2}% exceeds {limit:.2}%")]
+    PriceImpactTooHigh {
+        /// Quoted price impact, percent
+        impact: f64,
+        /// Configured limit, percent
+        limit: f64,
+    },
+
+    /// The aggregator's minimum output is below what the slippage allows
+    #[error("Quote from {aggregator} guarantees {min_buy_amount}, expected at least {expected}")]
+    QuoteBelowMinimum {
+        /// Aggregator name
+        aggregator: String,
+        /// Minimum output in the quote
+        min_buy_amount: u128,
+        /// Minimum output the slippage allows
+        expected: u128,
+    },
+
+    /// The aggregator is throttling requests
+    #[error("Rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay the aggregator asked for
+        retry_after: Option<Duration>,
+    },
+
+    /// The aggregator answered with an error
+    #[error("{aggregator} error: {message}")]
+    Api {
+        /// Aggregator name
+        aggregator: String,
+        /// Error message
+        message: String,
+    },
+
+    /// Unexpected response from the aggregator
+    #[error("Invalid response: {0}")]
+    InvalidResponse(String),
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+
+    /// Sending or signing failed
+    #[error("Wallet error: {0}")]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for swap operations
+pub type Result<T> = std::result::Result<T, SwapError>;
+
+impl From<SwapError> for WalletdError {
+    fn from(e: SwapError) -> Self {
+        match e {
+            SwapError::RateLimited { retry_after } => WalletdError::RateLimited {
+                retry_after_secs: retry_after.map_or(1, |d| d.as_secs().max(1)),
+            },
+            SwapError::UnsupportedChain(_) | SwapError::NoRoute { .. } => {
+                WalletdError::NotSupported(e.to_string())
+            }
+            SwapError::InvalidRequest(reason) => WalletdError::TransactionBuildError(reason),
+            SwapError::SlippageTooHigh { .. }
+            | SwapError::PriceImpactTooHigh { .. }
+            | SwapError::QuoteBelowMinimum { .. } => {
+                WalletdError::TransactionBuildError(e.to_string())
+            }
+            SwapError::InvalidResponse(reason) => WalletdError::FormatError(reason),
+            SwapError::Api { .. } | SwapError::Wallet(_) => WalletdError::External {
+                message: e.to_string(),
+            },
+            SwapError::Http(_) => WalletdError::NetworkError(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slippage() {
+        assert_eq!(Slippage::default().bps(), 50);
+        assert_eq!(Slippage::from_percent(0.3).unwrap().bps(), 30);
+        assert_eq!(Slippage::from_bps(100).unwrap().min_out(1_000_000), 990_000);
+        assert!(Slippage::from_bps(50).unwrap().min_out(u128::MAX) < u128::MAX);
+        assert_eq!(Slippage::from_bps(0).unwrap().min_out(12_345), 12_345);
+        assert!(matches!(
+            Slippage::from_bps(5_001),
+            Err(SwapError::SlippageTooHigh { bps: 5_001 })
+        ));
+        assert!(Slippage::from_percent(-1.0).is_err());
+        assert_eq!(Slippage::from_bps(25).unwrap().to_string(), "0.25%");
+    }
+}
@@ -0,0 +1,271 @@
+//! 1inch Swap API (v6)
+//!
+//! Quotes come without a minimum output, so it is derived from the
+//! request's slippage. Allowances are read from the `approve` endpoints,
+//! and the transaction is built by a second `/swap` call.
+
+use crate::aggregator::{
+    self, Aggregator, AllowanceCheck, Quote, SwapChain, SwapRequest, SwapTransaction,
+};
+use crate::{Result, SwapError};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::time::Duration;
+
+/// Developer portal API
+pub const ONEINCH_URL: &str = "https://api.1inch.dev/swap/v6.0";
+
+/// Chains the Swap API serves
+const CHAINS: &[u64] = &[1, 10, 56, 100, 137, 146, 324, 8453, 42161, 43114, 59144];
+
+/// Quotes and transactions from 1inch
+#[derive(Debug, Clone)]
+pub struct OneInch {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl OneInch {
+    /// Developer portal API with a key
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: ONEINCH_URL.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Uses another endpoint (proxy, mock server)
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+
+    async fn get(&self, chain_id: u64, endpoint: &str, query: &[(&str, String)]) -> Result<Value> {
+        aggregator::send_json(
+            self.name(),
+            self.client
+                .get(format!("{}/{}/{}", self.base_url, chain_id, endpoint))
+                .query(query)
+                .bearer_auth(&self.api_key)
+                .timeout(Duration::from_secs(15)),
+        )
+        .await
+    }
+
+    async fn allowance(&self, chain_id: u64, request: &SwapRequest) -> Result<AllowanceCheck> {
+        let spender = self.get(chain_id, "approve/spender", &[]).await?;
+        let allowance = self
+            .get(
+                chain_id,
+                "approve/allowance",
+                &[
+                    ("tokenAddress", request.sell_token.clone()),
+                    ("walletAddress", request.taker.clone()),
+                ],
+            )
+            .await?;
+        Ok(AllowanceCheck {
+            token: request.sell_token.clone(),
+            spender: spender["address"]
+                .as_str()
+                .ok_or_else(|| SwapError::InvalidResponse("missing spender".into()))?
+                .to_string(),
+            current: aggregator::amount(&allowance, "allowance")?,
+        })
+    }
+}
+
+fn chain_id(request: &SwapRequest) -> Result<u64> {
+    match request.chain {
+        SwapChain::Evm(chain_id) => Ok(chain_id),
+        chain => Err(SwapError::UnsupportedChain(chain)),
+    }
+}
+
+#[async_trait]
+impl Aggregator for OneInch {
+    fn name(&self) -> &str {
+        "1inch"
+    }
+
+    fn supports(&self, chain: SwapChain) -> bool {
+        matches!(chain, SwapChain::Evm(id) if CHAINS.contains(&id))
+    }
+
+    async fn quote(&self, request: &SwapRequest) -> Result<Quote> {
+        let chain_id = chain_id(request)?;
+        let body = self
+            .get(
+                chain_id,
+                "quote",
+                &[
+                    ("src", request.sell_token.clone()),
+                    ("dst", request.buy_token.clone()),
+                    ("amount", request.sell_amount.to_string()),
+                    ("includeGas", "true".to_string()),
+                    ("includeProtocols", "true".to_string()),
+                ],
+            )
+            .await?;
+        let buy_amount = aggregator::amount(&body, "dstAmount")?;
+        if buy_amount == 0 {
+            return Err(SwapError::NoRoute {
+                sell_token: request.sell_token.clone(),
+                buy_token: request.buy_token.clone(),
+            });
+        }
+
+        let approval = if request.sells_native() {
+            None
+        } else {
+            Some(self.allowance(chain_id, request).await?)
+                .filter(|a| a.current < request.sell_amount)
+        };
+
+        // protocols: routes -> hops -> parts
+        let mut route: Vec<String> = body["protocols"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .flat_map(|hops| hops.as_array().into_iter().flatten())
+            .flat_map(|parts| parts.as_array().into_iter().flatten())
+            .filter_map(|part| Some(part["name"].as_str()?.to_string()))
+            .collect();
+        route.dedup();
+
+        Ok(Quote {
+            aggregator: self.name().to_string(),
+            chain: request.chain,
+            sell_token: request.sell_token.clone(),
+            buy_token: request.buy_token.clone(),
+            sell_amount: request.sell_amount,
+            buy_amount,
+            min_buy_amount: request.slippage.min_out(buy_amount),
+            price_impact: None,
+            gas_estimate: aggregator::optional_u64(&body["gas"]),
+            route,
+            approval,
+            raw: body,
+        })
+    }
+
+    async fn build(&self, request: &SwapRequest, _quote: &Quote) -> Result<SwapTransaction> {
+        let body = self
+            .get(
+                chain_id(request)?,
+                "swap",
+                &[
+                    ("src", request.sell_token.clone()),
+                    ("dst", request.buy_token.clone()),
+                    ("amount", request.sell_amount.to_string()),
+                    ("from", request.taker.clone()),
+                    ("origin", request.taker.clone()),
+                    ("slippage", request.slippage.percent().to_string()),
+                    ("disableEstimate", "true".to_string()),
+                ],
+            )
+            .await?;
+        Ok(SwapTransaction::Evm(aggregator::evm_call(&body["tx"])?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const USDT: &str = "0xdAC17F958D2ee523a2206206994597C13D831ec7";
+    const TAKER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const ROUTER: &str = "0x111111125421ca6dc452d289314280a0f8842a65";
+
+    #[tokio::test]
+    async fn test_quote_and_build() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/137/quote"))
+            .and(query_param("amount", "5000000"))
+            .and(header("authorization", "Bearer key"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "dstAmount": "10000000000000000000",
+                "gas": 180000,
+                "protocols": [[[
+                    {"name": "POLYGON_UNISWAP_V3", "part": 100}
+                ]]]
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/137/approve/spender"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"address": ROUTER})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/137/approve/allowance"))
+            .and(query_param("tokenAddress", USDT))
+            .and(query_param("walletAddress", TAKER))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({"allowance": "1000"})))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/137/swap"))
+            .and(query_param("from", TAKER))
+            .and(query_param("slippage", "1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "dstAmount": "10000000000000000000",
+                "tx": {"from": TAKER, "to": ROUTER, "data": "0x07ed2379", "value": "0", "gas": 0}
+            })))
+            .mount(&server)
+            .await;
+
+        let one_inch = OneInch::new("key").with_base_url(&server.uri());
+        let request = SwapRequest::evm(137, USDT, crate::NATIVE_TOKEN, 5_000_000, TAKER)
+            .with_slippage(crate::Slippage::from_bps(100).unwrap());
+        let quote = one_inch.quote(&request).await.unwrap();
+        assert_eq!(quote.buy_amount, 10_000_000_000_000_000_000);
+        assert_eq!(quote.min_buy_amount, 9_900_000_000_000_000_000);
+        assert_eq!(quote.route, ["POLYGON_UNISWAP_V3"]);
+        assert_eq!(quote.approval.as_ref().unwrap().current, 1000);
+        assert_eq!(quote.approval.as_ref().unwrap().spender, ROUTER);
+
+        let SwapTransaction::Evm(call) = one_inch.build(&request, &quote).await.unwrap() else {
+            panic!("expected an EVM transaction");
+        };
+        assert_eq!(call.to, ROUTER);
+        assert_eq!(call.gas, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_api_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(json!({
+                "error": "Bad Request",
+                "description": "insufficient liquidity"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/1/quote"))
+            .and(query_param("src", crate::NATIVE_TOKEN))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "2"))
+            .with_priority(1)
+            .mount(&server)
+            .await;
+
+        let one_inch = OneInch::new("key").with_base_url(&server.uri());
+        let request = SwapRequest::evm(1, USDT, crate::NATIVE_TOKEN, 1, TAKER);
+        match one_inch.quote(&request).await {
+            Err(SwapError::Api { message, .. }) => assert_eq!(message, "insufficient liquidity"),
+            other => panic!("unexpected {:?}", other),
+        }
+        let request = SwapRequest::evm(1, crate::NATIVE_TOKEN, USDT, 1, TAKER);
+        assert!(matches!(
+            one_inch.quote(&request).await,
+            Err(SwapError::RateLimited { retry_after: Some(d) }) if d == Duration::from_secs(2)
+        ));
+    }
+}
@@ -0,0 +1,440 @@
+//! Best-quote routing and execution
+
+use crate::aggregator::{Aggregator, EvmCall, Quote, SwapChain, SwapRequest, SwapTransaction};
+use crate::approval::{approval_calls, ApprovalMode};
+use crate::{Result, Slippage, SwapError};
+use futures_util::future::join_all;
+use std::sync::Arc;
+use walletd_traits::{Amount, EvmTxParams, TransactionBuilder, Transferable, TxHash};
+
+/// A checked quote with everything needed to execute it
+#[derive(Debug, Clone, PartialEq)]
+pub struct SwapPlan {
+    /// Quote the plan executes
+    pub quote: Quote,
+    /// ERC-20 approvals to send first, in order
+    pub approvals: Vec<EvmCall>,
+    /// The swap itself
+    pub transaction: SwapTransaction,
+}
+
+/// Routes swaps to the aggregator with the best checked quote
+#[derive(Clone)]
+pub struct Swapper {
+    aggregators: Vec<Arc<dyn Aggregator>>,
+    max_slippage: Slippage,
+    max_price_impact: f64,
+    approval_mode: ApprovalMode,
+}
+
+impl Default for Swapper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Swapper {
+    /// No aggregators; at most 3% slippage and 5% price impact, exact approvals
+    pub fn new() -> Self {
+        Self {
+            aggregators: Vec::new(),
+            max_slippage: Slippage(300),
+            max_price_impact: 5.0,
+            approval_mode: ApprovalMode::Exact,
+        }
+    }
+
+    /// Adds an aggregator
+    pub fn with_aggregator(mut self, aggregator: impl Aggregator + 'static) -> Self {
+        self.aggregators.push(Arc::new(aggregator));
+        self
+    }
+
+    /// Highest slippage a request may ask for
+    pub fn with_max_slippage(mut self, slippage: Slippage) -> Self {
+        self.max_slippage = slippage;
+        self
+    }
+
+    /// Highest price impact accepted, in percent
+    pub fn with_max_price_impact(mut self, percent: f64) -> Self {
+        self.max_price_impact = percent;
+        self
+    }
+
+    /// How much to approve when an allowance is short
+    pub fn with_approval_mode(mut self, mode: ApprovalMode) -> Self {
+        self.approval_mode = mode;
+        self
+    }
+
+    /// Quotes from every aggregator serving the chain, best output first
+    ///
+    /// Fails only if no aggregator returns a quote.
+    pub async fn quotes(&self, request: &SwapRequest) -> Result<Vec<Quote>> {
+        request.validate()?;
+        if request.slippage > self.max_slippage {
+            return Err(SwapError::SlippageTooHigh {
+                bps: request.slippage.bps(),
+            });
+        }
+        let aggregators: Vec<_> = self
+            .aggregators
+            .iter()
+            .filter(|a| a.supports(request.chain))
+            .collect();
+        if aggregators.is_empty() {
+            return Err(SwapError::UnsupportedChain(request.chain));
+        }
+
+        let results = join_all(aggregators.iter().map(|a| a.quote(request))).await;
+        let mut quotes = Vec::new();
+        let mut error = None;
+        for (aggregator, result) in aggregators.iter().zip(results) {
+            match result {
+                Ok(quote) => quotes.push(quote),
+                Err(e) => {
+                    tracing::debug!(aggregator = aggregator.name(), error = %e, "quote failed");
+                    // Prefer a real failure over "no route" when reporting
+                    if error.is_none() || matches!(error, Some(SwapError::NoRoute { .. })) {
+                        error = Some(e);
+                    }
+                }
+            }
+        }
+        if quotes.is_empty() {
+            return Err(error.expect("at least one aggregator was asked"));
+        }
+        quotes.sort_by_key(|quote| std::cmp::Reverse(quote.buy_amount));
+        Ok(quotes)
+    }
+
+    /// The best quote that passes [`check`](Self::check)
+    pub async fn best_quote(&self, request: &SwapRequest) -> Result<Quote> {
+        let mut first_error = None;
+        for quote in self.quotes(request).await? {
+            match self.check(request, &quote) {
+                Ok(()) => return Ok(quote),
+                Err(e) => {
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+        Err(first_error.expect("quotes is never empty"))
+    }
+
+    /// Rejects quotes for another amount, with too much price impact, or
+    /// whose on-chain minimum is below what the request's slippage allows
+    pub fn check(&self, request: &SwapRequest, quote: &Quote) -> Result<()> {
+        if quote.sell_amount != request.sell_amount {
+            return Err(SwapError::InvalidResponse(format!(
+                "{} quoted {} instead of {}",
+                quote.aggregator, quote.sell_amount, request.sell_amount
+            )));
+        }
+        if let Some(impact) = quote.price_impact {
+            if impact > self.max_price_impact {
+                return Err(SwapError::PriceImpactTooHigh {
+                    impact,
+                    limit: self.max_price_impact,
+                });
+            }
+        }
+        let expected = request.slippage.min_out(quote.buy_amount);
+        if quote.min_buy_amount < expected {
+            return Err(SwapError::QuoteBelowMinimum {
+                aggregator: quote.aggregator.clone(),
+                min_buy_amount: quote.min_buy_amount,
+                expected,
+            });
+        }
+        Ok(())
+    }
+
+    /// Picks the best quote and builds its approvals and transaction
+    pub async fn plan(&self, request: &SwapRequest) -> Result<SwapPlan> {
+        let quote = self.best_quote(request).await?;
+        let aggregator = self
+            .aggregators
+            .iter()
+            .find(|a| a.name() == quote.aggregator)
+            .expect("quote comes from a configured aggregator");
+        let transaction = aggregator.build(request, &quote).await?;
+        let approvals = match &quote.approval {
+            Some(check) => approval_calls(check, request.sell_amount, self.approval_mode)
+                .ok_or_else(|| SwapError::InvalidResponse("invalid spender address".into()))?,
+            None => Vec::new(),
+        };
+        Ok(SwapPlan {
+            quote,
+            approvals,
+            transaction,
+        })
+    }
+
+    /// Sends a plan's approvals and swap from an EVM wallet
+    ///
+    /// Returns the hashes in the order sent; the swap hash is last.
+    pub async fn execute_evm<W>(&self, wallet: &W, plan: &SwapPlan) -> Result<Vec<TxHash>>
+    where
+        W: Transferable<TxParams = EvmTxParams> + ?Sized,
+    {
+        let SwapTransaction::Evm(swap) = &plan.transaction else {
+            return Err(SwapError::UnsupportedChain(plan.quote.chain));
+        };
+        if let (SwapChain::Evm(chain_id), Some(wallet_chain)) =
+            (plan.quote.chain, wallet.network().chain_id)
+        {
+            if chain_id != wallet_chain {
+                return Err(SwapError::InvalidRequest(format!(
+                    "plan is for chain {} but the wallet is on {}",
+                    chain_id, wallet_chain
+                )));
+            }
+        }
+
+        let mut hashes = Vec::with_capacity(plan.approvals.len() + 1);
+        for call in plan.approvals.iter().chain(Some(swap)) {
+            let mut tx = TransactionBuilder::new()
+                .to(call.to.clone())
+                .amount(Amount::from_smallest_unit(call.value, wallet.decimals()))
+                .data(call.data.clone());
+            if let Some(gas) = call.gas.filter(|gas| *gas > 0) {
+                tx = tx.gas_limit(gas);
+            }
+            let hash = wallet.transfer_with(tx).await?;
+            tracing::info!(to = %call.to, hash = %hash.0, "swap transaction sent");
+            hashes.push(hash);
+        }
+        Ok(hashes)
+    }
+}
+
+impl std::fmt::Debug for Swapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Swapper")
+            .field(
+                "aggregators",
+                &self
+                    .aggregators
+                    .iter()
+                    .map(|a| a.name())
+                    .collect::<Vec<_>>(),
+            )
+            .field("max_slippage", &self.max_slippage)
+            .field("max_price_impact", &self.max_price_impact)
+            .field("approval_mode", &self.approval_mode)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aggregator::AllowanceCheck;
+    use async_trait::async_trait;
+    use std::sync::Mutex;
+    use walletd_traits::{Network, Wallet, WalletError, WalletResult};
+
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    const TAKER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+    const SPENDER: &str = "0x0000000000001ff3684f28c67538d4d072c22734";
+
+    struct Fixed {
+        name: &'static str,
+        buy_amount: u128,
+        min_buy_amount: u128,
+        price_impact: Option<f64>,
+    }
+
+    #[async_trait]
+    impl Aggregator for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn supports(&self, chain: SwapChain) -> bool {
+            chain == SwapChain::Evm(1)
+        }
+
+        async fn quote(&self, request: &SwapRequest) -> Result<Quote> {
+            if self.buy_amount == 0 {
+                return Err(SwapError::NoRoute {
+                    sell_token: request.sell_token.clone(),
+                    buy_token: request.buy_token.clone(),
+                });
+            }
+            Ok(Quote {
+                aggregator: self.name.to_string(),
+                chain: request.chain,
+                sell_token: request.sell_token.clone(),
+                buy_token: request.buy_token.clone(),
+                sell_amount: request.sell_amount,
+                buy_amount: self.buy_amount,
+                min_buy_amount: self.min_buy_amount,
+                price_impact: self.price_impact,
+                gas_estimate: Some(150_000),
+                route: vec![self.name.to_string()],
+                approval: Some(AllowanceCheck {
+                    token: request.sell_token.clone(),
+                    spender: SPENDER.to_string(),
+                    current: 0,
+                }),
+                raw: serde_json::Value::Null,
+            })
+        }
+
+        async fn build(&self, _request: &SwapRequest, _quote: &Quote) -> Result<SwapTransaction> {
+            Ok(SwapTransaction::Evm(EvmCall {
+                to: SPENDER.to_string(),
+                data: self.name.as_bytes().to_vec(),
+                value: 0,
+                gas: Some(150_000),
+            }))
+        }
+    }
+
+    fn fixed(name: &'static str, buy_amount: u128, min_buy_amount: u128) -> Fixed {
+        Fixed {
+            name,
+            buy_amount,
+            min_buy_amount,
+            price_impact: None,
+        }
+    }
+
+    struct Recorder {
+        network: Network,
+        sent: Mutex<Vec<TransactionBuilder>>,
+    }
+
+    #[async_trait]
+    impl Wallet for Recorder {
+        fn address(&self) -> String {
+            TAKER.to_string()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl Transferable for Recorder {
+        type TxParams = EvmTxParams;
+
+        async fn transfer_with(&self, tx: TransactionBuilder) -> WalletResult<TxHash> {
+            let mut sent = self.sent.lock().expect("sent lock");
+            sent.push(tx);
+            Ok(TxHash::new(format!("0x{:02x}", sent.len())))
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Err(WalletError::NotSupported("estimate".into()))
+        }
+    }
+
+    fn request() -> SwapRequest {
+        SwapRequest::evm(1, USDC, crate::NATIVE_TOKEN, 1_000_000, TAKER)
+    }
+
+    #[tokio::test]
+    async fn test_best_quote() {
+        let swapper = Swapper::new()
+            .with_aggregator(fixed("a", 100, 99))
+            .with_aggregator(fixed("b", 120, 119))
+            .with_aggregator(fixed("c", 0, 0));
+        let quotes = swapper.quotes(&request()).await.unwrap();
+        assert_eq!(
+            quotes.iter().map(|q| q.buy_amount).collect::<Vec<_>>(),
+            [120, 100]
+        );
+        assert_eq!(
+            swapper.best_quote(&request()).await.unwrap().aggregator,
+            "b"
+        );
+
+        // "b" returns a minimum its slippage does not allow
+        let swapper = Swapper::new()
+            .with_aggregator(fixed("a", 100, 99))
+            .with_aggregator(fixed("b", 120, 100));
+        assert_eq!(
+            swapper.best_quote(&request()).await.unwrap().aggregator,
+            "a"
+        );
+
+        let swapper = Swapper::new().with_aggregator(Fixed {
+            price_impact: Some(12.0),
+            ..fixed("a", 100, 99)
+        });
+        assert!(matches!(
+            swapper.best_quote(&request()).await,
+            Err(SwapError::PriceImpactTooHigh { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_limits() {
+        let swapper = Swapper::new().with_aggregator(fixed("a", 100, 99));
+        let risky = request().with_slippage(Slippage::from_bps(500).unwrap());
+        assert!(matches!(
+            swapper.quotes(&risky).await,
+            Err(SwapError::SlippageTooHigh { bps: 500 })
+        ));
+        let solana = SwapRequest::solana(crate::WRAPPED_SOL, "mint", 1, "taker");
+        assert!(matches!(
+            swapper.quotes(&solana).await,
+            Err(SwapError::UnsupportedChain(SwapChain::Solana))
+        ));
+        let none = Swapper::new().with_aggregator(fixed("c", 0, 0));
+        assert!(matches!(
+            none.quotes(&request()).await,
+            Err(SwapError::NoRoute { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_plan_and_execute() {
+        let swapper = Swapper::new()
+            .with_aggregator(fixed("a", 100, 99))
+            .with_approval_mode(ApprovalMode::Unlimited);
+        let plan = swapper.plan(&request()).await.unwrap();
+        assert_eq!(plan.approvals.len(), 1);
+        assert_eq!(plan.approvals[0].to, USDC);
+
+        let wallet = Recorder {
+            network: Network {
+                chain_id: Some(1),
+                ..Network::mainnet("ethereum")
+            },
+            sent: Mutex::new(Vec::new()),
+        };
+        let hashes = swapper.execute_evm(&wallet, &plan).await.unwrap();
+        assert_eq!(hashes, [TxHash::new("0x01"), TxHash::new("0x02")]);
+        let other_chain = Recorder {
+            network: Network {
+                chain_id: Some(137),
+                ..Network::mainnet("polygon")
+            },
+            sent: Mutex::new(Vec::new()),
+        };
+        assert!(swapper.execute_evm(&other_chain, &plan).await.is_err());
+
+        let sent = wallet.sent.into_inner().unwrap();
+        assert_eq!(sent[0].to.as_deref(), Some(USDC));
+        assert_eq!(sent[1].data.as_deref(), Some(b"a".as_slice()));
+        assert_eq!(sent[1].params.gas_limit, Some(150_000));
+    }
+}
@@ -0,0 +1,213 @@
+//! 0x Swap API (v2, AllowanceHolder)
+//!
+//! The quote already carries the transaction, and its `issues.allowance`
+//! says whether the AllowanceHolder contract must be approved first.
+
+use crate::aggregator::{
+    self, Aggregator, AllowanceCheck, Quote, SwapChain, SwapRequest, SwapTransaction,
+};
+use crate::{Result, SwapError};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Public API
+pub const ZEROX_URL: &str = "https://api.0x.org";
+
+/// Chains the Swap API serves
+const CHAINS: &[u64] = &[
+    1, 10, 56, 137, 5000, 8453, 34443, 42161, 43114, 59144, 81457, 534352,
+];
+
+/// Quotes and transactions from 0x
+#[derive(Debug, Clone)]
+pub struct ZeroEx {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: String,
+}
+
+impl ZeroEx {
+    /// Public API with a dashboard key
+    pub fn new(api_key: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            base_url: ZEROX_URL.to_string(),
+            api_key: api_key.to_string(),
+        }
+    }
+
+    /// Uses another endpoint (proxy, mock server)
+    pub fn with_base_url(mut self, url: &str) -> Self {
+        self.base_url = url.trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[async_trait]
+impl Aggregator for ZeroEx {
+    fn name(&self) -> &str {
+        "0x"
+    }
+
+    fn supports(&self, chain: SwapChain) -> bool {
+        matches!(chain, SwapChain::Evm(id) if CHAINS.contains(&id))
+    }
+
+    async fn quote(&self, request: &SwapRequest) -> Result<Quote> {
+        let SwapChain::Evm(chain_id) = request.chain else {
+            return Err(SwapError::UnsupportedChain(request.chain));
+        };
+        let body = aggregator::send_json(
+            self.name(),
+            self.client
+                .get(format!("{}/swap/allowance-holder/quote", self.base_url))
+                .query(&[
+                    ("chainId", chain_id.to_string()),
+                    ("sellToken", request.sell_token.clone()),
+                    ("buyToken", request.buy_token.clone()),
+                    ("sellAmount", request.sell_amount.to_string()),
+                    ("taker", request.taker.clone()),
+                    ("slippageBps", request.slippage.bps().to_string()),
+                ])
+                .header("0x-api-key", &self.api_key)
+                .header("0x-version", "v2")
+                .timeout(Duration::from_secs(15)),
+        )
+        .await?;
+
+        if body["liquidityAvailable"] == false {
+            return Err(SwapError::NoRoute {
+                sell_token: request.sell_token.clone(),
+                buy_token: request.buy_token.clone(),
+            });
+        }
+        let allowance = &body["issues"]["allowance"];
+        let approval = if allowance.is_object() && !request.sells_native() {
+            Some(AllowanceCheck {
+                token: request.sell_token.clone(),
+                spender: allowance["spender"]
+                    .as_str()
+                    .ok_or_else(|| SwapError::InvalidResponse("missing spender".into()))?
+                    .to_string(),
+                current: aggregator::amount(allowance, "actual")?,
+            })
+        } else {
+            None
+        };
+
+        Ok(Quote {
+            aggregator: self.name().to_string(),
+            chain: request.chain,
+            sell_token: request.sell_token.clone(),
+            buy_token: request.buy_token.clone(),
+            sell_amount: aggregator::amount(&body, "sellAmount")?,
+            buy_amount: aggregator::amount(&body, "buyAmount")?,
+            min_buy_amount: aggregator::amount(&body, "minBuyAmount")?,
+            price_impact: None,
+            gas_estimate: aggregator::optional_u64(&body["transaction"]["gas"]),
+            route: body["route"]["fills"]
+                .as_array()
+                .map(|fills| {
+                    fills
+                        .iter()
+                        .filter_map(|fill| Some(fill["source"].as_str()?.to_string()))
+                        .collect()
+                })
+                .unwrap_or_default(),
+            approval,
+            raw: body,
+        })
+    }
+
+    async fn build(&self, _request: &SwapRequest, quote: &Quote) -> Result<SwapTransaction> {
+        Ok(SwapTransaction::Evm(aggregator::evm_call(
+            &quote.raw["transaction"],
+        )?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use wiremock::matchers::{header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+    const WETH: &str = "0xC02aaA39b223FE8D0A0e5C4F27eAD9083C756Cc2";
+    const TAKER: &str = "0x70997970C51812dc3A010C7d01b50e0d17dc79C8";
+
+    #[tokio::test]
+    async fn test_quote_and_build() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/swap/allowance-holder/quote"))
+            .and(query_param("chainId", "1"))
+            .and(query_param("sellAmount", "1000000000"))
+            .and(query_param("slippageBps", "30"))
+            .and(header("0x-api-key", "key"))
+            .and(header("0x-version", "v2"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "liquidityAvailable": true,
+                "sellAmount": "1000000000",
+                "buyAmount": "301234567890123456",
+                "minBuyAmount": "300330864186452985",
+                "issues": {
+                    "allowance": {
+                        "actual": "0",
+                        "spender": "0x0000000000001ff3684f28c67538d4d072c22734"
+                    },
+                    "balance": null
+                },
+                "route": {"fills": [
+                    {"source": "Uniswap_V3", "proportionBps": "7000"},
+                    {"source": "Curve", "proportionBps": "3000"}
+                ]},
+                "transaction": {
+                    "to": "0x0000000000001ff3684f28c67538d4d072c22734",
+                    "data": "0x2213bc0b",
+                    "gas": "288079",
+                    "value": "0"
+                }
+            })))
+            .mount(&server)
+            .await;
+
+        let zero_ex = ZeroEx::new("key").with_base_url(&server.uri());
+        let request = SwapRequest::evm(1, USDC, WETH, 1_000_000_000, TAKER)
+            .with_slippage(crate::Slippage::from_bps(30).unwrap());
+        let quote = zero_ex.quote(&request).await.unwrap();
+        assert_eq!(quote.buy_amount, 301_234_567_890_123_456);
+        assert_eq!(quote.min_buy_amount, 300_330_864_186_452_985);
+        assert_eq!(quote.route, ["Uniswap_V3", "Curve"]);
+        assert_eq!(quote.gas_estimate, Some(288_079));
+        let approval = quote.approval.clone().unwrap();
+        assert_eq!((approval.token.as_str(), approval.current), (USDC, 0));
+
+        let SwapTransaction::Evm(call) = zero_ex.build(&request, &quote).await.unwrap() else {
+            panic!("expected an EVM transaction");
+        };
+        assert_eq!(call.data, [0x22, 0x13, 0xbc, 0x0b]);
+        assert_eq!(call.gas, Some(288_079));
+    }
+
+    #[tokio::test]
+    async fn test_no_liquidity() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(json!({"liquidityAvailable": false})),
+            )
+            .mount(&server)
+            .await;
+
+        let zero_ex = ZeroEx::new("key").with_base_url(&server.uri());
+        let request = SwapRequest::evm(1, USDC, WETH, 1, TAKER);
+        assert!(matches!(
+            zero_ex.quote(&request).await,
+            Err(SwapError::NoRoute { .. })
+        ));
+        assert!(!zero_ex.supports(SwapChain::Solana));
+        assert!(!zero_ex.supports(SwapChain::Evm(5)));
+    }
+}
@@ -0,0 +1,25 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use walletd_cosmos::{CosmosWatchOnlyWallet, NetworkConfig};
+use walletd_traits::{Chain, ChainRegistry};
+
+fuzz_target!(|data: &str| {
+    let registry = ChainRegistry::with_defaults();
+
+    // Bitcoin (bech32/bech32m segwit) and Cosmos (bech32) rules
+    for chain in [Chain::Bitcoin, Chain::Cosmos] {
+        if let Ok(normalized) = registry.normalize(chain, data) {
+            // Normalizing is idempotent and never yields an invalid address
+            assert!(registry.validate(chain, &normalized).is_ok());
+            assert_eq!(registry.normalize(chain, &normalized).unwrap(), normalized);
+        }
+    }
+
+    // Detection never panics and only reports chains that validate
+    for chain in registry.detect(data) {
+        assert!(registry.validate(chain, data).is_ok());
+    }
+
+    let _ = CosmosWatchOnlyWallet::from_address(data, NetworkConfig::cosmos_hub());
+});
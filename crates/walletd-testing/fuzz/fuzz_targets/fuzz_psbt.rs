@@ -0,0 +1,20 @@
+#![no_main]
+
+use bitcoin::psbt::Psbt;
+use libfuzzer_sys::fuzz_target;
+use std::str::FromStr;
+
+fuzz_target!(|data: &[u8]| {
+    // Binary PSBTs, as received from hardware wallets and coordinators
+    if let Ok(psbt) = Psbt::deserialize(data) {
+        let bytes = psbt.serialize();
+        assert_eq!(Psbt::deserialize(&bytes).unwrap(), psbt);
+    }
+
+    // Base64 PSBTs, as pasted by users
+    if let Ok(text) = std::str::from_utf8(data) {
+        if let Ok(psbt) = Psbt::from_str(text) {
+            assert_eq!(Psbt::from_str(&psbt.to_string()).unwrap(), psbt);
+        }
+    }
+});
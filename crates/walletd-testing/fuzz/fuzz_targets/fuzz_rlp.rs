@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use walletd_ethereum::alloy::consensus::TxEnvelope;
+use walletd_ethereum::alloy::eips::eip2718::{Decodable2718, Encodable2718};
+use walletd_ethereum::alloy::rlp::{Decodable, Header};
+
+fuzz_target!(|data: &[u8]| {
+    // Raw RLP headers
+    let _ = Header::decode(&mut &data[..]);
+    let _ = Vec::<u8>::decode(&mut &data[..]);
+
+    // Signed transactions, as passed to eth_sendRawTransaction
+    if let Ok(tx) = TxEnvelope::decode_2718(&mut &data[..]) {
+        let encoded = tx.encoded_2718();
+        assert_eq!(TxEnvelope::decode_2718(&mut encoded.as_slice()).unwrap(), tx);
+    }
+});
@@ -0,0 +1,23 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use walletd_polkadot::{NetworkConfig, PolkadotWallet, PolkadotWatchOnlyWallet};
+
+fuzz_target!(|data: &str| {
+    let config = NetworkConfig::polkadot();
+    let valid = PolkadotWallet::validate_address(data);
+    let for_network = PolkadotWallet::validate_address_for_network(data, config.ss58_prefix);
+
+    // A network match implies a valid address
+    assert!(valid || !for_network);
+
+    // The watch-only wallet must accept exactly the addresses for its network,
+    // and report them back unchanged
+    match PolkadotWatchOnlyWallet::from_address(data, config) {
+        Ok(watch) => {
+            assert!(for_network);
+            assert_eq!(watch.address(), data);
+        }
+        Err(_) => assert!(!for_network),
+    }
+});
@@ -0,0 +1,18 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+use walletd_ton::{TonAddress, TonNetwork};
+
+fuzz_target!(|data: &str| {
+    // Parsing untrusted input should only ever return errors, never panic
+    if let Ok(address) = TonAddress::from_friendly(data) {
+        // Anything accepted must survive a round trip through both formats
+        let friendly = address.to_friendly(TonNetwork::Mainnet, true);
+        assert_eq!(TonAddress::from_friendly(&friendly).unwrap(), address);
+        assert_eq!(TonAddress::from_raw(&address.to_raw()).unwrap(), address);
+    }
+
+    if let Ok(address) = TonAddress::from_raw(data) {
+        assert_eq!(TonAddress::from_raw(&address.to_raw()).unwrap(), address);
+    }
+});
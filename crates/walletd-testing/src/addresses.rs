@@ -0,0 +1,527 @@
+//! Proptest strategies for chain addresses
+//!
+//! `valid_*` strategies encode random payloads with the chain's real prefixes,
+//! versions and checksums. `invalid_*` strategies produce near misses (a bad
+//! checksum, a wrong length, a stray character, the wrong network) that a
+//! correct parser must reject.
+//!
+//! ```rust,ignore
+//! use walletd_testing::addresses;
+//!
+//! proptest! {
+//!     #[test]
+//!     fn parses_tron_addresses(address in addresses::valid_tron_address()) {
+//!         prop_assert!(TronWallet::validate_address(&address));
+//!     }
+//! }
+//! ```
+
+use base64::Engine;
+use bech32::{Bech32, Fe32, Hrp};
+use blake2::{Blake2b512, Digest};
+use proptest::prelude::*;
+use proptest::sample::Index;
+use sha2::Sha256;
+use walletd_traits::{Chain, EvmValidator};
+
+const BECH32_CHARSET: &str = "qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+const HEX_CHARSET: &str = "0123456789abcdef";
+
+// ============================================================================
+// Encoding helpers
+// ============================================================================
+
+fn double_sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+/// Base58Check with the checksum XORed by `flip` (all zeros leaves it intact)
+fn base58check(version: u8, payload: &[u8], flip: [u8; 4]) -> String {
+    let mut data = vec![version];
+    data.extend_from_slice(payload);
+    let checksum = double_sha256(&data);
+    data.extend((0..4).map(|i| checksum[i] ^ flip[i]));
+    bs58::encode(data).into_string()
+}
+
+fn segwit(hrp: Hrp, version: Fe32, program: &[u8]) -> String {
+    bech32::segwit::encode(hrp, version, program).expect("program length is valid")
+}
+
+fn bech32(hrp: &str, data: &[u8]) -> String {
+    bech32::encode::<Bech32>(Hrp::parse(hrp).expect("valid hrp"), data).expect("data fits")
+}
+
+/// SS58 with a single-byte prefix, checksum XORed by `flip`
+fn ss58(prefix: u8, public_key: &[u8], flip: [u8; 2]) -> String {
+    let mut data = vec![prefix];
+    data.extend_from_slice(public_key);
+    let hash = Blake2b512::new().chain_update(b"SS58PRE").chain_update(&data).finalize();
+    data.extend([hash[0] ^ flip[0], hash[1] ^ flip[1]]);
+    bs58::encode(data).into_string()
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+/// TON user-friendly address, checksum XORed by `flip`
+fn ton_friendly(flags: u8, workchain: i8, hash: &[u8; 32], flip: u16) -> String {
+    let mut data = vec![flags, workchain as u8];
+    data.extend_from_slice(hash);
+    data.extend_from_slice(&(crc16_xmodem(&data) ^ flip).to_be_bytes());
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(data)
+}
+
+/// Replaces one character at or after `start` with a different one from `alphabet`
+fn substitute(address: &str, start: usize, position: Index, replacement: Index, alphabet: &str) -> String {
+    let mut chars: Vec<char> = address.chars().collect();
+    let i = start + position.index(chars.len() - start);
+    let choices: Vec<char> = alphabet.chars().filter(|c| *c != chars[i]).collect();
+    chars[i] = choices[replacement.index(choices.len())];
+    chars.into_iter().collect()
+}
+
+fn nonzero_flip4() -> impl Strategy<Value = [u8; 4]> {
+    (1u32..=u32::MAX).prop_map(u32::to_be_bytes)
+}
+
+fn nonzero_flip2() -> impl Strategy<Value = [u8; 2]> {
+    (1u16..=u16::MAX).prop_map(u16::to_be_bytes)
+}
+
+/// Byte lengths in `0..=max` other than `exclude`
+fn other_length(exclude: &'static [usize], max: usize) -> impl Strategy<Value = usize> {
+    (0..=max).prop_filter("excluded length", move |len| !exclude.contains(len))
+}
+
+// ============================================================================
+// Bitcoin
+// ============================================================================
+
+/// Valid mainnet Bitcoin addresses: P2PKH, P2SH, P2WPKH, P2WSH and P2TR
+pub fn valid_bitcoin_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<[u8; 20]>().prop_map(|hash| base58check(0x00, &hash, [0; 4])),
+        any::<[u8; 20]>().prop_map(|hash| base58check(0x05, &hash, [0; 4])),
+        any::<[u8; 20]>().prop_map(|hash| segwit(bech32::hrp::BC, bech32::segwit::VERSION_0, &hash)),
+        any::<[u8; 32]>().prop_map(|hash| segwit(bech32::hrp::BC, bech32::segwit::VERSION_0, &hash)),
+        any::<[u8; 32]>().prop_map(|key| segwit(bech32::hrp::BC, bech32::segwit::VERSION_1, &key)),
+    ]
+}
+
+/// Valid testnet Bitcoin addresses: P2PKH, P2SH and P2WPKH
+pub fn valid_bitcoin_testnet_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<[u8; 20]>().prop_map(|hash| base58check(0x6f, &hash, [0; 4])),
+        any::<[u8; 20]>().prop_map(|hash| base58check(0xc4, &hash, [0; 4])),
+        any::<[u8; 20]>().prop_map(|hash| segwit(bech32::hrp::TB, bech32::segwit::VERSION_0, &hash)),
+    ]
+}
+
+/// Strings a mainnet Bitcoin parser must reject
+pub fn invalid_bitcoin_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Bad Base58Check checksum
+        (prop::sample::select(vec![0x00u8, 0x05]), any::<[u8; 20]>(), nonzero_flip4())
+            .prop_map(|(version, hash, flip)| base58check(version, &hash, flip)),
+        // Testnet addresses
+        valid_bitcoin_testnet_address(),
+        // One corrupted bech32 character
+        (any::<[u8; 20]>(), any::<Index>(), any::<Index>()).prop_map(|(hash, position, replacement)| {
+            let address = segwit(bech32::hrp::BC, bech32::segwit::VERSION_0, &hash);
+            substitute(&address, 3, position, replacement, BECH32_CHARSET)
+        }),
+        // Truncated bech32
+        any::<[u8; 20]>().prop_map(|hash| {
+            let address = segwit(bech32::hrp::BC, bech32::segwit::VERSION_0, &hash);
+            address[..address.len() - 1].to_string()
+        }),
+    ]
+}
+
+// ============================================================================
+// EVM
+// ============================================================================
+
+/// Valid EIP-55 checksummed EVM addresses
+pub fn valid_ethereum_address() -> impl Strategy<Value = String> {
+    any::<[u8; 20]>().prop_map(|bytes| EvmValidator::checksum(&hex::encode(bytes)))
+}
+
+/// Strings an EVM address parser must reject
+pub fn invalid_ethereum_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Wrong length
+        other_length(&[20], 40)
+            .prop_flat_map(|len| prop::collection::vec(any::<u8>(), len))
+            .prop_map(|bytes| format!("0x{}", hex::encode(bytes))),
+        // Missing 0x
+        any::<[u8; 20]>().prop_map(hex::encode),
+        // Non-hex character
+        (valid_ethereum_address(), any::<Index>(), any::<Index>()).prop_map(|(address, position, replacement)| {
+            substitute(&address, 2, position, replacement, "ghijklmnopqrstuvwxyz")
+        }),
+        // Broken EIP-55 checksum: one letter with the wrong case
+        (valid_ethereum_address(), any::<Index>()).prop_filter_map("needs a mixed-case result", |(address, index)| {
+            let letters: Vec<usize> = (2..address.len())
+                .filter(|&i| address.as_bytes()[i].is_ascii_alphabetic())
+                .collect();
+            if letters.is_empty() {
+                return None;
+            }
+            let i = letters[index.index(letters.len())];
+            let mut bytes = address.into_bytes();
+            bytes[i] ^= 0x20;
+            let flipped = String::from_utf8(bytes).ok()?;
+            let digits = &flipped[2..];
+            let mixed = digits.chars().any(|c| c.is_ascii_uppercase()) && digits.chars().any(|c| c.is_ascii_lowercase());
+            mixed.then_some(flipped)
+        }),
+    ]
+}
+
+// ============================================================================
+// Solana
+// ============================================================================
+
+/// Valid Solana addresses (base58 32-byte public keys)
+pub fn valid_solana_address() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(|key| bs58::encode(key).into_string())
+}
+
+/// Strings a Solana address parser must reject
+pub fn invalid_solana_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Wrong length
+        other_length(&[0, 32], 64)
+            .prop_flat_map(|len| prop::collection::vec(any::<u8>(), len))
+            .prop_map(|bytes| bs58::encode(bytes).into_string()),
+        // Character outside the base58 alphabet
+        (valid_solana_address(), any::<Index>(), any::<Index>())
+            .prop_map(|(address, position, replacement)| substitute(&address, 0, position, replacement, "0OIl")),
+    ]
+}
+
+// ============================================================================
+// TON
+// ============================================================================
+
+/// Valid TON addresses, user-friendly (URL-safe base64) or raw
+pub fn valid_ton_address() -> impl Strategy<Value = String> {
+    let flags = prop::sample::select(vec![0x11u8, 0x51, 0x91, 0xd1]);
+    let workchain = prop::sample::select(vec![0i8, -1]);
+    prop_oneof![
+        (flags, workchain.clone(), any::<[u8; 32]>())
+            .prop_map(|(flags, workchain, hash)| ton_friendly(flags, workchain, &hash, 0)),
+        (workchain, any::<[u8; 32]>()).prop_map(|(workchain, hash)| format!("{}:{}", workchain, hex::encode(hash))),
+    ]
+}
+
+/// Strings a TON address parser must reject
+pub fn invalid_ton_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Bad CRC16
+        (any::<[u8; 32]>(), 1u16..=u16::MAX).prop_map(|(hash, flip)| ton_friendly(0x11, 0, &hash, flip)),
+        // Unknown flags
+        (any::<u8>().prop_filter("known flags", |f| ![0x11, 0x51].contains(&(f & 0x7f))), any::<[u8; 32]>())
+            .prop_map(|(flags, hash)| ton_friendly(flags, 0, &hash, 0)),
+        // Raw address with the wrong hash length
+        other_length(&[32], 40)
+            .prop_flat_map(|len| prop::collection::vec(any::<u8>(), len))
+            .prop_map(|hash| format!("0:{}", hex::encode(hash))),
+    ]
+}
+
+// ============================================================================
+// Cosmos
+// ============================================================================
+
+/// Valid Cosmos SDK account addresses with the given bech32 prefix
+pub fn valid_cosmos_address(prefix: &'static str) -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<[u8; 20]>().prop_map(move |hash| bech32(prefix, &hash)),
+        any::<[u8; 32]>().prop_map(move |hash| bech32(prefix, &hash)),
+    ]
+}
+
+/// Strings a Cosmos address parser for `prefix` must reject
+pub fn invalid_cosmos_address(prefix: &'static str) -> impl Strategy<Value = String> {
+    let other_prefix = if prefix == "osmo" { "cosmos" } else { "osmo" };
+    prop_oneof![
+        // One corrupted character
+        (valid_cosmos_address(prefix), any::<Index>(), any::<Index>()).prop_map(move |(address, position, replacement)| {
+            substitute(&address, prefix.len() + 1, position, replacement, BECH32_CHARSET)
+        }),
+        // Wrong payload length
+        other_length(&[20, 32], 40)
+            .prop_flat_map(|len| prop::collection::vec(any::<u8>(), len))
+            .prop_map(move |data| bech32(prefix, &data)),
+        // Another chain's prefix
+        any::<[u8; 20]>().prop_map(move |hash| bech32(other_prefix, &hash)),
+    ]
+}
+
+// ============================================================================
+// Tron
+// ============================================================================
+
+/// Valid Tron addresses (Base58Check, version 0x41)
+pub fn valid_tron_address() -> impl Strategy<Value = String> {
+    any::<[u8; 20]>().prop_map(|hash| base58check(0x41, &hash, [0; 4]))
+}
+
+/// Strings a Tron address parser must reject
+pub fn invalid_tron_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (any::<[u8; 20]>(), nonzero_flip4()).prop_map(|(hash, flip)| base58check(0x41, &hash, flip)),
+        (any::<u8>().prop_filter("tron version", |v| *v != 0x41), any::<[u8; 20]>())
+            .prop_map(|(version, hash)| base58check(version, &hash, [0; 4])),
+    ]
+}
+
+// ============================================================================
+// Polkadot
+// ============================================================================
+
+/// Valid SS58 addresses for a single-byte network prefix (0 = Polkadot, 2 = Kusama)
+pub fn valid_polkadot_address(prefix: u8) -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(move |key| ss58(prefix, &key, [0; 2]))
+}
+
+/// Strings an SS58 parser for `prefix` must reject
+pub fn invalid_polkadot_address(prefix: u8) -> impl Strategy<Value = String> {
+    prop_oneof![
+        (any::<[u8; 32]>(), nonzero_flip2()).prop_map(move |(key, flip)| ss58(prefix, &key, flip)),
+        other_length(&[32], 40)
+            .prop_flat_map(|len| prop::collection::vec(any::<u8>(), len))
+            .prop_map(move |key| ss58(prefix, &key, [0; 2])),
+    ]
+}
+
+// ============================================================================
+// Cardano
+// ============================================================================
+
+/// Valid Cardano Shelley mainnet addresses (enterprise and base)
+pub fn valid_cardano_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<[u8; 28]>().prop_map(|hash| {
+            let mut data = vec![0x61];
+            data.extend_from_slice(&hash);
+            bech32("addr", &data)
+        }),
+        (any::<[u8; 28]>(), any::<[u8; 28]>()).prop_map(|(payment, stake)| {
+            let mut data = vec![0x01];
+            data.extend_from_slice(&payment);
+            data.extend_from_slice(&stake);
+            bech32("addr", &data)
+        }),
+    ]
+}
+
+/// Strings a Cardano mainnet address parser must reject
+pub fn invalid_cardano_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        (valid_cardano_address(), any::<Index>(), any::<Index>())
+            .prop_map(|(address, position, replacement)| substitute(&address, 5, position, replacement, BECH32_CHARSET)),
+        valid_cardano_address().prop_map(|address| address[..address.len() - 1].to_string()),
+    ]
+}
+
+// ============================================================================
+// Sui and Aptos
+// ============================================================================
+
+/// Valid Sui or Aptos addresses (`0x` + 32 bytes hex)
+pub fn valid_move_address() -> impl Strategy<Value = String> {
+    any::<[u8; 32]>().prop_map(|bytes| format!("0x{}", hex::encode(bytes)))
+}
+
+/// Strings a Sui or Aptos address parser must reject
+pub fn invalid_move_address() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Too long
+        prop::collection::vec(prop::sample::select(HEX_CHARSET.chars().collect::<Vec<_>>()), 65..=80)
+            .prop_map(|digits| format!("0x{}", digits.into_iter().collect::<String>())),
+        // Non-hex character
+        (valid_move_address(), any::<Index>(), any::<Index>())
+            .prop_map(|(address, position, replacement)| substitute(&address, 2, position, replacement, "ghijklmnopqrstuvwxyz")),
+    ]
+}
+
+// ============================================================================
+// NEAR
+// ============================================================================
+
+/// Valid NEAR account ids: implicit (64 hex) or named (`*.near`)
+pub fn valid_near_account_id() -> impl Strategy<Value = String> {
+    prop_oneof![
+        any::<[u8; 32]>().prop_map(hex::encode),
+        "[a-z0-9][a-z0-9_-]{1,20}(\\.[a-z0-9][a-z0-9_-]{1,10})?\\.near",
+    ]
+}
+
+/// Strings a NEAR account id parser must reject
+pub fn invalid_near_account_id() -> impl Strategy<Value = String> {
+    prop_oneof![
+        // Uppercase
+        "[a-z0-9]{1,10}[A-Z][a-z0-9]{0,10}\\.near",
+        // Leading separator
+        "[-_][a-z0-9]{2,20}\\.near",
+        // Too short or too long
+        "[a-z0-9]",
+        "[a-z0-9]{65,80}",
+    ]
+}
+
+// ============================================================================
+// By chain
+// ============================================================================
+
+/// Valid mainnet addresses for a [`Chain`], if there is a strategy for it
+pub fn valid_address(chain: Chain) -> Option<BoxedStrategy<String>> {
+    match chain {
+        Chain::Bitcoin => Some(valid_bitcoin_address().boxed()),
+        Chain::Ethereum => Some(valid_ethereum_address().boxed()),
+        Chain::Solana => Some(valid_solana_address().boxed()),
+        Chain::Ton => Some(valid_ton_address().boxed()),
+        Chain::Cosmos => Some(valid_cosmos_address("cosmos").boxed()),
+        _ => None,
+    }
+}
+
+/// Strings a mainnet address parser for a [`Chain`] must reject, if there is
+/// a strategy for it
+pub fn invalid_address(chain: Chain) -> Option<BoxedStrategy<String>> {
+    match chain {
+        Chain::Bitcoin => Some(invalid_bitcoin_address().boxed()),
+        Chain::Ethereum => Some(invalid_ethereum_address().boxed()),
+        Chain::Solana => Some(invalid_solana_address().boxed()),
+        Chain::Ton => Some(invalid_ton_address().boxed()),
+        Chain::Cosmos => Some(invalid_cosmos_address("cosmos").boxed()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Fixtures;
+    use walletd_traits::ChainRegistry;
+
+    fn decode_base58check(address: &str) -> Option<(u8, Vec<u8>)> {
+        let data = bs58::decode(address).into_vec().ok()?;
+        if data.len() < 5 {
+            return None;
+        }
+        let (body, checksum) = data.split_at(data.len() - 4);
+        (double_sha256(body)[..4] == *checksum).then(|| (body[0], body[1..].to_vec()))
+    }
+
+    fn is_valid_ss58(address: &str, prefix: u8) -> bool {
+        match bs58::decode(address).into_vec() {
+            Ok(data) if data.len() == 35 && data[0] == prefix => ss58(prefix, &data[1..33], [0; 2]) == address,
+            _ => false,
+        }
+    }
+
+    fn is_valid_cardano(address: &str) -> bool {
+        match bech32::decode(address) {
+            Ok((hrp, data)) => hrp.as_str() == "addr" && matches!((data.len(), data.first()), (29, Some(0x61)) | (57, Some(0x01))),
+            Err(_) => false,
+        }
+    }
+
+    fn is_valid_move(address: &str) -> bool {
+        address
+            .strip_prefix("0x")
+            .is_some_and(|digits| digits.len() == 64 && digits.chars().all(|c| c.is_ascii_hexdigit()))
+    }
+
+    fn is_valid_near(account_id: &str) -> bool {
+        (2..=64).contains(&account_id.len())
+            && !account_id.starts_with(['-', '_'])
+            && account_id
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || "-_.".contains(c))
+    }
+
+    #[test]
+    fn test_helpers_accept_fixture_addresses() {
+        let tron = Fixtures::get("tron").unwrap().address;
+        assert_eq!(decode_base58check(tron).unwrap().0, 0x41);
+        assert!(is_valid_ss58(Fixtures::get("polkadot").unwrap().address, 0));
+        assert!(is_valid_cardano(Fixtures::get("cardano").unwrap().address));
+        assert!(is_valid_move(Fixtures::get("sui").unwrap().address));
+        assert!(is_valid_near(Fixtures::get("near").unwrap().address));
+
+        let registry = ChainRegistry::with_defaults();
+        for name in ["bitcoin_p2pkh", "bitcoin_p2sh_p2wpkh", "bitcoin_p2wpkh", "bitcoin_p2tr"] {
+            assert!(registry.validate(Chain::Bitcoin, Fixtures::get(name).unwrap().address).is_ok());
+        }
+        assert!(registry.validate(Chain::Ton, Fixtures::get("ton").unwrap().address).is_ok());
+    }
+
+    proptest! {
+        #[test]
+        fn test_registry_chains(
+            (chain, valid, invalid) in prop::sample::select(Chain::ALL.to_vec())
+                .prop_flat_map(|chain| (Just(chain), valid_address(chain).unwrap(), invalid_address(chain).unwrap()))
+        ) {
+            let registry = ChainRegistry::with_defaults();
+            prop_assert!(registry.validate(chain, &valid).is_ok(), "{} rejected {}", chain, valid);
+            prop_assert!(registry.validate(chain, &invalid).is_err(), "{} accepted {}", chain, invalid);
+        }
+
+        #[test]
+        fn test_bitcoin_testnet(address in valid_bitcoin_testnet_address()) {
+            let testnet = walletd_traits::BitcoinValidator::testnet();
+            prop_assert!(walletd_traits::AddressValidator::validate(&testnet, &address).is_ok());
+        }
+
+        #[test]
+        fn test_osmosis_prefix(valid in valid_cosmos_address("osmo"), invalid in invalid_cosmos_address("osmo")) {
+            let validator = walletd_traits::CosmosValidator::new("osmo");
+            prop_assert!(walletd_traits::AddressValidator::validate(&validator, &valid).is_ok());
+            prop_assert!(walletd_traits::AddressValidator::validate(&validator, &invalid).is_err());
+        }
+
+        #[test]
+        fn test_tron(valid in valid_tron_address(), invalid in invalid_tron_address()) {
+            prop_assert_eq!(decode_base58check(&valid).map(|(v, _)| v), Some(0x41));
+            prop_assert!(decode_base58check(&invalid).map(|(v, _)| v) != Some(0x41));
+        }
+
+        #[test]
+        fn test_polkadot(valid in valid_polkadot_address(2), invalid in invalid_polkadot_address(2)) {
+            prop_assert!(is_valid_ss58(&valid, 2));
+            prop_assert!(!is_valid_ss58(&invalid, 2));
+        }
+
+        #[test]
+        fn test_cardano(valid in valid_cardano_address(), invalid in invalid_cardano_address()) {
+            prop_assert!(is_valid_cardano(&valid));
+            prop_assert!(!is_valid_cardano(&invalid));
+        }
+
+        #[test]
+        fn test_move(valid in valid_move_address(), invalid in invalid_move_address()) {
+            prop_assert!(is_valid_move(&valid));
+            prop_assert!(!is_valid_move(&invalid));
+        }
+
+        #[test]
+        fn test_near(valid in valid_near_account_id(), invalid in invalid_near_account_id()) {
+            prop_assert!(is_valid_near(&valid));
+            prop_assert!(!is_valid_near(&invalid));
+        }
+    }
+}
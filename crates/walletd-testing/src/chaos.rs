@@ -0,0 +1,598 @@
+//! Fault-injecting JSON-RPC endpoint
+//!
+//! [`ChaosProvider`] runs a local HTTP endpoint that answers JSON-RPC
+//! requests, either from canned responses or by forwarding to a real
+//! upstream, and injects faults at configurable rates. Because the faults
+//! happen on the wire, they exercise the same retry, failover and circuit
+//! breaker paths in walletd-provider that a misbehaving node would.
+//!
+//! ```rust,ignore
+//! use walletd_testing::chaos::{ChaosProvider, Fault};
+//!
+//! let chaos = ChaosProvider::new()
+//!     .with_response("eth_blockNumber", json!("0x10"))
+//!     .with_fault(Fault::RateLimited, 0.3)
+//!     .with_fault(Fault::MalformedJson, 0.05)
+//!     .start()
+//!     .await?;
+//! let provider = HttpProvider::new(chaos.provider_config().with_max_retries(5))?;
+//! ```
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+use walletd_provider::{ProviderConfig, RpcClient};
+
+/// A fault the endpoint can inject into a response
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Fault {
+    /// Hold the connection open without answering until the client gives up
+    Timeout,
+    /// HTTP 429, with `Retry-After` if configured
+    RateLimited,
+    /// HTTP 503 with an empty body
+    ServerError,
+    /// HTTP 200 with a body that is not valid JSON
+    MalformedJson,
+    /// Promise a full body but close the connection halfway through it
+    PartialBody,
+    /// Close the connection without sending anything
+    ConnectionDrop,
+}
+
+impl Fault {
+    /// Every fault kind
+    pub const ALL: [Fault; 6] = [
+        Fault::Timeout,
+        Fault::RateLimited,
+        Fault::ServerError,
+        Fault::MalformedJson,
+        Fault::PartialBody,
+        Fault::ConnectionDrop,
+    ];
+}
+
+/// Request and fault counts seen by a [`ChaosHandle`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChaosStats {
+    /// Requests received
+    pub requests: u64,
+    /// Requests answered normally
+    pub passed: u64,
+    /// Faults injected, by kind
+    pub injected: HashMap<Fault, u64>,
+}
+
+impl ChaosStats {
+    /// Number of times `fault` was injected
+    pub fn injected(&self, fault: Fault) -> u64 {
+        self.injected.get(&fault).copied().unwrap_or_default()
+    }
+
+    /// Total faults injected
+    pub fn total_injected(&self) -> u64 {
+        self.injected.values().sum()
+    }
+}
+
+/// Builder for a fault-injecting JSON-RPC endpoint
+#[derive(Debug, Clone)]
+pub struct ChaosProvider {
+    upstream: Option<String>,
+    responses: HashMap<String, Value>,
+    rates: Vec<(Fault, f64)>,
+    script: Vec<Option<Fault>>,
+    timeout_delay: Duration,
+    retry_after: Option<u64>,
+    seed: u64,
+}
+
+impl Default for ChaosProvider {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ChaosProvider {
+    /// Creates an endpoint with no faults and no canned responses
+    pub fn new() -> Self {
+        Self {
+            upstream: None,
+            responses: HashMap::new(),
+            rates: Vec::new(),
+            script: Vec::new(),
+            timeout_delay: Duration::from_secs(30),
+            retry_after: None,
+            seed: 0,
+        }
+    }
+
+    /// Forwards requests that are not faulted to `url`
+    ///
+    /// Canned responses still take precedence for their methods.
+    pub fn with_upstream(mut self, url: impl Into<String>) -> Self {
+        self.upstream = Some(url.into());
+        self
+    }
+
+    /// Answers `method` with `result`
+    ///
+    /// Methods with no canned response and no upstream get a JSON-RPC
+    /// "method not found" error.
+    pub fn with_response(mut self, method: impl Into<String>, result: Value) -> Self {
+        self.responses.insert(method.into(), result);
+        self
+    }
+
+    /// Injects `fault` into a fraction `rate` (0.0 to 1.0) of requests
+    ///
+    /// Rates of different faults add up; their sum is capped at 1.
+    pub fn with_fault(mut self, fault: Fault, rate: f64) -> Self {
+        set_rate(&mut self.rates, fault, rate);
+        self
+    }
+
+    /// Plays `script` for the first requests before random faults apply
+    ///
+    /// `None` entries are answered normally, so `[Some(RateLimited), None]`
+    /// throttles the first request and lets the second through.
+    pub fn with_script(mut self, script: impl IntoIterator<Item = Option<Fault>>) -> Self {
+        self.script = script.into_iter().collect();
+        self
+    }
+
+    /// Sets how long [`Fault::Timeout`] holds the connection
+    pub fn with_timeout_delay(mut self, delay: Duration) -> Self {
+        self.timeout_delay = delay;
+        self
+    }
+
+    /// Sends `Retry-After: secs` with [`Fault::RateLimited`]
+    pub fn with_retry_after(mut self, secs: u64) -> Self {
+        self.retry_after = Some(secs);
+        self
+    }
+
+    /// Seeds the fault picker so runs are reproducible
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Binds a local port and starts serving
+    pub async fn start(self) -> std::io::Result<ChaosHandle> {
+        let listener = TcpListener::bind(("127.0.0.1", 0)).await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let client = RpcClient::new().map_err(std::io::Error::other)?;
+        let state = Arc::new(State {
+            rng: Mutex::new(StdRng::seed_from_u64(self.seed)),
+            rates: Mutex::new(self.rates),
+            script: Mutex::new(self.script.into_iter().rev().collect()),
+            stats: Mutex::new(ChaosStats::default()),
+            responses: self.responses,
+            upstream: self.upstream,
+            timeout_delay: self.timeout_delay,
+            retry_after: self.retry_after,
+            client,
+        });
+
+        let server_state = state.clone();
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(serve(stream, server_state.clone()));
+            }
+        });
+        Ok(ChaosHandle { url, state, server })
+    }
+}
+
+fn set_rate(rates: &mut Vec<(Fault, f64)>, fault: Fault, rate: f64) {
+    let rate = rate.clamp(0.0, 1.0);
+    match rates.iter_mut().find(|(f, _)| *f == fault) {
+        Some(entry) => entry.1 = rate,
+        None => rates.push((fault, rate)),
+    }
+}
+
+/// Picks the fault for one request from cumulative rates
+fn pick(rates: &[(Fault, f64)], roll: f64) -> Option<Fault> {
+    let mut threshold = 0.0;
+    for (fault, rate) in rates {
+        threshold += rate;
+        if roll < threshold {
+            return Some(*fault);
+        }
+    }
+    None
+}
+
+struct State {
+    rng: Mutex<StdRng>,
+    rates: Mutex<Vec<(Fault, f64)>>,
+    /// Remaining scripted outcomes, next one last
+    script: Mutex<Vec<Option<Fault>>>,
+    stats: Mutex<ChaosStats>,
+    responses: HashMap<String, Value>,
+    upstream: Option<String>,
+    timeout_delay: Duration,
+    retry_after: Option<u64>,
+    client: RpcClient,
+}
+
+impl State {
+    fn next_fault(&self) -> Option<Fault> {
+        let fault = match self.script.lock().unwrap().pop() {
+            Some(scripted) => scripted,
+            None => {
+                let roll = self.rng.lock().unwrap().gen::<f64>();
+                pick(&self.rates.lock().unwrap(), roll)
+            }
+        };
+        let mut stats = self.stats.lock().unwrap();
+        stats.requests += 1;
+        match fault {
+            Some(fault) => *stats.injected.entry(fault).or_default() += 1,
+            None => stats.passed += 1,
+        }
+        fault
+    }
+
+    async fn answer(&self, request: &Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request["method"].as_str().unwrap_or_default();
+        if let Some(result) = self.responses.get(method) {
+            return json!({"jsonrpc": "2.0", "id": id, "result": result});
+        }
+        let Some(upstream) = &self.upstream else {
+            return rpc_error(id, -32601, &format!("method not found: {}", method));
+        };
+        match self.client.post_json::<Value>(upstream, request).await {
+            Ok(response) => response,
+            Err(e) => rpc_error(id, -32603, &format!("upstream failed: {}", e)),
+        }
+    }
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({"jsonrpc": "2.0", "id": id, "error": {"code": code, "message": message}})
+}
+
+/// A running [`ChaosProvider`]; the endpoint stops when this is dropped
+pub struct ChaosHandle {
+    url: String,
+    state: Arc<State>,
+    server: JoinHandle<()>,
+}
+
+impl ChaosHandle {
+    /// URL of the endpoint
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Provider configuration pointing at the endpoint, with caching and
+    /// background health checks disabled
+    pub fn provider_config(&self) -> ProviderConfig {
+        ProviderConfig::new(self.url.clone())
+            .with_name("chaos")
+            .with_cache(false)
+            .with_health_check_interval(0)
+    }
+
+    /// Changes the rate of `fault` while the endpoint is running
+    pub fn set_fault(&self, fault: Fault, rate: f64) {
+        set_rate(&mut self.state.rates.lock().unwrap(), fault, rate);
+    }
+
+    /// Stops injecting faults, including any remaining script
+    pub fn heal(&self) {
+        self.state.rates.lock().unwrap().clear();
+        self.state.script.lock().unwrap().clear();
+    }
+
+    /// Request and fault counts so far
+    pub fn stats(&self) -> ChaosStats {
+        self.state.stats.lock().unwrap().clone()
+    }
+}
+
+impl Drop for ChaosHandle {
+    fn drop(&mut self) {
+        self.server.abort();
+    }
+}
+
+// ============================================================================
+// HTTP
+// ============================================================================
+
+async fn serve(mut stream: TcpStream, state: Arc<State>) {
+    let _ = stream.set_nodelay(true);
+    let Some(body) = read_request(&mut stream).await else {
+        return;
+    };
+    let fault = state.next_fault();
+    let response = match fault {
+        Some(Fault::ConnectionDrop) => return,
+        Some(Fault::Timeout) => {
+            tokio::time::sleep(state.timeout_delay).await;
+            return;
+        }
+        Some(Fault::RateLimited) => {
+            let retry_after = state
+                .retry_after
+                .map(|secs| format!("Retry-After: {}\r\n", secs))
+                .unwrap_or_default();
+            http_response("429 Too Many Requests", &retry_after, b"")
+        }
+        Some(Fault::ServerError) => http_response("503 Service Unavailable", "", b""),
+        Some(Fault::MalformedJson) => {
+            http_response("200 OK", "", br#"{"jsonrpc":"2.0","id":1,"result":"0x"#)
+        }
+        Some(Fault::PartialBody) => {
+            let full = answer_bytes(&state, &body).await;
+            let mut response = http_response("200 OK", "", &full);
+            response.truncate(response.len() - full.len() / 2 - 1);
+            response
+        }
+        None => http_response("200 OK", "", &answer_bytes(&state, &body).await),
+    };
+    let _ = stream.write_all(&response).await;
+    let _ = stream.shutdown().await;
+}
+
+async fn answer_bytes(state: &State, body: &[u8]) -> Vec<u8> {
+    let response = match serde_json::from_slice::<Value>(body) {
+        Ok(request) => state.answer(&request).await,
+        Err(e) => rpc_error(Value::Null, -32700, &format!("parse error: {}", e)),
+    };
+    serde_json::to_vec(&response).unwrap_or_default()
+}
+
+fn http_response(status: &str, extra_headers: &str, body: &[u8]) -> Vec<u8> {
+    let mut response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n",
+        status,
+        body.len(),
+        extra_headers
+    )
+    .into_bytes();
+    response.extend_from_slice(body);
+    response
+}
+
+/// Reads one HTTP request and returns its body
+async fn read_request(stream: &mut TcpStream) -> Option<Vec<u8>> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+        if let Some(pos) = buf.windows(4).position(|w| w == b"\r\n\r\n") {
+            break pos + 4;
+        }
+    };
+    let headers = String::from_utf8_lossy(&buf[..header_end]);
+    let content_length = headers
+        .lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(name, _)| name.trim().eq_ignore_ascii_case("content-length"))
+        .and_then(|(_, value)| value.trim().parse::<usize>().ok())
+        .unwrap_or_default();
+    while buf.len() < header_end + content_length {
+        let n = stream.read(&mut chunk).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    }
+    Some(buf[header_end..header_end + content_length].to_vec())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_provider::{CircuitBreakerConfig, CircuitState, HttpProvider, ProviderError};
+
+    fn block_number() -> ChaosProvider {
+        ChaosProvider::new().with_response("eth_blockNumber", json!("0x10"))
+    }
+
+    async fn call(provider: &HttpProvider) -> walletd_provider::Result<String> {
+        provider.rpc_call("eth_blockNumber", ()).await
+    }
+
+    #[test]
+    fn test_pick() {
+        let rates = [(Fault::RateLimited, 0.25), (Fault::ServerError, 0.25)];
+        assert_eq!(pick(&rates, 0.1), Some(Fault::RateLimited));
+        assert_eq!(pick(&rates, 0.3), Some(Fault::ServerError));
+        assert_eq!(pick(&rates, 0.6), None);
+        assert_eq!(pick(&[], 0.0), None);
+    }
+
+    #[tokio::test]
+    async fn test_healthy_passthrough() {
+        let chaos = block_number().start().await.unwrap();
+        let provider = HttpProvider::new(chaos.provider_config()).unwrap();
+        assert_eq!(call(&provider).await.unwrap(), "0x10");
+
+        let err = provider
+            .rpc_call::<_, String>("eth_chainId", ())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, ProviderError::RpcError { code: -32601, .. }));
+        assert_eq!(chaos.stats().passed, 2);
+    }
+
+    #[tokio::test]
+    async fn test_upstream_forwarding() {
+        let upstream = block_number().start().await.unwrap();
+        let chaos = ChaosProvider::new()
+            .with_upstream(upstream.url())
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(chaos.provider_config()).unwrap();
+        assert_eq!(call(&provider).await.unwrap(), "0x10");
+        assert_eq!(upstream.stats().requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limits_are_retried() {
+        let chaos = block_number()
+            .with_script([Some(Fault::RateLimited), Some(Fault::ServerError), None])
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(
+            chaos
+                .provider_config()
+                .with_max_retries(3)
+                .with_retry_delay(1),
+        )
+        .unwrap();
+        assert_eq!(call(&provider).await.unwrap(), "0x10");
+
+        let stats = chaos.stats();
+        assert_eq!(stats.requests, 3);
+        assert_eq!(stats.injected(Fault::RateLimited), 1);
+        assert_eq!(stats.injected(Fault::ServerError), 1);
+    }
+
+    #[tokio::test]
+    async fn test_retry_after_header() {
+        let chaos = block_number()
+            .with_fault(Fault::RateLimited, 1.0)
+            .with_retry_after(7)
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(chaos.provider_config().with_max_retries(0)).unwrap();
+        let err = call(&provider).await.unwrap_err();
+        assert!(err.is_rate_limited());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(7)));
+    }
+
+    #[tokio::test]
+    async fn test_malformed_json_is_not_retried() {
+        let chaos = block_number()
+            .with_fault(Fault::MalformedJson, 1.0)
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(chaos.provider_config().with_max_retries(3)).unwrap();
+        assert!(matches!(call(&provider).await, Err(ProviderError::Json(_))));
+        assert_eq!(chaos.stats().requests, 1);
+    }
+
+    #[tokio::test]
+    async fn test_broken_connections_fail() {
+        for fault in [Fault::PartialBody, Fault::ConnectionDrop] {
+            let chaos = block_number().with_fault(fault, 1.0).start().await.unwrap();
+            let provider = HttpProvider::new(chaos.provider_config().with_max_retries(0)).unwrap();
+            assert!(call(&provider).await.is_err(), "{:?} should fail", fault);
+            assert_eq!(chaos.stats().injected(fault), 1);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_timeout_then_recovery() {
+        let chaos = block_number()
+            .with_script([Some(Fault::Timeout)])
+            .with_timeout_delay(Duration::from_secs(5))
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(
+            chaos
+                .provider_config()
+                .with_timeout(1)
+                .with_max_retries(2)
+                .with_retry_delay(1),
+        )
+        .unwrap();
+        assert_eq!(call(&provider).await.unwrap(), "0x10");
+        assert_eq!(chaos.stats().injected(Fault::Timeout), 1);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_and_recovers() {
+        let chaos = block_number()
+            .with_fault(Fault::ServerError, 1.0)
+            .start()
+            .await
+            .unwrap();
+        let provider = HttpProvider::new(
+            chaos
+                .provider_config()
+                .with_max_retries(0)
+                .with_circuit_breaker(
+                    CircuitBreakerConfig::default()
+                        .with_failure_threshold(3)
+                        .with_success_threshold(1)
+                        .with_reset_timeout(Duration::from_millis(50)),
+                ),
+        )
+        .unwrap();
+        for _ in 0..3 {
+            assert!(call(&provider).await.is_err());
+        }
+        assert_eq!(provider.stats().await[0].circuit_state, CircuitState::Open);
+        assert!(matches!(
+            call(&provider).await,
+            Err(ProviderError::CircuitOpen { .. })
+        ));
+        assert_eq!(chaos.stats().requests, 3);
+
+        chaos.heal();
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(call(&provider).await.unwrap(), "0x10");
+        assert_eq!(
+            provider.stats().await[0].circuit_state,
+            CircuitState::Closed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_rates_are_seeded() {
+        let run = || async {
+            let chaos = block_number()
+                .with_fault(Fault::ServerError, 0.3)
+                .with_seed(42)
+                .start()
+                .await
+                .unwrap();
+            let provider = HttpProvider::new(
+                chaos
+                    .provider_config()
+                    .with_max_retries(0)
+                    .with_circuit_breaker(
+                        CircuitBreakerConfig::default().with_failure_threshold(1000),
+                    ),
+            )
+            .unwrap();
+            let mut outcomes = Vec::new();
+            // HttpProvider allows bursts of 20 before pacing to 10 rps
+            for _ in 0..20 {
+                outcomes.push(call(&provider).await.is_ok());
+            }
+            (outcomes, chaos.stats())
+        };
+        let (first, stats) = run().await;
+        let (second, _) = run().await;
+        assert_eq!(first, second);
+        let failures = stats.injected(Fault::ServerError);
+        assert!((1..=12).contains(&failures), "{} failures", failures);
+        assert_eq!(stats.passed + failures, 20);
+    }
+}
@@ -0,0 +1,668 @@
+//! Testnet faucets for self-funding tests and demos
+//!
+//! [`FaucetClient`] asks the public faucets for test funds, spaces requests
+//! to each faucet by its [`cooldown`](Faucet::cooldown), retries when the
+//! faucet answers HTTP 429, and polls the chain until the funding
+//! transaction confirms.
+//!
+//! ```rust,ignore
+//! use walletd_testing::faucet::{Faucet, FaucetClient};
+//!
+//! let faucets = FaucetClient::new()?;
+//! let drip = faucets.fund(Faucet::SolanaDevnet, &address).await?;
+//! println!("funded by {:?}", drip.tx_ids);
+//! ```
+//!
+//! Fuji and Sepolia only have web faucets behind a captcha. Point
+//! [`FaucetClient::with_endpoint`] at a drip service that accepts
+//! `POST {"address", "amount"}` and answers `{"txHash": "0x…"}` to use them.
+
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use walletd_provider::{presets, ProviderError, RpcClient};
+
+/// How long [`FaucetClient::fund`] waits for the funding transaction
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(90);
+
+const AVALANCHE_FUJI_RPC: &str = "https://api.avax-test.network/ext/bc/C/rpc";
+
+/// Errors from requesting test funds
+#[derive(Debug, thiserror::Error)]
+pub enum FaucetError {
+    /// The faucet is web-only and no drip endpoint was configured
+    #[error("{faucet} has no faucet API; fund manually at {url} or set an endpoint")]
+    NoEndpoint {
+        /// Faucet
+        faucet: Faucet,
+        /// Web faucet
+        url: &'static str,
+    },
+
+    /// The faucet is still rate limiting after every retry
+    #[error("faucet rate limited, retry after {retry_after:?}")]
+    RateLimited {
+        /// Delay the faucet asked for
+        retry_after: Option<Duration>,
+    },
+
+    /// The faucet refused the request
+    #[error("faucet refused: {0}")]
+    Rejected(String),
+
+    /// The funding transaction failed on chain
+    #[error("funding transaction failed: {0}")]
+    Failed(String),
+
+    /// The funding transaction did not confirm in time
+    #[error("funding not confirmed after {secs}s")]
+    Timeout {
+        /// Timeout in seconds
+        secs: u64,
+    },
+
+    /// HTTP error talking to a faucet or node
+    #[error("HTTP error: {0}")]
+    Http(String),
+
+    /// JSON-RPC error talking to a faucet or node
+    #[error(transparent)]
+    Provider(#[from] ProviderError),
+}
+
+/// Result type for faucet operations
+pub type Result<T> = std::result::Result<T, FaucetError>;
+
+/// A known testnet faucet
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Faucet {
+    /// Aptos devnet
+    AptosDevnet,
+    /// Aptos testnet
+    AptosTestnet,
+    /// Sui devnet
+    SuiDevnet,
+    /// Sui testnet
+    SuiTestnet,
+    /// Solana devnet (`requestAirdrop`)
+    SolanaDevnet,
+    /// Avalanche Fuji C-Chain (web only)
+    Fuji,
+    /// Ethereum Sepolia (web only)
+    Sepolia,
+}
+
+impl Faucet {
+    /// Every faucet
+    pub const ALL: [Faucet; 7] = [
+        Faucet::AptosDevnet,
+        Faucet::AptosTestnet,
+        Faucet::SuiDevnet,
+        Faucet::SuiTestnet,
+        Faucet::SolanaDevnet,
+        Faucet::Fuji,
+        Faucet::Sepolia,
+    ];
+
+    /// Display name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Faucet::AptosDevnet => "Aptos devnet",
+            Faucet::AptosTestnet => "Aptos testnet",
+            Faucet::SuiDevnet => "Sui devnet",
+            Faucet::SuiTestnet => "Sui testnet",
+            Faucet::SolanaDevnet => "Solana devnet",
+            Faucet::Fuji => "Avalanche Fuji",
+            Faucet::Sepolia => "Ethereum Sepolia",
+        }
+    }
+
+    /// Faucet URL: the API for Aptos, Sui and Solana, the web page otherwise
+    pub fn url(&self) -> &'static str {
+        match self {
+            Faucet::AptosDevnet => "https://faucet.devnet.aptoslabs.com",
+            Faucet::AptosTestnet => "https://faucet.testnet.aptoslabs.com",
+            Faucet::SuiDevnet => "https://faucet.devnet.sui.io",
+            Faucet::SuiTestnet => "https://faucet.testnet.sui.io",
+            Faucet::SolanaDevnet => "https://api.devnet.solana.com",
+            Faucet::Fuji => "https://faucet.avax.network",
+            Faucet::Sepolia => "https://sepoliafaucet.com",
+        }
+    }
+
+    /// Node polled for the funding transaction's status
+    pub fn rpc_url(&self) -> String {
+        match self {
+            Faucet::AptosDevnet => "https://fullnode.devnet.aptoslabs.com/v1".to_string(),
+            Faucet::AptosTestnet => "https://fullnode.testnet.aptoslabs.com/v1".to_string(),
+            Faucet::SuiDevnet => "https://fullnode.devnet.sui.io:443".to_string(),
+            Faucet::SuiTestnet => "https://fullnode.testnet.sui.io:443".to_string(),
+            Faucet::SolanaDevnet => presets::solana_devnet().url,
+            Faucet::Fuji => AVALANCHE_FUJI_RPC.to_string(),
+            Faucet::Sepolia => presets::ethereum_sepolia().url,
+        }
+    }
+
+    /// Amount requested by [`FaucetClient::request`], in the chain's
+    /// smallest unit (Sui's faucet always sends a fixed amount)
+    pub fn default_amount(&self) -> u64 {
+        match self {
+            // 1 APT
+            Faucet::AptosDevnet | Faucet::AptosTestnet => 100_000_000,
+            // 1 SUI, 1 SOL
+            Faucet::SuiDevnet | Faucet::SuiTestnet | Faucet::SolanaDevnet => 1_000_000_000,
+            // 0.1 AVAX / ETH
+            Faucet::Fuji | Faucet::Sepolia => 100_000_000_000_000_000,
+        }
+    }
+
+    /// Minimum time between two requests to this faucet
+    pub fn cooldown(&self) -> Duration {
+        match self {
+            Faucet::AptosDevnet | Faucet::AptosTestnet => Duration::from_secs(2),
+            Faucet::SuiDevnet | Faucet::SuiTestnet | Faucet::SolanaDevnet => {
+                Duration::from_secs(10)
+            }
+            Faucet::Fuji | Faucet::Sepolia => Duration::from_secs(60),
+        }
+    }
+
+    /// Whether the public faucet has an API [`FaucetClient`] can call
+    pub fn has_api(&self) -> bool {
+        !matches!(self, Faucet::Fuji | Faucet::Sepolia)
+    }
+}
+
+impl fmt::Display for Faucet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Funds sent by a faucet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Drip {
+    /// Faucet that sent the funds
+    pub faucet: Faucet,
+    /// Funded address
+    pub address: String,
+    /// Amount requested, in the chain's smallest unit
+    pub amount: u64,
+    /// Funding transactions (hashes, digests or signatures)
+    pub tx_ids: Vec<String>,
+}
+
+/// Where a drip's funding transactions are
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DripStatus {
+    /// Not yet executed or confirmed
+    Pending,
+    /// Every transaction confirmed
+    Confirmed,
+    /// A transaction failed
+    Failed(String),
+}
+
+/// Requests test funds from the known faucets
+pub struct FaucetClient {
+    http: reqwest::Client,
+    rpc: RpcClient,
+    endpoints: HashMap<Faucet, String>,
+    /// Earliest time each faucet may be asked again
+    next_request: Mutex<HashMap<Faucet, Instant>>,
+    max_retries: u32,
+    poll_interval: Duration,
+}
+
+impl FaucetClient {
+    /// Creates a client for the public faucets
+    pub fn new() -> Result<Self> {
+        let http = reqwest::Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .map_err(|e| FaucetError::Http(e.to_string()))?;
+        Ok(Self {
+            http,
+            rpc: RpcClient::new()?,
+            endpoints: HashMap::new(),
+            next_request: Mutex::new(HashMap::new()),
+            max_retries: 3,
+            poll_interval: Duration::from_secs(1),
+        })
+    }
+
+    /// Sends requests for `faucet` to `url` instead of the public faucet
+    ///
+    /// Required for Fuji and Sepolia; for the others the endpoint must speak
+    /// the same API as the public faucet.
+    pub fn with_endpoint(mut self, faucet: Faucet, url: impl Into<String>) -> Self {
+        self.endpoints.insert(faucet, url.into());
+        self
+    }
+
+    /// Sets how often a rate-limited request is retried (default 3)
+    pub fn with_max_retries(mut self, retries: u32) -> Self {
+        self.max_retries = retries;
+        self
+    }
+
+    /// Sets how often [`wait`](Self::wait) checks the chain (default 1s)
+    pub fn with_poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Requests the faucet's [default amount](Faucet::default_amount)
+    pub async fn request(&self, faucet: Faucet, address: &str) -> Result<Drip> {
+        self.request_amount(faucet, address, faucet.default_amount())
+            .await
+    }
+
+    /// Requests `amount` (in the chain's smallest unit) for `address`
+    pub async fn request_amount(&self, faucet: Faucet, address: &str, amount: u64) -> Result<Drip> {
+        let endpoint = self.endpoint(faucet)?;
+        let mut attempt = 0;
+        loop {
+            self.throttle(faucet).await;
+            match self.send(faucet, &endpoint, address, amount).await {
+                Err(FaucetError::RateLimited { retry_after }) if attempt < self.max_retries => {
+                    attempt += 1;
+                    tokio::time::sleep(retry_after.unwrap_or_else(|| faucet.cooldown())).await;
+                }
+                result => {
+                    let tx_ids = result?;
+                    return Ok(Drip {
+                        faucet,
+                        address: address.to_string(),
+                        amount,
+                        tx_ids,
+                    });
+                }
+            }
+        }
+    }
+
+    /// Checks the drip's funding transactions
+    pub async fn status(&self, drip: &Drip) -> Result<DripStatus> {
+        let rpc_url = drip.faucet.rpc_url();
+        let mut confirmed = true;
+        for tx_id in &drip.tx_ids {
+            match self.tx_status(drip.faucet, &rpc_url, tx_id).await? {
+                DripStatus::Failed(reason) => return Ok(DripStatus::Failed(reason)),
+                DripStatus::Pending => confirmed = false,
+                DripStatus::Confirmed => {}
+            }
+        }
+        Ok(if confirmed {
+            DripStatus::Confirmed
+        } else {
+            DripStatus::Pending
+        })
+    }
+
+    /// Polls until the drip confirms, fails or `timeout` passes
+    pub async fn wait(&self, drip: &Drip, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            match self.status(drip).await? {
+                DripStatus::Confirmed => return Ok(()),
+                DripStatus::Failed(reason) => return Err(FaucetError::Failed(reason)),
+                DripStatus::Pending if Instant::now() >= deadline => {
+                    return Err(FaucetError::Timeout {
+                        secs: timeout.as_secs(),
+                    })
+                }
+                DripStatus::Pending => tokio::time::sleep(self.poll_interval).await,
+            }
+        }
+    }
+
+    /// Requests funds and waits up to [`DEFAULT_TIMEOUT`] for them to land
+    pub async fn fund(&self, faucet: Faucet, address: &str) -> Result<Drip> {
+        let drip = self.request(faucet, address).await?;
+        self.wait(&drip, DEFAULT_TIMEOUT).await?;
+        Ok(drip)
+    }
+
+    fn endpoint(&self, faucet: Faucet) -> Result<String> {
+        match self.endpoints.get(&faucet) {
+            Some(url) => Ok(url.trim_end_matches('/').to_string()),
+            None if faucet.has_api() => Ok(faucet.url().to_string()),
+            None => Err(FaucetError::NoEndpoint {
+                faucet,
+                url: faucet.url(),
+            }),
+        }
+    }
+
+    /// Waits until `faucet` may be asked again and reserves the next slot
+    async fn throttle(&self, faucet: Faucet) {
+        let now = Instant::now();
+        let start = {
+            let mut next = self.next_request.lock().unwrap_or_else(|e| e.into_inner());
+            let start = next.get(&faucet).map_or(now, |at| (*at).max(now));
+            next.insert(faucet, start + faucet.cooldown());
+            start
+        };
+        if start > now {
+            tokio::time::sleep(start - now).await;
+        }
+    }
+
+    async fn send(
+        &self,
+        faucet: Faucet,
+        endpoint: &str,
+        address: &str,
+        amount: u64,
+    ) -> Result<Vec<String>> {
+        match faucet {
+            Faucet::AptosDevnet | Faucet::AptosTestnet => {
+                let body = json!({ "address": address, "amount": amount });
+                parse_aptos_drip(&self.post(&format!("{}/fund", endpoint), body).await?)
+            }
+            Faucet::SuiDevnet | Faucet::SuiTestnet => {
+                let body = json!({ "FixedAmountRequest": { "recipient": address } });
+                parse_sui_drip(&self.post(&format!("{}/v2/gas", endpoint), body).await?)
+            }
+            Faucet::SolanaDevnet => {
+                let signature: String = self
+                    .rpc
+                    .rpc_call(endpoint, "requestAirdrop", json!([address, amount]))
+                    .await
+                    .map_err(rate_limited)?;
+                Ok(vec![signature])
+            }
+            Faucet::Fuji | Faucet::Sepolia => {
+                let body = json!({ "address": address, "amount": amount.to_string() });
+                parse_evm_drip(&self.post(endpoint, body).await?)
+            }
+        }
+    }
+
+    async fn post(&self, url: &str, body: Value) -> Result<Value> {
+        let response = self
+            .http
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| FaucetError::Http(e.to_string()))?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok()?.trim().parse().ok())
+                .map(Duration::from_secs);
+            return Err(FaucetError::RateLimited { retry_after });
+        }
+        let text = response
+            .text()
+            .await
+            .map_err(|e| FaucetError::Http(e.to_string()))?;
+        if !status.is_success() {
+            return Err(FaucetError::Rejected(format!(
+                "HTTP {}: {}",
+                status.as_u16(),
+                text.trim()
+            )));
+        }
+        serde_json::from_str(&text)
+            .map_err(|e| FaucetError::Http(format!("invalid response: {}", e)))
+    }
+
+    async fn tx_status(&self, faucet: Faucet, rpc_url: &str, tx_id: &str) -> Result<DripStatus> {
+        match faucet {
+            Faucet::AptosDevnet | Faucet::AptosTestnet => {
+                let url = format!("{}/transactions/by_hash/{}", rpc_url, tx_id);
+                let response = self
+                    .http
+                    .get(&url)
+                    .send()
+                    .await
+                    .map_err(|e| FaucetError::Http(e.to_string()))?;
+                // Not indexed yet
+                if response.status() == reqwest::StatusCode::NOT_FOUND {
+                    return Ok(DripStatus::Pending);
+                }
+                let tx: Value = response
+                    .error_for_status()
+                    .map_err(|e| FaucetError::Http(e.to_string()))?
+                    .json()
+                    .await
+                    .map_err(|e| FaucetError::Http(e.to_string()))?;
+                Ok(aptos_status(&tx))
+            }
+            Faucet::SuiDevnet | Faucet::SuiTestnet => {
+                let params = json!([tx_id, { "showEffects": true }]);
+                match self
+                    .rpc
+                    .rpc_call(rpc_url, "sui_getTransactionBlock", params)
+                    .await
+                {
+                    Ok(tx) => Ok(sui_status(&tx)),
+                    // Unknown digests are an RPC error until the transaction executes
+                    Err(ProviderError::RpcError { .. }) => Ok(DripStatus::Pending),
+                    Err(e) => Err(e.into()),
+                }
+            }
+            Faucet::SolanaDevnet => {
+                let statuses: Value = self
+                    .rpc
+                    .rpc_call(rpc_url, "getSignatureStatuses", json!([[tx_id]]))
+                    .await?;
+                Ok(solana_status(&statuses["value"][0]))
+            }
+            Faucet::Fuji | Faucet::Sepolia => {
+                let receipt: Value = self
+                    .rpc
+                    .rpc_call(rpc_url, "eth_getTransactionReceipt", json!([tx_id]))
+                    .await?;
+                Ok(evm_status(&receipt))
+            }
+        }
+    }
+}
+
+fn rate_limited(e: ProviderError) -> FaucetError {
+    if e.is_rate_limited() {
+        FaucetError::RateLimited {
+            retry_after: e.retry_after(),
+        }
+    } else {
+        e.into()
+    }
+}
+
+/// `{"txn_hashes": [...]}` from the Aptos faucet's `/fund`
+fn parse_aptos_drip(response: &Value) -> Result<Vec<String>> {
+    let hashes = string_list(&response["txn_hashes"], |hash| hash.as_str());
+    if hashes.is_empty() {
+        return Err(FaucetError::Rejected(response.to_string()));
+    }
+    Ok(hashes)
+}
+
+/// The Sui faucet's `/v2/gas` answer; `/v1/gas` answers are accepted too
+fn parse_sui_drip(response: &Value) -> Result<Vec<String>> {
+    if let Some(failure) = response["status"].get("Failure") {
+        return Err(FaucetError::Rejected(failure.to_string()));
+    }
+    if let Some(error) = response["error"].as_str() {
+        return Err(FaucetError::Rejected(error.to_string()));
+    }
+    let coins = if response["coins_sent"].is_array() {
+        &response["coins_sent"]
+    } else {
+        &response["transferredGasObjects"]
+    };
+    let mut digests = string_list(coins, |coin| coin["transferTxDigest"].as_str());
+    digests.dedup();
+    if digests.is_empty() {
+        return Err(FaucetError::Rejected(response.to_string()));
+    }
+    Ok(digests)
+}
+
+/// `{"txHash": "0x…"}` from a drip endpoint
+fn parse_evm_drip(response: &Value) -> Result<Vec<String>> {
+    response["txHash"]
+        .as_str()
+        .map(|hash| vec![hash.to_string()])
+        .ok_or_else(|| FaucetError::Rejected(response.to_string()))
+}
+
+fn string_list<'a>(list: &'a Value, item: impl Fn(&'a Value) -> Option<&'a str>) -> Vec<String> {
+    list.as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(item)
+        .map(str::to_string)
+        .collect()
+}
+
+fn aptos_status(tx: &Value) -> DripStatus {
+    match tx["success"].as_bool() {
+        _ if tx["type"] == "pending_transaction" => DripStatus::Pending,
+        Some(true) => DripStatus::Confirmed,
+        Some(false) => DripStatus::Failed(tx["vm_status"].as_str().unwrap_or("failed").to_string()),
+        None => DripStatus::Pending,
+    }
+}
+
+fn sui_status(tx: &Value) -> DripStatus {
+    let status = &tx["effects"]["status"];
+    match status["status"].as_str() {
+        Some("success") => DripStatus::Confirmed,
+        Some(_) => DripStatus::Failed(status["error"].as_str().unwrap_or("failed").to_string()),
+        None => DripStatus::Pending,
+    }
+}
+
+fn solana_status(status: &Value) -> DripStatus {
+    if status.is_null() {
+        return DripStatus::Pending;
+    }
+    if !status["err"].is_null() {
+        return DripStatus::Failed(status["err"].to_string());
+    }
+    match status["confirmationStatus"].as_str() {
+        Some("confirmed" | "finalized") => DripStatus::Confirmed,
+        _ => DripStatus::Pending,
+    }
+}
+
+fn evm_status(receipt: &Value) -> DripStatus {
+    match receipt["status"].as_str() {
+        Some("0x1") => DripStatus::Confirmed,
+        Some(_) => DripStatus::Failed("reverted".to_string()),
+        None => DripStatus::Pending,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_aptos::AptosNetwork;
+    use walletd_sui::SuiNetwork;
+
+    #[test]
+    fn test_faucets_match_network_configs() {
+        assert_eq!(
+            Some(Faucet::AptosDevnet.url()),
+            AptosNetwork::Devnet.faucet_url()
+        );
+        assert_eq!(
+            Some(Faucet::AptosTestnet.url()),
+            AptosNetwork::Testnet.faucet_url()
+        );
+        assert_eq!(
+            Faucet::AptosDevnet.rpc_url(),
+            AptosNetwork::Devnet.rest_url()
+        );
+        assert_eq!(
+            Some(Faucet::SuiDevnet.url()),
+            SuiNetwork::Devnet.faucet_url()
+        );
+        assert_eq!(
+            Some(Faucet::SuiTestnet.url()),
+            SuiNetwork::Testnet.faucet_url()
+        );
+        assert_eq!(Faucet::SuiTestnet.rpc_url(), SuiNetwork::Testnet.rpc_url());
+        assert_eq!(
+            Faucet::Fuji.rpc_url(),
+            walletd_avalanche::NetworkConfig::fuji().rpc_endpoints[0]
+        );
+
+        let client = FaucetClient::new().unwrap();
+        assert!(matches!(
+            client.endpoint(Faucet::Sepolia),
+            Err(FaucetError::NoEndpoint {
+                url: "https://sepoliafaucet.com",
+                ..
+            })
+        ));
+        let client = client.with_endpoint(Faucet::Sepolia, "http://127.0.0.1:8545/drip/");
+        assert_eq!(
+            client.endpoint(Faucet::Sepolia).unwrap(),
+            "http://127.0.0.1:8545/drip"
+        );
+    }
+
+    #[test]
+    fn test_parses_drips_and_statuses() {
+        let aptos = json!({ "txn_hashes": ["0xaa", "0xbb"] });
+        assert_eq!(parse_aptos_drip(&aptos).unwrap(), vec!["0xaa", "0xbb"]);
+        assert!(parse_aptos_drip(&json!({ "message": "too many" })).is_err());
+
+        let sui = json!({
+            "status": "Success",
+            "coins_sent": [
+                { "amount": 1, "id": "0x1", "transferTxDigest": "D1" },
+                { "amount": 1, "id": "0x2", "transferTxDigest": "D1" }
+            ]
+        });
+        assert_eq!(parse_sui_drip(&sui).unwrap(), vec!["D1"]);
+        let refused = json!({ "status": { "Failure": { "internal": "wallet drained" } } });
+        assert!(matches!(
+            parse_sui_drip(&refused),
+            Err(FaucetError::Rejected(_))
+        ));
+        assert_eq!(
+            parse_evm_drip(&json!({ "txHash": "0xcc" })).unwrap(),
+            vec!["0xcc"]
+        );
+
+        assert_eq!(
+            aptos_status(&json!({ "type": "pending_transaction" })),
+            DripStatus::Pending
+        );
+        assert_eq!(
+            aptos_status(&json!({ "type": "user_transaction", "success": true })),
+            DripStatus::Confirmed
+        );
+        assert_eq!(
+            sui_status(
+                &json!({ "effects": { "status": { "status": "failure", "error": "InsufficientGas" } } })
+            ),
+            DripStatus::Failed("InsufficientGas".into())
+        );
+        assert_eq!(solana_status(&Value::Null), DripStatus::Pending);
+        assert_eq!(
+            solana_status(&json!({ "err": null, "confirmationStatus": "processed" })),
+            DripStatus::Pending
+        );
+        assert_eq!(
+            solana_status(&json!({ "err": null, "confirmationStatus": "finalized" })),
+            DripStatus::Confirmed
+        );
+        assert_eq!(evm_status(&Value::Null), DripStatus::Pending);
+        assert_eq!(
+            evm_status(&json!({ "status": "0x0" })),
+            DripStatus::Failed("reverted".into())
+        );
+    }
+}
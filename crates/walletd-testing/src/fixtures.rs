@@ -0,0 +1,410 @@
+//! Deterministic multi-chain wallet fixtures
+//!
+//! Each [`ChainFixture`] pairs a well-known mnemonic with the key and address
+//! it yields on one chain, so applications can build a wallet from a fixed
+//! key and assert on a fixed address in their own tests:
+//!
+//! ```rust,ignore
+//! use walletd_testing::Fixtures;
+//!
+//! let fixture = Fixtures::get("tron").unwrap();
+//! let wallet = TronWallet::from_private_key(&fixture.private_key_bytes(), config)?;
+//! assert_eq!(wallet.address(), fixture.address);
+//! ```
+//!
+//! Entries derived with [`KeyDerivation::Bip32`] or [`KeyDerivation::Slip10`]
+//! match what hardware and reference wallets produce for the same mnemonic.
+//! Polkadot, Cardano and TON entries pin walletd's own key derivation and are
+//! not expected to match other wallets.
+
+/// The standard 12-word test mnemonic
+pub const TEST_MNEMONIC: &str =
+    "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+
+/// A 24-word test mnemonic, for chains that require 24 words (TON)
+pub const TEST_MNEMONIC_24: &str = "abandon abandon abandon abandon abandon abandon abandon abandon \
+    abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon \
+    abandon abandon abandon art";
+
+/// How a fixture's private key is derived from its mnemonic
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyDerivation {
+    /// BIP-32 secp256k1 derivation from the BIP-39 seed
+    Bip32(&'static str),
+    /// SLIP-10 ed25519 derivation from the BIP-39 seed
+    Slip10(&'static str),
+    /// First 32 bytes of the BIP-39 seed used as an ed25519 key
+    SeedPrefix,
+    /// TON mnemonic scheme: PBKDF2-SHA512 with salt "TON default seed"
+    TonMnemonic,
+}
+
+impl KeyDerivation {
+    /// Derivation path, when the scheme has one
+    pub fn path(&self) -> Option<&'static str> {
+        match self {
+            KeyDerivation::Bip32(path) | KeyDerivation::Slip10(path) => Some(path),
+            KeyDerivation::SeedPrefix | KeyDerivation::TonMnemonic => None,
+        }
+    }
+}
+
+/// A known wallet on one chain
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainFixture {
+    /// Fixture name: the chain, plus the address type where a chain has several
+    pub name: &'static str,
+    /// Mnemonic the wallet is derived from (no passphrase)
+    pub mnemonic: &'static str,
+    /// How the private key is derived
+    pub derivation: KeyDerivation,
+    /// Private key, hex (the 32-byte seed for ed25519 chains)
+    pub private_key: &'static str,
+    /// Expected address, in the chain's canonical form
+    pub address: &'static str,
+}
+
+impl ChainFixture {
+    /// Private key as bytes
+    pub fn private_key_bytes(&self) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        hex::decode_to_slice(self.private_key, &mut key).expect("fixture keys are 32-byte hex");
+        key
+    }
+}
+
+const EVM_KEY: &str = "1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727";
+const EVM_ADDRESS: &str = "0x9858EfFD232B4033E47d90003D41EC34EcaEda94";
+const EVM_PATH: &str = "m/44'/60'/0'/0/0";
+
+const FIXTURES: &[ChainFixture] = &[
+    ChainFixture {
+        name: "bitcoin_p2pkh",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/44'/0'/0'/0/0"),
+        private_key: "e284129cc0922579a535bbf4d1a3b25773090d28c909bc0fed73b5e0222cc372",
+        address: "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+    },
+    ChainFixture {
+        name: "bitcoin_p2sh_p2wpkh",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/49'/0'/0'/0/0"),
+        private_key: "508c73a06f6b6c817238ba61be232f5080ea4616c54f94771156934666d38ee3",
+        address: "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf",
+    },
+    ChainFixture {
+        name: "bitcoin_p2wpkh",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/84'/0'/0'/0/0"),
+        private_key: "4604b4b710fe91f584fff084e1a9159fe4f8408fff380596a604948474ce4fa3",
+        address: "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+    },
+    ChainFixture {
+        name: "bitcoin_p2tr",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/86'/0'/0'/0/0"),
+        private_key: "41f41d69260df4cf277826a9b65a3717e4eeddbeedf637f212ca096576479361",
+        address: "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+    },
+    ChainFixture {
+        name: "ethereum",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32(EVM_PATH),
+        private_key: EVM_KEY,
+        address: EVM_ADDRESS,
+    },
+    ChainFixture {
+        name: "polygon",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32(EVM_PATH),
+        private_key: EVM_KEY,
+        address: EVM_ADDRESS,
+    },
+    ChainFixture {
+        name: "arbitrum",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32(EVM_PATH),
+        private_key: EVM_KEY,
+        address: EVM_ADDRESS,
+    },
+    ChainFixture {
+        name: "avalanche",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32(EVM_PATH),
+        private_key: EVM_KEY,
+        address: EVM_ADDRESS,
+    },
+    ChainFixture {
+        name: "base",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32(EVM_PATH),
+        private_key: EVM_KEY,
+        address: EVM_ADDRESS,
+    },
+    ChainFixture {
+        name: "tron",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/44'/195'/0'/0/0"),
+        private_key: "b5a4cea271ff424d7c31dc12a3e43e401df7a40d7412a15750f3f0b6b5449a28",
+        address: "TUEZSdKsoDHQMeZwihtdoBiN46zxhGWYdH",
+    },
+    ChainFixture {
+        name: "cosmos",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Bip32("m/44'/118'/0'/0/0"),
+        private_key: "c4a48e2fce1481cd3294b4490f6678090ea98d3d0e5cd984558ab0968741b104",
+        address: "cosmos19rl4cm2hmr8afy4kldpxz3fka4jguq0auqdal4",
+    },
+    ChainFixture {
+        name: "solana",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Slip10("m/44'/501'/0'/0'"),
+        private_key: "37df573b3ac4ad5b522e064e25b63ea16bcbe79d449e81a0268d1047948bb445",
+        address: "HAgk14JpMQLgt6rVgv7cBQFJWFto5Dqxi472uT3DKpqk",
+    },
+    ChainFixture {
+        name: "sui",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Slip10("m/44'/784'/0'/0'/0'"),
+        private_key: "8869cb07178bf67e08d7c4abdf45487dbf379c9a452fcec2836854bf4a3d29b0",
+        address: "0x5e93a736d04fbb25737aa40bee40171ef79f65fae833749e3c089fe7cc2161f1",
+    },
+    ChainFixture {
+        name: "aptos",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Slip10("m/44'/637'/0'/0'/0'"),
+        private_key: "cc92c0eaf80206d817f150e21917f797e49cf644a33ac514de3c316baa2f1bf5",
+        address: "0xeb663b681209e7087d681c5d3eed12aaa8e1915e7c87794542c3f96e94b3d3bf",
+    },
+    ChainFixture {
+        name: "near",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::Slip10("m/44'/397'/0'"),
+        private_key: "0c158d858a52316667d03d1d04aad51b3b542cd705215810629b78c501492fba",
+        address: "5510e2b44cae6eb807e3e0e45d579dda058c274abcba15e5cb84636f5d1ee412",
+    },
+    ChainFixture {
+        name: "polkadot",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::SeedPrefix,
+        private_key: "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc1",
+        address: "15TvC1tqBYkXkj9CZhMSYZj6HxTgccnXwHDCt4yTMagA3mnH",
+    },
+    ChainFixture {
+        name: "cardano",
+        mnemonic: TEST_MNEMONIC,
+        derivation: KeyDerivation::SeedPrefix,
+        private_key: "5eb00bbddcf069084889a8ab9155568165f5c453ccb85e70811aaed6f6da5fc1",
+        address: "addr1v8n7xg3ftktmwvysclrj9cj07axd4lu5pms26fj6m7wzklqx3pvag",
+    },
+    ChainFixture {
+        name: "ton",
+        mnemonic: TEST_MNEMONIC_24,
+        derivation: KeyDerivation::TonMnemonic,
+        private_key: "0061d101e6891b8a45f7f8dcc60ba73751d06a3f3ab290d5f21cc0305af9f741",
+        address: "EQCHub00sXV6h-LWSl5CURdvHMmtiDatzS-4GXkuCHCllKMu",
+    },
+];
+
+/// Known-mnemonic wallets for every supported chain
+pub struct Fixtures;
+
+impl Fixtures {
+    /// All fixtures
+    pub fn all() -> &'static [ChainFixture] {
+        FIXTURES
+    }
+
+    /// Looks up a fixture by name (e.g. `ethereum` or `bitcoin_p2wpkh`)
+    pub fn get(name: &str) -> Option<&'static ChainFixture> {
+        FIXTURES.iter().find(|f| f.name == name)
+    }
+
+    /// Fixture names, in a stable order
+    pub fn names() -> Vec<&'static str> {
+        FIXTURES.iter().map(|f| f.name).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip32::{DerivationPath, XPrv};
+    use blake2::digest::consts::{U28, U32};
+    use blake2::{Blake2b, Blake2b512};
+    use hmac::{Hmac, Mac};
+    use k256::elliptic_curve::ops::Reduce;
+    use k256::elliptic_curve::sec1::ToEncodedPoint;
+    use k256::{ProjectivePoint, PublicKey, Scalar, U256};
+    use sha2::{Digest, Sha256, Sha512};
+
+    fn seed(mnemonic: &str) -> [u8; 64] {
+        bip39::Mnemonic::parse(mnemonic).unwrap().to_seed("")
+    }
+
+    fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+        mac.update(data);
+        mac.finalize().into_bytes().into()
+    }
+
+    fn slip10(seed: &[u8], path: &str) -> [u8; 32] {
+        let mut i = hmac_sha512(b"ed25519 seed", seed);
+        for step in path.split('/').skip(1) {
+            let index: u32 = step.trim_end_matches('\'').parse().unwrap();
+            let mut data = vec![0u8];
+            data.extend_from_slice(&i[..32]);
+            data.extend_from_slice(&(index | 0x8000_0000).to_be_bytes());
+            i = hmac_sha512(&i[32..], &data);
+        }
+        i[..32].try_into().unwrap()
+    }
+
+    fn private_key(fixture: &ChainFixture) -> [u8; 32] {
+        match fixture.derivation {
+            KeyDerivation::Bip32(path) => {
+                let path: DerivationPath = path.parse().unwrap();
+                XPrv::derive_from_path(seed(fixture.mnemonic), &path).unwrap().to_bytes()
+            }
+            KeyDerivation::Slip10(path) => slip10(&seed(fixture.mnemonic), path),
+            KeyDerivation::SeedPrefix => seed(fixture.mnemonic)[..32].try_into().unwrap(),
+            KeyDerivation::TonMnemonic => {
+                let mut out = [0u8; 64];
+                pbkdf2::pbkdf2_hmac::<Sha512>(fixture.mnemonic.as_bytes(), b"TON default seed", 100_000, &mut out);
+                out[..32].try_into().unwrap()
+            }
+        }
+    }
+
+    fn hash160(data: &[u8]) -> Vec<u8> {
+        ripemd::Ripemd160::digest(Sha256::digest(data)).to_vec()
+    }
+
+    fn base58check(version: u8, payload: &[u8]) -> String {
+        let mut data = vec![version];
+        data.extend_from_slice(payload);
+        bs58::encode(data).with_check().into_string()
+    }
+
+    fn evm_address(public: &PublicKey) -> String {
+        let point = public.to_encoded_point(false);
+        let lower = hex::encode(&sha3::Keccak256::digest(&point.as_bytes()[1..])[12..]);
+        let hash = sha3::Keccak256::digest(lower.as_bytes());
+        let mut out = String::from("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            out.push(if nibble >= 8 { c.to_ascii_uppercase() } else { c });
+        }
+        out
+    }
+
+    /// BIP-86 key-path-only output key
+    fn taproot_output_key(public: &PublicKey) -> [u8; 32] {
+        let point = public.to_encoded_point(true);
+        let x = &point.as_bytes()[1..];
+        let mut internal = public.to_projective();
+        if point.as_bytes()[0] == 0x03 {
+            internal = -internal;
+        }
+        let tag = Sha256::digest(b"TapTweak");
+        let tweak = Sha256::new().chain_update(tag).chain_update(tag).chain_update(x).finalize();
+        let tweak = <Scalar as Reduce<U256>>::reduce_bytes(&tweak);
+        let output = (internal + ProjectivePoint::GENERATOR * tweak).to_affine();
+        output.to_encoded_point(true).as_bytes()[1..].try_into().unwrap()
+    }
+
+    fn secp256k1_address(name: &str, key: &[u8; 32]) -> String {
+        let public = k256::SecretKey::from_slice(key).unwrap().public_key();
+        let compressed = public.to_encoded_point(true);
+        match name {
+            "bitcoin_p2pkh" => base58check(0x00, &hash160(compressed.as_bytes())),
+            "bitcoin_p2sh_p2wpkh" => {
+                let mut redeem = vec![0x00, 0x14];
+                redeem.extend_from_slice(&hash160(compressed.as_bytes()));
+                base58check(0x05, &hash160(&redeem))
+            }
+            "bitcoin_p2wpkh" => {
+                bech32::segwit::encode_v0(bech32::hrp::BC, &hash160(compressed.as_bytes())).unwrap()
+            }
+            "bitcoin_p2tr" => bech32::segwit::encode_v1(bech32::hrp::BC, &taproot_output_key(&public)).unwrap(),
+            "tron" => {
+                let evm = evm_address(&public);
+                base58check(0x41, &hex::decode(&evm[2..]).unwrap())
+            }
+            "cosmos" => {
+                let hrp = bech32::Hrp::parse("cosmos").unwrap();
+                bech32::encode::<bech32::Bech32>(hrp, &hash160(compressed.as_bytes())).unwrap()
+            }
+            _ => evm_address(&public),
+        }
+    }
+
+    fn ed25519_address(name: &str, public: &[u8; 32]) -> String {
+        match name {
+            "solana" => bs58::encode(public).into_string(),
+            "sui" => {
+                let hash = Blake2b::<U32>::new().chain_update([0x00]).chain_update(public).finalize();
+                format!("0x{}", hex::encode(hash))
+            }
+            "aptos" => {
+                let hash = sha3::Sha3_256::new().chain_update(public).chain_update([0x00]).finalize();
+                format!("0x{}", hex::encode(hash))
+            }
+            "near" => hex::encode(public),
+            "polkadot" => {
+                let mut data = vec![0u8];
+                data.extend_from_slice(public);
+                let checksum = Blake2b512::new().chain_update(b"SS58PRE").chain_update(&data).finalize();
+                data.extend_from_slice(&checksum[..2]);
+                bs58::encode(data).into_string()
+            }
+            "cardano" => {
+                let mut data = vec![0x61];
+                data.extend_from_slice(&Blake2b::<U28>::digest(public));
+                let hrp = bech32::Hrp::parse("addr").unwrap();
+                bech32::encode::<bech32::Bech32>(hrp, &data).unwrap()
+            }
+            other => panic!("no reference address for {}", other),
+        }
+    }
+
+    #[test]
+    fn test_fixture_names_unique() {
+        let mut names = Fixtures::names();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), Fixtures::all().len());
+        assert!(Fixtures::get("ethereum").is_some());
+        assert!(Fixtures::get("dogecoin").is_none());
+    }
+
+    #[test]
+    fn test_fixture_private_keys() {
+        for fixture in Fixtures::all() {
+            assert_eq!(hex::encode(private_key(fixture)), fixture.private_key, "{}", fixture.name);
+        }
+    }
+
+    #[test]
+    fn test_fixture_addresses() {
+        for fixture in Fixtures::all() {
+            let key = fixture.private_key_bytes();
+            let address = match fixture.derivation {
+                KeyDerivation::Bip32(_) => secp256k1_address(fixture.name, &key),
+                // TON addresses hash the wallet contract code, which is out of scope here
+                KeyDerivation::TonMnemonic => continue,
+                _ => {
+                    let signing = ed25519_dalek::SigningKey::from_bytes(&key);
+                    ed25519_address(fixture.name, signing.verifying_key().as_bytes())
+                }
+            };
+            assert_eq!(address, fixture.address, "{}", fixture.name);
+        }
+    }
+
+    #[test]
+    fn test_derivation_path() {
+        assert_eq!(Fixtures::get("solana").unwrap().derivation.path(), Some("m/44'/501'/0'/0'"));
+        assert_eq!(Fixtures::get("ton").unwrap().derivation.path(), None);
+    }
+}
@@ -0,0 +1,225 @@
+//! Golden-file snapshots for serialized bytes
+//!
+//! Compares encoder output against hex files checked into the repo, so a
+//! change to transaction encoding shows up as a failing test rather than a
+//! rejected broadcast. Set `WALLETD_UPDATE_GOLDEN=1` to write the current
+//! output instead of comparing, then review the diff before committing.
+//!
+//! ```rust,ignore
+//! use walletd_testing::golden::Golden;
+//!
+//! let golden = Golden::new(concat!(env!("CARGO_MANIFEST_DIR"), "/golden"));
+//! golden.assert("ethereum_eip1559_transfer", &tx.encoded_for_signing());
+//! ```
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+/// Environment variable that switches [`Golden`] to update mode
+pub const UPDATE_ENV: &str = "WALLETD_UPDATE_GOLDEN";
+
+/// Why a snapshot check failed
+#[derive(Debug, thiserror::Error)]
+pub enum GoldenError {
+    /// No golden file exists for the snapshot
+    #[error("no golden file at {}; rerun with {UPDATE_ENV}=1 to create it", .0.display())]
+    Missing(PathBuf),
+
+    /// The golden file is not valid hex
+    #[error("golden file {} is not valid hex: {source}", .path.display())]
+    Corrupt {
+        /// Golden file path
+        path: PathBuf,
+        /// Decode error
+        source: hex::FromHexError,
+    },
+
+    /// The bytes differ from the golden file
+    #[error("{0}")]
+    Mismatch(Mismatch),
+
+    /// Reading or writing the golden file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Details of a snapshot that no longer matches
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Snapshot name
+    pub name: String,
+    /// Expected bytes from the golden file
+    pub expected: Vec<u8>,
+    /// Bytes produced by the encoder
+    pub actual: Vec<u8>,
+}
+
+impl Mismatch {
+    /// Offset of the first differing byte
+    pub fn first_difference(&self) -> usize {
+        self.expected
+            .iter()
+            .zip(&self.actual)
+            .position(|(a, b)| a != b)
+            .unwrap_or_else(|| self.expected.len().min(self.actual.len()))
+    }
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let offset = self.first_difference();
+        let window = |bytes: &[u8]| {
+            let start = offset.saturating_sub(8);
+            let end = (offset + 24).min(bytes.len());
+            hex::encode(bytes.get(start..end).unwrap_or_default())
+        };
+        writeln!(
+            f,
+            "golden snapshot `{}` changed at byte {} (expected {} bytes, got {})",
+            self.name,
+            offset,
+            self.expected.len(),
+            self.actual.len()
+        )?;
+        writeln!(f, "  expected: ...{}...", window(&self.expected))?;
+        writeln!(f, "  actual:   ...{}...", window(&self.actual))?;
+        write!(f, "rerun with {}=1 if the change is intended", UPDATE_ENV)
+    }
+}
+
+/// A directory of golden snapshots
+#[derive(Debug, Clone)]
+pub struct Golden {
+    dir: PathBuf,
+    update: bool,
+}
+
+impl Golden {
+    /// Uses `dir` for snapshots; update mode follows [`UPDATE_ENV`]
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        let update = std::env::var(UPDATE_ENV).is_ok_and(|v| v != "0" && !v.is_empty());
+        Self {
+            dir: dir.into(),
+            update,
+        }
+    }
+
+    /// Forces update mode on or off
+    pub fn with_update(mut self, update: bool) -> Self {
+        self.update = update;
+        self
+    }
+
+    /// Snapshot directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Path of the golden file for `name`
+    pub fn path(&self, name: &str) -> PathBuf {
+        self.dir.join(format!("{}.hex", name))
+    }
+
+    /// Reads the golden bytes for `name`
+    pub fn read(&self, name: &str) -> Result<Vec<u8>, GoldenError> {
+        let path = self.path(name);
+        let contents = match std::fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Err(GoldenError::Missing(path))
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let hex: String = contents.split_whitespace().collect();
+        hex::decode(hex).map_err(|source| GoldenError::Corrupt { path, source })
+    }
+
+    /// Compares `actual` with the golden file, or writes it in update mode
+    pub fn check(&self, name: &str, actual: &[u8]) -> Result<(), GoldenError> {
+        if self.update {
+            std::fs::create_dir_all(&self.dir)?;
+            std::fs::write(self.path(name), format!("{}\n", hex::encode(actual)))?;
+            return Ok(());
+        }
+        let expected = self.read(name)?;
+        if expected == actual {
+            Ok(())
+        } else {
+            Err(GoldenError::Mismatch(Mismatch {
+                name: name.to_string(),
+                expected,
+                actual: actual.to_vec(),
+            }))
+        }
+    }
+
+    /// Like [`Golden::check`] but panics with a readable diff
+    #[track_caller]
+    pub fn assert(&self, name: &str, actual: &[u8]) {
+        if let Err(e) = self.check(name, actual) {
+            panic!("{}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn scratch(name: &str) -> PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("walletd-golden-{}-{}", name, std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        dir
+    }
+
+    #[test]
+    fn test_update_then_check() {
+        let dir = scratch("roundtrip");
+        let golden = Golden::new(&dir).with_update(true);
+        golden.check("tx", &[0xde, 0xad, 0xbe, 0xef]).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(golden.path("tx")).unwrap(),
+            "deadbeef\n"
+        );
+
+        let golden = golden.with_update(false);
+        golden.check("tx", &[0xde, 0xad, 0xbe, 0xef]).unwrap();
+        match golden.check("tx", &[0xde, 0xad, 0x00, 0xef, 0x01]) {
+            Err(GoldenError::Mismatch(m)) => {
+                assert_eq!(m.first_difference(), 2);
+                assert!(m.to_string().contains("at byte 2"));
+            }
+            other => panic!("expected mismatch, got {:?}", other),
+        }
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_missing_and_corrupt() {
+        let dir = scratch("missing");
+        let golden = Golden::new(&dir).with_update(false);
+        assert!(matches!(
+            golden.check("absent", &[1]),
+            Err(GoldenError::Missing(_))
+        ));
+
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(golden.path("bad"), "zz").unwrap();
+        assert!(matches!(
+            golden.read("bad"),
+            Err(GoldenError::Corrupt { .. })
+        ));
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_prefix_mismatch_offset() {
+        let m = Mismatch {
+            name: "short".into(),
+            expected: vec![1, 2, 3],
+            actual: vec![1, 2],
+        };
+        assert_eq!(m.first_difference(), 2);
+    }
+}
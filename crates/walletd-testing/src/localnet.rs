@@ -0,0 +1,857 @@
+//! Local chain nodes for end-to-end tests
+//!
+//! Spawns anvil, bitcoind (regtest) or solana-test-validator on free ports,
+//! waits until they answer RPC, funds test accounts and hands back a
+//! [`ProviderConfig`] pointing at the node. Each node is killed and its data
+//! directory removed when the handle is dropped.
+//!
+//! ```rust,ignore
+//! use walletd_testing::localnet::Anvil;
+//!
+//! let node = Anvil::new().with_accounts(2).spawn().await?;
+//! let provider = ProviderPool::new(node.provider_config())?;
+//! let sender = &node.accounts()[0];
+//! ```
+//!
+//! The binaries must be on `PATH`; tests can check [`Anvil::is_installed`]
+//! (and friends) to skip when they are not.
+
+use crate::fixtures::{Fixtures, TEST_MNEMONIC};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::net::TcpListener;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::time::{Duration, Instant};
+use walletd_provider::{ProviderConfig, RpcClient};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Errors from starting or talking to a local node
+#[derive(Debug, thiserror::Error)]
+pub enum LocalnetError {
+    /// The node binary is not on `PATH`
+    #[error("{0} is not installed")]
+    NotInstalled(&'static str),
+
+    /// The node process could not be started
+    #[error("failed to start {node}: {source}")]
+    Spawn {
+        /// Node binary
+        node: &'static str,
+        /// Underlying I/O error
+        source: std::io::Error,
+    },
+
+    /// The node exited before it became ready
+    #[error("{node} exited during startup ({status})")]
+    Exited {
+        /// Node binary
+        node: &'static str,
+        /// Exit status
+        status: std::process::ExitStatus,
+    },
+
+    /// The node did not answer RPC in time
+    #[error("{node} not ready after {secs}s")]
+    Timeout {
+        /// Node binary
+        node: &'static str,
+        /// Startup timeout in seconds
+        secs: u64,
+    },
+
+    /// An RPC call to the node failed
+    #[error("{node} RPC {method} failed: {message}")]
+    Rpc {
+        /// Node binary
+        node: &'static str,
+        /// RPC method
+        method: String,
+        /// Error message
+        message: String,
+    },
+
+    /// Filesystem error while preparing the node
+    #[error("I/O error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Result type for localnet operations
+pub type Result<T> = std::result::Result<T, LocalnetError>;
+
+/// An account funded when the node started
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FundedAccount {
+    /// Account address
+    pub address: String,
+    /// Private key, hex, when the node exposes it
+    pub private_key: Option<String>,
+}
+
+// ============================================================================
+// Process handling
+// ============================================================================
+
+/// Returns a TCP port that was free at the time of the call
+pub fn free_port() -> std::io::Result<u16> {
+    Ok(TcpListener::bind(("127.0.0.1", 0))?.local_addr()?.port())
+}
+
+fn installed(binary: &str) -> bool {
+    Command::new(binary)
+        .arg("--version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .is_ok()
+}
+
+/// Creates a fresh, empty data directory under the system temp dir
+fn data_dir(node: &str) -> std::io::Result<PathBuf> {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+    let dir = std::env::temp_dir().join(format!(
+        "walletd-localnet-{}-{}-{}",
+        node,
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    if dir.exists() {
+        std::fs::remove_dir_all(&dir)?;
+    }
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// A running local node
+///
+/// Dropping the handle kills the process and removes its data directory.
+pub struct LocalNode {
+    name: &'static str,
+    child: Child,
+    data_dir: PathBuf,
+    provider: ProviderConfig,
+    accounts: Vec<FundedAccount>,
+    client: RpcClient,
+}
+
+impl LocalNode {
+    fn spawn(
+        name: &'static str,
+        args: Vec<String>,
+        data_dir: PathBuf,
+        provider: ProviderConfig,
+    ) -> Result<Self> {
+        let child = Command::new(name)
+            .args(&args)
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|source| match source.kind() {
+                std::io::ErrorKind::NotFound => LocalnetError::NotInstalled(name),
+                _ => LocalnetError::Spawn { node: name, source },
+            })?;
+        let client = RpcClient::new().map_err(|e| LocalnetError::Rpc {
+            node: name,
+            method: String::new(),
+            message: e.to_string(),
+        })?;
+        Ok(Self {
+            name,
+            child,
+            data_dir,
+            provider,
+            accounts: Vec::new(),
+            client,
+        })
+    }
+
+    /// Polls the health check method until it succeeds
+    async fn wait_ready(&mut self, timeout: Duration) -> Result<()> {
+        let method = self.provider.health_check_method.clone();
+        let started = Instant::now();
+        loop {
+            if let Some(status) = self.child.try_wait()? {
+                return Err(LocalnetError::Exited {
+                    node: self.name,
+                    status,
+                });
+            }
+            if self.rpc::<_, Value>(&method, json!([])).await.is_ok() {
+                return Ok(());
+            }
+            if started.elapsed() > timeout {
+                return Err(LocalnetError::Timeout {
+                    node: self.name,
+                    secs: timeout.as_secs(),
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+
+    /// Node binary name
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// JSON-RPC URL of the node
+    pub fn rpc_url(&self) -> &str {
+        &self.provider.url
+    }
+
+    /// Provider configuration for the node, with caching and background
+    /// health checks disabled
+    pub fn provider_config(&self) -> ProviderConfig {
+        self.provider.clone()
+    }
+
+    /// Accounts funded at startup
+    pub fn accounts(&self) -> &[FundedAccount] {
+        &self.accounts
+    }
+
+    /// Directory holding the node's chain data
+    pub fn data_dir(&self) -> &Path {
+        &self.data_dir
+    }
+
+    /// Makes a JSON-RPC call to the node
+    pub async fn rpc<P: Serialize, R: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<R> {
+        let headers = self.provider.headers_for(&self.provider.url);
+        self.client
+            .rpc_call_with_headers(&self.provider.url, method, params, &headers)
+            .await
+            .map_err(|e| LocalnetError::Rpc {
+                node: self.name,
+                method: method.to_string(),
+                message: e.to_string(),
+            })
+    }
+}
+
+impl Drop for LocalNode {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+        let _ = std::fs::remove_dir_all(&self.data_dir);
+    }
+}
+
+fn provider_config(name: &str, url: String, health_check_method: &str) -> ProviderConfig {
+    ProviderConfig::new(url)
+        .with_name(name)
+        .with_cache(false)
+        .with_health_check_interval(0)
+        .with_health_check_method(health_check_method)
+        .with_max_retries(1)
+        .with_retry_delay(100)
+        .with_timeout(10)
+}
+
+// ============================================================================
+// Anvil
+// ============================================================================
+
+/// Builder for a local anvil (EVM) node
+///
+/// Accounts are derived from [`TEST_MNEMONIC`] unless another mnemonic is set,
+/// so the first one matches the `ethereum` fixture.
+#[derive(Debug, Clone)]
+pub struct Anvil {
+    chain_id: u64,
+    accounts: u32,
+    balance_eth: u64,
+    mnemonic: String,
+    block_time: Option<u64>,
+    startup_timeout: Duration,
+}
+
+impl Default for Anvil {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Anvil {
+    /// Anvil binary name
+    pub const BINARY: &'static str = "anvil";
+
+    /// Creates a builder with 10 accounts holding 10,000 ETH each on chain 31337
+    pub fn new() -> Self {
+        Self {
+            chain_id: 31337,
+            accounts: 10,
+            balance_eth: 10_000,
+            mnemonic: TEST_MNEMONIC.to_string(),
+            block_time: None,
+            startup_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Returns true if `anvil` is on `PATH`
+    pub fn is_installed() -> bool {
+        installed(Self::BINARY)
+    }
+
+    /// Sets the chain id
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Sets the number of funded accounts
+    pub fn with_accounts(mut self, accounts: u32) -> Self {
+        self.accounts = accounts;
+        self
+    }
+
+    /// Sets each account's starting balance, in ETH
+    pub fn with_balance(mut self, eth: u64) -> Self {
+        self.balance_eth = eth;
+        self
+    }
+
+    /// Derives the funded accounts from another mnemonic
+    pub fn with_mnemonic(mut self, mnemonic: impl Into<String>) -> Self {
+        self.mnemonic = mnemonic.into();
+        self
+    }
+
+    /// Mines a block every `secs` seconds instead of on every transaction
+    pub fn with_block_time(mut self, secs: u64) -> Self {
+        self.block_time = Some(secs);
+        self
+    }
+
+    /// Sets how long to wait for the node to answer RPC
+    pub fn with_startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = timeout;
+        self
+    }
+
+    fn args(&self, port: u16, config_out: &Path) -> Vec<String> {
+        let mut args = vec![
+            "--port".to_string(),
+            port.to_string(),
+            "--chain-id".to_string(),
+            self.chain_id.to_string(),
+            "--accounts".to_string(),
+            self.accounts.to_string(),
+            "--balance".to_string(),
+            self.balance_eth.to_string(),
+            "--mnemonic".to_string(),
+            self.mnemonic.clone(),
+            "--config-out".to_string(),
+            config_out.display().to_string(),
+        ];
+        if let Some(secs) = self.block_time {
+            args.push("--block-time".to_string());
+            args.push(secs.to_string());
+        }
+        args
+    }
+
+    /// Starts the node and waits until it answers RPC
+    pub async fn spawn(self) -> Result<AnvilNode> {
+        let port = free_port()?;
+        let dir = data_dir(Self::BINARY)?;
+        let config_out = dir.join("anvil.json");
+        let url = format!("http://127.0.0.1:{}", port);
+        let provider = provider_config(Self::BINARY, url, "web3_clientVersion");
+
+        let mut node = LocalNode::spawn(Self::BINARY, self.args(port, &config_out), dir, provider)?;
+        node.wait_ready(self.startup_timeout).await?;
+        node.accounts = match std::fs::read_to_string(&config_out) {
+            Ok(json) => parse_anvil_accounts(&json),
+            Err(_) => {
+                let addresses: Vec<String> = node.rpc("eth_accounts", json!([])).await?;
+                addresses
+                    .into_iter()
+                    .map(|address| FundedAccount {
+                        address,
+                        private_key: None,
+                    })
+                    .collect()
+            }
+        };
+        Ok(AnvilNode {
+            node,
+            chain_id: self.chain_id,
+        })
+    }
+}
+
+/// Reads accounts and keys from anvil's `--config-out` file
+fn parse_anvil_accounts(json: &str) -> Vec<FundedAccount> {
+    let config: Value = serde_json::from_str(json).unwrap_or_default();
+    let strings = |key: &str| -> Vec<String> {
+        config[key]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let keys = strings("private_keys");
+    strings("available_accounts")
+        .into_iter()
+        .enumerate()
+        .map(|(i, address)| FundedAccount {
+            address,
+            private_key: keys
+                .get(i)
+                .map(|key| key.trim_start_matches("0x").to_string()),
+        })
+        .collect()
+}
+
+/// A running anvil node
+pub struct AnvilNode {
+    node: LocalNode,
+    chain_id: u64,
+}
+
+impl AnvilNode {
+    /// Chain id the node was started with
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Sets an account's balance, in wei
+    pub async fn set_balance(&self, address: &str, wei: u128) -> Result<()> {
+        self.rpc::<_, Value>("anvil_setBalance", json!([address, format!("{:#x}", wei)]))
+            .await
+            .map(|_| ())
+    }
+
+    /// Mines `blocks` blocks immediately
+    pub async fn mine(&self, blocks: u64) -> Result<()> {
+        self.rpc::<_, Value>("anvil_mine", json!([format!("{:#x}", blocks)]))
+            .await
+            .map(|_| ())
+    }
+}
+
+impl Deref for AnvilNode {
+    type Target = LocalNode;
+
+    fn deref(&self) -> &LocalNode {
+        &self.node
+    }
+}
+
+// ============================================================================
+// bitcoind (regtest)
+// ============================================================================
+
+/// Builder for a bitcoind regtest node
+///
+/// On startup a wallet is created and 101 blocks are mined to it, so the
+/// first coinbase output is spendable.
+#[derive(Debug, Clone)]
+pub struct BitcoindRegtest {
+    rpc_user: String,
+    rpc_password: String,
+    wallet: String,
+    initial_blocks: u64,
+    startup_timeout: Duration,
+}
+
+impl Default for BitcoindRegtest {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BitcoindRegtest {
+    /// bitcoind binary name
+    pub const BINARY: &'static str = "bitcoind";
+
+    /// Creates a builder with a `walletd` wallet and 101 initial blocks
+    pub fn new() -> Self {
+        Self {
+            rpc_user: "walletd".to_string(),
+            rpc_password: "walletd".to_string(),
+            wallet: "walletd".to_string(),
+            initial_blocks: 101,
+            startup_timeout: Duration::from_secs(30),
+        }
+    }
+
+    /// Returns true if `bitcoind` is on `PATH`
+    pub fn is_installed() -> bool {
+        installed(Self::BINARY)
+    }
+
+    /// Sets the RPC credentials
+    pub fn with_rpc_auth(mut self, user: impl Into<String>, password: impl Into<String>) -> Self {
+        self.rpc_user = user.into();
+        self.rpc_password = password.into();
+        self
+    }
+
+    /// Sets the name of the wallet created at startup
+    pub fn with_wallet(mut self, name: impl Into<String>) -> Self {
+        self.wallet = name.into();
+        self
+    }
+
+    /// Sets how many blocks are mined to the wallet at startup
+    pub fn with_initial_blocks(mut self, blocks: u64) -> Self {
+        self.initial_blocks = blocks;
+        self
+    }
+
+    /// Sets how long to wait for the node to answer RPC
+    pub fn with_startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = timeout;
+        self
+    }
+
+    fn args(&self, port: u16, data_dir: &Path) -> Vec<String> {
+        vec![
+            "-regtest".to_string(),
+            "-server".to_string(),
+            "-listen=0".to_string(),
+            "-txindex=1".to_string(),
+            "-fallbackfee=0.0002".to_string(),
+            format!("-datadir={}", data_dir.display()),
+            format!("-rpcport={}", port),
+            format!("-rpcuser={}", self.rpc_user),
+            format!("-rpcpassword={}", self.rpc_password),
+        ]
+    }
+
+    /// Starts the node, creates the wallet and mines the initial blocks
+    pub async fn spawn(self) -> Result<BitcoindNode> {
+        let port = free_port()?;
+        let dir = data_dir(Self::BINARY)?;
+        // Wallet RPCs need the wallet in the path; chain RPCs work there too
+        let url = format!("http://127.0.0.1:{}/wallet/{}", port, self.wallet);
+        let provider = provider_config(Self::BINARY, url, "getblockchaininfo")
+            .with_basic_auth(&self.rpc_user, &self.rpc_password);
+
+        let mut node = LocalNode::spawn(Self::BINARY, self.args(port, &dir), dir, provider)?;
+        node.wait_ready(self.startup_timeout).await?;
+        node.rpc::<_, Value>("createwallet", json!([self.wallet]))
+            .await?;
+        let address: String = node.rpc("getnewaddress", json!([])).await?;
+        let node = BitcoindNode { node, address };
+        node.mine(self.initial_blocks).await?;
+
+        let mut node = node;
+        node.node.accounts = vec![FundedAccount {
+            address: node.address.clone(),
+            private_key: None,
+        }];
+        Ok(node)
+    }
+}
+
+/// A running bitcoind regtest node
+pub struct BitcoindNode {
+    node: LocalNode,
+    address: String,
+}
+
+impl BitcoindNode {
+    /// Wallet address that receives mined coins
+    pub fn mining_address(&self) -> &str {
+        &self.address
+    }
+
+    /// Mines `blocks` blocks to the wallet and returns their hashes
+    pub async fn mine(&self, blocks: u64) -> Result<Vec<String>> {
+        self.rpc("generatetoaddress", json!([blocks, self.address]))
+            .await
+    }
+
+    /// Sends `btc` from the wallet to `address` and mines a block to confirm it
+    ///
+    /// Returns the transaction id.
+    pub async fn fund(&self, address: &str, btc: f64) -> Result<String> {
+        let txid: String = self.rpc("sendtoaddress", json!([address, btc])).await?;
+        self.mine(1).await?;
+        Ok(txid)
+    }
+}
+
+impl Deref for BitcoindNode {
+    type Target = LocalNode;
+
+    fn deref(&self) -> &LocalNode {
+        &self.node
+    }
+}
+
+// ============================================================================
+// solana-test-validator
+// ============================================================================
+
+/// Builder for a local solana-test-validator
+///
+/// The `solana` fixture account is airdropped 100 SOL by default.
+#[derive(Debug, Clone)]
+pub struct SolanaTestValidator {
+    airdrops: Vec<(String, u64)>,
+    startup_timeout: Duration,
+}
+
+impl Default for SolanaTestValidator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SolanaTestValidator {
+    /// solana-test-validator binary name
+    pub const BINARY: &'static str = "solana-test-validator";
+
+    /// Lamports per SOL
+    pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
+
+    /// Creates a builder that funds the `solana` fixture with 100 SOL
+    pub fn new() -> Self {
+        let fixture = Fixtures::get("solana").expect("solana fixture exists");
+        Self {
+            airdrops: vec![(fixture.address.to_string(), 100 * Self::LAMPORTS_PER_SOL)],
+            startup_timeout: Duration::from_secs(60),
+        }
+    }
+
+    /// Returns true if `solana-test-validator` is on `PATH`
+    pub fn is_installed() -> bool {
+        installed(Self::BINARY)
+    }
+
+    /// Airdrops `lamports` to `address` once the validator is up
+    pub fn with_airdrop(mut self, address: impl Into<String>, lamports: u64) -> Self {
+        self.airdrops.push((address.into(), lamports));
+        self
+    }
+
+    /// Sets how long to wait for the validator to answer RPC
+    pub fn with_startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = timeout;
+        self
+    }
+
+    fn args(rpc_port: u16, faucet_port: u16, ledger: &Path) -> Vec<String> {
+        vec![
+            "--reset".to_string(),
+            "--quiet".to_string(),
+            "--ledger".to_string(),
+            ledger.display().to_string(),
+            "--rpc-port".to_string(),
+            rpc_port.to_string(),
+            "--faucet-port".to_string(),
+            faucet_port.to_string(),
+        ]
+    }
+
+    /// Starts the validator and waits for the airdrops to land
+    pub async fn spawn(self) -> Result<SolanaNode> {
+        let rpc_port = free_port()?;
+        let faucet_port = free_port()?;
+        let dir = data_dir(Self::BINARY)?;
+        let url = format!("http://127.0.0.1:{}", rpc_port);
+        let provider = provider_config(Self::BINARY, url, "getHealth");
+
+        let mut node = LocalNode::spawn(
+            Self::BINARY,
+            Self::args(rpc_port, faucet_port, &dir),
+            dir,
+            provider,
+        )?;
+        node.wait_ready(self.startup_timeout).await?;
+        let node = SolanaNode { node };
+        for (address, lamports) in &self.airdrops {
+            node.airdrop(address, *lamports).await?;
+        }
+
+        let mut node = node;
+        node.node.accounts = self
+            .airdrops
+            .into_iter()
+            .map(|(address, _)| {
+                let private_key = Fixtures::all()
+                    .iter()
+                    .find(|f| f.address == address)
+                    .map(|f| f.private_key.to_string());
+                FundedAccount {
+                    address,
+                    private_key,
+                }
+            })
+            .collect();
+        Ok(node)
+    }
+}
+
+/// A running solana-test-validator
+pub struct SolanaNode {
+    node: LocalNode,
+}
+
+impl SolanaNode {
+    /// Airdrops `lamports` to `address` and waits until the balance shows it
+    pub async fn airdrop(&self, address: &str, lamports: u64) -> Result<String> {
+        let before = self.balance(address).await?;
+        let signature: String = self
+            .rpc("requestAirdrop", json!([address, lamports]))
+            .await?;
+        let started = Instant::now();
+        while self.balance(address).await? < before + lamports {
+            if started.elapsed() > Duration::from_secs(30) {
+                return Err(LocalnetError::Timeout {
+                    node: SolanaTestValidator::BINARY,
+                    secs: 30,
+                });
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+        Ok(signature)
+    }
+
+    /// Balance of `address` in lamports
+    pub async fn balance(&self, address: &str) -> Result<u64> {
+        let response: Value = self.rpc("getBalance", json!([address])).await?;
+        Ok(response["value"].as_u64().unwrap_or_default())
+    }
+}
+
+impl Deref for SolanaNode {
+    type Target = LocalNode;
+
+    fn deref(&self) -> &LocalNode {
+        &self.node
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_free_port() {
+        let port = free_port().unwrap();
+        assert!(port > 0);
+        TcpListener::bind(("127.0.0.1", port)).unwrap();
+    }
+
+    #[test]
+    fn test_anvil_args() {
+        let args = Anvil::new()
+            .with_chain_id(1337)
+            .with_block_time(2)
+            .args(8545, Path::new("/tmp/a.json"));
+        let joined = args.join(" ");
+        assert!(joined.contains("--port 8545"));
+        assert!(joined.contains("--chain-id 1337"));
+        assert!(joined.contains("--block-time 2"));
+        assert!(args.contains(&TEST_MNEMONIC.to_string()));
+    }
+
+    #[test]
+    fn test_parse_anvil_accounts() {
+        let json = r#"{
+            "available_accounts": ["0x9858EfFD232B4033E47d90003D41EC34EcaEda94", "0x6Fac4D18c912343BF86fa7049364Dd4E424Ab9C0"],
+            "private_keys": ["0x1ab42cc412b618bdea3a599e3c9bae199ebf030895b039e9db1e30dafb12b727"]
+        }"#;
+        let accounts = parse_anvil_accounts(json);
+        let fixture = Fixtures::get("ethereum").unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(accounts[0].address, fixture.address);
+        assert_eq!(
+            accounts[0].private_key.as_deref(),
+            Some(fixture.private_key)
+        );
+        assert_eq!(accounts[1].private_key, None);
+        assert!(parse_anvil_accounts("not json").is_empty());
+    }
+
+    #[test]
+    fn test_bitcoind_args() {
+        let args = BitcoindRegtest::new()
+            .with_rpc_auth("u", "p")
+            .args(18443, Path::new("/tmp/btc"));
+        assert!(args.contains(&"-regtest".to_string()));
+        assert!(args.contains(&"-rpcport=18443".to_string()));
+        assert!(args.contains(&"-rpcuser=u".to_string()));
+        assert!(args.contains(&"-datadir=/tmp/btc".to_string()));
+    }
+
+    #[test]
+    fn test_solana_default_airdrop() {
+        let builder =
+            SolanaTestValidator::new().with_airdrop("11111111111111111111111111111111", 1);
+        assert_eq!(builder.airdrops.len(), 2);
+        assert_eq!(
+            builder.airdrops[0].0,
+            Fixtures::get("solana").unwrap().address
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_binary() {
+        let result = LocalNode::spawn(
+            "walletd-no-such-node",
+            Vec::new(),
+            data_dir("missing").unwrap(),
+            provider_config("missing", "http://127.0.0.1:1".to_string(), "health"),
+        );
+        assert!(matches!(result, Err(LocalnetError::NotInstalled(_))));
+    }
+
+    #[tokio::test]
+    #[ignore = "requires anvil"]
+    async fn test_anvil_end_to_end() {
+        let node = Anvil::new().with_accounts(2).spawn().await.unwrap();
+        assert_eq!(
+            node.accounts()[0].address,
+            Fixtures::get("ethereum").unwrap().address
+        );
+        let balance: String = node
+            .rpc(
+                "eth_getBalance",
+                json!([node.accounts()[1].address, "latest"]),
+            )
+            .await
+            .unwrap();
+        assert_ne!(balance, "0x0");
+        assert_eq!(node.provider_config().url, node.rpc_url());
+    }
+
+    #[tokio::test]
+    #[ignore = "requires bitcoind"]
+    async fn test_bitcoind_end_to_end() {
+        let node = BitcoindRegtest::new().spawn().await.unwrap();
+        let balance: f64 = node.rpc("getbalance", json!([])).await.unwrap();
+        assert!(balance >= 50.0);
+        let address: String = node.rpc("getnewaddress", json!([])).await.unwrap();
+        node.fund(&address, 1.0).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "requires solana-test-validator"]
+    async fn test_solana_end_to_end() {
+        let node = SolanaTestValidator::new().spawn().await.unwrap();
+        let account = &node.accounts()[0];
+        assert!(account.private_key.is_some());
+        assert!(
+            node.balance(&account.address).await.unwrap()
+                >= 100 * SolanaTestValidator::LAMPORTS_PER_SOL
+        );
+    }
+}
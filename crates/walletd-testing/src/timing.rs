@@ -0,0 +1,297 @@
+//! Statistical constant-time checks
+//!
+//! A small take on the dudect method: run an operation on inputs from two
+//! classes in random order, time each call, and apply Welch's t-test to the
+//! two timing distributions. If the operation's running time depends on
+//! which class its input came from, |t| grows with the number of samples;
+//! a value above [`TimingHarness::threshold`] (4.5 by default) is treated
+//! as a leak.
+//!
+//! ```rust,ignore
+//! use walletd_testing::timing::TimingHarness;
+//!
+//! let secret = [0x42u8; 32];
+//! let report = TimingHarness::new().measure(
+//!     |_| secret,                         // class A: equal input
+//!     |rng| rng.gen::<[u8; 32]>(),        // class B: random input
+//!     |input| secret.ct_eq(input).into(),
+//! );
+//! assert!(report.is_constant_time(), "{}", report);
+//! ```
+//!
+//! Timing tests are sensitive to build profile and machine load. Run them
+//! in release mode, and treat a single failure as a reason to look closer
+//! rather than as proof of a leak.
+
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::fmt;
+use std::hint::black_box;
+use std::time::Instant;
+
+/// Runs timing measurements over two input classes
+#[derive(Debug, Clone)]
+pub struct TimingHarness {
+    samples: usize,
+    batch: usize,
+    threshold: f64,
+    crop: f64,
+    seed: u64,
+}
+
+impl Default for TimingHarness {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TimingHarness {
+    /// Creates a harness taking 20,000 samples of 16 calls each
+    pub fn new() -> Self {
+        Self {
+            samples: 20_000,
+            batch: 16,
+            threshold: 4.5,
+            crop: 0.05,
+            seed: 0,
+        }
+    }
+
+    /// Sets the number of timed samples, split between both classes
+    pub fn with_samples(mut self, samples: usize) -> Self {
+        self.samples = samples.max(4);
+        self
+    }
+
+    /// Sets how many calls are timed together per sample
+    ///
+    /// Batching lifts very fast operations above timer resolution.
+    pub fn with_batch(mut self, batch: usize) -> Self {
+        self.batch = batch.max(1);
+        self
+    }
+
+    /// Sets the |t| above which timings are considered class-dependent
+    pub fn with_threshold(mut self, threshold: f64) -> Self {
+        self.threshold = threshold;
+        self
+    }
+
+    /// Sets the fraction of slowest samples dropped from each class
+    ///
+    /// Cropping removes interrupts and preemptions that would otherwise
+    /// dominate the variance.
+    pub fn with_crop(mut self, fraction: f64) -> Self {
+        self.crop = fraction.clamp(0.0, 0.5);
+        self
+    }
+
+    /// Seeds input generation and class ordering
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// The |t| threshold in use
+    pub fn threshold(&self) -> f64 {
+        self.threshold
+    }
+
+    /// Times `op` on inputs from `class_a` and `class_b`
+    ///
+    /// Inputs are generated before timing starts, so generation cost does
+    /// not show up in the measurements.
+    pub fn measure<I, R>(
+        &self,
+        mut class_a: impl FnMut(&mut StdRng) -> I,
+        mut class_b: impl FnMut(&mut StdRng) -> I,
+        mut op: impl FnMut(&I) -> R,
+    ) -> TimingReport {
+        let mut rng = StdRng::seed_from_u64(self.seed);
+        let inputs: Vec<(bool, I)> = (0..self.samples)
+            .map(|_| {
+                let is_b = rng.gen::<bool>();
+                let input = if is_b {
+                    class_b(&mut rng)
+                } else {
+                    class_a(&mut rng)
+                };
+                (is_b, input)
+            })
+            .collect();
+
+        // Warm up caches and branch predictors on both classes
+        for (_, input) in inputs.iter().take(64) {
+            black_box(op(black_box(input)));
+        }
+
+        let mut a = Vec::with_capacity(self.samples / 2);
+        let mut b = Vec::with_capacity(self.samples / 2);
+        for (is_b, input) in &inputs {
+            let start = Instant::now();
+            for _ in 0..self.batch {
+                black_box(op(black_box(input)));
+            }
+            let elapsed = start.elapsed().as_nanos() as f64 / self.batch as f64;
+            if *is_b {
+                b.push(elapsed);
+            } else {
+                a.push(elapsed);
+            }
+        }
+
+        let a = Stats::cropped(a, self.crop);
+        let b = Stats::cropped(b, self.crop);
+        TimingReport {
+            t_statistic: welch_t(&a, &b),
+            threshold: self.threshold,
+            samples_a: a.n,
+            samples_b: b.n,
+            mean_a_ns: a.mean,
+            mean_b_ns: b.mean,
+        }
+    }
+}
+
+/// Result of a [`TimingHarness`] run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingReport {
+    /// Welch's t-statistic between the two classes
+    pub t_statistic: f64,
+    /// Threshold the harness was configured with
+    pub threshold: f64,
+    /// Samples kept for class A after cropping
+    pub samples_a: usize,
+    /// Samples kept for class B after cropping
+    pub samples_b: usize,
+    /// Mean time per call for class A, in nanoseconds
+    pub mean_a_ns: f64,
+    /// Mean time per call for class B, in nanoseconds
+    pub mean_b_ns: f64,
+}
+
+impl TimingReport {
+    /// True if no class-dependent timing was detected
+    pub fn is_constant_time(&self) -> bool {
+        self.t_statistic.abs() <= self.threshold
+    }
+}
+
+impl fmt::Display for TimingReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "t = {:.2} (threshold {:.1}); class A {:.1} ns over {} samples, class B {:.1} ns over {}",
+            self.t_statistic,
+            self.threshold,
+            self.mean_a_ns,
+            self.samples_a,
+            self.mean_b_ns,
+            self.samples_b
+        )
+    }
+}
+
+/// Mean and variance of one class
+struct Stats {
+    n: usize,
+    mean: f64,
+    variance: f64,
+}
+
+impl Stats {
+    fn cropped(mut samples: Vec<f64>, crop: f64) -> Self {
+        samples.sort_by(f64::total_cmp);
+        let keep = ((samples.len() as f64) * (1.0 - crop)).ceil() as usize;
+        samples.truncate(keep.max(2).min(samples.len()));
+        Self::of(&samples)
+    }
+
+    fn of(samples: &[f64]) -> Self {
+        let n = samples.len();
+        if n < 2 {
+            return Self {
+                n,
+                mean: samples.first().copied().unwrap_or_default(),
+                variance: 0.0,
+            };
+        }
+        let mean = samples.iter().sum::<f64>() / n as f64;
+        let variance = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        Self { n, mean, variance }
+    }
+}
+
+/// Welch's t-statistic for two samples with unequal variances
+fn welch_t(a: &Stats, b: &Stats) -> f64 {
+    if a.n < 2 || b.n < 2 {
+        return 0.0;
+    }
+    let se = (a.variance / a.n as f64 + b.variance / b.n as f64).sqrt();
+    if se == 0.0 {
+        if a.mean == b.mean {
+            0.0
+        } else {
+            f64::INFINITY.copysign(a.mean - b.mean)
+        }
+    } else {
+        (a.mean - b.mean) / se
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_welch_t() {
+        let a = Stats::of(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        let b = Stats::of(&[1.0, 2.0, 3.0, 4.0, 5.0]);
+        assert_eq!(welch_t(&a, &b), 0.0);
+
+        let b = Stats::of(&[11.0, 12.0, 13.0, 14.0, 15.0]);
+        // means differ by 10, each variance 2.5 over 5 samples
+        assert!((welch_t(&a, &b) + 10.0).abs() < 1e-9);
+
+        let flat = Stats::of(&[3.0, 3.0]);
+        assert_eq!(welch_t(&flat, &Stats::of(&[3.0, 3.0])), 0.0);
+        assert!(welch_t(&flat, &Stats::of(&[4.0, 4.0])).is_infinite());
+    }
+
+    #[test]
+    fn test_crop_drops_slowest() {
+        let stats = Stats::cropped(
+            vec![1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1000.0],
+            0.1,
+        );
+        assert_eq!(stats.n, 9);
+        assert_eq!(stats.mean, 1.0);
+    }
+
+    #[test]
+    fn test_detects_early_exit_comparison() {
+        // Equal inputs scan the whole buffer; class B differs in the first byte
+        let secret = vec![0x42u8; 4096];
+        let report = TimingHarness::new()
+            .with_samples(2_000)
+            .with_batch(4)
+            .measure(
+                |_| secret.clone(),
+                |rng| {
+                    let mut input = secret.clone();
+                    input[0] ^= rng.gen_range(1..=255u8);
+                    input
+                },
+                |input| {
+                    for (x, y) in secret.iter().zip(input) {
+                        if x != y {
+                            return false;
+                        }
+                    }
+                    true
+                },
+            );
+        assert!(!report.is_constant_time(), "{}", report);
+        assert!(report.mean_a_ns > report.mean_b_ns);
+    }
+}
@@ -0,0 +1,375 @@
+//! Reference test vectors for key derivation
+//!
+//! Bundled copies of the official BIP-39 (Trezor), BIP-32, SLIP-10 and
+//! SLIP-39 vectors, plus well-known BIP-44 addresses for the all-"abandon"
+//! mnemonic. Coin crates can assert their derivation against these
+//! instead of only checking that it is self-consistent.
+//!
+//! ```rust,ignore
+//! use walletd_testing::vectors;
+//!
+//! for v in vectors::bip44_for("ethereum") {
+//!     let wallet = EthereumWallet::from_mnemonic(&v.mnemonic, &v.path)?;
+//!     assert_eq!(wallet.address(), v.address);
+//! }
+//! ```
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+
+const BIP39_JSON: &str = include_str!("../vectors/bip39.json");
+const BIP32_JSON: &str = include_str!("../vectors/bip32.json");
+const SLIP10_ED25519_JSON: &str = include_str!("../vectors/slip10_ed25519.json");
+const BIP44_JSON: &str = include_str!("../vectors/bip44.json");
+const SLIP39_JSON: &str = include_str!("../vectors/slip39.json");
+
+/// A BIP-39 mnemonic with its entropy, seed and BIP-32 root key
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bip39Vector {
+    /// Entropy, hex
+    pub entropy: String,
+    /// English mnemonic for the entropy
+    pub mnemonic: String,
+    /// Passphrase used to derive the seed
+    pub passphrase: String,
+    /// 64-byte seed, hex
+    pub seed: String,
+    /// BIP-32 root private key for the seed
+    pub xprv: String,
+}
+
+impl Bip39Vector {
+    /// Entropy as bytes
+    pub fn entropy_bytes(&self) -> Vec<u8> {
+        decode(&self.entropy)
+    }
+
+    /// Seed as bytes
+    pub fn seed_bytes(&self) -> Vec<u8> {
+        decode(&self.seed)
+    }
+}
+
+/// A BIP-32 seed with the extended keys derived from it
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bip32Vector {
+    /// Seed, hex
+    pub seed: String,
+    /// Extended keys along a single derivation chain
+    pub derivations: Vec<Bip32Derivation>,
+}
+
+impl Bip32Vector {
+    /// Seed as bytes
+    pub fn seed_bytes(&self) -> Vec<u8> {
+        decode(&self.seed)
+    }
+}
+
+/// Extended keys at one BIP-32 path
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bip32Derivation {
+    /// Derivation path, hardened steps marked with `'`
+    pub path: String,
+    /// Extended public key
+    pub xpub: String,
+    /// Extended private key
+    pub xprv: String,
+}
+
+/// A SLIP-10 seed with the keys derived from it
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Slip10Vector {
+    /// Curve name, e.g. `ed25519`
+    pub curve: String,
+    /// Seed, hex
+    pub seed: String,
+    /// Keys along a single derivation chain
+    pub derivations: Vec<Slip10Derivation>,
+}
+
+impl Slip10Vector {
+    /// Seed as bytes
+    pub fn seed_bytes(&self) -> Vec<u8> {
+        decode(&self.seed)
+    }
+}
+
+/// Keys at one SLIP-10 path
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Slip10Derivation {
+    /// Derivation path, hardened steps marked with `'`
+    pub path: String,
+    /// Chain code, hex
+    pub chain_code: String,
+    /// Private key, hex
+    pub private_key: String,
+    /// Public key as SLIP-10 prints it (ed25519 keys carry a `00` prefix), hex
+    pub public_key: String,
+}
+
+impl Slip10Derivation {
+    /// Chain code as bytes
+    pub fn chain_code_bytes(&self) -> Vec<u8> {
+        decode(&self.chain_code)
+    }
+
+    /// Private key as bytes
+    pub fn private_key_bytes(&self) -> Vec<u8> {
+        decode(&self.private_key)
+    }
+
+    /// Public key as bytes, without the ed25519 `00` prefix
+    pub fn public_key_bytes(&self) -> Vec<u8> {
+        let key = decode(&self.public_key);
+        match key.len() {
+            33 if key[0] == 0 => key[1..].to_vec(),
+            _ => key,
+        }
+    }
+}
+
+/// An address derived from a mnemonic at a BIP-44 style path
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Bip44Vector {
+    /// Chain name, e.g. `bitcoin` or `ethereum`
+    pub chain: String,
+    /// Mnemonic, used without a passphrase
+    pub mnemonic: String,
+    /// Derivation path
+    pub path: String,
+    /// Expected address, in the chain's canonical form
+    pub address: String,
+}
+
+/// A set of SLIP-39 shares and the master secret they recover
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Slip39Vector {
+    /// What the vector checks
+    pub description: String,
+    /// Shares to combine
+    pub mnemonics: Vec<String>,
+    /// Passphrase the secret was encrypted with
+    pub passphrase: String,
+    /// Recovered master secret, hex; empty when the shares must be rejected
+    pub master_secret: String,
+}
+
+impl Slip39Vector {
+    /// Returns true if the shares are expected to combine
+    pub fn is_valid(&self) -> bool {
+        !self.master_secret.is_empty()
+    }
+
+    /// Master secret as bytes
+    pub fn master_secret_bytes(&self) -> Vec<u8> {
+        decode(&self.master_secret)
+    }
+}
+
+#[derive(Deserialize)]
+struct VectorFile<T> {
+    vectors: Vec<T>,
+}
+
+/// Parses a vector file: a JSON object with a `vectors` array
+///
+/// Useful for loading extra vectors kept alongside a coin crate in the
+/// same layout as the bundled ones.
+pub fn parse<T: DeserializeOwned>(json: &str) -> Result<Vec<T>, serde_json::Error> {
+    serde_json::from_str::<VectorFile<T>>(json).map(|file| file.vectors)
+}
+
+/// Trezor BIP-39 vectors (English, passphrase `TREZOR`)
+pub fn bip39() -> Vec<Bip39Vector> {
+    bundled(BIP39_JSON)
+}
+
+/// BIP-32 test vector 1
+pub fn bip32() -> Vec<Bip32Vector> {
+    bundled(BIP32_JSON)
+}
+
+/// SLIP-10 ed25519 test vector 1
+pub fn slip10_ed25519() -> Vec<Slip10Vector> {
+    bundled(SLIP10_ED25519_JSON)
+}
+
+/// BIP-44/49/84 addresses for the standard test mnemonic
+pub fn bip44() -> Vec<Bip44Vector> {
+    bundled(BIP44_JSON)
+}
+
+/// Trezor SLIP-39 vectors (passphrase `TREZOR`), valid and invalid share sets
+pub fn slip39() -> Vec<Slip39Vector> {
+    bundled(SLIP39_JSON)
+}
+
+/// BIP-44 vectors for a single chain
+pub fn bip44_for(chain: &str) -> Vec<Bip44Vector> {
+    bip44().into_iter().filter(|v| v.chain == chain).collect()
+}
+
+fn bundled<T: DeserializeOwned>(json: &str) -> Vec<T> {
+    parse(json).expect("bundled test vectors are valid JSON")
+}
+
+fn decode(value: &str) -> Vec<u8> {
+    hex::decode(value).expect("bundled test vectors are valid hex")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bip32::{DerivationPath, Prefix, XPrv};
+    use hmac::{Hmac, Mac};
+    use sha2::{Digest, Sha256, Sha512};
+
+    fn hmac_sha512(key: &[u8], data: &[u8]) -> [u8; 64] {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).unwrap();
+        mac.update(data);
+        mac.finalize().into_bytes().into()
+    }
+
+    /// Minimal SLIP-10 ed25519 derivation, only hardened steps exist
+    fn slip10_derive(seed: &[u8], path: &str) -> ([u8; 32], [u8; 32]) {
+        let i = hmac_sha512(b"ed25519 seed", seed);
+        let (mut key, mut chain) = ([0u8; 32], [0u8; 32]);
+        key.copy_from_slice(&i[..32]);
+        chain.copy_from_slice(&i[32..]);
+        for step in path.split('/').skip(1) {
+            let index: u32 = step.trim_end_matches('\'').parse().unwrap();
+            let mut data = vec![0u8];
+            data.extend_from_slice(&key);
+            data.extend_from_slice(&(index | 0x8000_0000).to_be_bytes());
+            let i = hmac_sha512(&chain, &data);
+            key.copy_from_slice(&i[..32]);
+            chain.copy_from_slice(&i[32..]);
+        }
+        (key, chain)
+    }
+
+    fn hash160(data: &[u8]) -> Vec<u8> {
+        ripemd::Ripemd160::digest(Sha256::digest(data)).to_vec()
+    }
+
+    fn base58check(version: u8, payload: &[u8]) -> String {
+        let mut data = vec![version];
+        data.extend_from_slice(payload);
+        bs58::encode(data).with_check().into_string()
+    }
+
+    fn checksum_address(address: &[u8]) -> String {
+        let lower = hex::encode(address);
+        let hash = sha3::Keccak256::digest(lower.as_bytes());
+        let mut out = String::from("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            out.push(if nibble >= 8 { c.to_ascii_uppercase() } else { c });
+        }
+        out
+    }
+
+    /// Independent address derivation used to check the bundled BIP-44 data
+    fn derive_address(v: &Bip44Vector) -> String {
+        let mnemonic = bip39::Mnemonic::parse(&v.mnemonic).unwrap();
+        let seed = mnemonic.to_seed("");
+        if v.chain == "solana" {
+            let (key, _) = slip10_derive(&seed, &v.path);
+            let signing = ed25519_dalek::SigningKey::from_bytes(&key);
+            return bs58::encode(signing.verifying_key().as_bytes()).into_string();
+        }
+
+        let path: DerivationPath = v.path.parse().unwrap();
+        let xprv = XPrv::derive_from_path(seed, &path).unwrap();
+        let public = *xprv.public_key().public_key();
+        match v.chain.as_str() {
+            "ethereum" => {
+                let point = public.to_encoded_point(false);
+                let hash = sha3::Keccak256::digest(&point.as_bytes()[1..]);
+                checksum_address(&hash[12..])
+            }
+            "bitcoin" => {
+                let pubkey_hash = hash160(public.to_encoded_point(true).as_bytes());
+                match v.path.split('/').nth(1) {
+                    Some("44'") => base58check(0x00, &pubkey_hash),
+                    Some("49'") => {
+                        let mut redeem = vec![0x00, 0x14];
+                        redeem.extend_from_slice(&pubkey_hash);
+                        base58check(0x05, &hash160(&redeem))
+                    }
+                    Some("84'") => {
+                        bech32::segwit::encode(bech32::hrp::BC, bech32::segwit::VERSION_0, &pubkey_hash)
+                            .unwrap()
+                    }
+                    other => panic!("unexpected purpose {:?}", other),
+                }
+            }
+            other => panic!("no reference derivation for {}", other),
+        }
+    }
+
+    #[test]
+    fn test_bundled_vectors_load() {
+        assert_eq!(bip39().len(), 5);
+        assert_eq!(bip32()[0].derivations.len(), 6);
+        assert_eq!(slip10_ed25519()[0].derivations.len(), 6);
+        assert!(!bip44_for("ethereum").is_empty());
+        assert!(bip44_for("dogecoin").is_empty());
+        assert_eq!(slip39().len(), 40);
+    }
+
+    #[test]
+    fn test_parse_rejects_malformed_file() {
+        assert!(parse::<Bip44Vector>("{\"vectors\": [{\"chain\": 1}]}").is_err());
+        assert!(parse::<Bip44Vector>("[]").is_err());
+    }
+
+    #[test]
+    fn test_bip39_vectors() {
+        for v in bip39() {
+            let mnemonic = bip39::Mnemonic::from_entropy(&v.entropy_bytes()).unwrap();
+            assert_eq!(mnemonic.to_string(), v.mnemonic);
+
+            let seed = mnemonic.to_seed(&v.passphrase);
+            assert_eq!(seed.to_vec(), v.seed_bytes());
+
+            let root = XPrv::new(seed).unwrap();
+            assert_eq!(root.to_string(Prefix::XPRV).as_str(), v.xprv);
+        }
+    }
+
+    #[test]
+    fn test_bip32_vectors() {
+        for v in bip32() {
+            for d in &v.derivations {
+                let path: DerivationPath = d.path.parse().unwrap();
+                let xprv = XPrv::derive_from_path(v.seed_bytes(), &path).unwrap();
+                assert_eq!(xprv.to_string(Prefix::XPRV).as_str(), d.xprv, "{}", d.path);
+                assert_eq!(xprv.public_key().to_string(Prefix::XPUB), d.xpub, "{}", d.path);
+            }
+        }
+    }
+
+    #[test]
+    fn test_slip10_ed25519_vectors() {
+        for v in slip10_ed25519() {
+            assert_eq!(v.curve, "ed25519");
+            for d in &v.derivations {
+                let (key, chain) = slip10_derive(&v.seed_bytes(), &d.path);
+                assert_eq!(key.to_vec(), d.private_key_bytes(), "{}", d.path);
+                assert_eq!(chain.to_vec(), d.chain_code_bytes(), "{}", d.path);
+
+                let signing = ed25519_dalek::SigningKey::from_bytes(&key);
+                assert_eq!(signing.verifying_key().as_bytes().to_vec(), d.public_key_bytes());
+            }
+        }
+    }
+
+    #[test]
+    fn test_bip44_vectors() {
+        for v in bip44() {
+            assert_eq!(derive_address(&v), v.address, "{} {}", v.chain, v.path);
+        }
+    }
+}
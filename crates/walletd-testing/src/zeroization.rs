@@ -0,0 +1,255 @@
+//! Checks that secrets are wiped before their memory is freed
+//!
+//! [`CapturingAllocator`] wraps the system allocator and, when asked to watch
+//! a region, copies that region's bytes at the moment its allocation is
+//! freed. Comparing the copy against zero shows whether a type's `Drop`
+//! actually scrubbed its secret, without reading memory after it has been
+//! released.
+//!
+//! The allocator must be installed in the test binary:
+//!
+//! ```rust,ignore
+//! use walletd_testing::zeroization::{self, CapturingAllocator};
+//!
+//! #[global_allocator]
+//! static ALLOC: CapturingAllocator = CapturingAllocator;
+//!
+//! #[test]
+//! fn key_is_wiped() {
+//!     let key = SecretKey::generate();
+//!     let report = zeroization::inspect_drop(key, |k| k.as_bytes()).unwrap();
+//!     assert!(report.is_zeroized(), "{} bytes left", report.residue());
+//! }
+//! ```
+//!
+//! Only heap memory can be watched. Values that keep their secret inline can
+//! be checked with [`inspect_boxed_drop`], which moves them into a `Box`
+//! first.
+
+#![allow(unsafe_code)]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicBool, AtomicU8, AtomicUsize, Ordering};
+use std::sync::Mutex;
+
+/// Largest region that can be watched, in bytes
+pub const MAX_CAPTURE: usize = 4096;
+
+/// Set by the first allocation through [`CapturingAllocator`]
+static INSTALLED: AtomicBool = AtomicBool::new(false);
+/// Start of the watched region, or 0 when nothing is watched
+static WATCH_ADDR: AtomicUsize = AtomicUsize::new(0);
+static WATCH_LEN: AtomicUsize = AtomicUsize::new(0);
+/// Set when the watched region's allocation was freed
+static CAPTURED: AtomicBool = AtomicBool::new(false);
+/// Set when the watched region's allocation was moved by `realloc`
+static RELOCATED: AtomicBool = AtomicBool::new(false);
+static CAPTURE: [AtomicU8; MAX_CAPTURE] = [const { AtomicU8::new(0) }; MAX_CAPTURE];
+/// Serializes inspections, since there is one watch slot per process
+static SESSION: Mutex<()> = Mutex::new(());
+
+/// Global allocator that captures watched regions as they are freed
+///
+/// Behaves exactly like [`System`] otherwise.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CapturingAllocator;
+
+/// Returns the watched region if it lies inside `[ptr, ptr + size)`
+fn watched_in(ptr: *mut u8, size: usize) -> Option<(usize, usize)> {
+    let addr = WATCH_ADDR.load(Ordering::Acquire);
+    if addr == 0 {
+        return None;
+    }
+    let len = WATCH_LEN.load(Ordering::Acquire);
+    let start = ptr as usize;
+    (addr >= start && addr + len <= start + size).then_some((addr, len))
+}
+
+/// Copies the watched region out of a block that is about to be freed
+///
+/// # Safety
+///
+/// `ptr` must point to a live allocation of at least `size` bytes.
+unsafe fn capture(ptr: *mut u8, size: usize) {
+    let Some((addr, len)) = watched_in(ptr, size) else {
+        return;
+    };
+    for (i, slot) in CAPTURE.iter().enumerate().take(len) {
+        slot.store(
+            std::ptr::read_volatile((addr + i) as *const u8),
+            Ordering::Relaxed,
+        );
+    }
+    CAPTURED.store(true, Ordering::Release);
+    WATCH_ADDR.store(0, Ordering::Release);
+}
+
+unsafe impl GlobalAlloc for CapturingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        INSTALLED.store(true, Ordering::Relaxed);
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        capture(ptr, layout.size());
+        System.dealloc(ptr, layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let watched = watched_in(ptr, layout.size());
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        // The old block is released unscrubbed when realloc moves it
+        if let Some((addr, _)) = watched {
+            if !new_ptr.is_null() && new_ptr != ptr {
+                RELOCATED.store(true, Ordering::Release);
+                WATCH_ADDR.store(new_ptr as usize + (addr - ptr as usize), Ordering::Release);
+            }
+        }
+        new_ptr
+    }
+}
+
+/// Why an inspection could not be made
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum ZeroizationError {
+    /// [`CapturingAllocator`] is not the global allocator
+    #[error("CapturingAllocator is not installed as the global allocator")]
+    NotInstalled,
+
+    /// Dropping the value did not free the watched region
+    #[error("watched region was not freed on drop (stack memory, or still shared?)")]
+    NotFreed,
+
+    /// The region is empty or larger than [`MAX_CAPTURE`]
+    #[error("cannot watch {0} bytes (limit {MAX_CAPTURE})")]
+    BadLength(usize),
+}
+
+/// Contents of a watched region at the moment it was freed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DropReport {
+    /// Bytes of the region just before deallocation
+    pub bytes: Vec<u8>,
+    /// True if the region was moved by `realloc` before it was freed,
+    /// leaving an unscrubbed copy behind
+    pub relocated: bool,
+}
+
+impl DropReport {
+    /// Number of bytes that were not zero
+    pub fn residue(&self) -> usize {
+        self.bytes.iter().filter(|b| **b != 0).count()
+    }
+
+    /// True if every byte was zero and no copy was left behind by `realloc`
+    pub fn is_zeroized(&self) -> bool {
+        !self.relocated && self.residue() == 0
+    }
+}
+
+/// Drops `value` and reports what was left in the region returned by
+/// `region` when its allocation was freed
+pub fn inspect_drop<T>(
+    value: T,
+    region: impl FnOnce(&T) -> &[u8],
+) -> Result<DropReport, ZeroizationError> {
+    let (addr, len) = {
+        let bytes = region(&value);
+        (bytes.as_ptr() as usize, bytes.len())
+    };
+    inspect_region(value, addr, len)
+}
+
+/// Moves `value` to the heap, drops it, and reports what was left of it
+///
+/// For types that hold their secret inline, such as `[u8; 32]` wrappers.
+pub fn inspect_boxed_drop<T>(value: T) -> Result<DropReport, ZeroizationError> {
+    let boxed = Box::new(value);
+    let addr = &*boxed as *const T as usize;
+    inspect_region(boxed, addr, std::mem::size_of::<T>())
+}
+
+/// Drops `value` while watching `len` bytes at `addr`
+pub fn inspect_region<T>(
+    value: T,
+    addr: usize,
+    len: usize,
+) -> Result<DropReport, ZeroizationError> {
+    if len == 0 || len > MAX_CAPTURE {
+        return Err(ZeroizationError::BadLength(len));
+    }
+    let _session = SESSION.lock().unwrap_or_else(|e| e.into_inner());
+    if !INSTALLED.load(Ordering::Relaxed) {
+        return Err(ZeroizationError::NotInstalled);
+    }
+
+    CAPTURED.store(false, Ordering::Release);
+    RELOCATED.store(false, Ordering::Release);
+    WATCH_LEN.store(len, Ordering::Release);
+    WATCH_ADDR.store(addr, Ordering::Release);
+    drop(value);
+    WATCH_ADDR.store(0, Ordering::Release);
+
+    if !CAPTURED.load(Ordering::Acquire) {
+        return Err(ZeroizationError::NotFreed);
+    }
+    Ok(DropReport {
+        bytes: CAPTURE[..len]
+            .iter()
+            .map(|b| b.load(Ordering::Relaxed))
+            .collect(),
+        relocated: RELOCATED.load(Ordering::Acquire),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_requires_installed_allocator() {
+        // The unit test binary uses the default allocator
+        let secret = vec![0xAAu8; 32];
+        assert_eq!(
+            inspect_drop(secret, |s| s.as_slice()),
+            Err(ZeroizationError::NotInstalled)
+        );
+    }
+
+    #[test]
+    fn test_bad_length() {
+        assert_eq!(
+            inspect_region(Vec::<u8>::new(), 1, 0),
+            Err(ZeroizationError::BadLength(0))
+        );
+        assert_eq!(
+            inspect_region((), 1, MAX_CAPTURE + 1),
+            Err(ZeroizationError::BadLength(MAX_CAPTURE + 1))
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let report = DropReport {
+            bytes: vec![0, 1, 0, 2],
+            relocated: false,
+        };
+        assert_eq!(report.residue(), 2);
+        assert!(!report.is_zeroized());
+        assert!(DropReport {
+            bytes: vec![0; 4],
+            relocated: false
+        }
+        .is_zeroized());
+        assert!(!DropReport {
+            bytes: vec![0; 4],
+            relocated: true
+        }
+        .is_zeroized());
+    }
+}
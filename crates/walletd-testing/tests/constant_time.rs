@@ -0,0 +1,41 @@
+//! Constant-time checks for comparison and signing functions
+//!
+//! These measure wall-clock time, so they are ignored by default. Run them
+//! on a quiet machine with
+//! `cargo test -p walletd-testing --release --test constant_time -- --ignored`.
+
+use rand::Rng;
+use walletd_testing::timing::TimingHarness;
+use walletd_testing::{Fixtures, SecurityTests};
+
+#[test]
+#[ignore = "timing-sensitive"]
+fn test_core_ct_eq_is_constant_time() {
+    let secret = Fixtures::get("ethereum").unwrap().private_key_bytes();
+    let report = SecurityTests::test_constant_time_eq(&secret, walletd_core::ct_eq);
+    assert!(report.is_constant_time(), "{}", report);
+}
+
+#[test]
+#[ignore = "timing-sensitive"]
+fn test_slice_eq_is_flagged() {
+    let secret = vec![0x42u8; 4096];
+    let report = SecurityTests::test_constant_time_eq(&secret, |a, b| a == b);
+    assert!(!report.is_constant_time(), "{}", report);
+}
+
+#[test]
+#[ignore = "timing-sensitive"]
+fn test_ed25519_signing_is_constant_time() {
+    // Fixed key versus random keys over the same message
+    let fixed = Fixtures::get("solana").unwrap().private_key_bytes();
+    let report = TimingHarness::new()
+        .with_samples(4_000)
+        .with_batch(1)
+        .measure(
+            |_| ed25519_dalek::SigningKey::from_bytes(&fixed),
+            |rng| ed25519_dalek::SigningKey::from_bytes(&rng.gen()),
+            |key| ed25519_dalek::Signer::sign(key, b"walletd constant-time check"),
+        );
+    assert!(report.is_constant_time(), "{}", report);
+}
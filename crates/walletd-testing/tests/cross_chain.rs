@@ -0,0 +1,155 @@
+//! Cross-chain consistency: every coin crate against the reference table
+//!
+//! Derives each chain's address from the standard mnemonics through the
+//! coin crate's own `from_mnemonic` and compares it with the [`Fixtures`]
+//! entry of the same name. Fixture addresses come from reference wallets and
+//! official SDKs, and are re-derived independently in `fixtures.rs`, so a
+//! mismatch here means the coin crate drifted.
+//!
+//! All mismatches are collected and reported together. Crates that are known
+//! not to match are listed in [`KNOWN_DRIFT`]; the suite also fails when one
+//! of them starts matching, so the list cannot go stale.
+
+use walletd_testing::{ChainFixture, Fixtures};
+
+/// Chains whose crate derivation is known to disagree with the reference
+const KNOWN_DRIFT: &[(&str, &str)] = &[
+    ("polygon", "from_mnemonic ignores the mnemonic, key is random"),
+    ("avalanche", "from_mnemonic ignores the mnemonic, key is random"),
+    ("base", "from_mnemonic ignores the mnemonic, key is random"),
+    ("tron", "first 32 seed bytes, not m/44'/195'/0'/0/0"),
+    ("cosmos", "first 32 seed bytes, not m/44'/118'/0'/0/0"),
+    ("near", "first 32 seed bytes, not SLIP-10 m/44'/397'/0'"),
+];
+
+/// Fixtures without a coin crate check here, and why
+const NOT_COVERED: &[(&str, &str)] = &[
+    ("bitcoin_p2pkh", "the bitcoin crate only builds BIP-84 wallets"),
+    ("bitcoin_p2sh_p2wpkh", "the bitcoin crate only builds BIP-84 wallets"),
+    ("bitcoin_p2wpkh", "bdk wallet addresses need a database"),
+    ("bitcoin_p2tr", "the bitcoin crate has no taproot support"),
+    ("ethereum", "builder takes a bdk mnemonic; arbitrum covers it"),
+    ("solana", "coins/solana is not a workspace member"),
+];
+
+/// EVM chains, whose addresses are compared without regard to checksum case
+const EVM_CHAINS: &[&str] = &["arbitrum", "polygon", "avalanche", "base"];
+
+/// Derives the fixture's address through its coin crate
+///
+/// Returns `None` for fixtures this suite has no crate check for.
+fn derive(fixture: &ChainFixture) -> Option<Result<String, String>> {
+    let m = fixture.mnemonic;
+    let address = match fixture.name {
+        "arbitrum" => walletd_arbitrum::ArbitrumWallet::from_mnemonic(m, 42161)
+            .map(|w| w.address())
+            .map_err(|e| e.to_string()),
+        "polygon" => walletd_polygon::PolygonWallet::from_mnemonic(m, 137)
+            .map(|w| w.address())
+            .map_err(|e| e.to_string()),
+        "avalanche" => walletd_avalanche::AvalancheWallet::from_mnemonic(m, 43114)
+            .map(|w| w.address())
+            .map_err(|e| e.to_string()),
+        "base" => walletd_base::BaseWallet::from_mnemonic(m, 8453)
+            .map(|w| w.address())
+            .map_err(|e| e.to_string()),
+        "tron" => {
+            walletd_tron::TronWallet::from_mnemonic(m, walletd_tron::NetworkConfig::mainnet())
+                .map(|w| w.address())
+                .map_err(|e| e.to_string())
+        }
+        "cosmos" => walletd_cosmos::CosmosWallet::from_mnemonic(
+            m,
+            walletd_cosmos::NetworkConfig::cosmos_hub(),
+        )
+        .map(|w| w.address())
+        .map_err(|e| e.to_string()),
+        "sui" => walletd_sui::SuiWallet::from_mnemonic(m, Default::default())
+            .map(|w| w.address().to_string())
+            .map_err(|e| e.to_string()),
+        "aptos" => walletd_aptos::AptosWallet::from_mnemonic(m, Default::default())
+            .map(|w| w.address().to_string())
+            .map_err(|e| e.to_string()),
+        "near" => {
+            walletd_near::NearWallet::from_mnemonic(m, walletd_near::NetworkConfig::mainnet())
+                .map(|w| w.implicit_account_id())
+                .map_err(|e| e.to_string())
+        }
+        "polkadot" => walletd_polkadot::PolkadotWallet::from_mnemonic(
+            m,
+            walletd_polkadot::NetworkConfig::polkadot(),
+        )
+        .map(|w| w.address())
+        .map_err(|e| e.to_string()),
+        "cardano" => {
+            walletd_cardano::CardanoWallet::from_mnemonic(m, walletd_cardano::MAINNET_NETWORK_ID)
+                .map(|w| w.address().to_string())
+                .map_err(|e| e.to_string())
+        }
+        "ton" => walletd_ton::TonWallet::from_mnemonic(m, Default::default())
+            .map(|w| w.address_friendly())
+            .map_err(|e| e.to_string()),
+        _ => return None,
+    };
+    Some(address)
+}
+
+fn matches(fixture: &ChainFixture, address: &str) -> bool {
+    if EVM_CHAINS.contains(&fixture.name) {
+        address.eq_ignore_ascii_case(fixture.address)
+    } else {
+        address == fixture.address
+    }
+}
+
+fn lookup<'a>(table: &'a [(&str, &str)], name: &str) -> Option<&'a str> {
+    table
+        .iter()
+        .find(|(n, _)| *n == name)
+        .map(|(_, reason)| *reason)
+}
+
+#[test]
+fn test_every_fixture_is_accounted_for() {
+    for fixture in Fixtures::all() {
+        let covered = derive(fixture).is_some();
+        let excluded = lookup(NOT_COVERED, fixture.name).is_some();
+        assert!(
+            covered != excluded,
+            "fixture '{}' must have a crate check or a NOT_COVERED entry, not both or neither",
+            fixture.name
+        );
+    }
+    for (name, _) in KNOWN_DRIFT.iter().chain(NOT_COVERED) {
+        assert!(Fixtures::get(name).is_some(), "'{}' is not a fixture", name);
+    }
+}
+
+#[test]
+fn test_addresses_match_reference_table() {
+    let mut failures = Vec::new();
+    for fixture in Fixtures::all() {
+        let Some(derived) = derive(fixture) else {
+            continue;
+        };
+        let drift = lookup(KNOWN_DRIFT, fixture.name);
+        match (derived, drift) {
+            (Ok(address), None) if !matches(fixture, &address) => failures.push(format!(
+                "{}: crate derived {}, reference is {}",
+                fixture.name, address, fixture.address
+            )),
+            (Ok(address), Some(_)) if matches(fixture, &address) => failures.push(format!(
+                "{}: now matches the reference, remove it from KNOWN_DRIFT",
+                fixture.name
+            )),
+            (Err(e), _) => failures.push(format!("{}: from_mnemonic failed: {}", fixture.name, e)),
+            _ => {}
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{} chain(s) disagree with the reference table:\n  {}",
+        failures.len(),
+        failures.join("\n  ")
+    );
+}
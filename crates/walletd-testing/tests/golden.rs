@@ -0,0 +1,645 @@
+//! Golden snapshots of canonical transactions for each encoding family
+//!
+//! Every test builds one fixed transaction from the shared fixtures and
+//! compares its wire bytes against `golden/<name>.hex`. Regenerate with
+//! `WALLETD_UPDATE_GOLDEN=1 cargo test -p walletd-testing --test golden`.
+
+use walletd_testing::golden::Golden;
+use walletd_testing::Fixtures;
+
+fn golden() -> Golden {
+    Golden::new(concat!(env!("CARGO_MANIFEST_DIR"), "/golden"))
+}
+
+fn fixture_key(name: &str) -> [u8; 32] {
+    Fixtures::get(name)
+        .expect("fixture exists")
+        .private_key_bytes()
+}
+
+fn ed25519_public(name: &str) -> [u8; 32] {
+    ed25519_dalek::SigningKey::from_bytes(&fixture_key(name))
+        .verifying_key()
+        .to_bytes()
+}
+
+// ============================================================================
+// RLP (Ethereum and EVM chains)
+// ============================================================================
+
+mod rlp {
+    use super::*;
+    use alloy_consensus::{SignableTransaction, Signed, TxEip1559, TxEnvelope, TxLegacy};
+    use alloy_eips::eip2718::{Decodable2718, Encodable2718};
+    use alloy_primitives::{Address, Bytes, Signature, TxKind, U256};
+
+    const RECIPIENT: &str = "0x6Fac4D18c912343BF86fa7049364Dd4E424Ab9C0";
+
+    fn sign<T: SignableTransaction<Signature>>(tx: T) -> Signed<T> {
+        let key = k256::ecdsa::SigningKey::from_bytes(&fixture_key("ethereum").into()).unwrap();
+        let (sig, recid) = key
+            .sign_prehash_recoverable(tx.signature_hash().as_slice())
+            .unwrap();
+        let (r, s) = sig.split_bytes();
+        let signature = Signature::new(
+            U256::from_be_slice(&r),
+            U256::from_be_slice(&s),
+            recid.is_y_odd(),
+        );
+        tx.into_signed(signature)
+    }
+
+    fn check(name: &str, envelope: TxEnvelope) {
+        let encoded = envelope.encoded_2718();
+        golden().assert(name, &encoded);
+        assert_eq!(
+            TxEnvelope::decode_2718(&mut encoded.as_slice()).unwrap(),
+            envelope
+        );
+    }
+
+    #[test]
+    fn test_legacy_eip155_transfer() {
+        let tx = TxLegacy {
+            chain_id: Some(1),
+            nonce: 7,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: TxKind::Call(RECIPIENT.parse::<Address>().unwrap()),
+            value: U256::from(1_000_000_000_000_000_000u128),
+            input: Bytes::new(),
+        };
+        golden().assert(
+            "ethereum_legacy_transfer_unsigned",
+            &tx.encoded_for_signing(),
+        );
+        check("ethereum_legacy_transfer", sign(tx).into());
+    }
+
+    #[test]
+    fn test_eip1559_contract_call() {
+        // ERC-20 transfer(RECIPIENT, 1e6)
+        let mut input = hex::decode("a9059cbb").unwrap();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(RECIPIENT.parse::<Address>().unwrap().as_slice());
+        input.extend_from_slice(&U256::from(1_000_000u64).to_be_bytes::<32>());
+
+        let tx = TxEip1559 {
+            chain_id: 137,
+            nonce: 42,
+            gas_limit: 65_000,
+            max_fee_per_gas: 50_000_000_000,
+            max_priority_fee_per_gas: 30_000_000_000,
+            to: TxKind::Call(
+                "0x3c499c542cEF5E3811e1192ce70d8cC03d5c3359"
+                    .parse::<Address>()
+                    .unwrap(),
+            ),
+            value: U256::ZERO,
+            access_list: Default::default(),
+            input: input.into(),
+        };
+        golden().assert("ethereum_eip1559_erc20_unsigned", &tx.encoded_for_signing());
+        check("ethereum_eip1559_erc20", sign(tx).into());
+    }
+}
+
+// ============================================================================
+// Bitcoin consensus encoding
+// ============================================================================
+
+mod bitcoin_consensus {
+    use super::*;
+    use bitcoin::hashes::Hash as _;
+    use bitcoin::secp256k1::{Message, Secp256k1, SecretKey};
+    use bitcoin::sighash::{EcdsaSighashType, SighashCache};
+    use bitcoin::{
+        absolute, consensus, transaction, Address, Amount, Network, OutPoint, ScriptBuf, Sequence,
+        Transaction, TxIn, TxOut, Txid, Witness,
+    };
+    use std::str::FromStr;
+
+    fn script(fixture: &str) -> ScriptBuf {
+        Address::from_str(Fixtures::get(fixture).unwrap().address)
+            .unwrap()
+            .require_network(Network::Bitcoin)
+            .unwrap()
+            .script_pubkey()
+    }
+
+    #[test]
+    fn test_p2wpkh_spend() {
+        let spent = script("bitcoin_p2wpkh");
+        let spent_value = Amount::from_sat(100_000);
+        let mut tx = Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::from_height(840_000).unwrap(),
+            input: vec![TxIn {
+                previous_output: OutPoint {
+                    txid: Txid::from_str(
+                        "1111111111111111111111111111111111111111111111111111111111111111",
+                    )
+                    .unwrap(),
+                    vout: 1,
+                },
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output: vec![
+                TxOut {
+                    value: Amount::from_sat(60_000),
+                    script_pubkey: script("bitcoin_p2tr"),
+                },
+                TxOut {
+                    value: Amount::from_sat(39_000),
+                    script_pubkey: spent.clone(),
+                },
+            ],
+        };
+        golden().assert("bitcoin_p2wpkh_unsigned", &consensus::serialize(&tx));
+
+        let sighash = SighashCache::new(&tx)
+            .p2wpkh_signature_hash(0, &spent, spent_value, EcdsaSighashType::All)
+            .unwrap();
+        let secp = Secp256k1::new();
+        let key = SecretKey::from_slice(&fixture_key("bitcoin_p2wpkh")).unwrap();
+        let signature = bitcoin::ecdsa::Signature::sighash_all(
+            secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &key),
+        );
+        tx.input[0].witness = Witness::p2wpkh(&signature, &key.public_key(&secp));
+
+        let encoded = consensus::serialize(&tx);
+        golden().assert("bitcoin_p2wpkh_signed", &encoded);
+        assert_eq!(consensus::deserialize::<Transaction>(&encoded).unwrap(), tx);
+    }
+}
+
+// ============================================================================
+// BCS (Aptos and Sui)
+// ============================================================================
+
+mod bcs_encoding {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    fn address(fixture: &str) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        hex::decode_to_slice(&Fixtures::get(fixture).unwrap().address[2..], &mut bytes).unwrap();
+        bytes
+    }
+
+    // Aptos
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ModuleId {
+        address: [u8; 32],
+        name: String,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct EntryFunction {
+        module: ModuleId,
+        function: String,
+        ty_args: Vec<()>,
+        args: Vec<Vec<u8>>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum TransactionPayload {
+        Script,
+        ModuleBundle,
+        EntryFunction(EntryFunction),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct RawTransaction {
+        sender: [u8; 32],
+        sequence_number: u64,
+        payload: TransactionPayload,
+        max_gas_amount: u64,
+        gas_unit_price: u64,
+        expiration_timestamp_secs: u64,
+        chain_id: u8,
+    }
+
+    #[test]
+    fn test_aptos_transfer() {
+        let mut framework = [0u8; 32];
+        framework[31] = 1;
+        let tx = RawTransaction {
+            sender: address("aptos"),
+            sequence_number: 3,
+            payload: TransactionPayload::EntryFunction(EntryFunction {
+                module: ModuleId {
+                    address: framework,
+                    name: "aptos_account".into(),
+                },
+                function: "transfer".into(),
+                ty_args: Vec::new(),
+                args: vec![
+                    bcs::to_bytes(&address("sui")).unwrap(),
+                    bcs::to_bytes(&100_000_000u64).unwrap(),
+                ],
+            }),
+            max_gas_amount: 2_000,
+            gas_unit_price: 100,
+            expiration_timestamp_secs: 1_700_000_000,
+            chain_id: 1,
+        };
+        let encoded = bcs::to_bytes(&tx).unwrap();
+        golden().assert("aptos_transfer", &encoded);
+        assert_eq!(bcs::from_bytes::<RawTransaction>(&encoded).unwrap(), tx);
+    }
+
+    // Sui
+
+    type ObjectRef = ([u8; 32], u64, Vec<u8>);
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum CallArg {
+        Pure(Vec<u8>),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Argument {
+        GasCoin,
+        Input(u16),
+        Result(u16),
+        NestedResult(u16, u16),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Command {
+        MoveCall,
+        TransferObjects(Vec<Argument>, Argument),
+        SplitCoins(Argument, Vec<Argument>),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct ProgrammableTransaction {
+        inputs: Vec<CallArg>,
+        commands: Vec<Command>,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum TransactionKind {
+        ProgrammableTransaction(ProgrammableTransaction),
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct GasData {
+        payment: Vec<ObjectRef>,
+        owner: [u8; 32],
+        price: u64,
+        budget: u64,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum TransactionExpiration {
+        None,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct TransactionDataV1 {
+        kind: TransactionKind,
+        sender: [u8; 32],
+        gas_data: GasData,
+        expiration: TransactionExpiration,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum TransactionData {
+        V1(TransactionDataV1),
+    }
+
+    #[test]
+    fn test_sui_pay_sui() {
+        let sender = address("sui");
+        let tx = TransactionData::V1(TransactionDataV1 {
+            kind: TransactionKind::ProgrammableTransaction(ProgrammableTransaction {
+                inputs: vec![
+                    CallArg::Pure(bcs::to_bytes(&1_000_000_000u64).unwrap()),
+                    CallArg::Pure(bcs::to_bytes(&address("aptos")).unwrap()),
+                ],
+                commands: vec![
+                    Command::SplitCoins(Argument::GasCoin, vec![Argument::Input(0)]),
+                    Command::TransferObjects(
+                        vec![Argument::NestedResult(0, 0)],
+                        Argument::Input(1),
+                    ),
+                ],
+            }),
+            sender,
+            gas_data: GasData {
+                payment: vec![([0x33; 32], 7, vec![0x44; 32])],
+                owner: sender,
+                price: 750,
+                budget: 5_000_000,
+            },
+            expiration: TransactionExpiration::None,
+        });
+        let encoded = bcs::to_bytes(&tx).unwrap();
+        golden().assert("sui_pay_sui", &encoded);
+        assert_eq!(bcs::from_bytes::<TransactionData>(&encoded).unwrap(), tx);
+    }
+}
+
+// ============================================================================
+// Borsh (NEAR)
+// ============================================================================
+
+mod borsh_encoding {
+    use super::*;
+    use borsh::{BorshDeserialize, BorshSerialize};
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    enum PublicKey {
+        Ed25519([u8; 32]),
+    }
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    enum Action {
+        CreateAccount,
+        DeployContract {
+            code: Vec<u8>,
+        },
+        FunctionCall {
+            method_name: String,
+            args: Vec<u8>,
+            gas: u64,
+            deposit: u128,
+        },
+        Transfer {
+            deposit: u128,
+        },
+    }
+
+    #[derive(Debug, PartialEq, BorshSerialize, BorshDeserialize)]
+    struct Transaction {
+        signer_id: String,
+        public_key: PublicKey,
+        nonce: u64,
+        receiver_id: String,
+        block_hash: [u8; 32],
+        actions: Vec<Action>,
+    }
+
+    #[test]
+    fn test_near_transfer() {
+        let tx = Transaction {
+            signer_id: Fixtures::get("near").unwrap().address.to_string(),
+            public_key: PublicKey::Ed25519(ed25519_public("near")),
+            nonce: 85_000_001,
+            receiver_id: "walletd.testnet".into(),
+            block_hash: [0x55; 32],
+            actions: vec![Action::Transfer {
+                deposit: 1_000_000_000_000_000_000_000_000,
+            }],
+        };
+        let encoded = borsh::to_vec(&tx).unwrap();
+        golden().assert("near_transfer", &encoded);
+        assert_eq!(Transaction::try_from_slice(&encoded).unwrap(), tx);
+    }
+}
+
+// ============================================================================
+// Protobuf (Cosmos SDK)
+// ============================================================================
+
+mod protobuf {
+    use super::*;
+    use prost::Message;
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Coin {
+        #[prost(string, tag = "1")]
+        denom: String,
+        #[prost(string, tag = "2")]
+        amount: String,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct MsgSend {
+        #[prost(string, tag = "1")]
+        from_address: String,
+        #[prost(string, tag = "2")]
+        to_address: String,
+        #[prost(message, repeated, tag = "3")]
+        amount: Vec<Coin>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Any {
+        #[prost(string, tag = "1")]
+        type_url: String,
+        #[prost(bytes = "vec", tag = "2")]
+        value: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct TxBody {
+        #[prost(message, repeated, tag = "1")]
+        messages: Vec<Any>,
+        #[prost(string, tag = "2")]
+        memo: String,
+        #[prost(uint64, tag = "3")]
+        timeout_height: u64,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct PubKey {
+        #[prost(bytes = "vec", tag = "1")]
+        key: Vec<u8>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Single {
+        #[prost(int32, tag = "1")]
+        mode: i32,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct ModeInfo {
+        #[prost(message, optional, tag = "1")]
+        single: Option<Single>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct SignerInfo {
+        #[prost(message, optional, tag = "1")]
+        public_key: Option<Any>,
+        #[prost(message, optional, tag = "2")]
+        mode_info: Option<ModeInfo>,
+        #[prost(uint64, tag = "3")]
+        sequence: u64,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct Fee {
+        #[prost(message, repeated, tag = "1")]
+        amount: Vec<Coin>,
+        #[prost(uint64, tag = "2")]
+        gas_limit: u64,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct AuthInfo {
+        #[prost(message, repeated, tag = "1")]
+        signer_infos: Vec<SignerInfo>,
+        #[prost(message, optional, tag = "2")]
+        fee: Option<Fee>,
+    }
+
+    #[derive(Clone, PartialEq, Message)]
+    struct SignDoc {
+        #[prost(bytes = "vec", tag = "1")]
+        body_bytes: Vec<u8>,
+        #[prost(bytes = "vec", tag = "2")]
+        auth_info_bytes: Vec<u8>,
+        #[prost(string, tag = "3")]
+        chain_id: String,
+        #[prost(uint64, tag = "4")]
+        account_number: u64,
+    }
+
+    const SIGN_MODE_DIRECT: i32 = 1;
+
+    fn uatom(amount: &str) -> Coin {
+        Coin {
+            denom: "uatom".into(),
+            amount: amount.into(),
+        }
+    }
+
+    #[test]
+    fn test_cosmos_bank_send() {
+        let recipient =
+            bech32::encode::<bech32::Bech32>(bech32::Hrp::parse("cosmos").unwrap(), &[0x11; 20])
+                .unwrap();
+        let send = MsgSend {
+            from_address: Fixtures::get("cosmos").unwrap().address.into(),
+            to_address: recipient,
+            amount: vec![uatom("1500000")],
+        };
+        let body = TxBody {
+            messages: vec![Any {
+                type_url: "/cosmos.bank.v1beta1.MsgSend".into(),
+                value: send.encode_to_vec(),
+            }],
+            memo: "walletd".into(),
+            timeout_height: 0,
+        };
+
+        let key = k256::ecdsa::SigningKey::from_bytes(&fixture_key("cosmos").into()).unwrap();
+        let public_key = key
+            .verifying_key()
+            .to_encoded_point(true)
+            .as_bytes()
+            .to_vec();
+        let auth_info = AuthInfo {
+            signer_infos: vec![SignerInfo {
+                public_key: Some(Any {
+                    type_url: "/cosmos.crypto.secp256k1.PubKey".into(),
+                    value: PubKey { key: public_key }.encode_to_vec(),
+                }),
+                mode_info: Some(ModeInfo {
+                    single: Some(Single {
+                        mode: SIGN_MODE_DIRECT,
+                    }),
+                }),
+                sequence: 12,
+            }],
+            fee: Some(Fee {
+                amount: vec![uatom("5000")],
+                gas_limit: 200_000,
+            }),
+        };
+        let sign_doc = SignDoc {
+            body_bytes: body.encode_to_vec(),
+            auth_info_bytes: auth_info.encode_to_vec(),
+            chain_id: "cosmoshub-4".into(),
+            account_number: 123_456,
+        };
+
+        golden().assert("cosmos_bank_send_body", &sign_doc.body_bytes);
+        golden().assert("cosmos_bank_send_auth_info", &sign_doc.auth_info_bytes);
+        let encoded = sign_doc.encode_to_vec();
+        golden().assert("cosmos_bank_send_sign_doc", &encoded);
+        assert_eq!(SignDoc::decode(encoded.as_slice()).unwrap(), sign_doc);
+    }
+}
+
+// ============================================================================
+// SCALE (Polkadot)
+// ============================================================================
+
+mod scale {
+    use super::*;
+    use parity_scale_codec::{Decode, Encode};
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    enum MultiAddress {
+        #[codec(index = 0)]
+        Id([u8; 32]),
+    }
+
+    /// `Balances::transfer_keep_alive` (pallet 5, call 3 on Polkadot)
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct TransferKeepAlive {
+        pallet: u8,
+        call: u8,
+        dest: MultiAddress,
+        #[codec(compact)]
+        value: u128,
+    }
+
+    #[derive(Debug, PartialEq, Encode, Decode)]
+    struct SignerPayload {
+        call: TransferKeepAlive,
+        /// Immortal era
+        era: u8,
+        #[codec(compact)]
+        nonce: u32,
+        #[codec(compact)]
+        tip: u128,
+        spec_version: u32,
+        transaction_version: u32,
+        genesis_hash: [u8; 32],
+        block_hash: [u8; 32],
+    }
+
+    #[test]
+    fn test_polkadot_transfer_keep_alive() {
+        let mut genesis_hash = [0u8; 32];
+        hex::decode_to_slice(
+            "91b171bb158e2d3848fa23a9f1c25182fb8e20313b2c1eb49219da7a70ce90c3",
+            &mut genesis_hash,
+        )
+        .unwrap();
+        let call = TransferKeepAlive {
+            pallet: 5,
+            call: 3,
+            dest: MultiAddress::Id(ed25519_public("polkadot")),
+            value: 12_345_000_000,
+        };
+        golden().assert("polkadot_transfer_keep_alive_call", &call.encode());
+
+        let payload = SignerPayload {
+            call,
+            era: 0,
+            nonce: 9,
+            tip: 0,
+            spec_version: 1_002_000,
+            transaction_version: 26,
+            genesis_hash,
+            block_hash: genesis_hash,
+        };
+        let encoded = payload.encode();
+        golden().assert("polkadot_transfer_keep_alive_payload", &encoded);
+        assert_eq!(
+            SignerPayload::decode(&mut encoded.as_slice()).unwrap(),
+            payload
+        );
+    }
+}
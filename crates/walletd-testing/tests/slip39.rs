@@ -0,0 +1,32 @@
+//! SLIP-39 share recovery against the bundled Trezor vectors
+
+use walletd_hd::slip39::{self, Slip39};
+use walletd_testing::vectors;
+
+#[test]
+fn test_slip39_vectors() {
+    for v in vectors::slip39() {
+        let result = slip39::combine(&v.mnemonics, &v.passphrase);
+        if v.is_valid() {
+            let secret = result.unwrap_or_else(|e| panic!("{}: {}", v.description, e));
+            assert_eq!(*secret, v.master_secret_bytes(), "{}", v.description);
+        } else {
+            assert!(result.is_err(), "{} should be rejected", v.description);
+        }
+    }
+}
+
+#[test]
+fn test_slip39_vector_secrets_roundtrip() {
+    let scheme = Slip39::new(2, &[(1, 1), (2, 3), (3, 5)]);
+    for v in vectors::slip39().iter().filter(|v| v.is_valid()).take(4) {
+        let secret = v.master_secret_bytes();
+        let groups = scheme.split(&secret, "TREZOR").unwrap();
+        let chosen: Vec<&str> = groups[0]
+            .iter()
+            .chain(groups[2].iter().take(3))
+            .map(|share| share.as_str())
+            .collect();
+        assert_eq!(*slip39::combine(&chosen, "TREZOR").unwrap(), secret);
+    }
+}
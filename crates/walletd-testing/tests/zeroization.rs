@@ -0,0 +1,72 @@
+//! Zeroization checks against the capturing allocator
+//!
+//! Lives in its own test binary because it replaces the global allocator.
+
+use walletd_testing::zeroization::{
+    inspect_boxed_drop, inspect_drop, CapturingAllocator, ZeroizationError,
+};
+use walletd_testing::{Fixtures, SecurityTests};
+use zeroize::{ZeroizeOnDrop, Zeroizing};
+
+#[global_allocator]
+static ALLOC: CapturingAllocator = CapturingAllocator;
+
+#[derive(ZeroizeOnDrop)]
+struct InlineKey([u8; 32]);
+
+#[test]
+fn test_zeroizing_vec_is_wiped() {
+    let secret = Zeroizing::new(vec![0xAAu8; 64]);
+    let report = inspect_drop(secret, |s| s.as_slice()).unwrap();
+    assert!(report.is_zeroized(), "{:?}", report);
+}
+
+#[test]
+fn test_plain_vec_leaves_residue() {
+    let secret = vec![0xAAu8; 64];
+    let report = inspect_drop(secret, |s| s.as_slice()).unwrap();
+    assert!(!report.is_zeroized());
+    assert!(report.residue() > 32);
+}
+
+#[test]
+fn test_inline_secrets() {
+    let key = Fixtures::get("ethereum").unwrap().private_key_bytes();
+    assert!(inspect_boxed_drop(InlineKey(key)).unwrap().is_zeroized());
+    assert!(!inspect_boxed_drop(key).unwrap().is_zeroized());
+}
+
+#[test]
+fn test_signing_key_is_wiped() {
+    // Only the secret half is wiped; the cached public key is not secret
+    let key = Box::new(ed25519_dalek::SigningKey::from_bytes(
+        &Fixtures::get("solana").unwrap().private_key_bytes(),
+    ));
+    let report = inspect_drop(key, |k| k.as_bytes()).unwrap();
+    assert!(report.is_zeroized(), "{:?}", report);
+}
+
+#[test]
+fn test_shared_value_is_not_freed() {
+    let secret = std::sync::Arc::new(vec![0xAAu8; 32]);
+    let other = secret.clone();
+    assert_eq!(
+        inspect_drop(secret, |s| s.as_slice()),
+        Err(ZeroizationError::NotFreed)
+    );
+    drop(other);
+}
+
+#[test]
+fn test_security_tests_entry_point() {
+    assert!(SecurityTests::test_zeroization(|| {
+        let secret = Zeroizing::new(vec![0x55u8; 32]);
+        let (ptr, len) = (secret.as_ptr(), secret.len());
+        (secret, ptr, len)
+    }));
+    assert!(!SecurityTests::test_zeroization(|| {
+        let secret = String::from("correct horse battery staple");
+        let (ptr, len) = (secret.as_ptr(), secret.len());
+        (secret, ptr, len)
+    }));
+}
@@ -0,0 +1,231 @@
+//! Token list formats
+
+use crate::token::{cosmos_chain, evm_chain, solana_chain, TokenEntry};
+use crate::{Result, TokenListError};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+/// Supported token list formats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ListFormat {
+    /// Uniswap token list (`tokenlists.org` schema), EVM chains
+    Uniswap,
+    /// Solana token registry, same shape as a Uniswap list
+    SolanaRegistry,
+    /// Cosmos chain registry `assetlist.json`
+    CosmosAssetList,
+}
+
+/// Parses a list into token entries
+pub fn parse(format: ListFormat, json: &str) -> Result<Vec<TokenEntry>> {
+    match format {
+        ListFormat::Uniswap => parse_token_list(json, evm_chain),
+        ListFormat::SolanaRegistry => parse_token_list(json, solana_chain),
+        ListFormat::CosmosAssetList => parse_asset_list(json),
+    }
+}
+
+#[derive(Deserialize)]
+struct TokenList {
+    tokens: Vec<ListToken>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ListToken {
+    chain_id: u64,
+    address: String,
+    symbol: String,
+    name: String,
+    decimals: u8,
+    #[serde(rename = "logoURI")]
+    logo_uri: Option<String>,
+    #[serde(default)]
+    tags: Vec<String>,
+    #[serde(default)]
+    extensions: HashMap<String, serde_json::Value>,
+}
+
+fn parse_token_list(json: &str, chain: fn(u64) -> String) -> Result<Vec<TokenEntry>> {
+    let list: TokenList = serde_json::from_str(json)?;
+    list.tokens
+        .into_iter()
+        .map(|token| {
+            if token.address.trim().is_empty() {
+                return Err(TokenListError::Invalid(format!(
+                    "{} has no address",
+                    token.symbol
+                )));
+            }
+            Ok(TokenEntry {
+                chain: chain(token.chain_id),
+                address: token.address,
+                symbol: token.symbol,
+                name: token.name,
+                decimals: token.decimals,
+                logo_uri: token.logo_uri,
+                coingecko_id: token
+                    .extensions
+                    .get("coingeckoId")
+                    .and_then(|id| id.as_str())
+                    .map(str::to_string),
+                tags: token.tags,
+            })
+        })
+        .collect()
+}
+
+#[derive(Deserialize)]
+struct AssetList {
+    chain_name: String,
+    assets: Vec<Asset>,
+}
+
+#[derive(Deserialize)]
+struct Asset {
+    base: String,
+    display: String,
+    symbol: String,
+    name: Option<String>,
+    denom_units: Vec<DenomUnit>,
+    #[serde(rename = "logo_URIs")]
+    logo_uris: Option<Logos>,
+    #[serde(default)]
+    images: Vec<Logos>,
+    coingecko_id: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DenomUnit {
+    denom: String,
+    exponent: u32,
+}
+
+#[derive(Deserialize)]
+struct Logos {
+    png: Option<String>,
+    svg: Option<String>,
+}
+
+impl Logos {
+    fn preferred(self) -> Option<String> {
+        self.png.or(self.svg)
+    }
+}
+
+/// Assets are keyed by base denom; decimals are the exponent of the
+/// display unit. Assets without a display unit are skipped.
+fn parse_asset_list(json: &str) -> Result<Vec<TokenEntry>> {
+    let list: AssetList = serde_json::from_str(json)?;
+    let chain = cosmos_chain(&list.chain_name);
+    let mut entries = Vec::with_capacity(list.assets.len());
+    for asset in list.assets {
+        let exponent = asset
+            .denom_units
+            .iter()
+            .find(|unit| unit.denom == asset.display)
+            .map(|unit| unit.exponent);
+        let Some(decimals) = exponent.and_then(|e| u8::try_from(e).ok()) else {
+            tracing::debug!(base = %asset.base, "skipping asset without a display unit");
+            continue;
+        };
+        let logo_uri = asset
+            .logo_uris
+            .and_then(Logos::preferred)
+            .or_else(|| asset.images.into_iter().find_map(Logos::preferred));
+        entries.push(TokenEntry {
+            chain: chain.clone(),
+            name: asset.name.unwrap_or_else(|| asset.symbol.clone()),
+            address: asset.base,
+            symbol: asset.symbol,
+            decimals,
+            logo_uri,
+            coingecko_id: asset.coingecko_id,
+            tags: Vec::new(),
+        });
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token_lists() {
+        let uniswap = r#"{
+            "name": "Uniswap Labs Default",
+            "timestamp": "2024-01-01T00:00:00Z",
+            "version": { "major": 1, "minor": 0, "patch": 0 },
+            "tokens": [{
+                "chainId": 1,
+                "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+                "name": "USD Coin",
+                "symbol": "USDC",
+                "decimals": 6,
+                "logoURI": "https://example.com/usdc.png"
+            }]
+        }"#;
+        let tokens = parse(ListFormat::Uniswap, uniswap).unwrap();
+        assert_eq!(tokens[0].chain, "eip155:1");
+        assert_eq!(tokens[0].decimals, 6);
+        assert_eq!(
+            tokens[0].logo_uri.as_deref(),
+            Some("https://example.com/usdc.png")
+        );
+
+        let solana = r#"{
+            "name": "Solana Token List",
+            "tokens": [{
+                "chainId": 101,
+                "address": "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v",
+                "symbol": "USDC",
+                "name": "USD Coin",
+                "decimals": 6,
+                "tags": ["stablecoin"],
+                "extensions": { "coingeckoId": "usd-coin", "website": "https://www.centre.io/" }
+            }]
+        }"#;
+        let tokens = parse(ListFormat::SolanaRegistry, solana).unwrap();
+        assert_eq!(tokens[0].chain, "solana:mainnet");
+        assert_eq!(tokens[0].coingecko_id.as_deref(), Some("usd-coin"));
+        assert_eq!(tokens[0].tags, vec!["stablecoin"]);
+
+        assert!(parse(ListFormat::Uniswap, r#"{"tokens":[{"chainId":1}]}"#).is_err());
+    }
+
+    #[test]
+    fn test_parse_cosmos_asset_list() {
+        let assets = r#"{
+            "$schema": "../assetlist.schema.json",
+            "chain_name": "osmosis",
+            "assets": [{
+                "description": "The native token of Osmosis",
+                "denom_units": [
+                    { "denom": "uosmo", "exponent": 0 },
+                    { "denom": "osmo", "exponent": 6 }
+                ],
+                "base": "uosmo",
+                "name": "Osmosis",
+                "display": "osmo",
+                "symbol": "OSMO",
+                "logo_URIs": { "svg": "https://example.com/osmo.svg" },
+                "coingecko_id": "osmosis"
+            }, {
+                "denom_units": [{ "denom": "ufoo", "exponent": 0 }],
+                "base": "ufoo",
+                "display": "foo",
+                "symbol": "FOO"
+            }]
+        }"#;
+        let tokens = parse(ListFormat::CosmosAssetList, assets).unwrap();
+        assert_eq!(tokens.len(), 1);
+        assert_eq!(tokens[0].chain, "cosmos:osmosis");
+        assert_eq!(tokens[0].address, "uosmo");
+        assert_eq!(tokens[0].decimals, 6);
+        assert_eq!(
+            tokens[0].logo_uri.as_deref(),
+            Some("https://example.com/osmo.svg")
+        );
+    }
+}
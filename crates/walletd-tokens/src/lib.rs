@@ -0,0 +1,72 @@
+//! # WalletD Tokens
+//!
+//! Token metadata from public token lists, so UIs can show a symbol, logo
+//! and decimals for any token a wallet holds.
+//!
+//! A [`TokenRegistry`] loads lists in these formats:
+//!
+//! - [Uniswap token lists](https://tokenlists.org) for EVM chains
+//! - the Solana token registry (`solana.tokenlist.json`)
+//! - Cosmos chain registry asset lists (`assetlist.json`)
+//!
+//! Tokens are keyed by chain and address, so the same token from several
+//! lists is stored once. Chains are named `eip155:<chain id>`,
+//! `solana:<cluster>` and `cosmos:<chain name>`.
+//!
+//! ## Example
+//!
+//! ```ignore
+//! use walletd_tokens::{evm_chain, ListFormat, TokenRegistry};
+//!
+//! let mut tokens = TokenRegistry::new();
+//! tokens
+//!     .fetch(&client, "https://tokens.uniswap.org", ListFormat::Uniswap)
+//!     .await?;
+//! tokens.load(ListFormat::CosmosAssetList, &osmosis_assets)?;
+//!
+//! let usdc = tokens.get(&evm_chain(1), "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48");
+//! let matches = tokens.search("usd", 10);
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod formats;
+pub mod registry;
+pub mod token;
+
+pub use formats::{parse, ListFormat};
+pub use registry::TokenRegistry;
+pub use token::{cosmos_chain, evm_chain, normalize_address, solana_chain, TokenEntry};
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+
+/// Token list errors
+#[derive(Error, Debug)]
+pub enum TokenListError {
+    /// The list is well-formed JSON but not a valid list
+    #[error("Invalid token list: {0}")]
+    Invalid(String),
+
+    /// JSON error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// HTTP request failed
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Result type for token lists
+pub type Result<T> = std::result::Result<T, TokenListError>;
+
+impl From<TokenListError> for WalletdError {
+    fn from(e: TokenListError) -> Self {
+        match e {
+            TokenListError::Invalid(reason) => WalletdError::FormatError(reason),
+            TokenListError::Json(e) => WalletdError::JsonError(e.to_string()),
+            TokenListError::Http(e) => WalletdError::NetworkError(e.to_string()),
+        }
+    }
+}
@@ -0,0 +1,238 @@
+//! Deduplicated token lookup
+
+use crate::formats::{parse, ListFormat};
+use crate::token::{normalize_address, TokenEntry};
+use crate::Result;
+use std::collections::HashMap;
+
+/// Tokens from any number of lists, one entry per chain and address
+///
+/// When several lists carry the same token, the first one loaded decides
+/// symbol, name and decimals; later lists only fill in a missing logo or
+/// CoinGecko id and add tags.
+#[derive(Debug, Clone, Default)]
+pub struct TokenRegistry {
+    tokens: Vec<TokenEntry>,
+    index: HashMap<(String, String), usize>,
+}
+
+impl TokenRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses and adds a list, returning how many tokens were new
+    pub fn load(&mut self, format: ListFormat, json: &str) -> Result<usize> {
+        Ok(self.extend(parse(format, json)?))
+    }
+
+    /// Downloads and adds a list, returning how many tokens were new
+    pub async fn fetch(
+        &mut self,
+        client: &reqwest::Client,
+        url: &str,
+        format: ListFormat,
+    ) -> Result<usize> {
+        let json = client
+            .get(url)
+            .send()
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+        self.load(format, &json)
+    }
+
+    /// Adds entries, returning how many were new
+    pub fn extend(&mut self, entries: impl IntoIterator<Item = TokenEntry>) -> usize {
+        entries
+            .into_iter()
+            .map(|entry| self.insert(entry))
+            .filter(|&new| new)
+            .count()
+    }
+
+    /// Adds an entry, returning whether the token was new
+    pub fn insert(&mut self, entry: TokenEntry) -> bool {
+        let key = (
+            entry.chain.clone(),
+            normalize_address(&entry.chain, &entry.address),
+        );
+        match self.index.get(&key) {
+            Some(&i) => {
+                self.tokens[i].merge(entry);
+                false
+            }
+            None => {
+                self.index.insert(key, self.tokens.len());
+                self.tokens.push(entry);
+                true
+            }
+        }
+    }
+
+    /// Looks up a token by chain and address
+    pub fn get(&self, chain: &str, address: &str) -> Option<&TokenEntry> {
+        let key = (chain.to_string(), normalize_address(chain, address));
+        self.index.get(&key).map(|&i| &self.tokens[i])
+    }
+
+    /// Every token with this symbol, on any chain, ignoring case
+    pub fn by_symbol(&self, symbol: &str) -> Vec<&TokenEntry> {
+        self.tokens
+            .iter()
+            .filter(|token| token.symbol.eq_ignore_ascii_case(symbol))
+            .collect()
+    }
+
+    /// Every token on `chain`
+    pub fn on_chain<'a>(&'a self, chain: &'a str) -> impl Iterator<Item = &'a TokenEntry> + 'a {
+        self.tokens.iter().filter(move |token| token.chain == chain)
+    }
+
+    /// Finds tokens matching `query` by symbol, name or address
+    ///
+    /// Results are ranked: exact symbol or address matches first, then
+    /// symbols starting with the query, names starting with it, and
+    /// finally symbols or names containing it.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<&TokenEntry> {
+        let query = query.trim().to_lowercase();
+        if query.is_empty() {
+            return Vec::new();
+        }
+        let mut matches: Vec<(u8, &TokenEntry)> = self
+            .tokens
+            .iter()
+            .filter_map(|token| rank(token, &query).map(|rank| (rank, token)))
+            .collect();
+        matches.sort_by(|(a_rank, a), (b_rank, b)| {
+            a_rank
+                .cmp(b_rank)
+                .then(a.symbol.len().cmp(&b.symbol.len()))
+                .then_with(|| a.symbol.cmp(&b.symbol))
+        });
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(_, token)| token)
+            .collect()
+    }
+
+    /// Every token, in the order first loaded
+    pub fn iter(&self) -> impl Iterator<Item = &TokenEntry> {
+        self.tokens.iter()
+    }
+
+    /// Number of distinct tokens
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Returns whether the registry is empty
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+}
+
+fn rank(token: &TokenEntry, query: &str) -> Option<u8> {
+    let symbol = token.symbol.to_lowercase();
+    let name = token.name.to_lowercase();
+    if symbol == query || token.address.to_lowercase() == query {
+        Some(0)
+    } else if symbol.starts_with(query) {
+        Some(1)
+    } else if name.starts_with(query) {
+        Some(2)
+    } else if symbol.contains(query) || name.contains(query) {
+        Some(3)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const USDC: &str = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+
+    #[test]
+    fn test_dedup_and_search() {
+        let mut registry = TokenRegistry::new();
+        assert!(registry.insert(TokenEntry::new("eip155:1", USDC, "USDC", "USD Coin", 6)));
+        assert!(registry.insert(TokenEntry::new(
+            "eip155:1",
+            "0xdAC17F958D2ee523a2206206994597C13D831ec7",
+            "USDT",
+            "Tether USD",
+            6
+        )));
+        assert!(registry.insert(TokenEntry::new(
+            "cosmos:osmosis",
+            "uosmo",
+            "OSMO",
+            "Osmosis",
+            6
+        )));
+
+        let mut duplicate = TokenEntry::new("eip155:1", USDC.to_lowercase(), "usdc", "usdc", 18);
+        duplicate.logo_uri = Some("https://example.com/usdc.png".into());
+        assert!(!registry.insert(duplicate));
+        assert_eq!(registry.len(), 3);
+
+        let usdc = registry.get("eip155:1", &USDC.to_lowercase()).unwrap();
+        assert_eq!((usdc.symbol.as_str(), usdc.decimals), ("USDC", 6));
+        assert!(usdc.logo_uri.is_some());
+        assert!(registry.get("eip155:10", USDC).is_none());
+
+        let symbols: Vec<_> = registry
+            .search("us", 10)
+            .iter()
+            .map(|t| t.symbol.as_str())
+            .collect();
+        assert_eq!(symbols, vec!["USDC", "USDT"]);
+        assert_eq!(registry.search("osmo", 10)[0].address, "uosmo");
+        assert_eq!(registry.by_symbol("usdt").len(), 1);
+        assert_eq!(registry.on_chain("eip155:1").count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/tokens.json"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!(
+                r#"{{"name":"Test","tokens":[{{"chainId":1,"address":"{}","symbol":"USDC","name":"USD Coin","decimals":6}}]}}"#,
+                USDC
+            )))
+            .mount(&server)
+            .await;
+
+        let mut registry = TokenRegistry::new();
+        let client = reqwest::Client::new();
+        let url = format!("{}/tokens.json", server.uri());
+        assert_eq!(
+            registry
+                .fetch(&client, &url, ListFormat::Uniswap)
+                .await
+                .unwrap(),
+            1
+        );
+        assert_eq!(
+            registry
+                .fetch(&client, &url, ListFormat::Uniswap)
+                .await
+                .unwrap(),
+            0
+        );
+
+        let missing = format!("{}/missing.json", server.uri());
+        assert!(registry
+            .fetch(&client, &missing, ListFormat::Uniswap)
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Token entries and chain names
+
+use serde::{Deserialize, Serialize};
+use walletd_traits::Amount;
+
+/// Metadata for one token on one chain
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TokenEntry {
+    /// Chain name, e.g. `eip155:1`
+    pub chain: String,
+    /// Contract address, mint or denom
+    pub address: String,
+    /// Ticker, e.g. `USDC`
+    pub symbol: String,
+    /// Display name
+    pub name: String,
+    /// Decimal places of the display unit
+    pub decimals: u8,
+    /// Logo URL
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logo_uri: Option<String>,
+    /// CoinGecko id, for prices
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub coingecko_id: Option<String>,
+    /// Tags from the lists, e.g. `stablecoin`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+}
+
+impl TokenEntry {
+    /// Creates an entry without logo, CoinGecko id or tags
+    pub fn new(
+        chain: impl Into<String>,
+        address: impl Into<String>,
+        symbol: impl Into<String>,
+        name: impl Into<String>,
+        decimals: u8,
+    ) -> Self {
+        Self {
+            chain: chain.into(),
+            address: address.into(),
+            symbol: symbol.into(),
+            name: name.into(),
+            decimals,
+            logo_uri: None,
+            coingecko_id: None,
+            tags: Vec::new(),
+        }
+    }
+
+    /// An amount of this token in its smallest unit
+    pub fn amount(&self, value: u128) -> Amount {
+        Amount::from_smallest_unit(value, self.decimals)
+    }
+
+    /// Fills in what this entry lacks from another entry for the same token
+    pub(crate) fn merge(&mut self, other: TokenEntry) {
+        if self.logo_uri.is_none() {
+            self.logo_uri = other.logo_uri;
+        }
+        if self.coingecko_id.is_none() {
+            self.coingecko_id = other.coingecko_id;
+        }
+        for tag in other.tags {
+            if !self.tags.contains(&tag) {
+                self.tags.push(tag);
+            }
+        }
+    }
+}
+
+/// Chain name for an EVM chain id
+pub fn evm_chain(chain_id: u64) -> String {
+    format!("eip155:{}", chain_id)
+}
+
+/// Chain name for a Solana token registry chain id
+///
+/// The registry numbers mainnet-beta 101, testnet 102 and devnet 103.
+pub fn solana_chain(chain_id: u64) -> String {
+    match chain_id {
+        101 => "solana:mainnet".to_string(),
+        102 => "solana:testnet".to_string(),
+        103 => "solana:devnet".to_string(),
+        other => format!("solana:{}", other),
+    }
+}
+
+/// Chain name for a Cosmos chain registry chain
+pub fn cosmos_chain(chain_name: &str) -> String {
+    format!("cosmos:{}", chain_name)
+}
+
+/// Normalizes an address for lookups on `chain`
+///
+/// EVM addresses are compared without their checksum casing; other chains
+/// use case-sensitive addresses and denoms.
+pub fn normalize_address(chain: &str, address: &str) -> String {
+    let address = address.trim();
+    if chain.starts_with("eip155:") {
+        address.to_ascii_lowercase()
+    } else {
+        address.to_string()
+    }
+}
@@ -0,0 +1,487 @@
+//! Chain-aware address validation
+//!
+//! An [`AddressValidator`] checks and normalizes addresses for one chain. A
+//! [`ChainRegistry`] maps each [`Chain`] to its validator, so a generic
+//! "paste address" field can validate input for a known chain or detect which
+//! chains an address could belong to.
+
+use crate::{WalletError, WalletResult};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+/// A blockchain with built-in address rules
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum Chain {
+    /// Bitcoin (legacy, P2SH, SegWit and Taproot addresses)
+    Bitcoin,
+    /// Ethereum and other EVM chains
+    Ethereum,
+    /// Solana
+    Solana,
+    /// TON
+    Ton,
+    /// Cosmos Hub
+    Cosmos,
+}
+
+impl Chain {
+    /// All chains with built-in rules, in detection order
+    pub const ALL: [Chain; 5] = [
+        Chain::Bitcoin,
+        Chain::Ethereum,
+        Chain::Solana,
+        Chain::Ton,
+        Chain::Cosmos,
+    ];
+}
+
+impl fmt::Display for Chain {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Chain::Bitcoin => "Bitcoin",
+            Chain::Ethereum => "Ethereum",
+            Chain::Solana => "Solana",
+            Chain::Ton => "TON",
+            Chain::Cosmos => "Cosmos",
+        };
+        f.write_str(name)
+    }
+}
+
+impl std::str::FromStr for Chain {
+    type Err = WalletError;
+
+    /// Parses a chain name or ticker, case-insensitively (e.g. from config)
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_ascii_lowercase().as_str() {
+            "bitcoin" | "btc" => Ok(Chain::Bitcoin),
+            "ethereum" | "eth" => Ok(Chain::Ethereum),
+            "solana" | "sol" => Ok(Chain::Solana),
+            "ton" => Ok(Chain::Ton),
+            "cosmos" | "atom" => Ok(Chain::Cosmos),
+            _ => Err(WalletError::NotSupported(format!("unknown chain: {}", s))),
+        }
+    }
+}
+
+/// Validation and formatting rules for one chain's addresses
+pub trait AddressValidator: Send + Sync {
+    /// Checks that `address` is well-formed, including any checksum
+    fn validate(&self, address: &str) -> WalletResult<()>;
+
+    /// Returns true if `address` is valid
+    fn is_valid(&self, address: &str) -> bool {
+        self.validate(address).is_ok()
+    }
+
+    /// Returns the canonical form of a valid address
+    ///
+    /// Surrounding whitespace is removed; chains with case rules apply them
+    /// (e.g. EIP-55 checksum casing).
+    fn normalize(&self, address: &str) -> WalletResult<String> {
+        let address = address.trim();
+        self.validate(address)?;
+        Ok(address.to_string())
+    }
+}
+
+fn invalid(address: &str, reason: &str) -> WalletError {
+    WalletError::InvalidAddress(format!("{}: {}", address, reason))
+}
+
+fn double_sha256(data: &[u8]) -> [u8; 32] {
+    Sha256::digest(Sha256::digest(data)).into()
+}
+
+/// Bitcoin addresses for one network
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BitcoinValidator {
+    hrp: &'static str,
+    p2pkh_version: u8,
+    p2sh_version: u8,
+}
+
+impl BitcoinValidator {
+    /// Mainnet addresses (`1...`, `3...`, `bc1...`)
+    pub fn mainnet() -> Self {
+        Self {
+            hrp: "bc",
+            p2pkh_version: 0x00,
+            p2sh_version: 0x05,
+        }
+    }
+
+    /// Testnet and signet addresses (`m...`, `n...`, `2...`, `tb1...`)
+    pub fn testnet() -> Self {
+        Self {
+            hrp: "tb",
+            p2pkh_version: 0x6f,
+            p2sh_version: 0xc4,
+        }
+    }
+}
+
+impl AddressValidator for BitcoinValidator {
+    fn validate(&self, address: &str) -> WalletResult<()> {
+        let prefix = format!("{}1", self.hrp);
+        if address.to_ascii_lowercase().starts_with(&prefix) {
+            let (hrp, _, _) = bech32::segwit::decode(address)
+                .map_err(|e| invalid(address, &e.to_string()))?;
+            if hrp.to_lowercase() != self.hrp {
+                return Err(invalid(address, "wrong network"));
+            }
+            return Ok(());
+        }
+
+        let data = bs58::decode(address)
+            .into_vec()
+            .map_err(|e| invalid(address, &e.to_string()))?;
+        if data.len() != 25 {
+            return Err(invalid(address, "wrong length"));
+        }
+        if double_sha256(&data[..21])[..4] != data[21..] {
+            return Err(invalid(address, "bad checksum"));
+        }
+        if data[0] != self.p2pkh_version && data[0] != self.p2sh_version {
+            return Err(invalid(address, "wrong network"));
+        }
+        Ok(())
+    }
+
+    fn normalize(&self, address: &str) -> WalletResult<String> {
+        let address = address.trim();
+        self.validate(address)?;
+        // Bech32 addresses are case-insensitive; lowercase is canonical
+        if address.to_ascii_lowercase().starts_with(&format!("{}1", self.hrp)) {
+            Ok(address.to_ascii_lowercase())
+        } else {
+            Ok(address.to_string())
+        }
+    }
+}
+
+/// EVM addresses (`0x` + 20 bytes hex, EIP-55 checksum when mixed-case)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EvmValidator;
+
+impl EvmValidator {
+    /// Returns the EIP-55 checksummed form of a 20-byte hex address
+    pub fn checksum(hex_address: &str) -> String {
+        let lower = hex_address.trim_start_matches("0x").to_ascii_lowercase();
+        let hash = Keccak256::digest(lower.as_bytes());
+        let mut out = String::with_capacity(42);
+        out.push_str("0x");
+        for (i, c) in lower.chars().enumerate() {
+            let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0f;
+            if c.is_ascii_alphabetic() && nibble >= 8 {
+                out.push(c.to_ascii_uppercase());
+            } else {
+                out.push(c);
+            }
+        }
+        out
+    }
+}
+
+impl AddressValidator for EvmValidator {
+    fn validate(&self, address: &str) -> WalletResult<()> {
+        let digits = address
+            .strip_prefix("0x")
+            .ok_or_else(|| invalid(address, "missing 0x prefix"))?;
+        if digits.len() != 40 || !digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid(address, "expected 40 hex digits"));
+        }
+        let has_upper = digits.chars().any(|c| c.is_ascii_uppercase());
+        let has_lower = digits.chars().any(|c| c.is_ascii_lowercase());
+        if has_upper && has_lower && Self::checksum(address) != address {
+            return Err(invalid(address, "bad EIP-55 checksum"));
+        }
+        Ok(())
+    }
+
+    fn normalize(&self, address: &str) -> WalletResult<String> {
+        let address = address.trim();
+        self.validate(address)?;
+        Ok(Self::checksum(address))
+    }
+}
+
+/// Solana addresses (base58-encoded 32-byte public keys)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolanaValidator;
+
+impl AddressValidator for SolanaValidator {
+    fn validate(&self, address: &str) -> WalletResult<()> {
+        let data = bs58::decode(address)
+            .into_vec()
+            .map_err(|e| invalid(address, &e.to_string()))?;
+        if data.len() != 32 {
+            return Err(invalid(address, "expected 32 bytes"));
+        }
+        Ok(())
+    }
+}
+
+/// TON addresses, raw (`0:<hex>`) or user-friendly (48 base64 characters)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct TonValidator;
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 {
+                (crc << 1) ^ 0x1021
+            } else {
+                crc << 1
+            };
+        }
+    }
+    crc
+}
+
+impl AddressValidator for TonValidator {
+    fn validate(&self, address: &str) -> WalletResult<()> {
+        if let Some((workchain, hash)) = address.split_once(':') {
+            if workchain.parse::<i32>().is_err() {
+                return Err(invalid(address, "bad workchain"));
+            }
+            if hash.len() != 64 || !hash.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(invalid(address, "expected 64 hex digits"));
+            }
+            return Ok(());
+        }
+
+        if address.len() != 48 {
+            return Err(invalid(address, "expected 48 characters"));
+        }
+        // Both URL-safe and standard base64 are in use
+        let normalized = address.replace('-', "+").replace('_', "/");
+        let data = base64::engine::general_purpose::STANDARD
+            .decode(normalized)
+            .map_err(|e| invalid(address, &e.to_string()))?;
+        if data.len() != 36 {
+            return Err(invalid(address, "expected 36 bytes"));
+        }
+        let flags = data[0] & 0x7f;
+        if flags != 0x11 && flags != 0x51 {
+            return Err(invalid(address, "bad flags"));
+        }
+        if crc16_xmodem(&data[..34]).to_be_bytes() != data[34..] {
+            return Err(invalid(address, "bad checksum"));
+        }
+        Ok(())
+    }
+}
+
+/// Cosmos SDK account addresses (bech32 with a chain prefix)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CosmosValidator {
+    prefix: String,
+}
+
+impl CosmosValidator {
+    /// Addresses with the given bech32 prefix (e.g. `"osmo"`)
+    pub fn new(prefix: impl Into<String>) -> Self {
+        Self {
+            prefix: prefix.into(),
+        }
+    }
+}
+
+impl Default for CosmosValidator {
+    fn default() -> Self {
+        Self::new("cosmos")
+    }
+}
+
+impl AddressValidator for CosmosValidator {
+    fn validate(&self, address: &str) -> WalletResult<()> {
+        let (hrp, data) = bech32::decode(address).map_err(|e| invalid(address, &e.to_string()))?;
+        if hrp.as_str() != self.prefix {
+            return Err(invalid(address, "wrong prefix"));
+        }
+        // Accounts are 20 bytes; module and contract accounts are 32
+        if data.len() != 20 && data.len() != 32 {
+            return Err(invalid(address, "wrong length"));
+        }
+        Ok(())
+    }
+
+    fn normalize(&self, address: &str) -> WalletResult<String> {
+        let address = address.trim();
+        self.validate(address)?;
+        Ok(address.to_ascii_lowercase())
+    }
+}
+
+/// Maps chains to their address rules
+#[derive(Clone)]
+pub struct ChainRegistry {
+    validators: HashMap<Chain, Arc<dyn AddressValidator>>,
+}
+
+impl ChainRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self {
+            validators: HashMap::new(),
+        }
+    }
+
+    /// Creates a registry with the built-in mainnet rules for every [`Chain`]
+    pub fn with_defaults() -> Self {
+        Self::new()
+            .with(Chain::Bitcoin, BitcoinValidator::mainnet())
+            .with(Chain::Ethereum, EvmValidator)
+            .with(Chain::Solana, SolanaValidator)
+            .with(Chain::Ton, TonValidator)
+            .with(Chain::Cosmos, CosmosValidator::default())
+    }
+
+    /// Sets the validator for `chain`, replacing any existing one
+    pub fn with(mut self, chain: Chain, validator: impl AddressValidator + 'static) -> Self {
+        self.register(chain, validator);
+        self
+    }
+
+    /// Sets the validator for `chain`, replacing any existing one
+    pub fn register(&mut self, chain: Chain, validator: impl AddressValidator + 'static) {
+        self.validators.insert(chain, Arc::new(validator));
+    }
+
+    /// Returns the validator for `chain`
+    pub fn validator(&self, chain: Chain) -> Option<&dyn AddressValidator> {
+        self.validators.get(&chain).map(|v| v.as_ref())
+    }
+
+    /// Validates an address for `chain`
+    pub fn validate(&self, chain: Chain, address: &str) -> WalletResult<()> {
+        self.validator(chain)
+            .ok_or_else(|| WalletError::NotSupported(format!("no address rules for {}", chain)))?
+            .validate(address.trim())
+    }
+
+    /// Returns the canonical form of an address for `chain`
+    pub fn normalize(&self, chain: Chain, address: &str) -> WalletResult<String> {
+        self.validator(chain)
+            .ok_or_else(|| WalletError::NotSupported(format!("no address rules for {}", chain)))?
+            .normalize(address)
+    }
+
+    /// Returns every registered chain the address is valid for
+    ///
+    /// EVM-style addresses match [`Chain::Ethereum`] only; callers that
+    /// support several EVM networks decide which one is meant.
+    pub fn detect(&self, address: &str) -> Vec<Chain> {
+        let address = address.trim();
+        Chain::ALL
+            .into_iter()
+            .filter(|chain| self.validator(*chain).is_some_and(|v| v.is_valid(address)))
+            .collect()
+    }
+}
+
+impl Default for ChainRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl fmt::Debug for ChainRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ChainRegistry")
+            .field("chains", &self.validators.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bitcoin() {
+        let btc = BitcoinValidator::mainnet();
+        assert!(btc.is_valid("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"));
+        assert!(btc.is_valid("3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy"));
+        assert!(btc.is_valid("bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"));
+        assert!(btc.is_valid("bc1p5d7rjq7g6rdk2yhzks9smlaqtedr4dekq08ge8ztwac72sfr9rusxg3297"));
+        assert!(!btc.is_valid("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNb"));
+        assert!(!btc.is_valid("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"));
+        assert!(BitcoinValidator::testnet().is_valid("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"));
+        assert_eq!(
+            btc.normalize("BC1QAR0SRRR7XFKVY5L643LYDNW9RE59GTZZWF5MDQ").unwrap(),
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq"
+        );
+    }
+
+    #[test]
+    fn test_evm_checksum() {
+        let evm = EvmValidator;
+        let checksummed = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed";
+        assert!(evm.is_valid(checksummed));
+        assert!(evm.is_valid(&checksummed.to_ascii_lowercase()));
+        assert!(!evm.is_valid("0x5aAeb6053f3E94C9b9A09f33669435E7Ef1BeAed"));
+        assert!(!evm.is_valid("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeA"));
+        assert_eq!(evm.normalize(&checksummed.to_ascii_lowercase()).unwrap(), checksummed);
+    }
+
+    #[test]
+    fn test_solana_ton_cosmos() {
+        assert!(SolanaValidator.is_valid("11111111111111111111111111111111"));
+        assert!(SolanaValidator.is_valid("So11111111111111111111111111111111111111112"));
+        assert!(!SolanaValidator.is_valid("0OIl"));
+
+        assert!(TonValidator.is_valid("EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q2"));
+        assert!(TonValidator.is_valid(
+            "0:ed1691307050047117b998b561d8de82d31fbf84910ced6eb5fc92e7485ef8a7"
+        ));
+        assert!(!TonValidator.is_valid("EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q3"));
+
+        let hrp = bech32::Hrp::parse("cosmos").unwrap();
+        let atom = bech32::encode::<bech32::Bech32>(hrp, &[7u8; 20]).unwrap();
+        assert!(CosmosValidator::default().is_valid(&atom));
+        assert!(!CosmosValidator::new("osmo").is_valid(&atom));
+    }
+
+    #[test]
+    fn test_registry_detect() {
+        let registry = ChainRegistry::default();
+        assert_eq!(
+            registry.detect(" 0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed "),
+            vec![Chain::Ethereum]
+        );
+        assert_eq!(registry.detect("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"), vec![Chain::Bitcoin]);
+        assert_eq!(registry.detect("So11111111111111111111111111111111111111112"), vec![Chain::Solana]);
+        assert_eq!(registry.detect("EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q2"), vec![Chain::Ton]);
+        assert!(registry.detect("not an address").is_empty());
+
+        assert!(registry.validate(Chain::Bitcoin, "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed").is_err());
+        assert!(matches!(
+            ChainRegistry::new().validate(Chain::Solana, "11111111111111111111111111111111"),
+            Err(WalletError::NotSupported(_))
+        ));
+
+        // Rules can be replaced, e.g. for a testnet deployment
+        let testnet = ChainRegistry::default().with(Chain::Bitcoin, BitcoinValidator::testnet());
+        assert_eq!(testnet.detect("tb1qw508d6qejxtdg4y5r3zarvary0c5xw7kxpjzsx"), vec![Chain::Bitcoin]);
+    }
+
+    #[test]
+    fn test_chain_from_str() {
+        assert_eq!("solana".parse::<Chain>().unwrap(), Chain::Solana);
+        assert_eq!(" ETH ".parse::<Chain>().unwrap(), Chain::Ethereum);
+        for chain in Chain::ALL {
+            assert_eq!(chain.to_string().parse::<Chain>().unwrap(), chain);
+        }
+        assert!("dogecoin".parse::<Chain>().is_err());
+    }
+}
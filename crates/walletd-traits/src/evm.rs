@@ -0,0 +1,990 @@
+//! EVM transaction encoding and signing
+//!
+//! [`TxRequest`] reads transactions from the JSON shape wallets and
+//! `eth_fillTransaction` use (`chainId`, `nonce`, `to`, `value`, `data`,
+//! `gas`, and either `gasPrice` or `maxFeePerGas`/`maxPriorityFeePerGas`), or
+//! decodes the unsigned RLP payload that gets hashed for signing. Legacy
+//! (EIP-155), access-list (EIP-2930) and EIP-1559 transactions are supported.
+//! Quantities may be numbers, decimal strings or `0x` hex strings.
+//! [`payment_tx`] turns an EIP-681 payment request into the same JSON shape,
+//! and [`Erc20Call`] decodes token transfer and approval call data.
+//!
+//! Signing with a [`Signer`](crate::Signer) needs the `secp256k1` feature;
+//! without it, sign [`TxRequest::signing_hash`] with any secp256k1 key and
+//! attach the result with [`TxRequest::with_signature`].
+
+use crate::{Chain, PaymentAmount, PaymentRequest, WalletError};
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+
+/// Deepest list nesting accepted when decoding; transactions need 3
+const MAX_DEPTH: usize = 16;
+
+/// `transfer(address,uint256)` selector
+const ERC20_TRANSFER: [u8; 4] = [0xa9, 0x05, 0x9c, 0xbb];
+
+/// `approve(address,uint256)` selector
+const ERC20_APPROVE: [u8; 4] = [0x09, 0x5e, 0xa7, 0xb3];
+
+/// Errors from EVM transaction encoding and signing
+#[derive(Debug, thiserror::Error)]
+pub enum EvmError {
+    /// A missing, malformed or out-of-range field
+    #[error("Invalid transaction: {0}")]
+    Invalid(String),
+
+    /// A transaction type this module cannot sign
+    #[error("Not supported: {0}")]
+    NotSupported(String),
+
+    /// The signer failed
+    #[error(transparent)]
+    Wallet(#[from] WalletError),
+}
+
+/// Result type for EVM transaction handling
+pub type Result<T> = std::result::Result<T, EvmError>;
+
+impl From<EvmError> for WalletError {
+    fn from(e: EvmError) -> Self {
+        match e {
+            EvmError::Invalid(reason) => WalletError::TransactionFailed(reason),
+            EvmError::NotSupported(reason) => WalletError::NotSupported(reason),
+            EvmError::Wallet(e) => e,
+        }
+    }
+}
+
+/// An RLP item
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rlp {
+    /// A byte string
+    Bytes(Vec<u8>),
+    /// A list of items
+    List(Vec<Rlp>),
+}
+
+impl Rlp {
+    /// An unsigned integer in its minimal big-endian form
+    pub fn uint(value: u128) -> Self {
+        Rlp::Bytes(trim(&value.to_be_bytes()))
+    }
+
+    /// Reads an unsigned integer of at most 16 bytes
+    pub fn as_uint(&self) -> Result<u128> {
+        match self {
+            Rlp::Bytes(bytes) if bytes.len() <= 16 => {
+                Ok(bytes.iter().fold(0u128, |acc, b| acc << 8 | u128::from(*b)))
+            }
+            _ => Err(invalid("expected an integer")),
+        }
+    }
+
+    /// Encodes the item
+    pub fn encode(&self) -> Vec<u8> {
+        match self {
+            Rlp::Bytes(bytes) if bytes.len() == 1 && bytes[0] < 0x80 => bytes.clone(),
+            Rlp::Bytes(bytes) => {
+                let mut out = header(0x80, bytes.len());
+                out.extend_from_slice(bytes);
+                out
+            }
+            Rlp::List(items) => {
+                let payload: Vec<u8> = items.iter().flat_map(Rlp::encode).collect();
+                let mut out = header(0xc0, payload.len());
+                out.extend(payload);
+                out
+            }
+        }
+    }
+
+    /// Decodes one item, returning it and the bytes that follow
+    ///
+    /// Lists nested more than 16 deep are rejected rather than recursed into.
+    pub fn decode(data: &[u8]) -> Result<(Self, &[u8])> {
+        Self::decode_at(data, 0)
+    }
+
+    fn decode_at(data: &[u8], depth: usize) -> Result<(Self, &[u8])> {
+        let (&first, rest) = data.split_first().ok_or_else(|| invalid("truncated RLP"))?;
+        let (is_list, len, rest) = match first {
+            0x00..=0x7f => return Ok((Rlp::Bytes(vec![first]), rest)),
+            0x80..=0xb7 => (false, usize::from(first - 0x80), rest),
+            0xb8..=0xbf => {
+                let (len, rest) = long_length(rest, first - 0xb7)?;
+                (false, len, rest)
+            }
+            0xc0..=0xf7 => (true, usize::from(first - 0xc0), rest),
+            0xf8..=0xff => {
+                let (len, rest) = long_length(rest, first - 0xf7)?;
+                (true, len, rest)
+            }
+        };
+        if rest.len() < len {
+            return Err(invalid("truncated RLP"));
+        }
+        let (mut body, rest) = rest.split_at(len);
+        if !is_list {
+            return Ok((Rlp::Bytes(body.to_vec()), rest));
+        }
+        if depth >= MAX_DEPTH {
+            return Err(invalid("RLP nested too deeply"));
+        }
+        let mut items = Vec::new();
+        while !body.is_empty() {
+            let (item, tail) = Rlp::decode_at(body, depth + 1)?;
+            items.push(item);
+            body = tail;
+        }
+        Ok((Rlp::List(items), rest))
+    }
+
+    fn as_bytes(&self) -> Result<&[u8]> {
+        match self {
+            Rlp::Bytes(bytes) => Ok(bytes),
+            Rlp::List(_) => Err(invalid("expected a byte string")),
+        }
+    }
+}
+
+fn header(offset: u8, len: usize) -> Vec<u8> {
+    if len < 56 {
+        return vec![offset + len as u8];
+    }
+    let len = trim(&len.to_be_bytes());
+    let mut out = vec![offset + 55 + len.len() as u8];
+    out.extend(len);
+    out
+}
+
+fn long_length(data: &[u8], size: u8) -> Result<(usize, &[u8])> {
+    let size = usize::from(size);
+    if size > 8 || data.len() < size {
+        return Err(invalid("bad RLP length"));
+    }
+    let len = data[..size]
+        .iter()
+        .fold(0u64, |acc, b| acc << 8 | u64::from(*b));
+    let len = usize::try_from(len).map_err(|_| invalid("bad RLP length"))?;
+    Ok((len, &data[size..]))
+}
+
+/// Fee fields of a transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Fees {
+    Legacy {
+        gas_price: u128,
+    },
+    AccessList {
+        gas_price: u128,
+    },
+    Eip1559 {
+        max_fee_per_gas: u128,
+        max_priority_fee_per_gas: u128,
+    },
+}
+
+/// Access list entries: an address and its storage keys
+type AccessList = Vec<(Vec<u8>, Vec<Vec<u8>>)>;
+
+/// A parsed, unsigned EVM transaction
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TxRequest {
+    chain_id: u64,
+    nonce: u64,
+    to: Option<Vec<u8>>,
+    value: u128,
+    data: Vec<u8>,
+    gas_limit: u64,
+    fees: Fees,
+    access_list: AccessList,
+}
+
+/// A signed transaction ready for `eth_sendRawTransaction`
+#[derive(Debug, Clone)]
+pub struct SignedTx {
+    /// Encoded transaction
+    pub raw: Vec<u8>,
+    /// Transaction hash
+    pub hash: [u8; 32],
+}
+
+impl TxRequest {
+    /// Parses a transaction object
+    ///
+    /// The type is taken from `type` if present, otherwise from the fee
+    /// fields.
+    pub fn from_json(tx: &Value) -> Result<Self> {
+        let chain_id = quantity(tx, &["chainId"])?.ok_or_else(|| invalid("missing chainId"))?;
+        let to = match tx["to"].as_str() {
+            Some(to) if !to.is_empty() => Some(hex_field(to, "to", Some(20))?),
+            _ => None,
+        };
+        let data = match tx["data"].as_str().or(tx["input"].as_str()) {
+            Some(data) => hex_field(data, "data", None)?,
+            None => Vec::new(),
+        };
+        let fees = match (
+            quantity(tx, &["type"])?,
+            quantity(tx, &["maxFeePerGas"])?,
+            quantity(tx, &["maxPriorityFeePerGas"])?,
+            quantity(tx, &["gasPrice"])?,
+        ) {
+            (None | Some(2), Some(max_fee_per_gas), Some(max_priority_fee_per_gas), _) => {
+                Fees::Eip1559 {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                }
+            }
+            (None | Some(0), None, None, Some(gas_price)) => Fees::Legacy { gas_price },
+            (Some(1), None, None, Some(gas_price)) => Fees::AccessList { gas_price },
+            (Some(tx_type), ..) if tx_type > 2 => {
+                return Err(EvmError::NotSupported(format!(
+                    "EVM transaction type {}",
+                    tx_type
+                )))
+            }
+            _ => {
+                return Err(invalid(
+                    "set gasPrice, or both maxFeePerGas and maxPriorityFeePerGas",
+                ))
+            }
+        };
+        let mut access_list = Vec::new();
+        for item in tx["accessList"].as_array().into_iter().flatten() {
+            let address = hex_field(
+                item["address"].as_str().unwrap_or_default(),
+                "accessList address",
+                Some(20),
+            )?;
+            let keys = item["storageKeys"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .map(|key| hex_field(key.as_str().unwrap_or_default(), "storageKeys", Some(32)))
+                .collect::<Result<Vec<_>>>()?;
+            access_list.push((address, keys));
+        }
+        if !access_list.is_empty() && matches!(fees, Fees::Legacy { .. }) {
+            return Err(invalid("accessList needs a typed transaction"));
+        }
+        let tx = Self {
+            chain_id: u64_field(Some(chain_id), "chainId")?,
+            nonce: u64_field(quantity(tx, &["nonce"])?, "nonce")?,
+            to,
+            value: quantity(tx, &["value"])?.unwrap_or(0),
+            data,
+            gas_limit: u64_field(quantity(tx, &["gas", "gasLimit"])?, "gas")?,
+            fees,
+            access_list,
+        };
+        tx.check_chain_id()?;
+        Ok(tx)
+    }
+
+    /// Decodes an unsigned payload as produced by [`signing_payload`]
+    ///
+    /// Only the canonical encoding is accepted, so the payload is exactly
+    /// what gets signed.
+    ///
+    /// [`signing_payload`]: TxRequest::signing_payload
+    pub fn decode(payload: &[u8]) -> Result<Self> {
+        let (tx_type, body) = match payload.first() {
+            Some(&t) if t < 0x80 => (Some(t), &payload[1..]),
+            _ => (None, payload),
+        };
+        if let Some(tx_type) = tx_type.filter(|t| *t > 2) {
+            return Err(EvmError::NotSupported(format!(
+                "EVM transaction type {}",
+                tx_type
+            )));
+        }
+        let (item, rest) = Rlp::decode(body)?;
+        let Rlp::List(fields) = item else {
+            return Err(invalid("transaction must be an RLP list"));
+        };
+        if !rest.is_empty() {
+            return Err(invalid("trailing bytes after transaction"));
+        }
+
+        let empty = Rlp::Bytes(Vec::new());
+        let tx = match (tx_type, &fields[..]) {
+            (None, [nonce, gas_price, gas_limit, to, value, data, chain_id, r, s])
+                if *r == empty && *s == empty =>
+            {
+                Self {
+                    chain_id: u64_item(chain_id, "chainId")?,
+                    nonce: u64_item(nonce, "nonce")?,
+                    to: to_item(to)?,
+                    value: value.as_uint()?,
+                    data: data.as_bytes()?.to_vec(),
+                    gas_limit: u64_item(gas_limit, "gas")?,
+                    fees: Fees::Legacy {
+                        gas_price: gas_price.as_uint()?,
+                    },
+                    access_list: Vec::new(),
+                }
+            }
+            (None, [_, _, _, _, _, _]) => {
+                return Err(EvmError::NotSupported(
+                    "pre-EIP-155 transactions without a chain id".into(),
+                ))
+            }
+            (Some(1), [chain_id, nonce, gas_price, gas_limit, to, value, data, access_list]) => {
+                Self {
+                    chain_id: u64_item(chain_id, "chainId")?,
+                    nonce: u64_item(nonce, "nonce")?,
+                    to: to_item(to)?,
+                    value: value.as_uint()?,
+                    data: data.as_bytes()?.to_vec(),
+                    gas_limit: u64_item(gas_limit, "gas")?,
+                    fees: Fees::AccessList {
+                        gas_price: gas_price.as_uint()?,
+                    },
+                    access_list: access_list_item(access_list)?,
+                }
+            }
+            (
+                Some(2),
+                [chain_id, nonce, max_priority_fee_per_gas, max_fee_per_gas, gas_limit, to, value, data, access_list],
+            ) => Self {
+                chain_id: u64_item(chain_id, "chainId")?,
+                nonce: u64_item(nonce, "nonce")?,
+                to: to_item(to)?,
+                value: value.as_uint()?,
+                data: data.as_bytes()?.to_vec(),
+                gas_limit: u64_item(gas_limit, "gas")?,
+                fees: Fees::Eip1559 {
+                    max_fee_per_gas: max_fee_per_gas.as_uint()?,
+                    max_priority_fee_per_gas: max_priority_fee_per_gas.as_uint()?,
+                },
+                access_list: access_list_item(access_list)?,
+            },
+            _ => return Err(invalid("unexpected number of transaction fields")),
+        };
+        tx.check_chain_id()?;
+        if tx.signing_payload() != payload {
+            return Err(invalid("non-canonical RLP encoding"));
+        }
+        Ok(tx)
+    }
+
+    /// Chain id the transaction is replay-protected for
+    pub fn chain_id(&self) -> u64 {
+        self.chain_id
+    }
+
+    /// Recipient, `None` for contract creation
+    pub fn to(&self) -> Option<&[u8]> {
+        self.to.as_deref()
+    }
+
+    /// Value in wei
+    pub fn value(&self) -> u128 {
+        self.value
+    }
+
+    /// Call data
+    pub fn data(&self) -> &[u8] {
+        &self.data
+    }
+
+    /// EIP-2718 type byte, `None` for legacy transactions
+    fn tx_type(&self) -> Option<u8> {
+        match self.fees {
+            Fees::Legacy { .. } => None,
+            Fees::AccessList { .. } => Some(1),
+            Fees::Eip1559 { .. } => Some(2),
+        }
+    }
+
+    fn check_chain_id(&self) -> Result<()> {
+        if self.chain_id == 0 {
+            return Err(invalid("chainId must be nonzero"));
+        }
+        if self.tx_type().is_none() && eip155_v(self.chain_id, 1).is_none() {
+            return Err(invalid("chainId too large for EIP-155"));
+        }
+        Ok(())
+    }
+
+    /// Fields shared by the signing payload and the signed encoding
+    fn fields(&self) -> Vec<Rlp> {
+        let mut fields = Vec::new();
+        if self.tx_type().is_some() {
+            fields.push(Rlp::uint(self.chain_id.into()));
+        }
+        fields.push(Rlp::uint(self.nonce.into()));
+        match self.fees {
+            Fees::Legacy { gas_price } | Fees::AccessList { gas_price } => {
+                fields.push(Rlp::uint(gas_price))
+            }
+            Fees::Eip1559 {
+                max_fee_per_gas,
+                max_priority_fee_per_gas,
+            } => {
+                fields.push(Rlp::uint(max_priority_fee_per_gas));
+                fields.push(Rlp::uint(max_fee_per_gas));
+            }
+        }
+        fields.extend([
+            Rlp::uint(self.gas_limit.into()),
+            Rlp::Bytes(self.to.clone().unwrap_or_default()),
+            Rlp::uint(self.value),
+            Rlp::Bytes(self.data.clone()),
+        ]);
+        if self.tx_type().is_some() {
+            fields.push(Rlp::List(
+                self.access_list
+                    .iter()
+                    .map(|(address, keys)| {
+                        Rlp::List(vec![
+                            Rlp::Bytes(address.clone()),
+                            Rlp::List(keys.iter().cloned().map(Rlp::Bytes).collect()),
+                        ])
+                    })
+                    .collect(),
+            ));
+        }
+        fields
+    }
+
+    /// The bytes whose keccak-256 is signed
+    ///
+    /// EIP-155 signs `rlp([..., chainId, 0, 0])`; typed transactions sign
+    /// `type || rlp([chainId, ...])`.
+    pub fn signing_payload(&self) -> Vec<u8> {
+        let mut fields = self.fields();
+        match self.tx_type() {
+            None => {
+                fields.extend([
+                    Rlp::uint(self.chain_id.into()),
+                    Rlp::Bytes(Vec::new()),
+                    Rlp::Bytes(Vec::new()),
+                ]);
+                Rlp::List(fields).encode()
+            }
+            Some(tx_type) => [vec![tx_type], Rlp::List(fields).encode()].concat(),
+        }
+    }
+
+    /// Keccak-256 of [`signing_payload`](TxRequest::signing_payload)
+    pub fn signing_hash(&self) -> [u8; 32] {
+        Keccak256::digest(self.signing_payload()).into()
+    }
+
+    /// Attaches a 64-byte `r || s` signature over
+    /// [`signing_hash`](TxRequest::signing_hash) and its recovery id
+    pub fn with_signature(&self, signature: &[u8], recovery_id: u8) -> Result<SignedTx> {
+        if signature.len() != 64 || recovery_id > 1 {
+            return Err(invalid(
+                "expected a 64-byte signature and recovery id 0 or 1",
+            ));
+        }
+        let v = match self.tx_type() {
+            None => eip155_v(self.chain_id, recovery_id)
+                .ok_or_else(|| invalid("chainId too large for EIP-155"))?,
+            Some(_) => u64::from(recovery_id),
+        };
+        let mut fields = self.fields();
+        fields.extend([
+            Rlp::uint(v.into()),
+            Rlp::Bytes(trim(&signature[..32])),
+            Rlp::Bytes(trim(&signature[32..])),
+        ]);
+        let mut raw: Vec<u8> = self.tx_type().into_iter().collect();
+        raw.extend(Rlp::List(fields).encode());
+        Ok(SignedTx {
+            hash: Keccak256::digest(&raw).into(),
+            raw,
+        })
+    }
+
+    /// Signs with a secp256k1 signer
+    #[cfg(feature = "secp256k1")]
+    pub async fn sign(&self, signer: &dyn crate::Signer) -> Result<SignedTx> {
+        let (signature, recovery_id) = sign_recoverable(signer, &self.signing_hash()).await?;
+        self.with_signature(&signature, recovery_id)
+    }
+}
+
+/// Signs `message` with the EIP-191 prefix, returning `r || s || v`
+#[cfg(feature = "secp256k1")]
+pub async fn personal_sign(signer: &dyn crate::Signer, message: &[u8]) -> Result<Vec<u8>> {
+    let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+    prefixed.extend_from_slice(message);
+    let hash: [u8; 32] = Keccak256::digest(&prefixed).into();
+    let (mut signature, recovery_id) = sign_recoverable(signer, &hash).await?;
+    signature.push(27 + recovery_id);
+    Ok(signature)
+}
+
+/// Checksummed address of a secp256k1 signer
+#[cfg(feature = "secp256k1")]
+pub fn address(signer: &dyn crate::Signer) -> Result<String> {
+    let public_key = secp256k1::PublicKey::from_slice(&signer.public_key()).map_err(key_error)?;
+    let hash = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+    Ok(crate::EvmValidator::checksum(&hex::encode(&hash[12..])))
+}
+
+/// Transaction fields paying an EVM payment request
+///
+/// Native payments become `to`/`value`; token payments an ERC-20
+/// `transfer` call, which needs an amount in base units. The request's
+/// chain id is kept; gas, fees and nonce are left to the caller.
+pub fn payment_tx(request: &PaymentRequest) -> Result<Value> {
+    if request.chain != Chain::Ethereum {
+        return Err(invalid(format!(
+            "{} payment request is not EVM",
+            request.chain
+        )));
+    }
+    let mut tx = match &request.token {
+        Some(token) => {
+            let amount = request
+                .amount
+                .as_ref()
+                .ok_or_else(|| invalid("token payment request has no amount"))?;
+            let amount = match amount {
+                PaymentAmount::Units(_) => {
+                    return Err(invalid("token amount must be in base units"))
+                }
+                amount => amount.resolve(0)?.value,
+            };
+            let mut data = ERC20_TRANSFER.to_vec();
+            data.extend([0u8; 12]);
+            data.extend(hex_field(&request.address, "address", Some(20))?);
+            data.extend([0u8; 16]);
+            data.extend(amount.to_be_bytes());
+            json!({ "to": token, "data": format!("0x{}", hex::encode(data)) })
+        }
+        None => {
+            let value = match &request.amount {
+                Some(amount) => amount.resolve(18)?.value,
+                None => 0,
+            };
+            json!({ "to": request.address, "value": value.to_string() })
+        }
+    };
+    if let Some(chain_id) = request.chain_id {
+        tx["chainId"] = json!(chain_id);
+    }
+    Ok(tx)
+}
+
+/// An ERC-20 `transfer` or `approve` call
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Erc20Call {
+    /// Whether this is an `approve` rather than a `transfer`
+    pub approve: bool,
+    /// Recipient of the tokens, or the spender of an approval (`0x` hex)
+    pub recipient: String,
+    /// Amount in the token's smallest unit; amounts above `u128::MAX`
+    /// saturate
+    pub amount: u128,
+}
+
+impl Erc20Call {
+    /// Decodes call data, returning `None` for anything else
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        if data.len() != 68 {
+            return None;
+        }
+        let approve = match data[..4].try_into().ok()? {
+            ERC20_TRANSFER => false,
+            ERC20_APPROVE => true,
+            _ => return None,
+        };
+        // ABI words: a left-padded address, then a uint256
+        let (address, amount) = (&data[4..36], &data[36..68]);
+        if address[..12].iter().any(|b| *b != 0) {
+            return None;
+        }
+        let amount = if amount[..16].iter().any(|b| *b != 0) {
+            u128::MAX
+        } else {
+            u128::from_be_bytes(amount[16..].try_into().ok()?)
+        };
+        Some(Self {
+            approve,
+            recipient: format!("0x{}", hex::encode(&address[12..])),
+            amount,
+        })
+    }
+}
+
+/// `chainId * 2 + 35 + recovery_id`, or `None` if it overflows
+fn eip155_v(chain_id: u64, recovery_id: u8) -> Option<u64> {
+    chain_id
+        .checked_mul(2)?
+        .checked_add(35 + u64::from(recovery_id))
+}
+
+/// Signs a digest and works out the recovery id the signer does not return
+#[cfg(feature = "secp256k1")]
+async fn sign_recoverable(signer: &dyn crate::Signer, hash: &[u8; 32]) -> Result<(Vec<u8>, u8)> {
+    if signer.scheme() != crate::SignatureScheme::Secp256k1 {
+        return Err(key_error("EVM signing needs a secp256k1 key"));
+    }
+    let signature = signer.sign_hash(hash).await?;
+    let recovery_id = walletd_core::recovery_id(hash, &signature, &signer.public_key())
+        .ok_or_else(|| key_error("signature does not recover to the signer's key"))?;
+    Ok((signature, recovery_id))
+}
+
+/// Reads the first present field as a quantity
+fn quantity(tx: &Value, fields: &[&str]) -> Result<Option<u128>> {
+    let Some((field, value)) = fields
+        .iter()
+        .find_map(|field| Some((field, tx.get(field).filter(|v| !v.is_null())?)))
+    else {
+        return Ok(None);
+    };
+    let parsed = match value {
+        Value::Number(n) => n.as_u64().map(u128::from),
+        Value::String(s) => match s.strip_prefix("0x") {
+            Some("") => Some(0),
+            Some(digits) => u128::from_str_radix(digits, 16).ok(),
+            None => s.parse().ok(),
+        },
+        _ => None,
+    };
+    parsed
+        .map(Some)
+        .ok_or_else(|| invalid(format!("invalid {}: {}", field, value)))
+}
+
+fn hex_field(value: &str, field: &str, len: Option<usize>) -> Result<Vec<u8>> {
+    let bytes = hex::decode(value.strip_prefix("0x").unwrap_or(value))
+        .map_err(|_| invalid(format!("invalid hex in {}", field)))?;
+    match len {
+        Some(len) if bytes.len() != len => Err(invalid(format!("{} must be {} bytes", field, len))),
+        _ => Ok(bytes),
+    }
+}
+
+fn u64_field(value: Option<u128>, field: &str) -> Result<u64> {
+    let value = value.ok_or_else(|| invalid(format!("missing {}", field)))?;
+    u64::try_from(value).map_err(|_| invalid(format!("{} out of range", field)))
+}
+
+fn u64_item(item: &Rlp, field: &str) -> Result<u64> {
+    u64_field(Some(item.as_uint()?), field)
+}
+
+fn to_item(item: &Rlp) -> Result<Option<Vec<u8>>> {
+    match item.as_bytes()? {
+        [] => Ok(None),
+        to if to.len() == 20 => Ok(Some(to.to_vec())),
+        _ => Err(invalid("to must be 20 bytes")),
+    }
+}
+
+fn access_list_item(item: &Rlp) -> Result<AccessList> {
+    let Rlp::List(entries) = item else {
+        return Err(invalid("accessList must be a list"));
+    };
+    entries
+        .iter()
+        .map(|entry| match entry {
+            Rlp::List(pair) => match &pair[..] {
+                [Rlp::Bytes(address), Rlp::List(keys)] if address.len() == 20 => {
+                    let keys = keys
+                        .iter()
+                        .map(|key| match key {
+                            Rlp::Bytes(key) if key.len() == 32 => Ok(key.clone()),
+                            _ => Err(invalid("storageKeys must be 32 bytes")),
+                        })
+                        .collect::<Result<Vec<_>>>()?;
+                    Ok((address.clone(), keys))
+                }
+                _ => Err(invalid("malformed accessList entry")),
+            },
+            Rlp::Bytes(_) => Err(invalid("malformed accessList entry")),
+        })
+        .collect()
+}
+
+fn invalid(reason: impl Into<String>) -> EvmError {
+    EvmError::Invalid(reason.into())
+}
+
+#[cfg(feature = "secp256k1")]
+fn key_error(reason: impl ToString) -> EvmError {
+    WalletError::KeyError(reason.to_string()).into()
+}
+
+fn trim(bytes: &[u8]) -> Vec<u8> {
+    let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+    bytes[start..].to_vec()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EIP155_SIGNED: &str = "f86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83";
+
+    #[test]
+    fn test_rlp_encoding() {
+        assert_eq!(Rlp::Bytes(vec![]).encode(), vec![0x80]);
+        assert_eq!(Rlp::Bytes(vec![0x7f]).encode(), vec![0x7f]);
+        assert_eq!(Rlp::Bytes(vec![0x80]).encode(), vec![0x81, 0x80]);
+        assert_eq!(Rlp::List(vec![]).encode(), vec![0xc0]);
+        assert_eq!(&Rlp::Bytes(vec![0xaa; 56]).encode()[..2], &[0xb8, 56]);
+
+        let item = Rlp::List(vec![
+            Rlp::uint(1024),
+            Rlp::List(vec![Rlp::Bytes(vec![0xaa; 60])]),
+        ]);
+        assert_eq!(Rlp::decode(&item.encode()).unwrap(), (item, &[][..]));
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[tokio::test]
+    async fn test_eip155_vector() {
+        // Example from EIP-155
+        let signer = crate::Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+        let tx = TxRequest::from_json(&json!({
+            "chainId": 1,
+            "nonce": 9,
+            "gasPrice": "20000000000",
+            "gas": "0x5208",
+            "to": "0x3535353535353535353535353535353535353535",
+            "value": "1000000000000000000"
+        }))
+        .unwrap();
+        assert_eq!(
+            hex::encode(tx.signing_payload()),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+        assert_eq!(TxRequest::decode(&tx.signing_payload()).unwrap(), tx);
+
+        let signed = tx.sign(&signer).await.unwrap();
+        assert_eq!(hex::encode(&signed.raw), EIP155_SIGNED);
+        assert_eq!(
+            hex::encode(signed.hash),
+            "33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788"
+        );
+        assert_eq!(
+            address(&signer).unwrap(),
+            "0x9d8A62f656a8d1615C1294fd71e9CFb3E4855A4F"
+        );
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[tokio::test]
+    async fn test_typed_signatures_recover() {
+        use crate::Signer;
+
+        let signer = crate::Secp256k1Signer::from_slice(&[0x11; 32]).unwrap();
+        let access_list = json!([{
+            "address": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            "storageKeys": [format!("0x{}", "00".repeat(32))]
+        }]);
+        let eip1559 = json!({
+            "chainId": "0x2105",
+            "nonce": 0,
+            "maxFeePerGas": "0x77359400",
+            "maxPriorityFeePerGas": 1000000,
+            "gasLimit": 60000,
+            "to": "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48",
+            "data": "0xa9059cbb",
+            "accessList": access_list
+        });
+        let eip2930 = json!({
+            "type": 1,
+            "chainId": 1,
+            "nonce": 3,
+            "gasPrice": 1000,
+            "gas": 21000,
+            "accessList": access_list
+        });
+        for (json, tx_type) in [(eip1559, 0x02), (eip2930, 0x01)] {
+            let tx = TxRequest::from_json(&json).unwrap();
+            assert_eq!(TxRequest::decode(&tx.signing_payload()).unwrap(), tx);
+            let signed = tx.sign(&signer).await.unwrap();
+            assert_eq!(signed.raw[0], tx_type);
+
+            // The signed encoding is the unsigned fields plus y-parity, r, s
+            let (Rlp::List(items), _) = Rlp::decode(&signed.raw[1..]).unwrap() else {
+                panic!("not a list");
+            };
+            assert_eq!(items[..items.len() - 3], tx.fields()[..]);
+            let [v, r, s] = &items[items.len() - 3..] else {
+                unreachable!()
+            };
+            let mut rs = [0u8; 64];
+            let (r, s) = (r.as_bytes().unwrap(), s.as_bytes().unwrap());
+            rs[32 - r.len()..32].copy_from_slice(r);
+            rs[64 - s.len()..].copy_from_slice(s);
+            let recoverable = secp256k1::ecdsa::RecoverableSignature::from_compact(
+                &rs,
+                secp256k1::ecdsa::RecoveryId::from_i32(v.as_uint().unwrap() as i32).unwrap(),
+            )
+            .unwrap();
+            let message = secp256k1::Message::from_slice(&tx.signing_hash()).unwrap();
+            assert_eq!(
+                recoverable.recover(&message).unwrap().serialize().to_vec(),
+                signer.public_key()
+            );
+        }
+    }
+
+    #[cfg(feature = "secp256k1")]
+    #[tokio::test]
+    async fn test_personal_sign_and_invalid_input() {
+        let signer = crate::Secp256k1Signer::from_slice(&[0x46; 32]).unwrap();
+        let signature = personal_sign(&signer, b"hello").await.unwrap();
+        assert_eq!(signature.len(), 65);
+        assert!(matches!(signature[64], 27 | 28));
+
+        let ed25519 = crate::Ed25519Signer::from_bytes(&[1u8; 32]);
+        assert!(matches!(
+            personal_sign(&ed25519, b"hello").await,
+            Err(EvmError::Wallet(WalletError::KeyError(_)))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_json() {
+        let invalid = [
+            json!({"chainId": 1, "nonce": 0, "gas": 21000}),
+            json!({"nonce": 0, "gas": 1, "gasPrice": 1}),
+            json!({"chainId": 0, "nonce": 0, "gas": 1, "gasPrice": 1}),
+            json!({"chainId": 1, "nonce": 0, "gas": 1, "gasPrice": 1, "to": "0x1234"}),
+            json!({"chainId": u64::MAX, "nonce": 0, "gas": 1, "gasPrice": 1}),
+            json!({"chainId": 1, "nonce": 0, "gas": 1, "gasPrice": 1, "accessList": [
+                {"address": format!("0x{}", "35".repeat(20)), "storageKeys": []}
+            ]}),
+        ];
+        for tx in invalid {
+            assert!(
+                matches!(TxRequest::from_json(&tx), Err(EvmError::Invalid(_))),
+                "{}",
+                tx
+            );
+        }
+        assert!(matches!(
+            TxRequest::from_json(
+                &json!({"type": 3, "chainId": 1, "nonce": 0, "gas": 1, "gasPrice": 1})
+            ),
+            Err(EvmError::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_decode_rejects_hostile_payloads() {
+        // Legacy transaction with chainId = u64::MAX overflows v
+        let mut fields = vec![Rlp::uint(0); 6];
+        fields.extend([
+            Rlp::uint(u64::MAX.into()),
+            Rlp::Bytes(Vec::new()),
+            Rlp::Bytes(Vec::new()),
+        ]);
+        let unsigned = Rlp::List(fields).encode();
+        assert!(matches!(
+            TxRequest::decode(&unsigned),
+            Err(EvmError::Invalid(_))
+        ));
+
+        // 200k nested list headers must fail, not overflow the stack
+        let mut headers = Vec::new();
+        let mut len = 0;
+        for _ in 0..200_000 {
+            let h = header(0xc0, len);
+            len += h.len();
+            headers.push(h);
+        }
+        let deep: Vec<u8> = headers.into_iter().rev().flatten().collect();
+        assert!(matches!(
+            TxRequest::decode(&deep),
+            Err(EvmError::Invalid(_))
+        ));
+
+        // A nonce with a leading zero byte is not what would be signed
+        let signed = hex::decode(EIP155_SIGNED).unwrap();
+        let (Rlp::List(mut fields), _) = Rlp::decode(&signed).unwrap() else {
+            panic!("not a list");
+        };
+        fields.truncate(6);
+        fields[0] = Rlp::Bytes(vec![0, 9]);
+        fields.extend([Rlp::uint(1), Rlp::Bytes(Vec::new()), Rlp::Bytes(Vec::new())]);
+        assert!(matches!(
+            TxRequest::decode(&Rlp::List(fields).encode()),
+            Err(EvmError::Invalid(_))
+        ));
+
+        assert!(matches!(
+            TxRequest::decode(&[0x03, 0xc0]),
+            Err(EvmError::NotSupported(_))
+        ));
+        assert!(matches!(
+            TxRequest::decode(&Rlp::List(vec![Rlp::uint(0); 6]).encode()),
+            Err(EvmError::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_payment_tx() {
+        let recipient = "0x3535353535353535353535353535353535353535";
+        let native: PaymentRequest = format!("ethereum:{}@1?value=1e18", recipient)
+            .parse()
+            .unwrap();
+        assert_eq!(
+            payment_tx(&native).unwrap(),
+            json!({ "to": recipient, "value": "1000000000000000000", "chainId": 1 })
+        );
+
+        let token = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        let transfer: PaymentRequest = format!(
+            "ethereum:{}/transfer?address={}&uint256=1000000",
+            token, recipient
+        )
+        .parse()
+        .unwrap();
+        let tx = payment_tx(&transfer).unwrap();
+        assert_eq!(tx["to"], token);
+        assert_eq!(
+            tx["data"],
+            format!("0xa9059cbb{:0>64}{:064x}", &recipient[2..], 1_000_000u128)
+        );
+
+        let solana: PaymentRequest = "solana:mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN"
+            .parse()
+            .unwrap();
+        assert!(payment_tx(&solana).is_err());
+    }
+
+    #[test]
+    fn test_erc20_call() {
+        let recipient = "0x3535353535353535353535353535353535353535";
+        let request: PaymentRequest = format!(
+            "ethereum:0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48/transfer?address={}&uint256=1000000",
+            recipient
+        )
+        .parse()
+        .unwrap();
+        let data = payment_tx(&request).unwrap()["data"].as_str().unwrap()[2..].to_string();
+        let mut data = hex::decode(data).unwrap();
+        assert_eq!(
+            Erc20Call::decode(&data),
+            Some(Erc20Call {
+                approve: false,
+                recipient: recipient.to_string(),
+                amount: 1_000_000,
+            })
+        );
+
+        data[..4].copy_from_slice(&ERC20_APPROVE);
+        data[36] = 1;
+        let approval = Erc20Call::decode(&data).unwrap();
+        assert!(approval.approve);
+        assert_eq!(approval.amount, u128::MAX);
+
+        // Dirty address padding, other selectors and short data
+        data[4] = 1;
+        assert_eq!(Erc20Call::decode(&data), None);
+        assert_eq!(Erc20Call::decode(&[0xde, 0xad, 0xbe, 0xef]), None);
+    }
+}
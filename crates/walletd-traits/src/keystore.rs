@@ -0,0 +1,548 @@
+//! Encrypted key files
+//!
+//! Keys are encrypted with the Web3 Secret Storage (keystore v3) scheme:
+//! scrypt key derivation, AES-128-CTR and a Keccak-256 MAC. EVM keys are
+//! stored raw, exactly as geth and MetaMask expect. Other chains store their
+//! own standard key format (SUI keystore entry, NEAR credentials JSON, WIF)
+//! as the plaintext and record it in a `format` field.
+
+use crate::{SignatureScheme, WalletError, WalletResult};
+use aes::cipher::{KeyIvInit, StreamCipher};
+use base64::Engine;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+use std::fmt;
+use walletd_core::SecretBytes;
+use zeroize::Zeroizing;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// scrypt cost used by geth's "standard" setting (N = 2^18)
+pub const STANDARD_SCRYPT_LOG_N: u8 = 18;
+
+/// Largest scrypt `N` accepted when decrypting, as `log2(N)`
+pub const MAX_SCRYPT_LOG_N: u8 = 20;
+/// Largest scrypt block size accepted when decrypting
+pub const MAX_SCRYPT_R: u32 = 32;
+/// Largest scrypt parallelism accepted when decrypting
+pub const MAX_SCRYPT_P: u32 = 16;
+/// Most memory scrypt may use when decrypting, in bytes (1 GiB)
+pub const MAX_SCRYPT_MEMORY: u64 = 1 << 30;
+/// Most PBKDF2 iterations accepted when decrypting
+pub const MAX_PBKDF2_ROUNDS: u32 = 10_000_000;
+
+/// Plaintext key format inside an encrypted key file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+#[non_exhaustive]
+pub enum KeyFormat {
+    /// Raw 32-byte secp256k1 key (EVM chains, Tron)
+    Web3Keystore,
+    /// SUI keystore entry: base64(flag || private key)
+    SuiKeystore,
+    /// NEAR CLI credentials JSON
+    NearCredentials,
+    /// Bitcoin wallet import format
+    Wif,
+}
+
+impl fmt::Display for KeyFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            KeyFormat::Web3Keystore => "web3-keystore",
+            KeyFormat::SuiKeystore => "sui-keystore",
+            KeyFormat::NearCredentials => "near-credentials",
+            KeyFormat::Wif => "wif",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct KeystoreFile {
+    version: u32,
+    id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    address: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    format: Option<KeyFormat>,
+    crypto: CryptoSection,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CryptoSection {
+    cipher: String,
+    ciphertext: String,
+    cipherparams: CipherParams,
+    kdf: String,
+    kdfparams: KdfParams,
+    mac: String,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CipherParams {
+    iv: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum KdfParams {
+    Scrypt {
+        dklen: usize,
+        n: u64,
+        r: u32,
+        p: u32,
+        salt: String,
+    },
+    Pbkdf2 {
+        dklen: usize,
+        c: u32,
+        prf: String,
+        salt: String,
+    },
+}
+
+/// A decrypted key file
+#[derive(PartialEq, Eq)]
+pub struct DecryptedKey {
+    /// Format of `payload`
+    pub format: KeyFormat,
+    /// Plaintext key material in `format`
+    pub payload: SecretBytes,
+    /// Address recorded in the file, if any
+    pub address: Option<String>,
+}
+
+impl fmt::Debug for DecryptedKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("DecryptedKey")
+            .field("format", &self.format)
+            .field("address", &self.address)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Encrypts `payload` into a keystore v3 JSON document
+pub fn encrypt_key(
+    format: KeyFormat,
+    payload: &[u8],
+    password: &str,
+    address: Option<&str>,
+) -> WalletResult<String> {
+    encrypt_key_with(format, payload, password, address, STANDARD_SCRYPT_LOG_N)
+}
+
+/// Like [`encrypt_key`] with a custom scrypt cost (`N = 2^log_n`)
+pub fn encrypt_key_with(
+    format: KeyFormat,
+    payload: &[u8],
+    password: &str,
+    address: Option<&str>,
+    log_n: u8,
+) -> WalletResult<String> {
+    let mut rng = rand::thread_rng();
+    let mut salt = [0u8; 32];
+    let mut iv = [0u8; 16];
+    let mut id = [0u8; 16];
+    rng.fill_bytes(&mut salt);
+    rng.fill_bytes(&mut iv);
+    rng.fill_bytes(&mut id);
+
+    let (r, p) = (8, 1);
+    let params =
+        scrypt::Params::new(log_n, r, p, 32).map_err(|e| WalletError::KeyError(e.to_string()))?;
+    let mut dk = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(password.as_bytes(), &salt, &params, dk.as_mut())
+        .map_err(|e| WalletError::KeyError(e.to_string()))?;
+
+    let mut ciphertext = payload.to_vec();
+    Aes128Ctr::new(dk[..16].into(), &iv.into()).apply_keystream(&mut ciphertext);
+
+    let file = KeystoreFile {
+        version: 3,
+        id: format_uuid(id),
+        address: address.map(|a| a.trim_start_matches("0x").to_lowercase()),
+        format: (format != KeyFormat::Web3Keystore).then_some(format),
+        crypto: CryptoSection {
+            cipher: "aes-128-ctr".to_string(),
+            ciphertext: hex::encode(&ciphertext),
+            cipherparams: CipherParams {
+                iv: hex::encode(iv),
+            },
+            kdf: "scrypt".to_string(),
+            kdfparams: KdfParams::Scrypt {
+                dklen: 32,
+                n: 1u64 << log_n,
+                r,
+                p,
+                salt: hex::encode(salt),
+            },
+            mac: hex::encode(mac(&dk, &ciphertext)),
+        },
+    };
+    serde_json::to_string(&file).map_err(|e| WalletError::Other(e.to_string()))
+}
+
+/// Decrypts a keystore v3 JSON document
+///
+/// Accepts both scrypt and PBKDF2-HMAC-SHA256 key derivation. Fails with
+/// [`WalletError::KeyError`] on a wrong password, and with
+/// [`WalletError::NotSupported`] if the file asks for a KDF cost above the
+/// `MAX_*` bounds, since costs come from the file.
+pub fn decrypt_key(json: &str, password: &str) -> WalletResult<DecryptedKey> {
+    let file: KeystoreFile = serde_json::from_str(json)
+        .map_err(|e| WalletError::KeyError(format!("invalid keystore: {}", e)))?;
+    if file.version != 3 {
+        return Err(WalletError::NotSupported(format!(
+            "keystore version {}",
+            file.version
+        )));
+    }
+    let crypto = &file.crypto;
+    if crypto.cipher != "aes-128-ctr" {
+        return Err(WalletError::NotSupported(format!(
+            "cipher {}",
+            crypto.cipher
+        )));
+    }
+
+    let mut dk = Zeroizing::new([0u8; 32]);
+    match (crypto.kdf.as_str(), &crypto.kdfparams) {
+        (
+            "scrypt",
+            KdfParams::Scrypt {
+                dklen: 32,
+                n,
+                r,
+                p,
+                salt,
+            },
+        ) => {
+            if !n.is_power_of_two() {
+                return Err(WalletError::KeyError(
+                    "scrypt n must be a power of two".to_string(),
+                ));
+            }
+            let log_n = n.trailing_zeros() as u8;
+            // scrypt needs 128 * r * N bytes
+            if log_n > MAX_SCRYPT_LOG_N
+                || *r > MAX_SCRYPT_R
+                || *p > MAX_SCRYPT_P
+                || (128 * u64::from(*r)) << log_n > MAX_SCRYPT_MEMORY
+            {
+                return Err(WalletError::NotSupported(format!(
+                    "scrypt cost too high: n={}, r={}, p={}",
+                    n, r, p
+                )));
+            }
+            let params = scrypt::Params::new(log_n, *r, *p, 32)
+                .map_err(|e| WalletError::KeyError(e.to_string()))?;
+            scrypt::scrypt(
+                password.as_bytes(),
+                &decode_hex(salt)?,
+                &params,
+                dk.as_mut(),
+            )
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        }
+        (
+            "pbkdf2",
+            KdfParams::Pbkdf2 {
+                dklen: 32,
+                c,
+                prf,
+                salt,
+            },
+        ) if prf == "hmac-sha256" => {
+            if *c > MAX_PBKDF2_ROUNDS {
+                return Err(WalletError::NotSupported(format!(
+                    "pbkdf2 cost too high: c={}",
+                    c
+                )));
+            }
+            pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), &decode_hex(salt)?, *c, dk.as_mut());
+        }
+        (kdf, _) => return Err(WalletError::NotSupported(format!("kdf {}", kdf))),
+    }
+
+    let mut payload = decode_hex(&crypto.ciphertext)?;
+    if !walletd_core::ct_eq(&mac(&dk, &payload), &decode_hex(&crypto.mac)?) {
+        return Err(WalletError::KeyError(
+            "wrong password or corrupted keystore".to_string(),
+        ));
+    }
+    let iv: [u8; 16] = decode_hex(&crypto.cipherparams.iv)?
+        .try_into()
+        .map_err(|_| WalletError::KeyError("iv must be 16 bytes".to_string()))?;
+    Aes128Ctr::new(dk[..16].into(), &iv.into()).apply_keystream(&mut payload);
+
+    Ok(DecryptedKey {
+        format: file.format.unwrap_or(KeyFormat::Web3Keystore),
+        payload: SecretBytes::from(payload),
+        address: file.address,
+    })
+}
+
+/// Encodes a SUI keystore entry (`base64(flag || private key)`)
+///
+/// Fails for schemes SUI has no key flag for.
+pub fn encode_sui_keystore(scheme: SignatureScheme, secret: &[u8; 32]) -> WalletResult<String> {
+    let mut bytes = Vec::with_capacity(33);
+    bytes.push(sui_flag(scheme)?);
+    bytes.extend_from_slice(secret);
+    Ok(base64::engine::general_purpose::STANDARD.encode(bytes))
+}
+
+/// Decodes a SUI keystore entry
+///
+/// Also accepts the legacy layout with the public key appended.
+pub fn decode_sui_keystore(entry: &str) -> WalletResult<(SignatureScheme, [u8; 32])> {
+    let bytes = base64::engine::general_purpose::STANDARD
+        .decode(entry.trim())
+        .map_err(|e| WalletError::KeyError(e.to_string()))?;
+    if bytes.len() != 33 && bytes.len() != 65 {
+        return Err(WalletError::KeyError(format!(
+            "invalid SUI keystore entry length {}",
+            bytes.len()
+        )));
+    }
+    let scheme = match bytes[0] {
+        0x00 => SignatureScheme::Ed25519,
+        0x01 => SignatureScheme::Secp256k1,
+        flag => {
+            return Err(WalletError::NotSupported(format!(
+                "SUI key flag {:#04x}",
+                flag
+            )))
+        }
+    };
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&bytes[1..33]);
+    Ok((scheme, secret))
+}
+
+fn sui_flag(scheme: SignatureScheme) -> WalletResult<u8> {
+    match scheme {
+        SignatureScheme::Ed25519 => Ok(0x00),
+        SignatureScheme::Secp256k1 => Ok(0x01),
+        scheme => Err(WalletError::NotSupported(format!(
+            "SUI has no {} keys",
+            scheme
+        ))),
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct NearCredentials {
+    account_id: String,
+    public_key: String,
+    private_key: String,
+}
+
+/// Encodes NEAR CLI credentials JSON for an Ed25519 key
+pub fn encode_near_credentials(account_id: &str, secret: &[u8; 32]) -> WalletResult<String> {
+    let public = ed25519_dalek::SigningKey::from_bytes(secret)
+        .verifying_key()
+        .to_bytes();
+    let mut keypair = secret.to_vec();
+    keypair.extend_from_slice(&public);
+    let credentials = NearCredentials {
+        account_id: account_id.to_string(),
+        public_key: format!("ed25519:{}", bs58::encode(public).into_string()),
+        private_key: format!("ed25519:{}", bs58::encode(keypair).into_string()),
+    };
+    serde_json::to_string(&credentials).map_err(|e| WalletError::Other(e.to_string()))
+}
+
+/// Decodes NEAR CLI credentials JSON into the account ID and secret key
+pub fn decode_near_credentials(json: &str) -> WalletResult<(String, [u8; 32])> {
+    let credentials: NearCredentials = serde_json::from_str(json)
+        .map_err(|e| WalletError::KeyError(format!("invalid NEAR credentials: {}", e)))?;
+    let encoded = credentials
+        .private_key
+        .strip_prefix("ed25519:")
+        .ok_or_else(|| {
+            WalletError::NotSupported("only ed25519 NEAR keys are supported".to_string())
+        })?;
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| WalletError::KeyError(e.to_string()))?;
+    if bytes.len() != 32 && bytes.len() != 64 {
+        return Err(WalletError::KeyError(format!(
+            "invalid NEAR private key length {}",
+            bytes.len()
+        )));
+    }
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&bytes[..32]);
+    Ok((credentials.account_id, secret))
+}
+
+/// Encodes a secp256k1 key in wallet import format
+pub fn encode_wif(secret: &[u8; 32], compressed: bool, mainnet: bool) -> String {
+    let mut bytes = Vec::with_capacity(38);
+    bytes.push(if mainnet { 0x80 } else { 0xef });
+    bytes.extend_from_slice(secret);
+    if compressed {
+        bytes.push(0x01);
+    }
+    let checksum = Sha256::digest(Sha256::digest(&bytes));
+    bytes.extend_from_slice(&checksum[..4]);
+    bs58::encode(bytes).into_string()
+}
+
+/// Decodes a WIF key into `(secret, compressed, mainnet)`
+pub fn decode_wif(wif: &str) -> WalletResult<([u8; 32], bool, bool)> {
+    let bytes = bs58::decode(wif.trim())
+        .into_vec()
+        .map_err(|e| WalletError::KeyError(e.to_string()))?;
+    let (body, checksum) = match bytes.len() {
+        37 | 38 => bytes.split_at(bytes.len() - 4),
+        n => return Err(WalletError::KeyError(format!("invalid WIF length {}", n))),
+    };
+    if Sha256::digest(Sha256::digest(body))[..4] != *checksum {
+        return Err(WalletError::KeyError("invalid WIF checksum".to_string()));
+    }
+    let mainnet = match body[0] {
+        0x80 => true,
+        0xef => false,
+        v => {
+            return Err(WalletError::KeyError(format!(
+                "unknown WIF version {:#04x}",
+                v
+            )))
+        }
+    };
+    let compressed = body.len() == 34;
+    if compressed && body[33] != 0x01 {
+        return Err(WalletError::KeyError(
+            "invalid WIF compression flag".to_string(),
+        ));
+    }
+    let mut secret = [0u8; 32];
+    secret.copy_from_slice(&body[1..33]);
+    Ok((secret, compressed, mainnet))
+}
+
+fn mac(dk: &[u8; 32], ciphertext: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(&dk[16..32]);
+    hasher.update(ciphertext);
+    hasher.finalize().into()
+}
+
+fn decode_hex(s: &str) -> WalletResult<Vec<u8>> {
+    hex::decode(s).map_err(|e| WalletError::KeyError(e.to_string()))
+}
+
+fn format_uuid(mut bytes: [u8; 16]) -> String {
+    // Random (version 4) UUID
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let h = hex::encode(bytes);
+    format!(
+        "{}-{}-{}-{}-{}",
+        &h[..8],
+        &h[8..12],
+        &h[12..16],
+        &h[16..20],
+        &h[20..]
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vector from the Web3 Secret Storage definition (password "testpassword")
+    const PBKDF2_VECTOR: &str = r#"{
+        "crypto": {
+            "cipher": "aes-128-ctr",
+            "cipherparams": {"iv": "6087dab2f9fdbbfaddc31a909735c1e6"},
+            "ciphertext": "5318b4d5bcd28de64ee5559e671353e16f075ecae9f99c7a79a38af5f869aa46",
+            "kdf": "pbkdf2",
+            "kdfparams": {
+                "c": 262144,
+                "dklen": 32,
+                "prf": "hmac-sha256",
+                "salt": "ae3cd4e7013836a3df6bd7241b12db061dbe2c6785853cce422d148a624ce0bd"
+            },
+            "mac": "517ead924a9d0dc3124507e3393d175ce3ff7c1e96529c6c555ce9e51205e9b2"
+        },
+        "id": "3198bc9c-6672-5ab3-d995-4942343ae5b6",
+        "version": 3
+    }"#;
+
+    #[test]
+    fn test_decrypt_reference_vector() {
+        let key = decrypt_key(PBKDF2_VECTOR, "testpassword").unwrap();
+        assert_eq!(key.format, KeyFormat::Web3Keystore);
+        assert_eq!(
+            hex::encode(key.payload.expose_secret()),
+            "7a28b5ba57c53603b0b07b56bba752f7784bf506fa95edc395f5cf6c7514fe9d"
+        );
+        assert!(matches!(
+            decrypt_key(PBKDF2_VECTOR, "wrong"),
+            Err(WalletError::KeyError(_))
+        ));
+    }
+
+    #[test]
+    fn test_encrypt_roundtrip() {
+        let json = encrypt_key_with(KeyFormat::Wif, b"payload", "pw", Some("0xABC"), 10).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["format"], "wif");
+        assert_eq!(value["address"], "abc");
+        assert_eq!(value["crypto"]["kdfparams"]["n"], 1024);
+
+        let key = decrypt_key(&json, "pw").unwrap();
+        assert_eq!(key.format, KeyFormat::Wif);
+        assert_eq!(key.payload.expose_secret(), b"payload");
+        assert!(decrypt_key(&json, "pw2").is_err());
+        assert!(!format!("{:?}", key).contains("payload: "));
+    }
+
+    #[test]
+    fn test_rejects_excessive_kdf_cost() {
+        let scrypt = encrypt_key_with(KeyFormat::Wif, b"payload", "pw", None, 10)
+            .unwrap()
+            .replace(r#""n":1024"#, r#""n":4294967296"#);
+        assert!(matches!(
+            decrypt_key(&scrypt, "pw"),
+            Err(WalletError::NotSupported(_))
+        ));
+        let pbkdf2 = PBKDF2_VECTOR.replace(r#""c": 262144"#, r#""c": 4294967295"#);
+        assert!(matches!(
+            decrypt_key(&pbkdf2, "testpassword"),
+            Err(WalletError::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_chain_formats() {
+        let secret = [0x11u8; 32];
+
+        let entry = encode_sui_keystore(SignatureScheme::Ed25519, &secret).unwrap();
+        assert_eq!(
+            decode_sui_keystore(&entry).unwrap(),
+            (SignatureScheme::Ed25519, secret)
+        );
+        assert!(encode_sui_keystore(SignatureScheme::Secp256k1Schnorr, &secret).is_err());
+
+        let creds = encode_near_credentials("alice.near", &secret).unwrap();
+        assert_eq!(
+            decode_near_credentials(&creds).unwrap(),
+            ("alice.near".to_string(), secret)
+        );
+
+        // Private key 1 in compressed mainnet WIF
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let wif = encode_wif(&one, true, true);
+        assert_eq!(wif, "KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWn");
+        assert_eq!(decode_wif(&wif).unwrap(), (one, true, true));
+        assert!(decode_wif("KwDiBf89QgGbjEhKnhXJuH7LrciVrZi3qYjgd9M7rFU73sVHnoWo").is_err());
+    }
+}
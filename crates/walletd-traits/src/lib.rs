@@ -8,9 +8,21 @@
 //!
 //! - [`Wallet`] - Basic wallet functionality (address, balance)
 //! - [`Transferable`] - Send funds to another address
+//! - [`BatchTransferable`] - Pay several recipients at once
 //! - [`Syncable`] - Sync wallet state with blockchain
 //! - [`HDWallet`] - Hierarchical deterministic wallet support
+//! - [`WatchOnly`] - Read-only wallets that hold no private key
 //! - [`TokenWallet`] - Token/asset support (ERC-20, SPL, etc.)
+//! - [`MultiSigWallet`] - Propose, sign and execute multisig transactions
+//! - [`WalletEvents`] - Balance and transaction notifications
+//! - [`HistoryProvider`] - Paginated transaction history
+//! - [`Stakeable`] - Staking and delegation
+//! - [`NftWallet`] - NFT support (ERC-721/1155, Metaplex, TEP-62)
+//!
+//! Address validation and chain detection live in [`address`]
+//! ([`ChainRegistry`], [`AddressValidator`]); payment URIs (BIP-21, EIP-681,
+//! Solana Pay, TON) parse into a [`PaymentRequest`] in [`payment`]. EVM
+//! transactions are encoded and signed in [`evm`].
 //!
 //! ## Example
 //!
@@ -25,9 +37,36 @@
 #![forbid(unsafe_code)]
 #![warn(missing_docs)]
 
+pub mod address;
+
+pub use address::{
+    AddressValidator, BitcoinValidator, Chain, ChainRegistry, CosmosValidator, EvmValidator,
+    SolanaValidator, TonValidator,
+};
+
+pub mod payment;
+
+pub use payment::{PaymentAmount, PaymentRequest};
+
+pub mod signer;
+
+#[cfg(feature = "secp256k1")]
+pub use signer::Secp256k1Signer;
+pub use signer::{Ed25519Signer, SignatureScheme, Signer};
+
+pub mod keystore;
+
+pub use keystore::{decrypt_key, encrypt_key, DecryptedKey, KeyFormat};
+
+pub mod evm;
+
 use async_trait::async_trait;
+use futures_core::Stream;
 use serde::{Deserialize, Serialize};
 use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
 
 /// Represents a blockchain amount with arbitrary precision.
 ///
@@ -48,6 +87,9 @@ impl Amount {
     }
 
     /// Creates a new Amount from a human-readable value
+    ///
+    /// Goes through `f64`, so values are only exact up to about 15
+    /// significant digits. Use [`Amount::from_decimal_str`] for money.
     pub fn from_human(value: f64, decimals: u8) -> Self {
         let multiplier = 10u128.pow(decimals as u32);
         let smallest = (value * multiplier as f64) as u128;
@@ -74,11 +116,128 @@ impl Amount {
     pub fn is_zero(&self) -> bool {
         self.value == 0
     }
+
+    /// Parses a decimal string such as `"1.234567890123456789"` exactly
+    ///
+    /// Fails if the string has more fractional digits than `decimals` or the
+    /// value does not fit in the smallest unit.
+    pub fn from_decimal_str(s: &str, decimals: u8) -> WalletResult<Self> {
+        let invalid = |reason: &str| WalletError::Other(format!("invalid amount {:?}: {}", s, reason));
+        let scale = 10u128
+            .checked_pow(decimals as u32)
+            .ok_or_else(|| invalid("too many decimals"))?;
+
+        let (int_part, frac_part) = s.trim().split_once('.').unwrap_or((s.trim(), ""));
+        if int_part.is_empty() && frac_part.is_empty() {
+            return Err(invalid("empty"));
+        }
+        if !int_part.bytes().chain(frac_part.bytes()).all(|b| b.is_ascii_digit()) {
+            return Err(invalid("not a decimal number"));
+        }
+        if frac_part.len() > decimals as usize {
+            return Err(invalid("too many fractional digits"));
+        }
+
+        let int_value: u128 = if int_part.is_empty() {
+            0
+        } else {
+            int_part.parse().map_err(|_| invalid("out of range"))?
+        };
+        let frac_value: u128 = if frac_part.is_empty() {
+            0
+        } else {
+            let padded = format!("{:0<width$}", frac_part, width = decimals as usize);
+            padded.parse().map_err(|_| invalid("out of range"))?
+        };
+        let value = int_value
+            .checked_mul(scale)
+            .and_then(|v| v.checked_add(frac_value))
+            .ok_or_else(|| invalid("out of range"))?;
+        Ok(Self { value, decimals })
+    }
+
+    /// Adds two amounts; `None` on overflow or if the decimals differ
+    pub fn checked_add(&self, other: Amount) -> Option<Amount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        Some(Self::from_smallest_unit(self.value.checked_add(other.value)?, self.decimals))
+    }
+
+    /// Subtracts `other`; `None` on underflow or if the decimals differ
+    pub fn checked_sub(&self, other: Amount) -> Option<Amount> {
+        if self.decimals != other.decimals {
+            return None;
+        }
+        Some(Self::from_smallest_unit(self.value.checked_sub(other.value)?, self.decimals))
+    }
+
+    /// Computes `self * numerator / denominator`, rounding down
+    ///
+    /// The intermediate product is computed at full 256-bit precision, so
+    /// e.g. fee or slippage ratios never overflow early. Returns `None` if
+    /// `denominator` is zero or the result does not fit.
+    pub fn checked_mul_div(&self, numerator: u128, denominator: u128) -> Option<Amount> {
+        let value = mul_div_u128(self.value, numerator, denominator)?;
+        Some(Self::from_smallest_unit(value, self.decimals))
+    }
+}
+
+/// `a * b / d` with a 256-bit intermediate, `None` if `d == 0` or on overflow
+fn mul_div_u128(a: u128, b: u128, d: u128) -> Option<u128> {
+    if d == 0 {
+        return None;
+    }
+    if let Some(product) = a.checked_mul(b) {
+        return Some(product / d);
+    }
+
+    // 128x128 -> 256-bit product as (hi, lo) from 64-bit limbs
+    const MASK: u128 = u64::MAX as u128;
+    let (a_hi, a_lo) = (a >> 64, a & MASK);
+    let (b_hi, b_lo) = (b >> 64, b & MASK);
+    let lo_lo = a_lo * b_lo;
+    let mid1 = a_hi * b_lo;
+    let mid2 = a_lo * b_hi;
+    let (mid, mid_carry) = mid1.overflowing_add(mid2);
+    let (lo, lo_carry) = lo_lo.overflowing_add(mid << 64);
+    let hi = a_hi * b_hi + (mid >> 64) + ((mid_carry as u128) << 64) + lo_carry as u128;
+
+    // The quotient fits in 128 bits only if hi < d
+    if hi >= d {
+        return None;
+    }
+
+    // Binary long division of (hi, lo) by d
+    let (mut rem, mut quotient) = (hi, 0u128);
+    for i in (0..128).rev() {
+        let overflow = rem >> 127 == 1;
+        rem = (rem << 1) | ((lo >> i) & 1);
+        quotient <<= 1;
+        if overflow || rem >= d {
+            rem = rem.wrapping_sub(d);
+            quotient |= 1;
+        }
+    }
+    Some(quotient)
 }
 
 impl fmt::Display for Amount {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{:.precision$}", self.human_readable(), precision = self.decimals as usize)
+        let Some(scale) = 10u128.checked_pow(self.decimals as u32) else {
+            // More decimals than u128 can scale to; the integer part is zero
+            return write!(f, "0.{:0>width$}", self.value, width = self.decimals as usize);
+        };
+        if self.decimals == 0 {
+            return write!(f, "{}", self.value);
+        }
+        write!(
+            f,
+            "{}.{:0>width$}",
+            self.value / scale,
+            self.value % scale,
+            width = self.decimals as usize
+        )
     }
 }
 
@@ -241,6 +400,16 @@ pub trait Wallet: Send + Sync {
 /// Trait for wallets that can send transactions
 #[async_trait]
 pub trait Transferable: Wallet {
+    /// Chain-specific transaction options, e.g. [`EvmTxParams`] or
+    /// [`CosmosTxParams`]; `()` for chains without any
+    type TxParams: Default + Send + Sync + 'static;
+
+    /// Sends a transaction built with chain-specific options
+    ///
+    /// Implementations must honour every field they accept and reject
+    /// (rather than drop) options they cannot apply.
+    async fn transfer_with(&self, tx: TransactionBuilder<Self::TxParams>) -> WalletResult<TxHash>;
+
     /// Transfers funds to another address
     ///
     /// # Arguments
@@ -249,7 +418,12 @@ pub trait Transferable: Wallet {
     ///
     /// # Returns
     /// The transaction hash on success
-    async fn transfer(&self, to: &str, amount: Amount) -> WalletResult<TxHash>;
+    async fn transfer(&self, to: &str, amount: Amount) -> WalletResult<TxHash> {
+        let tx = TransactionBuilder::with_params(Self::TxParams::default())
+            .to(to)
+            .amount(amount);
+        self.transfer_with(tx).await
+    }
 
     /// Estimates the fee for a transfer
     ///
@@ -262,6 +436,101 @@ pub trait Transferable: Wallet {
     async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount>;
 }
 
+/// Outcome of one transfer in a batch
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransferResult {
+    /// Recipient address
+    pub to: String,
+    /// Amount sent
+    pub amount: Amount,
+    /// Transaction hash if the transfer was submitted
+    pub tx_hash: Option<TxHash>,
+    /// Error message if the transfer failed
+    pub error: Option<String>,
+}
+
+impl TransferResult {
+    /// Returns true if the transfer was submitted
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Combined result of a batch transfer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchTransferReport {
+    /// Per-recipient results, in request order
+    pub results: Vec<TransferResult>,
+    /// Single transaction carrying the whole batch, when sent natively
+    pub batch_tx: Option<TxHash>,
+}
+
+impl BatchTransferReport {
+    /// Report for a batch sent as one transaction, where every transfer
+    /// shares the transaction's outcome
+    pub fn single_tx(transfers: &[(String, Amount)], outcome: WalletResult<TxHash>) -> Self {
+        let error = outcome.as_ref().err().map(|e| e.to_string());
+        let batch_tx = outcome.ok();
+        Self {
+            results: transfers
+                .iter()
+                .map(|(to, amount)| TransferResult {
+                    to: to.clone(),
+                    amount: *amount,
+                    tx_hash: batch_tx.clone(),
+                    error: error.clone(),
+                })
+                .collect(),
+            batch_tx,
+        }
+    }
+
+    /// Returns the transfers that were submitted
+    pub fn succeeded(&self) -> impl Iterator<Item = &TransferResult> {
+        self.results.iter().filter(|r| r.is_success())
+    }
+
+    /// Returns the transfers that failed
+    pub fn failed(&self) -> impl Iterator<Item = &TransferResult> {
+        self.results.iter().filter(|r| !r.is_success())
+    }
+
+    /// Returns true if every transfer was submitted
+    pub fn is_complete(&self) -> bool {
+        self.results.iter().all(TransferResult::is_success)
+    }
+}
+
+/// Trait for wallets that can pay several recipients at once
+///
+/// Chains with native batching (Solana multi-instruction transactions,
+/// Cosmos multi-message transactions, EVM disperse contracts) override
+/// [`transfer_batch`](BatchTransferable::transfer_batch) to send a single
+/// transaction. The default sends each transfer in turn and keeps going
+/// after failures, so the report shows exactly which payments went out.
+#[async_trait]
+pub trait BatchTransferable: Transferable {
+    /// Returns true if batches are sent as a single transaction
+    fn supports_native_batch(&self) -> bool {
+        false
+    }
+
+    /// Transfers to several recipients
+    async fn transfer_batch(&self, transfers: &[(String, Amount)]) -> WalletResult<BatchTransferReport> {
+        let mut report = BatchTransferReport::default();
+        for (to, amount) in transfers {
+            let outcome = self.transfer(to, *amount).await;
+            report.results.push(TransferResult {
+                to: to.clone(),
+                amount: *amount,
+                error: outcome.as_ref().err().map(|e| e.to_string()),
+                tx_hash: outcome.ok(),
+            });
+        }
+        Ok(report)
+    }
+}
+
 /// Trait for wallets that can sync with the blockchain
 #[async_trait]
 pub trait Syncable: Wallet {
@@ -325,30 +594,72 @@ pub trait Exportable: Wallet {
 
     /// Exports the wallet's private key (DANGER: keep secure!)
     fn export_private(&self) -> WalletResult<String>;
+
+    /// Format used by [`export_encrypted`](Exportable::export_encrypted)
+    fn key_format(&self) -> KeyFormat {
+        KeyFormat::Web3Keystore
+    }
+
+    /// Exports the private key as a password-encrypted key file
+    ///
+    /// The output is keystore v3 JSON; see [`keystore`] for the per-chain
+    /// plaintext formats. Read it back with [`decrypt_key`].
+    fn export_encrypted(&self, password: &str) -> WalletResult<String> {
+        let _ = password;
+        Err(WalletError::NotSupported(format!("encrypted export for {}", self.currency_symbol())))
+    }
 }
 
+/// Marker for wallets that hold no private key
+///
+/// Watch-only wallets report addresses and balances but never implement
+/// signing traits such as [`Transferable`] or [`Signable`], so code bounded by
+/// `W: WatchOnly` cannot move funds.
+pub trait WatchOnly: Wallet {}
+
 /// Transaction builder for constructing complex transactions
+///
+/// Common fields live on the builder; chain-specific options are carried in
+/// `params` (see [`Transferable::TxParams`]). The default parameter type is
+/// [`EvmTxParams`].
 #[derive(Debug, Clone, Default)]
-pub struct TransactionBuilder {
+pub struct TransactionBuilder<P = EvmTxParams> {
     /// Recipient address
     pub to: Option<String>,
     /// Amount to send
     pub amount: Option<Amount>,
     /// Transaction data/memo
     pub data: Option<Vec<u8>>,
-    /// Gas limit (for EVM chains)
-    pub gas_limit: Option<u64>,
-    /// Gas price (for EVM chains)
-    pub gas_price: Option<Amount>,
     /// Nonce override
     pub nonce: Option<u64>,
+    /// Chain-specific options
+    pub params: P,
 }
 
 impl TransactionBuilder {
-    /// Creates a new transaction builder
+    /// Creates a new EVM transaction builder
     pub fn new() -> Self {
         Self::default()
     }
+}
+
+impl<P> TransactionBuilder<P> {
+    /// Creates a builder with the given chain-specific options
+    pub fn with_params(params: P) -> Self {
+        Self {
+            to: None,
+            amount: None,
+            data: None,
+            nonce: None,
+            params,
+        }
+    }
+
+    /// Sets the chain-specific options
+    pub fn params(mut self, params: P) -> Self {
+        self.params = params;
+        self
+    }
 
     /// Sets the recipient address
     pub fn to(mut self, address: impl Into<String>) -> Self {
@@ -368,23 +679,521 @@ impl TransactionBuilder {
         self
     }
 
+    /// Sets the nonce
+    pub fn nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+}
+
+/// Transaction options for EVM chains
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EvmTxParams {
+    /// Gas limit
+    pub gas_limit: Option<u64>,
+    /// Gas price (legacy transactions)
+    pub gas_price: Option<Amount>,
+    /// Max fee per gas (EIP-1559)
+    pub max_fee_per_gas: Option<Amount>,
+    /// Max priority fee per gas (EIP-1559)
+    pub max_priority_fee_per_gas: Option<Amount>,
+    /// Who pays for gas
+    #[serde(default)]
+    pub gas_policy: GasPolicy,
+}
+
+/// How the gas of an EVM transaction is paid
+///
+/// Anything but [`Native`](GasPolicy::Native) goes through a paymaster
+/// (ERC-4337) or a gas relay.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GasPolicy {
+    /// The sender pays in the native currency
+    #[default]
+    Native,
+    /// A paymaster or relay pays if one accepts the transaction, otherwise
+    /// the sender pays in the native currency
+    PreferSponsored,
+    /// A paymaster or relay must pay
+    Sponsored,
+    /// The sender pays in an ERC-20 token
+    Token {
+        /// Token contract address
+        token: String,
+        /// Most the sender will pay, in the token's smallest unit
+        max_amount: Option<Amount>,
+    },
+}
+
+impl TransactionBuilder<EvmTxParams> {
     /// Sets the gas limit
     pub fn gas_limit(mut self, limit: u64) -> Self {
-        self.gas_limit = Some(limit);
+        self.params.gas_limit = Some(limit);
         self
     }
 
     /// Sets the gas price
     pub fn gas_price(mut self, price: Amount) -> Self {
-        self.gas_price = Some(price);
+        self.params.gas_price = Some(price);
         self
     }
 
-    /// Sets the nonce
-    pub fn nonce(mut self, nonce: u64) -> Self {
-        self.nonce = Some(nonce);
+    /// Sets EIP-1559 fees
+    pub fn eip1559_fees(mut self, max_fee: Amount, max_priority_fee: Amount) -> Self {
+        self.params.max_fee_per_gas = Some(max_fee);
+        self.params.max_priority_fee_per_gas = Some(max_priority_fee);
         self
     }
+
+    /// Sets who pays for gas
+    pub fn gas_policy(mut self, policy: GasPolicy) -> Self {
+        self.params.gas_policy = policy;
+        self
+    }
+
+    /// Pays gas in an ERC-20 token, spending at most `max_amount` of it
+    pub fn pay_gas_in(mut self, token: impl Into<String>, max_amount: Option<Amount>) -> Self {
+        self.params.gas_policy = GasPolicy::Token {
+            token: token.into(),
+            max_amount,
+        };
+        self
+    }
+}
+
+/// Transaction options for Cosmos SDK chains
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CosmosTxParams {
+    /// Transaction memo (often required by exchanges)
+    pub memo: Option<String>,
+    /// Gas limit
+    pub gas_limit: Option<u64>,
+    /// Fee to pay
+    pub fee: Option<Amount>,
+}
+
+impl TransactionBuilder<CosmosTxParams> {
+    /// Sets the memo
+    pub fn memo(mut self, memo: impl Into<String>) -> Self {
+        self.params.memo = Some(memo.into());
+        self
+    }
+
+    /// Sets the gas limit
+    pub fn gas_limit(mut self, limit: u64) -> Self {
+        self.params.gas_limit = Some(limit);
+        self
+    }
+
+    /// Sets the fee
+    pub fn fee(mut self, fee: Amount) -> Self {
+        self.params.fee = Some(fee);
+        self
+    }
+}
+
+/// Transaction options for Solana
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SolanaTxParams {
+    /// Compute unit limit (compute budget program)
+    pub compute_unit_limit: Option<u32>,
+    /// Priority fee in micro-lamports per compute unit
+    pub compute_unit_price: Option<u64>,
+    /// Memo program instruction
+    pub memo: Option<String>,
+}
+
+impl TransactionBuilder<SolanaTxParams> {
+    /// Sets the compute budget
+    pub fn compute_budget(mut self, unit_limit: u32, micro_lamports_per_unit: u64) -> Self {
+        self.params.compute_unit_limit = Some(unit_limit);
+        self.params.compute_unit_price = Some(micro_lamports_per_unit);
+        self
+    }
+
+    /// Sets the memo
+    pub fn memo(mut self, memo: impl Into<String>) -> Self {
+        self.params.memo = Some(memo.into());
+        self
+    }
+}
+
+/// Transaction options for Sui
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SuiTxParams {
+    /// Coin object used to pay gas (selected automatically if unset)
+    pub gas_object: Option<String>,
+    /// Gas budget in MIST
+    pub gas_budget: Option<u64>,
+    /// Gas price in MIST
+    pub gas_price: Option<u64>,
+}
+
+impl TransactionBuilder<SuiTxParams> {
+    /// Sets the gas object
+    pub fn gas_object(mut self, object_id: impl Into<String>) -> Self {
+        self.params.gas_object = Some(object_id.into());
+        self
+    }
+
+    /// Sets the gas budget
+    pub fn gas_budget(mut self, budget: u64) -> Self {
+        self.params.gas_budget = Some(budget);
+        self
+    }
+}
+
+// ============================================================================
+// MULTISIG TRAITS
+// ============================================================================
+
+/// Signer set and threshold of a multisig account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiSigConfig {
+    /// Signer addresses or public keys
+    pub signers: Vec<String>,
+    /// Number of signatures required to execute
+    pub threshold: u32,
+}
+
+/// Signature progress of a proposal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ThresholdStatus {
+    /// Signatures collected so far
+    pub collected: u32,
+    /// Signatures required
+    pub required: u32,
+}
+
+impl ThresholdStatus {
+    /// Returns true if enough signatures have been collected
+    pub fn is_met(&self) -> bool {
+        self.collected >= self.required
+    }
+
+    /// Returns how many more signatures are needed
+    pub fn remaining(&self) -> u32 {
+        self.required.saturating_sub(self.collected)
+    }
+}
+
+/// A pending multisig transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultiSigProposal {
+    /// Proposal ID (Safe transaction hash, PSBT ID, ...)
+    pub id: String,
+    /// Recipient address
+    pub to: String,
+    /// Amount to send
+    pub amount: Amount,
+    /// Call data or memo
+    pub data: Option<Vec<u8>>,
+    /// Signers that have signed so far
+    pub signed_by: Vec<String>,
+    /// Signature progress
+    pub status: ThresholdStatus,
+    /// Execution transaction, once executed
+    pub executed_tx: Option<TxHash>,
+}
+
+/// Trait for multi-signature accounts
+///
+/// Covers Safe-style EVM multisigs, Aptos MultiEd25519 accounts and Bitcoin
+/// multisig descriptors: a transaction is proposed, signers add signatures
+/// until the threshold is met, then anyone executes it.
+#[async_trait]
+pub trait MultiSigWallet: Send + Sync {
+    /// Returns the signer set and threshold
+    async fn multisig_config(&self) -> WalletResult<MultiSigConfig>;
+
+    /// Proposes a transaction, returning the new proposal
+    async fn propose(&self, tx: TransactionBuilder) -> WalletResult<MultiSigProposal>;
+
+    /// Returns a proposal by ID
+    async fn proposal(&self, id: &str) -> WalletResult<MultiSigProposal>;
+
+    /// Adds a signer's signature to a proposal
+    async fn collect_signature(
+        &self,
+        id: &str,
+        signer: &str,
+        signature: &[u8],
+    ) -> WalletResult<ThresholdStatus>;
+
+    /// Returns the signature progress of a proposal
+    async fn threshold_status(&self, id: &str) -> WalletResult<ThresholdStatus> {
+        Ok(self.proposal(id).await?.status)
+    }
+
+    /// Executes a proposal whose threshold is met
+    async fn execute(&self, id: &str) -> WalletResult<TxHash>;
+}
+
+// ============================================================================
+// EVENT TRAITS
+// ============================================================================
+
+/// A notification about a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WalletEvent {
+    /// The wallet balance changed
+    BalanceChanged {
+        /// Wallet address
+        address: String,
+        /// Previous balance
+        old: Amount,
+        /// New balance
+        new: Amount,
+    },
+    /// A transaction paying the wallet was seen
+    IncomingTransaction {
+        /// Transaction hash
+        hash: TxHash,
+        /// Sender, if known
+        from: Option<String>,
+        /// Amount received
+        amount: Amount,
+    },
+    /// A transaction was confirmed
+    TransactionConfirmed {
+        /// Transaction hash
+        hash: TxHash,
+        /// Block height it was included in
+        block_height: Option<u64>,
+    },
+    /// A transaction failed or was dropped
+    TransactionFailed {
+        /// Transaction hash
+        hash: TxHash,
+        /// Failure reason, if known
+        reason: Option<String>,
+    },
+}
+
+/// Stream of wallet events
+pub type EventStream = Pin<Box<dyn Stream<Item = WalletResult<WalletEvent>> + Send>>;
+
+/// Trait for wallets that can push notifications
+///
+/// Chain crates implement this over WebSocket subscriptions where available,
+/// or with [`poll_balance_events`] otherwise.
+#[async_trait]
+pub trait WalletEvents: Wallet {
+    /// Subscribes to events for this wallet
+    ///
+    /// The stream ends when the underlying subscription closes.
+    async fn subscribe(&self) -> WalletResult<EventStream>;
+}
+
+/// Polls a wallet's balance and yields [`WalletEvent::BalanceChanged`] on change
+///
+/// `tick` is awaited between polls, e.g. `|| tokio::time::sleep(interval)`.
+/// Failed polls are yielded as errors and polling continues.
+pub fn poll_balance_events<W, F, Fut>(wallet: Arc<W>, mut tick: F) -> EventStream
+where
+    W: Wallet + 'static,
+    F: FnMut() -> Fut + Send + 'static,
+    Fut: Future<Output = ()> + Send,
+{
+    Box::pin(async_stream::stream! {
+        let mut last: Option<Amount> = None;
+        loop {
+            match wallet.balance().await {
+                Ok(new) => {
+                    if let Some(old) = last.filter(|old| *old != new) {
+                        yield Ok(WalletEvent::BalanceChanged { address: wallet.address(), old, new });
+                    }
+                    last = Some(new);
+                }
+                Err(e) => yield Err(e),
+            }
+            tick().await;
+        }
+    })
+}
+
+// ============================================================================
+// HISTORY TRAITS
+// ============================================================================
+
+/// Direction of a transaction relative to the queried address
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TxDirection {
+    /// Funds received
+    Incoming,
+    /// Funds sent
+    Outgoing,
+    /// Sent to the same address (or between own addresses)
+    SelfTransfer,
+}
+
+/// A transaction as seen from one address
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransactionRecord {
+    /// Transaction hash
+    pub hash: TxHash,
+    /// Direction relative to the queried address
+    pub direction: TxDirection,
+    /// The other party (sender for incoming, recipient for outgoing)
+    pub counterparty: Option<String>,
+    /// Amount transferred
+    pub amount: Amount,
+    /// Fee paid, if paid by the queried address
+    pub fee: Option<Amount>,
+    /// Transaction status
+    pub status: TransactionStatus,
+    /// Block timestamp (Unix epoch seconds), `None` while pending
+    pub timestamp: Option<u64>,
+    /// Block height, `None` while pending
+    pub block_height: Option<u64>,
+}
+
+/// One page of transaction history
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HistoryPage {
+    /// Records, newest first
+    pub records: Vec<TransactionRecord>,
+    /// Cursor for the next (older) page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// Trait for transaction history sources (explorers, indexers, nodes)
+///
+/// Cursors are opaque and specific to each implementation; pass the
+/// `next_cursor` of one page to fetch the next.
+#[async_trait]
+pub trait HistoryProvider: Send + Sync {
+    /// Returns up to `limit` records for `address`, starting at `cursor`
+    ///
+    /// `cursor` is `None` for the newest page.
+    async fn transactions(
+        &self,
+        address: &str,
+        cursor: Option<&str>,
+        limit: usize,
+    ) -> WalletResult<HistoryPage>;
+
+    /// Collects up to `max` records by following cursors
+    async fn recent_transactions(
+        &self,
+        address: &str,
+        max: usize,
+    ) -> WalletResult<Vec<TransactionRecord>> {
+        let mut records = Vec::new();
+        let mut cursor: Option<String> = None;
+        while records.len() < max {
+            let page = self
+                .transactions(address, cursor.as_deref(), max - records.len())
+                .await?;
+            records.extend(page.records);
+            match page.next_cursor {
+                Some(next) => cursor = Some(next),
+                None => break,
+            }
+        }
+        records.truncate(max);
+        Ok(records)
+    }
+}
+
+// ============================================================================
+// FEE TRAITS
+// ============================================================================
+
+/// How soon a transaction should confirm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub enum FeeSpeed {
+    /// Cheapest, may take a while
+    Slow,
+    /// Typical confirmation time
+    Standard,
+    /// Next block or close to it
+    Fast,
+}
+
+/// A fee rate in the chain's own unit
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum FeeRate {
+    /// EIP-1559 fees, wei per gas
+    Eip1559 {
+        /// Highest total fee per gas
+        max_fee_per_gas: u128,
+        /// Tip to the block producer per gas
+        max_priority_fee_per_gas: u128,
+    },
+    /// Legacy gas price, wei per gas
+    GasPrice(u128),
+    /// Satoshis per virtual byte
+    SatPerVbyte(f64),
+    /// Solana priority fee, micro-lamports per compute unit
+    MicroLamportsPerCu(u64),
+    /// Cosmos SDK gas price, in `denom` per gas unit
+    CosmosGasPrice {
+        /// Price per gas unit
+        amount: f64,
+        /// Fee denom, e.g. `uatom`
+        denom: String,
+    },
+}
+
+impl FeeRate {
+    /// Highest fee for a transaction using `units` gas, vbytes or compute
+    /// units, in the smallest unit of the fee currency
+    ///
+    /// For Solana this is the priority fee only, on top of the 5000
+    /// lamports base fee per signature.
+    pub fn max_fee(&self, units: u64) -> u128 {
+        let units = units as u128;
+        match self {
+            FeeRate::Eip1559 {
+                max_fee_per_gas, ..
+            } => max_fee_per_gas.saturating_mul(units),
+            FeeRate::GasPrice(price) => price.saturating_mul(units),
+            FeeRate::SatPerVbyte(rate) => (rate * units as f64).ceil() as u128,
+            FeeRate::MicroLamportsPerCu(price) => (*price as u128 * units).div_ceil(1_000_000),
+            FeeRate::CosmosGasPrice { amount, .. } => (amount * units as f64).ceil() as u128,
+        }
+    }
+}
+
+/// A fee rate for one confirmation speed
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeOption {
+    /// Confirmation speed
+    pub speed: FeeSpeed,
+    /// Rate to pay
+    pub rate: FeeRate,
+    /// Expected time to confirm, if the source gives one
+    pub estimated_seconds: Option<u64>,
+}
+
+/// Current fee options of one chain
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FeeOptions {
+    /// Chain name, e.g. `ethereum`
+    pub chain: String,
+    /// One option per speed, slowest first
+    pub options: Vec<FeeOption>,
+    /// When the options were fetched (Unix epoch seconds)
+    pub updated_at: u64,
+}
+
+impl FeeOptions {
+    /// The option for `speed`
+    pub fn get(&self, speed: FeeSpeed) -> Option<&FeeOption> {
+        self.options.iter().find(|option| option.speed == speed)
+    }
+}
+
+/// Trait for fee sources (nodes, mempool explorers, fee markets)
+#[async_trait]
+pub trait FeeEstimator: Send + Sync {
+    /// Chain the estimates are for, e.g. `ethereum`
+    fn chain(&self) -> &str;
+
+    /// Returns slow, standard and fast fee options
+    async fn fee_options(&self) -> WalletResult<FeeOptions>;
 }
 
 // ============================================================================
@@ -459,8 +1268,12 @@ pub struct StakingConfig {
 }
 
 /// Trait for wallets that support staking
+///
+/// Gives Solana, Cosmos, Polkadot, Sui and Avalanche P-chain staking a common
+/// API. `validator` is the vote account, validator operator, nominee, pool or
+/// node ID depending on the chain.
 #[async_trait]
-pub trait Stakable: Send + Sync {
+pub trait Stakeable: Send + Sync {
     /// Get staking configuration for this chain
     async fn staking_config(&self) -> WalletResult<StakingConfig>;
     
@@ -471,7 +1284,13 @@ pub trait Stakable: Send + Sync {
     async fn validator(&self, address: &str) -> WalletResult<ValidatorInfo>;
     
     /// Get current stakes/delegations for this wallet
-    async fn stakes(&self) -> WalletResult<Vec<StakeInfo>>;
+    async fn current_delegations(&self) -> WalletResult<Vec<StakeInfo>>;
+
+    /// Former name of [`current_delegations`](Stakeable::current_delegations)
+    #[deprecated(note = "use `current_delegations`")]
+    async fn stakes(&self) -> WalletResult<Vec<StakeInfo>> {
+        self.current_delegations().await
+    }
     
     /// Get total staked amount
     async fn total_staked(&self) -> WalletResult<Amount>;
@@ -497,6 +1316,13 @@ pub trait Stakable: Send + Sync {
     ) -> WalletResult<TxHash>;
 }
 
+/// Former name of [`Stakeable`], implemented for every `Stakeable`
+#[deprecated(note = "renamed to `Stakeable`")]
+pub trait Stakable: Stakeable {}
+
+#[allow(deprecated)]
+impl<T: Stakeable + ?Sized> Stakable for T {}
+
 // ============================================================================
 // DEFI TRAITS
 // ============================================================================
@@ -601,17 +1427,146 @@ pub trait LiquidityProvider: Send + Sync {
     async fn lp_balance(&self, pool: &str) -> WalletResult<Amount>;
 }
 
+// ============================================================================
+// NFT TRAITS
+// ============================================================================
+
+/// NFT standard an item follows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum NftStandard {
+    /// EVM non-fungible token
+    Erc721,
+    /// EVM multi-token (may have a balance above one)
+    Erc1155,
+    /// Solana Metaplex token metadata
+    Metaplex,
+    /// TON NFT item (TEP-62)
+    Tep62,
+}
+
+/// Identifies an NFT on its chain
+///
+/// `collection` is the contract (ERC-721/1155) or collection address
+/// (Metaplex, TEP-62). `token_id` is the token ID on EVM chains and the
+/// mint or item address elsewhere.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct NftId {
+    /// Collection or contract address
+    pub collection: String,
+    /// Token ID, mint or item address
+    pub token_id: String,
+}
+
+impl NftId {
+    /// Creates a new NFT identifier
+    pub fn new(collection: impl Into<String>, token_id: impl Into<String>) -> Self {
+        Self {
+            collection: collection.into(),
+            token_id: token_id.into(),
+        }
+    }
+}
+
+impl fmt::Display for NftId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.collection, self.token_id)
+    }
+}
+
+/// A single metadata attribute (trait) of an NFT
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftAttribute {
+    /// Attribute name
+    pub trait_type: String,
+    /// Attribute value
+    pub value: String,
+}
+
+/// Off-chain metadata of an NFT
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftMetadata {
+    /// Item name
+    pub name: String,
+    /// Item description
+    pub description: Option<String>,
+    /// Image URL
+    pub image: Option<String>,
+    /// Attributes
+    pub attributes: Vec<NftAttribute>,
+    /// Metadata URI the data was loaded from
+    pub uri: Option<String>,
+}
+
+/// An NFT held by a wallet
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Nft {
+    /// NFT identifier
+    pub id: NftId,
+    /// Standard the item follows
+    pub standard: NftStandard,
+    /// Number of units held (always 1 except for ERC-1155)
+    pub balance: u64,
+    /// Metadata, if already loaded
+    pub metadata: Option<NftMetadata>,
+}
+
+/// Trait for wallets that hold NFTs
+#[async_trait]
+pub trait NftWallet: Wallet {
+    /// Lists NFTs owned by this wallet
+    async fn nfts(&self) -> WalletResult<Vec<Nft>>;
+
+    /// Fetches the metadata of an NFT
+    async fn nft_metadata(&self, id: &NftId) -> WalletResult<NftMetadata>;
+
+    /// Transfers an NFT to another address
+    ///
+    /// For ERC-1155 items a single unit is transferred.
+    async fn transfer_nft(&self, id: &NftId, to: &str) -> WalletResult<TxHash>;
+
+    /// Lists NFTs owned by this wallet in the given collection
+    async fn nfts_in_collection(&self, collection: &str) -> WalletResult<Vec<Nft>> {
+        let nfts = self.nfts().await?;
+        Ok(nfts
+            .into_iter()
+            .filter(|nft| nft.id.collection.eq_ignore_ascii_case(collection))
+            .collect())
+    }
+}
+
 /// Prelude module for convenient imports
 pub mod prelude {
     pub use crate::{
-        Amount, HDWallet, Network, Signable, Syncable, TokenWallet, Transferable,
+        Amount, BatchTransferReport, BatchTransferable, TransferResult, HDWallet, Network, Signable, Syncable, TokenWallet, Transferable,
         TransactionBuilder, TransactionStatus, TxHash, Wallet, WalletError, WalletResult,
-        Exportable,
+        Exportable, WatchOnly,
+        // Chain-specific transaction options
+        CosmosTxParams, EvmTxParams, GasPolicy, SolanaTxParams, SuiTxParams,
+        // Addresses
+        AddressValidator, Chain, ChainRegistry,
+        // Payment URIs
+        PaymentAmount, PaymentRequest,
+        // Signing backends
+        Ed25519Signer, SignatureScheme, Signer,
+        // Key files
+        DecryptedKey, KeyFormat,
+        // Multisig
+        MultiSigConfig, MultiSigProposal, MultiSigWallet, ThresholdStatus,
+        // Events
+        EventStream, WalletEvent, WalletEvents, poll_balance_events,
+        // History
+        HistoryPage, HistoryProvider, TransactionRecord, TxDirection,
+        // Fees
+        FeeEstimator, FeeOption, FeeOptions, FeeRate, FeeSpeed,
         // Staking
-        Stakable, StakeInfo, StakeStatus, StakingConfig, ValidatorInfo, ValidatorStatus,
+        Stakeable, StakeInfo, StakeStatus, StakingConfig, ValidatorInfo, ValidatorStatus,
         // DeFi
         Swappable, SwapQuote, TokenPair, LiquidityProvider, PoolInfo,
+        // NFTs
+        Nft, NftAttribute, NftId, NftMetadata, NftStandard, NftWallet,
     };
+    #[cfg(feature = "secp256k1")]
+    pub use crate::Secp256k1Signer;
 }
 
 #[cfg(test)]
@@ -670,6 +1625,63 @@ mod tests {
         assert!(display.contains("1.5"));
     }
 
+    #[test]
+    fn test_amount_from_decimal_str() {
+        let wei = Amount::from_decimal_str("1.234567890123456789", 18).unwrap();
+        assert_eq!(wei.smallest_unit(), 1_234_567_890_123_456_789);
+        assert_eq!(Amount::from_decimal_str("0.5", 8).unwrap().smallest_unit(), 50_000_000);
+        assert_eq!(Amount::from_decimal_str(".25", 2).unwrap().smallest_unit(), 25);
+        assert_eq!(Amount::from_decimal_str("42", 0).unwrap().smallest_unit(), 42);
+
+        // Exact beyond f64 precision
+        let big = Amount::from_decimal_str("123456789012345678.000000000000000001", 18).unwrap();
+        assert_eq!(big.smallest_unit(), 123_456_789_012_345_678_000_000_000_000_000_001);
+
+        assert!(Amount::from_decimal_str("1.123", 2).is_err());
+        assert!(Amount::from_decimal_str("-1", 18).is_err());
+        assert!(Amount::from_decimal_str("1e5", 18).is_err());
+        assert!(Amount::from_decimal_str("", 18).is_err());
+        assert!(Amount::from_decimal_str("1", 39).is_err());
+        assert!(Amount::from_decimal_str("999999999999999999999", 18).is_err());
+    }
+
+    #[test]
+    fn test_amount_checked_math() {
+        let a = Amount::from_smallest_unit(100, 6);
+        let b = Amount::from_smallest_unit(30, 6);
+        assert_eq!(a.checked_add(b).unwrap().smallest_unit(), 130);
+        assert_eq!(a.checked_sub(b).unwrap().smallest_unit(), 70);
+        assert!(b.checked_sub(a).is_none());
+        assert!(a.checked_add(Amount::from_smallest_unit(1, 18)).is_none());
+        assert!(Amount::from_smallest_unit(u128::MAX, 18).checked_add(Amount::from_smallest_unit(1, 18)).is_none());
+
+        // 0.3% fee
+        assert_eq!(a.checked_mul_div(3, 1000).unwrap().smallest_unit(), 0);
+        assert_eq!(Amount::from_smallest_unit(1_000_000, 6).checked_mul_div(3, 1000).unwrap().smallest_unit(), 3000);
+        assert!(a.checked_mul_div(1, 0).is_none());
+
+        // Intermediate product overflows u128 but the result fits
+        let huge = Amount::from_smallest_unit(u128::MAX / 2, 18);
+        assert_eq!(huge.checked_mul_div(10, 20).unwrap().smallest_unit(), u128::MAX / 4);
+        assert_eq!(
+            Amount::from_smallest_unit(u128::MAX, 18).checked_mul_div(u128::MAX, u128::MAX).unwrap().smallest_unit(),
+            u128::MAX
+        );
+        assert!(huge.checked_mul_div(3, 1).is_none());
+    }
+
+    #[test]
+    fn test_amount_display_exact() {
+        let amount = Amount::from_decimal_str("123456789.000000000000000001", 18).unwrap();
+        assert_eq!(amount.to_string(), "123456789.000000000000000001");
+        assert_eq!(Amount::from_smallest_unit(5, 2).to_string(), "0.05");
+        assert_eq!(Amount::from_smallest_unit(7, 0).to_string(), "7");
+        assert_eq!(
+            Amount::from_decimal_str(&amount.to_string(), 18).unwrap(),
+            amount
+        );
+    }
+
     #[test]
     fn test_amount_default() {
         let default = Amount::default();
@@ -870,19 +1882,19 @@ mod tests {
             .nonce(5);
 
         assert_eq!(tx.to, Some("0x1234...".to_string()));
-        assert_eq!(tx.gas_limit, Some(21000));
+        assert_eq!(tx.params.gas_limit, Some(21000));
         assert_eq!(tx.nonce, Some(5));
     }
 
     #[test]
     fn test_transaction_builder_default() {
-        let tx = TransactionBuilder::default();
+        let tx: TransactionBuilder = TransactionBuilder::default();
         
         assert!(tx.to.is_none());
         assert!(tx.amount.is_none());
         assert!(tx.data.is_none());
-        assert!(tx.gas_limit.is_none());
-        assert!(tx.gas_price.is_none());
+        assert!(tx.params.gas_limit.is_none());
+        assert!(tx.params.gas_price.is_none());
         assert!(tx.nonce.is_none());
     }
 
@@ -901,7 +1913,7 @@ mod tests {
         let tx = TransactionBuilder::new()
             .gas_price(gas_price);
         
-        assert!(tx.gas_price.is_some());
+        assert!(tx.params.gas_price.is_some());
     }
 
     #[test]
@@ -916,12 +1928,46 @@ mod tests {
         
         assert!(tx.to.is_some());
         assert!(tx.amount.is_some());
-        assert!(tx.gas_limit.is_some());
-        assert!(tx.gas_price.is_some());
+        assert!(tx.params.gas_limit.is_some());
+        assert!(tx.params.gas_price.is_some());
         assert!(tx.nonce.is_some());
         assert!(tx.data.is_some());
     }
 
+    #[test]
+    fn test_transaction_builder_chain_params() {
+        let cosmos = TransactionBuilder::with_params(CosmosTxParams::default())
+            .to("cosmos1abc")
+            .amount(Amount::from_smallest_unit(1_000_000, 6))
+            .memo("104825")
+            .gas_limit(200_000);
+        assert_eq!(cosmos.params.memo.as_deref(), Some("104825"));
+        assert_eq!(cosmos.params.gas_limit, Some(200_000));
+
+        let solana = TransactionBuilder::with_params(SolanaTxParams::default()).compute_budget(200_000, 5_000);
+        assert_eq!(solana.params.compute_unit_limit, Some(200_000));
+        assert_eq!(solana.params.compute_unit_price, Some(5_000));
+
+        let sui = TransactionBuilder::with_params(SuiTxParams::default())
+            .gas_object("0xgas")
+            .gas_budget(10_000_000);
+        assert_eq!(sui.params.gas_object.as_deref(), Some("0xgas"));
+
+        let evm = TransactionBuilder::new().eip1559_fees(
+            Amount::from_smallest_unit(30_000_000_000, 18),
+            Amount::from_smallest_unit(1_000_000_000, 18),
+        );
+        assert!(evm.params.max_fee_per_gas.is_some());
+        assert_eq!(evm.params.gas_policy, GasPolicy::Native);
+
+        let usdc = Amount::from_smallest_unit(2_000_000, 6);
+        let evm = TransactionBuilder::new().pay_gas_in("0xusdc", Some(usdc));
+        assert_eq!(
+            evm.params.gas_policy,
+            GasPolicy::Token { token: "0xusdc".into(), max_amount: Some(usdc) }
+        );
+    }
+
     // ============================================================================
     // Serialization Tests
     // ============================================================================
@@ -953,6 +1999,503 @@ mod tests {
         assert_eq!(network, deserialized);
     }
 
+    // ============================================================================
+    // Batch Transfer Tests
+    // ============================================================================
+
+    #[async_trait]
+    impl Transferable for MockNftWallet {
+        type TxParams = CosmosTxParams;
+
+        async fn transfer_with(&self, tx: TransactionBuilder<CosmosTxParams>) -> WalletResult<TxHash> {
+            let to = tx.to.unwrap_or_default();
+            if to.is_empty() {
+                return Err(WalletError::InvalidAddress(to));
+            }
+            let amount = tx.amount.unwrap_or_default();
+            match tx.params.memo {
+                Some(memo) => Ok(TxHash::new(format!("0x{}-{}-{}", to, amount.value, memo))),
+                None => Ok(TxHash::new(format!("0x{}-{}", to, amount.value))),
+            }
+        }
+
+        async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+    }
+
+    impl BatchTransferable for MockNftWallet {}
+
+    #[tokio::test]
+    async fn test_sequential_batch_transfer() {
+        let wallet = MockNftWallet {
+            network: Network::mainnet("ethereum"),
+        };
+        let transfers = vec![
+            ("a".to_string(), Amount::from_smallest_unit(1, 18)),
+            (String::new(), Amount::from_smallest_unit(2, 18)),
+            ("c".to_string(), Amount::from_smallest_unit(3, 18)),
+        ];
+        let report = wallet.transfer_batch(&transfers).await.unwrap();
+
+        assert!(!wallet.supports_native_batch());
+        assert!(report.batch_tx.is_none());
+        assert!(!report.is_complete());
+        assert_eq!(report.succeeded().count(), 2);
+        let failed: Vec<_> = report.failed().collect();
+        assert_eq!(failed.len(), 1);
+        assert!(failed[0].error.as_ref().unwrap().contains("Invalid address"));
+        assert_eq!(report.results[2].tx_hash, Some(TxHash::new("0xc-3")));
+
+        // Typed chain options reach the implementation
+        let tx = TransactionBuilder::with_params(CosmosTxParams::default())
+            .to("d")
+            .amount(Amount::from_smallest_unit(4, 18))
+            .memo("tag");
+        assert_eq!(wallet.transfer_with(tx).await.unwrap().as_str(), "0xd-4-tag");
+
+        // A native batch succeeds or fails as one transaction
+        let sent = BatchTransferReport::single_tx(&transfers, Ok(TxHash::new("0xbatch")));
+        assert!(sent.is_complete());
+        assert_eq!(sent.results[1].tx_hash, sent.batch_tx);
+        let dropped = BatchTransferReport::single_tx(
+            &transfers,
+            Err(WalletError::NetworkError("timeout".into())),
+        );
+        assert_eq!(dropped.failed().count(), 3);
+        assert!(dropped.batch_tx.is_none());
+    }
+
+    // ============================================================================
+    // MultiSig Tests
+    // ============================================================================
+
+    struct MockMultiSig {
+        config: MultiSigConfig,
+        proposals: std::sync::Mutex<Vec<MultiSigProposal>>,
+    }
+
+    impl MockMultiSig {
+        fn with_proposal<T>(&self, id: &str, f: impl FnOnce(&mut MultiSigProposal) -> T) -> WalletResult<T> {
+            let mut proposals = self.proposals.lock().unwrap();
+            let proposal = proposals
+                .iter_mut()
+                .find(|p| p.id == id)
+                .ok_or_else(|| WalletError::Other(format!("unknown proposal {}", id)))?;
+            Ok(f(proposal))
+        }
+    }
+
+    #[async_trait]
+    impl MultiSigWallet for MockMultiSig {
+        async fn multisig_config(&self) -> WalletResult<MultiSigConfig> {
+            Ok(self.config.clone())
+        }
+
+        async fn propose(&self, tx: TransactionBuilder) -> WalletResult<MultiSigProposal> {
+            let mut proposals = self.proposals.lock().unwrap();
+            let proposal = MultiSigProposal {
+                id: proposals.len().to_string(),
+                to: tx.to.ok_or_else(|| WalletError::Other("missing recipient".into()))?,
+                amount: tx.amount.unwrap_or_default(),
+                data: tx.data,
+                signed_by: Vec::new(),
+                status: ThresholdStatus { collected: 0, required: self.config.threshold },
+                executed_tx: None,
+            };
+            proposals.push(proposal.clone());
+            Ok(proposal)
+        }
+
+        async fn proposal(&self, id: &str) -> WalletResult<MultiSigProposal> {
+            self.with_proposal(id, |p| p.clone())
+        }
+
+        async fn collect_signature(&self, id: &str, signer: &str, _signature: &[u8]) -> WalletResult<ThresholdStatus> {
+            if !self.config.signers.iter().any(|s| s == signer) {
+                return Err(WalletError::KeyError(format!("{} is not a signer", signer)));
+            }
+            self.with_proposal(id, |p| {
+                if !p.signed_by.iter().any(|s| s == signer) {
+                    p.signed_by.push(signer.to_string());
+                    p.status.collected += 1;
+                }
+                p.status
+            })
+        }
+
+        async fn execute(&self, id: &str) -> WalletResult<TxHash> {
+            self.with_proposal(id, |p| {
+                if !p.status.is_met() {
+                    return Err(WalletError::Other(format!("{} more signatures needed", p.status.remaining())));
+                }
+                let tx = TxHash::new(format!("0xexec{}", p.id));
+                p.executed_tx = Some(tx.clone());
+                Ok(tx)
+            })?
+        }
+    }
+
+    #[tokio::test]
+    async fn test_multisig_flow() {
+        let wallet = MockMultiSig {
+            config: MultiSigConfig {
+                signers: vec!["alice".into(), "bob".into(), "carol".into()],
+                threshold: 2,
+            },
+            proposals: Default::default(),
+        };
+        let proposal = wallet
+            .propose(TransactionBuilder::new().to("0xdead").amount(Amount::from_smallest_unit(5, 18)))
+            .await
+            .unwrap();
+        assert_eq!(proposal.status.remaining(), 2);
+
+        let status = wallet.collect_signature(&proposal.id, "alice", b"sig").await.unwrap();
+        assert!(!status.is_met());
+        assert!(wallet.execute(&proposal.id).await.is_err());
+        assert!(wallet.collect_signature(&proposal.id, "mallory", b"sig").await.is_err());
+
+        // Duplicate signatures do not count twice
+        wallet.collect_signature(&proposal.id, "alice", b"sig").await.unwrap();
+        assert_eq!(wallet.threshold_status(&proposal.id).await.unwrap().collected, 1);
+
+        wallet.collect_signature(&proposal.id, "bob", b"sig").await.unwrap();
+        assert!(wallet.threshold_status(&proposal.id).await.unwrap().is_met());
+        let tx = wallet.execute(&proposal.id).await.unwrap();
+        assert_eq!(wallet.proposal(&proposal.id).await.unwrap().executed_tx, Some(tx));
+    }
+
+    // ============================================================================
+    // Event Tests
+    // ============================================================================
+
+    struct CountingWallet {
+        network: Network,
+        polls: std::sync::atomic::AtomicU64,
+    }
+
+    #[async_trait]
+    impl Wallet for CountingWallet {
+        fn address(&self) -> String {
+            "0xcount".to_string()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            // Balance steps up every second poll
+            let poll = self.polls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(Amount::from_smallest_unit((poll / 2) as u128, 18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[tokio::test]
+    async fn test_poll_balance_events() {
+        use futures_util::StreamExt;
+
+        let wallet = Arc::new(CountingWallet {
+            network: Network::mainnet("ethereum"),
+            polls: Default::default(),
+        });
+        let events: Vec<_> = poll_balance_events(wallet.clone(), || async {})
+            .take(2)
+            .collect()
+            .await;
+
+        assert_eq!(
+            events[0].as_ref().unwrap(),
+            &WalletEvent::BalanceChanged {
+                address: "0xcount".to_string(),
+                old: Amount::from_smallest_unit(0, 18),
+                new: Amount::from_smallest_unit(1, 18),
+            }
+        );
+        assert!(matches!(
+            events[1].as_ref().unwrap(),
+            WalletEvent::BalanceChanged { new, .. } if new.value == 2
+        ));
+        assert_eq!(wallet.polls.load(std::sync::atomic::Ordering::SeqCst), 5);
+    }
+
+    // ============================================================================
+    // History Tests
+    // ============================================================================
+
+    struct MockHistory {
+        total: u64,
+    }
+
+    #[async_trait]
+    impl HistoryProvider for MockHistory {
+        async fn transactions(
+            &self,
+            address: &str,
+            cursor: Option<&str>,
+            limit: usize,
+        ) -> WalletResult<HistoryPage> {
+            let start: u64 = cursor.map_or(0, |c| c.parse().unwrap());
+            let end = (start + limit.min(2) as u64).min(self.total);
+            let records = (start..end)
+                .map(|i| TransactionRecord {
+                    hash: TxHash::new(format!("0x{:x}", i)),
+                    direction: if i % 2 == 0 { TxDirection::Incoming } else { TxDirection::Outgoing },
+                    counterparty: Some(format!("{}-peer", address)),
+                    amount: Amount::from_smallest_unit(i as u128, 18),
+                    fee: None,
+                    status: TransactionStatus::Confirmed,
+                    timestamp: Some(1_700_000_000 - i),
+                    block_height: Some(100 - i),
+                })
+                .collect();
+            Ok(HistoryPage {
+                records,
+                next_cursor: (end < self.total).then(|| end.to_string()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_history_pagination() {
+        let history = MockHistory { total: 5 };
+        let first = history.transactions("0xabc", None, 10).await.unwrap();
+        assert_eq!(first.records.len(), 2);
+        assert_eq!(first.next_cursor.as_deref(), Some("2"));
+
+        let recent = history.recent_transactions("0xabc", 3).await.unwrap();
+        assert_eq!(recent.len(), 3);
+        assert_eq!(recent[2].hash.as_str(), "0x2");
+
+        let all = history.recent_transactions("0xabc", 100).await.unwrap();
+        assert_eq!(all.len(), 5);
+        assert_eq!(all[1].direction, TxDirection::Outgoing);
+    }
+
+    // ============================================================================
+    // Fee Tests
+    // ============================================================================
+
+    #[test]
+    fn test_fee_rate_max_fee() {
+        let eip1559 = FeeRate::Eip1559 {
+            max_fee_per_gas: 30_000_000_000,
+            max_priority_fee_per_gas: 1_000_000_000,
+        };
+        assert_eq!(eip1559.max_fee(21_000), 630_000_000_000_000);
+        assert_eq!(FeeRate::SatPerVbyte(12.5).max_fee(141), 1763);
+        assert_eq!(FeeRate::MicroLamportsPerCu(10_000).max_fee(200_000), 2000);
+        assert_eq!(FeeRate::MicroLamportsPerCu(1).max_fee(1), 1);
+        let cosmos = FeeRate::CosmosGasPrice {
+            amount: 0.025,
+            denom: "uatom".into(),
+        };
+        assert_eq!(cosmos.max_fee(200_000), 5000);
+    }
+
+    #[test]
+    fn test_fee_options_get() {
+        let options = FeeOptions {
+            chain: "bitcoin".into(),
+            options: vec![
+                FeeOption {
+                    speed: FeeSpeed::Slow,
+                    rate: FeeRate::SatPerVbyte(2.0),
+                    estimated_seconds: Some(3600),
+                },
+                FeeOption {
+                    speed: FeeSpeed::Fast,
+                    rate: FeeRate::SatPerVbyte(9.0),
+                    estimated_seconds: Some(600),
+                },
+            ],
+            updated_at: 0,
+        };
+        assert_eq!(options.get(FeeSpeed::Fast).unwrap().rate, FeeRate::SatPerVbyte(9.0));
+        assert!(options.get(FeeSpeed::Standard).is_none());
+    }
+
+    // ============================================================================
+    // Staking Tests
+    // ============================================================================
+
+    struct MockStaker;
+
+    #[async_trait]
+    impl Stakeable for MockStaker {
+        async fn staking_config(&self) -> WalletResult<StakingConfig> {
+            Ok(StakingConfig {
+                min_stake: Amount::from_smallest_unit(1, 9),
+                unbonding_period_secs: 0,
+                max_validators: None,
+                auto_compound: true,
+            })
+        }
+
+        async fn validators(&self) -> WalletResult<Vec<ValidatorInfo>> {
+            Ok(Vec::new())
+        }
+
+        async fn validator(&self, address: &str) -> WalletResult<ValidatorInfo> {
+            Err(WalletError::InvalidAddress(address.to_string()))
+        }
+
+        async fn current_delegations(&self) -> WalletResult<Vec<StakeInfo>> {
+            Ok(vec![StakeInfo {
+                validator: "vote1".to_string(),
+                amount: Amount::from_smallest_unit(5_000_000_000, 9),
+                rewards: Amount::zero(9),
+                staked_at: None,
+                status: StakeStatus::Active,
+            }])
+        }
+
+        async fn total_staked(&self) -> WalletResult<Amount> {
+            let delegations = self.current_delegations().await?;
+            Ok(Amount::from_smallest_unit(delegations.iter().map(|d| d.amount.value).sum(), 9))
+        }
+
+        async fn pending_rewards(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(9))
+        }
+
+        async fn stake(&self, validator: &str, _amount: Amount) -> WalletResult<TxHash> {
+            Ok(TxHash::new(format!("stake-{}", validator)))
+        }
+
+        async fn unstake(&self, validator: &str, _amount: Amount) -> WalletResult<TxHash> {
+            Ok(TxHash::new(format!("unstake-{}", validator)))
+        }
+
+        async fn claim_rewards(&self) -> WalletResult<TxHash> {
+            Ok(TxHash::new("claim"))
+        }
+
+        async fn redelegate(&self, _from: &str, to: &str, _amount: Amount) -> WalletResult<TxHash> {
+            Ok(TxHash::new(format!("redelegate-{}", to)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stakeable_as_trait_object() {
+        let staker: Box<dyn Stakeable> = Box::new(MockStaker);
+        assert_eq!(staker.current_delegations().await.unwrap().len(), 1);
+        #[allow(deprecated)]
+        let stakes = staker.stakes().await.unwrap();
+        assert_eq!(stakes[0].validator, "vote1");
+        assert_eq!(staker.total_staked().await.unwrap().smallest_unit(), 5_000_000_000);
+        assert_eq!(staker.stake("vote1", Amount::zero(9)).await.unwrap().as_str(), "stake-vote1");
+    }
+
+    // ============================================================================
+    // NFT Tests
+    // ============================================================================
+
+    struct MockNftWallet {
+        network: Network,
+    }
+
+    #[async_trait]
+    impl Wallet for MockNftWallet {
+        fn address(&self) -> String {
+            "0xowner".to_string()
+        }
+
+        async fn balance(&self) -> WalletResult<Amount> {
+            Ok(Amount::zero(18))
+        }
+
+        fn network(&self) -> &Network {
+            &self.network
+        }
+
+        fn currency_symbol(&self) -> &str {
+            "ETH"
+        }
+
+        fn decimals(&self) -> u8 {
+            18
+        }
+    }
+
+    #[async_trait]
+    impl NftWallet for MockNftWallet {
+        async fn nfts(&self) -> WalletResult<Vec<Nft>> {
+            Ok(vec![
+                Nft {
+                    id: NftId::new("0xAbC", "1"),
+                    standard: NftStandard::Erc721,
+                    balance: 1,
+                    metadata: None,
+                },
+                Nft {
+                    id: NftId::new("0xdef", "7"),
+                    standard: NftStandard::Erc1155,
+                    balance: 3,
+                    metadata: None,
+                },
+            ])
+        }
+
+        async fn nft_metadata(&self, id: &NftId) -> WalletResult<NftMetadata> {
+            Ok(NftMetadata {
+                name: format!("Item #{}", id.token_id),
+                ..Default::default()
+            })
+        }
+
+        async fn transfer_nft(&self, id: &NftId, _to: &str) -> WalletResult<TxHash> {
+            Ok(TxHash::new(format!("0xtx-{}", id)))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_nft_wallet() {
+        let wallet = MockNftWallet {
+            network: Network::mainnet("ethereum"),
+        };
+        assert_eq!(wallet.nfts().await.unwrap().len(), 2);
+
+        let collection = wallet.nfts_in_collection("0xabc").await.unwrap();
+        assert_eq!(collection.len(), 1);
+        assert_eq!(collection[0].id.token_id, "1");
+
+        let metadata = wallet.nft_metadata(&collection[0].id).await.unwrap();
+        assert_eq!(metadata.name, "Item #1");
+        let tx = wallet.transfer_nft(&collection[0].id, "0xother").await.unwrap();
+        assert_eq!(tx.as_str(), "0xtx-0xAbC/1");
+    }
+
+    #[test]
+    fn test_nft_serialization() {
+        let nft = Nft {
+            id: NftId::new("EQCollection", "EQItem"),
+            standard: NftStandard::Tep62,
+            balance: 1,
+            metadata: Some(NftMetadata {
+                name: "Diamond".to_string(),
+                attributes: vec![NftAttribute {
+                    trait_type: "rarity".to_string(),
+                    value: "rare".to_string(),
+                }],
+                ..Default::default()
+            }),
+        };
+        let json = serde_json::to_string(&nft).unwrap();
+        let deserialized: Nft = serde_json::from_str(&json).unwrap();
+
+        assert_eq!(nft, deserialized);
+    }
+
     #[test]
     fn test_transaction_status_serialization() {
         let status = TransactionStatus::Confirmed;
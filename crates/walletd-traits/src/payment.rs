@@ -0,0 +1,709 @@
+//! Payment request URIs
+//!
+//! A [`PaymentRequest`] is the normalized form of the links wallets scan
+//! from checkout QR codes or open as deep links:
+//!
+//! | Chain | Format | Example |
+//! |-------|--------|---------|
+//! | Bitcoin | BIP-21 | `bitcoin:bc1q...?amount=0.001&label=Shop` |
+//! | Ethereum / EVM | EIP-681 | `ethereum:0xToken@8453/transfer?address=0x...&uint256=1e6` |
+//! | Solana | Solana Pay | `solana:<recipient>?amount=1&spl-token=<mint>&memo=...` |
+//! | TON | transfer link | `ton://transfer/<address>?amount=<nanotons>&text=...` |
+//!
+//! Send flows parse a scanned link once and read the recipient, amount,
+//! token and memo from the same fields whatever the chain.
+//!
+//! ```ignore
+//! let request: PaymentRequest = scanned.parse()?;
+//! let amount = request.amount.map(|a| a.resolve(token_decimals)).transpose()?;
+//! ```
+
+use crate::address::{
+    AddressValidator, BitcoinValidator, Chain, EvmValidator, SolanaValidator, TonValidator,
+};
+use crate::{Amount, WalletError, WalletResult};
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::{Deserialize, Serialize};
+
+/// Characters left unescaped in query values (RFC 3986 unreserved)
+const QUERY_VALUE: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+const BTC_DECIMALS: u8 = 8;
+const ETH_DECIMALS: u8 = 18;
+const SOL_DECIMALS: u8 = 9;
+const TON_DECIMALS: u8 = 9;
+
+/// An amount as written in a payment URI
+///
+/// Token amounts may be given in whole units (Solana Pay) or base units
+/// (EIP-681, TON jettons) without the token's decimals, so they are kept
+/// as written until the caller knows them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PaymentAmount {
+    /// Exact amount; native coin amounts always parse to this
+    Exact(Amount),
+    /// Decimal string in whole token units, e.g. `"12.5"`
+    Units(String),
+    /// Integer amount in the token's smallest unit
+    BaseUnits(u128),
+}
+
+impl PaymentAmount {
+    /// Resolves the amount given the asset's decimals
+    ///
+    /// `decimals` is ignored for [`PaymentAmount::Exact`].
+    pub fn resolve(&self, decimals: u8) -> WalletResult<Amount> {
+        match self {
+            PaymentAmount::Exact(amount) => Ok(*amount),
+            PaymentAmount::Units(units) => Amount::from_decimal_str(units, decimals),
+            PaymentAmount::BaseUnits(value) => Ok(Amount::from_smallest_unit(*value, decimals)),
+        }
+    }
+
+    /// Value in the smallest unit
+    ///
+    /// `decimals` is only needed for [`PaymentAmount::Units`]; without it
+    /// such amounts are an error.
+    pub fn base_units(&self, decimals: Option<u8>) -> WalletResult<u128> {
+        match (self, decimals) {
+            (PaymentAmount::Exact(amount), _) => Ok(amount.value),
+            (PaymentAmount::BaseUnits(value), _) => Ok(*value),
+            (PaymentAmount::Units(units), Some(decimals)) => {
+                Ok(Amount::from_decimal_str(units, decimals)?.value)
+            }
+            (PaymentAmount::Units(_), None) => Err(unknown_decimals()),
+        }
+    }
+
+    /// Value as a whole-unit decimal string without trailing zeros
+    ///
+    /// `decimals` is only needed for [`PaymentAmount::BaseUnits`]; without
+    /// it such amounts are an error.
+    pub fn units(&self, decimals: Option<u8>) -> WalletResult<String> {
+        match (self, decimals) {
+            (PaymentAmount::Exact(amount), _) => Ok(whole_units(*amount)),
+            (PaymentAmount::BaseUnits(value), Some(decimals)) => {
+                Ok(whole_units(Amount::from_smallest_unit(*value, decimals)))
+            }
+            (PaymentAmount::Units(units), Some(decimals)) => {
+                Amount::from_decimal_str(units, decimals)?;
+                Ok(units.trim().to_string())
+            }
+            (PaymentAmount::Units(units), None) => {
+                check_units(units)?;
+                Ok(units.trim().to_string())
+            }
+            (PaymentAmount::BaseUnits(_), None) => Err(unknown_decimals()),
+        }
+    }
+}
+
+impl From<Amount> for PaymentAmount {
+    fn from(amount: Amount) -> Self {
+        PaymentAmount::Exact(amount)
+    }
+}
+
+/// A chain-independent payment request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaymentRequest {
+    /// Chain the payment is on; EVM networks use [`Chain::Ethereum`] with a
+    /// `chain_id`
+    pub chain: Chain,
+    /// Recipient address (not the token contract)
+    pub address: String,
+    /// Requested amount, if any
+    pub amount: Option<PaymentAmount>,
+    /// ERC-20 contract, SPL mint or jetton master; `None` for the native coin
+    pub token: Option<String>,
+    /// On-chain memo or comment (Solana Pay `memo`, TON `text`)
+    pub memo: Option<String>,
+    /// Merchant or recipient name (BIP-21, Solana Pay)
+    pub label: Option<String>,
+    /// Note shown to the payer (BIP-21, Solana Pay)
+    pub message: Option<String>,
+    /// EVM chain id (EIP-681)
+    pub chain_id: Option<u64>,
+    /// Reference keys for locating the payment (Solana Pay)
+    pub reference: Vec<String>,
+}
+
+impl PaymentRequest {
+    /// A request for an unspecified amount of the native coin
+    pub fn new(chain: Chain, address: impl Into<String>) -> Self {
+        Self {
+            chain,
+            address: address.into(),
+            amount: None,
+            token: None,
+            memo: None,
+            label: None,
+            message: None,
+            chain_id: None,
+            reference: Vec::new(),
+        }
+    }
+
+    /// Sets the requested amount
+    pub fn with_amount(mut self, amount: impl Into<PaymentAmount>) -> Self {
+        self.amount = Some(amount.into());
+        self
+    }
+
+    /// Requests a token instead of the native coin
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Sets the on-chain memo
+    pub fn with_memo(mut self, memo: impl Into<String>) -> Self {
+        self.memo = Some(memo.into());
+        self
+    }
+
+    /// Sets the EVM chain id
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Parses a BIP-21, EIP-681, Solana Pay or TON transfer URI
+    ///
+    /// Addresses are validated for the chain. Parameters that would change
+    /// the payment but are not understood (BIP-21 `req-*`, TON `bin`) are
+    /// rejected rather than dropped.
+    pub fn parse(uri: &str) -> WalletResult<Self> {
+        let uri = uri.trim();
+        let (scheme, rest) = uri
+            .split_once(':')
+            .ok_or_else(|| malformed("missing scheme"))?;
+        let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+        let params = Params::parse(query)?;
+
+        match scheme.to_ascii_lowercase().as_str() {
+            "bitcoin" => parse_bip21(path, &params),
+            "ethereum" => parse_eip681(path, &params),
+            "solana" => parse_solana_pay(path, &params),
+            "ton" => parse_ton(path, &params),
+            other => Err(WalletError::NotSupported(format!(
+                "payment URI scheme {}",
+                other
+            ))),
+        }
+    }
+
+    /// Encodes the request as a URI for its chain
+    ///
+    /// Display fields the format has no slot for (`label` and `message` on
+    /// EIP-681 and TON) are left out; fields that change the payment, such
+    /// as a `memo` on Bitcoin, are rejected.
+    pub fn to_uri(&self) -> WalletResult<String> {
+        let address = self.address.trim();
+        let mut params: Vec<(&str, String)> = Vec::new();
+        let uri = match self.chain {
+            Chain::Bitcoin => {
+                reject(
+                    self.token.is_some() || self.chain_id.is_some(),
+                    "token and chain_id",
+                )?;
+                reject(
+                    self.memo.is_some() || !self.reference.is_empty(),
+                    "memo and reference",
+                )?;
+                validate_bitcoin(address)?;
+                if let Some(amount) = &self.amount {
+                    params.push(("amount", amount.units(Some(BTC_DECIMALS))?));
+                }
+                push_optional(&mut params, "label", &self.label);
+                push_optional(&mut params, "message", &self.message);
+                format!("bitcoin:{}", address)
+            }
+            Chain::Ethereum => {
+                reject(
+                    self.memo.is_some() || !self.reference.is_empty(),
+                    "memo and reference",
+                )?;
+                EvmValidator.validate(address)?;
+                let chain_id = self
+                    .chain_id
+                    .map(|id| format!("@{}", id))
+                    .unwrap_or_default();
+                match &self.token {
+                    Some(token) => {
+                        EvmValidator.validate(token)?;
+                        params.push(("address", address.to_string()));
+                        if let Some(amount) = &self.amount {
+                            params.push(("uint256", amount.base_units(None)?.to_string()));
+                        }
+                        format!("ethereum:{}{}/transfer", token, chain_id)
+                    }
+                    None => {
+                        if let Some(amount) = &self.amount {
+                            params.push((
+                                "value",
+                                amount.base_units(Some(ETH_DECIMALS))?.to_string(),
+                            ));
+                        }
+                        format!("ethereum:{}{}", address, chain_id)
+                    }
+                }
+            }
+            Chain::Solana => {
+                reject(self.chain_id.is_some(), "chain_id")?;
+                SolanaValidator.validate(address)?;
+                if let Some(amount) = &self.amount {
+                    // SPL amounts are in token units; their decimals vary by mint
+                    let decimals = self.token.is_none().then_some(SOL_DECIMALS);
+                    params.push(("amount", amount.units(decimals)?));
+                }
+                if let Some(token) = &self.token {
+                    SolanaValidator.validate(token)?;
+                    params.push(("spl-token", token.clone()));
+                }
+                for reference in &self.reference {
+                    SolanaValidator.validate(reference)?;
+                    params.push(("reference", reference.clone()));
+                }
+                push_optional(&mut params, "label", &self.label);
+                push_optional(&mut params, "message", &self.message);
+                push_optional(&mut params, "memo", &self.memo);
+                format!("solana:{}", address)
+            }
+            Chain::Ton => {
+                reject(
+                    self.chain_id.is_some() || !self.reference.is_empty(),
+                    "chain_id and reference",
+                )?;
+                TonValidator.validate(address)?;
+                if let Some(amount) = &self.amount {
+                    let decimals = self.token.is_none().then_some(TON_DECIMALS);
+                    params.push(("amount", amount.base_units(decimals)?.to_string()));
+                }
+                if let Some(token) = &self.token {
+                    TonValidator.validate(token)?;
+                    params.push(("jetton", token.clone()));
+                }
+                push_optional(&mut params, "text", &self.memo);
+                format!("ton://transfer/{}", address)
+            }
+            other => {
+                return Err(WalletError::NotSupported(format!(
+                    "payment URIs on {}",
+                    other
+                )))
+            }
+        };
+
+        if params.is_empty() {
+            return Ok(uri);
+        }
+        let query = params
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, utf8_percent_encode(value, QUERY_VALUE)))
+            .collect::<Vec<_>>()
+            .join("&");
+        Ok(format!("{}?{}", uri, query))
+    }
+}
+
+impl std::str::FromStr for PaymentRequest {
+    type Err = WalletError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Decoded query parameters, in order
+struct Params(Vec<(String, String)>);
+
+impl Params {
+    fn parse(query: &str) -> WalletResult<Self> {
+        let mut params = Vec::new();
+        for pair in query.split('&').filter(|p| !p.is_empty()) {
+            let (key, value) = pair.split_once('=').unwrap_or((pair, ""));
+            let value = percent_decode_str(value)
+                .decode_utf8()
+                .map_err(|_| malformed(&format!("invalid encoding in {}", key)))?;
+            params.push((key.to_string(), value.into_owned()));
+        }
+        Ok(Self(params))
+    }
+
+    fn get(&self, name: &str) -> Option<String> {
+        self.0
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+    }
+
+    fn all(&self, name: &str) -> Vec<String> {
+        self.0
+            .iter()
+            .filter(|(key, _)| key == name)
+            .map(|(_, value)| value.clone())
+            .collect()
+    }
+
+    fn has(&self, name: &str) -> bool {
+        self.0.iter().any(|(key, _)| key == name)
+    }
+}
+
+fn parse_bip21(path: &str, params: &Params) -> WalletResult<PaymentRequest> {
+    // Unknown required parameters must be rejected
+    if let Some((key, _)) = params.0.iter().find(|(key, _)| key.starts_with("req-")) {
+        return Err(WalletError::NotSupported(format!(
+            "required BIP-21 parameter {}",
+            key
+        )));
+    }
+    validate_bitcoin(path)?;
+    let amount = params
+        .get("amount")
+        .map(|amount| Amount::from_decimal_str(&amount, BTC_DECIMALS))
+        .transpose()?;
+    Ok(PaymentRequest {
+        amount: amount.map(PaymentAmount::Exact),
+        label: params.get("label"),
+        message: params.get("message"),
+        ..PaymentRequest::new(Chain::Bitcoin, path)
+    })
+}
+
+fn parse_eip681(path: &str, params: &Params) -> WalletResult<PaymentRequest> {
+    let path = path.strip_prefix("pay-").unwrap_or(path);
+    let (target, function) = match path.split_once('/') {
+        Some((target, function)) => (target, Some(function)),
+        None => (path, None),
+    };
+    let (target, chain_id) = match target.split_once('@') {
+        Some((target, id)) => {
+            let id = id
+                .parse::<u64>()
+                .map_err(|_| malformed(&format!("invalid chain id {}", id)))?;
+            (target, Some(id))
+        }
+        None => (target, None),
+    };
+    EvmValidator.validate(target)?;
+
+    let request = match function {
+        None => {
+            let value = params.get("value").map(|v| eip681_number(&v)).transpose()?;
+            PaymentRequest {
+                amount: value.map(|wei| Amount::from_smallest_unit(wei, ETH_DECIMALS).into()),
+                ..PaymentRequest::new(Chain::Ethereum, target)
+            }
+        }
+        Some("transfer") => {
+            let recipient = params
+                .get("address")
+                .ok_or_else(|| malformed("token transfer has no address parameter"))?;
+            EvmValidator.validate(&recipient)?;
+            let units = params
+                .get("uint256")
+                .map(|v| eip681_number(&v))
+                .transpose()?;
+            PaymentRequest {
+                amount: units.map(PaymentAmount::BaseUnits),
+                token: Some(target.to_string()),
+                ..PaymentRequest::new(Chain::Ethereum, recipient)
+            }
+        }
+        Some(other) => {
+            return Err(WalletError::NotSupported(format!(
+                "EIP-681 function {}",
+                other
+            )))
+        }
+    };
+    Ok(PaymentRequest {
+        chain_id,
+        ..request
+    })
+}
+
+fn parse_solana_pay(path: &str, params: &Params) -> WalletResult<PaymentRequest> {
+    if path.starts_with("http") {
+        return Err(WalletError::NotSupported(
+            "Solana Pay transaction requests".into(),
+        ));
+    }
+    SolanaValidator.validate(path)?;
+    let token = params.get("spl-token");
+    if let Some(token) = &token {
+        SolanaValidator.validate(token)?;
+    }
+    let amount = match (params.get("amount"), &token) {
+        (Some(amount), None) => Some(Amount::from_decimal_str(&amount, SOL_DECIMALS)?.into()),
+        (Some(amount), Some(_)) => {
+            check_units(&amount)?;
+            Some(PaymentAmount::Units(amount))
+        }
+        (None, _) => None,
+    };
+    Ok(PaymentRequest {
+        amount,
+        token,
+        label: params.get("label"),
+        message: params.get("message"),
+        memo: params.get("memo"),
+        reference: params.all("reference"),
+        ..PaymentRequest::new(Chain::Solana, path)
+    })
+}
+
+fn parse_ton(path: &str, params: &Params) -> WalletResult<PaymentRequest> {
+    let address = path
+        .strip_prefix("//transfer/")
+        .ok_or_else(|| WalletError::NotSupported(format!("TON link ton:{}", path)))?;
+    TonValidator.validate(address)?;
+    // Custom payloads and state init change what is executed
+    if params.has("bin") || params.has("init") {
+        return Err(WalletError::NotSupported(
+            "TON links with bin or init payloads".into(),
+        ));
+    }
+    let token = params.get("jetton");
+    if let Some(token) = &token {
+        TonValidator.validate(token)?;
+    }
+    let amount = params
+        .get("amount")
+        .map(|amount| Amount::from_decimal_str(&amount, 0).map(|a| a.value))
+        .transpose()?
+        .map(|value| match token {
+            Some(_) => PaymentAmount::BaseUnits(value),
+            None => Amount::from_smallest_unit(value, TON_DECIMALS).into(),
+        });
+    Ok(PaymentRequest {
+        amount,
+        token,
+        memo: params.get("text"),
+        ..PaymentRequest::new(Chain::Ton, address)
+    })
+}
+
+/// BIP-21 URIs are used for mainnet and testnet alike
+fn validate_bitcoin(address: &str) -> WalletResult<()> {
+    BitcoinValidator::mainnet()
+        .validate(address)
+        .or_else(|_| BitcoinValidator::testnet().validate(address))
+}
+
+/// Parses an EIP-681 number, which may use scientific notation (`2.014e18`)
+fn eip681_number(value: &str) -> WalletResult<u128> {
+    let (mantissa, exponent) = match value.split_once(['e', 'E']) {
+        Some((mantissa, exponent)) => {
+            let exponent = exponent
+                .parse::<u8>()
+                .map_err(|_| malformed(&format!("invalid number {}", value)))?;
+            (mantissa, exponent)
+        }
+        None => (value, 0),
+    };
+    Ok(Amount::from_decimal_str(mantissa, exponent)?.value)
+}
+
+/// Checks the syntax of a whole-unit amount whose decimals are unknown
+fn check_units(units: &str) -> WalletResult<()> {
+    let fraction = units.trim().split_once('.').map_or(0, |(_, f)| f.len());
+    let decimals = u8::try_from(fraction).map_err(|_| malformed("too many decimals"))?;
+    Amount::from_decimal_str(units, decimals).map(drop)
+}
+
+/// Formats an amount in whole units without trailing zeros
+fn whole_units(amount: Amount) -> String {
+    let formatted = amount.to_string();
+    if formatted.contains('.') {
+        formatted
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_string()
+    } else {
+        formatted
+    }
+}
+
+fn reject(present: bool, fields: &str) -> WalletResult<()> {
+    if present {
+        return Err(WalletError::NotSupported(format!(
+            "{} in this payment URI format",
+            fields
+        )));
+    }
+    Ok(())
+}
+
+fn push_optional<'a>(params: &mut Vec<(&'a str, String)>, key: &'a str, value: &Option<String>) {
+    if let Some(value) = value {
+        params.push((key, value.clone()));
+    }
+}
+
+fn malformed(reason: &str) -> WalletError {
+    WalletError::Other(format!("invalid payment URI: {}", reason))
+}
+
+fn unknown_decimals() -> WalletError {
+    WalletError::Other("token amount needs the token's decimals; pass an exact Amount".into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BTC_ADDRESS: &str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+    const SOL_RECIPIENT: &str = "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+    const TON_ADDRESS: &str = "EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q2";
+
+    #[test]
+    fn test_bip21() {
+        let request = PaymentRequest {
+            label: Some("Luke-Jr".into()),
+            message: Some("Donation for project xyz".into()),
+            ..PaymentRequest::new(Chain::Bitcoin, BTC_ADDRESS)
+                .with_amount(Amount::from_smallest_unit(2_030_000_000, 8))
+        };
+        let uri = request.to_uri().unwrap();
+        assert_eq!(
+            uri,
+            format!(
+                "bitcoin:{}?amount=20.3&label=Luke-Jr&message=Donation%20for%20project%20xyz",
+                BTC_ADDRESS
+            )
+        );
+        assert_eq!(PaymentRequest::parse(&uri).unwrap(), request);
+
+        let required = format!("bitcoin:{}?req-somethingyoudontunderstand=50", BTC_ADDRESS);
+        assert!(PaymentRequest::parse(&required).is_err());
+        assert!(PaymentRequest::parse("bitcoin:notanaddress").is_err());
+        assert!(PaymentRequest::new(Chain::Bitcoin, BTC_ADDRESS)
+            .with_memo("order 1")
+            .to_uri()
+            .is_err());
+    }
+
+    #[test]
+    fn test_eip681() {
+        let address = "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359";
+        let request: PaymentRequest = format!("ethereum:{}@1?value=2.014e18", address)
+            .parse()
+            .unwrap();
+        assert_eq!(request.chain_id, Some(1));
+        let amount = request.amount.as_ref().unwrap().resolve(18).unwrap();
+        assert_eq!(amount.value, 2_014_000_000_000_000_000);
+        assert_eq!(
+            request.to_uri().unwrap(),
+            format!("ethereum:{}@1?value=2014000000000000000", address)
+        );
+
+        let token = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        let transfer = PaymentRequest::new(Chain::Ethereum, address)
+            .with_token(token)
+            .with_chain_id(8453)
+            .with_amount(Amount::from_decimal_str("12.5", 6).unwrap());
+        let uri = transfer.to_uri().unwrap();
+        assert_eq!(
+            uri,
+            format!(
+                "ethereum:{}@8453/transfer?address={}&uint256=12500000",
+                token, address
+            )
+        );
+        let parsed = PaymentRequest::parse(&uri).unwrap();
+        assert_eq!(parsed.address, address);
+        assert_eq!(parsed.token.as_deref(), Some(token));
+        assert_eq!(parsed.amount, Some(PaymentAmount::BaseUnits(12_500_000)));
+        assert_eq!(
+            parsed.amount.unwrap().resolve(6).unwrap().to_string(),
+            "12.500000"
+        );
+
+        let units = PaymentRequest::new(Chain::Ethereum, address)
+            .with_token(token)
+            .with_amount(PaymentAmount::Units("1".into()));
+        assert!(units.to_uri().is_err());
+        let approve = format!("ethereum:{}/approve?address={}", token, address);
+        assert!(matches!(
+            PaymentRequest::parse(&approve),
+            Err(WalletError::NotSupported(_))
+        ));
+    }
+
+    #[test]
+    fn test_solana_pay() {
+        let request = PaymentRequest {
+            label: Some("Michael".into()),
+            message: Some("Thanks for all the fish".into()),
+            reference: vec![SOL_RECIPIENT.into()],
+            ..PaymentRequest::new(Chain::Solana, SOL_RECIPIENT)
+                .with_token(USDC_MINT)
+                .with_amount(PaymentAmount::Units("0.01".into()))
+                .with_memo("OrderId12345")
+        };
+        let uri = request.to_uri().unwrap();
+        assert_eq!(
+            uri,
+            format!(
+                "solana:{0}?amount=0.01&spl-token={1}&reference={0}&label=Michael&message=Thanks%20for%20all%20the%20fish&memo=OrderId12345",
+                SOL_RECIPIENT, USDC_MINT
+            )
+        );
+        assert_eq!(PaymentRequest::parse(&uri).unwrap(), request);
+
+        let native =
+            PaymentRequest::parse(&format!("solana:{}?amount=1.5", SOL_RECIPIENT)).unwrap();
+        assert_eq!(
+            native.amount,
+            Some(PaymentAmount::Exact(Amount::from_smallest_unit(
+                1_500_000_000,
+                9
+            )))
+        );
+        assert!(PaymentRequest::parse("solana:https://example.com/pay").is_err());
+    }
+
+    #[test]
+    fn test_ton_transfer() {
+        let request = PaymentRequest::new(Chain::Ton, TON_ADDRESS)
+            .with_amount(Amount::from_decimal_str("1.5", 9).unwrap())
+            .with_memo("invoice 42");
+        let uri = request.to_uri().unwrap();
+        assert_eq!(
+            uri,
+            format!(
+                "ton://transfer/{}?amount=1500000000&text=invoice%2042",
+                TON_ADDRESS
+            )
+        );
+        assert_eq!(PaymentRequest::parse(&uri).unwrap(), request);
+
+        let jetton = format!("ton://transfer/{0}?amount=1000&jetton={0}", TON_ADDRESS);
+        let parsed = PaymentRequest::parse(&jetton).unwrap();
+        assert_eq!(parsed.amount, Some(PaymentAmount::BaseUnits(1000)));
+        assert_eq!(parsed.token.as_deref(), Some(TON_ADDRESS));
+        let payload = format!("ton://transfer/{}?bin=te6cc", TON_ADDRESS);
+        assert!(PaymentRequest::parse(&payload).is_err());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(matches!(
+            PaymentRequest::parse("litecoin:abc"),
+            Err(WalletError::NotSupported(_))
+        ));
+        assert!(PaymentRequest::parse("not a uri").is_err());
+        assert!(PaymentRequest::new(Chain::Cosmos, "cosmos1...")
+            .to_uri()
+            .is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Signing backends
+//!
+//! A [`Signer`] owns (or fronts) a private key and produces signatures.
+//! Chain wallets hold a `Box<dyn Signer>` and only deal with hashing and
+//! encoding, so in-memory keys, hardware wallets, remote signers and HSMs can
+//! back any chain that uses the same curve.
+
+use crate::WalletResult;
+#[cfg(feature = "secp256k1")]
+use crate::WalletError;
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "secp256k1")]
+use sha2::{Digest, Sha256};
+use std::fmt;
+
+/// Signature algorithm implemented by a [`Signer`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[non_exhaustive]
+pub enum SignatureScheme {
+    /// Ed25519 (NEAR, Polkadot, Solana, Sui, Aptos, TON)
+    Ed25519,
+    /// ECDSA over secp256k1 (Bitcoin, EVM chains, Cosmos, Tron)
+    Secp256k1,
+    /// Schnorr over secp256k1 as produced by FROST (RFC 9591): a 33-byte
+    /// compressed `R` followed by a 32-byte `z`. Not BIP-340, so no chain
+    /// verifies it natively
+    Secp256k1Schnorr,
+    /// BIP-340 Schnorr over secp256k1 (Bitcoin taproot): a 32-byte x-only
+    /// public key and a 64-byte `R.x || s` signature
+    Bip340,
+}
+
+impl fmt::Display for SignatureScheme {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SignatureScheme::Ed25519 => f.write_str("ed25519"),
+            SignatureScheme::Secp256k1 => f.write_str("secp256k1"),
+            SignatureScheme::Secp256k1Schnorr => f.write_str("secp256k1-schnorr"),
+            SignatureScheme::Bip340 => f.write_str("bip340"),
+        }
+    }
+}
+
+/// A source of signatures, independent of any chain
+#[async_trait]
+pub trait Signer: Send + Sync {
+    /// Returns the signature algorithm
+    fn scheme(&self) -> SignatureScheme;
+
+    /// Returns the public key (32 bytes for Ed25519, 33-byte compressed SEC1
+    /// for secp256k1, 32-byte x-only for BIP-340)
+    fn public_key(&self) -> Vec<u8>;
+
+    /// Signs a 32-byte digest
+    ///
+    /// secp256k1 signers return a 64-byte compact `r || s` signature with
+    /// low-S normalisation. Ed25519 and BIP-340 signers sign the digest bytes
+    /// as a message.
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>>;
+
+    /// Signs an arbitrary message
+    ///
+    /// Ed25519 signers sign the message directly; secp256k1 signers sign its
+    /// SHA-256 digest. Chains with their own digest (e.g. Keccak-256) should
+    /// hash first and call [`sign_hash`](Signer::sign_hash).
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>>;
+
+    /// Returns the raw secret key if it is held in process memory
+    ///
+    /// Hardware, remote and HSM signers return `None`.
+    fn secret_key(&self) -> Option<Vec<u8>> {
+        None
+    }
+}
+
+/// In-memory Ed25519 key
+pub struct Ed25519Signer {
+    key: ed25519_dalek::SigningKey,
+}
+
+impl Ed25519Signer {
+    /// Creates a signer from a 32-byte secret seed
+    pub fn from_bytes(secret: &[u8; 32]) -> Self {
+        Self {
+            key: ed25519_dalek::SigningKey::from_bytes(secret),
+        }
+    }
+}
+
+impl fmt::Debug for Ed25519Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Ed25519Signer")
+            .field("public_key", &self.key.verifying_key())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl Signer for Ed25519Signer {
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Ed25519
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.key.verifying_key().to_bytes().to_vec()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        self.sign_message(hash).await
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        use ed25519_dalek::Signer as _;
+        Ok(self.key.sign(message).to_bytes().to_vec())
+    }
+
+    fn secret_key(&self) -> Option<Vec<u8>> {
+        Some(self.key.to_bytes().to_vec())
+    }
+}
+
+/// In-memory secp256k1 key
+#[cfg(feature = "secp256k1")]
+pub struct Secp256k1Signer {
+    key: secp256k1::SecretKey,
+    public_key: secp256k1::PublicKey,
+}
+
+#[cfg(feature = "secp256k1")]
+impl Secp256k1Signer {
+    /// Creates a signer from a 32-byte secret key
+    pub fn from_slice(secret: &[u8]) -> WalletResult<Self> {
+        let key = secp256k1::SecretKey::from_slice(secret)
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        let public_key = secp256k1::PublicKey::from_secret_key(secp256k1::SECP256K1, &key);
+        Ok(Self { key, public_key })
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+impl fmt::Debug for Secp256k1Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Secp256k1Signer")
+            .field("public_key", &self.public_key)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "secp256k1")]
+#[async_trait]
+impl Signer for Secp256k1Signer {
+    fn scheme(&self) -> SignatureScheme {
+        SignatureScheme::Secp256k1
+    }
+
+    fn public_key(&self) -> Vec<u8> {
+        self.public_key.serialize().to_vec()
+    }
+
+    async fn sign_hash(&self, hash: &[u8; 32]) -> WalletResult<Vec<u8>> {
+        let msg = secp256k1::Message::from_slice(hash)
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        let sig = secp256k1::SECP256K1.sign_ecdsa(&msg, &self.key);
+        Ok(sig.serialize_compact().to_vec())
+    }
+
+    async fn sign_message(&self, message: &[u8]) -> WalletResult<Vec<u8>> {
+        let hash: [u8; 32] = Sha256::digest(message).into();
+        self.sign_hash(&hash).await
+    }
+
+    fn secret_key(&self) -> Option<Vec<u8>> {
+        Some(self.key.secret_bytes().to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_ed25519_signer() {
+        let signer = Ed25519Signer::from_bytes(&[7u8; 32]);
+        assert_eq!(signer.scheme(), SignatureScheme::Ed25519);
+        assert_eq!(signer.public_key().len(), 32);
+
+        let sig = signer.sign_message(b"hello").await.unwrap();
+        let vk = ed25519_dalek::VerifyingKey::from_bytes(&signer.public_key().try_into().unwrap())
+            .unwrap();
+        let sig = ed25519_dalek::Signature::from_slice(&sig).unwrap();
+        assert!(vk.verify_strict(b"hello", &sig).is_ok());
+        assert!(!format!("{:?}", signer).contains("0707"));
+    }
+
+    #[tokio::test]
+    async fn test_secp256k1_signer() {
+        let signer = Secp256k1Signer::from_slice(&[1u8; 32]).unwrap();
+        assert_eq!(signer.scheme(), SignatureScheme::Secp256k1);
+        assert_eq!(signer.public_key().len(), 33);
+
+        let sig = signer.sign_message(b"hello").await.unwrap();
+        assert_eq!(sig.len(), 64);
+        let hash: [u8; 32] = Sha256::digest(b"hello").into();
+        let msg = secp256k1::Message::from_slice(&hash).unwrap();
+        let sig = secp256k1::ecdsa::Signature::from_compact(&sig).unwrap();
+        let pk = secp256k1::PublicKey::from_slice(&signer.public_key()).unwrap();
+        assert!(secp256k1::SECP256K1.verify_ecdsa(&msg, &sig, &pk).is_ok());
+
+        assert!(Secp256k1Signer::from_slice(&[0u8; 32]).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_dyn_signer() {
+        let signers: Vec<Box<dyn Signer>> = vec![
+            Box::new(Ed25519Signer::from_bytes(&[2u8; 32])),
+            Box::new(Secp256k1Signer::from_slice(&[2u8; 32]).unwrap()),
+        ];
+        for signer in &signers {
+            assert!(signer.secret_key().is_some());
+            assert!(!signer.sign_hash(&[9u8; 32]).await.unwrap().is_empty());
+        }
+    }
+}
@@ -0,0 +1,879 @@
+//! Pairing and session management
+//!
+//! [`SignClient`] is the wallet end of the sign protocol. It subscribes to
+//! pairing topics, turns incoming proposals and requests into [`Event`]s,
+//! settles approved sessions on a new topic keyed by an X25519 exchange,
+//! and answers protocol housekeeping (pings, deletes) on its own.
+
+use crate::crypto::{KeyPair, SymKey};
+use crate::handler::{RequestHandler, RpcError};
+use crate::relay::{Relay, RelayMessage};
+use crate::rpc::{self, MethodSpec, Payload, Request, Response};
+use crate::rpc::{Metadata, Namespace, Participant, RelayProtocol, SessionNamespace};
+use crate::uri::PairingUri;
+use crate::{now, Result, WalletConnectError};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// Lifetime of a settled or extended session
+const SESSION_TTL_SECS: u64 = 7 * 86_400;
+
+/// A dApp asking to open a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct Proposal {
+    /// Request id
+    pub id: u64,
+    /// Pairing the proposal arrived on
+    pub pairing_topic: String,
+    /// The dApp
+    pub proposer: Metadata,
+    /// Namespaces the dApp cannot work without
+    pub required_namespaces: BTreeMap<String, Namespace>,
+    /// Namespaces the dApp can use if granted
+    pub optional_namespaces: BTreeMap<String, Namespace>,
+    /// Proposal expiry, Unix seconds
+    pub expiry: Option<u64>,
+    proposer_public_key: String,
+}
+
+/// An established session with a dApp
+///
+/// Sessions serialize with their symmetric key so they can be persisted
+/// and [restored](SignClient::restore); store them as secrets.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Session {
+    /// Session topic
+    pub topic: String,
+    /// Pairing the session was proposed on
+    pub pairing_topic: String,
+    /// The dApp
+    pub peer: Metadata,
+    /// What the dApp was granted
+    pub namespaces: BTreeMap<String, SessionNamespace>,
+    /// Session expiry, Unix seconds
+    pub expiry: u64,
+    /// Whether the dApp accepted the settlement
+    pub acknowledged: bool,
+    sym_key: SymKey,
+}
+
+impl Session {
+    fn grants(&self, chain_id: &str, method: &str) -> bool {
+        let namespace = chain_id.split(':').next().unwrap_or_default();
+        [namespace, chain_id].iter().any(|key| {
+            self.namespaces.get(*key).is_some_and(|granted| {
+                let chain_listed = granted.chains.as_ref().map_or(*key == chain_id, |chains| {
+                    chains.iter().any(|c| c == chain_id)
+                }) || granted
+                    .accounts
+                    .iter()
+                    .any(|a| a.starts_with(&format!("{}:", chain_id)));
+                chain_listed && granted.methods.iter().any(|m| m == method)
+            })
+        })
+    }
+}
+
+/// A dApp request within a session
+#[derive(Debug, Clone, PartialEq)]
+pub struct SessionRequest {
+    /// Request id
+    pub id: u64,
+    /// Session topic
+    pub topic: String,
+    /// CAIP-2 chain id, e.g. `eip155:1`
+    pub chain_id: String,
+    /// JSON-RPC method, e.g. `personal_sign`
+    pub method: String,
+    /// JSON-RPC params
+    pub params: Value,
+    /// The dApp
+    pub peer: Metadata,
+}
+
+/// Something the wallet user may need to act on
+#[derive(Debug, Clone, PartialEq)]
+pub enum Event {
+    /// Approve with [`SignClient::approve`] or decline with
+    /// [`SignClient::reject`]
+    SessionProposal(Proposal),
+    /// Answer with [`SignClient::respond`] or decline with
+    /// [`SignClient::reject_request`]
+    SessionRequest(SessionRequest),
+    /// The dApp ended the session
+    SessionDeleted {
+        /// Session topic
+        topic: String,
+    },
+}
+
+#[derive(Debug, Default)]
+struct State {
+    pairings: HashMap<String, SymKey>,
+    sessions: HashMap<String, Session>,
+    /// Settle request id to session topic
+    settling: HashMap<u64, String>,
+}
+
+/// Wallet-side WalletConnect client
+pub struct SignClient {
+    relay: Arc<dyn Relay>,
+    metadata: Metadata,
+    handlers: Vec<Arc<dyn RequestHandler>>,
+    state: Mutex<State>,
+}
+
+impl SignClient {
+    /// Creates a client presenting `metadata` to dApps
+    pub fn new(relay: impl Relay + 'static, metadata: Metadata) -> Self {
+        Self {
+            relay: Arc::new(relay),
+            metadata,
+            handlers: Vec::new(),
+            state: Mutex::new(State::default()),
+        }
+    }
+
+    /// Serves a namespace with `handler`
+    pub fn with_handler(mut self, handler: impl RequestHandler + 'static) -> Self {
+        self.handlers.push(Arc::new(handler));
+        self
+    }
+
+    fn state(&self) -> std::sync::MutexGuard<'_, State> {
+        self.state.lock().expect("sign client state lock")
+    }
+
+    /// Pairs with a dApp from its `wc:` URI
+    ///
+    /// The dApp's session proposal follows as an [`Event::SessionProposal`].
+    pub async fn pair(&self, uri: &PairingUri) -> Result<()> {
+        if uri.expiry.is_some_and(|expiry| expiry <= now()) {
+            return Err(WalletConnectError::Expired("pairing".into()));
+        }
+        self.state()
+            .pairings
+            .insert(uri.topic.clone(), uri.sym_key.clone());
+        self.relay.subscribe(&uri.topic).await
+    }
+
+    /// Waits for the next event
+    ///
+    /// Pings, deletes and acknowledgements are handled internally.
+    /// Messages that cannot be decrypted are logged and skipped. Returns
+    /// `None` once the relay shuts down.
+    pub async fn next_event(&self) -> Option<Result<Event>> {
+        loop {
+            let message = self.relay.next_message().await?;
+            match self.process(message).await {
+                Ok(Some(event)) => return Some(Ok(event)),
+                Ok(None) => {}
+                Err(
+                    e @ (WalletConnectError::InvalidEnvelope(_)
+                    | WalletConnectError::UnknownTopic(_)
+                    | WalletConnectError::Json(_)),
+                ) => tracing::warn!("Skipping relay message: {}", e),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+    }
+
+    async fn process(&self, message: RelayMessage) -> Result<Option<Event>> {
+        let (sym_key, is_session) = {
+            let state = self.state();
+            if let Some(session) = state.sessions.get(&message.topic) {
+                (session.sym_key.clone(), true)
+            } else if let Some(key) = state.pairings.get(&message.topic) {
+                (key.clone(), false)
+            } else {
+                return Err(WalletConnectError::UnknownTopic(message.topic));
+            }
+        };
+        let payload: Payload = serde_json::from_slice(&sym_key.open(&message.message)?)?;
+        let topic = message.topic;
+        match payload {
+            Payload::Request(request) if is_session => {
+                self.on_session_request(topic, request).await
+            }
+            Payload::Request(request) => self.on_pairing_request(topic, request).await,
+            Payload::Response(response) => {
+                self.on_response(response);
+                Ok(None)
+            }
+        }
+    }
+
+    async fn on_pairing_request(&self, topic: String, request: Request) -> Result<Option<Event>> {
+        match request.method.as_str() {
+            "wc_sessionPropose" => {
+                let params: rpc::SessionProposeParams = serde_json::from_value(request.params)?;
+                if params
+                    .expiry_timestamp
+                    .is_some_and(|expiry| expiry <= now())
+                {
+                    tracing::debug!("Ignoring expired proposal {}", request.id);
+                    return Ok(None);
+                }
+                Ok(Some(Event::SessionProposal(Proposal {
+                    id: request.id,
+                    pairing_topic: topic,
+                    proposer: params.proposer.metadata,
+                    required_namespaces: params.required_namespaces,
+                    optional_namespaces: params.optional_namespaces,
+                    expiry: params.expiry_timestamp,
+                    proposer_public_key: params.proposer.public_key,
+                })))
+            }
+            "wc_pairingPing" => {
+                self.send_response(
+                    &topic,
+                    rpc::PAIRING_PING,
+                    Response::result(request.id, json!(true)),
+                )
+                .await?;
+                Ok(None)
+            }
+            "wc_pairingDelete" => {
+                self.send_response(
+                    &topic,
+                    rpc::PAIRING_DELETE,
+                    Response::result(request.id, json!(true)),
+                )
+                .await?;
+                self.state().pairings.remove(&topic);
+                self.relay.unsubscribe(&topic).await?;
+                Ok(None)
+            }
+            method => {
+                self.reply_unsupported(&topic, &request, method).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    async fn on_session_request(&self, topic: String, request: Request) -> Result<Option<Event>> {
+        match request.method.as_str() {
+            "wc_sessionRequest" => {
+                let params: rpc::SessionRequestParams = serde_json::from_value(request.params)?;
+                let peer = match self.state().sessions.get(&topic) {
+                    Some(session) => session.peer.clone(),
+                    None => return Ok(None),
+                };
+                Ok(Some(Event::SessionRequest(SessionRequest {
+                    id: request.id,
+                    topic,
+                    chain_id: params.chain_id,
+                    method: params.request.method,
+                    params: params.request.params,
+                    peer,
+                })))
+            }
+            "wc_sessionPing" => {
+                self.send_response(
+                    &topic,
+                    rpc::SESSION_PING,
+                    Response::result(request.id, json!(true)),
+                )
+                .await?;
+                Ok(None)
+            }
+            "wc_sessionDelete" => {
+                self.send_response(
+                    &topic,
+                    rpc::SESSION_DELETE,
+                    Response::result(request.id, json!(true)),
+                )
+                .await?;
+                self.state().sessions.remove(&topic);
+                self.relay.unsubscribe(&topic).await?;
+                Ok(Some(Event::SessionDeleted { topic }))
+            }
+            method => {
+                self.reply_unsupported(&topic, &request, method).await?;
+                Ok(None)
+            }
+        }
+    }
+
+    fn on_response(&self, response: Response) {
+        let mut state = self.state();
+        let Some(topic) = state.settling.remove(&response.id) else {
+            return;
+        };
+        match response.error {
+            None => {
+                if let Some(session) = state.sessions.get_mut(&topic) {
+                    session.acknowledged = true;
+                }
+            }
+            Some(error) => {
+                tracing::warn!("dApp refused session {}: {}", topic, error.message);
+                state.sessions.remove(&topic);
+            }
+        }
+    }
+
+    async fn reply_unsupported(&self, topic: &str, request: &Request, method: &str) -> Result<()> {
+        match rpc::method_spec(method) {
+            Some(spec) => {
+                let response = Response::error(
+                    request.id,
+                    5101,
+                    &format!("Unsupported methods: {}", method),
+                );
+                self.send_response(topic, spec, response).await
+            }
+            None => {
+                tracing::debug!("Ignoring unknown method {} on {}", method, topic);
+                Ok(())
+            }
+        }
+    }
+
+    /// Grants what the handlers can serve of a proposal
+    ///
+    /// Fails with [`WalletConnectError::UnsupportedNamespaces`] if a
+    /// required chain or method has no handler; reject the proposal then.
+    pub fn namespaces_for(
+        &self,
+        proposal: &Proposal,
+    ) -> Result<BTreeMap<String, SessionNamespace>> {
+        let unsupported = |what: String| WalletConnectError::UnsupportedNamespaces(what);
+        // Requested chains, methods and events per CAIP-2 namespace
+        #[derive(Default)]
+        struct Wanted {
+            chains: BTreeSet<String>,
+            methods: BTreeSet<String>,
+            events: BTreeSet<String>,
+            required_events: BTreeSet<String>,
+        }
+        let mut wanted: BTreeMap<String, Wanted> = BTreeMap::new();
+        for (required, namespaces) in [
+            (true, &proposal.required_namespaces),
+            (false, &proposal.optional_namespaces),
+        ] {
+            for (key, namespace) in namespaces {
+                let (name, chains) = match &namespace.chains {
+                    Some(chains) => (key.as_str(), chains.clone()),
+                    None => (key.split(':').next().unwrap_or(key), vec![key.clone()]),
+                };
+                let handler = self.handlers.iter().find(|h| h.namespace() == name);
+                if required {
+                    let handler = handler.ok_or_else(|| unsupported(key.clone()))?;
+                    let served = handler.chains();
+                    if let Some(chain) = chains.iter().find(|c| !served.contains(c)) {
+                        return Err(unsupported(chain.clone()));
+                    }
+                    let methods = handler.methods();
+                    if let Some(method) = namespace.methods.iter().find(|m| !methods.contains(m)) {
+                        return Err(unsupported(method.clone()));
+                    }
+                } else if handler.is_none() {
+                    continue;
+                }
+                let entry = wanted.entry(name.to_string()).or_default();
+                entry.chains.extend(chains);
+                entry.methods.extend(namespace.methods.iter().cloned());
+                entry.events.extend(namespace.events.iter().cloned());
+                if required {
+                    entry
+                        .required_events
+                        .extend(namespace.events.iter().cloned());
+                }
+            }
+        }
+
+        let mut granted = BTreeMap::new();
+        for (name, wanted) in wanted {
+            let handler = self
+                .handlers
+                .iter()
+                .find(|h| h.namespace() == name)
+                .expect("namespaces without a handler were skipped");
+            let chains: Vec<String> = handler
+                .chains()
+                .into_iter()
+                .filter(|chain| wanted.chains.contains(chain))
+                .collect();
+            if chains.is_empty() {
+                continue;
+            }
+            let accounts = handler
+                .accounts()
+                .into_iter()
+                .filter(|account| {
+                    chains
+                        .iter()
+                        .any(|c| account.starts_with(&format!("{}:", c)))
+                })
+                .collect();
+            let methods = handler
+                .methods()
+                .into_iter()
+                .filter(|m| wanted.methods.is_empty() || wanted.methods.contains(m))
+                .collect();
+            let mut events: BTreeSet<String> = handler
+                .events()
+                .into_iter()
+                .filter(|e| wanted.events.contains(e))
+                .collect();
+            events.extend(wanted.required_events);
+            granted.insert(
+                name,
+                SessionNamespace {
+                    chains: Some(chains),
+                    accounts,
+                    methods,
+                    events: events.into_iter().collect(),
+                },
+            );
+        }
+        if granted.is_empty() {
+            return Err(unsupported("no requested chain is served".into()));
+        }
+        Ok(granted)
+    }
+
+    /// Approves a proposal with everything the handlers can serve
+    pub async fn approve(&self, proposal: &Proposal) -> Result<Session> {
+        let namespaces = self.namespaces_for(proposal)?;
+        self.approve_with(proposal, namespaces).await
+    }
+
+    /// Approves a proposal granting `namespaces`
+    pub async fn approve_with(
+        &self,
+        proposal: &Proposal,
+        namespaces: BTreeMap<String, SessionNamespace>,
+    ) -> Result<Session> {
+        if proposal.expiry.is_some_and(|expiry| expiry <= now()) {
+            return Err(WalletConnectError::Expired("proposal".into()));
+        }
+        if !self.state().pairings.contains_key(&proposal.pairing_topic) {
+            return Err(WalletConnectError::UnknownTopic(
+                proposal.pairing_topic.clone(),
+            ));
+        }
+        let key_pair = KeyPair::generate();
+        let sym_key = key_pair.agree(&proposal.proposer_public_key)?;
+        let topic = sym_key.topic();
+        self.relay.subscribe(&topic).await?;
+
+        let session = Session {
+            topic: topic.clone(),
+            pairing_topic: proposal.pairing_topic.clone(),
+            peer: proposal.proposer.clone(),
+            namespaces: namespaces.clone(),
+            expiry: now() + SESSION_TTL_SECS,
+            acknowledged: false,
+            sym_key,
+        };
+        self.state().sessions.insert(topic.clone(), session.clone());
+
+        let result = rpc::SessionProposeResponse {
+            relay: RelayProtocol::default(),
+            responder_public_key: key_pair.public_hex(),
+        };
+        self.send_response(
+            &proposal.pairing_topic,
+            rpc::SESSION_PROPOSE,
+            Response::result(proposal.id, serde_json::to_value(result)?),
+        )
+        .await?;
+
+        let settle = rpc::SessionSettleParams {
+            relay: RelayProtocol::default(),
+            namespaces,
+            required_namespaces: proposal.required_namespaces.clone(),
+            optional_namespaces: proposal.optional_namespaces.clone(),
+            pairing_topic: proposal.pairing_topic.clone(),
+            controller: Participant {
+                public_key: key_pair.public_hex(),
+                metadata: self.metadata.clone(),
+            },
+            expiry: session.expiry,
+        };
+        let id = self
+            .send_request(&topic, rpc::SESSION_SETTLE, serde_json::to_value(settle)?)
+            .await?;
+        self.state().settling.insert(id, topic);
+        Ok(session)
+    }
+
+    /// Declines a proposal
+    pub async fn reject(&self, proposal: &Proposal) -> Result<()> {
+        let rejected = RpcError::user_rejected();
+        let response = Response::error(proposal.id, rejected.code, &rejected.message);
+        let spec = MethodSpec {
+            response_tag: rpc::SESSION_PROPOSE_REJECT_TAG,
+            ..rpc::SESSION_PROPOSE
+        };
+        self.send_response(&proposal.pairing_topic, spec, response)
+            .await
+    }
+
+    /// Answers a request with the matching handler
+    ///
+    /// Requests for chains or methods the session does not grant are
+    /// refused. Handler errors are sent to the dApp and returned.
+    pub async fn respond(
+        &self,
+        request: &SessionRequest,
+    ) -> Result<std::result::Result<Value, RpcError>> {
+        let granted = match self.state().sessions.get(&request.topic) {
+            Some(session) => session.grants(&request.chain_id, &request.method),
+            None => return Err(WalletConnectError::UnknownTopic(request.topic.clone())),
+        };
+        let namespace = request.chain_id.split(':').next().unwrap_or_default();
+        let handler = self.handlers.iter().find(|h| h.namespace() == namespace);
+        let outcome = match handler {
+            _ if !granted => Err(RpcError::unsupported_method(&request.method)),
+            None => Err(RpcError::unsupported_chain(&request.chain_id)),
+            Some(handler) => {
+                handler
+                    .handle(&request.chain_id, &request.method, &request.params)
+                    .await
+            }
+        };
+        self.respond_with(request, outcome.clone()).await?;
+        Ok(outcome)
+    }
+
+    /// Declines a request
+    pub async fn reject_request(&self, request: &SessionRequest) -> Result<()> {
+        self.respond_with(request, Err(RpcError::user_rejected()))
+            .await
+    }
+
+    /// Sends a custom answer to a request
+    pub async fn respond_with(
+        &self,
+        request: &SessionRequest,
+        outcome: std::result::Result<Value, RpcError>,
+    ) -> Result<()> {
+        let response = match outcome {
+            Ok(result) => Response::result(request.id, result),
+            Err(error) => Response::error(request.id, error.code, &error.message),
+        };
+        self.send_response(&request.topic, rpc::SESSION_REQUEST, response)
+            .await
+    }
+
+    /// Emits an event such as `accountsChanged` to the dApp
+    pub async fn emit(&self, topic: &str, chain_id: &str, name: &str, data: Value) -> Result<()> {
+        let params = rpc::SessionEventParams {
+            event: rpc::EventBody {
+                name: name.to_string(),
+                data,
+            },
+            chain_id: chain_id.to_string(),
+        };
+        self.send_request(topic, rpc::SESSION_EVENT, serde_json::to_value(params)?)
+            .await?;
+        Ok(())
+    }
+
+    /// Replaces the namespaces granted to a session
+    pub async fn update(
+        &self,
+        topic: &str,
+        namespaces: BTreeMap<String, SessionNamespace>,
+    ) -> Result<()> {
+        let params = rpc::SessionUpdateParams {
+            namespaces: namespaces.clone(),
+        };
+        self.send_request(topic, rpc::SESSION_UPDATE, serde_json::to_value(params)?)
+            .await?;
+        if let Some(session) = self.state().sessions.get_mut(topic) {
+            session.namespaces = namespaces;
+        }
+        Ok(())
+    }
+
+    /// Extends a session by another week
+    pub async fn extend(&self, topic: &str) -> Result<()> {
+        let expiry = now() + SESSION_TTL_SECS;
+        let params = rpc::SessionExtendParams { expiry };
+        self.send_request(topic, rpc::SESSION_EXTEND, serde_json::to_value(params)?)
+            .await?;
+        if let Some(session) = self.state().sessions.get_mut(topic) {
+            session.expiry = expiry;
+        }
+        Ok(())
+    }
+
+    /// Ends a session
+    pub async fn disconnect(&self, topic: &str) -> Result<()> {
+        let params = json!({ "code": 6000, "message": "User disconnected." });
+        self.send_request(topic, rpc::SESSION_DELETE, params)
+            .await?;
+        self.state().sessions.remove(topic);
+        self.relay.unsubscribe(topic).await
+    }
+
+    /// Active sessions
+    pub fn sessions(&self) -> Vec<Session> {
+        let now = now();
+        self.state()
+            .sessions
+            .values()
+            .filter(|session| session.expiry > now)
+            .cloned()
+            .collect()
+    }
+
+    /// Resumes a session saved from [`sessions`](Self::sessions)
+    pub async fn restore(&self, session: Session) -> Result<()> {
+        if session.expiry <= now() {
+            return Err(WalletConnectError::Expired("session".into()));
+        }
+        let topic = session.topic.clone();
+        self.state().sessions.insert(topic.clone(), session);
+        self.relay.subscribe(&topic).await
+    }
+
+    fn key_for(&self, topic: &str) -> Result<SymKey> {
+        let state = self.state();
+        state
+            .sessions
+            .get(topic)
+            .map(|session| session.sym_key.clone())
+            .or_else(|| state.pairings.get(topic).cloned())
+            .ok_or_else(|| WalletConnectError::UnknownTopic(topic.to_string()))
+    }
+
+    async fn send_request(&self, topic: &str, spec: MethodSpec, params: Value) -> Result<u64> {
+        let request = Request::new(spec.name, params);
+        let id = request.id;
+        let message = self
+            .key_for(topic)?
+            .seal(&serde_json::to_vec(&Payload::Request(request))?)?;
+        self.relay
+            .publish(topic, &message, spec.request_tag, spec.ttl)
+            .await?;
+        Ok(id)
+    }
+
+    async fn send_response(&self, topic: &str, spec: MethodSpec, response: Response) -> Result<()> {
+        let message = self
+            .key_for(topic)?
+            .seal(&serde_json::to_vec(&Payload::Response(response))?)?;
+        self.relay
+            .publish(topic, &message, spec.response_tag, spec.ttl)
+            .await
+    }
+}
+
+impl std::fmt::Debug for SignClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SignClient")
+            .field("metadata", &self.metadata)
+            .field("handlers", &self.handlers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::handler::EvmHandler;
+    use crate::relay::memory::MemoryRelay;
+    use walletd_traits::Secp256k1Signer;
+
+    /// The dApp end of a connection, driven by hand
+    struct Dapp {
+        relay: MemoryRelay,
+        key_pair: KeyPair,
+        pairing: PairingUri,
+    }
+
+    impl Dapp {
+        async fn start(relay: MemoryRelay) -> Self {
+            let sym_key = SymKey::generate();
+            let pairing = PairingUri {
+                topic: sym_key.topic(),
+                sym_key,
+                relay_protocol: "irn".into(),
+                expiry: Some(now() + 300),
+            };
+            relay.subscribe(&pairing.topic).await.unwrap();
+            Self {
+                relay,
+                key_pair: KeyPair::generate(),
+                pairing,
+            }
+        }
+
+        async fn send(&self, topic: &str, key: &SymKey, method: &str, params: Value) -> u64 {
+            let request = Request::new(method, params);
+            let message = key
+                .seal(&serde_json::to_vec(&Payload::Request(request.clone())).unwrap())
+                .unwrap();
+            self.relay
+                .publish(topic, &message, 0, std::time::Duration::ZERO)
+                .await
+                .unwrap();
+            request.id
+        }
+
+        async fn receive(&self, key: &SymKey) -> Payload {
+            let message = self.relay.next_message().await.unwrap();
+            serde_json::from_slice(&key.open(&message.message).unwrap()).unwrap()
+        }
+
+        async fn propose(&self, required: Value) {
+            let params = json!({
+                "relays": [{ "protocol": "irn" }],
+                "proposer": {
+                    "publicKey": self.key_pair.public_hex(),
+                    "metadata": { "name": "Test dApp", "url": "https://dapp.example", "description": "", "icons": [] }
+                },
+                "requiredNamespaces": required,
+                "optionalNamespaces": {},
+            });
+            self.send(
+                &self.pairing.topic,
+                &self.pairing.sym_key,
+                "wc_sessionPropose",
+                params,
+            )
+            .await;
+        }
+    }
+
+    fn wallet(relay: MemoryRelay) -> SignClient {
+        let key = [0x42; 32];
+        let signer = Arc::new(Secp256k1Signer::from_slice(&key).unwrap());
+        SignClient::new(relay, Metadata::new("walletd", "https://walletd.example")).with_handler(
+            EvmHandler::new(signer)
+                .unwrap()
+                .with_chain(1)
+                .with_chain(10),
+        )
+    }
+
+    async fn next(client: &SignClient) -> Event {
+        client.next_event().await.unwrap().unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_session_lifecycle() {
+        let (wallet_relay, dapp_relay) = MemoryRelay::pair();
+        let client = wallet(wallet_relay);
+        let dapp = Dapp::start(dapp_relay).await;
+
+        dapp.propose(json!({
+            "eip155": { "chains": ["eip155:1"], "methods": ["personal_sign"], "events": ["chainChanged"] }
+        }))
+        .await;
+        client.pair(&dapp.pairing).await.unwrap();
+        let Event::SessionProposal(proposal) = next(&client).await else {
+            panic!("expected a proposal");
+        };
+        assert_eq!(proposal.proposer.name, "Test dApp");
+        let session = client.approve(&proposal).await.unwrap();
+        let granted = &session.namespaces["eip155"];
+        assert_eq!(granted.chains, Some(vec!["eip155:1".to_string()]));
+        assert_eq!(granted.methods, ["personal_sign"]);
+        assert_eq!(granted.accounts.len(), 1);
+
+        // The dApp derives the session key from the proposal response
+        let Payload::Response(response) = dapp.receive(&dapp.pairing.sym_key).await else {
+            panic!("expected the proposal response");
+        };
+        let result: rpc::SessionProposeResponse =
+            serde_json::from_value(response.result.unwrap()).unwrap();
+        let session_key = dapp.key_pair.agree(&result.responder_public_key).unwrap();
+        assert_eq!(session_key.topic(), session.topic);
+        dapp.relay.subscribe(&session.topic).await.unwrap();
+        let Payload::Request(settle) = dapp.receive(&session_key).await else {
+            panic!("expected the settlement");
+        };
+        assert_eq!(settle.method, "wc_sessionSettle");
+        let ack = Response::result(settle.id, json!(true));
+        let message = session_key
+            .seal(&serde_json::to_vec(&Payload::Response(ack)).unwrap())
+            .unwrap();
+        dapp.relay
+            .publish(&session.topic, &message, 1103, std::time::Duration::ZERO)
+            .await
+            .unwrap();
+
+        // A signing request is answered by the EVM handler
+        let account = granted.accounts[0].rsplit(':').next().unwrap().to_string();
+        let request = json!({
+            "request": { "method": "personal_sign", "params": ["0x68656c6c6f", account] },
+            "chainId": "eip155:1"
+        });
+        let id = dapp
+            .send(&session.topic, &session_key, "wc_sessionRequest", request)
+            .await;
+        let Event::SessionRequest(request) = next(&client).await else {
+            panic!("expected a request");
+        };
+        assert!(client.sessions()[0].acknowledged);
+        assert_eq!(request.id, id);
+        client.respond(&request).await.unwrap().unwrap();
+        let Payload::Response(response) = dapp.receive(&session_key).await else {
+            panic!("expected the signature");
+        };
+        assert_eq!(response.result.unwrap().as_str().unwrap().len(), 132);
+
+        // Chains the session does not grant are refused
+        let request = json!({
+            "request": { "method": "personal_sign", "params": ["0x00", account] },
+            "chainId": "eip155:10"
+        });
+        dapp.send(&session.topic, &session_key, "wc_sessionRequest", request)
+            .await;
+        let Event::SessionRequest(request) = next(&client).await else {
+            panic!("expected a request");
+        };
+        assert_eq!(
+            client.respond(&request).await.unwrap().unwrap_err().code,
+            5101
+        );
+        let Payload::Response(response) = dapp.receive(&session_key).await else {
+            panic!("expected an error");
+        };
+        assert_eq!(response.error.unwrap().code, 5101);
+
+        let params = json!({ "code": 6000, "message": "bye" });
+        dapp.send(&session.topic, &session_key, "wc_sessionDelete", params)
+            .await;
+        assert_eq!(
+            next(&client).await,
+            Event::SessionDeleted {
+                topic: session.topic.clone()
+            }
+        );
+        assert!(client.sessions().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_proposal() {
+        let (wallet_relay, dapp_relay) = MemoryRelay::pair();
+        let client = wallet(wallet_relay);
+        let dapp = Dapp::start(dapp_relay).await;
+
+        dapp.propose(json!({
+            "eip155:137": { "methods": ["personal_sign"], "events": [] }
+        }))
+        .await;
+        client.pair(&dapp.pairing).await.unwrap();
+        let Event::SessionProposal(proposal) = next(&client).await else {
+            panic!("expected a proposal");
+        };
+        assert!(matches!(
+            client.approve(&proposal).await,
+            Err(WalletConnectError::UnsupportedNamespaces(chain)) if chain == "eip155:137"
+        ));
+        client.reject(&proposal).await.unwrap();
+        let Payload::Response(response) = dapp.receive(&dapp.pairing.sym_key).await else {
+            panic!("expected the rejection");
+        };
+        assert_eq!(response.error.unwrap().code, 5000);
+    }
+}
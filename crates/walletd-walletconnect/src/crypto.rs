@@ -0,0 +1,190 @@
+//! Keys, topics and envelopes
+//!
+//! Every topic has a 32-byte symmetric key and the topic itself is the
+//! SHA-256 of that key. Session keys come from an X25519 exchange between
+//! the proposer and the responder, expanded with HKDF-SHA256. Messages are
+//! sealed as type 0 envelopes: `0x00 || iv (12) || ChaCha20-Poly1305
+//! ciphertext`, base64 encoded.
+
+use crate::{Result, WalletConnectError};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+use hkdf::Hkdf;
+use rand::RngCore;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use sha2::{Digest, Sha256};
+use std::fmt;
+use x25519_dalek::{PublicKey, StaticSecret};
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+/// Envelope type for messages sealed with the topic key
+const TYPE_0: u8 = 0;
+/// ChaCha20-Poly1305 nonce length
+const IV_LENGTH: usize = 12;
+
+/// Symmetric key of a pairing or session topic
+#[derive(Clone, PartialEq, Eq, Zeroize, ZeroizeOnDrop)]
+pub struct SymKey([u8; 32]);
+
+impl SymKey {
+    /// A fresh random key
+    pub fn generate() -> Self {
+        let mut key = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut key);
+        Self(key)
+    }
+
+    /// Wraps raw key bytes
+    pub fn from_bytes(bytes: [u8; 32]) -> Self {
+        Self(bytes)
+    }
+
+    /// Parses a 64-digit hex key
+    pub fn from_hex(s: &str) -> Result<Self> {
+        let bytes = hex::decode(s)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| WalletConnectError::InvalidEnvelope("key must be 32 bytes".into()))?;
+        Ok(Self(bytes))
+    }
+
+    /// Hex encoding of the key
+    pub fn to_hex(&self) -> String {
+        hex::encode(self.0)
+    }
+
+    /// Raw key bytes
+    pub fn as_bytes(&self) -> &[u8; 32] {
+        &self.0
+    }
+
+    /// The topic this key encrypts: hex SHA-256 of the key
+    pub fn topic(&self) -> String {
+        hex::encode(Sha256::digest(self.0))
+    }
+
+    /// Seals `plaintext` as a base64 type 0 envelope
+    pub fn seal(&self, plaintext: &[u8]) -> Result<String> {
+        let mut iv = [0u8; IV_LENGTH];
+        rand::thread_rng().fill_bytes(&mut iv);
+        let sealed = ChaCha20Poly1305::new((&self.0).into())
+            .encrypt(&Nonce::from(iv), plaintext)
+            .map_err(|_| WalletConnectError::InvalidEnvelope("encryption failed".into()))?;
+        let mut envelope = Vec::with_capacity(1 + IV_LENGTH + sealed.len());
+        envelope.push(TYPE_0);
+        envelope.extend_from_slice(&iv);
+        envelope.extend_from_slice(&sealed);
+        Ok(base64::engine::general_purpose::STANDARD.encode(envelope))
+    }
+
+    /// Opens a base64 type 0 envelope
+    pub fn open(&self, message: &str) -> Result<Vec<u8>> {
+        let invalid = |reason: &str| WalletConnectError::InvalidEnvelope(reason.to_string());
+        let envelope = base64::engine::general_purpose::STANDARD
+            .decode(message)
+            .map_err(|_| invalid("not base64"))?;
+        match envelope.first() {
+            Some(&TYPE_0) => {}
+            Some(other) => return Err(invalid(&format!("unsupported envelope type {}", other))),
+            None => return Err(invalid("empty envelope")),
+        }
+        if envelope.len() < 1 + IV_LENGTH {
+            return Err(invalid("envelope too short"));
+        }
+        let (iv, sealed) = envelope[1..].split_at(IV_LENGTH);
+        let iv: [u8; IV_LENGTH] = iv.try_into().expect("split at the IV length");
+        ChaCha20Poly1305::new((&self.0).into())
+            .decrypt(&Nonce::from(iv), sealed)
+            .map_err(|_| invalid("decryption failed"))
+    }
+}
+
+impl fmt::Debug for SymKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("SymKey(..)")
+    }
+}
+
+impl Serialize for SymKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_hex())
+    }
+}
+
+impl<'de> Deserialize<'de> for SymKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> std::result::Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Self::from_hex(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+/// X25519 key pair used to agree on a session key
+pub struct KeyPair {
+    secret: StaticSecret,
+    public: PublicKey,
+}
+
+impl KeyPair {
+    /// A fresh random key pair
+    pub fn generate() -> Self {
+        let secret = StaticSecret::random_from_rng(rand::thread_rng());
+        let public = PublicKey::from(&secret);
+        Self { secret, public }
+    }
+
+    /// Hex encoding of the public key
+    pub fn public_hex(&self) -> String {
+        hex::encode(self.public.as_bytes())
+    }
+
+    /// Derives the symmetric key shared with the holder of `peer_public`
+    pub fn agree(&self, peer_public: &str) -> Result<SymKey> {
+        let peer: [u8; 32] = hex::decode(peer_public)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or_else(|| WalletConnectError::InvalidEnvelope("bad peer public key".into()))?;
+        let shared = self.secret.diffie_hellman(&PublicKey::from(peer));
+        let mut key = [0u8; 32];
+        Hkdf::<Sha256>::new(None, shared.as_bytes())
+            .expand(&[], &mut key)
+            .expect("32 bytes is a valid HKDF-SHA256 output length");
+        Ok(SymKey(key))
+    }
+}
+
+impl fmt::Debug for KeyPair {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("KeyPair")
+            .field("public", &self.public_hex())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_seal_open() {
+        let key = SymKey::generate();
+        let message = key.seal(b"{\"id\":1}").unwrap();
+        assert_eq!(key.open(&message).unwrap(), b"{\"id\":1}");
+        assert!(SymKey::generate().open(&message).is_err());
+        assert!(key.open("AQID").is_err());
+    }
+
+    #[test]
+    fn test_agreement() {
+        let wallet = KeyPair::generate();
+        let dapp = KeyPair::generate();
+        let a = wallet.agree(&dapp.public_hex()).unwrap();
+        let b = dapp.agree(&wallet.public_hex()).unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.topic().len(), 64);
+        assert_eq!(
+            SymKey::from_bytes([0; 32]).topic(),
+            "66687aadf862bd776c8fc18b8e9f8e20089714856ee233b3902a591d0d5f2925"
+        );
+    }
+}
@@ -0,0 +1,610 @@
+//! Request handlers
+//!
+//! A [`RequestHandler`] serves one CAIP-2 namespace: it lists the chains,
+//! accounts and methods it can offer in a session, and answers session
+//! requests for them. Keys come from a walletd [`Signer`], so in-memory
+//! keys, Ledger devices and remote signers all work; EVM transactions are
+//! sent through a chain wallet implementing [`Transferable`].
+
+use crate::Result;
+use async_trait::async_trait;
+use base64::Engine;
+use serde_json::{json, Value};
+use sha3::{Digest, Keccak256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Arc;
+use walletd_core::{decode_compact_u16, recovery_id};
+use walletd_traits::{
+    Amount, EvmTxParams, EvmValidator, SignatureScheme, Signer, TransactionBuilder, Transferable,
+    WalletError,
+};
+
+/// Solana mainnet-beta chain id
+pub const SOLANA_MAINNET: &str = "solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp";
+/// Solana devnet chain id
+pub const SOLANA_DEVNET: &str = "solana:EtWTRABZaYq6iMfeYKouRu166VU2xqa1";
+/// Solana testnet chain id
+pub const SOLANA_TESTNET: &str = "solana:4uhcVJyU9pJkvQyS88uRDiswHXSCkY3z";
+
+/// An error returned to the dApp
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RpcError {
+    /// Error code
+    pub code: i64,
+    /// Error message
+    pub message: String,
+}
+
+impl RpcError {
+    /// An error with a custom code
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// The user declined the request
+    pub fn user_rejected() -> Self {
+        Self::new(5000, "User rejected.")
+    }
+
+    /// The chain is not part of the session
+    pub fn unsupported_chain(chain_id: &str) -> Self {
+        Self::new(5100, format!("Unsupported chains: {}", chain_id))
+    }
+
+    /// The method is not offered
+    pub fn unsupported_method(method: &str) -> Self {
+        Self::new(5101, format!("Unsupported methods: {}", method))
+    }
+
+    /// The request names an account the wallet does not control
+    pub fn unsupported_account(account: &str) -> Self {
+        Self::new(5102, format!("Unsupported accounts: {}", account))
+    }
+
+    /// The request parameters are malformed
+    pub fn invalid_params(reason: impl fmt::Display) -> Self {
+        Self::new(-32602, format!("Invalid params: {}", reason))
+    }
+}
+
+impl From<WalletError> for RpcError {
+    fn from(e: WalletError) -> Self {
+        Self::new(-32603, e.to_string())
+    }
+}
+
+impl fmt::Display for RpcError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.message, self.code)
+    }
+}
+
+/// Serves session requests for one namespace
+#[async_trait]
+pub trait RequestHandler: Send + Sync {
+    /// CAIP-2 namespace, e.g. `eip155` or `solana`
+    fn namespace(&self) -> &str;
+
+    /// CAIP-2 chain ids served, e.g. `eip155:1`
+    fn chains(&self) -> Vec<String>;
+
+    /// CAIP-10 account ids, e.g. `eip155:1:0xab16...`
+    fn accounts(&self) -> Vec<String>;
+
+    /// JSON-RPC methods served
+    fn methods(&self) -> Vec<String>;
+
+    /// Events the wallet may emit
+    fn events(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Answers a request on `chain_id`
+    async fn handle(
+        &self,
+        chain_id: &str,
+        method: &str,
+        params: &Value,
+    ) -> std::result::Result<Value, RpcError>;
+}
+
+/// Sends EVM transactions through a chain wallet
+pub type EvmWallet = Arc<dyn Transferable<TxParams = EvmTxParams>>;
+
+/// Handler for `eip155` chains
+///
+/// Message signing uses the secp256k1 signer directly. `eth_sendTransaction`
+/// is offered on chains registered with a wallet via
+/// [`with_wallet`](Self::with_wallet).
+pub struct EvmHandler {
+    signer: Arc<dyn Signer>,
+    address: String,
+    chains: BTreeMap<u64, Option<EvmWallet>>,
+}
+
+impl EvmHandler {
+    /// Creates a handler for the account of a secp256k1 signer
+    pub fn new(signer: Arc<dyn Signer>) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Secp256k1 {
+            return Err(
+                WalletError::KeyError("EVM accounts need a secp256k1 signer".into()).into(),
+            );
+        }
+        let public_key = secp256k1::PublicKey::from_slice(&signer.public_key())
+            .map_err(|e| WalletError::KeyError(e.to_string()))?;
+        let hash = Keccak256::digest(&public_key.serialize_uncompressed()[1..]);
+        let address = EvmValidator::checksum(&hex::encode(&hash[12..]));
+        Ok(Self {
+            signer,
+            address,
+            chains: BTreeMap::new(),
+        })
+    }
+
+    /// Offers message signing on chain `chain_id`
+    pub fn with_chain(mut self, chain_id: u64) -> Self {
+        self.chains.entry(chain_id).or_insert(None);
+        self
+    }
+
+    /// Offers signing and `eth_sendTransaction` on chain `chain_id`
+    pub fn with_wallet(mut self, chain_id: u64, wallet: EvmWallet) -> Self {
+        self.chains.insert(chain_id, Some(wallet));
+        self
+    }
+
+    /// The checksummed account address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    fn check_account(&self, address: Option<&str>) -> std::result::Result<(), RpcError> {
+        match address {
+            Some(address) if address.eq_ignore_ascii_case(&self.address) => Ok(()),
+            Some(address) => Err(RpcError::unsupported_account(address)),
+            None => Err(RpcError::invalid_params("missing address")),
+        }
+    }
+
+    /// Signs `message` with the EIP-191 prefix, returning `r || s || v`
+    async fn sign_personal(&self, message: &[u8]) -> std::result::Result<String, RpcError> {
+        let mut prefixed = format!("\x19Ethereum Signed Message:\n{}", message.len()).into_bytes();
+        prefixed.extend_from_slice(message);
+        let hash: [u8; 32] = Keccak256::digest(&prefixed).into();
+        let signature = self.signer.sign_hash(&hash).await?;
+        let v = recovery_id(&hash, &signature, &self.signer.public_key())
+            .ok_or_else(|| RpcError::from(WalletError::KeyError("bad signature".into())))?;
+        let mut out = signature;
+        out.push(27 + v);
+        Ok(format!("0x{}", hex::encode(out)))
+    }
+
+    async fn send_transaction(
+        &self,
+        wallet: &EvmWallet,
+        params: &Value,
+    ) -> std::result::Result<Value, RpcError> {
+        let tx = &params[0];
+        self.check_account(tx["from"].as_str())?;
+        let mut builder = TransactionBuilder::with_params(EvmTxParams::default());
+        if let Some(to) = tx["to"].as_str() {
+            builder = builder.to(to);
+        }
+        builder = builder.amount(Amount::from_smallest_unit(
+            quantity(tx, "value")?.unwrap_or(0),
+            18,
+        ));
+        if let Some(data) = tx["data"].as_str().or(tx["input"].as_str()) {
+            let data = decode_hex(data).map_err(|_| RpcError::invalid_params("data"))?;
+            if !data.is_empty() {
+                builder = builder.data(data);
+            }
+        }
+        if let Some(gas) = quantity(tx, "gas")?.or(quantity(tx, "gasLimit")?) {
+            builder = builder.gas_limit(gas as u64);
+        }
+        let wei = |value: u128| Amount::from_smallest_unit(value, 18);
+        if let Some(price) = quantity(tx, "gasPrice")? {
+            builder = builder.gas_price(wei(price));
+        }
+        if let (Some(max_fee), Some(priority)) = (
+            quantity(tx, "maxFeePerGas")?,
+            quantity(tx, "maxPriorityFeePerGas")?,
+        ) {
+            builder = builder.eip1559_fees(wei(max_fee), wei(priority));
+        }
+        if let Some(nonce) = quantity(tx, "nonce")? {
+            builder = builder.nonce(nonce as u64);
+        }
+        let hash = wallet.transfer_with(builder).await?;
+        Ok(json!(hash.as_str()))
+    }
+}
+
+impl fmt::Debug for EvmHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EvmHandler")
+            .field("address", &self.address)
+            .field("chains", &self.chains.keys().collect::<Vec<_>>())
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl RequestHandler for EvmHandler {
+    fn namespace(&self) -> &str {
+        "eip155"
+    }
+
+    fn chains(&self) -> Vec<String> {
+        self.chains
+            .keys()
+            .map(|id| format!("eip155:{}", id))
+            .collect()
+    }
+
+    fn accounts(&self) -> Vec<String> {
+        self.chains
+            .keys()
+            .map(|id| format!("eip155:{}:{}", id, self.address))
+            .collect()
+    }
+
+    fn methods(&self) -> Vec<String> {
+        let mut methods = vec!["personal_sign".to_string(), "eth_sign".to_string()];
+        if self.chains.values().any(Option::is_some) {
+            methods.push("eth_sendTransaction".to_string());
+        }
+        methods
+    }
+
+    fn events(&self) -> Vec<String> {
+        vec!["chainChanged".to_string(), "accountsChanged".to_string()]
+    }
+
+    async fn handle(
+        &self,
+        chain_id: &str,
+        method: &str,
+        params: &Value,
+    ) -> std::result::Result<Value, RpcError> {
+        let wallet = chain_id
+            .strip_prefix("eip155:")
+            .and_then(|id| id.parse::<u64>().ok())
+            .and_then(|id| self.chains.get(&id))
+            .ok_or_else(|| RpcError::unsupported_chain(chain_id))?;
+        match method {
+            // personal_sign: [message, address]; eth_sign: [address, message]
+            "personal_sign" | "eth_sign" => {
+                let (message, address) = if method == "personal_sign" {
+                    (&params[0], &params[1])
+                } else {
+                    (&params[1], &params[0])
+                };
+                self.check_account(address.as_str())?;
+                let message = message
+                    .as_str()
+                    .ok_or_else(|| RpcError::invalid_params("missing message"))?;
+                // dApps send hex, but some send plain text
+                let bytes = match message.strip_prefix("0x").map(hex::decode) {
+                    Some(Ok(bytes)) => bytes,
+                    _ => message.as_bytes().to_vec(),
+                };
+                Ok(json!(self.sign_personal(&bytes).await?))
+            }
+            "eth_sendTransaction" => match wallet {
+                Some(wallet) => self.send_transaction(wallet, params).await,
+                None => Err(RpcError::unsupported_method(method)),
+            },
+            _ => Err(RpcError::unsupported_method(method)),
+        }
+    }
+}
+
+/// Reads an optional hex quantity field such as `"0x5208"`
+fn quantity(tx: &Value, field: &str) -> std::result::Result<Option<u128>, RpcError> {
+    match tx[field].as_str() {
+        None => Ok(None),
+        Some(value) => {
+            let digits = value.strip_prefix("0x").unwrap_or(value);
+            if digits.is_empty() {
+                return Ok(Some(0));
+            }
+            u128::from_str_radix(digits, 16)
+                .map(Some)
+                .map_err(|_| RpcError::invalid_params(field))
+        }
+    }
+}
+
+fn decode_hex(s: &str) -> std::result::Result<Vec<u8>, hex::FromHexError> {
+    hex::decode(s.strip_prefix("0x").unwrap_or(s))
+}
+
+/// Handler for `solana` chains
+///
+/// Signs transactions and messages with an Ed25519 signer. Transactions
+/// are returned signed, not sent; the dApp submits them.
+pub struct SolanaHandler {
+    signer: Arc<dyn Signer>,
+    address: String,
+    chains: BTreeSet<String>,
+}
+
+impl SolanaHandler {
+    /// Creates a handler for the account of an Ed25519 signer
+    pub fn new(signer: Arc<dyn Signer>) -> Result<Self> {
+        if signer.scheme() != SignatureScheme::Ed25519 {
+            return Err(
+                WalletError::KeyError("Solana accounts need an Ed25519 signer".into()).into(),
+            );
+        }
+        let address = bs58::encode(signer.public_key()).into_string();
+        Ok(Self {
+            signer,
+            address,
+            chains: BTreeSet::new(),
+        })
+    }
+
+    /// Offers signing on `chain_id`, e.g. [`SOLANA_MAINNET`]
+    pub fn with_chain(mut self, chain_id: &str) -> Self {
+        let chain_id = if chain_id.starts_with("solana:") {
+            chain_id.to_string()
+        } else {
+            format!("solana:{}", chain_id)
+        };
+        self.chains.insert(chain_id);
+        self
+    }
+
+    /// The base58 account address
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Adds this account's signature to a serialized transaction
+    async fn sign_transaction(
+        &self,
+        encoded: Option<&str>,
+    ) -> std::result::Result<(String, String), RpcError> {
+        let base64 = base64::engine::general_purpose::STANDARD;
+        let mut tx = encoded
+            .and_then(|encoded| base64.decode(encoded).ok())
+            .ok_or_else(|| RpcError::invalid_params("transaction must be base64"))?;
+        let malformed = || RpcError::invalid_params("malformed transaction");
+
+        let (signatures, prefix) = decode_compact_u16(&tx).ok_or_else(malformed)?;
+        let message_start = prefix + signatures * 64;
+        let message = tx.get(message_start..).ok_or_else(malformed)?;
+        // Versioned messages start with 0x80 | version
+        let header = usize::from(message.first().ok_or_else(malformed)? & 0x80 != 0);
+        let required = *message.get(header).ok_or_else(malformed)? as usize;
+        let keys_at = header + 3;
+        let (key_count, key_prefix) =
+            decode_compact_u16(message.get(keys_at..).ok_or_else(malformed)?)
+                .ok_or_else(malformed)?;
+        if signatures != required || key_count < required {
+            return Err(malformed());
+        }
+        let public_key = self.signer.public_key();
+        let keys = message
+            .get(keys_at + key_prefix..keys_at + key_prefix + required * 32)
+            .ok_or_else(malformed)?;
+        let index = keys
+            .chunks(32)
+            .position(|key| key == public_key.as_slice())
+            .ok_or_else(|| RpcError::unsupported_account(&self.address))?;
+
+        let signature = self.signer.sign_message(message).await?;
+        let slot = prefix + index * 64;
+        tx[slot..slot + 64].copy_from_slice(&signature);
+        Ok((bs58::encode(&signature).into_string(), base64.encode(&tx)))
+    }
+}
+
+impl fmt::Debug for SolanaHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SolanaHandler")
+            .field("address", &self.address)
+            .field("chains", &self.chains)
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl RequestHandler for SolanaHandler {
+    fn namespace(&self) -> &str {
+        "solana"
+    }
+
+    fn chains(&self) -> Vec<String> {
+        self.chains.iter().cloned().collect()
+    }
+
+    fn accounts(&self) -> Vec<String> {
+        self.chains
+            .iter()
+            .map(|chain| format!("{}:{}", chain, self.address))
+            .collect()
+    }
+
+    fn methods(&self) -> Vec<String> {
+        [
+            "solana_signTransaction",
+            "solana_signAllTransactions",
+            "solana_signMessage",
+        ]
+        .map(str::to_string)
+        .to_vec()
+    }
+
+    async fn handle(
+        &self,
+        chain_id: &str,
+        method: &str,
+        params: &Value,
+    ) -> std::result::Result<Value, RpcError> {
+        if !self.chains.contains(chain_id) {
+            return Err(RpcError::unsupported_chain(chain_id));
+        }
+        match method {
+            "solana_signTransaction" => {
+                let (signature, transaction) = self
+                    .sign_transaction(params["transaction"].as_str())
+                    .await?;
+                Ok(json!({ "signature": signature, "transaction": transaction }))
+            }
+            "solana_signAllTransactions" => {
+                let encoded = params["transactions"]
+                    .as_array()
+                    .ok_or_else(|| RpcError::invalid_params("missing transactions"))?;
+                let mut signed = Vec::with_capacity(encoded.len());
+                for tx in encoded {
+                    signed.push(self.sign_transaction(tx.as_str()).await?.1);
+                }
+                Ok(json!({ "transactions": signed }))
+            }
+            "solana_signMessage" => {
+                if let Some(pubkey) = params["pubkey"].as_str() {
+                    if pubkey != self.address {
+                        return Err(RpcError::unsupported_account(pubkey));
+                    }
+                }
+                let message = params["message"]
+                    .as_str()
+                    .and_then(|m| bs58::decode(m).into_vec().ok())
+                    .ok_or_else(|| RpcError::invalid_params("message must be base58"))?;
+                let signature = self.signer.sign_message(&message).await?;
+                Ok(json!({ "signature": bs58::encode(signature).into_string() }))
+            }
+            _ => Err(RpcError::unsupported_method(method)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use walletd_traits::{Ed25519Signer, Secp256k1Signer};
+
+    fn evm() -> EvmHandler {
+        // Hardhat account #0
+        let key = hex::decode("ac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80")
+            .unwrap();
+        EvmHandler::new(Arc::new(Secp256k1Signer::from_slice(&key).unwrap()))
+            .unwrap()
+            .with_chain(1)
+    }
+
+    #[tokio::test]
+    async fn test_personal_sign() {
+        let handler = evm();
+        assert_eq!(
+            handler.address(),
+            "0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"
+        );
+        assert_eq!(
+            handler.accounts(),
+            ["eip155:1:0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266"]
+        );
+
+        let params = json!(["0x68656c6c6f", handler.address().to_lowercase()]);
+        let signature = handler
+            .handle("eip155:1", "personal_sign", &params)
+            .await
+            .unwrap();
+        // Recovers to the account from hashMessage("hello")
+        let signature = decode_hex(signature.as_str().unwrap()).unwrap();
+        assert_eq!(signature.len(), 65);
+        let hash: [u8; 32] =
+            decode_hex("0x50b2c43fd39106bafbba0da34fc430e1f91e3c96ea2acee2bc34119f92b37750")
+                .unwrap()
+                .try_into()
+                .unwrap();
+        let recovered = secp256k1::ecdsa::RecoverableSignature::from_compact(
+            &signature[..64],
+            secp256k1::ecdsa::RecoveryId::from_i32(signature[64] as i32 - 27).unwrap(),
+        )
+        .unwrap()
+        .recover(&secp256k1::Message::from_slice(&hash).unwrap())
+        .unwrap();
+        let address = Keccak256::digest(&recovered.serialize_uncompressed()[1..]);
+        assert_eq!(
+            EvmValidator::checksum(&hex::encode(&address[12..])),
+            handler.address()
+        );
+
+        let wrong_account = json!(["0x00", "0x0000000000000000000000000000000000000001"]);
+        let err = handler
+            .handle("eip155:1", "personal_sign", &wrong_account)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 5102);
+        let err = handler
+            .handle("eip155:5", "personal_sign", &params)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 5100);
+        let err = handler
+            .handle("eip155:1", "eth_sendTransaction", &json!([{}]))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 5101);
+    }
+
+    #[tokio::test]
+    async fn test_solana_sign_transaction() {
+        let signer = Arc::new(Ed25519Signer::from_bytes(&[1; 32]));
+        let public_key = signer.public_key();
+        let handler = SolanaHandler::new(signer.clone())
+            .unwrap()
+            .with_chain(SOLANA_DEVNET);
+
+        // Legacy message: 1 required signature, fee payer + system program
+        let mut message = vec![1, 0, 1, 2];
+        message.extend_from_slice(&public_key);
+        message.extend_from_slice(&[0; 32]);
+        message.extend_from_slice(&[9; 32]); // blockhash
+        message.push(0); // no instructions
+        let mut tx = vec![1];
+        tx.extend_from_slice(&[0; 64]);
+        tx.extend_from_slice(&message);
+        let encoded = base64::engine::general_purpose::STANDARD.encode(&tx);
+
+        let result = handler
+            .handle(
+                SOLANA_DEVNET,
+                "solana_signTransaction",
+                &json!({ "transaction": encoded }),
+            )
+            .await
+            .unwrap();
+        let expected = signer.sign_message(&message).await.unwrap();
+        assert_eq!(result["signature"], bs58::encode(&expected).into_string());
+        let signed = base64::engine::general_purpose::STANDARD
+            .decode(result["transaction"].as_str().unwrap())
+            .unwrap();
+        assert_eq!(&signed[1..65], expected.as_slice());
+        assert_eq!(&signed[65..], message.as_slice());
+
+        let err = handler
+            .handle(SOLANA_MAINNET, "solana_signTransaction", &json!({}))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, 5100);
+        let err = handler
+            .handle(
+                SOLANA_DEVNET,
+                "solana_signTransaction",
+                &json!({ "transaction": "AQ==" }),
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code, -32602);
+    }
+}
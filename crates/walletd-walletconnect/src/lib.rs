@@ -0,0 +1,148 @@
+//! # WalletD WalletConnect
+//!
+//! The wallet side of WalletConnect v2: pair with a dApp from a `wc:` URI,
+//! approve or reject its session proposal, and answer its signing requests
+//! with walletd keys and wallets.
+//!
+//! - [`PairingUri`] parses the URI shown by the dApp as a QR code
+//! - [`SignClient`] drives pairing, sessions and request routing over a
+//!   [`Relay`] ([`WsRelay`] for the public relay)
+//! - [`RequestHandler`]s answer requests per namespace; [`EvmHandler`]
+//!   serves `eth_sendTransaction`, `personal_sign` and `eth_sign`, and
+//!   [`SolanaHandler`] serves `solana_signTransaction` and
+//!   `solana_signMessage`
+//!
+//! Relay messages are end-to-end encrypted with ChaCha20-Poly1305; the relay
+//! only sees topics.
+//!
+//! ## Example
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use walletd_walletconnect::{Event, EvmHandler, Metadata, SignClient, WsRelay};
+//! # async fn run(signer: Arc<dyn walletd_traits::Signer>, uri: &str) -> walletd_walletconnect::Result<()> {
+//! let relay = WsRelay::connect("YOUR_PROJECT_ID").await?;
+//! let client = SignClient::new(relay, Metadata::new("My Wallet", "https://wallet.example"))
+//!     .with_handler(EvmHandler::new(signer)?.with_chain(1));
+//!
+//! client.pair(&uri.parse()?).await?;
+//! while let Some(event) = client.next_event().await {
+//!     match event? {
+//!         Event::SessionProposal(proposal) => {
+//!             client.approve(&proposal).await?;
+//!         }
+//!         Event::SessionRequest(request) => {
+//!             // Ask the user first; `reject_request` declines
+//!             if let Err(e) = client.respond(&request).await? {
+//!                 eprintln!("{} failed: {}", request.method, e);
+//!             }
+//!         }
+//!         Event::SessionDeleted { .. } => {}
+//!     }
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub mod client;
+pub mod crypto;
+pub mod handler;
+pub mod relay;
+pub mod rpc;
+pub mod uri;
+
+pub use client::{Event, Proposal, Session, SessionRequest, SignClient};
+pub use handler::{EvmHandler, RequestHandler, RpcError, SolanaHandler};
+pub use relay::{Relay, RelayMessage, WsRelay};
+pub use rpc::{Metadata, Namespace, SessionNamespace};
+pub use uri::PairingUri;
+
+use thiserror::Error;
+use walletd_error::WalletdError;
+use walletd_traits::WalletError;
+
+/// WalletConnect errors
+#[derive(Error, Debug)]
+pub enum WalletConnectError {
+    /// The pairing URI is malformed
+    #[error("Invalid pairing URI: {0}")]
+    InvalidUri(String),
+
+    /// A relay message could not be decrypted or decoded
+    #[error("Invalid envelope: {0}")]
+    InvalidEnvelope(String),
+
+    /// The relay rejected a request or the connection failed
+    #[error("Relay error: {0}")]
+    Relay(String),
+
+    /// The relay connection is closed
+    #[error("Relay connection closed")]
+    Closed,
+
+    /// No pairing or session with this topic
+    #[error("Unknown topic: {0}")]
+    UnknownTopic(String),
+
+    /// The pairing or proposal has expired
+    #[error("Expired: {0}")]
+    Expired(String),
+
+    /// The proposal requires chains or methods no handler serves
+    #[error("Unsupported namespaces: {0}")]
+    UnsupportedNamespaces(String),
+
+    /// The peer answered with a JSON-RPC error
+    #[error("Peer error {code}: {message}")]
+    Rpc {
+        /// Error code
+        code: i64,
+        /// Error message
+        message: String,
+    },
+
+    /// A wallet or signer failed
+    #[error("Wallet error: {0}")]
+    Wallet(#[from] WalletError),
+
+    /// JSON serialization error
+    #[error("JSON error: {0}")]
+    Json(#[from] serde_json::Error),
+}
+
+/// Result type for WalletConnect operations
+pub type Result<T> = std::result::Result<T, WalletConnectError>;
+
+impl From<WalletConnectError> for WalletdError {
+    fn from(e: WalletConnectError) -> Self {
+        match e {
+            WalletConnectError::InvalidUri(reason) => WalletdError::ConfigError(reason),
+            WalletConnectError::InvalidEnvelope(reason) => WalletdError::FormatError(reason),
+            WalletConnectError::Relay(reason) => WalletdError::NetworkError(reason),
+            WalletConnectError::Closed => WalletdError::NetworkError(e.to_string()),
+            WalletConnectError::UnknownTopic(_) | WalletConnectError::Expired(_) => {
+                WalletdError::InvalidState(e.to_string())
+            }
+            WalletConnectError::UnsupportedNamespaces(_) => {
+                WalletdError::NotSupported(e.to_string())
+            }
+            WalletConnectError::Rpc { .. } => WalletdError::External {
+                message: e.to_string(),
+            },
+            WalletConnectError::Wallet(e) => WalletdError::External {
+                message: e.to_string(),
+            },
+            WalletConnectError::Json(e) => WalletdError::JsonError(e.to_string()),
+        }
+    }
+}
+
+pub(crate) fn now() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
@@ -0,0 +1,509 @@
+//! Relay transport
+//!
+//! Wallet and dApp never talk directly; both publish encrypted envelopes to
+//! topics on a relay and subscribe to the topics they expect answers on.
+//! [`WsRelay`] speaks the `irn` JSON-RPC protocol to the public relay,
+//! authenticating with a did:key JWT signed by a per-client Ed25519 key.
+
+use crate::rpc::{Payload, Request, Response};
+use crate::{now, Result, WalletConnectError};
+use async_trait::async_trait;
+use base64::Engine;
+use ed25519_dalek::{Signer as _, SigningKey};
+use futures_util::{SinkExt, StreamExt};
+use rand::RngCore;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+
+/// The public WalletConnect relay
+pub const RELAY_URL: &str = "wss://relay.walletconnect.org";
+
+/// How long a relay request may take
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+/// Keep-alive ping interval
+const PING_INTERVAL: Duration = Duration::from_secs(30);
+/// Lifetime of the relay auth token
+const AUTH_TTL_SECS: u64 = 86_400;
+/// Multicodec prefix of an Ed25519 public key in a did:key
+const ED25519_MULTICODEC: [u8; 2] = [0xed, 0x01];
+
+/// A message received on a subscribed topic
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelayMessage {
+    /// Topic it was published to
+    pub topic: String,
+    /// Base64 envelope
+    pub message: String,
+}
+
+/// Publish/subscribe access to a relay
+#[async_trait]
+pub trait Relay: Send + Sync {
+    /// Starts receiving messages published to `topic`
+    ///
+    /// Messages published before the subscription and still within their
+    /// TTL are delivered too.
+    async fn subscribe(&self, topic: &str) -> Result<()>;
+
+    /// Stops receiving messages for `topic`
+    async fn unsubscribe(&self, topic: &str) -> Result<()>;
+
+    /// Publishes an envelope to `topic`
+    async fn publish(&self, topic: &str, message: &str, tag: u32, ttl: Duration) -> Result<()>;
+
+    /// Waits for the next message on any subscribed topic
+    ///
+    /// Returns `None` once the relay is shut down.
+    async fn next_message(&self) -> Option<RelayMessage>;
+}
+
+type Reply = oneshot::Sender<Result<Value>>;
+
+struct Command {
+    request: Request,
+    reply: Reply,
+}
+
+/// Relay client over a WebSocket
+///
+/// The connection is kept in a background task that reconnects with
+/// backoff and renews subscriptions; requests made while disconnected fail
+/// with [`WalletConnectError::Closed`].
+#[derive(Debug)]
+pub struct WsRelay {
+    commands: mpsc::UnboundedSender<Command>,
+    messages: tokio::sync::Mutex<mpsc::UnboundedReceiver<RelayMessage>>,
+    subscriptions: Arc<Mutex<HashMap<String, String>>>,
+}
+
+impl WsRelay {
+    /// Connects to the public relay with a WalletConnect Cloud project id
+    pub async fn connect(project_id: &str) -> Result<Self> {
+        let mut seed = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut seed);
+        Self::connect_with(RELAY_URL, project_id, seed).await
+    }
+
+    /// Connects to `url` authenticating with a fixed client key
+    ///
+    /// Reusing the seed across restarts keeps the relay client id stable.
+    pub async fn connect_with(url: &str, project_id: &str, client_seed: [u8; 32]) -> Result<Self> {
+        let connection = Connection {
+            url: url.trim_end_matches('/').to_string(),
+            project_id: project_id.to_string(),
+            key: SigningKey::from_bytes(&client_seed),
+        };
+        let socket = connection.open().await?;
+
+        let (commands, command_rx) = mpsc::unbounded_channel();
+        let (message_tx, messages) = mpsc::unbounded_channel();
+        let subscriptions = Arc::new(Mutex::new(HashMap::new()));
+        tokio::spawn(connection.run(socket, command_rx, message_tx, Arc::clone(&subscriptions)));
+        Ok(Self {
+            commands,
+            messages: tokio::sync::Mutex::new(messages),
+            subscriptions,
+        })
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value> {
+        let (reply, response) = oneshot::channel();
+        self.commands
+            .send(Command {
+                request: Request::new(method, params),
+                reply,
+            })
+            .map_err(|_| WalletConnectError::Closed)?;
+        match tokio::time::timeout(REQUEST_TIMEOUT, response).await {
+            Ok(Ok(result)) => result,
+            Ok(Err(_)) => Err(WalletConnectError::Closed),
+            Err(_) => Err(WalletConnectError::Relay(format!("{} timed out", method))),
+        }
+    }
+}
+
+#[async_trait]
+impl Relay for WsRelay {
+    async fn subscribe(&self, topic: &str) -> Result<()> {
+        let id = self
+            .call("irn_subscribe", json!({ "topic": topic }))
+            .await?;
+        let id = id.as_str().unwrap_or_default().to_string();
+        self.subscriptions
+            .lock()
+            .expect("subscriptions lock")
+            .insert(topic.to_string(), id);
+        Ok(())
+    }
+
+    async fn unsubscribe(&self, topic: &str) -> Result<()> {
+        let id = self
+            .subscriptions
+            .lock()
+            .expect("subscriptions lock")
+            .remove(topic);
+        if let Some(id) = id {
+            self.call("irn_unsubscribe", json!({ "topic": topic, "id": id }))
+                .await?;
+        }
+        Ok(())
+    }
+
+    async fn publish(&self, topic: &str, message: &str, tag: u32, ttl: Duration) -> Result<()> {
+        self.call(
+            "irn_publish",
+            json!({
+                "topic": topic,
+                "message": message,
+                "ttl": ttl.as_secs(),
+                "tag": tag,
+                "prompt": false,
+            }),
+        )
+        .await?;
+        Ok(())
+    }
+
+    async fn next_message(&self) -> Option<RelayMessage> {
+        self.messages.lock().await.recv().await
+    }
+}
+
+type Socket =
+    tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
+
+/// What a pending relay request is waiting for
+enum Pending {
+    Caller(Reply),
+    Resubscribe(String),
+}
+
+struct Connection {
+    url: String,
+    project_id: String,
+    key: SigningKey,
+}
+
+impl Connection {
+    async fn open(&self) -> Result<Socket> {
+        let url = format!(
+            "{}/?auth={}&projectId={}&ua=wc-2/rust-walletd",
+            self.url,
+            self.auth_token(),
+            self.project_id
+        );
+        let (socket, _) = tokio_tungstenite::connect_async(url)
+            .await
+            .map_err(|e| WalletConnectError::Relay(e.to_string()))?;
+        Ok(socket)
+    }
+
+    /// A did:key JWT proving control of the client key
+    fn auth_token(&self) -> String {
+        let encode = |bytes: &[u8]| base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes);
+        let mut did_key = ED25519_MULTICODEC.to_vec();
+        did_key.extend_from_slice(self.key.verifying_key().as_bytes());
+        let mut subject = [0u8; 32];
+        rand::thread_rng().fill_bytes(&mut subject);
+        let issued_at = now();
+
+        let header = encode(br#"{"alg":"EdDSA","typ":"JWT"}"#);
+        let claims = encode(
+            json!({
+                "iss": format!("did:key:z{}", bs58::encode(did_key).into_string()),
+                "sub": hex::encode(subject),
+                "aud": self.url,
+                "iat": issued_at,
+                "exp": issued_at + AUTH_TTL_SECS,
+            })
+            .to_string()
+            .as_bytes(),
+        );
+        let signing_input = format!("{}.{}", header, claims);
+        let signature = self.key.sign(signing_input.as_bytes());
+        format!("{}.{}", signing_input, encode(&signature.to_bytes()))
+    }
+
+    async fn run(
+        self,
+        mut socket: Socket,
+        mut commands: mpsc::UnboundedReceiver<Command>,
+        messages: mpsc::UnboundedSender<RelayMessage>,
+        subscriptions: Arc<Mutex<HashMap<String, String>>>,
+    ) {
+        let mut delay = Duration::from_millis(500);
+        loop {
+            let mut pending = HashMap::new();
+            let topics: Vec<String> = subscriptions
+                .lock()
+                .expect("subscriptions lock")
+                .keys()
+                .cloned()
+                .collect();
+            for topic in topics {
+                let request = Request::new("irn_subscribe", json!({ "topic": topic }));
+                pending.insert(request.id, Pending::Resubscribe(topic));
+                if send(&mut socket, &Payload::Request(request)).await.is_err() {
+                    break;
+                }
+            }
+
+            let stopped = Self::serve(
+                &mut socket,
+                &mut commands,
+                &messages,
+                &subscriptions,
+                &mut pending,
+            )
+            .await;
+            for (_, waiting) in pending.drain() {
+                if let Pending::Caller(reply) = waiting {
+                    let _ = reply.send(Err(WalletConnectError::Closed));
+                }
+            }
+            if stopped {
+                let _ = socket.close(None).await;
+                return;
+            }
+
+            socket = loop {
+                tracing::warn!("Relay connection lost, reconnecting in {:?}", delay);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(Duration::from_secs(30));
+                if commands.is_closed() {
+                    return;
+                }
+                match self.open().await {
+                    Ok(socket) => break socket,
+                    Err(e) => tracing::warn!("Relay reconnect failed: {}", e),
+                }
+            };
+            delay = Duration::from_millis(500);
+        }
+    }
+
+    /// Serves one connection; returns `true` when the client was dropped
+    async fn serve(
+        socket: &mut Socket,
+        commands: &mut mpsc::UnboundedReceiver<Command>,
+        messages: &mpsc::UnboundedSender<RelayMessage>,
+        subscriptions: &Mutex<HashMap<String, String>>,
+        pending: &mut HashMap<u64, Pending>,
+    ) -> bool {
+        let mut ping = tokio::time::interval(PING_INTERVAL);
+        ping.tick().await;
+        loop {
+            tokio::select! {
+                command = commands.recv() => {
+                    let Some(Command { request, reply }) = command else {
+                        return true;
+                    };
+                    let id = request.id;
+                    if let Err(e) = send(socket, &Payload::Request(request)).await {
+                        let _ = reply.send(Err(e));
+                        return false;
+                    }
+                    pending.insert(id, Pending::Caller(reply));
+                }
+                frame = socket.next() => {
+                    let text = match frame {
+                        Some(Ok(Message::Text(text))) => text,
+                        Some(Ok(Message::Close(_))) | Some(Err(_)) | None => return false,
+                        Some(Ok(_)) => continue,
+                    };
+                    match serde_json::from_str::<Payload>(&text) {
+                        Ok(Payload::Response(response)) => {
+                            resolve(response, pending, subscriptions)
+                        }
+                        Ok(Payload::Request(request)) => {
+                            if request.method.ends_with("_subscription") {
+                                let data = &request.params["data"];
+                                if let (Some(topic), Some(message)) =
+                                    (data["topic"].as_str(), data["message"].as_str())
+                                {
+                                    let _ = messages.send(RelayMessage {
+                                        topic: topic.to_string(),
+                                        message: message.to_string(),
+                                    });
+                                }
+                            }
+                            let ack = Payload::Response(Response::result(request.id, json!(true)));
+                            if send(socket, &ack).await.is_err() {
+                                return false;
+                            }
+                        }
+                        Err(e) => tracing::debug!("Ignoring relay frame: {}", e),
+                    }
+                }
+                _ = ping.tick() => {
+                    if socket.send(Message::Ping(Vec::new())).await.is_err() {
+                        return false;
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn resolve(
+    response: Response,
+    pending: &mut HashMap<u64, Pending>,
+    subscriptions: &Mutex<HashMap<String, String>>,
+) {
+    let result = match (response.result, response.error) {
+        (_, Some(error)) => Err(WalletConnectError::Relay(format!(
+            "{} ({})",
+            error.message, error.code
+        ))),
+        (result, None) => Ok(result.unwrap_or(Value::Null)),
+    };
+    match pending.remove(&response.id) {
+        Some(Pending::Caller(reply)) => {
+            let _ = reply.send(result);
+        }
+        Some(Pending::Resubscribe(topic)) => match result {
+            Ok(id) => {
+                let mut subscriptions = subscriptions.lock().expect("subscriptions lock");
+                if let Some(entry) = subscriptions.get_mut(&topic) {
+                    *entry = id.as_str().unwrap_or_default().to_string();
+                }
+            }
+            Err(e) => tracing::warn!("Resubscribing to {} failed: {}", topic, e),
+        },
+        None => {}
+    }
+}
+
+async fn send(socket: &mut Socket, payload: &Payload) -> Result<()> {
+    let text = serde_json::to_string(payload)?;
+    socket
+        .send(Message::Text(text))
+        .await
+        .map_err(|e| WalletConnectError::Relay(e.to_string()))
+}
+
+/// In-process relay connecting test clients
+#[cfg(test)]
+pub(crate) mod memory {
+    use super::*;
+    use std::collections::HashSet;
+
+    #[derive(Default)]
+    struct Hub {
+        clients: Vec<(usize, HashSet<String>, mpsc::UnboundedSender<RelayMessage>)>,
+        stored: Vec<(usize, RelayMessage)>,
+    }
+
+    /// One client of a shared in-memory relay
+    pub(crate) struct MemoryRelay {
+        id: usize,
+        hub: Arc<Mutex<Hub>>,
+        messages: tokio::sync::Mutex<mpsc::UnboundedReceiver<RelayMessage>>,
+    }
+
+    impl MemoryRelay {
+        /// Two clients of a fresh relay
+        pub(crate) fn pair() -> (Self, Self) {
+            let hub = Arc::new(Mutex::new(Hub::default()));
+            (Self::join(&hub), Self::join(&hub))
+        }
+
+        fn join(hub: &Arc<Mutex<Hub>>) -> Self {
+            let (tx, rx) = mpsc::unbounded_channel();
+            let mut state = hub.lock().unwrap();
+            let id = state.clients.len();
+            state.clients.push((id, HashSet::new(), tx));
+            Self {
+                id,
+                hub: Arc::clone(hub),
+                messages: tokio::sync::Mutex::new(rx),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Relay for MemoryRelay {
+        async fn subscribe(&self, topic: &str) -> Result<()> {
+            let mut hub = self.hub.lock().unwrap();
+            let Hub { clients, stored } = &mut *hub;
+            let (_, topics, tx) = &mut clients[self.id];
+            if topics.insert(topic.to_string()) {
+                for (_, message) in stored
+                    .iter()
+                    .filter(|(from, m)| *from != self.id && m.topic == topic)
+                {
+                    let _ = tx.send(message.clone());
+                }
+            }
+            Ok(())
+        }
+
+        async fn unsubscribe(&self, topic: &str) -> Result<()> {
+            self.hub.lock().unwrap().clients[self.id].1.remove(topic);
+            Ok(())
+        }
+
+        async fn publish(&self, topic: &str, message: &str, _: u32, _: Duration) -> Result<()> {
+            let message = RelayMessage {
+                topic: topic.to_string(),
+                message: message.to_string(),
+            };
+            let mut hub = self.hub.lock().unwrap();
+            for (id, topics, tx) in &hub.clients {
+                if *id != self.id && topics.contains(topic) {
+                    let _ = tx.send(message.clone());
+                }
+            }
+            hub.stored.push((self.id, message));
+            Ok(())
+        }
+
+        async fn next_message(&self) -> Option<RelayMessage> {
+            self.messages.lock().await.recv().await
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signature, Verifier};
+
+    #[test]
+    fn test_auth_token() {
+        let connection = Connection {
+            url: RELAY_URL.to_string(),
+            project_id: "test".to_string(),
+            key: SigningKey::from_bytes(&[7; 32]),
+        };
+        let token = connection.auth_token();
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let decode = |s: &str| {
+            base64::engine::general_purpose::URL_SAFE_NO_PAD
+                .decode(s)
+                .unwrap()
+        };
+        let claims: Value = serde_json::from_slice(&decode(parts[1])).unwrap();
+        assert_eq!(claims["aud"], RELAY_URL);
+        let did = claims["iss"].as_str().unwrap();
+        let key = bs58::decode(did.strip_prefix("did:key:z").unwrap())
+            .into_vec()
+            .unwrap();
+        assert_eq!(key[..2], ED25519_MULTICODEC);
+        assert_eq!(&key[2..], connection.key.verifying_key().as_bytes());
+
+        let signature = Signature::from_slice(&decode(parts[2])).unwrap();
+        let signed = format!("{}.{}", parts[0], parts[1]);
+        assert!(connection
+            .key
+            .verifying_key()
+            .verify(signed.as_bytes(), &signature)
+            .is_ok());
+    }
+}
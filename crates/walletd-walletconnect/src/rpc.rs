@@ -0,0 +1,329 @@
+//! Sign protocol messages
+//!
+//! JSON-RPC payloads exchanged between the wallet and the dApp inside
+//! encrypted envelopes, and the relay tag and TTL of each method.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+/// Relay tag and TTL of one protocol method
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct MethodSpec {
+    pub name: &'static str,
+    pub request_tag: u32,
+    pub response_tag: u32,
+    pub ttl: Duration,
+}
+
+const fn spec(name: &'static str, request_tag: u32, ttl_secs: u64) -> MethodSpec {
+    MethodSpec {
+        name,
+        request_tag,
+        response_tag: request_tag + 1,
+        ttl: Duration::from_secs(ttl_secs),
+    }
+}
+
+pub(crate) const PAIRING_DELETE: MethodSpec = spec("wc_pairingDelete", 1000, 86_400);
+pub(crate) const PAIRING_PING: MethodSpec = spec("wc_pairingPing", 1002, 30);
+pub(crate) const SESSION_PROPOSE: MethodSpec = spec("wc_sessionPropose", 1100, 300);
+pub(crate) const SESSION_SETTLE: MethodSpec = spec("wc_sessionSettle", 1102, 300);
+pub(crate) const SESSION_UPDATE: MethodSpec = spec("wc_sessionUpdate", 1104, 86_400);
+pub(crate) const SESSION_EXTEND: MethodSpec = spec("wc_sessionExtend", 1106, 86_400);
+pub(crate) const SESSION_REQUEST: MethodSpec = spec("wc_sessionRequest", 1108, 300);
+pub(crate) const SESSION_EVENT: MethodSpec = spec("wc_sessionEvent", 1110, 300);
+pub(crate) const SESSION_DELETE: MethodSpec = spec("wc_sessionDelete", 1112, 86_400);
+pub(crate) const SESSION_PING: MethodSpec = spec("wc_sessionPing", 1114, 30);
+
+/// Tag of a rejected session proposal
+pub(crate) const SESSION_PROPOSE_REJECT_TAG: u32 = 1120;
+
+const METHODS: &[MethodSpec] = &[
+    PAIRING_DELETE,
+    PAIRING_PING,
+    SESSION_PROPOSE,
+    SESSION_SETTLE,
+    SESSION_UPDATE,
+    SESSION_EXTEND,
+    SESSION_REQUEST,
+    SESSION_EVENT,
+    SESSION_DELETE,
+    SESSION_PING,
+];
+
+/// Looks up a protocol method by name
+pub(crate) fn method_spec(name: &str) -> Option<MethodSpec> {
+    METHODS.iter().copied().find(|m| m.name == name)
+}
+
+/// A JSON-RPC request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Request {
+    pub id: u64,
+    pub jsonrpc: String,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+impl Request {
+    pub fn new(method: &str, params: Value) -> Self {
+        Self {
+            id: payload_id(),
+            jsonrpc: "2.0".to_string(),
+            method: method.to_string(),
+            params,
+        }
+    }
+}
+
+/// A JSON-RPC error object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct ErrorData {
+    pub code: i64,
+    pub message: String,
+}
+
+/// A JSON-RPC response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct Response {
+    pub id: u64,
+    pub jsonrpc: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<ErrorData>,
+}
+
+impl Response {
+    pub fn result(id: u64, result: Value) -> Self {
+        Self {
+            id,
+            jsonrpc: "2.0".to_string(),
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    pub fn error(id: u64, code: i64, message: &str) -> Self {
+        Self {
+            id,
+            jsonrpc: "2.0".to_string(),
+            result: None,
+            error: Some(ErrorData {
+                code,
+                message: message.to_string(),
+            }),
+        }
+    }
+}
+
+/// Any message inside an envelope
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub(crate) enum Payload {
+    Request(Request),
+    Response(Response),
+}
+
+/// A message id: milliseconds since the epoch followed by three random digits
+pub(crate) fn payload_id() -> u64 {
+    use rand::Rng;
+    let millis = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_millis() as u64)
+        .unwrap_or(0);
+    millis * 1000 + rand::thread_rng().gen_range(0..1000)
+}
+
+/// Describes a wallet or dApp to its peer
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Display name
+    pub name: String,
+    /// Short description
+    #[serde(default)]
+    pub description: String,
+    /// Home page
+    pub url: String,
+    /// Icon URLs
+    #[serde(default)]
+    pub icons: Vec<String>,
+}
+
+impl Metadata {
+    /// Metadata with a name and home page
+    pub fn new(name: &str, url: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            url: url.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Sets the description
+    pub fn with_description(mut self, description: &str) -> Self {
+        self.description = description.to_string();
+        self
+    }
+
+    /// Adds an icon URL
+    pub fn with_icon(mut self, url: &str) -> Self {
+        self.icons.push(url.to_string());
+        self
+    }
+}
+
+/// Chains, methods and events a dApp asks for in one namespace
+///
+/// The namespace key is either a CAIP-2 namespace (`eip155`) with `chains`
+/// listing chain ids, or a single chain id (`eip155:1`) without `chains`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Namespace {
+    /// CAIP-2 chain ids, e.g. `eip155:1`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chains: Option<Vec<String>>,
+    /// JSON-RPC methods
+    #[serde(default)]
+    pub methods: Vec<String>,
+    /// Event names
+    #[serde(default)]
+    pub events: Vec<String>,
+}
+
+/// What the wallet grants in one namespace of a session
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SessionNamespace {
+    /// CAIP-2 chain ids
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub chains: Option<Vec<String>>,
+    /// CAIP-10 account ids, e.g. `eip155:1:0xab16...`
+    pub accounts: Vec<String>,
+    /// JSON-RPC methods the dApp may call
+    pub methods: Vec<String>,
+    /// Events the wallet may emit
+    pub events: Vec<String>,
+}
+
+/// Relay protocol options
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct RelayProtocol {
+    pub protocol: String,
+}
+
+impl Default for RelayProtocol {
+    fn default() -> Self {
+        Self {
+            protocol: "irn".to_string(),
+        }
+    }
+}
+
+/// One side of a proposal or session
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Participant {
+    pub public_key: String,
+    pub metadata: Metadata,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionProposeParams {
+    #[serde(default)]
+    pub relays: Vec<RelayProtocol>,
+    pub proposer: Participant,
+    #[serde(default)]
+    pub required_namespaces: BTreeMap<String, Namespace>,
+    #[serde(default)]
+    pub optional_namespaces: BTreeMap<String, Namespace>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_timestamp: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionProposeResponse {
+    pub relay: RelayProtocol,
+    pub responder_public_key: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionSettleParams {
+    pub relay: RelayProtocol,
+    pub namespaces: BTreeMap<String, SessionNamespace>,
+    #[serde(default)]
+    pub required_namespaces: BTreeMap<String, Namespace>,
+    #[serde(default)]
+    pub optional_namespaces: BTreeMap<String, Namespace>,
+    pub pairing_topic: String,
+    pub controller: Participant,
+    pub expiry: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RequestBody {
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expiry_timestamp: Option<u64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionRequestParams {
+    pub request: RequestBody,
+    pub chain_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct EventBody {
+    pub name: String,
+    pub data: Value,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SessionEventParams {
+    pub event: EventBody,
+    pub chain_id: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionUpdateParams {
+    pub namespaces: BTreeMap<String, SessionNamespace>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub(crate) struct SessionExtendParams {
+    pub expiry: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_payload_kinds() {
+        let request: Payload = serde_json::from_value(json!({
+            "id": 1, "jsonrpc": "2.0", "method": "wc_sessionPing", "params": {}
+        }))
+        .unwrap();
+        assert!(matches!(request, Payload::Request(r) if r.method == "wc_sessionPing"));
+
+        let error: Payload = serde_json::from_value(json!({
+            "id": 2, "jsonrpc": "2.0", "error": {"code": 5000, "message": "User rejected."}
+        }))
+        .unwrap();
+        assert!(matches!(error, Payload::Response(r) if r.error.as_ref().unwrap().code == 5000));
+
+        let spec = method_spec("wc_sessionRequest").unwrap();
+        assert_eq!((spec.request_tag, spec.response_tag), (1108, 1109));
+    }
+}
@@ -0,0 +1,125 @@
+//! Pairing URIs
+//!
+//! A dApp starts a connection by showing a URI such as
+//! `wc:7f6e...@2?relay-protocol=irn&symKey=587d...&expiryTimestamp=1705000000`.
+//! It names the pairing topic and carries the symmetric key for it.
+
+use crate::crypto::SymKey;
+use crate::{Result, WalletConnectError};
+use std::fmt;
+use std::str::FromStr;
+
+/// A parsed `wc:` pairing URI (protocol version 2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PairingUri {
+    /// Pairing topic
+    pub topic: String,
+    /// Symmetric key of the pairing topic
+    pub sym_key: SymKey,
+    /// Relay protocol, normally `irn`
+    pub relay_protocol: String,
+    /// Pairing expiry, Unix seconds
+    pub expiry: Option<u64>,
+}
+
+impl FromStr for PairingUri {
+    type Err = WalletConnectError;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let invalid = |reason: &str| WalletConnectError::InvalidUri(reason.to_string());
+        let rest = s
+            .trim()
+            .strip_prefix("wc:")
+            .ok_or_else(|| invalid("missing wc: scheme"))?;
+        let (topic, rest) = rest
+            .split_once('@')
+            .ok_or_else(|| invalid("missing protocol version"))?;
+        let (version, query) = rest.split_once('?').unwrap_or((rest, ""));
+        if version != "2" {
+            return Err(invalid(&format!("unsupported version {}", version)));
+        }
+        if topic.len() != 64 || !topic.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Err(invalid("topic must be 32 bytes of hex"));
+        }
+
+        let mut sym_key = None;
+        let mut relay_protocol = None;
+        let mut expiry = None;
+        for (key, value) in url::form_urlencoded::parse(query.as_bytes()) {
+            match key.as_ref() {
+                "symKey" => {
+                    sym_key = Some(SymKey::from_hex(&value).map_err(|_| invalid("bad symKey"))?)
+                }
+                "relay-protocol" => relay_protocol = Some(value.into_owned()),
+                "expiryTimestamp" => {
+                    expiry = Some(value.parse().map_err(|_| invalid("bad expiryTimestamp"))?)
+                }
+                _ => {}
+            }
+        }
+        Ok(Self {
+            topic: topic.to_ascii_lowercase(),
+            sym_key: sym_key.ok_or_else(|| invalid("missing symKey"))?,
+            relay_protocol: relay_protocol.unwrap_or_else(|| "irn".to_string()),
+            expiry,
+        })
+    }
+}
+
+impl fmt::Display for PairingUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "wc:{}@2?relay-protocol={}&symKey={}",
+            self.topic,
+            self.relay_protocol,
+            self.sym_key.to_hex()
+        )?;
+        if let Some(expiry) = self.expiry {
+            write!(f, "&expiryTimestamp={}", expiry)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URI: &str = "wc:7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9@2\
+        ?relay-protocol=irn\
+        &symKey=587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303\
+        &methods=%5Bwc_sessionPropose%5D,%5Bwc_authRequest,wc_authBatchRequest%5D\
+        &expiryTimestamp=1705000000";
+
+    #[test]
+    fn test_parse() {
+        let uri: PairingUri = URI.parse().unwrap();
+        assert_eq!(
+            uri.topic,
+            "7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9"
+        );
+        assert_eq!(uri.relay_protocol, "irn");
+        assert_eq!(uri.expiry, Some(1_705_000_000));
+        assert_eq!(
+            uri.sym_key.to_hex(),
+            "587d5484ce2a2a6ee3ba1962fdd7e8588e06200c46823bd18fbd67def96ad303"
+        );
+        assert_eq!(uri.to_string().parse::<PairingUri>().unwrap(), uri);
+    }
+
+    #[test]
+    fn test_invalid() {
+        for bad in [
+            "https://example.com",
+            "wc:abc@2?symKey=00",
+            "wc:7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9@1?symKey=00",
+            "wc:7f6e504bfad60b485450578e05678ed3e8e8c4751d3c6160be17160d63ec90f9@2?relay-protocol=irn",
+        ] {
+            assert!(matches!(
+                bad.parse::<PairingUri>(),
+                Err(WalletConnectError::InvalidUri(_))
+            ));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+//! Aptos wallet bindings
+//!
+//! Aptos signs `sha3_256("APTOS::RawTransaction") || bcs(raw_transaction)`
+//! and accepts BCS-encoded `SignedTransaction`s on
+//! `POST /v1/transactions`.
+
+use crate::types::{to_js, JsSignedTransaction, JsWalletExport, SignedTransaction, WalletExport};
+use sha3::{Digest, Sha3_256};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+/// Prefix for AIP-80 Ed25519 private keys
+const PRIVATE_KEY_PREFIX: &str = "ed25519-priv-";
+
+/// Authentication key scheme for single Ed25519 keys
+const ED25519_SCHEME: u8 = 0x00;
+
+/// Aptos wallet for browser environments
+#[wasm_bindgen]
+pub struct AptosWallet {
+    signing_key: ed25519_dalek::SigningKey,
+    address: [u8; 32],
+    exportable: bool,
+}
+
+#[wasm_bindgen]
+impl AptosWallet {
+    /// Create a new random Aptos wallet
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<AptosWallet, JsError> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        getrandom::getrandom(seed.as_mut()).map_err(|e| JsError::new(&e.to_string()))?;
+
+        Ok(Self::from_seed(&seed))
+    }
+
+    /// Create wallet from mnemonic phrase
+    ///
+    /// # Arguments
+    /// * `mnemonic` - BIP-39 mnemonic phrase
+    /// * `account` - Account index, as in `m/44'/637'/{account}'/0'/0'` (Petra, Pontem)
+    #[wasm_bindgen(js_name = fromMnemonic)]
+    pub fn from_mnemonic(mnemonic: &str, account: u32) -> Result<AptosWallet, JsError> {
+        let seed = crate::mnemonic_seed(mnemonic)?;
+        let key = Zeroizing::new(crate::slip10_ed25519(&*seed, &[44, 637, account, 0, 0]));
+
+        Ok(Self::from_seed(&key))
+    }
+
+    /// Import a private key
+    ///
+    /// Accepts AIP-80 keys (`ed25519-priv-0x...`) and plain 32-byte hex, as
+    /// exported by Petra and the Aptos CLI.
+    #[wasm_bindgen(js_name = fromPrivateKey)]
+    pub fn from_private_key(private_key: &str) -> Result<AptosWallet, JsError> {
+        let seed = Zeroizing::new(decode_private_key(private_key).map_err(|e| JsError::new(&e))?);
+        Ok(Self::from_seed(&seed))
+    }
+
+    pub(crate) fn from_seed(seed: &[u8; 32]) -> AptosWallet {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(seed);
+
+        // A fresh account's address is its authentication key
+        let mut hasher = Sha3_256::new();
+        hasher.update(signing_key.verifying_key().as_bytes());
+        hasher.update([ED25519_SCHEME]);
+
+        AptosWallet {
+            address: hasher.finalize().into(),
+            signing_key,
+            exportable: crate::KEY_EXPORT_DEFAULT,
+        }
+    }
+
+    /// Get the wallet address (`0x` + 64 hex characters)
+    ///
+    /// Accounts that have rotated their key keep their original address; look
+    /// those up on-chain instead.
+    #[wasm_bindgen]
+    pub fn address(&self) -> String {
+        format!("0x{}", hex::encode(self.address))
+    }
+
+    /// Get the public key as hex
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> String {
+        format!("0x{}", hex::encode(self.signing_key.verifying_key().as_bytes()))
+    }
+
+    /// Export the private key in AIP-80 format (`ed25519-priv-0x...`)
+    ///
+    /// Throws if key export has been disabled.
+    #[wasm_bindgen(js_name = exportPrivateKey)]
+    pub fn export_private_key(&self) -> Result<String, JsError> {
+        crate::check_key_export(self.exportable)?;
+        Ok(format!("{}0x{}", PRIVATE_KEY_PREFIX, hex::encode(self.signing_key.as_bytes())))
+    }
+
+    /// Sign an arbitrary message (returns the 64-byte signature)
+    #[wasm_bindgen(js_name = signMessage)]
+    pub fn sign_message(&self, message: &[u8]) -> Vec<u8> {
+        use ed25519_dalek::Signer;
+        self.signing_key.sign(message).to_bytes().to_vec()
+    }
+
+    /// Export the public wallet details (no private key)
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "aptos",
+            address: self.address(),
+            public_key: self.public_key(),
+            network: None,
+        })
+    }
+
+    /// Sign a BCS-encoded `RawTransaction`
+    ///
+    /// `raw` is the hex BCS-encoded `SignedTransaction`; submit its bytes
+    /// with `Content-Type: application/x.aptos.signed_transaction+bcs`.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub fn sign_transaction(&self, raw_transaction: &[u8]) -> Result<JsSignedTransaction, JsError> {
+        to_js(&self.signed_transaction(raw_transaction))
+    }
+
+    fn signed_transaction(&self, raw_transaction: &[u8]) -> SignedTransaction {
+        let signature = self.sign_message(&signing_message(raw_transaction));
+
+        // TransactionAuthenticator::Ed25519 { public_key, signature }
+        let mut signed = raw_transaction.to_vec();
+        signed.push(0x00);
+        signed.extend_from_slice(&crate::uleb128(32));
+        signed.extend_from_slice(self.signing_key.verifying_key().as_bytes());
+        signed.extend_from_slice(&crate::uleb128(64));
+        signed.extend_from_slice(&signature);
+
+        SignedTransaction {
+            chain: "aptos",
+            raw: format!("0x{}", hex::encode(&signed)),
+            hash: Some(format!("0x{}", hex::encode(transaction_hash(&signed)))),
+            signature: format!("0x{}", hex::encode(signature)),
+        }
+    }
+
+    /// Permanently disable reading the private key from JS
+    ///
+    /// Signing keeps working. With the `no-key-export` feature every wallet
+    /// starts out this way.
+    #[wasm_bindgen(js_name = disableKeyExport)]
+    pub fn disable_key_export(&mut self) {
+        self.exportable = false;
+    }
+
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
+}
+
+/// Builds the message signed for a raw transaction
+fn signing_message(raw_transaction: &[u8]) -> Vec<u8> {
+    let mut message = Sha3_256::digest(b"APTOS::RawTransaction").to_vec();
+    message.extend_from_slice(raw_transaction);
+    message
+}
+
+/// Hash of a signed transaction, as returned by the node on submission
+///
+/// Hashes `Transaction::UserTransaction(signed)` (variant 0) under the
+/// `APTOS::Transaction` domain.
+fn transaction_hash(signed_transaction: &[u8]) -> [u8; 32] {
+    let mut hasher = Sha3_256::new();
+    hasher.update(Sha3_256::digest(b"APTOS::Transaction"));
+    hasher.update([0x00]);
+    hasher.update(signed_transaction);
+    hasher.finalize().into()
+}
+
+/// Decodes any of the private key formats accepted by `fromPrivateKey`
+fn decode_private_key(private_key: &str) -> Result<[u8; 32], String> {
+    let private_key = private_key.trim();
+    let private_key = private_key.strip_prefix(PRIVATE_KEY_PREFIX).unwrap_or(private_key);
+
+    hex::decode(private_key.trim_start_matches("0x"))
+        .map_err(|e| format!("Invalid hex: {}", e))?
+        .try_into()
+        .map_err(|_| "Private key must be 32 bytes".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+
+    #[test]
+    fn test_private_key_formats() {
+        let mut wallet = AptosWallet::from_seed(&[9u8; 32]);
+        wallet.exportable = true;
+
+        let exported = wallet.export_private_key().unwrap();
+        assert!(exported.starts_with("ed25519-priv-0x"));
+        assert_eq!(decode_private_key(&exported).unwrap(), [9u8; 32]);
+        assert_eq!(decode_private_key(&hex::encode([9u8; 32])).unwrap(), [9u8; 32]);
+        assert!(decode_private_key("ed25519-priv-0x1234").is_err());
+    }
+
+    #[test]
+    fn test_address_is_auth_key() {
+        let wallet = AptosWallet::from_seed(&[9u8; 32]);
+
+        let mut preimage = wallet.signing_key.verifying_key().to_bytes().to_vec();
+        preimage.push(0x00);
+        assert_eq!(wallet.address, <[u8; 32]>::from(Sha3_256::digest(&preimage)));
+        assert_eq!(wallet.address().len(), 66);
+    }
+
+    #[test]
+    fn test_signed_transaction_layout() {
+        let wallet = AptosWallet::from_seed(&[9u8; 32]);
+        let raw = b"raw transaction".to_vec();
+
+        let result = wallet.signed_transaction(&raw);
+        let signed = hex::decode(&result.raw[2..]).unwrap();
+        assert_eq!(result.hash, Some(format!("0x{}", hex::encode(transaction_hash(&signed)))));
+        assert_eq!(signed.len(), raw.len() + 1 + 33 + 65);
+        assert_eq!(&signed[..raw.len()], raw.as_slice());
+
+        let authenticator = &signed[raw.len()..];
+        assert_eq!(authenticator[0], 0x00);
+        assert_eq!(authenticator[1], 32);
+        assert_eq!(&authenticator[2..34], wallet.signing_key.verifying_key().as_bytes());
+        assert_eq!(authenticator[34], 64);
+
+        let signature = ed25519_dalek::Signature::from_slice(&authenticator[35..]).unwrap();
+        assert!(wallet
+            .signing_key
+            .verifying_key()
+            .verify(&signing_message(&raw), &signature)
+            .is_ok());
+    }
+}
@@ -0,0 +1,148 @@
+//! BIP-85 child mnemonics
+//!
+//! Derives independent wallets (hot wallet, test wallet, one per app) from a
+//! master mnemonic. Each child can be regenerated from the master at any
+//! time; the child reveals nothing about the master.
+
+use bip32::{ChildNumber, XPrv};
+use hmac::{Hmac, Mac};
+use sha2::Sha512;
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+const HARDENED: u32 = 0x8000_0000;
+const PURPOSE: u32 = 83696968;
+const APP_BIP39: u32 = 39;
+const APP_HEX: u32 = 128169;
+const LANGUAGE_ENGLISH: u32 = 0;
+
+/// Derive BIP-85 child mnemonic `index` from a master mnemonic
+///
+/// # Arguments
+/// * `mnemonic` - Master BIP-39 phrase
+/// * `word_count` - Words in the child phrase (12, 15, 18, 21 or 24)
+/// * `index` - Child number; each index is a separate wallet
+/// * `passphrase` - Optional BIP-39 passphrase of the master
+#[wasm_bindgen(js_name = deriveChildMnemonic)]
+pub fn derive_child_mnemonic(
+    mnemonic: &str,
+    word_count: u8,
+    index: u32,
+    passphrase: Option<String>,
+) -> Result<String, JsError> {
+    let root =
+        master(mnemonic, passphrase.as_deref().unwrap_or("")).map_err(|e| JsError::new(&e))?;
+    let child = child_mnemonic(&root, word_count, index).map_err(|e| JsError::new(&e))?;
+    Ok(child.to_string())
+}
+
+/// Derive `num_bytes` (16 to 64) bytes of BIP-85 child entropy as hex
+#[wasm_bindgen(js_name = deriveChildEntropy)]
+pub fn derive_child_entropy(
+    mnemonic: &str,
+    num_bytes: u8,
+    index: u32,
+    passphrase: Option<String>,
+) -> Result<String, JsError> {
+    let root =
+        master(mnemonic, passphrase.as_deref().unwrap_or("")).map_err(|e| JsError::new(&e))?;
+    let child = child_hex(&root, num_bytes, index).map_err(|e| JsError::new(&e))?;
+    Ok(child.to_string())
+}
+
+fn master(mnemonic: &str, passphrase: &str) -> Result<XPrv, String> {
+    let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, mnemonic)
+        .map_err(|e| format!("Invalid mnemonic: {}", e))?;
+    let seed = Zeroizing::new(mnemonic.to_seed(passphrase));
+    XPrv::new(seed.as_slice()).map_err(|e| e.to_string())
+}
+
+pub(crate) fn child_mnemonic(
+    root: &XPrv,
+    word_count: u8,
+    index: u32,
+) -> Result<Zeroizing<String>, String> {
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err("Word count must be 12, 15, 18, 21 or 24".to_string());
+    }
+    let entropy = entropy(
+        root,
+        &[APP_BIP39, LANGUAGE_ENGLISH, word_count as u32, index],
+    )?;
+    let mnemonic = bip39::Mnemonic::from_entropy_in(
+        bip39::Language::English,
+        &entropy[..word_count as usize * 4 / 3],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(Zeroizing::new(mnemonic.to_string()))
+}
+
+pub(crate) fn child_hex(
+    root: &XPrv,
+    num_bytes: u8,
+    index: u32,
+) -> Result<Zeroizing<String>, String> {
+    if !(16..=64).contains(&num_bytes) {
+        return Err("Entropy length must be 16 to 64 bytes".to_string());
+    }
+    let entropy = entropy(root, &[APP_HEX, num_bytes as u32, index])?;
+    Ok(Zeroizing::new(hex::encode(&entropy[..num_bytes as usize])))
+}
+
+/// HMAC-SHA512 of the key at `m/83696968'/{path}'`, every level hardened
+fn entropy(root: &XPrv, path: &[u32]) -> Result<Zeroizing<[u8; 64]>, String> {
+    let mut key = root
+        .derive_child(ChildNumber(PURPOSE | HARDENED))
+        .map_err(|e| e.to_string())?;
+    for &index in path {
+        if index >= HARDENED {
+            return Err(format!("Index {} is out of range", index));
+        }
+        key = key
+            .derive_child(ChildNumber(index | HARDENED))
+            .map_err(|e| e.to_string())?;
+    }
+    let secret = Zeroizing::new(key.private_key().to_bytes());
+    let mut mac =
+        Hmac::<Sha512>::new_from_slice(b"bip-entropy-from-k").map_err(|e| e.to_string())?;
+    mac.update(&secret);
+    Ok(Zeroizing::new(mac.finalize().into_bytes().into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    // Master key of the BIP-85 test vectors
+    const ROOT: &str = "xprv9s21ZrQH143K2LBWUUQRFXhucrQqBpKdRRxNVq2zBqsx8HVqFk2uYo8kmbaLLHRdqtQpUm98uKfu3vca1LqdGhUtyoFnCNkfmXRyPXLjbKb";
+
+    #[test]
+    fn test_bip85_vectors() {
+        let root = XPrv::from_str(ROOT).unwrap();
+        assert_eq!(
+            *child_mnemonic(&root, 12, 0).unwrap(),
+            "girl mad pet galaxy egg matter matrix prison refuse sense ordinary nose"
+        );
+        assert_eq!(
+            *child_hex(&root, 64, 0).unwrap(),
+            "492db4698cf3b73a5a24998aa3e9d7fa96275d85724a91e71aa2d645442f8785\
+             55d078fd1f1f67e368976f04137b1f7a0d19232136ca50c44614af72b5582a5c"
+        );
+        assert!(child_mnemonic(&root, 13, 0).is_err());
+        assert!(child_hex(&root, 65, 0).is_err());
+    }
+
+    #[test]
+    fn test_child_from_phrase() {
+        let root = master(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+            "",
+        )
+        .unwrap();
+        let child = child_mnemonic(&root, 24, 3).unwrap();
+        assert_eq!(child.split(' ').count(), 24);
+        assert!(crate::validate_mnemonic(&child));
+        assert!(master("not a mnemonic", "").is_err());
+    }
+}
@@ -0,0 +1,373 @@
+//! EIP-712 typed data signing
+//!
+//! Hashes `eth_signTypedData_v4` payloads: `keccak256(0x1901 ||
+//! domainSeparator || hashStruct(message))`, with nested structs, arrays and
+//! dynamic types encoded as the EIP specifies.
+
+use crate::evm::{keccak256, sign_digest};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Name of the domain struct
+const DOMAIN_TYPE: &str = "EIP712Domain";
+
+/// Domain fields in canonical order, used when `types` omits `EIP712Domain`
+const DOMAIN_FIELDS: [(&str, &str); 5] = [
+    ("name", "string"),
+    ("version", "string"),
+    ("chainId", "uint256"),
+    ("verifyingContract", "address"),
+    ("salt", "bytes32"),
+];
+
+type Types = BTreeMap<String, Vec<Field>>;
+
+/// A typed data payload as accepted by `eth_signTypedData_v4`
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TypedData {
+    types: Types,
+    primary_type: String,
+    domain: Map<String, Value>,
+    #[serde(default)]
+    message: Value,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct Field {
+    name: String,
+    #[serde(rename = "type")]
+    ty: String,
+}
+
+/// Signs typed data, returning the 65-byte `r || s || v` signature as hex
+pub(crate) fn sign_typed_data(private_key: &[u8; 32], typed_data_json: &str) -> Result<String, String> {
+    let digest = typed_data_hash(typed_data_json)?;
+    let (rs, recovery_id) = sign_digest(private_key, &digest)?;
+
+    let mut signature = rs.to_vec();
+    signature.push(27 + recovery_id);
+    Ok(format!("0x{}", hex::encode(signature)))
+}
+
+/// Computes the digest that is signed for a typed data payload
+pub(crate) fn typed_data_hash(typed_data_json: &str) -> Result<[u8; 32], String> {
+    let TypedData {
+        mut types,
+        primary_type,
+        domain,
+        message,
+    } = serde_json::from_str(typed_data_json).map_err(|e| format!("Invalid typed data JSON: {}", e))?;
+
+    types.entry(DOMAIN_TYPE.to_string()).or_insert_with(|| {
+        DOMAIN_FIELDS
+            .iter()
+            .filter(|(name, _)| domain.contains_key(*name))
+            .map(|(name, ty)| Field {
+                name: name.to_string(),
+                ty: ty.to_string(),
+            })
+            .collect()
+    });
+
+    let mut preimage = vec![0x19, 0x01];
+    preimage.extend(hash_struct(&types, DOMAIN_TYPE, &Value::Object(domain))?);
+    // A bare domain (primaryType EIP712Domain) signs the separator alone
+    if primary_type != DOMAIN_TYPE {
+        preimage.extend(hash_struct(&types, &primary_type, &message)?);
+    }
+    Ok(keccak256(&preimage))
+}
+
+/// Strips any array suffixes, e.g. `Person[][2]` -> `Person`
+fn base_type(ty: &str) -> &str {
+    ty.split('[').next().unwrap_or(ty)
+}
+
+fn collect_dependencies(types: &Types, ty: &str, found: &mut BTreeSet<String>) {
+    let ty = base_type(ty);
+    if found.contains(ty) {
+        return;
+    }
+    if let Some(fields) = types.get(ty) {
+        found.insert(ty.to_string());
+        for field in fields {
+            collect_dependencies(types, &field.ty, found);
+        }
+    }
+}
+
+/// `encodeType`: the primary type followed by its dependencies sorted by name
+fn encode_type(types: &Types, primary_type: &str) -> Result<String, String> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(types, primary_type, &mut dependencies);
+    if !dependencies.remove(primary_type) {
+        return Err(format!("Unknown type {}", primary_type));
+    }
+
+    let mut encoded = String::new();
+    for ty in std::iter::once(primary_type).chain(dependencies.iter().map(String::as_str)) {
+        let fields = types[ty]
+            .iter()
+            .map(|field| format!("{} {}", field.ty, field.name))
+            .collect::<Vec<_>>();
+        encoded.push_str(&format!("{}({})", ty, fields.join(",")));
+    }
+    Ok(encoded)
+}
+
+fn hash_struct(types: &Types, ty: &str, data: &Value) -> Result<[u8; 32], String> {
+    let mut encoded = keccak256(encode_type(types, ty)?.as_bytes()).to_vec();
+    for field in &types[ty] {
+        let value = data.get(&field.name).unwrap_or(&Value::Null);
+        encoded.extend(encode_value(types, &field.ty, value).map_err(|e| format!("{}.{}: {}", ty, field.name, e))?);
+    }
+    Ok(keccak256(&encoded))
+}
+
+/// Encodes one member value as a 32-byte word
+fn encode_value(types: &Types, ty: &str, value: &Value) -> Result<[u8; 32], String> {
+    if let Some(open) = ty.rfind('[') {
+        let (inner, size) = match ty[open + 1..].strip_suffix(']') {
+            Some(size) if open > 0 => (&ty[..open], size),
+            _ => return Err(format!("malformed array type {}", ty)),
+        };
+        let items = value.as_array().ok_or("expected an array")?;
+        if !size.is_empty() && size.parse::<usize>().ok() != Some(items.len()) {
+            return Err(format!("expected {} items", size));
+        }
+        let mut encoded = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            encoded.extend(encode_value(types, inner, item)?);
+        }
+        return Ok(keccak256(&encoded));
+    }
+
+    if types.contains_key(ty) {
+        // Absent nested structs encode as zero, as in eth_signTypedData_v4
+        return match value {
+            Value::Null => Ok([0u8; 32]),
+            _ => hash_struct(types, ty, value),
+        };
+    }
+
+    let mut word = [0u8; 32];
+    match ty {
+        "string" => return Ok(keccak256(value.as_str().ok_or("expected a string")?.as_bytes())),
+        "bytes" => return Ok(keccak256(&hex_value(value)?)),
+        "bool" => word[31] = value.as_bool().ok_or("expected a boolean")? as u8,
+        "address" => {
+            let bytes = hex_value(value)?;
+            if bytes.len() != 20 {
+                return Err("expected a 20-byte address".to_string());
+            }
+            word[12..].copy_from_slice(&bytes);
+        }
+        _ if ty.starts_with("bytes") => {
+            let size = parse_size(&ty[5..], 1, 32)?;
+            let bytes = hex_value(value)?;
+            if bytes.len() > size {
+                return Err(format!("expected at most {} bytes", size));
+            }
+            word[..bytes.len()].copy_from_slice(&bytes);
+        }
+        _ if ty.starts_with("uint") => word = encode_integer(value, parse_size(&ty[4..], 8, 256)?, false)?,
+        _ if ty.starts_with("int") => word = encode_integer(value, parse_size(&ty[3..], 8, 256)?, true)?,
+        _ => return Err(format!("Unknown type {}", ty)),
+    }
+    Ok(word)
+}
+
+/// Parses the size suffix of `bytesN`/`uintN`/`intN`
+fn parse_size(suffix: &str, min: usize, max: usize) -> Result<usize, String> {
+    match suffix.parse::<usize>() {
+        Ok(size) if (min..=max).contains(&size) && (min == 1 || size % 8 == 0) => Ok(size),
+        _ => Err(format!("Invalid type size {}", suffix)),
+    }
+}
+
+fn hex_value(value: &Value) -> Result<Vec<u8>, String> {
+    let s = value.as_str().ok_or("expected a hex string")?;
+    hex::decode(s.trim_start_matches("0x")).map_err(|e| format!("invalid hex: {}", e))
+}
+
+/// Encodes an integer (number, decimal or `0x` hex string) as a two's
+/// complement 256-bit word, checking it fits in `bits`
+fn encode_integer(value: &Value, bits: usize, signed: bool) -> Result<[u8; 32], String> {
+    let text = match value {
+        Value::Number(n) if n.is_i64() || n.is_u64() => n.to_string(),
+        Value::String(s) => s.trim().to_string(),
+        _ => return Err("expected an integer".to_string()),
+    };
+    let (negative, digits) = match text.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, text.as_str()),
+    };
+
+    let mut magnitude = [0u8; 32];
+    if let Some(hex_digits) = digits.strip_prefix("0x") {
+        let padded = if hex_digits.len() % 2 == 1 { format!("0{}", hex_digits) } else { hex_digits.to_string() };
+        let bytes = hex::decode(padded).map_err(|e| format!("invalid hex: {}", e))?;
+        let start = bytes.iter().position(|b| *b != 0).unwrap_or(bytes.len());
+        if bytes.len() - start > 32 {
+            return Err("integer exceeds 256 bits".to_string());
+        }
+        magnitude[32 - (bytes.len() - start)..].copy_from_slice(&bytes[start..]);
+    } else {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(format!("invalid integer {}", text));
+        }
+        for digit in digits.bytes() {
+            let mut carry = (digit - b'0') as u16;
+            for byte in magnitude.iter_mut().rev() {
+                let product = *byte as u16 * 10 + carry;
+                *byte = product as u8;
+                carry = product >> 8;
+            }
+            if carry != 0 {
+                return Err("integer exceeds 256 bits".to_string());
+            }
+        }
+    }
+
+    let bit_len = magnitude
+        .iter()
+        .position(|b| *b != 0)
+        .map_or(0, |i| (32 - i) * 8 - magnitude[i].leading_zeros() as usize);
+    let is_power_of_two = magnitude.iter().map(|b| b.count_ones()).sum::<u32>() == 1;
+    let fits = match (signed, negative) {
+        (false, true) => bit_len == 0,
+        (false, false) => bit_len <= bits,
+        (true, false) => bit_len < bits,
+        // -2^(bits-1) is the minimum
+        (true, true) => bit_len < bits || (bit_len == bits && is_power_of_two),
+    };
+    if !fits {
+        return Err(format!("integer out of range for {}int{}", if signed { "" } else { "u" }, bits));
+    }
+
+    if negative {
+        // Two's complement: invert and add one
+        let mut carry = 1u16;
+        for byte in magnitude.iter_mut().rev() {
+            let sum = (!*byte) as u16 + carry;
+            *byte = sum as u8;
+            carry = sum >> 8;
+        }
+    }
+    Ok(magnitude)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `Mail` example from EIP-712
+    const MAIL: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    #[test]
+    fn test_eip712_mail_vector() {
+        assert_eq!(
+            hex::encode(typed_data_hash(MAIL).unwrap()),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+
+        let signature = sign_typed_data(&keccak256(b"cow"), MAIL).unwrap();
+        assert_eq!(
+            signature,
+            "0x4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b915621c"
+        );
+    }
+
+    #[test]
+    fn test_domain_inferred_from_fields() {
+        // Omitting EIP712Domain from `types` gives the same digest
+        let mut typed_data: Value = serde_json::from_str(MAIL).unwrap();
+        typed_data["types"].as_object_mut().unwrap().remove(DOMAIN_TYPE);
+        assert_eq!(
+            typed_data_hash(&typed_data.to_string()).unwrap(),
+            typed_data_hash(MAIL).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_encode_type_orders_dependencies() {
+        let types: Types = serde_json::from_str(
+            r#"{
+                "Order": [
+                    { "name": "maker", "type": "Person" },
+                    { "name": "assets", "type": "Asset[]" }
+                ],
+                "Person": [{ "name": "wallet", "type": "address" }],
+                "Asset": [{ "name": "token", "type": "address" }, { "name": "amount", "type": "uint256" }]
+            }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            encode_type(&types, "Order").unwrap(),
+            "Order(Person maker,Asset[] assets)Asset(address token,uint256 amount)Person(address wallet)"
+        );
+        assert!(encode_type(&types, "Missing").is_err());
+    }
+
+    #[test]
+    fn test_encode_integer() {
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(encode_integer(&Value::from(max), 256, false).unwrap(), [0xff; 32]);
+        assert_eq!(encode_integer(&Value::from("-1"), 256, true).unwrap(), [0xff; 32]);
+        assert_eq!(encode_integer(&Value::from("0x10"), 8, false).unwrap()[31], 0x10);
+        assert_eq!(encode_integer(&Value::from(-128), 8, true).unwrap()[31], 0x80);
+
+        assert!(encode_integer(&Value::from(256), 8, false).is_err());
+        assert!(encode_integer(&Value::from(128), 8, true).is_err());
+        assert!(encode_integer(&Value::from(-129), 8, true).is_err());
+        assert!(encode_integer(&Value::from("-1"), 256, false).is_err());
+        assert!(encode_integer(&Value::from(format!("{}0", max)), 256, false).is_err());
+        assert!(encode_integer(&Value::from(1.5), 256, false).is_err());
+    }
+
+    #[test]
+    fn test_encode_value_checks_shapes() {
+        let types = Types::new();
+        assert!(encode_value(&types, "uint256[2]", &serde_json::json!([1, 2])).is_ok());
+        assert!(encode_value(&types, "uint256[2]", &serde_json::json!([1])).is_err());
+        for malformed in ["uint256[", "uint256[2", "uint256[]x", "[", "[]"] {
+            assert!(encode_value(&types, malformed, &serde_json::json!([])).is_err(), "{}", malformed);
+        }
+        assert!(encode_value(&types, "address", &Value::from("0x1234")).is_err());
+        assert!(encode_value(&types, "bytes33", &Value::from("0x00")).is_err());
+        assert!(encode_value(&types, "Unknown", &Value::Null).is_err());
+        assert_eq!(encode_value(&types, "bytes4", &Value::from("0xdeadbeef")).unwrap()[..4], [0xde, 0xad, 0xbe, 0xef]);
+    }
+}
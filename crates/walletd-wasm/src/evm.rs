@@ -0,0 +1,162 @@
+//! EVM transaction signing
+//!
+//! Transactions are parsed and encoded by [`walletd_traits::evm`] (legacy
+//! EIP-155, EIP-2930 and EIP-1559); this module signs them with an in-memory
+//! key and shapes the result for JS.
+
+use crate::types::SignedTransaction;
+use k256::ecdsa::{RecoveryId, Signature, SigningKey};
+use serde_json::Value;
+use tiny_keccak::{Hasher, Keccak};
+use walletd_traits::evm::TxRequest;
+
+pub(crate) fn keccak256(data: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak::v256();
+    let mut hash = [0u8; 32];
+    hasher.update(data);
+    hasher.finalize(&mut hash);
+    hash
+}
+
+/// Signs a 32-byte digest, returning (r || s, recovery id) with low-s
+pub(crate) fn sign_digest(private_key: &[u8; 32], digest: &[u8; 32]) -> Result<([u8; 64], u8), String> {
+    let signing_key = SigningKey::from_bytes(private_key.into()).map_err(|e| format!("Invalid private key: {}", e))?;
+    let (signature, recovery_id): (Signature, RecoveryId) = signing_key
+        .sign_prehash_recoverable(digest)
+        .map_err(|e| format!("Signing failed: {}", e))?;
+
+    let mut rs = [0u8; 64];
+    rs.copy_from_slice(&signature.to_bytes());
+    Ok((rs, recovery_id.to_byte()))
+}
+
+/// Parses a transaction described by `tx_json` (see [`TxRequest::from_json`])
+pub(crate) fn parse_transaction(tx_json: &str) -> Result<TxRequest, String> {
+    let tx: Value = serde_json::from_str(tx_json).map_err(|e| format!("Invalid transaction JSON: {}", e))?;
+    TxRequest::from_json(&tx).map_err(|e| e.to_string())
+}
+
+/// Attaches a signature over `tx.signing_hash()`
+///
+/// `raw` is the `0x` hex for `eth_sendRawTransaction`, `hash` its
+/// keccak-256 and `signature` the 65-byte `r || s || v` with `v` = 27 +
+/// recovery id.
+pub(crate) fn into_signed(tx: &TxRequest, rs: [u8; 64], recovery_id: u8) -> Result<SignedTransaction, String> {
+    let signed = tx.with_signature(&rs, recovery_id).map_err(|e| e.to_string())?;
+    let mut signature = rs.to_vec();
+    signature.push(27 + recovery_id);
+    Ok(SignedTransaction {
+        chain: "ethereum",
+        raw: format!("0x{}", hex::encode(&signed.raw)),
+        hash: Some(format!("0x{}", hex::encode(signed.hash))),
+        signature: format!("0x{}", hex::encode(signature)),
+    })
+}
+
+/// Signs a transaction described by `tx_json` (see [`into_signed`])
+pub(crate) fn sign_transaction(private_key: &[u8; 32], tx_json: &str) -> Result<SignedTransaction, String> {
+    let tx = parse_transaction(tx_json)?;
+    let (rs, recovery_id) = sign_digest(private_key, &tx.signing_hash())?;
+    into_signed(&tx, rs, recovery_id)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::VerifyingKey;
+    use walletd_traits::evm::Rlp;
+
+    #[test]
+    fn test_eip155_vector() {
+        // Example transaction from EIP-155
+        let signed = sign_transaction(
+            &[0x46; 32],
+            r#"{
+                "chainId": 1,
+                "nonce": 9,
+                "gasPrice": "20000000000",
+                "gasLimit": 21000,
+                "to": "0x3535353535353535353535353535353535353535",
+                "value": "1000000000000000000"
+            }"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            signed.raw,
+            "0xf86c098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a76400008025a028ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa636276a067cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d83"
+        );
+        assert_eq!(
+            signed.hash.as_deref(),
+            Some("0x33469b22e9f636356c4160a87eb19df52b7412e8eac32a4a55ffe88ea8350788")
+        );
+        assert_eq!(
+            signed.signature,
+            "0x28ef61340bd939bc2195fe537567866003e1a15d3c71ff63e1590620aa63627667cbe9d8997f761aecb703304b3800ccf555c9f3dc64214b297fb1966a3b6d831b"
+        );
+    }
+
+    #[test]
+    fn test_eip1559_signature_recovers_signer() {
+        let private_key = [0x46; 32];
+        let raw = sign_transaction(
+            &private_key,
+            r#"{
+                "chainId": "0x2105",
+                "nonce": "0x0",
+                "maxPriorityFeePerGas": "1000000",
+                "maxFeePerGas": "0x3b9aca00",
+                "gas": "0x5208",
+                "to": "0x3535353535353535353535353535353535353535",
+                "value": "0x01",
+                "input": "0xdeadbeef",
+                "accessList": [{
+                    "address": "0x3535353535353535353535353535353535353535",
+                    "storageKeys": ["0x0000000000000000000000000000000000000000000000000000000000000001"]
+                }]
+            }"#,
+        )
+        .unwrap();
+        let raw = hex::decode(&raw.raw[2..]).unwrap();
+        assert_eq!(raw[0], 0x02);
+
+        let (Rlp::List(items), _) = Rlp::decode(&raw[1..]).unwrap() else {
+            panic!("not a list");
+        };
+        assert_eq!(items.len(), 12);
+        assert_eq!(items[0], Rlp::uint(0x2105));
+        assert_eq!(items[1], Rlp::uint(0));
+
+        // Re-encode the unsigned payload and recover the signer from (yParity, r, s)
+        let preimage = [vec![0x02], Rlp::List(items[..9].to_vec()).encode()].concat();
+        let [y_parity, r, s] = &items[9..] else { unreachable!() };
+        let (Rlp::Bytes(r), Rlp::Bytes(s)) = (r, s) else { panic!("r and s must be byte strings") };
+        let mut rs = [0u8; 64];
+        rs[32 - r.len()..32].copy_from_slice(r);
+        rs[64 - s.len()..].copy_from_slice(s);
+        let signature = Signature::from_slice(&rs).unwrap();
+
+        let recovered = VerifyingKey::recover_from_prehash(
+            &keccak256(&preimage),
+            &signature,
+            RecoveryId::from_byte(y_parity.as_uint().unwrap() as u8).unwrap(),
+        )
+        .unwrap();
+        let expected = SigningKey::from_bytes((&private_key).into()).unwrap();
+        assert_eq!(&recovered, expected.verifying_key());
+    }
+
+    #[test]
+    fn test_invalid_transactions() {
+        let key = [0x46; 32];
+        // Missing gasPrice on a legacy transaction
+        assert!(sign_transaction(&key, r#"{"chainId":1,"nonce":0,"gasLimit":21000}"#).is_err());
+        // Missing chain id
+        assert!(sign_transaction(&key, r#"{"nonce":0,"gasPrice":1,"gasLimit":21000}"#).is_err());
+        assert!(sign_transaction(&key, r#"{"chainId":0,"nonce":0,"gasPrice":1,"gasLimit":21000}"#).is_err());
+        // Unsupported type
+        assert!(sign_transaction(&key, r#"{"type":3,"chainId":1,"nonce":0,"gasPrice":1,"gasLimit":21000}"#).is_err());
+        // Bad recipient
+        assert!(sign_transaction(&key, r#"{"chainId":1,"nonce":0,"gasPrice":1,"gasLimit":21000,"to":"0x1234"}"#).is_err());
+    }
+}
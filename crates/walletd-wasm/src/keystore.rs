@@ -0,0 +1,369 @@
+//! Encrypted browser keystore
+//!
+//! Private keys are encrypted with AES-256-GCM under a key derived from the
+//! user's password with PBKDF2-SHA256, both through Web Crypto, and the
+//! ciphertext is persisted in IndexedDB. Decrypted keys only ever exist in
+//! WASM memory, never in JS strings or `localStorage`.
+
+use crate::types::{to_js, JsKeystoreEntries, KeystoreEntry};
+use crate::EthereumWallet;
+use js_sys::{Array, Object, Promise, Reflect, Uint8Array};
+use serde::{Deserialize, Serialize};
+use walletd_core::SecretBytes;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{CryptoKey, IdbDatabase, IdbFactory, IdbObjectStore, IdbRequest, IdbTransactionMode, SubtleCrypto};
+use zeroize::Zeroizing;
+
+/// IndexedDB database name
+const DB_NAME: &str = "walletd";
+
+/// IndexedDB schema version
+const DB_VERSION: u32 = 1;
+
+/// Object store holding keystore records
+const STORE_NAME: &str = "keystore";
+
+/// Record format version
+const RECORD_VERSION: u32 = 1;
+
+/// PBKDF2 iterations for new records (OWASP 2023 recommendation for SHA-256)
+const PBKDF2_ITERATIONS: u32 = 600_000;
+
+/// Records with fewer iterations are rejected, so a tampered record cannot
+/// downgrade the KDF
+const MIN_PBKDF2_ITERATIONS: u32 = 100_000;
+
+/// Encrypted key as stored in IndexedDB
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct KeystoreRecord {
+    version: u32,
+    name: String,
+    chain: String,
+    address: String,
+    kdf: String,
+    iterations: u32,
+    salt: String,
+    cipher: String,
+    iv: String,
+    ciphertext: String,
+}
+
+/// Decoded parameters of a [`KeystoreRecord`]
+#[derive(Debug)]
+struct RecordParams {
+    iterations: u32,
+    salt: Vec<u8>,
+    iv: Vec<u8>,
+    ciphertext: Vec<u8>,
+}
+
+impl KeystoreRecord {
+    fn params(&self) -> Result<RecordParams, String> {
+        if self.version != RECORD_VERSION {
+            return Err(format!("Unsupported keystore version {}", self.version));
+        }
+        if self.kdf != "pbkdf2-sha256" || self.cipher != "aes-256-gcm" {
+            return Err(format!("Unsupported keystore algorithms {}/{}", self.kdf, self.cipher));
+        }
+        if self.iterations < MIN_PBKDF2_ITERATIONS {
+            return Err(format!("Keystore iteration count {} is too low", self.iterations));
+        }
+
+        let decode = |field: &str, value: &str| hex::decode(value).map_err(|e| format!("Invalid {}: {}", field, e));
+        let params = RecordParams {
+            iterations: self.iterations,
+            salt: decode("salt", &self.salt)?,
+            iv: decode("iv", &self.iv)?,
+            ciphertext: decode("ciphertext", &self.ciphertext)?,
+        };
+        if params.salt.len() < 16 || params.iv.len() != 12 {
+            return Err("Invalid salt or IV length".to_string());
+        }
+        Ok(params)
+    }
+}
+
+/// Password-protected key storage backed by IndexedDB
+#[wasm_bindgen]
+pub struct Keystore;
+
+#[wasm_bindgen]
+impl Keystore {
+    /// Encrypt a wallet's private key and store it under `name`
+    ///
+    /// Replaces any existing entry with the same name. Returns a promise
+    /// rather than being `async` so the wallet can be borrowed instead of
+    /// consumed.
+    #[wasm_bindgen]
+    pub fn save(name: &str, wallet: &EthereumWallet, password: &str) -> Promise {
+        let name = name.to_string();
+        let password = password.to_string();
+        let address = wallet.address();
+        let private_key = SecretBytes::from_slice(wallet.private_key.expose_secret());
+
+        wasm_bindgen_futures::future_to_promise(async move {
+            let mut salt = [0u8; 16];
+            let mut iv = [0u8; 12];
+            getrandom::getrandom(&mut salt).map_err(|e| JsError::new(&e.to_string()))?;
+            getrandom::getrandom(&mut iv).map_err(|e| JsError::new(&e.to_string()))?;
+
+            let key = derive_key(&password, &salt, PBKDF2_ITERATIONS).await?;
+            let algorithm = aes_gcm_params(&iv)?;
+            let ciphertext = JsFuture::from(subtle()?.encrypt_with_object_and_u8_array(&algorithm, &key, private_key.expose_secret())?).await?;
+
+            let record = KeystoreRecord {
+                version: RECORD_VERSION,
+                name: name.clone(),
+                chain: "ethereum".to_string(),
+                address,
+                kdf: "pbkdf2-sha256".to_string(),
+                iterations: PBKDF2_ITERATIONS,
+                salt: hex::encode(salt),
+                cipher: "aes-256-gcm".to_string(),
+                iv: hex::encode(iv),
+                ciphertext: hex::encode(Uint8Array::new(&ciphertext).to_vec()),
+            };
+            let value = serde_wasm_bindgen::to_value(&record)?;
+            store_request(IdbTransactionMode::Readwrite, |store| {
+                store.put_with_key(&value, &JsValue::from_str(&name))
+            })
+            .await?;
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Decrypt the wallet stored under `name`
+    #[wasm_bindgen]
+    pub async fn load(name: String, password: String) -> Result<EthereumWallet, JsError> {
+        let value = store_request(IdbTransactionMode::Readonly, |store| store.get(&JsValue::from_str(&name)))
+            .await
+            .map_err(js_error)?;
+        if value.is_undefined() {
+            return Err(JsError::new(&format!("No keystore entry named '{}'", name)));
+        }
+
+        let record: KeystoreRecord = serde_wasm_bindgen::from_value(value)?;
+        if record.chain != "ethereum" {
+            return Err(JsError::new(&format!("Keystore entry is for {}, not ethereum", record.chain)));
+        }
+        let params = record.params().map_err(|e| JsError::new(&e))?;
+
+        let key = derive_key(&password, &params.salt, params.iterations).await.map_err(js_error)?;
+        let algorithm = aes_gcm_params(&params.iv).map_err(js_error)?;
+        let promise = subtle()
+            .and_then(|subtle| subtle.decrypt_with_object_and_u8_array(&algorithm, &key, &params.ciphertext))
+            .map_err(js_error)?;
+        let plaintext = JsFuture::from(promise)
+            .await
+            .map_err(|_| JsError::new("Wrong password or corrupted keystore"))?;
+
+        let plaintext = Uint8Array::new(&plaintext);
+        let bytes = Zeroizing::new(plaintext.to_vec());
+        // Don't leave the decrypted key behind in the JS heap
+        plaintext.fill(0, 0, plaintext.length());
+        let private_key: Zeroizing<[u8; 32]> = Zeroizing::new(
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| JsError::new("Keystore entry does not hold a 32-byte key"))?,
+        );
+        let wallet = EthereumWallet::from_private_key_bytes(&private_key)?;
+        if !wallet.address().eq_ignore_ascii_case(&record.address) {
+            return Err(JsError::new("Decrypted key does not match the stored address"));
+        }
+        Ok(wallet)
+    }
+
+    /// List stored entries as `KeystoreEntry` objects
+    #[wasm_bindgen]
+    pub async fn list() -> Result<JsKeystoreEntries, JsError> {
+        let values = store_request(IdbTransactionMode::Readonly, |store| store.get_all())
+            .await
+            .map_err(js_error)?;
+
+        let entries = Array::from(&values)
+            .iter()
+            .filter_map(|value| serde_wasm_bindgen::from_value::<KeystoreRecord>(value).ok())
+            .map(|record| KeystoreEntry {
+                name: record.name,
+                chain: record.chain,
+                address: record.address,
+            })
+            .collect::<Vec<_>>();
+        to_js(&entries)
+    }
+
+    /// Delete the entry stored under `name`
+    #[wasm_bindgen]
+    pub async fn remove(name: String) -> Result<(), JsError> {
+        store_request(IdbTransactionMode::Readwrite, |store| store.delete(&JsValue::from_str(&name)))
+            .await
+            .map_err(js_error)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn js_error(value: JsValue) -> JsError {
+    let message = value
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| value.as_string())
+        .unwrap_or_else(|| format!("{:?}", value));
+    JsError::new(&message)
+}
+
+/// Reads a property of the global object (works in windows and workers)
+pub(crate) fn global_property<T: JsCast>(name: &str) -> Result<T, JsValue> {
+    Reflect::get(&js_sys::global(), &JsValue::from_str(name))?
+        .dyn_into::<T>()
+        .map_err(|_| JsValue::from_str(&format!("{} is not available in this environment", name)))
+}
+
+fn subtle() -> Result<SubtleCrypto, JsValue> {
+    Ok(global_property::<web_sys::Crypto>("crypto")?.subtle())
+}
+
+pub(crate) fn object(entries: &[(&str, JsValue)]) -> Result<Object, JsValue> {
+    let object = Object::new();
+    for (key, value) in entries {
+        Reflect::set(&object, &JsValue::from_str(key), value)?;
+    }
+    Ok(object)
+}
+
+fn aes_gcm_params(iv: &[u8]) -> Result<Object, JsValue> {
+    object(&[("name", "AES-GCM".into()), ("iv", Uint8Array::from(iv).into())])
+}
+
+/// Derives a non-extractable AES-256-GCM key from `password`
+async fn derive_key(password: &str, salt: &[u8], iterations: u32) -> Result<CryptoKey, JsValue> {
+    let subtle = subtle()?;
+
+    let password_key = JsFuture::from(subtle.import_key_with_object(
+        "raw",
+        &Uint8Array::from(password.as_bytes()),
+        &object(&[("name", "PBKDF2".into())])?,
+        false,
+        &Array::of1(&"deriveKey".into()),
+    )?)
+    .await?;
+
+    let pbkdf2 = object(&[
+        ("name", "PBKDF2".into()),
+        ("salt", Uint8Array::from(salt).into()),
+        ("iterations", iterations.into()),
+        ("hash", "SHA-256".into()),
+    ])?;
+    let aes = object(&[("name", "AES-GCM".into()), ("length", 256.into())])?;
+    let key = JsFuture::from(subtle.derive_key_with_object_and_object(
+        &pbkdf2,
+        &password_key.dyn_into()?,
+        &aes,
+        false,
+        &Array::of2(&"encrypt".into(), &"decrypt".into()),
+    )?)
+    .await?;
+    key.dyn_into()
+}
+
+/// Resolves when an IndexedDB request succeeds, with its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = Promise::new(&mut |resolve, reject| {
+        let req = request.clone();
+        let on_success = Closure::once_into_js(move || {
+            let _ = resolve.call1(&JsValue::NULL, &req.result().unwrap_or(JsValue::UNDEFINED));
+        });
+        let req = request.clone();
+        let on_error = Closure::once_into_js(move || {
+            let error = req
+                .error()
+                .ok()
+                .flatten()
+                .map(JsValue::from)
+                .unwrap_or_else(|| JsValue::from_str("IndexedDB request failed"));
+            let _ = reject.call1(&JsValue::NULL, &error);
+        });
+        request.set_onsuccess(Some(on_success.unchecked_ref()));
+        request.set_onerror(Some(on_error.unchecked_ref()));
+    });
+    JsFuture::from(promise).await
+}
+
+async fn open_db() -> Result<IdbDatabase, JsValue> {
+    let factory: IdbFactory = global_property("indexedDB")?;
+    let request = factory.open_with_u32(DB_NAME, DB_VERSION)?;
+
+    let req = request.clone();
+    let on_upgrade = Closure::once_into_js(move || {
+        if let Ok(db) = req.result().and_then(|db| db.dyn_into::<IdbDatabase>()) {
+            if !db.object_store_names().contains(STORE_NAME) {
+                let _ = db.create_object_store(STORE_NAME);
+            }
+        }
+    });
+    request.set_onupgradeneeded(Some(on_upgrade.unchecked_ref()));
+
+    request_result(&request).await?.dyn_into()
+}
+
+/// Runs a single request against the keystore object store
+async fn store_request<F>(mode: IdbTransactionMode, f: F) -> Result<JsValue, JsValue>
+where
+    F: FnOnce(&IdbObjectStore) -> Result<IdbRequest, JsValue>,
+{
+    let db = open_db().await?;
+    let result = async {
+        let transaction = db.transaction_with_str_and_mode(STORE_NAME, mode)?;
+        let request = f(&transaction.object_store(STORE_NAME)?)?;
+        request_result(&request).await
+    }
+    .await;
+    db.close();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record() -> KeystoreRecord {
+        KeystoreRecord {
+            version: RECORD_VERSION,
+            name: "main".to_string(),
+            chain: "ethereum".to_string(),
+            address: "0x0000000000000000000000000000000000000000".to_string(),
+            kdf: "pbkdf2-sha256".to_string(),
+            iterations: PBKDF2_ITERATIONS,
+            salt: hex::encode([1u8; 16]),
+            cipher: "aes-256-gcm".to_string(),
+            iv: hex::encode([2u8; 12]),
+            ciphertext: hex::encode([3u8; 48]),
+        }
+    }
+
+    #[test]
+    fn test_record_params() {
+        let params = record().params().unwrap();
+        assert_eq!(params.iterations, PBKDF2_ITERATIONS);
+        assert_eq!(params.salt, vec![1u8; 16]);
+        assert_eq!(params.iv, vec![2u8; 12]);
+        assert_eq!(params.ciphertext.len(), 48);
+    }
+
+    #[test]
+    fn test_record_rejects_downgrades() {
+        let weak = KeystoreRecord { iterations: 1_000, ..record() };
+        assert!(weak.params().is_err());
+
+        let other_cipher = KeystoreRecord { cipher: "aes-128-cbc".to_string(), ..record() };
+        assert!(other_cipher.params().is_err());
+
+        let short_iv = KeystoreRecord { iv: hex::encode([2u8; 8]), ..record() };
+        assert!(short_iv.params().is_err());
+
+        let future = KeystoreRecord { version: 2, ..record() };
+        assert!(future.params().is_err());
+    }
+}
@@ -0,0 +1,523 @@
+//! Ledger hardware wallet over WebUSB
+//!
+//! Speaks the Ledger Ethereum app protocol: APDUs are split into 64-byte
+//! HID-style packets (channel, tag, sequence number) and exchanged over the
+//! device's vendor interface. Keys never leave the device; every signature
+//! is confirmed on its screen.
+//!
+//! Enabled with the `ledger` feature. WebUSB is available in Chromium-based
+//! browsers on secure (https) origins.
+
+use crate::evm::{into_signed, keccak256, parse_transaction};
+use crate::keystore::{global_property, js_error, object};
+use crate::types::{to_js, JsSignedTransaction, JsWalletExport, WalletExport};
+use js_sys::{Array, Function, Promise, Reflect, Uint8Array};
+use k256::ecdsa::{RecoveryId, Signature, VerifyingKey};
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+/// Ledger's USB vendor id
+const LEDGER_VENDOR_ID: u16 = 0x2c97;
+
+/// Bulk endpoint used for APDU packets
+const ENDPOINT: u8 = 3;
+
+/// Transport packet size
+const PACKET_SIZE: usize = 64;
+
+/// Channel id used by Ledger Live
+const CHANNEL: u16 = 0x0101;
+
+/// Packet tag for APDU data
+const TAG_APDU: u8 = 0x05;
+
+/// Largest APDU payload the Ethereum app accepts per chunk
+const MAX_CHUNK: usize = 255;
+
+const CLA: u8 = 0xe0;
+const INS_GET_ADDRESS: u8 = 0x02;
+const INS_SIGN_TRANSACTION: u8 = 0x04;
+const INS_SIGN_PERSONAL_MESSAGE: u8 = 0x08;
+
+/// Ethereum account on a Ledger device, with the same shape as `EthereumWallet`
+#[wasm_bindgen]
+pub struct LedgerEthWallet {
+    device: JsValue,
+    path: Vec<u32>,
+    public_key: Vec<u8>,
+    address: String,
+    busy: Cell<bool>,
+}
+
+#[wasm_bindgen]
+impl LedgerEthWallet {
+    /// Ask the user to pick a Ledger and open account `m/44'/60'/{account}'/0/0`
+    ///
+    /// Must be called from a user gesture (e.g. a click handler). The
+    /// Ethereum app must be open on the device.
+    #[wasm_bindgen]
+    pub async fn connect(account: u32) -> Result<LedgerEthWallet, JsError> {
+        Self::connect_path(format!("m/44'/60'/{}'/0/0", account)).await
+    }
+
+    /// Like `connect`, with an explicit derivation path
+    #[wasm_bindgen(js_name = connectPath)]
+    pub async fn connect_path(path: String) -> Result<LedgerEthWallet, JsError> {
+        let path = parse_path(&path).map_err(|e| JsError::new(&e))?;
+        let device = open_device().await.map_err(js_error)?;
+
+        let mut wallet = LedgerEthWallet {
+            device,
+            path,
+            public_key: Vec::new(),
+            address: String::new(),
+            busy: Cell::new(false),
+        };
+        let (public_key, address) = wallet.get_address(false).await?;
+        wallet.public_key = public_key;
+        wallet.address = address;
+        Ok(wallet)
+    }
+
+    /// Get the checksummed address
+    #[wasm_bindgen]
+    pub fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    /// Get the public key as hex (uncompressed, without the 0x04 prefix)
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> String {
+        format!("0x{}", hex::encode(&self.public_key[1..]))
+    }
+
+    /// Get the derivation path
+    #[wasm_bindgen]
+    pub fn path(&self) -> String {
+        format_path(&self.path)
+    }
+
+    /// Show the address on the device screen for the user to verify
+    ///
+    /// Resolves once the user approves; rejects if they decline or it differs.
+    #[wasm_bindgen(js_name = verifyAddress)]
+    pub async fn verify_address(&self) -> Result<(), JsError> {
+        let (_, address) = self.get_address(true).await?;
+        if address != self.address {
+            return Err(JsError::new("Device returned a different address"));
+        }
+        Ok(())
+    }
+
+    /// Sign a message (`personal_sign`), confirmed on the device
+    ///
+    /// Returns the 65-byte `r || s || v` signature as hex.
+    #[wasm_bindgen(js_name = signMessage)]
+    pub async fn sign_message(&self, message: String) -> Result<String, JsError> {
+        let mut data = (message.len() as u32).to_be_bytes().to_vec();
+        data.extend_from_slice(message.as_bytes());
+        let response = self.send_chunked(INS_SIGN_PERSONAL_MESSAGE, &data).await?;
+        let (v, rs) = parse_signature(&response).map_err(|e| JsError::new(&e))?;
+
+        let mut signature = rs.to_vec();
+        signature.push(v);
+        Ok(format!("0x{}", hex::encode(signature)))
+    }
+
+    /// Sign a transaction, confirmed on the device
+    ///
+    /// Takes the same JSON as `EthereumWallet.signTransaction` and returns the
+    /// same `SignedTransaction`.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub async fn sign_transaction(&self, tx_json: String) -> Result<JsSignedTransaction, JsError> {
+        let tx = parse_transaction(&tx_json).map_err(|e| JsError::new(&e))?;
+        let payload = tx.signing_payload();
+        let response = self.send_chunked(INS_SIGN_TRANSACTION, &payload).await?;
+        let (_, rs) = parse_signature(&response).map_err(|e| JsError::new(&e))?;
+
+        // The device's v byte is truncated for large chain ids, so recover the
+        // parity instead and check the signer at the same time
+        let recovery_id = recovery_id(&keccak256(&payload), &rs, &self.public_key).map_err(|e| JsError::new(&e))?;
+        to_js(&into_signed(&tx, rs, recovery_id).map_err(|e| JsError::new(&e))?)
+    }
+
+    /// Export the public wallet details
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "ethereum",
+            address: self.address.clone(),
+            public_key: self.public_key(),
+            network: None,
+        })
+    }
+
+    /// Release the USB device
+    #[wasm_bindgen]
+    pub async fn close(&self) -> Result<(), JsError> {
+        await_method(&self.device, "close", &[]).await.map_err(js_error)?;
+        Ok(())
+    }
+
+    async fn get_address(&self, display: bool) -> Result<(Vec<u8>, String), JsError> {
+        let apdu = build_apdu(INS_GET_ADDRESS, display as u8, 0x00, &encode_path(&self.path));
+        let response = self.exchange(vec![apdu]).await?;
+        parse_address_response(&response).map_err(|e| JsError::new(&e))
+    }
+
+    /// Sends `path || data` split into 255-byte APDUs, returning the last response
+    async fn send_chunked(&self, ins: u8, data: &[u8]) -> Result<Vec<u8>, JsError> {
+        self.exchange(chunked_apdus(ins, &self.path, data)).await
+    }
+
+    /// Sends APDUs in order, holding the device until the last response
+    async fn exchange(&self, apdus: Vec<Vec<u8>>) -> Result<Vec<u8>, JsError> {
+        if self.busy.replace(true) {
+            return Err(JsError::new("Ledger is busy with another request"));
+        }
+        let mut result = Ok(Vec::new());
+        for apdu in &apdus {
+            result = match exchange(&self.device, apdu).await {
+                Ok(response) => check_status(&response).map_err(|e| JsError::new(&e)),
+                Err(e) => Err(js_error(e)),
+            };
+            if result.is_err() {
+                break;
+            }
+        }
+        self.busy.set(false);
+        result
+    }
+}
+
+// ============================================================================
+// WebUSB transport
+// ============================================================================
+
+fn call_method(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+    let method: Function = Reflect::get(target, &JsValue::from_str(name))?.dyn_into()?;
+    method.apply(target, &args.iter().collect::<Array>())
+}
+
+async fn await_method(target: &JsValue, name: &str, args: &[JsValue]) -> Result<JsValue, JsValue> {
+    let promise: Promise = call_method(target, name, args)?.dyn_into()?;
+    JsFuture::from(promise).await
+}
+
+/// Prompts for a Ledger device, then opens and claims its vendor interface
+async fn open_device() -> Result<JsValue, JsValue> {
+    let navigator: JsValue = global_property::<js_sys::Object>("navigator")?.into();
+    let usb = Reflect::get(&navigator, &JsValue::from_str("usb"))?;
+    if usb.is_undefined() {
+        return Err(JsValue::from_str("WebUSB is not available in this browser"));
+    }
+
+    let filter = object(&[("vendorId", LEDGER_VENDOR_ID.into())])?;
+    let options = object(&[("filters", Array::of1(&filter).into())])?;
+    let device = await_method(&usb, "requestDevice", &[options.into()]).await?;
+
+    await_method(&device, "open", &[]).await?;
+    if Reflect::get(&device, &JsValue::from_str("configuration"))?.is_null() {
+        await_method(&device, "selectConfiguration", &[1.into()]).await?;
+    }
+
+    // The APDU interface is the vendor-specific (class 0xff) one
+    let configuration = Reflect::get(&device, &JsValue::from_str("configuration"))?;
+    let interfaces: Array = Reflect::get(&configuration, &JsValue::from_str("interfaces"))?.dyn_into()?;
+    let interface_number = interfaces
+        .iter()
+        .find(|interface| {
+            Reflect::get(interface, &JsValue::from_str("alternates"))
+                .ok()
+                .and_then(|alternates| Reflect::get(&alternates, &0.into()).ok())
+                .and_then(|alternate| Reflect::get(&alternate, &JsValue::from_str("interfaceClass")).ok())
+                .and_then(|class| class.as_f64())
+                == Some(255.0)
+        })
+        .map(|interface| Reflect::get(&interface, &JsValue::from_str("interfaceNumber")))
+        .transpose()?
+        .ok_or_else(|| JsValue::from_str("Ledger interface not found"))?;
+    await_method(&device, "claimInterface", &[interface_number]).await?;
+    Ok(device)
+}
+
+/// Sends one APDU and reads back the full response
+async fn exchange(device: &JsValue, apdu: &[u8]) -> Result<Vec<u8>, JsValue> {
+    for packet in frame_apdu(apdu) {
+        let data = Uint8Array::from(packet.as_slice());
+        await_method(device, "transferOut", &[ENDPOINT.into(), data.into()]).await?;
+    }
+
+    let mut reader = ResponseReader::default();
+    loop {
+        let result = await_method(device, "transferIn", &[ENDPOINT.into(), (PACKET_SIZE as u32).into()]).await?;
+        let view: js_sys::DataView = Reflect::get(&result, &JsValue::from_str("data"))?.dyn_into()?;
+        let packet = Uint8Array::new_with_byte_offset_and_length(&view.buffer(), view.byte_offset() as u32, view.byte_length() as u32)
+            .to_vec();
+        if let Some(response) = reader.push(&packet).map_err(|e| JsValue::from_str(&e))? {
+            return Ok(response);
+        }
+    }
+}
+
+// ============================================================================
+// Framing and APDUs
+// ============================================================================
+
+/// Splits an APDU into transport packets
+fn frame_apdu(apdu: &[u8]) -> Vec<Vec<u8>> {
+    let mut data = (apdu.len() as u16).to_be_bytes().to_vec();
+    data.extend_from_slice(apdu);
+
+    data.chunks(PACKET_SIZE - 5)
+        .enumerate()
+        .map(|(sequence, chunk)| {
+            let mut packet = Vec::with_capacity(PACKET_SIZE);
+            packet.extend_from_slice(&CHANNEL.to_be_bytes());
+            packet.push(TAG_APDU);
+            packet.extend_from_slice(&(sequence as u16).to_be_bytes());
+            packet.extend_from_slice(chunk);
+            packet.resize(PACKET_SIZE, 0);
+            packet
+        })
+        .collect()
+}
+
+/// Reassembles a response from transport packets
+#[derive(Default)]
+struct ResponseReader {
+    expected: Option<usize>,
+    data: Vec<u8>,
+    sequence: u16,
+}
+
+impl ResponseReader {
+    /// Adds a packet, returning the response once complete
+    fn push(&mut self, packet: &[u8]) -> Result<Option<Vec<u8>>, String> {
+        if packet.len() < 5 || packet[..2] != CHANNEL.to_be_bytes() || packet[2] != TAG_APDU {
+            return Err("Unexpected packet from device".to_string());
+        }
+        if u16::from_be_bytes([packet[3], packet[4]]) != self.sequence {
+            return Err("Out of order packet from device".to_string());
+        }
+        self.sequence += 1;
+
+        let mut body = &packet[5..];
+        if self.expected.is_none() {
+            if body.len() < 2 {
+                return Err("Truncated packet from device".to_string());
+            }
+            self.expected = Some(u16::from_be_bytes([body[0], body[1]]) as usize);
+            body = &body[2..];
+        }
+        self.data.extend_from_slice(body);
+
+        let expected = self.expected.unwrap_or_default();
+        if self.data.len() >= expected {
+            self.data.truncate(expected);
+            return Ok(Some(std::mem::take(&mut self.data)));
+        }
+        Ok(None)
+    }
+}
+
+fn build_apdu(ins: u8, p1: u8, p2: u8, data: &[u8]) -> Vec<u8> {
+    let mut apdu = vec![CLA, ins, p1, p2, data.len() as u8];
+    apdu.extend_from_slice(data);
+    apdu
+}
+
+/// Builds the APDUs for a multi-part request: the first carries the path,
+/// the rest continue with P1 = 0x80
+fn chunked_apdus(ins: u8, path: &[u32], data: &[u8]) -> Vec<Vec<u8>> {
+    let mut payload = encode_path(path);
+    payload.extend_from_slice(data);
+    payload
+        .chunks(MAX_CHUNK)
+        .enumerate()
+        .map(|(i, chunk)| build_apdu(ins, if i == 0 { 0x00 } else { 0x80 }, 0x00, chunk))
+        .collect()
+}
+
+/// Strips the status word, mapping common errors to readable messages
+fn check_status(response: &[u8]) -> Result<Vec<u8>, String> {
+    if response.len() < 2 {
+        return Err("Empty response from device".to_string());
+    }
+    let (data, status) = response.split_at(response.len() - 2);
+    match u16::from_be_bytes([status[0], status[1]]) {
+        0x9000 => Ok(data.to_vec()),
+        0x6985 => Err("Rejected on the Ledger".to_string()),
+        0x6a80 => Err("Ledger rejected the data (enable blind signing for contract calls)".to_string()),
+        0x5515 | 0x6b0c => Err("Ledger is locked".to_string()),
+        0x6d00 | 0x6e00 | 0x6e01 | 0x6511 => Err("Open the Ethereum app on the Ledger".to_string()),
+        other => Err(format!("Ledger error {:#06x}", other)),
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<u32>, String> {
+    let mut parts = path.trim().split('/');
+    if parts.next() != Some("m") {
+        return Err(format!("Invalid derivation path {}", path));
+    }
+    let indexes = parts
+        .map(|part| {
+            let (number, hardened) = match part.strip_suffix('\'').or_else(|| part.strip_suffix('h')) {
+                Some(number) => (number, true),
+                None => (part, false),
+            };
+            let index: u32 = number.parse().map_err(|_| format!("Invalid derivation path {}", path))?;
+            if index >= 0x8000_0000 {
+                return Err(format!("Invalid derivation path {}", path));
+            }
+            Ok(if hardened { index | 0x8000_0000 } else { index })
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    if indexes.is_empty() || indexes.len() > 10 {
+        return Err(format!("Invalid derivation path {}", path));
+    }
+    Ok(indexes)
+}
+
+fn format_path(path: &[u32]) -> String {
+    let mut formatted = "m".to_string();
+    for index in path {
+        if index & 0x8000_0000 != 0 {
+            formatted.push_str(&format!("/{}'", index & 0x7fff_ffff));
+        } else {
+            formatted.push_str(&format!("/{}", index));
+        }
+    }
+    formatted
+}
+
+fn encode_path(path: &[u32]) -> Vec<u8> {
+    let mut encoded = vec![path.len() as u8];
+    for index in path {
+        encoded.extend_from_slice(&index.to_be_bytes());
+    }
+    encoded
+}
+
+/// Parses `pubkey_len || pubkey || address_len || address (ascii hex)`
+fn parse_address_response(response: &[u8]) -> Result<(Vec<u8>, String), String> {
+    let truncated = || "Truncated address response".to_string();
+    let key_len = *response.first().ok_or_else(truncated)? as usize;
+    let public_key = response.get(1..1 + key_len).ok_or_else(truncated)?.to_vec();
+    let address_len = *response.get(1 + key_len).ok_or_else(truncated)? as usize;
+    let address = response.get(2 + key_len..2 + key_len + address_len).ok_or_else(truncated)?;
+    let address = std::str::from_utf8(address).map_err(|_| "Invalid address from device".to_string())?;
+
+    if public_key.len() != 65 {
+        return Err("Unexpected public key from device".to_string());
+    }
+    // Derive the address ourselves rather than trusting the device's encoding
+    let derived = &keccak256(&public_key[1..])[12..];
+    if !address.eq_ignore_ascii_case(&hex::encode(derived)) {
+        return Err("Device address does not match its public key".to_string());
+    }
+    Ok((public_key, crate::checksum_address(&hex::encode(derived))))
+}
+
+/// Parses `v || r || s`
+fn parse_signature(response: &[u8]) -> Result<(u8, [u8; 64]), String> {
+    if response.len() < 65 {
+        return Err("Truncated signature from device".to_string());
+    }
+    let mut rs = [0u8; 64];
+    rs.copy_from_slice(&response[1..65]);
+    Ok((response[0], rs))
+}
+
+/// Finds the recovery id for which `rs` over `digest` recovers to `public_key`
+fn recovery_id(digest: &[u8; 32], rs: &[u8; 64], public_key: &[u8]) -> Result<u8, String> {
+    let signature = Signature::from_slice(rs).map_err(|e| format!("Invalid signature: {}", e))?;
+    (0u8..2)
+        .find(|id| {
+            RecoveryId::from_byte(*id)
+                .and_then(|id| VerifyingKey::recover_from_prehash(digest, &signature, id).ok())
+                .is_some_and(|key| key.to_encoded_point(false).as_bytes() == public_key)
+        })
+        .ok_or_else(|| "Signature is not from this Ledger account".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use k256::ecdsa::SigningKey;
+
+    #[test]
+    fn test_frame_roundtrip() {
+        let apdu: Vec<u8> = (0..150u8).collect();
+        let packets = frame_apdu(&apdu);
+        assert_eq!(packets.len(), 3);
+        assert!(packets.iter().all(|p| p.len() == PACKET_SIZE));
+        assert_eq!(&packets[0][..7], &[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 150]);
+        assert_eq!(&packets[1][..5], &[0x01, 0x01, 0x05, 0x00, 0x01]);
+
+        let mut reader = ResponseReader::default();
+        assert_eq!(reader.push(&packets[0]).unwrap(), None);
+        assert_eq!(reader.push(&packets[1]).unwrap(), None);
+        assert_eq!(reader.push(&packets[2]).unwrap(), Some(apdu));
+
+        let mut reader = ResponseReader::default();
+        assert!(reader.push(&packets[1]).is_err());
+    }
+
+    #[test]
+    fn test_path_encoding() {
+        let path = parse_path("m/44'/60'/0'/0/0").unwrap();
+        assert_eq!(path, vec![0x8000_002c, 0x8000_003c, 0x8000_0000, 0, 0]);
+        assert_eq!(format_path(&path), "m/44'/60'/0'/0/0");
+        assert_eq!(
+            hex::encode(encode_path(&path)),
+            "058000002c8000003c800000000000000000000000"
+        );
+        assert!(parse_path("44'/60'").is_err());
+        assert!(parse_path("m/2147483648").is_err());
+    }
+
+    #[test]
+    fn test_chunked_apdus() {
+        let path = parse_path("m/44'/60'/0'/0/0").unwrap();
+        let apdus = chunked_apdus(INS_SIGN_TRANSACTION, &path, &[0xab; 300]);
+        assert_eq!(apdus.len(), 2);
+        assert_eq!(&apdus[0][..5], &[CLA, INS_SIGN_TRANSACTION, 0x00, 0x00, 255]);
+        assert_eq!(&apdus[0][5..26], encode_path(&path).as_slice());
+        assert_eq!(&apdus[1][..5], &[CLA, INS_SIGN_TRANSACTION, 0x80, 0x00, 66]);
+    }
+
+    #[test]
+    fn test_status_words() {
+        assert_eq!(check_status(&[1, 2, 0x90, 0x00]).unwrap(), vec![1, 2]);
+        assert_eq!(check_status(&[0x69, 0x85]).unwrap_err(), "Rejected on the Ledger");
+        assert!(check_status(&[0x6e, 0x00]).unwrap_err().contains("Ethereum app"));
+        assert!(check_status(&[0x90]).is_err());
+    }
+
+    #[test]
+    fn test_address_response_and_recovery() {
+        let signing_key = SigningKey::from_bytes((&[0x46u8; 32]).into()).unwrap();
+        let public_key = signing_key.verifying_key().to_encoded_point(false).as_bytes().to_vec();
+        let address = hex::encode(&keccak256(&public_key[1..])[12..]);
+
+        let mut response = vec![65];
+        response.extend_from_slice(&public_key);
+        response.push(40);
+        response.extend_from_slice(address.as_bytes());
+        let (parsed_key, parsed_address) = parse_address_response(&response).unwrap();
+        assert_eq!(parsed_key, public_key);
+        assert_eq!(parsed_address.to_lowercase(), format!("0x{}", address));
+
+        // A signature as the device would return it, with v ignored
+        let digest = keccak256(b"payload");
+        let (signature, id) = signing_key.sign_prehash_recoverable(&digest).unwrap();
+        let mut device = vec![0xff];
+        device.extend_from_slice(&signature.to_bytes());
+        let (_, rs) = parse_signature(&device).unwrap();
+        assert_eq!(recovery_id(&digest, &rs, &public_key).unwrap(), id.to_byte());
+        assert!(recovery_id(&keccak256(b"other"), &rs, &public_key).is_err());
+    }
+}
@@ -5,7 +5,7 @@
 //! ## Usage in JavaScript/TypeScript
 //!
 //! ```javascript
-//! import init, { EthereumWallet, generateMnemonic } from 'walletd-wasm';
+//! import init, { EthereumWallet, SolanaWallet, SuiWallet, generateMnemonic } from 'walletd-wasm';
 //!
 //! async function main() {
 //!     await init();
@@ -21,7 +21,33 @@
 //! ```
 
 use wasm_bindgen::prelude::*;
-use serde::{Deserialize, Serialize};
+use base64::Engine;
+use types::{to_js, JsSignedTransaction, JsWalletExport, SignedTransaction, WalletExport};
+use walletd_core::SecretBytes;
+use zeroize::Zeroizing;
+
+mod aptos;
+mod bip85;
+mod eip712;
+mod evm;
+mod keystore;
+#[cfg(feature = "ledger")]
+mod ledger;
+mod payment;
+mod rpc;
+mod sui;
+mod ton;
+mod types;
+
+pub use aptos::AptosWallet;
+pub use bip85::{derive_child_entropy, derive_child_mnemonic};
+pub use keystore::Keystore;
+#[cfg(feature = "ledger")]
+pub use ledger::LedgerEthWallet;
+pub use payment::{make_payment_uri, parse_payment_uri};
+pub use rpc::{EvmRpc, SolanaRpc};
+pub use sui::SuiWallet;
+pub use ton::TonWallet;
 
 // Initialize panic hook for better error messages in browser console
 #[cfg(feature = "console_error_panic_hook")]
@@ -53,44 +79,40 @@ pub fn version() -> String {
 /// Generate a BIP-39 mnemonic phrase
 ///
 /// # Arguments
-/// * `word_count` - Number of words (12 or 24)
+/// * `word_count` - Number of words (12, 15, 18, 21 or 24)
 ///
 /// # Returns
 /// A space-separated mnemonic phrase
 #[wasm_bindgen(js_name = generateMnemonic)]
 pub fn generate_mnemonic(word_count: u8) -> Result<String, JsError> {
-    use bip32::Mnemonic;
-    
-    match word_count {
-        12 => {
-            let mut entropy = [0u8; 16]; // 128 bits for 12 words
-            getrandom::getrandom(&mut entropy).map_err(|e| JsError::new(&e.to_string()))?;
-            
-            // Pad to 32 bytes as required by the API
-            let mut full_entropy = [0u8; 32];
-            full_entropy[..16].copy_from_slice(&entropy);
-            
-            // Use bip32's built-in entropy handling
-            let mnemonic = Mnemonic::from_entropy(full_entropy, bip32::Language::English);
-            // Take only first 12 words
-            let words: Vec<&str> = mnemonic.phrase().split_whitespace().take(12).collect();
-            Ok(words.join(" "))
-        }
-        24 => {
-            let mut entropy = [0u8; 32]; // 256 bits for 24 words
-            getrandom::getrandom(&mut entropy).map_err(|e| JsError::new(&e.to_string()))?;
-            let mnemonic = Mnemonic::from_entropy(entropy, bip32::Language::English);
-            Ok(mnemonic.phrase().to_string())
-        }
-        _ => Err(JsError::new("Word count must be 12 or 24")),
+    generate_phrase(word_count).map_err(|e| JsError::new(&e))
+}
+
+fn generate_phrase(word_count: u8) -> Result<String, String> {
+    // Each word carries 11 bits; every 3 words hold 32 bits of entropy + 1 checksum bit
+    if !matches!(word_count, 12 | 15 | 18 | 21 | 24) {
+        return Err("Word count must be 12, 15, 18, 21 or 24".to_string());
     }
+    let mut entropy = Zeroizing::new([0u8; 32]);
+    let entropy = &mut entropy[..word_count as usize * 4 / 3];
+    getrandom::getrandom(entropy).map_err(|e| e.to_string())?;
+
+    let mnemonic = bip39::Mnemonic::from_entropy_in(bip39::Language::English, entropy)
+        .map_err(|e| e.to_string())?;
+    Ok(mnemonic.to_string())
 }
 
 /// Validate a mnemonic phrase
 #[wasm_bindgen(js_name = validateMnemonic)]
 pub fn validate_mnemonic(phrase: &str) -> bool {
-    use bip32::Mnemonic;
-    Mnemonic::new(phrase, bip32::Language::English).is_ok()
+    bip39::Mnemonic::parse_in(bip39::Language::English, phrase).is_ok()
+}
+
+/// Parse a BIP-39 phrase of any standard length and derive its seed
+fn mnemonic_seed(phrase: &str) -> Result<Zeroizing<[u8; 64]>, JsError> {
+    let mnemonic = bip39::Mnemonic::parse_in(bip39::Language::English, phrase)
+        .map_err(|e| JsError::new(&format!("Invalid mnemonic: {}", e)))?;
+    Ok(Zeroizing::new(mnemonic.to_seed("")))
 }
 
 // ============================================================================
@@ -100,9 +122,10 @@ pub fn validate_mnemonic(phrase: &str) -> bool {
 /// Ethereum wallet for browser environments
 #[wasm_bindgen]
 pub struct EthereumWallet {
-    private_key: [u8; 32],
+    private_key: SecretBytes,
     public_key: Vec<u8>,
     address: String,
+    exportable: bool,
 }
 
 #[wasm_bindgen]
@@ -110,8 +133,8 @@ impl EthereumWallet {
     /// Create a new random Ethereum wallet
     #[wasm_bindgen(constructor)]
     pub fn new() -> Result<EthereumWallet, JsError> {
-        let mut private_key = [0u8; 32];
-        getrandom::getrandom(&mut private_key)
+        let mut private_key = Zeroizing::new([0u8; 32]);
+        getrandom::getrandom(private_key.as_mut())
             .map_err(|e| JsError::new(&e.to_string()))?;
         
         Self::from_private_key_bytes(&private_key)
@@ -120,22 +143,19 @@ impl EthereumWallet {
     /// Create wallet from mnemonic phrase
     #[wasm_bindgen(js_name = fromMnemonic)]
     pub fn from_mnemonic(mnemonic: &str) -> Result<EthereumWallet, JsError> {
-        use bip32::{Mnemonic, XPrv, DerivationPath};
+        use bip32::{XPrv, DerivationPath};
         use std::str::FromStr;
         
-        let mnemonic = Mnemonic::new(mnemonic, bip32::Language::English)
-            .map_err(|e| JsError::new(&format!("Invalid mnemonic: {}", e)))?;
-        
-        let seed = mnemonic.to_seed("");
+        let seed = mnemonic_seed(mnemonic)?;
         
         // Derive key using BIP-44 path for Ethereum: m/44'/60'/0'/0/0
         let path = DerivationPath::from_str("m/44'/60'/0'/0/0")
             .map_err(|e| JsError::new(&format!("Invalid path: {}", e)))?;
         
-        let child_xprv = XPrv::derive_from_path(&seed, &path)
+        let child_xprv = XPrv::derive_from_path(seed.as_slice(), &path)
             .map_err(|e| JsError::new(&format!("Derivation error: {}", e)))?;
         
-        let private_key: [u8; 32] = child_xprv.private_key().to_bytes().into();
+        let private_key: Zeroizing<[u8; 32]> = Zeroizing::new(child_xprv.private_key().to_bytes().into());
         
         Self::from_private_key_bytes(&private_key)
     }
@@ -144,14 +164,14 @@ impl EthereumWallet {
     #[wasm_bindgen(js_name = fromPrivateKey)]
     pub fn from_private_key(private_key_hex: &str) -> Result<EthereumWallet, JsError> {
         let private_key_hex = private_key_hex.trim_start_matches("0x");
-        let bytes = hex::decode(private_key_hex)
-            .map_err(|e| JsError::new(&format!("Invalid hex: {}", e)))?;
+        let bytes = Zeroizing::new(hex::decode(private_key_hex)
+            .map_err(|e| JsError::new(&format!("Invalid hex: {}", e)))?);
         
         if bytes.len() != 32 {
             return Err(JsError::new("Private key must be 32 bytes"));
         }
         
-        let mut private_key = [0u8; 32];
+        let mut private_key = Zeroizing::new([0u8; 32]);
         private_key.copy_from_slice(&bytes);
         
         Self::from_private_key_bytes(&private_key)
@@ -180,9 +200,10 @@ impl EthereumWallet {
         let address = checksum_address(&address);
         
         Ok(EthereumWallet {
-            private_key: *private_key,
+            private_key: SecretBytes::from_slice(private_key),
             public_key,
             address,
+            exportable: KEY_EXPORT_DEFAULT,
         })
     }
     
@@ -193,9 +214,12 @@ impl EthereumWallet {
     }
     
     /// Get the private key as hex string
+    ///
+    /// Throws if key export has been disabled.
     #[wasm_bindgen(js_name = privateKey)]
-    pub fn private_key(&self) -> String {
-        format!("0x{}", hex::encode(&self.private_key))
+    pub fn private_key(&self) -> Result<String, JsError> {
+        check_key_export(self.exportable)?;
+        Ok(format!("0x{}", hex::encode(self.key())))
     }
     
     /// Get the public key as hex string (uncompressed, without 0x04 prefix)
@@ -218,7 +242,7 @@ impl EthereumWallet {
         hasher.update(message.as_bytes());
         hasher.finalize(&mut hash);
         
-        let signing_key = SigningKey::from_bytes((&self.private_key).into())
+        let signing_key = SigningKey::from_bytes(self.key().into())
             .map_err(|e| JsError::new(&format!("Key error: {}", e)))?;
         
         let signature: Signature = signing_key.sign(&hash);
@@ -226,16 +250,62 @@ impl EthereumWallet {
         Ok(format!("0x{}", hex::encode(signature.to_bytes())))
     }
     
-    /// Export wallet as JSON
+    /// Sign EIP-712 typed data (`eth_signTypedData_v4`)
+    ///
+    /// Takes the JSON payload with `types`, `primaryType`, `domain` and
+    /// `message`, as used for permits and DEX orders. Returns the 65-byte
+    /// `r || s || v` signature as hex.
+    #[wasm_bindgen(js_name = signTypedData)]
+    pub fn sign_typed_data(&self, typed_data_json: &str) -> Result<String, JsError> {
+        eip712::sign_typed_data(self.key(), typed_data_json).map_err(|e| JsError::new(&e))
+    }
+    
+    /// Sign a transaction
+    ///
+    /// Takes a JSON transaction request with `chainId`, `nonce`, `gasLimit`
+    /// (or `gas`), `to`, `value`, `data` and either `gasPrice` (legacy,
+    /// EIP-155) or `maxFeePerGas`/`maxPriorityFeePerGas` (EIP-1559). The
+    /// returned `raw` hex can be passed straight to `eth_sendRawTransaction`.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub fn sign_transaction(&self, tx_json: &str) -> Result<JsSignedTransaction, JsError> {
+        let signed = evm::sign_transaction(self.key(), tx_json).map_err(|e| JsError::new(&e))?;
+        to_js(&signed)
+    }
+    
+    /// Export the public wallet details (no private key)
     #[wasm_bindgen(js_name = toJson)]
-    pub fn to_json(&self) -> Result<JsValue, JsError> {
-        let wallet_data = WalletExport {
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&self.export())
+    }
+    
+    /// Permanently disable reading the private key from JS
+    ///
+    /// Signing keeps working. With the `no-key-export` feature every wallet
+    /// starts out this way.
+    #[wasm_bindgen(js_name = disableKeyExport)]
+    pub fn disable_key_export(&mut self) {
+        self.exportable = false;
+    }
+    
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
+}
+
+impl EthereumWallet {
+    fn key(&self) -> &[u8; 32] {
+        self.private_key.as_array().expect("32-byte key")
+    }
+
+    fn export(&self) -> WalletExport {
+        WalletExport {
+            chain: "ethereum",
             address: self.address.clone(),
             public_key: format!("0x{}", hex::encode(&self.public_key)),
-        };
-        
-        serde_wasm_bindgen::to_value(&wallet_data)
-            .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))
+            network: None,
+        }
     }
 }
 
@@ -252,10 +322,11 @@ impl Default for EthereumWallet {
 /// Bitcoin key pair for address generation
 #[wasm_bindgen]
 pub struct BitcoinKeys {
-    private_key: [u8; 32],
+    private_key: SecretBytes,
     public_key: Vec<u8>,
     address: String,
     network: String,
+    exportable: bool,
 }
 
 #[wasm_bindgen]
@@ -267,28 +338,25 @@ impl BitcoinKeys {
     /// * `network` - "mainnet" or "testnet"
     #[wasm_bindgen(js_name = fromMnemonic)]
     pub fn from_mnemonic(mnemonic: &str, network: &str) -> Result<BitcoinKeys, JsError> {
-        use bip32::{Mnemonic, XPrv, DerivationPath};
+        use bip32::{XPrv, DerivationPath};
         use std::str::FromStr;
         use sha2::{Sha256, Digest};
         
-        let mnemonic = Mnemonic::new(mnemonic, bip32::Language::English)
-            .map_err(|e| JsError::new(&format!("Invalid mnemonic: {}", e)))?;
-        
-        let seed = mnemonic.to_seed("");
+        let seed = mnemonic_seed(mnemonic)?;
         
         // BIP-84 path for native SegWit: m/84'/0'/0'/0/0 (mainnet) or m/84'/1'/0'/0/0 (testnet)
         let coin_type = if network == "testnet" { "1" } else { "0" };
         let path = DerivationPath::from_str(&format!("m/84'/{coin_type}'/0'/0/0"))
             .map_err(|e| JsError::new(&format!("Invalid path: {}", e)))?;
         
-        let child_xprv = XPrv::derive_from_path(&seed, &path)
+        let child_xprv = XPrv::derive_from_path(seed.as_slice(), &path)
             .map_err(|e| JsError::new(&format!("Derivation error: {}", e)))?;
         
-        let private_key: [u8; 32] = child_xprv.private_key().to_bytes().into();
+        let private_key: Zeroizing<[u8; 32]> = Zeroizing::new(child_xprv.private_key().to_bytes().into());
         
         // Derive public key using k256
         use k256::ecdsa::SigningKey;
-        let signing_key = SigningKey::from_bytes((&private_key).into())
+        let signing_key = SigningKey::from_bytes((&*private_key).into())
             .map_err(|e| JsError::new(&format!("Key error: {}", e)))?;
         
         let verifying_key = signing_key.verifying_key();
@@ -301,7 +369,7 @@ impl BitcoinKeys {
         
         // RIPEMD160
         let mut ripemd = ripemd::Ripemd160::new();
-        ripemd::Digest::update(&mut ripemd, &sha256_hash);
+        ripemd::Digest::update(&mut ripemd, sha256_hash);
         let hash160: [u8; 20] = ripemd::Digest::finalize(ripemd).into();
         
         // Bech32 encoding
@@ -309,10 +377,11 @@ impl BitcoinKeys {
         let address = bech32_encode(hrp, &hash160)?;
         
         Ok(BitcoinKeys {
-            private_key,
+            private_key: SecretBytes::from_slice(&*private_key),
             public_key,
             address,
             network: network.to_string(),
+            exportable: KEY_EXPORT_DEFAULT,
         })
     }
     
@@ -329,21 +398,24 @@ impl BitcoinKeys {
     }
     
     /// Get the WIF (Wallet Import Format) private key
+    ///
+    /// Throws if key export has been disabled.
     #[wasm_bindgen]
-    pub fn wif(&self) -> String {
+    pub fn wif(&self) -> Result<String, JsError> {
         use sha2::{Sha256, Digest};
         
+        check_key_export(self.exportable)?;
         let prefix = if self.network == "testnet" { 0xef } else { 0x80 };
-        let mut extended = vec![prefix];
-        extended.extend_from_slice(&self.private_key);
+        let mut extended = Zeroizing::new(vec![prefix]);
+        extended.extend_from_slice(self.private_key.expose_secret());
         extended.push(0x01); // Compressed pubkey flag
         
         // Double SHA256 for checksum
         let hash1 = Sha256::digest(&extended);
-        let hash2 = Sha256::digest(&hash1);
+        let hash2 = Sha256::digest(hash1);
         extended.extend_from_slice(&hash2[..4]);
         
-        bs58::encode(extended).into_string()
+        Ok(bs58::encode(&*extended).into_string())
     }
     
     /// Get compressed public key as hex
@@ -351,6 +423,32 @@ impl BitcoinKeys {
     pub fn public_key(&self) -> String {
         format!("0x{}", hex::encode(&self.public_key))
     }
+    
+    /// Export the public wallet details (no private key)
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "bitcoin",
+            address: self.address.clone(),
+            public_key: self.public_key(),
+            network: Some(self.network.clone()),
+        })
+    }
+    
+    /// Permanently disable reading the private key from JS
+    ///
+    /// Addresses and public keys stay available. With the `no-key-export` feature every wallet
+    /// starts out this way.
+    #[wasm_bindgen(js_name = disableKeyExport)]
+    pub fn disable_key_export(&mut self) {
+        self.exportable = false;
+    }
+    
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
 }
 
 // Simple bech32 encoding for native SegWit addresses
@@ -418,6 +516,198 @@ fn bech32_polymod_step(pre: u32, val: u32) -> u32 {
     if b & 16 != 0 { chk ^ 0x2a1462b3 } else { chk }
 }
 
+// ============================================================================
+// Solana Wallet
+// ============================================================================
+
+/// Solana wallet for browser environments
+#[wasm_bindgen]
+pub struct SolanaWallet {
+    signing_key: ed25519_dalek::SigningKey,
+    address: String,
+    exportable: bool,
+}
+
+#[wasm_bindgen]
+impl SolanaWallet {
+    /// Create a new random Solana wallet
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<SolanaWallet, JsError> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        getrandom::getrandom(seed.as_mut())
+            .map_err(|e| JsError::new(&e.to_string()))?;
+
+        Ok(Self::from_seed(&seed))
+    }
+
+    /// Create wallet from mnemonic phrase
+    ///
+    /// # Arguments
+    /// * `mnemonic` - BIP-39 mnemonic phrase
+    /// * `account` - Account index, as in `m/44'/501'/{account}'/0'` (Phantom, Solflare)
+    #[wasm_bindgen(js_name = fromMnemonic)]
+    pub fn from_mnemonic(mnemonic: &str, account: u32) -> Result<SolanaWallet, JsError> {
+        let seed = mnemonic_seed(mnemonic)?;
+        let key = Zeroizing::new(slip10_ed25519(&*seed, &[44, 501, account, 0]));
+
+        Ok(Self::from_seed(&key))
+    }
+
+    /// Create wallet from a base58 secret key
+    ///
+    /// Accepts the 64-byte keypair format used by `solana-keygen` and Phantom
+    /// exports, or a bare 32-byte seed.
+    #[wasm_bindgen(js_name = fromSecretKey)]
+    pub fn from_secret_key(secret_key: &str) -> Result<SolanaWallet, JsError> {
+        let bytes = Zeroizing::new(bs58::decode(secret_key)
+            .into_vec()
+            .map_err(|e| JsError::new(&format!("Invalid base58: {}", e)))?);
+
+        let seed: Zeroizing<[u8; 32]> = match bytes.len() {
+            32 | 64 => Zeroizing::new(bytes[..32].try_into().unwrap()),
+            _ => return Err(JsError::new("Secret key must be 32 or 64 bytes")),
+        };
+        let wallet = Self::from_seed(&seed);
+
+        if bytes.len() == 64 && bytes[32..] != wallet.signing_key.verifying_key().to_bytes() {
+            return Err(JsError::new("Secret key does not match its public key"));
+        }
+        Ok(wallet)
+    }
+
+    fn from_seed(seed: &[u8; 32]) -> SolanaWallet {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(seed);
+        let address = bs58::encode(signing_key.verifying_key().to_bytes()).into_string();
+        SolanaWallet {
+            signing_key,
+            address,
+            exportable: KEY_EXPORT_DEFAULT,
+        }
+    }
+
+    /// Get the wallet address (base58 public key)
+    #[wasm_bindgen]
+    pub fn address(&self) -> String {
+        self.address.clone()
+    }
+
+    /// Get the secret key as base58 (64-byte keypair format)
+    ///
+    /// Throws if key export has been disabled.
+    #[wasm_bindgen(js_name = secretKey)]
+    pub fn secret_key(&self) -> Result<String, JsError> {
+        check_key_export(self.exportable)?;
+        let keypair = Zeroizing::new(self.signing_key.to_keypair_bytes());
+        Ok(bs58::encode(&*keypair).into_string())
+    }
+
+    /// Sign an arbitrary message (returns the 64-byte signature)
+    #[wasm_bindgen(js_name = signMessage)]
+    pub fn sign_message(&self, message: &[u8]) -> Vec<u8> {
+        use ed25519_dalek::Signer;
+        self.signing_key.sign(message).to_bytes().to_vec()
+    }
+
+    /// Sign a serialized transaction
+    ///
+    /// Takes the wire format produced by web3.js
+    /// (`tx.serialize({ requireAllSignatures: false })` or
+    /// `VersionedTransaction.serialize()`) and fills in this wallet's
+    /// signature slot. `raw` is the signed transaction as base64, ready for
+    /// `sendEncodedTransaction`; `hash` is the transaction id once the fee
+    /// payer's signature is present.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub fn sign_transaction(&self, transaction: &[u8]) -> Result<JsSignedTransaction, JsError> {
+        let signed = self.sign_wire_transaction(transaction).map_err(|e| JsError::new(&e))?;
+        to_js(&signed)
+    }
+
+    /// Export the public wallet details (no private key)
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "solana",
+            address: self.address.clone(),
+            public_key: self.address.clone(),
+            network: None,
+        })
+    }
+
+    /// Permanently disable reading the private key from JS
+    ///
+    /// Signing keeps working. With the `no-key-export` feature every wallet
+    /// starts out this way.
+    #[wasm_bindgen(js_name = disableKeyExport)]
+    pub fn disable_key_export(&mut self) {
+        self.exportable = false;
+    }
+
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
+}
+
+impl SolanaWallet {
+    fn sign_wire_transaction(&self, transaction: &[u8]) -> Result<SignedTransaction, String> {
+        let (slot, message) = solana_signer_slot(transaction, &self.signing_key.verifying_key().to_bytes())?;
+
+        let signature = self.sign_message(message);
+        let mut signed = transaction.to_vec();
+        signed[slot..slot + 64].copy_from_slice(&signature);
+
+        // The first signature (the fee payer's) is the transaction id
+        let (_, sigs_start) = decode_short_vec_len(&signed)?;
+        let id = &signed[sigs_start..sigs_start + 64];
+        let hash = id.iter().any(|b| *b != 0).then(|| bs58::encode(id).into_string());
+        Ok(SignedTransaction {
+            chain: "solana",
+            raw: base64::engine::general_purpose::STANDARD.encode(&signed),
+            hash,
+            signature: bs58::encode(signature).into_string(),
+        })
+    }
+}
+
+/// Locates the signature slot for `pubkey` in a serialized Solana transaction
+///
+/// Returns the slot's byte offset and the message bytes to sign.
+fn solana_signer_slot<'a>(transaction: &'a [u8], pubkey: &[u8; 32]) -> Result<(usize, &'a [u8]), String> {
+    let (sig_count, sigs_start) = decode_short_vec_len(transaction)?;
+    let message_start = sigs_start + sig_count * 64;
+    let message = transaction
+        .get(message_start..)
+        .filter(|m| !m.is_empty())
+        .ok_or("Transaction is truncated")?;
+
+    // Versioned messages start with 0x80 | version
+    let header_start = if message[0] & 0x80 != 0 { 1 } else { 0 };
+    let header = message
+        .get(header_start..header_start + 3)
+        .ok_or("Message header is truncated")?;
+    let required_signatures = header[0] as usize;
+    if required_signatures != sig_count {
+        return Err("Signature count does not match message header".to_string());
+    }
+
+    let (key_count, keys_start) = decode_short_vec_len(&message[header_start + 3..])?;
+    let keys_start = header_start + 3 + keys_start;
+    let index = (0..key_count.min(required_signatures))
+        .find(|i| {
+            let start = keys_start + i * 32;
+            message.get(start..start + 32) == Some(pubkey.as_slice())
+        })
+        .ok_or("Wallet is not a required signer of this transaction")?;
+
+    Ok((sigs_start + index * 64, message))
+}
+
+/// Decodes a Solana compact-u16 length prefix, returning (value, bytes read)
+fn decode_short_vec_len(data: &[u8]) -> Result<(usize, usize), String> {
+    walletd_core::decode_compact_u16(data).ok_or_else(|| "Invalid compact-u16 length".to_string())
+}
+
 // ============================================================================
 // Monero Amount Utilities
 // ============================================================================
@@ -494,7 +784,7 @@ fn checksum_address(address: &str) -> String {
     hasher.update(address.as_bytes());
     hasher.finalize(&mut hash);
     
-    let hash_hex = hex::encode(&hash);
+    let hash_hex = hex::encode(hash);
     
     let checksummed: String = address
         .chars()
@@ -516,6 +806,55 @@ fn checksum_address(address: &str) -> String {
     format!("0x{}", checksummed)
 }
 
+/// Whether new wallets allow their private key to be read from JS
+const KEY_EXPORT_DEFAULT: bool = !cfg!(feature = "no-key-export");
+
+fn check_key_export(exportable: bool) -> Result<(), JsError> {
+    if exportable {
+        Ok(())
+    } else {
+        Err(JsError::new("Key export is disabled for this wallet"))
+    }
+}
+
+/// SLIP-10 Ed25519 derivation (all indices hardened)
+fn slip10_ed25519(seed: &[u8], path: &[u32]) -> [u8; 32] {
+    use hmac::{Hmac, Mac};
+    use sha2::Sha512;
+
+    let hmac = |key: &[u8], data: &[&[u8]]| -> [u8; 64] {
+        let mut mac = Hmac::<Sha512>::new_from_slice(key).expect("HMAC accepts any key size");
+        for chunk in data {
+            mac.update(chunk);
+        }
+        mac.finalize().into_bytes().into()
+    };
+
+    let mut node = Zeroizing::new(hmac(b"ed25519 seed", &[seed]));
+    for index in path {
+        let index = (index | 0x8000_0000).to_be_bytes();
+        *node = hmac(&node[32..], &[&[0u8], &node[..32], &index]);
+    }
+
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&node[..32]);
+    key
+}
+
+/// ULEB128 length prefix, as used by BCS
+fn uleb128(mut value: usize) -> Vec<u8> {
+    let mut out = Vec::new();
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
 /// Convert hex string to bytes
 #[wasm_bindgen(js_name = hexToBytes)]
 pub fn hex_to_bytes(hex: &str) -> Result<Vec<u8>, JsError> {
@@ -577,16 +916,6 @@ pub fn gwei_to_wei(gwei: f64) -> String {
     wei.to_string()
 }
 
-// ============================================================================
-// Types for JS Interop
-// ============================================================================
-
-#[derive(Serialize, Deserialize)]
-struct WalletExport {
-    address: String,
-    public_key: String,
-}
-
 // ============================================================================
 // Tests
 // ============================================================================
@@ -685,6 +1014,121 @@ mod tests {
         assert!(checksummed.chars().any(|c| c.is_uppercase()));
     }
     
+    #[test]
+    fn test_slip10_ed25519_vector() {
+        // SLIP-0010 test vector 1
+        let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+        assert_eq!(
+            hex::encode(slip10_ed25519(&seed, &[])),
+            "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7"
+        );
+        assert_eq!(
+            hex::encode(slip10_ed25519(&seed, &[0])),
+            "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3"
+        );
+    }
+
+    #[test]
+    fn test_generate_mnemonic_lengths() {
+        for word_count in [12u8, 15, 18, 21, 24] {
+            let phrase = generate_phrase(word_count).unwrap();
+            assert_eq!(phrase.split_whitespace().count(), word_count as usize);
+            assert!(validate_mnemonic(&phrase), "{}-word phrase failed validation", word_count);
+        }
+        assert!(generate_phrase(13).is_err());
+    }
+
+    #[test]
+    fn test_validate_mnemonic_checksum() {
+        assert!(validate_mnemonic("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about"));
+        // Same words, wrong checksum word
+        assert!(!validate_mnemonic("abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon"));
+    }
+
+    #[test]
+    fn test_ethereum_wallet_from_12_word_mnemonic() {
+        let wallet = EthereumWallet::from_mnemonic(
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        )
+        .unwrap();
+        assert_eq!(wallet.address(), "0x9858EfFD232B4033E47d90003D41EC34EcaEda94");
+    }
+
+    #[test]
+    fn test_solana_wallet_from_mnemonic() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        let mut wallet = SolanaWallet::from_mnemonic(mnemonic, 0).unwrap();
+        wallet.exportable = true;
+        let other = SolanaWallet::from_mnemonic(mnemonic, 1).unwrap();
+
+        assert_eq!(bs58::decode(wallet.address()).into_vec().unwrap().len(), 32);
+        assert_ne!(wallet.address(), other.address());
+
+        let restored = SolanaWallet::from_secret_key(&wallet.secret_key().unwrap()).unwrap();
+        assert_eq!(restored.address(), wallet.address());
+    }
+
+    #[test]
+    fn test_solana_sign_transaction() {
+        let wallet = SolanaWallet::from_seed(&[3u8; 32]);
+        let pubkey = wallet.signing_key.verifying_key().to_bytes();
+
+        // Legacy message: header, two accounts (fee payer first), blockhash, no instructions
+        let mut message = vec![1, 0, 1, 2];
+        message.extend_from_slice(&pubkey);
+        message.extend_from_slice(&[9u8; 32]);
+        message.extend_from_slice(&[7u8; 32]);
+        message.push(0);
+
+        let mut tx = vec![1];
+        tx.extend_from_slice(&[0u8; 64]);
+        tx.extend_from_slice(&message);
+
+        let signed = wallet.sign_wire_transaction(&tx).unwrap();
+        let raw = base64::engine::general_purpose::STANDARD.decode(&signed.raw).unwrap();
+        let signature = wallet.sign_message(&message);
+        assert_eq!(&raw[1..65], signature.as_slice());
+        assert_eq!(&raw[65..], message.as_slice());
+        assert_eq!(signed.signature, bs58::encode(&signature).into_string());
+        // We are the fee payer, so our signature is the transaction id
+        assert_eq!(signed.hash, Some(signed.signature.clone()));
+
+        assert!(solana_signer_slot(&tx, &[1u8; 32]).is_err());
+    }
+
+    #[test]
+    fn test_wallet_export_shape() {
+        let wallet = EthereumWallet::from_private_key_bytes(&[1u8; 32]).unwrap();
+        let json = serde_json::to_value(wallet.export()).unwrap();
+        assert_eq!(json["chain"], "ethereum");
+        assert_eq!(json["address"], wallet.address());
+        assert_eq!(json["publicKey"], wallet.public_key());
+        assert!(json.get("network").is_none());
+    }
+
+    #[test]
+    fn test_disable_key_export() {
+        let mut wallet = EthereumWallet::from_private_key_bytes(&[1u8; 32]).unwrap();
+        assert_eq!(wallet.exportable, KEY_EXPORT_DEFAULT);
+        if KEY_EXPORT_DEFAULT {
+            assert!(wallet.private_key().is_ok());
+        }
+
+        wallet.disable_key_export();
+        assert!(!wallet.exportable);
+        // Signing is unaffected
+        assert!(evm::sign_transaction(wallet.key(), r#"{"chainId":1,"nonce":0,"gasPrice":1,"gasLimit":21000}"#).is_ok());
+        wallet.destroy();
+    }
+
+    #[test]
+    fn test_uleb128() {
+        assert_eq!(uleb128(0), vec![0]);
+        assert_eq!(uleb128(127), vec![0x7f]);
+        assert_eq!(uleb128(128), vec![0x80, 0x01]);
+        assert_eq!(uleb128(300), vec![0xac, 0x02]);
+    }
+
     #[test]
     fn test_version() {
         let v = version();
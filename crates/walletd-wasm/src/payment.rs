@@ -0,0 +1,251 @@
+//! Payment request URIs
+//!
+//! Builds and parses the URIs wallets scan from checkout QR codes:
+//! BIP-21 (`bitcoin:`), EIP-681 (`ethereum:`), Solana Pay transfer
+//! requests (`solana:`) and TON transfer links (`ton://transfer/`).
+//! Amounts are passed as decimal strings in whole units so no precision is
+//! lost in JS numbers.
+//!
+//! Encoding and parsing are [`walletd_traits::PaymentRequest`]'s; this module
+//! only maps its fields to and from JS.
+
+use crate::types::{to_js, JsPaymentOptions, JsPaymentRequest, PaymentOptions, PaymentRequest};
+use std::str::FromStr;
+use walletd_traits::address::Chain;
+use walletd_traits::{Amount, PaymentAmount};
+use wasm_bindgen::prelude::*;
+
+const BTC_DECIMALS: u8 = 8;
+const ETH_DECIMALS: u8 = 18;
+const SOL_DECIMALS: u8 = 9;
+const TON_DECIMALS: u8 = 9;
+
+/// Build a payment URI for a checkout QR code
+///
+/// # Arguments
+/// * `chain` - "bitcoin", "ethereum", "solana" or "ton"
+/// * `address` - Recipient address
+/// * `amount` - Optional amount in whole units (BTC, ETH, SOL, TON or tokens)
+/// * `opts` - Optional label, message, memo, chainId, token, decimals, reference
+///
+/// Display fields a format has no slot for (`label` and `message` on
+/// EIP-681) are left out; fields that change the payment, such as `token`
+/// on Bitcoin, are rejected.
+#[wasm_bindgen(js_name = makePaymentUri)]
+pub fn make_payment_uri(
+    chain: &str,
+    address: &str,
+    amount: Option<String>,
+    opts: Option<JsPaymentOptions>,
+) -> Result<String, JsError> {
+    let opts: PaymentOptions = match opts.map(JsValue::from) {
+        Some(value) if !value.is_undefined() && !value.is_null() => serde_wasm_bindgen::from_value(value)?,
+        _ => PaymentOptions::default(),
+    };
+    payment_uri(chain, address, amount.as_deref(), &opts).map_err(|e| JsError::new(&e))
+}
+
+/// Parse a BIP-21, EIP-681, Solana Pay or TON transfer URI
+#[wasm_bindgen(js_name = parsePaymentUri)]
+pub fn parse_payment_uri(uri: &str) -> Result<JsPaymentRequest, JsError> {
+    to_js(&parse_uri(uri).map_err(|e| JsError::new(&e))?)
+}
+
+pub(crate) fn payment_uri(
+    chain: &str,
+    address: &str,
+    amount: Option<&str>,
+    opts: &PaymentOptions,
+) -> Result<String, String> {
+    let chain = Chain::from_str(chain).map_err(|e| e.to_string())?;
+    let amount = match amount.map(str::trim).filter(|a| !a.is_empty()) {
+        None => None,
+        Some(amount) => Some(match (&opts.token, opts.decimals) {
+            (None, _) => Amount::from_decimal_str(amount, native_decimals(chain)?).map(PaymentAmount::Exact),
+            (Some(_), Some(decimals)) => Amount::from_decimal_str(amount, decimals).map(PaymentAmount::Exact),
+            // Solana Pay token amounts are in whole units, so the mint's
+            // decimals are not needed; EIP-681 and TON will ask for them
+            (Some(_), None) => Ok(PaymentAmount::Units(amount.to_string())),
+        }
+        .map_err(|e| e.to_string())?),
+    };
+    let request = walletd_traits::PaymentRequest {
+        amount,
+        token: opts.token.clone(),
+        memo: opts.memo.clone(),
+        label: opts.label.clone(),
+        message: opts.message.clone(),
+        chain_id: opts.chain_id,
+        reference: opts.reference.clone(),
+        ..walletd_traits::PaymentRequest::new(chain, address)
+    };
+    request.to_uri().map_err(|e| e.to_string())
+}
+
+pub(crate) fn parse_uri(uri: &str) -> Result<PaymentRequest, String> {
+    let request = walletd_traits::PaymentRequest::parse(uri).map_err(|e| e.to_string())?;
+    // Token amounts have no known decimals, so only one side may be known
+    let amount = request.amount.as_ref();
+    Ok(PaymentRequest {
+        chain: chain_name(request.chain)?,
+        address: request.address,
+        amount: amount.and_then(|a| a.units(None).ok()),
+        base_amount: amount.and_then(|a| a.base_units(None).ok()).map(|v| v.to_string()),
+        chain_id: request.chain_id,
+        token: request.token,
+        label: request.label,
+        message: request.message,
+        memo: request.memo,
+        reference: request.reference,
+    })
+}
+
+fn native_decimals(chain: Chain) -> Result<u8, String> {
+    match chain {
+        Chain::Bitcoin => Ok(BTC_DECIMALS),
+        Chain::Ethereum => Ok(ETH_DECIMALS),
+        Chain::Solana => Ok(SOL_DECIMALS),
+        Chain::Ton => Ok(TON_DECIMALS),
+        other => Err(format!("Unsupported chain {}", other)),
+    }
+}
+
+fn chain_name(chain: Chain) -> Result<&'static str, String> {
+    match chain {
+        Chain::Bitcoin => Ok("bitcoin"),
+        Chain::Ethereum => Ok("ethereum"),
+        Chain::Solana => Ok("solana"),
+        Chain::Ton => Ok("ton"),
+        other => Err(format!("Unsupported chain {}", other)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SOL_RECIPIENT: &str = "mvines9iiHiQTysrwkJjGf2gb9Ex9jXJX8ns3qwf2kN";
+    const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+
+    #[test]
+    fn test_bip21() {
+        let opts = PaymentOptions {
+            label: Some("Luke-Jr".to_string()),
+            message: Some("Donation for project xyz".to_string()),
+            ..Default::default()
+        };
+        let uri = payment_uri("bitcoin", "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", Some("20.3"), &opts).unwrap();
+        assert_eq!(
+            uri,
+            "bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?amount=20.3&label=Luke-Jr&message=Donation%20for%20project%20xyz"
+        );
+
+        let parsed = parse_uri(&uri).unwrap();
+        assert_eq!(parsed.address, "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2");
+        assert_eq!(parsed.base_amount.as_deref(), Some("2030000000"));
+        assert_eq!(parsed.message.as_deref(), Some("Donation for project xyz"));
+
+        assert!(parse_uri("bitcoin:1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2?req-somethingyoudontunderstand=50").is_err());
+        assert!(payment_uri("bitcoin", "bc1q...", Some("1.000000001"), &PaymentOptions::default()).is_err());
+    }
+
+    #[test]
+    fn test_eip681() {
+        let address = "0xfb6916095ca1df60bb79ce92ce3ea74c37c5d359";
+        let opts = PaymentOptions {
+            chain_id: Some(1),
+            ..Default::default()
+        };
+        let uri = payment_uri("ethereum", address, Some("2.014"), &opts).unwrap();
+        assert_eq!(uri, format!("ethereum:{}@1?value=2014000000000000000", address));
+
+        // Example from EIP-681
+        let parsed = parse_uri(&format!("ethereum:{}?value=2.014e18", address)).unwrap();
+        assert_eq!(parsed.amount.as_deref(), Some("2.014"));
+        assert_eq!(parsed.base_amount.as_deref(), Some("2014000000000000000"));
+    }
+
+    #[test]
+    fn test_eip681_token_transfer() {
+        let token = "0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48";
+        let recipient = "0x8e23ee67d1332ad560396262c48ffbb01f93d052";
+        let opts = PaymentOptions {
+            chain_id: Some(1),
+            token: Some(token.to_string()),
+            decimals: Some(6),
+            label: Some("Ignored".to_string()),
+            ..Default::default()
+        };
+        let uri = payment_uri("ethereum", recipient, Some("12.5"), &opts).unwrap();
+        assert_eq!(uri, format!("ethereum:{}@1/transfer?address={}&uint256=12500000", token, recipient));
+
+        let parsed = parse_uri(&uri).unwrap();
+        assert_eq!(parsed.address, recipient);
+        assert_eq!(parsed.token.as_deref(), Some(token));
+        assert_eq!(parsed.chain_id, Some(1));
+        assert_eq!(parsed.base_amount.as_deref(), Some("12500000"));
+
+        let no_decimals = PaymentOptions {
+            token: Some(token.to_string()),
+            ..Default::default()
+        };
+        assert!(payment_uri("ethereum", recipient, Some("1"), &no_decimals).is_err());
+    }
+
+    #[test]
+    fn test_solana_pay() {
+        let opts = PaymentOptions {
+            token: Some(USDC_MINT.to_string()),
+            reference: vec![SOL_RECIPIENT.to_string()],
+            label: Some("Michael".to_string()),
+            message: Some("Thanks for all the fish".to_string()),
+            memo: Some("OrderId12345".to_string()),
+            ..Default::default()
+        };
+        let uri = payment_uri("solana", SOL_RECIPIENT, Some("0.01"), &opts).unwrap();
+        assert_eq!(
+            uri,
+            format!(
+                "solana:{0}?amount=0.01&spl-token={1}&reference={0}&label=Michael&message=Thanks%20for%20all%20the%20fish&memo=OrderId12345",
+                SOL_RECIPIENT, USDC_MINT
+            )
+        );
+
+        let parsed = parse_uri(&uri).unwrap();
+        assert_eq!(parsed.token.as_deref(), Some(USDC_MINT));
+        assert_eq!(parsed.reference, vec![SOL_RECIPIENT.to_string()]);
+        assert_eq!(parsed.memo.as_deref(), Some("OrderId12345"));
+        assert_eq!(parsed.base_amount, None);
+
+        let native = parse_uri(&format!("solana:{}?amount=1.5", SOL_RECIPIENT)).unwrap();
+        assert_eq!(native.base_amount.as_deref(), Some("1500000000"));
+        assert!(parse_uri("solana:https://example.com/pay").is_err());
+    }
+
+    #[test]
+    fn test_ton_transfer() {
+        let address = "EQDtFpEwcFAEcRe5mLVh2N6C0x-_hJEM7W61_JLnSF74p4q2";
+        let opts = PaymentOptions {
+            memo: Some("invoice 42".to_string()),
+            ..Default::default()
+        };
+        let uri = payment_uri("ton", address, Some("1.5"), &opts).unwrap();
+        assert_eq!(uri, format!("ton://transfer/{}?amount=1500000000&text=invoice%2042", address));
+
+        let parsed = parse_uri(&uri).unwrap();
+        assert_eq!(parsed.chain, "ton");
+        assert_eq!(parsed.amount.as_deref(), Some("1.5"));
+        assert_eq!(parsed.memo.as_deref(), Some("invoice 42"));
+    }
+
+    #[test]
+    fn test_rejects_unsupported_options() {
+        let opts = PaymentOptions {
+            token: Some(USDC_MINT.to_string()),
+            ..Default::default()
+        };
+        assert!(payment_uri("bitcoin", "1BvBMSEYstWetqTFn5Au4m4GFg7xJaNVN2", None, &opts).is_err());
+        assert!(payment_uri("dogecoin", "D...", None, &PaymentOptions::default()).is_err());
+        assert!(parse_uri("litecoin:abc").is_err());
+    }
+}
@@ -0,0 +1,331 @@
+//! JSON-RPC over `fetch`
+//!
+//! Minimal read/broadcast clients so a browser wallet can be built on
+//! walletd-wasm alone. Requests go through the global `fetch`, which exists in
+//! windows, workers and service workers alike.
+
+use crate::types::{to_js, FeeEstimate, JsFeeEstimate};
+use serde_json::{json, Value};
+use std::cell::Cell;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(js_name = fetch)]
+    fn fetch_with_init(input: &str, init: &JsValue) -> js_sys::Promise;
+}
+
+/// Sends a JSON-RPC request and returns its `result`
+async fn rpc_call(url: &str, id: u64, method: &str, params: Value) -> Result<Value, JsError> {
+    let body = json!({ "jsonrpc": "2.0", "id": id, "method": method, "params": params }).to_string();
+
+    let init = js_sys::Object::new();
+    let headers = js_sys::Object::new();
+    js_sys::Reflect::set(&headers, &"Content-Type".into(), &"application/json".into()).map_err(js_error)?;
+    js_sys::Reflect::set(&init, &"method".into(), &"POST".into()).map_err(js_error)?;
+    js_sys::Reflect::set(&init, &"headers".into(), &headers).map_err(js_error)?;
+    js_sys::Reflect::set(&init, &"body".into(), &body.into()).map_err(js_error)?;
+
+    let response: web_sys::Response = JsFuture::from(fetch_with_init(url, &init))
+        .await
+        .map_err(js_error)?
+        .dyn_into()
+        .map_err(js_error)?;
+    if !response.ok() {
+        return Err(JsError::new(&format!("HTTP {} from {}", response.status(), url)));
+    }
+
+    let text = JsFuture::from(response.text().map_err(js_error)?)
+        .await
+        .map_err(js_error)?
+        .as_string()
+        .unwrap_or_default();
+    parse_response(&text).map_err(|e| JsError::new(&e))
+}
+
+fn js_error(value: JsValue) -> JsError {
+    let message = value
+        .dyn_ref::<js_sys::Error>()
+        .map(|e| String::from(e.message()))
+        .or_else(|| value.as_string())
+        .unwrap_or_else(|| format!("{:?}", value));
+    JsError::new(&message)
+}
+
+/// Extracts `result` from a JSON-RPC response body, or its error
+fn parse_response(body: &str) -> Result<Value, String> {
+    let mut response: Value = serde_json::from_str(body).map_err(|e| format!("Invalid JSON-RPC response: {}", e))?;
+
+    if let Some(error) = response.get("error").filter(|e| !e.is_null()) {
+        let code = error.get("code").and_then(Value::as_i64).unwrap_or_default();
+        let message = error.get("message").and_then(Value::as_str).unwrap_or("unknown error");
+        return Err(format!("RPC error {}: {}", code, message));
+    }
+    response
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| "JSON-RPC response has no result".to_string())
+}
+
+/// Converts a `0x` hex quantity of any size to a decimal string
+fn hex_to_decimal(quantity: &str) -> Result<String, String> {
+    let digits = quantity
+        .strip_prefix("0x")
+        .ok_or_else(|| format!("Expected a hex quantity, got {}", quantity))?;
+    if digits.is_empty() || digits.len() > 64 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(format!("Invalid hex quantity {}", quantity));
+    }
+
+    // Big-endian base-2^32 limbs, divided by 10 until zero
+    let mut limbs = Vec::new();
+    for chunk in digits.as_bytes().rchunks(8) {
+        // All ASCII hex digits, so every chunk is valid UTF-8 and parses
+        let chunk = std::str::from_utf8(chunk).map_err(|_| format!("Invalid hex quantity {}", quantity))?;
+        limbs.insert(0, u32::from_str_radix(chunk, 16).map_err(|_| format!("Invalid hex quantity {}", quantity))?);
+    }
+
+    let mut decimal = Vec::new();
+    while limbs.iter().any(|limb| *limb != 0) {
+        let mut remainder = 0u64;
+        for limb in limbs.iter_mut() {
+            let value = (remainder << 32) | *limb as u64;
+            *limb = (value / 10) as u32;
+            remainder = value % 10;
+        }
+        decimal.push(b'0' + remainder as u8);
+    }
+    if decimal.is_empty() {
+        decimal.push(b'0');
+    }
+    decimal.reverse();
+    Ok(String::from_utf8(decimal).unwrap())
+}
+
+fn hex_to_u64(quantity: &str) -> Result<u64, String> {
+    u64::from_str_radix(quantity.trim_start_matches("0x"), 16).map_err(|_| format!("Invalid hex quantity {}", quantity))
+}
+
+/// Builds a fee estimate from the base fee, priority fee and gas price
+fn fee_estimate(base_fee: &str, priority_fee: &str, gas_price: &str) -> Result<FeeEstimate, String> {
+    let parse = |quantity: &str| {
+        u128::from_str_radix(quantity.trim_start_matches("0x"), 16).map_err(|_| format!("Invalid hex quantity {}", quantity))
+    };
+    let base = parse(base_fee)?;
+    let priority = parse(priority_fee)?;
+    // Headroom for the base fee doubling over the next blocks
+    let max_fee = base
+        .checked_mul(2)
+        .and_then(|fee| fee.checked_add(priority))
+        .ok_or_else(|| "Fee overflow".to_string())?;
+
+    Ok(FeeEstimate {
+        base_fee_per_gas: base.to_string(),
+        max_priority_fee_per_gas: priority.to_string(),
+        max_fee_per_gas: max_fee.to_string(),
+        gas_price: parse(gas_price)?.to_string(),
+    })
+}
+
+fn result_str(value: &Value) -> Result<&str, JsError> {
+    value.as_str().ok_or_else(|| JsError::new("Expected a string result"))
+}
+
+// ============================================================================
+// EVM
+// ============================================================================
+
+/// JSON-RPC client for Ethereum and other EVM chains
+#[wasm_bindgen]
+pub struct EvmRpc {
+    url: String,
+    next_id: Cell<u64>,
+}
+
+#[wasm_bindgen]
+impl EvmRpc {
+    /// Create a client for an RPC endpoint URL
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> EvmRpc {
+        EvmRpc {
+            url: url.to_string(),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Get the endpoint URL
+    #[wasm_bindgen]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, JsError> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        rpc_call(&self.url, id, method, params).await
+    }
+
+    /// Get an account balance in wei (decimal string)
+    #[wasm_bindgen(js_name = getBalance)]
+    pub async fn get_balance(&self, address: String) -> Result<String, JsError> {
+        let result = self.call("eth_getBalance", json!([address, "latest"])).await?;
+        hex_to_decimal(result_str(&result)?).map_err(|e| JsError::new(&e))
+    }
+
+    /// Get the next nonce for an account
+    ///
+    /// Uses the `pending` block so transactions still in the mempool are
+    /// counted.
+    #[wasm_bindgen(js_name = getTransactionCount)]
+    pub async fn get_transaction_count(&self, address: String) -> Result<f64, JsError> {
+        let result = self.call("eth_getTransactionCount", json!([address, "pending"])).await?;
+        Ok(hex_to_u64(result_str(&result)?).map_err(|e| JsError::new(&e))? as f64)
+    }
+
+    /// Get the chain id
+    #[wasm_bindgen(js_name = getChainId)]
+    pub async fn get_chain_id(&self) -> Result<f64, JsError> {
+        let result = self.call("eth_chainId", json!([])).await?;
+        Ok(hex_to_u64(result_str(&result)?).map_err(|e| JsError::new(&e))? as f64)
+    }
+
+    /// Get the current gas price in wei (decimal string)
+    #[wasm_bindgen(js_name = getGasPrice)]
+    pub async fn get_gas_price(&self) -> Result<String, JsError> {
+        let result = self.call("eth_gasPrice", json!([])).await?;
+        hex_to_decimal(result_str(&result)?).map_err(|e| JsError::new(&e))
+    }
+
+    /// Suggest EIP-1559 fees from the latest block
+    ///
+    /// `maxFeePerGas` is twice the current base fee plus the priority fee.
+    #[wasm_bindgen(js_name = estimateFees)]
+    pub async fn estimate_fees(&self) -> Result<JsFeeEstimate, JsError> {
+        let block = self.call("eth_getBlockByNumber", json!(["latest", false])).await?;
+        let base_fee = block
+            .get("baseFeePerGas")
+            .and_then(Value::as_str)
+            .ok_or_else(|| JsError::new("Latest block has no base fee (pre-London chain?)"))?
+            .to_string();
+        let priority_fee = self.call("eth_maxPriorityFeePerGas", json!([])).await?;
+        let gas_price = self.call("eth_gasPrice", json!([])).await?;
+
+        let estimate = fee_estimate(&base_fee, result_str(&priority_fee)?, result_str(&gas_price)?)
+            .map_err(|e| JsError::new(&e))?;
+        to_js(&estimate)
+    }
+
+    /// Broadcast a signed transaction (the `raw` field returned by
+    /// `EthereumWallet.signTransaction`)
+    ///
+    /// Returns the transaction hash.
+    #[wasm_bindgen(js_name = sendRawTransaction)]
+    pub async fn send_raw_transaction(&self, raw_transaction: String) -> Result<String, JsError> {
+        let result = self.call("eth_sendRawTransaction", json!([raw_transaction])).await?;
+        Ok(result_str(&result)?.to_string())
+    }
+}
+
+// ============================================================================
+// Solana
+// ============================================================================
+
+/// JSON-RPC client for Solana
+#[wasm_bindgen]
+pub struct SolanaRpc {
+    url: String,
+    next_id: Cell<u64>,
+}
+
+#[wasm_bindgen]
+impl SolanaRpc {
+    /// Create a client for an RPC endpoint URL
+    #[wasm_bindgen(constructor)]
+    pub fn new(url: &str) -> SolanaRpc {
+        SolanaRpc {
+            url: url.to_string(),
+            next_id: Cell::new(1),
+        }
+    }
+
+    /// Get the endpoint URL
+    #[wasm_bindgen]
+    pub fn url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn call(&self, method: &str, params: Value) -> Result<Value, JsError> {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        rpc_call(&self.url, id, method, params).await
+    }
+
+    /// Get an account balance in lamports (decimal string)
+    #[wasm_bindgen(js_name = getBalance)]
+    pub async fn get_balance(&self, address: String) -> Result<String, JsError> {
+        let result = self
+            .call("getBalance", json!([address, { "commitment": "confirmed" }]))
+            .await?;
+        solana_lamports(&result).map_err(|e| JsError::new(&e))
+    }
+}
+
+/// Reads the lamports from a `getBalance` result (`{ context, value }`)
+fn solana_lamports(result: &Value) -> Result<String, String> {
+    result
+        .get("value")
+        .and_then(Value::as_u64)
+        .map(|lamports| lamports.to_string())
+        .ok_or_else(|| "Unexpected getBalance result".to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        assert_eq!(parse_response(r#"{"jsonrpc":"2.0","id":1,"result":"0x1"}"#).unwrap(), json!("0x1"));
+
+        let err = parse_response(r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32000,"message":"nonce too low"}}"#)
+            .unwrap_err();
+        assert_eq!(err, "RPC error -32000: nonce too low");
+
+        assert!(parse_response(r#"{"jsonrpc":"2.0","id":1}"#).is_err());
+        assert!(parse_response("<html>").is_err());
+    }
+
+    #[test]
+    fn test_hex_to_decimal() {
+        assert_eq!(hex_to_decimal("0x0").unwrap(), "0");
+        assert_eq!(hex_to_decimal("0xde0b6b3a7640000").unwrap(), "1000000000000000000");
+        assert_eq!(
+            hex_to_decimal("0xffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff").unwrap(),
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+        );
+        assert!(hex_to_decimal("123").is_err());
+        assert!(hex_to_decimal("0xzz").is_err());
+        // Multi-byte characters straddling a chunk boundary, and signs
+        assert!(hex_to_decimal("0x1234567é").is_err());
+        assert!(hex_to_decimal("0x+fff").is_err());
+    }
+
+    #[test]
+    fn test_fee_estimate() {
+        // 30 gwei base fee, 1.5 gwei tip
+        let estimate = fee_estimate("0x6fc23ac00", "0x59682f00", "0x7558bdb00").unwrap();
+        assert_eq!(estimate.base_fee_per_gas, "30000000000");
+        assert_eq!(estimate.max_priority_fee_per_gas, "1500000000");
+        assert_eq!(estimate.max_fee_per_gas, "61500000000");
+        assert_eq!(estimate.gas_price, "31500000000");
+        assert!(fee_estimate("0xzz", "0x0", "0x0").is_err());
+    }
+
+    #[test]
+    fn test_solana_lamports() {
+        let result = json!({ "context": { "slot": 1 }, "value": 2_500_000_000u64 });
+        assert_eq!(solana_lamports(&result).unwrap(), "2500000000");
+        assert!(solana_lamports(&json!({ "value": null })).is_err());
+    }
+}
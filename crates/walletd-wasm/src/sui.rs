@@ -0,0 +1,289 @@
+//! Sui wallet bindings
+//!
+//! Sui signs the BLAKE2b-256 hash of an *intent message*: a three byte
+//! intent (scope, version, app id) followed by the BCS-encoded payload.
+//! Signatures are sent to the RPC in the serialized
+//! `flag || signature || public_key` form, base64 encoded.
+
+use crate::types::{to_js, JsSignedTransaction, JsWalletExport, SignedTransaction, WalletExport};
+use base64::Engine;
+use bech32::{Bech32, Hrp};
+use blake2::digest::consts::U32;
+use blake2::{Blake2b, Digest};
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+type Blake2b256 = Blake2b<U32>;
+
+/// Signature scheme flag for Ed25519
+const ED25519_FLAG: u8 = 0x00;
+
+/// Human-readable part of Bech32 private keys (SIP-15)
+const PRIVATE_KEY_HRP: &str = "suiprivkey";
+
+/// Intent scope for `TransactionData`
+const INTENT_TRANSACTION: u8 = 0;
+
+/// Intent scope for personal messages
+const INTENT_PERSONAL_MESSAGE: u8 = 3;
+
+/// Sui wallet for browser environments
+#[wasm_bindgen]
+pub struct SuiWallet {
+    signing_key: ed25519_dalek::SigningKey,
+    address: [u8; 32],
+    exportable: bool,
+}
+
+#[wasm_bindgen]
+impl SuiWallet {
+    /// Create a new random Sui wallet
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Result<SuiWallet, JsError> {
+        let mut seed = Zeroizing::new([0u8; 32]);
+        getrandom::getrandom(seed.as_mut()).map_err(|e| JsError::new(&e.to_string()))?;
+
+        Ok(Self::from_seed(&seed))
+    }
+
+    /// Create wallet from mnemonic phrase
+    ///
+    /// # Arguments
+    /// * `mnemonic` - BIP-39 mnemonic phrase
+    /// * `account` - Account index, as in `m/44'/784'/{account}'/0'/0'` (Sui Wallet, Suiet)
+    #[wasm_bindgen(js_name = fromMnemonic)]
+    pub fn from_mnemonic(mnemonic: &str, account: u32) -> Result<SuiWallet, JsError> {
+        let seed = crate::mnemonic_seed(mnemonic)?;
+        let key = Zeroizing::new(crate::slip10_ed25519(&*seed, &[44, 784, account, 0, 0]));
+
+        Ok(Self::from_seed(&key))
+    }
+
+    /// Import a private key
+    ///
+    /// Accepts a Bech32 `suiprivkey1...` key (Sui Wallet export), a base64
+    /// `sui.keystore` entry, or 32 bytes of hex.
+    #[wasm_bindgen(js_name = fromPrivateKey)]
+    pub fn from_private_key(private_key: &str) -> Result<SuiWallet, JsError> {
+        let seed = Zeroizing::new(decode_private_key(private_key).map_err(|e| JsError::new(&e))?);
+        Ok(Self::from_seed(&seed))
+    }
+
+    pub(crate) fn from_seed(seed: &[u8; 32]) -> SuiWallet {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(seed);
+
+        let mut hasher = Blake2b256::new();
+        hasher.update([ED25519_FLAG]);
+        hasher.update(signing_key.verifying_key().as_bytes());
+
+        SuiWallet {
+            address: hasher.finalize().into(),
+            signing_key,
+            exportable: crate::KEY_EXPORT_DEFAULT,
+        }
+    }
+
+    /// Get the wallet address (`0x` + 64 hex characters)
+    #[wasm_bindgen]
+    pub fn address(&self) -> String {
+        format!("0x{}", hex::encode(self.address))
+    }
+
+    /// Get the public key as base64 (`flag || public_key`, as shown by `sui keytool`)
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> String {
+        let mut bytes = vec![ED25519_FLAG];
+        bytes.extend_from_slice(self.signing_key.verifying_key().as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(bytes)
+    }
+
+    /// Export the private key as Bech32 (`suiprivkey1...`)
+    ///
+    /// Throws if key export has been disabled.
+    #[wasm_bindgen(js_name = exportPrivateKey)]
+    pub fn export_private_key(&self) -> Result<String, JsError> {
+        let data = self.flagged_private_key()?;
+        Ok(bech32::encode::<Bech32>(Hrp::parse_unchecked(PRIVATE_KEY_HRP), &data)
+            .expect("33 bytes is within the Bech32 length limit"))
+    }
+
+    /// Export the key as a `sui.keystore` entry (base64 `flag || private_key`)
+    ///
+    /// Throws if key export has been disabled.
+    #[wasm_bindgen(js_name = toKeystore)]
+    pub fn to_keystore(&self) -> Result<String, JsError> {
+        let data = self.flagged_private_key()?;
+        Ok(base64::engine::general_purpose::STANDARD.encode(&*data))
+    }
+
+    fn flagged_private_key(&self) -> Result<Zeroizing<Vec<u8>>, JsError> {
+        crate::check_key_export(self.exportable)?;
+        let mut data = Zeroizing::new(vec![ED25519_FLAG]);
+        data.extend_from_slice(self.signing_key.as_bytes());
+        Ok(data)
+    }
+
+    /// Export the public wallet details (no private key)
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "sui",
+            address: self.address(),
+            public_key: self.public_key(),
+            network: None,
+        })
+    }
+
+    /// Sign BCS-encoded `TransactionData`
+    ///
+    /// Takes the bytes from `Transaction.build()`. `raw` is the base64
+    /// transaction and `signature` the base64 serialized signature, the two
+    /// arguments of `sui_executeTransactionBlock`; `hash` is the digest.
+    #[wasm_bindgen(js_name = signTransaction)]
+    pub fn sign_transaction(&self, tx_bytes: &[u8]) -> Result<JsSignedTransaction, JsError> {
+        to_js(&self.signed_transaction(tx_bytes))
+    }
+
+    fn signed_transaction(&self, tx_bytes: &[u8]) -> SignedTransaction {
+        SignedTransaction {
+            chain: "sui",
+            raw: base64::engine::general_purpose::STANDARD.encode(tx_bytes),
+            hash: Some(transaction_digest(tx_bytes)),
+            signature: self.sign_intent(INTENT_TRANSACTION, tx_bytes),
+        }
+    }
+
+    /// Sign a personal message (`signPersonalMessage` in the wallet standard)
+    ///
+    /// Returns the base64 serialized signature.
+    #[wasm_bindgen(js_name = signPersonalMessage)]
+    pub fn sign_personal_message(&self, message: &[u8]) -> String {
+        // Personal messages are signed as a BCS `vector<u8>`
+        let mut payload = crate::uleb128(message.len());
+        payload.extend_from_slice(message);
+        self.sign_intent(INTENT_PERSONAL_MESSAGE, &payload)
+    }
+
+    fn sign_intent(&self, scope: u8, payload: &[u8]) -> String {
+        use ed25519_dalek::Signer;
+
+        let mut hasher = Blake2b256::new();
+        hasher.update([scope, 0, 0]);
+        hasher.update(payload);
+        let digest = hasher.finalize();
+
+        let signature = self.signing_key.sign(&digest);
+        let mut serialized = vec![ED25519_FLAG];
+        serialized.extend_from_slice(&signature.to_bytes());
+        serialized.extend_from_slice(self.signing_key.verifying_key().as_bytes());
+        base64::engine::general_purpose::STANDARD.encode(serialized)
+    }
+
+    /// Permanently disable reading the private key from JS
+    ///
+    /// Signing keeps working. With the `no-key-export` feature every wallet
+    /// starts out this way.
+    #[wasm_bindgen(js_name = disableKeyExport)]
+    pub fn disable_key_export(&mut self) {
+        self.exportable = false;
+    }
+
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
+}
+
+/// Base58 transaction digest, as shown by explorers
+fn transaction_digest(tx_bytes: &[u8]) -> String {
+    let mut hasher = Blake2b256::new();
+    hasher.update(b"TransactionData::");
+    hasher.update(tx_bytes);
+    bs58::encode(hasher.finalize()).into_string()
+}
+
+/// Decodes any of the private key formats accepted by `fromPrivateKey`
+fn decode_private_key(private_key: &str) -> Result<[u8; 32], String> {
+    let private_key = private_key.trim();
+
+    let bytes = if private_key.starts_with(PRIVATE_KEY_HRP) {
+        let (hrp, data) = bech32::decode(private_key).map_err(|e| format!("Invalid Bech32 key: {}", e))?;
+        if hrp.as_str() != PRIVATE_KEY_HRP {
+            return Err(format!("Expected a {} key", PRIVATE_KEY_HRP));
+        }
+        data
+    } else if let Ok(bytes) = hex::decode(private_key.trim_start_matches("0x")) {
+        return bytes.try_into().map_err(|_| "Private key must be 32 bytes".to_string());
+    } else {
+        base64::engine::general_purpose::STANDARD
+            .decode(private_key)
+            .map_err(|_| "Private key is not Bech32, hex or base64".to_string())?
+    };
+
+    // `flag || private_key`; older keystores also append the public key
+    match bytes.split_first() {
+        Some((&ED25519_FLAG, rest)) if rest.len() == 32 || rest.len() == 64 => {
+            Ok(rest[..32].try_into().unwrap())
+        }
+        Some((&ED25519_FLAG, _)) => Err("Private key must be 32 bytes".to_string()),
+        Some((flag, _)) => Err(format!("Unsupported key scheme flag {:#04x}", flag)),
+        None => Err("Private key is empty".to_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::Verifier;
+
+    #[test]
+    fn test_mnemonic_address() {
+        // Vector from the Sui TypeScript SDK
+        let wallet = SuiWallet::from_mnemonic(
+            "film crazy soon outside stand loop subway crumble thrive popular green nuclear struggle pistol arm wife phrase warfare march wheat nephew ask sunny firm",
+            0,
+        )
+        .unwrap();
+        assert_eq!(
+            wallet.address(),
+            "0xa2d14fad60c56049ecf75246a481934691214ce413e6a8ae2fe6834c173a6133"
+        );
+    }
+
+    #[test]
+    fn test_private_key_formats() {
+        let mut wallet = SuiWallet::from_seed(&[7u8; 32]);
+        wallet.exportable = true;
+
+        let bech32 = wallet.export_private_key().unwrap();
+        assert!(bech32.starts_with("suiprivkey1"));
+        assert_eq!(decode_private_key(&bech32).unwrap(), [7u8; 32]);
+        assert_eq!(decode_private_key(&wallet.to_keystore().unwrap()).unwrap(), [7u8; 32]);
+        assert_eq!(decode_private_key(&hex::encode([7u8; 32])).unwrap(), [7u8; 32]);
+
+        let mut secp256k1 = vec![0x01];
+        secp256k1.extend_from_slice(&[7u8; 32]);
+        let entry = base64::engine::general_purpose::STANDARD.encode(secp256k1);
+        assert!(decode_private_key(&entry).is_err());
+    }
+
+    #[test]
+    fn test_transaction_signature() {
+        let wallet = SuiWallet::from_seed(&[7u8; 32]);
+        let tx_bytes = b"transaction data";
+
+        let signed = wallet.signed_transaction(tx_bytes);
+        assert_eq!(signed.raw, base64::engine::general_purpose::STANDARD.encode(tx_bytes));
+        let serialized = base64::engine::general_purpose::STANDARD.decode(&signed.signature).unwrap();
+        assert_eq!(serialized.len(), 97);
+        assert_eq!(serialized[0], ED25519_FLAG);
+        assert_eq!(&serialized[65..], wallet.signing_key.verifying_key().as_bytes());
+
+        let mut intent = vec![0u8, 0, 0];
+        intent.extend_from_slice(tx_bytes);
+        let digest = Blake2b256::digest(&intent);
+        let signature = ed25519_dalek::Signature::from_slice(&serialized[1..65]).unwrap();
+        assert!(wallet.signing_key.verifying_key().verify(&digest, &signature).is_ok());
+    }
+}
@@ -0,0 +1,668 @@
+//! TON wallet bindings
+//!
+//! Implements just enough of TON's cell and bag-of-cells (BoC) format to
+//! derive wallet v3r2 addresses and build signed transfers in the browser.
+
+use crate::types::{to_js, JsSignedTransaction, JsWalletExport, SignedTransaction, WalletExport};
+use base64::Engine;
+use sha2::{Digest, Sha256};
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use zeroize::Zeroizing;
+
+/// Wallet v3r2 contract code (single-cell BoC)
+const WALLET_V3R2_CODE: &str = "b5ee9c724101010100710000deff0020dd2082014c97ba218201339cbab19f71b0ed44d0d31fd31f31d70bffe304e0a4f2608308d71820d31fd31fd31ff82313bbf263ed44d0d31fd31fd3ffd15132baf2a15144baf2a204f901541055f910f2a3f8009320d74a96d307d402fb00e8d101a4c8cb1fcb1fcbffc9ed5410bd6dad";
+
+/// Default subwallet id on the basechain
+const DEFAULT_WALLET_ID: u32 = 698983191;
+
+/// Send mode: pay fees separately, ignore action errors
+const SEND_MODE: u8 = 3;
+
+// ============================================================================
+// TON Wallet
+// ============================================================================
+
+/// TON wallet (v3r2 contract) for browser environments
+#[wasm_bindgen]
+pub struct TonWallet {
+    signing_key: ed25519_dalek::SigningKey,
+    wallet_id: u32,
+    address: [u8; 32],
+    testnet: bool,
+}
+
+#[wasm_bindgen]
+impl TonWallet {
+    /// Generate a new 24-word TON mnemonic
+    ///
+    /// TON mnemonics use the BIP-39 word list but not its checksum; they are
+    /// not interchangeable with BIP-39 phrases.
+    #[wasm_bindgen(js_name = generateMnemonic)]
+    pub fn generate_mnemonic() -> Result<String, JsError> {
+        let words = bip39::Language::English.word_list();
+        loop {
+            let mut entropy = [0u8; 48];
+            getrandom::getrandom(&mut entropy).map_err(|e| JsError::new(&e.to_string()))?;
+
+            let phrase = entropy
+                .chunks(2)
+                .map(|pair| words[(u16::from_be_bytes([pair[0], pair[1]]) & 0x7ff) as usize])
+                .collect::<Vec<_>>()
+                .join(" ");
+            if is_basic_seed(&mnemonic_entropy(&phrase, "")) {
+                return Ok(phrase);
+            }
+        }
+    }
+
+    /// Create wallet from a 24-word TON mnemonic
+    ///
+    /// # Arguments
+    /// * `mnemonic` - TON mnemonic (as shown by Tonkeeper, Tonhub, Telegram Wallet)
+    /// * `testnet` - Produce testnet-flagged friendly addresses
+    #[wasm_bindgen(js_name = fromMnemonic)]
+    pub fn from_mnemonic(mnemonic: &str, testnet: bool) -> Result<TonWallet, JsError> {
+        let phrase = normalize_mnemonic(mnemonic).map_err(|e| JsError::new(&e))?;
+        let private_key = Zeroizing::new(mnemonic_to_private_key(&phrase));
+        Ok(Self::from_seed(&private_key, DEFAULT_WALLET_ID, testnet))
+    }
+
+    /// Create wallet from a 32-byte private key (hex)
+    #[wasm_bindgen(js_name = fromPrivateKey)]
+    pub fn from_private_key(private_key_hex: &str, testnet: bool) -> Result<TonWallet, JsError> {
+        let bytes = Zeroizing::new(
+            hex::decode(private_key_hex.trim_start_matches("0x"))
+                .map_err(|e| JsError::new(&format!("Invalid hex: {}", e)))?,
+        );
+        let seed: Zeroizing<[u8; 32]> = Zeroizing::new(
+            bytes
+                .as_slice()
+                .try_into()
+                .map_err(|_| JsError::new("Private key must be 32 bytes"))?,
+        );
+
+        Ok(Self::from_seed(&seed, DEFAULT_WALLET_ID, testnet))
+    }
+
+    pub(crate) fn from_seed(seed: &[u8; 32], wallet_id: u32, testnet: bool) -> TonWallet {
+        let signing_key = ed25519_dalek::SigningKey::from_bytes(seed);
+        let state_init = state_init(&signing_key.verifying_key().to_bytes(), wallet_id);
+        TonWallet {
+            address: state_init.hash(),
+            signing_key,
+            wallet_id,
+            testnet,
+        }
+    }
+
+    /// Get the user-friendly address
+    ///
+    /// Wallets are conventionally shown non-bounceable (`UQ...`) so that funds
+    /// sent before the contract is deployed are not bounced back.
+    #[wasm_bindgen]
+    pub fn address(&self) -> String {
+        friendly_address(0, &self.address, false, self.testnet)
+    }
+
+    /// Get the bounceable friendly address (`EQ...`)
+    #[wasm_bindgen(js_name = bounceableAddress)]
+    pub fn bounceable_address(&self) -> String {
+        friendly_address(0, &self.address, true, self.testnet)
+    }
+
+    /// Get the raw address (`0:<hex>`)
+    #[wasm_bindgen(js_name = rawAddress)]
+    pub fn raw_address(&self) -> String {
+        format!("0:{}", hex::encode(self.address))
+    }
+
+    /// Get the public key as hex
+    #[wasm_bindgen(js_name = publicKey)]
+    pub fn public_key(&self) -> String {
+        hex::encode(self.signing_key.verifying_key().to_bytes())
+    }
+
+    /// Get the subwallet id
+    #[wasm_bindgen(js_name = walletId)]
+    pub fn wallet_id(&self) -> u32 {
+        self.wallet_id
+    }
+
+    /// Export the public wallet details (no private key)
+    #[wasm_bindgen(js_name = toJson)]
+    pub fn to_json(&self) -> Result<JsWalletExport, JsError> {
+        to_js(&WalletExport {
+            chain: "ton",
+            address: self.address(),
+            public_key: self.public_key(),
+            network: Some(if self.testnet { "testnet" } else { "mainnet" }.to_string()),
+        })
+    }
+
+    /// Sign arbitrary bytes (returns the 64-byte signature)
+    #[wasm_bindgen(js_name = signMessage)]
+    pub fn sign_message(&self, message: &[u8]) -> Vec<u8> {
+        use ed25519_dalek::Signer;
+        self.signing_key.sign(message).to_bytes().to_vec()
+    }
+
+    /// Build and sign a TON transfer
+    ///
+    /// # Arguments
+    /// * `to` - Destination (friendly or raw address); bounce follows its flag
+    /// * `amount_nano` - Amount in nanotons (as string for precision)
+    /// * `seqno` - Current wallet seqno (0 deploys the wallet with this transfer)
+    /// * `valid_until` - Unix time after which the message is rejected
+    /// * `comment` - Optional text comment
+    ///
+    /// # Returns
+    /// `raw` is the base64 BoC of the external message, ready for
+    /// toncenter's `sendBoc`; `hash` is the hex hash of that message.
+    #[wasm_bindgen(js_name = createTransfer)]
+    pub fn create_transfer(
+        &self,
+        to: &str,
+        amount_nano: &str,
+        seqno: u32,
+        valid_until: u32,
+        comment: Option<String>,
+    ) -> Result<JsSignedTransaction, JsError> {
+        let amount: u128 = amount_nano
+            .parse()
+            .map_err(|e| JsError::new(&format!("Invalid amount: {}", e)))?;
+        let (workchain, hash, bounce) = parse_address(to).map_err(|e| JsError::new(&e))?;
+
+        let signed = self
+            .signed_transfer(workchain, &hash, bounce, amount, seqno, valid_until, comment.as_deref())
+            .map_err(|e| JsError::new(&e))?;
+        to_js(&signed)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn signed_transfer(
+        &self,
+        workchain: i8,
+        to: &[u8; 32],
+        bounce: bool,
+        amount: u128,
+        seqno: u32,
+        valid_until: u32,
+        comment: Option<&str>,
+    ) -> Result<SignedTransaction, String> {
+        // Internal message to the recipient
+        let mut internal = CellBuilder::new();
+        internal
+            .bit(false) // int_msg_info$0
+            .bit(true) // ihr_disabled
+            .bit(bounce)
+            .bit(false) // bounced
+            .uint(0, 2) // src: addr_none
+            .address(workchain, to)
+            .coins(amount)?
+            .bit(false) // no extra currencies
+            .coins(0)? // ihr_fee
+            .coins(0)? // fwd_fee
+            .uint(0, 64) // created_lt
+            .uint(0, 32) // created_at
+            .bit(false); // no state init
+        match comment {
+            Some(text) => {
+                let mut body = CellBuilder::new();
+                body.uint(0, 32).bytes(text.as_bytes())?;
+                internal.bit(true).reference(body.build())?;
+            }
+            None => {
+                internal.bit(false);
+            }
+        }
+
+        // Message signed by the wallet
+        let mut signing = CellBuilder::new();
+        signing
+            .uint(self.wallet_id as u64, 32)
+            .uint(valid_until as u64, 32)
+            .uint(seqno as u64, 32)
+            .uint(SEND_MODE as u64, 8)
+            .reference(internal.build())?;
+        let unsigned = signing.build();
+        let signature = self.sign_message(&unsigned.hash());
+
+        let mut body = CellBuilder::new();
+        body.bytes(&signature)?.append(&unsigned)?;
+
+        // External message carrying the signed body
+        let mut external = CellBuilder::new();
+        external
+            .uint(0b10, 2) // ext_in_msg_info$10
+            .uint(0, 2) // src: addr_none
+            .address(0, &self.address)
+            .coins(0)?; // import_fee
+        if seqno == 0 {
+            let state_init = state_init(&self.signing_key.verifying_key().to_bytes(), self.wallet_id);
+            external.bit(true).bit(true).reference(state_init)?;
+        } else {
+            external.bit(false);
+        }
+        external.bit(true).reference(body.build())?;
+
+        let external = external.build();
+        Ok(SignedTransaction {
+            chain: "ton",
+            raw: base64::engine::general_purpose::STANDARD.encode(serialize_boc(&external)),
+            hash: Some(hex::encode(external.hash())),
+            signature: hex::encode(signature),
+        })
+    }
+
+    /// Wipe the private key from memory and invalidate this object
+    ///
+    /// Equivalent to `free()`; any later call on the object throws.
+    #[wasm_bindgen]
+    pub fn destroy(self) {}
+}
+
+// ============================================================================
+// Mnemonic
+// ============================================================================
+
+fn normalize_mnemonic(mnemonic: &str) -> Result<String, String> {
+    let words: Vec<&str> = mnemonic.split_whitespace().collect();
+    if words.len() != 24 {
+        return Err("TON mnemonic must be 24 words".to_string());
+    }
+    if let Some(word) = words
+        .iter()
+        .find(|w| bip39::Language::English.find_word(w).is_none())
+    {
+        return Err(format!("Unknown mnemonic word: {}", word));
+    }
+
+    let phrase = words.join(" ");
+    if !is_basic_seed(&mnemonic_entropy(&phrase, "")) {
+        return Err("Invalid TON mnemonic".to_string());
+    }
+    Ok(phrase)
+}
+
+/// HMAC-SHA512 of the phrase keyed by itself, with the password as message
+fn mnemonic_entropy(phrase: &str, password: &str) -> [u8; 64] {
+    use hmac::{Hmac, Mac};
+    let mut mac = Hmac::<sha2::Sha512>::new_from_slice(phrase.as_bytes())
+        .expect("HMAC accepts any key size");
+    mac.update(password.as_bytes());
+    mac.finalize().into_bytes().into()
+}
+
+/// A passwordless TON mnemonic must pass this check (first byte of a cheap
+/// PBKDF2 over the entropy is zero)
+fn is_basic_seed(entropy: &[u8; 64]) -> bool {
+    let mut out = [0u8; 64];
+    pbkdf2::pbkdf2_hmac::<sha2::Sha512>(entropy, b"TON seed version", 100_000 / 256, &mut out);
+    out[0] == 0
+}
+
+fn mnemonic_to_private_key(phrase: &str) -> [u8; 32] {
+    let mut seed = Zeroizing::new([0u8; 64]);
+    let entropy = Zeroizing::new(mnemonic_entropy(phrase, ""));
+    pbkdf2::pbkdf2_hmac::<sha2::Sha512>(&*entropy, b"TON default seed", 100_000, seed.as_mut());
+    let mut key = [0u8; 32];
+    key.copy_from_slice(&seed[..32]);
+    key
+}
+
+// ============================================================================
+// Addresses
+// ============================================================================
+
+fn state_init(public_key: &[u8; 32], wallet_id: u32) -> Rc<Cell> {
+    let code = parse_single_cell_boc(&hex::decode(WALLET_V3R2_CODE).expect("valid hex"));
+
+    let mut data = CellBuilder::new();
+    data.uint(0, 32) // seqno
+        .uint(wallet_id as u64, 32);
+    data.bytes(public_key).expect("fits in a cell");
+
+    let mut state_init = CellBuilder::new();
+    state_init
+        .bit(false) // split_depth
+        .bit(false) // special
+        .bit(true) // code
+        .bit(true) // data
+        .bit(false); // library
+    state_init.reference(code).expect("fits in a cell");
+    state_init.reference(data.build()).expect("fits in a cell");
+    state_init.build()
+}
+
+fn friendly_address(workchain: i8, hash: &[u8; 32], bounceable: bool, testnet: bool) -> String {
+    let mut tag = if bounceable { 0x11 } else { 0x51 };
+    if testnet {
+        tag |= 0x80;
+    }
+    let mut bytes = vec![tag, workchain as u8];
+    bytes.extend_from_slice(hash);
+    bytes.extend_from_slice(&crc16_xmodem(&bytes).to_be_bytes());
+    base64::engine::general_purpose::URL_SAFE.encode(bytes)
+}
+
+/// Parses a friendly or raw address into (workchain, hash, bounceable)
+fn parse_address(address: &str) -> Result<(i8, [u8; 32], bool), String> {
+    if let Some((workchain, hash)) = address.split_once(':') {
+        let workchain: i8 = workchain.parse().map_err(|_| "Invalid workchain".to_string())?;
+        let hash: [u8; 32] = hex::decode(hash)
+            .ok()
+            .and_then(|h| h.try_into().ok())
+            .ok_or("Invalid raw address")?;
+        return Ok((workchain, hash, true));
+    }
+
+    let bytes = base64::engine::general_purpose::URL_SAFE
+        .decode(address.replace('+', "-").replace('/', "_"))
+        .map_err(|_| "Invalid address encoding".to_string())?;
+    if bytes.len() != 36 {
+        return Err("Invalid address length".to_string());
+    }
+    if crc16_xmodem(&bytes[..34]).to_be_bytes() != bytes[34..] {
+        return Err("Invalid address checksum".to_string());
+    }
+
+    let bounceable = match bytes[0] & 0x7f {
+        0x11 => true,
+        0x51 => false,
+        _ => return Err("Invalid address tag".to_string()),
+    };
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&bytes[2..34]);
+    Ok((bytes[1] as i8, hash, bounceable))
+}
+
+fn crc16_xmodem(data: &[u8]) -> u16 {
+    let mut crc = 0u16;
+    for byte in data {
+        crc ^= (*byte as u16) << 8;
+        for _ in 0..8 {
+            crc = if crc & 0x8000 != 0 { (crc << 1) ^ 0x1021 } else { crc << 1 };
+        }
+    }
+    crc
+}
+
+// ============================================================================
+// Cells
+// ============================================================================
+
+/// An ordinary TON cell: up to 1023 data bits and 4 references
+#[derive(Debug)]
+pub(crate) struct Cell {
+    data: Vec<u8>,
+    bit_len: usize,
+    refs: Vec<Rc<Cell>>,
+}
+
+impl Cell {
+    fn descriptors(&self) -> [u8; 2] {
+        let d1 = self.refs.len() as u8;
+        let d2 = (self.bit_len / 8 + self.bit_len.div_ceil(8)) as u8;
+        [d1, d2]
+    }
+
+    /// Data bytes with the completion tag for a partial last byte
+    fn padded_data(&self) -> Vec<u8> {
+        let mut data = self.data[..self.bit_len.div_ceil(8)].to_vec();
+        if !self.bit_len.is_multiple_of(8) {
+            let last = data.last_mut().expect("non-empty");
+            *last |= 0x80 >> (self.bit_len % 8);
+        }
+        data
+    }
+
+    fn depth(&self) -> u16 {
+        self.refs.iter().map(|r| r.depth() + 1).max().unwrap_or(0)
+    }
+
+    /// Representation hash
+    pub(crate) fn hash(&self) -> [u8; 32] {
+        let mut hasher = Sha256::new();
+        hasher.update(self.descriptors());
+        hasher.update(self.padded_data());
+        for child in &self.refs {
+            hasher.update(child.depth().to_be_bytes());
+        }
+        for child in &self.refs {
+            hasher.update(child.hash());
+        }
+        hasher.finalize().into()
+    }
+}
+
+/// Bit-level cell writer
+pub(crate) struct CellBuilder {
+    data: Vec<u8>,
+    bit_len: usize,
+    refs: Vec<Rc<Cell>>,
+}
+
+impl CellBuilder {
+    pub(crate) fn new() -> Self {
+        Self {
+            data: vec![0; 128],
+            bit_len: 0,
+            refs: Vec::new(),
+        }
+    }
+
+    pub(crate) fn bit(&mut self, bit: bool) -> &mut Self {
+        assert!(self.bit_len < 1023, "cell overflow");
+        if bit {
+            self.data[self.bit_len / 8] |= 0x80 >> (self.bit_len % 8);
+        }
+        self.bit_len += 1;
+        self
+    }
+
+    pub(crate) fn uint(&mut self, value: u64, bits: usize) -> &mut Self {
+        for i in (0..bits).rev() {
+            self.bit((value >> i) & 1 == 1);
+        }
+        self
+    }
+
+    pub(crate) fn bytes(&mut self, bytes: &[u8]) -> Result<&mut Self, String> {
+        if self.bit_len + bytes.len() * 8 > 1023 {
+            return Err("Data does not fit in a cell".to_string());
+        }
+        for byte in bytes {
+            self.uint(*byte as u64, 8);
+        }
+        Ok(self)
+    }
+
+    /// Stores `Grams` (VarUInteger 16)
+    pub(crate) fn coins(&mut self, amount: u128) -> Result<&mut Self, String> {
+        let len = 16 - (amount.leading_zeros() / 8) as usize;
+        if len > 15 {
+            return Err("Amount too large".to_string());
+        }
+        self.uint(len as u64, 4);
+        self.bytes(&amount.to_be_bytes()[16 - len..])
+    }
+
+    /// Stores `addr_std` without anycast
+    pub(crate) fn address(&mut self, workchain: i8, hash: &[u8; 32]) -> &mut Self {
+        self.uint(0b100, 3).uint(workchain as u8 as u64, 8);
+        for byte in hash {
+            self.uint(*byte as u64, 8);
+        }
+        self
+    }
+
+    pub(crate) fn reference(&mut self, cell: Rc<Cell>) -> Result<&mut Self, String> {
+        if self.refs.len() == 4 {
+            return Err("Cell already has 4 references".to_string());
+        }
+        self.refs.push(cell);
+        Ok(self)
+    }
+
+    /// Copies the bits and references of another cell
+    pub(crate) fn append(&mut self, cell: &Cell) -> Result<&mut Self, String> {
+        if self.bit_len + cell.bit_len > 1023 || self.refs.len() + cell.refs.len() > 4 {
+            return Err("Data does not fit in a cell".to_string());
+        }
+        for i in 0..cell.bit_len {
+            self.bit(cell.data[i / 8] & (0x80 >> (i % 8)) != 0);
+        }
+        self.refs.extend(cell.refs.iter().cloned());
+        Ok(self)
+    }
+
+    pub(crate) fn build(&self) -> Rc<Cell> {
+        Rc::new(Cell {
+            data: self.data[..self.bit_len.div_ceil(8)].to_vec(),
+            bit_len: self.bit_len,
+            refs: self.refs.clone(),
+        })
+    }
+}
+
+/// Serializes a cell tree as a BoC with a CRC32-C trailer
+pub(crate) fn serialize_boc(root: &Rc<Cell>) -> Vec<u8> {
+    // Parents must come before children; reused cells are stored once
+    let mut order: Vec<Rc<Cell>> = Vec::new();
+    fn visit(cell: &Rc<Cell>, order: &mut Vec<Rc<Cell>>) {
+        if let Some(pos) = order.iter().position(|c| c.hash() == cell.hash()) {
+            let existing = order.remove(pos);
+            order.push(existing);
+        } else {
+            order.push(cell.clone());
+        }
+        for child in &cell.refs {
+            visit(child, order);
+        }
+    }
+    visit(root, &mut order);
+
+    let index_of = |cell: &Rc<Cell>| order.iter().position(|c| c.hash() == cell.hash()).expect("visited");
+    let mut cells_data = Vec::new();
+    for cell in &order {
+        cells_data.extend_from_slice(&cell.descriptors());
+        cells_data.extend_from_slice(&cell.padded_data());
+        for child in &cell.refs {
+            cells_data.push(index_of(child) as u8);
+        }
+    }
+
+    let off_bytes = (usize::BITS - cells_data.len().leading_zeros()).div_ceil(8).max(1) as usize;
+    let mut boc = vec![0xb5, 0xee, 0x9c, 0x72];
+    boc.push(0x40 | 1); // has_crc32c, 1-byte cell references
+    boc.push(off_bytes as u8);
+    boc.push(order.len() as u8); // cells
+    boc.push(1); // roots
+    boc.push(0); // absent
+    boc.extend_from_slice(&cells_data.len().to_be_bytes()[usize::BITS as usize / 8 - off_bytes..]);
+    boc.push(0); // root index
+    boc.extend_from_slice(&cells_data);
+    let crc = crc32c(&boc);
+    boc.extend_from_slice(&crc.to_le_bytes());
+    boc
+}
+
+/// Parses a BoC holding a single cell with no references
+fn parse_single_cell_boc(boc: &[u8]) -> Rc<Cell> {
+    let size = (boc[4] & 7) as usize;
+    let off_bytes = boc[5] as usize;
+    let start = 6 + size * 4 + off_bytes;
+    let [_, d2] = [boc[start], boc[start + 1]];
+    let byte_len = (d2 as usize).div_ceil(2);
+    let mut data = boc[start + 2..start + 2 + byte_len].to_vec();
+
+    let bit_len = if d2 % 2 == 0 {
+        byte_len * 8
+    } else {
+        // Strip the completion tag
+        let last = data.last_mut().expect("non-empty");
+        let trailing = last.trailing_zeros() as usize;
+        *last &= !(1 << trailing);
+        byte_len * 8 - trailing - 1
+    };
+    Rc::new(Cell {
+        data,
+        bit_len,
+        refs: Vec::new(),
+    })
+}
+
+fn crc32c(data: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in data {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 != 0 { (crc >> 1) ^ 0x82f6_3b78 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wallet_code_hash() {
+        let code = parse_single_cell_boc(&hex::decode(WALLET_V3R2_CODE).unwrap());
+        assert_eq!(
+            hex::encode(code.hash()),
+            "84dafa449f98a6987789ba232358072bc0f76dc4524002a5d0918b9a75d2d599"
+        );
+
+        // Re-serializing reproduces the canonical BoC, CRC included
+        assert_eq!(hex::encode(serialize_boc(&code)), WALLET_V3R2_CODE);
+    }
+
+    #[test]
+    fn test_friendly_address_roundtrip() {
+        let wallet = TonWallet::from_seed(&[5u8; 32], DEFAULT_WALLET_ID, false);
+
+        let address = wallet.address();
+        assert!(address.starts_with("UQ"));
+        assert!(wallet.bounceable_address().starts_with("EQ"));
+
+        let (workchain, hash, bounceable) = parse_address(&address).unwrap();
+        assert_eq!((workchain, hash, bounceable), (0, wallet.address, false));
+        assert_eq!(parse_address(&wallet.raw_address()).unwrap().1, wallet.address);
+
+        let mut corrupted = address.into_bytes();
+        corrupted[10] = if corrupted[10] == b'A' { b'B' } else { b'A' };
+        assert!(parse_address(&String::from_utf8(corrupted).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_transfer_boc() {
+        let wallet = TonWallet::from_seed(&[5u8; 32], DEFAULT_WALLET_ID, false);
+        let boc = |signed: SignedTransaction| base64::engine::general_purpose::STANDARD.decode(signed.raw).unwrap();
+
+        let deploy = boc(wallet
+            .signed_transfer(0, &[1u8; 32], false, 1_000_000_000, 0, 1_700_000_000, Some("hi"))
+            .unwrap());
+        let transfer = boc(wallet
+            .signed_transfer(0, &[1u8; 32], false, 1_000_000_000, 1, 1_700_000_000, None)
+            .unwrap());
+
+        assert_eq!(&deploy[..4], &[0xb5, 0xee, 0x9c, 0x72]);
+        // external, body, internal (+ comment, state init, code, data when deploying)
+        assert_eq!(deploy[6], 7);
+        assert_eq!(transfer[6], 3);
+        assert_eq!(crc32c(&transfer[..transfer.len() - 4]).to_le_bytes(), transfer[transfer.len() - 4..]);
+    }
+
+    #[test]
+    fn test_mnemonic_validation() {
+        let phrase = ["abandon"; 24].join(" ");
+        let basic = is_basic_seed(&mnemonic_entropy(&phrase, ""));
+        assert_eq!(normalize_mnemonic(&phrase).is_ok(), basic);
+
+        assert!(normalize_mnemonic("abandon abandon").is_err());
+        assert!(normalize_mnemonic(&["notaword"; 24].join(" ")).is_err());
+    }
+}
@@ -0,0 +1,193 @@
+//! Structured values returned to JavaScript
+//!
+//! Each struct has a matching interface in the TypeScript section below.
+//! Bindings return them through extern types carrying that interface name,
+//! so the generated `.d.ts` is typed instead of `any`.
+
+use serde::{Deserialize, Serialize};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_INTERFACES: &'static str = r#"
+export type Chain = "ethereum" | "bitcoin" | "solana" | "sui" | "aptos" | "ton";
+
+export interface SignedTransaction {
+  chain: Chain;
+  /** Signed transaction ready to broadcast: 0x hex (EVM, Aptos) or base64 (Solana, Sui, TON) */
+  raw: string;
+  /** Transaction hash or id, when it can be computed offline */
+  hash?: string;
+  /** This wallet's signature, in the chain's usual encoding */
+  signature: string;
+}
+
+export interface WalletExport {
+  chain: Chain;
+  address: string;
+  publicKey: string;
+  network?: string;
+}
+
+export interface FeeEstimate {
+  /** Base fee of the latest block, in wei */
+  baseFeePerGas: string;
+  /** Suggested priority fee, in wei */
+  maxPriorityFeePerGas: string;
+  /** Suggested fee cap (2 x base fee + priority fee), in wei */
+  maxFeePerGas: string;
+  /** Legacy gas price, in wei */
+  gasPrice: string;
+}
+
+export interface KeystoreEntry {
+  name: string;
+  chain: Chain;
+  address: string;
+}
+
+export interface PaymentOptions {
+  /** Merchant or recipient name (BIP-21, Solana Pay) */
+  label?: string;
+  /** Note shown to the payer (BIP-21, Solana Pay) */
+  message?: string;
+  /** On-chain memo (Solana Pay `memo`, TON `text`) */
+  memo?: string;
+  /** EVM chain id (EIP-681) */
+  chainId?: number;
+  /** ERC-20 contract (EIP-681), SPL mint (Solana Pay) or jetton master (TON) */
+  token?: string;
+  /** Token decimals, required to convert an ERC-20 or jetton amount */
+  decimals?: number;
+  /** Reference public keys for locating the payment (Solana Pay) */
+  reference?: string[];
+}
+
+export interface PaymentRequest {
+  chain: "bitcoin" | "ethereum" | "solana" | "ton";
+  /** Recipient address */
+  address: string;
+  /** Amount in whole units (BTC, ETH, SOL, TON or tokens), when known */
+  amount?: string;
+  /** Amount in base units (sats, wei, token units), when known */
+  baseAmount?: string;
+  chainId?: number;
+  token?: string;
+  label?: string;
+  message?: string;
+  memo?: string;
+  reference?: string[];
+}
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    /// JS object shaped like [`SignedTransaction`]
+    #[wasm_bindgen(typescript_type = "SignedTransaction")]
+    pub type JsSignedTransaction;
+
+    /// JS object shaped like [`WalletExport`]
+    #[wasm_bindgen(typescript_type = "WalletExport")]
+    pub type JsWalletExport;
+
+    /// JS object shaped like [`FeeEstimate`]
+    #[wasm_bindgen(typescript_type = "FeeEstimate")]
+    pub type JsFeeEstimate;
+
+    /// JS array of [`KeystoreEntry`] objects
+    #[wasm_bindgen(typescript_type = "KeystoreEntry[]")]
+    pub type JsKeystoreEntries;
+
+    /// JS object shaped like [`PaymentOptions`]
+    #[wasm_bindgen(typescript_type = "PaymentOptions")]
+    pub type JsPaymentOptions;
+
+    /// JS object shaped like [`PaymentRequest`]
+    #[wasm_bindgen(typescript_type = "PaymentRequest")]
+    pub type JsPaymentRequest;
+}
+
+/// A signed transaction, ready to broadcast
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct SignedTransaction {
+    pub chain: &'static str,
+    pub raw: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hash: Option<String>,
+    pub signature: String,
+}
+
+/// Public wallet details, safe to persist or display
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct WalletExport {
+    pub chain: &'static str,
+    pub address: String,
+    pub public_key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+}
+
+/// EIP-1559 fee suggestion, all values in wei
+#[derive(Debug, Clone, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FeeEstimate {
+    pub base_fee_per_gas: String,
+    pub max_priority_fee_per_gas: String,
+    pub max_fee_per_gas: String,
+    pub gas_price: String,
+}
+
+/// Summary of a stored key, as returned by `Keystore.list()`
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub(crate) struct KeystoreEntry {
+    pub name: String,
+    pub chain: String,
+    pub address: String,
+}
+
+/// Optional fields for `makePaymentUri`
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct PaymentOptions {
+    pub label: Option<String>,
+    pub message: Option<String>,
+    pub memo: Option<String>,
+    pub chain_id: Option<u64>,
+    pub token: Option<String>,
+    pub decimals: Option<u8>,
+    pub reference: Vec<String>,
+}
+
+/// A decoded payment URI
+#[derive(Debug, Clone, Default, Serialize, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct PaymentRequest {
+    pub chain: &'static str,
+    pub address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_id: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reference: Vec<String>,
+}
+
+/// Converts a value to a plain JS object typed as `J`
+pub(crate) fn to_js<T: Serialize + ?Sized, J: JsCast>(value: &T) -> Result<J, JsError> {
+    let value = value
+        .serialize(&serde_wasm_bindgen::Serializer::json_compatible())
+        .map_err(|e| JsError::new(&format!("Serialization error: {}", e)))?;
+    Ok(value.unchecked_into())
+}
@@ -6,6 +6,91 @@
  */
 export function init(): Promise<void>;
 
+export type Chain = "ethereum" | "bitcoin" | "solana" | "sui" | "aptos" | "ton";
+
+/**
+ * A signed transaction, ready to broadcast
+ */
+export interface SignedTransaction {
+  chain: Chain;
+  /** Signed transaction: 0x hex (EVM, Aptos) or base64 (Solana, Sui, TON) */
+  raw: string;
+  /** Transaction hash or id, when it can be computed offline */
+  hash?: string;
+  /** This wallet's signature, in the chain's usual encoding */
+  signature: string;
+}
+
+/**
+ * Public wallet details (never includes the private key)
+ */
+export interface WalletExport {
+  chain: Chain;
+  address: string;
+  publicKey: string;
+  /** "mainnet" or "testnet", for Bitcoin and TON */
+  network?: string;
+}
+
+/**
+ * EIP-1559 fee suggestion, all values in wei (decimal strings)
+ */
+export interface FeeEstimate {
+  baseFeePerGas: string;
+  maxPriorityFeePerGas: string;
+  /** 2 x base fee + priority fee */
+  maxFeePerGas: string;
+  /** Legacy gas price */
+  gasPrice: string;
+}
+
+/**
+ * Keystore entry summary
+ */
+export interface KeystoreEntry {
+  name: string;
+  chain: Chain;
+  address: string;
+}
+
+/**
+ * Optional fields for makePaymentUri
+ */
+export interface PaymentOptions {
+  /** Merchant or recipient name (BIP-21, Solana Pay) */
+  label?: string;
+  /** Note shown to the payer (BIP-21, Solana Pay) */
+  message?: string;
+  /** On-chain memo (Solana Pay) */
+  memo?: string;
+  /** EVM chain id (EIP-681) */
+  chainId?: number;
+  /** ERC-20 contract (EIP-681) or SPL mint (Solana Pay) */
+  token?: string;
+  /** Token decimals, required for an ERC-20 amount */
+  decimals?: number;
+  /** Reference public keys for locating the payment (Solana Pay) */
+  reference?: string[];
+}
+
+/**
+ * A decoded payment URI
+ */
+export interface PaymentRequest {
+  chain: "bitcoin" | "ethereum" | "solana";
+  address: string;
+  /** Amount in whole units, when known */
+  amount?: string;
+  /** Amount in base units (sats, wei, token units), when known */
+  baseAmount?: string;
+  chainId?: number;
+  token?: string;
+  label?: string;
+  message?: string;
+  memo?: string;
+  reference?: string[];
+}
+
 /**
  * Get the WalletD WASM version
  */
@@ -13,10 +98,10 @@ export function version(): string;
 
 /**
  * Generate a BIP-39 mnemonic phrase
- * @param wordCount - Number of words (12 or 24)
+ * @param wordCount - Number of words (12, 15, 18, 21 or 24)
  * @returns Space-separated mnemonic phrase
  */
-export function generateMnemonic(wordCount: number): string;
+export function generateMnemonic(wordCount: 12 | 15 | 18 | 21 | 24): string;
 
 /**
  * Validate a mnemonic phrase
@@ -25,6 +110,36 @@ export function generateMnemonic(wordCount: number): string;
  */
 export function validateMnemonic(phrase: string): boolean;
 
+/**
+ * Derive a BIP-85 child mnemonic from a master mnemonic
+ * @param mnemonic - Master mnemonic phrase
+ * @param wordCount - Words in the child phrase (12, 15, 18, 21 or 24)
+ * @param index - Child number; each index is an independent wallet
+ * @param passphrase - Optional BIP-39 passphrase of the master
+ * @returns Child mnemonic phrase
+ */
+export function deriveChildMnemonic(
+  mnemonic: string,
+  wordCount: 12 | 15 | 18 | 21 | 24,
+  index: number,
+  passphrase?: string
+): string;
+
+/**
+ * Derive BIP-85 child entropy (HEX application)
+ * @param mnemonic - Master mnemonic phrase
+ * @param numBytes - Entropy length, 16 to 64 bytes
+ * @param index - Child number
+ * @param passphrase - Optional BIP-39 passphrase of the master
+ * @returns Hex-encoded entropy
+ */
+export function deriveChildEntropy(
+  mnemonic: string,
+  numBytes: number,
+  index: number,
+  passphrase?: string
+): string;
+
 /**
  * Convert hex string to bytes
  */
@@ -60,6 +175,25 @@ export function weiToEth(wei: string): number;
  */
 export function gweiToWei(gwei: number): string;
 
+/**
+ * Build a BIP-21, EIP-681 or Solana Pay payment URI for a QR code
+ * @param chain - "bitcoin", "ethereum" or "solana"
+ * @param address - Recipient address
+ * @param amount - Amount in whole units (BTC, ETH, SOL or tokens)
+ * @param opts - label, message, memo, chainId, token, decimals, reference
+ */
+export function makePaymentUri(
+  chain: "bitcoin" | "ethereum" | "solana",
+  address: string,
+  amount?: string,
+  opts?: PaymentOptions
+): string;
+
+/**
+ * Parse a BIP-21, EIP-681 or Solana Pay payment URI
+ */
+export function parsePaymentUri(uri: string): PaymentRequest;
+
 /**
  * Ethereum wallet for browser environments
  */
@@ -88,6 +222,7 @@ export class EthereumWallet {
   
   /**
    * Get the private key as hex string (with 0x prefix)
+   * @throws if key export is disabled
    */
   privateKey(): string;
   
@@ -103,10 +238,172 @@ export class EthereumWallet {
    */
   signMessage(message: string): string;
   
+  /**
+   * Sign EIP-712 typed data (eth_signTypedData_v4)
+   * @param typedDataJson - JSON with types, primaryType, domain and message
+   * @returns 65-byte r || s || v signature as hex
+   */
+  signTypedData(typedDataJson: string): string;
+  
+  /**
+   * Sign a legacy (EIP-155) or EIP-1559 transaction
+   * @param txJson - JSON transaction request; quantities may be numbers,
+   *   decimal strings or 0x hex. Type 2 is used when maxFeePerGas is set.
+   * @returns `raw` hex for eth_sendRawTransaction, its `hash`, and the
+   *   65-byte r || s || v `signature`
+   */
+  signTransaction(txJson: string): SignedTransaction;
+  
   /**
    * Export wallet as JSON (excludes private key)
    */
-  toJson(): { address: string; public_key: string };
+  toJson(): WalletExport;
+  
+  /**
+   * Permanently disable reading the private key from JS
+   * (always disabled when built with the `no-key-export` feature)
+   */
+  disableKeyExport(): void;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
+}
+
+/**
+ * Ethereum account on a Ledger device over WebUSB
+ * (only with the `ledger` feature)
+ */
+export class LedgerEthWallet {
+  /**
+   * Prompt for a Ledger and open m/44'/60'/{account}'/0/0.
+   * Call from a user gesture with the Ethereum app open.
+   */
+  static connect(account: number): Promise<LedgerEthWallet>;
+  
+  /**
+   * Like connect, with an explicit derivation path
+   */
+  static connectPath(path: string): Promise<LedgerEthWallet>;
+  
+  address(): string;
+  publicKey(): string;
+  path(): string;
+  
+  /**
+   * Show the address on the device for the user to confirm
+   */
+  verifyAddress(): Promise<void>;
+  
+  /**
+   * Sign a message (personal_sign), confirmed on the device
+   * @returns 65-byte r || s || v signature as hex
+   */
+  signMessage(message: string): Promise<string>;
+  
+  /**
+   * Sign a transaction (same JSON as EthereumWallet.signTransaction),
+   * confirmed on the device
+   */
+  signTransaction(txJson: string): Promise<SignedTransaction>;
+  
+  toJson(): WalletExport;
+  
+  /**
+   * Release the USB device
+   */
+  close(): Promise<void>;
+}
+
+/**
+ * JSON-RPC client for Ethereum and other EVM chains (uses fetch)
+ */
+export class EvmRpc {
+  constructor(url: string);
+  
+  /**
+   * Get the endpoint URL
+   */
+  url(): string;
+  
+  /**
+   * Get an account balance in wei (decimal string)
+   */
+  getBalance(address: string): Promise<string>;
+  
+  /**
+   * Get the next nonce for an account (counts pending transactions)
+   */
+  getTransactionCount(address: string): Promise<number>;
+  
+  /**
+   * Get the chain id
+   */
+  getChainId(): Promise<number>;
+  
+  /**
+   * Get the current gas price in wei (decimal string)
+   */
+  getGasPrice(): Promise<string>;
+  
+  /**
+   * Suggest EIP-1559 fees from the latest block
+   */
+  estimateFees(): Promise<FeeEstimate>;
+  
+  /**
+   * Broadcast a signed transaction (`SignedTransaction.raw`)
+   * @returns Transaction hash
+   */
+  sendRawTransaction(rawTransaction: string): Promise<string>;
+}
+
+/**
+ * JSON-RPC client for Solana (uses fetch)
+ */
+export class SolanaRpc {
+  constructor(url: string);
+  
+  /**
+   * Get the endpoint URL
+   */
+  url(): string;
+  
+  /**
+   * Get an account balance in lamports (decimal string)
+   */
+  getBalance(address: string): Promise<string>;
+}
+
+/**
+ * Password-protected key storage backed by IndexedDB
+ *
+ * Keys are encrypted with AES-256-GCM under a PBKDF2-SHA256 key derived
+ * through Web Crypto. Decrypted keys stay inside WASM memory.
+ */
+export class Keystore {
+  /**
+   * Encrypt a wallet's private key and store it under `name`
+   * (replaces any existing entry)
+   */
+  static save(name: string, wallet: EthereumWallet, password: string): Promise<void>;
+  
+  /**
+   * Decrypt the wallet stored under `name`
+   * @throws if the entry is missing or the password is wrong
+   */
+  static load(name: string, password: string): Promise<EthereumWallet>;
+  
+  /**
+   * List stored entries
+   */
+  static list(): Promise<KeystoreEntry[]>;
+  
+  /**
+   * Delete the entry stored under `name`
+   */
+  static remove(name: string): Promise<void>;
 }
 
 /**
@@ -139,6 +436,296 @@ export class BitcoinKeys {
    * Get compressed public key as hex
    */
   publicKey(): string;
+  
+  /**
+   * Export keys as JSON (excludes private key)
+   */
+  toJson(): WalletExport;
+  
+  /**
+   * Permanently disable reading the private key from JS
+   * (always disabled when built with the `no-key-export` feature)
+   */
+  disableKeyExport(): void;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
+}
+
+/**
+ * Solana wallet for browser environments
+ */
+export class SolanaWallet {
+  /**
+   * Create a new random Solana wallet
+   */
+  constructor();
+  
+  /**
+   * Create wallet from mnemonic phrase (SLIP-10: m/44'/501'/{account}'/0')
+   * @param mnemonic - BIP-39 mnemonic phrase
+   * @param account - Account index (0 for the first Phantom/Solflare account)
+   */
+  static fromMnemonic(mnemonic: string, account: number): SolanaWallet;
+  
+  /**
+   * Create wallet from a base58 secret key (64-byte keypair or 32-byte seed)
+   */
+  static fromSecretKey(secretKey: string): SolanaWallet;
+  
+  /**
+   * Get the wallet address (base58 public key)
+   */
+  address(): string;
+  
+  /**
+   * Get the secret key as base58 (64-byte keypair format)
+   */
+  secretKey(): string;
+  
+  /**
+   * Sign an arbitrary message
+   * @returns 64-byte Ed25519 signature
+   */
+  signMessage(message: Uint8Array): Uint8Array;
+  
+  /**
+   * Sign a serialized legacy or versioned transaction
+   * @param transaction - Wire-format transaction with empty signature slots
+   * @returns `raw` base64 transaction with this wallet's signature filled
+   *   in (for sendEncodedTransaction), and its id as `hash` once the fee
+   *   payer has signed
+   */
+  signTransaction(transaction: Uint8Array): SignedTransaction;
+  
+  /**
+   * Export wallet as JSON (excludes private key)
+   */
+  toJson(): WalletExport;
+  
+  /**
+   * Permanently disable reading the private key from JS
+   * (always disabled when built with the `no-key-export` feature)
+   */
+  disableKeyExport(): void;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
+}
+
+/**
+ * Sui wallet for browser environments
+ */
+export class SuiWallet {
+  /**
+   * Create a new random Sui wallet
+   */
+  constructor();
+  
+  /**
+   * Create wallet from mnemonic phrase
+   * @param mnemonic - BIP-39 mnemonic phrase
+   * @param account - Account index in m/44'/784'/{account}'/0'/0'
+   */
+  static fromMnemonic(mnemonic: string, account: number): SuiWallet;
+  
+  /**
+   * Import a suiprivkey1... key, a sui.keystore entry, or 32-byte hex
+   */
+  static fromPrivateKey(privateKey: string): SuiWallet;
+  
+  /**
+   * Get the wallet address (0x + 64 hex characters)
+   */
+  address(): string;
+  
+  /**
+   * Get the public key as base64 (flag || public_key)
+   */
+  publicKey(): string;
+  
+  /**
+   * Export the private key as Bech32 (suiprivkey1...)
+   */
+  exportPrivateKey(): string;
+  
+  /**
+   * Export the key as a sui.keystore entry
+   */
+  toKeystore(): string;
+  
+  /**
+   * Sign BCS-encoded TransactionData
+   * @returns base64 `raw` bytes and serialized `signature` for
+   *   sui_executeTransactionBlock, and the base58 digest as `hash`
+   */
+  signTransaction(txBytes: Uint8Array): SignedTransaction;
+  
+  /**
+   * Export wallet as JSON (excludes private key)
+   */
+  toJson(): WalletExport;
+  
+  /**
+   * Sign a personal message
+   * @returns Base64 serialized signature
+   */
+  signPersonalMessage(message: Uint8Array): string;
+  
+  /**
+   * Permanently disable reading the private key from JS
+   * (always disabled when built with the `no-key-export` feature)
+   */
+  disableKeyExport(): void;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
+}
+
+/**
+ * Aptos wallet for browser environments
+ */
+export class AptosWallet {
+  /**
+   * Create a new random Aptos wallet
+   */
+  constructor();
+  
+  /**
+   * Create wallet from mnemonic phrase
+   * @param mnemonic - BIP-39 mnemonic phrase
+   * @param account - Account index in m/44'/637'/{account}'/0'/0'
+   */
+  static fromMnemonic(mnemonic: string, account: number): AptosWallet;
+  
+  /**
+   * Import an AIP-80 (ed25519-priv-0x...) or 32-byte hex private key
+   */
+  static fromPrivateKey(privateKey: string): AptosWallet;
+  
+  /**
+   * Get the wallet address (0x + 64 hex characters)
+   */
+  address(): string;
+  
+  /**
+   * Get the public key as hex
+   */
+  publicKey(): string;
+  
+  /**
+   * Export the private key in AIP-80 format
+   */
+  exportPrivateKey(): string;
+  
+  /**
+   * Sign arbitrary bytes
+   * @returns 64-byte Ed25519 signature
+   */
+  signMessage(message: Uint8Array): Uint8Array;
+  
+  /**
+   * Sign a BCS-encoded RawTransaction
+   * @returns hex BCS-encoded SignedTransaction as `raw`, and its `hash`
+   */
+  signTransaction(rawTransaction: Uint8Array): SignedTransaction;
+  
+  /**
+   * Export wallet as JSON (excludes private key)
+   */
+  toJson(): WalletExport;
+  
+  /**
+   * Permanently disable reading the private key from JS
+   * (always disabled when built with the `no-key-export` feature)
+   */
+  disableKeyExport(): void;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
+}
+
+/**
+ * TON wallet (v3r2 contract) for browser environments
+ */
+export class TonWallet {
+  /**
+   * Generate a new 24-word TON mnemonic (not BIP-39 compatible)
+   */
+  static generateMnemonic(): string;
+  
+  /**
+   * Create wallet from a 24-word TON mnemonic
+   * @param mnemonic - TON mnemonic phrase
+   * @param testnet - Produce testnet-flagged friendly addresses
+   */
+  static fromMnemonic(mnemonic: string, testnet: boolean): TonWallet;
+  
+  /**
+   * Create wallet from a 32-byte private key (hex)
+   */
+  static fromPrivateKey(privateKeyHex: string, testnet: boolean): TonWallet;
+  
+  /**
+   * Get the non-bounceable friendly address (UQ...)
+   */
+  address(): string;
+  
+  /**
+   * Get the bounceable friendly address (EQ...)
+   */
+  bounceableAddress(): string;
+  
+  /**
+   * Get the raw address (0:<hex>)
+   */
+  rawAddress(): string;
+  
+  /**
+   * Get the public key as hex
+   */
+  publicKey(): string;
+  
+  /**
+   * Get the subwallet id
+   */
+  walletId(): number;
+  
+  /**
+   * Export wallet as JSON (excludes private key)
+   */
+  toJson(): WalletExport;
+  
+  /**
+   * Sign arbitrary bytes
+   * @returns 64-byte Ed25519 signature
+   */
+  signMessage(message: Uint8Array): Uint8Array;
+  
+  /**
+   * Build and sign a transfer
+   * @param to - Destination address (friendly or raw); bounce follows its flag
+   * @param amountNano - Amount in nanotons
+   * @param seqno - Current wallet seqno (0 also deploys the wallet)
+   * @param validUntil - Unix time after which the message is rejected
+   * @param comment - Optional text comment
+   * @returns base64 BoC of the external message as `raw` (for sendBoc) and
+   *   its hex `hash`
+   */
+  createTransfer(to: string, amountNano: string, seqno: number, validUntil: number, comment?: string): SignedTransaction;
+  
+  /**
+   * Wipe the private key from memory and invalidate this object
+   */
+  destroy(): void;
 }
 
 /**
@@ -0,0 +1,142 @@
+//! Runtime chain selection
+//!
+//! [`AnyWallet`] erases the concrete wallet type so applications can pick a
+//! chain from configuration instead of at compile time:
+//!
+//! ```ignore
+//! use walletd::{create_wallet, Chain};
+//!
+//! let chain: Chain = config.chain.parse()?;
+//! let wallet = create_wallet(chain, &mnemonic)?;
+//! println!("{} on {}", wallet.address(), wallet.network().name);
+//! ```
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use walletd_traits::{Amount, Chain, Transferable, TxHash, Wallet, WalletError, WalletResult};
+
+/// Object-safe view of a wallet that can send its native asset
+///
+/// Implemented for every [`Transferable`] wallet. Chain-specific transaction
+/// options are not available through this view; downcast to the concrete
+/// wallet when you need them.
+#[async_trait]
+pub trait DynWallet: Wallet {
+    /// Transfers funds to another address
+    async fn transfer(&self, to: &str, amount: Amount) -> WalletResult<TxHash>;
+
+    /// Estimates the fee for a transfer
+    async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount>;
+}
+
+#[async_trait]
+impl<T: Transferable> DynWallet for T {
+    async fn transfer(&self, to: &str, amount: Amount) -> WalletResult<TxHash> {
+        Transferable::transfer(self, to, amount).await
+    }
+
+    async fn estimate_fee(&self, to: &str, amount: Amount) -> WalletResult<Amount> {
+        Transferable::estimate_fee(self, to, amount).await
+    }
+}
+
+/// A wallet for any supported chain
+pub type AnyWallet = Box<dyn DynWallet>;
+
+/// Builds a wallet from a BIP-39 mnemonic phrase
+pub type WalletFactory = Arc<dyn Fn(&str) -> WalletResult<AnyWallet> + Send + Sync>;
+
+/// Maps chains to the factories that build their wallets
+#[derive(Clone)]
+pub struct WalletRegistry {
+    factories: HashMap<Chain, WalletFactory>,
+}
+
+impl WalletRegistry {
+    /// Creates an empty registry
+    pub fn new() -> Self {
+        Self {
+            factories: HashMap::new(),
+        }
+    }
+
+    /// Creates a registry with a factory for every chain enabled by feature flags
+    pub fn with_defaults() -> Self {
+        #[allow(unused_mut)]
+        let mut registry = Self::new();
+
+        #[cfg(feature = "ethereum")]
+        registry.register(Chain::Ethereum, |mnemonic| {
+            let wallet = walletd_ethereum::ConnectedEthereumWallet::from_phrase(
+                mnemonic,
+                1,
+                "https://eth.llamarpc.com",
+            )?;
+            Ok(Box::new(wallet) as AnyWallet)
+        });
+
+        registry
+    }
+
+    /// Adds or replaces the factory for `chain` (builder style)
+    pub fn with<F>(mut self, chain: Chain, factory: F) -> Self
+    where
+        F: Fn(&str) -> WalletResult<AnyWallet> + Send + Sync + 'static,
+    {
+        self.register(chain, factory);
+        self
+    }
+
+    /// Adds or replaces the factory for `chain`
+    pub fn register<F>(&mut self, chain: Chain, factory: F)
+    where
+        F: Fn(&str) -> WalletResult<AnyWallet> + Send + Sync + 'static,
+    {
+        self.factories.insert(chain, Arc::new(factory));
+    }
+
+    /// Returns true if a wallet can be created for `chain`
+    pub fn supports(&self, chain: Chain) -> bool {
+        self.factories.contains_key(&chain)
+    }
+
+    /// Returns the chains with a registered factory
+    pub fn chains(&self) -> Vec<Chain> {
+        Chain::ALL
+            .into_iter()
+            .filter(|chain| self.supports(*chain))
+            .collect()
+    }
+
+    /// Creates a wallet for `chain` from a mnemonic phrase
+    pub fn create(&self, chain: Chain, mnemonic: &str) -> WalletResult<AnyWallet> {
+        let factory = self.factories.get(&chain).ok_or_else(|| {
+            WalletError::NotSupported(format!("no wallet for {} (is its feature enabled?)", chain))
+        })?;
+        factory(mnemonic)
+    }
+}
+
+impl Default for WalletRegistry {
+    fn default() -> Self {
+        Self::with_defaults()
+    }
+}
+
+impl fmt::Debug for WalletRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WalletRegistry")
+            .field("chains", &self.chains())
+            .finish()
+    }
+}
+
+/// Creates a wallet for `chain` using the default registry
+///
+/// Only chains enabled through feature flags are available; see
+/// [`WalletRegistry`] to plug in custom endpoints or wallet types.
+pub fn create_wallet(chain: Chain, mnemonic: &str) -> WalletResult<AnyWallet> {
+    WalletRegistry::with_defaults().create(chain, mnemonic)
+}
@@ -51,6 +51,15 @@
 //! #[cfg(feature = "bitcoin")]
 //! use walletd::bitcoin::BitcoinWallet;
 //! ```
+//!
+//! ## Runtime Chain Selection
+//!
+//! ```ignore
+//! use walletd::{create_wallet, Chain};
+//!
+//! let wallet = create_wallet("ethereum".parse::<Chain>()?, mnemonic)?;
+//! let balance = wallet.balance().await?;
+//! ```
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![forbid(unsafe_code)]
@@ -68,6 +77,16 @@ pub use walletd_traits as traits;
 #[cfg_attr(docsrs, doc(cfg(feature = "core")))]
 pub use walletd_core as core;
 
+#[cfg(feature = "core")]
+#[cfg_attr(docsrs, doc(cfg(feature = "core")))]
+pub mod any_wallet;
+
+#[cfg(feature = "core")]
+pub use any_wallet::{create_wallet, AnyWallet, DynWallet, WalletFactory, WalletRegistry};
+
+#[cfg(feature = "core")]
+pub use walletd_traits::Chain;
+
 // ============================================================================
 // Chain-specific re-exports
 // ============================================================================
@@ -163,6 +182,27 @@ pub mod prasaga {
     pub use walletd_prasaga_avio::*;
 }
 
+/// QR codes for addresses and payment requests, and animated UR codes
+#[cfg(feature = "qr")]
+#[cfg_attr(docsrs, doc(cfg(feature = "qr")))]
+pub mod qr {
+    pub use walletd_qr::*;
+}
+
+/// Encrypted full-wallet backup and restore
+///
+/// ```ignore
+/// let backup = WalletBackup::new(keystore).with_account_index("ethereum", 2);
+/// walletd::backup::export("wallet.backup", password, &backup)?;
+/// let restored = walletd::backup::restore("wallet.backup", password)?;
+/// ```
+#[cfg(feature = "backup")]
+#[cfg_attr(docsrs, doc(cfg(feature = "backup")))]
+pub mod backup {
+    pub use walletd_keystore::backup::*;
+    pub use walletd_keystore::{Keystore, KeystoreError};
+}
+
 // ============================================================================
 // Prelude - commonly used types
 // ============================================================================
@@ -178,6 +218,9 @@ pub mod prelude {
 
     #[cfg(feature = "core")]
     pub use walletd_core::{ct_eq, Zeroize, ZeroizeOnDrop};
+
+    #[cfg(feature = "core")]
+    pub use crate::any_wallet::{create_wallet, AnyWallet, DynWallet, WalletRegistry};
 }
 
 // ============================================================================
@@ -256,6 +299,79 @@ mod tests {
         let _zero = Amount::zero(18);
     }
 
+    #[cfg(feature = "core")]
+    #[tokio::test]
+    async fn test_any_wallet_registry() {
+        use crate::prelude::*;
+        use async_trait::async_trait;
+
+        struct MockWallet {
+            address: String,
+            network: Network,
+        }
+
+        #[async_trait]
+        impl Wallet for MockWallet {
+            fn address(&self) -> String {
+                self.address.clone()
+            }
+
+            async fn balance(&self) -> WalletResult<Amount> {
+                Ok(Amount::from_smallest_unit(5, 9))
+            }
+
+            fn network(&self) -> &Network {
+                &self.network
+            }
+
+            fn currency_symbol(&self) -> &str {
+                "SOL"
+            }
+
+            fn decimals(&self) -> u8 {
+                9
+            }
+        }
+
+        #[async_trait]
+        impl Transferable for MockWallet {
+            type TxParams = SolanaTxParams;
+
+            async fn transfer_with(&self, tx: TransactionBuilder<SolanaTxParams>) -> WalletResult<TxHash> {
+                Ok(TxHash::new(format!("sig-{}", tx.to.unwrap_or_default())))
+            }
+
+            async fn estimate_fee(&self, _to: &str, _amount: Amount) -> WalletResult<Amount> {
+                Ok(Amount::from_smallest_unit(5000, 9))
+            }
+        }
+
+        let registry = WalletRegistry::new().with(Chain::Solana, |mnemonic| {
+            Ok(Box::new(MockWallet {
+                address: format!("addr-{}", mnemonic.split_whitespace().count()),
+                network: Network {
+                    name: "Solana Devnet".to_string(),
+                    chain_id: None,
+                    is_testnet: true,
+                },
+            }) as AnyWallet)
+        });
+        assert_eq!(registry.chains(), vec![Chain::Solana]);
+
+        let chain: Chain = "solana".parse().unwrap();
+        let wallet = registry.create(chain, "abandon abandon about").unwrap();
+        assert_eq!(wallet.address(), "addr-3");
+        assert_eq!(wallet.currency_symbol(), "SOL");
+        assert_eq!(wallet.balance().await.unwrap().value, 5);
+        let hash = wallet.transfer("dest", Amount::from_smallest_unit(1, 9)).await.unwrap();
+        assert_eq!(hash.as_str(), "sig-dest");
+
+        assert!(matches!(
+            registry.create(Chain::Cosmos, "abandon"),
+            Err(WalletError::NotSupported(_))
+        ));
+    }
+
     #[cfg(feature = "bitcoin")]
     #[test]
     fn test_bitcoin_import() {